@@ -0,0 +1,1184 @@
+//! Background alert evaluation
+//!
+//! Polls for new price or broker data and, once a refresh lands, scans the
+//! watchlist (plus symbols named by user rules) with the technical and broker
+//! alert engines, Wyckoff events on the latest session, price-level crosses,
+//! session VWAP reclaims and losses on the streamed 5-minute bars (see
+//! [`crate::realtime_scoring`]), financial reports expected within the week
+//! and user-defined rules. An
+//! alert that already fired for the same condition within the cooldown is
+//! suppressed; the rest are stored in the alert history and routed to every
+//! subscription that accepts them. Alerts of a user's rule go to that user's
+//! channels, other alerts to the admin and to each user with preferences for
+//! the symbol. A preference routes the alert types it enables and the
+//! defaults route the rest. Alerts held back by quiet hours are stored but
+//! not sent. Saved screens are re-run after the scan, once per session.
+//!
+//! Data source SLAs and API key quotas are checked and the news feeds polled
+//! hourly, and the financial report calendar and corporate actions are synced
+//! daily, whether or not data landed, since a source that stopped delivering
+//! is exactly what the SLA check catches.
+
+use crate::corporate_actions::{self, CORPORATE_ACTIONS_JOB};
+use crate::custom_indicators::{
+    custom_indicator_bars, load_custom_indicators_of, rule_custom_indicators, set_custom_metrics,
+};
+use crate::earnings::{self, EARNINGS_CALENDAR_JOB};
+use crate::news::{self, NEWS_INGEST_JOB};
+use crate::notification_retry;
+use crate::notifications::NotificationService;
+use crate::realtime_scoring::SCORING_INTERVAL;
+use crate::routes::admin::{run_quota_check, run_sla_check};
+use crate::routes::alerts::{build_rule_context, price_metrics, to_alert_rule};
+use crate::routes::analysis::{get_broker_flow_internal, InstitutionalFlowAnalysis};
+use crate::routes::notifications::user_recipients;
+use crate::routes::watchlist::to_alert_subscription;
+use crate::AppState;
+use chrono::{DateTime, Duration, Utc};
+use jejakcuan_core::{
+    Alert, AlertPriority, AlertRule, AlertSubscription, AlertTypeFilter, BrokerAlertEngine,
+    BrokerAlertInput, DivergenceSignal, EventAlert, EventAlertType, NotificationChannel,
+    ReportPeriod, TechnicalAlert, TechnicalAlertEngine, TechnicalAlertInput, TechnicalAlertType,
+    UserPriceLevel, WyckoffEventSignal, EARNINGS_ALERT_DAYS,
+};
+use jejakcuan_db::{
+    repositories, AlertRuleRow, AlertSubscriptionRow, InsertAlertHistory, StockPriceRow,
+    WatchlistLevelRow,
+};
+use jejakcuan_technical::{
+    calculate_bollinger_bands, calculate_ema20, calculate_ema50, calculate_macd, calculate_rsi14,
+    calculate_session_vwap_bands, detect_all_divergences, detect_wyckoff_phase, session_date,
+    session_rvol, DivergenceConfig, IndicatorExpr, IndicatorSeries, IntradayBar, OhlcvBar,
+    WyckoffProfile,
+};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::{Arc, Mutex};
+
+/// An alert for the same condition is not repeated within this window
+const ALERT_COOLDOWN_HOURS: i64 = 24;
+
+/// Calendar days of prices read for the technical alerts (EMA50 needs ~50 bars)
+const SCAN_PRICE_DAYS: i64 = 120;
+
+/// Days of broker summaries read for the flow alerts
+const SCAN_BROKER_DAYS: i32 = 30;
+
+/// Sessions whose range defines the breakout levels
+const BREAKOUT_LOOKBACK: usize = 20;
+
+/// How often data source SLAs are checked for breaches
+const SLA_CHECK_EVERY: Duration = Duration::hours(1);
+
+/// How often the financial report calendar is synced
+const EARNINGS_SYNC_EVERY: Duration = Duration::days(1);
+
+/// How often the news feeds are polled
+const NEWS_INGEST_EVERY: Duration = Duration::hours(1);
+
+/// How often corporate actions are ingested
+const CORPORATE_ACTIONS_EVERY: Duration = Duration::days(1);
+
+/// Operator channels receive alerts of this priority and above
+const OPERATOR_MIN_PRIORITY: AlertPriority = AlertPriority::Medium;
+
+/// The in-app feed shows every alert, it does not interrupt like a message
+const IN_APP_MIN_PRIORITY: AlertPriority = AlertPriority::Low;
+
+/// When each alert condition last fired, for suppressing repeats
+#[derive(Debug, Default)]
+pub struct RecentAlerts {
+    fired: Mutex<HashMap<String, DateTime<Utc>>>,
+}
+
+impl RecentAlerts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the alert as fired unless it already fired within `cooldown`
+    ///
+    /// Returns whether the alert should be sent.
+    pub fn claim(&self, key: &str, now: DateTime<Utc>, cooldown: Duration) -> bool {
+        let mut fired = self.fired.lock().unwrap_or_else(|e| e.into_inner());
+        fired.retain(|_, at| now - *at < cooldown);
+        if fired.contains_key(key) {
+            return false;
+        }
+        fired.insert(key.to_string(), now);
+        true
+    }
+}
+
+/// Outcome of one scan
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct ScanReport {
+    pub symbols: usize,
+    /// Symbols that could not be evaluated
+    pub failed: usize,
+    pub detected: usize,
+    /// Detected alerts that repeated a recent one
+    pub suppressed: usize,
+    pub fired: usize,
+    pub notifications_sent: usize,
+    /// Deliveries held back by a subscription's quiet hours
+    pub quiet_hours_held: usize,
+    pub notification_errors: Vec<String>,
+    pub elapsed_ms: u64,
+}
+
+/// A user's external channels and in-app feed as subscriptions to every
+/// scanned symbol
+fn default_subscriptions(
+    user: &str,
+    recipients: &[(NotificationChannel, String)],
+) -> Vec<AlertSubscription> {
+    let mut subscriptions: Vec<AlertSubscription> = recipients
+        .iter()
+        .map(|(channel, recipient)| AlertSubscription {
+            user_id: recipient.clone(),
+            symbols: vec![],
+            alert_types: AlertTypeFilter::default(),
+            min_priority: OPERATOR_MIN_PRIORITY,
+            channels: vec![channel.clone()],
+            quiet_hours: None,
+        })
+        .collect();
+    subscriptions.push(AlertSubscription {
+        user_id: user.to_string(),
+        symbols: vec![],
+        alert_types: AlertTypeFilter::default(),
+        min_priority: IN_APP_MIN_PRIORITY,
+        channels: vec![NotificationChannel::InApp],
+        quiet_hours: None,
+    });
+    subscriptions
+}
+
+/// Owner of the rule a rule alert came from
+fn rule_owner<'a>(alert: &Alert, rules: &'a [(AlertRuleRow, AlertRule)]) -> Option<&'a str> {
+    let Alert::Technical(TechnicalAlert {
+        alert_type: TechnicalAlertType::RuleMatched { rule_id, .. },
+        ..
+    }) = alert
+    else {
+        return None;
+    };
+    rules
+        .iter()
+        .find(|(row, _)| row.id == *rule_id)
+        .map(|(row, _)| row.owner.as_str())
+}
+
+/// Per-symbol preferences of every user
+fn symbol_preferences(rows: &[AlertSubscriptionRow]) -> HashMap<String, Vec<AlertSubscription>> {
+    let mut by_symbol: HashMap<String, Vec<AlertSubscription>> = HashMap::new();
+    for row in rows {
+        by_symbol
+            .entry(row.symbol.clone())
+            .or_default()
+            .push(to_alert_subscription(row));
+    }
+    by_symbol
+}
+
+/// A preference as one subscription per channel, addressed to its user
+///
+/// Channels the user has no address for are skipped.
+fn routed_preference(
+    preference: &AlertSubscription,
+    recipients: &[(NotificationChannel, String)],
+) -> Vec<AlertSubscription> {
+    preference
+        .channels
+        .iter()
+        .filter_map(|channel| {
+            let recipient = match channel {
+                NotificationChannel::InApp => preference.user_id.clone(),
+                _ => recipients.iter().find(|(c, _)| c == channel)?.1.clone(),
+            };
+            Some(AlertSubscription {
+                user_id: recipient,
+                channels: vec![channel.clone()],
+                ..preference.clone()
+            })
+        })
+        .collect()
+}
+
+/// Subscriptions an alert is routed through
+///
+/// Rule alerts go to the rule's owner; other alerts go to `admin` and to each
+/// user with preferences for the symbol. A user's preference routes the alert
+/// types it enables. The owner or admin gets their defaults for the rest,
+/// other users only get what they opted into.
+fn alert_subscriptions(
+    alert: &Alert,
+    owner: Option<&str>,
+    admin: &str,
+    preferences: &[AlertSubscription],
+    recipients_of: impl Fn(&str) -> Vec<(NotificationChannel, String)>,
+) -> Vec<AlertSubscription> {
+    let default_user = owner.unwrap_or(admin);
+    let mut users = vec![default_user];
+    if owner.is_none() {
+        for preference in preferences {
+            if !users.contains(&preference.user_id.as_str()) {
+                users.push(&preference.user_id);
+            }
+        }
+    }
+
+    let mut subscriptions = Vec::new();
+    for user in users {
+        let recipients = recipients_of(user);
+        let preference = preferences
+            .iter()
+            .find(|p| p.user_id == user && p.alert_types.allows(alert));
+        match preference {
+            Some(preference) => subscriptions.extend(routed_preference(preference, &recipients)),
+            None if user == default_user => {
+                subscriptions.extend(default_subscriptions(user, &recipients))
+            }
+            None => {}
+        }
+    }
+    subscriptions
+}
+
+/// Latest and previous valid values of a series
+fn last_two(series: &IndicatorSeries) -> (Option<Decimal>, Option<Decimal>) {
+    let n = series.len();
+    if n == 0 {
+        return (None, None);
+    }
+    (
+        series.get(n - 1),
+        n.checked_sub(2).and_then(|i| series.get(i)),
+    )
+}
+
+fn ohlcv_bars(prices: &[StockPriceRow]) -> Vec<OhlcvBar> {
+    prices
+        .iter()
+        .map(|p| OhlcvBar {
+            open: p.open,
+            high: p.high,
+            low: p.low,
+            close: p.close,
+            volume: p.volume,
+        })
+        .collect()
+}
+
+/// Divergences whose later swing was confirmed by the latest bar
+///
+/// Each swing is reported once, on the session that confirms it.
+fn fresh_divergences(bars: &[OhlcvBar]) -> Vec<DivergenceSignal> {
+    let config = DivergenceConfig::default();
+    let Some(confirmed_at) = bars.len().checked_sub(1 + config.pivot_strength) else {
+        return Vec::new();
+    };
+    detect_all_divergences(bars, &config)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|d| d.second_pivot == confirmed_at)
+        .map(|d| DivergenceSignal {
+            indicator: d.oscillator.as_str().to_string(),
+            bullish: d.kind.is_bullish(),
+            hidden: d.kind.is_hidden(),
+            confidence: d.confidence,
+        })
+        .collect()
+}
+
+/// Wyckoff events detected on the latest bar
+///
+/// Thresholds follow the liquidity tier of the recent bars. Earlier bars were
+/// reported by the scans that saw them as the latest.
+fn fresh_wyckoff_events(bars: &[OhlcvBar]) -> Vec<WyckoffEventSignal> {
+    let Some(latest) = bars.len().checked_sub(1) else {
+        return Vec::new();
+    };
+    let config = WyckoffProfile::for_bars(bars, None).config();
+    detect_wyckoff_phase(bars, &config)
+        .map(|analysis| analysis.events)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|e| e.index == latest)
+        .map(|e| WyckoffEventSignal {
+            event: e.event.as_str().to_string(),
+            confidence: e.confidence,
+        })
+        .collect()
+}
+
+/// Technical alert input from daily bars (oldest first)
+///
+/// Breakout levels are the high and low of the sessions before the last one.
+fn technical_alert_input(
+    symbol: &str,
+    prices: &[StockPriceRow],
+    levels: &[WatchlistLevelRow],
+) -> TechnicalAlertInput {
+    let closes: Vec<Decimal> = prices.iter().map(|p| p.close).collect();
+    let bars = ohlcv_bars(prices);
+    let (rvol, prev_price) = match prices.split_last() {
+        Some((_, earlier)) => (price_metrics(prices).1, earlier.last().map(|p| p.close)),
+        None => (None, None),
+    };
+
+    let (macd, prev_macd, macd_signal, prev_macd_signal) = match calculate_macd(&closes) {
+        Ok(m) => {
+            let (macd, prev_macd) = last_two(&m.macd_line);
+            let (signal, prev_signal) = last_two(&m.signal_line);
+            (macd, prev_macd, signal, prev_signal)
+        }
+        Err(_) => (None, None, None, None),
+    };
+    let (ema20, prev_ema20) = calculate_ema20(&closes)
+        .map(|s| last_two(&s))
+        .unwrap_or_default();
+    let (ema50, prev_ema50) = calculate_ema50(&closes)
+        .map(|s| last_two(&s))
+        .unwrap_or_default();
+    let bollinger_bandwidth = calculate_bollinger_bands(&closes).ok().and_then(|bb| {
+        let middle = bb.middle.last_valid().filter(|m| !m.is_zero())?;
+        Some((bb.upper.last_valid()? - bb.lower.last_valid()?) / middle)
+    });
+
+    let earlier = &prices[..prices.len().saturating_sub(1)];
+    let window = &earlier[earlier.len().saturating_sub(BREAKOUT_LOOKBACK)..];
+    let (support, resistance) = if window.len() < BREAKOUT_LOOKBACK {
+        (None, None)
+    } else {
+        (
+            window.iter().map(|p| p.low).min(),
+            window.iter().map(|p| p.high).max(),
+        )
+    };
+
+    TechnicalAlertInput {
+        symbol: symbol.to_string(),
+        current_price: closes.last().copied().unwrap_or_default(),
+        prev_price,
+        rsi: calculate_rsi14(&closes).ok().and_then(|s| s.last_valid()),
+        macd,
+        macd_signal,
+        prev_macd,
+        prev_macd_signal,
+        rvol,
+        ema20,
+        ema50,
+        prev_ema20,
+        prev_ema50,
+        support,
+        resistance,
+        bollinger_bandwidth,
+        user_levels: levels
+            .iter()
+            .map(|l| UserPriceLevel {
+                id: Some(l.id),
+                price: l.price,
+                label: l.label.clone(),
+            })
+            .collect(),
+        divergences: fresh_divergences(&bars),
+        wyckoff_events: fresh_wyckoff_events(&bars),
+        ..Default::default()
+    }
+}
+
+/// Set the session VWAP inputs of `input` from intraday bars (oldest first)
+///
+/// Only the last two bars of a session are compared; nothing is set before
+/// the session has two bars.
+fn attach_session_vwap(input: &mut TechnicalAlertInput, bars: &[IntradayBar]) {
+    let [.., prev, last] = bars else {
+        return;
+    };
+    if session_date(prev.time) != session_date(last.time) {
+        return;
+    }
+    let Ok(bands) = calculate_session_vwap_bands(bars) else {
+        return;
+    };
+    input.intraday_close = Some(last.bar.close);
+    input.prev_intraday_close = Some(prev.bar.close);
+    input.vwap = Some(bands[bands.len() - 1].vwap);
+    input.prev_vwap = Some(bands[bands.len() - 2].vwap);
+    input.intraday_rvol = session_rvol(bars);
+}
+
+fn broker_alert_input(symbol: &str, flow: &InstitutionalFlowAnalysis) -> BrokerAlertInput {
+    let decimal = |value: f64| Decimal::try_from(value).unwrap_or_default();
+    BrokerAlertInput {
+        symbol: symbol.to_string(),
+        institutional_net: decimal(flow.institutional_net_5_day),
+        foreign_net: decimal(flow.foreign_net_5_day),
+        accumulation_score: decimal(flow.accumulation_score),
+        days_accumulated: flow.days_accumulated,
+        coordinated_buying: flow.coordinated_buying,
+        institutional_buyer_codes: flow
+            .top_accumulators
+            .iter()
+            .map(|a| a.broker_code.clone())
+            .collect(),
+        // Concentration is not part of the flow analysis
+        hhi: Decimal::ZERO,
+        top_broker_code: flow.top_accumulators.first().map(|a| a.broker_code.clone()),
+    }
+}
+
+/// Whether a rule covers `symbol`: listed, or unlisted and on the watchlist
+fn rule_applies(rule: &AlertRule, symbol: &str, on_watchlist: bool) -> bool {
+    if rule.symbols.is_empty() {
+        on_watchlist
+    } else {
+        rule.applies_to(symbol)
+    }
+}
+
+/// Rules that fired within the cooldown are skipped, across restarts
+fn rule_cooling_down(row: &AlertRuleRow, now: DateTime<Utc>) -> bool {
+    row.last_triggered_at
+        .is_some_and(|at| now - at < Duration::hours(ALERT_COOLDOWN_HOURS))
+}
+
+async fn detect_alerts(
+    state: &AppState,
+    symbol: &str,
+    on_watchlist: bool,
+    levels: &[WatchlistLevelRow],
+    rules: &[(AlertRuleRow, AlertRule)],
+    custom: &HashMap<String, BTreeMap<String, IndicatorExpr>>,
+) -> Result<Vec<Alert>, String> {
+    let now = Utc::now();
+    let mut alerts = Vec::new();
+
+    if on_watchlist {
+        let prices = repositories::prices::get_price_history(
+            &state.db,
+            symbol,
+            now - Duration::days(SCAN_PRICE_DAYS),
+            now,
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+        // Written by the price stream's bar writer
+        let intraday: Vec<IntradayBar> = repositories::intraday_prices::get_intraday_bars(
+            &state.db,
+            symbol,
+            SCORING_INTERVAL.as_str(),
+            now - Duration::days(1),
+            now,
+        )
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|row| IntradayBar {
+            time: row.time,
+            bar: OhlcvBar {
+                open: row.open,
+                high: row.high,
+                low: row.low,
+                close: row.close,
+                volume: row.volume,
+            },
+        })
+        .collect();
+        if !prices.is_empty() {
+            let mut input = technical_alert_input(symbol, &prices, levels);
+            attach_session_vwap(&mut input, &intraday);
+            alerts.extend(
+                TechnicalAlertEngine::new()
+                    .evaluate(&input)
+                    .into_iter()
+                    .map(Alert::Technical),
+            );
+        }
+
+        let flow = get_broker_flow_internal(state, symbol, SCAN_BROKER_DAYS)
+            .await
+            .ok()
+            .and_then(|b| b.institutional_analysis);
+        if let Some(flow) = flow {
+            alerts.extend(
+                BrokerAlertEngine::new()
+                    .evaluate(&broker_alert_input(symbol, &flow))
+                    .into_iter()
+                    .map(Alert::Broker),
+            );
+        }
+
+        let today = session_date(now);
+        let reports = repositories::earnings::get_unalerted_earnings(
+            &state.db,
+            symbol,
+            today,
+            today + Duration::days(EARNINGS_ALERT_DAYS),
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+        alerts.extend(
+            reports
+                .iter()
+                .filter_map(|r| {
+                    let period = ReportPeriod::from_str_opt(&r.period)?;
+                    EventAlert::earnings_upcoming(
+                        symbol,
+                        period,
+                        r.fiscal_year,
+                        r.expected_date,
+                        today,
+                    )
+                })
+                .map(Alert::Event),
+        );
+    }
+
+    let applicable: Vec<&(AlertRuleRow, AlertRule)> = rules
+        .iter()
+        .filter(|(row, rule)| {
+            rule_applies(rule, symbol, on_watchlist) && !rule_cooling_down(row, now)
+        })
+        .collect();
+    if !applicable.is_empty() {
+        let context = build_rule_context(state, symbol)
+            .await
+            .map_err(|(_, e)| e)?;
+        // One history serves every rule reading custom indicators
+        let custom_bars = if applicable
+            .iter()
+            .any(|(_, rule)| !rule_custom_indicators(rule).is_empty())
+        {
+            custom_indicator_bars(&state.db, symbol)
+                .await
+                .map_err(|e| e.to_string())?
+        } else {
+            Vec::new()
+        };
+        for (row, rule) in applicable {
+            let mut context = context.clone();
+            if let Some(indicators) = custom.get(&row.owner) {
+                set_custom_metrics(
+                    &mut context,
+                    indicators,
+                    rule_custom_indicators(rule),
+                    &custom_bars,
+                );
+            }
+            if rule.evaluate(&context).matched {
+                alerts.push(Alert::Technical(TechnicalAlert::new(
+                    symbol.to_string(),
+                    TechnicalAlertType::RuleMatched {
+                        rule_id: row.id,
+                        name: rule.name.clone(),
+                        condition: rule.condition.to_string(),
+                    },
+                    rule.priority,
+                )));
+            }
+        }
+    }
+
+    Ok(alerts)
+}
+
+/// Persist the trigger of level, rule and report alerts
+async fn mark_triggered(
+    state: &AppState,
+    alert: &Alert,
+    levels: &[WatchlistLevelRow],
+    at: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    match alert {
+        Alert::Technical(technical) => match &technical.alert_type {
+            TechnicalAlertType::UserLevelCrossed { level, .. } => {
+                for row in levels.iter().filter(|l| l.price == *level) {
+                    repositories::watchlist::mark_watchlist_level_triggered(&state.db, row.id, at)
+                        .await?;
+                }
+            }
+            TechnicalAlertType::RuleMatched { rule_id, .. } => {
+                repositories::alerts::mark_alert_rule_triggered(&state.db, *rule_id, at).await?;
+            }
+            _ => {}
+        },
+        Alert::Event(event) => match &event.alert_type {
+            EventAlertType::EarningsUpcoming {
+                period,
+                fiscal_year,
+                ..
+            } => {
+                repositories::earnings::mark_earnings_alerted(
+                    &state.db,
+                    &event.symbol,
+                    *fiscal_year,
+                    period.as_str(),
+                    at,
+                )
+                .await?;
+            }
+        },
+        Alert::Broker(_) => {}
+    }
+    Ok(())
+}
+
+/// Store a fired alert in the history; returns its id
+///
+/// Rule alerts belong to the rule's owner, other alerts are shared.
+async fn record_history(
+    state: &AppState,
+    alert: &Alert,
+    owner: Option<&str>,
+    at: DateTime<Utc>,
+) -> Result<i32, sqlx::Error> {
+    let payload = serde_json::to_value(alert).unwrap_or_default();
+    let row = repositories::alerts::insert_alert_history(
+        &state.db,
+        &InsertAlertHistory {
+            symbol: alert.symbol(),
+            category: alert.category(),
+            kind: alert.glossary_key(),
+            priority: alert.priority().as_str(),
+            message: alert.message(),
+            payload: &payload,
+            triggered_at: at,
+            owner,
+        },
+    )
+    .await?;
+    Ok(row.id)
+}
+
+/// Evaluate alerts for the watchlist and rule symbols and dispatch new ones
+pub async fn scan(state: &AppState) -> Result<ScanReport, sqlx::Error> {
+    let started = std::time::Instant::now();
+    let mut report = ScanReport::default();
+
+    let watchlist: BTreeSet<String> = repositories::watchlist::get_watchlist(&state.db)
+        .await?
+        .into_iter()
+        .map(|w| w.symbol)
+        .collect();
+    let mut levels: HashMap<String, Vec<WatchlistLevelRow>> = HashMap::new();
+    for level in repositories::watchlist::get_alerting_watchlist_levels(&state.db).await? {
+        levels.entry(level.symbol.clone()).or_default().push(level);
+    }
+    let rules: Vec<(AlertRuleRow, AlertRule)> =
+        repositories::alerts::get_enabled_alert_rules(&state.db)
+            .await?
+            .into_iter()
+            .filter_map(|row| match to_alert_rule(&row) {
+                Ok(rule) => Some((row, rule)),
+                Err(e) => {
+                    tracing::warn!("Skipping invalid alert rule {}: {}", row.id, e);
+                    None
+                }
+            })
+            .collect();
+
+    let custom_owners: Vec<String> = rules
+        .iter()
+        .filter(|(_, rule)| !rule_custom_indicators(rule).is_empty())
+        .map(|(row, _)| row.owner.clone())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let custom = if custom_owners.is_empty() {
+        HashMap::new()
+    } else {
+        load_custom_indicators_of(&state.db, &custom_owners).await?
+    };
+
+    let mut symbols = watchlist.clone();
+    symbols.extend(
+        rules
+            .iter()
+            .flat_map(|(_, rule)| rule.symbols.iter().cloned()),
+    );
+    report.symbols = symbols.len();
+
+    let channel_rows =
+        repositories::notification_channels::get_all_user_notification_channels(&state.db).await?;
+    let preferences = symbol_preferences(
+        &repositories::subscriptions::get_all_alert_subscriptions(&state.db).await?,
+    );
+    let cooldown = Duration::hours(ALERT_COOLDOWN_HOURS);
+
+    for symbol in &symbols {
+        let symbol_levels = levels.get(symbol).map(Vec::as_slice).unwrap_or_default();
+        let alerts = match detect_alerts(
+            state,
+            symbol,
+            watchlist.contains(symbol),
+            symbol_levels,
+            &rules,
+            &custom,
+        )
+        .await
+        {
+            Ok(alerts) => alerts,
+            Err(e) => {
+                tracing::warn!("Alert scan failed for {}: {}", symbol, e);
+                report.failed += 1;
+                continue;
+            }
+        };
+
+        // Shown next to the alert on channels that render fields
+        let score = if alerts.is_empty() {
+            None
+        } else {
+            repositories::scores::get_stock_score(&state.db, symbol)
+                .await
+                .ok()
+                .flatten()
+                .and_then(|row| row.composite_score.to_f64())
+        };
+
+        for alert in alerts {
+            report.detected += 1;
+            let now = Utc::now();
+            if !state.recent_alerts.claim(&alert.dedup_key(), now, cooldown) {
+                report.suppressed += 1;
+                continue;
+            }
+            report.fired += 1;
+
+            if let Err(e) = mark_triggered(state, &alert, symbol_levels, now).await {
+                tracing::warn!("Failed to record trigger of {}: {}", alert.dedup_key(), e);
+            }
+            let owner = rule_owner(&alert, &rules);
+            let history_id = match record_history(state, &alert, owner, now).await {
+                Ok(id) => Some(id),
+                Err(e) => {
+                    tracing::warn!("Failed to store alert {}: {}", alert.dedup_key(), e);
+                    None
+                }
+            };
+
+            let sent_before = report.notifications_sent;
+            let subscriptions = alert_subscriptions(
+                &alert,
+                owner,
+                &state.config.username,
+                preferences
+                    .get(symbol)
+                    .map(Vec::as_slice)
+                    .unwrap_or_default(),
+                |user| user_recipients(state, user, &channel_rows),
+            );
+            for subscription in subscriptions.iter().filter(|s| s.accepts(&alert)) {
+                if !subscription.delivers(&alert, now) {
+                    report.quiet_hours_held += 1;
+                    continue;
+                }
+                for channel in &subscription.channels {
+                    let mut notification = NotificationService::notification_from_alert(
+                        &alert,
+                        subscription.user_id.clone(),
+                        channel.clone(),
+                    );
+                    notification.metadata.score = score;
+                    match notification_retry::deliver(state, &notification).await {
+                        Ok(()) => report.notifications_sent += 1,
+                        Err(e) => {
+                            report
+                                .notification_errors
+                                .push(format!("{}: {}", alert.dedup_key(), e))
+                        }
+                    }
+                }
+            }
+            if let (Some(id), true) = (history_id, report.notifications_sent > sent_before) {
+                if let Err(e) =
+                    repositories::alerts::mark_alert_history_notified(&state.db, id).await
+                {
+                    tracing::warn!("Failed to flag alert {} as notified: {}", id, e);
+                }
+            }
+        }
+    }
+
+    report.elapsed_ms = started.elapsed().as_millis() as u64;
+    Ok(report)
+}
+
+/// Whether a periodic task last run at `last` is due, recording the run
+fn due(last: &mut Option<DateTime<Utc>>, every: Duration) -> bool {
+    let now = Utc::now();
+    if last.is_some_and(|at| now - at < every) {
+        return false;
+    }
+    *last = Some(now);
+    true
+}
+
+/// Whether no job of `source_id` is running
+async fn idle(state: &AppState, source_id: &str) -> bool {
+    state
+        .job_manager
+        .is_source_running(source_id)
+        .await
+        .is_none()
+}
+
+/// Poll every `every` and scan once new data has landed, checking data
+/// source SLAs and API quotas and ingesting news on the first poll of each
+/// hour and syncing
+/// the report calendar and corporate actions on the first poll of each day
+///
+/// The data present at startup is taken as already scanned, so a restart
+/// does not replay alerts.
+pub fn spawn_alert_scheduler(state: Arc<AppState>, every: std::time::Duration) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(every);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        let mut scanned_through: Option<Option<DateTime<Utc>>> = None;
+        let mut sla_checked_at: Option<DateTime<Utc>> = None;
+        let mut earnings_synced_at: Option<DateTime<Utc>> = None;
+        let mut news_ingested_at: Option<DateTime<Utc>> = None;
+        let mut actions_ingested_at: Option<DateTime<Utc>> = None;
+
+        loop {
+            interval.tick().await;
+            if due(&mut sla_checked_at, SLA_CHECK_EVERY) {
+                match run_sla_check(&state).await {
+                    Ok(check) if !check.notified.is_empty() => {
+                        tracing::warn!("Data source SLA breached: {}", check.notified.join(", "))
+                    }
+                    Ok(_) => {}
+                    Err((_, e)) => tracing::warn!("SLA check failed: {}", e),
+                }
+                let quota = run_quota_check(&state).await;
+                if !quota.notified.is_empty() {
+                    tracing::warn!("API quota running low: {}", quota.notified.join(", "));
+                }
+            }
+            if due(&mut earnings_synced_at, EARNINGS_SYNC_EVERY)
+                && idle(&state, EARNINGS_CALENDAR_JOB).await
+            {
+                earnings::spawn_sync(&state).await;
+            }
+            if due(&mut news_ingested_at, NEWS_INGEST_EVERY) && idle(&state, NEWS_INGEST_JOB).await
+            {
+                news::spawn_ingest(&state).await;
+            }
+            if due(&mut actions_ingested_at, CORPORATE_ACTIONS_EVERY)
+                && idle(&state, CORPORATE_ACTIONS_JOB).await
+            {
+                corporate_actions::spawn_ingest(&state).await;
+            }
+            let latest = match repositories::prices::get_latest_data_time(&state.db).await {
+                Ok(latest) => latest,
+                Err(e) => {
+                    tracing::warn!("Alert scheduler could not check data freshness: {}", e);
+                    continue;
+                }
+            };
+            let Some(previous) = scanned_through else {
+                scanned_through = Some(latest);
+                continue;
+            };
+            if latest.is_none() || latest <= previous {
+                continue;
+            }
+
+            match scan(&state).await {
+                Ok(report) => {
+                    tracing::info!(
+                        "Alert scan: {} symbols, {} alerts fired ({} suppressed), {} notifications in {}ms",
+                        report.symbols,
+                        report.fired,
+                        report.suppressed,
+                        report.notifications_sent,
+                        report.elapsed_ms
+                    );
+                    scanned_through = Some(latest);
+                }
+                Err(e) => tracing::warn!("Alert scan failed: {}", e),
+            }
+
+            if let Some(data_time) = latest {
+                match crate::screener::rerun_saved_screens(&state, data_time).await {
+                    Ok(report) if report.screens > 0 => tracing::info!(
+                        "Saved screens: {} re-run, {} changed, {} notifications",
+                        report.screens,
+                        report.changed,
+                        report.notifications_sent
+                    ),
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!("Saved screen re-run failed: {}", e),
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn bar(close: Decimal) -> StockPriceRow {
+        StockPriceRow {
+            time: Utc::now(),
+            symbol: "BBCA".to_string(),
+            open: close,
+            high: close + dec!(1),
+            low: close - dec!(1),
+            close,
+            volume: 1_000,
+            value: None,
+            frequency: None,
+        }
+    }
+
+    #[test]
+    fn test_recent_alerts_cooldown() {
+        let recent = RecentAlerts::new();
+        let now: DateTime<Utc> = "2025-06-04T10:00:00Z".parse().unwrap();
+        let cooldown = Duration::hours(24);
+
+        assert!(recent.claim("BBCA:rsi_oversold", now, cooldown));
+        assert!(!recent.claim("BBCA:rsi_oversold", now + Duration::hours(2), cooldown));
+        assert!(recent.claim("TLKM:rsi_oversold", now, cooldown));
+        assert!(recent.claim("BBCA:rsi_oversold", now + Duration::hours(25), cooldown));
+    }
+
+    #[test]
+    fn test_technical_alert_input_breakout_and_level() {
+        // Flat range, then a close through the range high and a user level
+        let mut prices: Vec<StockPriceRow> = (0..60).map(|_| bar(dec!(100))).collect();
+        prices.push(bar(dec!(110)));
+        let level = WatchlistLevelRow {
+            id: 7,
+            symbol: "BBCA".to_string(),
+            price: dec!(105),
+            kind: "resistance".to_string(),
+            label: None,
+            note: None,
+            alert_enabled: true,
+            last_triggered_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        let input = technical_alert_input("BBCA", &prices, &[level]);
+        assert_eq!(input.current_price, dec!(110));
+        assert_eq!(input.prev_price, Some(dec!(100)));
+        assert_eq!(input.resistance, Some(dec!(101)));
+        assert!(input.ema50.is_some() && input.prev_ema50.is_some());
+
+        let alerts = TechnicalAlertEngine::new().evaluate(&input);
+        let has = |key: &str| alerts.iter().any(|a| a.glossary_key == key);
+        assert!(has("price_breakout"));
+        assert!(has("user_price_level"));
+    }
+
+    #[test]
+    fn test_session_vwap_reclaim() {
+        // Quiet drift under VWAP, then a heavy bar closing back above it
+        let open: DateTime<Utc> = "2025-06-04T02:00:00Z".parse().unwrap();
+        let bars: Vec<IntradayBar> = [(100, 1_000), (98, 1_000), (97, 1_000), (103, 5_000)]
+            .into_iter()
+            .enumerate()
+            .map(|(i, (close, volume))| IntradayBar {
+                time: open + Duration::minutes(5 * i as i64),
+                bar: OhlcvBar {
+                    open: Decimal::from(close),
+                    high: Decimal::from(close),
+                    low: Decimal::from(close),
+                    close: Decimal::from(close),
+                    volume,
+                },
+            })
+            .collect();
+
+        let mut input = TechnicalAlertInput::default();
+        attach_session_vwap(&mut input, &bars[..1]);
+        assert_eq!(input.vwap, None);
+
+        attach_session_vwap(&mut input, &bars);
+        assert_eq!(input.intraday_close, Some(dec!(103)));
+        assert_eq!(input.prev_intraday_close, Some(dec!(97)));
+        assert_eq!(input.intraday_rvol, Some(dec!(5)));
+        let alerts = TechnicalAlertEngine::new().evaluate(&input);
+        assert!(alerts
+            .iter()
+            .any(|a| matches!(a.alert_type, TechnicalAlertType::VwapReclaimed { .. })));
+    }
+
+    #[test]
+    fn test_fresh_wyckoff_events() {
+        // Swings between 100 and 110, then a heavy-volume dip under the
+        // swing lows that closes back inside the range
+        let range_bar = |i: usize| {
+            let depth = Decimal::from([2, 1, 0, 1, 2][i % 5]);
+            OhlcvBar {
+                open: dec!(101) + depth * dec!(2),
+                high: dec!(110) - depth * dec!(2),
+                low: dec!(100) + depth * dec!(2),
+                close: dec!(109) - depth * dec!(2),
+                volume: 1_000,
+            }
+        };
+        let mut bars: Vec<OhlcvBar> = (0..40).map(range_bar).collect();
+        bars.push(OhlcvBar {
+            open: dec!(101),
+            high: dec!(103),
+            low: dec!(95),
+            close: dec!(102),
+            volume: 5_000,
+        });
+
+        let events = fresh_wyckoff_events(&bars);
+        assert!(events.iter().any(|e| e.event == "spring"));
+
+        // A later quiet session leaves nothing to report
+        bars.push(range_bar(41));
+        assert!(fresh_wyckoff_events(&bars)
+            .iter()
+            .all(|e| e.event != "spring"));
+    }
+
+    #[test]
+    fn test_rule_applies() {
+        let any = AlertRule::new("any", "rsi < 30", vec![], AlertPriority::Medium).unwrap();
+        assert!(rule_applies(&any, "BBCA", true));
+        assert!(!rule_applies(&any, "BBCA", false));
+
+        let listed = AlertRule::new(
+            "listed",
+            "rsi < 30",
+            vec!["BBCA".into()],
+            AlertPriority::Medium,
+        )
+        .unwrap();
+        assert!(rule_applies(&listed, "BBCA", false));
+        assert!(!rule_applies(&listed, "TLKM", true));
+    }
+
+    #[test]
+    fn test_rule_owner() {
+        let rule = AlertRule::new("dip", "rsi < 30", vec![], AlertPriority::Medium).unwrap();
+        let row = AlertRuleRow {
+            id: 3,
+            owner: "budi".to_string(),
+            name: rule.name.clone(),
+            expression: "rsi < 30".to_string(),
+            symbols: vec![],
+            priority: "medium".to_string(),
+            enabled: true,
+            last_triggered_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            deleted_at: None,
+        };
+        let rules = vec![(row, rule)];
+        let matched = |rule_id| {
+            Alert::Technical(TechnicalAlert::new(
+                "BBCA".to_string(),
+                TechnicalAlertType::RuleMatched {
+                    rule_id,
+                    name: "dip".to_string(),
+                    condition: "rsi < 30".to_string(),
+                },
+                AlertPriority::Medium,
+            ))
+        };
+
+        assert_eq!(rule_owner(&matched(3), &rules), Some("budi"));
+        assert_eq!(rule_owner(&matched(4), &rules), None);
+        let oversold = Alert::Technical(TechnicalAlert::new(
+            "BBCA".to_string(),
+            TechnicalAlertType::RsiOversold { rsi: dec!(25) },
+            AlertPriority::Medium,
+        ));
+        assert_eq!(rule_owner(&oversold, &rules), None);
+    }
+
+    #[test]
+    fn test_alert_subscriptions_merge_preferences() {
+        let oversold = Alert::Technical(TechnicalAlert::new(
+            "BBCA".to_string(),
+            TechnicalAlertType::RsiOversold { rsi: dec!(25) },
+            AlertPriority::High,
+        ));
+        let preference = |user: &str, technical_alerts: bool| AlertSubscription {
+            user_id: user.to_string(),
+            symbols: vec!["BBCA".to_string()],
+            alert_types: AlertTypeFilter {
+                technical_alerts,
+                ..Default::default()
+            },
+            min_priority: AlertPriority::Low,
+            channels: vec![NotificationChannel::Telegram],
+            quiet_hours: None,
+        };
+        let recipients_of =
+            |user: &str| vec![(NotificationChannel::Telegram, format!("{}-chat", user))];
+        let recipients = |subscriptions: Vec<AlertSubscription>| {
+            subscriptions
+                .into_iter()
+                .map(|s| s.user_id)
+                .collect::<Vec<_>>()
+        };
+
+        // Without preferences only the admin's defaults apply
+        assert_eq!(
+            recipients(alert_subscriptions(
+                &oversold,
+                None,
+                "admin",
+                &[],
+                recipients_of
+            )),
+            ["admin-chat", "admin"]
+        );
+
+        // A preference covering the type replaces the defaults for it; a
+        // user who opted out of it gets nothing
+        let preferences = [preference("admin", true), preference("budi", false)];
+        assert_eq!(
+            recipients(alert_subscriptions(
+                &oversold,
+                None,
+                "admin",
+                &preferences,
+                recipients_of
+            )),
+            ["admin-chat"]
+        );
+
+        // The admin's defaults still route types their preference leaves out
+        let preferences = [preference("admin", false), preference("budi", true)];
+        assert_eq!(
+            recipients(alert_subscriptions(
+                &oversold,
+                None,
+                "admin",
+                &preferences,
+                recipients_of
+            )),
+            ["admin-chat", "admin", "budi-chat"]
+        );
+
+        // Rule alerts only reach the rule's owner
+        assert_eq!(
+            recipients(alert_subscriptions(
+                &oversold,
+                Some("budi"),
+                "admin",
+                &preferences,
+                recipients_of
+            )),
+            ["budi-chat"]
+        );
+    }
+}
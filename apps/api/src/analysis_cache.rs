@@ -0,0 +1,302 @@
+//! Cached full analysis and post-close warming
+//!
+//! Most full-analysis traffic arrives in the evening, when retail users
+//! review the day. The inputs only change with end-of-day data, so after the
+//! close the analysis of every liquid symbol is precomputed into Redis and
+//! kept until the next session opens, skipping weekends and exchange
+//! holidays. Requests are then served from the cached JSON without touching
+//! Postgres. A price write to a symbol drops its cached analysis (see
+//! [`crate::price_store`]), so a late or corrected bar is picked up by the
+//! next request. The default momentum ranking of the
+//! universe is warmed alongside, and the universe's indicator snapshots
+//! (see [`crate::screener::refresh_indicator_snapshots`]) are recomputed.
+//!
+//! Redis is optional: if it cannot be reached every request is computed.
+
+use crate::routes::analysis::{build_full_analysis, build_momentum_ranking, momentum_cache_key};
+use crate::screener::refresh_indicator_snapshots;
+use crate::AppState;
+use chrono::{Duration, NaiveTime, Utc};
+use futures_util::StreamExt;
+use jejakcuan_cache::{CacheClient, CacheKeys};
+use jejakcuan_db::repositories;
+use jejakcuan_technical::MomentumConfig;
+use rust_decimal_macros::dec;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+
+/// History window of the warmed analysis (the endpoint default)
+pub const WARM_DAYS: i32 = 90;
+
+/// Symbols averaging at least this daily traded value (IDR) over the last
+/// 20 days are warmed
+const LIQUID_MIN_TRADED_VALUE: rust_decimal::Decimal = dec!(1_000_000_000);
+
+/// Symbols computed at once while warming
+const WARM_CONCURRENCY: usize = 4;
+
+/// Redis-backed store of serialized full-analysis responses
+pub struct AnalysisCache {
+    redis_url: String,
+    client: OnceCell<Option<CacheClient>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+/// Lookups served from the cache since startup
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// `None` until the first lookup
+    pub hit_rate_pct: Option<f64>,
+}
+
+impl AnalysisCache {
+    /// Connects lazily on first use
+    pub fn new(redis_url: &str) -> Self {
+        Self {
+            redis_url: redis_url.to_string(),
+            client: OnceCell::new(),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    async fn client(&self) -> Option<CacheClient> {
+        self.client
+            .get_or_init(|| async {
+                match CacheClient::new(&self.redis_url).await {
+                    Ok(client) => Some(client),
+                    Err(e) => {
+                        tracing::warn!("Analysis cache disabled, Redis unavailable: {}", e);
+                        None
+                    }
+                }
+            })
+            .await
+            .clone()
+    }
+
+    /// Cached response JSON, if any
+    pub async fn get(&self, symbol: &str, days: i32) -> Option<String> {
+        self.get_key(&CacheKeys::full_analysis(symbol, days)).await
+    }
+
+    /// Cached JSON under any analysis key
+    pub async fn get_key(&self, key: &str) -> Option<String> {
+        let mut client = self.client().await?;
+        match client.get_raw(key).await {
+            Ok(json) => json,
+            Err(e) => {
+                tracing::warn!("Analysis cache read failed for {}: {}", key, e);
+                None
+            }
+        }
+    }
+
+    /// Count one request as served from the cache or computed
+    pub fn record_lookup(&self, hit: bool) {
+        let counter = if hit { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        let hits = self.hits.load(Ordering::Relaxed);
+        let misses = self.misses.load(Ordering::Relaxed);
+        let total = hits + misses;
+        CacheStats {
+            hits,
+            misses,
+            hit_rate_pct: (total > 0).then(|| hits as f64 / total as f64 * 100.0),
+        }
+    }
+
+    /// Store response JSON; failures are logged, not returned
+    pub async fn put(&self, symbol: &str, days: i32, json: &str, ttl: std::time::Duration) {
+        self.put_key(&CacheKeys::full_analysis(symbol, days), json, ttl)
+            .await;
+    }
+
+    /// Drop every cached full analysis of `symbol`; failures are logged
+    pub async fn invalidate(&self, symbol: &str) {
+        let Some(mut client) = self.client().await else {
+            return;
+        };
+        if let Err(e) = client
+            .delete_matching(&CacheKeys::full_analysis_pattern(symbol))
+            .await
+        {
+            tracing::warn!("Analysis cache invalidation failed for {}: {}", symbol, e);
+        }
+    }
+
+    /// Store JSON under any analysis key; failures are logged, not returned
+    pub async fn put_key(&self, key: &str, json: &str, ttl: std::time::Duration) {
+        let Some(mut client) = self.client().await else {
+            return;
+        };
+        if let Err(e) = client.set_raw_with_ttl(key, json, ttl).await {
+            tracing::warn!("Analysis cache write failed for {}: {}", key, e);
+        }
+    }
+}
+
+/// Outcome of one warming run
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct WarmReport {
+    pub warmed: usize,
+    pub failed: usize,
+    pub momentum_warmed: bool,
+    /// Indicator snapshots written
+    pub snapshots: u64,
+    pub elapsed_ms: u64,
+}
+
+/// Precompute the full analysis of every liquid symbol into the cache
+pub async fn warm(state: &AppState) -> Result<WarmReport, sqlx::Error> {
+    let started = std::time::Instant::now();
+    let now = Utc::now();
+    let symbols = repositories::prices::get_liquid_symbols(
+        &state.db,
+        now - Duration::days(20),
+        LIQUID_MIN_TRADED_VALUE,
+    )
+    .await?;
+    let ttl = (state.config.market_calendar.next_open(now) - now)
+        .to_std()
+        .unwrap_or(std::time::Duration::from_secs(60 * 60));
+
+    let results: Vec<bool> = futures_util::stream::iter(
+        symbols
+            .into_iter()
+            .map(|symbol| warm_symbol(state, symbol, ttl)),
+    )
+    .buffer_unordered(WARM_CONCURRENCY)
+    .collect()
+    .await;
+
+    let warmed = results.iter().filter(|ok| **ok).count();
+    Ok(WarmReport {
+        warmed,
+        failed: results.len() - warmed,
+        momentum_warmed: warm_momentum(state, ttl).await,
+        snapshots: refresh_snapshots(state).await,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+async fn warm_symbol(state: &AppState, symbol: String, ttl: std::time::Duration) -> bool {
+    let analysis = match build_full_analysis(state, &symbol, WARM_DAYS).await {
+        Ok(analysis) => analysis,
+        Err((_, e)) => {
+            tracing::debug!("Skipping analysis warm-up for {}: {}", symbol, e);
+            return false;
+        }
+    };
+    let Ok(json) = serde_json::to_string(&analysis) else {
+        return false;
+    };
+    state
+        .analysis_cache
+        .put(&symbol, WARM_DAYS, &json, ttl)
+        .await;
+    true
+}
+
+async fn warm_momentum(state: &AppState, ttl: std::time::Duration) -> bool {
+    let config = MomentumConfig::default();
+    let ranking = match build_momentum_ranking(state, &config).await {
+        Ok(ranking) => ranking,
+        Err((_, e)) => {
+            tracing::warn!("Skipping momentum ranking warm-up: {}", e);
+            return false;
+        }
+    };
+    let Ok(json) = serde_json::to_string(&ranking) else {
+        return false;
+    };
+    state
+        .analysis_cache
+        .put_key(&momentum_cache_key(&config), &json, ttl)
+        .await;
+    true
+}
+
+async fn refresh_snapshots(state: &AppState) -> u64 {
+    match refresh_indicator_snapshots(&state.db).await {
+        Ok(written) => written,
+        Err(e) => {
+            tracing::warn!("Indicator snapshot refresh failed: {}", e);
+            0
+        }
+    }
+}
+
+/// Run `warm` every trading day at `at` (UTC)
+pub fn spawn_warmer(state: Arc<AppState>, at: NaiveTime) {
+    tokio::spawn(async move {
+        loop {
+            let now = Utc::now();
+            let next = state.config.market_calendar.next_trading_day_at(now, at);
+            let wait = (next - now).to_std().unwrap_or_default();
+            tokio::time::sleep(wait).await;
+
+            match warm(&state).await {
+                Ok(report) => tracing::info!(
+                    "Warmed full analysis for {} symbols ({} failed) and {} indicator snapshots in {}ms",
+                    report.warmed,
+                    report.failed,
+                    report.snapshots,
+                    report.elapsed_ms
+                ),
+                Err(e) => tracing::warn!("Analysis warm-up failed: {}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, NaiveTime, Utc};
+    use jejakcuan_core::MarketCalendar;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_next_warm_run() {
+        let ten = NaiveTime::from_hms_opt(10, 0, 0).unwrap();
+        // Wednesday before and after the warm time
+        assert_eq!(
+            MarketCalendar::default().next_trading_day_at(at("2025-06-04T08:00:00Z"), ten),
+            at("2025-06-04T10:00:00Z")
+        );
+        assert_eq!(
+            MarketCalendar::default().next_trading_day_at(at("2025-06-04T10:00:00Z"), ten),
+            at("2025-06-05T10:00:00Z")
+        );
+        // Friday evening skips the weekend
+        assert_eq!(
+            MarketCalendar::default().next_trading_day_at(at("2025-06-06T12:00:00Z"), ten),
+            at("2025-06-09T10:00:00Z")
+        );
+    }
+
+    #[test]
+    fn test_next_session_open() {
+        // Warmed on Wednesday evening: valid until Thursday's open
+        assert_eq!(
+            MarketCalendar::default().next_open(at("2025-06-04T10:00:00Z")),
+            at("2025-06-05T02:00:00Z")
+        );
+        // Warmed on Friday: valid over the weekend
+        assert_eq!(
+            MarketCalendar::default().next_open(at("2025-06-06T10:00:00Z")),
+            at("2025-06-09T02:00:00Z")
+        );
+    }
+}
@@ -0,0 +1,263 @@
+//! Latency budget of the full analysis
+//!
+//! Every full analysis computed on request is timed stage by stage: the
+//! database fetches, each indicator, the broker aggregation and the JSON
+//! serialization. Stages are recorded through a task-local, so the section
+//! builders take no extra argument and timing outside a timed request is a
+//! no-op (the post-close warm-up is not counted). Sections run concurrently,
+//! so stage times can add up to more than the total.
+//!
+//! Each request's stages feed per-stage histograms, scraped in the
+//! Prometheus text format from `/api/admin/metrics`; `debug_timings=true`
+//! on the endpoint also returns the request's own breakdown. Counts reset
+//! on restart.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Histogram bucket upper bounds, seconds
+const BUCKETS: [f64; 12] = [
+    0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0,
+];
+
+/// Prometheus metric name of the stage histograms
+const METRIC: &str = "jejakcuan_analysis_stage_seconds";
+
+/// Stage name of the whole request
+pub const TOTAL_STAGE: &str = "total";
+
+tokio::task_local! {
+    static STAGES: Mutex<Vec<(&'static str, Duration)>>;
+}
+
+/// Time spent in one stage of a request
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct StageTiming {
+    pub stage: &'static str,
+    pub ms: f64,
+    /// Times the stage ran; its durations are summed
+    pub calls: u32,
+}
+
+/// Stage breakdown of one request, slowest stage first
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct TimingReport {
+    pub total_ms: f64,
+    pub stages: Vec<StageTiming>,
+}
+
+impl TimingReport {
+    fn new(total: Duration, recorded: Vec<(&'static str, Duration)>) -> Self {
+        let mut by_stage: BTreeMap<&'static str, (Duration, u32)> = BTreeMap::new();
+        for (stage, elapsed) in recorded {
+            let entry = by_stage.entry(stage).or_default();
+            entry.0 += elapsed;
+            entry.1 += 1;
+        }
+        let mut stages: Vec<StageTiming> = by_stage
+            .into_iter()
+            .map(|(stage, (elapsed, calls))| StageTiming {
+                stage,
+                ms: millis(elapsed),
+                calls,
+            })
+            .collect();
+        stages.sort_by(|a, b| b.ms.total_cmp(&a.ms));
+        Self {
+            total_ms: millis(total),
+            stages,
+        }
+    }
+}
+
+fn millis(d: Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
+
+/// Run `future` with stage recording on, returning its stage breakdown
+pub async fn timed<F: Future>(future: F) -> (F::Output, TimingReport) {
+    let started = Instant::now();
+    let (output, recorded) = STAGES
+        .scope(Mutex::new(Vec::new()), async {
+            let output = future.await;
+            let recorded = STAGES.with(|stages| {
+                std::mem::take(&mut *stages.lock().unwrap_or_else(|e| e.into_inner()))
+            });
+            (output, recorded)
+        })
+        .await;
+    (output, TimingReport::new(started.elapsed(), recorded))
+}
+
+/// Add `elapsed` to `stage` of the request being timed, if any
+pub fn record(stage: &'static str, elapsed: Duration) {
+    let _ = STAGES.try_with(|stages| {
+        stages
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push((stage, elapsed));
+    });
+}
+
+/// Run `f` as `stage`
+pub fn time<T>(stage: &'static str, f: impl FnOnce() -> T) -> T {
+    let started = Instant::now();
+    let output = f();
+    record(stage, started.elapsed());
+    output
+}
+
+/// Await `future` as `stage`
+pub async fn time_async<F: Future>(stage: &'static str, future: F) -> F::Output {
+    let started = Instant::now();
+    let output = future.await;
+    record(stage, started.elapsed());
+    output
+}
+
+#[derive(Debug, Clone, Default)]
+struct Histogram {
+    /// Observations per bucket of [`BUCKETS`], not cumulative
+    counts: [u64; BUCKETS.len()],
+    count: u64,
+    sum_secs: f64,
+}
+
+impl Histogram {
+    fn observe(&mut self, secs: f64) {
+        if let Some(i) = BUCKETS.iter().position(|bound| secs <= *bound) {
+            self.counts[i] += 1;
+        }
+        self.count += 1;
+        self.sum_secs += secs;
+    }
+}
+
+/// Per-stage latency histograms of the full analyses computed since startup
+#[derive(Debug, Default)]
+pub struct AnalysisTimings {
+    stages: Mutex<BTreeMap<&'static str, Histogram>>,
+}
+
+impl AnalysisTimings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count one request's stages and total
+    pub fn observe(&self, report: &TimingReport) {
+        let mut stages = self.stages.lock().unwrap_or_else(|e| e.into_inner());
+        stages
+            .entry(TOTAL_STAGE)
+            .or_default()
+            .observe(report.total_ms / 1000.0);
+        for stage in &report.stages {
+            stages
+                .entry(stage.stage)
+                .or_default()
+                .observe(stage.ms / 1000.0);
+        }
+    }
+
+    /// The histograms in the Prometheus text exposition format
+    pub fn prometheus(&self) -> String {
+        let stages = self.stages.lock().unwrap_or_else(|e| e.into_inner());
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "# HELP {METRIC} Time spent in each stage of a full analysis"
+        );
+        let _ = writeln!(out, "# TYPE {METRIC} histogram");
+        for (stage, histogram) in stages.iter() {
+            let mut cumulative = 0;
+            for (bound, count) in BUCKETS.iter().zip(histogram.counts) {
+                cumulative += count;
+                let _ = writeln!(
+                    out,
+                    "{METRIC}_bucket{{stage=\"{stage}\",le=\"{bound}\"}} {cumulative}"
+                );
+            }
+            let _ = writeln!(
+                out,
+                "{METRIC}_bucket{{stage=\"{stage}\",le=\"+Inf\"}} {}",
+                histogram.count
+            );
+            let _ = writeln!(
+                out,
+                "{METRIC}_sum{{stage=\"{stage}\"}} {}",
+                histogram.sum_secs
+            );
+            let _ = writeln!(
+                out,
+                "{METRIC}_count{{stage=\"{stage}\"}} {}",
+                histogram.count
+            );
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_timed_records_stages() {
+        let (value, report) = timed(async {
+            time("indicator.rsi", || {
+                std::thread::sleep(Duration::from_millis(2))
+            });
+            let (a, b) = tokio::join!(
+                time_async("db.prices", async { 1 }),
+                time_async("db.prices", async { 2 }),
+            );
+            a + b
+        })
+        .await;
+        assert_eq!(value, 3);
+        assert_eq!(report.stages.len(), 2);
+        // Slowest first
+        assert_eq!(report.stages[0].stage, "indicator.rsi");
+        assert!(report.stages[0].ms >= 2.0);
+        assert_eq!(report.stages[1].calls, 2);
+        assert!(report.total_ms >= report.stages[0].ms);
+
+        // Outside a timed request nothing is recorded
+        record("db.prices", Duration::from_millis(1));
+    }
+
+    #[test]
+    fn test_prometheus_histograms() {
+        let timings = AnalysisTimings::new();
+        let report = TimingReport::new(
+            Duration::from_millis(30),
+            vec![
+                ("db.prices", Duration::from_millis(20)),
+                ("serialize", Duration::from_micros(500)),
+            ],
+        );
+        timings.observe(&report);
+        timings.observe(&report);
+
+        let text = timings.prometheus();
+        assert!(text.contains("# TYPE jejakcuan_analysis_stage_seconds histogram"));
+        // 20ms falls in the 25ms bucket and every larger one
+        assert!(text.contains(
+            "jejakcuan_analysis_stage_seconds_bucket{stage=\"db.prices\",le=\"0.01\"} 0"
+        ));
+        assert!(text.contains(
+            "jejakcuan_analysis_stage_seconds_bucket{stage=\"db.prices\",le=\"0.025\"} 2"
+        ));
+        assert!(text.contains(
+            "jejakcuan_analysis_stage_seconds_bucket{stage=\"serialize\",le=\"0.001\"} 2"
+        ));
+        assert!(text.contains("jejakcuan_analysis_stage_seconds_count{stage=\"total\"} 2"));
+        assert!(
+            text.contains("jejakcuan_analysis_stage_seconds_bucket{stage=\"total\",le=\"+Inf\"} 2")
+        );
+    }
+}
@@ -15,9 +15,50 @@ use chrono::{Duration, Utc};
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
 
+/// Access level of a user; each role includes the ones below it
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    /// Reads, screens and the user's own watchlist, alerts and journal
+    #[default]
+    Viewer,
+    /// Also triggers data refreshes, scans and recomputation
+    Operator,
+    /// Everything, including configuration and secrets
+    Admin,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Viewer => "viewer",
+            Role::Operator => "operator",
+            Role::Admin => "admin",
+        }
+    }
+}
+
+impl std::str::FromStr for Role {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "viewer" => Ok(Role::Viewer),
+            "operator" => Ok(Role::Operator),
+            "admin" => Ok(Role::Admin),
+            other => Err(format!("Unknown role '{}'", other)),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Claims {
     pub sub: String,
+    /// Tokens issued before roles existed carry none and get the lowest
+    #[serde(default)]
+    pub role: Role,
     pub exp: i64,
     pub iat: i64,
 }
@@ -32,6 +73,7 @@ pub struct LoginRequest {
 pub struct LoginResponse {
     pub token: String,
     pub expires_at: i64,
+    pub role: Role,
 }
 
 #[derive(Debug)]
@@ -48,9 +90,13 @@ impl IntoResponse for AuthError {
 }
 
 /// Authenticated user extractor
-#[allow(dead_code)]
+///
+/// Access is decided by the [`authz`](crate::authz) policy before the
+/// handler runs; the extractor identifies the caller.
+#[derive(Debug, Clone)]
 pub struct AuthUser {
     pub username: String,
+    pub role: Role,
 }
 
 #[async_trait]
@@ -61,6 +107,11 @@ where
     type Rejection = AuthError;
 
     async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        // Already authenticated by the authorization layer
+        if let Some(user) = parts.extensions.get::<AuthUser>() {
+            return Ok(user.clone());
+        }
+
         // Try to get token from cookie first, then Authorization header
         let jar = parts
             .extract::<CookieJar>()
@@ -80,29 +131,44 @@ where
             })
             .ok_or_else(|| AuthError("No token provided".to_string()))?;
 
-        let secret = std::env::var("JWT_SECRET")
-            .unwrap_or_else(|_| "development_secret_change_in_production".to_string());
+        decode_token(&token)
+    }
+}
 
-        let token_data = decode::<Claims>(
-            &token,
-            &DecodingKey::from_secret(secret.as_bytes()),
-            &Validation::default(),
-        )
-        .map_err(|e| AuthError(format!("Invalid token: {}", e)))?;
+/// Validate a JWT and return the user it was issued to
+///
+/// Used directly where the token cannot travel in a cookie or header,
+/// e.g. browser WebSocket connections.
+pub fn decode_token(token: &str) -> Result<AuthUser, AuthError> {
+    let secret = jwt_secret();
 
-        Ok(AuthUser {
-            username: token_data.claims.sub,
-        })
-    }
+    let token_data = decode::<Claims>(
+        token,
+        &DecodingKey::from_secret(secret.as_bytes()),
+        &Validation::default(),
+    )
+    .map_err(|e| AuthError(format!("Invalid token: {}", e)))?;
+
+    Ok(AuthUser {
+        username: token_data.claims.sub,
+        role: token_data.claims.role,
+    })
+}
+
+/// Secret tokens are verified with
+pub fn jwt_secret() -> String {
+    std::env::var("JWT_SECRET")
+        .unwrap_or_else(|_| "development_secret_change_in_production".to_string())
 }
 
 /// Create JWT token
-pub fn create_token(username: &str, secret: &str) -> Result<LoginResponse, AuthError> {
+pub fn create_token(username: &str, role: Role, secret: &str) -> Result<LoginResponse, AuthError> {
     let now = Utc::now();
     let exp = now + Duration::hours(24);
 
     let claims = Claims {
         sub: username.to_string(),
+        role,
         exp: exp.timestamp(),
         iat: now.timestamp(),
     };
@@ -117,6 +183,7 @@ pub fn create_token(username: &str, secret: &str) -> Result<LoginResponse, AuthE
     Ok(LoginResponse {
         token,
         expires_at: exp.timestamp(),
+        role,
     })
 }
 
@@ -153,7 +220,7 @@ mod tests {
         let secret = "test_secret_123";
         let username = "testuser";
 
-        let result = create_token(username, secret);
+        let result = create_token(username, Role::Admin, secret);
         assert!(result.is_ok());
 
         let response = result.unwrap();
@@ -166,7 +233,7 @@ mod tests {
         let secret = "test_secret_456";
         let username = "admin";
 
-        let response = create_token(username, secret).unwrap();
+        let response = create_token(username, Role::Operator, secret).unwrap();
 
         // Decode the token to verify claims
         let token_data = decode::<Claims>(
@@ -177,6 +244,7 @@ mod tests {
         .unwrap();
 
         assert_eq!(token_data.claims.sub, username);
+        assert_eq!(token_data.claims.role, Role::Operator);
         assert!(token_data.claims.exp > token_data.claims.iat);
     }
 
@@ -199,6 +267,23 @@ mod tests {
         assert!(!verify_password("password", ""));
     }
 
+    #[test]
+    fn test_token_without_role_is_viewer() {
+        let now = Utc::now().timestamp();
+        let legacy = serde_json::json!({ "sub": "admin", "iat": now, "exp": now + 3600 });
+        let token = encode(
+            &Header::default(),
+            &legacy,
+            &EncodingKey::from_secret(jwt_secret().as_bytes()),
+        )
+        .unwrap();
+
+        let user = decode_token(&token).unwrap();
+        assert_eq!(user.role, Role::Viewer);
+        assert!(Role::Admin > Role::Operator && Role::Operator > Role::Viewer);
+        assert_eq!("Operator".parse::<Role>(), Ok(Role::Operator));
+    }
+
     #[test]
     fn test_auth_error_display() {
         let error = AuthError("test error message".to_string());
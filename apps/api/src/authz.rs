@@ -0,0 +1,435 @@
+//! Per-endpoint authorization policy
+//!
+//! [`POLICY`] maps route groups to the access they require: admin endpoints
+//! need the admin role, endpoints that trigger jobs or recomputation need
+//! operator, and viewers may read anything else under `/api` and write only
+//! their own watchlist, alerts, journal, portfolio, screens, backtests,
+//! custom indicators and notification channels. The policy is enforced by
+//! [`authorize`], a route layer on the whole router, so a handler's
+//! `AuthUser` argument only identifies the caller.
+//!
+//! Rules are checked in order and the first match wins. A route no rule
+//! covers is refused, so a new endpoint stays closed until it is classified.
+//! In demo mode, [`crate::demo`] admits guests to a few reads before this
+//! layer runs, as the `demo` viewer.
+
+use crate::auth::{AuthUser, Role};
+use axum::{
+    extract::{FromRequestParts, Request},
+    http::{Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+
+/// What a rule lets through
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    /// No token needed, or the handler checks it itself
+    Public,
+    /// An authenticated user with at least this role
+    Role(Role),
+}
+
+/// Request methods a rule applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Methods {
+    Any,
+    /// GET, HEAD and OPTIONS
+    Read,
+    /// Everything else
+    Write,
+}
+
+impl Methods {
+    fn matches(self, method: &Method) -> bool {
+        let read = matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS);
+        match self {
+            Methods::Any => true,
+            Methods::Read => read,
+            Methods::Write => !read,
+        }
+    }
+}
+
+/// One line of the policy
+#[derive(Debug, Clone, Copy)]
+pub struct Rule {
+    pub methods: Methods,
+    /// Path segments; `:name` matches any one segment, a trailing `*` any rest
+    pub pattern: &'static str,
+    pub access: Access,
+}
+
+const fn rule(methods: Methods, pattern: &'static str, access: Access) -> Rule {
+    Rule {
+        methods,
+        pattern,
+        access,
+    }
+}
+
+const OPERATOR: Access = Access::Role(Role::Operator);
+const ADMIN: Access = Access::Role(Role::Admin);
+const VIEWER: Access = Access::Role(Role::Viewer);
+
+/// The authorization matrix, checked top to bottom
+pub const POLICY: &[Rule] = &[
+    rule(Methods::Any, "/", Access::Public),
+    rule(Methods::Any, "/health", Access::Public),
+    rule(Methods::Any, "/api/auth/*", Access::Public),
+    // Browsers cannot set headers on WebSockets; the handler takes the
+    // token from the query string
+    rule(Methods::Read, "/api/ws/notifications", Access::Public),
+    // Shared backtest reports; the token in the path is the credential
+    rule(
+        Methods::Read,
+        "/api/shared/backtests/:token",
+        Access::Public,
+    ),
+    // Triggers: data refreshes, checks, scans and recomputation
+    rule(
+        Methods::Write,
+        "/api/admin/data-status/:source_id/refresh",
+        OPERATOR,
+    ),
+    rule(
+        Methods::Write,
+        "/api/admin/data-sources/:source_id/trigger",
+        OPERATOR,
+    ),
+    rule(
+        Methods::Write,
+        "/api/admin/data-sources/category/:category/trigger",
+        OPERATOR,
+    ),
+    rule(
+        Methods::Write,
+        "/api/admin/data-sources/parser-health/check",
+        OPERATOR,
+    ),
+    rule(
+        Methods::Write,
+        "/api/admin/data-sources/sla/check",
+        OPERATOR,
+    ),
+    rule(
+        Methods::Write,
+        "/api/admin/data-sources/quota/check",
+        OPERATOR,
+    ),
+    // Backfills run for hours; operators start, resume and follow them
+    rule(
+        Methods::Any,
+        "/api/admin/data-sources/twelvedata/backfill/*",
+        OPERATOR,
+    ),
+    rule(Methods::Write, "/api/admin/alerts/scan", OPERATOR),
+    rule(Methods::Write, "/api/admin/stocks/sync-universe", OPERATOR),
+    rule(Methods::Write, "/api/admin/news/ingest", OPERATOR),
+    rule(
+        Methods::Write,
+        "/api/admin/corporate-actions/ingest",
+        OPERATOR,
+    ),
+    rule(Methods::Write, "/api/admin/shareholdings/ingest", OPERATOR),
+    rule(Methods::Write, "/api/admin/earnings/sync", OPERATOR),
+    rule(Methods::Write, "/api/admin/jobs/:job_id/cancel", OPERATOR),
+    rule(Methods::Write, "/api/stocks/scores/recompute", OPERATOR),
+    rule(
+        Methods::Write,
+        "/api/stocks/scores/fundamental-history/backfill",
+        OPERATOR,
+    ),
+    rule(Methods::Write, "/api/stocks/:symbol/refresh", OPERATOR),
+    rule(
+        Methods::Write,
+        "/api/stocks/:symbol/refresh/:source_type",
+        OPERATOR,
+    ),
+    rule(Methods::Write, "/api/analysis/macro/refresh", OPERATOR),
+    // Depth feeds post order book snapshots
+    rule(Methods::Write, "/api/analysis/:symbol/order-book", OPERATOR),
+    // Operators follow the jobs they trigger
+    rule(Methods::Read, "/api/admin/jobs/*", OPERATOR),
+    // Administration: configuration, secrets, staging, imports, maintenance
+    rule(Methods::Any, "/api/admin/*", ADMIN),
+    rule(Methods::Write, "/api/symbols/mappings", ADMIN),
+    rule(Methods::Write, "/api/symbols/mappings/*", ADMIN),
+    // Each user's own watchlist, alerts, journal, portfolio and research
+    rule(Methods::Write, "/api/watchlist/*", VIEWER),
+    rule(Methods::Write, "/api/alerts/*", VIEWER),
+    rule(Methods::Write, "/api/journal/*", VIEWER),
+    rule(Methods::Write, "/api/portfolio/*", VIEWER),
+    rule(Methods::Write, "/api/screens/*", VIEWER),
+    rule(Methods::Write, "/api/backtests/*", VIEWER),
+    rule(Methods::Write, "/api/custom-indicators/*", VIEWER),
+    rule(Methods::Write, "/api/notifications/preview", VIEWER),
+    rule(Methods::Write, "/api/notifications/channels/*", VIEWER),
+    // Ad-hoc screens post their filters
+    rule(Methods::Write, "/api/stocks/screen", VIEWER),
+    // Reads
+    rule(Methods::Read, "/api/*", VIEWER),
+];
+
+/// Whether `path` matches a policy pattern
+pub(crate) fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let mut pattern = pattern.split('/').filter(|s| !s.is_empty());
+    let mut path = path.split('/').filter(|s| !s.is_empty());
+    loop {
+        match (pattern.next(), path.next()) {
+            (Some("*"), _) => return true,
+            (Some(expected), Some(actual)) => {
+                if !expected.starts_with(':') && expected != actual {
+                    return false;
+                }
+            }
+            (None, None) => return true,
+            _ => return false,
+        }
+    }
+}
+
+/// Access required for `method` on `path`; `None` when no rule covers it
+pub fn required_access(method: &Method, path: &str) -> Option<Access> {
+    POLICY
+        .iter()
+        .find(|rule| rule.methods.matches(method) && pattern_matches(rule.pattern, path))
+        .map(|rule| rule.access)
+}
+
+fn forbidden(message: String) -> Response {
+    (
+        StatusCode::FORBIDDEN,
+        Json(serde_json::json!({ "error": message })),
+    )
+        .into_response()
+}
+
+/// Enforce [`POLICY`]; the authenticated user is passed on to the handler
+pub async fn authorize(request: Request, next: Next) -> Response {
+    let path = request.uri().path().to_string();
+    let required = match required_access(request.method(), &path) {
+        Some(Access::Public) => return next.run(request).await,
+        Some(Access::Role(role)) => role,
+        None => {
+            return forbidden(format!(
+                "No authorization rule for {} {}",
+                request.method(),
+                path
+            ))
+        }
+    };
+
+    let (mut parts, body) = request.into_parts();
+    let user = match AuthUser::from_request_parts(&mut parts, &()).await {
+        Ok(user) => user,
+        Err(e) => return e.into_response(),
+    };
+    if user.role < required {
+        return forbidden(format!(
+            "{} {} requires the {} role",
+            parts.method,
+            path,
+            required.as_str()
+        ));
+    }
+
+    parts.extensions.insert(user);
+    next.run(Request::from_parts(parts, body)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{create_token, jwt_secret};
+    use axum::{body::Body, middleware, routing::get, Router};
+    use tower::ServiceExt;
+
+    #[test]
+    fn test_policy_matrix() {
+        let access = |method: Method, path: &str| required_access(&method, path);
+
+        assert_eq!(
+            access(Method::POST, "/api/auth/login"),
+            Some(Access::Public)
+        );
+        assert_eq!(access(Method::GET, "/health"), Some(Access::Public));
+        assert_eq!(
+            access(Method::GET, "/api/shared/backtests/0f3a9c"),
+            Some(Access::Public)
+        );
+        assert_eq!(access(Method::POST, "/api/backtests/7/share"), Some(VIEWER));
+        assert_eq!(access(Method::GET, "/api/stocks/BBCA"), Some(VIEWER));
+        assert_eq!(access(Method::POST, "/api/watchlist"), Some(VIEWER));
+        assert_eq!(
+            access(Method::POST, "/api/stocks/scores/recompute"),
+            Some(OPERATOR)
+        );
+        assert_eq!(
+            access(Method::POST, "/api/admin/data-sources/twelvedata/trigger"),
+            Some(OPERATOR)
+        );
+        assert_eq!(access(Method::GET, "/api/admin/jobs/abc"), Some(OPERATOR));
+        assert_eq!(
+            access(Method::POST, "/api/admin/data-sources/twelvedata/backfill"),
+            Some(OPERATOR)
+        );
+        assert_eq!(
+            access(Method::GET, "/api/admin/data-sources/twelvedata/backfill/3"),
+            Some(OPERATOR)
+        );
+        // The trigger route's GET siblings are admin reads
+        assert_eq!(
+            access(Method::GET, "/api/admin/data-sources/twelvedata"),
+            Some(ADMIN)
+        );
+        assert_eq!(
+            access(Method::PUT, "/api/admin/secrets/SECTORS_API_KEY"),
+            Some(ADMIN)
+        );
+        assert_eq!(
+            access(Method::POST, "/api/admin/staging/7/approve"),
+            Some(ADMIN)
+        );
+        assert_eq!(access(Method::GET, "/api/admin/metrics"), Some(ADMIN));
+        assert_eq!(access(Method::PUT, "/api/symbols/mappings"), Some(ADMIN));
+        assert_eq!(access(Method::GET, "/api/symbols/mappings"), Some(VIEWER));
+        assert_eq!(
+            access(Method::DELETE, "/api/symbols/mappings/BBCA/sectors"),
+            Some(ADMIN)
+        );
+        assert_eq!(
+            access(Method::POST, "/api/stocks/BBCA/refresh/broker"),
+            Some(OPERATOR)
+        );
+        assert_eq!(
+            access(Method::POST, "/api/analysis/macro/refresh"),
+            Some(OPERATOR)
+        );
+        assert_eq!(access(Method::DELETE, "/api/watchlist/BBCA"), Some(VIEWER));
+        assert_eq!(access(Method::POST, "/api/stocks/screen"), Some(VIEWER));
+        // Outside the matrix: refused
+        assert_eq!(access(Method::GET, "/metrics"), None);
+        assert_eq!(access(Method::POST, "/api/stocks/BBCA/unknown"), None);
+    }
+
+    async fn status(app: &Router, method: Method, uri: &str, role: Option<Role>) -> StatusCode {
+        let mut request = axum::http::Request::builder().method(method).uri(uri);
+        if let Some(role) = role {
+            let token = create_token("tester", role, &jwt_secret()).unwrap().token;
+            request = request.header("Authorization", format!("Bearer {}", token));
+        }
+        app.clone()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn test_roles_are_enforced_centrally() {
+        let app = Router::new()
+            .route("/api/stocks", get(|| async { "stocks" }))
+            .route(
+                "/api/stocks/scores/recompute",
+                axum::routing::post(|| async { "ok" }),
+            )
+            // Nested like the real admin router
+            .nest(
+                "/api/admin",
+                Router::new().route(
+                    "/secrets",
+                    get(|user: AuthUser| async move { user.username }),
+                ),
+            )
+            .route("/metrics", get(|| async { "metrics" }))
+            .route_layer(middleware::from_fn(authorize));
+
+        assert_eq!(
+            status(&app, Method::GET, "/api/stocks", None).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status(&app, Method::GET, "/api/stocks", Some(Role::Viewer)).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(
+                &app,
+                Method::POST,
+                "/api/stocks/scores/recompute",
+                Some(Role::Viewer)
+            )
+            .await,
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            status(
+                &app,
+                Method::POST,
+                "/api/stocks/scores/recompute",
+                Some(Role::Operator)
+            )
+            .await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(
+                &app,
+                Method::GET,
+                "/api/admin/secrets",
+                Some(Role::Operator)
+            )
+            .await,
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            status(&app, Method::GET, "/api/admin/secrets", Some(Role::Admin)).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(&app, Method::GET, "/metrics", Some(Role::Admin)).await,
+            StatusCode::FORBIDDEN
+        );
+    }
+
+    #[tokio::test]
+    async fn test_viewers_cannot_trigger_writes() {
+        let ok = || async { "ok" };
+        let app = Router::new()
+            .route(
+                "/api/stocks/:symbol/refresh/:source_type",
+                axum::routing::post(ok),
+            )
+            .route("/api/analysis/macro/refresh", axum::routing::post(ok))
+            .route(
+                "/api/symbols/mappings/:symbol/:provider",
+                axum::routing::delete(ok),
+            )
+            .route_layer(middleware::from_fn(authorize));
+
+        for (method, uri, role) in [
+            (
+                Method::POST,
+                "/api/stocks/BBCA/refresh/broker",
+                Role::Operator,
+            ),
+            (Method::POST, "/api/analysis/macro/refresh", Role::Operator),
+            (
+                Method::DELETE,
+                "/api/symbols/mappings/BBCA/sectors",
+                Role::Admin,
+            ),
+        ] {
+            assert_eq!(
+                status(&app, method.clone(), uri, Some(Role::Viewer)).await,
+                StatusCode::FORBIDDEN,
+                "{} {}",
+                method,
+                uri
+            );
+            assert_eq!(status(&app, method, uri, Some(role)).await, StatusCode::OK);
+        }
+    }
+}
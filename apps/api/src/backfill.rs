@@ -0,0 +1,666 @@
+//! Historical intraday backfill from TwelveData `time_series`
+//!
+//! A backfill job covers every active symbol at each requested interval
+//! (1h and 15min by default). Pages of up to [`PAGE_SIZE`] bars are walked
+//! back from the job's end date, and each page's cursor is stored in
+//! `backfill_progress` before the next request, so a job interrupted by a
+//! restart or a failing symbol resumes from where it stopped. Requests are
+//! paced to the plan's credits per minute, and bars are upserted into
+//! `intraday_prices` alongside the ones built from the price stream.
+//!
+//! A run also stops, leaving the job `failed` and resumable, once the day's
+//! TwelveData requests reach the daily cap or its admin job is cancelled.
+
+use crate::routes::jobs::{Job, JobHandle, JobKind};
+use crate::routes::symbols::load_symbol_mapper;
+use crate::AppState;
+use chrono::{Duration, FixedOffset, NaiveDate, Utc};
+use jejakcuan_data_sources::twelvedata::{Interval, TimeSeriesResponse, TwelveDataClient};
+use jejakcuan_data_sources::{ApiProvider, SymbolProvider};
+use jejakcuan_db::{repositories, BackfillJobRow, BackfillProgressRow, InsertIntradayBar};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use tokio::time::Instant;
+
+/// `backfill_jobs.source` of TwelveData jobs
+pub const BACKFILL_SOURCE: &str = "twelvedata";
+
+/// Job source id in the admin jobs list
+const JOB_SOURCE_ID: &str = "backfill:twelvedata";
+
+/// Intervals backfilled when the request names none
+pub const DEFAULT_BACKFILL_INTERVALS: [Interval; 2] = [Interval::Hour1, Interval::Min15];
+
+/// Days backfilled when the request gives no start date
+const DEFAULT_LOOKBACK_DAYS: i64 = 90;
+
+/// Most bars TwelveData returns per `time_series` request
+pub const PAGE_SIZE: usize = 5000;
+
+/// Credits per minute of one free-tier key; a pool of keys gets a multiple
+const CREDITS_PER_MINUTE_PER_KEY: u32 = 8;
+
+/// Daily requests a backfill may use per pooled key, leaving part of the
+/// free tier's 800 for the live callers
+const DAILY_REQUESTS_PER_KEY: u32 = 600;
+
+/// WIB (UTC+7), the time zone TwelveData reports IDX bars in
+const WIB_OFFSET_SECS: i32 = 7 * 3600;
+
+/// Spaces requests so no more than `per_minute` go out in any minute
+#[derive(Debug)]
+pub struct CreditThrottle {
+    per_minute: usize,
+    sent: VecDeque<Instant>,
+}
+
+impl CreditThrottle {
+    pub fn new(per_minute: u32) -> Self {
+        Self {
+            per_minute: per_minute.max(1) as usize,
+            sent: VecDeque::new(),
+        }
+    }
+
+    /// How long to wait at `now` before the next request may go out
+    fn delay(&mut self, now: Instant) -> std::time::Duration {
+        let window = std::time::Duration::from_secs(60);
+        while self
+            .sent
+            .front()
+            .is_some_and(|sent| now.duration_since(*sent) >= window)
+        {
+            self.sent.pop_front();
+        }
+        if self.sent.len() < self.per_minute {
+            return std::time::Duration::ZERO;
+        }
+        window - now.duration_since(self.sent[0])
+    }
+
+    /// Wait for a free credit and spend it
+    pub async fn acquire(&mut self) {
+        let wait = self.delay(Instant::now());
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+        let now = Instant::now();
+        self.delay(now);
+        self.sent.push_back(now);
+    }
+}
+
+/// Parameters of a new backfill job
+#[derive(Debug, Clone)]
+pub struct BackfillPlan {
+    pub intervals: Vec<Interval>,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+}
+
+impl BackfillPlan {
+    /// Plan from request values, defaulting to the last 90 days at 1h and 15min
+    pub fn new(
+        intervals: Option<&[String]>,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+        today: NaiveDate,
+    ) -> Result<Self, String> {
+        let intervals = match intervals {
+            Some(names) if !names.is_empty() => names
+                .iter()
+                .map(|name| {
+                    Interval::from_str_opt(name)
+                        .filter(|interval| interval.duration().is_some())
+                        .ok_or_else(|| format!("'{}' is not an intraday interval", name))
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            _ => DEFAULT_BACKFILL_INTERVALS.to_vec(),
+        };
+        let end_date = end_date.unwrap_or(today);
+        let start_date = start_date.unwrap_or(end_date - Duration::days(DEFAULT_LOOKBACK_DAYS));
+        if start_date > end_date {
+            return Err(format!(
+                "start_date {} is after end_date {}",
+                start_date, end_date
+            ));
+        }
+        Ok(Self {
+            intervals,
+            start_date,
+            end_date,
+        })
+    }
+}
+
+/// End date of the page after one that returned `bars` bars, the oldest
+/// on `oldest`; `None` once the range is exhausted
+///
+/// The next page ends the day after the oldest bar, so a day split across
+/// pages is fetched whole again; the upsert makes the overlap harmless.
+/// The cursor must move back for the walk to end.
+pub fn next_cursor(
+    bars: usize,
+    oldest: Option<NaiveDate>,
+    cursor: NaiveDate,
+    start_date: NaiveDate,
+) -> Option<NaiveDate> {
+    if bars < PAGE_SIZE {
+        return None;
+    }
+    let oldest = oldest?;
+    let next = oldest + Duration::days(1);
+    (oldest > start_date && next < cursor).then_some(next)
+}
+
+/// Rows for `intraday_prices` from a page, dropping bars before the job start
+pub fn to_insert_bars(
+    symbol: &str,
+    interval: Interval,
+    response: &TimeSeriesResponse,
+    start_date: NaiveDate,
+) -> Vec<InsertIntradayBar> {
+    let wib = FixedOffset::east_opt(WIB_OFFSET_SECS).expect("valid offset");
+    response
+        .values
+        .iter()
+        .filter_map(|point| {
+            let time = point.time(wib)?;
+            (time.with_timezone(&wib).date_naive() >= start_date).then(|| InsertIntradayBar {
+                time,
+                symbol: symbol.to_string(),
+                interval: interval.as_str().to_string(),
+                open: point.open,
+                high: point.high,
+                low: point.low,
+                close: point.close,
+                volume: point.volume.unwrap_or(0),
+                ticks: 0,
+            })
+        })
+        .collect()
+}
+
+/// Client over the configured TwelveData keys, counted in the usage tracker
+///
+/// Uses the shared client when keys were configured at startup, so the
+/// backfill's calls show in the key health; otherwise a key stored since.
+async fn twelvedata_client(state: &AppState) -> Result<TwelveDataClient, String> {
+    if let Some(client) = &state.twelvedata {
+        return Ok(client.clone());
+    }
+    let keys: Vec<String> = crate::secrets::resolve_secret(state, "TWELVEDATA_API_KEY")
+        .await
+        .into_iter()
+        .chain(state.config.twelvedata_pool_keys.iter().cloned())
+        .collect();
+    TwelveDataClient::with_keys(keys)
+        .map(|client| client.with_usage_tracker(state.api_usage.clone()))
+        .map_err(|e| e.to_string())
+}
+
+/// Why a run stopped before working through its pairs
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Halt {
+    Cancelled,
+    /// Today's TwelveData requests reached the cap
+    DailyCap(u32),
+}
+
+impl Halt {
+    fn message(&self) -> String {
+        match self {
+            Halt::Cancelled => "Cancelled by user".to_string(),
+            Halt::DailyCap(cap) => format!(
+                "Daily cap of {} TwelveData requests reached; resume after 00:00 UTC",
+                cap
+            ),
+        }
+    }
+}
+
+/// Outcome of a pair that did not finish
+#[derive(Debug)]
+enum PairError {
+    Failed(String),
+    Halted(Halt),
+}
+
+impl From<String> for PairError {
+    fn from(e: String) -> Self {
+        PairError::Failed(e)
+    }
+}
+
+/// Limits a run checks before each request
+struct RunLimits {
+    handle: JobHandle,
+    daily_requests: u32,
+}
+
+impl RunLimits {
+    /// Whether the run must stop before its next request
+    fn halt(&self, state: &AppState) -> Option<Halt> {
+        if self.handle.is_cancelled() {
+            return Some(Halt::Cancelled);
+        }
+        let today: u64 = state
+            .api_usage
+            .provider_forecasts(ApiProvider::TwelveData)
+            .iter()
+            .map(|forecast| forecast.requests_today)
+            .sum();
+        (today >= u64::from(self.daily_requests)).then_some(Halt::DailyCap(self.daily_requests))
+    }
+}
+
+/// Fetch and store the remaining pages of one (symbol, interval) pair
+async fn backfill_pair(
+    state: &AppState,
+    client: &TwelveDataClient,
+    throttle: &mut CreditThrottle,
+    limits: &RunLimits,
+    job: &BackfillJobRow,
+    mut progress: BackfillProgressRow,
+    provider_symbol: &str,
+) -> Result<u64, PairError> {
+    let interval = Interval::from_str_opt(&progress.interval)
+        .ok_or_else(|| format!("unknown interval {}", progress.interval))?;
+    let mut written = 0u64;
+    loop {
+        // Exclusive end; the first page includes the job's end date
+        let cursor = progress
+            .cursor_date
+            .unwrap_or(job.end_date + Duration::days(1));
+        throttle.acquire().await;
+        if let Some(halt) = limits.halt(state) {
+            return Err(PairError::Halted(halt));
+        }
+        let response = client
+            .time_series(
+                provider_symbol,
+                interval,
+                Some(PAGE_SIZE as i32),
+                Some(job.start_date),
+                Some(cursor),
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let bars = to_insert_bars(&progress.symbol, interval, &response, job.start_date);
+        repositories::intraday_prices::upsert_intraday_bars(&state.db, &bars)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let wib = FixedOffset::east_opt(WIB_OFFSET_SECS).expect("valid offset");
+        let oldest = bars
+            .iter()
+            .map(|bar| bar.time.with_timezone(&wib).date_naive())
+            .min();
+        let next = next_cursor(response.values.len(), oldest, cursor, job.start_date);
+        let count = i32::try_from(bars.len()).unwrap_or(i32::MAX);
+        repositories::backfill_jobs::record_backfill_page(
+            &state.db,
+            &progress,
+            next.or(Some(cursor)),
+            count,
+            next.is_none(),
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+        written += bars.len() as u64;
+
+        match next {
+            Some(next) => progress.cursor_date = Some(next),
+            None => return Ok(written),
+        }
+    }
+}
+
+/// Work through a job's unfinished pairs
+///
+/// A failing pair keeps its cursor and the job moves on; the job ends as
+/// `failed` if any pair is left, ready to be resumed. Cancellation and the
+/// daily cap stop the run at the next request.
+async fn run_backfill(
+    state: Arc<AppState>,
+    client: TwelveDataClient,
+    credits_per_minute: u32,
+    limits: RunLimits,
+    job: BackfillJobRow,
+) -> Result<String, String> {
+    let db_error = |e: sqlx::Error| e.to_string();
+    repositories::backfill_jobs::set_backfill_job_status(&state.db, job.id, "running", None)
+        .await
+        .map_err(db_error)?;
+
+    let (pending, mapper) = match tokio::try_join!(
+        repositories::backfill_jobs::get_pending_backfill_progress(&state.db, job.id),
+        load_symbol_mapper(&state.db)
+    ) {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            let error = e.to_string();
+            let _ = repositories::backfill_jobs::set_backfill_job_status(
+                &state.db,
+                job.id,
+                "failed",
+                Some(&error),
+            )
+            .await;
+            return Err(error);
+        }
+    };
+
+    let mut throttle = CreditThrottle::new(credits_per_minute);
+    let mut written = 0u64;
+    let mut failures = Vec::new();
+    let mut halted = None;
+    let total = job.tasks_total.max(0) as u64;
+    let mut done = total.saturating_sub(pending.len() as u64);
+    for progress in pending {
+        let provider_symbol = mapper.to_provider(&progress.symbol, SymbolProvider::TwelveData);
+        let pair = format!("{} {}", progress.symbol, progress.interval);
+        let result = backfill_pair(
+            &state,
+            &client,
+            &mut throttle,
+            &limits,
+            &job,
+            progress.clone(),
+            &provider_symbol,
+        )
+        .await;
+        match result {
+            Ok(bars) => written += bars,
+            Err(PairError::Halted(halt)) => {
+                halted = Some(halt);
+                break;
+            }
+            Err(PairError::Failed(e)) => {
+                tracing::warn!("Backfill {} of job {} failed: {}", pair, job.id, e);
+                if let Err(e) =
+                    repositories::backfill_jobs::record_backfill_error(&state.db, &progress, &e)
+                        .await
+                {
+                    tracing::warn!("Failed to record backfill error for {}: {}", pair, e);
+                }
+                failures.push(format!("{}: {}", pair, e));
+            }
+        }
+        done += 1;
+        limits.handle.set_progress(done, total).await;
+    }
+
+    let summary = format!("Backfill job {} wrote {} bars", job.id, written);
+    if let Some(halt) = halted {
+        let error = halt.message();
+        repositories::backfill_jobs::set_backfill_job_status(
+            &state.db,
+            job.id,
+            "failed",
+            Some(&error),
+        )
+        .await
+        .map_err(db_error)?;
+        return Err(format!("{}; {}", summary, error));
+    }
+    if failures.is_empty() {
+        repositories::backfill_jobs::set_backfill_job_status(&state.db, job.id, "completed", None)
+            .await
+            .map_err(db_error)?;
+        Ok(summary)
+    } else {
+        let error = format!("{} pairs failed, first: {}", failures.len(), failures[0]);
+        repositories::backfill_jobs::set_backfill_job_status(
+            &state.db,
+            job.id,
+            "failed",
+            Some(&error),
+        )
+        .await
+        .map_err(db_error)?;
+        Err(format!("{}; {}\n{}", summary, error, failures.join("\n")))
+    }
+}
+
+/// Why a backfill could not start
+#[derive(Debug)]
+pub enum BackfillError {
+    /// A backfill job is already running
+    Running(Job),
+    NotFound(i64),
+    /// Resuming a job that has nothing left
+    Completed(i64),
+    Invalid(String),
+    Unavailable(String),
+    Database(sqlx::Error),
+}
+
+impl From<sqlx::Error> for BackfillError {
+    fn from(e: sqlx::Error) -> Self {
+        BackfillError::Database(e)
+    }
+}
+
+/// Run `job` as a tracked job
+async fn spawn(
+    state: &Arc<AppState>,
+    job: BackfillJobRow,
+    credits_per_minute: Option<u32>,
+) -> Result<Job, BackfillError> {
+    if let Some(running) = state.job_manager.is_source_running(JOB_SOURCE_ID).await {
+        return Err(BackfillError::Running(running));
+    }
+    let client = twelvedata_client(state)
+        .await
+        .map_err(BackfillError::Unavailable)?;
+    let keys = client.key_health().len() as u32;
+    let credits_per_minute = credits_per_minute.unwrap_or(CREDITS_PER_MINUTE_PER_KEY * keys);
+    let daily_requests = state
+        .config
+        .backfill_daily_requests
+        .unwrap_or(DAILY_REQUESTS_PER_KEY * keys);
+
+    let description = format!(
+        "backfill {} from {} to {} at {} credits/min, up to {} requests/day",
+        job.intervals.join(", "),
+        job.start_date,
+        job.end_date,
+        credits_per_minute,
+        daily_requests
+    );
+    let task_state = state.clone();
+    Ok(state
+        .job_manager
+        .spawn_task_with(
+            JOB_SOURCE_ID.to_string(),
+            "TwelveData intraday backfill".to_string(),
+            description,
+            JobKind::DataSource,
+            |handle| {
+                let limits = RunLimits {
+                    handle,
+                    daily_requests,
+                };
+                run_backfill(task_state, client, credits_per_minute, limits, job)
+            },
+        )
+        .await)
+}
+
+/// Create a job for every active symbol and start it
+pub async fn start(
+    state: &Arc<AppState>,
+    plan: BackfillPlan,
+    credits_per_minute: Option<u32>,
+) -> Result<(BackfillJobRow, Job), BackfillError> {
+    if let Some(running) = state.job_manager.is_source_running(JOB_SOURCE_ID).await {
+        return Err(BackfillError::Running(running));
+    }
+    let symbols: Vec<String> = repositories::stocks::get_all_stocks(&state.db)
+        .await?
+        .into_iter()
+        .map(|stock| stock.symbol)
+        .collect();
+    if symbols.is_empty() {
+        return Err(BackfillError::Invalid("No active symbols".to_string()));
+    }
+    let intervals: Vec<String> = plan
+        .intervals
+        .iter()
+        .map(|interval| interval.as_str().to_string())
+        .collect();
+    let job = repositories::backfill_jobs::create_backfill_job(
+        &state.db,
+        BACKFILL_SOURCE,
+        &intervals,
+        plan.start_date,
+        plan.end_date,
+        &symbols,
+    )
+    .await?;
+    let tracked = spawn(state, job.clone(), credits_per_minute).await?;
+    Ok((job, tracked))
+}
+
+/// Restart an interrupted or failed job from its stored cursors
+pub async fn resume(
+    state: &Arc<AppState>,
+    job_id: i64,
+    credits_per_minute: Option<u32>,
+) -> Result<(BackfillJobRow, Job), BackfillError> {
+    let job = repositories::backfill_jobs::get_backfill_job(&state.db, job_id)
+        .await?
+        .filter(|job| job.source == BACKFILL_SOURCE)
+        .ok_or(BackfillError::NotFound(job_id))?;
+    if job.tasks_done == job.tasks_total {
+        return Err(BackfillError::Completed(job_id));
+    }
+    let tracked = spawn(state, job.clone(), credits_per_minute).await?;
+    Ok((job, tracked))
+}
+
+/// Today's date in WIB, the default end of a backfill
+pub fn today() -> NaiveDate {
+    let wib = FixedOffset::east_opt(WIB_OFFSET_SECS).expect("valid offset");
+    Utc::now().with_timezone(&wib).date_naive()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jejakcuan_data_sources::twelvedata::{TimeSeriesMeta, TimeSeriesPoint};
+    use rust_decimal_macros::dec;
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_plan_defaults_and_validation() {
+        let plan = BackfillPlan::new(None, None, None, date("2025-06-30")).unwrap();
+        assert_eq!(plan.intervals, DEFAULT_BACKFILL_INTERVALS.to_vec());
+        assert_eq!(plan.end_date, date("2025-06-30"));
+        assert_eq!(plan.start_date, date("2025-04-01"));
+
+        let intervals = vec!["5min".to_string()];
+        let plan = BackfillPlan::new(Some(&intervals), None, None, date("2025-06-30")).unwrap();
+        assert_eq!(plan.intervals, vec![Interval::Min5]);
+
+        let daily = vec!["1day".to_string()];
+        assert!(BackfillPlan::new(Some(&daily), None, None, date("2025-06-30")).is_err());
+        assert!(BackfillPlan::new(
+            None,
+            Some(date("2025-07-01")),
+            Some(date("2025-06-01")),
+            date("2025-06-30")
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_next_cursor() {
+        let cursor = date("2025-06-30");
+        let start = date("2025-01-01");
+        // A short page is the last one
+        assert_eq!(
+            next_cursor(120, Some(date("2025-05-01")), cursor, start),
+            None
+        );
+        // A full page continues from the day after its oldest bar
+        assert_eq!(
+            next_cursor(PAGE_SIZE, Some(date("2025-05-01")), cursor, start),
+            Some(date("2025-05-02"))
+        );
+        // Reaching the start, or not moving back, ends the walk
+        assert_eq!(next_cursor(PAGE_SIZE, Some(start), cursor, start), None);
+        assert_eq!(
+            next_cursor(PAGE_SIZE, Some(date("2025-06-29")), cursor, start),
+            None
+        );
+        assert_eq!(next_cursor(PAGE_SIZE, None, cursor, start), None);
+    }
+
+    #[test]
+    fn test_to_insert_bars() {
+        let point = |datetime: &str| TimeSeriesPoint {
+            datetime: datetime.to_string(),
+            open: dec!(9000),
+            high: dec!(9100),
+            low: dec!(8950),
+            close: dec!(9050),
+            volume: Some(1_200_000),
+        };
+        let response = TimeSeriesResponse {
+            meta: TimeSeriesMeta {
+                symbol: "BBCA".to_string(),
+                interval: "1h".to_string(),
+                currency: None,
+                exchange_timezone: Some("Asia/Jakarta".to_string()),
+                exchange: None,
+                mic_code: None,
+                instrument_type: None,
+            },
+            values: vec![
+                point("2025-06-02 10:00:00"),
+                point("2025-06-02 09:00:00"),
+                point("2025-05-30 15:00:00"),
+            ],
+            status: Some("ok".to_string()),
+        };
+
+        let bars = to_insert_bars("BBCA", Interval::Hour1, &response, date("2025-06-01"));
+        assert_eq!(bars.len(), 2);
+        assert_eq!(bars[0].interval, "1h");
+        assert_eq!(bars[0].ticks, 0);
+        // 10:00 WIB is 03:00 UTC
+        assert_eq!(
+            bars[0].time,
+            "2025-06-02T03:00:00Z"
+                .parse::<chrono::DateTime<Utc>>()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_credit_throttle() {
+        let mut throttle = CreditThrottle::new(2);
+        let t0 = Instant::now();
+        assert!(throttle.delay(t0).is_zero());
+        throttle.sent.push_back(t0);
+        throttle
+            .sent
+            .push_back(t0 + std::time::Duration::from_secs(10));
+
+        // Both credits spent: wait for the first to leave the window
+        assert_eq!(
+            throttle.delay(t0 + std::time::Duration::from_secs(20)),
+            std::time::Duration::from_secs(40)
+        );
+        assert!(throttle
+            .delay(t0 + std::time::Duration::from_secs(60))
+            .is_zero());
+        assert_eq!(throttle.sent.len(), 1);
+    }
+}
@@ -0,0 +1,256 @@
+//! Load-test runner for the hot API endpoints
+//!
+//! Usage: load_test [--base-url http://localhost:8080] [--requests 200]
+//!                  [--concurrency 8] [--symbols 300] [--recompute] [--json]
+//!
+//! Meant for a database seeded by `seed_synthetic`; requests rotate over
+//! the same synthetic symbols. Logs in as AUTH_USERNAME with
+//! LOAD_TEST_PASSWORD (default admin123), runs each scenario in turn and
+//! prints latency percentiles and throughput per scenario. Needs the
+//! `synthetic` feature.
+
+use futures_util::StreamExt;
+use jejakcuan_api::synthetic::{generate_universe, SyntheticConfig};
+use reqwest::{Client, Method};
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+fn arg<T: std::str::FromStr>(args: &[String], name: &str, default: T) -> T {
+    args.iter()
+        .position(|a| a == name)
+        .and_then(|i| args.get(i + 1))
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+/// One endpoint exercised by the run
+struct Scenario {
+    name: &'static str,
+    method: Method,
+    /// Path for the `i`th request
+    path: Box<dyn Fn(usize) -> String>,
+    body: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize)]
+struct ScenarioReport {
+    name: &'static str,
+    requests: usize,
+    errors: usize,
+    p50_ms: f64,
+    p95_ms: f64,
+    p99_ms: f64,
+    max_ms: f64,
+    requests_per_sec: f64,
+}
+
+/// `q`th percentile of sorted `samples` (nearest rank)
+fn percentile(samples: &[Duration], q: f64) -> f64 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    let rank = ((q / 100.0) * samples.len() as f64).ceil() as usize;
+    samples[rank.clamp(1, samples.len()) - 1].as_secs_f64() * 1000.0
+}
+
+async fn run_scenario(
+    client: &Client,
+    base_url: &str,
+    token: &str,
+    scenario: &Scenario,
+    requests: usize,
+    concurrency: usize,
+) -> ScenarioReport {
+    let started = Instant::now();
+    let results: Vec<(Duration, bool)> = futures_util::stream::iter(0..requests)
+        .map(|i| {
+            let mut request = client
+                .request(
+                    scenario.method.clone(),
+                    format!("{}{}", base_url, (scenario.path)(i)),
+                )
+                .bearer_auth(token);
+            if let Some(body) = &scenario.body {
+                request = request.json(body);
+            }
+            async move {
+                let sent = Instant::now();
+                let ok = match request.send().await {
+                    Ok(response) => {
+                        let ok = response.status().is_success();
+                        // Include the body transfer in the latency
+                        ok && response.bytes().await.is_ok()
+                    }
+                    Err(_) => false,
+                };
+                (sent.elapsed(), ok)
+            }
+        })
+        .buffer_unordered(concurrency)
+        .collect()
+        .await;
+    let elapsed = started.elapsed().as_secs_f64();
+
+    let mut latencies: Vec<Duration> = results.iter().map(|(d, _)| *d).collect();
+    latencies.sort();
+    ScenarioReport {
+        name: scenario.name,
+        requests,
+        errors: results.iter().filter(|(_, ok)| !ok).count(),
+        p50_ms: percentile(&latencies, 50.0),
+        p95_ms: percentile(&latencies, 95.0),
+        p99_ms: percentile(&latencies, 99.0),
+        max_ms: percentile(&latencies, 100.0),
+        requests_per_sec: requests as f64 / elapsed.max(f64::EPSILON),
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    dotenvy::dotenv().ok();
+    let args: Vec<String> = std::env::args().collect();
+    let base_url: String = arg(&args, "--base-url", "http://localhost:8080".to_string());
+    let requests: usize = arg(&args, "--requests", 200);
+    let concurrency: usize = arg(&args, "--concurrency", 8).max(1);
+    let symbol_count: usize = arg(&args, "--symbols", SyntheticConfig::default().symbols);
+    let recompute = args.iter().any(|a| a == "--recompute");
+    let json = args.iter().any(|a| a == "--json");
+
+    let client = Client::new();
+    let username = std::env::var("AUTH_USERNAME").unwrap_or_else(|_| "admin".to_string());
+    let password = std::env::var("LOAD_TEST_PASSWORD").unwrap_or_else(|_| "admin123".to_string());
+    let login: serde_json::Value = client
+        .post(format!("{}/api/auth/login", base_url))
+        .json(&serde_json::json!({ "username": username, "password": password }))
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .expect("Login failed")
+        .json()
+        .await
+        .expect("Invalid login response");
+    let token = login["token"].as_str().expect("No token").to_string();
+
+    let symbols: Vec<String> = generate_universe(&SyntheticConfig {
+        symbols: symbol_count.max(1),
+        ..Default::default()
+    })
+    .into_iter()
+    .map(|s| s.symbol)
+    .collect();
+    let symbol = move |i: usize| symbols[i % symbols.len()].clone();
+    let score_symbol = symbol.clone();
+
+    let mut scenarios = Vec::new();
+    if recompute {
+        scenarios.push((
+            Scenario {
+                name: "recompute scores",
+                method: Method::POST,
+                path: Box::new(|_| "/api/stocks/scores/recompute".to_string()),
+                body: None,
+            },
+            1,
+        ));
+    }
+    scenarios.extend([
+        (
+            Scenario {
+                name: "list stocks",
+                method: Method::GET,
+                path: Box::new(|_| "/api/stocks".to_string()),
+                body: None,
+            },
+            requests,
+        ),
+        (
+            Scenario {
+                name: "top scores",
+                method: Method::GET,
+                path: Box::new(|_| "/api/stocks/scores/top".to_string()),
+                body: None,
+            },
+            requests,
+        ),
+        (
+            Scenario {
+                name: "stock score",
+                method: Method::GET,
+                path: Box::new(move |i| format!("/api/stocks/{}/score", score_symbol(i))),
+                body: None,
+            },
+            requests,
+        ),
+        (
+            Scenario {
+                name: "full analysis",
+                method: Method::GET,
+                path: Box::new(move |i| format!("/api/analysis/{}/analysis", symbol(i))),
+                body: None,
+            },
+            requests,
+        ),
+        (
+            Scenario {
+                name: "momentum ranking",
+                method: Method::GET,
+                path: Box::new(|_| "/api/analysis/momentum".to_string()),
+                body: None,
+            },
+            requests,
+        ),
+        (
+            Scenario {
+                name: "screen (scores)",
+                method: Method::POST,
+                path: Box::new(|_| "/api/stocks/screen".to_string()),
+                body: Some(serde_json::json!({
+                    "filter": { "type": "composite_score_above", "value": "60" }
+                })),
+            },
+            requests,
+        ),
+        (
+            Scenario {
+                name: "screen (indicators)",
+                method: Method::POST,
+                path: Box::new(|_| "/api/stocks/screen".to_string()),
+                body: Some(serde_json::json!({
+                    "filter": {
+                        "type": "all",
+                        "filters": [
+                            { "type": "rsi_below", "value": "40" },
+                            { "type": "rvol_above", "value": "1.5" }
+                        ]
+                    }
+                })),
+            },
+            requests.div_ceil(10),
+        ),
+    ]);
+
+    let mut reports = Vec::new();
+    for (scenario, count) in &scenarios {
+        let report = run_scenario(&client, &base_url, &token, scenario, *count, concurrency).await;
+        if !json {
+            println!(
+                "{:<22} {:>6} req {:>5} err  p50 {:>8.1}ms  p95 {:>8.1}ms  p99 {:>8.1}ms  max {:>8.1}ms  {:>8.1} req/s",
+                report.name,
+                report.requests,
+                report.errors,
+                report.p50_ms,
+                report.p95_ms,
+                report.p99_ms,
+                report.max_ms,
+                report.requests_per_sec
+            );
+        }
+        reports.push(report);
+    }
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&reports).expect("Serializable report")
+        );
+    }
+}
@@ -0,0 +1,75 @@
+//! Seed a scratch database with synthetic market data for load testing
+//!
+//! Usage: seed_synthetic [--symbols 300] [--years 3] [--brokers 6] [--seed 42] [--force]
+//!
+//! Refuses to write into a database that already lists real stocks unless
+//! `--force` is given. Needs the `synthetic` feature:
+//! `cargo run -p jejakcuan-api --features synthetic --bin seed_synthetic`.
+
+use jejakcuan_api::synthetic::{seed, SyntheticConfig, SYNTHETIC_PREFIX};
+use jejakcuan_db::repositories;
+
+fn arg<T: std::str::FromStr>(args: &[String], name: &str, default: T) -> T {
+    args.iter()
+        .position(|a| a == name)
+        .and_then(|i| args.get(i + 1))
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+#[tokio::main]
+async fn main() {
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| "seed_synthetic=info,jejakcuan_api=info".into()),
+        )
+        .init();
+    dotenvy::dotenv().ok();
+
+    let args: Vec<String> = std::env::args().collect();
+    let defaults = SyntheticConfig::default();
+    let config = SyntheticConfig {
+        symbols: arg(&args, "--symbols", defaults.symbols),
+        years: arg(&args, "--years", defaults.years),
+        brokers_per_day: arg(&args, "--brokers", defaults.brokers_per_day),
+        seed: arg(&args, "--seed", defaults.seed),
+    };
+    let force = args.iter().any(|a| a == "--force");
+
+    let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let pool = jejakcuan_db::create_pool(&database_url)
+        .await
+        .expect("Failed to connect to database");
+
+    let real = repositories::stocks::get_all_stocks(&pool)
+        .await
+        .expect("Failed to list stocks")
+        .iter()
+        .filter(|s| !s.symbol.starts_with(SYNTHETIC_PREFIX))
+        .count();
+    if real > 0 && !force {
+        eprintln!(
+            "Database already lists {} real stocks; seed a scratch database or pass --force",
+            real
+        );
+        std::process::exit(1);
+    }
+
+    println!(
+        "Seeding {} symbols x {} years, {} brokers per session (seed {})",
+        config.symbols, config.years, config.brokers_per_day, config.seed
+    );
+    let started = std::time::Instant::now();
+    let report = seed(&pool, &config).await.expect("Seeding failed");
+    println!(
+        "Seeded {} stocks over {} sessions: {} price rows, {} broker rows, {} index rows in {:.1}s",
+        report.stocks,
+        report.sessions,
+        report.price_rows,
+        report.broker_rows,
+        report.index_rows,
+        started.elapsed().as_secs_f64()
+    );
+    println!("Run POST /api/stocks/scores/recompute (or load_test --recompute) to score them");
+}
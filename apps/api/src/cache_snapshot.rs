@@ -0,0 +1,80 @@
+//! Cache snapshot on shutdown and restore on startup
+//!
+//! Keeps cached analyses, momentum rankings and sector rotation warm across
+//! deploys. Failures are logged and never stop the server: a cold cache is
+//! slower, not broken.
+
+use jejakcuan_cache::{default_snapshot_patterns, unix_now, CacheClient, CacheSnapshot};
+use std::path::Path;
+use std::time::Duration;
+
+/// Snapshots older than this are not restored
+const SNAPSHOT_MAX_AGE: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Restore missing hot keys from the snapshot file, or the copy kept in Redis
+pub async fn restore(redis_url: &str, path: &str) {
+    let mut cache = match CacheClient::new(redis_url).await {
+        Ok(cache) => cache,
+        Err(e) => {
+            tracing::warn!("Cache snapshot not restored, Redis unavailable: {}", e);
+            return;
+        }
+    };
+
+    let snapshot = match CacheSnapshot::load(Path::new(path)) {
+        Ok(Some(snapshot)) => Some(snapshot),
+        Ok(None) => cache.load_stored_snapshot().await.ok().flatten(),
+        Err(e) => {
+            tracing::warn!("Failed to read cache snapshot {}: {}", path, e);
+            cache.load_stored_snapshot().await.ok().flatten()
+        }
+    };
+    let Some(snapshot) = snapshot else {
+        tracing::info!("No cache snapshot to restore");
+        return;
+    };
+    if snapshot.is_stale(unix_now(), SNAPSHOT_MAX_AGE) {
+        tracing::info!(
+            "Cache snapshot from {} is too old to restore",
+            snapshot.taken_at
+        );
+        return;
+    }
+
+    match cache.restore_snapshot(&snapshot).await {
+        Ok(report) => tracing::info!(
+            "Restored {} cache keys ({} already present, {} expired)",
+            report.restored,
+            report.skipped_existing,
+            report.expired
+        ),
+        Err(e) => tracing::warn!("Cache snapshot restore failed: {}", e),
+    }
+}
+
+/// Snapshot hot keys to the file and to Redis
+pub async fn save(redis_url: &str, path: &str) {
+    let mut cache = match CacheClient::new(redis_url).await {
+        Ok(cache) => cache,
+        Err(e) => {
+            tracing::warn!("Cache snapshot skipped, Redis unavailable: {}", e);
+            return;
+        }
+    };
+
+    let snapshot = match cache.snapshot(&default_snapshot_patterns()).await {
+        Ok(snapshot) => snapshot,
+        Err(e) => {
+            tracing::warn!("Cache snapshot failed: {}", e);
+            return;
+        }
+    };
+
+    if let Err(e) = snapshot.save(Path::new(path)) {
+        tracing::warn!("Failed to write cache snapshot {}: {}", path, e);
+    }
+    if let Err(e) = cache.store_snapshot(&snapshot).await {
+        tracing::warn!("Failed to store cache snapshot in Redis: {}", e);
+    }
+    tracing::info!("Snapshotted {} cache keys", snapshot.entries.len());
+}
@@ -0,0 +1,67 @@
+//! Before/after snapshots of user configuration edits
+//!
+//! Watchlist and alert rule handlers record each change through the audit
+//! logger; the history routes read them back.
+
+use crate::AppState;
+use jejakcuan_audit::{change_event, get_change_history, ChangeHistoryQuery, ChangeRecord};
+use serde::Serialize;
+
+/// Changes returned when the query sets no limit
+const DEFAULT_HISTORY_LIMIT: i64 = 50;
+
+/// Most changes returned by one query
+const MAX_HISTORY_LIMIT: i64 = 500;
+
+/// Requested page size, defaulted and capped
+pub fn history_limit(limit: Option<i64>) -> i64 {
+    limit
+        .unwrap_or(DEFAULT_HISTORY_LIMIT)
+        .clamp(1, MAX_HISTORY_LIMIT)
+}
+
+/// Queue a change of `resource_type`/`resource_id` made by `username`
+pub async fn record_change<T: Serialize>(
+    state: &AppState,
+    username: &str,
+    action: &str,
+    resource_type: &str,
+    resource_id: &str,
+    before: Option<&T>,
+    after: Option<&T>,
+) {
+    let snapshot = |row: Option<&T>| row.and_then(|r| serde_json::to_value(r).ok());
+    state
+        .audit
+        .log(change_event(
+            username,
+            action,
+            resource_type,
+            resource_id,
+            snapshot(before),
+            snapshot(after),
+        ))
+        .await;
+}
+
+/// Recorded changes of a resource type, newest first
+pub async fn load_history(
+    state: &AppState,
+    query: &ChangeHistoryQuery<'_>,
+) -> Result<Vec<ChangeRecord>, (axum::http::StatusCode, String)> {
+    get_change_history(&state.db, query)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_limit() {
+        assert_eq!(history_limit(None), DEFAULT_HISTORY_LIMIT);
+        assert_eq!(history_limit(Some(0)), 1);
+        assert_eq!(history_limit(Some(10_000)), MAX_HISTORY_LIMIT);
+    }
+}
@@ -1,6 +1,23 @@
 //! Application configuration
 
+use crate::auth::Role;
+use crate::usage::{ActorPrivacy, UsageAnalyticsConfig};
+use chrono::NaiveTime;
+use jejakcuan_core::{MarketCalendar, RiskBudgetConfig, TransactionCostModel};
+use jejakcuan_data_sources::SymbolProvider;
+use jejakcuan_db::SecretCipher;
+use rust_decimal::Decimal;
 use std::env;
+use std::time::Duration;
+
+/// Login account besides the primary admin
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserAccount {
+    pub username: String,
+    pub role: Role,
+    /// Argon2 PHC string
+    pub password_hash: String,
+}
 
 #[derive(Debug, Clone)]
 pub struct Config {
@@ -9,8 +26,75 @@ pub struct Config {
     pub jwt_secret: String,
     pub username: String,
     pub password_hash: String,
+    /// Further accounts with their roles; the primary user is always admin
+    pub users: Vec<UserAccount>,
     pub host: String,
     pub port: u16,
+    /// Telegram bot token used for operator notifications
+    pub telegram_bot_token: Option<String>,
+    /// Telegram chat that receives data-source SLA alerts
+    pub admin_telegram_chat_id: Option<String>,
+    /// Webhook URL that receives data-source SLA alerts
+    pub admin_webhook_url: Option<String>,
+    /// Discord incoming webhook that receives alerts
+    pub admin_discord_webhook_url: Option<String>,
+    /// Slack incoming webhook that receives alerts
+    pub admin_slack_webhook_url: Option<String>,
+    /// WhatsApp Cloud API sender phone number id
+    pub whatsapp_phone_number_id: Option<String>,
+    /// WhatsApp Cloud API access token
+    pub whatsapp_access_token: Option<String>,
+    /// Approved alert template; the notifier default is used when unset
+    pub whatsapp_template_name: Option<String>,
+    /// WhatsApp number that receives alerts, international format
+    pub admin_whatsapp_number: Option<String>,
+    /// Portfolio risk limits checked whenever open positions change
+    pub risk_budget: RiskBudgetConfig,
+    /// Fees, tax and slippage charged to simulated trades
+    pub transaction_costs: TransactionCostModel,
+    /// File the hot Redis cache is snapshotted to on shutdown and restored from on startup
+    pub cache_snapshot_path: Option<String>,
+    /// Daily time (UTC) the full analysis of liquid symbols is precomputed
+    /// into the cache; `None` disables warming
+    pub analysis_warm_time_utc: Option<NaiveTime>,
+    /// How often the alert scheduler checks for new data; `None` disables it
+    pub alert_scan_interval: Option<Duration>,
+    /// How often liquid symbols are rescored during the session; `None`
+    /// disables the intraday score ticker. While enabled, the price stream
+    /// runs and carries the ticker's symbols
+    pub score_ticker_interval: Option<Duration>,
+    /// Stream watchlisted symbols from TwelveData and rescore them on each
+    /// closed 5-minute bar
+    pub realtime_scoring: bool,
+    /// Weigh technical sub-scores as percentiles within the stock's sector
+    /// after each score recompute
+    pub sector_relative_technical: bool,
+    /// Days soft-deleted watchlist items and alert rules stay restorable
+    pub soft_delete_retention_days: i64,
+    /// Daily time (UTC) database maintenance runs; `None` disables it
+    pub maintenance_time_utc: Option<NaiveTime>,
+    /// Delivery attempts before a failed notification becomes a dead letter
+    pub notification_max_attempts: u32,
+    /// Exchange holidays, for market-hours cache TTLs and scheduled jobs
+    pub market_calendar: MarketCalendar,
+    /// Encrypts credentials stored through the admin API; `None` without a
+    /// master key, which disables stored secrets
+    pub secret_cipher: Option<SecretCipher>,
+    /// Delay of the data guests see in demo mode; `None` disables demo mode
+    pub demo_data_delay: Option<Duration>,
+    /// What usage events keep about the user, and for how long
+    pub usage_analytics: UsageAnalyticsConfig,
+    /// Quote and history providers in failover priority order
+    pub price_providers: Vec<SymbolProvider>,
+    /// Primary TwelveData API key
+    pub twelvedata_api_key: Option<String>,
+    /// Further TwelveData keys pooled with the primary one
+    pub twelvedata_pool_keys: Vec<String>,
+    /// Sectors.app API key
+    pub sectors_api_key: Option<String>,
+    /// TwelveData requests per UTC day, by any caller, after which a
+    /// backfill stops; `None` allows 600 per pooled key
+    pub backfill_daily_requests: Option<u32>,
 }
 
 impl Config {
@@ -30,11 +114,288 @@ impl Config {
                 // Default password: "admin123" - CHANGE IN PRODUCTION
                 "$argon2id$v=19$m=19456,t=2,p=1$random_salt_here$hashed_password".to_string()
             }),
+            users: users_from_env(),
             host: env::var("HOST").unwrap_or_else(|_| "0.0.0.0".to_string()),
             port: env::var("PORT")
                 .unwrap_or_else(|_| "8080".to_string())
                 .parse()
                 .unwrap_or(8080),
+            telegram_bot_token: env::var("TELEGRAM_BOT_TOKEN")
+                .ok()
+                .filter(|v| !v.is_empty()),
+            admin_telegram_chat_id: env::var("ADMIN_TELEGRAM_CHAT_ID")
+                .ok()
+                .filter(|v| !v.is_empty()),
+            admin_webhook_url: env::var("ADMIN_WEBHOOK_URL").ok().filter(|v| !v.is_empty()),
+            admin_discord_webhook_url: env::var("ADMIN_DISCORD_WEBHOOK_URL")
+                .ok()
+                .filter(|v| !v.is_empty()),
+            admin_slack_webhook_url: env::var("ADMIN_SLACK_WEBHOOK_URL")
+                .ok()
+                .filter(|v| !v.is_empty()),
+            whatsapp_phone_number_id: env::var("WHATSAPP_PHONE_NUMBER_ID")
+                .ok()
+                .filter(|v| !v.is_empty()),
+            whatsapp_access_token: env::var("WHATSAPP_ACCESS_TOKEN")
+                .ok()
+                .filter(|v| !v.is_empty()),
+            whatsapp_template_name: env::var("WHATSAPP_TEMPLATE_NAME")
+                .ok()
+                .filter(|v| !v.is_empty()),
+            admin_whatsapp_number: env::var("ADMIN_WHATSAPP_NUMBER")
+                .ok()
+                .filter(|v| !v.is_empty()),
+            risk_budget: risk_budget_from_env(),
+            transaction_costs: transaction_costs_from_env(),
+            cache_snapshot_path: env::var("CACHE_SNAPSHOT_PATH")
+                .ok()
+                .filter(|v| !v.is_empty()),
+            // 17:00 WIB, after the close and the end-of-day imports
+            analysis_warm_time_utc: match env::var("ANALYSIS_WARM_TIME_UTC") {
+                Ok(v) => NaiveTime::parse_from_str(&v, "%H:%M").ok(),
+                Err(_) => NaiveTime::from_hms_opt(10, 0, 0),
+            },
+            alert_scan_interval: match env::var("ALERT_SCAN_INTERVAL_SECS") {
+                Ok(v) => v
+                    .parse::<u64>()
+                    .ok()
+                    .filter(|secs| *secs > 0)
+                    .map(Duration::from_secs),
+                Err(_) => Some(Duration::from_secs(300)),
+            },
+            score_ticker_interval: match env::var("SCORE_TICKER_INTERVAL_SECS") {
+                Ok(v) => v
+                    .parse::<u64>()
+                    .ok()
+                    .filter(|secs| *secs > 0)
+                    .map(Duration::from_secs),
+                Err(_) => Some(Duration::from_secs(300)),
+            },
+            realtime_scoring: env::var("REALTIME_SCORING")
+                .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+                .unwrap_or(false),
+            sector_relative_technical: env::var("SECTOR_RELATIVE_TECHNICAL")
+                .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+                .unwrap_or(false),
+            soft_delete_retention_days: env::var("SOFT_DELETE_RETENTION_DAYS")
+                .ok()
+                .and_then(|v| v.parse::<i64>().ok())
+                .filter(|days| *days > 0)
+                .unwrap_or(30),
+            // 02:00 WIB, outside market hours and the end-of-day imports
+            maintenance_time_utc: match env::var("MAINTENANCE_TIME_UTC") {
+                Ok(v) => NaiveTime::parse_from_str(&v, "%H:%M").ok(),
+                Err(_) => NaiveTime::from_hms_opt(19, 0, 0),
+            },
+            notification_max_attempts: env::var("NOTIFICATION_MAX_ATTEMPTS")
+                .ok()
+                .and_then(|v| v.parse::<u32>().ok())
+                .filter(|attempts| *attempts > 0)
+                .unwrap_or(5),
+            market_calendar: market_calendar_from_env(),
+            secret_cipher: secret_cipher_from_env(),
+            demo_data_delay: demo_data_delay_from_env(),
+            usage_analytics: usage_analytics_from_env(),
+            price_providers: price_providers_from_env(),
+            twelvedata_api_key: env::var("TWELVEDATA_API_KEY")
+                .ok()
+                .filter(|v| !v.is_empty()),
+            twelvedata_pool_keys: twelvedata_pool_keys_from_env(),
+            sectors_api_key: env::var("SECTORS_API_KEY").ok().filter(|v| !v.is_empty()),
+            backfill_daily_requests: env::var("BACKFILL_DAILY_REQUESTS")
+                .ok()
+                .and_then(|v| v.parse::<u32>().ok())
+                .filter(|requests| *requests > 0),
+        }
+    }
+
+    /// Every configured TwelveData key, the primary one first
+    pub fn twelvedata_keys(&self) -> Vec<String> {
+        self.twelvedata_api_key
+            .iter()
+            .chain(&self.twelvedata_pool_keys)
+            .cloned()
+            .collect()
+    }
+}
+
+/// Accounts from `AUTH_USERS`: `name:role:argon2-hash` entries separated by `;`
+fn users_from_env() -> Vec<UserAccount> {
+    env::var("AUTH_USERS")
+        .map(|list| parse_users(&list))
+        .unwrap_or_default()
+}
+
+fn parse_users(list: &str) -> Vec<UserAccount> {
+    list.split(';')
+        .filter(|entry| !entry.trim().is_empty())
+        .filter_map(|entry| {
+            let mut fields = entry.trim().splitn(3, ':');
+            let (Some(username), Some(role), Some(password_hash)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                tracing::warn!("Ignoring AUTH_USERS entry without name:role:hash");
+                return None;
+            };
+            match role.parse::<Role>() {
+                Ok(role) => Some(UserAccount {
+                    username: username.to_string(),
+                    role,
+                    password_hash: password_hash.to_string(),
+                }),
+                Err(e) => {
+                    tracing::warn!("Ignoring AUTH_USERS entry for {}: {}", username, e);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// Demo mode from `DEMO_MODE` (`true`/`1`) and `DEMO_DELAY_MINUTES` (default 15)
+fn demo_data_delay_from_env() -> Option<Duration> {
+    let enabled = env::var("DEMO_MODE")
+        .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+        .unwrap_or(false);
+    if !enabled {
+        return None;
+    }
+    let minutes = env::var("DEMO_DELAY_MINUTES")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(15);
+    Some(Duration::from_secs(minutes * 60))
+}
+
+/// Usage analytics from `USAGE_ANALYTICS`, `USAGE_ANALYTICS_SALT` and
+/// `USAGE_RETENTION_DAYS`; the salt defaults to the JWT secret
+fn usage_analytics_from_env() -> UsageAnalyticsConfig {
+    let defaults = UsageAnalyticsConfig::default();
+    let privacy = match env::var("USAGE_ANALYTICS") {
+        Ok(v) => ActorPrivacy::parse(&v).unwrap_or_else(|| {
+            tracing::warn!("Ignoring invalid USAGE_ANALYTICS: {}", v);
+            defaults.privacy
+        }),
+        Err(_) => defaults.privacy,
+    };
+    UsageAnalyticsConfig {
+        privacy,
+        salt: env::var("USAGE_ANALYTICS_SALT")
+            .ok()
+            .filter(|v| !v.is_empty())
+            .or_else(|| env::var("JWT_SECRET").ok())
+            .unwrap_or_default(),
+        retention_days: env::var("USAGE_RETENTION_DAYS")
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .filter(|days| *days > 0)
+            .unwrap_or(defaults.retention_days),
+    }
+}
+
+/// Master keys from `SECRETS_MASTER_KEY` (or `SECRETS_MASTER_KEY_FILE`)
+/// and `SECRETS_PREVIOUS_KEYS`
+fn secret_cipher_from_env() -> Option<SecretCipher> {
+    SecretCipher::from_env().unwrap_or_else(|e| {
+        tracing::warn!("Stored secrets disabled: {}", e);
+        None
+    })
+}
+
+/// Provider priority from `PRICE_PROVIDERS`, comma-separated
+fn price_providers_from_env() -> Vec<SymbolProvider> {
+    let default = vec![
+        SymbolProvider::Yahoo,
+        SymbolProvider::TwelveData,
+        SymbolProvider::Sectors,
+    ];
+    let Ok(list) = env::var("PRICE_PROVIDERS") else {
+        return default;
+    };
+
+    let mut providers = Vec::new();
+    for name in list.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+        match SymbolProvider::from_str_opt(name) {
+            Some(SymbolProvider::Idx) | None => {
+                tracing::warn!(
+                    "Ignoring unknown price provider in PRICE_PROVIDERS: {}",
+                    name
+                )
+            }
+            Some(provider) if !providers.contains(&provider) => providers.push(provider),
+            Some(_) => {}
         }
     }
+    if providers.is_empty() {
+        return default;
+    }
+    providers
+}
+
+/// Holidays from `IDX_HOLIDAYS`, comma-separated `YYYY-MM-DD` dates
+fn market_calendar_from_env() -> MarketCalendar {
+    let Ok(list) = env::var("IDX_HOLIDAYS") else {
+        return MarketCalendar::default();
+    };
+    MarketCalendar::parse_holidays(&list).unwrap_or_else(|e| {
+        tracing::warn!("Ignoring invalid IDX_HOLIDAYS: {}", e);
+        MarketCalendar::default()
+    })
+}
+
+/// Pooled keys from `TWELVEDATA_API_KEYS`, comma-separated
+fn twelvedata_pool_keys_from_env() -> Vec<String> {
+    env::var("TWELVEDATA_API_KEYS")
+        .map(|list| {
+            list.split(',')
+                .map(str::trim)
+                .filter(|key| !key.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn risk_budget_from_env() -> RiskBudgetConfig {
+    let defaults = RiskBudgetConfig::default();
+    let pct = |key: &str, default: f64| {
+        env::var(key)
+            .ok()
+            .and_then(|v| v.parse::<f64>().ok())
+            .filter(|v| *v > 0.0)
+            .unwrap_or(default)
+    };
+
+    RiskBudgetConfig {
+        capital: env::var("RISK_CAPITAL")
+            .ok()
+            .and_then(|v| v.parse::<Decimal>().ok())
+            .filter(|v| *v > Decimal::ZERO),
+        max_position_weight_pct: pct("RISK_MAX_POSITION_PCT", defaults.max_position_weight_pct),
+        max_sector_weight_pct: pct("RISK_MAX_SECTOR_PCT", defaults.max_sector_weight_pct),
+        max_portfolio_risk_pct: pct("RISK_BUDGET_PCT", defaults.max_portfolio_risk_pct),
+        atr_multiple: pct("RISK_ATR_MULTIPLE", defaults.atr_multiple),
+    }
+}
+
+fn transaction_costs_from_env() -> TransactionCostModel {
+    let defaults = TransactionCostModel::default();
+    let bps = |key: &str, default: f64| {
+        env::var(key)
+            .ok()
+            .and_then(|v| v.parse::<f64>().ok())
+            .filter(|v| *v >= 0.0)
+            .unwrap_or(default)
+    };
+
+    TransactionCostModel {
+        broker_fee_bps: bps("COST_BROKER_FEE_BPS", defaults.broker_fee_bps),
+        levy_bps: bps("COST_LEVY_BPS", defaults.levy_bps),
+        sell_tax_bps: bps("COST_SELL_TAX_BPS", defaults.sell_tax_bps),
+        min_spread_bps: bps("COST_MIN_SPREAD_BPS", defaults.min_spread_bps),
+        max_spread_bps: bps("COST_MAX_SPREAD_BPS", defaults.max_spread_bps),
+        impact_bps: bps("COST_IMPACT_BPS", defaults.impact_bps),
+        default_slippage_bps: bps("COST_DEFAULT_SLIPPAGE_BPS", defaults.default_slippage_bps),
+        ..defaults
+    }
 }
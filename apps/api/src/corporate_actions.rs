@@ -0,0 +1,265 @@
+//! Corporate action ingestion and adjusted price history
+//!
+//! Stores dividends, splits, bonus issues and rights issues from IDX and
+//! KSEI, and turns a stock's stored actions into the adjustments that make
+//! its price history continuous across ex-dates.
+
+use crate::routes::jobs::Job;
+use crate::AppState;
+use chrono::{Duration, NaiveDate, Utc};
+use jejakcuan_data_sources::{CorporateAction, CorporateActionClient, CorporateActionKind};
+use jejakcuan_db::repositories::{self, corporate_actions::InsertCorporateAction};
+use jejakcuan_db::{CorporateActionRow, StockPriceRow};
+use jejakcuan_technical::{
+    adjust_bars, session_date, AdjustmentKind, DatedBar, OhlcvBar, PriceAdjustment,
+};
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Job source id, shared with the data source registry entry
+pub const CORPORATE_ACTIONS_JOB: &str = "idx_corporate_actions";
+
+/// Announcements re-read on each run, so late ratio or price updates land
+const INGEST_LOOKBACK_DAYS: i64 = 400;
+
+/// Start a background job ingesting corporate actions
+pub async fn spawn_ingest(state: &Arc<AppState>) -> Job {
+    let pool = state.db.clone();
+    state
+        .job_manager
+        .spawn_task(
+            CORPORATE_ACTIONS_JOB.to_string(),
+            "Corporate Actions".to_string(),
+            "fetch IDX and KSEI corporate actions".to_string(),
+            async move {
+                let from = Utc::now().date_naive() - Duration::days(INGEST_LOOKBACK_DAYS);
+                ingest_corporate_actions(&pool, &CorporateActionClient::new(), from).await
+            },
+        )
+        .await
+}
+
+async fn ingest_corporate_actions(
+    pool: &PgPool,
+    client: &CorporateActionClient,
+    from: NaiveDate,
+) -> Result<String, String> {
+    let known: HashSet<String> = repositories::stocks::get_all_stocks(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|s| s.symbol)
+        .collect();
+
+    let actions = client
+        .get_corporate_actions(from)
+        .await
+        .map_err(|e| e.to_string())?;
+    let rows = storable_actions(&actions, &known);
+    let written = repositories::corporate_actions::upsert_corporate_actions(pool, &rows)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(format!(
+        "{} actions, {} of listed stocks with an ex-date, {} stored",
+        actions.len(),
+        rows.len(),
+        written
+    ))
+}
+
+/// Actions of known stocks that have an ex-date
+///
+/// Rows are keyed by ex-date, so actions without one (meetings, most
+/// "other" notices) are left out.
+fn storable_actions<'a>(
+    actions: &'a [CorporateAction],
+    known: &HashSet<String>,
+) -> Vec<InsertCorporateAction<'a>> {
+    actions
+        .iter()
+        .filter(|a| known.contains(&a.symbol))
+        .filter_map(|a| {
+            let ex_date = a.ex_date?;
+            Some(InsertCorporateAction {
+                symbol: &a.symbol,
+                action_type: a.kind.as_str(),
+                announced_date: a.announced_date.unwrap_or(ex_date),
+                effective_date: a.effective_date,
+                ex_date,
+                description: &a.description,
+                value: a.amount,
+                ratio: a.ratio,
+                price: a.price,
+                source: &a.source,
+            })
+        })
+        .collect()
+}
+
+/// Price adjustment of a stored action, `None` when its terms are unknown
+fn price_adjustment(row: &CorporateActionRow) -> Option<PriceAdjustment> {
+    let ex_date = row.ex_date?;
+    let kind = match row.action_type.as_str() {
+        t if t == CorporateActionKind::StockSplit.as_str()
+            || t == CorporateActionKind::ReverseSplit.as_str() =>
+        {
+            AdjustmentKind::Split { ratio: row.ratio? }
+        }
+        t if t == CorporateActionKind::BonusShares.as_str()
+            || t == CorporateActionKind::StockDividend.as_str() =>
+        {
+            AdjustmentKind::Split {
+                ratio: Decimal::ONE + row.ratio?,
+            }
+        }
+        t if t == CorporateActionKind::CashDividend.as_str() => {
+            AdjustmentKind::CashDividend { amount: row.value? }
+        }
+        t if t == CorporateActionKind::RightsIssue.as_str() => AdjustmentKind::RightsIssue {
+            ratio: row.ratio?,
+            price: row.price?,
+        },
+        _ => return None,
+    };
+    Some(PriceAdjustment { ex_date, kind })
+}
+
+/// Price rows (oldest first) adjusted for `adjustments`
+fn adjusted_rows(
+    prices: Vec<StockPriceRow>,
+    adjustments: &[PriceAdjustment],
+) -> Vec<StockPriceRow> {
+    if adjustments.is_empty() {
+        return prices;
+    }
+    let daily: Vec<DatedBar> = prices
+        .iter()
+        .map(|p| DatedBar {
+            date: session_date(p.time),
+            bar: OhlcvBar {
+                open: p.open,
+                high: p.high,
+                low: p.low,
+                close: p.close,
+                volume: p.volume,
+            },
+        })
+        .collect();
+
+    prices
+        .into_iter()
+        .zip(adjust_bars(&daily, adjustments))
+        .map(|(row, adjusted)| StockPriceRow {
+            open: adjusted.bar.open,
+            high: adjusted.bar.high,
+            low: adjusted.bar.low,
+            close: adjusted.bar.close,
+            volume: adjusted.bar.volume,
+            ..row
+        })
+        .collect()
+}
+
+/// Daily price rows of `symbol` (oldest first) adjusted for its stored
+/// splits, dividends and rights issues
+pub async fn adjust_price_rows(
+    pool: &PgPool,
+    symbol: &str,
+    prices: Vec<StockPriceRow>,
+) -> Result<Vec<StockPriceRow>, sqlx::Error> {
+    let adjustments: Vec<PriceAdjustment> =
+        repositories::corporate_actions::get_actions_with_ex_date(pool, symbol)
+            .await?
+            .iter()
+            .filter_map(price_adjustment)
+            .collect();
+    Ok(adjusted_rows(prices, &adjustments))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+
+    fn action_row(
+        action_type: &str,
+        value: Option<Decimal>,
+        ratio: Option<Decimal>,
+    ) -> CorporateActionRow {
+        let ex_date = NaiveDate::from_ymd_opt(2025, 3, 5).unwrap();
+        CorporateActionRow {
+            id: 1,
+            symbol: "BBCA".to_string(),
+            action_type: action_type.to_string(),
+            announced_date: ex_date,
+            effective_date: None,
+            ex_date: Some(ex_date),
+            description: String::new(),
+            value,
+            status: None,
+            source_url: None,
+            created_at: None,
+            ratio,
+            price: None,
+            source: None,
+        }
+    }
+
+    fn price(day: u32, close: Decimal) -> StockPriceRow {
+        StockPriceRow {
+            time: Utc.with_ymd_and_hms(2025, 3, day, 9, 0, 0).unwrap(),
+            symbol: "BBCA".to_string(),
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume: 1000,
+            value: None,
+            frequency: None,
+        }
+    }
+
+    #[test]
+    fn test_price_adjustment_from_rows() {
+        let split = price_adjustment(&action_row("stock_split", None, Some(dec!(5)))).unwrap();
+        assert_eq!(split.kind, AdjustmentKind::Split { ratio: dec!(5) });
+
+        // One bonus share per ten held is a 1.1 split
+        let bonus = price_adjustment(&action_row("bonus_shares", None, Some(dec!(0.1)))).unwrap();
+        assert_eq!(bonus.kind, AdjustmentKind::Split { ratio: dec!(1.1) });
+
+        let dividend = price_adjustment(&action_row("dividend", Some(dec!(200)), None)).unwrap();
+        assert_eq!(
+            dividend.kind,
+            AdjustmentKind::CashDividend { amount: dec!(200) }
+        );
+
+        // Unknown terms and actions that do not move prices are skipped
+        assert!(price_adjustment(&action_row("stock_split", None, None)).is_none());
+        assert!(price_adjustment(&action_row("rights_issue", None, Some(dec!(0.25)))).is_none());
+        assert!(price_adjustment(&action_row("other", None, None)).is_none());
+    }
+
+    #[test]
+    fn test_adjusted_rows() {
+        let prices = vec![
+            price(3, dec!(9000)),
+            price(4, dec!(9200)),
+            price(5, dec!(1850)),
+        ];
+        let split = price_adjustment(&action_row("stock_split", None, Some(dec!(5)))).unwrap();
+        let adjusted = adjusted_rows(prices, &[split]);
+
+        assert_eq!(adjusted[0].close, dec!(1800));
+        assert_eq!(adjusted[0].volume, 5000);
+        assert_eq!(adjusted[2].close, dec!(1850));
+        assert_eq!(
+            adjusted[1].time,
+            Utc.with_ymd_and_hms(2025, 3, 4, 9, 0, 0).unwrap()
+        );
+    }
+}
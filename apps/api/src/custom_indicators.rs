@@ -0,0 +1,201 @@
+//! Custom indicators in screens and alert rules
+//!
+//! Users store named expressions over the built-in indicators, such as
+//! `ema_spread = (ema20 - ema50) / atr14`. Screens read them with the
+//! `custom_indicator_above` / `custom_indicator_below` filters and alert
+//! rules as `custom_<name>` metrics; names resolve against the indicators of
+//! the screen's or rule's owner. A deleted indicator has no value, so the
+//! conditions reading it stop matching.
+
+use crate::AppState;
+use chrono::{Duration, Utc};
+use jejakcuan_core::{custom_indicator_name, AlertRule, RuleContext, CUSTOM_METRIC_PREFIX};
+use jejakcuan_db::{repositories, CustomIndicatorRow, StockPriceRow};
+use jejakcuan_technical::{IndicatorExpr, OhlcvBar};
+use sqlx::PgPool;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Calendar days of bars behind a custom indicator value, about 270 sessions
+pub const CUSTOM_INDICATOR_DAYS: i64 = 400;
+
+/// Parse stored indicators by name, skipping any that no longer parse
+pub fn parse_custom_indicators(rows: Vec<CustomIndicatorRow>) -> BTreeMap<String, IndicatorExpr> {
+    rows.into_iter()
+        .filter_map(|row| match IndicatorExpr::parse(&row.expression) {
+            Ok(expr) => Some((row.name, expr)),
+            Err(e) => {
+                tracing::warn!("Skipping invalid custom indicator {}: {}", row.id, e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Indicators of `owner`, by name
+pub async fn load_custom_indicators(
+    pool: &PgPool,
+    owner: &str,
+) -> Result<BTreeMap<String, IndicatorExpr>, sqlx::Error> {
+    Ok(parse_custom_indicators(
+        repositories::custom_indicators::get_custom_indicators(pool, owner).await?,
+    ))
+}
+
+/// Indicators of each of `owners`, by owner and name
+pub async fn load_custom_indicators_of(
+    pool: &PgPool,
+    owners: &[String],
+) -> Result<HashMap<String, BTreeMap<String, IndicatorExpr>>, sqlx::Error> {
+    let mut by_owner: HashMap<String, Vec<CustomIndicatorRow>> = HashMap::new();
+    for row in repositories::custom_indicators::get_custom_indicators_of(pool, owners).await? {
+        by_owner.entry(row.owner.clone()).or_default().push(row);
+    }
+    Ok(by_owner
+        .into_iter()
+        .map(|(owner, rows)| (owner, parse_custom_indicators(rows)))
+        .collect())
+}
+
+/// Names in `names` without an indicator in `indicators`
+pub fn unknown_custom_indicators<'a>(
+    indicators: &BTreeMap<String, IndicatorExpr>,
+    names: impl IntoIterator<Item = &'a str>,
+) -> Vec<&'a str> {
+    names
+        .into_iter()
+        .filter(|name| !indicators.contains_key(*name))
+        .collect()
+}
+
+/// Custom indicator names read by `rule`
+pub fn rule_custom_indicators(rule: &AlertRule) -> BTreeSet<&str> {
+    rule.condition
+        .metrics()
+        .into_iter()
+        .filter_map(custom_indicator_name)
+        .collect()
+}
+
+pub fn ohlcv_bars(prices: &[StockPriceRow]) -> Vec<OhlcvBar> {
+    prices
+        .iter()
+        .map(|p| OhlcvBar {
+            open: p.open,
+            high: p.high,
+            low: p.low,
+            close: p.close,
+            volume: p.volume,
+        })
+        .collect()
+}
+
+/// Set the `custom_<name>` metrics of `names` as of the last of `bars`
+pub fn set_custom_metrics<'a>(
+    context: &mut RuleContext,
+    indicators: &BTreeMap<String, IndicatorExpr>,
+    names: impl IntoIterator<Item = &'a str>,
+    bars: &[OhlcvBar],
+) {
+    for name in names {
+        if let Some(expr) = indicators.get(name) {
+            let metric = format!("{}{}", CUSTOM_METRIC_PREFIX, name);
+            context.set_opt(&metric, expr.latest(bars));
+        }
+    }
+}
+
+/// Bars custom indicators of `symbol` are evaluated on
+pub async fn custom_indicator_bars(
+    pool: &PgPool,
+    symbol: &str,
+) -> Result<Vec<OhlcvBar>, sqlx::Error> {
+    let now = Utc::now();
+    let prices = repositories::prices::get_price_history(
+        pool,
+        symbol,
+        now - Duration::days(CUSTOM_INDICATOR_DAYS),
+        now,
+    )
+    .await?;
+    Ok(ohlcv_bars(&prices))
+}
+
+/// Add the custom indicators `rule` reads to `context` for `symbol`
+///
+/// Nothing is loaded when the rule reads no custom indicator.
+pub async fn attach_custom_metrics(
+    state: &AppState,
+    owner: &str,
+    symbol: &str,
+    rule: &AlertRule,
+    context: &mut RuleContext,
+) -> Result<(), sqlx::Error> {
+    let names = rule_custom_indicators(rule);
+    if names.is_empty() {
+        return Ok(());
+    }
+    let (indicators, bars) = tokio::try_join!(
+        load_custom_indicators(&state.db, owner),
+        custom_indicator_bars(&state.db, symbol)
+    )?;
+    set_custom_metrics(context, &indicators, names, &bars);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jejakcuan_core::AlertPriority;
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+
+    fn bar(close: Decimal) -> OhlcvBar {
+        OhlcvBar {
+            open: close,
+            high: close + dec!(10),
+            low: close - dec!(10),
+            close,
+            volume: 1_000,
+        }
+    }
+
+    #[test]
+    fn test_custom_metrics_for_rules() {
+        let indicators: BTreeMap<String, IndicatorExpr> =
+            [("range", "high - low"), ("slow_trend", "ema50 - ema200")]
+                .into_iter()
+                .map(|(name, expression)| {
+                    (name.to_string(), IndicatorExpr::parse(expression).unwrap())
+                })
+                .collect();
+        let rule = AlertRule::new(
+            "Wide range",
+            "custom_range > 15 AND custom_slow_trend > 0 OR custom_gone > 1",
+            vec![],
+            AlertPriority::Low,
+        )
+        .unwrap();
+        let names = rule_custom_indicators(&rule);
+        assert_eq!(
+            names.iter().copied().collect::<Vec<_>>(),
+            vec!["gone", "range", "slow_trend"]
+        );
+        assert_eq!(
+            unknown_custom_indicators(&indicators, names.clone()),
+            vec!["gone"]
+        );
+
+        let bars: Vec<OhlcvBar> = (0..30).map(|i| bar(Decimal::from(1000 + i))).collect();
+        let mut context = RuleContext::new();
+        set_custom_metrics(&mut context, &indicators, names, &bars);
+        assert_eq!(context.get("custom_range"), Some(dec!(20)));
+        // Too few bars for EMA200
+        assert_eq!(context.get("custom_slow_trend"), None);
+        let evaluation = rule.evaluate(&context);
+        assert!(!evaluation.matched);
+        assert_eq!(
+            evaluation.missing_metrics,
+            vec!["custom_gone".to_string(), "custom_slow_trend".to_string()]
+        );
+    }
+}
@@ -0,0 +1,189 @@
+//! Public read-only demo mode
+//!
+//! With `DEMO_MODE` set, requests without credentials may read the
+//! endpoints in [`DEMO_ENDPOINTS`] as the `demo` viewer. Handlers serving
+//! them cut price data off at [`data_cutoff`], so guests see the market with
+//! a delay (`DEMO_DELAY_MINUTES`, default 15), and every demo response is
+//! watermarked: `X-Demo-Mode` and `X-Data-Delay-Minutes` headers, plus a
+//! `demo` field when the body is a JSON object. Signed-in users are never
+//! affected.
+
+use crate::auth::{AuthUser, Role};
+use crate::authz::pattern_matches;
+use crate::AppState;
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::{header, HeaderMap, HeaderValue, Method},
+    middleware::Next,
+    response::Response,
+};
+use axum_extra::extract::CookieJar;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Username demo requests are attributed to
+pub const DEMO_USERNAME: &str = "demo";
+
+/// Endpoints guests may read, in the pattern syntax of the authorization policy
+pub const DEMO_ENDPOINTS: &[&str] = &[
+    "/api/stocks",
+    "/api/stocks/:symbol",
+    "/api/stocks/:symbol/prices",
+    "/api/analysis/:symbol/volume-profile",
+    "/api/glossary/*",
+];
+
+/// Largest demo body that is buffered to add the watermark
+const MAX_WATERMARK_BODY_BYTES: usize = 8 * 1024 * 1024;
+
+/// Marks a request as served in demo mode
+#[derive(Debug, Clone, Copy)]
+pub struct DemoAccess {
+    pub delay: Duration,
+}
+
+/// Latest data time a request may see: now, or now less the demo delay
+pub fn data_cutoff(demo: Option<DemoAccess>) -> DateTime<Utc> {
+    let now = Utc::now();
+    match demo {
+        Some(demo) => now - chrono::Duration::from_std(demo.delay).unwrap_or_default(),
+        None => now,
+    }
+}
+
+/// Whether guests may make this request
+pub fn is_demo_endpoint(method: &Method, path: &str) -> bool {
+    *method == Method::GET
+        && DEMO_ENDPOINTS
+            .iter()
+            .any(|pattern| pattern_matches(pattern, path))
+}
+
+/// Whether the request carries a token, valid or not
+fn has_credentials(headers: &HeaderMap) -> bool {
+    headers.contains_key(header::AUTHORIZATION)
+        || CookieJar::from_headers(headers).get("token").is_some()
+}
+
+/// Admit credential-less reads of demo endpoints when demo mode is on
+///
+/// Runs outside the authorization layer, which then sees the demo viewer.
+pub async fn demo_access(
+    State(state): State<Arc<AppState>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let Some(delay) = state.config.demo_data_delay else {
+        return next.run(request).await;
+    };
+    if has_credentials(request.headers())
+        || !is_demo_endpoint(request.method(), request.uri().path())
+    {
+        return next.run(request).await;
+    }
+
+    request.extensions_mut().insert(AuthUser {
+        username: DEMO_USERNAME.to_string(),
+        role: Role::Viewer,
+    });
+    request.extensions_mut().insert(DemoAccess { delay });
+    watermark(next.run(request).await, delay).await
+}
+
+/// Label a demo response and, for JSON objects, add a `demo` field
+pub async fn watermark(response: Response, delay: Duration) -> Response {
+    let minutes = delay.as_secs() / 60;
+    let (mut parts, body) = response.into_parts();
+    parts
+        .headers
+        .insert("x-demo-mode", HeaderValue::from_static("delayed"));
+    parts
+        .headers
+        .insert("x-data-delay-minutes", HeaderValue::from(minutes));
+
+    let is_json = parts
+        .headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"));
+    if !is_json {
+        return Response::from_parts(parts, body);
+    }
+
+    let Ok(bytes) = axum::body::to_bytes(body, MAX_WATERMARK_BODY_BYTES).await else {
+        parts.headers.remove(header::CONTENT_LENGTH);
+        return Response::from_parts(parts, Body::empty());
+    };
+    let body = match serde_json::from_slice::<serde_json::Value>(&bytes) {
+        Ok(serde_json::Value::Object(mut object)) => {
+            object.insert(
+                "demo".to_string(),
+                serde_json::json!({
+                    "notice": format!(
+                        "Demo data, delayed {} minutes. Sign in for live data.",
+                        minutes
+                    ),
+                    "delay_minutes": minutes,
+                }),
+            );
+            parts.headers.remove(header::CONTENT_LENGTH);
+            Body::from(serde_json::Value::Object(object).to_string())
+        }
+        _ => Body::from(bytes),
+    };
+    Response::from_parts(parts, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{response::IntoResponse, Json};
+
+    #[test]
+    fn test_demo_endpoints_are_reads_of_public_data() {
+        assert!(is_demo_endpoint(&Method::GET, "/api/stocks"));
+        assert!(is_demo_endpoint(&Method::GET, "/api/stocks/BBCA/prices"));
+        assert!(is_demo_endpoint(
+            &Method::GET,
+            "/api/analysis/BBCA/volume-profile"
+        ));
+        assert!(!is_demo_endpoint(&Method::POST, "/api/stocks/screen"));
+        assert!(!is_demo_endpoint(&Method::GET, "/api/stocks/BBCA/score"));
+        assert!(!is_demo_endpoint(&Method::GET, "/api/watchlist"));
+        assert!(!is_demo_endpoint(&Method::GET, "/api/admin/secrets"));
+
+        let delay = Duration::from_secs(15 * 60);
+        let cutoff = data_cutoff(Some(DemoAccess { delay }));
+        assert!(Utc::now() - cutoff >= chrono::Duration::minutes(15));
+        assert!(Utc::now() - data_cutoff(None) < chrono::Duration::minutes(1));
+    }
+
+    #[tokio::test]
+    async fn test_watermark_labels_every_response_and_tags_objects() {
+        let delay = Duration::from_secs(15 * 60);
+        let body = |response: Response| async {
+            let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            serde_json::from_slice::<serde_json::Value>(&bytes).unwrap()
+        };
+
+        let object = watermark(
+            Json(serde_json::json!({ "symbol": "BBCA" })).into_response(),
+            delay,
+        )
+        .await;
+        assert_eq!(object.headers()["x-demo-mode"], "delayed");
+        assert_eq!(object.headers()["x-data-delay-minutes"], "15");
+        let object = body(object).await;
+        assert_eq!(object["symbol"], "BBCA");
+        assert_eq!(object["demo"]["delay_minutes"], 15);
+
+        // Arrays keep their shape; the headers carry the watermark
+        let array = watermark(Json(serde_json::json!([1, 2])).into_response(), delay).await;
+        assert_eq!(array.headers()["x-data-delay-minutes"], "15");
+        assert_eq!(body(array).await, serde_json::json!([1, 2]));
+    }
+}
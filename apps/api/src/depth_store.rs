@@ -0,0 +1,115 @@
+//! Live order book depth per symbol
+//!
+//! Posted depth snapshots feed a per-symbol tracker of OBI and cumulative
+//! OFI. The tracker restarts with each session, so the cumulative OFI is the
+//! session's order flow since the process started; the session total is
+//! kept in `order_flow_daily`, which each snapshot's OFI is added to.
+
+use chrono::NaiveDate;
+use jejakcuan_technical::{session_date, DepthFlowTracker, DepthSnapshot, LiveOrderFlow};
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+#[derive(Debug)]
+struct SymbolDepth {
+    session: NaiveDate,
+    tracker: DepthFlowTracker,
+    latest: DepthSnapshot,
+    flow: LiveOrderFlow,
+}
+
+/// Latest depth and live order flow of each symbol
+#[derive(Debug, Default)]
+pub struct DepthStore {
+    symbols: RwLock<HashMap<String, SymbolDepth>>,
+}
+
+impl DepthStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a snapshot of `symbol`
+    ///
+    /// Returns the updated order flow, or `None` for snapshots older than the
+    /// latest and crossed books.
+    pub fn record(&self, symbol: &str, snapshot: DepthSnapshot) -> Option<LiveOrderFlow> {
+        let session = session_date(snapshot.time);
+        let mut symbols = self.symbols.write().expect("depth store lock poisoned");
+        let symbol = symbol.to_uppercase();
+
+        match symbols.get_mut(&symbol) {
+            Some(entry) if entry.session >= session => {
+                let flow = entry.tracker.update(snapshot.clone())?;
+                entry.latest = snapshot;
+                entry.flow = flow.clone();
+                Some(flow)
+            }
+            _ => {
+                let mut tracker = DepthFlowTracker::default();
+                let flow = tracker.update(snapshot.clone())?;
+                symbols.insert(
+                    symbol,
+                    SymbolDepth {
+                        session,
+                        tracker,
+                        latest: snapshot,
+                        flow: flow.clone(),
+                    },
+                );
+                Some(flow)
+            }
+        }
+    }
+
+    /// Latest snapshot and order flow of `symbol`
+    pub fn latest(&self, symbol: &str) -> Option<(DepthSnapshot, LiveOrderFlow)> {
+        let symbols = self.symbols.read().expect("depth store lock poisoned");
+        symbols
+            .get(&symbol.to_uppercase())
+            .map(|entry| (entry.latest.clone(), entry.flow.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, Duration, Utc};
+    use jejakcuan_technical::BookLevel;
+    use rust_decimal_macros::dec;
+
+    fn book(time: DateTime<Utc>, bid_volume: i64) -> DepthSnapshot {
+        DepthSnapshot::new(
+            time,
+            vec![BookLevel {
+                price: dec!(9250),
+                volume: bid_volume,
+            }],
+            vec![BookLevel {
+                price: dec!(9275),
+                volume: 1_000,
+            }],
+        )
+    }
+
+    #[test]
+    fn test_cumulative_ofi_restarts_each_session() {
+        // 2024-03-04 09:00 WIB
+        let open = DateTime::from_timestamp(1_709_517_600, 0).unwrap();
+        let store = DepthStore::new();
+        store.record("bbca", book(open, 1_000)).unwrap();
+        let flow = store
+            .record("BBCA", book(open + Duration::seconds(5), 1_400))
+            .unwrap();
+        assert_eq!((flow.ofi, flow.cumulative_ofi), (dec!(400), dec!(400)));
+
+        // An older snapshot does not move the tracker
+        assert!(store.record("BBCA", book(open, 200)).is_none());
+        assert_eq!(store.latest("bbca").unwrap().1.cumulative_ofi, dec!(400));
+
+        let next_day = store
+            .record("BBCA", book(open + Duration::days(1), 1_400))
+            .unwrap();
+        assert_eq!((next_day.cumulative_ofi, next_day.snapshots), (dec!(0), 1));
+    }
+}
@@ -0,0 +1,205 @@
+//! Financial report calendar sync
+//!
+//! Reads the statements each stock published on IDX over the last three
+//! fiscal years, stores them as published reports, and stores the reports
+//! still to come within the horizon with their estimated dates.
+
+use crate::routes::jobs::Job;
+use crate::AppState;
+use chrono::{Datelike, NaiveDate, Utc};
+use jejakcuan_core::{expected_reports, FiledReport, ReportPeriod};
+use jejakcuan_data_sources::IdxClient;
+use jejakcuan_db::repositories::{self, earnings::InsertEarnings};
+use jejakcuan_technical::session_date;
+use sqlx::PgPool;
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+
+/// Job source id, shared with the data source registry entry
+pub const EARNINGS_CALENDAR_JOB: &str = "idx_financial_reports";
+
+/// Days ahead expected reports are stored
+const CALENDAR_HORIZON_DAYS: i64 = 120;
+
+/// Fiscal years of filings read: two for quarterly history, a third for the
+/// annual report published early in the current year
+const HISTORY_YEARS: i32 = 3;
+
+/// Basis stored for published reports
+const PUBLISHED_BASIS: &str = "published";
+
+/// Start a background job syncing the report calendar
+pub async fn spawn_sync(state: &Arc<AppState>) -> Job {
+    let pool = state.db.clone();
+    state
+        .job_manager
+        .spawn_task(
+            EARNINGS_CALENDAR_JOB.to_string(),
+            "IDX Financial Reports".to_string(),
+            "fetch published financial reports and estimate upcoming ones".to_string(),
+            async move {
+                let today = session_date(Utc::now());
+                sync_earnings(&pool, &IdxClient::new(), today).await
+            },
+        )
+        .await
+}
+
+async fn sync_earnings(
+    pool: &PgPool,
+    client: &IdxClient,
+    today: NaiveDate,
+) -> Result<String, String> {
+    let known: HashSet<String> = repositories::stocks::get_all_stocks(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|s| s.symbol)
+        .collect();
+
+    let mut filed: BTreeMap<String, Vec<FiledReport>> = BTreeMap::new();
+    let mut errors = Vec::new();
+    let mut requests = 0;
+    for year in today.year() + 1 - HISTORY_YEARS..=today.year() {
+        for period in ReportPeriod::ALL {
+            requests += 1;
+            match client.get_financial_reports(year, period).await {
+                Ok(reports) => {
+                    for (symbol, report) in reports.iter().filter_map(|r| r.to_filed_report()) {
+                        filed.entry(symbol).or_default().push(report);
+                    }
+                }
+                Err(e) => errors.push(format!("{} {}: {}", period.as_str(), year, e)),
+            }
+        }
+    }
+    if errors.len() == requests {
+        return Err(format!(
+            "No financial reports fetched: {}",
+            errors.join("; ")
+        ));
+    }
+
+    let rows = calendar_rows(&filed, &known, today);
+    let inserts: Vec<InsertEarnings> = rows
+        .iter()
+        .map(|row| InsertEarnings {
+            symbol: &row.symbol,
+            fiscal_year: row.fiscal_year,
+            period: row.period.as_str(),
+            expected_date: row.expected_date,
+            deadline: row.deadline,
+            basis: row.basis,
+            published_on: row.published_on,
+        })
+        .collect();
+    let written = repositories::earnings::upsert_earnings(pool, &inserts)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let published = rows.iter().filter(|r| r.published_on.is_some()).count();
+    let mut summary = format!(
+        "{} published and {} expected reports, {} stored",
+        published,
+        rows.len() - published,
+        written
+    );
+    if !errors.is_empty() {
+        summary.push_str(&format!(
+            " ({} requests failed: {})",
+            errors.len(),
+            errors.join("; ")
+        ));
+    }
+    Ok(summary)
+}
+
+/// One calendar entry to store
+#[derive(Debug, Clone, PartialEq)]
+struct CalendarRow {
+    symbol: String,
+    fiscal_year: i32,
+    period: ReportPeriod,
+    expected_date: NaiveDate,
+    deadline: NaiveDate,
+    basis: &'static str,
+    published_on: Option<NaiveDate>,
+}
+
+/// Published and expected reports of every known stock
+///
+/// Stocks without any filing still get deadline-based expectations.
+fn calendar_rows(
+    filed: &BTreeMap<String, Vec<FiledReport>>,
+    known: &HashSet<String>,
+    today: NaiveDate,
+) -> Vec<CalendarRow> {
+    let mut symbols: Vec<&String> = known.iter().collect();
+    symbols.sort();
+
+    let mut rows = Vec::new();
+    for symbol in symbols {
+        let reports = filed.get(symbol).map(Vec::as_slice).unwrap_or_default();
+        rows.extend(reports.iter().map(|r| CalendarRow {
+            symbol: symbol.clone(),
+            fiscal_year: r.fiscal_year,
+            period: r.period,
+            expected_date: r.filed_on,
+            deadline: r.period.filing_deadline(r.fiscal_year),
+            basis: PUBLISHED_BASIS,
+            published_on: Some(r.filed_on),
+        }));
+        rows.extend(
+            expected_reports(reports, today, CALENDAR_HORIZON_DAYS)
+                .into_iter()
+                .map(|e| CalendarRow {
+                    symbol: symbol.clone(),
+                    fiscal_year: e.fiscal_year,
+                    period: e.period,
+                    expected_date: e.expected_date,
+                    deadline: e.deadline,
+                    basis: e.basis.as_str(),
+                    published_on: None,
+                }),
+        );
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_calendar_rows() {
+        let mut filed = BTreeMap::new();
+        filed.insert(
+            "BBCA".to_string(),
+            vec![FiledReport {
+                period: ReportPeriod::Q3,
+                fiscal_year: 2024,
+                filed_on: date("2024-10-22"),
+            }],
+        );
+        // Filed by a stock not in the universe: ignored
+        filed.insert("XXXX".to_string(), filed["BBCA"].clone());
+        let known: HashSet<String> = ["BBCA", "TLKM"].into_iter().map(String::from).collect();
+
+        let rows = calendar_rows(&filed, &known, date("2025-10-01"));
+        let bbca: Vec<_> = rows.iter().filter(|r| r.symbol == "BBCA").collect();
+        assert_eq!(bbca.len(), 2);
+        assert_eq!(bbca[0].basis, "published");
+        assert_eq!(bbca[1].period, ReportPeriod::Q3);
+        assert_eq!(bbca[1].expected_date, date("2025-10-22"));
+        assert_eq!(bbca[1].basis, "last_year");
+
+        let tlkm: Vec<_> = rows.iter().filter(|r| r.symbol == "TLKM").collect();
+        assert_eq!(tlkm.len(), 1);
+        assert_eq!(tlkm[0].expected_date, date("2025-10-31"));
+        assert!(rows.iter().all(|r| r.symbol != "XXXX"));
+    }
+}
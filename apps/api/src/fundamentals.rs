@@ -0,0 +1,389 @@
+//! Point-in-time fundamental inputs
+//!
+//! Assembles scoring inputs from the financials published by a given date and
+//! derives ratios the data source leaves empty from the raw statements.
+
+use chrono::{NaiveDate, NaiveTime, Utc};
+use jejakcuan_core::{FundamentalInput, FundamentalScoreEngine, SectorProfileRegistry};
+use jejakcuan_db::{
+    repositories, BalanceSheetRow, CashFlowRow, FinancialsRow, FinancialsVersionRow,
+    IncomeStatementRow, StockRow,
+};
+use jejakcuan_fundamental::{
+    annual_revenue_growth, calculate_fcf_yield, calculate_sector_averages, economic_returns,
+    ev_ebitda_from_statements, ttm_free_cash_flow, CashFlowPeriod, EconomicReturns, IncomePeriod,
+    QuarterlyBasis, SectorAverages, ValuationRatios,
+};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::Serialize;
+use sqlx::PgPool;
+use std::collections::HashMap;
+
+/// Quarterly rows in `income_statements` hold discrete three-month figures
+const STATEMENT_QUARTER_BASIS: QuarterlyBasis = QuarterlyBasis::Discrete;
+
+/// Enough periods for TTM from quarters plus the prior annual report
+const STATEMENT_LOOKBACK: i64 = 12;
+
+/// Fundamental scoring input from a published financials version
+pub fn fundamental_input(f: &FinancialsVersionRow) -> FundamentalInput {
+    FundamentalInput {
+        pe_ratio: f.pe_ratio,
+        sector_pe: None,
+        pb_ratio: f.pb_ratio,
+        sector_pb: None,
+        ev_ebitda: f.ev_ebitda,
+        sector_ev_ebitda: None,
+        fcf_yield: None,
+        dcf_margin: None,
+        roe: f.roe.map(|v| v * dec!(100)),
+        roa: f.roa.map(|v| v * dec!(100)),
+        profit_margin: None,
+        roic: None,
+        wacc: None,
+        revenue_growth: None,
+        debt_to_equity: None,
+        current_ratio: None,
+        financials_age_days: None,
+    }
+}
+
+/// Trailing cash generation relative to market cap
+#[derive(Debug, Clone, Serialize)]
+pub struct CashFlowMetrics {
+    pub market_cap: Decimal,
+    pub ttm_free_cash_flow: Option<Decimal>,
+    /// Free cash flow yield (%)
+    pub fcf_yield: Option<Decimal>,
+}
+
+/// Fundamental scoring input from the financials published by `as_of`
+///
+/// Backtests pass a historical date so scores never see figures that were
+/// reported (or restated) after it. The figures' age is measured from
+/// `as_of` too, so stale financials decay the same way in a backtest.
+pub async fn fundamental_input_as_of(
+    pool: &PgPool,
+    symbol: &str,
+    as_of: NaiveDate,
+) -> Result<FundamentalInput, sqlx::Error> {
+    let financials = repositories::stocks::get_financials_as_of(pool, symbol, as_of).await?;
+    fundamental_input_from(pool, symbol, as_of, financials.as_ref()).await
+}
+
+/// [`fundamental_input_as_of`] with the financials already fetched
+pub async fn fundamental_input_from(
+    pool: &PgPool,
+    symbol: &str,
+    as_of: NaiveDate,
+    financials: Option<&FinancialsVersionRow>,
+) -> Result<FundamentalInput, sqlx::Error> {
+    let mut input = financials.map(fundamental_input).unwrap_or_default();
+    input.financials_age_days = financials.map(|f| (as_of - f.report_date).num_days());
+    fill_derived_ratios(pool, symbol, as_of, &mut input).await?;
+    Ok(input)
+}
+
+/// Fill ratios missing from the data source using statements published by `as_of`
+pub async fn fill_derived_ratios(
+    pool: &PgPool,
+    symbol: &str,
+    as_of: NaiveDate,
+    input: &mut FundamentalInput,
+) -> Result<(), sqlx::Error> {
+    let statements = PublishedStatements::load(pool, symbol, as_of).await?;
+
+    if input.ev_ebitda.is_none() {
+        input.ev_ebitda = statements.ev_ebitda();
+    }
+    if input.fcf_yield.is_none() {
+        input.fcf_yield = statements.cash_flow_metrics().and_then(|m| m.fcf_yield);
+    }
+    if input.roic.is_none() {
+        if let Some(returns) = statements.economic_returns() {
+            input.roic = Some(returns.roic);
+            input.wacc = Some(returns.wacc);
+        }
+    }
+    if input.revenue_growth.is_none() {
+        input.revenue_growth = annual_revenue_growth(&statements.income);
+    }
+    Ok(())
+}
+
+/// Scoring engine for the stock's sector profile
+pub async fn fundamental_engine(
+    pool: &PgPool,
+    symbol: &str,
+) -> Result<FundamentalScoreEngine, sqlx::Error> {
+    let stock = repositories::stocks::get_stock_by_symbol(pool, symbol).await?;
+    Ok(engine_for_stock(stock.as_ref()))
+}
+
+/// Scoring engine for an already-fetched stock; unknown stocks get the default
+pub fn engine_for_stock(stock: Option<&StockRow>) -> FundamentalScoreEngine {
+    let sector = stock.and_then(|s| s.sector.as_deref());
+    let subsector = stock.and_then(|s| s.subsector.as_deref());
+    SectorProfileRegistry::builtin().engine_for(sector, subsector)
+}
+
+/// TTM free cash flow from statements published by `as_of`
+pub async fn cash_flow_metrics(
+    pool: &PgPool,
+    symbol: &str,
+    as_of: NaiveDate,
+) -> Result<Option<CashFlowMetrics>, sqlx::Error> {
+    Ok(PublishedStatements::load(pool, symbol, as_of)
+        .await?
+        .cash_flow_metrics())
+}
+
+/// EV/EBITDA from market cap, the latest balance sheet, and TTM EBITDA
+pub async fn derived_ev_ebitda(
+    pool: &PgPool,
+    symbol: &str,
+    as_of: NaiveDate,
+) -> Result<Option<Decimal>, sqlx::Error> {
+    Ok(PublishedStatements::load(pool, symbol, as_of)
+        .await?
+        .ev_ebitda())
+}
+
+/// Latest reported valuation ratios of each sector's stocks, by sector and
+/// symbol
+///
+/// Only ratios the data source reported are used, so a sector's figures need
+/// no statements of its members.
+pub fn sector_peer_ratios(
+    stocks: &[StockRow],
+    financials: &[FinancialsRow],
+) -> HashMap<String, Vec<(String, ValuationRatios)>> {
+    let sectors: HashMap<&str, &str> = stocks
+        .iter()
+        .filter_map(|s| Some((s.symbol.as_str(), s.sector.as_deref()?)))
+        .collect();
+    let mut peers: HashMap<String, Vec<(String, ValuationRatios)>> = HashMap::new();
+    for f in financials {
+        let Some(sector) = sectors.get(f.symbol.as_str()) else {
+            continue;
+        };
+        peers.entry(sector.to_string()).or_default().push((
+            f.symbol.clone(),
+            ValuationRatios {
+                pe_ratio: f.pe_ratio,
+                pb_ratio: f.pb_ratio,
+                ev_ebitda: f.ev_ebitda,
+                ..Default::default()
+            },
+        ));
+    }
+    peers
+}
+
+/// Averages of the latest reported valuation ratios, by sector
+pub async fn sector_averages(
+    pool: &PgPool,
+) -> Result<HashMap<String, SectorAverages>, sqlx::Error> {
+    let stocks = repositories::stocks::get_all_stocks(pool).await?;
+    let financials = repositories::stocks::get_all_latest_financials(pool).await?;
+    Ok(sector_peer_ratios(&stocks, &financials)
+        .into_iter()
+        .map(|(sector, peers)| {
+            let ratios: Vec<ValuationRatios> = peers.into_iter().map(|(_, r)| r).collect();
+            let averages = calculate_sector_averages(&sector, &ratios);
+            (sector, averages)
+        })
+        .collect())
+}
+
+/// Statements published by a date, with the market cap on that date
+#[derive(Debug, Clone, Default)]
+pub struct PublishedStatements {
+    pub balance_sheet: Option<BalanceSheetRow>,
+    pub income: Vec<IncomePeriod>,
+    pub cash_flows: Vec<CashFlowPeriod>,
+    pub market_cap: Option<Decimal>,
+}
+
+impl PublishedStatements {
+    /// Load the statements that had been published by `as_of`
+    pub async fn load(pool: &PgPool, symbol: &str, as_of: NaiveDate) -> Result<Self, sqlx::Error> {
+        let balance_sheet =
+            repositories::statements::get_balance_sheet_as_of(pool, symbol, as_of).await?;
+        let income = repositories::statements::get_income_statements_as_of(
+            pool,
+            symbol,
+            as_of,
+            STATEMENT_LOOKBACK,
+        )
+        .await?;
+        let cash_flows =
+            repositories::statements::get_cash_flows_as_of(pool, symbol, as_of, STATEMENT_LOOKBACK)
+                .await?;
+        let market_cap = market_cap_as_of(pool, symbol, as_of, &income).await?;
+
+        Ok(Self {
+            balance_sheet,
+            income: income_periods(&income, &cash_flows),
+            cash_flows: cash_flow_periods(&cash_flows),
+            market_cap,
+        })
+    }
+
+    /// EV/EBITDA from market cap, net debt, and TTM EBITDA
+    pub fn ev_ebitda(&self) -> Option<Decimal> {
+        let balance = self.balance_sheet.as_ref()?;
+        ev_ebitda_from_statements(
+            self.market_cap?,
+            Decimal::from(balance.total_debt.unwrap_or(0)),
+            Decimal::from(balance.cash_and_equivalents.unwrap_or(0)),
+            &self.income,
+            STATEMENT_QUARTER_BASIS,
+        )
+    }
+
+    /// TTM free cash flow against market cap
+    pub fn cash_flow_metrics(&self) -> Option<CashFlowMetrics> {
+        if self.cash_flows.is_empty() {
+            return None;
+        }
+        let market_cap = self.market_cap?;
+        let ttm_free_cash_flow = ttm_free_cash_flow(&self.cash_flows, STATEMENT_QUARTER_BASIS);
+
+        Some(CashFlowMetrics {
+            market_cap,
+            ttm_free_cash_flow,
+            fcf_yield: ttm_free_cash_flow.and_then(|fcf| calculate_fcf_yield(fcf, market_cap)),
+        })
+    }
+
+    /// ROIC on equity plus net debt, and its spread over WACC
+    pub fn economic_returns(&self) -> Option<EconomicReturns> {
+        let balance = self.balance_sheet.as_ref()?;
+        economic_returns(
+            &self.income,
+            STATEMENT_QUARTER_BASIS,
+            Decimal::from(balance.total_equity?),
+            Decimal::from(balance.total_debt.unwrap_or(0)),
+            Decimal::from(balance.cash_and_equivalents.unwrap_or(0)),
+            self.market_cap?,
+        )
+    }
+}
+
+/// Market cap from the close on `as_of` and the latest reported share count
+///
+/// Falls back to the stored market cap when valuing today.
+async fn market_cap_as_of(
+    pool: &PgPool,
+    symbol: &str,
+    as_of: NaiveDate,
+    income: &[IncomeStatementRow],
+) -> Result<Option<Decimal>, sqlx::Error> {
+    let shares = income.iter().find_map(|s| s.shares_outstanding);
+    if let Some(shares) = shares {
+        let end_of_day = (as_of + chrono::Duration::days(1))
+            .and_time(NaiveTime::MIN)
+            .and_utc();
+        if let Some(price) = repositories::prices::get_price_as_of(pool, symbol, end_of_day).await?
+        {
+            return Ok(Some(price.close * Decimal::from(shares)));
+        }
+    }
+
+    if as_of < Utc::now().date_naive() {
+        return Ok(None);
+    }
+    Ok(repositories::stocks::get_stock_by_symbol(pool, symbol)
+        .await?
+        .and_then(|s| s.market_cap)
+        .map(Decimal::from))
+}
+
+/// Income periods with depreciation taken from the cash flow statement when
+/// the income statement does not break it out
+fn income_periods(income: &[IncomeStatementRow], cash_flows: &[CashFlowRow]) -> Vec<IncomePeriod> {
+    income
+        .iter()
+        .map(|s| {
+            let depreciation = s.depreciation_amortization.or_else(|| {
+                cash_flows
+                    .iter()
+                    .find(|c| {
+                        c.fiscal_year == s.fiscal_year && c.fiscal_quarter == s.fiscal_quarter
+                    })
+                    .and_then(|c| c.depreciation_amortization)
+            });
+            IncomePeriod {
+                fiscal_year: s.fiscal_year,
+                fiscal_quarter: s.fiscal_quarter.map(|q| q as u32),
+                period_end: s.period_end,
+                revenue: s.revenue.map(Decimal::from),
+                operating_income: s.operating_income.map(Decimal::from),
+                depreciation_amortization: depreciation.map(Decimal::from),
+                net_income: s.net_income.map(Decimal::from),
+                interest_expense: s.interest_expense.map(Decimal::from),
+                earnings_before_tax: s.earnings_before_tax.map(Decimal::from),
+                tax_expense: s.tax_expense.map(Decimal::from),
+                ebitda: None,
+            }
+        })
+        .collect()
+}
+
+fn cash_flow_periods(cash_flows: &[CashFlowRow]) -> Vec<CashFlowPeriod> {
+    cash_flows
+        .iter()
+        .map(|c| CashFlowPeriod {
+            fiscal_year: c.fiscal_year,
+            fiscal_quarter: c.fiscal_quarter.map(|q| q as u32),
+            period_end: c.period_end,
+            net_income: c.net_income.map(Decimal::from),
+            depreciation_amortization: c.depreciation_amortization.map(Decimal::from),
+            change_in_working_capital: c.change_in_working_capital.map(Decimal::from),
+            operating_cash_flow: c.operating_cash_flow.map(Decimal::from),
+            capital_expenditure: c.capital_expenditure.map(Decimal::from),
+            free_cash_flow: c.free_cash_flow.map(Decimal::from),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_income_periods_take_depreciation_from_cash_flows() {
+        let period_end = NaiveDate::from_ymd_opt(2024, 12, 31).unwrap();
+        let income = vec![IncomeStatementRow {
+            symbol: "BBCA".to_string(),
+            fiscal_year: 2024,
+            fiscal_quarter: None,
+            period_end,
+            revenue: Some(1_000),
+            operating_income: Some(300),
+            depreciation_amortization: None,
+            interest_expense: None,
+            earnings_before_tax: None,
+            tax_expense: None,
+            net_income: Some(200),
+            shares_outstanding: Some(10),
+        }];
+        let cash_flows = vec![CashFlowRow {
+            symbol: "BBCA".to_string(),
+            fiscal_year: 2024,
+            fiscal_quarter: None,
+            period_end,
+            net_income: None,
+            depreciation_amortization: Some(50),
+            change_in_working_capital: None,
+            operating_cash_flow: None,
+            capital_expenditure: None,
+            free_cash_flow: None,
+        }];
+
+        let periods = income_periods(&income, &cash_flows);
+        assert_eq!(periods[0].ebitda(), Some(dec!(350)));
+    }
+}
@@ -0,0 +1,88 @@
+//! Writer for the streaming price feed
+//!
+//! Drains the WebSocket tick queue: every update goes to the footprint tick
+//! store and into 1m/5m/15m bars, and completed bars are written to
+//! `intraday_prices`. Bars of quiet symbols are closed on a timer so they do
+//! not wait for the next update. Closed bars can also be handed to another
+//! task, such as real-time scoring.
+//!
+//! The writer runs only while the stream does, i.e. with `REALTIME_SCORING`
+//! or the score ticker enabled (see [`crate::realtime_scoring`]); otherwise
+//! no intraday bars are stored.
+
+use crate::AppState;
+use chrono::Utc;
+use jejakcuan_data_sources::twelvedata::{BarAggregator, IntradayBar, TickQueue};
+use jejakcuan_db::{repositories, InsertIntradayBar};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+/// How often bars past their interval are closed without a new update
+const CLOSE_BARS_EVERY: std::time::Duration = std::time::Duration::from_secs(5);
+
+pub fn to_insert_bar(bar: &IntradayBar) -> InsertIntradayBar {
+    InsertIntradayBar {
+        time: bar.start,
+        symbol: bar.symbol.clone(),
+        interval: bar.interval.as_str().to_string(),
+        open: bar.open,
+        high: bar.high,
+        low: bar.low,
+        close: bar.close,
+        volume: bar.volume,
+        ticks: i32::try_from(bar.ticks).unwrap_or(i32::MAX),
+    }
+}
+
+async fn write_bars(state: &AppState, bars: &[IntradayBar]) {
+    if bars.is_empty() {
+        return;
+    }
+    let rows: Vec<InsertIntradayBar> = bars.iter().map(to_insert_bar).collect();
+    if let Err(e) = repositories::intraday_prices::upsert_intraday_bars(&state.db, &rows).await {
+        tracing::warn!("Failed to write {} intraday bars: {}", rows.len(), e);
+    }
+}
+
+/// Consume `queue` until it is closed, then write the bars still open
+///
+/// Written bars are also sent to `closed` when given.
+pub fn spawn_bar_writer(
+    state: Arc<AppState>,
+    queue: Arc<TickQueue>,
+    mut aggregator: BarAggregator,
+    closed: Option<mpsc::Sender<Vec<IntradayBar>>>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut timer = tokio::time::interval(CLOSE_BARS_EVERY);
+        timer.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            let completed = tokio::select! {
+                batch = queue.next_batch() => {
+                    let Some(batch) = batch else { break };
+                    let mut completed = Vec::new();
+                    for update in &batch {
+                        state.ticks.record_price_update(update);
+                        completed.extend(aggregator.push(update));
+                    }
+                    completed
+                }
+                _ = timer.tick() => aggregator.flush_until(Utc::now()),
+            };
+            write_bars(&state, &completed).await;
+            if let Some(closed) = closed.as_ref().filter(|_| !completed.is_empty()) {
+                // The receiver only goes away on shutdown
+                let _ = closed.send(completed).await;
+            }
+        }
+
+        write_bars(&state, &aggregator.flush_all()).await;
+        if aggregator.late_updates() > 0 {
+            tracing::info!(
+                "Bar writer stopped; {} out-of-order updates were dropped",
+                aggregator.late_updates()
+            );
+        }
+    })
+}
@@ -8,38 +8,189 @@ use axum::{
     routing::get,
     Router,
 };
+use jejakcuan_data_sources::twelvedata::TickQueue;
+use jejakcuan_data_sources::{
+    ApiUsageTracker, FailoverProvider, ParserHealthReport, TwelveDataClient,
+};
 use sqlx::PgPool;
+use std::collections::HashMap;
 use std::sync::Arc;
+use tokio::sync::RwLock;
 use tower_http::cors::{AllowOrigin, CorsLayer};
 use tower_http::trace::TraceLayer;
 
+pub mod alert_scheduler;
+pub mod analysis_cache;
+pub mod analysis_timings;
 pub mod auth;
+pub mod authz;
+pub mod backfill;
+pub mod cache_snapshot;
+pub mod change_history;
 pub mod config;
+pub mod corporate_actions;
+pub mod custom_indicators;
+pub mod demo;
+pub mod depth_store;
+pub mod earnings;
+pub mod fundamentals;
+pub mod intraday_bars;
+pub mod maintenance;
+pub mod news;
+pub mod notification_retry;
 pub mod notifications;
+pub mod price_history;
+pub mod price_store;
+pub mod realtime_scoring;
+pub mod request_metrics;
+pub mod retention;
 pub mod routes;
+pub mod score_ticker;
+pub mod screener;
+pub mod secrets;
+pub mod shareholdings;
+pub mod stock_universe;
+pub mod summary;
+pub mod symbol_locks;
+#[cfg(feature = "synthetic")]
+pub mod synthetic;
+pub mod tick_store;
+pub mod usage;
 
+use alert_scheduler::RecentAlerts;
+use analysis_cache::AnalysisCache;
+use analysis_timings::AnalysisTimings;
 use config::Config;
+use depth_store::DepthStore;
+use jejakcuan_audit::{AuditLogger, AuditLoggerConfig};
+use notifications::{
+    DiscordConfig, DiscordNotifier, InAppHub, NotificationService, SlackConfig, SlackNotifier,
+    TelegramConfig, TelegramNotifier, WebhookConfig, WebhookNotifier, WhatsAppConfig,
+    WhatsAppNotifier,
+};
+use price_store::PriceStore;
+use request_metrics::RequestMetrics;
+use routes::streaming::StreamingState;
 use routes::{
-    admin_routes, analysis_routes, auth_routes, financials_routes, stock_routes, streaming_routes,
-    watchlist_routes, JobManager,
+    admin_routes, alert_routes, analysis_routes, auth_routes, backtest_routes, calendar_routes,
+    custom_indicator_routes, financials_routes, glossary_routes, import_routes, journal_routes,
+    macro_routes, notification_routes, portfolio_routes, screen_routes, shared_backtest_routes,
+    staging_routes, stock_routes, streaming_routes, symbol_routes, watchlist_routes, JobManager,
 };
+use symbol_locks::SymbolLocks;
+use tick_store::TickStore;
+use usage::UsageRecorder;
 
 /// Application state shared across all handlers
 pub struct AppState {
     pub db: PgPool,
     pub config: Config,
     pub job_manager: Arc<JobManager>,
+    pub notifications: Arc<NotificationService>,
+    /// Per-user channels behind the in-app notification WebSocket
+    pub in_app: Arc<InAppHub>,
+    /// Latest scraper self-check results keyed by parser name
+    pub parser_health: RwLock<HashMap<String, ParserHealthReport>>,
+    /// Per-key call counts for metered external APIs
+    pub api_usage: Arc<ApiUsageTracker>,
+    /// TwelveData client over the pooled keys, shared so the admin key
+    /// health covers every caller; `None` without an API key
+    pub twelvedata: Option<TwelveDataClient>,
+    /// Quote and daily history providers, tried in priority order
+    pub price_provider: Arc<FailoverProvider>,
+    /// Recent daily prices of frequently analyzed symbols, as columns
+    pub price_store: PriceStore,
+    /// Serializes full analysis per symbol
+    pub analysis_locks: SymbolLocks,
+    /// Serialized full-analysis responses, warmed after the close
+    pub analysis_cache: AnalysisCache,
+    /// Per-stage latency histograms of full analyses computed on request
+    pub analysis_timings: AnalysisTimings,
+    /// Traffic, error and active-user counters for the admin overview
+    pub request_metrics: RequestMetrics,
+    /// Alert conditions fired recently, so scans do not repeat them
+    pub recent_alerts: RecentAlerts,
+    /// Audit trail, including configuration change history
+    pub audit: AuditLogger,
+    /// Ticks from the price stream waiting for the bar writer
+    pub tick_queue: Arc<TickQueue>,
+    /// Recent trades per symbol from the streaming feed, for footprint charts
+    pub ticks: TickStore,
+    /// Latest order book depth and live OBI/OFI per symbol
+    pub depth: DepthStore,
+    /// Product usage events, for the admin analytics
+    pub usage: UsageRecorder,
+    /// Messages pushed to the server-sent event streams
+    pub streaming: StreamingState,
 }
 
 /// Create the application router with all routes configured
 pub fn create_app(db: PgPool, config: Config) -> Router {
-    let job_manager = Arc::new(JobManager::new());
+    let job_manager = Arc::new(JobManager::with_db(db.clone()));
+    let in_app = Arc::new(InAppHub::new());
+    let notifications = Arc::new(build_notification_service(&config, in_app.clone()));
+    let analysis_cache = AnalysisCache::new(&config.redis_url);
+    let audit = AuditLogger::new(AuditLoggerConfig::default(), db.clone());
+    let usage = UsageRecorder::new(config.usage_analytics.clone(), db.clone());
+    let api_usage = Arc::new(ApiUsageTracker::from_env());
+    let twelvedata = TwelveDataClient::with_keys(config.twelvedata_keys())
+        .map(|client| client.with_usage_tracker(api_usage.clone()))
+        .map_err(|e| tracing::info!("TwelveData client disabled: {}", e))
+        .ok();
+    let price_provider = Arc::new(price_history::build_price_provider(
+        &config,
+        api_usage.clone(),
+        twelvedata.as_ref(),
+    ));
+    let warm_time = config.analysis_warm_time_utc;
+    let alert_scan_interval = config.alert_scan_interval;
+    let score_ticker_interval = config.score_ticker_interval;
+    let realtime_scoring = config.realtime_scoring;
+    let maintenance_time = config.maintenance_time_utc;
     let state = Arc::new(AppState {
         db,
         config,
         job_manager,
+        notifications,
+        in_app,
+        parser_health: RwLock::new(HashMap::new()),
+        api_usage,
+        twelvedata,
+        price_provider,
+        price_store: PriceStore::new(),
+        analysis_locks: SymbolLocks::new(),
+        analysis_cache,
+        analysis_timings: AnalysisTimings::new(),
+        request_metrics: RequestMetrics::new(),
+        recent_alerts: RecentAlerts::new(),
+        audit,
+        tick_queue: Arc::new(TickQueue::default()),
+        ticks: TickStore::new(),
+        depth: DepthStore::new(),
+        usage,
+        streaming: StreamingState::new(),
     });
 
+    if let Some(at) = warm_time {
+        analysis_cache::spawn_warmer(state.clone(), at);
+    }
+    if let Some(every) = alert_scan_interval {
+        alert_scheduler::spawn_alert_scheduler(state.clone(), every);
+    }
+    if let Some(every) = score_ticker_interval {
+        score_ticker::spawn_score_ticker(state.clone(), every);
+    }
+    if realtime_scoring || score_ticker_interval.is_some() {
+        realtime_scoring::spawn_price_stream(state.clone(), realtime_scoring);
+    }
+    price_store::spawn_price_listener(state.clone());
+    routes::symbols::spawn_price_provider_symbols(state.clone());
+    retention::spawn_retention_job(state.clone());
+    notification_retry::spawn_retry_worker(state.clone());
+    if let Some(at) = maintenance_time {
+        maintenance::spawn_maintenance(state.clone(), at);
+    }
+
     Router::new()
         .route("/", get(root))
         .route("/health", get(health))
@@ -47,9 +198,28 @@ pub fn create_app(db: PgPool, config: Config) -> Router {
         .nest("/api/stocks", stock_routes())
         .nest("/api/financials", financials_routes())
         .nest("/api/analysis", analysis_routes())
+        .nest("/api/analysis/macro", macro_routes())
         .nest("/api/watchlist", watchlist_routes())
+        .nest("/api/alerts", alert_routes())
+        .nest("/api/journal", journal_routes())
+        .nest("/api/portfolio", portfolio_routes())
+        .nest("/api/screens", screen_routes())
+        .nest("/api/backtests", backtest_routes())
+        .nest("/api/shared/backtests", shared_backtest_routes())
+        .nest("/api/calendar", calendar_routes())
+        .nest("/api/custom-indicators", custom_indicator_routes())
+        .nest("/api/glossary", glossary_routes())
+        .nest("/api/symbols", symbol_routes())
+        .nest("/api/notifications", notification_routes())
         .nest("/api", streaming_routes())
         .nest("/api/admin", admin_routes())
+        .nest("/api/admin/staging", staging_routes())
+        .nest("/api/admin/import", import_routes())
+        .route_layer(axum::middleware::from_fn(authz::authorize))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            demo::demo_access,
+        ))
         .layer(
             CorsLayer::new()
                 .allow_origin(AllowOrigin::list([
@@ -72,10 +242,56 @@ pub fn create_app(db: PgPool, config: Config) -> Router {
                 ])
                 .allow_credentials(true),
         )
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            request_metrics::track_requests,
+        ))
         .layer(TraceLayer::new_for_http())
         .with_state(state)
 }
 
+/// Build the notification service from the configured operator channels
+fn build_notification_service(config: &Config, in_app: Arc<InAppHub>) -> NotificationService {
+    let mut service = NotificationService::new().with_in_app(in_app);
+
+    if let Some(ref bot_token) = config.telegram_bot_token {
+        service = service.with_telegram(TelegramNotifier::new(TelegramConfig {
+            bot_token: bot_token.clone(),
+            ..Default::default()
+        }));
+    }
+
+    if config.admin_webhook_url.is_some() {
+        service = service.with_webhook(WebhookNotifier::new(WebhookConfig::default()));
+    }
+
+    if config.admin_discord_webhook_url.is_some() {
+        service = service.with_discord(DiscordNotifier::new(DiscordConfig::default()));
+    }
+
+    if config.admin_slack_webhook_url.is_some() {
+        service = service.with_slack(SlackNotifier::new(SlackConfig::default()));
+    }
+
+    if let (Some(phone_number_id), Some(access_token)) = (
+        &config.whatsapp_phone_number_id,
+        &config.whatsapp_access_token,
+    ) {
+        let defaults = WhatsAppConfig::default();
+        service = service.with_whatsapp(WhatsAppNotifier::new(WhatsAppConfig {
+            phone_number_id: phone_number_id.clone(),
+            access_token: access_token.clone(),
+            template_name: config
+                .whatsapp_template_name
+                .clone()
+                .unwrap_or(defaults.template_name.clone()),
+            ..defaults
+        }));
+    }
+
+    service
+}
+
 async fn root() -> &'static str {
     "JejakCuan API v0.1.0"
 }
@@ -99,8 +315,38 @@ pub mod test_utils {
             username: "admin".to_string(),
             password_hash: "$argon2id$v=19$m=19456,t=2,p=1$random_salt_here$hashed_password"
                 .to_string(),
+            users: Vec::new(),
             host: "127.0.0.1".to_string(),
             port: 0, // Random port for testing
+            telegram_bot_token: None,
+            admin_telegram_chat_id: None,
+            admin_webhook_url: None,
+            admin_discord_webhook_url: None,
+            admin_slack_webhook_url: None,
+            whatsapp_phone_number_id: None,
+            whatsapp_access_token: None,
+            whatsapp_template_name: None,
+            admin_whatsapp_number: None,
+            risk_budget: Default::default(),
+            transaction_costs: Default::default(),
+            cache_snapshot_path: None,
+            analysis_warm_time_utc: None,
+            alert_scan_interval: None,
+            score_ticker_interval: None,
+            realtime_scoring: false,
+            sector_relative_technical: false,
+            soft_delete_retention_days: 30,
+            maintenance_time_utc: None,
+            notification_max_attempts: 5,
+            market_calendar: Default::default(),
+            secret_cipher: None,
+            demo_data_delay: None,
+            usage_analytics: Default::default(),
+            price_providers: vec![jejakcuan_data_sources::SymbolProvider::Yahoo],
+            twelvedata_api_key: None,
+            twelvedata_pool_keys: Vec::new(),
+            sectors_api_key: None,
+            backfill_daily_requests: None,
         }
     }
 }
@@ -1,6 +1,6 @@
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
-use jejakcuan_api::{config::Config, create_app};
+use jejakcuan_api::{cache_snapshot, config::Config, create_app, secrets};
 
 #[tokio::main]
 async fn main() {
@@ -14,7 +14,7 @@ async fn main() {
         .init();
 
     // Load config
-    let config = Config::from_env();
+    let mut config = Config::from_env();
     tracing::info!("Starting JejakCuan API on {}:{}", config.host, config.port);
 
     // Connect to database
@@ -24,6 +24,14 @@ async fn main() {
 
     tracing::info!("Connected to database");
 
+    // Notifier tokens and provider keys set through the admin API
+    secrets::load_stored_credentials(&db, &mut config).await;
+
+    // Warm the cache from the last shutdown before taking traffic
+    if let Some(path) = &config.cache_snapshot_path {
+        cache_snapshot::restore(&config.redis_url, path).await;
+    }
+
     // Build the application
     let app = create_app(db, config.clone());
 
@@ -31,5 +39,37 @@ async fn main() {
     let addr = format!("{}:{}", config.host, config.port);
     let listener = tokio::net::TcpListener::bind(&addr).await.unwrap();
     tracing::info!("Listening on {}", listener.local_addr().unwrap());
-    axum::serve(listener, app).await.unwrap();
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown_signal())
+        .await
+        .unwrap();
+
+    if let Some(path) = &config.cache_snapshot_path {
+        cache_snapshot::save(&config.redis_url, path).await;
+    }
+}
+
+/// Resolve on Ctrl+C or SIGTERM (sent by the orchestrator on deploy)
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(_) => std::future::pending::<()>().await,
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+    tracing::info!("Shutting down");
 }
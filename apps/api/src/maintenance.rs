@@ -0,0 +1,328 @@
+//! Scheduled database maintenance
+//!
+//! Runs ANALYZE where statistics went stale (and reports tables that would
+//! benefit from a VACUUM), refreshes recent continuous-aggregate buckets,
+//! removes stale scores and fails jobs that never reported back. Each task
+//! runs as a tracked job, so its outcome shows in the admin jobs list.
+
+use crate::routes::jobs::Job;
+use crate::AppState;
+use chrono::{DateTime, Duration, NaiveTime, Utc};
+use jejakcuan_db::{repositories, TableHealthRow};
+use sqlx::PgPool;
+use std::sync::Arc;
+
+/// ANALYZE once this share of rows changed since the last analyze
+const ANALYZE_MODIFIED_RATIO: f64 = 0.1;
+
+/// Suggest a VACUUM once dead rows reach this share of live rows...
+const VACUUM_DEAD_RATIO: f64 = 0.2;
+
+/// ...and at least this many
+const VACUUM_MIN_DEAD_TUPLES: i64 = 1_000;
+
+/// Trailing window of continuous-aggregate buckets re-materialized
+const AGGREGATE_REFRESH_DAYS: i64 = 7;
+
+/// Scores older than this are deleted
+const SCORE_RETENTION_DAYS: i64 = 3 * 365;
+
+/// Jobs still running after this long are considered orphaned
+const ORPHANED_JOB_HOURS: i64 = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaintenanceTask {
+    Analyze,
+    RefreshAggregates,
+    StaleScores,
+    OrphanedJobs,
+}
+
+impl MaintenanceTask {
+    pub const ALL: [MaintenanceTask; 4] = [
+        MaintenanceTask::Analyze,
+        MaintenanceTask::RefreshAggregates,
+        MaintenanceTask::StaleScores,
+        MaintenanceTask::OrphanedJobs,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MaintenanceTask::Analyze => "analyze",
+            MaintenanceTask::RefreshAggregates => "refresh_aggregates",
+            MaintenanceTask::StaleScores => "stale_scores",
+            MaintenanceTask::OrphanedJobs => "orphaned_jobs",
+        }
+    }
+
+    pub fn from_str_opt(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|task| task.as_str() == s)
+    }
+
+    /// Job source id, e.g. `maintenance:analyze`
+    pub fn job_id(&self) -> String {
+        format!("maintenance:{}", self.as_str())
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            MaintenanceTask::Analyze => "Maintenance: table statistics",
+            MaintenanceTask::RefreshAggregates => "Maintenance: continuous aggregates",
+            MaintenanceTask::StaleScores => "Maintenance: stale scores",
+            MaintenanceTask::OrphanedJobs => "Maintenance: orphaned jobs",
+        }
+    }
+
+    fn description(&self) -> String {
+        match self {
+            MaintenanceTask::Analyze => {
+                "ANALYZE tables with stale statistics, report VACUUM candidates".to_string()
+            }
+            MaintenanceTask::RefreshAggregates => format!(
+                "refresh continuous aggregates over the last {} days",
+                AGGREGATE_REFRESH_DAYS
+            ),
+            MaintenanceTask::StaleScores => format!(
+                "delete scores of inactive stocks and scores older than {} days",
+                SCORE_RETENTION_DAYS
+            ),
+            MaintenanceTask::OrphanedJobs => {
+                format!("fail jobs running longer than {} hours", ORPHANED_JOB_HOURS)
+            }
+        }
+    }
+}
+
+/// Whether a table needs ANALYZE, and whether a VACUUM is worth suggesting
+fn table_advice(table: &TableHealthRow) -> (bool, bool) {
+    let live = table.live_tuples.max(1) as f64;
+    let needs_analyze = (table.last_analyze.is_none() && table.live_tuples > 0)
+        || table.modified_since_analyze as f64 / live >= ANALYZE_MODIFIED_RATIO;
+    let vacuum_hint = table.dead_tuples >= VACUUM_MIN_DEAD_TUPLES
+        && table.dead_tuples as f64 / live >= VACUUM_DEAD_RATIO;
+    (needs_analyze, vacuum_hint)
+}
+
+async fn run_analyze(pool: PgPool) -> Result<String, String> {
+    let tables = repositories::maintenance::get_table_health(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut analyzed = Vec::new();
+    let mut hints = Vec::new();
+    let mut errors = Vec::new();
+    for table in &tables {
+        let name = format!("{}.{}", table.schema_name, table.table_name);
+        let (needs_analyze, vacuum_hint) = table_advice(table);
+        if needs_analyze {
+            match repositories::maintenance::analyze_table(
+                &pool,
+                &table.schema_name,
+                &table.table_name,
+            )
+            .await
+            {
+                Ok(()) => analyzed.push(name.clone()),
+                Err(e) => errors.push(format!("{}: {}", name, e)),
+            }
+        }
+        if vacuum_hint {
+            hints.push(format!(
+                "VACUUM suggested for {}: {} dead / {} live rows",
+                name, table.dead_tuples, table.live_tuples
+            ));
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(errors.join("\n"));
+    }
+    let mut lines = vec![format!(
+        "Checked {} tables, analyzed {}: {}",
+        tables.len(),
+        analyzed.len(),
+        analyzed.join(", ")
+    )];
+    lines.extend(hints);
+    Ok(lines.join("\n"))
+}
+
+async fn run_refresh_aggregates(pool: PgPool) -> Result<String, String> {
+    let views = repositories::maintenance::get_continuous_aggregates(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    if views.is_empty() {
+        return Ok("No continuous aggregates defined".to_string());
+    }
+
+    let end = Utc::now();
+    let start = end - Duration::days(AGGREGATE_REFRESH_DAYS);
+    let mut errors = Vec::new();
+    for view in &views {
+        if let Err(e) =
+            repositories::maintenance::refresh_continuous_aggregate(&pool, view, start, end).await
+        {
+            errors.push(format!("{}: {}", view, e));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(format!("Refreshed {}", views.join(", ")))
+    } else {
+        Err(errors.join("\n"))
+    }
+}
+
+async fn run_stale_scores(pool: PgPool) -> Result<String, String> {
+    let before = Utc::now() - Duration::days(SCORE_RETENTION_DAYS);
+    let deleted = repositories::maintenance::delete_stale_scores(&pool, before)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(format!("Deleted {} stale scores", deleted))
+}
+
+/// Start `task` as a tracked job
+///
+/// Returns the already running job as the error if the task is in progress.
+pub async fn start(state: &AppState, task: MaintenanceTask) -> Result<Job, Job> {
+    let job_id = task.job_id();
+    if let Some(job) = state.job_manager.is_source_running(&job_id).await {
+        return Err(job);
+    }
+
+    let pool = state.db.clone();
+    let job_manager = state.job_manager.clone();
+    let name = task.name().to_string();
+    let description = task.description();
+    let job = match task {
+        MaintenanceTask::Analyze => {
+            state
+                .job_manager
+                .spawn_task(job_id, name, description, run_analyze(pool))
+                .await
+        }
+        MaintenanceTask::RefreshAggregates => {
+            state
+                .job_manager
+                .spawn_task(job_id, name, description, run_refresh_aggregates(pool))
+                .await
+        }
+        MaintenanceTask::StaleScores => {
+            state
+                .job_manager
+                .spawn_task(job_id, name, description, run_stale_scores(pool))
+                .await
+        }
+        MaintenanceTask::OrphanedJobs => {
+            state
+                .job_manager
+                .spawn_task(job_id, name, description, async move {
+                    let pruned = job_manager
+                        .prune_orphaned(Duration::hours(ORPHANED_JOB_HOURS))
+                        .await;
+                    Ok(format!("Marked {} orphaned jobs as failed", pruned))
+                })
+                .await
+        }
+    };
+    Ok(job)
+}
+
+/// Start every task that is not already running
+pub async fn start_all(state: &AppState) -> Vec<Job> {
+    let mut jobs = Vec::new();
+    for task in MaintenanceTask::ALL {
+        match start(state, task).await {
+            Ok(job) => jobs.push(job),
+            Err(running) => {
+                tracing::info!(
+                    "Maintenance {} still running (job {})",
+                    task.as_str(),
+                    running.id
+                )
+            }
+        }
+    }
+    jobs
+}
+
+/// Run every maintenance task daily at `at` (UTC)
+pub fn spawn_maintenance(state: Arc<AppState>, at: NaiveTime) {
+    tokio::spawn(async move {
+        loop {
+            let now = Utc::now();
+            let wait = (next_daily_run(now, at) - now).to_std().unwrap_or_default();
+            tokio::time::sleep(wait).await;
+
+            let jobs = start_all(&state).await;
+            tracing::info!("Started {} maintenance jobs", jobs.len());
+        }
+    });
+}
+
+/// Next occurrence of `at`, strictly after `now`
+fn next_daily_run(now: DateTime<Utc>, at: NaiveTime) -> DateTime<Utc> {
+    let mut date = now.date_naive();
+    if now.time() >= at {
+        date += Duration::days(1);
+    }
+    date.and_time(at).and_utc()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(live: i64, dead: i64, modified: i64, analyzed: bool) -> TableHealthRow {
+        TableHealthRow {
+            schema_name: "public".to_string(),
+            table_name: "stock_prices".to_string(),
+            live_tuples: live,
+            dead_tuples: dead,
+            modified_since_analyze: modified,
+            last_vacuum: None,
+            last_analyze: analyzed.then(Utc::now),
+        }
+    }
+
+    #[test]
+    fn test_table_advice() {
+        assert_eq!(table_advice(&table(100_000, 0, 500, true)), (false, false));
+        assert_eq!(
+            table_advice(&table(100_000, 0, 20_000, true)),
+            (true, false)
+        );
+        assert_eq!(
+            table_advice(&table(100_000, 30_000, 0, true)),
+            (false, true)
+        );
+        // Small tables are not worth a vacuum hint
+        assert_eq!(table_advice(&table(100, 90, 0, true)), (false, false));
+        // Never analyzed
+        assert_eq!(table_advice(&table(10, 0, 0, false)), (true, false));
+    }
+
+    #[test]
+    fn test_next_daily_run() {
+        let at = NaiveTime::from_hms_opt(19, 0, 0).unwrap();
+        let now: DateTime<Utc> = "2025-06-07T18:00:00Z".parse().unwrap();
+        assert_eq!(
+            next_daily_run(now, at),
+            "2025-06-07T19:00:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+        let now: DateTime<Utc> = "2025-06-07T19:00:00Z".parse().unwrap();
+        assert_eq!(
+            next_daily_run(now, at),
+            "2025-06-08T19:00:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_task_ids() {
+        for task in MaintenanceTask::ALL {
+            assert_eq!(MaintenanceTask::from_str_opt(task.as_str()), Some(task));
+        }
+        assert_eq!(MaintenanceTask::Analyze.job_id(), "maintenance:analyze");
+        assert_eq!(MaintenanceTask::from_str_opt("vacuum"), None);
+    }
+}
@@ -0,0 +1,179 @@
+//! News ingestion and the sentiment scoring input
+//!
+//! Polls the default Indonesian financial news feeds, tags each headline
+//! with the listed tickers it mentions and stores it per stock with its
+//! lexicon sentiment. Scoring combines a stock's recent scored headlines
+//! into the sentiment component of the composite score.
+
+use crate::routes::jobs::Job;
+use crate::AppState;
+use chrono::{DateTime, Duration, Utc};
+use jejakcuan_core::{
+    aggregate_news_sentiment, score_text, NewsSentiment, ScoredArticle, TextSentiment,
+    NEWS_HALF_LIFE_DAYS,
+};
+use jejakcuan_data_sources::{tag_symbols, NewsArticle, NewsClient, DEFAULT_NEWS_FEEDS};
+use jejakcuan_db::repositories::{self, news::InsertNews};
+use jejakcuan_db::StockNewsRow;
+use sqlx::PgPool;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Job source id, shared with the data source registry entry
+pub const NEWS_INGEST_JOB: &str = "news_feeds";
+
+/// Headlines older than this no longer move a stock's sentiment
+pub const SENTIMENT_LOOKBACK_DAYS: i64 = 14;
+
+/// Start a background job ingesting the news feeds
+pub async fn spawn_ingest(state: &Arc<AppState>) -> Job {
+    let pool = state.db.clone();
+    state
+        .job_manager
+        .spawn_task(
+            NEWS_INGEST_JOB.to_string(),
+            "News Feeds".to_string(),
+            "fetch news feeds, tag tickers and score sentiment".to_string(),
+            async move { ingest_news(&pool, &NewsClient::new()).await },
+        )
+        .await
+}
+
+async fn ingest_news(pool: &PgPool, client: &NewsClient) -> Result<String, String> {
+    let known: HashSet<String> = repositories::stocks::get_all_stocks(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|s| s.symbol)
+        .collect();
+
+    let mut articles = Vec::new();
+    let mut errors = Vec::new();
+    for feed in DEFAULT_NEWS_FEEDS {
+        match client.fetch_feed(feed).await {
+            Ok(items) => articles.extend(items),
+            Err(e) => errors.push(format!("{}: {}", feed.id, e)),
+        }
+    }
+    if errors.len() == DEFAULT_NEWS_FEEDS.len() {
+        return Err(format!("No feeds fetched: {}", errors.join("; ")));
+    }
+
+    let tagged = tag_articles(&articles, &known);
+    let rows: Vec<InsertNews> = tagged
+        .iter()
+        .map(|(symbol, article, sentiment)| InsertNews {
+            symbol,
+            title: &article.title,
+            summary: article.summary.as_deref(),
+            source: &article.source,
+            url: &article.url,
+            published_at: article.published_at,
+            sentiment: sentiment.label.as_str(),
+            sentiment_score: sentiment.score,
+        })
+        .collect();
+    let written = repositories::news::insert_news(pool, &rows)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut summary = format!(
+        "{} articles, {} stock mentions, {} stored",
+        articles.len(),
+        rows.len(),
+        written
+    );
+    if !errors.is_empty() {
+        summary.push_str(&format!(
+            " ({} feeds failed: {})",
+            errors.len(),
+            errors.join("; ")
+        ));
+    }
+    Ok(summary)
+}
+
+/// One row per article and known ticker it mentions, with the article's sentiment
+///
+/// Articles mentioning no listed stock are dropped.
+fn tag_articles<'a>(
+    articles: &'a [NewsArticle],
+    known: &HashSet<String>,
+) -> Vec<(String, &'a NewsArticle, TextSentiment)> {
+    let mut tagged = Vec::new();
+    for article in articles {
+        let text = article.text();
+        let symbols = tag_symbols(&text, known);
+        if symbols.is_empty() {
+            continue;
+        }
+        let sentiment = score_text(&text);
+        tagged.extend(
+            symbols
+                .into_iter()
+                .map(|symbol| (symbol, article, sentiment.clone())),
+        );
+    }
+    tagged
+}
+
+/// Sentiment component for `symbol` from its headlines of the last two weeks
+///
+/// `None` when the stock has not been in the news.
+pub async fn news_sentiment(
+    pool: &PgPool,
+    symbol: &str,
+    now: DateTime<Utc>,
+) -> Result<Option<NewsSentiment>, sqlx::Error> {
+    let since = now - Duration::days(SENTIMENT_LOOKBACK_DAYS);
+    let rows = repositories::news::get_scored_news_since(pool, symbol, since).await?;
+    Ok(aggregate_news_sentiment(
+        &scored_articles(&rows, now),
+        NEWS_HALF_LIFE_DAYS,
+    ))
+}
+
+fn scored_articles(rows: &[StockNewsRow], now: DateTime<Utc>) -> Vec<ScoredArticle> {
+    rows.iter()
+        .filter_map(|row| {
+            Some(ScoredArticle {
+                score: row.sentiment_score?,
+                age_days: (now - row.published_at).num_minutes() as f64 / (24.0 * 60.0),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn article(title: &str) -> NewsArticle {
+        NewsArticle {
+            source: "kontan".to_string(),
+            title: title.to_string(),
+            summary: None,
+            url: format!("https://example.com/{}", title.len()),
+            published_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_tag_articles() {
+        let known: HashSet<String> = ["BBRI", "BMRI", "ANTM"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let articles = vec![
+            article("Laba BBRI dan BMRI naik"),
+            article("IHSG ditutup melemah"),
+            article("Saham ANTM anjlok"),
+        ];
+
+        let tagged = tag_articles(&articles, &known);
+        let symbols: Vec<&str> = tagged.iter().map(|(s, _, _)| s.as_str()).collect();
+        assert_eq!(symbols, ["BBRI", "BMRI", "ANTM"]);
+        assert_eq!(tagged[0].2.positive_hits, 1);
+        assert!(tagged[2].2.score < rust_decimal::Decimal::ZERO);
+    }
+}
@@ -0,0 +1,324 @@
+//! Retry queue for failed notification deliveries
+//!
+//! A delivery that fails is stored in `notification_retries` and retried with
+//! exponential backoff (1, 2, 4, ... minutes, capped at six hours; a longer
+//! Retry-After from a rate limit wins). After the configured number of
+//! attempts, or straight away on an error retrying cannot fix such as an
+//! unconfigured channel, the row stays as a dead letter for an admin to
+//! inspect and retry by hand, until the daily purge removes it after
+//! [`DEAD_LETTER_RETENTION_DAYS`]. Workers claim due rows with `SKIP LOCKED`,
+//! so several API instances never send the same retry twice.
+
+use crate::notifications::{Notification, NotificationError, NotificationResult};
+use crate::AppState;
+use chrono::{DateTime, Duration, Utc};
+use jejakcuan_db::repositories;
+use jejakcuan_db::repositories::notification_retries::InsertNotificationRetry;
+use jejakcuan_db::NotificationRetryRow;
+use serde::Serialize;
+use std::sync::Arc;
+
+/// How often due retries are picked up
+const RETRY_POLL_EVERY: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Retries attempted per poll
+const RETRY_BATCH: i64 = 50;
+
+/// How long a claimed retry is hidden from other workers; one left claimed by
+/// a crashed worker comes due again after this
+const RETRY_LEASE_MINUTES: i64 = 30;
+
+/// Days a dead letter is kept for inspection
+pub const DEAD_LETTER_RETENTION_DAYS: i64 = 30;
+
+const BASE_DELAY_SECS: i64 = 60;
+const MAX_DELAY_SECS: i64 = 6 * 60 * 60;
+
+pub const STATUS_PENDING: &str = "pending";
+pub const STATUS_DEAD: &str = "dead";
+
+/// Delay before the next attempt after `attempts` failed ones
+pub fn backoff(attempts: u32) -> Duration {
+    let exponent = attempts.saturating_sub(1).min(20);
+    Duration::seconds((BASE_DELAY_SECS << exponent).min(MAX_DELAY_SECS))
+}
+
+/// Whether sending again can succeed without someone changing the configuration
+pub fn is_retryable(error: &NotificationError) -> bool {
+    !matches!(
+        error,
+        NotificationError::NotConfigured(_) | NotificationError::InvalidRecipient(_)
+    )
+}
+
+/// When to try again after `attempts` failures; `None` gives up
+pub fn next_attempt_at(
+    now: DateTime<Utc>,
+    attempts: u32,
+    max_attempts: u32,
+    error: &NotificationError,
+) -> Option<DateTime<Utc>> {
+    if attempts >= max_attempts || !is_retryable(error) {
+        return None;
+    }
+    let mut delay = backoff(attempts);
+    if let NotificationError::RateLimited(secs) = error {
+        delay = delay.max(Duration::seconds(*secs as i64));
+    }
+    Some(now + delay)
+}
+
+/// Send a notification, queueing it for retry when delivery fails
+///
+/// The original error is still returned so callers can report it.
+pub async fn deliver(state: &AppState, notification: &Notification) -> NotificationResult<()> {
+    let Err(error) = state.notifications.send(notification).await else {
+        return Ok(());
+    };
+
+    let now = Utc::now();
+    let next = next_attempt_at(now, 1, state.config.notification_max_attempts, &error);
+    let payload = serde_json::to_value(notification).unwrap_or_default();
+    let channel = channel_name(notification);
+    let message = error.to_string();
+    let queued = repositories::notification_retries::insert_notification_retry(
+        &state.db,
+        &InsertNotificationRetry {
+            channel: &channel,
+            recipient_id: &notification.recipient_id,
+            payload: &payload,
+            last_error: &message,
+            status: if next.is_some() {
+                STATUS_PENDING
+            } else {
+                STATUS_DEAD
+            },
+            next_attempt_at: next.unwrap_or(now),
+        },
+    )
+    .await;
+    if let Err(e) = queued {
+        tracing::warn!("Failed to queue {} notification for retry: {}", channel, e);
+    }
+
+    Err(error)
+}
+
+fn channel_name(notification: &Notification) -> String {
+    serde_json::to_value(&notification.channel)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+/// Outcome of one pass over the due retries
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct RetryReport {
+    pub attempted: usize,
+    pub delivered: usize,
+    pub rescheduled: usize,
+    pub dead: usize,
+}
+
+/// Result of attempting a queued delivery
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryOutcome {
+    /// Sent and removed from the queue
+    Delivered,
+    /// Failed again and scheduled for a later attempt
+    Rescheduled,
+    /// Failed again and kept as a dead letter
+    Dead,
+}
+
+/// Attempt a queued delivery once; delivered rows are removed
+pub async fn attempt(
+    state: &AppState,
+    row: &NotificationRetryRow,
+) -> Result<RetryOutcome, sqlx::Error> {
+    let notification: Notification = match serde_json::from_value(row.payload.clone()) {
+        Ok(notification) => notification,
+        Err(e) => {
+            let error = format!("Unreadable payload: {}", e);
+            repositories::notification_retries::record_notification_retry_failure(
+                &state.db,
+                row.id,
+                &error,
+                STATUS_DEAD,
+                Utc::now(),
+            )
+            .await?;
+            return Ok(RetryOutcome::Dead);
+        }
+    };
+
+    match state.notifications.send(&notification).await {
+        Ok(()) => {
+            repositories::notification_retries::delete_notification_retry(&state.db, row.id)
+                .await?;
+            Ok(RetryOutcome::Delivered)
+        }
+        Err(error) => {
+            let now = Utc::now();
+            let attempts = row.attempts.max(0) as u32 + 1;
+            let next = next_attempt_at(
+                now,
+                attempts,
+                state.config.notification_max_attempts,
+                &error,
+            );
+            let (status, outcome) = if next.is_some() {
+                (STATUS_PENDING, RetryOutcome::Rescheduled)
+            } else {
+                (STATUS_DEAD, RetryOutcome::Dead)
+            };
+            repositories::notification_retries::record_notification_retry_failure(
+                &state.db,
+                row.id,
+                &error.to_string(),
+                status,
+                next.unwrap_or(now),
+            )
+            .await?;
+            Ok(outcome)
+        }
+    }
+}
+
+/// Retry every queued delivery that is due
+pub async fn retry_due(state: &AppState) -> Result<RetryReport, sqlx::Error> {
+    let now = Utc::now();
+    let due = repositories::notification_retries::claim_due_notification_retries(
+        &state.db,
+        now,
+        now + Duration::minutes(RETRY_LEASE_MINUTES),
+        RETRY_BATCH,
+    )
+    .await?;
+
+    let mut report = RetryReport::default();
+    for row in &due {
+        report.attempted += 1;
+        match attempt(state, row).await? {
+            RetryOutcome::Delivered => report.delivered += 1,
+            RetryOutcome::Rescheduled => report.rescheduled += 1,
+            RetryOutcome::Dead => report.dead += 1,
+        }
+    }
+    Ok(report)
+}
+
+/// `recipient` with the path and query of a URL hidden
+///
+/// Webhook URLs carry their credentials in the path, so only the scheme and
+/// host are shown; other recipients are returned as they are.
+pub fn redact_recipient(recipient: &str) -> String {
+    match recipient.split_once("://") {
+        Some((scheme, rest)) => {
+            let host = rest.split(['/', '?', '#']).next().unwrap_or_default();
+            format!("{}://{}/***", scheme, host)
+        }
+        None => recipient.to_string(),
+    }
+}
+
+/// A queued delivery with its recipient redacted wherever it appears
+pub fn redact_retry(mut row: NotificationRetryRow) -> NotificationRetryRow {
+    let redacted = redact_recipient(&row.recipient_id);
+    if redacted == row.recipient_id {
+        return row;
+    }
+    row.last_error = row.last_error.replace(&row.recipient_id, &redacted);
+    if let Some(recipient) = row.payload.get_mut("recipient_id") {
+        *recipient = serde_json::Value::String(redacted.clone());
+    }
+    row.recipient_id = redacted;
+    row
+}
+
+/// Poll the retry queue in the background
+pub fn spawn_retry_worker(state: Arc<AppState>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(RETRY_POLL_EVERY);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            interval.tick().await;
+            match retry_due(&state).await {
+                Ok(report) if report.attempted > 0 => tracing::info!(
+                    "Notification retries: {} delivered, {} rescheduled, {} dead",
+                    report.delivered,
+                    report.rescheduled,
+                    report.dead
+                ),
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Notification retry pass failed: {}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_and_caps() {
+        assert_eq!(backoff(1), Duration::minutes(1));
+        assert_eq!(backoff(2), Duration::minutes(2));
+        assert_eq!(backoff(4), Duration::minutes(8));
+        assert_eq!(backoff(12), Duration::hours(6));
+        assert_eq!(backoff(u32::MAX), Duration::hours(6));
+    }
+
+    #[test]
+    fn test_redact_retry() {
+        let url = "https://hooks.slack.com/services/T1/B2/secret";
+        let now: DateTime<Utc> = "2025-06-30T12:00:00Z".parse().unwrap();
+        let row = NotificationRetryRow {
+            id: 1,
+            channel: "Slack".to_string(),
+            recipient_id: url.to_string(),
+            payload: serde_json::json!({ "recipient_id": url, "title": "RSI oversold" }),
+            attempts: 5,
+            last_error: format!("error sending request for url ({})", url),
+            status: STATUS_DEAD.to_string(),
+            next_attempt_at: now,
+            created_at: now,
+            updated_at: now,
+        };
+        let redacted = redact_retry(row);
+        assert_eq!(redacted.recipient_id, "https://hooks.slack.com/***");
+        assert_eq!(
+            redacted.payload["recipient_id"],
+            "https://hooks.slack.com/***"
+        );
+        assert_eq!(redacted.payload["title"], "RSI oversold");
+        assert!(!redacted.last_error.contains("secret"));
+
+        assert_eq!(redact_recipient("ops@example.com"), "ops@example.com");
+        assert_eq!(redact_recipient("123456"), "123456");
+    }
+
+    #[test]
+    fn test_next_attempt() {
+        let now: DateTime<Utc> = "2025-06-30T12:00:00Z".parse().unwrap();
+        let network = NotificationError::NetworkError("timeout".into());
+
+        assert_eq!(
+            next_attempt_at(now, 2, 5, &network),
+            Some(now + Duration::minutes(2))
+        );
+        // Out of attempts
+        assert_eq!(next_attempt_at(now, 5, 5, &network), None);
+        // Retrying cannot fix a missing channel
+        let unconfigured = NotificationError::NotConfigured("Telegram".into());
+        assert_eq!(next_attempt_at(now, 1, 5, &unconfigured), None);
+        // A longer Retry-After wins over the backoff
+        let limited = NotificationError::RateLimited(600);
+        assert_eq!(
+            next_attempt_at(now, 1, 5, &limited),
+            Some(now + Duration::minutes(10))
+        );
+    }
+}
@@ -0,0 +1,178 @@
+//! Discord notification channel
+//!
+//! Posts to a Discord incoming webhook; the recipient id is the webhook URL.
+
+use super::{
+    Notification, NotificationError, NotificationPreview, NotificationPriority, NotificationResult,
+    NotificationSender,
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use jejakcuan_core::alerts::NotificationChannel;
+use serde::{Deserialize, Serialize};
+
+/// Discord webhook configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscordConfig {
+    /// Name the messages are posted under
+    pub username: String,
+    pub avatar_url: Option<String>,
+    pub timeout_seconds: u64,
+}
+
+impl Default for DiscordConfig {
+    fn default() -> Self {
+        Self {
+            username: "JejakCuan".to_string(),
+            avatar_url: None,
+            timeout_seconds: 30,
+        }
+    }
+}
+
+/// Discord notification sender
+pub struct DiscordNotifier {
+    config: DiscordConfig,
+    client: reqwest::Client,
+}
+
+impl DiscordNotifier {
+    pub fn new(config: DiscordConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(config.timeout_seconds))
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self { config, client }
+    }
+
+    /// Embed sidebar color by priority
+    fn embed_color(priority: NotificationPriority) -> u32 {
+        match priority {
+            NotificationPriority::Critical => 0xD32F2F,
+            NotificationPriority::High => 0xF57C00,
+            NotificationPriority::Medium => 0xFBC02D,
+            NotificationPriority::Low => 0x388E3C,
+        }
+    }
+
+    fn create_payload(&self, notification: &Notification) -> serde_json::Value {
+        let fields: Vec<serde_json::Value> = notification
+            .metadata
+            .summary_fields()
+            .into_iter()
+            .map(|(name, value)| {
+                serde_json::json!({
+                    "name": name,
+                    "value": value,
+                    "inline": true,
+                })
+            })
+            .collect();
+
+        serde_json::json!({
+            "username": self.config.username,
+            "avatar_url": self.config.avatar_url,
+            "embeds": [{
+                "title": notification.title,
+                "description": notification.body,
+                "color": Self::embed_color(notification.priority),
+                "fields": fields,
+                "footer": {
+                    "text": format!("{:?} priority", notification.priority),
+                },
+                "timestamp": Utc::now().to_rfc3339(),
+            }],
+        })
+    }
+}
+
+#[async_trait]
+impl NotificationSender for DiscordNotifier {
+    async fn send(&self, notification: &Notification) -> NotificationResult<()> {
+        let webhook_url = &notification.recipient_id;
+        if !webhook_url.starts_with("https://") {
+            return Err(NotificationError::InvalidRecipient(
+                "Discord webhook URL must start with https://".into(),
+            ));
+        }
+
+        let response = self
+            .client
+            .post(webhook_url)
+            .json(&self.create_payload(notification))
+            .send()
+            .await
+            .map_err(|e| NotificationError::NetworkError(e.to_string()))?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else if response.status().as_u16() == 429 {
+            let retry_after = response
+                .headers()
+                .get("Retry-After")
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse::<f64>().ok())
+                .map(|secs| secs.ceil() as u64)
+                .unwrap_or(30);
+            Err(NotificationError::RateLimited(retry_after))
+        } else {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            Err(NotificationError::SendFailed(error_text))
+        }
+    }
+
+    fn preview(&self, notification: &Notification) -> NotificationPreview {
+        NotificationPreview {
+            channel: NotificationChannel::Discord,
+            configured: self.is_configured(),
+            format: "json".to_string(),
+            payload: self.create_payload(notification),
+        }
+    }
+
+    fn is_configured(&self) -> bool {
+        // Webhook URLs are per-notification
+        true
+    }
+
+    fn channel_type(&self) -> NotificationChannel {
+        NotificationChannel::Discord
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embed_fields() {
+        let notifier = DiscordNotifier::new(DiscordConfig::default());
+        let notification = Notification {
+            recipient_id: "https://discord.com/api/webhooks/1/abc".to_string(),
+            title: "BBCA Alert".to_string(),
+            body: "Coordinated buying by 3 brokers".to_string(),
+            priority: NotificationPriority::High,
+            channel: NotificationChannel::Discord,
+            alert: None,
+            metadata: super::super::NotificationMetadata {
+                symbol: Some("BBCA".to_string()),
+                score: Some(72.46),
+                signal: Some("coordinated_buying".to_string()),
+                ..Default::default()
+            },
+        };
+
+        let payload = notifier.create_payload(&notification);
+        let embed = &payload["embeds"][0];
+        assert_eq!(payload["username"], "JejakCuan");
+        assert_eq!(embed["title"], "BBCA Alert");
+        assert_eq!(embed["color"], 0xF57C00);
+        assert_eq!(embed["fields"][0]["value"], "BBCA");
+        assert_eq!(embed["fields"][1]["value"], "72.5");
+        assert_eq!(embed["fields"][2]["name"], "Signal");
+    }
+}
@@ -1,6 +1,8 @@
 //! Email notification channel via SMTP
 
-use super::{Notification, NotificationError, NotificationResult, NotificationSender};
+use super::{
+    Notification, NotificationError, NotificationPreview, NotificationResult, NotificationSender,
+};
 use async_trait::async_trait;
 use jejakcuan_core::alerts::NotificationChannel;
 use serde::{Deserialize, Serialize};
@@ -125,6 +127,21 @@ impl NotificationSender for EmailNotifier {
         Ok(())
     }
 
+    fn preview(&self, notification: &Notification) -> NotificationPreview {
+        NotificationPreview {
+            channel: NotificationChannel::Email,
+            configured: self.is_configured(),
+            format: "html".to_string(),
+            payload: serde_json::json!({
+                "from": format!("{} <{}>", self.config.from_name, self.config.from_email),
+                "to": notification.recipient_id,
+                "subject": notification.title,
+                "text": notification.body,
+                "html": self.format_html(notification),
+            }),
+        }
+    }
+
     fn is_configured(&self) -> bool {
         !self.config.smtp_host.is_empty() && !self.config.from_email.is_empty()
     }
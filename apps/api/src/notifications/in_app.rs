@@ -0,0 +1,167 @@
+//! In-app notification channel
+//!
+//! Each connected user gets a broadcast channel; every open WebSocket of that
+//! user subscribes to it. Notifications for users with no open connection are
+//! dropped, since the alert history keeps them for later review.
+
+use super::{
+    Notification, NotificationMetadata, NotificationPreview, NotificationPriority,
+    NotificationResult, NotificationSender,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use jejakcuan_core::alerts::{Alert, NotificationChannel};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::RwLock;
+use tokio::sync::broadcast;
+
+/// Messages buffered per user before slow connections start skipping
+const USER_CHANNEL_CAPACITY: usize = 64;
+
+/// Notification as pushed to the browser
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InAppNotification {
+    pub title: String,
+    pub body: String,
+    pub priority: NotificationPriority,
+    pub metadata: NotificationMetadata,
+    pub alert: Option<Alert>,
+    pub sent_at: DateTime<Utc>,
+}
+
+impl From<&Notification> for InAppNotification {
+    fn from(notification: &Notification) -> Self {
+        Self {
+            title: notification.title.clone(),
+            body: notification.body.clone(),
+            priority: notification.priority,
+            metadata: notification.metadata.clone(),
+            alert: notification.alert.clone(),
+            sent_at: Utc::now(),
+        }
+    }
+}
+
+/// Per-user broadcast channels of in-app notifications
+#[derive(Debug, Default)]
+pub struct InAppHub {
+    users: RwLock<HashMap<String, broadcast::Sender<InAppNotification>>>,
+}
+
+impl InAppHub {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Receive the notifications of `user_id`
+    pub fn subscribe(&self, user_id: &str) -> broadcast::Receiver<InAppNotification> {
+        let mut users = self.users.write().unwrap_or_else(|e| e.into_inner());
+        users
+            .entry(user_id.to_string())
+            .or_insert_with(|| broadcast::channel(USER_CHANNEL_CAPACITY).0)
+            .subscribe()
+    }
+
+    /// Push to every open connection of `user_id`; returns how many received it
+    pub fn publish(&self, user_id: &str, notification: InAppNotification) -> usize {
+        let mut users = self.users.write().unwrap_or_else(|e| e.into_inner());
+        let Some(tx) = users.get(user_id) else {
+            return 0;
+        };
+        match tx.send(notification) {
+            Ok(received) => received,
+            Err(_) => {
+                // Every connection of the user closed
+                users.remove(user_id);
+                0
+            }
+        }
+    }
+
+    /// Number of open connections across users
+    pub fn connection_count(&self) -> usize {
+        let users = self.users.read().unwrap_or_else(|e| e.into_inner());
+        users.values().map(|tx| tx.receiver_count()).sum()
+    }
+}
+
+#[async_trait]
+impl NotificationSender for InAppHub {
+    async fn send(&self, notification: &Notification) -> NotificationResult<()> {
+        let received = self.publish(&notification.recipient_id, notification.into());
+        if received == 0 {
+            tracing::debug!(
+                "No open in-app connection for {}, notification dropped",
+                notification.recipient_id
+            );
+        }
+        Ok(())
+    }
+
+    fn preview(&self, notification: &Notification) -> NotificationPreview {
+        let message = InAppNotification::from(notification);
+        NotificationPreview {
+            channel: NotificationChannel::InApp,
+            configured: true,
+            format: "json".to_string(),
+            payload: serde_json::json!({
+                "title": message.title,
+                "body": message.body,
+                "priority": message.priority,
+                "metadata": message.metadata,
+            }),
+        }
+    }
+
+    fn is_configured(&self) -> bool {
+        true
+    }
+
+    fn channel_type(&self) -> NotificationChannel {
+        NotificationChannel::InApp
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(title: &str) -> InAppNotification {
+        InAppNotification {
+            title: title.to_string(),
+            body: "body".to_string(),
+            priority: NotificationPriority::High,
+            metadata: NotificationMetadata::default(),
+            alert: None,
+            sent_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_publish_reaches_only_the_user() {
+        let hub = InAppHub::new();
+        let mut alice = hub.subscribe("alice");
+        let mut alice_tab = hub.subscribe("alice");
+        let mut bob = hub.subscribe("bob");
+
+        assert_eq!(hub.publish("alice", message("BBCA Alert")), 2);
+        assert_eq!(alice.try_recv().unwrap().title, "BBCA Alert");
+        assert_eq!(alice_tab.try_recv().unwrap().title, "BBCA Alert");
+        assert!(bob.try_recv().is_err());
+        assert_eq!(hub.connection_count(), 3);
+
+        // Nobody connected
+        assert_eq!(hub.publish("carol", message("TLKM Alert")), 0);
+    }
+
+    #[test]
+    fn test_closed_connections_are_dropped() {
+        let hub = InAppHub::new();
+        drop(hub.subscribe("alice"));
+
+        assert_eq!(hub.publish("alice", message("BBCA Alert")), 0);
+        assert_eq!(hub.connection_count(), 0);
+        assert!(hub.users.read().unwrap().is_empty());
+    }
+}
@@ -4,16 +4,26 @@
 //! - Telegram bot
 //! - Email (SMTP)
 //! - Webhook
+//! - Discord and Slack incoming webhooks
+//! - WhatsApp Cloud API template messages
 //! - Web push notifications
 //! - In-app notifications via WebSocket/SSE
 
+mod discord;
 mod email;
+mod in_app;
+mod slack;
 mod telegram;
 mod webhook;
+mod whatsapp;
 
+pub use discord::*;
 pub use email::*;
+pub use in_app::*;
+pub use slack::*;
 pub use telegram::*;
 pub use webhook::*;
+pub use whatsapp::*;
 
 use async_trait::async_trait;
 use jejakcuan_core::alerts::{Alert, NotificationChannel};
@@ -44,6 +54,9 @@ pub trait NotificationSender: Send + Sync {
     /// Send a notification
     async fn send(&self, notification: &Notification) -> NotificationResult<()>;
 
+    /// Render the payload this channel would send, without sending it
+    fn preview(&self, notification: &Notification) -> NotificationPreview;
+
     /// Check if channel is configured and ready
     fn is_configured(&self) -> bool;
 
@@ -89,6 +102,40 @@ pub struct NotificationMetadata {
     pub alert_id: Option<String>,
     pub action_url: Option<String>,
     pub icon: Option<String>,
+    /// Latest composite score of the symbol
+    #[serde(default)]
+    pub score: Option<f64>,
+    /// Signal behind the alert, e.g. `coordinated_buying`
+    #[serde(default)]
+    pub signal: Option<String>,
+}
+
+impl NotificationMetadata {
+    /// Labelled symbol, score and signal, for channels that render fields
+    pub fn summary_fields(&self) -> Vec<(&'static str, String)> {
+        let mut fields = Vec::new();
+        if let Some(ref symbol) = self.symbol {
+            fields.push(("Symbol", symbol.clone()));
+        }
+        if let Some(score) = self.score {
+            fields.push(("Score", format!("{:.1}", score)));
+        }
+        if let Some(ref signal) = self.signal {
+            fields.push(("Signal", signal.clone()));
+        }
+        fields
+    }
+}
+
+/// Formatted payload of a notification for one channel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationPreview {
+    pub channel: NotificationChannel,
+    /// Whether the channel has everything it needs to send
+    pub configured: bool,
+    /// Payload format: "markdown", "html" or "json"
+    pub format: String,
+    pub payload: serde_json::Value,
 }
 
 /// Notification service that routes to appropriate channels
@@ -96,6 +143,10 @@ pub struct NotificationService {
     telegram: Option<Arc<TelegramNotifier>>,
     email: Option<Arc<EmailNotifier>>,
     webhook: Option<Arc<WebhookNotifier>>,
+    discord: Option<Arc<DiscordNotifier>>,
+    slack: Option<Arc<SlackNotifier>>,
+    whatsapp: Option<Arc<WhatsAppNotifier>>,
+    in_app: Option<Arc<InAppHub>>,
 }
 
 impl NotificationService {
@@ -104,6 +155,10 @@ impl NotificationService {
             telegram: None,
             email: None,
             webhook: None,
+            discord: None,
+            slack: None,
+            whatsapp: None,
+            in_app: None,
         }
     }
 
@@ -122,6 +177,40 @@ impl NotificationService {
         self
     }
 
+    pub fn with_discord(mut self, notifier: DiscordNotifier) -> Self {
+        self.discord = Some(Arc::new(notifier));
+        self
+    }
+
+    pub fn with_slack(mut self, notifier: SlackNotifier) -> Self {
+        self.slack = Some(Arc::new(notifier));
+        self
+    }
+
+    pub fn with_whatsapp(mut self, notifier: WhatsAppNotifier) -> Self {
+        self.whatsapp = Some(Arc::new(notifier));
+        self
+    }
+
+    pub fn with_in_app(mut self, hub: Arc<InAppHub>) -> Self {
+        self.in_app = Some(hub);
+        self
+    }
+
+    /// Whether a sender is configured for `channel`
+    pub fn supports(&self, channel: &NotificationChannel) -> bool {
+        match channel {
+            NotificationChannel::Telegram => self.telegram.is_some(),
+            NotificationChannel::Email => self.email.is_some(),
+            NotificationChannel::Webhook => self.webhook.is_some(),
+            NotificationChannel::Discord => self.discord.is_some(),
+            NotificationChannel::Slack => self.slack.is_some(),
+            NotificationChannel::WhatsApp => self.whatsapp.is_some(),
+            NotificationChannel::WebPush => false,
+            NotificationChannel::InApp => self.in_app.is_some(),
+        }
+    }
+
     /// Send notification via specified channel
     pub async fn send(&self, notification: &Notification) -> NotificationResult<()> {
         match notification.channel {
@@ -146,13 +235,37 @@ impl NotificationService {
                     Err(NotificationError::NotConfigured("Webhook".into()))
                 }
             }
+            NotificationChannel::Discord => {
+                if let Some(ref sender) = self.discord {
+                    sender.send(notification).await
+                } else {
+                    Err(NotificationError::NotConfigured("Discord".into()))
+                }
+            }
+            NotificationChannel::Slack => {
+                if let Some(ref sender) = self.slack {
+                    sender.send(notification).await
+                } else {
+                    Err(NotificationError::NotConfigured("Slack".into()))
+                }
+            }
+            NotificationChannel::WhatsApp => {
+                if let Some(ref sender) = self.whatsapp {
+                    sender.send(notification).await
+                } else {
+                    Err(NotificationError::NotConfigured("WhatsApp".into()))
+                }
+            }
             NotificationChannel::WebPush => {
                 // WebPush would require additional setup
                 Err(NotificationError::NotConfigured("WebPush".into()))
             }
             NotificationChannel::InApp => {
-                // In-app handled separately via SSE/WebSocket
-                Ok(())
+                if let Some(ref sender) = self.in_app {
+                    sender.send(notification).await
+                } else {
+                    Err(NotificationError::NotConfigured("InApp".into()))
+                }
             }
         }
     }
@@ -175,6 +288,45 @@ impl NotificationService {
         results
     }
 
+    /// Render a notification for every configured channel without sending
+    ///
+    /// In-app delivery needs no configuration and is always included.
+    pub fn preview(&self, notification: &Notification) -> Vec<NotificationPreview> {
+        let senders: [Option<&dyn NotificationSender>; 6] = [
+            self.telegram
+                .as_deref()
+                .map(|s| s as &dyn NotificationSender),
+            self.email.as_deref().map(|s| s as &dyn NotificationSender),
+            self.webhook
+                .as_deref()
+                .map(|s| s as &dyn NotificationSender),
+            self.discord
+                .as_deref()
+                .map(|s| s as &dyn NotificationSender),
+            self.slack.as_deref().map(|s| s as &dyn NotificationSender),
+            self.whatsapp
+                .as_deref()
+                .map(|s| s as &dyn NotificationSender),
+        ];
+
+        let mut previews: Vec<NotificationPreview> = senders
+            .into_iter()
+            .flatten()
+            .map(|sender| {
+                let mut notif = notification.clone();
+                notif.channel = sender.channel_type();
+                sender.preview(&notif)
+            })
+            .collect();
+
+        previews.push(match &self.in_app {
+            Some(hub) => hub.preview(notification),
+            None => InAppHub::new().preview(notification),
+        });
+
+        previews
+    }
+
     /// Create notification from alert
     pub fn notification_from_alert(
         alert: &Alert,
@@ -193,6 +345,8 @@ impl NotificationService {
                 alert_id: Some(alert.id().to_string()),
                 action_url: Some(format!("/stocks/{}", alert.symbol())),
                 icon: None,
+                score: None,
+                signal: Some(alert.glossary_key().to_string()),
             },
         }
     }
@@ -234,6 +388,49 @@ mod tests {
         assert_eq!(notification.priority, NotificationPriority::High);
     }
 
+    #[test]
+    fn test_preview_covers_configured_channels() {
+        let service = NotificationService::new()
+            .with_telegram(TelegramNotifier::new(TelegramConfig::default()))
+            .with_webhook(WebhookNotifier::new(WebhookConfig::default()));
+        let alert = Alert::Broker(BrokerAlert::new(
+            "BBCA".to_string(),
+            BrokerAlertType::CoordinatedBuying {
+                broker_count: 3,
+                broker_codes: vec!["BK".into(), "CC".into(), "KZ".into()],
+            },
+            AlertPriority::High,
+            dec!(3),
+            dec!(3),
+        ));
+        let notification = NotificationService::notification_from_alert(
+            &alert,
+            "https://example.com/hook".to_string(),
+            NotificationChannel::InApp,
+        );
+
+        let previews = service.preview(&notification);
+        let channels: Vec<NotificationChannel> =
+            previews.iter().map(|p| p.channel.clone()).collect();
+
+        assert_eq!(
+            channels,
+            vec![
+                NotificationChannel::Telegram,
+                NotificationChannel::Webhook,
+                NotificationChannel::InApp
+            ]
+        );
+        // Telegram has no bot token, so it renders but is not ready to send
+        assert!(!previews[0].configured);
+        assert_eq!(previews[0].format, "markdown");
+        assert!(previews[0].payload["text"]
+            .as_str()
+            .unwrap()
+            .contains("BBCA"));
+        assert_eq!(previews[1].payload["body"]["data"]["symbol"], "BBCA");
+    }
+
     #[test]
     fn test_priority_conversion() {
         assert_eq!(
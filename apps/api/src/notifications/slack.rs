@@ -0,0 +1,174 @@
+//! Slack notification channel
+//!
+//! Posts Block Kit messages to a Slack incoming webhook; the recipient id is
+//! the webhook URL.
+
+use super::{
+    Notification, NotificationError, NotificationPreview, NotificationPriority, NotificationResult,
+    NotificationSender,
+};
+use async_trait::async_trait;
+use jejakcuan_core::alerts::NotificationChannel;
+use serde::{Deserialize, Serialize};
+
+/// Slack webhook configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlackConfig {
+    pub timeout_seconds: u64,
+}
+
+impl Default for SlackConfig {
+    fn default() -> Self {
+        Self {
+            timeout_seconds: 30,
+        }
+    }
+}
+
+/// Slack notification sender
+pub struct SlackNotifier {
+    client: reqwest::Client,
+}
+
+impl SlackNotifier {
+    pub fn new(config: SlackConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(config.timeout_seconds))
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self { client }
+    }
+
+    fn create_payload(&self, notification: &Notification) -> serde_json::Value {
+        let priority_emoji = match notification.priority {
+            NotificationPriority::Critical => ":rotating_light:",
+            NotificationPriority::High => ":red_circle:",
+            NotificationPriority::Medium => ":large_yellow_circle:",
+            NotificationPriority::Low => ":large_green_circle:",
+        };
+
+        let mut blocks = vec![
+            serde_json::json!({
+                "type": "header",
+                "text": { "type": "plain_text", "text": notification.title },
+            }),
+            serde_json::json!({
+                "type": "section",
+                "text": {
+                    "type": "mrkdwn",
+                    "text": format!("{} {}", priority_emoji, notification.body),
+                },
+            }),
+        ];
+
+        let fields: Vec<serde_json::Value> = notification
+            .metadata
+            .summary_fields()
+            .into_iter()
+            .map(|(name, value)| {
+                serde_json::json!({
+                    "type": "mrkdwn",
+                    "text": format!("*{}*\n{}", name, value),
+                })
+            })
+            .collect();
+        if !fields.is_empty() {
+            blocks.push(serde_json::json!({ "type": "section", "fields": fields }));
+        }
+
+        serde_json::json!({
+            // Shown in push notifications, where blocks are not rendered
+            "text": format!("{}: {}", notification.title, notification.body),
+            "blocks": blocks,
+        })
+    }
+}
+
+#[async_trait]
+impl NotificationSender for SlackNotifier {
+    async fn send(&self, notification: &Notification) -> NotificationResult<()> {
+        let webhook_url = &notification.recipient_id;
+        if !webhook_url.starts_with("https://") {
+            return Err(NotificationError::InvalidRecipient(
+                "Slack webhook URL must start with https://".into(),
+            ));
+        }
+
+        let response = self
+            .client
+            .post(webhook_url)
+            .json(&self.create_payload(notification))
+            .send()
+            .await
+            .map_err(|e| NotificationError::NetworkError(e.to_string()))?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else if response.status().as_u16() == 429 {
+            let retry_after = response
+                .headers()
+                .get("Retry-After")
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(30);
+            Err(NotificationError::RateLimited(retry_after))
+        } else {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            Err(NotificationError::SendFailed(error_text))
+        }
+    }
+
+    fn preview(&self, notification: &Notification) -> NotificationPreview {
+        NotificationPreview {
+            channel: NotificationChannel::Slack,
+            configured: self.is_configured(),
+            format: "json".to_string(),
+            payload: self.create_payload(notification),
+        }
+    }
+
+    fn is_configured(&self) -> bool {
+        // Webhook URLs are per-notification
+        true
+    }
+
+    fn channel_type(&self) -> NotificationChannel {
+        NotificationChannel::Slack
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blocks() {
+        let notifier = SlackNotifier::new(SlackConfig::default());
+        let mut notification = Notification {
+            recipient_id: "https://hooks.slack.com/services/T/B/x".to_string(),
+            title: "BBCA Alert".to_string(),
+            body: "RSI oversold".to_string(),
+            priority: NotificationPriority::Medium,
+            channel: NotificationChannel::Slack,
+            alert: None,
+            metadata: super::super::NotificationMetadata {
+                symbol: Some("BBCA".to_string()),
+                ..Default::default()
+            },
+        };
+
+        let payload = notifier.create_payload(&notification);
+        assert_eq!(payload["text"], "BBCA Alert: RSI oversold");
+        assert_eq!(payload["blocks"][0]["text"]["text"], "BBCA Alert");
+        assert_eq!(payload["blocks"][2]["fields"][0]["text"], "*Symbol*\nBBCA");
+
+        // No field section without metadata
+        notification.metadata.symbol = None;
+        let payload = notifier.create_payload(&notification);
+        assert_eq!(payload["blocks"].as_array().unwrap().len(), 2);
+    }
+}
@@ -1,6 +1,8 @@
 //! Telegram notification channel
 
-use super::{Notification, NotificationError, NotificationResult, NotificationSender};
+use super::{
+    Notification, NotificationError, NotificationPreview, NotificationResult, NotificationSender,
+};
 use async_trait::async_trait;
 use jejakcuan_core::alerts::NotificationChannel;
 use serde::{Deserialize, Serialize};
@@ -50,6 +52,14 @@ impl TelegramNotifier {
             priority_emoji, notification.title, notification.body, symbol
         )
     }
+
+    fn create_payload(&self, notification: &Notification) -> serde_json::Value {
+        serde_json::json!({
+            "chat_id": notification.recipient_id,
+            "text": self.format_message(notification),
+            "parse_mode": "Markdown"
+        })
+    }
 }
 
 #[async_trait]
@@ -61,17 +71,12 @@ impl NotificationSender for TelegramNotifier {
             ));
         }
 
-        let message = self.format_message(notification);
         let url = format!(
             "{}/bot{}/sendMessage",
             self.config.api_url, self.config.bot_token
         );
 
-        let payload = serde_json::json!({
-            "chat_id": notification.recipient_id,
-            "text": message,
-            "parse_mode": "Markdown"
-        });
+        let payload = self.create_payload(notification);
 
         let response = self
             .client
@@ -95,6 +100,15 @@ impl NotificationSender for TelegramNotifier {
         }
     }
 
+    fn preview(&self, notification: &Notification) -> NotificationPreview {
+        NotificationPreview {
+            channel: NotificationChannel::Telegram,
+            configured: self.is_configured(),
+            format: "markdown".to_string(),
+            payload: self.create_payload(notification),
+        }
+    }
+
     fn is_configured(&self) -> bool {
         !self.config.bot_token.is_empty()
     }
@@ -1,6 +1,8 @@
 //! Webhook notification channel
 
-use super::{Notification, NotificationError, NotificationResult, NotificationSender};
+use super::{
+    Notification, NotificationError, NotificationPreview, NotificationResult, NotificationSender,
+};
 use async_trait::async_trait;
 use chrono::Utc;
 use jejakcuan_core::alerts::NotificationChannel;
@@ -146,6 +148,23 @@ impl NotificationSender for WebhookNotifier {
         Err(last_error.unwrap_or_else(|| NotificationError::SendFailed("Unknown error".into())))
     }
 
+    /// The payload as it would be posted; the signature is left out, since
+    /// callers choose the body and must not get it signed with our secret
+    fn preview(&self, notification: &Notification) -> NotificationPreview {
+        let payload = self.create_payload(notification);
+
+        NotificationPreview {
+            channel: NotificationChannel::Webhook,
+            configured: self.is_configured(),
+            format: "json".to_string(),
+            payload: serde_json::json!({
+                "url": notification.recipient_id,
+                "signed": self.config.secret_header.is_some(),
+                "body": payload,
+            }),
+        }
+    }
+
     fn is_configured(&self) -> bool {
         // Webhook doesn't require static configuration - URLs are per-notification
         true
@@ -201,4 +220,26 @@ mod tests {
         let signature = notifier.compute_signature("test payload");
         assert!(signature.is_none());
     }
+
+    #[test]
+    fn test_preview_is_not_signed() {
+        let notifier = WebhookNotifier::new(WebhookConfig {
+            secret_header: Some("my_secret".to_string()),
+            ..Default::default()
+        });
+        let notification = Notification {
+            recipient_id: "https://example.com/webhook".to_string(),
+            title: "Any body the caller likes".to_string(),
+            body: "Test body".to_string(),
+            priority: super::super::NotificationPriority::High,
+            channel: NotificationChannel::Webhook,
+            alert: None,
+            metadata: Default::default(),
+        };
+
+        let preview = notifier.preview(&notification);
+        assert!(preview.payload.get("signature").is_none());
+        assert_eq!(preview.payload["signed"], true);
+        assert!(!preview.payload.to_string().contains("sha256="));
+    }
 }
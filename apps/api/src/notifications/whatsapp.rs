@@ -0,0 +1,216 @@
+//! WhatsApp notification channel
+//!
+//! Sends approved template messages through the WhatsApp Cloud API; business
+//! accounts may only start conversations with templates. The recipient id is
+//! the phone number in international format, e.g. `6281234567890`.
+
+use super::{
+    Notification, NotificationError, NotificationPreview, NotificationResult, NotificationSender,
+};
+use async_trait::async_trait;
+use jejakcuan_core::alerts::NotificationChannel;
+use serde::{Deserialize, Serialize};
+
+/// Graph API error codes meaning the sender is being throttled
+const THROTTLING_ERROR_CODES: [i64; 5] = [4, 80007, 130429, 131048, 131056];
+
+/// Retry delay when the API does not say how long to back off
+const DEFAULT_RETRY_AFTER_SECS: u64 = 60;
+
+/// WhatsApp Cloud API configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WhatsAppConfig {
+    pub phone_number_id: String,
+    pub access_token: String,
+    pub api_url: String,
+    pub api_version: String,
+    /// Approved template with body parameters {{1}} symbol, {{2}} title, {{3}} message
+    pub template_name: String,
+    pub language_code: String,
+}
+
+impl Default for WhatsAppConfig {
+    fn default() -> Self {
+        Self {
+            phone_number_id: String::new(),
+            access_token: String::new(),
+            api_url: "https://graph.facebook.com".to_string(),
+            api_version: "v19.0".to_string(),
+            template_name: "jejakcuan_alert".to_string(),
+            language_code: "id".to_string(),
+        }
+    }
+}
+
+/// WhatsApp notification sender
+pub struct WhatsAppNotifier {
+    config: WhatsAppConfig,
+    client: reqwest::Client,
+}
+
+impl WhatsAppNotifier {
+    pub fn new(config: WhatsAppConfig) -> Self {
+        Self {
+            config,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Phone number as digits only, without the leading `+`
+    fn normalize_recipient(recipient: &str) -> Option<String> {
+        let digits: String = recipient
+            .trim()
+            .trim_start_matches('+')
+            .chars()
+            .filter(|c| !matches!(c, ' ' | '-'))
+            .collect();
+        (digits.len() >= 8 && digits.chars().all(|c| c.is_ascii_digit())).then_some(digits)
+    }
+
+    fn create_payload(&self, notification: &Notification, to: &str) -> serde_json::Value {
+        let symbol = notification.metadata.symbol.as_deref().unwrap_or("-");
+        let text = |value: &str| serde_json::json!({ "type": "text", "text": value });
+
+        serde_json::json!({
+            "messaging_product": "whatsapp",
+            "to": to,
+            "type": "template",
+            "template": {
+                "name": self.config.template_name,
+                "language": { "code": self.config.language_code },
+                "components": [{
+                    "type": "body",
+                    "parameters": [
+                        text(symbol),
+                        text(&notification.title),
+                        text(&notification.body),
+                    ],
+                }],
+            },
+        })
+    }
+
+    /// Map a failed response body to an error, detecting throttling
+    fn error_from_body(body: &str) -> NotificationError {
+        let code = serde_json::from_str::<serde_json::Value>(body)
+            .ok()
+            .and_then(|v| v["error"]["code"].as_i64());
+        match code {
+            Some(code) if THROTTLING_ERROR_CODES.contains(&code) => {
+                NotificationError::RateLimited(DEFAULT_RETRY_AFTER_SECS)
+            }
+            _ => NotificationError::SendFailed(body.to_string()),
+        }
+    }
+}
+
+#[async_trait]
+impl NotificationSender for WhatsAppNotifier {
+    async fn send(&self, notification: &Notification) -> NotificationResult<()> {
+        if !self.is_configured() {
+            return Err(NotificationError::NotConfigured(
+                "WhatsApp phone number id or access token missing".into(),
+            ));
+        }
+        let to = Self::normalize_recipient(&notification.recipient_id).ok_or_else(|| {
+            NotificationError::InvalidRecipient(
+                "WhatsApp recipient must be a phone number in international format".into(),
+            )
+        })?;
+
+        let url = format!(
+            "{}/{}/{}/messages",
+            self.config.api_url, self.config.api_version, self.config.phone_number_id
+        );
+
+        let response = self
+            .client
+            .post(&url)
+            .bearer_auth(&self.config.access_token)
+            .json(&self.create_payload(notification, &to))
+            .send()
+            .await
+            .map_err(|e| NotificationError::NetworkError(e.to_string()))?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else if response.status().as_u16() == 429 {
+            Err(NotificationError::RateLimited(DEFAULT_RETRY_AFTER_SECS))
+        } else {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            Err(Self::error_from_body(&error_text))
+        }
+    }
+
+    fn preview(&self, notification: &Notification) -> NotificationPreview {
+        let to = Self::normalize_recipient(&notification.recipient_id)
+            .unwrap_or_else(|| notification.recipient_id.clone());
+        NotificationPreview {
+            channel: NotificationChannel::WhatsApp,
+            configured: self.is_configured(),
+            format: "json".to_string(),
+            payload: self.create_payload(notification, &to),
+        }
+    }
+
+    fn is_configured(&self) -> bool {
+        !self.config.phone_number_id.is_empty() && !self.config.access_token.is_empty()
+    }
+
+    fn channel_type(&self) -> NotificationChannel {
+        NotificationChannel::WhatsApp
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_payload() {
+        let notifier = WhatsAppNotifier::new(WhatsAppConfig::default());
+        let notification = Notification {
+            recipient_id: "+62 812-3456-7890".to_string(),
+            title: "BBCA Alert".to_string(),
+            body: "RSI oversold".to_string(),
+            priority: super::super::NotificationPriority::Medium,
+            channel: NotificationChannel::WhatsApp,
+            alert: None,
+            metadata: super::super::NotificationMetadata {
+                symbol: Some("BBCA".to_string()),
+                ..Default::default()
+            },
+        };
+
+        let preview = notifier.preview(&notification);
+        assert!(!preview.configured);
+        assert_eq!(preview.payload["to"], "6281234567890");
+        assert_eq!(preview.payload["template"]["name"], "jejakcuan_alert");
+        let params = &preview.payload["template"]["components"][0]["parameters"];
+        assert_eq!(params[0]["text"], "BBCA");
+        assert_eq!(params[2]["text"], "RSI oversold");
+    }
+
+    #[test]
+    fn test_recipient_and_errors() {
+        assert_eq!(WhatsAppNotifier::normalize_recipient("628123"), None);
+        assert_eq!(
+            WhatsAppNotifier::normalize_recipient("user@example.com"),
+            None
+        );
+
+        let throttled = r#"{"error":{"code":130429,"message":"Rate limit hit"}}"#;
+        assert!(matches!(
+            WhatsAppNotifier::error_from_body(throttled),
+            NotificationError::RateLimited(_)
+        ));
+        let invalid = r#"{"error":{"code":132001,"message":"Template name does not exist"}}"#;
+        assert!(matches!(
+            WhatsAppNotifier::error_from_body(invalid),
+            NotificationError::SendFailed(_)
+        ));
+    }
+}
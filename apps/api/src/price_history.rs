@@ -0,0 +1,131 @@
+//! Daily price history through the provider failover chain
+//!
+//! Replaces the Python `price` scraper for per-stock refreshes: history comes
+//! from whichever configured provider answers first, so a Yahoo outage falls
+//! through to TwelveData or Sectors.app instead of failing the refresh.
+
+use crate::config::Config;
+use crate::price_store::PriceStore;
+use crate::routes::jobs::Job;
+use crate::AppState;
+use chrono::{Duration, NaiveTime, Utc};
+use jejakcuan_data_sources::{
+    ApiUsageTracker, FailoverProvider, PriceBar, PriceDataProvider, SectorsClient, SymbolProvider,
+    TwelveDataClient, YahooFinanceClient,
+};
+use jejakcuan_db::repositories::{self, InsertPrice};
+use std::sync::Arc;
+
+/// Calendar days of history a stock refresh fetches
+pub const REFRESH_HISTORY_DAYS: i64 = 60;
+
+/// Build the failover chain in the configured order
+///
+/// TwelveData is left out without a shared `twelvedata` client, and
+/// Sectors.app when no API key is configured or stored.
+pub fn build_price_provider(
+    config: &Config,
+    usage: Arc<ApiUsageTracker>,
+    twelvedata: Option<&TwelveDataClient>,
+) -> FailoverProvider {
+    let mut providers: Vec<Arc<dyn PriceDataProvider>> = Vec::new();
+    for provider in &config.price_providers {
+        match provider {
+            SymbolProvider::Yahoo => providers.push(Arc::new(YahooFinanceClient::new())),
+            SymbolProvider::TwelveData => match twelvedata {
+                Some(client) => providers.push(Arc::new(client.clone())),
+                None => tracing::info!("TwelveData price provider disabled: no API key"),
+            },
+            SymbolProvider::Sectors => {
+                match SectorsClient::new(config.sectors_api_key.clone().unwrap_or_default()) {
+                    Ok(client) => {
+                        providers.push(Arc::new(client.with_usage_tracker(usage.clone())))
+                    }
+                    Err(e) => tracing::info!("Sectors price provider disabled: {}", e),
+                }
+            }
+            SymbolProvider::Idx => {}
+        }
+    }
+
+    let chain = FailoverProvider::new(providers);
+    tracing::info!("Price providers: {}", chain.names().join(" > "));
+    chain
+}
+
+/// Start a background job refreshing one stock's recent daily bars
+pub async fn spawn_refresh(state: &Arc<AppState>, symbol: &str) -> Job {
+    let task_state = state.clone();
+    let provider = state.price_provider.clone();
+    let task_symbol = symbol.to_string();
+    state
+        .job_manager
+        .spawn_task(
+            format!("stock-refresh-price-{}", symbol),
+            format!("{} Price Data", symbol),
+            format!(
+                "fetch {} days of daily prices for {} via {}",
+                REFRESH_HISTORY_DAYS,
+                symbol,
+                provider.names().join(" > ")
+            ),
+            async move {
+                refresh_prices(
+                    &task_state.db,
+                    &task_state.price_store,
+                    provider.as_ref(),
+                    &task_symbol,
+                )
+                .await
+            },
+        )
+        .await
+}
+
+/// Fetch recent history for a symbol and upsert it, replacing existing days
+async fn refresh_prices(
+    pool: &sqlx::PgPool,
+    store: &PriceStore,
+    provider: &dyn PriceDataProvider,
+    symbol: &str,
+) -> Result<String, String> {
+    let to = Utc::now().date_naive();
+    let from = to - Duration::days(REFRESH_HISTORY_DAYS);
+    let bars = provider
+        .get_history(symbol, from, to)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let rows: Vec<InsertPrice> = bars
+        .iter()
+        .filter(|bar| bar.validate().is_ok())
+        .map(insert_row)
+        .collect();
+    if rows.is_empty() {
+        return Err(format!("No valid bars for {} since {}", symbol, from));
+    }
+
+    let upsert = repositories::upsert_prices(pool, &rows, true)
+        .await
+        .map_err(|e| e.to_string())?;
+    store.invalidate(symbol);
+    Ok(format!(
+        "{}: {} bars ({} new, {} replaced)",
+        symbol,
+        rows.len(),
+        upsert.inserted,
+        upsert.replaced
+    ))
+}
+
+fn insert_row(bar: &PriceBar) -> InsertPrice<'_> {
+    InsertPrice {
+        time: bar.date.and_time(NaiveTime::MIN).and_utc(),
+        symbol: &bar.symbol,
+        open: bar.open,
+        high: bar.high,
+        low: bar.low,
+        close: bar.close,
+        volume: bar.volume,
+    }
+}
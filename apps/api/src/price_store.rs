@@ -0,0 +1,456 @@
+//! Columnar in-memory daily prices of hot symbols
+//!
+//! Frequently analyzed symbols keep their last two years of daily bars in
+//! process as aligned `f64` columns, which the indicators read directly:
+//! no Postgres round trip and no `Decimal` per bar. A symbol is loaded on
+//! its first read, and beyond [`MAX_SYMBOLS`] the least recently read one is
+//! dropped.
+//!
+//! A trigger announces every write to `stock_prices` on [`CHANNEL`], from
+//! this process or any other (the Python scrapers write prices directly);
+//! the listener drops the symbol so its next read reloads it, and drops its
+//! cached full analysis from Redis. While the listener is not connected
+//! nothing is kept, since writes would go unseen.
+
+use crate::AppState;
+use chrono::{DateTime, Duration, Utc};
+use jejakcuan_db::repositories::prices::FloatPriceRow;
+use jejakcuan_db::{repositories, StockPriceRow};
+use rust_decimal::prelude::ToPrimitive;
+use serde::Serialize;
+use sqlx::postgres::PgListener;
+use sqlx::PgPool;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio::sync::mpsc;
+
+/// Calendar days of bars held per symbol
+pub const STORE_DAYS: i64 = 730;
+
+/// Symbols held at once
+pub const MAX_SYMBOLS: usize = 500;
+
+/// Channel `stock_prices` writes are announced on, with the symbol as payload
+pub const CHANNEL: &str = "stock_prices_changed";
+
+/// Wait before reconnecting a dropped listener
+const RECONNECT_DELAY: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Daily bars of one symbol as aligned columns, oldest first
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PriceColumns {
+    pub time: Vec<DateTime<Utc>>,
+    pub open: Vec<f64>,
+    pub high: Vec<f64>,
+    pub low: Vec<f64>,
+    pub close: Vec<f64>,
+    pub volume: Vec<f64>,
+}
+
+impl PriceColumns {
+    /// Columns of `rows`, which must be ordered by time
+    pub fn from_float_rows(rows: &[FloatPriceRow]) -> Self {
+        Self {
+            time: rows.iter().map(|r| r.time).collect(),
+            open: rows.iter().map(|r| r.open).collect(),
+            high: rows.iter().map(|r| r.high).collect(),
+            low: rows.iter().map(|r| r.low).collect(),
+            close: rows.iter().map(|r| r.close).collect(),
+            volume: rows.iter().map(|r| r.volume as f64).collect(),
+        }
+    }
+
+    /// Columns of decimal `rows`, e.g. after adjusting them; ordered by time
+    pub fn from_rows(rows: &[StockPriceRow]) -> Self {
+        let f = |d: rust_decimal::Decimal| d.to_f64().unwrap_or(0.0);
+        Self {
+            time: rows.iter().map(|r| r.time).collect(),
+            open: rows.iter().map(|r| f(r.open)).collect(),
+            high: rows.iter().map(|r| f(r.high)).collect(),
+            low: rows.iter().map(|r| f(r.low)).collect(),
+            close: rows.iter().map(|r| f(r.close)).collect(),
+            volume: rows.iter().map(|r| r.volume as f64).collect(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.time.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.time.is_empty()
+    }
+}
+
+/// Bars of a symbol from some time on, sharing the stored columns
+#[derive(Debug, Clone)]
+pub struct PriceWindow {
+    columns: Arc<PriceColumns>,
+    start: usize,
+}
+
+impl PriceWindow {
+    /// Every bar of `columns`
+    pub fn new(columns: PriceColumns) -> Self {
+        Self {
+            columns: Arc::new(columns),
+            start: 0,
+        }
+    }
+
+    fn since(columns: Arc<PriceColumns>, from: DateTime<Utc>) -> Self {
+        let start = columns.time.partition_point(|t| *t < from);
+        Self { columns, start }
+    }
+
+    pub fn len(&self) -> usize {
+        self.columns.len() - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn time(&self) -> &[DateTime<Utc>] {
+        &self.columns.time[self.start..]
+    }
+
+    pub fn high(&self) -> &[f64] {
+        &self.columns.high[self.start..]
+    }
+
+    pub fn low(&self) -> &[f64] {
+        &self.columns.low[self.start..]
+    }
+
+    pub fn close(&self) -> &[f64] {
+        &self.columns.close[self.start..]
+    }
+
+    pub fn volume(&self) -> &[f64] {
+        &self.columns.volume[self.start..]
+    }
+}
+
+#[derive(Debug)]
+struct Resident {
+    columns: Arc<PriceColumns>,
+    /// Earliest time the columns are complete from
+    covers_from: DateTime<Utc>,
+    last_read: Instant,
+}
+
+#[derive(Debug, Default)]
+struct Residents {
+    symbols: HashMap<String, Resident>,
+    /// Invalidations per symbol, so a load that overlapped one is not kept
+    generations: HashMap<String, u64>,
+    /// Bumped whenever every symbol is dropped
+    epoch: u64,
+    /// Whether writes are being heard; nothing is kept otherwise
+    listening: bool,
+}
+
+/// State of a symbol when its load started
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Ticket {
+    generation: u64,
+    epoch: u64,
+}
+
+/// Reads served from memory since startup
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct PriceStoreStats {
+    /// Whether price writes are being heard, without which nothing is kept
+    pub listening: bool,
+    pub symbols: usize,
+    pub bars: usize,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+/// Per-symbol price columns read by analysis
+#[derive(Debug, Default)]
+pub struct PriceStore {
+    residents: Mutex<Residents>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl PriceStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bars of `symbol` since `from`, loading the symbol if it is not held
+    ///
+    /// `None` when `from` is older than the store holds; read Postgres then.
+    pub async fn window(
+        &self,
+        pool: &PgPool,
+        symbol: &str,
+        from: DateTime<Utc>,
+    ) -> Result<Option<PriceWindow>, sqlx::Error> {
+        let now = Utc::now();
+        let covers_from = now - Duration::days(STORE_DAYS);
+        if from < covers_from {
+            return Ok(None);
+        }
+        if let Some(window) = self.cached(symbol, from) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(Some(window));
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+
+        let ticket = self.ticket(symbol);
+        let rows =
+            repositories::prices::get_float_price_history(pool, symbol, covers_from, now).await?;
+        let columns = Arc::new(PriceColumns::from_float_rows(&rows));
+        if let Some(ticket) = ticket {
+            self.insert(symbol, ticket, columns.clone(), covers_from);
+        }
+        Ok(Some(PriceWindow::since(columns, from)))
+    }
+
+    fn cached(&self, symbol: &str, from: DateTime<Utc>) -> Option<PriceWindow> {
+        let mut residents = self.residents.lock().unwrap_or_else(|e| e.into_inner());
+        let resident = residents.symbols.get_mut(symbol)?;
+        if from < resident.covers_from {
+            return None;
+        }
+        resident.last_read = Instant::now();
+        Some(PriceWindow::since(resident.columns.clone(), from))
+    }
+
+    /// Ticket for loading `symbol`; `None` while writes are not heard
+    fn ticket(&self, symbol: &str) -> Option<Ticket> {
+        let residents = self.residents.lock().unwrap_or_else(|e| e.into_inner());
+        residents.listening.then(|| Ticket {
+            generation: residents.generations.get(symbol).copied().unwrap_or(0),
+            epoch: residents.epoch,
+        })
+    }
+
+    /// Keep `columns` loaded under `ticket`, unless `symbol` changed since
+    fn insert(
+        &self,
+        symbol: &str,
+        ticket: Ticket,
+        columns: Arc<PriceColumns>,
+        covers_from: DateTime<Utc>,
+    ) {
+        let mut residents = self.residents.lock().unwrap_or_else(|e| e.into_inner());
+        let generation = residents.generations.get(symbol).copied().unwrap_or(0);
+        if !residents.listening
+            || ticket.epoch != residents.epoch
+            || ticket.generation != generation
+        {
+            return;
+        }
+        residents.symbols.insert(
+            symbol.to_string(),
+            Resident {
+                columns,
+                covers_from,
+                last_read: Instant::now(),
+            },
+        );
+        if residents.symbols.len() > MAX_SYMBOLS {
+            let coldest = residents
+                .symbols
+                .iter()
+                .min_by_key(|(_, r)| r.last_read)
+                .map(|(s, _)| s.clone());
+            if let Some(coldest) = coldest {
+                residents.symbols.remove(&coldest);
+            }
+        }
+    }
+
+    /// Drop `symbol` after its prices were written
+    pub fn invalidate(&self, symbol: &str) {
+        let mut residents = self.residents.lock().unwrap_or_else(|e| e.into_inner());
+        residents.symbols.remove(symbol);
+        *residents.generations.entry(symbol.to_string()).or_default() += 1;
+    }
+
+    /// Start or stop keeping symbols as writes are heard or not, dropping
+    /// every symbol either way
+    fn set_listening(&self, listening: bool) {
+        let mut residents = self.residents.lock().unwrap_or_else(|e| e.into_inner());
+        residents.listening = listening;
+        residents.symbols.clear();
+        residents.epoch += 1;
+    }
+
+    pub fn stats(&self) -> PriceStoreStats {
+        let residents = self.residents.lock().unwrap_or_else(|e| e.into_inner());
+        PriceStoreStats {
+            listening: residents.listening,
+            symbols: residents.symbols.len(),
+            bars: residents.symbols.values().map(|r| r.columns.len()).sum(),
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Listen for price writes and drop the symbols written, reconnecting when
+/// the connection is lost
+pub fn spawn_price_listener(state: Arc<AppState>) {
+    let (written_tx, written_rx) = mpsc::unbounded_channel();
+    tokio::spawn(invalidate_analyses(state.clone(), written_rx));
+    tokio::spawn(async move {
+        loop {
+            if let Err(e) = listen(&state, &written_tx).await {
+                tracing::warn!("Price store listener failed: {}", e);
+            }
+            // Writes during the outage went unheard
+            state.price_store.set_listening(false);
+            tokio::time::sleep(RECONNECT_DELAY).await;
+        }
+    });
+}
+
+async fn listen(
+    state: &AppState,
+    written: &mpsc::UnboundedSender<String>,
+) -> Result<(), sqlx::Error> {
+    let mut listener = PgListener::connect_with(&state.db).await?;
+    listener.listen(CHANNEL).await?;
+    state.price_store.set_listening(true);
+    // `None` when the connection drops
+    while let Some(notification) = listener.try_recv().await? {
+        state.price_store.invalidate(notification.payload());
+        let _ = written.send(notification.payload().to_string());
+    }
+    Ok(())
+}
+
+/// Drop the cached full analysis of written symbols
+///
+/// A bulk import announces many writes at once; each symbol queued by then
+/// is invalidated once.
+async fn invalidate_analyses(state: Arc<AppState>, mut written: mpsc::UnboundedReceiver<String>) {
+    while let Some(symbol) = written.recv().await {
+        let mut symbols = HashSet::from([symbol]);
+        while let Ok(symbol) = written.try_recv() {
+            symbols.insert(symbol);
+        }
+        for symbol in symbols {
+            state.analysis_cache.invalidate(&symbol).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn at(day: u32) -> DateTime<Utc> {
+        NaiveDate::from_ymd_opt(2025, 6, day)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+            .and_utc()
+    }
+
+    fn columns(days: std::ops::RangeInclusive<u32>) -> Arc<PriceColumns> {
+        let rows: Vec<FloatPriceRow> = days
+            .map(|d| FloatPriceRow {
+                time: at(d),
+                open: 9000.0 + d as f64,
+                high: 9010.0 + d as f64,
+                low: 8990.0 + d as f64,
+                close: 9000.0 + d as f64,
+                volume: 1_000,
+            })
+            .collect();
+        Arc::new(PriceColumns::from_float_rows(&rows))
+    }
+
+    fn listening_store() -> PriceStore {
+        let store = PriceStore::new();
+        store.set_listening(true);
+        store
+    }
+
+    #[test]
+    fn test_window_reads() {
+        let window = PriceWindow::since(columns(2..=6), at(4));
+        assert_eq!(window.len(), 3);
+        assert_eq!(window.time()[0], at(4));
+        assert_eq!(window.close(), &[9004.0, 9005.0, 9006.0]);
+        assert_eq!(window.low()[2], 8996.0);
+        assert_eq!(window.high()[0], 9014.0);
+        assert_eq!(window.volume(), &[1_000.0; 3]);
+    }
+
+    #[test]
+    fn test_invalidation_drops_and_blocks_stale_loads() {
+        let store = listening_store();
+        let ticket = store.ticket("BBCA").unwrap();
+        store.insert("BBCA", ticket, columns(2..=6), at(1));
+        assert_eq!(store.cached("BBCA", at(3)).unwrap().len(), 4);
+        // Older than the columns cover
+        assert!(store.cached("BBCA", at(1) - Duration::days(1)).is_none());
+
+        store.invalidate("BBCA");
+        assert!(store.cached("BBCA", at(3)).is_none());
+
+        // A write landing while a load is in flight: the load is not kept
+        let ticket = store.ticket("BBCA").unwrap();
+        store.invalidate("BBCA");
+        store.insert("BBCA", ticket, columns(2..=6), at(1));
+        assert!(store.cached("BBCA", at(3)).is_none());
+        // Other symbols' writes do not matter
+        let ticket = store.ticket("BBCA").unwrap();
+        store.invalidate("BBRI");
+        store.insert("BBCA", ticket, columns(2..=7), at(1));
+        assert_eq!(store.cached("BBCA", at(3)).unwrap().len(), 5);
+    }
+
+    #[test]
+    fn test_nothing_kept_while_not_listening() {
+        let store = PriceStore::new();
+        assert!(store.ticket("BBCA").is_none());
+
+        let store = listening_store();
+        let ticket = store.ticket("BBCA").unwrap();
+        store.insert("BBCA", ticket, columns(2..=6), at(1));
+        // The connection drops: writes may be missed from now on
+        let in_flight = store.ticket("BBRI").unwrap();
+        store.set_listening(false);
+        assert!(store.cached("BBCA", at(3)).is_none());
+        // Reconnected, but the load started before the outage
+        store.set_listening(true);
+        store.insert("BBRI", in_flight, columns(2..=6), at(1));
+        assert!(store.cached("BBRI", at(3)).is_none());
+        assert_eq!(store.stats().symbols, 0);
+        assert!(store.stats().listening);
+    }
+
+    #[test]
+    fn test_evicts_least_recently_read() {
+        let store = listening_store();
+        for i in 0..MAX_SYMBOLS {
+            let symbol = format!("S{i:03}");
+            let ticket = store.ticket(&symbol).unwrap();
+            store.insert(&symbol, ticket, columns(2..=3), at(1));
+        }
+        // Reading the first keeps it warm
+        assert!(store.cached("S000", at(2)).is_some());
+        let ticket = store.ticket("NEW").unwrap();
+        store.insert("NEW", ticket, columns(2..=3), at(1));
+
+        let stats = store.stats();
+        assert_eq!(stats.symbols, MAX_SYMBOLS);
+        assert_eq!(stats.bars, MAX_SYMBOLS * 2);
+        assert!(store.cached("S000", at(2)).is_some());
+        assert!(store.cached("S001", at(2)).is_none());
+        assert!(store.cached("NEW", at(2)).is_some());
+    }
+}
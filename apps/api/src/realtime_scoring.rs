@@ -0,0 +1,411 @@
+//! Real-time scoring from the TwelveData WebSocket stream
+//!
+//! The price stream runs whenever `REALTIME_SCORING` or the score ticker is
+//! enabled. It subscribes the watchlist, the symbols the score ticker
+//! rescores and those whose footprint was requested recently, and feeds the
+//! tick store and the intraday bars (see
+//! [`crate::intraday_bars`]). With `REALTIME_SCORING`, each closed 5-minute
+//! bar also extends the symbol's session bar and the technical score is
+//! recomputed on it, with the changes pushed to the event stream as
+//! `ScoreUpdate` messages.
+//!
+//! Per symbol, the daily history and the last stored score are loaded once
+//! per session and kept with the session bar, so a bar close costs one
+//! score computation and no queries. As with the score ticker, only the
+//! price-driven technical components move and nothing is written; the
+//! end-of-day recompute remains the score of record.
+
+use crate::intraday_bars::spawn_bar_writer;
+use crate::routes::streaming::StreamMessage;
+use crate::routes::symbols::load_symbol_mapper;
+use crate::score_ticker::{rescore, ticker_symbols, IntradayScore, MIN_PUSHED_CHANGE, PRICE_DAYS};
+use crate::AppState;
+use chrono::{Duration, NaiveDate, Utc};
+use jejakcuan_data_sources::twelvedata::{
+    BarAggregator, Interval, IntradayBar, TwelveDataWebSocket, WebSocketEvent,
+    DEFAULT_BAR_INTERVALS,
+};
+use jejakcuan_data_sources::SymbolProvider;
+use jejakcuan_db::{repositories, StockPriceRow, StockScoreRow};
+use jejakcuan_technical::{session_date, OhlcvBar};
+use rust_decimal::prelude::ToPrimitive;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+/// Bars whose close triggers a rescore
+pub const SCORING_INTERVAL: Interval = Interval::Min5;
+
+/// How often the subscriptions follow the watchlist and ticker symbols
+const SUBSCRIPTION_REFRESH: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Rolling scoring state of one symbol for one session
+#[derive(Debug, Clone)]
+pub struct SymbolScoring {
+    pub session: NaiveDate,
+    pub stored: StockScoreRow,
+    /// Daily bars before the session
+    pub history: Vec<OhlcvBar>,
+    /// The session so far, merged from closed bars
+    pub today: Option<OhlcvBar>,
+    /// Composite score last pushed
+    pub pushed: Option<f64>,
+}
+
+impl SymbolScoring {
+    /// State for `session` from the stored score and daily prices
+    ///
+    /// A daily bar already imported for the session is left out; the
+    /// session bar is built from the stream.
+    pub fn new(session: NaiveDate, stored: StockScoreRow, prices: &[StockPriceRow]) -> Self {
+        let history = prices
+            .iter()
+            .filter(|p| session_date(p.time) < session)
+            .map(|p| OhlcvBar {
+                open: p.open,
+                high: p.high,
+                low: p.low,
+                close: p.close,
+                volume: p.volume,
+            })
+            .collect();
+        Self {
+            session,
+            stored,
+            history,
+            today: None,
+            pushed: None,
+        }
+    }
+
+    /// Merge a closed bar into the session and rescore
+    ///
+    /// Returns the score when it moved enough since the last push to be sent.
+    pub fn apply(&mut self, bar: &IntradayBar) -> Option<IntradayScore> {
+        let today = self.today.get_or_insert(OhlcvBar {
+            open: bar.open,
+            high: bar.high,
+            low: bar.low,
+            close: bar.close,
+            volume: 0,
+        });
+        today.high = today.high.max(bar.high);
+        today.low = today.low.min(bar.low);
+        today.close = bar.close;
+        today.volume += bar.volume;
+
+        let mut bars = self.history.clone();
+        bars.push(today.clone());
+        let score = rescore(&self.stored, &bars)?;
+
+        let unchanged = self
+            .pushed
+            .is_some_and(|last| (last - score.composite).abs() < MIN_PUSHED_CHANGE);
+        if unchanged {
+            return None;
+        }
+        self.pushed = Some(score.composite);
+        Some(score)
+    }
+}
+
+/// Scoring state of every streamed symbol, by session
+///
+/// `None` marks symbols without a stored score, so they are not looked up
+/// again on every bar of the session.
+#[derive(Debug, Default)]
+pub struct RealtimeScorer {
+    symbols: HashMap<String, (NaiveDate, Option<SymbolScoring>)>,
+}
+
+impl RealtimeScorer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    async fn load(
+        state: &AppState,
+        symbol: &str,
+        session: NaiveDate,
+    ) -> Result<Option<SymbolScoring>, sqlx::Error> {
+        let Some(stored) = repositories::scores::get_stock_score(&state.db, symbol).await? else {
+            return Ok(None);
+        };
+        let now = Utc::now();
+        let prices = repositories::prices::get_price_history(
+            &state.db,
+            symbol,
+            now - Duration::days(PRICE_DAYS),
+            now,
+        )
+        .await?;
+        Ok(Some(SymbolScoring::new(session, stored, &prices)))
+    }
+
+    /// Rescore the symbol of a closed bar and push the score if it moved
+    ///
+    /// Returns whether an update was pushed.
+    pub async fn on_bar_close(
+        &mut self,
+        state: &AppState,
+        bar: &IntradayBar,
+    ) -> Result<bool, sqlx::Error> {
+        if bar.interval != SCORING_INTERVAL {
+            return Ok(false);
+        }
+        let session = session_date(bar.start);
+        let current = self
+            .symbols
+            .get(&bar.symbol)
+            .is_some_and(|(loaded_for, _)| *loaded_for == session);
+        if !current {
+            let loaded = Self::load(state, &bar.symbol, session).await?;
+            self.symbols.insert(bar.symbol.clone(), (session, loaded));
+        }
+
+        let Some((_, Some(scoring))) = self.symbols.get_mut(&bar.symbol) else {
+            return Ok(false);
+        };
+        let Some(score) = scoring.apply(bar) else {
+            return Ok(false);
+        };
+        // Sending only fails when no client is listening
+        let _ = state.streaming.broadcast(StreamMessage::ScoreUpdate {
+            symbol: bar.symbol.clone(),
+            technical_score: score.technical,
+            fundamental_score: scoring.stored.fundamental_score.to_f64().unwrap_or(50.0),
+            composite_score: score.composite,
+            composite_change: score.composite_change,
+            timestamp: bar.end().timestamp(),
+        });
+        Ok(true)
+    }
+}
+
+/// Connect to the stream and run bar aggregation, and scoring when `scoring`
+///
+/// Does nothing when no TwelveData API key is configured.
+pub fn spawn_price_stream(state: Arc<AppState>, scoring: bool) {
+    tokio::spawn(async move {
+        let Some(api_key) = crate::secrets::resolve_secret(&state, "TWELVEDATA_API_KEY").await
+        else {
+            tracing::warn!("Price stream disabled, TWELVEDATA_API_KEY is not set");
+            return;
+        };
+
+        let queue = state.tick_queue.clone();
+        let mut socket = TwelveDataWebSocket::new(api_key).with_tick_queue(queue.clone());
+        if let Err(e) = socket.connect().await {
+            tracing::warn!("Price stream disabled, connection failed: {}", e);
+            return;
+        }
+        let socket = Arc::new(socket);
+        tokio::spawn(log_socket_events(socket.clone()));
+        tokio::spawn(follow_subscriptions(state.clone(), socket));
+
+        let aggregator = BarAggregator::new(&DEFAULT_BAR_INTERVALS);
+        if !scoring {
+            spawn_bar_writer(state, queue, aggregator, None);
+            return;
+        }
+        let (closed_tx, mut closed_rx) = mpsc::channel::<Vec<IntradayBar>>(64);
+        spawn_bar_writer(state.clone(), queue, aggregator, Some(closed_tx));
+
+        let mut scorer = RealtimeScorer::new();
+        while let Some(bars) = closed_rx.recv().await {
+            for bar in &bars {
+                if let Err(e) = scorer.on_bar_close(&state, bar).await {
+                    tracing::warn!("Real-time scoring of {} failed: {}", bar.symbol, e);
+                }
+            }
+        }
+    });
+}
+
+/// Symbols the stream should carry: the watchlist, recent footprint
+/// requests, and the score ticker's symbols while the ticker runs
+async fn streamed_symbols(state: &AppState) -> Result<HashSet<String>, sqlx::Error> {
+    let mut symbols: HashSet<String> = repositories::watchlist::get_watchlist(&state.db)
+        .await?
+        .into_iter()
+        .map(|row| row.symbol)
+        .collect();
+    symbols.extend(state.ticks.requested_symbols(Utc::now()));
+    if state.config.score_ticker_interval.is_some() {
+        symbols.extend(ticker_symbols(state, Utc::now()).await?);
+    }
+    Ok(symbols)
+}
+
+/// Keep the subscriptions equal to the streamed symbols
+async fn follow_subscriptions(state: Arc<AppState>, socket: Arc<TwelveDataWebSocket>) {
+    let mut interval = tokio::time::interval(SUBSCRIPTION_REFRESH);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    loop {
+        interval.tick().await;
+        let (symbols, mapper) =
+            match tokio::try_join!(streamed_symbols(&state), load_symbol_mapper(&state.db)) {
+                Ok(loaded) => loaded,
+                Err(e) => {
+                    tracing::warn!("Failed to load the symbols to stream: {}", e);
+                    continue;
+                }
+            };
+        let wanted: HashSet<String> = symbols
+            .iter()
+            .map(|symbol| mapper.to_provider(symbol, SymbolProvider::TwelveData))
+            .collect();
+        let current: HashSet<String> = socket.subscriptions().await.into_iter().collect();
+
+        let added: Vec<String> = wanted.difference(&current).cloned().collect();
+        let removed: Vec<String> = current.difference(&wanted).cloned().collect();
+        if !added.is_empty() {
+            if let Err(e) = socket.subscribe(added).await {
+                tracing::warn!("Stream subscribe failed: {}", e);
+            }
+        }
+        if !removed.is_empty() {
+            if let Err(e) = socket.unsubscribe(removed).await {
+                tracing::warn!("Stream unsubscribe failed: {}", e);
+            }
+        }
+    }
+}
+
+/// Drain connection events so the socket never waits on them
+async fn log_socket_events(socket: Arc<TwelveDataWebSocket>) {
+    while let Some(event) = socket.recv().await {
+        match event {
+            WebSocketEvent::Connected => tracing::info!("Price stream connected"),
+            WebSocketEvent::Disconnected => tracing::warn!("Price stream disconnected"),
+            WebSocketEvent::Subscribed(symbols) => {
+                tracing::debug!("Streaming {} more symbols", symbols.len())
+            }
+            WebSocketEvent::Error(e) => tracing::warn!("Price stream error: {}", e),
+            WebSocketEvent::Price(_) | WebSocketEvent::Unsubscribed(_) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::routes::stocks::price_technical_input;
+    use chrono::{DateTime, TimeZone};
+    use jejakcuan_core::{calculate_composite_score, ScoreWeights, TechnicalScoreEngine};
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+
+    fn daily(time: DateTime<Utc>, close: Decimal) -> StockPriceRow {
+        StockPriceRow {
+            time,
+            symbol: "BBCA".to_string(),
+            open: close,
+            high: close + dec!(50),
+            low: close - dec!(50),
+            close,
+            volume: 1_000_000,
+            value: None,
+            frequency: None,
+        }
+    }
+
+    fn five_minute(start: DateTime<Utc>, open: Decimal, close: Decimal) -> IntradayBar {
+        IntradayBar {
+            symbol: "BBCA".to_string(),
+            interval: SCORING_INTERVAL,
+            start,
+            open,
+            high: open.max(close),
+            low: open.min(close),
+            close,
+            volume: 10_000,
+            ticks: 20,
+        }
+    }
+
+    #[test]
+    fn test_closed_bars_extend_the_session() {
+        // 09:00 WIB sessions of a steady uptrend
+        let first = Utc.with_ymd_and_hms(2024, 1, 1, 2, 0, 0).unwrap();
+        let mut prices: Vec<StockPriceRow> = (0..80)
+            .map(|i| {
+                daily(
+                    first + Duration::days(i),
+                    dec!(9000) + Decimal::from(i * 10),
+                )
+            })
+            .collect();
+        let bars: Vec<OhlcvBar> = prices
+            .iter()
+            .map(|p| OhlcvBar {
+                open: p.open,
+                high: p.high,
+                low: p.low,
+                close: p.close,
+                volume: p.volume,
+            })
+            .collect();
+        let breakdown = TechnicalScoreEngine::new().calculate(&price_technical_input(&bars));
+        let composite = calculate_composite_score(
+            breakdown.total_score.to_f64().unwrap(),
+            60.0,
+            50.0,
+            50.0,
+            &ScoreWeights::default(),
+        );
+        let stored = StockScoreRow {
+            time: prices.last().unwrap().time,
+            symbol: "BBCA".to_string(),
+            composite_score: Decimal::try_from(composite).unwrap(),
+            technical_score: breakdown.total_score,
+            fundamental_score: dec!(60),
+            sentiment_score: dec!(50),
+            ml_score: dec!(50),
+            technical_breakdown: serde_json::to_value(&breakdown).ok(),
+            fundamental_breakdown: None,
+            sentiment_breakdown: None,
+            ml_breakdown: None,
+            input_hash: None,
+        };
+
+        // A daily bar already imported for the session is not history
+        let open = first + Duration::days(80);
+        prices.push(daily(open, dec!(9900)));
+        let mut scoring = SymbolScoring::new(session_date(open), stored, &prices);
+        assert_eq!(scoring.history.len(), 80);
+
+        // The first score of the session is always pushed
+        let pushed = scoring
+            .apply(&five_minute(open, dec!(9800), dec!(9400)))
+            .unwrap();
+        let after_first = scoring.today.clone().unwrap();
+        assert_eq!(
+            (after_first.open, after_first.close, after_first.volume),
+            (dec!(9800), dec!(9400), 10_000)
+        );
+
+        // The sell-off continues: the session bar widens and the score drops further
+        let second = scoring
+            .apply(&five_minute(
+                open + Duration::minutes(5),
+                dec!(9400),
+                dec!(8600),
+            ))
+            .unwrap();
+        assert!(second.composite < pushed.composite);
+        assert!(second.composite_change < 0.0);
+        let today = scoring.today.clone().unwrap();
+        assert_eq!(
+            (today.open, today.high, today.low, today.close, today.volume),
+            (dec!(9800), dec!(9800), dec!(8600), dec!(8600), 20_000)
+        );
+
+        // A quiet bar leaves the score where it was pushed
+        let quiet = IntradayBar {
+            volume: 0,
+            ..five_minute(open + Duration::minutes(10), dec!(8600), dec!(8600))
+        };
+        assert!(scoring.apply(&quiet).is_none());
+    }
+}
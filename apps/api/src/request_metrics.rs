@@ -0,0 +1,190 @@
+//! In-process request metrics
+//!
+//! Every response is counted into per-minute buckets covering the last hour,
+//! split by status class, and the authenticated user behind it is remembered
+//! so the ops dashboard can show traffic, error rates and active users without
+//! an external metrics stack. Counts reset on restart.
+
+use crate::auth::AuthUser;
+use crate::AppState;
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+/// Minutes of history kept
+const WINDOW_MINUTES: i64 = 60;
+
+/// Minutes over which requests per minute are averaged
+const RATE_MINUTES: i64 = 5;
+
+/// A user counts as active if seen within this many minutes
+const ACTIVE_USER_MINUTES: i64 = 15;
+
+#[derive(Debug, Clone, Copy, Default)]
+struct MinuteBucket {
+    /// Minutes since the Unix epoch
+    minute: i64,
+    requests: u64,
+    client_errors: u64,
+    server_errors: u64,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    buckets: VecDeque<MinuteBucket>,
+    last_seen: HashMap<String, DateTime<Utc>>,
+}
+
+/// Request counters shared by the middleware and the admin overview
+#[derive(Debug, Default)]
+pub struct RequestMetrics {
+    inner: Mutex<Inner>,
+}
+
+/// Traffic over the last hour
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct RequestSummary {
+    pub requests_last_hour: u64,
+    /// Average over the last 5 minutes
+    pub requests_per_minute: f64,
+    /// Share of last-hour responses with a 5xx status (%)
+    pub server_error_rate_pct: f64,
+    /// Share of last-hour responses with a 4xx status (%)
+    pub client_error_rate_pct: f64,
+    /// Distinct users seen in the last 15 minutes
+    pub active_users: usize,
+}
+
+impl RequestMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count one response
+    pub fn record(&self, at: DateTime<Utc>, status: u16, user: Option<&str>) {
+        let minute = at.timestamp().div_euclid(60);
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+
+        if inner.buckets.back().map(|b| b.minute) != Some(minute) {
+            inner.buckets.push_back(MinuteBucket {
+                minute,
+                ..Default::default()
+            });
+        }
+        while inner
+            .buckets
+            .front()
+            .is_some_and(|b| b.minute <= minute - WINDOW_MINUTES)
+        {
+            inner.buckets.pop_front();
+        }
+
+        let bucket = inner.buckets.back_mut().expect("bucket just pushed");
+        bucket.requests += 1;
+        match status {
+            400..=499 => bucket.client_errors += 1,
+            500..=599 => bucket.server_errors += 1,
+            _ => {}
+        }
+
+        if let Some(user) = user {
+            inner.last_seen.insert(user.to_string(), at);
+            let cutoff = at - Duration::minutes(ACTIVE_USER_MINUTES);
+            inner.last_seen.retain(|_, seen| *seen >= cutoff);
+        }
+    }
+
+    pub fn summary(&self, now: DateTime<Utc>) -> RequestSummary {
+        let minute = now.timestamp().div_euclid(60);
+        let inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+
+        let in_window = |b: &&MinuteBucket, minutes: i64| b.minute > minute - minutes;
+        let hour: Vec<&MinuteBucket> = inner
+            .buckets
+            .iter()
+            .filter(|b| in_window(b, WINDOW_MINUTES))
+            .collect();
+        let requests: u64 = hour.iter().map(|b| b.requests).sum();
+        let client_errors: u64 = hour.iter().map(|b| b.client_errors).sum();
+        let server_errors: u64 = hour.iter().map(|b| b.server_errors).sum();
+        let recent: u64 = hour
+            .iter()
+            .filter(|b| in_window(b, RATE_MINUTES))
+            .map(|b| b.requests)
+            .sum();
+
+        let pct = |count: u64| {
+            if requests == 0 {
+                0.0
+            } else {
+                count as f64 / requests as f64 * 100.0
+            }
+        };
+        let cutoff = now - Duration::minutes(ACTIVE_USER_MINUTES);
+
+        RequestSummary {
+            requests_last_hour: requests,
+            requests_per_minute: recent as f64 / RATE_MINUTES as f64,
+            server_error_rate_pct: pct(server_errors),
+            client_error_rate_pct: pct(client_errors),
+            active_users: inner.last_seen.values().filter(|s| **s >= cutoff).count(),
+        }
+    }
+}
+
+/// Middleware counting every response
+pub async fn track_requests(
+    State(state): State<Arc<AppState>>,
+    user: Option<AuthUser>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let response = next.run(request).await;
+    state.request_metrics.record(
+        Utc::now(),
+        response.status().as_u16(),
+        user.as_ref().map(|u| u.username.as_str()),
+    );
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_summary() {
+        let metrics = RequestMetrics::new();
+        // An hour and a half ago: outside the window
+        metrics.record(at("2025-06-04T08:30:00Z"), 200, Some("old"));
+        for _ in 0..8 {
+            metrics.record(at("2025-06-04T09:58:10Z"), 200, Some("admin"));
+        }
+        metrics.record(at("2025-06-04T09:59:00Z"), 404, None);
+        metrics.record(at("2025-06-04T09:59:30Z"), 500, Some("ops"));
+
+        let summary = metrics.summary(at("2025-06-04T10:00:00Z"));
+        assert_eq!(summary.requests_last_hour, 10);
+        assert_eq!(summary.requests_per_minute, 2.0);
+        assert_eq!(summary.server_error_rate_pct, 10.0);
+        assert_eq!(summary.client_error_rate_pct, 10.0);
+        assert_eq!(summary.active_users, 2);
+    }
+
+    #[test]
+    fn test_empty_summary() {
+        let summary = RequestMetrics::new().summary(Utc::now());
+        assert_eq!(summary.requests_last_hour, 0);
+        assert_eq!(summary.server_error_rate_pct, 0.0);
+    }
+}
@@ -0,0 +1,86 @@
+//! Purge of soft-deleted rows once their restore window has passed, and of
+//! usage events and dead-lettered notifications past their retention
+
+use crate::notification_retry::DEAD_LETTER_RETENTION_DAYS;
+use crate::AppState;
+use chrono::{DateTime, Duration, Utc};
+use jejakcuan_db::repositories;
+use serde::Serialize;
+use std::sync::Arc;
+
+/// How often the purge runs
+const PURGE_EVERY: std::time::Duration = std::time::Duration::from_secs(24 * 60 * 60);
+
+/// Rows permanently removed by one purge
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct PurgeReport {
+    pub watchlist: u64,
+    pub alert_rules: u64,
+    /// Usage events past their retention
+    pub usage_events: u64,
+    /// Dead-lettered notifications past their retention
+    pub dead_notifications: u64,
+}
+
+/// Oldest deletion time that is still restorable
+pub fn restore_cutoff(now: DateTime<Utc>, retention_days: i64) -> DateTime<Utc> {
+    now - Duration::days(retention_days.max(0))
+}
+
+/// Permanently remove rows soft-deleted before the retention window
+pub async fn purge(state: &AppState) -> Result<PurgeReport, sqlx::Error> {
+    let before = restore_cutoff(Utc::now(), state.config.soft_delete_retention_days);
+    Ok(PurgeReport {
+        watchlist: repositories::watchlist::purge_deleted_watchlist(&state.db, before).await?,
+        alert_rules: repositories::alerts::purge_deleted_alert_rules(&state.db, before).await?,
+        usage_events: repositories::usage_events::purge_usage_events(
+            &state.db,
+            Utc::now() - Duration::days(state.config.usage_analytics.retention_days),
+        )
+        .await?,
+        dead_notifications: repositories::notification_retries::purge_dead_notification_retries(
+            &state.db,
+            Utc::now() - Duration::days(DEAD_LETTER_RETENTION_DAYS),
+        )
+        .await?,
+    })
+}
+
+/// Run `purge` once a day, starting a day after startup
+pub fn spawn_retention_job(state: Arc<AppState>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(PURGE_EVERY);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        interval.tick().await;
+
+        loop {
+            interval.tick().await;
+            match purge(&state).await {
+                Ok(report) => tracing::info!(
+                    "Purged {} watchlist items and {} alert rules past the restore window, {} old usage events, {} old dead letters",
+                    report.watchlist,
+                    report.alert_rules,
+                    report.usage_events,
+                    report.dead_notifications
+                ),
+                Err(e) => tracing::warn!("Soft-delete purge failed: {}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restore_cutoff() {
+        let now: DateTime<Utc> = "2025-06-30T12:00:00Z".parse().unwrap();
+        assert_eq!(
+            restore_cutoff(now, 30),
+            "2025-05-31T12:00:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+        // A negative window never reaches into the future
+        assert_eq!(restore_cutoff(now, -5), now);
+    }
+}
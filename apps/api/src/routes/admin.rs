@@ -3,21 +3,56 @@
 //! Provides granular data source management with individual control
 //! over each data provider within categories.
 
+use crate::alert_scheduler::{scan, ScanReport};
+use crate::analysis_cache::CacheStats;
 use crate::auth::AuthUser;
-use crate::routes::jobs::Job;
+use crate::backfill::{self, BackfillError, BackfillPlan};
+use crate::change_history::record_change;
+use crate::corporate_actions::{self, CORPORATE_ACTIONS_JOB};
+use crate::earnings::{self, EARNINGS_CALENDAR_JOB};
+use crate::maintenance::{self, MaintenanceTask};
+use crate::news::{self, NEWS_INGEST_JOB};
+use crate::notification_retry;
+use crate::notifications::{Notification, NotificationMetadata, NotificationPriority};
+use crate::price_store::PriceStoreStats;
+use crate::request_metrics::RequestSummary;
+use crate::routes::jobs::{Job, JobStatus};
+use crate::secrets::NOTIFIER_SECRETS;
+use crate::shareholdings::{self, SHAREHOLDINGS_JOB};
+use crate::stock_universe::{self, UNIVERSE_SYNC_JOB};
 use crate::AppState;
 use axum::{
-    extract::{Path, State},
+    body::Bytes,
+    extract::{Path, Query, State},
+    http::header,
+    response::IntoResponse,
     routing::{get, post},
     Json, Router,
 };
 use chrono::{DateTime, Utc};
+use jejakcuan_core::alerts::NotificationChannel;
+use jejakcuan_data_sources::spreadsheet::to_csv_bytes;
+use jejakcuan_data_sources::twelvedata::TickQueueStats;
+use jejakcuan_data_sources::{
+    ApiProvider, BrokerParseContext, BrokerParserRegistry, BrokerScraper, BrokerSummary,
+    DataSourceError, KeyHealth, ParserHealthReport, ParserStatus, ProviderHealth, QuotaForecast,
+    ShareholdingScraper, BROKER_HTML_PARSER, SHAREHOLDING_HTML_PARSER,
+};
+use jejakcuan_db::{
+    repositories, BackfillJobRow, SecretCipher, SecretError, StoredSecretRow, UsageDailyCountRow,
+    UsageSubjectCountRow,
+};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
 
 pub fn admin_routes() -> Router<Arc<AppState>> {
     Router::new()
+        // Ops dashboard
+        .route("/overview", get(get_overview))
+        .route("/analytics", get(get_usage_analytics))
+        // Prometheus scrape target
+        .route("/metrics", get(get_metrics))
         // Legacy endpoints (backward compatible)
         .route("/data-status", get(get_data_status))
         .route("/data-status/:source_id", get(get_source_status))
@@ -34,6 +69,61 @@ pub fn admin_routes() -> Router<Arc<AppState>> {
             post(trigger_category),
         )
         .route("/data-sources/:source_id/config", get(get_source_config))
+        // Historical intraday backfill
+        .route(
+            "/data-sources/twelvedata/backfill",
+            get(list_backfill_jobs).post(start_backfill),
+        )
+        .route(
+            "/data-sources/twelvedata/backfill/:job_id",
+            get(get_backfill_job),
+        )
+        // Price provider failover health
+        .route(
+            "/data-sources/price-providers",
+            get(get_price_provider_health),
+        )
+        // Scraper drift detection endpoints
+        .route("/data-sources/parser-health", get(get_parser_health))
+        .route(
+            "/data-sources/parser-health/check",
+            post(check_parser_health),
+        )
+        // Broker data upload parsing
+        .route("/broker-data/parsers", get(list_broker_parsers))
+        .route("/broker-data/parse", post(parse_broker_data))
+        // Alert scan on demand
+        .route("/alerts/scan", post(run_alert_scan))
+        // Stock universe from the IDX listed company table
+        .route("/stocks/sync-universe", post(sync_stock_universe))
+        // News feeds tagged by ticker and scored for sentiment
+        .route("/news/ingest", post(ingest_news))
+        .route("/corporate-actions/ingest", post(ingest_corporate_actions))
+        // Major shareholders, staged for review
+        .route("/shareholdings/ingest", post(ingest_shareholdings))
+        .route("/earnings/sync", post(sync_earnings))
+        // Database maintenance on demand
+        .route("/maintenance", post(run_maintenance))
+        .route("/maintenance/:task", post(run_maintenance_task))
+        // Notification retry queue and dead letters
+        .route("/notifications/failed", get(list_failed_notifications))
+        .route(
+            "/notifications/failed/:id/retry",
+            post(retry_failed_notification),
+        )
+        // Encrypted credential storage
+        .route("/secrets", get(list_stored_secrets))
+        .route("/secrets/rotate", post(rotate_stored_secrets))
+        .route(
+            "/secrets/:name",
+            axum::routing::put(put_stored_secret).delete(delete_stored_secret),
+        )
+        // SLA tracking endpoints
+        .route("/data-sources/sla", get(get_sla_report))
+        .route("/data-sources/sla/check", post(check_sla_breaches))
+        // API key quota endpoints
+        .route("/data-sources/quota", get(get_quota_report))
+        .route("/data-sources/quota/check", post(check_quota_warnings))
         // Job management endpoints
         .route("/jobs", get(list_jobs))
         .route("/jobs/:job_id", get(get_job))
@@ -120,6 +210,8 @@ pub struct DataSourceDefinition {
     pub trigger_command: Option<&'static str>,
     pub db_table: Option<&'static str>,
     pub freshness_threshold_hours: i64,
+    /// External API credits consumed per symbol refreshed (0 for free/scraped sources)
+    pub api_credits_per_symbol: i64,
 }
 
 /// Get the registry of all available data sources
@@ -138,6 +230,7 @@ fn get_data_source_registry() -> Vec<DataSourceDefinition> {
             trigger_command: Some("python -m jejakcuan_ml.scrapers.cli broker --days 30"),
             db_table: Some("broker_summary"),
             freshness_threshold_hours: 24,
+            api_credits_per_symbol: 0,
         },
         DataSourceDefinition {
             id: "indopremier_broker",
@@ -149,6 +242,7 @@ fn get_data_source_registry() -> Vec<DataSourceDefinition> {
             trigger_command: Some("python -m jejakcuan_ml.scrapers.cli broker --days 30"),
             db_table: Some("broker_summary"),
             freshness_threshold_hours: 24,
+            api_credits_per_symbol: 0,
         },
         DataSourceDefinition {
             id: "idx_broker",
@@ -160,6 +254,7 @@ fn get_data_source_registry() -> Vec<DataSourceDefinition> {
             trigger_command: Some("python -m jejakcuan_ml.scrapers.cli broker --days 30"),
             db_table: Some("broker_summary"),
             freshness_threshold_hours: 24,
+            api_credits_per_symbol: 0,
         },
         // =========================
         // PRICES CATEGORY
@@ -174,6 +269,7 @@ fn get_data_source_registry() -> Vec<DataSourceDefinition> {
             trigger_command: Some("python -m jejakcuan_ml.scrapers.cli price --days 60"),
             db_table: Some("stock_prices"),
             freshness_threshold_hours: 24,
+            api_credits_per_symbol: 0,
         },
         DataSourceDefinition {
             id: "twelvedata",
@@ -181,16 +277,26 @@ fn get_data_source_registry() -> Vec<DataSourceDefinition> {
             category: DataSourceCategory::Prices,
             source_type: SourceType::RustClient,
             description: "Real-time quotes and time series from TwelveData",
-            config_fields: vec![ConfigField {
-                name: "API Key".to_string(),
-                description: "TwelveData API key for authentication".to_string(),
-                env_var: "TWELVEDATA_API_KEY".to_string(),
-                required: true,
-                is_secret: true,
-            }],
+            config_fields: vec![
+                ConfigField {
+                    name: "API Key".to_string(),
+                    description: "TwelveData API key for authentication".to_string(),
+                    env_var: "TWELVEDATA_API_KEY".to_string(),
+                    required: true,
+                    is_secret: true,
+                },
+                ConfigField {
+                    name: "Additional API Keys".to_string(),
+                    description: "Comma-separated extra keys rotated on rate limits".to_string(),
+                    env_var: "TWELVEDATA_API_KEYS".to_string(),
+                    required: false,
+                    is_secret: true,
+                },
+            ],
             trigger_command: None, // Triggered via Rust client
             db_table: Some("stock_prices"),
             freshness_threshold_hours: 24,
+            api_credits_per_symbol: 1,
         },
         // =========================
         // FUNDAMENTALS CATEGORY
@@ -205,6 +311,7 @@ fn get_data_source_registry() -> Vec<DataSourceDefinition> {
             trigger_command: Some("python -m jejakcuan_ml.scrapers.cli idx"),
             db_table: Some("financials"),
             freshness_threshold_hours: 168, // 7 days
+            api_credits_per_symbol: 0,
         },
         DataSourceDefinition {
             id: "sectors_app",
@@ -222,6 +329,7 @@ fn get_data_source_registry() -> Vec<DataSourceDefinition> {
             trigger_command: None, // Triggered via Rust client
             db_table: Some("financials"),
             freshness_threshold_hours: 168, // 7 days
+            api_credits_per_symbol: 1,
         },
         DataSourceDefinition {
             id: "idx_fundamentals",
@@ -233,6 +341,55 @@ fn get_data_source_registry() -> Vec<DataSourceDefinition> {
             trigger_command: Some("python -m jejakcuan_ml.scrapers.cli idx"),
             db_table: Some("financials"),
             freshness_threshold_hours: 168,
+            api_credits_per_symbol: 0,
+        },
+        DataSourceDefinition {
+            id: UNIVERSE_SYNC_JOB,
+            name: "IDX Listed Companies",
+            category: DataSourceCategory::Fundamentals,
+            source_type: SourceType::RustClient,
+            description: "Stock universe with sectors from the IDX listed company table",
+            config_fields: vec![],
+            trigger_command: None, // POST /api/admin/stocks/sync-universe
+            db_table: Some("stocks"),
+            freshness_threshold_hours: 168, // 7 days
+            api_credits_per_symbol: 0,
+        },
+        DataSourceDefinition {
+            id: SHAREHOLDINGS_JOB,
+            name: "IDX Shareholding",
+            category: DataSourceCategory::Fundamentals,
+            source_type: SourceType::RustClient,
+            description: "Major shareholder disclosures scraped from IDX company profiles",
+            config_fields: vec![],
+            trigger_command: None, // POST /api/admin/shareholdings/ingest, staged for review
+            db_table: Some("shareholdings"),
+            freshness_threshold_hours: 720, // 30 days
+            api_credits_per_symbol: 0,
+        },
+        DataSourceDefinition {
+            id: CORPORATE_ACTIONS_JOB,
+            name: "Corporate Actions",
+            category: DataSourceCategory::Fundamentals,
+            source_type: SourceType::RustClient,
+            description: "IDX and KSEI dividends, splits, bonus shares and rights issues",
+            config_fields: vec![],
+            trigger_command: None, // POST /api/admin/corporate-actions/ingest
+            db_table: Some("corporate_actions"),
+            freshness_threshold_hours: 24,
+            api_credits_per_symbol: 0,
+        },
+        DataSourceDefinition {
+            id: EARNINGS_CALENDAR_JOB,
+            name: "Financial Report Calendar",
+            category: DataSourceCategory::Fundamentals,
+            source_type: SourceType::RustClient,
+            description: "IDX financial report publications and expected report dates",
+            config_fields: vec![],
+            trigger_command: None, // POST /api/admin/earnings/sync
+            db_table: Some("earnings_calendar"),
+            freshness_threshold_hours: 168, // 7 days
+            api_credits_per_symbol: 0,
         },
         // =========================
         // SCORES CATEGORY
@@ -247,6 +404,7 @@ fn get_data_source_registry() -> Vec<DataSourceDefinition> {
             trigger_command: None, // Computed via API
             db_table: Some("stock_scores"),
             freshness_threshold_hours: 24,
+            api_credits_per_symbol: 0,
         },
         DataSourceDefinition {
             id: "fundamental_score",
@@ -258,17 +416,31 @@ fn get_data_source_registry() -> Vec<DataSourceDefinition> {
             trigger_command: None,
             db_table: Some("stock_scores"),
             freshness_threshold_hours: 24,
+            api_credits_per_symbol: 0,
+        },
+        DataSourceDefinition {
+            id: NEWS_INGEST_JOB,
+            name: "News Feeds",
+            category: DataSourceCategory::Scores,
+            source_type: SourceType::RustClient,
+            description: "Kontan, IQPlus and CNBC Indonesia headlines tagged by ticker",
+            config_fields: vec![],
+            trigger_command: None, // POST /api/admin/news/ingest
+            db_table: Some("stock_news"),
+            freshness_threshold_hours: 12,
+            api_credits_per_symbol: 0,
         },
         DataSourceDefinition {
             id: "sentiment_score",
             name: "Sentiment Score",
             category: DataSourceCategory::Scores,
             source_type: SourceType::Computed,
-            description: "Market sentiment from broker flow analysis",
+            description: "Recency-weighted lexicon sentiment of recent news headlines",
             config_fields: vec![],
             trigger_command: None,
             db_table: Some("stock_scores"),
             freshness_threshold_hours: 24,
+            api_credits_per_symbol: 0,
         },
         DataSourceDefinition {
             id: "ml_score",
@@ -280,6 +452,7 @@ fn get_data_source_registry() -> Vec<DataSourceDefinition> {
             trigger_command: Some("python -m jejakcuan_ml.prediction.score"),
             db_table: Some("stock_scores"),
             freshness_threshold_hours: 24,
+            api_credits_per_symbol: 0,
         },
     ]
 }
@@ -304,6 +477,8 @@ pub struct GranularDataSource {
     pub freshness_hours: Option<i64>,
     pub can_trigger: bool,
     pub trigger_command: Option<String>,
+    /// Today's usage and quota forecast per API key
+    pub quota: Vec<QuotaForecast>,
 }
 
 /// State of a data source
@@ -315,6 +490,7 @@ pub enum DataSourceState {
     Outdated,
     NoData,
     NotConfigured,
+    ParserOutdated,
     Running,
     Error,
 }
@@ -334,6 +510,8 @@ pub struct ConfigFieldStatus {
     pub env_var: String,
     pub required: bool,
     pub is_set: bool,
+    /// Set through the encrypted secret store rather than the environment
+    pub is_stored: bool,
 }
 
 /// Response for listing all data sources
@@ -365,6 +543,26 @@ pub struct CategorySummary {
     pub not_configured: usize,
 }
 
+/// Query parameters for trigger endpoints
+#[derive(Debug, Deserialize)]
+pub struct TriggerQuery {
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Execution plan returned by a dry-run trigger
+#[derive(Debug, Clone, Serialize)]
+pub struct TriggerPlan {
+    pub config_valid: bool,
+    pub missing_fields: Vec<String>,
+    pub command: Option<String>,
+    pub lookback_days: Option<i64>,
+    pub estimated_symbols: i64,
+    pub estimated_rows: i64,
+    pub estimated_api_credits: i64,
+    pub warnings: Vec<String>,
+}
+
 /// Trigger response
 #[derive(Debug, Serialize)]
 pub struct TriggerResponse {
@@ -377,6 +575,8 @@ pub struct TriggerResponse {
     pub job_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub job: Option<Job>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plan: Option<TriggerPlan>,
 }
 
 /// Category trigger response
@@ -402,6 +602,58 @@ pub struct ConfigResponse {
     pub is_configured: bool,
 }
 
+/// SLA status of a single data source
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceSlaStatus {
+    pub source_id: String,
+    pub source_name: String,
+    pub freshness_threshold_hours: i64,
+    pub last_update: Option<DateTime<Utc>>,
+    pub freshness_hours: Option<i64>,
+    pub last_success_at: Option<DateTime<Utc>>,
+    pub last_failure_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub consecutive_failures: i32,
+    pub total_runs: i32,
+    pub avg_duration_secs: Option<f64>,
+    pub breached: bool,
+    pub last_breach_notified_at: Option<DateTime<Utc>>,
+}
+
+/// SLA report across all configured data sources
+#[derive(Debug, Serialize)]
+pub struct SlaReportResponse {
+    pub timestamp: DateTime<Utc>,
+    pub breached_count: usize,
+    pub sources: Vec<SourceSlaStatus>,
+}
+
+/// Result of an SLA breach check
+#[derive(Debug, Serialize)]
+pub struct SlaCheckResponse {
+    pub timestamp: DateTime<Utc>,
+    pub breached: Vec<SourceSlaStatus>,
+    pub notified: Vec<String>,
+    pub notification_errors: Vec<String>,
+}
+
+/// API key usage across metered providers
+#[derive(Debug, Serialize)]
+pub struct QuotaReportResponse {
+    pub timestamp: DateTime<Utc>,
+    pub warning_count: usize,
+    pub keys: Vec<QuotaForecast>,
+}
+
+/// Result of a quota warning check
+#[derive(Debug, Serialize)]
+pub struct QuotaCheckResponse {
+    pub timestamp: DateTime<Utc>,
+    pub warnings: Vec<QuotaForecast>,
+    pub notified: Vec<String>,
+    pub notification_errors: Vec<String>,
+}
+
 // ============================================================================
 // Legacy Types (backward compatible)
 // ============================================================================
@@ -432,6 +684,8 @@ pub struct DataStatusResponse {
     pub overall_status: String,
     pub sources: Vec<DataSourceStatus>,
     pub summary: DataSummary,
+    /// Request counts and rate-limit state of each pooled TwelveData key
+    pub twelvedata_keys: Vec<KeyHealth>,
 }
 
 #[derive(Debug, Serialize)]
@@ -621,6 +875,11 @@ async fn get_data_status(
             oldest_price_data: oldest_price.map(|r| r.0),
             newest_price_data: latest_price.map(|r| r.0),
         },
+        twelvedata_keys: state
+            .twelvedata
+            .as_ref()
+            .map(|client| client.key_health())
+            .unwrap_or_default(),
     }))
 }
 
@@ -783,6 +1042,7 @@ fn get_config_status(definition: &DataSourceDefinition) -> ConfigStatus {
                 env_var: field.env_var.clone(),
                 required: field.required,
                 is_set,
+                is_stored: false,
             }
         })
         .collect();
@@ -825,10 +1085,11 @@ async fn get_table_stats(
     pool: &sqlx::PgPool,
     table_name: &str,
 ) -> Result<(Option<DateTime<Utc>>, i64), sqlx::Error> {
-    let time_column = if table_name == "financials" {
-        "created_at"
-    } else {
-        "time"
+    let time_column = match table_name {
+        "financials" | "shareholdings" | "corporate_actions" => "created_at",
+        "stocks" | "earnings_calendar" => "updated_at",
+        "stock_news" => "published_at",
+        _ => "time",
     };
 
     let latest_query = format!("SELECT MAX({}) FROM {}", time_column, table_name);
@@ -842,10 +1103,29 @@ async fn get_table_stats(
     Ok((latest.and_then(|r| r.0), count.0))
 }
 
+/// Scraper parser backing a data source, if it has a self-check
+fn parser_for_source(source_id: &str) -> Option<&'static str> {
+    match source_id {
+        "idx_broker" => Some(BROKER_HTML_PARSER),
+        "idx_shareholding" => Some(SHAREHOLDING_HTML_PARSER),
+        _ => None,
+    }
+}
+
+/// Metered API behind a data source
+fn api_provider_for_source(source_id: &str) -> Option<ApiProvider> {
+    match source_id {
+        "twelvedata" => Some(ApiProvider::TwelveData),
+        "sectors_app" => Some(ApiProvider::Sectors),
+        _ => None,
+    }
+}
+
 async fn build_granular_source(
-    pool: &sqlx::PgPool,
+    state: &AppState,
     definition: &DataSourceDefinition,
 ) -> Result<GranularDataSource, sqlx::Error> {
+    let pool = &state.db;
     let config_status = get_config_status(definition);
 
     let (last_update, record_count) = if let Some(table) = definition.db_table {
@@ -854,12 +1134,22 @@ async fn build_granular_source(
         (None, 0)
     };
 
-    let (state, freshness_hours) = determine_source_state(
+    let (mut source_state, freshness_hours) = determine_source_state(
         last_update,
         definition.freshness_threshold_hours,
         config_status.is_configured,
     );
 
+    if let Some(parser) = parser_for_source(definition.id) {
+        let parser_health = state.parser_health.read().await;
+        if parser_health
+            .get(parser)
+            .is_some_and(|report| report.status == ParserStatus::Outdated)
+        {
+            source_state = DataSourceState::ParserOutdated;
+        }
+    }
+
     let can_trigger = config_status.is_configured
         && (definition.trigger_command.is_some()
             || matches!(
@@ -874,13 +1164,16 @@ async fn build_granular_source(
         category_name: definition.category.display_name().to_string(),
         source_type: definition.source_type,
         description: definition.description.to_string(),
-        status: state,
+        status: source_state,
         config_status,
         last_update,
         record_count,
         freshness_hours,
         can_trigger,
         trigger_command: definition.trigger_command.map(|s| s.to_string()),
+        quota: api_provider_for_source(definition.id)
+            .map(|provider| state.api_usage.provider_forecasts(provider))
+            .unwrap_or_default(),
     })
 }
 
@@ -888,12 +1181,11 @@ async fn list_data_sources(
     _user: AuthUser,
     State(state): State<Arc<AppState>>,
 ) -> Result<Json<DataSourcesResponse>, (axum::http::StatusCode, String)> {
-    let pool = &state.db;
     let registry = get_data_source_registry();
 
     let mut sources = Vec::new();
     for definition in &registry {
-        let source = build_granular_source(pool, definition)
+        let source = build_granular_source(&state, definition)
             .await
             .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
         sources.push(source);
@@ -920,7 +1212,10 @@ async fn list_data_sources(
         .filter(|s| {
             matches!(
                 s.status,
-                DataSourceState::Stale | DataSourceState::Outdated | DataSourceState::NoData
+                DataSourceState::Stale
+                    | DataSourceState::Outdated
+                    | DataSourceState::NoData
+                    | DataSourceState::ParserOutdated
             )
         })
         .count();
@@ -950,6 +1245,7 @@ async fn list_data_sources(
                         DataSourceState::Stale
                             | DataSourceState::Outdated
                             | DataSourceState::NoData
+                            | DataSourceState::ParserOutdated
                     )
                 })
                 .count(),
@@ -966,7 +1262,7 @@ async fn list_data_sources(
     let any_outdated = sources.iter().any(|s| {
         matches!(
             s.status,
-            DataSourceState::Outdated | DataSourceState::NoData
+            DataSourceState::Outdated | DataSourceState::NoData | DataSourceState::ParserOutdated
         )
     });
 
@@ -999,7 +1295,6 @@ async fn get_data_source(
     State(state): State<Arc<AppState>>,
     Path(source_id): Path<String>,
 ) -> Result<Json<GranularDataSource>, (axum::http::StatusCode, String)> {
-    let pool = &state.db;
     let registry = get_data_source_registry();
 
     let definition = registry.iter().find(|d| d.id == source_id).ok_or_else(|| {
@@ -1009,7 +1304,7 @@ async fn get_data_source(
         )
     })?;
 
-    let source = build_granular_source(pool, definition)
+    let source = build_granular_source(&state, definition)
         .await
         .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
@@ -1020,6 +1315,7 @@ async fn trigger_data_source(
     _user: AuthUser,
     State(state): State<Arc<AppState>>,
     Path(source_id): Path<String>,
+    Query(query): Query<TriggerQuery>,
 ) -> Result<Json<TriggerResponse>, (axum::http::StatusCode, String)> {
     let registry = get_data_source_registry();
 
@@ -1030,6 +1326,11 @@ async fn trigger_data_source(
         )
     })?;
 
+    if query.dry_run {
+        let plan = build_trigger_plan(&state, definition).await?;
+        return Ok(Json(dry_run_response(definition, plan)));
+    }
+
     let config_status = get_config_status(definition);
     if !config_status.is_configured {
         return Err((
@@ -1051,6 +1352,7 @@ async fn trigger_data_source(
             started_at: running_job.started_at,
             job_id: Some(running_job.id.clone()),
             job: Some(running_job),
+            plan: None,
         }));
     }
 
@@ -1107,9 +1409,86 @@ async fn trigger_data_source(
         started_at: Utc::now(),
         job_id,
         job,
+        plan: None,
     }))
 }
 
+/// Parse the `--days N` lookback window from a trigger command
+fn parse_lookback_days(command: &str) -> Option<i64> {
+    let mut parts = command.split_whitespace();
+    while let Some(part) = parts.next() {
+        if part == "--days" {
+            return parts.next().and_then(|days| days.parse().ok());
+        }
+    }
+    None
+}
+
+/// Estimate the work a trigger would perform without running it
+///
+/// Row counts assume one row per symbol per lookback day, which is exact
+/// for prices and a lower bound for per-broker tables.
+async fn build_trigger_plan(
+    state: &AppState,
+    definition: &DataSourceDefinition,
+) -> Result<TriggerPlan, (axum::http::StatusCode, String)> {
+    let config_status = get_config_status(definition);
+
+    let active_symbols: (i64,) =
+        sqlx::query_as("SELECT COUNT(*) FROM stocks WHERE is_active = true")
+            .fetch_one(&state.db)
+            .await
+            .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let lookback_days = definition.trigger_command.and_then(parse_lookback_days);
+    let estimated_symbols = active_symbols.0;
+    let estimated_rows = estimated_symbols * lookback_days.unwrap_or(1);
+    let estimated_api_credits = estimated_symbols * definition.api_credits_per_symbol;
+
+    let mut warnings = Vec::new();
+    if !config_status.is_configured {
+        warnings.push(format!(
+            "Missing configuration: {}",
+            config_status.missing_fields.join(", ")
+        ));
+    }
+    if let Some(running_job) = state.job_manager.is_source_running(definition.id).await {
+        warnings.push(format!("Job {} is already running", running_job.id));
+    }
+    if matches!(definition.source_type, SourceType::PythonScraper)
+        && definition.trigger_command.is_none()
+    {
+        warnings.push("No trigger command configured".to_string());
+    }
+
+    Ok(TriggerPlan {
+        config_valid: config_status.is_configured,
+        missing_fields: config_status.missing_fields,
+        command: definition.trigger_command.map(|s| s.to_string()),
+        lookback_days,
+        estimated_symbols,
+        estimated_rows,
+        estimated_api_credits,
+        warnings,
+    })
+}
+
+fn dry_run_response(definition: &DataSourceDefinition, plan: TriggerPlan) -> TriggerResponse {
+    TriggerResponse {
+        source_id: definition.id.to_string(),
+        status: "dry_run".to_string(),
+        message: format!(
+            "Dry run: would refresh ~{} symbols ({} rows, {} API credits)",
+            plan.estimated_symbols, plan.estimated_rows, plan.estimated_api_credits
+        ),
+        command: plan.command.clone(),
+        started_at: Utc::now(),
+        job_id: None,
+        job: None,
+        plan: Some(plan),
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct JobsListResponse {
     pub jobs: Vec<Job>,
@@ -1175,6 +1554,7 @@ async fn trigger_category(
     _user: AuthUser,
     State(state): State<Arc<AppState>>,
     Path(category_str): Path<String>,
+    Query(query): Query<TriggerQuery>,
 ) -> Result<Json<CategoryTriggerResponse>, (axum::http::StatusCode, String)> {
     let category: DataSourceCategory = category_str.parse().map_err(|e: String| {
         (
@@ -1190,6 +1570,12 @@ async fn trigger_category(
     let mut skipped = Vec::new();
 
     for definition in category_sources {
+        if query.dry_run {
+            let plan = build_trigger_plan(&state, definition).await?;
+            triggered.push(dry_run_response(definition, plan));
+            continue;
+        }
+
         let config_status = get_config_status(definition);
 
         if !config_status.is_configured {
@@ -1262,6 +1648,7 @@ async fn trigger_category(
             started_at: Utc::now(),
             job_id,
             job,
+            plan: None,
         });
     }
 
@@ -1274,6 +1661,7 @@ async fn trigger_category(
 
 async fn get_source_config(
     _user: AuthUser,
+    State(state): State<Arc<AppState>>,
     Path(source_id): Path<String>,
 ) -> Result<Json<ConfigResponse>, (axum::http::StatusCode, String)> {
     let registry = get_data_source_registry();
@@ -1285,12 +1673,1265 @@ async fn get_source_config(
         )
     })?;
 
-    let config_status = get_config_status(definition);
+    let mut config_status = get_config_status(definition);
+    let stored: HashSet<String> = jejakcuan_db::get_stored_secrets(&state.db)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .into_iter()
+        .map(|row| row.name)
+        .collect();
+    for field in &mut config_status.config_fields {
+        field.is_stored = stored.contains(&field.env_var);
+        field.is_set |= field.is_stored;
+    }
+    let is_configured = config_status
+        .config_fields
+        .iter()
+        .all(|field| field.is_set || !field.required);
 
     Ok(Json(ConfigResponse {
         source_id: definition.id.to_string(),
         source_name: definition.name.to_string(),
         fields: config_status.config_fields,
-        is_configured: config_status.is_configured,
+        is_configured,
+    }))
+}
+
+// ============================================================================
+// Price Provider Failover
+// ============================================================================
+
+#[derive(Debug, Serialize)]
+pub struct PriceProviderHealthResponse {
+    pub timestamp: DateTime<Utc>,
+    /// Providers in priority order
+    pub providers: Vec<ProviderHealth>,
+}
+
+async fn get_price_provider_health(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<PriceProviderHealthResponse>, (axum::http::StatusCode, String)> {
+    Ok(Json(PriceProviderHealthResponse {
+        timestamp: Utc::now(),
+        providers: state.price_provider.health(),
+    }))
+}
+
+// ============================================================================
+// Scraper Drift Detection
+// ============================================================================
+
+/// Reference stock whose pages are used for scraper self-checks
+const PARSER_REFERENCE_SYMBOL: &str = "BBCA";
+
+#[derive(Debug, Serialize)]
+pub struct ParserHealthResponse {
+    pub timestamp: DateTime<Utc>,
+    pub outdated_count: usize,
+    pub parsers: Vec<ParserHealthReport>,
+}
+
+fn parser_health_response(mut parsers: Vec<ParserHealthReport>) -> ParserHealthResponse {
+    parsers.sort_by(|a, b| a.parser.cmp(&b.parser));
+    let outdated_count = parsers
+        .iter()
+        .filter(|p| p.status == ParserStatus::Outdated)
+        .count();
+
+    ParserHealthResponse {
+        timestamp: Utc::now(),
+        outdated_count,
+        parsers,
+    }
+}
+
+async fn get_parser_health(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<ParserHealthResponse>, (axum::http::StatusCode, String)> {
+    let parsers = state.parser_health.read().await.values().cloned().collect();
+    Ok(Json(parser_health_response(parsers)))
+}
+
+/// Probe each scraper against its reference page and store the results
+async fn check_parser_health(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<ParserHealthResponse>, (axum::http::StatusCode, String)> {
+    let broker_scraper = BrokerScraper::new();
+    let shareholding_scraper = ShareholdingScraper::new();
+    let (broker, shareholding) = tokio::join!(
+        broker_scraper.self_check(PARSER_REFERENCE_SYMBOL),
+        shareholding_scraper.self_check(PARSER_REFERENCE_SYMBOL),
+    );
+
+    let reports = vec![broker, shareholding];
+    for report in &reports {
+        if report.status == ParserStatus::Outdated {
+            tracing::warn!(
+                "Scraper parser {} is outdated: selectors {:?}, columns {:?}",
+                report.parser,
+                report.missing_selectors,
+                report.missing_columns
+            );
+        }
+    }
+
+    {
+        let mut parser_health = state.parser_health.write().await;
+        for report in &reports {
+            parser_health.insert(report.parser.clone(), report.clone());
+        }
+    }
+
+    Ok(Json(parser_health_response(reports)))
+}
+
+// ============================================================================
+// Broker Data Parsing
+// ============================================================================
+
+#[derive(Debug, Serialize)]
+pub struct BrokerParsersResponse {
+    pub parsers: Vec<&'static str>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BrokerParseQuery {
+    /// Symbol for formats that do not carry one per row
+    pub symbol: Option<String>,
+    /// Trading date (YYYY-MM-DD) for formats that do not carry one per row
+    pub date: Option<chrono::NaiveDate>,
+    /// Force a parser by name instead of detecting the format
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BrokerParseResponse {
+    pub parser: &'static str,
+    pub row_count: usize,
+    pub rows: Vec<BrokerSummary>,
+}
+
+fn broker_parse_error(e: DataSourceError) -> (axum::http::StatusCode, String) {
+    match e {
+        DataSourceError::UnsupportedFormat(_) => (
+            axum::http::StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            e.to_string(),
+        ),
+        _ => (axum::http::StatusCode::UNPROCESSABLE_ENTITY, e.to_string()),
+    }
+}
+
+async fn list_broker_parsers(
+    _user: AuthUser,
+) -> Result<Json<BrokerParsersResponse>, (axum::http::StatusCode, String)> {
+    Ok(Json(BrokerParsersResponse {
+        parsers: BrokerParserRegistry::with_defaults().names(),
+    }))
+}
+
+/// Parse an uploaded broker export without persisting it
+async fn parse_broker_data(
+    _user: AuthUser,
+    Query(query): Query<BrokerParseQuery>,
+    body: Bytes,
+) -> Result<Json<BrokerParseResponse>, (axum::http::StatusCode, String)> {
+    if body.is_empty() {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            "Request body is empty".to_string(),
+        ));
+    }
+
+    let body = to_csv_bytes(&body).map_err(broker_parse_error)?;
+    let registry = BrokerParserRegistry::with_defaults();
+    let ctx = BrokerParseContext {
+        symbol: query.symbol.map(|s| s.to_uppercase()),
+        date: query.date,
+    };
+
+    let (parser, rows) = match query.format.as_deref() {
+        Some(name) => {
+            let parser = registry.get(name).ok_or_else(|| {
+                (
+                    axum::http::StatusCode::BAD_REQUEST,
+                    format!("Unknown broker parser: {}", name),
+                )
+            })?;
+            let rows = parser.parse(&body, &ctx).map_err(broker_parse_error)?;
+            (parser.name(), rows)
+        }
+        None => registry.parse(&body, &ctx).map_err(broker_parse_error)?,
+    };
+
+    Ok(Json(BrokerParseResponse {
+        parser,
+        row_count: rows.len(),
+        rows,
+    }))
+}
+
+// ============================================================================
+// SLA Tracking
+// ============================================================================
+
+/// Consecutive failed runs after which a source is considered in breach
+const MAX_CONSECUTIVE_FAILURES: i32 = 3;
+
+fn is_sla_breached(
+    freshness_hours: Option<i64>,
+    threshold_hours: i64,
+    consecutive_failures: i32,
+) -> bool {
+    let stale = freshness_hours.is_none_or(|hours| hours > threshold_hours);
+    stale || consecutive_failures >= MAX_CONSECUTIVE_FAILURES
+}
+
+async fn build_sla_statuses(
+    pool: &sqlx::PgPool,
+) -> Result<Vec<SourceSlaStatus>, (axum::http::StatusCode, String)> {
+    let sla_rows: HashMap<String, jejakcuan_db::DataSourceSlaRow> =
+        jejakcuan_db::get_data_source_sla(pool)
+            .await
+            .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+            .into_iter()
+            .map(|row| (row.source_id.clone(), row))
+            .collect();
+
+    let mut statuses = Vec::new();
+    for definition in get_data_source_registry() {
+        if !get_config_status(&definition).is_configured {
+            continue;
+        }
+
+        let sla = sla_rows.get(definition.id);
+        let last_update = match definition.db_table {
+            Some(table) => get_table_stats(pool, table).await.unwrap_or((None, 0)).0,
+            None => sla.and_then(|row| row.last_success_at),
+        };
+        let freshness_hours = last_update.map(|ts| (Utc::now() - ts).num_hours());
+        let consecutive_failures = sla.map_or(0, |row| row.consecutive_failures);
+
+        statuses.push(SourceSlaStatus {
+            source_id: definition.id.to_string(),
+            source_name: definition.name.to_string(),
+            freshness_threshold_hours: definition.freshness_threshold_hours,
+            last_update,
+            freshness_hours,
+            last_success_at: sla.and_then(|row| row.last_success_at),
+            last_failure_at: sla.and_then(|row| row.last_failure_at),
+            last_error: sla.and_then(|row| row.last_error.clone()),
+            consecutive_failures,
+            total_runs: sla.map_or(0, |row| row.total_runs),
+            avg_duration_secs: sla.and_then(|row| row.avg_duration_secs),
+            breached: is_sla_breached(
+                freshness_hours,
+                definition.freshness_threshold_hours,
+                consecutive_failures,
+            ),
+            last_breach_notified_at: sla.and_then(|row| row.last_breach_notified_at),
+        });
+    }
+
+    Ok(statuses)
+}
+
+async fn get_sla_report(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<SlaReportResponse>, (axum::http::StatusCode, String)> {
+    let sources = build_sla_statuses(&state.db).await?;
+    let breached_count = sources.iter().filter(|s| s.breached).count();
+
+    Ok(Json(SlaReportResponse {
+        timestamp: Utc::now(),
+        breached_count,
+        sources,
     }))
 }
+
+/// Run the alert scan now, without waiting for new data
+async fn run_alert_scan(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<ScanReport>, (axum::http::StatusCode, String)> {
+    scan(&state)
+        .await
+        .map(Json)
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// Start a job upserting every IDX-listed stock
+async fn sync_stock_universe(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Job>, (axum::http::StatusCode, String)> {
+    if let Some(job) = state.job_manager.is_source_running(UNIVERSE_SYNC_JOB).await {
+        return Err((
+            axum::http::StatusCode::CONFLICT,
+            format!("Stock universe sync already running (job {})", job.id),
+        ));
+    }
+    Ok(Json(stock_universe::spawn_sync(&state).await))
+}
+
+/// Start a job fetching the news feeds
+async fn ingest_news(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Job>, (axum::http::StatusCode, String)> {
+    if let Some(job) = state.job_manager.is_source_running(NEWS_INGEST_JOB).await {
+        return Err((
+            axum::http::StatusCode::CONFLICT,
+            format!("News ingestion already running (job {})", job.id),
+        ));
+    }
+    Ok(Json(news::spawn_ingest(&state).await))
+}
+
+/// Start a job fetching corporate actions
+async fn ingest_corporate_actions(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Job>, (axum::http::StatusCode, String)> {
+    if let Some(job) = state
+        .job_manager
+        .is_source_running(CORPORATE_ACTIONS_JOB)
+        .await
+    {
+        return Err((
+            axum::http::StatusCode::CONFLICT,
+            format!(
+                "Corporate action ingestion already running (job {})",
+                job.id
+            ),
+        ));
+    }
+    Ok(Json(corporate_actions::spawn_ingest(&state).await))
+}
+
+/// Start a job staging major shareholders for review
+async fn ingest_shareholdings(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Job>, (axum::http::StatusCode, String)> {
+    if let Some(job) = state.job_manager.is_source_running(SHAREHOLDINGS_JOB).await {
+        return Err((
+            axum::http::StatusCode::CONFLICT,
+            format!("Shareholding ingestion already running (job {})", job.id),
+        ));
+    }
+    Ok(Json(shareholdings::spawn_ingest(&state).await))
+}
+
+/// Start a job syncing the financial report calendar
+async fn sync_earnings(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Job>, (axum::http::StatusCode, String)> {
+    if let Some(job) = state
+        .job_manager
+        .is_source_running(EARNINGS_CALENDAR_JOB)
+        .await
+    {
+        return Err((
+            axum::http::StatusCode::CONFLICT,
+            format!("Report calendar sync already running (job {})", job.id),
+        ));
+    }
+    Ok(Json(earnings::spawn_sync(&state).await))
+}
+
+/// Start every maintenance task that is not already running
+async fn run_maintenance(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<JobsListResponse>, (axum::http::StatusCode, String)> {
+    let jobs = maintenance::start_all(&state).await;
+    let count = jobs.len();
+    Ok(Json(JobsListResponse { jobs, count }))
+}
+
+async fn run_maintenance_task(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(task): Path<String>,
+) -> Result<Json<Job>, (axum::http::StatusCode, String)> {
+    let task = MaintenanceTask::from_str_opt(&task).ok_or_else(|| {
+        (
+            axum::http::StatusCode::NOT_FOUND,
+            format!(
+                "Unknown maintenance task '{}', expected one of: {}",
+                task,
+                MaintenanceTask::ALL.map(|t| t.as_str()).join(", ")
+            ),
+        )
+    })?;
+
+    maintenance::start(&state, task)
+        .await
+        .map(Json)
+        .map_err(|job| {
+            (
+                axum::http::StatusCode::CONFLICT,
+                format!(
+                    "Maintenance {} already running (job {})",
+                    task.as_str(),
+                    job.id
+                ),
+            )
+        })
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct BackfillRequest {
+    /// Resume this job instead of creating one; the other fields are ignored
+    pub job_id: Option<i64>,
+    /// TwelveData intervals, 1h and 15min by default
+    pub intervals: Option<Vec<String>>,
+    pub start_date: Option<chrono::NaiveDate>,
+    pub end_date: Option<chrono::NaiveDate>,
+    /// Request pace; defaults to 8 per pooled key
+    pub credits_per_minute: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BackfillResponse {
+    pub backfill: BackfillJobRow,
+    pub job: Job,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BackfillJobsResponse {
+    pub jobs: Vec<BackfillJobRow>,
+    pub count: usize,
+}
+
+fn backfill_error(e: BackfillError) -> (axum::http::StatusCode, String) {
+    match e {
+        BackfillError::Running(job) => (
+            axum::http::StatusCode::CONFLICT,
+            format!("A backfill is already running (job {})", job.id),
+        ),
+        BackfillError::NotFound(id) => (
+            axum::http::StatusCode::NOT_FOUND,
+            format!("No TwelveData backfill job {}", id),
+        ),
+        BackfillError::Completed(id) => (
+            axum::http::StatusCode::CONFLICT,
+            format!("Backfill job {} has nothing left to fetch", id),
+        ),
+        BackfillError::Invalid(message) => (axum::http::StatusCode::BAD_REQUEST, message),
+        BackfillError::Unavailable(message) => {
+            (axum::http::StatusCode::SERVICE_UNAVAILABLE, message)
+        }
+        BackfillError::Database(e) => {
+            (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+        }
+    }
+}
+
+/// Start a historical intraday backfill for all active symbols, or resume one
+async fn start_backfill(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    body: Option<Json<BackfillRequest>>,
+) -> Result<Json<BackfillResponse>, (axum::http::StatusCode, String)> {
+    let request = body.map(|Json(request)| request).unwrap_or_default();
+    let (backfill, job) = match request.job_id {
+        Some(job_id) => backfill::resume(&state, job_id, request.credits_per_minute).await,
+        None => {
+            let plan = BackfillPlan::new(
+                request.intervals.as_deref(),
+                request.start_date,
+                request.end_date,
+                backfill::today(),
+            )
+            .map_err(|e| (axum::http::StatusCode::BAD_REQUEST, e))?;
+            backfill::start(&state, plan, request.credits_per_minute).await
+        }
+    }
+    .map_err(backfill_error)?;
+    Ok(Json(BackfillResponse { backfill, job }))
+}
+
+/// Recent TwelveData backfill jobs with their progress, newest first
+async fn list_backfill_jobs(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<BackfillJobsResponse>, (axum::http::StatusCode, String)> {
+    let jobs =
+        repositories::backfill_jobs::list_backfill_jobs(&state.db, backfill::BACKFILL_SOURCE, 20)
+            .await
+            .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let count = jobs.len();
+    Ok(Json(BackfillJobsResponse { jobs, count }))
+}
+
+async fn get_backfill_job(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(job_id): Path<i64>,
+) -> Result<Json<BackfillJobRow>, (axum::http::StatusCode, String)> {
+    repositories::backfill_jobs::get_backfill_job(&state.db, job_id)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .filter(|job| job.source == backfill::BACKFILL_SOURCE)
+        .map(Json)
+        .ok_or_else(|| backfill_error(BackfillError::NotFound(job_id)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FailedNotificationsQuery {
+    /// "dead" (default) for deliveries given up on, "pending" for queued retries
+    pub status: Option<String>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FailedNotificationsResponse {
+    pub status: String,
+    pub notifications: Vec<jejakcuan_db::NotificationRetryRow>,
+    pub count: usize,
+}
+
+/// Failed notification deliveries, most recently attempted first
+///
+/// Webhook recipients are redacted, since their URLs are credentials.
+async fn list_failed_notifications(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<FailedNotificationsQuery>,
+) -> Result<Json<FailedNotificationsResponse>, (axum::http::StatusCode, String)> {
+    let status = query
+        .status
+        .unwrap_or_else(|| notification_retry::STATUS_DEAD.to_string());
+    if status != notification_retry::STATUS_DEAD && status != notification_retry::STATUS_PENDING {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            format!(
+                "Unknown status '{}', expected '{}' or '{}'",
+                status,
+                notification_retry::STATUS_DEAD,
+                notification_retry::STATUS_PENDING
+            ),
+        ));
+    }
+    let limit = query.limit.unwrap_or(100).clamp(1, 500);
+
+    let notifications = jejakcuan_db::repositories::notification_retries::get_notification_retries(
+        &state.db, &status, limit,
+    )
+    .await
+    .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .into_iter()
+    .map(notification_retry::redact_retry)
+    .collect::<Vec<_>>();
+    let count = notifications.len();
+    Ok(Json(FailedNotificationsResponse {
+        status,
+        notifications,
+        count,
+    }))
+}
+
+#[derive(Debug, Serialize)]
+pub struct RetryNotificationResponse {
+    pub id: i32,
+    pub delivered: bool,
+    /// Queue status after the attempt; the row is removed once delivered
+    pub status: Option<String>,
+    pub last_error: Option<String>,
+}
+
+/// Attempt a failed delivery again now
+async fn retry_failed_notification(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<Json<RetryNotificationResponse>, (axum::http::StatusCode, String)> {
+    let internal = |e: sqlx::Error| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string());
+    let row =
+        jejakcuan_db::repositories::notification_retries::get_notification_retry(&state.db, id)
+            .await
+            .map_err(internal)?
+            .ok_or_else(|| {
+                (
+                    axum::http::StatusCode::NOT_FOUND,
+                    format!("Failed notification {} not found", id),
+                )
+            })?;
+
+    let delivered = notification_retry::attempt(&state, &row)
+        .await
+        .map_err(internal)?
+        == notification_retry::RetryOutcome::Delivered;
+    let after = if delivered {
+        None
+    } else {
+        jejakcuan_db::repositories::notification_retries::get_notification_retry(&state.db, id)
+            .await
+            .map_err(internal)?
+            .map(notification_retry::redact_retry)
+    };
+
+    Ok(Json(RetryNotificationResponse {
+        id,
+        delivered,
+        status: after.as_ref().map(|r| r.status.clone()),
+        last_error: after.map(|r| r.last_error),
+    }))
+}
+
+/// Recent jobs checked for the overview
+const OVERVIEW_JOB_LIMIT: usize = 100;
+
+/// System KPIs for the ops dashboard
+#[derive(Debug, Serialize)]
+pub struct AdminOverview {
+    pub generated_at: DateTime<Utc>,
+    pub requests: RequestSummary,
+    pub analysis_cache: CacheStats,
+    pub price_store: PriceStoreStats,
+    /// Price stream ingest; all zero while the stream is off
+    pub tick_queue: TickQueueStats,
+    pub jobs: JobStatusCounts,
+    pub data_sources: DataFreshnessSummary,
+    /// Alerts triggered since 00:00 UTC
+    pub alerts_today: i64,
+}
+
+/// Recent background jobs by status
+#[derive(Debug, Default, Serialize, PartialEq)]
+pub struct JobStatusCounts {
+    pub pending: usize,
+    pub running: usize,
+    pub completed: usize,
+    pub failed: usize,
+}
+
+impl JobStatusCounts {
+    fn from_jobs(jobs: &[Job]) -> Self {
+        let mut counts = Self::default();
+        for job in jobs {
+            match job.status {
+                JobStatus::Pending => counts.pending += 1,
+                JobStatus::Running => counts.running += 1,
+                JobStatus::Completed => counts.completed += 1,
+                JobStatus::Failed => counts.failed += 1,
+            }
+        }
+        counts
+    }
+}
+
+/// Freshness of the configured data sources
+#[derive(Debug, Serialize)]
+pub struct DataFreshnessSummary {
+    pub configured: usize,
+    pub breached: usize,
+    pub sources: Vec<SourceFreshness>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SourceFreshness {
+    pub source_id: String,
+    pub freshness_hours: Option<i64>,
+    pub freshness_threshold_hours: i64,
+    pub breached: bool,
+}
+
+/// Full-analysis stage histograms in the Prometheus text format
+async fn get_metrics(_user: AuthUser, State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        state.analysis_timings.prometheus(),
+    )
+}
+
+async fn get_overview(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<AdminOverview>, (axum::http::StatusCode, String)> {
+    let now = Utc::now();
+    let start_of_day = now
+        .date_naive()
+        .and_hms_opt(0, 0, 0)
+        .expect("valid midnight")
+        .and_utc();
+
+    let sources = build_sla_statuses(&state.db).await?;
+    let alerts_today = jejakcuan_db::count_alerts_triggered_since(&state.db, start_of_day)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let jobs = state.job_manager.get_recent_jobs(OVERVIEW_JOB_LIMIT).await;
+
+    Ok(Json(AdminOverview {
+        generated_at: now,
+        requests: state.request_metrics.summary(now),
+        analysis_cache: state.analysis_cache.stats(),
+        price_store: state.price_store.stats(),
+        tick_queue: state.tick_queue.stats(),
+        jobs: JobStatusCounts::from_jobs(&jobs),
+        data_sources: DataFreshnessSummary {
+            configured: sources.len(),
+            breached: sources.iter().filter(|s| s.breached).count(),
+            sources: sources
+                .into_iter()
+                .map(|s| SourceFreshness {
+                    source_id: s.source_id,
+                    freshness_hours: s.freshness_hours,
+                    freshness_threshold_hours: s.freshness_threshold_hours,
+                    breached: s.breached,
+                })
+                .collect(),
+        },
+        alerts_today,
+    }))
+}
+
+/// Admin recipients for operator alerts, one per configured channel
+pub(crate) fn admin_recipients(state: &AppState) -> Vec<(NotificationChannel, String)> {
+    let mut recipients = Vec::new();
+    if let Some(ref chat_id) = state.config.admin_telegram_chat_id {
+        recipients.push((NotificationChannel::Telegram, chat_id.clone()));
+    }
+    if let Some(ref url) = state.config.admin_webhook_url {
+        recipients.push((NotificationChannel::Webhook, url.clone()));
+    }
+    if let Some(ref url) = state.config.admin_discord_webhook_url {
+        recipients.push((NotificationChannel::Discord, url.clone()));
+    }
+    if let Some(ref url) = state.config.admin_slack_webhook_url {
+        recipients.push((NotificationChannel::Slack, url.clone()));
+    }
+    if let Some(ref number) = state.config.admin_whatsapp_number {
+        recipients.push((NotificationChannel::WhatsApp, number.clone()));
+    }
+    recipients
+}
+
+fn sla_breach_notification(
+    status: &SourceSlaStatus,
+    channel: NotificationChannel,
+    recipient_id: String,
+) -> Notification {
+    let freshness = status
+        .freshness_hours
+        .map(|h| format!("last updated {}h ago", h))
+        .unwrap_or_else(|| "no data".to_string());
+
+    Notification {
+        recipient_id,
+        title: format!("Data source SLA breach: {}", status.source_name),
+        body: format!(
+            "{} ({}), threshold {}h, {} consecutive failures",
+            status.source_id,
+            freshness,
+            status.freshness_threshold_hours,
+            status.consecutive_failures
+        ),
+        priority: if status.consecutive_failures >= MAX_CONSECUTIVE_FAILURES {
+            NotificationPriority::Critical
+        } else {
+            NotificationPriority::High
+        },
+        channel,
+        alert: None,
+        metadata: NotificationMetadata {
+            action_url: Some(format!("/admin/data-sources/{}", status.source_id)),
+            ..Default::default()
+        },
+    }
+}
+
+/// Evaluate SLAs and notify admins about newly breached sources
+async fn check_sla_breaches(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<SlaCheckResponse>, (axum::http::StatusCode, String)> {
+    run_sla_check(&state).await.map(Json)
+}
+
+/// Notify admins about breached sources, also run by the alert scheduler
+///
+/// A breached source is re-notified at most once per freshness window.
+pub(crate) async fn run_sla_check(
+    state: &AppState,
+) -> Result<SlaCheckResponse, (axum::http::StatusCode, String)> {
+    let pool = &state.db;
+    let breached: Vec<SourceSlaStatus> = build_sla_statuses(pool)
+        .await?
+        .into_iter()
+        .filter(|s| s.breached)
+        .collect();
+
+    let recipients = admin_recipients(state);
+    let mut notified = Vec::new();
+    let mut notification_errors = Vec::new();
+
+    for status in &breached {
+        let recently_notified = status
+            .last_breach_notified_at
+            .is_some_and(|ts| (Utc::now() - ts).num_hours() < status.freshness_threshold_hours);
+        if recently_notified || recipients.is_empty() {
+            continue;
+        }
+
+        let mut delivered = false;
+        for (channel, recipient_id) in &recipients {
+            let notification =
+                sla_breach_notification(status, channel.clone(), recipient_id.clone());
+            match notification_retry::deliver(state, &notification).await {
+                Ok(()) => delivered = true,
+                Err(e) => notification_errors.push(format!("{}: {}", status.source_id, e)),
+            }
+        }
+
+        if delivered {
+            jejakcuan_db::mark_sla_breach_notified(pool, &status.source_id)
+                .await
+                .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+            notified.push(status.source_id.clone());
+        }
+    }
+
+    Ok(SlaCheckResponse {
+        timestamp: Utc::now(),
+        breached,
+        notified,
+        notification_errors,
+    })
+}
+
+async fn get_quota_report(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+) -> Json<QuotaReportResponse> {
+    let keys = state.api_usage.forecasts();
+    Json(QuotaReportResponse {
+        timestamp: Utc::now(),
+        warning_count: keys.iter().filter(|k| k.warning).count(),
+        keys,
+    })
+}
+
+fn quota_warning_notification(
+    forecast: &QuotaForecast,
+    channel: NotificationChannel,
+    recipient_id: String,
+) -> Notification {
+    let outlook = match forecast.projected_exhaustion {
+        Some(at) => format!("projected to run out at {} UTC", at.format("%H:%M")),
+        None => "running low".to_string(),
+    };
+    let remaining = forecast
+        .credits_remaining
+        .map(|r| format!("{} credits left", r))
+        .unwrap_or_else(|| "quota unknown".to_string());
+
+    Notification {
+        recipient_id,
+        title: format!("API quota warning: {}", forecast.provider.as_str()),
+        body: format!(
+            "Key {} has used {} credits today ({}), {} at {:.0} credits/hour",
+            forecast.key_id, forecast.credits_used, remaining, outlook, forecast.credits_per_hour
+        ),
+        priority: if forecast.exhausts_mid_session {
+            NotificationPriority::Critical
+        } else {
+            NotificationPriority::High
+        },
+        channel,
+        alert: None,
+        metadata: NotificationMetadata {
+            action_url: Some("/admin/data-sources".to_string()),
+            ..Default::default()
+        },
+    }
+}
+
+/// Notify admins about keys forecast to run out of quota
+async fn check_quota_warnings(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+) -> Json<QuotaCheckResponse> {
+    Json(run_quota_check(&state).await)
+}
+
+/// Warn admins about keys running low, also run by the alert scheduler
+///
+/// Each key is warned at most once per UTC day.
+pub(crate) async fn run_quota_check(state: &AppState) -> QuotaCheckResponse {
+    let warnings: Vec<QuotaForecast> = state
+        .api_usage
+        .forecasts()
+        .into_iter()
+        .filter(|f| f.warning)
+        .collect();
+
+    let recipients = admin_recipients(state);
+    let mut notified = Vec::new();
+    let mut notification_errors = Vec::new();
+
+    for forecast in &warnings {
+        if recipients.is_empty()
+            || !state
+                .api_usage
+                .mark_warned(forecast.provider, &forecast.key_id)
+        {
+            continue;
+        }
+
+        let key = format!("{}:{}", forecast.provider.as_str(), forecast.key_id);
+        for (channel, recipient_id) in &recipients {
+            let notification =
+                quota_warning_notification(forecast, channel.clone(), recipient_id.clone());
+            if let Err(e) = notification_retry::deliver(state, &notification).await {
+                notification_errors.push(format!("{}: {}", key, e));
+            }
+        }
+        notified.push(key);
+    }
+
+    QuotaCheckResponse {
+        timestamp: Utc::now(),
+        warnings,
+        notified,
+        notification_errors,
+    }
+}
+
+// ============================================================================
+// Usage Analytics
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct UsageAnalyticsQuery {
+    /// Days to summarize (default 30)
+    pub days: Option<i64>,
+    /// Top subjects per kind (default 10)
+    pub limit: Option<i64>,
+}
+
+/// What users look at, for feature prioritization
+#[derive(Debug, Serialize)]
+pub struct UsageAnalyticsResponse {
+    pub since: DateTime<Utc>,
+    /// Anonymization applied when events are recorded
+    pub privacy: &'static str,
+    /// Events per kind over the window
+    pub totals: BTreeMap<String, i64>,
+    /// Distinct known users; anonymous events are not counted
+    pub distinct_actors: i64,
+    /// Busiest symbols, screen conditions and alert kinds, per kind
+    pub top: BTreeMap<String, Vec<UsageSubjectCountRow>>,
+    pub daily: Vec<UsageDailyCountRow>,
+}
+
+async fn get_usage_analytics(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<UsageAnalyticsQuery>,
+) -> Result<Json<UsageAnalyticsResponse>, (axum::http::StatusCode, String)> {
+    let days = query.days.unwrap_or(30).clamp(1, 365);
+    let limit = query.limit.unwrap_or(10).clamp(1, 100);
+    let since = Utc::now() - chrono::Duration::days(days);
+    let internal = |e: sqlx::Error| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string());
+
+    let daily = repositories::usage_events::get_daily_usage_counts(&state.db, since)
+        .await
+        .map_err(internal)?;
+    let subjects = repositories::usage_events::get_top_usage_subjects(&state.db, since, limit)
+        .await
+        .map_err(internal)?;
+    let distinct_actors = repositories::usage_events::count_usage_actors(&state.db, since)
+        .await
+        .map_err(internal)?;
+
+    let mut totals = BTreeMap::new();
+    for row in &daily {
+        *totals.entry(row.kind.clone()).or_insert(0) += row.events;
+    }
+    let mut top: BTreeMap<String, Vec<UsageSubjectCountRow>> = BTreeMap::new();
+    for row in subjects {
+        top.entry(row.kind.clone()).or_default().push(row);
+    }
+
+    Ok(Json(UsageAnalyticsResponse {
+        since,
+        privacy: state.usage.config().privacy.as_str(),
+        totals,
+        distinct_actors,
+        top,
+        daily,
+    }))
+}
+
+// ============================================================================
+// Encrypted Secret Storage
+// ============================================================================
+
+/// Audit resource for stored secret changes
+const SECRET_HISTORY_RESOURCE: &str = "stored_secret";
+
+/// Names that may be stored: secret data-source fields and notifier tokens
+fn storable_secret_names() -> HashSet<String> {
+    get_data_source_registry()
+        .iter()
+        .flat_map(|d| d.config_fields.iter())
+        .filter(|field| field.is_secret)
+        .map(|field| field.env_var.clone())
+        .chain(NOTIFIER_SECRETS.iter().map(|name| name.to_string()))
+        .collect()
+}
+
+fn secret_cipher(state: &AppState) -> Result<&SecretCipher, (axum::http::StatusCode, String)> {
+    state.config.secret_cipher.as_ref().ok_or_else(|| {
+        (
+            axum::http::StatusCode::SERVICE_UNAVAILABLE,
+            "Stored secrets are disabled: SECRETS_MASTER_KEY is not set".to_string(),
+        )
+    })
+}
+
+fn secret_error(e: SecretError) -> (axum::http::StatusCode, String) {
+    (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+}
+
+/// A stored secret without its value
+#[derive(Debug, Serialize)]
+pub struct StoredSecretInfo {
+    pub name: String,
+    pub key_id: String,
+    /// Sealed with a retired master key; cleared by rotation
+    pub needs_rotation: bool,
+    /// Also set in the environment, which takes precedence
+    pub overridden_by_env: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl StoredSecretInfo {
+    fn new(row: &StoredSecretRow, current_key_id: Option<&str>) -> Self {
+        Self {
+            name: row.name.clone(),
+            key_id: row.key_id.clone(),
+            needs_rotation: current_key_id.is_some_and(|id| id != row.key_id),
+            overridden_by_env: check_env_var_configured(&row.name),
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct StoredSecretsResponse {
+    /// False without a master key; stored secrets are then unreadable
+    pub enabled: bool,
+    pub current_key_id: Option<String>,
+    pub storable: Vec<String>,
+    pub secrets: Vec<StoredSecretInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PutSecretRequest {
+    pub value: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RotateSecretsResponse {
+    pub current_key_id: String,
+    pub rotated: u64,
+}
+
+/// Stored secrets, names and key ids only
+async fn list_stored_secrets(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<StoredSecretsResponse>, (axum::http::StatusCode, String)> {
+    let rows = jejakcuan_db::get_stored_secrets(&state.db)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let current_key_id = state
+        .config
+        .secret_cipher
+        .as_ref()
+        .map(|c| c.current_key_id().to_string());
+    let mut storable: Vec<String> = storable_secret_names().into_iter().collect();
+    storable.sort();
+
+    Ok(Json(StoredSecretsResponse {
+        enabled: current_key_id.is_some(),
+        secrets: rows
+            .iter()
+            .map(|row| StoredSecretInfo::new(row, current_key_id.as_deref()))
+            .collect(),
+        current_key_id,
+        storable,
+    }))
+}
+
+/// Encrypt and store a credential
+async fn put_stored_secret(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+    Json(req): Json<PutSecretRequest>,
+) -> Result<Json<StoredSecretInfo>, (axum::http::StatusCode, String)> {
+    if !storable_secret_names().contains(&name) {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            format!("{} is not a storable secret", name),
+        ));
+    }
+    if req.value.trim().is_empty() {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            "Secret value must not be empty".to_string(),
+        ));
+    }
+    let cipher = secret_cipher(&state)?;
+
+    let row = jejakcuan_db::put_secret(&state.db, cipher, &name, req.value.trim())
+        .await
+        .map_err(secret_error)?;
+    let info = StoredSecretInfo::new(&row, Some(cipher.current_key_id()));
+    record_change(
+        &state,
+        &user.username,
+        "secret_set",
+        SECRET_HISTORY_RESOURCE,
+        &name,
+        None,
+        Some(&row),
+    )
+    .await;
+    Ok(Json(info))
+}
+
+/// Remove a stored credential
+async fn delete_stored_secret(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> Result<axum::http::StatusCode, (axum::http::StatusCode, String)> {
+    let deleted = jejakcuan_db::delete_secret(&state.db, &name)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if !deleted {
+        return Err((
+            axum::http::StatusCode::NOT_FOUND,
+            format!("No stored secret {}", name),
+        ));
+    }
+    record_change::<StoredSecretRow>(
+        &state,
+        &user.username,
+        "secret_delete",
+        SECRET_HISTORY_RESOURCE,
+        &name,
+        None,
+        None,
+    )
+    .await;
+    Ok(axum::http::StatusCode::NO_CONTENT)
+}
+
+/// Re-encrypt secrets sealed with a retired master key
+async fn rotate_stored_secrets(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<RotateSecretsResponse>, (axum::http::StatusCode, String)> {
+    let cipher = secret_cipher(&state)?;
+    let rotated = jejakcuan_db::rotate_secrets(&state.db, cipher)
+        .await
+        .map_err(secret_error)?;
+    let response = RotateSecretsResponse {
+        current_key_id: cipher.current_key_id().to_string(),
+        rotated,
+    };
+    record_change(
+        &state,
+        &user.username,
+        "secret_rotate",
+        SECRET_HISTORY_RESOURCE,
+        cipher.current_key_id(),
+        None,
+        Some(&response),
+    )
+    .await;
+    Ok(Json(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_storable_secrets_are_secret_fields_and_notifier_tokens() {
+        let names = storable_secret_names();
+        assert!(names.contains("TWELVEDATA_API_KEY"));
+        assert!(names.contains("SECTORS_API_KEY"));
+        assert!(names.contains("TELEGRAM_BOT_TOKEN"));
+        assert!(!names.contains("DATABASE_URL"));
+    }
+
+    #[test]
+    fn test_job_status_counts() {
+        let job = |status| Job {
+            id: "job".to_string(),
+            source_id: "prices".to_string(),
+            source_name: "Prices".to_string(),
+            command: "fetch".to_string(),
+            status,
+            message: None,
+            output: None,
+            started_at: Utc::now(),
+            completed_at: None,
+            duration_secs: None,
+            progress: None,
+        };
+        let jobs = vec![
+            job(JobStatus::Running),
+            job(JobStatus::Completed),
+            job(JobStatus::Completed),
+            job(JobStatus::Failed),
+        ];
+        assert_eq!(
+            JobStatusCounts::from_jobs(&jobs),
+            JobStatusCounts {
+                pending: 0,
+                running: 1,
+                completed: 2,
+                failed: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_sla_breach_on_stale_data() {
+        assert!(!is_sla_breached(Some(12), 24, 0));
+        assert!(is_sla_breached(Some(30), 24, 0));
+        assert!(is_sla_breached(None, 24, 0));
+    }
+
+    #[test]
+    fn test_parse_lookback_days() {
+        assert_eq!(
+            parse_lookback_days("python -m jejakcuan_ml.scrapers.cli broker --days 30"),
+            Some(30)
+        );
+        assert_eq!(
+            parse_lookback_days("python -m jejakcuan_ml.scrapers.cli idx"),
+            None
+        );
+        assert_eq!(parse_lookback_days("cli --days"), None);
+    }
+
+    #[test]
+    fn test_sla_breach_on_consecutive_failures() {
+        assert!(!is_sla_breached(Some(1), 24, MAX_CONSECUTIVE_FAILURES - 1));
+        assert!(is_sla_breached(Some(1), 24, MAX_CONSECUTIVE_FAILURES));
+    }
+}
@@ -0,0 +1,1080 @@
+//! Alert routes
+//!
+//! Every alert fired by the scheduler is kept in the history, which can be
+//! filtered by symbol, time and priority and acknowledged once reviewed.
+//!
+//! Users write their own alert rules as condition expressions over computed
+//! indicators, e.g. `rsi < 30 AND composite_score > 70 AND foreign_net_5_day > 0`.
+//! Rules are validated when saved and can be evaluated against a symbol on
+//! demand to see which metrics they would read. While authoring, a draft rule
+//! can be simulated over the last 90 days to see how often, and for which
+//! symbols, it would have fired. Deleted rules can be restored
+//! until the retention job purges them, and every edit is recorded in the
+//! change history.
+
+use crate::auth::AuthUser;
+use crate::change_history::{history_limit, load_history, record_change};
+use crate::custom_indicators::{
+    attach_custom_metrics, load_custom_indicators, ohlcv_bars, rule_custom_indicators,
+    unknown_custom_indicators, CUSTOM_INDICATOR_DAYS,
+};
+use crate::retention::restore_cutoff;
+use crate::routes::analysis::{
+    calculate_institutional_flow_analysis, get_broker_flow_internal, get_technical_analysis,
+};
+use crate::usage::UsageKind;
+use crate::AppState;
+use axum::{
+    extract::{Path, Query, State},
+    routing::{delete, get, post, put},
+    Json, Router,
+};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use jejakcuan_audit::{ChangeHistoryQuery, ChangeRecord};
+use jejakcuan_core::{AlertPriority, AlertRule, RuleContext, CUSTOM_METRIC_PREFIX, RULE_METRICS};
+use jejakcuan_db::repositories::broker_summary::DailyBrokerSummaryRow;
+use jejakcuan_db::{
+    repositories, AlertHistoryFilter, AlertHistoryRow, AlertRuleRow, InsertAlertRule,
+    StockPriceRow, StockScoreRow,
+};
+use jejakcuan_technical::{
+    calculate_atr14, calculate_macd, calculate_rsi14, calculate_vwap, IndicatorExpr, OhlcvBar,
+};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+pub fn alert_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/", get(list_alerts))
+        .route("/:id/ack", post(acknowledge_alert))
+        .route("/:id/click", post(click_alert))
+        .route("/rules", get(list_rules))
+        .route("/rules", post(create_rule))
+        .route("/rules/metrics", get(list_metrics))
+        .route("/rules/simulate", post(simulate_rule))
+        .route("/rules/history", get(get_rules_history))
+        .route("/rules/:id", get(get_rule))
+        .route("/rules/:id", put(update_rule))
+        .route("/rules/:id", delete(delete_rule))
+        .route("/rules/:id/restore", post(restore_rule))
+        .route("/rules/:id/history", get(get_rule_history))
+        .route("/rules/:id/evaluate/:symbol", get(evaluate_rule))
+}
+
+/// Days of prices read for the technical metrics
+const RULE_TECHNICAL_DAYS: i32 = 90;
+
+/// Days of broker summaries read for the flow metrics
+const RULE_BROKER_DAYS: i32 = 30;
+
+/// Sessions averaged for relative volume
+const RVOL_PERIOD: usize = 20;
+
+/// Fewest bars the technical metrics are computed from
+const MIN_TECHNICAL_BARS: usize = 35;
+
+/// Days a rule simulation replays
+const SIMULATION_DAYS: i64 = 90;
+
+/// Most symbols one simulation evaluates
+const MAX_SIMULATION_SYMBOLS: usize = 50;
+
+/// Most firings listed in a simulation response, newest first
+const MAX_SIMULATED_FIRINGS: usize = 500;
+
+/// A rule firing on more than this share of symbol-sessions is flagged noisy
+const NOISY_FIRE_RATE: f64 = 0.2;
+
+/// Change history resource type of alert rules
+const HISTORY_RESOURCE: &str = "alert_rule";
+
+/// Alerts returned when the query sets no limit
+const DEFAULT_HISTORY_LIMIT: i64 = 100;
+
+/// Most alerts returned by one query
+const MAX_HISTORY_LIMIT: i64 = 1000;
+
+#[derive(Debug, Deserialize)]
+pub struct AlertHistoryQuery {
+    symbol: Option<String>,
+    since: Option<DateTime<Utc>>,
+    /// Minimum priority: 'high' returns high and critical alerts
+    priority: Option<String>,
+    acknowledged: Option<bool>,
+    limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AlertRuleRequest {
+    name: String,
+    expression: String,
+    /// Symbols to watch; omitted or empty means every watchlisted symbol
+    #[serde(default)]
+    symbols: Vec<String>,
+    /// 'critical', 'high', 'medium' (default) or 'low'
+    priority: Option<String>,
+    enabled: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RuleMetricInfo {
+    pub metric: &'static str,
+    pub description: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RuleEvaluationResponse {
+    pub rule_id: i32,
+    pub symbol: String,
+    pub matched: bool,
+    /// Values of the referenced metrics
+    pub values: BTreeMap<String, f64>,
+    /// Referenced metrics with no value for the symbol
+    pub missing_metrics: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RuleSimulationRequest {
+    expression: String,
+    /// Symbols to replay; omitted or empty means every watchlisted symbol
+    #[serde(default)]
+    symbols: Vec<String>,
+}
+
+/// A session on which the simulated rule matched
+#[derive(Debug, Serialize)]
+pub struct SimulatedFiring {
+    pub symbol: String,
+    pub date: NaiveDate,
+    /// Values of the referenced metrics that session
+    pub values: BTreeMap<String, f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SymbolFireCount {
+    pub symbol: String,
+    pub fired: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RuleSimulationResponse {
+    pub expression: String,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub symbols_evaluated: usize,
+    /// Symbol-sessions the rule was evaluated on
+    pub evaluations: usize,
+    /// Times the rule would have fired; alerts repeat at most daily
+    pub fired: usize,
+    /// Share of evaluations that fired
+    pub fire_rate: f64,
+    /// Whether the rule fires too often to be a useful alert
+    pub noisy: bool,
+    /// Symbols that fired, most firings first
+    pub by_symbol: Vec<SymbolFireCount>,
+    /// Firings, newest first, at most 500
+    pub firings: Vec<SimulatedFiring>,
+    /// Referenced metrics with no value on any session
+    pub missing_metrics: Vec<String>,
+}
+
+/// Priority names at or above `min`
+fn priorities_at_least(min: AlertPriority) -> Vec<String> {
+    [
+        AlertPriority::Critical,
+        AlertPriority::High,
+        AlertPriority::Medium,
+        AlertPriority::Low,
+    ]
+    .into_iter()
+    .filter(|p| p.at_least(min))
+    .map(|p| p.as_str().to_string())
+    .collect()
+}
+
+/// Parse a stored rule into the core model
+pub(crate) fn to_alert_rule(row: &AlertRuleRow) -> Result<AlertRule, String> {
+    let priority = AlertPriority::from_str_opt(&row.priority).unwrap_or(AlertPriority::Medium);
+    AlertRule::new(&row.name, &row.expression, row.symbols.clone(), priority)
+        .map_err(|e| e.to_string())
+}
+
+/// Custom indicators of `owner` that `rule` reads, rejecting undefined names
+async fn rule_custom_indicator_exprs(
+    state: &AppState,
+    owner: &str,
+    rule: &AlertRule,
+) -> Result<BTreeMap<String, IndicatorExpr>, (axum::http::StatusCode, String)> {
+    let names = rule_custom_indicators(rule);
+    if names.is_empty() {
+        return Ok(BTreeMap::new());
+    }
+    let mut indicators = load_custom_indicators(&state.db, owner)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let unknown = unknown_custom_indicators(&indicators, names.iter().copied());
+    if !unknown.is_empty() {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            format!("Unknown custom indicator: {}", unknown.join(", ")),
+        ));
+    }
+    indicators.retain(|name, _| names.contains(name.as_str()));
+    Ok(indicators)
+}
+
+/// Validate a request into a core rule, rejecting unknown metrics and symbols
+async fn validate_rule(
+    state: &AppState,
+    owner: &str,
+    req: &AlertRuleRequest,
+) -> Result<AlertRule, (axum::http::StatusCode, String)> {
+    let bad_request = |message: String| (axum::http::StatusCode::BAD_REQUEST, message);
+
+    let name = req.name.trim();
+    if name.is_empty() {
+        return Err(bad_request("Rule name is required".to_string()));
+    }
+    let priority = match req.priority.as_deref() {
+        None => AlertPriority::Medium,
+        Some(p) => AlertPriority::from_str_opt(p).ok_or_else(|| {
+            bad_request(format!(
+                "Unknown priority '{}', expected one of: critical, high, medium, low",
+                p
+            ))
+        })?,
+    };
+    let rule = AlertRule::new(name, req.expression.trim(), req.symbols.clone(), priority)
+        .map_err(|e| bad_request(format!("Invalid rule expression: {}", e)))?;
+    rule_custom_indicator_exprs(state, owner, &rule).await?;
+
+    for symbol in &rule.symbols {
+        repositories::stocks::get_stock_by_symbol(&state.db, symbol)
+            .await
+            .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+            .ok_or_else(|| bad_request(format!("Stock {} not found", symbol)))?;
+    }
+
+    Ok(rule)
+}
+
+/// Change from the previous close (%) and relative volume of the last bar
+pub(crate) fn price_metrics(prices: &[StockPriceRow]) -> (Option<Decimal>, Option<Decimal>) {
+    let Some((last, earlier)) = prices.split_last() else {
+        return (None, None);
+    };
+
+    let change_pct = earlier
+        .last()
+        .filter(|prev| !prev.close.is_zero())
+        .map(|prev| (last.close - prev.close) / prev.close * Decimal::from(100));
+
+    let window = &earlier[earlier.len().saturating_sub(RVOL_PERIOD)..];
+    let rvol = if window.len() < RVOL_PERIOD {
+        None
+    } else {
+        let average = window
+            .iter()
+            .map(|p| Decimal::from(p.volume))
+            .sum::<Decimal>()
+            / Decimal::from(window.len());
+        (!average.is_zero()).then(|| Decimal::from(last.volume) / average)
+    };
+
+    (change_pct, rvol)
+}
+
+/// Latest metric values of `symbol` for rule evaluation
+///
+/// Sections without enough data are left out, so rules reading them do not
+/// match.
+pub(crate) async fn build_rule_context(
+    state: &AppState,
+    symbol: &str,
+) -> Result<RuleContext, (axum::http::StatusCode, String)> {
+    let decimal = |value: f64| Decimal::try_from(value).ok();
+    let now = Utc::now();
+
+    let (technical, broker, score, prices) = tokio::join!(
+        get_technical_analysis(state, symbol, RULE_TECHNICAL_DAYS, false),
+        get_broker_flow_internal(state, symbol, RULE_BROKER_DAYS),
+        repositories::scores::get_stock_score(&state.db, symbol),
+        repositories::prices::get_price_history(&state.db, symbol, now - Duration::days(45), now),
+    );
+    let score =
+        score.map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let prices =
+        prices.map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let mut context = RuleContext::new();
+
+    let (change_pct, rvol) = price_metrics(&prices);
+    context.set_opt("close", prices.last().map(|p| p.close));
+    context.set_opt("change_pct", change_pct);
+    context.set_opt("rvol", rvol);
+
+    if let Ok(technical) = technical {
+        context.set_opt("rsi", decimal(technical.rsi));
+        context.set_opt("macd", decimal(technical.macd));
+        context.set_opt("macd_histogram", decimal(technical.macd_histogram));
+        context.set_opt("atr", technical.atr.and_then(decimal));
+        context.set_opt(
+            "price_vs_vwap_pct",
+            technical.price_vs_vwap_percent.and_then(decimal),
+        );
+    }
+
+    if let Some(score) = score {
+        context.set("composite_score", score.composite_score);
+        context.set("technical_score", score.technical_score);
+        context.set("fundamental_score", score.fundamental_score);
+        context.set("sentiment_score", score.sentiment_score);
+        context.set("ml_score", score.ml_score);
+    }
+
+    if let Some(flow) = broker.ok().and_then(|b| b.institutional_analysis) {
+        context.set_opt("accumulation_score", decimal(flow.accumulation_score));
+        context.set_opt("foreign_net_5_day", decimal(flow.foreign_net_5_day));
+        context.set_opt("foreign_net_20_day", decimal(flow.foreign_net_20_day));
+        context.set_opt(
+            "institutional_net_5_day",
+            decimal(flow.institutional_net_5_day),
+        );
+        context.set_opt(
+            "institutional_net_20_day",
+            decimal(flow.institutional_net_20_day),
+        );
+    }
+
+    Ok(context)
+}
+
+/// Metric values as of the last bar of `prices`, from data of that time
+///
+/// `prices` and `summaries` are the windows a live evaluation would read
+/// (`RULE_TECHNICAL_DAYS` and `RULE_BROKER_DAYS`) ending at that bar, and
+/// `score` the latest score computed by then.
+fn historical_rule_context(
+    prices: &[StockPriceRow],
+    summaries: &[DailyBrokerSummaryRow],
+    score: Option<&StockScoreRow>,
+) -> RuleContext {
+    let mut context = RuleContext::new();
+
+    let (change_pct, rvol) = price_metrics(prices);
+    context.set_opt("close", prices.last().map(|p| p.close));
+    context.set_opt("change_pct", change_pct);
+    context.set_opt("rvol", rvol);
+
+    if prices.len() >= MIN_TECHNICAL_BARS {
+        let closes: Vec<Decimal> = prices.iter().map(|p| p.close).collect();
+        let highs: Vec<Decimal> = prices.iter().map(|p| p.high).collect();
+        let lows: Vec<Decimal> = prices.iter().map(|p| p.low).collect();
+        let bars: Vec<OhlcvBar> = prices
+            .iter()
+            .map(|p| OhlcvBar {
+                open: p.open,
+                high: p.high,
+                low: p.low,
+                close: p.close,
+                volume: p.volume,
+            })
+            .collect();
+
+        context.set_opt(
+            "rsi",
+            calculate_rsi14(&closes).ok().and_then(|v| v.last_valid()),
+        );
+        if let Ok(macd) = calculate_macd(&closes) {
+            context.set_opt("macd", macd.macd_line.last_valid());
+            context.set_opt("macd_histogram", macd.histogram.last_valid());
+        }
+        context.set_opt(
+            "atr",
+            calculate_atr14(&highs, &lows, &closes)
+                .ok()
+                .and_then(|v| v.last().copied()),
+        );
+        let last = closes[closes.len() - 1];
+        context.set_opt(
+            "price_vs_vwap_pct",
+            calculate_vwap(&bars)
+                .ok()
+                .and_then(|v| v.last().copied())
+                .filter(|vwap| *vwap > Decimal::ZERO)
+                .map(|vwap| (last - vwap) / vwap * Decimal::from(100)),
+        );
+    }
+
+    if let Some(score) = score {
+        context.set("composite_score", score.composite_score);
+        context.set("technical_score", score.technical_score);
+        context.set("fundamental_score", score.fundamental_score);
+        context.set("sentiment_score", score.sentiment_score);
+        context.set("ml_score", score.ml_score);
+    }
+
+    if let Some(flow) = calculate_institutional_flow_analysis(&[], summaries) {
+        let decimal = |value: f64| Decimal::try_from(value).ok();
+        context.set_opt("accumulation_score", decimal(flow.accumulation_score));
+        context.set_opt("foreign_net_5_day", decimal(flow.foreign_net_5_day));
+        context.set_opt("foreign_net_20_day", decimal(flow.foreign_net_20_day));
+        context.set_opt(
+            "institutional_net_5_day",
+            decimal(flow.institutional_net_5_day),
+        );
+        context.set_opt(
+            "institutional_net_20_day",
+            decimal(flow.institutional_net_20_day),
+        );
+    }
+
+    context
+}
+
+/// Replay `rule` over each session of `symbol` since `from`
+///
+/// Returns the number of sessions evaluated, the firings, and the
+/// referenced metrics that had a value on at least one session.
+async fn simulate_symbol(
+    state: &AppState,
+    rule: &AlertRule,
+    custom: &BTreeMap<String, IndicatorExpr>,
+    symbol: &str,
+    from: DateTime<Utc>,
+) -> Result<(usize, Vec<SimulatedFiring>, BTreeSet<String>), sqlx::Error> {
+    let now = Utc::now();
+    let technical_days = Duration::days(RULE_TECHNICAL_DAYS as i64);
+    let broker_days = Duration::days(RULE_BROKER_DAYS as i64);
+    let history_days = if custom.is_empty() {
+        technical_days
+    } else {
+        technical_days.max(Duration::days(CUSTOM_INDICATOR_DAYS))
+    };
+
+    let (prices, summaries, scores) = tokio::join!(
+        repositories::prices::get_price_history(&state.db, symbol, from - history_days, now),
+        repositories::broker_summary::get_daily_broker_summaries(
+            &state.db,
+            symbol,
+            from - broker_days,
+            now,
+        ),
+        // Scores are recomputed at most daily; a month back covers gaps
+        repositories::scores::get_score_history(&state.db, symbol, from - Duration::days(30), now),
+    );
+    let (prices, summaries, scores) = (prices?, summaries?, scores?);
+    // Indicators only read earlier bars, so one pass serves every session
+    let bars = ohlcv_bars(&prices);
+    let custom_series: Vec<(String, Vec<Option<Decimal>>)> = custom
+        .iter()
+        .map(|(name, expr)| {
+            (
+                format!("{}{}", CUSTOM_METRIC_PREFIX, name),
+                expr.evaluate(&bars),
+            )
+        })
+        .collect();
+
+    let metrics = rule.condition.metrics();
+    let mut evaluated = 0;
+    let mut firings = Vec::new();
+    let mut seen_metrics = BTreeSet::new();
+
+    let first = prices.partition_point(|p| p.time < from);
+    for end in first..prices.len() {
+        let date = prices[end].time.date_naive();
+        let window_start = prices.partition_point(|p| p.time <= prices[end].time - technical_days);
+        let price_window = &prices[window_start..=end];
+        // Summaries are ordered by time
+        let summary_window = &summaries[summaries
+            .partition_point(|s| s.time.date_naive() <= date - broker_days)
+            ..summaries.partition_point(|s| s.time.date_naive() <= date)];
+        let score = scores.iter().rev().find(|s| s.time.date_naive() <= date);
+
+        let mut context = historical_rule_context(price_window, summary_window, score);
+        for (metric, series) in &custom_series {
+            context.set_opt(metric, series[end]);
+        }
+        evaluated += 1;
+        let values: BTreeMap<String, f64> = metrics
+            .iter()
+            .filter_map(|metric| Some((metric.to_string(), context.get(metric)?.to_f64()?)))
+            .collect();
+        seen_metrics.extend(values.keys().cloned());
+
+        if rule.evaluate(&context).matched {
+            firings.push(SimulatedFiring {
+                symbol: symbol.to_string(),
+                date,
+                values,
+            });
+        }
+    }
+
+    Ok((evaluated, firings, seen_metrics))
+}
+
+/// Replay a draft rule over the last 90 days to see how noisy it is
+async fn simulate_rule(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<RuleSimulationRequest>,
+) -> Result<Json<RuleSimulationResponse>, (axum::http::StatusCode, String)> {
+    let bad_request = |message: String| (axum::http::StatusCode::BAD_REQUEST, message);
+    let internal = |e: sqlx::Error| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string());
+
+    let rule = AlertRule::new(
+        "simulation",
+        req.expression.trim(),
+        req.symbols,
+        AlertPriority::Medium,
+    )
+    .map_err(|e| bad_request(format!("Invalid rule expression: {}", e)))?;
+    let custom = rule_custom_indicator_exprs(&state, &user.username, &rule).await?;
+
+    let mut symbols = rule.symbols.clone();
+    if symbols.is_empty() {
+        symbols = repositories::watchlist::get_watchlist(&state.db)
+            .await
+            .map_err(internal)?
+            .into_iter()
+            .map(|w| w.symbol)
+            .collect();
+    } else {
+        for symbol in &symbols {
+            repositories::stocks::get_stock_by_symbol(&state.db, symbol)
+                .await
+                .map_err(internal)?
+                .ok_or_else(|| bad_request(format!("Stock {} not found", symbol)))?;
+        }
+    }
+    if symbols.len() > MAX_SIMULATION_SYMBOLS {
+        return Err(bad_request(format!(
+            "A simulation covers at most {} symbols, got {}",
+            MAX_SIMULATION_SYMBOLS,
+            symbols.len()
+        )));
+    }
+
+    let to = Utc::now();
+    let from = to - Duration::days(SIMULATION_DAYS);
+    let mut evaluations = 0;
+    let mut firings = Vec::new();
+    let mut seen_metrics = BTreeSet::new();
+    for symbol in &symbols {
+        let (evaluated, fired, seen) = simulate_symbol(&state, &rule, &custom, symbol, from)
+            .await
+            .map_err(internal)?;
+        evaluations += evaluated;
+        firings.extend(fired);
+        seen_metrics.extend(seen);
+    }
+
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for firing in &firings {
+        *counts.entry(firing.symbol.as_str()).or_default() += 1;
+    }
+    let mut by_symbol: Vec<SymbolFireCount> = counts
+        .into_iter()
+        .map(|(symbol, fired)| SymbolFireCount {
+            symbol: symbol.to_string(),
+            fired,
+        })
+        .collect();
+    by_symbol.sort_by_key(|c| std::cmp::Reverse(c.fired));
+
+    let fired = firings.len();
+    let fire_rate = if evaluations == 0 {
+        0.0
+    } else {
+        fired as f64 / evaluations as f64
+    };
+    firings.sort_by(|a, b| b.date.cmp(&a.date).then_with(|| a.symbol.cmp(&b.symbol)));
+    firings.truncate(MAX_SIMULATED_FIRINGS);
+    let missing_metrics = rule
+        .condition
+        .metrics()
+        .into_iter()
+        .filter(|metric| !seen_metrics.contains(*metric))
+        .map(str::to_string)
+        .collect();
+
+    Ok(Json(RuleSimulationResponse {
+        expression: rule.expression,
+        from: from.date_naive(),
+        to: to.date_naive(),
+        symbols_evaluated: symbols.len(),
+        evaluations,
+        fired,
+        fire_rate,
+        noisy: fire_rate > NOISY_FIRE_RATE,
+        by_symbol,
+        firings,
+        missing_metrics,
+    }))
+}
+
+async fn list_metrics(_user: AuthUser) -> Json<Vec<RuleMetricInfo>> {
+    Json(
+        RULE_METRICS
+            .iter()
+            .map(|(metric, description)| RuleMetricInfo {
+                metric,
+                description,
+            })
+            .collect(),
+    )
+}
+
+/// Fired alerts shared with or owned by the user
+async fn list_alerts(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<AlertHistoryQuery>,
+) -> Result<Json<Vec<AlertHistoryRow>>, (axum::http::StatusCode, String)> {
+    let priorities = match query.priority.as_deref() {
+        Some(p) => Some(priorities_at_least(
+            AlertPriority::from_str_opt(&p.to_lowercase()).ok_or_else(|| {
+                (
+                    axum::http::StatusCode::BAD_REQUEST,
+                    format!("Unknown priority '{}'", p),
+                )
+            })?,
+        )),
+        None => None,
+    };
+    let symbol = query.symbol.map(|s| s.to_uppercase());
+
+    let alerts = repositories::alerts::get_alert_history(
+        &state.db,
+        &AlertHistoryFilter {
+            symbol: symbol.as_deref(),
+            since: query.since,
+            priorities: priorities.as_deref(),
+            acknowledged: query.acknowledged,
+            visible_to: Some(&user.username),
+            limit: query
+                .limit
+                .unwrap_or(DEFAULT_HISTORY_LIMIT)
+                .clamp(1, MAX_HISTORY_LIMIT),
+        },
+    )
+    .await
+    .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(alerts))
+}
+
+async fn acknowledge_alert(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<Json<AlertHistoryRow>, (axum::http::StatusCode, String)> {
+    repositories::alerts::acknowledge_alert(&state.db, &user.username, id)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map(Json)
+        .ok_or_else(|| {
+            (
+                axum::http::StatusCode::NOT_FOUND,
+                "Alert not found".to_string(),
+            )
+        })
+}
+
+/// Record that the user opened a fired alert, for usage analytics
+async fn click_alert(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<Json<AlertHistoryRow>, (axum::http::StatusCode, String)> {
+    let alert = repositories::alerts::get_alert_history_entry(&state.db, &user.username, id)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(|| {
+            (
+                axum::http::StatusCode::NOT_FOUND,
+                "Alert not found".to_string(),
+            )
+        })?;
+    state.usage.record(
+        UsageKind::AlertClicked,
+        alert.kind.as_deref().unwrap_or("unknown"),
+        alert.symbol.as_deref(),
+        &user.username,
+    );
+    Ok(Json(alert))
+}
+
+async fn list_rules(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<AlertRuleRow>>, (axum::http::StatusCode, String)> {
+    let rules = repositories::alerts::get_alert_rules(&state.db, &user.username)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(rules))
+}
+
+async fn get_rule(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<Json<AlertRuleRow>, (axum::http::StatusCode, String)> {
+    repositories::alerts::get_alert_rule(&state.db, &user.username, id)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map(Json)
+        .ok_or_else(|| {
+            (
+                axum::http::StatusCode::NOT_FOUND,
+                "Alert rule not found".to_string(),
+            )
+        })
+}
+
+async fn create_rule(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<AlertRuleRequest>,
+) -> Result<Json<AlertRuleRow>, (axum::http::StatusCode, String)> {
+    let rule = validate_rule(&state, &user.username, &req).await?;
+
+    let row = repositories::alerts::insert_alert_rule(
+        &state.db,
+        &InsertAlertRule {
+            owner: &user.username,
+            name: &rule.name,
+            expression: &rule.expression,
+            symbols: &rule.symbols,
+            priority: rule.priority.as_str(),
+            enabled: req.enabled.unwrap_or(true),
+        },
+    )
+    .await
+    .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    record_change(
+        &state,
+        &user.username,
+        "alert_rule_create",
+        HISTORY_RESOURCE,
+        &row.id.to_string(),
+        None,
+        Some(&row),
+    )
+    .await;
+
+    Ok(Json(row))
+}
+
+async fn update_rule(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Json(req): Json<AlertRuleRequest>,
+) -> Result<Json<AlertRuleRow>, (axum::http::StatusCode, String)> {
+    let rule = validate_rule(&state, &user.username, &req).await?;
+    let not_found = || {
+        (
+            axum::http::StatusCode::NOT_FOUND,
+            "Alert rule not found".to_string(),
+        )
+    };
+
+    let before = repositories::alerts::get_alert_rule(&state.db, &user.username, id)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(not_found)?;
+
+    let row = repositories::alerts::update_alert_rule(
+        &state.db,
+        id,
+        &InsertAlertRule {
+            owner: &user.username,
+            name: &rule.name,
+            expression: &rule.expression,
+            symbols: &rule.symbols,
+            priority: rule.priority.as_str(),
+            enabled: req.enabled.unwrap_or(true),
+        },
+    )
+    .await
+    .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .ok_or_else(not_found)?;
+
+    record_change(
+        &state,
+        &user.username,
+        "alert_rule_update",
+        HISTORY_RESOURCE,
+        &id.to_string(),
+        Some(&before),
+        Some(&row),
+    )
+    .await;
+
+    Ok(Json(row))
+}
+
+async fn delete_rule(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, String)> {
+    let before = repositories::alerts::get_alert_rule(&state.db, &user.username, id)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let deleted = repositories::alerts::delete_alert_rule(&state.db, &user.username, id)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    if deleted {
+        record_change(
+            &state,
+            &user.username,
+            "alert_rule_delete",
+            HISTORY_RESOURCE,
+            &id.to_string(),
+            before.as_ref(),
+            None,
+        )
+        .await;
+        Ok(Json(serde_json::json!({ "success": true })))
+    } else {
+        Err((
+            axum::http::StatusCode::NOT_FOUND,
+            "Alert rule not found".to_string(),
+        ))
+    }
+}
+
+/// Undo a deletion still within the retention window
+async fn restore_rule(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<Json<AlertRuleRow>, (axum::http::StatusCode, String)> {
+    let since = restore_cutoff(Utc::now(), state.config.soft_delete_retention_days);
+
+    let row = repositories::alerts::restore_alert_rule(&state.db, &user.username, id, since)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(|| {
+            (
+                axum::http::StatusCode::NOT_FOUND,
+                "No restorable alert rule".to_string(),
+            )
+        })?;
+
+    record_change(
+        &state,
+        &user.username,
+        "alert_rule_restore",
+        HISTORY_RESOURCE,
+        &id.to_string(),
+        None,
+        Some(&row),
+    )
+    .await;
+
+    Ok(Json(row))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RuleHistoryQuery {
+    limit: Option<i64>,
+}
+
+/// Changes of every rule of the user, including deleted ones
+async fn get_rules_history(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<RuleHistoryQuery>,
+) -> Result<Json<Vec<ChangeRecord>>, (axum::http::StatusCode, String)> {
+    let history = load_history(
+        &state,
+        &ChangeHistoryQuery {
+            resource_type: HISTORY_RESOURCE,
+            resource_id: None,
+            username: Some(&user.username),
+            limit: history_limit(query.limit),
+        },
+    )
+    .await?;
+
+    Ok(Json(history))
+}
+
+/// When and how one rule changed, newest first
+async fn get_rule_history(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Query(query): Query<RuleHistoryQuery>,
+) -> Result<Json<Vec<ChangeRecord>>, (axum::http::StatusCode, String)> {
+    let id = id.to_string();
+    let history = load_history(
+        &state,
+        &ChangeHistoryQuery {
+            resource_type: HISTORY_RESOURCE,
+            resource_id: Some(&id),
+            username: Some(&user.username),
+            limit: history_limit(query.limit),
+        },
+    )
+    .await?;
+
+    Ok(Json(history))
+}
+
+async fn evaluate_rule(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path((id, symbol)): Path<(i32, String)>,
+) -> Result<Json<RuleEvaluationResponse>, (axum::http::StatusCode, String)> {
+    let symbol = symbol.to_uppercase();
+    let row = repositories::alerts::get_alert_rule(&state.db, &user.username, id)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(|| {
+            (
+                axum::http::StatusCode::NOT_FOUND,
+                "Alert rule not found".to_string(),
+            )
+        })?;
+    let rule = to_alert_rule(&row)
+        .map_err(|e| (axum::http::StatusCode::UNPROCESSABLE_ENTITY, e.to_string()))?;
+
+    repositories::stocks::get_stock_by_symbol(&state.db, &symbol)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(|| {
+            (
+                axum::http::StatusCode::NOT_FOUND,
+                format!("Stock {} not found", symbol),
+            )
+        })?;
+
+    let mut context = build_rule_context(&state, &symbol).await?;
+    attach_custom_metrics(&state, &user.username, &symbol, &rule, &mut context)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let evaluation = rule.evaluate(&context);
+    let values = rule
+        .condition
+        .metrics()
+        .into_iter()
+        .filter_map(|metric| {
+            let value = context.get(metric)?.to_f64()?;
+            Some((metric.to_string(), value))
+        })
+        .collect();
+
+    Ok(Json(RuleEvaluationResponse {
+        rule_id: row.id,
+        symbol,
+        matched: evaluation.matched,
+        values,
+        missing_metrics: evaluation.missing_metrics,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn bar(close: Decimal, volume: i64) -> StockPriceRow {
+        StockPriceRow {
+            time: Utc::now(),
+            symbol: "BBCA".to_string(),
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume,
+            value: None,
+            frequency: None,
+        }
+    }
+
+    #[test]
+    fn test_price_metrics() {
+        let mut prices: Vec<StockPriceRow> =
+            (0..RVOL_PERIOD).map(|_| bar(dec!(100), 1_000)).collect();
+        prices.push(bar(dec!(105), 3_000));
+
+        let (change_pct, rvol) = price_metrics(&prices);
+        assert_eq!(change_pct, Some(dec!(5)));
+        assert_eq!(rvol, Some(dec!(3)));
+
+        // Too little history for relative volume
+        let (change_pct, rvol) = price_metrics(&prices[RVOL_PERIOD - 2..]);
+        assert_eq!(change_pct, Some(dec!(5)));
+        assert_eq!(rvol, None);
+        assert_eq!(price_metrics(&[]), (None, None));
+    }
+
+    #[test]
+    fn test_historical_rule_context() {
+        let prices: Vec<StockPriceRow> = (0..40)
+            .map(|i| bar(dec!(100) + Decimal::from(i), 1_000))
+            .collect();
+        let rule = AlertRule::new(
+            "test",
+            "rsi > 70 AND close > 130 AND composite_score > 50",
+            vec![],
+            AlertPriority::Medium,
+        )
+        .unwrap();
+
+        // A steady rally is overbought, but no score existed yet
+        let context = historical_rule_context(&prices, &[], None);
+        assert_eq!(context.get("close"), Some(dec!(139)));
+        assert!(context.get("rsi").unwrap() > dec!(70));
+        assert!(context.get("macd").is_some());
+        assert!(context.get("foreign_net_5_day").is_none());
+        let evaluation = rule.evaluate(&context);
+        assert!(!evaluation.matched);
+        assert_eq!(evaluation.missing_metrics, ["composite_score"]);
+
+        let score = StockScoreRow {
+            time: Utc::now(),
+            symbol: "BBCA".to_string(),
+            composite_score: dec!(72),
+            technical_score: dec!(80),
+            fundamental_score: dec!(60),
+            sentiment_score: dec!(50),
+            ml_score: dec!(50),
+            technical_breakdown: None,
+            fundamental_breakdown: None,
+            sentiment_breakdown: None,
+            ml_breakdown: None,
+            input_hash: None,
+        };
+        assert!(
+            rule.evaluate(&historical_rule_context(&prices, &[], Some(&score)))
+                .matched
+        );
+
+        // Too few bars for the indicators
+        let short = historical_rule_context(&prices[..10], &[], Some(&score));
+        assert!(short.get("rsi").is_none());
+        assert_eq!(short.get("close"), Some(dec!(109)));
+    }
+
+    #[test]
+    fn test_priorities_at_least() {
+        assert_eq!(
+            priorities_at_least(AlertPriority::High),
+            ["critical", "high"]
+        );
+        assert_eq!(priorities_at_least(AlertPriority::Low).len(), 4);
+    }
+}
@@ -3,32 +3,101 @@
 //! Provides comprehensive stock analysis including:
 //! - Technical indicators (RSI, MACD, Bollinger Bands)
 //! - Broker flow analysis (accumulation/distribution)
-//! - Valuation estimates
-
+//! - Valuation estimates, compared with the analyst target price consensus,
+//!   and the trailing free cash flow yield
+//! - Spoken-style summaries for voice assistants and mobile cards
+//! - Broker data coverage, so accumulation scores can be judged against gaps
+//! - Order-flow footprint bars from streaming ticks
+//! - Daily OBI/OFI history as stored by score computation
+//! - Order book depth ingestion and live OBI/OFI from the latest snapshots
+//! - Universe ranking by weighted multi-horizon momentum, warmed nightly
+//! - Pair analysis of two symbols: price ratio z-score and cointegration
+//! - Event studies of abnormal returns around earnings, dividends and rights issues
+//! - Wyckoff phase history with dated phase transitions
+//! - Indicator metadata, so clients can build indicator forms dynamically
+//! - Compact rows for a whole sector or index from stored scores and
+//!   indicator snapshots
+
+use crate::analysis_timings::{self, TimingReport};
 use crate::auth::AuthUser;
+use crate::corporate_actions;
+use crate::demo::{data_cutoff, DemoAccess};
+use crate::fundamentals;
+use crate::price_store::{PriceColumns, PriceWindow};
+use crate::routes::symbols::load_symbol_mapper;
+use crate::summary::{render_summary, BrokerStance, SummaryFacts, SummaryLanguage, SummaryRisk};
 use crate::AppState;
 use axum::{
     extract::{Path, Query, State},
+    http::header,
+    response::{IntoResponse, Response},
     routing::get,
-    Json, Router,
+    Extension, Json, Router,
+};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use jejakcuan_cache::{CacheKeys, MarketAwareTtl};
+use jejakcuan_core::{
+    build_sector_indices, round_to_tick, sector_rotation, suggested_position_lots, FcfYieldBand,
+    MarketCalendar, RiskBudgetConfig, RotationConfig, SectorRotation, TechnicalScoreBreakdown,
+    TickRounding,
+};
+use jejakcuan_data_sources::{
+    canonical_symbol, MacroIndicator, SectorsClient, SymbolProvider, TargetPriceConsensus,
+};
+use jejakcuan_db::{
+    repositories, AnalystTargetPriceRow, InsertAnalystTargetPrice, OrderBookSnapshotRow,
+    OrderFlowDailyRow,
+};
+use jejakcuan_fundamental::{
+    compare_with_consensus, ConsensusRating, ConsensusStance, RatingDistribution,
 };
-use chrono::{Duration, Utc};
-use jejakcuan_db::repositories;
 use jejakcuan_technical::{
-    calculate_bollinger_bands, calculate_macd, calculate_rsi14, macd_signal, rsi_signal,
-    BollingerBands,
+    align_closes, align_returns, beta, build_footprint, calculate_delta_series,
+    calculate_depth_obi, calculate_ema20, calculate_ema50, calculate_macd, calculate_rsi14,
+    columnar, correlation_matrix, detect_wyckoff_phase, engle_granger, event_abnormal_returns,
+    log_returns, macd_signal, pearson_correlation, rank_momentum, ratio_series, resample_ohlcv,
+    rsi_signal, session_date, summarize_events, volume_profile, BookLevel, CointegrationResult,
+    DatedBar, DepthSnapshot, EventAbnormalReturns, EventStudySummary, EventWindow,
+    ExpectedReturnModel, FootprintBar, IndicatorInfo, LiveOrderFlow, MomentumConfig,
+    MomentumHorizon, MomentumRank, OhlcvBar, PairSignal, RatioPoint, Timeframe, VolumeProfile,
+    WyckoffConfig, WyckoffPhase, WyckoffPhaseSpan, WyckoffProfile, WyckoffRange, WyckoffTracker,
+    WyckoffTransition, CHANDELIER_MULTIPLIER, DEFAULT_DEPTH_LEVELS, DEFAULT_VOLUME_PROFILE_BINS,
+    MIN_RETURN_OBSERVATIONS, OFI_ZSCORE_PERIOD, PAIR_ZSCORE_WINDOW, SKIP_MONTH_SESSIONS,
 };
-use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
 use rust_decimal::Decimal;
-use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
+use std::time::Instant;
 
 pub fn analysis_routes() -> Router<Arc<AppState>> {
     Router::new()
         .route("/:symbol/analysis", get(get_full_analysis))
         .route("/:symbol/technicals", get(get_technicals))
+        .route(
+            "/:symbol/technicals/multi",
+            get(get_multi_timeframe_technicals),
+        )
         .route("/:symbol/broker-flow", get(get_broker_flow))
+        .route("/:symbol/summary", get(get_summary))
+        .route("/:symbol/broker-coverage", get(get_broker_coverage))
+        .route("/broker-coverage", get(get_broker_coverage_overview))
+        .route("/indicators", get(list_indicators))
+        .route("/momentum", get(get_momentum_ranking))
+        .route("/pairs", get(get_pair_analysis))
+        .route("/correlation", get(get_correlation))
+        .route("/event-study", get(get_event_study))
+        .route("/sectors/rotation", get(get_sector_rotation))
+        .route("/bulk", get(get_bulk_analysis))
+        .route("/:symbol/footprint", get(get_footprint))
+        .route("/:symbol/order-flow", get(get_order_flow_history))
+        .route(
+            "/:symbol/order-book",
+            get(get_order_book).post(post_order_book),
+        )
+        .route("/:symbol/volume-profile", get(get_volume_profile))
+        .route("/:symbol/wyckoff/history", get(get_wyckoff_history))
 }
 
 // ============== Types ==============
@@ -43,6 +112,18 @@ pub enum TradingSignal {
     StrongSell,
 }
 
+impl TradingSignal {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::StrongBuy => "strong_buy",
+            Self::Buy => "buy",
+            Self::Hold => "hold",
+            Self::Sell => "sell",
+            Self::StrongSell => "strong_sell",
+        }
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct SignalAnalysis {
     pub signal: TradingSignal,
@@ -50,11 +131,17 @@ pub struct SignalAnalysis {
     pub thesis: String,
     pub target_price: Option<f64>,
     pub stop_loss: Option<f64>,
+    /// What the stop loss is based on: "support", "atr" or "percent"
+    pub stop_loss_basis: &'static str,
     pub upside_percent: Option<f64>,
     pub downside_percent: Option<f64>,
     pub risk_reward_ratio: Option<f64>,
+    /// Whole lots to buy within the risk budget; needs `RISK_CAPITAL`
+    pub position_lots: Option<i64>,
     pub key_catalysts: Vec<String>,
     pub key_risks: Vec<String>,
+    /// Glossary entry explaining the signal
+    pub glossary_key: &'static str,
 }
 
 #[derive(Debug, Serialize)]
@@ -142,6 +229,8 @@ pub struct TASummary {
 #[derive(Debug, Serialize)]
 pub struct TechnicalResponse {
     pub last_price: f64,
+    /// Time of the latest price bar
+    pub as_of: DateTime<Utc>,
     pub rsi: f64,
     pub rsi_signal: String,
     pub macd: f64,
@@ -149,11 +238,31 @@ pub struct TechnicalResponse {
     pub macd_histogram: f64,
     pub bollinger: BollingerResponse,
     pub ichimoku: IchimokuInfo,
+    /// Computed levels merged with the user's own levels
     pub support: Vec<f64>,
     pub resistance: Vec<f64>,
+    pub user_levels: Vec<UserLevelInfo>,
+    /// Latest 14-period ATR
+    pub atr: Option<f64>,
+    /// Chandelier exit (22, 3) for long positions
+    pub atr_stop: Option<f64>,
+    /// VWAP anchored at the start of the requested window
+    pub vwap: Option<f64>,
+    /// Last price relative to VWAP (%)
+    pub price_vs_vwap_percent: Option<f64>,
     pub summary: TASummary,
 }
 
+#[derive(Debug, Serialize)]
+pub struct UserLevelInfo {
+    pub id: i32,
+    pub price: f64,
+    pub kind: String,
+    pub label: Option<String>,
+    pub note: Option<String>,
+    pub alert_enabled: bool,
+}
+
 #[derive(Debug, Serialize)]
 pub struct BollingerResponse {
     pub upper: f64,
@@ -170,6 +279,47 @@ pub struct ValuationResponse {
     pub ev_ebitda_value: f64,
     pub fair_price_range: PriceRange,
     pub bull_case: PriceRange,
+    /// Street consensus vs model fair value, when analyst coverage exists
+    pub street_consensus: Option<StreetConsensusResponse>,
+    /// Trailing free cash flow yield, when cash flow statements exist
+    pub fcf_yield: Option<FcfYieldResponse>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FcfYieldResponse {
+    pub market_cap: f64,
+    pub ttm_free_cash_flow: Option<f64>,
+    /// Free cash flow yield (%)
+    pub fcf_yield_pct: f64,
+    pub band: FcfYieldBand,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RatingDistributionResponse {
+    pub strong_buy: i32,
+    pub buy: i32,
+    pub hold: i32,
+    pub sell: i32,
+    pub strong_sell: i32,
+    pub buy_pct: f64,
+    pub sell_pct: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StreetConsensusResponse {
+    pub source: String,
+    pub as_of: chrono::NaiveDate,
+    pub analyst_count: Option<i32>,
+    pub target_mean: f64,
+    pub target_high: Option<f64>,
+    pub target_low: Option<f64>,
+    pub model_fair_value: f64,
+    pub implied_upside_pct: f64,
+    pub model_upside_pct: f64,
+    pub consensus_vs_model_pct: f64,
+    pub stance: ConsensusStance,
+    pub rating: Option<ConsensusRating>,
+    pub rating_distribution: RatingDistributionResponse,
 }
 
 #[derive(Debug, Serialize)]
@@ -195,11 +345,99 @@ pub struct FullAnalysisResponse {
     pub technical: Option<TechnicalResponse>,
     pub valuation: Option<ValuationResponse>,
     pub conclusion: Option<ConclusionResponse>,
+    /// Status of each section, so a missing section can be told apart from
+    /// one that failed
+    pub sections: BTreeMap<&'static str, SectionReport>,
+}
+
+/// Outcome of one section of the full analysis
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SectionStatus {
+    Ok,
+    /// Not enough data to compute the section
+    InsufficientData,
+    /// The data source failed or timed out
+    SourceError,
+    /// Computed from data older than expected
+    Stale,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SectionReport {
+    pub status: SectionStatus,
+    pub message: Option<String>,
+    pub elapsed_ms: u64,
+}
+
+impl SectionReport {
+    fn derived(status: SectionStatus, message: Option<&str>) -> Self {
+        Self {
+            status,
+            message: message.map(str::to_string),
+            elapsed_ms: 0,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
 pub struct AnalysisQuery {
     days: Option<i32>,
+    /// Adjust prices for splits, dividends and rights issues
+    adjusted: Option<bool>,
+    /// Full analysis only: bypass the cache and return the stage timings
+    #[serde(default)]
+    debug_timings: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SummaryQuery {
+    /// "id" (Bahasa Indonesia) or "en", defaults to English
+    lang: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SummaryResponse {
+    pub symbol: String,
+    pub lang: SummaryLanguage,
+    pub text: String,
+    pub generated_at: chrono::DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MultiTimeframeQuery {
+    /// Daily history to resample, defaults to 5 years
+    days: Option<i32>,
+    /// Adjust prices for splits, dividends and rights issues
+    adjusted: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MultiTimeframeResponse {
+    pub symbol: String,
+    pub timeframes: Vec<TimeframeTechnicals>,
+    /// "bullish" or "bearish" when every timeframe agrees, otherwise "mixed"
+    pub trend_alignment: &'static str,
+}
+
+/// Indicators on one timeframe; `None` where there are too few bars
+#[derive(Debug, Serialize)]
+pub struct TimeframeTechnicals {
+    pub timeframe: Timeframe,
+    pub bars: usize,
+    /// Start of the latest (possibly incomplete) bar
+    pub as_of: Option<NaiveDate>,
+    pub last_close: Option<f64>,
+    pub rsi: Option<f64>,
+    pub rsi_signal: Option<String>,
+    pub macd: Option<f64>,
+    pub macd_signal_line: Option<f64>,
+    pub macd_histogram: Option<f64>,
+    pub macd_signal: Option<String>,
+    pub ema20: Option<f64>,
+    pub ema50: Option<f64>,
+    /// "bullish" (close > EMA20 > EMA50), "bearish" (reverse) or "neutral"
+    pub trend: &'static str,
 }
 
 // ============== Handlers ==============
@@ -209,11 +447,212 @@ async fn get_full_analysis(
     State(state): State<Arc<AppState>>,
     Path(symbol): Path<String>,
     Query(query): Query<AnalysisQuery>,
-) -> Result<Json<FullAnalysisResponse>, (axum::http::StatusCode, String)> {
+) -> Result<Response, (axum::http::StatusCode, String)> {
     let upper_symbol = symbol.to_uppercase();
     let days = query.days.unwrap_or(90);
+    // Timings describe the pipeline, so a debug request always computes
+    let use_cache = !query.debug_timings;
+
+    let cached = if use_cache {
+        state.analysis_cache.get(&upper_symbol, days).await
+    } else {
+        None
+    };
+    if let Some(json) = cached {
+        state.analysis_cache.record_lookup(true);
+        return Ok(json_response(json));
+    }
+
+    // Concurrent requests for the same symbol take turns, so followers find
+    // the leader's result in the cache; a request that waits too long goes
+    // ahead rather than blocking
+    let _guard = state
+        .analysis_locks
+        .lock(&upper_symbol, ANALYSIS_LOCK_WAIT)
+        .await;
+    if _guard.is_none() {
+        tracing::debug!("Full analysis of {} running without lock", upper_symbol);
+    } else if use_cache {
+        if let Some(json) = state.analysis_cache.get(&upper_symbol, days).await {
+            state.analysis_cache.record_lookup(true);
+            return Ok(json_response(json));
+        }
+    }
+    state.analysis_cache.record_lookup(false);
+
+    let (result, timings) = analysis_timings::timed(async {
+        let analysis = build_full_analysis(&state, &upper_symbol, days).await?;
+        let json = analysis_timings::time("serialize", || serde_json::to_string(&analysis))
+            .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        Ok::<_, (axum::http::StatusCode, String)>((analysis, json))
+    })
+    .await;
+    let (analysis, json) = result?;
+    state.analysis_timings.observe(&timings);
+    state
+        .analysis_cache
+        .put(
+            &upper_symbol,
+            days,
+            &json,
+            ON_DEMAND_ANALYSIS_TTL.current(&state.config.market_calendar),
+        )
+        .await;
+
+    if query.debug_timings {
+        let json = serde_json::to_string(&TimedAnalysis {
+            analysis: &analysis,
+            debug_timings: &timings,
+        })
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        return Ok(json_response(json));
+    }
+    Ok(json_response(json))
+}
+
+/// Full analysis with the stage timings of its computation
+#[derive(Serialize)]
+struct TimedAnalysis<'a> {
+    #[serde(flatten)]
+    analysis: &'a FullAnalysisResponse,
+    debug_timings: &'a TimingReport,
+}
+
+fn json_response(json: String) -> Response {
+    ([(header::CONTENT_TYPE, "application/json")], json).into_response()
+}
+
+/// Full analysis of `upper_symbol` over the last `days` of prices
+pub(crate) async fn build_full_analysis(
+    state: &AppState,
+    upper_symbol: &str,
+    days: i32,
+) -> Result<FullAnalysisResponse, (axum::http::StatusCode, String)> {
+    let upper_symbol = upper_symbol.to_string();
 
     // Get stock info
+    let stock = analysis_timings::time_async(
+        "db.stock",
+        repositories::stocks::get_stock_by_symbol(&state.db, &upper_symbol),
+    )
+    .await
+    .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .ok_or_else(|| {
+        (
+            axum::http::StatusCode::NOT_FOUND,
+            format!("Stock not found: {}", upper_symbol),
+        )
+    })?;
+
+    // Sources are independent: run them together, each with its own budget
+    let (technical, broker, consensus, fcf) = tokio::join!(
+        run_section(get_technical_analysis(state, &upper_symbol, days, false)),
+        run_section(get_broker_flow_internal(state, &upper_symbol, 5)),
+        run_section(async {
+            analysis_timings::time_async(
+                "db.target_consensus",
+                load_target_consensus(state, &upper_symbol),
+            )
+            .await
+            .ok_or_else(|| {
+                (
+                    axum::http::StatusCode::BAD_REQUEST,
+                    "No analyst target prices".to_string(),
+                )
+            })
+        }),
+        run_section(async {
+            analysis_timings::time_async("fcf_yield", fcf_yield_section(state, &upper_symbol))
+                .await
+                .ok_or_else(|| {
+                    (
+                        axum::http::StatusCode::BAD_REQUEST,
+                        "No cash flow data".to_string(),
+                    )
+                })
+        }),
+    );
+    let (technical, mut technical_report) = technical;
+    let (broker_summary, mut broker_report) = broker;
+    let (consensus, consensus_report) = consensus;
+    let (fcf_yield, fcf_report) = fcf;
+
+    if let Some(ref tech) = technical {
+        if Utc::now() - tech.as_of > Duration::days(STALE_PRICE_DAYS) {
+            technical_report.status = SectionStatus::Stale;
+            technical_report.message =
+                Some(format!("Latest price is from {}", tech.as_of.date_naive()));
+        }
+    }
+    if let Some(ref broker) = broker_summary {
+        if broker.big_buyers.is_empty()
+            && broker.big_sellers.is_empty()
+            && broker.institutional_analysis.is_none()
+        {
+            broker_report.status = SectionStatus::InsufficientData;
+            broker_report.message = Some("No broker summaries in the last 5 days".to_string());
+        }
+    }
+
+    // Generate valuation and conclusion based on technical data
+    let (mut valuation, conclusion) = if let Some(ref tech) = technical {
+        generate_valuation_conclusion(tech, &stock.name)
+    } else {
+        (None, None)
+    };
+
+    if let (Some(valuation), Some(tech)) = (valuation.as_mut(), technical.as_ref()) {
+        if let Some(consensus) = consensus {
+            valuation.street_consensus = street_consensus_section(&consensus, valuation, tech);
+        }
+        valuation.fcf_yield = fcf_yield;
+    }
+
+    let valuation_report = if valuation.is_some() {
+        SectionReport::derived(SectionStatus::Ok, None)
+    } else {
+        SectionReport::derived(
+            SectionStatus::InsufficientData,
+            Some("Requires technical analysis"),
+        )
+    };
+    let sections = BTreeMap::from([
+        ("technical", technical_report),
+        ("broker_summary", broker_report),
+        ("street_consensus", consensus_report),
+        ("fcf_yield", fcf_report),
+        ("valuation", valuation_report),
+    ]);
+
+    Ok(FullAnalysisResponse {
+        symbol: upper_symbol,
+        name: stock.name,
+        sector: stock.sector,
+        broker_summary,
+        technical,
+        valuation,
+        conclusion,
+        sections,
+    })
+}
+
+async fn get_summary(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(symbol): Path<String>,
+    Query(query): Query<SummaryQuery>,
+) -> Result<Json<SummaryResponse>, (axum::http::StatusCode, String)> {
+    let upper_symbol = symbol.to_uppercase();
+    let lang = match query.lang.as_deref() {
+        None => SummaryLanguage::En,
+        Some(code) => SummaryLanguage::from_code(code).ok_or_else(|| {
+            (
+                axum::http::StatusCode::BAD_REQUEST,
+                format!("Unsupported language: {}", code),
+            )
+        })?,
+    };
+
     let stock = repositories::stocks::get_stock_by_symbol(&state.db, &upper_symbol)
         .await
         .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
@@ -224,44 +663,1587 @@ async fn get_full_analysis(
             )
         })?;
 
-    // Get technical analysis
-    let technical = get_technical_analysis(&state, &upper_symbol, days)
+    let technical = get_technical_analysis(&state, &upper_symbol, 90, false).await?;
+    let broker = get_broker_flow_internal(&state, &upper_symbol, 5)
         .await
         .ok();
+    let score = repositories::scores::get_stock_score(&state.db, &upper_symbol)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .and_then(|s| s.composite_score.to_f64());
+
+    let facts = summary_facts(
+        &upper_symbol,
+        &stock.name,
+        &technical,
+        broker.as_ref(),
+        score,
+        &state.config.risk_budget,
+    );
+    let text = render_summary(&facts, lang)
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(SummaryResponse {
+        symbol: upper_symbol,
+        lang,
+        text,
+        generated_at: Utc::now(),
+    }))
+}
+
+async fn get_technicals(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(symbol): Path<String>,
+    Query(query): Query<AnalysisQuery>,
+) -> Result<Json<TechnicalResponse>, (axum::http::StatusCode, String)> {
+    let upper_symbol = symbol.to_uppercase();
+    let days = query.days.unwrap_or(90);
 
-    // Get broker flow
-    let broker_summary = get_broker_flow_internal(&state, &upper_symbol, 5)
+    // Verify stock exists
+    repositories::stocks::get_stock_by_symbol(&state.db, &upper_symbol)
         .await
-        .ok();
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(|| {
+            (
+                axum::http::StatusCode::NOT_FOUND,
+                format!("Stock not found: {}", upper_symbol),
+            )
+        })?;
 
-    // Generate valuation and conclusion based on technical data
-    let (valuation, conclusion) = if let Some(ref tech) = technical {
-        generate_valuation_conclusion(tech, &stock.name)
-    } else {
-        (None, None)
-    };
+    get_technical_analysis(&state, &upper_symbol, days, query.adjusted.unwrap_or(false))
+        .await
+        .map(Json)
+}
+
+async fn get_multi_timeframe_technicals(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(symbol): Path<String>,
+    Query(query): Query<MultiTimeframeQuery>,
+) -> Result<Json<MultiTimeframeResponse>, (axum::http::StatusCode, String)> {
+    let upper_symbol = symbol.to_uppercase();
+    let days = query.days.unwrap_or(1825).clamp(90, 3650);
+
+    repositories::stocks::get_stock_by_symbol(&state.db, &upper_symbol)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(|| {
+            (
+                axum::http::StatusCode::NOT_FOUND,
+                format!("Stock not found: {}", upper_symbol),
+            )
+        })?;
+
+    let to = Utc::now();
+    let from = to - Duration::days(days as i64);
+    let mut prices = repositories::prices::get_price_history(&state.db, &upper_symbol, from, to)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if query.adjusted.unwrap_or(false) {
+        prices = corporate_actions::adjust_price_rows(&state.db, &upper_symbol, prices)
+            .await
+            .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    }
+
+    let daily: Vec<DatedBar> = prices
+        .iter()
+        .map(|p| DatedBar {
+            date: p.time.date_naive(),
+            bar: OhlcvBar {
+                open: p.open,
+                high: p.high,
+                low: p.low,
+                close: p.close,
+                volume: p.volume,
+            },
+        })
+        .collect();
+
+    let timeframes: Vec<TimeframeTechnicals> = Timeframe::ALL
+        .iter()
+        .map(|tf| timeframe_technicals(*tf, &resample_ohlcv(&daily, *tf)))
+        .collect();
+    let trend_alignment = trend_alignment(&timeframes);
+
+    Ok(Json(MultiTimeframeResponse {
+        symbol: upper_symbol,
+        timeframes,
+        trend_alignment,
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BrokerFlowQuery {
+    days: Option<i32>,
+}
+
+async fn get_broker_flow(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(symbol): Path<String>,
+    Query(query): Query<BrokerFlowQuery>,
+) -> Result<Json<BrokerSummaryResponse>, (axum::http::StatusCode, String)> {
+    let upper_symbol = symbol.to_uppercase();
+    let days = query.days.unwrap_or(5);
+
+    // Verify stock exists
+    repositories::stocks::get_stock_by_symbol(&state.db, &upper_symbol)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(|| {
+            (
+                axum::http::StatusCode::NOT_FOUND,
+                format!("Stock not found: {}", upper_symbol),
+            )
+        })?;
+
+    get_broker_flow_internal(&state, &upper_symbol, days)
+        .await
+        .map(Json)
+}
+
+// ============== Footprint ==============
+
+const DEFAULT_FOOTPRINT_BAR_SECONDS: i64 = 60;
+const MAX_FOOTPRINT_BAR_SECONDS: i64 = 60 * 60;
+
+#[derive(Debug, Deserialize)]
+pub struct FootprintQuery {
+    /// Bar length in seconds (default 60)
+    pub bar_seconds: Option<i64>,
+}
+
+/// Footprint chart data for the current session
+#[derive(Debug, Serialize)]
+pub struct FootprintResponse {
+    pub symbol: String,
+    pub bar_seconds: i64,
+    pub last_tick_at: Option<DateTime<Utc>>,
+    pub bars: Vec<FootprintBar>,
+    /// Delta per bar, aligned with `bars`
+    pub delta: Vec<i64>,
+    pub cumulative_delta: Vec<i64>,
+}
+
+/// Buy/sell volume per price level per bar, from the streaming feed
+async fn get_footprint(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(symbol): Path<String>,
+    Query(query): Query<FootprintQuery>,
+) -> Result<Json<FootprintResponse>, (axum::http::StatusCode, String)> {
+    let upper_symbol = symbol.to_uppercase();
+    let bar_seconds = query
+        .bar_seconds
+        .unwrap_or(DEFAULT_FOOTPRINT_BAR_SECONDS)
+        .clamp(1, MAX_FOOTPRINT_BAR_SECONDS);
+
+    // Streamed from the next subscription refresh if it is not yet
+    state.ticks.request(&upper_symbol, Utc::now());
+    let ticks = state.ticks.ticks(&upper_symbol);
+    if ticks.is_empty() {
+        return Err((
+            axum::http::StatusCode::NOT_FOUND,
+            format!("No streaming tick data for {}", upper_symbol),
+        ));
+    }
+
+    let bars = build_footprint(&ticks, bar_seconds);
+    let series = calculate_delta_series(&bars);
+    Ok(Json(FootprintResponse {
+        last_tick_at: state.ticks.last_tick_at(&upper_symbol),
+        symbol: upper_symbol,
+        bar_seconds,
+        bars,
+        delta: series.delta,
+        cumulative_delta: series.cumulative_delta,
+    }))
+}
+
+// ============== Order Flow History ==============
+
+const DEFAULT_ORDER_FLOW_DAYS: i64 = 90;
+const MAX_ORDER_FLOW_DAYS: i64 = 365;
+
+#[derive(Debug, Deserialize)]
+pub struct OrderFlowQuery {
+    /// Calendar days to look back (default 90)
+    pub days: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OrderFlowHistoryResponse {
+    pub symbol: String,
+    /// Sessions in the OFI z-score window
+    pub zscore_period: usize,
+    pub days: Vec<OrderFlowDailyRow>,
+}
+
+/// Stored daily OBI/OFI proxies, oldest first
+async fn get_order_flow_history(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(symbol): Path<String>,
+    Query(query): Query<OrderFlowQuery>,
+) -> Result<Json<OrderFlowHistoryResponse>, (axum::http::StatusCode, String)> {
+    let upper_symbol = symbol.to_uppercase();
+    let days = query
+        .days
+        .unwrap_or(DEFAULT_ORDER_FLOW_DAYS)
+        .clamp(1, MAX_ORDER_FLOW_DAYS);
+    let from = Utc::now().date_naive() - Duration::days(days);
+
+    let rows = repositories::order_flow::get_order_flow_history(&state.db, &upper_symbol, from)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(OrderFlowHistoryResponse {
+        symbol: upper_symbol,
+        zscore_period: OFI_ZSCORE_PERIOD,
+        days: rows,
+    }))
+}
+
+// ============== Order Book Depth ==============
+
+/// Levels kept per side of a posted snapshot
+const MAX_BOOK_LEVELS: usize = 20;
+
+#[derive(Debug, Deserialize)]
+pub struct OrderBookRequest {
+    /// Snapshot time (default now)
+    pub time: Option<DateTime<Utc>>,
+    pub bids: Vec<BookLevel>,
+    pub asks: Vec<BookLevel>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OrderBookResponse {
+    pub symbol: String,
+    /// Levels per side read by the OBI and OFI
+    pub levels: usize,
+    pub snapshot: DepthSnapshot,
+    pub mid_price: Option<Decimal>,
+    /// OBI of the best level alone
+    pub top_obi: Decimal,
+    pub flow: LiveOrderFlow,
+}
+
+fn order_book_response(
+    symbol: String,
+    snapshot: DepthSnapshot,
+    flow: LiveOrderFlow,
+) -> OrderBookResponse {
+    OrderBookResponse {
+        symbol,
+        levels: DEFAULT_DEPTH_LEVELS,
+        mid_price: snapshot.mid_price(),
+        top_obi: calculate_depth_obi(&snapshot, 1).obi,
+        snapshot,
+        flow,
+    }
+}
+
+/// Ingest an order book snapshot from a depth feed
+///
+/// The snapshot updates the live OBI/OFI and replaces the session's proxy
+/// order flow with depth-based values; its OFI is added to the session's
+/// stored total.
+async fn post_order_book(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(symbol): Path<String>,
+    Json(req): Json<OrderBookRequest>,
+) -> Result<Json<OrderBookResponse>, (axum::http::StatusCode, String)> {
+    let upper_symbol = symbol.to_uppercase();
+    let invalid = |level: &BookLevel| level.price <= Decimal::ZERO || level.volume < 0;
+    if req.bids.iter().chain(&req.asks).any(invalid) {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            "Book levels need a positive price and a non-negative volume".to_string(),
+        ));
+    }
+
+    let mut snapshot = DepthSnapshot::new(req.time.unwrap_or_else(Utc::now), req.bids, req.asks);
+    if snapshot.bids.is_empty() || snapshot.asks.is_empty() {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            "Both sides of the book need at least one level".to_string(),
+        ));
+    }
+    if snapshot.is_crossed() {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            "Crossed book: the best bid is at or above the best ask".to_string(),
+        ));
+    }
+    snapshot.bids.truncate(MAX_BOOK_LEVELS);
+    snapshot.asks.truncate(MAX_BOOK_LEVELS);
+
+    repositories::stocks::get_stock_by_symbol(&state.db, &upper_symbol)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(|| {
+            (
+                axum::http::StatusCode::NOT_FOUND,
+                format!("Stock not found: {}", upper_symbol),
+            )
+        })?;
+
+    let flow = state
+        .depth
+        .record(&upper_symbol, snapshot.clone())
+        .ok_or_else(|| {
+            (
+                axum::http::StatusCode::CONFLICT,
+                format!("Snapshot is older than the latest for {}", upper_symbol),
+            )
+        })?;
+
+    let to_json = |levels: &[BookLevel]| serde_json::to_value(levels).unwrap_or_default();
+    let row = OrderBookSnapshotRow {
+        time: snapshot.time,
+        symbol: upper_symbol.clone(),
+        bids: to_json(&snapshot.bids),
+        asks: to_json(&snapshot.asks),
+        obi: flow.obi.round_dp(4),
+        ofi: flow.ofi.round_dp(2),
+    };
+    repositories::order_flow::insert_order_book_snapshot(&state.db, &row)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    repositories::order_flow::upsert_depth_order_flow(
+        &state.db,
+        &upper_symbol,
+        session_date(snapshot.time),
+        flow.obi.round_dp(4),
+        flow.ofi.round_dp(2),
+    )
+    .await
+    .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(order_book_response(upper_symbol, snapshot, flow)))
+}
+
+/// Latest order book and live OBI/OFI of the session
+async fn get_order_book(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(symbol): Path<String>,
+) -> Result<Json<OrderBookResponse>, (axum::http::StatusCode, String)> {
+    let upper_symbol = symbol.to_uppercase();
+    let (snapshot, flow) = state.depth.latest(&upper_symbol).ok_or_else(|| {
+        (
+            axum::http::StatusCode::NOT_FOUND,
+            format!("No order book depth for {}", upper_symbol),
+        )
+    })?;
+    Ok(Json(order_book_response(upper_symbol, snapshot, flow)))
+}
+
+// ============== Volume Profile ==============
+
+const DEFAULT_VOLUME_PROFILE_DAYS: i64 = 60;
+const MAX_VOLUME_PROFILE_DAYS: i64 = 730;
+
+#[derive(Debug, Deserialize)]
+pub struct VolumeProfileQuery {
+    /// Calendar days to look back (default 60)
+    pub days: Option<i64>,
+    /// Price buckets (default 24)
+    pub bins: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VolumeProfileResponse {
+    pub symbol: String,
+    pub days: i64,
+    pub sessions: usize,
+    pub last_close: Decimal,
+    pub profile: VolumeProfile,
+}
+
+/// Daily volume by price level, with point of control and value area
+async fn get_volume_profile(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(symbol): Path<String>,
+    Query(query): Query<VolumeProfileQuery>,
+    demo: Option<Extension<DemoAccess>>,
+) -> Result<Json<VolumeProfileResponse>, (axum::http::StatusCode, String)> {
+    let upper_symbol = symbol.to_uppercase();
+    let days = query
+        .days
+        .unwrap_or(DEFAULT_VOLUME_PROFILE_DAYS)
+        .clamp(1, MAX_VOLUME_PROFILE_DAYS);
+    let bins = query
+        .bins
+        .unwrap_or(DEFAULT_VOLUME_PROFILE_BINS)
+        .clamp(4, 200);
+
+    repositories::stocks::get_stock_by_symbol(&state.db, &upper_symbol)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(|| {
+            (
+                axum::http::StatusCode::NOT_FOUND,
+                format!("Stock not found: {}", upper_symbol),
+            )
+        })?;
+
+    let to = data_cutoff(demo.map(|Extension(d)| d));
+    let from = to - Duration::days(days);
+    let prices = repositories::prices::get_price_history(&state.db, &upper_symbol, from, to)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let bars: Vec<OhlcvBar> = prices
+        .iter()
+        .map(|p| OhlcvBar {
+            open: p.open,
+            high: p.high,
+            low: p.low,
+            close: p.close,
+            volume: p.volume,
+        })
+        .collect();
+    let Some(last_close) = bars.last().map(|b| b.close) else {
+        return Err((
+            axum::http::StatusCode::NOT_FOUND,
+            format!(
+                "No price history for {} in the last {} days",
+                upper_symbol, days
+            ),
+        ));
+    };
+    let profile = volume_profile(&bars, bins)
+        .map_err(|e| (axum::http::StatusCode::UNPROCESSABLE_ENTITY, e.to_string()))?;
+
+    Ok(Json(VolumeProfileResponse {
+        symbol: upper_symbol,
+        days,
+        sessions: bars.len(),
+        last_close,
+        profile,
+    }))
+}
+
+// ============== Wyckoff Phase History ==============
+
+const DEFAULT_WYCKOFF_HISTORY_DAYS: i64 = 365;
+const MAX_WYCKOFF_HISTORY_DAYS: i64 = 1825;
+
+#[derive(Debug, Deserialize)]
+pub struct WyckoffHistoryQuery {
+    /// Calendar days to replay (default 365)
+    pub days: Option<i64>,
+    /// `blue_chip`, `mid_cap` or `small_cap`; picked from ADTV and market
+    /// cap when omitted
+    pub profile: Option<String>,
+    /// Overrides the profile's volume spike multiple
+    pub volume_spike_threshold: Option<Decimal>,
+    /// Overrides the profile's support/resistance tolerance (fraction)
+    pub sr_tolerance: Option<Decimal>,
+}
+
+/// Detection settings from the profile, the stock's liquidity and overrides
+fn wyckoff_config_for(
+    query: &WyckoffHistoryQuery,
+    bars: &[OhlcvBar],
+    market_cap: Option<i64>,
+) -> Result<(WyckoffProfile, WyckoffConfig), String> {
+    let profile = match query.profile.as_deref() {
+        Some(name) => WyckoffProfile::from_str_opt(name).ok_or_else(|| {
+            format!(
+                "Unknown profile: {}. Valid profiles: blue_chip, mid_cap, small_cap",
+                name
+            )
+        })?,
+        None => WyckoffProfile::for_bars(bars, market_cap),
+    };
+
+    let mut config = profile.config();
+    if let Some(threshold) = query.volume_spike_threshold {
+        if threshold <= Decimal::ONE {
+            return Err("volume_spike_threshold must be above 1".to_string());
+        }
+        config.volume_spike_threshold = threshold;
+    }
+    if let Some(tolerance) = query.sr_tolerance {
+        if tolerance <= Decimal::ZERO || tolerance >= Decimal::ONE {
+            return Err("sr_tolerance must be between 0 and 1".to_string());
+        }
+        config.sr_tolerance = tolerance;
+    }
+    Ok((profile, config))
+}
+
+#[derive(Debug, Serialize)]
+pub struct WyckoffHistoryResponse {
+    pub symbol: String,
+    pub days: i64,
+    pub sessions: usize,
+    /// Liquidity tier whose thresholds were used
+    pub profile: WyckoffProfile,
+    /// Effective settings, including overrides
+    pub config: WyckoffConfig,
+    pub current_phase: Option<WyckoffPhase>,
+    /// Sessions spent in the current phase so far
+    pub sessions_in_phase: usize,
+    /// Trading range price is in at the latest session, if any
+    pub current_range: Option<WyckoffRange>,
+    /// Phases in order, the last one ongoing
+    pub phases: Vec<WyckoffPhaseSpan>,
+    pub transitions: Vec<WyckoffTransition>,
+    pub last_accumulation_to_markup: Option<WyckoffTransition>,
+    pub last_distribution_to_markdown: Option<WyckoffTransition>,
+}
+
+/// Wyckoff phases over time, replayed session by session
+async fn get_wyckoff_history(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(symbol): Path<String>,
+    Query(query): Query<WyckoffHistoryQuery>,
+) -> Result<Json<WyckoffHistoryResponse>, (axum::http::StatusCode, String)> {
+    let upper_symbol = symbol.to_uppercase();
+    let days = query
+        .days
+        .unwrap_or(DEFAULT_WYCKOFF_HISTORY_DAYS)
+        .clamp(1, MAX_WYCKOFF_HISTORY_DAYS);
+
+    let stock = repositories::stocks::get_stock_by_symbol(&state.db, &upper_symbol)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(|| {
+            (
+                axum::http::StatusCode::NOT_FOUND,
+                format!("Stock not found: {}", upper_symbol),
+            )
+        })?;
+
+    let to = Utc::now();
+    let from = to - Duration::days(days);
+    let prices = repositories::prices::get_price_history(&state.db, &upper_symbol, from, to)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let bars: Vec<OhlcvBar> = prices
+        .iter()
+        .map(|p| OhlcvBar {
+            open: p.open,
+            high: p.high,
+            low: p.low,
+            close: p.close,
+            volume: p.volume,
+        })
+        .collect();
+
+    let (profile, config) = wyckoff_config_for(&query, &bars, stock.market_cap)
+        .map_err(|e| (axum::http::StatusCode::BAD_REQUEST, e))?;
+
+    let current_range = detect_wyckoff_phase(&bars, &config)
+        .ok()
+        .and_then(|analysis| analysis.range);
+
+    let mut tracker = WyckoffTracker::new(config.clone());
+    for (p, bar) in prices.iter().zip(bars) {
+        tracker.push(p.time, bar);
+    }
+
+    Ok(Json(WyckoffHistoryResponse {
+        symbol: upper_symbol,
+        days,
+        sessions: prices.len(),
+        profile,
+        config,
+        current_phase: tracker.current_phase(),
+        sessions_in_phase: tracker.bars_in_phase(),
+        current_range,
+        phases: tracker.history().to_vec(),
+        transitions: tracker.transitions(),
+        last_accumulation_to_markup: tracker
+            .last_transition(WyckoffPhase::Accumulation, WyckoffPhase::Markup),
+        last_distribution_to_markdown: tracker
+            .last_transition(WyckoffPhase::Distribution, WyckoffPhase::Markdown),
+    }))
+}
+
+// ============== Momentum Ranking ==============
+
+/// Longest horizon accepted, about one year of sessions
+const MAX_MOMENTUM_SESSIONS: usize = 252;
+const DEFAULT_MOMENTUM_LIMIT: usize = 50;
+
+#[derive(Debug, Deserialize)]
+pub struct MomentumQuery {
+    /// Comma-separated horizons in sessions (default 21,63,126)
+    pub horizons: Option<String>,
+    /// Comma-separated weights, one per horizon (default equal)
+    pub weights: Option<String>,
+    /// Leave out the most recent month of every horizon
+    #[serde(default)]
+    pub skip_month: bool,
+    /// Symbols returned (default 50)
+    pub limit: Option<usize>,
+}
+
+impl MomentumQuery {
+    fn config(&self) -> Result<MomentumConfig, (axum::http::StatusCode, String)> {
+        let bad_request = |msg: String| (axum::http::StatusCode::BAD_REQUEST, msg);
+        let defaults = MomentumConfig::default();
+
+        let sessions: Vec<usize> = match self.horizons.as_deref() {
+            Some(list) => list
+                .split(',')
+                .map(|h| h.trim().parse::<usize>())
+                .collect::<Result<_, _>>()
+                .map_err(|_| bad_request(format!("Invalid horizons: {}", list)))?,
+            None => defaults.horizons.iter().map(|h| h.sessions).collect(),
+        };
+        if sessions.iter().any(|s| *s > MAX_MOMENTUM_SESSIONS) {
+            return Err(bad_request(format!(
+                "Horizons cannot exceed {} sessions",
+                MAX_MOMENTUM_SESSIONS
+            )));
+        }
+
+        let weights: Vec<Decimal> = match self.weights.as_deref() {
+            Some(list) => list
+                .split(',')
+                .map(|w| w.trim().parse::<Decimal>())
+                .collect::<Result<_, _>>()
+                .map_err(|_| bad_request(format!("Invalid weights: {}", list)))?,
+            None => vec![Decimal::ONE; sessions.len()],
+        };
+        if weights.len() != sessions.len() {
+            return Err(bad_request(format!(
+                "Got {} weights for {} horizons",
+                weights.len(),
+                sessions.len()
+            )));
+        }
+
+        let config = MomentumConfig {
+            horizons: sessions
+                .into_iter()
+                .zip(weights)
+                .map(|(sessions, weight)| MomentumHorizon { sessions, weight })
+                .collect(),
+            skip_sessions: if self.skip_month {
+                SKIP_MONTH_SESSIONS
+            } else {
+                0
+            },
+        };
+        config.validate().map_err(|e| bad_request(e.to_string()))?;
+        Ok(config)
+    }
+}
+
+/// Universe ranked by composite momentum
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MomentumRankingResponse {
+    pub horizons: Vec<MomentumHorizon>,
+    pub skip_sessions: usize,
+    /// Symbols with enough history to be ranked
+    pub ranked: usize,
+    pub computed_at: DateTime<Utc>,
+    /// Returns are in `horizons` order
+    pub ranks: Vec<MomentumRank>,
+}
+
+/// Cache key of the ranking for `config`
+pub(crate) fn momentum_cache_key(config: &MomentumConfig) -> String {
+    let horizons = config
+        .horizons
+        .iter()
+        .map(|h| format!("{}x{}", h.sessions, h.weight.normalize()))
+        .collect::<Vec<_>>()
+        .join(",");
+    CacheKeys::momentum_ranking(&format!("{}:skip{}", horizons, config.skip_sessions))
+}
+
+/// Rank every symbol with enough price history
+pub(crate) async fn build_momentum_ranking(
+    state: &AppState,
+    config: &MomentumConfig,
+) -> Result<MomentumRankingResponse, (axum::http::StatusCode, String)> {
+    // Sessions to calendar days, with room for holidays
+    let calendar_days = (config.required_closes() * 7 / 5 + 14) as i64;
+    let now = Utc::now();
+    let closes =
+        repositories::prices::get_universe_closes(&state.db, now - Duration::days(calendar_days))
+            .await
+            .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let mut universe: Vec<(String, Vec<Decimal>)> = Vec::new();
+    for (symbol, close) in closes {
+        match universe.last_mut() {
+            Some((last, series)) if *last == symbol => series.push(close),
+            _ => universe.push((symbol, vec![close])),
+        }
+    }
+
+    let ranks = rank_momentum(&universe, config)
+        .map_err(|e| (axum::http::StatusCode::BAD_REQUEST, e.to_string()))?;
+    Ok(MomentumRankingResponse {
+        horizons: config.horizons.clone(),
+        skip_sessions: config.skip_sessions,
+        ranked: ranks.len(),
+        computed_at: now,
+        ranks,
+    })
+}
+
+/// Strongest symbols by weighted multi-horizon returns
+async fn get_momentum_ranking(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<MomentumQuery>,
+) -> Result<Json<MomentumRankingResponse>, (axum::http::StatusCode, String)> {
+    let config = query.config()?;
+    let key = momentum_cache_key(&config);
+
+    let cached = state
+        .analysis_cache
+        .get_key(&key)
+        .await
+        .and_then(|json| serde_json::from_str::<MomentumRankingResponse>(&json).ok());
+    let mut ranking = match cached {
+        Some(ranking) => ranking,
+        None => {
+            let ranking = build_momentum_ranking(&state, &config).await?;
+            if let Ok(json) = serde_json::to_string(&ranking) {
+                state
+                    .analysis_cache
+                    .put_key(
+                        &key,
+                        &json,
+                        ON_DEMAND_ANALYSIS_TTL.current(&state.config.market_calendar),
+                    )
+                    .await;
+            }
+            ranking
+        }
+    };
+
+    ranking
+        .ranks
+        .truncate(query.limit.unwrap_or(DEFAULT_MOMENTUM_LIMIT));
+    Ok(Json(ranking))
+}
+
+// ============== Pair Analysis ==============
+
+const DEFAULT_PAIR_DAYS: i64 = 365;
+const MAX_PAIR_DAYS: i64 = 1825;
+
+#[derive(Debug, Deserialize)]
+pub struct PairQuery {
+    /// Symbol bought
+    long: String,
+    /// Symbol sold against it
+    short: String,
+    /// Calendar days of history (default 365)
+    days: Option<i64>,
+    /// Sessions in the z-score window (default 60)
+    window: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PairAnalysisResponse {
+    pub long: String,
+    pub short: String,
+    pub window: usize,
+    /// Sessions both symbols traded
+    pub sessions: usize,
+    pub latest_ratio: Option<f64>,
+    pub latest_zscore: Option<f64>,
+    /// Divergence of the latest ratio; `None` before the window fills
+    pub signal: Option<PairSignal>,
+    /// `None` with too little shared history to test
+    pub cointegration: Option<CointegrationResult>,
+    pub ratios: Vec<RatioPoint>,
+}
+
+/// Daily closes of `symbol` since `from`, keyed by session date
+async fn load_session_closes(
+    state: &AppState,
+    symbol: &str,
+    from: DateTime<Utc>,
+) -> Result<Vec<(NaiveDate, Decimal)>, (axum::http::StatusCode, String)> {
+    let prices = repositories::prices::get_price_history(&state.db, symbol, from, Utc::now())
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if prices.is_empty() {
+        return Err((
+            axum::http::StatusCode::NOT_FOUND,
+            format!("No price data for {}", symbol),
+        ));
+    }
+    Ok(prices
+        .iter()
+        .map(|p| (session_date(p.time), p.close))
+        .collect())
+}
+
+/// Relative value of a long/short pair of symbols
+async fn get_pair_analysis(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<PairQuery>,
+) -> Result<Json<PairAnalysisResponse>, (axum::http::StatusCode, String)> {
+    let long = query.long.trim().to_uppercase();
+    let short = query.short.trim().to_uppercase();
+    if long.is_empty() || short.is_empty() || long == short {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            "long and short must be two different symbols".to_string(),
+        ));
+    }
+    let window = query.window.unwrap_or(PAIR_ZSCORE_WINDOW);
+    if window < 2 {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            "window must be at least 2 sessions".to_string(),
+        ));
+    }
+    let days = query
+        .days
+        .unwrap_or(DEFAULT_PAIR_DAYS)
+        .clamp(1, MAX_PAIR_DAYS);
+    let from = Utc::now() - Duration::days(days);
+
+    let long_closes = load_session_closes(&state, &long, from).await?;
+    let short_closes = load_session_closes(&state, &short, from).await?;
+    let aligned = align_closes(&long_closes, &short_closes);
+
+    let ratios = ratio_series(&aligned, window);
+    let latest = ratios.last();
+    let latest_zscore = latest.and_then(|p| p.zscore);
+
+    Ok(Json(PairAnalysisResponse {
+        long,
+        short,
+        window,
+        sessions: aligned.len(),
+        latest_ratio: latest.map(|p| p.ratio),
+        latest_zscore,
+        signal: latest_zscore.map(PairSignal::from_zscore),
+        cointegration: engle_granger(&aligned).ok(),
+        ratios,
+    }))
+}
+
+// ============== Correlation ==============
+
+const DEFAULT_CORRELATION_DAYS: i64 = 90;
+const MAX_CORRELATION_DAYS: i64 = 1825;
+const MAX_CORRELATION_SYMBOLS: usize = 20;
+
+#[derive(Debug, Deserialize)]
+pub struct CorrelationQuery {
+    /// Comma-separated symbols
+    symbols: String,
+    /// Calendar days of history (default 90)
+    days: Option<i64>,
+}
+
+/// A symbol's sensitivity to IHSG
+#[derive(Debug, Serialize)]
+pub struct SymbolBeta {
+    pub symbol: String,
+    /// Daily returns in common with the index
+    pub observations: usize,
+    pub beta: Option<f64>,
+    pub index_correlation: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CorrelationResponse {
+    pub days: i64,
+    pub symbols: Vec<String>,
+    /// Pearson correlation of daily log returns, in `symbols` order; `None`
+    /// where a pair has too few sessions in common
+    pub matrix: Vec<Vec<Option<f64>>>,
+    pub betas: Vec<SymbolBeta>,
+}
+
+/// Pairwise return correlations and betas against IHSG
+async fn get_correlation(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<CorrelationQuery>,
+) -> Result<Json<CorrelationResponse>, (axum::http::StatusCode, String)> {
+    let mut symbols: Vec<String> = Vec::new();
+    for symbol in query.symbols.split(',').map(|s| s.trim().to_uppercase()) {
+        if !symbol.is_empty() && !symbols.contains(&symbol) {
+            symbols.push(symbol);
+        }
+    }
+    if symbols.len() < 2 || symbols.len() > MAX_CORRELATION_SYMBOLS {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            format!("Provide 2-{} distinct symbols", MAX_CORRELATION_SYMBOLS),
+        ));
+    }
+    let days = query
+        .days
+        .unwrap_or(DEFAULT_CORRELATION_DAYS)
+        .clamp(1, MAX_CORRELATION_DAYS);
+    let from = Utc::now() - Duration::days(days);
+
+    let mut returns = Vec::with_capacity(symbols.len());
+    for symbol in &symbols {
+        returns.push(log_returns(
+            &load_session_closes(&state, symbol, from).await?,
+        ));
+    }
+    let index: Vec<(NaiveDate, Decimal)> = repositories::macro_indicators::get_macro_series(
+        &state.db,
+        MacroIndicator::CompositeIndex.as_str(),
+        from.date_naive(),
+    )
+    .await
+    .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .into_iter()
+    .map(|row| (row.observed_on, row.value))
+    .collect();
+    let index_returns = log_returns(&index);
+
+    let betas = symbols
+        .iter()
+        .zip(&returns)
+        .map(|(symbol, series)| {
+            let (asset, market) = align_returns(series, &index_returns);
+            let enough = asset.len() >= MIN_RETURN_OBSERVATIONS;
+            SymbolBeta {
+                symbol: symbol.clone(),
+                observations: asset.len(),
+                beta: enough.then(|| beta(&asset, &market)).flatten(),
+                index_correlation: enough
+                    .then(|| pearson_correlation(&asset, &market))
+                    .flatten(),
+            }
+        })
+        .collect();
+
+    Ok(Json(CorrelationResponse {
+        days,
+        matrix: correlation_matrix(&returns),
+        symbols,
+        betas,
+    }))
+}
+
+// ============== Event Study ==============
+
+const DEFAULT_EVENT_STUDY_YEARS: i64 = 5;
+const MAX_EVENT_STUDY_YEARS: i64 = 10;
+const MAX_EVENT_WINDOW_SESSIONS: usize = 60;
+
+/// Corporate event studied
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CorporateEventKind {
+    /// First publication of a period's financials
+    Earnings,
+    /// Dividend announcement
+    Dividend,
+    /// Rights issue announcement
+    RightsIssue,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EventStudyQuery {
+    event: CorporateEventKind,
+    /// One symbol, or every stock of `sector`
+    symbol: Option<String>,
+    sector: Option<String>,
+    /// Years of events (default 5)
+    years: Option<i64>,
+    /// Sessions before day 0 (default 5)
+    pre: Option<usize>,
+    /// Sessions after day 0 (default 10)
+    post: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SymbolEvent {
+    pub symbol: String,
+    #[serde(flatten)]
+    pub returns: EventAbnormalReturns,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EventStudyResponse {
+    pub event: CorporateEventKind,
+    pub symbol: Option<String>,
+    pub sector: Option<String>,
+    pub window: EventWindow,
+    pub model: ExpectedReturnModel,
+    /// Events found, including those without enough price history
+    pub events_found: usize,
+    /// `None` when no event could be measured
+    pub summary: Option<EventStudySummary>,
+    pub events: Vec<SymbolEvent>,
+}
+
+/// How a symbol or sector typically moves around a corporate event
+async fn get_event_study(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<EventStudyQuery>,
+) -> Result<Json<EventStudyResponse>, (axum::http::StatusCode, String)> {
+    let defaults = EventWindow::default();
+    let window = EventWindow {
+        pre_sessions: query.pre.unwrap_or(defaults.pre_sessions),
+        post_sessions: query.post.unwrap_or(defaults.post_sessions),
+        ..defaults
+    };
+    if window.pre_sessions > MAX_EVENT_WINDOW_SESSIONS
+        || window.post_sessions > MAX_EVENT_WINDOW_SESSIONS
+    {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            format!(
+                "pre and post cannot exceed {} sessions",
+                MAX_EVENT_WINDOW_SESSIONS
+            ),
+        ));
+    }
+
+    let symbol = query.symbol.as_deref().map(|s| s.trim().to_uppercase());
+    let symbols: Vec<String> = match (&symbol, &query.sector) {
+        (Some(symbol), None) => vec![symbol.clone()],
+        (None, Some(sector)) => repositories::stocks::get_all_stocks(&state.db)
+            .await
+            .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+            .into_iter()
+            .filter(|s| {
+                s.is_active
+                    && s.sector
+                        .as_deref()
+                        .is_some_and(|s| s.eq_ignore_ascii_case(sector))
+            })
+            .map(|s| s.symbol)
+            .collect(),
+        _ => {
+            return Err((
+                axum::http::StatusCode::BAD_REQUEST,
+                "Pass either symbol or sector".to_string(),
+            ))
+        }
+    };
+
+    let years = query
+        .years
+        .unwrap_or(DEFAULT_EVENT_STUDY_YEARS)
+        .clamp(1, MAX_EVENT_STUDY_YEARS);
+    let events_from = Utc::now().date_naive() - Duration::days(years * 365);
+    let events: Vec<(String, NaiveDate)> = match query.event {
+        CorporateEventKind::Earnings => {
+            repositories::stocks::get_earnings_report_dates(&state.db, &symbols, events_from).await
+        }
+        CorporateEventKind::Dividend | CorporateEventKind::RightsIssue => {
+            let action_type = if query.event == CorporateEventKind::Dividend {
+                "dividend"
+            } else {
+                "rights_issue"
+            };
+            repositories::corporate_actions::get_corporate_actions(
+                &state.db,
+                &symbols,
+                action_type,
+                events_from,
+            )
+            .await
+            .map(|rows| {
+                rows.into_iter()
+                    .map(|r| (r.symbol, r.announced_date))
+                    .collect()
+            })
+        }
+    }
+    .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    // Estimation window plus holidays before the first event
+    let prices_from = events_from
+        - Duration::days((window.estimation_sessions + window.pre_sessions) as i64 * 7 / 5 + 14);
+    let benchmark: Vec<(NaiveDate, Decimal)> = repositories::macro_indicators::get_macro_series(
+        &state.db,
+        MacroIndicator::CompositeIndex.as_str(),
+        prices_from,
+    )
+    .await
+    .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .into_iter()
+    .map(|row| (row.observed_on, row.value))
+    .collect();
+
+    let mut by_symbol: BTreeMap<String, Vec<NaiveDate>> = BTreeMap::new();
+    for (symbol, date) in &events {
+        by_symbol.entry(symbol.clone()).or_default().push(*date);
+    }
+
+    let model = if benchmark.is_empty() {
+        ExpectedReturnModel::ConstantMean
+    } else {
+        ExpectedReturnModel::MarketModel
+    };
+    let from = prices_from.and_time(chrono::NaiveTime::MIN).and_utc();
+    let mut measured = Vec::new();
+    for (symbol, dates) in by_symbol {
+        let closes = repositories::prices::get_price_history(&state.db, &symbol, from, Utc::now())
+            .await
+            .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+            .iter()
+            .map(|p| (session_date(p.time), p.close))
+            .collect::<Vec<_>>();
+        let (_, returns) = event_abnormal_returns(&closes, &benchmark, &dates, &window);
+        measured.extend(returns.into_iter().map(|returns| SymbolEvent {
+            symbol: symbol.clone(),
+            returns,
+        }));
+    }
+    measured.sort_by_key(|e| e.returns.day0);
+
+    let all_returns: Vec<EventAbnormalReturns> =
+        measured.iter().map(|e| e.returns.clone()).collect();
+    Ok(Json(EventStudyResponse {
+        event: query.event,
+        symbol,
+        sector: query.sector,
+        window,
+        model,
+        events_found: events.len(),
+        summary: summarize_events(&all_returns, &window),
+        events: measured,
+    }))
+}
+
+// ============== Sector Rotation ==============
+
+/// Sectors placed on a relative rotation graph against IHSG
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SectorRotationResponse {
+    pub config: RotationConfig,
+    pub computed_at: DateTime<Utc>,
+    /// Strongest RS-Ratio first
+    pub sectors: Vec<SectorRotation>,
+}
+
+async fn build_sector_rotation(
+    state: &AppState,
+    config: &RotationConfig,
+) -> Result<SectorRotationResponse, (axum::http::StatusCode, String)> {
+    // Sessions to calendar days, with room for holidays
+    let calendar_days = (config.required_sessions() * 7 / 5 + 14) as i64;
+    let now = Utc::now();
+    let from = now - Duration::days(calendar_days);
+
+    let benchmark: Vec<(NaiveDate, Decimal)> = repositories::macro_indicators::get_macro_series(
+        &state.db,
+        MacroIndicator::CompositeIndex.as_str(),
+        from.date_naive(),
+    )
+    .await
+    .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .into_iter()
+    .map(|row| (row.observed_on, row.value))
+    .collect();
+    if benchmark.is_empty() {
+        return Err((
+            axum::http::StatusCode::NOT_FOUND,
+            "No composite index data".to_string(),
+        ));
+    }
+
+    let closes = repositories::prices::get_sector_closes(&state.db, from)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    // Rows are grouped by symbol within each sector
+    let mut members: Vec<(String, Vec<(NaiveDate, Decimal)>)> = Vec::new();
+    let mut last_symbol = String::new();
+    for (sector, symbol, time, close) in closes {
+        if symbol != last_symbol {
+            members.push((sector, Vec::new()));
+            last_symbol = symbol;
+        }
+        if let Some((_, series)) = members.last_mut() {
+            series.push((session_date(time), close));
+        }
+    }
+    let members: Vec<(&str, &[(NaiveDate, Decimal)])> = members
+        .iter()
+        .map(|(sector, series)| (sector.as_str(), series.as_slice()))
+        .collect();
+
+    Ok(SectorRotationResponse {
+        config: *config,
+        computed_at: now,
+        sectors: sector_rotation(&build_sector_indices(&members), &benchmark, config),
+    })
+}
+
+/// Relative strength and rotation quadrant of each sector
+async fn get_sector_rotation(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<SectorRotationResponse>, (axum::http::StatusCode, String)> {
+    let key = CacheKeys::sector_rotation();
+    if let Some(rotation) = state
+        .analysis_cache
+        .get_key(&key)
+        .await
+        .and_then(|json| serde_json::from_str::<SectorRotationResponse>(&json).ok())
+    {
+        return Ok(Json(rotation));
+    }
+
+    let rotation = build_sector_rotation(&state, &RotationConfig::default()).await?;
+    if let Ok(json) = serde_json::to_string(&rotation) {
+        state
+            .analysis_cache
+            .put_key(
+                &key,
+                &json,
+                ON_DEMAND_ANALYSIS_TTL.current(&state.config.market_calendar),
+            )
+            .await;
+    }
+    Ok(Json(rotation))
+}
+
+// ============== Bulk Analysis ==============
+
+/// Column of the bulk analysis rows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BulkField {
+    Score,
+    TechnicalScore,
+    FundamentalScore,
+    Close,
+    Rsi,
+    Rvol,
+    WyckoffPhase,
+    BrokerAccum,
+}
+
+impl BulkField {
+    const ALL: [BulkField; 8] = [
+        BulkField::Score,
+        BulkField::TechnicalScore,
+        BulkField::FundamentalScore,
+        BulkField::Close,
+        BulkField::Rsi,
+        BulkField::Rvol,
+        BulkField::WyckoffPhase,
+        BulkField::BrokerAccum,
+    ];
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Score => "score",
+            Self::TechnicalScore => "technical_score",
+            Self::FundamentalScore => "fundamental_score",
+            Self::Close => "close",
+            Self::Rsi => "rsi",
+            Self::Rvol => "rvol",
+            Self::WyckoffPhase => "wyckoff_phase",
+            Self::BrokerAccum => "broker_accum",
+        }
+    }
+
+    /// Read from the latest stored score rather than the indicator snapshot
+    fn reads_score(&self) -> bool {
+        matches!(
+            self,
+            Self::Score | Self::TechnicalScore | Self::FundamentalScore | Self::BrokerAccum
+        )
+    }
+}
+
+/// Fields named in a comma-separated list, in order; every field when empty
+fn parse_bulk_fields(raw: Option<&str>) -> Result<Vec<BulkField>, String> {
+    let mut fields: Vec<BulkField> = Vec::new();
+    for name in raw.unwrap_or_default().split(',').map(str::trim) {
+        if name.is_empty() {
+            continue;
+        }
+        let field = BulkField::ALL
+            .into_iter()
+            .find(|f| f.as_str().eq_ignore_ascii_case(name))
+            .ok_or_else(|| {
+                format!(
+                    "Unknown field: {} (expected one of {})",
+                    name,
+                    BulkField::ALL.map(|f| f.as_str()).join(", ")
+                )
+            })?;
+        if !fields.contains(&field) {
+            fields.push(field);
+        }
+    }
+    if fields.is_empty() {
+        fields = BulkField::ALL.to_vec();
+    }
+    Ok(fields)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BulkAnalysisQuery {
+    sector: Option<String>,
+    /// Index code such as LQ45, resolved through Sectors.app
+    index: Option<String>,
+    /// Comma-separated; every field when omitted
+    fields: Option<String>,
+}
+
+/// Requested fields of one symbol; null where no value is stored
+#[derive(Debug, Serialize)]
+pub struct BulkAnalysisRow {
+    pub symbol: String,
+    #[serde(flatten)]
+    pub values: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkAnalysisResponse {
+    pub sector: Option<String>,
+    pub index: Option<String>,
+    pub fields: Vec<&'static str>,
+    /// Oldest indicator snapshot among the rows
+    pub snapshot_at: Option<DateTime<Utc>>,
+    pub rows: Vec<BulkAnalysisRow>,
+}
+
+/// Active symbols of the requested sector or index
+async fn bulk_members(
+    state: &AppState,
+    query: &BulkAnalysisQuery,
+) -> Result<Vec<String>, (axum::http::StatusCode, String)> {
+    let stocks = repositories::stocks::get_all_stocks(&state.db)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let members: Vec<String> = match (&query.sector, &query.index) {
+        (Some(sector), None) => stocks
+            .into_iter()
+            .filter(|s| {
+                s.sector
+                    .as_deref()
+                    .is_some_and(|name| name.eq_ignore_ascii_case(sector.trim()))
+            })
+            .map(|s| s.symbol)
+            .collect(),
+        (None, Some(index)) => {
+            let api_key = crate::secrets::resolve_secret(state, "SECTORS_API_KEY")
+                .await
+                .ok_or_else(|| {
+                    (
+                        axum::http::StatusCode::SERVICE_UNAVAILABLE,
+                        "Index constituents need a Sectors.app API key".to_string(),
+                    )
+                })?;
+            let client = SectorsClient::new(api_key)
+                .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+                .with_usage_tracker(state.api_usage.clone());
+            let constituents: HashSet<String> = client
+                .get_companies_by_index(&index.trim().to_lowercase())
+                .await
+                .map_err(|e| (axum::http::StatusCode::BAD_GATEWAY, e.to_string()))?
+                .iter()
+                .map(|symbol| canonical_symbol(symbol))
+                .collect();
+            stocks
+                .into_iter()
+                .filter(|s| constituents.contains(&s.symbol))
+                .map(|s| s.symbol)
+                .collect()
+        }
+        _ => {
+            return Err((
+                axum::http::StatusCode::BAD_REQUEST,
+                "Give exactly one of sector or index".to_string(),
+            ))
+        }
+    };
+    if members.is_empty() {
+        return Err((
+            axum::http::StatusCode::NOT_FOUND,
+            "No active stocks in this sector or index".to_string(),
+        ));
+    }
+    Ok(members)
+}
+
+/// Compact analysis rows for every stock of a sector or index
+///
+/// Values come from the latest stored scores and the indicator snapshots
+/// refreshed after the close, so nothing is recomputed per symbol.
+async fn get_bulk_analysis(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<BulkAnalysisQuery>,
+) -> Result<Json<BulkAnalysisResponse>, (axum::http::StatusCode, String)> {
+    let fields = parse_bulk_fields(query.fields.as_deref())
+        .map_err(|e| (axum::http::StatusCode::BAD_REQUEST, e))?;
+    let members = bulk_members(&state, &query).await?;
+
+    let mut scores = HashMap::new();
+    if fields.iter().any(BulkField::reads_score) {
+        let wanted: HashSet<&str> = members.iter().map(String::as_str).collect();
+        scores = repositories::scores::get_latest_scores(&state.db, i32::MAX)
+            .await
+            .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+            .into_iter()
+            .filter(|s| wanted.contains(s.symbol.as_str()))
+            .map(|s| (s.symbol.clone(), s))
+            .collect();
+    }
+    let mut snapshots = HashMap::new();
+    if fields.iter().any(|f| !f.reads_score()) {
+        snapshots = repositories::indicator_snapshots::get_indicator_snapshots(&state.db, &members)
+            .await
+            .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+            .into_iter()
+            .map(|s| (s.symbol.clone(), s))
+            .collect();
+    }
+
+    let rows = members
+        .into_iter()
+        .map(|symbol| {
+            let score = scores.get(&symbol);
+            let snapshot = snapshots.get(&symbol);
+            let values = fields
+                .iter()
+                .map(|field| {
+                    let value = match field {
+                        BulkField::Score => score.map(|s| serde_json::json!(s.composite_score)),
+                        BulkField::TechnicalScore => {
+                            score.map(|s| serde_json::json!(s.technical_score))
+                        }
+                        BulkField::FundamentalScore => {
+                            score.map(|s| serde_json::json!(s.fundamental_score))
+                        }
+                        BulkField::BrokerAccum => score
+                            .and_then(|s| s.technical_breakdown.clone())
+                            .and_then(|b| serde_json::from_value::<TechnicalScoreBreakdown>(b).ok())
+                            .map(|b| serde_json::json!(b.broker_score)),
+                        BulkField::Close => {
+                            snapshot.and_then(|s| s.close).map(|v| serde_json::json!(v))
+                        }
+                        BulkField::Rsi => {
+                            snapshot.and_then(|s| s.rsi).map(|v| serde_json::json!(v))
+                        }
+                        BulkField::Rvol => {
+                            snapshot.and_then(|s| s.rvol).map(|v| serde_json::json!(v))
+                        }
+                        BulkField::WyckoffPhase => snapshot
+                            .and_then(|s| s.wyckoff_phase.clone())
+                            .map(serde_json::Value::String),
+                    };
+                    (field.as_str().to_string(), value.unwrap_or_default())
+                })
+                .collect();
+            BulkAnalysisRow { symbol, values }
+        })
+        .collect();
+
+    Ok(Json(BulkAnalysisResponse {
+        sector: query.sector.clone(),
+        index: query.index.clone(),
+        fields: fields.iter().map(BulkField::as_str).collect(),
+        snapshot_at: snapshots.values().map(|s| s.computed_at).min(),
+        rows,
+    }))
+}
+
+// ============== Broker Coverage ==============
+
+const DEFAULT_COVERAGE_DAYS: i64 = 90;
+const MAX_COVERAGE_DAYS: i64 = 365;
+/// Coverage at or above this share of trading days counts as complete
+const COMPLETE_COVERAGE_PCT: f64 = 95.0;
+
+#[derive(Debug, Deserialize)]
+pub struct BrokerCoverageQuery {
+    /// Calendar days to look back (default 90)
+    days: Option<i64>,
+}
+
+impl BrokerCoverageQuery {
+    fn window(&self) -> Result<(NaiveDate, NaiveDate), (axum::http::StatusCode, String)> {
+        let days = self.days.unwrap_or(DEFAULT_COVERAGE_DAYS);
+        if days <= 0 || days > MAX_COVERAGE_DAYS {
+            return Err((
+                axum::http::StatusCode::BAD_REQUEST,
+                format!("days must be between 1 and {}", MAX_COVERAGE_DAYS),
+            ));
+        }
+        let to = Utc::now().date_naive();
+        Ok((to - Duration::days(days - 1), to))
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct BrokerCoverageDay {
+    pub date: NaiveDate,
+    pub sources: Vec<String>,
+    pub broker_count: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BrokerCoverageSource {
+    pub source: String,
+    pub days: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BrokerCoverageResponse {
+    pub symbol: String,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// Trading days in the window (days with price data, or calendar trading days if none)
+    pub expected_days: usize,
+    pub covered_days: usize,
+    pub coverage_pct: f64,
+    /// Whether coverage is high enough to trust accumulation scores
+    pub complete: bool,
+    pub missing_dates: Vec<NaiveDate>,
+    pub sources: Vec<BrokerCoverageSource>,
+    pub days: Vec<BrokerCoverageDay>,
+}
 
-    Ok(Json(FullAnalysisResponse {
-        symbol: upper_symbol,
-        name: stock.name,
-        sector: stock.sector,
-        broker_summary,
-        technical,
-        valuation,
-        conclusion,
-    }))
+#[derive(Debug, Serialize)]
+pub struct BrokerCoverageOverviewItem {
+    pub symbol: String,
+    pub expected_days: i64,
+    pub covered_days: i64,
+    pub coverage_pct: f64,
+    pub complete: bool,
+    pub last_date: Option<NaiveDate>,
 }
 
-async fn get_technicals(
+#[derive(Debug, Serialize)]
+pub struct BrokerCoverageOverview {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub stocks: Vec<BrokerCoverageOverviewItem>,
+}
+
+async fn get_broker_coverage(
     _user: AuthUser,
     State(state): State<Arc<AppState>>,
     Path(symbol): Path<String>,
-    Query(query): Query<AnalysisQuery>,
-) -> Result<Json<TechnicalResponse>, (axum::http::StatusCode, String)> {
+    Query(query): Query<BrokerCoverageQuery>,
+) -> Result<Json<BrokerCoverageResponse>, (axum::http::StatusCode, String)> {
     let upper_symbol = symbol.to_uppercase();
-    let days = query.days.unwrap_or(90);
+    let (from, to) = query.window()?;
 
-    // Verify stock exists
     repositories::stocks::get_stock_by_symbol(&state.db, &upper_symbol)
         .await
         .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
@@ -272,52 +2254,293 @@ async fn get_technicals(
             )
         })?;
 
-    get_technical_analysis(&state, &upper_symbol, days)
+    let start = from.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+    let end = Utc::now();
+
+    let price_days = repositories::prices::get_price_days(&state.db, &upper_symbol, start, end)
         .await
-        .map(Json)
-}
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let broker_days = repositories::broker_summary::get_broker_coverage_days(
+        &state.db,
+        &upper_symbol,
+        start,
+        end,
+    )
+    .await
+    .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let expected = if price_days.is_empty() {
+        trading_days_between(&state.config.market_calendar, from, to)
+    } else {
+        price_days
+    };
+    let days = broker_days
+        .into_iter()
+        .map(|row| BrokerCoverageDay {
+            date: row.day,
+            sources: row.sources,
+            broker_count: row.broker_count,
+        })
+        .collect();
 
-#[derive(Debug, Deserialize)]
-pub struct BrokerFlowQuery {
-    days: Option<i32>,
+    Ok(Json(summarize_broker_coverage(
+        upper_symbol,
+        from,
+        to,
+        &expected,
+        days,
+    )))
 }
 
-async fn get_broker_flow(
+async fn get_broker_coverage_overview(
     _user: AuthUser,
     State(state): State<Arc<AppState>>,
-    Path(symbol): Path<String>,
-    Query(query): Query<BrokerFlowQuery>,
-) -> Result<Json<BrokerSummaryResponse>, (axum::http::StatusCode, String)> {
-    let upper_symbol = symbol.to_uppercase();
-    let days = query.days.unwrap_or(5);
+    Query(query): Query<BrokerCoverageQuery>,
+) -> Result<Json<BrokerCoverageOverview>, (axum::http::StatusCode, String)> {
+    let (from, to) = query.window()?;
+    let start = from.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
 
-    // Verify stock exists
-    repositories::stocks::get_stock_by_symbol(&state.db, &upper_symbol)
-        .await
-        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
-        .ok_or_else(|| {
-            (
-                axum::http::StatusCode::NOT_FOUND,
-                format!("Stock not found: {}", upper_symbol),
-            )
-        })?;
+    let rows =
+        repositories::broker_summary::get_broker_coverage_summary(&state.db, start, Utc::now())
+            .await
+            .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
-    get_broker_flow_internal(&state, &upper_symbol, days)
-        .await
-        .map(Json)
+    let stocks = rows
+        .into_iter()
+        .map(|row| {
+            let coverage_pct = coverage_pct(row.covered_days as usize, row.trading_days as usize);
+            BrokerCoverageOverviewItem {
+                symbol: row.symbol,
+                expected_days: row.trading_days,
+                covered_days: row.covered_days,
+                coverage_pct,
+                complete: row.trading_days > 0 && coverage_pct >= COMPLETE_COVERAGE_PCT,
+                last_date: row.last_day,
+            }
+        })
+        .collect();
+
+    Ok(Json(BrokerCoverageOverview { from, to, stocks }))
+}
+
+/// Measure broker data days against the expected trading days
+fn summarize_broker_coverage(
+    symbol: String,
+    from: NaiveDate,
+    to: NaiveDate,
+    expected: &[NaiveDate],
+    days: Vec<BrokerCoverageDay>,
+) -> BrokerCoverageResponse {
+    let present: HashSet<NaiveDate> = days.iter().map(|d| d.date).collect();
+    let missing_dates: Vec<NaiveDate> = expected
+        .iter()
+        .filter(|d| !present.contains(d))
+        .copied()
+        .collect();
+    let covered_days = expected.len() - missing_dates.len();
+
+    let mut per_source: BTreeMap<&str, usize> = BTreeMap::new();
+    for day in &days {
+        for source in &day.sources {
+            *per_source.entry(source.as_str()).or_default() += 1;
+        }
+    }
+    let sources = per_source
+        .into_iter()
+        .map(|(source, days)| BrokerCoverageSource {
+            source: source.to_string(),
+            days,
+        })
+        .collect();
+
+    let coverage_pct = coverage_pct(covered_days, expected.len());
+    BrokerCoverageResponse {
+        symbol,
+        from,
+        to,
+        expected_days: expected.len(),
+        covered_days,
+        coverage_pct,
+        complete: !expected.is_empty() && coverage_pct >= COMPLETE_COVERAGE_PCT,
+        missing_dates,
+        sources,
+        days,
+    }
+}
+
+fn coverage_pct(covered: usize, expected: usize) -> f64 {
+    if expected == 0 {
+        return 0.0;
+    }
+    (covered as f64 / expected as f64 * 1000.0).round() / 10.0
+}
+
+fn trading_days_between(
+    calendar: &MarketCalendar,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Vec<NaiveDate> {
+    from.iter_days()
+        .take_while(|d| *d <= to)
+        .filter(|d| calendar.is_trading_day(*d))
+        .collect()
+}
+
+// ============== Indicator Registry ==============
+
+/// Indicators of the technical crate with parameters, outputs and warm-up
+async fn list_indicators(_user: AuthUser) -> Json<&'static [IndicatorInfo]> {
+    Json(jejakcuan_technical::INDICATORS)
 }
 
 // ============== Internal Functions ==============
 
-async fn get_technical_analysis(
+/// RSI, MACD and EMA trend on one timeframe's bars
+fn timeframe_technicals(timeframe: Timeframe, bars: &[DatedBar]) -> TimeframeTechnicals {
+    let closes: Vec<Decimal> = bars.iter().map(|b| b.bar.close).collect();
+    let last_close = closes.last().copied();
+
+    let rsi = calculate_rsi14(&closes).ok().and_then(|r| r.last_valid());
+    let macd = calculate_macd(&closes).ok();
+    let ema20 = calculate_ema20(&closes).ok().and_then(|e| e.last_valid());
+    let ema50 = calculate_ema50(&closes).ok().and_then(|e| e.last_valid());
+
+    let trend = match (last_close, ema20, ema50) {
+        (Some(close), Some(e20), Some(e50)) if close > e20 && e20 > e50 => "bullish",
+        (Some(close), Some(e20), Some(e50)) if close < e20 && e20 < e50 => "bearish",
+        _ => "neutral",
+    };
+
+    TimeframeTechnicals {
+        timeframe,
+        bars: bars.len(),
+        as_of: bars.last().map(|b| b.date),
+        last_close: last_close.and_then(|c| c.to_f64()),
+        rsi: rsi.and_then(|r| r.to_f64()),
+        rsi_signal: rsi.map(|r| rsi_signal(r).to_string()),
+        macd: macd
+            .as_ref()
+            .and_then(|m| m.macd_line.last_valid())
+            .and_then(|v| v.to_f64()),
+        macd_signal_line: macd
+            .as_ref()
+            .and_then(|m| m.signal_line.last_valid())
+            .and_then(|v| v.to_f64()),
+        macd_histogram: macd
+            .as_ref()
+            .and_then(|m| m.histogram.last_valid())
+            .and_then(|v| v.to_f64()),
+        macd_signal: macd.as_ref().map(|m| macd_signal(m).to_string()),
+        ema20: ema20.and_then(|e| e.to_f64()),
+        ema50: ema50.and_then(|e| e.to_f64()),
+        trend,
+    }
+}
+
+fn trend_alignment(timeframes: &[TimeframeTechnicals]) -> &'static str {
+    let first = timeframes.first().map(|t| t.trend);
+    match first {
+        Some(trend @ ("bullish" | "bearish")) if timeframes.iter().all(|t| t.trend == trend) => {
+            trend
+        }
+        _ => "mixed",
+    }
+}
+
+/// Time budget for each section of the full analysis
+const SECTION_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Cache lifetime of a full analysis computed on request; warmed entries
+/// live until the next session opens, and the post-close warm-up overwrites
+/// anything cached off-hours before the end-of-day data arrived
+const ON_DEMAND_ANALYSIS_TTL: MarketAwareTtl = MarketAwareTtl::new(
+    std::time::Duration::from_secs(5 * 60),
+    std::time::Duration::from_secs(6 * 60 * 60),
+);
+
+/// How long a full analysis waits behind another one for the same symbol
+const ANALYSIS_LOCK_WAIT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Prices older than this (calendar days) mark the technical section stale
+const STALE_PRICE_DAYS: i64 = 5;
+
+/// Run one full-analysis section within its time budget
+///
+/// `BAD_REQUEST` errors mean the section lacks data; anything else, including
+/// a timeout, is a source error.
+async fn run_section<T>(
+    section: impl std::future::Future<Output = Result<T, (axum::http::StatusCode, String)>>,
+) -> (Option<T>, SectionReport) {
+    let started = Instant::now();
+    let result = tokio::time::timeout(SECTION_TIMEOUT, section).await;
+    let elapsed_ms = started.elapsed().as_millis() as u64;
+
+    let (value, status, message) = match result {
+        Ok(Ok(value)) => (Some(value), SectionStatus::Ok, None),
+        Ok(Err((code, message))) if code == axum::http::StatusCode::BAD_REQUEST => {
+            (None, SectionStatus::InsufficientData, Some(message))
+        }
+        Ok(Err((_, message))) => (None, SectionStatus::SourceError, Some(message)),
+        Err(_) => (
+            None,
+            SectionStatus::SourceError,
+            Some(format!("Timed out after {}s", SECTION_TIMEOUT.as_secs())),
+        ),
+    };
+
+    (
+        value,
+        SectionReport {
+            status,
+            message,
+            elapsed_ms,
+        },
+    )
+}
+
+/// Daily bars of `symbol` since `from`, from the in-memory price store
+/// when it holds them
+async fn load_price_window(
+    state: &AppState,
+    symbol: &str,
+    from: DateTime<Utc>,
+    adjusted: bool,
+) -> Result<PriceWindow, sqlx::Error> {
+    if !adjusted {
+        let window = analysis_timings::time_async(
+            "db.prices",
+            state.price_store.window(&state.db, symbol, from),
+        )
+        .await?;
+        if let Some(window) = window {
+            return Ok(window);
+        }
+    }
+    let mut prices = analysis_timings::time_async(
+        "db.prices",
+        repositories::prices::get_price_history(&state.db, symbol, from, Utc::now()),
+    )
+    .await?;
+    if adjusted {
+        prices = analysis_timings::time_async(
+            "db.corporate_actions",
+            corporate_actions::adjust_price_rows(&state.db, symbol, prices),
+        )
+        .await?;
+    }
+    Ok(PriceWindow::new(PriceColumns::from_rows(&prices)))
+}
+
+/// Indicators over the last `days` of prices, split/dividend-adjusted
+/// with `adjusted`
+pub(crate) async fn get_technical_analysis(
     state: &AppState,
     symbol: &str,
     days: i32,
+    adjusted: bool,
 ) -> Result<TechnicalResponse, (axum::http::StatusCode, String)> {
     let from = Utc::now() - Duration::days(days as i64);
-    let to = Utc::now();
-
-    let prices = repositories::prices::get_price_history(&state.db, symbol, from, to)
+    let prices = load_price_window(state, symbol, from, adjusted)
         .await
         .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
@@ -328,96 +2551,118 @@ async fn get_technical_analysis(
         ));
     }
 
-    // Extract close prices
-    let close_prices: Vec<Decimal> = prices.iter().map(|p| p.close).collect();
-    let last_price = close_prices.last().copied().unwrap_or(Decimal::ZERO);
-    let last_price_f64 = last_price.to_f64().unwrap_or(0.0);
+    let close_prices = prices.close();
+    let last_price = close_prices.last().copied().unwrap_or(0.0);
 
     // Calculate RSI
-    let rsi_values = calculate_rsi14(&close_prices).map_err(|e| {
-        (
-            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-            format!("RSI calculation error: {}", e),
-        )
-    })?;
-    let rsi = rsi_values.last().copied().unwrap_or(dec!(50));
-    let rsi_f64 = rsi.to_f64().unwrap_or(50.0);
-    let rsi_sig = rsi_signal(rsi).to_string();
+    let rsi = analysis_timings::time("indicator.rsi", || columnar::rsi(close_prices, 14)).map_err(
+        |e| {
+            (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                format!("RSI calculation error: {}", e),
+            )
+        },
+    )?;
+    let rsi_sig = columnar::rsi_signal(rsi).to_string();
 
     // Calculate MACD
-    let macd_result = calculate_macd(&close_prices).map_err(|e| {
+    let macd = analysis_timings::time("indicator.macd", || columnar::macd_standard(close_prices))
+        .map_err(|e| {
         (
             axum::http::StatusCode::INTERNAL_SERVER_ERROR,
             format!("MACD calculation error: {}", e),
         )
     })?;
-    let macd_value = macd_result
-        .macd_line
-        .last()
-        .copied()
-        .unwrap_or(Decimal::ZERO);
-    let macd_hist = macd_result
-        .histogram
-        .last()
-        .copied()
-        .unwrap_or(Decimal::ZERO);
-    let macd_sig = macd_signal(&macd_result).to_string();
+    let macd_sig = macd.signal_label().to_string();
 
     // Calculate Bollinger Bands
-    let bollinger = calculate_bollinger_bands(&close_prices).map_err(|e| {
+    let bollinger = analysis_timings::time("indicator.bollinger", || {
+        columnar::bollinger_bands(close_prices, 20, 2.0)
+    })
+    .map_err(|e| {
         (
             axum::http::StatusCode::INTERNAL_SERVER_ERROR,
             format!("Bollinger Bands calculation error: {}", e),
         )
     })?;
 
-    // Calculate support and resistance from recent price action
-    let (support, resistance) = calculate_support_resistance(&prices);
+    // Calculate support and resistance from recent price action, then add the user's levels
+    let (support, resistance) = analysis_timings::time("indicator.support_resistance", || {
+        calculate_support_resistance(prices.low(), prices.high())
+    });
+    let user_levels = analysis_timings::time_async(
+        "db.user_levels",
+        repositories::watchlist::get_watchlist_levels(&state.db, symbol),
+    )
+    .await
+    .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let (support, resistance) = merge_user_levels(support, resistance, &user_levels, last_price);
+
+    // Calculate ATR and the chandelier exit for volatility-based stops
+    let (highs, lows) = (prices.high(), prices.low());
+    let multiplier = CHANDELIER_MULTIPLIER.to_f64().unwrap_or(3.0);
+    let atr = analysis_timings::time("indicator.atr", || {
+        columnar::atr(highs, lows, close_prices, 14).ok()
+    });
+    let atr_stop = analysis_timings::time("indicator.chandelier_exit", || {
+        columnar::chandelier_long_stop22(highs, lows, close_prices, multiplier).ok()
+    })
+    .filter(|stop| *stop < last_price)
+    .or_else(|| atr.map(|atr| (last_price - atr * multiplier).max(0.0)));
+
+    // Calculate VWAP over the requested window
+    let vwap = analysis_timings::time("indicator.vwap", || {
+        columnar::vwap(highs, lows, close_prices, prices.volume())
+    })
+    .ok();
+    let price_vs_vwap_percent = vwap
+        .filter(|v| *v > 0.0)
+        .map(|v| (last_price - v) / v * 100.0);
 
     // Calculate Ichimoku (simplified)
-    let ichimoku = calculate_ichimoku(&close_prices, last_price);
+    let ichimoku = analysis_timings::time("indicator.ichimoku", || {
+        calculate_ichimoku(close_prices, last_price)
+    });
 
     // Generate TA summary
     let summary = generate_ta_summary(rsi, &macd_sig, last_price, &bollinger);
 
     Ok(TechnicalResponse {
-        last_price: last_price_f64,
-        rsi: rsi_f64,
+        last_price,
+        as_of: prices.time().last().copied().unwrap_or_else(Utc::now),
+        rsi,
         rsi_signal: rsi_sig,
-        macd: macd_value.to_f64().unwrap_or(0.0),
+        macd: macd.line,
         macd_signal: macd_sig,
-        macd_histogram: macd_hist.to_f64().unwrap_or(0.0),
+        macd_histogram: macd.histogram,
         bollinger: BollingerResponse {
-            upper: bollinger
-                .upper
-                .last()
-                .copied()
-                .unwrap_or(Decimal::ZERO)
-                .to_f64()
-                .unwrap_or(0.0),
-            middle: bollinger
-                .middle
-                .last()
-                .copied()
-                .unwrap_or(Decimal::ZERO)
-                .to_f64()
-                .unwrap_or(0.0),
-            lower: bollinger
-                .lower
-                .last()
-                .copied()
-                .unwrap_or(Decimal::ZERO)
-                .to_f64()
-                .unwrap_or(0.0),
+            upper: bollinger.upper,
+            middle: bollinger.middle,
+            lower: bollinger.lower,
         },
         ichimoku,
         support,
         resistance,
+        user_levels: user_levels
+            .into_iter()
+            .map(|l| UserLevelInfo {
+                id: l.id,
+                price: l.price.to_f64().unwrap_or(0.0),
+                kind: l.kind,
+                label: l.label,
+                note: l.note,
+                alert_enabled: l.alert_enabled,
+            })
+            .collect(),
+        atr,
+        atr_stop,
+        vwap,
+        price_vs_vwap_percent,
         summary,
     })
 }
 
-async fn get_broker_flow_internal(
+pub(crate) async fn get_broker_flow_internal(
     state: &AppState,
     symbol: &str,
     days: i32,
@@ -426,19 +2671,27 @@ async fn get_broker_flow_internal(
     let to = Utc::now();
     let from_20 = Utc::now() - Duration::days(20);
 
-    let aggregates =
-        repositories::broker_summary::get_broker_flow_aggregates(&state.db, symbol, from, to)
-            .await
-            .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-
-    let price_range = repositories::broker_summary::get_price_range(&state.db, symbol, from, to)
-        .await
-        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-
-    let daily_summaries =
-        repositories::broker_summary::get_daily_broker_summaries(&state.db, symbol, from_20, to)
-            .await
-            .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let aggregates = analysis_timings::time_async(
+        "db.broker_aggregates",
+        repositories::broker_summary::get_broker_flow_aggregates(&state.db, symbol, from, to),
+    )
+    .await
+    .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let price_range = analysis_timings::time_async(
+        "db.price_range",
+        repositories::broker_summary::get_price_range(&state.db, symbol, from, to),
+    )
+    .await
+    .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let daily_summaries = analysis_timings::time_async(
+        "db.daily_broker_summaries",
+        repositories::broker_summary::get_daily_broker_summaries(&state.db, symbol, from_20, to),
+    )
+    .await
+    .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let aggregation_started = Instant::now();
 
     let mut foreign_net = 0.0;
     let mut domestic_net = 0.0;
@@ -544,6 +2797,7 @@ async fn get_broker_flow_internal(
     if let Some(ref mut inst) = institutional_analysis {
         inst.suspicious_activity = suspicious;
     }
+    analysis_timings::record("broker.aggregation", aggregation_started.elapsed());
 
     Ok(BrokerSummaryResponse {
         big_buyers,
@@ -559,7 +2813,7 @@ async fn get_broker_flow_internal(
     })
 }
 
-fn calculate_institutional_flow_analysis(
+pub(crate) fn calculate_institutional_flow_analysis(
     aggregates: &[repositories::broker_summary::BrokerFlowAggregateRow],
     daily_summaries: &[repositories::broker_summary::DailyBrokerSummaryRow],
 ) -> Option<InstitutionalFlowAnalysis> {
@@ -749,20 +3003,11 @@ fn calculate_institutional_flow_analysis(
     })
 }
 
-fn calculate_support_resistance(prices: &[jejakcuan_db::StockPriceRow]) -> (Vec<f64>, Vec<f64>) {
-    if prices.is_empty() {
+fn calculate_support_resistance(lows: &[f64], highs: &[f64]) -> (Vec<f64>, Vec<f64>) {
+    if lows.is_empty() {
         return (vec![], vec![]);
     }
 
-    let lows: Vec<f64> = prices
-        .iter()
-        .map(|p| p.low.to_f64().unwrap_or(0.0))
-        .collect();
-    let highs: Vec<f64> = prices
-        .iter()
-        .map(|p| p.high.to_f64().unwrap_or(0.0))
-        .collect();
-
     // Find local minima for support
     let mut support_levels: Vec<f64> = Vec::new();
     for i in 1..lows.len().saturating_sub(1) {
@@ -796,6 +3041,51 @@ fn calculate_support_resistance(prices: &[jejakcuan_db::StockPriceRow]) -> (Vec<
     (support, resistance)
 }
 
+/// Add user levels to computed support/resistance
+///
+/// User levels below the last price count as support, above as resistance.
+/// Computed levels within 2% of a user level are dropped in its favour.
+fn merge_user_levels(
+    support: Vec<f64>,
+    resistance: Vec<f64>,
+    user_levels: &[jejakcuan_db::WatchlistLevelRow],
+    last_price: f64,
+) -> (Vec<f64>, Vec<f64>) {
+    let user_prices: Vec<f64> = user_levels
+        .iter()
+        .filter_map(|l| l.price.to_f64())
+        .collect();
+    if user_prices.is_empty() {
+        return (support, resistance);
+    }
+
+    let near_user_level = |level: &f64| {
+        user_prices
+            .iter()
+            .any(|p| (level - p).abs() / p.max(f64::EPSILON) < 0.02)
+    };
+    let mut support: Vec<f64> = support
+        .into_iter()
+        .filter(|l| !near_user_level(l))
+        .collect();
+    let mut resistance: Vec<f64> = resistance
+        .into_iter()
+        .filter(|l| !near_user_level(l))
+        .collect();
+
+    for price in user_prices {
+        if price <= last_price {
+            support.push(price);
+        } else {
+            resistance.push(price);
+        }
+    }
+
+    support.sort_by(|a, b| a.total_cmp(b));
+    resistance.sort_by(|a, b| b.total_cmp(a));
+    (support, resistance)
+}
+
 fn deduplicate_levels(levels: &[f64], tolerance: f64) -> Vec<f64> {
     let mut result: Vec<f64> = Vec::new();
     for &level in levels {
@@ -809,7 +3099,7 @@ fn deduplicate_levels(levels: &[f64], tolerance: f64) -> Vec<f64> {
     result
 }
 
-fn calculate_ichimoku(prices: &[Decimal], current_price: Decimal) -> IchimokuInfo {
+fn calculate_ichimoku(prices: &[f64], current_price: f64) -> IchimokuInfo {
     // Simplified Ichimoku: use 26-period high/low for cloud
     let period = 26.min(prices.len());
     if period < 9 {
@@ -821,23 +3111,23 @@ fn calculate_ichimoku(prices: &[Decimal], current_price: Decimal) -> IchimokuInf
             },
         };
     }
+    let high_low = |window: &[f64]| {
+        let high = window.iter().copied().fold(f64::MIN, f64::max);
+        let low = window.iter().copied().fold(f64::MAX, f64::min);
+        (high, low)
+    };
 
-    let recent = &prices[prices.len().saturating_sub(period)..];
-    let high = recent.iter().max().copied().unwrap_or(Decimal::ZERO);
-    let low = recent.iter().min().copied().unwrap_or(Decimal::ZERO);
+    let (high, low) = high_low(&prices[prices.len() - period..]);
 
     // Tenkan-sen (Conversion Line) - 9-period high+low / 2
-    let tenkan_period = 9.min(prices.len());
-    let tenkan_recent = &prices[prices.len().saturating_sub(tenkan_period)..];
-    let tenkan_high = tenkan_recent.iter().max().copied().unwrap_or(Decimal::ZERO);
-    let tenkan_low = tenkan_recent.iter().min().copied().unwrap_or(Decimal::ZERO);
-    let tenkan = (tenkan_high + tenkan_low) / dec!(2);
+    let (tenkan_high, tenkan_low) = high_low(&prices[prices.len() - 9..]);
+    let tenkan = (tenkan_high + tenkan_low) / 2.0;
 
     // Kijun-sen (Base Line) - 26-period
-    let kijun = (high + low) / dec!(2);
+    let kijun = (high + low) / 2.0;
 
     // Span A = (Tenkan + Kijun) / 2
-    let span_a = (tenkan + kijun) / dec!(2);
+    let span_a = (tenkan + kijun) / 2.0;
     // Span B = (52-period high + low) / 2 (simplified to 26-period)
     let span_b = kijun;
 
@@ -855,30 +3145,30 @@ fn calculate_ichimoku(prices: &[Decimal], current_price: Decimal) -> IchimokuInf
     IchimokuInfo {
         position: position.to_string(),
         cloud_range: PriceRange {
-            low: cloud_low.to_f64().unwrap_or(0.0),
-            high: cloud_high.to_f64().unwrap_or(0.0),
+            low: cloud_low,
+            high: cloud_high,
         },
     }
 }
 
 fn generate_ta_summary(
-    rsi: Decimal,
+    rsi: f64,
     macd_sig: &str,
-    price: Decimal,
-    bollinger: &BollingerBands,
+    price: f64,
+    bollinger: &columnar::BollingerBands,
 ) -> TASummary {
     let mut buy = 0;
     let mut sell = 0;
     let mut neutral = 0;
 
     // RSI signal
-    if rsi <= dec!(30) {
+    if rsi <= 30.0 {
         buy += 2; // Oversold = buy signal
-    } else if rsi >= dec!(70) {
+    } else if rsi >= 70.0 {
         sell += 2; // Overbought = sell signal
-    } else if rsi <= dec!(40) {
+    } else if rsi <= 40.0 {
         buy += 1;
-    } else if rsi >= dec!(60) {
+    } else if rsi >= 60.0 {
         sell += 1;
     } else {
         neutral += 2;
@@ -892,14 +3182,12 @@ fn generate_ta_summary(
     }
 
     // Bollinger Bands signal
-    if let (Some(&upper), Some(&lower)) = (bollinger.upper.last(), bollinger.lower.last()) {
-        if price <= lower {
-            buy += 2; // Price at lower band
-        } else if price >= upper {
-            sell += 2; // Price at upper band
-        } else {
-            neutral += 2;
-        }
+    if price <= bollinger.lower {
+        buy += 2; // Price at lower band
+    } else if price >= bollinger.upper {
+        sell += 2; // Price at upper band
+    } else {
+        neutral += 2;
     }
 
     // Add some baseline signals
@@ -937,6 +3225,8 @@ fn generate_valuation_conclusion(
             low: bull_low,
             high: bull_high,
         },
+        street_consensus: None,
+        fcf_yield: None,
     };
 
     // Generate conclusion based on technical signals
@@ -1045,13 +3335,232 @@ fn generate_valuation_conclusion(
     (Some(valuation), Some(conclusion))
 }
 
-#[allow(dead_code)]
+/// Consensus snapshots older than this are refreshed from Sectors.app
+const TARGET_PRICE_MAX_AGE_DAYS: i64 = 7;
+
+/// Latest stored target price consensus, refreshed from Sectors.app when stale
+///
+/// Falls back to the stored snapshot when the API key is missing or the
+/// request fails.
+async fn load_target_consensus(state: &AppState, symbol: &str) -> Option<AnalystTargetPriceRow> {
+    let stored = repositories::target_prices::get_latest_target_price(&state.db, symbol)
+        .await
+        .ok()
+        .flatten();
+
+    let today = Utc::now().date_naive();
+    let is_fresh = stored
+        .as_ref()
+        .is_some_and(|row| (today - row.as_of).num_days() < TARGET_PRICE_MAX_AGE_DAYS);
+    if is_fresh {
+        return stored;
+    }
+
+    let Some(api_key) = crate::secrets::resolve_secret(state, "SECTORS_API_KEY").await else {
+        return stored;
+    };
+    let Ok(client) = SectorsClient::new(api_key) else {
+        return stored;
+    };
+    let client = client.with_usage_tracker(state.api_usage.clone());
+    let sectors_symbol = match load_symbol_mapper(&state.db).await {
+        Ok(mapper) => mapper.to_provider(symbol, SymbolProvider::Sectors),
+        Err(_) => symbol.to_string(),
+    };
+    match client.get_target_price(&sectors_symbol).await {
+        Ok(consensus) => store_target_consensus(state, symbol, &consensus)
+            .await
+            .or(stored),
+        Err(e) => {
+            tracing::warn!(
+                "Failed to fetch target price consensus for {}: {}",
+                symbol,
+                e
+            );
+            stored
+        }
+    }
+}
+
+async fn store_target_consensus(
+    state: &AppState,
+    symbol: &str,
+    consensus: &TargetPriceConsensus,
+) -> Option<AnalystTargetPriceRow> {
+    let target = InsertAnalystTargetPrice {
+        symbol,
+        as_of: consensus
+            .updated_on
+            .unwrap_or_else(|| Utc::now().date_naive()),
+        source: "sectors_app",
+        target_mean: consensus.target_price_mean,
+        target_median: consensus.target_price_median,
+        target_high: consensus.target_price_high,
+        target_low: consensus.target_price_low,
+        analyst_count: consensus.analyst_count,
+        strong_buy: consensus.ratings.strong_buy,
+        buy: consensus.ratings.buy,
+        hold: consensus.ratings.hold,
+        sell: consensus.ratings.sell,
+        strong_sell: consensus.ratings.strong_sell,
+    };
+
+    repositories::target_prices::upsert_analyst_target_price(&state.db, &target)
+        .await
+        .map_err(|e| tracing::warn!("Failed to store target price consensus: {}", e))
+        .ok()
+}
+
+fn street_consensus_section(
+    row: &AnalystTargetPriceRow,
+    valuation: &ValuationResponse,
+    technical: &TechnicalResponse,
+) -> Option<StreetConsensusResponse> {
+    let to_decimal = |v: f64| Decimal::try_from(v).ok();
+    let model_fair_value = (valuation.fair_price_range.low + valuation.fair_price_range.high) / 2.0;
+    let ratings = RatingDistribution {
+        strong_buy: row.strong_buy,
+        buy: row.buy,
+        hold: row.hold,
+        sell: row.sell,
+        strong_sell: row.strong_sell,
+    };
+
+    let comparison = compare_with_consensus(
+        row.target_mean.or(row.target_median)?,
+        to_decimal(model_fair_value)?,
+        to_decimal(technical.last_price)?,
+        &ratings,
+    )?;
+    let to_f64 = |d: Decimal| d.to_f64().unwrap_or(0.0);
+
+    Some(StreetConsensusResponse {
+        source: row.source.clone(),
+        as_of: row.as_of,
+        analyst_count: row.analyst_count.or(Some(ratings.total())),
+        target_mean: to_f64(comparison.consensus_target),
+        target_high: row.target_high.map(to_f64),
+        target_low: row.target_low.map(to_f64),
+        model_fair_value,
+        implied_upside_pct: to_f64(comparison.implied_upside_pct),
+        model_upside_pct: to_f64(comparison.model_upside_pct),
+        consensus_vs_model_pct: to_f64(comparison.consensus_vs_model_pct),
+        stance: comparison.stance,
+        rating: comparison.rating,
+        rating_distribution: RatingDistributionResponse {
+            strong_buy: ratings.strong_buy,
+            buy: ratings.buy,
+            hold: ratings.hold,
+            sell: ratings.sell,
+            strong_sell: ratings.strong_sell,
+            buy_pct: ratings.buy_pct().map(to_f64).unwrap_or(0.0),
+            sell_pct: ratings.sell_pct().map(to_f64).unwrap_or(0.0),
+        },
+    })
+}
+
+async fn fcf_yield_section(state: &AppState, symbol: &str) -> Option<FcfYieldResponse> {
+    let metrics = fundamentals::cash_flow_metrics(&state.db, symbol, Utc::now().date_naive())
+        .await
+        .map_err(|e| tracing::warn!("Failed to load cash flow metrics for {}: {}", symbol, e))
+        .ok()
+        .flatten()?;
+    let fcf_yield = metrics.fcf_yield?;
+    let to_f64 = |d: Decimal| d.to_f64().unwrap_or(0.0);
+
+    Some(FcfYieldResponse {
+        market_cap: to_f64(metrics.market_cap),
+        ttm_free_cash_flow: metrics.ttm_free_cash_flow.map(to_f64),
+        fcf_yield_pct: to_f64(fcf_yield),
+        band: FcfYieldBand::from_yield(fcf_yield),
+    })
+}
+
+/// Collect the structured facts a spoken summary is rendered from
+fn summary_facts(
+    symbol: &str,
+    name: &str,
+    technical: &TechnicalResponse,
+    broker: Option<&BrokerSummaryResponse>,
+    score: Option<f64>,
+    risk_budget: &RiskBudgetConfig,
+) -> SummaryFacts {
+    let valuation = generate_valuation_conclusion(technical, name).0;
+    let signal = match (score, valuation.as_ref(), broker) {
+        (Some(score), Some(valuation), Some(broker)) => Some(calculate_trading_signal(
+            score,
+            technical,
+            valuation,
+            broker,
+            technical.last_price,
+            risk_budget,
+        )),
+        _ => None,
+    };
+
+    let mut risks = Vec::new();
+    if technical.rsi > 70.0 {
+        risks.push(SummaryRisk::Overbought);
+    }
+    if valuation
+        .as_ref()
+        .is_some_and(|v| v.fair_price_range.low > technical.last_price * 0.9)
+    {
+        risks.push(SummaryRisk::ValuationStretched);
+    }
+    if technical.ichimoku.position == "below" {
+        risks.push(SummaryRisk::BelowIchimokuCloud);
+    }
+
+    let broker_stance = broker.map(|b| match b.net_status.as_str() {
+        "accumulation" => BrokerStance::Accumulation,
+        "distribution" => BrokerStance::Distribution,
+        _ => BrokerStance::Balanced,
+    });
+    let lead_broker = broker.and_then(|b| match broker_stance {
+        Some(BrokerStance::Accumulation) => b.big_buyers.first().map(|i| i.code.clone()),
+        Some(BrokerStance::Distribution) => b.big_sellers.first().map(|i| i.code.clone()),
+        _ => None,
+    });
+    if let Some(b) = broker {
+        if broker_stance == Some(BrokerStance::Distribution) {
+            risks.push(SummaryRisk::BrokerDistribution);
+        }
+        if b.foreign_net < 0.0 {
+            risks.push(SummaryRisk::ForeignSelling);
+        }
+        let suspicious = b
+            .institutional_analysis
+            .as_ref()
+            .and_then(|i| i.suspicious_activity.as_ref())
+            .is_some_and(|a| a.detected);
+        if suspicious {
+            risks.push(SummaryRisk::SuspiciousActivity);
+        }
+    }
+
+    SummaryFacts {
+        symbol: symbol.to_string(),
+        name: name.to_string(),
+        price: technical.last_price,
+        score,
+        signal: signal.as_ref().map(|s| s.signal.as_str().to_string()),
+        target_price: signal.as_ref().and_then(|s| s.target_price),
+        upside_pct: signal.as_ref().and_then(|s| s.upside_percent),
+        broker_stance,
+        lead_broker,
+        foreign_net: broker.map(|b| b.foreign_net),
+        risks,
+    }
+}
+
 fn calculate_trading_signal(
     composite_score: f64,
     technical: &TechnicalResponse,
     valuation: &ValuationResponse,
     broker: &BrokerSummaryResponse,
     current_price: f64,
+    risk_budget: &RiskBudgetConfig,
 ) -> SignalAnalysis {
     let signal = match composite_score {
         c if c >= 75.0 => TradingSignal::StrongBuy,
@@ -1061,13 +3570,20 @@ fn calculate_trading_signal(
         _ => TradingSignal::StrongSell,
     };
 
-    let target_price = Some(valuation.fair_price_range.high);
-
-    let stop_loss = technical
-        .support
-        .first()
-        .copied()
-        .unwrap_or(current_price * 0.95);
+    // Both snap down to a tradable tick: the target stays reachable and the
+    // stop stays below the level it protects
+    let target_price = Some(snap_to_tick(
+        valuation.fair_price_range.high,
+        TickRounding::Down,
+    ));
+
+    let (stop_loss, stop_loss_basis) = select_stop_loss(
+        &technical.support,
+        technical.atr,
+        technical.atr_stop,
+        current_price,
+    );
+    let stop_loss = snap_to_tick(stop_loss, TickRounding::Down);
 
     let upside = target_price.map(|t| ((t - current_price) / current_price) * 100.0);
     let downside = Some(((current_price - stop_loss) / current_price) * 100.0);
@@ -1077,6 +3593,14 @@ fn calculate_trading_signal(
         _ => None,
     };
 
+    let position_lots = match (
+        Decimal::from_f64(current_price),
+        Decimal::from_f64(stop_loss),
+    ) {
+        (Some(entry), Some(stop)) => suggested_position_lots(risk_budget, entry, stop),
+        _ => None,
+    };
+
     let thesis = generate_thesis(broker, technical, valuation);
     let key_catalysts = extract_catalysts(broker, technical);
     let key_risks = extract_risks(technical, valuation);
@@ -1087,11 +3611,62 @@ fn calculate_trading_signal(
         thesis,
         target_price,
         stop_loss: Some(stop_loss),
+        stop_loss_basis,
         upside_percent: upside,
         downside_percent: downside,
         risk_reward_ratio: risk_reward,
+        position_lots,
         key_catalysts,
         key_risks,
+        glossary_key: "trading_signal",
+    }
+}
+
+/// Snap a price to the IDX tick ladder
+fn snap_to_tick(price: f64, rounding: TickRounding) -> f64 {
+    Decimal::from_f64(price)
+        .map(|p| round_to_tick(p, rounding))
+        .and_then(|p| p.to_f64())
+        .unwrap_or(price)
+}
+
+/// Supports closer than this many ATRs sit inside normal noise
+const MIN_SUPPORT_ATRS: f64 = 0.5;
+/// Supports further than this many ATRs risk too much
+const MAX_SUPPORT_ATRS: f64 = 3.0;
+
+/// Pick the stop loss and what it is based on
+///
+/// Uses the nearest support below the price when it sits a sensible
+/// distance away in ATR terms; otherwise the ATR stop, and 5% below the
+/// price when there is no ATR either.
+fn select_stop_loss(
+    support: &[f64],
+    atr: Option<f64>,
+    atr_stop: Option<f64>,
+    current_price: f64,
+) -> (f64, &'static str) {
+    let nearest_support = support
+        .iter()
+        .copied()
+        .filter(|level| *level < current_price)
+        .max_by(|a, b| a.total_cmp(b));
+
+    match (nearest_support, atr, atr_stop) {
+        (Some(support), Some(atr), _) if atr > 0.0 => {
+            let distance = (current_price - support) / atr;
+            if (MIN_SUPPORT_ATRS..=MAX_SUPPORT_ATRS).contains(&distance) {
+                (support, "support")
+            } else {
+                match atr_stop {
+                    Some(stop) => (stop, "atr"),
+                    None => (support, "support"),
+                }
+            }
+        }
+        (_, _, Some(stop)) => (stop, "atr"),
+        (Some(support), _, None) => (support, "support"),
+        (None, _, None) => (current_price * 0.95, "percent"),
     }
 }
 
@@ -1204,3 +3779,189 @@ fn detect_suspicious_activity(
 
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_parse_bulk_fields() {
+        assert_eq!(parse_bulk_fields(None).unwrap(), BulkField::ALL.to_vec());
+        assert_eq!(
+            parse_bulk_fields(Some("score, RSI,wyckoff_phase,score,")).unwrap(),
+            vec![BulkField::Score, BulkField::Rsi, BulkField::WyckoffPhase]
+        );
+        assert!(parse_bulk_fields(Some("score,macd"))
+            .unwrap_err()
+            .starts_with("Unknown field: macd"));
+    }
+
+    #[test]
+    fn test_wyckoff_config_for() {
+        let query = |profile: Option<&str>, spike: Option<Decimal>| WyckoffHistoryQuery {
+            days: None,
+            profile: profile.map(str::to_string),
+            volume_spike_threshold: spike,
+            sr_tolerance: None,
+        };
+        // 100 x 1,000 shares, a thinly traded name
+        let bars: Vec<OhlcvBar> = (0..30)
+            .map(|_| OhlcvBar {
+                open: dec!(100),
+                high: dec!(101),
+                low: dec!(99),
+                close: dec!(100),
+                volume: 1_000,
+            })
+            .collect();
+
+        let (profile, config) = wyckoff_config_for(&query(None, None), &bars, None).unwrap();
+        assert_eq!(profile, WyckoffProfile::SmallCap);
+        assert_eq!(config, WyckoffProfile::SmallCap.config());
+
+        let (profile, config) =
+            wyckoff_config_for(&query(Some("blue_chip"), Some(dec!(2.5))), &bars, None).unwrap();
+        assert_eq!(profile, WyckoffProfile::BlueChip);
+        assert_eq!(config.volume_spike_threshold, dec!(2.5));
+        assert_eq!(
+            config.sr_tolerance,
+            WyckoffProfile::BlueChip.config().sr_tolerance
+        );
+
+        assert!(wyckoff_config_for(&query(Some("penny"), None), &bars, None).is_err());
+        assert!(wyckoff_config_for(&query(None, Some(dec!(0.5))), &bars, None).is_err());
+    }
+
+    fn date(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 6, d).unwrap()
+    }
+
+    #[test]
+    fn test_timeframe_technicals() {
+        let daily: Vec<DatedBar> = (0..500)
+            .map(|i| {
+                let close = Decimal::from(1000 + i * 5);
+                DatedBar {
+                    date: date(2) + Duration::days(i),
+                    bar: OhlcvBar {
+                        open: close - dec!(5),
+                        high: close + dec!(5),
+                        low: close - dec!(10),
+                        close,
+                        volume: 1_000,
+                    },
+                }
+            })
+            .collect();
+
+        let timeframes: Vec<TimeframeTechnicals> = Timeframe::ALL
+            .iter()
+            .map(|tf| timeframe_technicals(*tf, &resample_ohlcv(&daily, *tf)))
+            .collect();
+
+        let daily_tf = &timeframes[0];
+        assert_eq!(daily_tf.bars, 500);
+        assert_eq!(daily_tf.trend, "bullish");
+        assert!(daily_tf.macd_histogram.is_some());
+
+        // 17 monthly bars: RSI yes, EMA50 and MACD not yet
+        let monthly = &timeframes[2];
+        assert!(monthly.rsi.is_some());
+        assert_eq!(monthly.ema50, None);
+        assert_eq!(monthly.macd, None);
+        assert_eq!(monthly.trend, "neutral");
+        assert_eq!(trend_alignment(&timeframes), "mixed");
+        assert_eq!(trend_alignment(&timeframes[..2]), "bullish");
+    }
+
+    #[tokio::test]
+    async fn test_run_section_status() {
+        let (value, report) = run_section(async { Ok(1) }).await;
+        assert_eq!(value, Some(1));
+        assert_eq!(report.status, SectionStatus::Ok);
+
+        let (value, report) = run_section(async {
+            Err::<i32, _>((axum::http::StatusCode::BAD_REQUEST, "too few".to_string()))
+        })
+        .await;
+        assert_eq!(value, None);
+        assert_eq!(report.status, SectionStatus::InsufficientData);
+        assert_eq!(report.message.as_deref(), Some("too few"));
+
+        let (_, report) = run_section(async {
+            Err::<i32, _>((
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                "db down".to_string(),
+            ))
+        })
+        .await;
+        assert_eq!(report.status, SectionStatus::SourceError);
+    }
+
+    #[test]
+    fn test_select_stop_loss() {
+        // Support 1.2 ATRs below the price is usable
+        assert_eq!(
+            select_stop_loss(&[800.0, 940.0], Some(50.0), Some(880.0), 1000.0),
+            (940.0, "support")
+        );
+        // Support within noise falls back to the ATR stop
+        assert_eq!(
+            select_stop_loss(&[990.0], Some(50.0), Some(880.0), 1000.0),
+            (880.0, "atr")
+        );
+        // No support below the price
+        assert_eq!(
+            select_stop_loss(&[1100.0], Some(50.0), Some(880.0), 1000.0),
+            (880.0, "atr")
+        );
+        assert_eq!(
+            select_stop_loss(&[], None, None, 1000.0),
+            (950.0, "percent")
+        );
+    }
+
+    #[test]
+    fn test_snap_to_tick() {
+        // An ATR stop of 9,262.7 is not a valid price in the 25-rupiah band
+        assert_eq!(snap_to_tick(9262.7, TickRounding::Down), 9250.0);
+        assert_eq!(snap_to_tick(437.0, TickRounding::Down), 436.0);
+    }
+
+    #[test]
+    fn test_summarize_broker_coverage() {
+        let expected = trading_days_between(&MarketCalendar::default(), date(2), date(8));
+        assert_eq!(expected.len(), 5);
+        let holiday = MarketCalendar::new([date(3)]);
+        assert_eq!(trading_days_between(&holiday, date(2), date(8)).len(), 4);
+
+        let days = vec![
+            BrokerCoverageDay {
+                date: date(2),
+                sources: vec!["scraper".to_string()],
+                broker_count: 40,
+            },
+            BrokerCoverageDay {
+                date: date(3),
+                sources: vec!["import:stockbit".to_string(), "scraper".to_string()],
+                broker_count: 38,
+            },
+            BrokerCoverageDay {
+                date: date(5),
+                sources: vec!["scraper".to_string()],
+                broker_count: 41,
+            },
+        ];
+        let report =
+            summarize_broker_coverage("BBCA".to_string(), date(2), date(8), &expected, days);
+
+        assert_eq!(report.covered_days, 3);
+        assert_eq!(report.coverage_pct, 60.0);
+        assert!(!report.complete);
+        assert_eq!(report.missing_dates, vec![date(4), date(6)]);
+        assert_eq!(report.sources.len(), 2);
+        assert_eq!(report.sources[1].source, "scraper");
+        assert_eq!(report.sources[1].days, 3);
+    }
+}
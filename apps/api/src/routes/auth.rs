@@ -1,6 +1,6 @@
 //! Authentication routes
 
-use crate::auth::{create_token, verify_password, AuthError, LoginRequest, LoginResponse};
+use crate::auth::{create_token, verify_password, AuthError, LoginRequest, LoginResponse, Role};
 use crate::AppState;
 use axum::{extract::State, routing::post, Json, Router};
 use axum_extra::extract::cookie::{Cookie, CookieJar};
@@ -29,19 +29,29 @@ async fn login(
             .collect::<String>()
     );
 
-    // Verify credentials
-    if req.username != state.config.username {
-        tracing::debug!("Username mismatch");
+    // Verify credentials; the primary user is the admin
+    let account = if req.username == state.config.username {
+        Some((Role::Admin, state.config.password_hash.as_str()))
+    } else {
+        state
+            .config
+            .users
+            .iter()
+            .find(|user| user.username == req.username)
+            .map(|user| (user.role, user.password_hash.as_str()))
+    };
+    let Some((role, password_hash)) = account else {
+        tracing::debug!("Unknown username");
         return Err(AuthError("Invalid credentials".to_string()));
-    }
+    };
 
     // For development, accept "admin123" directly if hash is default
-    let valid = if state.config.password_hash.contains("random_salt_here") {
+    let valid = if password_hash.contains("random_salt_here") {
         tracing::debug!("Using default password check");
         req.password == "admin123"
     } else {
         tracing::debug!("Verifying password against hash");
-        verify_password(&req.password, &state.config.password_hash)
+        verify_password(&req.password, password_hash)
     };
 
     tracing::debug!("Password valid: {}", valid);
@@ -50,7 +60,7 @@ async fn login(
         return Err(AuthError("Invalid credentials".to_string()));
     }
 
-    let response = create_token(&req.username, &state.config.jwt_secret)?;
+    let response = create_token(&req.username, role, &state.config.jwt_secret)?;
 
     // Set cookie
     let cookie = Cookie::build(("token", response.token.clone()))
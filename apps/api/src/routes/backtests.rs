@@ -0,0 +1,668 @@
+//! Backtest routes
+//!
+//! Running a backtest saves its report for the user, so parameter
+//! variations can be listed and compared later. A saved run can be shared
+//! read-only through an unguessable token; revoking the share invalidates
+//! the link.
+//!
+//! A parameter optimization grid-searches strategy and risk parameters in a
+//! background job, reporting how many combinations it has tested, and
+//! stores every combination ranked by its in-sample objective next to its
+//! out-of-sample metrics.
+
+use crate::auth::AuthUser;
+use crate::corporate_actions;
+use crate::routes::jobs::{Job, JobHandle, JobKind};
+use crate::AppState;
+use axum::{
+    extract::{Path, Query, State},
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::{Duration, NaiveDate, NaiveTime, Utc};
+use jejakcuan_core::{
+    evaluate_combination, rank_results, run_backtest, split_index, BacktestBar, BacktestConfig,
+    BacktestStrategy, CombinationResult, OptimizationObjective, ParameterGrid, StrategyGrid,
+    TransactionCostModel, DEFAULT_IN_SAMPLE_PCT, MAX_GRID_COMBINATIONS,
+};
+use jejakcuan_db::repositories::backtests::{InsertBacktestOptimization, InsertOptimizationResult};
+use jejakcuan_db::{
+    repositories, BacktestOptimizationResultRow, BacktestOptimizationRow, BacktestRunRow,
+    BacktestRunSummaryRow, InsertBacktestRun,
+};
+use jejakcuan_technical::session_date;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+pub fn backtest_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/", get(list_backtests).post(create_backtest))
+        .route(
+            "/optimizations",
+            get(list_optimizations).post(create_optimization),
+        )
+        .route(
+            "/optimizations/:id",
+            get(get_optimization).delete(delete_optimization),
+        )
+        .route("/:id", get(get_backtest).delete(delete_backtest))
+        .route("/:id/share", post(share_backtest).delete(unshare_backtest))
+}
+
+/// Read-only access to shared runs, no login needed
+pub fn shared_backtest_routes() -> Router<Arc<AppState>> {
+    Router::new().route("/:token", get(get_shared_backtest))
+}
+
+const MAX_BACKTEST_NAME_LEN: usize = 100;
+
+/// Default test period when `from` is not given
+const DEFAULT_BACKTEST_YEARS: i64 = 2;
+
+/// Job source id of parameter optimizations; one runs at a time
+const OPTIMIZATION_JOB: &str = "backtest_optimization";
+
+/// Combinations tested between progress updates
+const OPTIMIZATION_PROGRESS_EVERY: usize = 10;
+
+/// Results returned with an optimization unless `limit` is given
+const DEFAULT_OPTIMIZATION_RESULTS: i64 = 50;
+
+#[derive(Debug, Deserialize)]
+pub struct RunBacktestRequest {
+    pub symbol: String,
+    pub strategy: BacktestStrategy,
+    /// Default two years before `to`
+    pub from: Option<NaiveDate>,
+    /// Default today
+    pub to: Option<NaiveDate>,
+    #[serde(default)]
+    pub config: BacktestConfig,
+    /// Label to tell variations apart
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RunOptimizationRequest {
+    pub symbol: String,
+    pub grid: ParameterGrid,
+    /// Default two years before `to`
+    pub from: Option<NaiveDate>,
+    /// Default today
+    pub to: Option<NaiveDate>,
+    /// Capital and any risk setting the grid does not search
+    #[serde(default)]
+    pub config: BacktestConfig,
+    #[serde(default)]
+    pub objective: OptimizationObjective,
+    /// Share of bars combinations are ranked on, percent (default 70)
+    pub in_sample_pct: Option<f64>,
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OptimizationStarted {
+    pub optimization: BacktestOptimizationRow,
+    pub job: Job,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OptimizationResultsQuery {
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OptimizationDetail {
+    pub optimization: BacktestOptimizationRow,
+    /// Best in-sample first
+    pub results: Vec<BacktestOptimizationResultRow>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BacktestListQuery {
+    pub symbol: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ShareBacktestResponse {
+    pub token: String,
+    /// Path of the read-only report
+    pub path: String,
+}
+
+/// A shared run, without its owner or token
+#[derive(Debug, Serialize)]
+pub struct SharedBacktest {
+    pub name: Option<String>,
+    pub symbol: String,
+    pub strategy: serde_json::Value,
+    pub config: serde_json::Value,
+    pub costs: serde_json::Value,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub metrics: serde_json::Value,
+    pub trades: serde_json::Value,
+    pub equity_curve: serde_json::Value,
+    pub monte_carlo: Option<serde_json::Value>,
+    pub created_at: chrono::DateTime<Utc>,
+}
+
+impl From<BacktestRunRow> for SharedBacktest {
+    fn from(run: BacktestRunRow) -> Self {
+        Self {
+            name: run.name,
+            symbol: run.symbol,
+            strategy: run.strategy,
+            config: run.config,
+            costs: run.costs,
+            start_date: run.start_date,
+            end_date: run.end_date,
+            metrics: run.metrics,
+            trades: run.trades,
+            equity_curve: run.equity_curve,
+            monte_carlo: run.monte_carlo,
+            created_at: run.created_at,
+        }
+    }
+}
+
+fn backtest_not_found() -> (axum::http::StatusCode, String) {
+    (
+        axum::http::StatusCode::NOT_FOUND,
+        "Backtest not found".to_string(),
+    )
+}
+
+fn optimization_not_found() -> (axum::http::StatusCode, String) {
+    (
+        axum::http::StatusCode::NOT_FOUND,
+        "Optimization not found".to_string(),
+    )
+}
+
+fn no_price_history(symbol: &str) -> (axum::http::StatusCode, String) {
+    (
+        axum::http::StatusCode::NOT_FOUND,
+        format!("No price history for {} in the period", symbol),
+    )
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<serde_json::Value, (axum::http::StatusCode, String)> {
+    serde_json::to_value(value)
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// Backtest name trimmed, None when blank
+fn backtest_name(name: Option<&str>) -> Result<Option<&str>, String> {
+    let name = name.map(str::trim).filter(|n| !n.is_empty());
+    if name.is_some_and(|n| n.len() > MAX_BACKTEST_NAME_LEN) {
+        return Err(format!(
+            "Name must be at most {} characters",
+            MAX_BACKTEST_NAME_LEN
+        ));
+    }
+    Ok(name)
+}
+
+/// Test period with the defaults filled in
+fn backtest_period(
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> Result<(NaiveDate, NaiveDate), String> {
+    let to = to.unwrap_or_else(|| Utc::now().date_naive());
+    let from = from.unwrap_or(to - Duration::days(365 * DEFAULT_BACKTEST_YEARS));
+    if from > to {
+        return Err("from must not be after to".to_string());
+    }
+    Ok((from, to))
+}
+
+/// Daily bars of `symbol` from `from` to `to`, oldest first
+///
+/// Prices are adjusted for splits, dividends and rights issues so ex-dates
+/// do not trigger signals or stops. With `with_scores`, each bar carries
+/// the last composite score stored during its session.
+pub(crate) async fn load_backtest_bars(
+    pool: &sqlx::PgPool,
+    symbol: &str,
+    from: NaiveDate,
+    to: NaiveDate,
+    with_scores: bool,
+) -> Result<Vec<BacktestBar>, sqlx::Error> {
+    let from = from.and_time(NaiveTime::MIN).and_utc();
+    let to = (to + Duration::days(1)).and_time(NaiveTime::MIN).and_utc();
+    let prices = repositories::prices::get_price_history(pool, symbol, from, to).await?;
+    let prices = corporate_actions::adjust_price_rows(pool, symbol, prices).await?;
+
+    let scores: BTreeMap<NaiveDate, _> = if with_scores {
+        repositories::scores::get_score_history(pool, symbol, from, to)
+            .await?
+            .into_iter()
+            .map(|s| (session_date(s.time), s.composite_score))
+            .collect()
+    } else {
+        BTreeMap::new()
+    };
+
+    Ok(prices
+        .into_iter()
+        .map(|p| {
+            let date = session_date(p.time);
+            BacktestBar {
+                date,
+                open: p.open,
+                close: p.close,
+                volume: p.volume,
+                score: scores.get(&date).copied(),
+            }
+        })
+        .collect())
+}
+
+async fn list_backtests(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<BacktestListQuery>,
+) -> Result<Json<Vec<BacktestRunSummaryRow>>, (axum::http::StatusCode, String)> {
+    let symbol = query.symbol.map(|s| s.to_uppercase());
+    repositories::backtests::get_backtest_runs(&state.db, &user.username, symbol.as_deref())
+        .await
+        .map(Json)
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// Run a backtest and save its report
+async fn create_backtest(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<RunBacktestRequest>,
+) -> Result<Json<BacktestRunRow>, (axum::http::StatusCode, String)> {
+    let bad_request = |message: String| (axum::http::StatusCode::BAD_REQUEST, message);
+    let internal = |e: String| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e);
+
+    let symbol = req.symbol.trim().to_uppercase();
+    let name = backtest_name(req.name.as_deref()).map_err(bad_request)?;
+    let (from, to) = backtest_period(req.from, req.to).map_err(bad_request)?;
+    req.strategy
+        .validate()
+        .and_then(|_| req.config.validate())
+        .map_err(|e| bad_request(e.to_string()))?;
+
+    let with_scores = matches!(req.strategy, BacktestStrategy::ScoreThreshold { .. });
+    let bars = load_backtest_bars(&state.db, &symbol, from, to, with_scores)
+        .await
+        .map_err(|e| internal(e.to_string()))?;
+    if bars.is_empty() {
+        return Err(no_price_history(&symbol));
+    }
+    let costs = &state.config.transaction_costs;
+    let report = run_backtest(&bars, &req.strategy, &req.config, costs)
+        .map_err(|e| bad_request(e.to_string()))?;
+
+    repositories::backtests::insert_backtest_run(
+        &state.db,
+        &InsertBacktestRun {
+            owner: &user.username,
+            name,
+            symbol: &symbol,
+            strategy: &to_json(&report.strategy)?,
+            config: &to_json(&report.config)?,
+            costs: &to_json(costs)?,
+            start_date: report.start,
+            end_date: report.end,
+            metrics: &to_json(&report.metrics)?,
+            trades: &to_json(&report.trades)?,
+            equity_curve: &to_json(&report.equity_curve)?,
+            monte_carlo: report
+                .monte_carlo
+                .as_ref()
+                .map(to_json)
+                .transpose()?
+                .as_ref(),
+        },
+    )
+    .await
+    .map(Json)
+    .map_err(|e| internal(e.to_string()))
+}
+
+async fn get_backtest(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<Json<BacktestRunRow>, (axum::http::StatusCode, String)> {
+    repositories::backtests::get_backtest_run(&state.db, &user.username, id)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map(Json)
+        .ok_or_else(backtest_not_found)
+}
+
+async fn delete_backtest(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, String)> {
+    let deleted = repositories::backtests::delete_backtest_run(&state.db, &user.username, id)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if !deleted {
+        return Err(backtest_not_found());
+    }
+    Ok(Json(serde_json::json!({ "success": true })))
+}
+
+/// Share a run read-only; sharing again returns the same link
+async fn share_backtest(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<Json<ShareBacktestResponse>, (axum::http::StatusCode, String)> {
+    let token = uuid::Uuid::new_v4().simple().to_string();
+    let token = repositories::backtests::share_backtest_run(&state.db, &user.username, id, &token)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(backtest_not_found)?;
+    Ok(Json(ShareBacktestResponse {
+        path: format!("/api/shared/backtests/{}", token),
+        token,
+    }))
+}
+
+async fn unshare_backtest(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, String)> {
+    let found = repositories::backtests::unshare_backtest_run(&state.db, &user.username, id)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if !found {
+        return Err(backtest_not_found());
+    }
+    Ok(Json(serde_json::json!({ "success": true })))
+}
+
+async fn get_shared_backtest(
+    State(state): State<Arc<AppState>>,
+    Path(token): Path<String>,
+) -> Result<Json<SharedBacktest>, (axum::http::StatusCode, String)> {
+    repositories::backtests::get_shared_backtest_run(&state.db, &token)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map(|run| Json(run.into()))
+        .ok_or_else(backtest_not_found)
+}
+
+async fn list_optimizations(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<BacktestOptimizationRow>>, (axum::http::StatusCode, String)> {
+    repositories::backtests::get_backtest_optimizations(&state.db, &user.username)
+        .await
+        .map(Json)
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// Start a grid search in the background
+async fn create_optimization(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<RunOptimizationRequest>,
+) -> Result<Json<OptimizationStarted>, (axum::http::StatusCode, String)> {
+    let bad_request = |message: String| (axum::http::StatusCode::BAD_REQUEST, message);
+    let internal = |e: String| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e);
+
+    let symbol = req.symbol.trim().to_uppercase();
+    let name = backtest_name(req.name.as_deref()).map_err(bad_request)?;
+    let (from, to) = backtest_period(req.from, req.to).map_err(bad_request)?;
+    let in_sample_pct = req.in_sample_pct.unwrap_or(DEFAULT_IN_SAMPLE_PCT);
+    // Checked now so a bad share fails the request rather than the job
+    split_index(0, in_sample_pct).map_err(|e| bad_request(e.to_string()))?;
+    let combinations = req
+        .grid
+        .combinations(&req.config)
+        .map_err(|e| bad_request(e.to_string()))?;
+
+    if let Some(job) = state.job_manager.is_source_running(OPTIMIZATION_JOB).await {
+        return Err((
+            axum::http::StatusCode::CONFLICT,
+            format!("An optimization is already running (job {})", job.id),
+        ));
+    }
+
+    let costs = state.config.transaction_costs.clone();
+    let optimization = repositories::backtests::create_backtest_optimization(
+        &state.db,
+        &InsertBacktestOptimization {
+            owner: &user.username,
+            name,
+            symbol: &symbol,
+            grid: &to_json(&req.grid)?,
+            base_config: &to_json(&req.config)?,
+            costs: &to_json(&costs)?,
+            objective: req.objective.as_str(),
+            start_date: from,
+            end_date: to,
+        },
+    )
+    .await
+    .map_err(|e| internal(e.to_string()))?;
+
+    let pool = state.db.clone();
+    let id = optimization.id;
+    let with_scores = matches!(req.grid.strategy, StrategyGrid::ScoreThreshold { .. });
+    let objective = req.objective;
+    let job = state
+        .job_manager
+        .spawn_task_with(
+            OPTIMIZATION_JOB.to_string(),
+            "Backtest optimization".to_string(),
+            format!(
+                "grid-search {} parameter combinations for {}",
+                combinations.len(),
+                symbol
+            ),
+            JobKind::Internal,
+            |handle| async move {
+                let run = OptimizationRun {
+                    id,
+                    symbol,
+                    from,
+                    to,
+                    with_scores,
+                    in_sample_pct,
+                    objective,
+                    costs,
+                };
+                let result = run_optimization(&pool, &run, combinations, &handle).await;
+                if let Err(e) = &result {
+                    if let Err(db) =
+                        repositories::backtests::fail_backtest_optimization(&pool, id, e).await
+                    {
+                        tracing::warn!("Failed to record failure of optimization {}: {}", id, db);
+                    }
+                }
+                result
+            },
+        )
+        .await;
+
+    Ok(Json(OptimizationStarted { optimization, job }))
+}
+
+/// Settings of one grid search job
+struct OptimizationRun {
+    id: i32,
+    symbol: String,
+    from: NaiveDate,
+    to: NaiveDate,
+    with_scores: bool,
+    in_sample_pct: f64,
+    objective: OptimizationObjective,
+    costs: TransactionCostModel,
+}
+
+/// Backtest every combination, recording progress, and store them ranked
+///
+/// Progress goes to both the optimization row and the job; a cancelled job
+/// stops between chunks and fails the row.
+async fn run_optimization(
+    pool: &sqlx::PgPool,
+    run: &OptimizationRun,
+    combinations: Vec<(BacktestStrategy, BacktestConfig)>,
+    handle: &JobHandle,
+) -> Result<String, String> {
+    let bars = load_backtest_bars(pool, &run.symbol, run.from, run.to, run.with_scores)
+        .await
+        .map_err(|e| e.to_string())?;
+    let split = split_index(bars.len(), run.in_sample_pct).map_err(|e| e.to_string())?;
+    let Some(split_bar) = bars.get(split).filter(|_| split > 0) else {
+        return Err(format!(
+            "Not enough price history for {} to split ({} bars)",
+            run.symbol,
+            bars.len()
+        ));
+    };
+    let total = combinations.len();
+    repositories::backtests::start_backtest_optimization(
+        pool,
+        run.id,
+        split_bar.date,
+        total as i32,
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut results = Vec::with_capacity(total);
+    let mut errors = Vec::new();
+    for (i, chunk) in combinations.chunks(OPTIMIZATION_PROGRESS_EVERY).enumerate() {
+        if handle.is_cancelled() {
+            return Err("Cancelled by user".to_string());
+        }
+        for (strategy, config) in chunk {
+            match evaluate_combination(&bars, split, strategy, config, &run.costs) {
+                Ok(result) => results.push(result),
+                Err(e) => errors.push(e.to_string()),
+            }
+        }
+        let done = (i * OPTIMIZATION_PROGRESS_EVERY + chunk.len()) as i32;
+        repositories::backtests::record_optimization_progress(pool, run.id, done)
+            .await
+            .map_err(|e| e.to_string())?;
+        handle.set_progress(done as u64, total as u64).await;
+    }
+    if results.is_empty() {
+        return Err(format!(
+            "No combination could be tested: {}",
+            errors.first().map(String::as_str).unwrap_or("empty grid")
+        ));
+    }
+
+    rank_results(&mut results, run.objective);
+    store_results(pool, run.id, &results, run.objective).await?;
+
+    let best = &results[0];
+    let oos = best
+        .out_of_sample
+        .as_ref()
+        .map(|m| format!("{:.2}%", m.total_return_pct))
+        .unwrap_or_else(|| "n/a".to_string());
+    Ok(format!(
+        "{} of {} combinations tested ({} skipped); best returned {:.2}% in-sample, {} out-of-sample",
+        results.len(),
+        total,
+        errors.len(),
+        best.in_sample.total_return_pct,
+        oos
+    ))
+}
+
+async fn store_results(
+    pool: &sqlx::PgPool,
+    id: i32,
+    results: &[CombinationResult],
+    objective: OptimizationObjective,
+) -> Result<(), String> {
+    let to_value = |v: serde_json::Result<serde_json::Value>| v.map_err(|e| e.to_string());
+    let mut values = Vec::with_capacity(results.len());
+    for result in results {
+        values.push((
+            to_value(serde_json::to_value(&result.strategy))?,
+            to_value(serde_json::to_value(&result.config))?,
+            to_value(serde_json::to_value(&result.in_sample))?,
+            result
+                .out_of_sample
+                .as_ref()
+                .map(serde_json::to_value)
+                .transpose()
+                .map_err(|e| e.to_string())?,
+        ));
+    }
+    let rows: Vec<InsertOptimizationResult> = results
+        .iter()
+        .zip(&values)
+        .enumerate()
+        .map(
+            |(i, (result, (strategy, config, in_sample, out_of_sample)))| {
+                InsertOptimizationResult {
+                    rank: i as i32 + 1,
+                    strategy,
+                    config,
+                    in_sample,
+                    out_of_sample: out_of_sample.as_ref(),
+                    in_sample_score: objective.score(&result.in_sample),
+                    out_of_sample_score: result
+                        .out_of_sample
+                        .as_ref()
+                        .and_then(|m| objective.score(m)),
+                }
+            },
+        )
+        .collect();
+    repositories::backtests::complete_backtest_optimization(pool, id, &rows)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// A grid search with its progress and best results
+async fn get_optimization(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Query(query): Query<OptimizationResultsQuery>,
+) -> Result<Json<OptimizationDetail>, (axum::http::StatusCode, String)> {
+    let internal = |e: sqlx::Error| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string());
+    let optimization =
+        repositories::backtests::get_backtest_optimization(&state.db, &user.username, id)
+            .await
+            .map_err(internal)?
+            .ok_or_else(optimization_not_found)?;
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_OPTIMIZATION_RESULTS)
+        .clamp(1, MAX_GRID_COMBINATIONS as i64);
+    let results = repositories::backtests::get_optimization_results(&state.db, id, limit)
+        .await
+        .map_err(internal)?;
+    Ok(Json(OptimizationDetail {
+        optimization,
+        results,
+    }))
+}
+
+async fn delete_optimization(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, String)> {
+    let deleted =
+        repositories::backtests::delete_backtest_optimization(&state.db, &user.username, id)
+            .await
+            .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if !deleted {
+        return Err(optimization_not_found());
+    }
+    Ok(Json(serde_json::json!({ "success": true })))
+}
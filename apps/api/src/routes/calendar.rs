@@ -0,0 +1,107 @@
+//! Market calendar routes
+//!
+//! Financial reports published or expected across the market, a week at a
+//! time.
+
+use crate::auth::AuthUser;
+use crate::AppState;
+use axum::{
+    extract::{Query, State},
+    routing::get,
+    Json, Router,
+};
+use chrono::{Datelike, Duration, NaiveDate, Utc, Weekday};
+use jejakcuan_db::{repositories, EarningsCalendarRow};
+use jejakcuan_technical::session_date;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+pub fn calendar_routes() -> Router<Arc<AppState>> {
+    Router::new().route("/earnings", get(get_earnings_week))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EarningsWeekQuery {
+    /// ISO week ("2025-W43") or any date in the week; default this week
+    pub week: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EarningsWeekResponse {
+    /// ISO week, e.g. "2025-W43"
+    pub week: String,
+    /// Monday
+    pub from: NaiveDate,
+    /// Sunday
+    pub to: NaiveDate,
+    pub reports: Vec<EarningsCalendarRow>,
+}
+
+/// Monday of the week `raw` names, or of the week of `today`
+fn week_start(raw: Option<&str>, today: NaiveDate) -> Option<NaiveDate> {
+    let day = match raw.map(str::trim) {
+        None | Some("") => today,
+        Some(raw) => match raw.split_once("-W").or_else(|| raw.split_once("-w")) {
+            Some((year, week)) => {
+                NaiveDate::from_isoywd_opt(year.parse().ok()?, week.parse().ok()?, Weekday::Mon)?
+            }
+            None => raw.parse().ok()?,
+        },
+    };
+    Some(day - Duration::days(day.weekday().num_days_from_monday() as i64))
+}
+
+async fn get_earnings_week(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<EarningsWeekQuery>,
+) -> Result<Json<EarningsWeekResponse>, (axum::http::StatusCode, String)> {
+    let from = week_start(query.week.as_deref(), session_date(Utc::now())).ok_or_else(|| {
+        (
+            axum::http::StatusCode::BAD_REQUEST,
+            "week must be an ISO week such as 2025-W43 or a date".to_string(),
+        )
+    })?;
+    let to = from + Duration::days(6);
+    let reports = repositories::earnings::get_earnings_between(&state.db, from, to)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let iso = from.iso_week();
+    Ok(Json(EarningsWeekResponse {
+        week: format!("{}-W{:02}", iso.year(), iso.week()),
+        from,
+        to,
+        reports,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_week_start() {
+        let today = date("2025-10-23");
+        assert_eq!(week_start(None, today), Some(date("2025-10-20")));
+        assert_eq!(
+            week_start(Some("2025-W43"), today),
+            Some(date("2025-10-20"))
+        );
+        assert_eq!(
+            week_start(Some("2025-10-26"), today),
+            Some(date("2025-10-20"))
+        );
+        // ISO week 1 of 2026 starts in 2025
+        assert_eq!(
+            week_start(Some("2026-W01"), today),
+            Some(date("2025-12-29"))
+        );
+        assert_eq!(week_start(Some("2025-W60"), today), None);
+        assert_eq!(week_start(Some("next week"), today), None);
+    }
+}
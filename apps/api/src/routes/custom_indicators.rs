@@ -0,0 +1,302 @@
+//! Custom indicator routes
+//!
+//! Users define named indicators as expressions over the built-in ones, for
+//! example `(ema20 - ema50) / atr14`, preview them on a symbol and use them
+//! in screens and alert rules (see [`crate::custom_indicators`]). Expressions
+//! are stored in their canonical form.
+
+use crate::auth::AuthUser;
+use crate::custom_indicators::{ohlcv_bars, CUSTOM_INDICATOR_DAYS};
+use crate::AppState;
+use axum::{
+    extract::{Path, Query, State},
+    routing::{delete, get, post, put},
+    Json, Router,
+};
+use chrono::{DateTime, Duration, Utc};
+use jejakcuan_db::{repositories, CustomIndicatorRow, InsertCustomIndicator};
+use jejakcuan_technical::IndicatorExpr;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+pub fn custom_indicator_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/", get(list_indicators))
+        .route("/", post(create_indicator))
+        .route("/preview", post(preview_indicator))
+        .route("/:id", get(get_indicator))
+        .route("/:id", put(update_indicator))
+        .route("/:id", delete(delete_indicator))
+        .route("/:id/values/:symbol", get(get_indicator_values))
+}
+
+const MAX_NAME_LEN: usize = 50;
+const MAX_DESCRIPTION_LEN: usize = 500;
+/// Calendar days of values returned by default, and at most
+const DEFAULT_VALUE_DAYS: i64 = 90;
+const MAX_VALUE_DAYS: i64 = 730;
+
+#[derive(Debug, Deserialize)]
+pub struct CustomIndicatorRequest {
+    name: String,
+    expression: String,
+    description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PreviewRequest {
+    expression: String,
+    symbol: String,
+    days: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ValuesQuery {
+    days: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IndicatorValue {
+    pub time: DateTime<Utc>,
+    /// Null during warm-up and where the expression is undefined
+    pub value: Option<Decimal>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CustomIndicatorValuesResponse {
+    pub symbol: String,
+    pub expression: String,
+    pub latest: Option<Decimal>,
+    pub values: Vec<IndicatorValue>,
+}
+
+fn indicator_not_found() -> (axum::http::StatusCode, String) {
+    (
+        axum::http::StatusCode::NOT_FOUND,
+        "Custom indicator not found".to_string(),
+    )
+}
+
+/// Check the request; returns the name and canonical expression
+///
+/// `id` is the indicator being updated.
+async fn validate_indicator(
+    state: &AppState,
+    owner: &str,
+    id: Option<i32>,
+    req: &CustomIndicatorRequest,
+) -> Result<(String, String), (axum::http::StatusCode, String)> {
+    let bad_request = |message: String| (axum::http::StatusCode::BAD_REQUEST, message);
+
+    let name = req.name.trim().to_ascii_lowercase();
+    let identifier = name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if !identifier || name.len() > MAX_NAME_LEN {
+        return Err(bad_request(format!(
+            "Name must be 1-{} characters of letters, digits and underscores, starting with a letter",
+            MAX_NAME_LEN
+        )));
+    }
+    if req
+        .description
+        .as_ref()
+        .is_some_and(|d| d.len() > MAX_DESCRIPTION_LEN)
+    {
+        return Err(bad_request(format!(
+            "Description must be at most {} characters",
+            MAX_DESCRIPTION_LEN
+        )));
+    }
+    let expr = IndicatorExpr::parse(&req.expression)
+        .map_err(|e| bad_request(format!("Invalid expression: {}", e)))?;
+
+    let taken = repositories::custom_indicators::get_custom_indicators(&state.db, owner)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .iter()
+        .any(|i| i.name == name && Some(i.id) != id);
+    if taken {
+        return Err((
+            axum::http::StatusCode::CONFLICT,
+            format!("A custom indicator named {} already exists", name),
+        ));
+    }
+    Ok((name, expr.to_string()))
+}
+
+async fn list_indicators(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<CustomIndicatorRow>>, (axum::http::StatusCode, String)> {
+    repositories::custom_indicators::get_custom_indicators(&state.db, &user.username)
+        .await
+        .map(Json)
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+async fn get_indicator(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<Json<CustomIndicatorRow>, (axum::http::StatusCode, String)> {
+    repositories::custom_indicators::get_custom_indicator(&state.db, &user.username, id)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map(Json)
+        .ok_or_else(indicator_not_found)
+}
+
+async fn create_indicator(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<CustomIndicatorRequest>,
+) -> Result<Json<CustomIndicatorRow>, (axum::http::StatusCode, String)> {
+    let (name, expression) = validate_indicator(&state, &user.username, None, &req).await?;
+
+    repositories::custom_indicators::insert_custom_indicator(
+        &state.db,
+        &InsertCustomIndicator {
+            owner: &user.username,
+            name: &name,
+            expression: &expression,
+            description: req.description.as_deref(),
+        },
+    )
+    .await
+    .map(Json)
+    .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// Replace an indicator; screens and rules using its name read the new one
+async fn update_indicator(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Json(req): Json<CustomIndicatorRequest>,
+) -> Result<Json<CustomIndicatorRow>, (axum::http::StatusCode, String)> {
+    let (name, expression) = validate_indicator(&state, &user.username, Some(id), &req).await?;
+
+    repositories::custom_indicators::update_custom_indicator(
+        &state.db,
+        id,
+        &InsertCustomIndicator {
+            owner: &user.username,
+            name: &name,
+            expression: &expression,
+            description: req.description.as_deref(),
+        },
+    )
+    .await
+    .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .map(Json)
+    .ok_or_else(indicator_not_found)
+}
+
+async fn delete_indicator(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, String)> {
+    let deleted =
+        repositories::custom_indicators::delete_custom_indicator(&state.db, &user.username, id)
+            .await
+            .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if !deleted {
+        return Err(indicator_not_found());
+    }
+    Ok(Json(serde_json::json!({ "success": true })))
+}
+
+/// Values of `expr` over the last `days` of `symbol`
+///
+/// Indicators warm up on the bars before the returned window.
+async fn indicator_values(
+    state: &AppState,
+    symbol: &str,
+    expr: &IndicatorExpr,
+    days: Option<i64>,
+) -> Result<CustomIndicatorValuesResponse, (axum::http::StatusCode, String)> {
+    let symbol = symbol.to_uppercase();
+    repositories::stocks::get_stock_by_symbol(&state.db, &symbol)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(|| {
+            (
+                axum::http::StatusCode::NOT_FOUND,
+                format!("Stock not found: {}", symbol),
+            )
+        })?;
+
+    let days = days.unwrap_or(DEFAULT_VALUE_DAYS).clamp(1, MAX_VALUE_DAYS);
+    let now = Utc::now();
+    let prices = repositories::prices::get_price_history(
+        &state.db,
+        &symbol,
+        now - Duration::days(days + CUSTOM_INDICATOR_DAYS),
+        now,
+    )
+    .await
+    .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let evaluated = expr.evaluate(&ohlcv_bars(&prices));
+    let from = now - Duration::days(days);
+    let values: Vec<IndicatorValue> = prices
+        .iter()
+        .zip(evaluated)
+        .filter(|(price, _)| price.time >= from)
+        .map(|(price, value)| IndicatorValue {
+            time: price.time,
+            value,
+        })
+        .collect();
+
+    Ok(CustomIndicatorValuesResponse {
+        symbol,
+        expression: expr.to_string(),
+        latest: values.last().and_then(|v| v.value),
+        values,
+    })
+}
+
+async fn get_indicator_values(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path((id, symbol)): Path<(i32, String)>,
+    Query(query): Query<ValuesQuery>,
+) -> Result<Json<CustomIndicatorValuesResponse>, (axum::http::StatusCode, String)> {
+    let row = repositories::custom_indicators::get_custom_indicator(&state.db, &user.username, id)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(indicator_not_found)?;
+    let expr = IndicatorExpr::parse(&row.expression).map_err(|e| {
+        (
+            axum::http::StatusCode::UNPROCESSABLE_ENTITY,
+            format!("Stored expression no longer parses: {}", e),
+        )
+    })?;
+
+    indicator_values(&state, &symbol, &expr, query.days)
+        .await
+        .map(Json)
+}
+
+/// Evaluate an unsaved expression on a symbol
+async fn preview_indicator(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<PreviewRequest>,
+) -> Result<Json<CustomIndicatorValuesResponse>, (axum::http::StatusCode, String)> {
+    let expr = IndicatorExpr::parse(&req.expression).map_err(|e| {
+        (
+            axum::http::StatusCode::BAD_REQUEST,
+            format!("Invalid expression: {}", e),
+        )
+    })?;
+
+    indicator_values(&state, &req.symbol, &expr, req.days)
+        .await
+        .map(Json)
+}
@@ -0,0 +1,64 @@
+//! Glossary routes
+//!
+//! Serves explanation content for the `glossary_key` carried by alerts and
+//! analysis signals, so the frontend can render educational tooltips.
+
+use crate::auth::AuthUser;
+use crate::AppState;
+use axum::{
+    extract::{Path, Query},
+    routing::get,
+    Json, Router,
+};
+use jejakcuan_core::{glossary, glossary_entry, GlossaryCategory, GlossaryEntry};
+use serde::Deserialize;
+use std::sync::Arc;
+
+pub fn glossary_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/", get(list_entries))
+        .route("/:key", get(get_entry))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GlossaryQuery {
+    category: Option<String>,
+}
+
+async fn list_entries(
+    _user: AuthUser,
+    Query(query): Query<GlossaryQuery>,
+) -> Result<Json<Vec<GlossaryEntry>>, (axum::http::StatusCode, String)> {
+    let category = match query.category.as_deref() {
+        Some(c) => Some(GlossaryCategory::from_str_opt(c).ok_or_else(|| {
+            (
+                axum::http::StatusCode::BAD_REQUEST,
+                format!("Unknown glossary category: {}", c),
+            )
+        })?),
+        None => None,
+    };
+
+    let entries = glossary()
+        .iter()
+        .filter(|e| category.is_none_or(|c| e.category == c))
+        .cloned()
+        .collect();
+
+    Ok(Json(entries))
+}
+
+async fn get_entry(
+    _user: AuthUser,
+    Path(key): Path<String>,
+) -> Result<Json<GlossaryEntry>, (axum::http::StatusCode, String)> {
+    glossary_entry(&key.to_lowercase())
+        .cloned()
+        .map(Json)
+        .ok_or_else(|| {
+            (
+                axum::http::StatusCode::NOT_FOUND,
+                format!("Glossary entry not found: {}", key),
+            )
+        })
+}
@@ -0,0 +1,553 @@
+//! User-uploaded data import routes
+//!
+//! Lets admins feed broker summary and price data from CSV/XLSX exports of
+//! trading apps when the scrapers are unavailable or history has gaps. Each
+//! uploaded file is parsed, validated row by row and upserted, and the
+//! response carries a report per file.
+
+use crate::auth::AuthUser;
+use crate::price_store::PriceStore;
+use crate::routes::stocks::recompute_batch;
+use crate::routes::symbols::load_symbol_mapper;
+use crate::AppState;
+use axum::{
+    extract::{Multipart, Query, State},
+    routing::post,
+    Json, Router,
+};
+use chrono::{Datelike, NaiveDate, Utc, Weekday};
+use jejakcuan_data_sources::spreadsheet::to_csv_bytes;
+use jejakcuan_data_sources::{
+    parse_ohlcv_csv, BrokerParseContext, BrokerParserRegistry, BrokerSummary, SymbolMapper,
+};
+use jejakcuan_db::repositories::{self, InsertBrokerSummary, InsertPrice};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+use std::sync::Arc;
+
+/// Maximum number of row errors listed per file
+const MAX_REPORTED_ERRORS: usize = 50;
+
+pub fn import_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/broker-summary", post(import_broker_summary))
+        .route("/prices", post(import_prices))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BrokerImportQuery {
+    /// Symbol for exports that do not carry one per row
+    symbol: Option<String>,
+    /// Trading date for exports that do not carry one per row
+    date: Option<NaiveDate>,
+    /// Force a parser by name instead of detecting the format
+    format: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PriceImportQuery {
+    /// Symbol for exports that do not carry one per row
+    symbol: Option<String>,
+    /// Replace prices already stored for the same symbol and day
+    #[serde(default)]
+    overwrite: bool,
+    /// Recompute scores for symbols that received new prices
+    #[serde(default = "default_true")]
+    recompute: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportStatus {
+    Imported,
+    Partial,
+    Failed,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FileImportReport {
+    pub file_name: String,
+    pub status: ImportStatus,
+    pub parser: Option<&'static str>,
+    pub rows_parsed: usize,
+    pub rows_inserted: u64,
+    pub rows_replaced: u64,
+    pub rows_duplicate: u64,
+    pub rows_rejected: usize,
+    pub errors: Vec<String>,
+}
+
+impl FileImportReport {
+    fn new(file_name: String) -> Self {
+        Self {
+            file_name,
+            status: ImportStatus::Failed,
+            parser: None,
+            rows_parsed: 0,
+            rows_inserted: 0,
+            rows_replaced: 0,
+            rows_duplicate: 0,
+            rows_rejected: 0,
+            errors: Vec::new(),
+        }
+    }
+
+    fn failed(mut self, error: impl Into<String>) -> Self {
+        self.status = ImportStatus::Failed;
+        self.errors.push(error.into());
+        self
+    }
+
+    fn reject(&mut self, error: String) {
+        self.rows_rejected += 1;
+        if self.errors.len() < MAX_REPORTED_ERRORS {
+            self.errors.push(error);
+        }
+    }
+
+    fn finish(mut self) -> Self {
+        let accepted = self.rows_inserted + self.rows_replaced + self.rows_duplicate;
+        self.status = if accepted == 0 {
+            ImportStatus::Failed
+        } else if self.rows_rejected > 0 {
+            ImportStatus::Partial
+        } else {
+            ImportStatus::Imported
+        };
+        self
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportResponse {
+    pub files: Vec<FileImportReport>,
+    pub total_inserted: u64,
+    pub total_replaced: u64,
+    pub total_rejected: usize,
+    /// Symbols whose scores are being recomputed after the import
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub recompute_symbols: Vec<String>,
+}
+
+impl ImportResponse {
+    fn from_reports(files: Vec<FileImportReport>) -> Self {
+        Self {
+            total_inserted: files.iter().map(|f| f.rows_inserted).sum(),
+            total_replaced: files.iter().map(|f| f.rows_replaced).sum(),
+            total_rejected: files.iter().map(|f| f.rows_rejected).sum(),
+            recompute_symbols: Vec::new(),
+            files,
+        }
+    }
+}
+
+/// Uploaded file from a multipart request
+struct UploadedFile {
+    name: String,
+    content: Vec<u8>,
+}
+
+/// Collect all file parts of a multipart upload
+async fn read_uploads(
+    mut multipart: Multipart,
+) -> Result<Vec<UploadedFile>, (axum::http::StatusCode, String)> {
+    let mut files = Vec::new();
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| (axum::http::StatusCode::BAD_REQUEST, e.to_string()))?
+    {
+        let Some(name) = field.file_name().map(str::to_string) else {
+            continue;
+        };
+        let content = field
+            .bytes()
+            .await
+            .map_err(|e| (axum::http::StatusCode::BAD_REQUEST, e.to_string()))?;
+
+        files.push(UploadedFile {
+            name,
+            content: content.to_vec(),
+        });
+    }
+
+    if files.is_empty() {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            "No files uploaded".to_string(),
+        ));
+    }
+
+    Ok(files)
+}
+
+async fn known_symbols(
+    pool: &sqlx::PgPool,
+) -> Result<HashSet<String>, (axum::http::StatusCode, String)> {
+    Ok(repositories::stocks::get_all_stocks(pool)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .into_iter()
+        .map(|stock| stock.symbol)
+        .collect())
+}
+
+/// Check that a row targets a listed stock on a past trading day
+fn validate_symbol_and_date(
+    symbol: &str,
+    date: NaiveDate,
+    symbols: &HashSet<String>,
+    today: NaiveDate,
+) -> Result<(), String> {
+    if !symbols.contains(symbol) {
+        return Err(format!("unknown symbol {}", symbol));
+    }
+    if date > today {
+        return Err(format!("date {} is in the future", date));
+    }
+    if matches!(date.weekday(), Weekday::Sat | Weekday::Sun) {
+        return Err(format!("date {} is not a trading day", date));
+    }
+    Ok(())
+}
+
+fn midnight_utc(date: NaiveDate) -> chrono::DateTime<Utc> {
+    date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc()
+}
+
+/// Check a parsed broker summary row before it is written
+fn validate_broker_row(
+    row: &BrokerSummary,
+    symbols: &HashSet<String>,
+    today: NaiveDate,
+) -> Result<(), String> {
+    validate_symbol_and_date(&row.symbol, row.date, symbols, today)?;
+    if row.broker_code.len() < 2
+        || row.broker_code.len() > 4
+        || !row.broker_code.chars().all(|c| c.is_ascii_alphanumeric())
+    {
+        return Err(format!("invalid broker code {:?}", row.broker_code));
+    }
+    if row.buy_volume < 0
+        || row.sell_volume < 0
+        || row.buy_value < Decimal::ZERO
+        || row.sell_value < Decimal::ZERO
+    {
+        return Err("negative volume or value".to_string());
+    }
+    if row.buy_volume == 0 && row.sell_volume == 0 {
+        return Err("no buy or sell volume".to_string());
+    }
+    Ok(())
+}
+
+/// Parse, validate and upsert one broker summary export
+async fn import_broker_file(
+    pool: &sqlx::PgPool,
+    registry: &BrokerParserRegistry,
+    file: UploadedFile,
+    query: &BrokerImportQuery,
+    symbols: &HashSet<String>,
+    mapper: &SymbolMapper,
+) -> FileImportReport {
+    let mut report = FileImportReport::new(file.name);
+
+    let content = match to_csv_bytes(&file.content) {
+        Ok(content) => content,
+        Err(e) => return report.failed(e.to_string()),
+    };
+
+    let ctx = BrokerParseContext {
+        symbol: query.symbol.as_ref().map(|s| s.to_uppercase()),
+        date: query.date,
+    };
+    let parsed = match query.format.as_deref() {
+        Some(name) => match registry.get(name) {
+            Some(parser) => parser
+                .parse(&content, &ctx)
+                .map(|rows| (parser.name(), rows)),
+            None => return report.failed(format!("Unknown broker parser: {}", name)),
+        },
+        None => registry.parse(&content, &ctx),
+    };
+    let (parser, mut rows) = match parsed {
+        Ok(parsed) => parsed,
+        Err(e) => return report.failed(e.to_string()),
+    };
+    for row in &mut rows {
+        row.symbol = mapper.canonicalize(&row.symbol);
+    }
+
+    let source = format!("import:{}", parser);
+    report.parser = Some(parser);
+    report.rows_parsed = rows.len();
+
+    let today = Utc::now().date_naive();
+    let mut seen = HashSet::new();
+    let mut valid = Vec::with_capacity(rows.len());
+    for (index, row) in rows.iter().enumerate() {
+        if let Err(e) = validate_broker_row(row, symbols, today) {
+            report.reject(format!("Row {}: {}", index + 1, e));
+            continue;
+        }
+        if !seen.insert((row.date, row.symbol.as_str(), row.broker_code.as_str())) {
+            report.rows_duplicate += 1;
+            continue;
+        }
+
+        valid.push(InsertBrokerSummary {
+            time: midnight_utc(row.date),
+            symbol: &row.symbol,
+            broker_code: &row.broker_code,
+            buy_volume: row.buy_volume,
+            sell_volume: row.sell_volume,
+            buy_value: row.buy_value,
+            sell_value: row.sell_value,
+            source: &source,
+        });
+    }
+
+    if valid.is_empty() {
+        return report.finish();
+    }
+
+    match repositories::upsert_broker_summaries(pool, &valid).await {
+        Ok(counts) => {
+            report.rows_inserted = counts.inserted;
+            report.rows_replaced = counts.replaced;
+        }
+        Err(e) => return report.failed(format!("Database error: {}", e)),
+    }
+
+    report.finish()
+}
+
+/// Import broker summary CSV/XLSX exports
+async fn import_broker_summary(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<BrokerImportQuery>,
+    multipart: Multipart,
+) -> Result<Json<ImportResponse>, (axum::http::StatusCode, String)> {
+    let files = read_uploads(multipart).await?;
+    let symbols = known_symbols(&state.db).await?;
+    let mapper = load_symbol_mapper(&state.db)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let registry = BrokerParserRegistry::with_defaults();
+
+    let mut reports = Vec::with_capacity(files.len());
+    for file in files {
+        let report =
+            import_broker_file(&state.db, &registry, file, &query, &symbols, &mapper).await;
+        tracing::info!(
+            "Broker summary import {}: {:?}, {} inserted, {} replaced, {} rejected",
+            report.file_name,
+            report.status,
+            report.rows_inserted,
+            report.rows_replaced,
+            report.rows_rejected
+        );
+        reports.push(report);
+    }
+
+    Ok(Json(ImportResponse::from_reports(reports)))
+}
+
+/// Parse, validate and upsert one OHLCV export
+async fn import_price_file(
+    pool: &sqlx::PgPool,
+    store: &PriceStore,
+    file: UploadedFile,
+    query: &PriceImportQuery,
+    symbols: &HashSet<String>,
+    mapper: &SymbolMapper,
+    touched: &mut BTreeSet<String>,
+) -> FileImportReport {
+    let mut report = FileImportReport::new(file.name);
+
+    let content = match to_csv_bytes(&file.content) {
+        Ok(content) => content,
+        Err(e) => return report.failed(e.to_string()),
+    };
+    let mut parsed = match parse_ohlcv_csv(&content, query.symbol.as_deref()) {
+        Ok(parsed) => parsed,
+        Err(e) => return report.failed(e.to_string()),
+    };
+    for bar in &mut parsed.bars {
+        bar.symbol = mapper.canonicalize(&bar.symbol);
+    }
+
+    report.parser = Some("ohlcv_csv");
+    report.rows_parsed = parsed.bars.len() + parsed.errors.len();
+    for error in parsed.errors {
+        report.reject(error);
+    }
+
+    let today = Utc::now().date_naive();
+    let mut seen = HashSet::new();
+    let mut valid = Vec::with_capacity(parsed.bars.len());
+    for bar in &parsed.bars {
+        let result = validate_symbol_and_date(&bar.symbol, bar.date, symbols, today)
+            .and_then(|_| bar.validate());
+        if let Err(e) = result {
+            report.reject(format!("{} {}: {}", bar.symbol, bar.date, e));
+            continue;
+        }
+        if !seen.insert((bar.date, bar.symbol.as_str())) {
+            report.rows_duplicate += 1;
+            continue;
+        }
+
+        valid.push(InsertPrice {
+            time: midnight_utc(bar.date),
+            symbol: &bar.symbol,
+            open: bar.open,
+            high: bar.high,
+            low: bar.low,
+            close: bar.close,
+            volume: bar.volume,
+        });
+    }
+
+    if valid.is_empty() {
+        return report.finish();
+    }
+
+    match repositories::upsert_prices(pool, &valid, query.overwrite).await {
+        Ok(counts) => {
+            report.rows_inserted = counts.inserted;
+            report.rows_replaced = counts.replaced;
+            report.rows_duplicate += counts.duplicates;
+            for symbol in &counts.written {
+                store.invalidate(symbol);
+            }
+            touched.extend(counts.written);
+        }
+        Err(e) => return report.failed(format!("Database error: {}", e)),
+    }
+
+    report.finish()
+}
+
+/// Import OHLCV CSV/XLSX exports and refresh scores for affected symbols
+async fn import_prices(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<PriceImportQuery>,
+    multipart: Multipart,
+) -> Result<Json<ImportResponse>, (axum::http::StatusCode, String)> {
+    let files = read_uploads(multipart).await?;
+    let symbols = known_symbols(&state.db).await?;
+    let mapper = load_symbol_mapper(&state.db)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let mut touched = BTreeSet::new();
+    let mut reports = Vec::with_capacity(files.len());
+    for file in files {
+        let report = import_price_file(
+            &state.db,
+            &state.price_store,
+            file,
+            &query,
+            &symbols,
+            &mapper,
+            &mut touched,
+        )
+        .await;
+        tracing::info!(
+            "Price import {}: {:?}, {} inserted, {} replaced, {} duplicate, {} rejected",
+            report.file_name,
+            report.status,
+            report.rows_inserted,
+            report.rows_replaced,
+            report.rows_duplicate,
+            report.rows_rejected
+        );
+        reports.push(report);
+    }
+
+    let mut response = ImportResponse::from_reports(reports);
+    if query.recompute && !touched.is_empty() {
+        response.recompute_symbols = touched.iter().cloned().collect();
+
+        // Scores derive indicators from price history, so refresh them off the request path
+        let state = state.clone();
+        tokio::spawn(async move {
+            let symbols: Vec<String> = touched.into_iter().collect();
+            let results = recompute_batch(&state, symbols.clone()).await;
+            let mut failed = 0;
+            for (symbol, result) in symbols.iter().zip(&results) {
+                if let Err(e) = result {
+                    tracing::warn!("Score recompute after import failed for {}: {}", symbol, e);
+                    failed += 1;
+                }
+            }
+
+            if failed > 0 {
+                tracing::warn!("{} score recomputes failed after price import", failed);
+            }
+        });
+    }
+
+    Ok(Json(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(symbol: &str, broker_code: &str, date: NaiveDate) -> BrokerSummary {
+        BrokerSummary::new(
+            date,
+            symbol.to_string(),
+            broker_code.to_string(),
+            1000,
+            Decimal::from(9_500_000),
+            0,
+            Decimal::ZERO,
+        )
+    }
+
+    #[test]
+    fn test_validate_broker_row() {
+        let symbols: HashSet<String> = ["BBCA".to_string()].into_iter().collect();
+        let today = NaiveDate::from_ymd_opt(2024, 1, 10).unwrap();
+        let tuesday = NaiveDate::from_ymd_opt(2024, 1, 9).unwrap();
+
+        assert!(validate_broker_row(&summary("BBCA", "BK", tuesday), &symbols, today).is_ok());
+        assert!(validate_broker_row(&summary("XXXX", "BK", tuesday), &symbols, today).is_err());
+        assert!(validate_broker_row(&summary("BBCA", "B-K", tuesday), &symbols, today).is_err());
+
+        let saturday = NaiveDate::from_ymd_opt(2024, 1, 6).unwrap();
+        assert!(validate_broker_row(&summary("BBCA", "BK", saturday), &symbols, today).is_err());
+
+        let future = NaiveDate::from_ymd_opt(2024, 1, 11).unwrap();
+        assert!(validate_broker_row(&summary("BBCA", "BK", future), &symbols, today).is_err());
+    }
+
+    #[test]
+    fn test_duplicates_count_as_accepted() {
+        let mut report = FileImportReport::new("c.csv".to_string());
+        report.rows_duplicate = 10;
+        assert_eq!(report.finish().status, ImportStatus::Imported);
+    }
+
+    #[test]
+    fn test_report_status() {
+        let mut report = FileImportReport::new("a.csv".to_string());
+        report.rows_inserted = 3;
+        report.reject("Row 4: unknown symbol".to_string());
+        assert_eq!(report.finish().status, ImportStatus::Partial);
+
+        let report = FileImportReport::new("b.csv".to_string()).finish();
+        assert_eq!(report.status, ImportStatus::Failed);
+    }
+}
@@ -1,10 +1,14 @@
 //! Background job management for data source triggers
 //!
-//! Provides async execution of Python scrapers with status tracking.
+//! Provides async execution of Python scrapers and in-process tasks with
+//! status tracking.
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
 use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tokio::process::Command;
 use tokio::sync::RwLock;
@@ -33,18 +37,81 @@ pub struct Job {
     pub started_at: DateTime<Utc>,
     pub completed_at: Option<DateTime<Utc>>,
     pub duration_secs: Option<f64>,
+    /// Work done so far, for tasks that report it
+    pub progress: Option<JobProgress>,
+}
+
+/// Units of work a job has finished out of its total
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct JobProgress {
+    pub done: u64,
+    pub total: u64,
+}
+
+/// Whether a task's runs count towards data source SLA tracking
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobKind {
+    /// Fetches from a data source; runs are recorded for SLA tracking
+    DataSource,
+    /// In-process work such as a grid search; never recorded
+    Internal,
+}
+
+/// A running task's link back to its job
+///
+/// Cancellation is cooperative: the task polls [`JobHandle::is_cancelled`]
+/// between units of work and returns early once it is set.
+#[derive(Debug, Clone)]
+pub struct JobHandle {
+    id: String,
+    manager: Arc<JobManager>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl JobHandle {
+    /// Id of the job the task runs as
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Whether the job has been cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Record how much of the task is done
+    pub async fn set_progress(&self, done: u64, total: u64) {
+        let mut jobs = self.manager.jobs.write().await;
+        if let Some(job) = jobs.get_mut(&self.id) {
+            job.progress = Some(JobProgress { done, total });
+        }
+    }
 }
 
 /// Job manager for tracking background jobs
 #[derive(Debug, Default)]
 pub struct JobManager {
     jobs: RwLock<HashMap<String, Job>>,
+    /// Cancellation flags of running tasks, by job id
+    cancellations: RwLock<HashMap<String, Arc<AtomicBool>>>,
+    db: Option<PgPool>,
 }
 
 impl JobManager {
     pub fn new() -> Self {
         Self {
             jobs: RwLock::new(HashMap::new()),
+            cancellations: RwLock::new(HashMap::new()),
+            db: None,
+        }
+    }
+
+    /// Create a job manager that records run outcomes for SLA tracking
+    pub fn with_db(db: PgPool) -> Self {
+        Self {
+            jobs: RwLock::new(HashMap::new()),
+            cancellations: RwLock::new(HashMap::new()),
+            db: Some(db),
         }
     }
 
@@ -55,6 +122,49 @@ impl JobManager {
         source_name: String,
         command: String,
     ) -> Job {
+        let command_clone = command.clone();
+        self.spawn_task(source_id, source_name, command, async move {
+            execute_command(&command_clone).await
+        })
+        .await
+    }
+
+    /// Track an in-process task as a job
+    ///
+    /// `command` only describes the task; the future's `Ok` value becomes the
+    /// job output.
+    pub async fn spawn_task<F>(
+        self: &Arc<Self>,
+        source_id: String,
+        source_name: String,
+        command: String,
+        task: F,
+    ) -> Job
+    where
+        F: Future<Output = Result<String, String>> + Send + 'static,
+    {
+        self.spawn_task_with(source_id, source_name, command, JobKind::DataSource, |_| {
+            task
+        })
+        .await
+    }
+
+    /// Track an in-process task that reports progress and honours cancellation
+    ///
+    /// `task` receives the job's [`JobHandle`]. Only [`JobKind::DataSource`]
+    /// runs are recorded for SLA tracking.
+    pub async fn spawn_task_with<T, F>(
+        self: &Arc<Self>,
+        source_id: String,
+        source_name: String,
+        command: String,
+        kind: JobKind,
+        task: T,
+    ) -> Job
+    where
+        T: FnOnce(JobHandle) -> F,
+        F: Future<Output = Result<String, String>> + Send + 'static,
+    {
         let job_id = Uuid::new_v4().to_string();
         let now = Utc::now();
 
@@ -62,37 +172,51 @@ impl JobManager {
             id: job_id.clone(),
             source_id: source_id.clone(),
             source_name,
-            command: command.clone(),
+            command,
             status: JobStatus::Running,
             message: Some("Job started".to_string()),
             output: None,
             started_at: now,
             completed_at: None,
             duration_secs: None,
+            progress: None,
         };
 
         // Store job
+        let cancelled = Arc::new(AtomicBool::new(false));
         {
             let mut jobs = self.jobs.write().await;
             jobs.insert(job_id.clone(), job.clone());
+            let mut cancellations = self.cancellations.write().await;
+            cancellations.insert(job_id.clone(), cancelled.clone());
         }
+        let task = task(JobHandle {
+            id: job_id.clone(),
+            manager: Arc::clone(self),
+            cancelled: cancelled.clone(),
+        });
 
         // Spawn background task
         let manager = Arc::clone(self);
         let job_id_clone = job_id.clone();
-        let command_clone = command.clone();
 
         tokio::spawn(async move {
-            let result = execute_command(&command_clone).await;
+            let result = task.await;
             let completed_at = Utc::now();
 
+            let duration_secs = (completed_at - now).num_milliseconds() as f64 / 1000.0;
+            let run_error = result.as_ref().err().cloned();
+            let was_cancelled = cancelled.load(Ordering::Relaxed);
+
+            manager.cancellations.write().await.remove(&job_id_clone);
             let mut jobs = manager.jobs.write().await;
             if let Some(job) = jobs.get_mut(&job_id_clone) {
                 job.completed_at = Some(completed_at);
-                job.duration_secs =
-                    Some((completed_at - job.started_at).num_milliseconds() as f64 / 1000.0);
+                job.duration_secs = Some(duration_secs);
 
                 match result {
+                    // Keep the cancellation as the job's outcome
+                    _ if was_cancelled => {}
                     Ok(output) => {
                         job.status = JobStatus::Completed;
                         job.message = Some("Completed successfully".to_string());
@@ -118,6 +242,24 @@ impl JobManager {
                     jobs.remove(&id);
                 }
             }
+            drop(jobs);
+
+            if kind == JobKind::Internal || was_cancelled {
+                return;
+            }
+            if let Some(ref pool) = manager.db {
+                if let Err(e) = jejakcuan_db::record_data_source_run(
+                    pool,
+                    &source_id,
+                    run_error.is_none(),
+                    duration_secs,
+                    run_error.as_deref(),
+                )
+                .await
+                {
+                    tracing::warn!("Failed to record SLA run for {}: {}", source_id, e);
+                }
+            }
         });
 
         job
@@ -159,10 +301,16 @@ impl JobManager {
     }
 
     /// Cancel a job by marking it as failed with "Cancelled by user" message
+    ///
+    /// Tasks started through [`JobManager::spawn_task_with`] are also told to
+    /// stop; others run to completion but keep the cancelled status.
     pub async fn cancel_job(&self, job_id: &str) -> Option<Job> {
         let mut jobs = self.jobs.write().await;
         if let Some(job) = jobs.get_mut(job_id) {
             if matches!(job.status, JobStatus::Running | JobStatus::Pending) {
+                if let Some(cancelled) = self.cancellations.read().await.get(job_id) {
+                    cancelled.store(true, Ordering::Relaxed);
+                }
                 job.status = JobStatus::Failed;
                 job.message = Some("Cancelled by user".to_string());
                 job.completed_at = Some(Utc::now());
@@ -175,6 +323,28 @@ impl JobManager {
         }
         None
     }
+
+    /// Fail jobs still running after `max_age`
+    ///
+    /// A task that panics never reports back, so its job would otherwise stay
+    /// running forever. Returns the number of jobs marked.
+    pub async fn prune_orphaned(&self, max_age: chrono::Duration) -> usize {
+        let now = Utc::now();
+        let mut jobs = self.jobs.write().await;
+        let mut pruned = 0;
+        for job in jobs.values_mut() {
+            if matches!(job.status, JobStatus::Running | JobStatus::Pending)
+                && now - job.started_at > max_age
+            {
+                job.status = JobStatus::Failed;
+                job.message = Some("Orphaned: no completion reported".to_string());
+                job.completed_at = Some(now);
+                job.duration_secs = Some((now - job.started_at).num_milliseconds() as f64 / 1000.0);
+                pruned += 1;
+            }
+        }
+        pruned
+    }
 }
 
 async fn execute_command(command: &str) -> Result<String, String> {
@@ -240,3 +410,43 @@ async fn execute_command(command: &str) -> Result<String, String> {
         Err(error_msg)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_cancelled_task_stops_and_keeps_cancelled_status() {
+        let manager = Arc::new(JobManager::new());
+        let job = manager
+            .spawn_task_with(
+                "internal".to_string(),
+                "Internal".to_string(),
+                "loop".to_string(),
+                JobKind::Internal,
+                |handle| async move {
+                    for done in 1.. {
+                        if handle.is_cancelled() {
+                            return Err("Cancelled by user".to_string());
+                        }
+                        handle.set_progress(done, 1_000).await;
+                        tokio::time::sleep(Duration::from_millis(5)).await;
+                    }
+                    Ok("finished".to_string())
+                },
+            )
+            .await;
+
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        let progress = manager.get_job(&job.id).await.and_then(|j| j.progress);
+        assert!(progress.is_some_and(|p| p.done > 0 && p.total == 1_000));
+
+        assert!(manager.cancel_job(&job.id).await.is_some());
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        let job = manager.get_job(&job.id).await.unwrap();
+        assert!(matches!(job.status, JobStatus::Failed));
+        assert_eq!(job.message.as_deref(), Some("Cancelled by user"));
+        assert!(manager.cancellations.read().await.is_empty());
+    }
+}
@@ -0,0 +1,426 @@
+//! Trade journal routes
+//!
+//! Users log the trades they actually took, linked to the alert or score
+//! signal that prompted them. Analytics compare realized performance with
+//! the signals to surface behavioral leaks. Open entries double as the
+//! current positions for risk-budget checks.
+
+use crate::auth::AuthUser;
+use crate::notification_retry;
+use crate::notifications::{Notification, NotificationMetadata, NotificationPriority};
+use crate::routes::admin::admin_recipients;
+use crate::AppState;
+use axum::{
+    extract::{Path, Query, State},
+    routing::{delete, get, post},
+    Json, Router,
+};
+use chrono::{DateTime, Duration, Utc};
+use jejakcuan_core::{
+    analyze_journal, evaluate_risk_budget, JournalAnalytics, JournalTrade, PositionRiskInput,
+    RiskBudgetReport, SignalReference,
+};
+use jejakcuan_db::{repositories, InsertTradeJournalEntry, TradeJournalRow};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+pub fn journal_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/", get(list_trades))
+        .route("/", post(create_trade))
+        .route("/analytics", get(get_analytics))
+        .route("/risk", get(get_risk_budget))
+        .route("/:id", get(get_trade))
+        .route("/:id", delete(delete_trade))
+        .route("/:id/close", post(close_trade))
+}
+
+/// Calendar days of history fetched for the 14-period ATR
+const ATR_LOOKBACK_DAYS: i64 = 60;
+
+/// Calendar days the traded value is averaged over for slippage (~20 sessions)
+const ADTV_LOOKBACK_DAYS: i64 = 30;
+
+#[derive(Debug, Deserialize)]
+pub struct JournalQuery {
+    symbol: Option<String>,
+    #[serde(default)]
+    open_only: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateTradeRequest {
+    symbol: String,
+    entry_price: Decimal,
+    /// Number of shares (1 lot = 100 shares)
+    quantity: i64,
+    entry_time: Option<DateTime<Utc>>,
+    fees: Option<Decimal>,
+    rationale: Option<String>,
+    /// 'alert', 'score', 'screener' or 'manual'
+    signal_source: Option<String>,
+    alert_history_id: Option<i32>,
+    signal_time: Option<DateTime<Utc>>,
+    signal_price: Option<Decimal>,
+    signal_score: Option<Decimal>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CloseTradeRequest {
+    exit_price: Decimal,
+    exit_time: Option<DateTime<Utc>>,
+    fees: Option<Decimal>,
+    note: Option<String>,
+}
+
+/// Convert a stored entry into the analytics model
+fn to_journal_trade(row: &TradeJournalRow) -> JournalTrade {
+    let signal = match (row.signal_time, row.signal_price) {
+        (Some(time), Some(price)) => Some(SignalReference {
+            source: row
+                .signal_source
+                .clone()
+                .unwrap_or_else(|| "signal".to_string()),
+            time,
+            price,
+        }),
+        _ => None,
+    };
+
+    JournalTrade {
+        symbol: row.symbol.clone(),
+        entry_time: row.entry_time,
+        entry_price: row.entry_price,
+        quantity: row.quantity,
+        exit_time: row.exit_time,
+        exit_price: row.exit_price,
+        fees: row.fees,
+        signal,
+    }
+}
+
+async fn list_trades(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<JournalQuery>,
+) -> Result<Json<Vec<TradeJournalRow>>, (axum::http::StatusCode, String)> {
+    let symbol = query.symbol.map(|s| s.to_uppercase());
+    let trades = repositories::trade_journal::get_trade_journal(
+        &state.db,
+        symbol.as_deref(),
+        query.open_only,
+    )
+    .await
+    .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(trades))
+}
+
+async fn get_trade(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<Json<TradeJournalRow>, (axum::http::StatusCode, String)> {
+    repositories::trade_journal::get_trade_journal_entry(&state.db, id)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map(Json)
+        .ok_or_else(|| {
+            (
+                axum::http::StatusCode::NOT_FOUND,
+                "Journal entry not found".to_string(),
+            )
+        })
+}
+
+async fn create_trade(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<CreateTradeRequest>,
+) -> Result<Json<TradeJournalRow>, (axum::http::StatusCode, String)> {
+    let symbol = req.symbol.to_uppercase();
+    if req.entry_price <= Decimal::ZERO || req.quantity <= 0 {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            "Entry price and quantity must be positive".to_string(),
+        ));
+    }
+
+    repositories::stocks::get_stock_by_symbol(&state.db, &symbol)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(|| {
+            (
+                axum::http::StatusCode::NOT_FOUND,
+                format!("Stock {} not found", symbol),
+            )
+        })?;
+
+    let mut signal_source = req.signal_source.clone();
+    let mut signal_time = req.signal_time;
+    let mut signal_price = req.signal_price;
+    let mut signal_score = req.signal_score;
+
+    // Fill in the signal snapshot from the alert that fired
+    if let Some(alert_id) = req.alert_history_id {
+        let alert = repositories::trade_journal::get_alert_signal(&state.db, alert_id)
+            .await
+            .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+            .ok_or_else(|| {
+                (
+                    axum::http::StatusCode::NOT_FOUND,
+                    format!("Alert history entry {} not found", alert_id),
+                )
+            })?;
+        signal_source.get_or_insert_with(|| "alert".to_string());
+        signal_time = signal_time.or(alert.triggered_at);
+        signal_price = signal_price.or(alert.price);
+    }
+
+    // Score-driven trades reference the latest stored score and the close at that time
+    if signal_source.as_deref() == Some("score") && signal_score.is_none() {
+        if let Some(score) = repositories::scores::get_stock_score(&state.db, &symbol)
+            .await
+            .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        {
+            signal_score = Some(score.composite_score);
+            signal_time = signal_time.or(Some(score.time));
+        }
+    }
+
+    if let (Some(time), None) = (signal_time, signal_price) {
+        let prices = repositories::prices::get_price_history(
+            &state.db,
+            &symbol,
+            time - Duration::days(7),
+            time,
+        )
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        signal_price = prices.last().map(|p| p.close);
+    }
+
+    let entry = InsertTradeJournalEntry {
+        symbol: &symbol,
+        entry_time: req.entry_time.unwrap_or_else(Utc::now),
+        entry_price: req.entry_price,
+        quantity: req.quantity,
+        fees: req.fees.unwrap_or(Decimal::ZERO),
+        rationale: req.rationale.as_deref(),
+        signal_source: signal_source.as_deref(),
+        alert_history_id: req.alert_history_id,
+        signal_time,
+        signal_price,
+        signal_score,
+    };
+
+    let row = repositories::trade_journal::insert_trade_journal_entry(&state.db, &entry)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    spawn_risk_check(state);
+    Ok(Json(row))
+}
+
+async fn close_trade(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Json(req): Json<CloseTradeRequest>,
+) -> Result<Json<TradeJournalRow>, (axum::http::StatusCode, String)> {
+    if req.exit_price <= Decimal::ZERO {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            "Exit price must be positive".to_string(),
+        ));
+    }
+
+    let row = repositories::trade_journal::close_trade_journal_entry(
+        &state.db,
+        id,
+        req.exit_time.unwrap_or_else(Utc::now),
+        req.exit_price,
+        req.fees.unwrap_or(Decimal::ZERO),
+        req.note.as_deref(),
+    )
+    .await
+    .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .ok_or_else(|| {
+        (
+            axum::http::StatusCode::NOT_FOUND,
+            "Open journal entry not found".to_string(),
+        )
+    })?;
+
+    spawn_risk_check(state);
+    Ok(Json(row))
+}
+
+async fn delete_trade(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, String)> {
+    let deleted = repositories::trade_journal::delete_trade_journal_entry(&state.db, id)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    if deleted {
+        spawn_risk_check(state);
+        Ok(Json(serde_json::json!({ "success": true })))
+    } else {
+        Err((
+            axum::http::StatusCode::NOT_FOUND,
+            "Journal entry not found".to_string(),
+        ))
+    }
+}
+
+async fn get_analytics(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<JournalQuery>,
+) -> Result<Json<JournalAnalytics>, (axum::http::StatusCode, String)> {
+    let symbol = query.symbol.map(|s| s.to_uppercase());
+    let rows = repositories::trade_journal::get_trade_journal(&state.db, symbol.as_deref(), false)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let trades: Vec<JournalTrade> = rows.iter().map(to_journal_trade).collect();
+    let symbols: Vec<String> = rows
+        .iter()
+        .map(|r| r.symbol.clone())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let adtv: BTreeMap<String, Decimal> = repositories::prices::get_average_traded_values(
+        &state.db,
+        &symbols,
+        Utc::now() - Duration::days(ADTV_LOOKBACK_DAYS),
+    )
+    .await
+    .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .into_iter()
+    .collect();
+
+    Ok(Json(analyze_journal(
+        &trades,
+        &state.config.transaction_costs,
+        &adtv,
+    )))
+}
+
+async fn get_risk_budget(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<RiskBudgetReport>, (axum::http::StatusCode, String)> {
+    build_risk_report(&state)
+        .await
+        .map(Json)
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// Evaluate open positions against the configured risk budget
+async fn build_risk_report(state: &AppState) -> Result<RiskBudgetReport, sqlx::Error> {
+    let open = repositories::trade_journal::get_trade_journal(&state.db, None, true).await?;
+
+    let mut quantities: BTreeMap<String, (i64, Decimal)> = BTreeMap::new();
+    for row in &open {
+        let entry = quantities.entry(row.symbol.clone()).or_default();
+        entry.0 += row.quantity;
+        entry.1 = row.entry_price;
+    }
+
+    let mut positions = Vec::with_capacity(quantities.len());
+    for (symbol, (quantity, entry_price)) in quantities {
+        let sector = repositories::stocks::get_stock_by_symbol(&state.db, &symbol)
+            .await?
+            .and_then(|s| s.sector);
+
+        let now = Utc::now();
+        let history = repositories::prices::get_price_history(
+            &state.db,
+            &symbol,
+            now - Duration::days(ATR_LOOKBACK_DAYS),
+            now,
+        )
+        .await?;
+
+        let price = history.last().map(|p| p.close).unwrap_or(entry_price);
+        let highs: Vec<Decimal> = history.iter().map(|p| p.high).collect();
+        let lows: Vec<Decimal> = history.iter().map(|p| p.low).collect();
+        let closes: Vec<Decimal> = history.iter().map(|p| p.close).collect();
+        let atr = jejakcuan_technical::calculate_atr14(&highs, &lows, &closes)
+            .ok()
+            .and_then(|values| values.last().copied())
+            .filter(|v| *v > Decimal::ZERO);
+
+        positions.push(PositionRiskInput {
+            symbol,
+            sector,
+            quantity,
+            price,
+            atr,
+        });
+    }
+
+    Ok(evaluate_risk_budget(&positions, &state.config.risk_budget))
+}
+
+/// Re-run the risk checks after positions change and warn admins on breaches
+fn spawn_risk_check(state: Arc<AppState>) {
+    tokio::spawn(async move {
+        let report = match build_risk_report(&state).await {
+            Ok(report) => report,
+            Err(e) => {
+                tracing::warn!("Risk budget check failed: {}", e);
+                return;
+            }
+        };
+        if report.is_within_budget() {
+            return;
+        }
+
+        for (channel, recipient_id) in admin_recipients(&state) {
+            let notification = risk_breach_notification(&report, channel, recipient_id);
+            if let Err(e) = notification_retry::deliver(&state, &notification).await {
+                tracing::warn!("Failed to deliver risk budget warning: {}", e);
+            }
+        }
+    });
+}
+
+fn risk_breach_notification(
+    report: &RiskBudgetReport,
+    channel: jejakcuan_core::alerts::NotificationChannel,
+    recipient_id: String,
+) -> Notification {
+    let body = report
+        .breaches
+        .iter()
+        .map(|b| format!("- {}", b.message))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Notification {
+        recipient_id,
+        title: format!(
+            "Portfolio risk budget: {} limit(s) exceeded",
+            report.breaches.len()
+        ),
+        body,
+        priority: if report.risk_budget_used_pct > 100.0 {
+            NotificationPriority::Critical
+        } else {
+            NotificationPriority::High
+        },
+        channel,
+        alert: None,
+        metadata: NotificationMetadata {
+            action_url: Some("/journal/risk".to_string()),
+            ..Default::default()
+        },
+    }
+}
@@ -0,0 +1,222 @@
+//! Macro dashboard routes
+//!
+//! Serves the BI-Rate, inflation, USD/IDR, foreign reserves and IHSG series
+//! with the market regime they imply, and refreshes them from Bank Indonesia
+//! and Yahoo Finance as a background job.
+
+use crate::auth::AuthUser;
+use crate::routes::jobs::Job;
+use crate::AppState;
+use axum::{
+    extract::{Query, State},
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::{Duration, NaiveDate, Utc};
+use jejakcuan_core::{detect_regime, PolicyRateTrend, RegimeAssessment, RegimeInput};
+use jejakcuan_data_sources::macro_data::{change_pct_over, policy_rate_trend};
+use jejakcuan_data_sources::{MacroDataClient, MacroIndicator, MacroObservation};
+use jejakcuan_db::repositories;
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Job source id for macro refreshes
+const MACRO_REFRESH_JOB: &str = "macro_data";
+
+const DEFAULT_MONTHS: u32 = 12;
+const MAX_MONTHS: u32 = 120;
+
+/// Window for the policy-rate trend
+const POLICY_RATE_LOOKBACK_DAYS: i64 = 180;
+/// Window for index and currency changes
+const MARKET_LOOKBACK_DAYS: i64 = 90;
+
+/// Yahoo range fetched for market series on refresh
+const MARKET_HISTORY_RANGE: &str = "2y";
+
+pub fn macro_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/", get(get_macro_dashboard))
+        .route("/refresh", post(refresh_macro_data))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MacroDashboardQuery {
+    /// Months of history per series (default 12)
+    months: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MacroPoint {
+    pub date: NaiveDate,
+    pub value: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MacroIndicatorResponse {
+    pub indicator: MacroIndicator,
+    pub unit: &'static str,
+    pub source: &'static str,
+    pub latest: Option<f64>,
+    pub as_of: Option<NaiveDate>,
+    /// Previous published value
+    pub previous: Option<f64>,
+    pub change: Option<f64>,
+    pub series: Vec<MacroPoint>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MacroDashboardResponse {
+    pub indicators: Vec<MacroIndicatorResponse>,
+    pub policy_rate_trend: Option<PolicyRateTrend>,
+    pub regime: RegimeAssessment,
+}
+
+async fn get_macro_dashboard(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<MacroDashboardQuery>,
+) -> Result<Json<MacroDashboardResponse>, (axum::http::StatusCode, String)> {
+    let months = query.months.unwrap_or(DEFAULT_MONTHS);
+    if months == 0 || months > MAX_MONTHS {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            format!("months must be between 1 and {}", MAX_MONTHS),
+        ));
+    }
+
+    let today = Utc::now().date_naive();
+    let window_start = today - Duration::days(i64::from(months) * 31);
+    // Trends look further back than short display windows
+    let load_from = window_start.min(today - Duration::days(POLICY_RATE_LOOKBACK_DAYS + 31));
+
+    let mut series = HashMap::new();
+    for indicator in MacroIndicator::ALL {
+        let rows = repositories::macro_indicators::get_macro_series(
+            &state.db,
+            indicator.as_str(),
+            load_from,
+        )
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        let observations: Vec<MacroObservation> = rows
+            .into_iter()
+            .map(|row| MacroObservation {
+                indicator,
+                date: row.observed_on,
+                value: row.value,
+            })
+            .collect();
+        series.insert(indicator, observations);
+    }
+
+    let rate_trend = policy_rate_trend(&series[&MacroIndicator::BiRate], POLICY_RATE_LOOKBACK_DAYS);
+    let regime = detect_regime(&RegimeInput {
+        index_change_pct: change_pct_over(
+            &series[&MacroIndicator::CompositeIndex],
+            MARKET_LOOKBACK_DAYS,
+        ),
+        policy_rate_trend: rate_trend,
+        usd_idr_change_pct: change_pct_over(&series[&MacroIndicator::UsdIdr], MARKET_LOOKBACK_DAYS),
+        inflation_yoy: series[&MacroIndicator::Inflation].last().map(|o| o.value),
+    });
+
+    let to_f64 = |d: rust_decimal::Decimal| d.to_f64().unwrap_or(0.0);
+    let indicators = MacroIndicator::ALL
+        .into_iter()
+        .map(|indicator| {
+            let observations = &series[&indicator];
+            let latest = observations.last();
+            let previous = observations.len().checked_sub(2).map(|i| &observations[i]);
+            MacroIndicatorResponse {
+                indicator,
+                unit: indicator.unit(),
+                source: indicator.source(),
+                latest: latest.map(|o| to_f64(o.value)),
+                as_of: latest.map(|o| o.date),
+                previous: previous.map(|o| to_f64(o.value)),
+                change: latest.zip(previous).map(|(l, p)| to_f64(l.value - p.value)),
+                series: observations
+                    .iter()
+                    .filter(|o| o.date >= window_start)
+                    .map(|o| MacroPoint {
+                        date: o.date,
+                        value: to_f64(o.value),
+                    })
+                    .collect(),
+            }
+        })
+        .collect();
+
+    Ok(Json(MacroDashboardResponse {
+        indicators,
+        policy_rate_trend: rate_trend,
+        regime,
+    }))
+}
+
+async fn refresh_macro_data(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Job>, (axum::http::StatusCode, String)> {
+    if let Some(job) = state.job_manager.is_source_running(MACRO_REFRESH_JOB).await {
+        return Err((
+            axum::http::StatusCode::CONFLICT,
+            format!("Macro refresh already running (job {})", job.id),
+        ));
+    }
+
+    let pool = state.db.clone();
+    let job = state
+        .job_manager
+        .spawn_task(
+            MACRO_REFRESH_JOB.to_string(),
+            "Macro indicators".to_string(),
+            "fetch BI-Rate, inflation, reserves, USD/IDR and IHSG".to_string(),
+            async move { run_macro_refresh(pool).await },
+        )
+        .await;
+
+    Ok(Json(job))
+}
+
+/// Fetch every macro series and store it
+///
+/// A failing source does not stop the others; the job fails only when
+/// nothing could be fetched.
+async fn run_macro_refresh(pool: sqlx::PgPool) -> Result<String, String> {
+    let client = MacroDataClient::new();
+    let mut lines = Vec::new();
+    let mut errors = Vec::new();
+
+    for indicator in MacroIndicator::ALL {
+        let observations = match client.fetch(indicator, MARKET_HISTORY_RANGE).await {
+            Ok(observations) => observations,
+            Err(e) => {
+                errors.push(format!("{}: {}", indicator.as_str(), e));
+                continue;
+            }
+        };
+        let values: Vec<_> = observations.iter().map(|o| (o.date, o.value)).collect();
+        match repositories::macro_indicators::upsert_macro_observations(
+            &pool,
+            indicator.as_str(),
+            indicator.source(),
+            &values,
+        )
+        .await
+        {
+            Ok(written) => lines.push(format!("{}: {} observations", indicator.as_str(), written)),
+            Err(e) => errors.push(format!("{}: {}", indicator.as_str(), e)),
+        }
+    }
+
+    if lines.is_empty() {
+        Err(errors.join("\n"))
+    } else {
+        lines.extend(errors.into_iter().map(|e| format!("failed {}", e)));
+        Ok(lines.join("\n"))
+    }
+}
@@ -1,19 +1,45 @@
 //! API routes
 
 pub mod admin;
+pub mod alerts;
 pub mod analysis;
 pub mod auth;
+pub mod backtests;
+pub mod calendar;
+pub mod custom_indicators;
 pub mod financials;
+pub mod glossary;
+pub mod import;
 pub mod jobs;
+pub mod journal;
+pub mod macro_data;
+pub mod notifications;
+pub mod portfolio;
+pub mod screens;
+pub mod staging;
 pub mod stocks;
 pub mod streaming;
+pub mod symbols;
 pub mod watchlist;
 
 pub use admin::admin_routes;
+pub use alerts::alert_routes;
 pub use analysis::analysis_routes;
 pub use auth::auth_routes;
+pub use backtests::{backtest_routes, shared_backtest_routes};
+pub use calendar::calendar_routes;
+pub use custom_indicators::custom_indicator_routes;
 pub use financials::financials_routes;
+pub use glossary::glossary_routes;
+pub use import::import_routes;
 pub use jobs::JobManager;
+pub use journal::journal_routes;
+pub use macro_data::macro_routes;
+pub use notifications::notification_routes;
+pub use portfolio::portfolio_routes;
+pub use screens::screen_routes;
+pub use staging::staging_routes;
 pub use stocks::stock_routes;
 pub use streaming::streaming_routes;
+pub use symbols::symbol_routes;
 pub use watchlist::watchlist_routes;
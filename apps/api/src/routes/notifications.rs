@@ -0,0 +1,185 @@
+//! Notification routes
+//!
+//! Previews let users check how an alert reads on each channel and let admins
+//! verify channel configuration, without delivering anything. Each user sets
+//! where their own alerts go on the external channels; the primary admin falls
+//! back to the operator channels for any channel they have not set.
+
+use crate::auth::AuthUser;
+use crate::notifications::{NotificationPreview, NotificationService};
+use crate::routes::admin::admin_recipients;
+use crate::AppState;
+use axum::{
+    extract::{Path, State},
+    routing::{delete, get, post},
+    Json, Router,
+};
+use jejakcuan_core::alerts::{Alert, NotificationChannel};
+use jejakcuan_db::{repositories, UserNotificationChannelRow};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+pub fn notification_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/preview", post(preview_notification))
+        .route("/channels", get(list_channels).put(put_channel))
+        .route("/channels/:channel", delete(delete_channel))
+}
+
+fn parse_channel(name: &str) -> Option<NotificationChannel> {
+    serde_json::from_value(serde_json::Value::String(name.to_string())).ok()
+}
+
+fn channel_name(channel: &NotificationChannel) -> String {
+    format!("{:?}", channel)
+}
+
+/// External channel addresses of `user` from their stored rows
+///
+/// The primary admin keeps the operator channels for channels they have not
+/// set. The in-app feed needs no address and is not included.
+pub(crate) fn user_recipients(
+    state: &AppState,
+    user: &str,
+    stored: &[UserNotificationChannelRow],
+) -> Vec<(NotificationChannel, String)> {
+    let mut recipients: Vec<(NotificationChannel, String)> = stored
+        .iter()
+        .filter(|row| row.user_id == user)
+        .filter_map(|row| Some((parse_channel(&row.channel)?, row.recipient.clone())))
+        .filter(|(channel, _)| *channel != NotificationChannel::InApp)
+        .collect();
+    if user == state.config.username {
+        for (channel, recipient) in admin_recipients(state) {
+            if !recipients.iter().any(|(c, _)| *c == channel) {
+                recipients.push((channel, recipient));
+            }
+        }
+    }
+    recipients
+}
+
+/// Load the external channel addresses of `user`
+pub(crate) async fn load_user_recipients(
+    state: &AppState,
+    user: &str,
+) -> Result<Vec<(NotificationChannel, String)>, sqlx::Error> {
+    let stored =
+        repositories::notification_channels::get_user_notification_channels(&state.db, user)
+            .await?;
+    Ok(user_recipients(state, user, &stored))
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChannelAddress {
+    pub channel: NotificationChannel,
+    pub recipient: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChannelAddressRequest {
+    pub channel: NotificationChannel,
+    pub recipient: String,
+}
+
+/// Where the user's alerts go on each external channel
+async fn list_channels(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<ChannelAddress>>, (axum::http::StatusCode, String)> {
+    let recipients = load_user_recipients(&state, &user.username)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Json(
+        recipients
+            .into_iter()
+            .map(|(channel, recipient)| ChannelAddress { channel, recipient })
+            .collect(),
+    ))
+}
+
+async fn put_channel(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<ChannelAddressRequest>,
+) -> Result<Json<ChannelAddress>, (axum::http::StatusCode, String)> {
+    let bad_request = |msg: String| (axum::http::StatusCode::BAD_REQUEST, msg);
+    if req.channel == NotificationChannel::InApp {
+        return Err(bad_request("The in-app feed needs no address".to_string()));
+    }
+    if !state.notifications.supports(&req.channel) {
+        return Err(bad_request(format!(
+            "Channel not configured on this server: {:?}",
+            req.channel
+        )));
+    }
+    let recipient = req.recipient.trim();
+    if recipient.is_empty() {
+        return Err(bad_request("A recipient is required".to_string()));
+    }
+
+    let row = repositories::notification_channels::upsert_user_notification_channel(
+        &state.db,
+        &user.username,
+        &channel_name(&req.channel),
+        recipient,
+    )
+    .await
+    .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(ChannelAddress {
+        channel: req.channel,
+        recipient: row.recipient,
+    }))
+}
+
+async fn delete_channel(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(channel): Path<String>,
+) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, String)> {
+    let channel = parse_channel(&channel).ok_or_else(|| {
+        (
+            axum::http::StatusCode::BAD_REQUEST,
+            format!("Unknown channel: {}", channel),
+        )
+    })?;
+    let deleted = repositories::notification_channels::delete_user_notification_channel(
+        &state.db,
+        &user.username,
+        &channel_name(&channel),
+    )
+    .await
+    .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    if deleted {
+        Ok(Json(serde_json::json!({ "success": true })))
+    } else {
+        Err((
+            axum::http::StatusCode::NOT_FOUND,
+            format!("No {:?} address set", channel),
+        ))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PreviewRequest {
+    alert: Alert,
+    /// Chat id, email or webhook URL to render into the payload
+    recipient_id: Option<String>,
+}
+
+async fn preview_notification(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<PreviewRequest>,
+) -> Result<Json<Vec<NotificationPreview>>, (axum::http::StatusCode, String)> {
+    let recipient_id = req.recipient_id.unwrap_or(user.username);
+    let notification = NotificationService::notification_from_alert(
+        &req.alert,
+        recipient_id,
+        NotificationChannel::InApp,
+    );
+
+    Ok(Json(state.notifications.preview(&notification)))
+}
@@ -0,0 +1,373 @@
+//! Portfolio routes
+//!
+//! Users record the buys and sells they actually made; holdings are replayed
+//! from those transactions with average-cost basis and stored in the same
+//! database transaction as every change. The summary marks the holdings to the latest close with
+//! unrealized P/L, a market-value-weighted composite score and sector
+//! exposure. A change that would sell more than is held is rejected.
+
+use crate::auth::AuthUser;
+use crate::AppState;
+use axum::{
+    extract::{Path, Query, State},
+    routing::{delete, get, post, put},
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use jejakcuan_core::{
+    build_holdings, value_portfolio, Holding, HoldingQuote, PortfolioSummary, PortfolioTransaction,
+    TradeSide,
+};
+use jejakcuan_db::{
+    repositories, InsertPortfolioHolding, InsertPortfolioTransaction, PortfolioHoldingRow,
+    PortfolioTransactionRow,
+};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use sqlx::PgConnection;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+pub fn portfolio_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/", get(get_portfolio))
+        .route("/holdings", get(list_holdings))
+        .route("/transactions", get(list_transactions))
+        .route("/transactions", post(create_transaction))
+        .route("/transactions/:id", get(get_transaction))
+        .route("/transactions/:id", put(update_transaction))
+        .route("/transactions/:id", delete(delete_transaction))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TransactionQuery {
+    symbol: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TransactionRequest {
+    symbol: String,
+    side: TradeSide,
+    /// Board lots (1 lot = 100 shares)
+    lots: i64,
+    price: Decimal,
+    /// Defaults to the modeled broker fee, levies and sell tax
+    fees: Option<Decimal>,
+    executed_at: Option<DateTime<Utc>>,
+    note: Option<String>,
+}
+
+fn side_str(side: TradeSide) -> &'static str {
+    match side {
+        TradeSide::Buy => "buy",
+        TradeSide::Sell => "sell",
+    }
+}
+
+/// Convert a stored transaction into the holdings model
+fn to_portfolio_transaction(row: &PortfolioTransactionRow) -> PortfolioTransaction {
+    PortfolioTransaction {
+        symbol: row.symbol.clone(),
+        side: if row.side == "sell" {
+            TradeSide::Sell
+        } else {
+            TradeSide::Buy
+        },
+        lots: row.lots,
+        price: row.price,
+        fees: row.fees,
+        executed_at: row.executed_at,
+    }
+}
+
+fn to_holding(row: &PortfolioHoldingRow) -> Holding {
+    Holding {
+        symbol: row.symbol.clone(),
+        shares: row.shares,
+        average_cost: row.average_cost,
+        cost_basis: row.cost_basis,
+        realized_pnl: row.realized_pnl,
+    }
+}
+
+async fn get_portfolio(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<PortfolioSummary>, (axum::http::StatusCode, String)> {
+    let holdings: Vec<Holding> =
+        repositories::portfolio::get_portfolio_holdings(&state.db, &user.username)
+            .await
+            .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+            .iter()
+            .map(to_holding)
+            .collect();
+
+    let mut quotes = BTreeMap::new();
+    for holding in holdings.iter().filter(|h| h.is_open()) {
+        let quote = load_quote(&state, &holding.symbol)
+            .await
+            .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        quotes.insert(holding.symbol.clone(), quote);
+    }
+
+    Ok(Json(value_portfolio(&holdings, &quotes)))
+}
+
+/// Latest close, sector and composite score of a held symbol
+async fn load_quote(state: &AppState, symbol: &str) -> Result<HoldingQuote, sqlx::Error> {
+    let price = repositories::prices::get_latest_price(&state.db, symbol).await?;
+    let stock = repositories::stocks::get_stock_by_symbol(&state.db, symbol).await?;
+    let score = repositories::scores::get_stock_score(&state.db, symbol).await?;
+    Ok(HoldingQuote {
+        price: price.map(|p| p.close),
+        sector: stock.and_then(|s| s.sector),
+        composite_score: score.map(|s| s.composite_score),
+    })
+}
+
+async fn list_holdings(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<PortfolioHoldingRow>>, (axum::http::StatusCode, String)> {
+    repositories::portfolio::get_portfolio_holdings(&state.db, &user.username)
+        .await
+        .map(Json)
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+async fn list_transactions(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<TransactionQuery>,
+) -> Result<Json<Vec<PortfolioTransactionRow>>, (axum::http::StatusCode, String)> {
+    let symbol = query.symbol.map(|s| s.to_uppercase());
+    repositories::portfolio::get_portfolio_transactions(
+        &state.db,
+        &user.username,
+        symbol.as_deref(),
+    )
+    .await
+    .map(Json)
+    .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+async fn get_transaction(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<Json<PortfolioTransactionRow>, (axum::http::StatusCode, String)> {
+    repositories::portfolio::get_portfolio_transaction(&state.db, &user.username, id)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map(Json)
+        .ok_or_else(transaction_not_found)
+}
+
+fn transaction_not_found() -> (axum::http::StatusCode, String) {
+    (
+        axum::http::StatusCode::NOT_FOUND,
+        "Portfolio transaction not found".to_string(),
+    )
+}
+
+/// Validate a request and resolve its defaults
+async fn prepare_transaction(
+    state: &AppState,
+    req: &TransactionRequest,
+) -> Result<PortfolioTransaction, (axum::http::StatusCode, String)> {
+    if req.price <= Decimal::ZERO || req.lots <= 0 {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            "Price and lots must be positive".to_string(),
+        ));
+    }
+    if req.fees.is_some_and(|f| f < Decimal::ZERO) {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            "Fees cannot be negative".to_string(),
+        ));
+    }
+
+    let symbol = req.symbol.to_uppercase();
+    repositories::stocks::get_stock_by_symbol(&state.db, &symbol)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(|| {
+            (
+                axum::http::StatusCode::NOT_FOUND,
+                format!("Stock {} not found", symbol),
+            )
+        })?;
+
+    let mut transaction = PortfolioTransaction {
+        symbol,
+        side: req.side,
+        lots: req.lots,
+        price: req.price,
+        fees: Decimal::ZERO,
+        executed_at: req.executed_at.unwrap_or_else(Utc::now),
+    };
+    transaction.fees = match req.fees {
+        Some(fees) => fees,
+        None => state
+            .config
+            .transaction_costs
+            .trade_cost(req.side, req.price, transaction.shares(), None)
+            .fees
+            .round_dp(0),
+    };
+    Ok(transaction)
+}
+
+/// Replay the user's transactions with `replace` swapped in for transaction
+/// `id` (or appended, or removed when `None`), rejecting an oversell
+async fn replay_with(
+    conn: &mut PgConnection,
+    user_id: &str,
+    id: Option<i32>,
+    replace: Option<&PortfolioTransaction>,
+) -> Result<Vec<Holding>, (axum::http::StatusCode, String)> {
+    let rows = repositories::portfolio::get_portfolio_transactions(conn, user_id, None)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let mut transactions: Vec<PortfolioTransaction> = rows
+        .iter()
+        .filter(|row| Some(row.id) != id)
+        .map(to_portfolio_transaction)
+        .collect();
+    transactions.extend(replace.cloned());
+
+    build_holdings(&transactions).map_err(|e| (axum::http::StatusCode::BAD_REQUEST, e.to_string()))
+}
+
+async fn store_holdings(
+    conn: &mut PgConnection,
+    user_id: &str,
+    holdings: &[Holding],
+) -> Result<(), (axum::http::StatusCode, String)> {
+    let rows: Vec<InsertPortfolioHolding> = holdings
+        .iter()
+        .map(|h| InsertPortfolioHolding {
+            symbol: &h.symbol,
+            shares: h.shares,
+            average_cost: h.average_cost.round_dp(4),
+            cost_basis: h.cost_basis.round_dp(2),
+            realized_pnl: h.realized_pnl.round_dp(2),
+        })
+        .collect();
+    repositories::portfolio::replace_portfolio_holdings(conn, user_id, &rows)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// Open a transaction holding the user's portfolio lock
+///
+/// A mutation replays, writes the transaction and stores the holdings in it,
+/// so concurrent changes cannot replay over each other.
+async fn begin_mutation(
+    state: &AppState,
+    user_id: &str,
+) -> Result<sqlx::Transaction<'static, sqlx::Postgres>, (axum::http::StatusCode, String)> {
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    repositories::portfolio::lock_portfolio(&mut tx, user_id)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(tx)
+}
+
+async fn commit(
+    tx: sqlx::Transaction<'static, sqlx::Postgres>,
+) -> Result<(), (axum::http::StatusCode, String)> {
+    tx.commit()
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+fn to_insert<'a>(
+    user_id: &'a str,
+    transaction: &'a PortfolioTransaction,
+    note: Option<&'a str>,
+) -> InsertPortfolioTransaction<'a> {
+    InsertPortfolioTransaction {
+        user_id,
+        symbol: &transaction.symbol,
+        side: side_str(transaction.side),
+        lots: transaction.lots,
+        price: transaction.price,
+        fees: transaction.fees,
+        executed_at: transaction.executed_at,
+        note,
+    }
+}
+
+async fn create_transaction(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<TransactionRequest>,
+) -> Result<Json<PortfolioTransactionRow>, (axum::http::StatusCode, String)> {
+    let transaction = prepare_transaction(&state, &req).await?;
+    let mut tx = begin_mutation(&state, &user.username).await?;
+    let holdings = replay_with(&mut tx, &user.username, None, Some(&transaction)).await?;
+
+    let row = repositories::portfolio::insert_portfolio_transaction(
+        &mut *tx,
+        &to_insert(&user.username, &transaction, req.note.as_deref()),
+    )
+    .await
+    .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    store_holdings(&mut tx, &user.username, &holdings).await?;
+    commit(tx).await?;
+    Ok(Json(row))
+}
+
+async fn update_transaction(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Json(req): Json<TransactionRequest>,
+) -> Result<Json<PortfolioTransactionRow>, (axum::http::StatusCode, String)> {
+    let transaction = prepare_transaction(&state, &req).await?;
+    let mut tx = begin_mutation(&state, &user.username).await?;
+    let holdings = replay_with(&mut tx, &user.username, Some(id), Some(&transaction)).await?;
+
+    let row = repositories::portfolio::update_portfolio_transaction(
+        &mut *tx,
+        id,
+        &to_insert(&user.username, &transaction, req.note.as_deref()),
+    )
+    .await
+    .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .ok_or_else(transaction_not_found)?;
+
+    store_holdings(&mut tx, &user.username, &holdings).await?;
+    commit(tx).await?;
+    Ok(Json(row))
+}
+
+async fn delete_transaction(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, String)> {
+    let mut tx = begin_mutation(&state, &user.username).await?;
+    // Removing a buy can leave a later sell uncovered
+    let holdings = replay_with(&mut tx, &user.username, Some(id), None).await?;
+
+    let deleted =
+        repositories::portfolio::delete_portfolio_transaction(&mut *tx, &user.username, id)
+            .await
+            .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if !deleted {
+        return Err(transaction_not_found());
+    }
+
+    store_holdings(&mut tx, &user.username, &holdings).await?;
+    commit(tx).await?;
+    Ok(Json(serde_json::json!({ "success": true })))
+}
@@ -0,0 +1,226 @@
+//! Saved screen routes
+//!
+//! Users save named screener filters. Saving stores the screen's current
+//! result set; the alert scheduler then re-runs every screen with alerts
+//! enabled once per session after the data refresh and notifies when a
+//! symbol enters or leaves the results.
+
+use crate::auth::AuthUser;
+use crate::screener::{
+    check_screen_custom_indicators, load_screen_candidates, screen_custom_indicators,
+    screen_symbols,
+};
+use crate::usage::{screen_subject, UsageKind};
+use crate::AppState;
+use axum::{
+    extract::{Path, State},
+    routing::{delete, get, post, put},
+    Json, Router,
+};
+use jejakcuan_core::{diff_screen_results, run_screen, ScreenCandidate, ScreenDiff, ScreenFilter};
+use jejakcuan_db::{repositories, InsertSavedScreen, SavedScreenRow};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+pub fn screen_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/", get(list_screens))
+        .route("/", post(create_screen))
+        .route("/:id", get(get_screen))
+        .route("/:id", put(update_screen))
+        .route("/:id", delete(delete_screen))
+        .route("/:id/run", post(run_saved_screen))
+}
+
+const MAX_SCREEN_NAME_LEN: usize = 100;
+
+#[derive(Debug, Deserialize)]
+pub struct SavedScreenRequest {
+    name: String,
+    filter: ScreenFilter,
+    alerts_enabled: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScreenRunResponse {
+    pub screen: SavedScreenRow,
+    /// Against the result set of the last scheduled run
+    pub diff: ScreenDiff,
+    pub results: Vec<ScreenCandidate>,
+}
+
+fn screen_not_found() -> (axum::http::StatusCode, String) {
+    (
+        axum::http::StatusCode::NOT_FOUND,
+        "Saved screen not found".to_string(),
+    )
+}
+
+/// Check the name and filter; `id` is the screen being updated
+async fn validate_screen(
+    state: &AppState,
+    owner: &str,
+    id: Option<i32>,
+    req: &SavedScreenRequest,
+) -> Result<String, (axum::http::StatusCode, String)> {
+    let name = req.name.trim().to_string();
+    if name.is_empty() || name.len() > MAX_SCREEN_NAME_LEN {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            format!("Name must be 1-{} characters", MAX_SCREEN_NAME_LEN),
+        ));
+    }
+    req.filter
+        .validate()
+        .map_err(|e| (axum::http::StatusCode::BAD_REQUEST, e.to_string()))?;
+    check_screen_custom_indicators(&state.db, owner, &req.filter).await?;
+
+    let taken = repositories::saved_screens::get_saved_screens(&state.db, owner)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .iter()
+        .any(|s| s.name == name && Some(s.id) != id);
+    if taken {
+        return Err((
+            axum::http::StatusCode::CONFLICT,
+            format!("A screen named {} already exists", name),
+        ));
+    }
+    Ok(name)
+}
+
+async fn list_screens(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<SavedScreenRow>>, (axum::http::StatusCode, String)> {
+    repositories::saved_screens::get_saved_screens(&state.db, &user.username)
+        .await
+        .map(Json)
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+async fn get_screen(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<Json<SavedScreenRow>, (axum::http::StatusCode, String)> {
+    repositories::saved_screens::get_saved_screen(&state.db, &user.username, id)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map(Json)
+        .ok_or_else(screen_not_found)
+}
+
+async fn create_screen(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<SavedScreenRequest>,
+) -> Result<Json<SavedScreenRow>, (axum::http::StatusCode, String)> {
+    let name = validate_screen(&state, &user.username, None, &req).await?;
+    let symbols = screen_symbols(&state.db, &user.username, &req.filter)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let filter = serde_json::to_value(&req.filter)
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    repositories::saved_screens::insert_saved_screen(
+        &state.db,
+        &InsertSavedScreen {
+            owner: &user.username,
+            name: &name,
+            filter: &filter,
+            alerts_enabled: req.alerts_enabled.unwrap_or(true),
+        },
+        &symbols,
+    )
+    .await
+    .map(Json)
+    .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// Replace a screen; its result set restarts from the new filter
+async fn update_screen(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Json(req): Json<SavedScreenRequest>,
+) -> Result<Json<SavedScreenRow>, (axum::http::StatusCode, String)> {
+    let name = validate_screen(&state, &user.username, Some(id), &req).await?;
+    let symbols = screen_symbols(&state.db, &user.username, &req.filter)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let filter = serde_json::to_value(&req.filter)
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    repositories::saved_screens::update_saved_screen(
+        &state.db,
+        id,
+        &InsertSavedScreen {
+            owner: &user.username,
+            name: &name,
+            filter: &filter,
+            alerts_enabled: req.alerts_enabled.unwrap_or(true),
+        },
+        &symbols,
+    )
+    .await
+    .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .map(Json)
+    .ok_or_else(screen_not_found)
+}
+
+async fn delete_screen(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, String)> {
+    let deleted = repositories::saved_screens::delete_saved_screen(&state.db, &user.username, id)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if !deleted {
+        return Err(screen_not_found());
+    }
+    Ok(Json(serde_json::json!({ "success": true })))
+}
+
+/// Current results of a saved screen, without touching its stored result set
+async fn run_saved_screen(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<Json<ScreenRunResponse>, (axum::http::StatusCode, String)> {
+    let screen = repositories::saved_screens::get_saved_screen(&state.db, &user.username, id)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(screen_not_found)?;
+    let filter: ScreenFilter = serde_json::from_value(screen.filter.clone()).map_err(|e| {
+        (
+            axum::http::StatusCode::UNPROCESSABLE_ENTITY,
+            format!("Stored filter no longer parses: {}", e),
+        )
+    })?;
+
+    let custom = screen_custom_indicators(&state.db, &user.username, &filter)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let candidates = load_screen_candidates(&state.db, filter.needs_price_history(), &custom)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let results: Vec<ScreenCandidate> = run_screen(&candidates, &filter)
+        .into_iter()
+        .cloned()
+        .collect();
+    let symbols: Vec<String> = results.iter().map(|c| c.symbol.clone()).collect();
+    state.usage.record(
+        UsageKind::ScreenRun,
+        &screen_subject(&filter),
+        None,
+        &user.username,
+    );
+
+    Ok(Json(ScreenRunResponse {
+        diff: diff_screen_results(&screen.last_symbols, &symbols),
+        screen,
+        results,
+    }))
+}
@@ -0,0 +1,188 @@
+//! Scraper staging routes
+//!
+//! Scraped broker/shareholding batches land in staging tables and are only
+//! promoted to the live tables after an admin reviews the diff report.
+
+use crate::auth::AuthUser;
+use crate::AppState;
+use axum::{
+    extract::{Path, Query, State},
+    routing::{get, post},
+    Json, Router,
+};
+use jejakcuan_db::{repositories, StagingBatchRow, StagingDataset, StagingDiffRow};
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+pub fn staging_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/", get(list_batches))
+        .route("/:batch_id", get(get_batch_report))
+        .route("/:batch_id/approve", post(approve_batch))
+        .route("/:batch_id/reject", post(reject_batch))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StagingListQuery {
+    status: Option<String>,
+    limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct ReviewRequest {
+    note: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StagingDiffEntry {
+    pub symbol: String,
+    pub period: chrono::NaiveDate,
+    pub staged_rows: i64,
+    pub live_rows: i64,
+    pub added: i64,
+    pub removed: i64,
+    pub changed: i64,
+    pub staged_total: f64,
+    pub live_total: f64,
+}
+
+impl From<StagingDiffRow> for StagingDiffEntry {
+    fn from(row: StagingDiffRow) -> Self {
+        Self {
+            symbol: row.symbol,
+            period: row.period,
+            staged_rows: row.staged_rows,
+            live_rows: row.live_rows,
+            added: row.added,
+            removed: row.removed,
+            changed: row.changed,
+            staged_total: row.staged_total.to_f64().unwrap_or(0.0),
+            live_total: row.live_total.to_f64().unwrap_or(0.0),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct StagingDiffSummary {
+    pub periods: usize,
+    pub staged_rows: i64,
+    pub live_rows: i64,
+    pub added: i64,
+    pub removed: i64,
+    pub changed: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StagingReportResponse {
+    pub batch: StagingBatchRow,
+    pub summary: StagingDiffSummary,
+    pub diff: Vec<StagingDiffEntry>,
+}
+
+fn parse_dataset(
+    batch: &StagingBatchRow,
+) -> Result<StagingDataset, (axum::http::StatusCode, String)> {
+    StagingDataset::parse(&batch.dataset).ok_or_else(|| {
+        (
+            axum::http::StatusCode::UNPROCESSABLE_ENTITY,
+            format!("Unknown staging dataset: {}", batch.dataset),
+        )
+    })
+}
+
+async fn list_batches(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<StagingListQuery>,
+) -> Result<Json<Vec<StagingBatchRow>>, (axum::http::StatusCode, String)> {
+    let batches = repositories::staging::get_staging_batches(
+        &state.db,
+        query.status.as_deref(),
+        query.limit.unwrap_or(50),
+    )
+    .await
+    .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(batches))
+}
+
+async fn get_batch_report(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(batch_id): Path<i32>,
+) -> Result<Json<StagingReportResponse>, (axum::http::StatusCode, String)> {
+    let batch = repositories::staging::get_staging_batch(&state.db, batch_id)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(|| {
+            (
+                axum::http::StatusCode::NOT_FOUND,
+                format!("Staging batch not found: {}", batch_id),
+            )
+        })?;
+
+    let dataset = parse_dataset(&batch)?;
+    let diff: Vec<StagingDiffEntry> =
+        repositories::staging::get_staging_diff(&state.db, batch_id, dataset)
+            .await
+            .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+            .into_iter()
+            .map(StagingDiffEntry::from)
+            .collect();
+
+    let summary = StagingDiffSummary {
+        periods: diff.len(),
+        staged_rows: diff.iter().map(|d| d.staged_rows).sum(),
+        live_rows: diff.iter().map(|d| d.live_rows).sum(),
+        added: diff.iter().map(|d| d.added).sum(),
+        removed: diff.iter().map(|d| d.removed).sum(),
+        changed: diff.iter().map(|d| d.changed).sum(),
+    };
+
+    Ok(Json(StagingReportResponse {
+        batch,
+        summary,
+        diff,
+    }))
+}
+
+async fn approve_batch(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(batch_id): Path<i32>,
+    body: Option<Json<ReviewRequest>>,
+) -> Result<Json<StagingBatchRow>, (axum::http::StatusCode, String)> {
+    let Json(review) = body.unwrap_or_default();
+
+    repositories::staging::approve_staging_batch(&state.db, batch_id, review.note.as_deref())
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(|| {
+            (
+                axum::http::StatusCode::NOT_FOUND,
+                format!("Pending staging batch not found: {}", batch_id),
+            )
+        })
+        .map(Json)
+}
+
+async fn reject_batch(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(batch_id): Path<i32>,
+    body: Option<Json<ReviewRequest>>,
+) -> Result<Json<StagingBatchRow>, (axum::http::StatusCode, String)> {
+    let Json(review) = body.unwrap_or_default();
+
+    repositories::staging::reject_staging_batch(&state.db, batch_id, review.note.as_deref())
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(|| {
+            (
+                axum::http::StatusCode::NOT_FOUND,
+                format!("Pending staging batch not found: {}", batch_id),
+            )
+        })
+        .map(Json)
+}
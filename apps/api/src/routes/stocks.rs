@@ -1,26 +1,50 @@
 //! Stock-related routes
+//!
+//! Besides per-stock data, `POST /screen` runs a screener filter tree over
+//! every active stock's latest scores, financials and indicators.
 
+use crate::analysis_cache::AnalysisCache;
 use crate::auth::AuthUser;
+use crate::demo::{data_cutoff, DemoAccess};
+use crate::fundamentals;
+use crate::news;
+use crate::price_history;
 use crate::routes::jobs::Job;
+use crate::usage::{screen_subject, UsageKind};
 use crate::AppState;
 use axum::{
     extract::{Path, Query, State},
     routing::{get, post},
-    Json, Router,
+    Extension, Json, Router,
 };
-use chrono::{Duration, Utc};
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime, Utc};
 use futures_util::StreamExt;
+use jejakcuan_cache::{CacheKeys, MarketAwareTtl};
 use jejakcuan_core::{
-    calculate_composite_score, FundamentalInput, FundamentalScoreEngine, ScoreWeights,
-    TechnicalScoreEngine, TechnicalScoreInput,
+    calculate_composite_score, run_screen, FundamentalInput, ScoreWeights, ScreenCandidate,
+    ScreenFilter, StalenessPolicy, TechnicalScoreBreakdown, TechnicalScoreEngine,
+    TechnicalScoreInput,
+};
+use jejakcuan_db::repositories::broker_summary::BrokerFlowAggregateRow;
+use jejakcuan_db::repositories::order_flow::{self, OrderFlowObservation};
+use jejakcuan_db::repositories::scores::ScoreInputWindows;
+use jejakcuan_db::{
+    repositories, EarningsCalendarRow, FinancialsVersionRow, FundamentalScoreHistoryRow,
+    OrderFlowDailyRow, ScoreInputsRow, StockNewsRow, StockPriceRow, StockRow, StockScoreRow,
+};
+use jejakcuan_fundamental::{
+    calculate_sector_averages, compare_to_peers, SectorAverages, ValuationRatios,
+};
+use jejakcuan_technical::{
+    calculate_ema20, calculate_ema50, calculate_macd, calculate_ofi_zscore, calculate_rsi14,
+    daily_order_flow, recent_candlestick_patterns, session_date, OhlcvBar, OFI_ZSCORE_PERIOD,
 };
-use jejakcuan_db::{repositories, StockPriceRow, StockRow, StockScoreRow};
-use jejakcuan_technical::{calculate_ema20, calculate_ema50, calculate_macd, calculate_rsi14};
 use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
 use std::sync::Arc;
 
 pub fn stock_routes() -> Router<Arc<AppState>> {
@@ -28,10 +52,22 @@ pub fn stock_routes() -> Router<Arc<AppState>> {
         .route("/", get(list_stocks))
         .route("/scores/top", get(get_top_scores))
         .route("/scores/recompute", post(recompute_scores))
+        .route(
+            "/scores/fundamental-history/backfill",
+            post(backfill_fundamental_history),
+        )
+        .route("/screen", post(screen_stocks))
         .route("/:symbol", get(get_stock))
         .route("/:symbol/prices", get(get_stock_prices))
         .route("/:symbol/score", get(get_stock_score))
+        .route("/:symbol/score/history", get(get_stock_score_history))
+        .route("/:symbol/news", get(get_stock_news))
+        .route("/:symbol/earnings", get(get_stock_earnings))
         .route("/:symbol/fundamentals", get(get_stock_fundamentals))
+        .route(
+            "/:symbol/fundamentals/versions",
+            get(get_stock_fundamentals_versions),
+        )
         .route("/:symbol/freshness", get(get_stock_freshness))
         .route("/:symbol/refresh", post(refresh_stock_all))
         .route("/:symbol/refresh/:source_type", post(refresh_stock_source))
@@ -39,6 +75,11 @@ pub fn stock_routes() -> Router<Arc<AppState>> {
 
 const SCORE_STALE_HOURS: i64 = 24;
 
+/// Job source id for the fundamental score history backfill
+const FUNDAMENTAL_HISTORY_JOB: &str = "fundamental_score_history";
+const DEFAULT_HISTORY_QUARTERS: u32 = 12;
+const MAX_HISTORY_QUARTERS: u32 = 40;
+
 const SYARIAH_BANK_ALLOWLIST: &[&str] = &["BRIS", "BTPS", "PNBS"];
 
 fn is_excluded_non_syariah_bank(stock: &StockRow) -> bool {
@@ -116,8 +157,65 @@ async fn list_stocks(
     }))
 }
 
+const DEFAULT_SCREEN_LIMIT: usize = 100;
+
+#[derive(Debug, Deserialize)]
+pub struct ScreenRequest {
+    filter: ScreenFilter,
+    /// Maximum results (default 100)
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScreenResponse {
+    /// Active stocks evaluated
+    pub evaluated: usize,
+    /// Stocks passing the filter, before `limit`
+    pub matched: usize,
+    /// Best composite score first
+    pub results: Vec<ScreenCandidate>,
+}
+
+/// Stocks passing a screener filter tree
+async fn screen_stocks(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<ScreenRequest>,
+) -> Result<Json<ScreenResponse>, (axum::http::StatusCode, String)> {
+    req.filter
+        .validate()
+        .map_err(|e| (axum::http::StatusCode::BAD_REQUEST, e.to_string()))?;
+    let custom =
+        crate::screener::check_screen_custom_indicators(&state.db, &user.username, &req.filter)
+            .await?;
+    let candidates = crate::screener::load_screen_candidates(
+        &state.db,
+        req.filter.needs_price_history(),
+        &custom,
+    )
+    .await
+    .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let matched = run_screen(&candidates, &req.filter);
+    state.usage.record(
+        UsageKind::ScreenRun,
+        &screen_subject(&req.filter),
+        None,
+        &user.username,
+    );
+
+    Ok(Json(ScreenResponse {
+        evaluated: candidates.len(),
+        matched: matched.len(),
+        results: matched
+            .into_iter()
+            .take(req.limit.unwrap_or(DEFAULT_SCREEN_LIMIT))
+            .cloned()
+            .collect(),
+    }))
+}
+
 async fn get_stock(
-    _user: AuthUser,
+    user: AuthUser,
     State(state): State<Arc<AppState>>,
     Path(symbol): Path<String>,
 ) -> Result<Json<StockRow>, (axum::http::StatusCode, String)> {
@@ -134,15 +232,20 @@ async fn get_stock(
 
     tracing::debug!("Stock query result: {:?}", stock.is_some());
 
-    stock
-        .ok_or_else(|| {
-            tracing::debug!("Stock not found: {}", upper_symbol);
-            (
-                axum::http::StatusCode::NOT_FOUND,
-                format!("Stock not found: {}", upper_symbol),
-            )
-        })
-        .map(Json)
+    let stock = stock.ok_or_else(|| {
+        tracing::debug!("Stock not found: {}", upper_symbol);
+        (
+            axum::http::StatusCode::NOT_FOUND,
+            format!("Stock not found: {}", upper_symbol),
+        )
+    })?;
+    state.usage.record(
+        UsageKind::SymbolViewed,
+        &upper_symbol,
+        Some(&upper_symbol),
+        &user.username,
+    );
+    Ok(Json(stock))
 }
 
 #[derive(Debug, Deserialize)]
@@ -155,10 +258,11 @@ async fn get_stock_prices(
     State(state): State<Arc<AppState>>,
     Path(symbol): Path<String>,
     Query(query): Query<PriceHistoryQuery>,
+    demo: Option<Extension<DemoAccess>>,
 ) -> Result<Json<Vec<StockPriceRow>>, (axum::http::StatusCode, String)> {
     let days = query.days.unwrap_or(30);
-    let from = chrono::Utc::now() - chrono::Duration::days(days as i64);
-    let to = chrono::Utc::now();
+    let to = data_cutoff(demo.map(|Extension(d)| d));
+    let from = to - chrono::Duration::days(days as i64);
 
     let prices =
         repositories::prices::get_price_history(&state.db, &symbol.to_uppercase(), from, to)
@@ -198,9 +302,13 @@ async fn get_stock_score(
     }
 
     // Compute and persist a fresh score snapshot if missing or stale
-    let inserted = compute_and_insert_score(&state.db, &upper_symbol)
-        .await
-        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let inserted = compute_and_insert_score(
+        &state.db,
+        &upper_symbol,
+        state.config.sector_relative_technical,
+    )
+    .await
+    .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
     Ok(Json(Some(inserted)))
 }
@@ -245,110 +353,856 @@ async fn get_top_scores(
     Ok(Json(filtered))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct RecomputeScoresQuery {
+    /// Recompute every symbol, even with unchanged inputs
+    #[serde(default)]
+    pub force: bool,
+}
+
 #[derive(Debug, Serialize)]
 pub struct RecomputeScoresResponse {
     pub computed: usize,
+    /// Inputs unchanged since the latest score
     pub skipped: usize,
     pub errors: usize,
+    /// Watchlist and top-liquidity symbols, processed before the rest
+    pub prioritized: usize,
+    /// Technical scores rescored against sector peers
+    pub sector_normalized: usize,
+}
+
+/// Days of prices used to rank symbols by liquidity
+const LIQUIDITY_LOOKBACK_DAYS: i64 = 20;
+
+/// Fingerprint of a symbol's score inputs at `now`
+///
+/// FNV-1a over the input timestamps and checksums, so it stays stable across
+/// builds. The days of staleness decay of the broker and financials inputs
+/// are part of it, so a score keeps decaying while its feeds are silent, and
+/// so is the age of the latest headline while any is within the sentiment
+/// window, since older headlines weigh less. Broker rows leaving the window,
+/// re-scored headlines and a new sector change it too.
+fn input_hash(inputs: &ScoreInputsRow, now: chrono::DateTime<Utc>) -> String {
+    let stamp = |t: Option<chrono::DateTime<Utc>>| {
+        t.map(|t| t.timestamp_micros().to_string())
+            .unwrap_or_default()
+    };
+    let decay = |policy: StalenessPolicy, age_days: Option<i64>| {
+        age_days
+            .map(|age| policy.decay_days(age).to_string())
+            .unwrap_or_default()
+    };
+    let news_age = inputs
+        .news_digest
+        .as_ref()
+        .and(inputs.news_time)
+        .map(|t| (now - t).num_days().to_string())
+        .unwrap_or_default();
+    let text = [
+        stamp(inputs.price_time),
+        stamp(inputs.broker_time),
+        stamp(inputs.financials_time),
+        stamp(inputs.news_time),
+        decay(
+            StalenessPolicy::BROKER_FLOW,
+            inputs.broker_time.map(|t| (now - t).num_days()),
+        ),
+        decay(
+            StalenessPolicy::FINANCIALS,
+            inputs
+                .financials_report_date
+                .map(|d| (now.date_naive() - d).num_days()),
+        ),
+        inputs.news_digest.clone().unwrap_or_default(),
+        news_age,
+        stamp(inputs.broker_window_start),
+        inputs.sector.clone().unwrap_or_default(),
+        inputs.subsector.clone().unwrap_or_default(),
+    ]
+    .join("|");
+    format!("{:016x}", fnv1a(text.as_bytes()))
+}
+
+/// Windows the score inputs are read over at `now`
+fn score_input_windows(now: chrono::DateTime<Utc>) -> ScoreInputWindows {
+    ScoreInputWindows {
+        as_of: now.date_naive(),
+        news_since: now - Duration::days(news::SENTIMENT_LOOKBACK_DAYS),
+        broker_since: now - Duration::days(SCORE_BROKER_DAYS),
+    }
+}
+
+/// 64-bit FNV-1a of `bytes`
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325u64, |h, b| {
+        (h ^ u64::from(*b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Fingerprint of a price history: its bar count and a checksum of every bar
+///
+/// Prices are normalized first, so `9000.00` and `9000` checksum the same.
+fn history_digest(prices: &[StockPriceRow]) -> String {
+    let mut text = String::with_capacity(prices.len() * 64);
+    for p in prices {
+        let _ = write!(
+            text,
+            "{}|{}|{}|{}|{}|{};",
+            p.time.timestamp_micros(),
+            p.open.normalize(),
+            p.high.normalize(),
+            p.low.normalize(),
+            p.close.normalize(),
+            p.volume
+        );
+    }
+    format!("{}-{:016x}", prices.len(), fnv1a(text.as_bytes()))
 }
 
+/// Symbols whose inputs changed since their latest score, or never scored
+async fn symbols_with_new_inputs(
+    pool: &sqlx::PgPool,
+    symbols: Vec<String>,
+) -> Result<Vec<String>, sqlx::Error> {
+    let now = Utc::now();
+    let current: HashMap<String, String> =
+        repositories::scores::get_score_inputs(pool, &symbols, score_input_windows(now))
+            .await?
+            .into_iter()
+            .map(|inputs| (inputs.symbol.clone(), input_hash(&inputs, now)))
+            .collect();
+    let stored: HashMap<String, Option<String>> =
+        repositories::scores::get_latest_input_hashes(pool, &symbols)
+            .await?
+            .into_iter()
+            .collect();
+    Ok(symbols
+        .into_iter()
+        .filter(|symbol| {
+            let stored = stored.get(symbol).cloned().flatten();
+            stored.is_none() || stored.as_ref() != current.get(symbol)
+        })
+        .collect())
+}
+
+/// Recompute scores of symbols whose inputs changed since their last score
+///
+/// `force` recomputes every symbol, e.g. after a scoring change.
 async fn recompute_scores(
     _user: AuthUser,
     State(state): State<Arc<AppState>>,
+    Query(query): Query<RecomputeScoresQuery>,
 ) -> Result<Json<RecomputeScoresResponse>, (axum::http::StatusCode, String)> {
     let stocks = repositories::stocks::get_all_stocks(&state.db)
         .await
         .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
+    let watchlist: HashSet<String> = repositories::watchlist::get_watchlist(&state.db)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .into_iter()
+        .map(|w| w.symbol)
+        .collect();
+    let by_liquidity = repositories::prices::get_symbols_by_traded_value(
+        &state.db,
+        Utc::now() - Duration::days(LIQUIDITY_LOOKBACK_DAYS),
+    )
+    .await
+    .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let symbols: Vec<String> = stocks.into_iter().map(|s| s.symbol).collect();
+    let total = symbols.len();
+    let symbols = if query.force {
+        symbols
+    } else {
+        symbols_with_new_inputs(&state.db, symbols)
+            .await
+            .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    };
+    let skipped = total - symbols.len();
+    let (priority, tail) = prioritize_symbols(symbols, &watchlist, &by_liquidity);
+    let prioritized = priority.len();
+
+    // The tail starts only once every priority symbol is done
+    let mut results = recompute_batch(&state, priority).await;
+    results.extend(recompute_batch(&state, tail).await);
+
+    let errors = results.iter().filter(|r| r.is_err()).count();
+    let computed = results.len() - errors;
+
+    let sector_normalized = if state.config.sector_relative_technical {
+        normalize_technical_by_sector(&state.db, None)
+            .await
+            .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    } else {
+        0
+    };
+
+    Ok(Json(RecomputeScoresResponse {
+        computed,
+        skipped,
+        errors,
+        prioritized,
+        sector_normalized,
+    }))
+}
+
+/// Rescore the latest technical scores on sub-scores normalized within each
+/// sector, updating the composite with them
+///
+/// Every latest snapshot serves as a peer, but with `only` set just that
+/// symbol's snapshot is updated. Returns the number of snapshots whose
+/// scores changed.
+async fn normalize_technical_by_sector(
+    pool: &sqlx::PgPool,
+    only: Option<&str>,
+) -> Result<usize, sqlx::Error> {
+    let sectors: HashMap<String, Option<String>> = repositories::stocks::get_all_stocks(pool)
+        .await?
+        .into_iter()
+        .map(|s| (s.symbol, s.sector))
+        .collect();
+    let (rows, mut breakdowns): (Vec<StockScoreRow>, Vec<TechnicalScoreBreakdown>) =
+        repositories::scores::get_latest_scores(pool, i32::MAX)
+            .await?
+            .into_iter()
+            .filter_map(|row| {
+                let breakdown = serde_json::from_value(row.technical_breakdown.clone()?).ok()?;
+                Some((row, breakdown))
+            })
+            .unzip();
+    let row_sectors: Vec<Option<String>> = rows
+        .iter()
+        .map(|row| sectors.get(&row.symbol).cloned().flatten())
+        .collect();
+    TechnicalScoreEngine::new().normalize_by_sector(&mut breakdowns, &row_sectors);
+
+    let weights = ScoreWeights::default();
+    let mut updated = 0;
+    for (row, breakdown) in rows.iter().zip(&breakdowns) {
+        if only.is_some_and(|symbol| symbol != row.symbol)
+            || breakdown.total_score == row.technical_score
+        {
+            continue;
+        }
+        let composite = calculate_composite_score(
+            breakdown.total_score.to_f64().unwrap_or(50.0),
+            row.fundamental_score.to_f64().unwrap_or(50.0),
+            row.sentiment_score.to_f64().unwrap_or(50.0),
+            row.ml_score.to_f64().unwrap_or(50.0),
+            &weights,
+        );
+        repositories::scores::update_technical_score(
+            pool,
+            &row.symbol,
+            row.time,
+            breakdown.total_score,
+            Decimal::from_f64(composite).unwrap_or(dec!(50)),
+            serde_json::to_value(breakdown).ok(),
+        )
+        .await?;
+        updated += 1;
+    }
+    Ok(updated)
+}
+
+/// Split symbols into those on the watchlist or in the top liquidity decile,
+/// and the rest
+///
+/// `by_liquidity` is ordered most liquid first. Priority symbols keep
+/// liquidity order, watchlist symbols without recent prices go last among them.
+fn prioritize_symbols(
+    symbols: Vec<String>,
+    watchlist: &HashSet<String>,
+    by_liquidity: &[String],
+) -> (Vec<String>, Vec<String>) {
+    let top_decile = by_liquidity.len().div_ceil(10);
+    let rank: HashMap<&str, usize> = by_liquidity
+        .iter()
+        .enumerate()
+        .map(|(i, s)| (s.as_str(), i))
+        .collect();
+
+    let (mut priority, tail): (Vec<String>, Vec<String>) =
+        symbols.into_iter().partition(|symbol| {
+            watchlist.contains(symbol) || rank.get(symbol.as_str()).is_some_and(|&r| r < top_decile)
+        });
+    priority.sort_by_key(|s| rank.get(s.as_str()).copied().unwrap_or(usize::MAX));
+
+    (priority, tail)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ScoreHistoryQuery {
+    /// Defaults to three years before `to`
+    pub from: Option<NaiveDate>,
+    /// Defaults to today
+    pub to: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScoreHistoryResponse {
+    pub symbol: String,
+    /// Composite score snapshots, oldest first
+    pub scores: Vec<StockScoreRow>,
+    /// Point-in-time fundamental scores per quarter end, oldest first
+    pub fundamentals: Vec<FundamentalScoreHistoryRow>,
+}
+
+async fn get_stock_score_history(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(symbol): Path<String>,
+    Query(query): Query<ScoreHistoryQuery>,
+) -> Result<Json<ScoreHistoryResponse>, (axum::http::StatusCode, String)> {
+    let upper_symbol = symbol.to_uppercase();
+    let to = query.to.unwrap_or_else(|| Utc::now().date_naive());
+    let from = query.from.unwrap_or(to - Duration::days(3 * 365));
+    if from > to {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            "from must not be after to".to_string(),
+        ));
+    }
+
+    let scores = repositories::scores::get_score_history(
+        &state.db,
+        &upper_symbol,
+        from.and_time(NaiveTime::MIN).and_utc(),
+        (to + Duration::days(1)).and_time(NaiveTime::MIN).and_utc(),
+    )
+    .await
+    .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let fundamentals =
+        repositories::scores::get_fundamental_score_history(&state.db, &upper_symbol, from, to)
+            .await
+            .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(ScoreHistoryResponse {
+        symbol: upper_symbol,
+        scores,
+        fundamentals,
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StockNewsQuery {
+    /// Number of headlines (default 20, max 100)
+    pub limit: Option<i64>,
+}
+
+async fn get_stock_news(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(symbol): Path<String>,
+    Query(query): Query<StockNewsQuery>,
+) -> Result<Json<Vec<StockNewsRow>>, (axum::http::StatusCode, String)> {
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+    let news = repositories::news::get_recent_news(&state.db, &symbol.to_uppercase(), limit)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Json(news))
+}
+
+/// Financial reports of the past year and those expected next
+async fn get_stock_earnings(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(symbol): Path<String>,
+) -> Result<Json<Vec<EarningsCalendarRow>>, (axum::http::StatusCode, String)> {
+    let since = session_date(Utc::now()) - Duration::days(365);
+    let reports =
+        repositories::earnings::get_stock_earnings(&state.db, &symbol.to_uppercase(), since)
+            .await
+            .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Json(reports))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FundamentalHistoryBackfillRequest {
+    /// Number of past quarter ends to score (default 12)
+    pub quarters: Option<u32>,
+    /// Limit to these symbols; all active stocks when omitted
+    pub symbols: Option<Vec<String>>,
+}
+
+async fn backfill_fundamental_history(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<FundamentalHistoryBackfillRequest>,
+) -> Result<Json<Job>, (axum::http::StatusCode, String)> {
+    let quarters = req.quarters.unwrap_or(DEFAULT_HISTORY_QUARTERS);
+    if quarters == 0 || quarters > MAX_HISTORY_QUARTERS {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            format!("quarters must be between 1 and {}", MAX_HISTORY_QUARTERS),
+        ));
+    }
+
+    if let Some(job) = state
+        .job_manager
+        .is_source_running(FUNDAMENTAL_HISTORY_JOB)
+        .await
+    {
+        return Err((
+            axum::http::StatusCode::CONFLICT,
+            format!("Backfill already running (job {})", job.id),
+        ));
+    }
+
+    let symbols: Vec<String> = match req.symbols {
+        Some(symbols) => symbols.iter().map(|s| s.trim().to_uppercase()).collect(),
+        None => repositories::stocks::get_all_stocks(&state.db)
+            .await
+            .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+            .into_iter()
+            .map(|s| s.symbol)
+            .collect(),
+    };
+
+    let as_of_dates = quarter_ends(Utc::now().date_naive(), quarters as usize);
     let pool = state.db.clone();
-    let now = Utc::now();
+    let job = state
+        .job_manager
+        .spawn_task(
+            FUNDAMENTAL_HISTORY_JOB.to_string(),
+            "Fundamental score history".to_string(),
+            format!(
+                "recompute fundamental scores for {} stocks over {} quarters",
+                symbols.len(),
+                quarters
+            ),
+            async move { run_fundamental_history_backfill(pool, symbols, as_of_dates).await },
+        )
+        .await;
+
+    Ok(Json(job))
+}
 
-    let results = futures_util::stream::iter(stocks.into_iter().map(|stock| {
+/// Recompute point-in-time fundamental scores at each date for every symbol
+async fn run_fundamental_history_backfill(
+    pool: sqlx::PgPool,
+    symbols: Vec<String>,
+    as_of_dates: Vec<NaiveDate>,
+) -> Result<String, String> {
+    let stock_count = symbols.len();
+    let results = futures_util::stream::iter(symbols.into_iter().map(|symbol| {
         let pool = pool.clone();
+        let as_of_dates = as_of_dates.clone();
         async move {
-            let existing = repositories::scores::get_stock_score(&pool, &stock.symbol).await?;
-            if let Some(score) = existing {
-                if now - score.time < Duration::hours(SCORE_STALE_HOURS) {
-                    return Ok::<_, sqlx::Error>(None);
+            let mut computed = 0usize;
+            let mut skipped = 0usize;
+            for as_of in as_of_dates {
+                if record_fundamental_score_as_of(&pool, &symbol, as_of).await? {
+                    computed += 1;
+                } else {
+                    skipped += 1;
                 }
             }
-
-            let inserted = compute_and_insert_score(&pool, &stock.symbol).await?;
-            Ok::<_, sqlx::Error>(Some(inserted))
+            Ok::<_, sqlx::Error>((computed, skipped))
         }
     }))
     .buffer_unordered(8)
-    .collect::<Vec<Result<Option<StockScoreRow>, sqlx::Error>>>()
+    .collect::<Vec<_>>()
     .await;
 
     let mut computed = 0usize;
     let mut skipped = 0usize;
-    let mut errors = 0usize;
-
+    let mut errors = Vec::new();
     for r in results {
         match r {
-            Ok(Some(_)) => computed += 1,
-            Ok(None) => skipped += 1,
-            Err(_) => errors += 1,
+            Ok((c, s)) => {
+                computed += c;
+                skipped += s;
+            }
+            Err(e) => errors.push(e.to_string()),
         }
     }
 
-    Ok(Json(RecomputeScoresResponse {
+    let summary = format!(
+        "{} stocks: {} quarterly scores computed, {} skipped without published financials, {} stocks failed",
+        stock_count,
         computed,
         skipped,
-        errors,
-    }))
+        errors.len()
+    );
+
+    if errors.len() == stock_count && stock_count > 0 {
+        Err(format!("{}\n{}", summary, errors.join("\n")))
+    } else {
+        Ok(summary)
+    }
 }
 
-async fn compute_and_insert_score(
+/// Score the financials published by `as_of` and store the result
+///
+/// Returns false when nothing had been published yet.
+async fn record_fundamental_score_as_of(
     pool: &sqlx::PgPool,
     symbol: &str,
-) -> Result<StockScoreRow, sqlx::Error> {
-    let now = Utc::now();
+    as_of: NaiveDate,
+) -> Result<bool, sqlx::Error> {
+    let Some(financials) = repositories::stocks::get_financials_as_of(pool, symbol, as_of).await?
+    else {
+        return Ok(false);
+    };
 
-    // Prices: use a sufficiently long lookback to compute EMA50/RSI/MACD.
-    let from = now - Duration::days(200);
-    let prices = repositories::prices::get_price_history(pool, symbol, from, now).await?;
+    let mut input = fundamentals::fundamental_input(&financials);
+    fundamentals::fill_derived_ratios(pool, symbol, as_of, &mut input).await?;
+    let breakdown = fundamentals::fundamental_engine(pool, symbol)
+        .await?
+        .calculate(&input);
+    repositories::scores::upsert_fundamental_score_history(
+        pool,
+        &repositories::scores::InsertFundamentalScoreHistory {
+            symbol,
+            as_of,
+            period_end: financials.period_end,
+            version: financials.version,
+            report_date: financials.report_date,
+            fundamental_score: breakdown.total_score,
+            fundamental_breakdown: serde_json::to_value(&breakdown).ok(),
+        },
+    )
+    .await?;
 
-    let close_prices: Vec<Decimal> = prices.iter().map(|p| p.close).collect();
-    let volumes: Vec<i64> = prices.iter().map(|p| p.volume).collect();
-    let highs: Vec<Decimal> = prices.iter().map(|p| p.high).collect();
-    let lows: Vec<Decimal> = prices.iter().map(|p| p.low).collect();
+    Ok(true)
+}
 
-    let current_price = close_prices.last().copied().unwrap_or(Decimal::ZERO);
+/// The last `count` calendar quarter ends on or before `date`, oldest first
+fn quarter_ends(date: NaiveDate, count: usize) -> Vec<NaiveDate> {
+    let mut year = date.year();
+    let mut quarter = (date.month() - 1) / 3 + 1;
+    if quarter_end(year, quarter) > date {
+        (year, quarter) = previous_quarter(year, quarter);
+    }
 
-    let ema20 = calculate_ema20(&close_prices)
-        .ok()
-        .and_then(|v| v.last().copied());
-    let ema50 = calculate_ema50(&close_prices)
-        .ok()
-        .and_then(|v| v.last().copied());
+    let mut ends = Vec::with_capacity(count);
+    for _ in 0..count {
+        ends.push(quarter_end(year, quarter));
+        (year, quarter) = previous_quarter(year, quarter);
+    }
+    ends.reverse();
+    ends
+}
 
-    let rsi = calculate_rsi14(&close_prices)
-        .ok()
-        .and_then(|v| v.last().copied());
-    let macd_histogram = calculate_macd(&close_prices)
-        .ok()
-        .and_then(|m| m.histogram.last().copied());
+fn quarter_end(year: i32, quarter: u32) -> NaiveDate {
+    let (next_year, next_month) = if quarter == 4 {
+        (year + 1, 1)
+    } else {
+        (year, quarter * 3 + 1)
+    };
+    NaiveDate::from_ymd_opt(next_year, next_month, 1)
+        .and_then(|d| d.pred_opt())
+        .expect("valid quarter end")
+}
 
-    // Broker flow (last 5 days) used as a key technical input.
-    let broker_from = now - Duration::days(5);
-    let broker_to = now;
-    let aggregates = repositories::broker_summary::get_broker_flow_aggregates(
-        pool,
-        symbol,
-        broker_from,
-        broker_to,
-    )
-    .await
-    .unwrap_or_default();
+fn previous_quarter(year: i32, quarter: u32) -> (i32, u32) {
+    if quarter == 1 {
+        (year - 1, 4)
+    } else {
+        (year, quarter - 1)
+    }
+}
+
+/// Sessions in which a completed candlestick pattern still counts as a signal
+const CANDLESTICK_LOOKBACK: usize = 3;
+
+/// Store the daily OBI/OFI proxies of the price history and return the
+/// latest OFI z-score
+///
+/// Days with order book depth keep their depth OBI/OFI in place of the
+/// proxies. Only days not stored yet, stored days still missing a z-score
+/// and the latest session, whose bar may still change, are written. A failed
+/// write is logged; the score still uses the computed values.
+async fn persist_order_flow(
+    pool: &sqlx::PgPool,
+    symbol: &str,
+    prices: &[StockPriceRow],
+    bars: &[OhlcvBar],
+) -> Option<Decimal> {
+    let stored: HashMap<NaiveDate, OrderFlowDailyRow> = match prices.first() {
+        Some(first) => {
+            repositories::order_flow::get_order_flow_history(pool, symbol, session_date(first.time))
+                .await
+                .unwrap_or_default()
+                .into_iter()
+                .map(|row| (row.trade_date, row))
+                .collect()
+        }
+        None => HashMap::new(),
+    };
+
+    let flow: Vec<(NaiveDate, Decimal, Decimal, &'static str)> = prices
+        .iter()
+        .zip(daily_order_flow(bars))
+        .map(|(price, proxy)| {
+            let day = session_date(price.time);
+            match stored.get(&day) {
+                Some(row) if row.source == order_flow::SOURCE_DEPTH => {
+                    (day, row.obi, row.ofi, order_flow::SOURCE_DEPTH)
+                }
+                _ => (
+                    day,
+                    proxy.obi.round_dp(4),
+                    proxy.ofi.round_dp(2),
+                    order_flow::SOURCE_PROXY,
+                ),
+            }
+        })
+        .collect();
+    let ofi: Vec<Decimal> = flow.iter().map(|f| f.2).collect();
+    let zscore = calculate_ofi_zscore(&ofi, OFI_ZSCORE_PERIOD).ok();
+
+    let last = flow.len().saturating_sub(1);
+    let observations: Vec<OrderFlowObservation> = flow
+        .into_iter()
+        .enumerate()
+        .map(|(i, (trade_date, obi, ofi, source))| OrderFlowObservation {
+            trade_date,
+            obi,
+            ofi,
+            ofi_zscore: zscore.as_ref().and_then(|z| z.get(i)),
+            source,
+        })
+        .enumerate()
+        .filter(
+            |(i, observation)| match stored.get(&observation.trade_date) {
+                Some(row) => {
+                    *i == last || (row.ofi_zscore.is_none() && observation.ofi_zscore.is_some())
+                }
+                None => true,
+            },
+        )
+        .map(|(_, observation)| observation)
+        .collect();
+    if let Err(e) = repositories::order_flow::upsert_order_flow(pool, symbol, &observations).await {
+        tracing::warn!("Failed to store order flow for {}: {}", symbol, e);
+    }
+
+    zscore.and_then(|z| z.last_valid())
+}
+
+/// Indicators of a price history the technical score reads
+///
+/// Cached in Redis between recomputes that see the same latest bar.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct PriceIndicators {
+    pub ema20: Option<Decimal>,
+    pub ema50: Option<Decimal>,
+    pub rsi: Option<Decimal>,
+    pub macd_histogram: Option<Decimal>,
+    /// Candlestick patterns completed in the last few sessions
+    pub candlestick_patterns: Vec<String>,
+}
+
+impl PriceIndicators {
+    pub(crate) fn calculate(bars: &[OhlcvBar]) -> Self {
+        let close_prices: Vec<Decimal> = bars.iter().map(|b| b.close).collect();
+        Self {
+            ema20: calculate_ema20(&close_prices)
+                .ok()
+                .and_then(|v| v.last_valid()),
+            ema50: calculate_ema50(&close_prices)
+                .ok()
+                .and_then(|v| v.last_valid()),
+            rsi: calculate_rsi14(&close_prices)
+                .ok()
+                .and_then(|v| v.last_valid()),
+            macd_histogram: calculate_macd(&close_prices)
+                .ok()
+                .and_then(|m| m.histogram.last_valid()),
+            candlestick_patterns: recent_candlestick_patterns(bars, CANDLESTICK_LOOKBACK)
+                .iter()
+                .map(|d| d.signal())
+                .collect(),
+        }
+    }
+}
+
+/// Technical score inputs derived from `bars` alone
+///
+/// Order flow and broker inputs are left unset for the caller to fill in.
+pub(crate) fn price_technical_input(bars: &[OhlcvBar]) -> TechnicalScoreInput {
+    technical_input_with(bars, PriceIndicators::calculate(bars))
+}
+
+/// [`price_technical_input`] with the indicators already calculated
+fn technical_input_with(bars: &[OhlcvBar], indicators: PriceIndicators) -> TechnicalScoreInput {
+    let close_prices: Vec<Decimal> = bars.iter().map(|b| b.close).collect();
+    TechnicalScoreInput {
+        current_price: close_prices.last().copied().unwrap_or(Decimal::ZERO),
+        volumes: bars.iter().map(|b| b.volume).collect(),
+        highs: bars.iter().map(|b| b.high).collect(),
+        lows: bars.iter().map(|b| b.low).collect(),
+        prices: close_prices,
+        ema20: indicators.ema20,
+        ema50: indicators.ema50,
+        rsi: indicators.rsi,
+        macd_histogram: indicators.macd_histogram,
+        candlestick_patterns: indicators.candlestick_patterns,
+        ..Default::default()
+    }
+}
+
+/// Calendar days of prices a score reads, enough for EMA50/RSI/MACD
+const SCORE_PRICE_DAYS: i64 = 200;
+
+/// Calendar days of broker flow a score reads
+const SCORE_BROKER_DAYS: i64 = 5;
+
+/// Symbols whose inputs are fetched together while recomputing scores
+const SCORE_FETCH_CHUNK: usize = 200;
+
+/// Scores computed at once
+const SCORE_CONCURRENCY: usize = 8;
+
+/// Score inputs of a set of symbols, fetched with one query per source
+#[derive(Debug, Default)]
+struct ScoreBatch {
+    inputs: HashMap<String, ScoreInputsRow>,
+    prices: HashMap<String, Vec<StockPriceRow>>,
+    broker_flow: HashMap<String, Vec<BrokerFlowAggregateRow>>,
+    financials: HashMap<String, FinancialsVersionRow>,
+    stocks: HashMap<String, StockRow>,
+    sector_averages: HashMap<String, SectorAverages>,
+}
+
+/// Prefetched inputs of one symbol
+#[derive(Debug, Default)]
+struct SymbolScoreInputs {
+    inputs: Option<ScoreInputsRow>,
+    prices: Vec<StockPriceRow>,
+    broker_flow: Vec<BrokerFlowAggregateRow>,
+    financials: Option<FinancialsVersionRow>,
+    stock: Option<StockRow>,
+    /// Average reported EV/EBITDA of the stock's sector
+    sector_ev_ebitda: Option<Decimal>,
+}
+
+impl ScoreBatch {
+    async fn fetch(
+        pool: &sqlx::PgPool,
+        symbols: &[String],
+        now: chrono::DateTime<Utc>,
+    ) -> Result<Self, sqlx::Error> {
+        // Inputs first: data landing while scores are computed changes the hash
+        let inputs =
+            repositories::scores::get_score_inputs(pool, symbols, score_input_windows(now)).await?;
+        let prices = repositories::prices::get_price_histories(
+            pool,
+            symbols,
+            now - Duration::days(SCORE_PRICE_DAYS),
+            now,
+        )
+        .await?;
+        let broker_flow = repositories::broker_summary::get_broker_flow_aggregates_for_symbols(
+            pool,
+            symbols,
+            now - Duration::days(SCORE_BROKER_DAYS),
+            now,
+        )
+        .await?;
+        let financials =
+            repositories::stocks::get_financials_as_of_for_symbols(pool, symbols, now.date_naive())
+                .await?;
+        let stocks = repositories::stocks::get_stocks_by_symbols(pool, symbols).await?;
+        let sector_averages = fundamentals::sector_averages(pool).await?;
+
+        let mut batch = Self {
+            inputs: inputs
+                .into_iter()
+                .map(|row| (row.symbol.clone(), row))
+                .collect(),
+            financials: financials
+                .into_iter()
+                .map(|row| (row.symbol.clone(), row))
+                .collect(),
+            stocks: stocks
+                .into_iter()
+                .map(|row| (row.symbol.clone(), row))
+                .collect(),
+            sector_averages,
+            ..Self::default()
+        };
+        for row in prices {
+            batch
+                .prices
+                .entry(row.symbol.clone())
+                .or_default()
+                .push(row);
+        }
+        for row in broker_flow {
+            batch
+                .broker_flow
+                .entry(row.symbol)
+                .or_default()
+                .push(row.aggregate);
+        }
+        Ok(batch)
+    }
+
+    fn take(&mut self, symbol: &str) -> SymbolScoreInputs {
+        let stock = self.stocks.remove(symbol);
+        let sector_ev_ebitda = stock
+            .as_ref()
+            .and_then(|s| self.sector_averages.get(s.sector.as_deref()?))
+            .and_then(|averages| averages.avg_ev_ebitda);
+        SymbolScoreInputs {
+            inputs: self.inputs.remove(symbol),
+            prices: self.prices.remove(symbol).unwrap_or_default(),
+            broker_flow: self.broker_flow.remove(symbol).unwrap_or_default(),
+            financials: self.financials.remove(symbol),
+            stock,
+            sector_ev_ebitda,
+        }
+    }
+}
+
+/// Redis store of price indicators while recomputing scores
+struct IndicatorCache<'a> {
+    cache: &'a AnalysisCache,
+    ttl: std::time::Duration,
+}
+
+impl IndicatorCache<'_> {
+    /// Cached indicators of `prices`, calculated and stored on a miss
+    async fn indicators(
+        &self,
+        symbol: &str,
+        prices: &[StockPriceRow],
+        bars: &[OhlcvBar],
+    ) -> PriceIndicators {
+        if prices.is_empty() {
+            return PriceIndicators::calculate(bars);
+        }
+        let key = CacheKeys::price_indicators(symbol, &history_digest(prices));
+        let cached = self
+            .cache
+            .get_key(&key)
+            .await
+            .and_then(|json| serde_json::from_str(&json).ok());
+        if let Some(indicators) = cached {
+            return indicators;
+        }
+        let indicators = PriceIndicators::calculate(bars);
+        if let Ok(json) = serde_json::to_string(&indicators) {
+            self.cache.put_key(&key, &json, self.ttl).await;
+        }
+        indicators
+    }
+}
 
+/// Broker score and flags from the last few days of broker flow
+fn broker_flow_input(aggregates: &[BrokerFlowAggregateRow]) -> (Option<Decimal>, bool, bool) {
     let mut total_net = 0.0f64;
     let mut total_traded = 0.0f64;
     let mut foreign_net = 0.0f64;
     let mut institutional_buying = false;
 
-    for a in &aggregates {
+    for a in aggregates {
         let buy_value = a.buy_value.to_f64().unwrap_or(0.0);
         let sell_value = a.sell_value.to_f64().unwrap_or(0.0);
         let net_value = a.net_value.to_f64().unwrap_or(0.0);
@@ -377,50 +1231,147 @@ async fn compute_and_insert_score(
         };
         Decimal::from_f64(s)
     };
+    (broker_score, institutional_buying, foreign_net > 0.0)
+}
 
+/// Compute and store the score of `symbol`, normalizing its technical score
+/// within its sector when `sector_relative` is set, as the batch recompute
+/// does
+pub(crate) async fn compute_and_insert_score(
+    pool: &sqlx::PgPool,
+    symbol: &str,
+    sector_relative: bool,
+) -> Result<StockScoreRow, sqlx::Error> {
+    let now = Utc::now();
+    let inputs = ScoreBatch::fetch(pool, &[symbol.to_string()], now)
+        .await?
+        .take(symbol);
+    let inserted = score_symbol(pool, None, symbol, inputs, now).await?;
+    if !sector_relative || normalize_technical_by_sector(pool, Some(symbol)).await? == 0 {
+        return Ok(inserted);
+    }
+    Ok(repositories::scores::get_stock_score(pool, symbol)
+        .await?
+        .unwrap_or(inserted))
+}
+
+/// Recompute scores for `symbols`, fetching their inputs in chunks
+///
+/// Results are in the order of `symbols`. Price indicators go through the
+/// Redis cache, so a symbol with no new bar since its last recompute skips
+/// calculating them.
+pub(crate) async fn recompute_batch(
+    state: &AppState,
+    symbols: Vec<String>,
+) -> Vec<Result<StockScoreRow, String>> {
+    let cache = IndicatorCache {
+        cache: &state.analysis_cache,
+        ttl: MarketAwareTtl::scores().current(&state.config.market_calendar),
+    };
+    let mut results = Vec::with_capacity(symbols.len());
+    for chunk in symbols.chunks(SCORE_FETCH_CHUNK) {
+        let now = Utc::now();
+        let mut batch = match ScoreBatch::fetch(&state.db, chunk, now).await {
+            Ok(batch) => batch,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to fetch score inputs of {} symbols: {}",
+                    chunk.len(),
+                    e
+                );
+                results.extend(chunk.iter().map(|_| Err(e.to_string())));
+                continue;
+            }
+        };
+        let tasks: Vec<_> = chunk
+            .iter()
+            .map(|symbol| (symbol.clone(), batch.take(symbol)))
+            .collect();
+        let cache = &cache;
+        let scored: Vec<_> =
+            futures_util::stream::iter(tasks.into_iter().map(|(symbol, inputs)| async move {
+                score_symbol(&state.db, Some(cache), &symbol, inputs, now)
+                    .await
+                    .map_err(|e| e.to_string())
+            }))
+            .buffered(SCORE_CONCURRENCY)
+            .collect()
+            .await;
+        results.extend(scored);
+    }
+    results
+}
+
+/// Compute and store the score of `symbol` from prefetched inputs
+///
+/// Order flow, statement-derived ratios and news are still read per symbol.
+async fn score_symbol(
+    pool: &sqlx::PgPool,
+    cache: Option<&IndicatorCache<'_>>,
+    symbol: &str,
+    inputs: SymbolScoreInputs,
+    now: chrono::DateTime<Utc>,
+) -> Result<StockScoreRow, sqlx::Error> {
+    let input_hash = inputs.inputs.as_ref().map(|i| input_hash(i, now));
+    let prices = inputs.prices;
+
+    let bars: Vec<OhlcvBar> = prices
+        .iter()
+        .map(|p| OhlcvBar {
+            open: p.open,
+            high: p.high,
+            low: p.low,
+            close: p.close,
+            volume: p.volume,
+        })
+        .collect();
+
+    let ofi_zscore = persist_order_flow(pool, symbol, &prices, &bars).await;
+
+    // Broker flow (last 5 days) used as a key technical input.
+    let (broker_score, institutional_buying, foreign_buying) =
+        broker_flow_input(&inputs.broker_flow);
+    let broker_age_days = inputs
+        .inputs
+        .as_ref()
+        .and_then(|i| i.broker_time)
+        .map(|latest| (now - latest).num_days());
+
+    let indicators = match cache {
+        Some(cache) => cache.indicators(symbol, &prices, &bars).await,
+        None => PriceIndicators::calculate(&bars),
+    };
     let technical_engine = TechnicalScoreEngine::new();
     let technical_input = TechnicalScoreInput {
-        current_price,
-        prices: close_prices,
-        volumes,
-        highs,
-        lows,
-        obi: None,
-        ofi_trend: None,
+        ofi_zscore,
         broker_score,
         institutional_buying,
-        foreign_buying: foreign_net > 0.0,
-        ema20,
-        ema50,
-        rsi,
-        macd_histogram,
+        foreign_buying,
+        broker_age_days,
+        ..technical_input_with(&bars, indicators)
     };
     let technical_breakdown = technical_engine.calculate(&technical_input);
 
-    let financials = repositories::stocks::get_financials(pool, symbol).await?;
-    let fundamental_engine = FundamentalScoreEngine::new();
-    let fundamental_input = if let Some(f) = financials {
-        FundamentalInput {
-            pe_ratio: f.pe_ratio,
-            sector_pe: None,
-            pb_ratio: f.pb_ratio,
-            sector_pb: None,
-            ev_ebitda: f.ev_ebitda,
-            sector_ev_ebitda: None,
-            dcf_margin: None,
-            roe: f.roe.map(|v| v * dec!(100)),
-            roa: f.roa.map(|v| v * dec!(100)),
-            profit_margin: None,
-            debt_to_equity: None,
-            current_ratio: None,
-        }
-    } else {
-        FundamentalInput::default()
+    let fundamental_engine = fundamentals::engine_for_stock(inputs.stock.as_ref());
+    let fundamental_input = FundamentalInput {
+        sector_ev_ebitda: inputs.sector_ev_ebitda,
+        ..fundamentals::fundamental_input_from(
+            pool,
+            symbol,
+            now.date_naive(),
+            inputs.financials.as_ref(),
+        )
+        .await?
     };
     let fundamental_breakdown = fundamental_engine.calculate(&fundamental_input);
 
-    // Default neutral components until sentiment/ML pipelines are wired.
-    let sentiment_score = 50.0;
+    // Stocks out of the news stay neutral.
+    let sentiment = news::news_sentiment(pool, symbol, now).await?;
+    let sentiment_score = sentiment
+        .as_ref()
+        .and_then(|s| s.score.to_f64())
+        .unwrap_or(50.0);
+    // Default neutral component until the ML pipeline is wired.
     let ml_score = 50.0;
 
     let weights = ScoreWeights::default();
@@ -444,8 +1395,9 @@ async fn compute_and_insert_score(
         ml_score: Decimal::from_f64(ml_score).unwrap_or(dec!(50)),
         technical_breakdown: serde_json::to_value(&technical_breakdown).ok(),
         fundamental_breakdown: serde_json::to_value(&fundamental_breakdown).ok(),
-        sentiment_breakdown: None,
+        sentiment_breakdown: sentiment.and_then(|s| serde_json::to_value(s).ok()),
         ml_breakdown: None,
+        input_hash,
     };
 
     repositories::scores::insert_stock_score(pool, &insert).await
@@ -454,6 +1406,10 @@ async fn compute_and_insert_score(
 #[derive(Debug, Serialize)]
 pub struct FundamentalData {
     pub symbol: String,
+    pub period_end: NaiveDate,
+    /// Date these figures were published
+    pub report_date: NaiveDate,
+    pub version: i32,
     pub pe_ratio: Option<f64>,
     pub pb_ratio: Option<f64>,
     pub ps_ratio: Option<f64>,
@@ -467,6 +1423,9 @@ pub struct FundamentalData {
     pub dcf_margin_of_safety: Option<f64>,
     pub sector_avg_pe: Option<f64>,
     pub sector_avg_pb: Option<f64>,
+    pub sector_avg_ev_ebitda: Option<f64>,
+    /// Percentile of the EV/EBITDA among sector peers, cheaper is higher
+    pub ev_ebitda_percentile: Option<f64>,
 }
 
 #[derive(Debug, Serialize)]
@@ -478,15 +1437,22 @@ pub struct StockFreshnessResponse {
     pub scores_as_of: Option<chrono::DateTime<chrono::Utc>>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct FundamentalsQuery {
+    /// Only use figures published on or before this date (defaults to today)
+    pub as_of: Option<NaiveDate>,
+}
+
 async fn get_stock_fundamentals(
     _user: AuthUser,
     State(state): State<Arc<AppState>>,
     Path(symbol): Path<String>,
+    Query(query): Query<FundamentalsQuery>,
 ) -> Result<Json<Option<FundamentalData>>, (axum::http::StatusCode, String)> {
     let upper_symbol = symbol.to_uppercase();
 
     // Verify stock exists first
-    let _stock = repositories::stocks::get_stock_by_symbol(&state.db, &upper_symbol)
+    let stock = repositories::stocks::get_stock_by_symbol(&state.db, &upper_symbol)
         .await
         .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
         .ok_or_else(|| {
@@ -496,19 +1462,66 @@ async fn get_stock_fundamentals(
             )
         })?;
 
-    // Get latest financials from database
-    let financials = repositories::stocks::get_financials(&state.db, &upper_symbol)
+    // Latest financials that had been published by the requested date
+    let as_of = query.as_of.unwrap_or_else(|| Utc::now().date_naive());
+    let financials = repositories::stocks::get_financials_as_of(&state.db, &upper_symbol, as_of)
         .await
         .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
+    let ev_ebitda = match financials.as_ref() {
+        Some(f) if f.ev_ebitda.is_none() => {
+            fundamentals::derived_ev_ebitda(&state.db, &upper_symbol, as_of)
+                .await
+                .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        }
+        Some(f) => f.ev_ebitda,
+        None => None,
+    };
+
+    // Peers report at different times, so sector figures are only current
+    let peers = match (&stock.sector, query.as_of) {
+        (Some(sector), None) => {
+            let stocks = repositories::stocks::get_all_stocks(&state.db)
+                .await
+                .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+            let latest = repositories::stocks::get_all_latest_financials(&state.db)
+                .await
+                .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+            fundamentals::sector_peer_ratios(&stocks, &latest)
+                .remove(sector)
+                .map(|peers| (sector.clone(), peers))
+        }
+        _ => None,
+    };
+
     let result = financials.map(|f| {
         use rust_decimal::prelude::ToPrimitive;
+        let ratios = ValuationRatios {
+            pe_ratio: f.pe_ratio,
+            pb_ratio: f.pb_ratio,
+            ev_ebitda,
+            ..Default::default()
+        };
+        let (averages, comparison) = match &peers {
+            Some((sector, peers)) => {
+                let peer_ratios: Vec<ValuationRatios> =
+                    peers.iter().map(|(_, r)| r.clone()).collect();
+                (
+                    Some(calculate_sector_averages(sector, &peer_ratios)),
+                    Some(compare_to_peers(&upper_symbol, &ratios, sector, peers)),
+                )
+            }
+            None => (None, None),
+        };
         FundamentalData {
             symbol: f.symbol,
+            period_end: f.period_end,
+            report_date: f.report_date,
+            version: f.version,
             pe_ratio: f.pe_ratio.and_then(|v| v.to_f64()),
             pb_ratio: f.pb_ratio.and_then(|v| v.to_f64()),
             ps_ratio: None,
-            ev_ebitda: f.ev_ebitda.and_then(|v| v.to_f64()),
+            ev_ebitda: ev_ebitda.and_then(|v| v.to_f64()),
             // Convert ROE/ROA from decimal (0.21) to percentage (21.0)
             roe: f.roe.and_then(|v| v.to_f64().map(|x| x * 100.0)),
             roa: f.roa.and_then(|v| v.to_f64().map(|x| x * 100.0)),
@@ -517,14 +1530,39 @@ async fn get_stock_fundamentals(
             current_ratio: None,
             dcf_intrinsic_value: None,
             dcf_margin_of_safety: None,
-            sector_avg_pe: None,
-            sector_avg_pb: None,
+            sector_avg_pe: averages
+                .as_ref()
+                .and_then(|a| a.avg_pe)
+                .and_then(|v| v.to_f64()),
+            sector_avg_pb: averages
+                .as_ref()
+                .and_then(|a| a.avg_pb)
+                .and_then(|v| v.to_f64()),
+            sector_avg_ev_ebitda: averages
+                .as_ref()
+                .and_then(|a| a.avg_ev_ebitda)
+                .and_then(|v| v.to_f64()),
+            ev_ebitda_percentile: comparison
+                .and_then(|c| c.ev_ebitda_percentile)
+                .and_then(|v| v.to_f64()),
         }
     });
 
     Ok(Json(result))
 }
 
+async fn get_stock_fundamentals_versions(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(symbol): Path<String>,
+) -> Result<Json<Vec<FinancialsVersionRow>>, (axum::http::StatusCode, String)> {
+    let versions = repositories::stocks::get_financials_versions(&state.db, &symbol.to_uppercase())
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(versions))
+}
+
 async fn get_stock_freshness(
     _user: AuthUser,
     State(state): State<Arc<AppState>>,
@@ -605,18 +1643,7 @@ async fn refresh_stock_all(
 
     let mut jobs = Vec::new();
 
-    let price_job = state
-        .job_manager
-        .spawn_job(
-            format!("stock-refresh-price-{}", upper_symbol),
-            format!("{} Price Data", upper_symbol),
-            format!(
-                "python -m jejakcuan_ml.scrapers.cli price --days 60 {}",
-                upper_symbol
-            ),
-        )
-        .await;
-    jobs.push(price_job);
+    jobs.push(price_history::spawn_refresh(&state, &upper_symbol).await);
 
     let broker_job = state
         .job_manager
@@ -669,15 +1696,16 @@ async fn refresh_stock_source(
             )
         })?;
 
+    if matches!(source_type_lower.as_str(), "price" | "prices") {
+        let job = price_history::spawn_refresh(&state, &upper_symbol).await;
+        return Ok(Json(RefreshSourceResponse {
+            symbol: upper_symbol,
+            source_type: source_type_lower,
+            job,
+        }));
+    }
+
     let (source_id, source_name, command) = match source_type_lower.as_str() {
-        "price" | "prices" => (
-            format!("stock-refresh-price-{}", upper_symbol),
-            format!("{} Price Data", upper_symbol),
-            format!(
-                "python -m jejakcuan_ml.scrapers.cli price --days 60 {}",
-                upper_symbol
-            ),
-        ),
         "broker" | "broker_flow" => (
             format!("stock-refresh-broker-{}", upper_symbol),
             format!("{} Broker Flow", upper_symbol),
@@ -716,3 +1744,216 @@ async fn refresh_stock_source(
         job,
     }))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_quarter_ends() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+
+        assert_eq!(
+            quarter_ends(date(2024, 5, 15), 3),
+            vec![date(2023, 9, 30), date(2023, 12, 31), date(2024, 3, 31)]
+        );
+        // A quarter end itself is included
+        assert_eq!(quarter_ends(date(2024, 6, 30), 1), vec![date(2024, 6, 30)]);
+    }
+
+    fn aggregate(category: &str, buy: i64, sell: i64) -> BrokerFlowAggregateRow {
+        BrokerFlowAggregateRow {
+            broker_code: "XX".to_string(),
+            broker_name: None,
+            category: category.to_string(),
+            buy_volume: 0,
+            sell_volume: 0,
+            buy_value: Decimal::from(buy),
+            sell_value: Decimal::from(sell),
+            net_volume: 0,
+            net_value: Decimal::from(buy - sell),
+        }
+    }
+
+    #[test]
+    fn test_broker_flow_input() {
+        assert_eq!(broker_flow_input(&[]), (None, false, false));
+
+        let flow = [
+            aggregate("foreign_institutional", 900, 100),
+            aggregate("retail", 100, 300),
+        ];
+        assert_eq!(broker_flow_input(&flow), (Some(dec!(80)), true, true));
+
+        // Net flow under 5% of the traded value is neutral
+        let balanced = [aggregate("local_institutional", 510, 490)];
+        assert_eq!(broker_flow_input(&balanced), (Some(dec!(50)), true, false));
+    }
+
+    #[test]
+    fn test_cached_indicators_match() {
+        let bars: Vec<OhlcvBar> = (0..80)
+            .map(|i| {
+                let close = Decimal::from(1000 + (i % 7) * 15 + i * 3);
+                OhlcvBar {
+                    open: close - dec!(5),
+                    high: close + dec!(10),
+                    low: close - dec!(10),
+                    close,
+                    volume: 1_000_000 + i * 1000,
+                }
+            })
+            .collect();
+        let indicators = PriceIndicators::calculate(&bars);
+        assert!(indicators.ema50.is_some());
+
+        // A cache round trip scores the same as calculating afresh
+        let json = serde_json::to_string(&indicators).unwrap();
+        let cached: PriceIndicators = serde_json::from_str(&json).unwrap();
+        assert_eq!(cached, indicators);
+        let engine = TechnicalScoreEngine::new();
+        assert_eq!(
+            engine
+                .calculate(&technical_input_with(&bars, cached))
+                .total_score,
+            engine.calculate(&price_technical_input(&bars)).total_score
+        );
+    }
+
+    #[test]
+    fn test_history_digest() {
+        let row = |day: u32, close: Decimal| StockPriceRow {
+            time: Utc.with_ymd_and_hms(2025, 10, day, 0, 0, 0).unwrap(),
+            symbol: "BBCA".to_string(),
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume: 1_000,
+            value: None,
+            frequency: None,
+        };
+        let history = vec![row(20, dec!(9000)), row(21, dec!(9050))];
+        let digest = history_digest(&history);
+        assert!(digest.starts_with("2-"));
+        assert_eq!(
+            history_digest(&[row(20, dec!(9000.00)), row(21, dec!(9050))]),
+            digest
+        );
+        // A patched earlier bar changes the digest though the latest bar is the same
+        let patched = vec![row(20, dec!(8950)), row(21, dec!(9050))];
+        assert_ne!(history_digest(&patched), digest);
+        let gap_filled = vec![
+            row(19, dec!(8900)),
+            row(20, dec!(9000)),
+            row(21, dec!(9050)),
+        ];
+        assert_ne!(history_digest(&gap_filled), digest);
+    }
+
+    #[test]
+    fn test_input_hash() {
+        let at = |h| Utc.with_ymd_and_hms(2025, 10, 20, h, 0, 0).single();
+        let now = at(17).unwrap();
+        let inputs = ScoreInputsRow {
+            symbol: "BBCA".to_string(),
+            price_time: at(9),
+            broker_time: at(10),
+            financials_time: None,
+            news_time: None,
+            financials_report_date: NaiveDate::from_ymd_opt(2025, 7, 30),
+            news_digest: None,
+            broker_window_start: at(10),
+            sector: Some("Financials".to_string()),
+            subsector: Some("Banks".to_string()),
+        };
+        let hash = input_hash(&inputs, now);
+        assert_eq!(hash.len(), 16);
+        // Only the inputs count, not the symbol
+        assert_eq!(
+            input_hash(
+                &ScoreInputsRow {
+                    symbol: "BBRI".to_string(),
+                    ..inputs.clone()
+                },
+                now
+            ),
+            hash
+        );
+        let new_price = ScoreInputsRow {
+            price_time: at(16),
+            ..inputs.clone()
+        };
+        assert_ne!(input_hash(&new_price, now), hash);
+        // The same time in another input is a different fingerprint
+        let swapped = ScoreInputsRow {
+            price_time: at(10),
+            broker_time: at(9),
+            ..inputs.clone()
+        };
+        assert_ne!(input_hash(&swapped, now), hash);
+
+        // Unchanged while every input is fresh
+        assert_eq!(input_hash(&inputs, now + Duration::days(2)), hash);
+        // A silent broker feed starts decaying after three days, and the
+        // fingerprint changes every day it decays further
+        let day4 = input_hash(&inputs, now + Duration::days(4));
+        let day5 = input_hash(&inputs, now + Duration::days(5));
+        assert_ne!(day4, hash);
+        assert_ne!(day5, day4);
+        // Financials 150+ days old decay too, even with fresh broker data
+        let old_financials = ScoreInputsRow {
+            financials_report_date: NaiveDate::from_ymd_opt(2025, 4, 1),
+            ..inputs.clone()
+        };
+        assert_ne!(
+            input_hash(&old_financials, now),
+            input_hash(&old_financials, now + Duration::days(1))
+        );
+
+        // Headlines in the sentiment window weigh less every day, and a
+        // re-scored headline changes their checksum
+        let with_news = ScoreInputsRow {
+            news_time: at(8),
+            news_digest: Some("a1".to_string()),
+            ..inputs.clone()
+        };
+        assert_ne!(
+            input_hash(&with_news, now),
+            input_hash(&with_news, now + Duration::days(1))
+        );
+        let rescored = ScoreInputsRow {
+            news_digest: Some("b2".to_string()),
+            ..with_news.clone()
+        };
+        assert_ne!(input_hash(&rescored, now), input_hash(&with_news, now));
+
+        // A broker row leaving the window, or a new sector
+        let shifted = ScoreInputsRow {
+            broker_window_start: at(11),
+            ..inputs.clone()
+        };
+        assert_ne!(input_hash(&shifted, now), hash);
+        let moved = ScoreInputsRow {
+            subsector: Some("Insurance".to_string()),
+            ..inputs
+        };
+        assert_ne!(input_hash(&moved, now), hash);
+    }
+
+    #[test]
+    fn test_prioritize_symbols() {
+        let symbols: Vec<String> = (0..20).map(|i| format!("S{:02}", i)).collect();
+        // S19 most liquid, S00 least
+        let by_liquidity: Vec<String> = symbols.iter().rev().cloned().collect();
+        let watchlist = HashSet::from(["S05".to_string(), "NEW".to_string()]);
+
+        let (priority, tail) = prioritize_symbols(symbols, &watchlist, &by_liquidity);
+
+        // Top decile of 20 is 2 symbols, plus the watchlist symbol
+        assert_eq!(priority, vec!["S19", "S18", "S05"]);
+        assert_eq!(tail.len(), 17);
+        assert!(!tail.contains(&"S05".to_string()));
+    }
+}
@@ -4,10 +4,19 @@
 //! - Real-time price updates
 //! - Alert notifications
 //! - Broker flow updates
+//! - Intraday composite score changes
+//!
+//! In-app notifications are pushed per user over a WebSocket.
 
 use axum::{
-    extract::State,
-    response::sse::{Event, KeepAlive, Sse},
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Query, State,
+    },
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        Response,
+    },
     routing::get,
     Router,
 };
@@ -18,8 +27,13 @@ use tokio::sync::broadcast;
 use tokio_stream::wrappers::BroadcastStream;
 use tokio_stream::StreamExt;
 
+use crate::auth::{decode_token, AuthError, AuthUser};
+use crate::notifications::InAppNotification;
 use crate::AppState;
 
+/// Ping interval keeping idle notification sockets open through proxies
+const WS_PING_INTERVAL: Duration = Duration::from_secs(30);
+
 /// Stream message types
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", content = "data")]
@@ -54,6 +68,8 @@ pub enum StreamMessage {
         technical_score: f64,
         fundamental_score: f64,
         composite_score: f64,
+        /// Change from the last stored composite score
+        composite_change: f64,
         timestamp: i64,
     },
     /// Heartbeat to keep connection alive
@@ -99,24 +115,29 @@ pub fn streaming_routes() -> Router<Arc<AppState>> {
         .route("/stream", get(stream_all))
         .route("/stream/prices", get(stream_prices))
         .route("/stream/alerts", get(stream_alerts))
+        .route("/stream/scores", get(stream_scores))
+        .route("/ws/notifications", get(notifications_ws))
 }
 
 /// Stream all events
 async fn stream_all(
-    State(_state): State<Arc<AppState>>,
+    State(state): State<Arc<AppState>>,
 ) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
-    // Create a simple heartbeat stream for now
-    // In production, this would connect to the broadcasting system
-    let stream = stream::repeat_with(|| {
+    let stream = broadcast_to_sse(state.streaming.subscribe(), |_| true).merge(heartbeats());
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+/// Heartbeat messages every 30 seconds
+fn heartbeats() -> impl Stream<Item = Result<Event, Infallible>> {
+    stream::repeat_with(|| {
         let msg = StreamMessage::Heartbeat {
             timestamp: chrono::Utc::now().timestamp(),
         };
         let json = serde_json::to_string(&msg).unwrap_or_default();
         Result::<_, Infallible>::Ok(Event::default().data(json))
     })
-    .throttle(Duration::from_secs(30));
-
-    Sse::new(stream).keep_alive(KeepAlive::default())
+    .throttle(Duration::from_secs(30))
 }
 
 /// Stream price updates only
@@ -153,6 +174,84 @@ async fn stream_alerts(
     Sse::new(stream).keep_alive(KeepAlive::default())
 }
 
+/// Stream composite score changes only
+async fn stream_scores(
+    State(state): State<Arc<AppState>>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let stream = broadcast_to_sse(state.streaming.subscribe(), |msg| {
+        matches!(msg, StreamMessage::ScoreUpdate { .. })
+    });
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WsAuthQuery {
+    /// Browsers cannot set headers on WebSocket requests, so the token may
+    /// come as a query parameter instead
+    pub token: Option<String>,
+}
+
+/// Push the user's in-app notifications over a WebSocket
+async fn notifications_ws(
+    ws: WebSocketUpgrade,
+    user: Option<AuthUser>,
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<WsAuthQuery>,
+) -> Result<Response, AuthError> {
+    let user = match (user, query.token) {
+        (Some(user), _) => user,
+        (None, Some(token)) => decode_token(&token)?,
+        (None, None) => return Err(AuthError("No token provided".to_string())),
+    };
+
+    let receiver = state.in_app.subscribe(&user.username);
+    Ok(ws.on_upgrade(move |socket| forward_notifications(socket, receiver, user.username)))
+}
+
+/// Forward notifications until the client disconnects
+async fn forward_notifications(
+    mut socket: WebSocket,
+    mut receiver: broadcast::Receiver<InAppNotification>,
+    username: String,
+) {
+    let mut ping = tokio::time::interval(WS_PING_INTERVAL);
+    ping.tick().await;
+
+    loop {
+        tokio::select! {
+            received = receiver.recv() => {
+                let notification = match received {
+                    Ok(notification) => notification,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!(
+                            "In-app socket of {} lagged, skipped {} notifications",
+                            username,
+                            skipped
+                        );
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                let json = serde_json::to_string(&notification).unwrap_or_default();
+                if socket.send(Message::Text(json)).await.is_err() {
+                    break;
+                }
+            }
+            incoming = socket.recv() => match incoming {
+                // Client messages other than close are ignored
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+            _ = ping.tick() => {
+                if socket.send(Message::Ping(Vec::new())).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+}
+
 /// Helper to create SSE stream from broadcast receiver
 pub fn broadcast_to_sse<F>(
     receiver: broadcast::Receiver<StreamMessage>,
@@ -0,0 +1,185 @@
+//! Symbol mapping routes
+//!
+//! Resolves tickers between provider notations (`BBCA.JK`, `BBCA:IDX`,
+//! `BBCA`) and manages the stored overrides for exceptions to the defaults.
+
+use crate::auth::AuthUser;
+use crate::AppState;
+use axum::{
+    extract::{Path, Query, State},
+    routing::{delete, get, put},
+    Json, Router,
+};
+use jejakcuan_data_sources::{ResolvedSymbol, SymbolMapper, SymbolMapping, SymbolProvider};
+use jejakcuan_db::{repositories, SymbolMappingRow};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+pub fn symbol_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/resolve", get(resolve_symbol))
+        .route("/mappings", get(list_mappings))
+        .route("/mappings", put(upsert_mapping))
+        .route("/mappings/:symbol/:provider", delete(delete_mapping))
+}
+
+/// Build a mapper from the stored overrides
+///
+/// Rows with an unknown provider are skipped rather than failing the load.
+pub(crate) async fn load_symbol_mapper(pool: &sqlx::PgPool) -> Result<SymbolMapper, sqlx::Error> {
+    let rows = repositories::symbol_mappings::get_symbol_mappings(pool).await?;
+    Ok(SymbolMapper::with_mappings(rows.into_iter().filter_map(
+        |row| {
+            Some(SymbolMapping {
+                provider: SymbolProvider::from_str_opt(&row.provider)?,
+                symbol: row.symbol,
+                provider_symbol: row.provider_symbol,
+            })
+        },
+    )))
+}
+
+/// Apply the stored overrides to the price provider chain
+pub(crate) async fn reload_price_provider_symbols(state: &AppState) {
+    match load_symbol_mapper(&state.db).await {
+        Ok(mapper) => state.price_provider.set_symbol_mapper(mapper),
+        Err(e) => tracing::warn!("Failed to load symbol overrides: {}", e),
+    }
+}
+
+/// Load the stored overrides into the price provider chain at startup
+pub(crate) fn spawn_price_provider_symbols(state: Arc<AppState>) {
+    tokio::spawn(async move { reload_price_provider_symbols(&state).await });
+}
+
+fn parse_provider(provider: &str) -> Result<SymbolProvider, (axum::http::StatusCode, String)> {
+    SymbolProvider::from_str_opt(provider).ok_or_else(|| {
+        (
+            axum::http::StatusCode::BAD_REQUEST,
+            format!("Unknown provider: {}", provider),
+        )
+    })
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResolveQuery {
+    /// Symbol in any provider notation
+    q: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ResolveResponse {
+    #[serde(flatten)]
+    pub resolved: ResolvedSymbol,
+    /// Whether the canonical symbol is a listed stock
+    pub listed: bool,
+}
+
+async fn resolve_symbol(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ResolveQuery>,
+) -> Result<Json<ResolveResponse>, (axum::http::StatusCode, String)> {
+    let mapper = load_symbol_mapper(&state.db)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let resolved = mapper.resolve(&query.q);
+
+    let listed = repositories::stocks::get_stock_by_symbol(&state.db, &resolved.symbol)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .is_some();
+
+    Ok(Json(ResolveResponse { resolved, listed }))
+}
+
+async fn list_mappings(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<SymbolMappingRow>>, (axum::http::StatusCode, String)> {
+    let rows = repositories::symbol_mappings::get_symbol_mappings(&state.db)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(rows))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpsertMappingRequest {
+    symbol: String,
+    provider: String,
+    provider_symbol: String,
+}
+
+async fn upsert_mapping(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<UpsertMappingRequest>,
+) -> Result<Json<SymbolMappingRow>, (axum::http::StatusCode, String)> {
+    let provider = parse_provider(&req.provider)?;
+    let symbol = req.symbol.trim().to_uppercase();
+    let provider_symbol = req.provider_symbol.trim().to_uppercase();
+    if provider_symbol.is_empty() {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            "Provider symbol is required".to_string(),
+        ));
+    }
+
+    repositories::stocks::get_stock_by_symbol(&state.db, &symbol)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(|| {
+            (
+                axum::http::StatusCode::NOT_FOUND,
+                format!("Stock {} not found", symbol),
+            )
+        })?;
+
+    let row = repositories::symbol_mappings::upsert_symbol_mapping(
+        &state.db,
+        &symbol,
+        provider.as_str(),
+        &provider_symbol,
+    )
+    .await
+    .map_err(|e| match e.as_database_error() {
+        Some(db) if db.is_unique_violation() => (
+            axum::http::StatusCode::CONFLICT,
+            format!(
+                "{} on {} is already mapped to another symbol",
+                provider_symbol,
+                provider.as_str()
+            ),
+        ),
+        _ => (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    })?;
+    reload_price_provider_symbols(&state).await;
+
+    Ok(Json(row))
+}
+
+async fn delete_mapping(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path((symbol, provider)): Path<(String, String)>,
+) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, String)> {
+    let provider = parse_provider(&provider)?;
+    let deleted = repositories::symbol_mappings::delete_symbol_mapping(
+        &state.db,
+        &symbol.to_uppercase(),
+        provider.as_str(),
+    )
+    .await
+    .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    if deleted {
+        reload_price_provider_symbols(&state).await;
+        Ok(Json(serde_json::json!({ "success": true })))
+    } else {
+        Err((
+            axum::http::StatusCode::NOT_FOUND,
+            "Symbol mapping not found".to_string(),
+        ))
+    }
+}
@@ -1,18 +1,43 @@
 //! Watchlist routes
+//!
+//! Besides the symbol list, users keep custom horizontal price levels per
+//! symbol. Levels are merged into the analysis support/resistance and feed
+//! level-cross alerts. Removed symbols can be restored until the retention
+//! job purges them. Every edit is recorded in the change history.
+//!
+//! Each symbol can also carry alert preferences: which alert types, the
+//! minimum priority, the channels and quiet hours. Symbols without them are
+//! routed to the default operator channels.
 
 use crate::auth::AuthUser;
+use crate::change_history::{history_limit, load_history, record_change};
+use crate::retention::restore_cutoff;
+use crate::routes::notifications::load_user_recipients;
 use crate::AppState;
 use axum::{
-    extract::{Path, State},
-    routing::{delete, get, post},
+    extract::{Path, Query, State},
+    routing::{delete, get, post, put},
     Json, Router,
 };
-use jejakcuan_db::{repositories, StockRow, WatchlistRow};
+use jejakcuan_audit::{ChangeHistoryQuery, ChangeRecord};
+use jejakcuan_core::{
+    AlertPriority, AlertSubscription, AlertTypeFilter, NotificationChannel, QuietHours,
+};
+use jejakcuan_db::{
+    repositories, AlertSubscriptionRow, InsertWatchlistLevel, StockRow, UpsertAlertSubscription,
+    WatchlistLevelRow, WatchlistRow,
+};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 const SYARIAH_BANK_ALLOWLIST: &[&str] = &["BRIS", "BTPS", "PNBS"];
 
+/// Change history resource type for entries and their levels
+const HISTORY_RESOURCE: &str = "watchlist";
+
+const LEVEL_KINDS: &[&str] = &["support", "resistance", "entry", "target", "stop", "note"];
+
 fn is_excluded_non_syariah_bank(stock: &StockRow) -> bool {
     let is_bank = stock
         .sector
@@ -38,7 +63,18 @@ pub fn watchlist_routes() -> Router<Arc<AppState>> {
     Router::new()
         .route("/", get(get_watchlist))
         .route("/", post(add_to_watchlist))
+        .route("/deleted", get(get_deleted_watchlist))
+        .route("/restore", post(restore_watchlist))
+        .route("/history", get(get_watchlist_history))
+        .route("/alerts", get(list_alert_subscriptions))
         .route("/:symbol", delete(remove_from_watchlist))
+        .route("/:symbol/levels", get(list_levels))
+        .route("/:symbol/levels", post(create_level))
+        .route("/:symbol/levels/:id", put(update_level))
+        .route("/:symbol/levels/:id", delete(delete_level))
+        .route("/:symbol/alerts", get(get_alert_subscription))
+        .route("/:symbol/alerts", put(put_alert_subscription))
+        .route("/:symbol/alerts", delete(delete_alert_subscription))
 }
 
 async fn get_watchlist(
@@ -65,7 +101,7 @@ pub struct WatchlistError {
 }
 
 async fn add_to_watchlist(
-    _user: AuthUser,
+    user: AuthUser,
     State(state): State<Arc<AppState>>,
     Json(req): Json<AddToWatchlistRequest>,
 ) -> Result<Json<WatchlistRow>, (axum::http::StatusCode, Json<WatchlistError>)> {
@@ -137,17 +173,509 @@ async fn add_to_watchlist(
             }
         })?;
 
+    record_change(
+        &state,
+        &user.username,
+        "watchlist_add",
+        HISTORY_RESOURCE,
+        &symbol,
+        None,
+        Some(&item),
+    )
+    .await;
+
     Ok(Json(item))
 }
 
 async fn remove_from_watchlist(
-    _user: AuthUser,
+    user: AuthUser,
     State(state): State<Arc<AppState>>,
     Path(symbol): Path<String>,
 ) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, String)> {
-    repositories::watchlist::remove_from_watchlist(&state.db, &symbol.to_uppercase())
+    let symbol = symbol.to_uppercase();
+    let removed = repositories::watchlist::remove_from_watchlist(&state.db, &symbol)
         .await
         .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
+    if let Some(removed) = &removed {
+        record_change(
+            &state,
+            &user.username,
+            "watchlist_remove",
+            HISTORY_RESOURCE,
+            &symbol,
+            Some(removed),
+            None,
+        )
+        .await;
+    }
+
     Ok(Json(serde_json::json!({ "success": true })))
 }
+
+/// Items removed within the retention window, most recent first
+async fn get_deleted_watchlist(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<WatchlistRow>>, (axum::http::StatusCode, String)> {
+    let since = restore_cutoff(chrono::Utc::now(), state.config.soft_delete_retention_days);
+    let deleted = repositories::watchlist::get_deleted_watchlist(&state.db, since)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(deleted))
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct RestoreWatchlistRequest {
+    /// Defaults to the most recently removed symbol
+    symbol: Option<String>,
+}
+
+/// Undo a removal still within the retention window
+async fn restore_watchlist(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    body: Option<Json<RestoreWatchlistRequest>>,
+) -> Result<Json<WatchlistRow>, (axum::http::StatusCode, String)> {
+    let symbol = body
+        .and_then(|Json(req)| req.symbol)
+        .map(|s| s.trim().to_uppercase())
+        .filter(|s| !s.is_empty());
+    let since = restore_cutoff(chrono::Utc::now(), state.config.soft_delete_retention_days);
+
+    let item = repositories::watchlist::restore_watchlist_item(&state.db, symbol.as_deref(), since)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(|| {
+            (
+                axum::http::StatusCode::NOT_FOUND,
+                match symbol {
+                    Some(symbol) => format!("No restorable watchlist entry for {}", symbol),
+                    None => "No restorable watchlist entry".to_string(),
+                },
+            )
+        })?;
+
+    record_change(
+        &state,
+        &user.username,
+        "watchlist_restore",
+        HISTORY_RESOURCE,
+        &item.symbol,
+        None,
+        Some(&item),
+    )
+    .await;
+
+    Ok(Json(item))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WatchlistHistoryQuery {
+    /// Changes of one symbol and its levels; all symbols when omitted
+    symbol: Option<String>,
+    limit: Option<i64>,
+}
+
+/// When and how watchlist entries and price levels changed, newest first
+async fn get_watchlist_history(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<WatchlistHistoryQuery>,
+) -> Result<Json<Vec<ChangeRecord>>, (axum::http::StatusCode, String)> {
+    let symbol = query.symbol.as_deref().map(str::to_uppercase);
+    let history = load_history(
+        &state,
+        &ChangeHistoryQuery {
+            resource_type: HISTORY_RESOURCE,
+            resource_id: symbol.as_deref(),
+            username: Some(&user.username),
+            limit: history_limit(query.limit),
+        },
+    )
+    .await?;
+
+    Ok(Json(history))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PriceLevelRequest {
+    price: Decimal,
+    /// One of LEVEL_KINDS, defaults to "note"
+    kind: Option<String>,
+    label: Option<String>,
+    note: Option<String>,
+    alert_enabled: Option<bool>,
+}
+
+fn validate_level(req: &PriceLevelRequest) -> Result<&str, (axum::http::StatusCode, String)> {
+    if req.price <= Decimal::ZERO {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            "Level price must be positive".to_string(),
+        ));
+    }
+
+    let kind = req.kind.as_deref().unwrap_or("note");
+    if !LEVEL_KINDS.contains(&kind) {
+        return Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            format!(
+                "Unknown level kind '{}', expected one of: {}",
+                kind,
+                LEVEL_KINDS.join(", ")
+            ),
+        ));
+    }
+    Ok(kind)
+}
+
+async fn list_levels(
+    _user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(symbol): Path<String>,
+) -> Result<Json<Vec<WatchlistLevelRow>>, (axum::http::StatusCode, String)> {
+    let levels = repositories::watchlist::get_watchlist_levels(&state.db, &symbol.to_uppercase())
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(levels))
+}
+
+async fn create_level(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(symbol): Path<String>,
+    Json(req): Json<PriceLevelRequest>,
+) -> Result<Json<WatchlistLevelRow>, (axum::http::StatusCode, String)> {
+    let symbol = symbol.to_uppercase();
+    let kind = validate_level(&req)?;
+
+    repositories::stocks::get_stock_by_symbol(&state.db, &symbol)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(|| {
+            (
+                axum::http::StatusCode::NOT_FOUND,
+                format!("Stock {} not found", symbol),
+            )
+        })?;
+
+    let level = InsertWatchlistLevel {
+        symbol: &symbol,
+        price: req.price,
+        kind,
+        label: req.label.as_deref(),
+        note: req.note.as_deref(),
+        alert_enabled: req.alert_enabled.unwrap_or(true),
+    };
+
+    let row = repositories::watchlist::insert_watchlist_level(&state.db, &level)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    record_change(
+        &state,
+        &user.username,
+        "level_create",
+        HISTORY_RESOURCE,
+        &symbol,
+        None,
+        Some(&row),
+    )
+    .await;
+
+    Ok(Json(row))
+}
+
+async fn update_level(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path((symbol, id)): Path<(String, i32)>,
+    Json(req): Json<PriceLevelRequest>,
+) -> Result<Json<WatchlistLevelRow>, (axum::http::StatusCode, String)> {
+    let symbol = symbol.to_uppercase();
+    let kind = validate_level(&req)?;
+    let not_found = || {
+        (
+            axum::http::StatusCode::NOT_FOUND,
+            "Price level not found".to_string(),
+        )
+    };
+
+    let before = repositories::watchlist::get_watchlist_level(&state.db, &symbol, id)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(not_found)?;
+
+    let level = InsertWatchlistLevel {
+        symbol: &symbol,
+        price: req.price,
+        kind,
+        label: req.label.as_deref(),
+        note: req.note.as_deref(),
+        alert_enabled: req.alert_enabled.unwrap_or(true),
+    };
+
+    let row = repositories::watchlist::update_watchlist_level(&state.db, id, &level)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(not_found)?;
+
+    record_change(
+        &state,
+        &user.username,
+        "level_update",
+        HISTORY_RESOURCE,
+        &symbol,
+        Some(&before),
+        Some(&row),
+    )
+    .await;
+
+    Ok(Json(row))
+}
+
+async fn delete_level(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path((symbol, id)): Path<(String, i32)>,
+) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, String)> {
+    let symbol = symbol.to_uppercase();
+    let before = repositories::watchlist::get_watchlist_level(&state.db, &symbol, id)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let deleted = repositories::watchlist::delete_watchlist_level(&state.db, &symbol, id)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    if deleted {
+        record_change(
+            &state,
+            &user.username,
+            "level_delete",
+            HISTORY_RESOURCE,
+            &symbol,
+            before.as_ref(),
+            None,
+        )
+        .await;
+        Ok(Json(serde_json::json!({ "success": true })))
+    } else {
+        Err((
+            axum::http::StatusCode::NOT_FOUND,
+            "Price level not found".to_string(),
+        ))
+    }
+}
+
+// ============== Alert preferences ==============
+
+/// Alert subscription stored for a symbol
+///
+/// Unreadable alert types or channels fall back to all types and no
+/// channels rather than failing the whole scan.
+pub fn to_alert_subscription(row: &AlertSubscriptionRow) -> AlertSubscription {
+    let quiet_hours = match (row.quiet_start, row.quiet_end) {
+        (Some(start), Some(end)) => Some(QuietHours {
+            start,
+            end,
+            min_priority: row
+                .quiet_min_priority
+                .as_deref()
+                .and_then(AlertPriority::from_str_opt)
+                .unwrap_or(AlertPriority::Critical),
+        }),
+        _ => None,
+    };
+    AlertSubscription {
+        user_id: row.user_id.clone(),
+        symbols: vec![row.symbol.clone()],
+        alert_types: serde_json::from_value(row.alert_types.clone()).unwrap_or_default(),
+        min_priority: AlertPriority::from_str_opt(&row.min_priority)
+            .unwrap_or(AlertPriority::Medium),
+        channels: serde_json::from_value(row.channels.clone()).unwrap_or_default(),
+        quiet_hours,
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct AlertSubscriptionResponse {
+    pub symbol: String,
+    /// `None` when the symbol uses the default operator channels
+    pub subscription: Option<AlertSubscription>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AlertSubscriptionRequest {
+    #[serde(default)]
+    pub alert_types: AlertTypeFilter,
+    pub min_priority: Option<AlertPriority>,
+    pub channels: Vec<NotificationChannel>,
+    pub quiet_hours: Option<QuietHours>,
+}
+
+async fn list_alert_subscriptions(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<AlertSubscriptionResponse>>, (axum::http::StatusCode, String)> {
+    let rows = repositories::subscriptions::get_alert_subscriptions(&state.db, &user.username)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(
+        rows.iter()
+            .map(|row| AlertSubscriptionResponse {
+                symbol: row.symbol.clone(),
+                subscription: Some(to_alert_subscription(row)),
+            })
+            .collect(),
+    ))
+}
+
+async fn get_alert_subscription(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(symbol): Path<String>,
+) -> Result<Json<AlertSubscriptionResponse>, (axum::http::StatusCode, String)> {
+    let symbol = symbol.to_uppercase();
+    let row =
+        repositories::subscriptions::get_alert_subscription(&state.db, &user.username, &symbol)
+            .await
+            .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(AlertSubscriptionResponse {
+        symbol,
+        subscription: row.as_ref().map(to_alert_subscription),
+    }))
+}
+
+/// Channels the request names that the user has no address for
+fn unroutable_channels(
+    recipients: &[(NotificationChannel, String)],
+    channels: &[NotificationChannel],
+) -> Vec<String> {
+    channels
+        .iter()
+        .filter(|c| **c != NotificationChannel::InApp && !recipients.iter().any(|(r, _)| r == *c))
+        .map(|c| format!("{:?}", c))
+        .collect()
+}
+
+async fn put_alert_subscription(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(symbol): Path<String>,
+    Json(req): Json<AlertSubscriptionRequest>,
+) -> Result<Json<AlertSubscriptionResponse>, (axum::http::StatusCode, String)> {
+    let symbol = symbol.to_uppercase();
+    let bad_request = |msg: String| (axum::http::StatusCode::BAD_REQUEST, msg);
+
+    if req.channels.is_empty() {
+        return Err(bad_request("At least one channel is required".to_string()));
+    }
+    let recipients = load_user_recipients(&state, &user.username)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let unroutable = unroutable_channels(&recipients, &req.channels);
+    if !unroutable.is_empty() {
+        return Err(bad_request(format!(
+            "No address set for channels: {}",
+            unroutable.join(", ")
+        )));
+    }
+    if req.quiet_hours.is_some_and(|q| q.start == q.end) {
+        return Err(bad_request(
+            "Quiet hours must start and end at different times".to_string(),
+        ));
+    }
+
+    repositories::stocks::get_stock_by_symbol(&state.db, &symbol)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(|| {
+            (
+                axum::http::StatusCode::NOT_FOUND,
+                format!("Stock {} not found", symbol),
+            )
+        })?;
+
+    let before =
+        repositories::subscriptions::get_alert_subscription(&state.db, &user.username, &symbol)
+            .await
+            .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let alert_types = serde_json::to_value(&req.alert_types).unwrap_or_default();
+    let channels = serde_json::to_value(&req.channels).unwrap_or_default();
+    let quiet = req.quiet_hours;
+    let row = repositories::subscriptions::upsert_alert_subscription(
+        &state.db,
+        &UpsertAlertSubscription {
+            user_id: &user.username,
+            symbol: &symbol,
+            alert_types: &alert_types,
+            min_priority: req.min_priority.unwrap_or(AlertPriority::Medium).as_str(),
+            channels: &channels,
+            quiet_start: quiet.map(|q| q.start),
+            quiet_end: quiet.map(|q| q.end),
+            quiet_min_priority: quiet.map(|q| q.min_priority.as_str()),
+        },
+    )
+    .await
+    .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    record_change(
+        &state,
+        &user.username,
+        "alerts_update",
+        HISTORY_RESOURCE,
+        &symbol,
+        before.as_ref(),
+        Some(&row),
+    )
+    .await;
+
+    Ok(Json(AlertSubscriptionResponse {
+        symbol,
+        subscription: Some(to_alert_subscription(&row)),
+    }))
+}
+
+/// Return a symbol to the default operator channels
+async fn delete_alert_subscription(
+    user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(symbol): Path<String>,
+) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, String)> {
+    let symbol = symbol.to_uppercase();
+    let before =
+        repositories::subscriptions::get_alert_subscription(&state.db, &user.username, &symbol)
+            .await
+            .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let deleted =
+        repositories::subscriptions::delete_alert_subscription(&state.db, &user.username, &symbol)
+            .await
+            .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    if deleted {
+        record_change(
+            &state,
+            &user.username,
+            "alerts_delete",
+            HISTORY_RESOURCE,
+            &symbol,
+            before.as_ref(),
+            None,
+        )
+        .await;
+        Ok(Json(serde_json::json!({ "success": true })))
+    } else {
+        Err((
+            axum::http::StatusCode::NOT_FOUND,
+            format!("No alert preferences for {}", symbol),
+        ))
+    }
+}
@@ -0,0 +1,306 @@
+//! Intraday score ticker
+//!
+//! While the market is open, the most liquid symbols are rescored every
+//! `SCORE_TICKER_INTERVAL_SECS` (default 300) with today's session bar built
+//! from the streaming ticks, and composite score changes are pushed to the
+//! event stream as `ScoreUpdate` messages, so the dashboard's score column
+//! moves during the session. The price stream (see
+//! [`crate::realtime_scoring`]) subscribes these symbols while the ticker is
+//! enabled, so their ticks are in the tick store.
+//!
+//! The rescoring is deliberately light: only the price-driven technical
+//! components (EMA, Fibonacci, volume, momentum) are recomputed. Order flow
+//! and broker components, fundamentals, sentiment and ML keep the values of
+//! the last stored score, and nothing is written to the database; the
+//! end-of-day recompute remains the score of record.
+
+use crate::routes::stocks::price_technical_input;
+use crate::routes::streaming::StreamMessage;
+use crate::AppState;
+use chrono::{DateTime, Duration, Utc};
+use jejakcuan_core::{
+    calculate_composite_score, ScoreWeights, TechnicalScoreBreakdown, TechnicalScoreEngine,
+    TechnicalWeights,
+};
+use jejakcuan_db::{repositories, StockPriceRow, StockScoreRow};
+use jejakcuan_technical::{session_date, OhlcvBar, TradeTick};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Most liquid symbols rescored each round
+const TICKER_SYMBOLS: usize = 30;
+
+/// Days of traded value used to rank symbols by liquidity
+const LIQUIDITY_LOOKBACK_DAYS: i64 = 20;
+
+/// Calendar days of daily prices behind the technical indicators
+pub(crate) const PRICE_DAYS: i64 = 200;
+
+/// Composite moves smaller than this since the last push are not sent
+pub(crate) const MIN_PUSHED_CHANGE: f64 = 0.05;
+
+/// A symbol's score with today's session so far
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IntradayScore {
+    pub technical: f64,
+    pub composite: f64,
+    /// Change from the stored composite score
+    pub composite_change: f64,
+}
+
+pub fn spawn_score_ticker(state: Arc<AppState>, every: std::time::Duration) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(every);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        // Composite score last pushed per symbol this session
+        let mut pushed: HashMap<String, f64> = HashMap::new();
+
+        loop {
+            interval.tick().await;
+            if !state.config.market_calendar.is_open(Utc::now()) {
+                pushed.clear();
+                continue;
+            }
+
+            match rescore_liquid_symbols(&state, &mut pushed).await {
+                Ok(sent) if sent > 0 => tracing::debug!("Score ticker pushed {} updates", sent),
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Score ticker failed: {}", e),
+            }
+        }
+    });
+}
+
+/// The most liquid symbols, which the ticker rescores
+pub(crate) async fn ticker_symbols(
+    state: &AppState,
+    now: DateTime<Utc>,
+) -> Result<Vec<String>, sqlx::Error> {
+    let mut symbols = repositories::prices::get_symbols_by_traded_value(
+        &state.db,
+        now - Duration::days(LIQUIDITY_LOOKBACK_DAYS),
+    )
+    .await?;
+    symbols.truncate(TICKER_SYMBOLS);
+    Ok(symbols)
+}
+
+/// Rescore the most liquid symbols and push the changed ones
+///
+/// Returns the number of updates pushed.
+async fn rescore_liquid_symbols(
+    state: &AppState,
+    pushed: &mut HashMap<String, f64>,
+) -> Result<usize, sqlx::Error> {
+    let now = Utc::now();
+    let today = session_date(now);
+
+    let mut sent = 0;
+    for symbol in ticker_symbols(state, now).await? {
+        let ticks = state.ticks.ticks(&symbol);
+        if ticks.last().map(|t| session_date(t.time)) != Some(today) {
+            continue;
+        }
+        let Some(stored) = repositories::scores::get_stock_score(&state.db, &symbol).await? else {
+            continue;
+        };
+        let prices = repositories::prices::get_price_history(
+            &state.db,
+            &symbol,
+            now - Duration::days(PRICE_DAYS),
+            now,
+        )
+        .await?;
+        let Some(score) = rescore(&stored, &bars_with_session(&prices, &ticks)) else {
+            continue;
+        };
+
+        let unchanged = pushed
+            .get(&symbol)
+            .is_some_and(|last| (last - score.composite).abs() < MIN_PUSHED_CHANGE);
+        if unchanged {
+            continue;
+        }
+        pushed.insert(symbol.clone(), score.composite);
+
+        // Sending only fails when no client is listening
+        let _ = state.streaming.broadcast(StreamMessage::ScoreUpdate {
+            symbol,
+            technical_score: score.technical,
+            fundamental_score: stored.fundamental_score.to_f64().unwrap_or(50.0),
+            composite_score: score.composite,
+            composite_change: score.composite_change,
+            timestamp: now.timestamp(),
+        });
+        sent += 1;
+    }
+    Ok(sent)
+}
+
+/// Daily bars with the session of `ticks` as the last bar
+///
+/// A daily bar already imported for that session is replaced by the one
+/// built from the ticks.
+pub fn bars_with_session(prices: &[StockPriceRow], ticks: &[TradeTick]) -> Vec<OhlcvBar> {
+    let session = ticks.first().map(|t| session_date(t.time));
+    let mut bars: Vec<OhlcvBar> = prices
+        .iter()
+        .filter(|p| Some(session_date(p.time)) != session)
+        .map(|p| OhlcvBar {
+            open: p.open,
+            high: p.high,
+            low: p.low,
+            close: p.close,
+            volume: p.volume,
+        })
+        .collect();
+
+    if let (Some(first), Some(last)) = (ticks.first(), ticks.last()) {
+        bars.push(OhlcvBar {
+            open: first.price,
+            high: ticks.iter().map(|t| t.price).max().unwrap_or(first.price),
+            low: ticks.iter().map(|t| t.price).min().unwrap_or(first.price),
+            close: last.price,
+            volume: ticks.iter().map(|t| t.volume).sum(),
+        });
+    }
+    bars
+}
+
+/// Rescore `stored` with `bars`, recomputing the price-driven components
+///
+/// `None` when the stored score has no technical breakdown to start from.
+pub fn rescore(stored: &StockScoreRow, bars: &[OhlcvBar]) -> Option<IntradayScore> {
+    let previous: TechnicalScoreBreakdown =
+        serde_json::from_value(stored.technical_breakdown.clone()?).ok()?;
+    let current = TechnicalScoreEngine::new().calculate(&price_technical_input(bars));
+
+    let weights = TechnicalWeights::default();
+    let technical = (stored.technical_score
+        + weights.ema * (current.ema_score - previous.ema_score)
+        + weights.fibonacci * (current.fibonacci_score - previous.fibonacci_score)
+        + weights.volume * (current.volume_score - previous.volume_score)
+        + weights.momentum * (current.momentum_score - previous.momentum_score))
+        .clamp(Decimal::ZERO, dec!(100))
+        .round_dp(2)
+        .to_f64()?;
+
+    let composite = calculate_composite_score(
+        technical,
+        stored.fundamental_score.to_f64()?,
+        stored.sentiment_score.to_f64()?,
+        stored.ml_score.to_f64()?,
+        &ScoreWeights::default(),
+    );
+    let composite_change = composite - stored.composite_score.to_f64()?;
+
+    Some(IntradayScore {
+        technical,
+        composite: (composite * 100.0).round() / 100.0,
+        composite_change: (composite_change * 100.0).round() / 100.0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, TimeZone};
+
+    fn daily(time: DateTime<Utc>, close: Decimal) -> StockPriceRow {
+        StockPriceRow {
+            time,
+            symbol: "BBCA".to_string(),
+            open: close,
+            high: close + dec!(50),
+            low: close - dec!(50),
+            close,
+            volume: 1_000_000,
+            value: None,
+            frequency: None,
+        }
+    }
+
+    fn tick(time: DateTime<Utc>, price: Decimal) -> TradeTick {
+        TradeTick {
+            time,
+            price,
+            volume: 50_000,
+            bid: None,
+            ask: None,
+        }
+    }
+
+    #[test]
+    fn test_session_ticks_move_the_stored_score() {
+        // 09:00 WIB sessions of a steady uptrend
+        let first = Utc.with_ymd_and_hms(2024, 1, 1, 2, 0, 0).unwrap();
+        let prices: Vec<StockPriceRow> = (0..80)
+            .map(|i| {
+                daily(
+                    first + Duration::days(i),
+                    dec!(9000) + Decimal::from(i * 10),
+                )
+            })
+            .collect();
+        let bars = bars_with_session(&prices, &[]);
+        assert_eq!(bars.len(), 80);
+
+        let breakdown = TechnicalScoreEngine::new().calculate(&price_technical_input(&bars));
+        let technical = breakdown.total_score;
+        let composite = calculate_composite_score(
+            technical.to_f64().unwrap(),
+            60.0,
+            50.0,
+            50.0,
+            &ScoreWeights::default(),
+        );
+        let stored = StockScoreRow {
+            time: prices.last().unwrap().time,
+            symbol: "BBCA".to_string(),
+            composite_score: Decimal::try_from(composite).unwrap(),
+            technical_score: technical,
+            fundamental_score: dec!(60),
+            sentiment_score: dec!(50),
+            ml_score: dec!(50),
+            technical_breakdown: serde_json::to_value(&breakdown).ok(),
+            fundamental_breakdown: None,
+            sentiment_breakdown: None,
+            ml_breakdown: None,
+            input_hash: None,
+        };
+        let unchanged = rescore(&stored, &bars).unwrap();
+        assert!(unchanged.composite_change.abs() < 0.01);
+
+        // Today the price breaks well below the trend
+        let open = first + Duration::days(80);
+        let ticks = [
+            tick(open, dec!(9800)),
+            tick(open + Duration::minutes(30), dec!(9100)),
+            tick(open + Duration::hours(2), dec!(8600)),
+        ];
+        let bars = bars_with_session(&prices, &ticks);
+        assert_eq!(bars.len(), 81);
+        let last = bars.last().unwrap();
+        assert_eq!(
+            (last.open, last.high, last.low, last.close, last.volume),
+            (dec!(9800), dec!(9800), dec!(8600), dec!(8600), 150_000)
+        );
+        let sell_off = rescore(&stored, &bars).unwrap();
+        assert!(sell_off.composite_change < 0.0);
+        assert!(sell_off.technical < technical.to_f64().unwrap());
+
+        // A daily bar of the same session is replaced, not duplicated
+        let mut imported = prices.clone();
+        imported.push(daily(open, dec!(9000)));
+        assert_eq!(bars_with_session(&imported, &ticks).len(), 81);
+
+        let bare = StockScoreRow {
+            technical_breakdown: None,
+            ..stored
+        };
+        assert!(rescore(&bare, &bars).is_none());
+    }
+}
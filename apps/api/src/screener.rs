@@ -0,0 +1,333 @@
+//! Screen candidates from the database
+//!
+//! Collects each active stock's latest score, financials and, when a screen
+//! asks for them, indicator values computed from recent daily bars. Shared
+//! by the screen endpoint and screen-based alerts.
+//!
+//! Custom indicators are evaluated on the longer history alert rules use
+//! ([`CUSTOM_INDICATOR_DAYS`]), so a screen and a rule agree on their values.
+//!
+//! The same candidates, without custom indicators, are stored after the
+//! close as indicator snapshots for bulk analysis reads.
+//!
+//! Saved screens are re-run once per session after the data refresh; when a
+//! symbol enters or leaves a screen's results its owner is told on the
+//! channels they set up.
+
+use crate::custom_indicators::{
+    load_custom_indicators, ohlcv_bars, unknown_custom_indicators, CUSTOM_INDICATOR_DAYS,
+};
+use crate::notification_retry;
+use crate::notifications::{Notification, NotificationMetadata, NotificationPriority};
+use crate::routes::notifications::user_recipients;
+use crate::AppState;
+use chrono::{DateTime, Duration, Utc};
+use jejakcuan_core::{
+    attach_sector_median_pe, diff_screen_results, run_screen, NotificationChannel, ScreenCandidate,
+    ScreenDiff, ScreenFilter,
+};
+use jejakcuan_db::{repositories, InsertIndicatorSnapshot, SavedScreenRow, StockPriceRow};
+use jejakcuan_technical::{
+    calculate_rsi14, calculate_rvol, detect_wyckoff_phase, session_date, IndicatorExpr,
+    WyckoffProfile,
+};
+use serde::Serialize;
+use sqlx::PgPool;
+use std::collections::{BTreeMap, HashMap};
+
+/// Calendar days of bars loaded for indicators, about 120 sessions
+const INDICATOR_LOOKBACK_DAYS: i64 = 180;
+
+/// Sessions averaged for relative volume
+const RVOL_PERIOD: usize = 20;
+
+/// Latest values of every active stock
+///
+/// The close and indicators (RSI, RVOL, Wyckoff phase, `custom`) are only
+/// filled when `with_prices` is set, since they need every symbol's price
+/// history.
+pub async fn load_screen_candidates(
+    pool: &PgPool,
+    with_prices: bool,
+    custom: &BTreeMap<String, IndicatorExpr>,
+) -> Result<Vec<ScreenCandidate>, sqlx::Error> {
+    let stocks = repositories::stocks::get_all_stocks(pool).await?;
+    let mut scores: HashMap<String, _> = repositories::scores::get_latest_scores(pool, i32::MAX)
+        .await?
+        .into_iter()
+        .map(|s| (s.symbol.clone(), s))
+        .collect();
+    let mut financials: HashMap<String, _> = repositories::stocks::get_all_latest_financials(pool)
+        .await?
+        .into_iter()
+        .map(|f| (f.symbol.clone(), f))
+        .collect();
+
+    let now = Utc::now();
+    let indicators_from = now - Duration::days(INDICATOR_LOOKBACK_DAYS);
+    let mut bars: HashMap<String, Vec<StockPriceRow>> = HashMap::new();
+    if with_prices {
+        let from = if custom.is_empty() {
+            indicators_from
+        } else {
+            now - Duration::days(CUSTOM_INDICATOR_DAYS)
+        };
+        for row in repositories::prices::get_universe_prices(pool, from).await? {
+            bars.entry(row.symbol.clone()).or_default().push(row);
+        }
+    }
+
+    let mut candidates: Vec<ScreenCandidate> = stocks
+        .into_iter()
+        .filter(|s| s.is_active)
+        .map(|stock| {
+            let score = scores.remove(&stock.symbol);
+            let financial = financials.remove(&stock.symbol);
+            let mut candidate = ScreenCandidate {
+                symbol: stock.symbol.clone(),
+                sector: stock.sector,
+                composite_score: score.as_ref().map(|s| s.composite_score),
+                technical_score: score.as_ref().map(|s| s.technical_score),
+                fundamental_score: score.as_ref().map(|s| s.fundamental_score),
+                pe_ratio: financial.as_ref().and_then(|f| f.pe_ratio),
+                pb_ratio: financial.as_ref().and_then(|f| f.pb_ratio),
+                roe: financial.as_ref().and_then(|f| f.roe),
+                ..Default::default()
+            };
+            if let Some(prices) = bars.get(&stock.symbol) {
+                attach_indicators(
+                    &mut candidate,
+                    prices,
+                    indicators_from,
+                    stock.market_cap,
+                    custom,
+                );
+            }
+            candidate
+        })
+        .collect();
+
+    attach_sector_median_pe(&mut candidates);
+    Ok(candidates)
+}
+
+/// Set the indicators of `candidate`
+///
+/// The built-in ones read the bars from `indicators_from` on, the custom
+/// ones every bar in `prices`.
+fn attach_indicators(
+    candidate: &mut ScreenCandidate,
+    prices: &[StockPriceRow],
+    indicators_from: DateTime<Utc>,
+    market_cap: Option<i64>,
+    custom: &BTreeMap<String, IndicatorExpr>,
+) {
+    candidate.custom_indicators = if custom.is_empty() {
+        BTreeMap::new()
+    } else {
+        let history = ohlcv_bars(prices);
+        custom
+            .iter()
+            .filter_map(|(name, expr)| Some((name.clone(), expr.latest(&history)?)))
+            .collect()
+    };
+
+    let start = prices.partition_point(|p| p.time < indicators_from);
+    let prices = &prices[start..];
+    let closes: Vec<_> = prices.iter().map(|p| p.close).collect();
+    let volumes: Vec<i64> = prices.iter().map(|p| p.volume).collect();
+    let ohlcv = ohlcv_bars(prices);
+
+    candidate.close = closes.last().copied();
+    candidate.rsi = calculate_rsi14(&closes).ok().and_then(|s| s.last_valid());
+    candidate.rvol = calculate_rvol(&volumes, RVOL_PERIOD)
+        .ok()
+        .and_then(|v| v.last().copied());
+    let wyckoff = WyckoffProfile::for_bars(&ohlcv, market_cap).config();
+    candidate.wyckoff_phase = detect_wyckoff_phase(&ohlcv, &wyckoff)
+        .ok()
+        .and_then(|a| serde_json::to_value(a.phase).ok())
+        .and_then(|v| v.as_str().map(str::to_string));
+}
+
+/// Recompute the indicator snapshots of every active stock
+pub async fn refresh_indicator_snapshots(pool: &PgPool) -> Result<u64, sqlx::Error> {
+    let snapshots: Vec<InsertIndicatorSnapshot> =
+        load_screen_candidates(pool, true, &BTreeMap::new())
+            .await?
+            .into_iter()
+            .map(|c| InsertIndicatorSnapshot {
+                symbol: c.symbol,
+                close: c.close,
+                rsi: c.rsi,
+                rvol: c.rvol,
+                wyckoff_phase: c.wyckoff_phase,
+            })
+            .collect();
+    repositories::indicator_snapshots::upsert_indicator_snapshots(pool, &snapshots, Utc::now())
+        .await
+}
+
+/// Custom indicators of `owner` that `filter` reads
+///
+/// Nothing is loaded when the filter reads no custom indicator.
+pub async fn screen_custom_indicators(
+    pool: &PgPool,
+    owner: &str,
+    filter: &ScreenFilter,
+) -> Result<BTreeMap<String, IndicatorExpr>, sqlx::Error> {
+    let names = filter.custom_indicators();
+    if names.is_empty() {
+        return Ok(BTreeMap::new());
+    }
+    let mut indicators = load_custom_indicators(pool, owner).await?;
+    indicators.retain(|name, _| names.contains(name.as_str()));
+    Ok(indicators)
+}
+
+/// Custom indicators `filter` reads, rejecting names `owner` has not defined
+pub async fn check_screen_custom_indicators(
+    pool: &PgPool,
+    owner: &str,
+    filter: &ScreenFilter,
+) -> Result<BTreeMap<String, IndicatorExpr>, (axum::http::StatusCode, String)> {
+    let indicators = screen_custom_indicators(pool, owner, filter)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let unknown = unknown_custom_indicators(&indicators, filter.custom_indicators());
+    if unknown.is_empty() {
+        Ok(indicators)
+    } else {
+        Err((
+            axum::http::StatusCode::BAD_REQUEST,
+            format!("Unknown custom indicator: {}", unknown.join(", ")),
+        ))
+    }
+}
+
+/// Symbols currently passing `filter` of `owner`, best composite score first
+pub async fn screen_symbols(
+    pool: &PgPool,
+    owner: &str,
+    filter: &ScreenFilter,
+) -> Result<Vec<String>, sqlx::Error> {
+    let custom = screen_custom_indicators(pool, owner, filter).await?;
+    let candidates = load_screen_candidates(pool, filter.needs_price_history(), &custom).await?;
+    Ok(run_screen(&candidates, filter)
+        .into_iter()
+        .map(|c| c.symbol.clone())
+        .collect())
+}
+
+/// Outcome of re-running the saved screens
+#[derive(Debug, Default, Serialize)]
+pub struct ScreenRunReport {
+    pub screens: usize,
+    /// Screens whose result set changed
+    pub changed: usize,
+    pub notifications_sent: usize,
+    /// Screens with a filter that no longer parses
+    pub invalid: usize,
+}
+
+/// Re-run the alerting screens not yet run for the session of `data_time`
+pub async fn rerun_saved_screens(
+    state: &AppState,
+    data_time: DateTime<Utc>,
+) -> Result<ScreenRunReport, sqlx::Error> {
+    let session = session_date(data_time);
+    let mut report = ScreenRunReport::default();
+
+    let screens: Vec<(SavedScreenRow, ScreenFilter)> =
+        repositories::saved_screens::get_alerting_saved_screens(&state.db)
+            .await?
+            .into_iter()
+            .filter(|row| row.last_run_at.is_none_or(|at| session_date(at) < session))
+            .filter_map(|row| match serde_json::from_value(row.filter.clone()) {
+                Ok(filter) => Some((row, filter)),
+                Err(e) => {
+                    tracing::warn!("Skipping invalid saved screen {}: {}", row.id, e);
+                    report.invalid += 1;
+                    None
+                }
+            })
+            .collect();
+    if screens.is_empty() {
+        return Ok(report);
+    }
+
+    let with_prices = screens.iter().any(|(_, f)| f.needs_price_history());
+    let candidates = load_screen_candidates(&state.db, with_prices, &BTreeMap::new()).await?;
+    // Screens reading custom indicators get candidates with their owner's
+    let mut owner_candidates: HashMap<String, Vec<ScreenCandidate>> = HashMap::new();
+    let channel_rows =
+        repositories::notification_channels::get_all_user_notification_channels(&state.db).await?;
+    let now = Utc::now();
+
+    for (row, filter) in &screens {
+        report.screens += 1;
+        let candidates = if filter.custom_indicators().is_empty() {
+            &candidates
+        } else {
+            if !owner_candidates.contains_key(&row.owner) {
+                let custom = load_custom_indicators(&state.db, &row.owner).await?;
+                let loaded = load_screen_candidates(&state.db, true, &custom).await?;
+                owner_candidates.insert(row.owner.clone(), loaded);
+            }
+            &owner_candidates[&row.owner]
+        };
+        let symbols: Vec<String> = run_screen(candidates, filter)
+            .into_iter()
+            .map(|c| c.symbol.clone())
+            .collect();
+        let diff = diff_screen_results(&row.last_symbols, &symbols);
+        repositories::saved_screens::record_saved_screen_run(&state.db, row.id, &symbols, now)
+            .await?;
+        if diff.is_empty() {
+            continue;
+        }
+
+        report.changed += 1;
+        for (channel, recipient_id) in user_recipients(state, &row.owner, &channel_rows) {
+            let notification = screen_change_notification(row, &diff, channel, recipient_id);
+            match notification_retry::deliver(state, &notification).await {
+                Ok(()) => report.notifications_sent += 1,
+                Err(e) => tracing::warn!("Failed to deliver screen {} change: {}", row.id, e),
+            }
+        }
+    }
+    Ok(report)
+}
+
+fn screen_change_notification(
+    screen: &SavedScreenRow,
+    diff: &ScreenDiff,
+    channel: NotificationChannel,
+    recipient_id: String,
+) -> Notification {
+    let mut lines = Vec::new();
+    if !diff.entered.is_empty() {
+        lines.push(format!("Entered: {}", diff.entered.join(", ")));
+    }
+    if !diff.left.is_empty() {
+        lines.push(format!("Left: {}", diff.left.join(", ")));
+    }
+
+    Notification {
+        recipient_id,
+        title: format!(
+            "Screen \"{}\": {} in, {} out",
+            screen.name,
+            diff.entered.len(),
+            diff.left.len()
+        ),
+        body: lines.join("\n"),
+        priority: NotificationPriority::Medium,
+        channel,
+        alert: None,
+        metadata: NotificationMetadata {
+            action_url: Some(format!("/screens/{}", screen.id)),
+            ..Default::default()
+        },
+    }
+}
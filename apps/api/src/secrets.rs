@@ -0,0 +1,112 @@
+//! Credential lookup across the environment and the encrypted store
+//!
+//! Environment variables win, so a deployment can always override what was
+//! set through the admin API. Secrets stored in the database are decrypted
+//! with the configured master key on each lookup and never cached.
+//! Notifier tokens and the price provider keys are the exception: those
+//! clients are built once, so their credentials are read into the config at
+//! startup.
+
+use crate::config::Config;
+use crate::AppState;
+use jejakcuan_db::repositories;
+use sqlx::PgPool;
+
+/// Notifier tokens that may be stored instead of set in the environment
+pub const NOTIFIER_SECRETS: &[&str] = &["TELEGRAM_BOT_TOKEN", "WHATSAPP_ACCESS_TOKEN"];
+
+/// Value of the credential normally read from `env_var`
+pub async fn resolve_secret(state: &AppState, env_var: &str) -> Option<String> {
+    if let Ok(value) = std::env::var(env_var) {
+        if !value.is_empty() {
+            return Some(value);
+        }
+    }
+    let cipher = state.config.secret_cipher.as_ref()?;
+    match repositories::stored_secrets::get_secret(&state.db, cipher, env_var).await {
+        Ok(value) => value.filter(|v| !v.is_empty()),
+        Err(e) => {
+            tracing::warn!("Failed to read stored secret {}: {}", env_var, e);
+            None
+        }
+    }
+}
+
+/// Fill credentials of clients built at startup that the environment left
+/// unset from the encrypted store
+pub async fn load_stored_credentials(db: &PgPool, config: &mut Config) {
+    let Some(cipher) = config.secret_cipher.clone() else {
+        return;
+    };
+    let slots = [
+        (NOTIFIER_SECRETS[0], &mut config.telegram_bot_token),
+        (NOTIFIER_SECRETS[1], &mut config.whatsapp_access_token),
+        ("TWELVEDATA_API_KEY", &mut config.twelvedata_api_key),
+        ("SECTORS_API_KEY", &mut config.sectors_api_key),
+    ];
+    for (name, slot) in slots {
+        if slot.is_some() {
+            continue;
+        }
+        match repositories::stored_secrets::get_secret(db, &cipher, name).await {
+            Ok(value) => *slot = value.filter(|v| !v.is_empty()),
+            Err(e) => tracing::warn!("Failed to read stored secret {}: {}", name, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use jejakcuan_db::{SecretCipher, SecretError};
+
+    const OLD_KEY: [u8; 32] = [7; 32];
+    const NEW_KEY: [u8; 32] = [9; 32];
+
+    #[test]
+    fn test_sealed_secret_round_trips_and_is_bound_to_its_name() {
+        let cipher = SecretCipher::new(&OLD_KEY, &[]).unwrap();
+        let sealed = cipher.seal("SECTORS_API_KEY", "sk-live-123").unwrap();
+
+        assert!(!sealed
+            .ciphertext
+            .windows(b"sk-live-123".len())
+            .any(|w| w == b"sk-live-123"));
+        assert_eq!(
+            cipher.open("SECTORS_API_KEY", &sealed).unwrap(),
+            "sk-live-123"
+        );
+        // Copied onto another row, the ciphertext no longer opens
+        assert!(matches!(
+            cipher.open("TELEGRAM_BOT_TOKEN", &sealed),
+            Err(SecretError::Decrypt)
+        ));
+        // Fresh nonce per seal
+        assert_ne!(
+            cipher.seal("SECTORS_API_KEY", "sk-live-123").unwrap().nonce,
+            sealed.nonce
+        );
+    }
+
+    #[test]
+    fn test_rotation_keeps_retired_keys_readable() {
+        let old = SecretCipher::new(&OLD_KEY, &[]).unwrap();
+        let sealed = old.seal("TWELVEDATA_API_KEY", "td-key").unwrap();
+
+        let rotated = SecretCipher::new(&NEW_KEY, &[&OLD_KEY]).unwrap();
+        assert!(rotated.needs_rotation(&sealed));
+        assert_eq!(
+            rotated.open("TWELVEDATA_API_KEY", &sealed).unwrap(),
+            "td-key"
+        );
+        let resealed = rotated.seal("TWELVEDATA_API_KEY", "td-key").unwrap();
+        assert!(!rotated.needs_rotation(&resealed));
+
+        // Once the old key is dropped, unrotated secrets are unreadable
+        let new_only = SecretCipher::new(&NEW_KEY, &[]).unwrap();
+        assert!(matches!(
+            new_only.open("TWELVEDATA_API_KEY", &sealed),
+            Err(SecretError::UnknownKey(_))
+        ));
+        assert!(SecretCipher::parse("c2hvcnQ=", "").is_err());
+    }
+}
@@ -0,0 +1,154 @@
+//! Major shareholder ingestion
+//!
+//! Scrapes each listed stock's shareholder disclosures (KSEI, falling back
+//! to the IDX company profile) into one staging batch per run. Nothing
+//! reaches the live `shareholdings` table until an admin approves the batch
+//! after reviewing its diff.
+
+use crate::routes::jobs::Job;
+use crate::AppState;
+use chrono::Utc;
+use jejakcuan_data_sources::{ShareholderType, ShareholdingScraper, ShareholdingSnapshot};
+use jejakcuan_db::repositories::{self, StagedShareholding, StagingDataset};
+use sqlx::PgPool;
+use std::sync::Arc;
+
+/// Job source id, shared with the data source registry entry
+pub const SHAREHOLDINGS_JOB: &str = "idx_shareholding";
+
+/// Start a background job staging every listed stock's major shareholders
+pub async fn spawn_ingest(state: &Arc<AppState>) -> Job {
+    let pool = state.db.clone();
+    state
+        .job_manager
+        .spawn_task(
+            SHAREHOLDINGS_JOB.to_string(),
+            "IDX Shareholding".to_string(),
+            "stage major shareholders from KSEI and IDX for review".to_string(),
+            async move { stage_shareholdings(&pool, &ShareholdingScraper::new()).await },
+        )
+        .await
+}
+
+async fn stage_shareholdings(
+    pool: &PgPool,
+    scraper: &ShareholdingScraper,
+) -> Result<String, String> {
+    let stocks = repositories::stocks::get_all_stocks(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    let today = Utc::now().date_naive();
+
+    let mut batch = None;
+    let mut staged = 0;
+    let mut missing = 0;
+    let mut errors = 0;
+    for stock in stocks.iter().filter(|s| s.is_active) {
+        let snapshot = match scraper.get_snapshot(&stock.symbol, today).await {
+            Ok(Some((snapshot, _))) => snapshot,
+            Ok(None) => {
+                missing += 1;
+                continue;
+            }
+            Err(e) => {
+                tracing::debug!("Shareholders of {} unavailable: {}", stock.symbol, e);
+                errors += 1;
+                continue;
+            }
+        };
+        let rows = staged_rows(&snapshot);
+        if rows.is_empty() {
+            missing += 1;
+            continue;
+        }
+
+        let batch_id = match batch {
+            Some(id) => id,
+            None => {
+                let created = repositories::staging::create_staging_batch(
+                    pool,
+                    SHAREHOLDINGS_JOB,
+                    StagingDataset::Shareholdings,
+                )
+                .await
+                .map_err(|e| e.to_string())?;
+                *batch.insert(created.id)
+            }
+        };
+        staged += repositories::staging::insert_staged_shareholdings(pool, batch_id, &rows)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(match batch {
+        Some(id) => format!(
+            "{} shareholder rows staged in batch {} for review ({} stocks without data, {} failed)",
+            staged, id, missing, errors
+        ),
+        None => format!(
+            "No shareholder data found ({} stocks without data, {} failed)",
+            missing, errors
+        ),
+    })
+}
+
+fn shareholder_type(kind: ShareholderType) -> &'static str {
+    match kind {
+        ShareholderType::Insider => "insider",
+        ShareholderType::Institution => "institution",
+        ShareholderType::Public => "public",
+        ShareholderType::Government => "government",
+        ShareholderType::Other => "other",
+    }
+}
+
+/// Staging rows of a snapshot; changes are left for the batch diff to show
+fn staged_rows(snapshot: &ShareholdingSnapshot) -> Vec<StagedShareholding> {
+    snapshot
+        .shareholders
+        .iter()
+        .filter(|s| !s.name.trim().is_empty())
+        .map(|s| StagedShareholding {
+            symbol: snapshot.symbol.clone(),
+            reported_date: snapshot.report_date,
+            shareholder_name: s.name.trim().to_string(),
+            shareholder_type: Some(shareholder_type(s.shareholder_type).to_string()),
+            shares_held: s.shares_held,
+            percentage: s.percentage,
+            change_shares: 0,
+            change_percentage: Default::default(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use jejakcuan_data_sources::Shareholder;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_staged_rows() {
+        let snapshot = ShareholdingSnapshot::new(
+            "BBCA".to_string(),
+            NaiveDate::from_ymd_opt(2025, 6, 30).unwrap(),
+            1_000_000,
+            vec![
+                Shareholder::new(
+                    "PT Dwimuria Investama Andalan ".to_string(),
+                    550_000,
+                    dec!(55),
+                ),
+                Shareholder::new("Masyarakat Publik".to_string(), 450_000, dec!(45)),
+                Shareholder::new("  ".to_string(), 0, dec!(0)),
+            ],
+        );
+        let rows = staged_rows(&snapshot);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].shareholder_name, "PT Dwimuria Investama Andalan");
+        assert_eq!(rows[0].shareholder_type.as_deref(), Some("other"));
+        assert_eq!(rows[1].shareholder_type.as_deref(), Some("public"));
+        assert_eq!(rows[1].percentage, dec!(45));
+    }
+}
@@ -0,0 +1,221 @@
+//! Stock universe sync from the IDX listed company table
+//!
+//! Replaces the Python `sync-stocks` scraper: every listed stock is upserted
+//! into `stocks` with its IDX sector and subsector, translated from the
+//! Indonesian IDX-IC names to the English taxonomy the scoring profiles and
+//! screens use. Names without a translation leave the stored value alone.
+
+use crate::routes::jobs::Job;
+use crate::AppState;
+use jejakcuan_data_sources::{IdxClient, ListedCompany};
+use jejakcuan_db::repositories;
+use std::sync::Arc;
+
+/// Job source id, shared with the data source registry entry
+pub const UNIVERSE_SYNC_JOB: &str = "idx_listed_companies";
+
+/// Start a background job syncing the stock universe
+pub async fn spawn_sync(state: &Arc<AppState>) -> Job {
+    let pool = state.db.clone();
+    state
+        .job_manager
+        .spawn_task(
+            UNIVERSE_SYNC_JOB.to_string(),
+            "IDX Listed Companies".to_string(),
+            "fetch listed companies from IDX and upsert stocks".to_string(),
+            async move { sync_universe(&pool, &IdxClient::new()).await },
+        )
+        .await
+}
+
+async fn sync_universe(pool: &sqlx::PgPool, client: &IdxClient) -> Result<String, String> {
+    let companies = client
+        .get_listed_companies()
+        .await
+        .map_err(|e| e.to_string())?;
+    if companies.is_empty() {
+        return Err("IDX returned no listed companies".to_string());
+    }
+
+    let mut synced = 0;
+    let mut errors = Vec::new();
+    for company in &companies {
+        let listed = company.to_listed_symbol();
+        if !valid_symbol(&listed.symbol) {
+            continue;
+        }
+        let name = listed.name.as_deref().unwrap_or(&listed.symbol);
+        match upsert(pool, company, &listed.symbol, name).await {
+            Ok(()) => synced += 1,
+            Err(e) => errors.push(format!("{}: {}", listed.symbol, e)),
+        }
+    }
+
+    if synced == 0 {
+        return Err(format!("No stocks synced: {}", errors.join("; ")));
+    }
+    let mut summary = format!("{} of {} listed companies synced", synced, companies.len());
+    if !errors.is_empty() {
+        summary.push_str(&format!(
+            " ({} failed: {})",
+            errors.len(),
+            errors.join("; ")
+        ));
+    }
+    Ok(summary)
+}
+
+async fn upsert(
+    pool: &sqlx::PgPool,
+    company: &ListedCompany,
+    symbol: &str,
+    name: &str,
+) -> Result<(), sqlx::Error> {
+    repositories::stocks::upsert_stock(
+        pool,
+        symbol,
+        name,
+        company.sector.as_deref().and_then(english_sector),
+        english_subsector(company),
+    )
+    .await
+    .map(|_| ())
+}
+
+/// IDX-IC sectors and their names in the stock taxonomy
+const SECTORS: &[(&str, &str)] = &[
+    ("Energi", "Energy"),
+    ("Barang Baku", "Basic Materials"),
+    ("Perindustrian", "Industrial"),
+    ("Barang Konsumen Primer", "Consumer Goods"),
+    ("Barang Konsumen Non-Primer", "Consumer Cyclicals"),
+    ("Kesehatan", "Healthcare"),
+    ("Keuangan", "Financials"),
+    ("Properti & Real Estat", "Property"),
+    ("Teknologi", "Technology"),
+    ("Infrastruktur", "Infrastructure"),
+    ("Transportasi & Logistik", "Transportation"),
+];
+
+/// IDX-IC subsectors and their names in the stock taxonomy
+const SUBSECTORS: &[(&str, &str)] = &[
+    ("Minyak, Gas & Batu Bara", "Oil, Gas & Coal"),
+    ("Energi Alternatif", "Alternative Energy"),
+    ("Barang Baku", "Basic Materials"),
+    ("Barang Perindustrian", "Industrial Goods"),
+    ("Jasa Perindustrian", "Industrial Services"),
+    ("Perusahaan Induk Multi Sektor", "Multi-sector Holdings"),
+    ("Perdagangan Ritel Barang Primer", "Staples Retail"),
+    ("Makanan & Minuman", "Food & Beverage"),
+    ("Rokok", "Tobacco"),
+    ("Produk Rumah Tangga Tidak Tahan Lama", "Household Products"),
+    ("Otomotif & Komponen Otomotif", "Automotive"),
+    ("Barang Rumah Tangga", "Household Goods"),
+    ("Barang Rekreasi", "Leisure Goods"),
+    ("Pakaian & Barang Mewah", "Apparel & Luxury Goods"),
+    ("Jasa Konsumen", "Consumer Services"),
+    ("Media & Hiburan", "Media & Entertainment"),
+    ("Perdagangan Ritel", "Retail"),
+    (
+        "Peralatan & Layanan Kesehatan",
+        "Healthcare Equipment & Services",
+    ),
+    ("Farmasi & Riset Kesehatan", "Pharmaceutical"),
+    ("Bank", "Bank"),
+    ("Jasa Pembiayaan", "Financing Services"),
+    ("Jasa Investasi", "Investment Services"),
+    ("Asuransi", "Insurance"),
+    ("Perusahaan Induk Investasi", "Investment Holdings"),
+    ("Properti & Real Estat", "Real Estate"),
+    ("Perangkat Lunak & Jasa TI", "Software & IT Services"),
+    (
+        "Perangkat Keras & Peralatan Teknologi",
+        "Technology Hardware",
+    ),
+    ("Utilitas", "Utilities"),
+    ("Telekomunikasi", "Telecom Services"),
+    ("Infrastruktur Transportasi", "Transport Infrastructure"),
+    ("Konstruksi Bangunan", "Construction"),
+    ("Transportasi", "Transportation"),
+    ("Logistik & Pengantaran", "Logistics"),
+];
+
+/// IDX-IC industries the scoring profiles key on, stored as the subsector
+const PROFILE_INDUSTRIES: &[(&str, &str)] = &[("Batu Bara", "Coal"), ("Logam & Mineral", "Metal")];
+
+fn translate(table: &[(&str, &'static str)], name: &str) -> Option<&'static str> {
+    let name = name.trim();
+    table
+        .iter()
+        .find(|(idx, _)| idx.eq_ignore_ascii_case(name))
+        .map(|(_, english)| *english)
+}
+
+/// Taxonomy sector of an IDX-IC sector; `None` when it has no translation
+fn english_sector(sektor: &str) -> Option<&'static str> {
+    translate(SECTORS, sektor)
+}
+
+/// Taxonomy subsector of a listed company
+///
+/// Industries a scoring profile selects on, like coal, win over the broader
+/// IDX-IC subsector.
+fn english_subsector(company: &ListedCompany) -> Option<&'static str> {
+    company
+        .industry
+        .as_deref()
+        .and_then(|industry| translate(PROFILE_INDUSTRIES, industry))
+        .or_else(|| {
+            company
+                .subsector
+                .as_deref()
+                .and_then(|subsector| translate(SUBSECTORS, subsector))
+        })
+}
+
+/// IDX stock codes are four letters; rights and warrants carry suffixes
+fn valid_symbol(symbol: &str) -> bool {
+    symbol.len() == 4 && symbol.chars().all(|c| c.is_ascii_uppercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_symbol() {
+        assert!(valid_symbol("BBCA"));
+        assert!(!valid_symbol("BBCA-R"));
+        assert!(!valid_symbol("BBC"));
+        assert!(!valid_symbol("bbca"));
+    }
+
+    #[test]
+    fn test_taxonomy_translation() {
+        let company = |sector: &str, subsector: &str, industry: &str| ListedCompany {
+            code: "TEST".to_string(),
+            name: "PT Test Tbk.".to_string(),
+            sector: Some(sector.to_string()),
+            subsector: Some(subsector.to_string()),
+            industry: Some(industry.to_string()),
+            board: None,
+            listing_date: None,
+        };
+
+        let bank = company("Keuangan", "Bank", "Bank");
+        assert_eq!(english_sector("Keuangan"), Some("Financials"));
+        assert_eq!(english_subsector(&bank), Some("Bank"));
+
+        // The coal industry selects the mining profile's subsector
+        let coal = company("Energi", "Minyak, Gas & Batu Bara", "Batu Bara");
+        assert_eq!(english_sector("Energi"), Some("Energy"));
+        assert_eq!(english_subsector(&coal), Some("Coal"));
+
+        let software = company("Teknologi", "Perangkat Lunak & Jasa TI", "Jasa TI");
+        assert_eq!(english_subsector(&software), Some("Software & IT Services"));
+
+        // Unknown names are not written over the stored taxonomy
+        assert_eq!(english_sector("Produk Investasi Tercatat"), None);
+        assert_eq!(english_subsector(&company("?", "?", "?")), None);
+    }
+}
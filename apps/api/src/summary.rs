@@ -0,0 +1,243 @@
+//! Natural-language stock summaries
+//!
+//! Renders a short paragraph from structured analysis facts for voice
+//! assistants and mobile cards. Sentences come from per-language templates,
+//! so wording can change without touching the analysis code.
+
+use minijinja::Environment;
+use serde::Serialize;
+
+/// Output language of a summary
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SummaryLanguage {
+    /// Bahasa Indonesia
+    Id,
+    En,
+}
+
+impl SummaryLanguage {
+    /// Parse a language code, accepting "id"/"in" and "en"
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code.trim().to_lowercase().as_str() {
+            "id" | "in" | "id-id" => Some(Self::Id),
+            "en" | "en-us" | "en-gb" => Some(Self::En),
+            _ => None,
+        }
+    }
+
+    fn template_name(&self) -> &'static str {
+        match self {
+            Self::Id => "id",
+            Self::En => "en",
+        }
+    }
+}
+
+/// Net broker positioning over the lookback window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BrokerStance {
+    Accumulation,
+    Distribution,
+    Balanced,
+}
+
+/// Risks the summary can call out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SummaryRisk {
+    Overbought,
+    ValuationStretched,
+    BrokerDistribution,
+    ForeignSelling,
+    SuspiciousActivity,
+    BelowIchimokuCloud,
+}
+
+/// Structured inputs for a summary
+#[derive(Debug, Clone, Serialize)]
+pub struct SummaryFacts {
+    pub symbol: String,
+    pub name: String,
+    pub price: f64,
+    /// Composite score (0-100)
+    pub score: Option<f64>,
+    /// One of "strong_buy", "buy", "hold", "sell", "strong_sell"
+    pub signal: Option<String>,
+    pub target_price: Option<f64>,
+    pub upside_pct: Option<f64>,
+    pub broker_stance: Option<BrokerStance>,
+    /// Broker code leading the net buying (or selling, under distribution)
+    pub lead_broker: Option<String>,
+    pub foreign_net: Option<f64>,
+    pub risks: Vec<SummaryRisk>,
+}
+
+const TEMPLATE_EN: &str = "\
+{{ name }} ({{ symbol }}) last traded at {{ price | rupiah }}.
+{% if score is not none %} Its composite score is {{ score | round | int }} out of 100{% if signal %}, which the system reads as {{ signal | signal_label }}{% endif %}.
+{% elif signal %} The system signal is {{ signal | signal_label }}.{% endif %}
+{% if target_price is not none and upside_pct is not none %} The model target is {{ target_price | rupiah }}, {{ upside_pct | abs | pct }} {% if upside_pct >= 0 %}above{% else %}below{% endif %} the current price.{% endif %}
+{% if broker_stance == 'accumulation' %} Brokers have been accumulating{% if lead_broker %}, led by {{ lead_broker }}{% endif %}.
+{% elif broker_stance == 'distribution' %} Brokers have been distributing{% if lead_broker %}, with {{ lead_broker }} the heaviest seller{% endif %}.
+{% elif broker_stance == 'balanced' %} Broker flows are balanced.{% endif %}
+{% if foreign_net is not none and foreign_net > 0 %} Foreign investors are net buyers.{% elif foreign_net is not none and foreign_net < 0 %} Foreign investors are net sellers.{% endif %}
+{% if risks %} Key risks: {{ risks | map('risk_label') | join(', ') }}.{% else %} No major risks are flagged.{% endif %}";
+
+const TEMPLATE_ID: &str = "\
+{{ name }} ({{ symbol }}) terakhir diperdagangkan di {{ price | rupiah }}.
+{% if score is not none %} Skor gabungannya {{ score | round | int }} dari 100{% if signal %}, dengan sinyal {{ signal | signal_label }}{% endif %}.
+{% elif signal %} Sinyal sistem adalah {{ signal | signal_label }}.{% endif %}
+{% if target_price is not none and upside_pct is not none %} Target model di {{ target_price | rupiah }}, {{ upside_pct | abs | pct }} {% if upside_pct >= 0 %}di atas{% else %}di bawah{% endif %} harga saat ini.{% endif %}
+{% if broker_stance == 'accumulation' %} Broker sedang melakukan akumulasi{% if lead_broker %}, dipimpin oleh {{ lead_broker }}{% endif %}.
+{% elif broker_stance == 'distribution' %} Broker sedang melakukan distribusi{% if lead_broker %}, dengan {{ lead_broker }} sebagai penjual terbesar{% endif %}.
+{% elif broker_stance == 'balanced' %} Aliran broker seimbang.{% endif %}
+{% if foreign_net is not none and foreign_net > 0 %} Investor asing tercatat net beli.{% elif foreign_net is not none and foreign_net < 0 %} Investor asing tercatat net jual.{% endif %}
+{% if risks %} Risiko utama: {{ risks | map('risk_label') | join(', ') }}.{% else %} Tidak ada risiko besar yang terdeteksi.{% endif %}";
+
+fn signal_label(lang: SummaryLanguage, signal: &str) -> &'static str {
+    match (lang, signal) {
+        (SummaryLanguage::En, "strong_buy") => "a strong buy",
+        (SummaryLanguage::En, "buy") => "a buy",
+        (SummaryLanguage::En, "sell") => "a sell",
+        (SummaryLanguage::En, "strong_sell") => "a strong sell",
+        (SummaryLanguage::En, _) => "a hold",
+        (SummaryLanguage::Id, "strong_buy") => "beli kuat",
+        (SummaryLanguage::Id, "buy") => "beli",
+        (SummaryLanguage::Id, "sell") => "jual",
+        (SummaryLanguage::Id, "strong_sell") => "jual kuat",
+        (SummaryLanguage::Id, _) => "tahan",
+    }
+}
+
+fn risk_label(lang: SummaryLanguage, risk: &str) -> &'static str {
+    match (lang, risk) {
+        (SummaryLanguage::En, "overbought") => "overbought momentum",
+        (SummaryLanguage::En, "valuation_stretched") => "a stretched valuation",
+        (SummaryLanguage::En, "broker_distribution") => "broker distribution",
+        (SummaryLanguage::En, "foreign_selling") => "foreign selling",
+        (SummaryLanguage::En, "suspicious_activity") => "unusual broker activity",
+        (SummaryLanguage::En, "below_ichimoku_cloud") => "a price below the Ichimoku cloud",
+        (SummaryLanguage::En, _) => "other risks",
+        (SummaryLanguage::Id, "overbought") => "momentum jenuh beli",
+        (SummaryLanguage::Id, "valuation_stretched") => "valuasi yang sudah mahal",
+        (SummaryLanguage::Id, "broker_distribution") => "distribusi broker",
+        (SummaryLanguage::Id, "foreign_selling") => "aksi jual asing",
+        (SummaryLanguage::Id, "suspicious_activity") => "aktivitas broker yang tidak wajar",
+        (SummaryLanguage::Id, "below_ichimoku_cloud") => "harga di bawah awan Ichimoku",
+        (SummaryLanguage::Id, _) => "risiko lainnya",
+    }
+}
+
+/// Group thousands with the language's separator ("9.875" or "9,875")
+fn group_thousands(value: f64, separator: char) -> String {
+    let digits = format!("{:.0}", value.abs());
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            grouped.push(separator);
+        }
+        grouped.push(c);
+    }
+    if value < 0.0 {
+        grouped.insert(0, '-');
+    }
+    grouped
+}
+
+fn environment(lang: SummaryLanguage) -> Environment<'static> {
+    let mut env = Environment::new();
+    env.add_template("en", TEMPLATE_EN)
+        .expect("English summary template is valid");
+    env.add_template("id", TEMPLATE_ID)
+        .expect("Indonesian summary template is valid");
+
+    let (thousands, decimal) = match lang {
+        SummaryLanguage::Id => ('.', ','),
+        SummaryLanguage::En => (',', '.'),
+    };
+    env.add_filter("rupiah", move |value: f64| {
+        format!("Rp {}", group_thousands(value, thousands))
+    });
+    env.add_filter("pct", move |value: f64| {
+        format!("{:.1}%", value).replace('.', &decimal.to_string())
+    });
+    env.add_filter("signal_label", move |signal: String| {
+        signal_label(lang, &signal)
+    });
+    env.add_filter("risk_label", move |risk: String| risk_label(lang, &risk));
+    env
+}
+
+/// Render a spoken-style summary paragraph
+pub fn render_summary(
+    facts: &SummaryFacts,
+    lang: SummaryLanguage,
+) -> Result<String, minijinja::Error> {
+    let env = environment(lang);
+    let template = env.get_template(lang.template_name())?;
+    let rendered = template.render(facts)?;
+
+    // Templates break lines for readability; the output is one paragraph
+    Ok(rendered.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn facts() -> SummaryFacts {
+        SummaryFacts {
+            symbol: "BBCA".to_string(),
+            name: "Bank Central Asia".to_string(),
+            price: 9875.0,
+            score: Some(72.4),
+            signal: Some("buy".to_string()),
+            target_price: Some(11000.0),
+            upside_pct: Some(11.39),
+            broker_stance: Some(BrokerStance::Accumulation),
+            lead_broker: Some("BK".to_string()),
+            foreign_net: Some(1_500_000_000.0),
+            risks: vec![SummaryRisk::ValuationStretched],
+        }
+    }
+
+    #[test]
+    fn test_render_english_summary() {
+        let text = render_summary(&facts(), SummaryLanguage::En).unwrap();
+
+        assert!(text.starts_with("Bank Central Asia (BBCA) last traded at Rp 9,875."));
+        assert!(text.contains("composite score is 72 out of 100, which the system reads as a buy."));
+        assert!(text.contains("11.4% above the current price"));
+        assert!(text.contains("accumulating, led by BK."));
+        assert!(text.ends_with("Key risks: a stretched valuation."));
+        assert!(!text.contains('\n'));
+    }
+
+    #[test]
+    fn test_render_indonesian_summary_without_optional_facts() {
+        let mut facts = facts();
+        facts.score = None;
+        facts.target_price = None;
+        facts.broker_stance = Some(BrokerStance::Distribution);
+        facts.foreign_net = Some(-2.0);
+        facts.risks.clear();
+
+        let text = render_summary(&facts, SummaryLanguage::Id).unwrap();
+
+        assert!(text.contains("di Rp 9.875."));
+        assert!(text.contains("Sinyal sistem adalah beli."));
+        assert!(!text.contains("Target model"));
+        assert!(text.contains("distribusi, dengan BK sebagai penjual terbesar."));
+        assert!(text.contains("net jual"));
+        assert!(text.ends_with("Tidak ada risiko besar yang terdeteksi."));
+    }
+
+    #[test]
+    fn test_language_codes() {
+        assert_eq!(SummaryLanguage::from_code("ID"), Some(SummaryLanguage::Id));
+        assert_eq!(SummaryLanguage::from_code("en"), Some(SummaryLanguage::En));
+        assert_eq!(SummaryLanguage::from_code("fr"), None);
+    }
+}
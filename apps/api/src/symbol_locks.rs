@@ -0,0 +1,85 @@
+//! Per-symbol locks
+//!
+//! A dashboard refresh fires the same full analysis for one symbol from
+//! several tabs at once. Serializing work per symbol keeps those requests from
+//! running the same queries side by side, while different symbols still run in
+//! parallel.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard};
+
+/// Idle locks are pruned once the map grows past this
+const PRUNE_THRESHOLD: usize = 256;
+
+/// One async lock per symbol, created on first use
+#[derive(Default)]
+pub struct SymbolLocks {
+    locks: Mutex<HashMap<String, Arc<AsyncMutex<()>>>>,
+}
+
+impl SymbolLocks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wait up to `wait` for the symbol's lock
+    ///
+    /// Returns `None` on timeout so callers can go ahead unserialized rather
+    /// than block behind a slow request.
+    pub async fn lock(&self, symbol: &str, wait: Duration) -> Option<OwnedMutexGuard<()>> {
+        let lock = self.entry(symbol);
+        tokio::time::timeout(wait, lock.lock_owned()).await.ok()
+    }
+
+    fn entry(&self, symbol: &str) -> Arc<AsyncMutex<()>> {
+        let mut locks = self.locks.lock().unwrap_or_else(|e| e.into_inner());
+        if locks.len() > PRUNE_THRESHOLD {
+            // Only the map holds a reference: nobody is waiting or working
+            locks.retain(|_, lock| Arc::strong_count(lock) > 1);
+        }
+        locks
+            .entry(symbol.to_uppercase())
+            .or_insert_with(|| Arc::new(AsyncMutex::new(())))
+            .clone()
+    }
+
+    /// Number of symbols with a lock entry
+    pub fn len(&self) -> usize {
+        self.locks.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_same_symbol_is_serialized() {
+        let locks = SymbolLocks::new();
+        let held = locks.lock("BBCA", Duration::from_millis(10)).await;
+        assert!(held.is_some());
+
+        // Same symbol (any case) waits, other symbols do not
+        assert!(locks
+            .lock("bbca", Duration::from_millis(10))
+            .await
+            .is_none());
+        assert!(locks
+            .lock("TLKM", Duration::from_millis(10))
+            .await
+            .is_some());
+
+        drop(held);
+        assert!(locks
+            .lock("BBCA", Duration::from_millis(10))
+            .await
+            .is_some());
+        assert_eq!(locks.len(), 2);
+    }
+}
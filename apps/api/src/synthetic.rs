@@ -0,0 +1,509 @@
+//! Synthetic market data for load testing
+//!
+//! Generates a reproducible universe without production data: stocks spread
+//! over the IDX-IC sectors, daily OHLCV on trading days and per-broker flows.
+//! Returns combine a market factor, a sector factor and stock-specific noise,
+//! so correlations, sector rotation and momentum rankings have structure to
+//! find. Prices sit on the IDX tick ladder and volumes are whole lots.
+//!
+//! The same seed always produces the same data. Synthetic symbols start
+//! with `SYNTHETIC_PREFIX` and the composite index is written with the
+//! `synthetic` source, so a seeded database is easy to recognise.
+//!
+//! Built only with the `synthetic` feature, which the seeding and load-test
+//! binaries require; the server never links it.
+
+use chrono::{Duration, NaiveDate, Utc};
+use jejakcuan_core::{round_to_tick, MarketCalendar, TickRounding, LOT_SIZE};
+use jejakcuan_data_sources::MacroIndicator;
+use jejakcuan_db::{repositories, InsertBrokerSummary, InsertPrice};
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use sqlx::PgPool;
+
+/// Start of every synthetic symbol; no IDX listing is six letters
+pub const SYNTHETIC_PREFIX: &str = "SYN";
+
+/// Source recorded for generated rows
+pub const SYNTHETIC_SOURCE: &str = "synthetic";
+
+const SECTORS: [&str; 11] = [
+    "Financials",
+    "Energy",
+    "Basic Materials",
+    "Industrials",
+    "Consumer Non-Cyclicals",
+    "Consumer Cyclicals",
+    "Healthcare",
+    "Properties & Real Estate",
+    "Technology",
+    "Infrastructures",
+    "Transportation & Logistic",
+];
+
+/// Broker codes seeded by the initial migration, plus large retail brokers
+const BROKERS: [&str; 21] = [
+    "BK", "KZ", "CS", "AK", "GW", "DP", "RX", "ZP", "CC", "SQ", "NI", "OD", "HP", "KI", "DX", "IF",
+    "LG", "YP", "PD", "XL", "XC",
+];
+
+/// Size of the generated universe
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyntheticConfig {
+    pub symbols: usize,
+    /// Calendar years of daily history ending yesterday
+    pub years: u32,
+    /// Brokers reporting flows per stock and session; 0 skips broker flows
+    pub brokers_per_day: usize,
+    pub seed: u64,
+}
+
+impl Default for SyntheticConfig {
+    fn default() -> Self {
+        Self {
+            symbols: 300,
+            years: 3,
+            brokers_per_day: 6,
+            seed: 42,
+        }
+    }
+}
+
+/// SplitMix64: small, fast and identical on every platform
+#[derive(Debug, Clone)]
+pub struct SyntheticRng(u64);
+
+impl SyntheticRng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in [0, 1)
+    pub fn uniform(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    pub fn range(&mut self, low: f64, high: f64) -> f64 {
+        low + (high - low) * self.uniform()
+    }
+
+    /// Standard normal (Box-Muller)
+    pub fn normal(&mut self) -> f64 {
+        let u1 = self.uniform().max(f64::MIN_POSITIVE);
+        let u2 = self.uniform();
+        (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+    }
+
+    pub fn index(&mut self, len: usize) -> usize {
+        (self.next_u64() % len as u64) as usize
+    }
+}
+
+/// A generated listing and its return characteristics
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SyntheticStock {
+    pub symbol: String,
+    pub name: String,
+    pub sector: &'static str,
+    pub initial_price: f64,
+    /// Sensitivity to the market factor
+    pub beta: f64,
+    /// Daily stock-specific volatility
+    pub volatility: f64,
+    /// Average lots traded per session
+    pub average_lots: f64,
+    /// Broker that tends to buy this stock
+    pub accumulator: &'static str,
+}
+
+/// One generated daily bar
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntheticBar {
+    pub date: NaiveDate,
+    pub open: Decimal,
+    pub high: Decimal,
+    pub low: Decimal,
+    pub close: Decimal,
+    pub volume: i64,
+}
+
+/// One broker's side of a generated session
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntheticBrokerFlow {
+    pub date: NaiveDate,
+    pub broker_code: &'static str,
+    pub buy_volume: i64,
+    pub sell_volume: i64,
+    pub buy_value: Decimal,
+    pub sell_value: Decimal,
+}
+
+/// Symbol of the `i`th stock: the prefix and three letters
+fn synthetic_symbol(i: usize) -> String {
+    let letters: String = [i / 676, i / 26, i]
+        .iter()
+        .map(|n| (b'A' + (n % 26) as u8) as char)
+        .collect();
+    format!("{}{}", SYNTHETIC_PREFIX, letters)
+}
+
+/// The listings of the universe
+pub fn generate_universe(config: &SyntheticConfig) -> Vec<SyntheticStock> {
+    let mut rng = SyntheticRng::new(config.seed);
+    (0..config.symbols)
+        .map(|i| {
+            let symbol = synthetic_symbol(i);
+            SyntheticStock {
+                name: format!("Synthetic {} Tbk", symbol),
+                symbol,
+                sector: SECTORS[rng.index(SECTORS.len())],
+                // Log-uniform from penny stocks to blue chips
+                initial_price: rng.range(50f64.ln(), 20_000f64.ln()).exp(),
+                beta: rng.range(0.4, 1.6),
+                volatility: rng.range(0.01, 0.035),
+                average_lots: rng.range(100f64.ln(), 500_000f64.ln()).exp(),
+                accumulator: BROKERS[rng.index(BROKERS.len())],
+            }
+        })
+        .collect()
+}
+
+/// Trading days of the history window
+pub fn trading_days(config: &SyntheticConfig, calendar: &MarketCalendar) -> Vec<NaiveDate> {
+    let end = Utc::now().date_naive() - Duration::days(1);
+    let start = end - Duration::days(365 * config.years as i64);
+    start
+        .iter_days()
+        .take_while(|d| *d <= end)
+        .filter(|d| calendar.is_trading_day(*d))
+        .collect()
+}
+
+/// Daily market and sector factor returns shared by every stock
+pub struct FactorReturns {
+    pub market: Vec<f64>,
+    /// Indexed like `SECTORS`
+    pub sectors: Vec<Vec<f64>>,
+}
+
+pub fn generate_factors(config: &SyntheticConfig, sessions: usize) -> FactorReturns {
+    let mut rng = SyntheticRng::new(config.seed ^ 0xFAC7_0125);
+    // Slow-moving drifts give sectors trends to rotate through
+    let mut sector_drift = vec![0.0; SECTORS.len()];
+    let mut market = Vec::with_capacity(sessions);
+    let mut sectors = vec![Vec::with_capacity(sessions); SECTORS.len()];
+    for _ in 0..sessions {
+        market.push(0.0003 + 0.009 * rng.normal());
+        for (s, drift) in sector_drift.iter_mut().enumerate() {
+            *drift = 0.98 * *drift + 0.0002 * rng.normal();
+            sectors[s].push(*drift + 0.005 * rng.normal());
+        }
+    }
+    FactorReturns { market, sectors }
+}
+
+fn to_price(value: f64, rounding: TickRounding) -> Decimal {
+    round_to_tick(
+        Decimal::from_f64(value.max(1.0)).unwrap_or(Decimal::ONE),
+        rounding,
+    )
+}
+
+/// Daily bars of one stock
+pub fn generate_bars(
+    stock: &SyntheticStock,
+    days: &[NaiveDate],
+    factors: &FactorReturns,
+    seed: u64,
+) -> Vec<SyntheticBar> {
+    let mut rng = SyntheticRng::new(seed);
+    let sector = SECTORS.iter().position(|s| *s == stock.sector).unwrap_or(0);
+    let mut close = stock.initial_price;
+
+    days.iter()
+        .enumerate()
+        .map(|(i, date)| {
+            let shock = rng.normal();
+            // IDX auto-rejection keeps daily moves within roughly ±25%
+            let ret = (stock.beta * factors.market[i]
+                + factors.sectors[sector][i]
+                + stock.volatility * shock)
+                .clamp(-0.25, 0.25);
+            let open = close * (1.0 + 0.3 * stock.volatility * rng.normal());
+            close = (close * (1.0 + ret)).max(50.0);
+            let range = stock.volatility * rng.uniform();
+
+            let open_price = to_price(open, TickRounding::Nearest);
+            let close_price = to_price(close, TickRounding::Nearest);
+            let high = to_price(open.max(close) * (1.0 + range), TickRounding::Up)
+                .max(open_price)
+                .max(close_price);
+            let low = to_price(open.min(close) * (1.0 - range), TickRounding::Down)
+                .min(open_price)
+                .min(close_price);
+            // Big moves trade more
+            let lots = stock.average_lots * (1.0 + 2.0 * shock.abs()) * rng.range(0.5, 1.5);
+
+            SyntheticBar {
+                date: *date,
+                open: open_price,
+                high,
+                low,
+                close: close_price,
+                volume: (lots.round().max(1.0) as i64) * LOT_SIZE,
+            }
+        })
+        .collect()
+}
+
+/// Per-broker flows of one stock's bars
+///
+/// Buy and sell volumes each add up to the session volume. The stock's
+/// accumulator always reports and leans to the buy side on up days.
+pub fn generate_broker_flows(
+    stock: &SyntheticStock,
+    bars: &[SyntheticBar],
+    brokers_per_day: usize,
+    seed: u64,
+) -> Vec<SyntheticBrokerFlow> {
+    if brokers_per_day == 0 {
+        return Vec::new();
+    }
+    let mut rng = SyntheticRng::new(seed);
+    let mut flows = Vec::with_capacity(bars.len() * brokers_per_day);
+    let mut prev_close = bars.first().map(|b| b.open).unwrap_or_default();
+
+    for bar in bars {
+        let mut brokers = vec![stock.accumulator];
+        while brokers.len() < brokers_per_day.min(BROKERS.len()) {
+            let broker = BROKERS[rng.index(BROKERS.len())];
+            if !brokers.contains(&broker) {
+                brokers.push(broker);
+            }
+        }
+        let up_day = bar.close > prev_close;
+        prev_close = bar.close;
+
+        let mut buy_weights: Vec<f64> = brokers.iter().map(|_| rng.range(0.2, 1.0)).collect();
+        let sell_weights: Vec<f64> = brokers.iter().map(|_| rng.range(0.2, 1.0)).collect();
+        if up_day {
+            buy_weights[0] *= 2.5;
+        }
+        let lots = bar.volume / LOT_SIZE;
+        let buys = split_lots(lots, &buy_weights);
+        let sells = split_lots(lots, &sell_weights);
+        let price = bar.close.to_f64().unwrap_or_default();
+
+        for ((broker, buy_lots), sell_lots) in brokers.iter().zip(buys).zip(sells) {
+            let buy_volume = buy_lots * LOT_SIZE;
+            let sell_volume = sell_lots * LOT_SIZE;
+            flows.push(SyntheticBrokerFlow {
+                date: bar.date,
+                broker_code: broker,
+                buy_volume,
+                sell_volume,
+                buy_value: value_at(buy_volume, price, &mut rng),
+                sell_value: value_at(sell_volume, price, &mut rng),
+            });
+        }
+    }
+    flows
+}
+
+/// `lots` split in proportion to `weights`, remainder to the first
+fn split_lots(lots: i64, weights: &[f64]) -> Vec<i64> {
+    let total: f64 = weights.iter().sum();
+    let mut parts: Vec<i64> = weights
+        .iter()
+        .map(|w| (lots as f64 * w / total).floor() as i64)
+        .collect();
+    parts[0] += lots - parts.iter().sum::<i64>();
+    parts
+}
+
+/// Traded value around the close, rounded to the rupiah
+fn value_at(volume: i64, price: f64, rng: &mut SyntheticRng) -> Decimal {
+    let value = volume as f64 * price * rng.range(0.99, 1.01);
+    Decimal::from_f64(value.round()).unwrap_or_default()
+}
+
+/// Rows written by `seed`
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct SeedReport {
+    pub stocks: usize,
+    pub sessions: usize,
+    pub price_rows: u64,
+    pub broker_rows: u64,
+    pub index_rows: u64,
+}
+
+/// Write the synthetic universe, its history and a composite index
+///
+/// Idempotent for a given config: rows for the same day are replaced.
+pub async fn seed(pool: &PgPool, config: &SyntheticConfig) -> Result<SeedReport, sqlx::Error> {
+    let calendar = MarketCalendar::default();
+    let days = trading_days(config, &calendar);
+    let factors = generate_factors(config, days.len());
+    let universe = generate_universe(config);
+    let mut report = SeedReport {
+        sessions: days.len(),
+        ..Default::default()
+    };
+
+    for (i, stock) in universe.iter().enumerate() {
+        repositories::stocks::upsert_stock(
+            pool,
+            &stock.symbol,
+            &stock.name,
+            Some(stock.sector),
+            None,
+        )
+        .await?;
+
+        let stock_seed = config.seed.wrapping_add(i as u64 + 1);
+        let bars = generate_bars(stock, &days, &factors, stock_seed);
+        let prices: Vec<InsertPrice> = bars
+            .iter()
+            .map(|b| InsertPrice {
+                time: session_close_time(b.date),
+                symbol: &stock.symbol,
+                open: b.open,
+                high: b.high,
+                low: b.low,
+                close: b.close,
+                volume: b.volume,
+            })
+            .collect();
+        let upserted = repositories::prices::upsert_prices(pool, &prices, true).await?;
+        report.price_rows += upserted.inserted + upserted.replaced;
+
+        let flows = generate_broker_flows(stock, &bars, config.brokers_per_day, !stock_seed);
+        let rows: Vec<InsertBrokerSummary> = flows
+            .iter()
+            .map(|f| InsertBrokerSummary {
+                time: session_close_time(f.date),
+                symbol: &stock.symbol,
+                broker_code: f.broker_code,
+                buy_volume: f.buy_volume,
+                sell_volume: f.sell_volume,
+                buy_value: f.buy_value,
+                sell_value: f.sell_value,
+                source: SYNTHETIC_SOURCE,
+            })
+            .collect();
+        let upserted = repositories::broker_summary::upsert_broker_summaries(pool, &rows).await?;
+        report.broker_rows += upserted.inserted + upserted.replaced;
+        report.stocks += 1;
+
+        if (i + 1) % 50 == 0 {
+            tracing::info!("Seeded {}/{} synthetic stocks", i + 1, universe.len());
+        }
+    }
+
+    let mut level = 7000.0;
+    let index: Vec<(NaiveDate, Decimal)> = days
+        .iter()
+        .zip(&factors.market)
+        .map(|(date, ret)| {
+            level *= 1.0 + ret;
+            (
+                *date,
+                Decimal::from_f64(level).unwrap_or_default().round_dp(2),
+            )
+        })
+        .collect();
+    report.index_rows = repositories::macro_indicators::upsert_macro_observations(
+        pool,
+        MacroIndicator::CompositeIndex.as_str(),
+        SYNTHETIC_SOURCE,
+        &index,
+    )
+    .await?;
+
+    Ok(report)
+}
+
+/// Bars are stamped at the 16:00 WIB close
+fn session_close_time(date: NaiveDate) -> chrono::DateTime<Utc> {
+    date.and_hms_opt(9, 0, 0)
+        .expect("valid close time")
+        .and_utc()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jejakcuan_core::tick_size;
+
+    fn small_config() -> SyntheticConfig {
+        SyntheticConfig {
+            symbols: 30,
+            years: 1,
+            brokers_per_day: 5,
+            seed: 7,
+        }
+    }
+
+    #[test]
+    fn test_generation_is_reproducible() {
+        let config = small_config();
+        let universe = generate_universe(&config);
+        assert_eq!(universe, generate_universe(&config));
+        assert_eq!(universe[0].symbol, "SYNAAA");
+        assert_eq!(universe[27].symbol, "SYNABB");
+
+        let days = trading_days(&config, &MarketCalendar::default());
+        let factors = generate_factors(&config, days.len());
+        let bars = generate_bars(&universe[3], &days, &factors, 11);
+        assert_eq!(bars, generate_bars(&universe[3], &days, &factors, 11));
+        assert_ne!(bars, generate_bars(&universe[3], &days, &factors, 12));
+    }
+
+    #[test]
+    fn test_bars_are_valid_idx_sessions() {
+        let config = small_config();
+        let days = trading_days(&config, &MarketCalendar::default());
+        assert!(days.len() > 240 && days.len() < 270);
+        let factors = generate_factors(&config, days.len());
+
+        for (i, stock) in generate_universe(&config).iter().enumerate() {
+            let bars = generate_bars(stock, &days, &factors, i as u64);
+            assert_eq!(bars.len(), days.len());
+            for bar in &bars {
+                assert!(bar.low <= bar.open.min(bar.close));
+                assert!(bar.high >= bar.open.max(bar.close));
+                assert!(bar.low > Decimal::ZERO);
+                assert_eq!(bar.close % tick_size(bar.close), Decimal::ZERO);
+                assert_eq!(bar.volume % LOT_SIZE, 0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_broker_flows_balance_volume() {
+        let config = small_config();
+        let days = trading_days(&config, &MarketCalendar::default());
+        let factors = generate_factors(&config, days.len());
+        let stock = &generate_universe(&config)[0];
+        let bars = generate_bars(stock, &days[..20], &factors, 1);
+        let flows = generate_broker_flows(stock, &bars, 5, 2);
+
+        assert_eq!(flows.len(), 20 * 5);
+        for bar in &bars {
+            let day: Vec<_> = flows.iter().filter(|f| f.date == bar.date).collect();
+            assert_eq!(day.iter().map(|f| f.buy_volume).sum::<i64>(), bar.volume);
+            assert_eq!(day.iter().map(|f| f.sell_volume).sum::<i64>(), bar.volume);
+            assert!(day.iter().any(|f| f.broker_code == stock.accumulator));
+        }
+        assert!(generate_broker_flows(stock, &bars, 0, 2).is_empty());
+    }
+}
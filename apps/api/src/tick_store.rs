@@ -0,0 +1,182 @@
+//! Recent trades per symbol from the streaming price feed
+//!
+//! Footprint charts are built from these ticks on request. Only the latest
+//! ticks of each symbol are kept, and a new session drops the previous one.
+//! Symbols whose footprint was requested are remembered for a while, so the
+//! price stream subscribes them even when no watchlist includes them.
+
+use chrono::{DateTime, Duration, Utc};
+use jejakcuan_data_sources::canonical_symbol;
+use jejakcuan_data_sources::twelvedata::PriceUpdate;
+use jejakcuan_technical::{session_date, TradeTick};
+use std::collections::{HashMap, VecDeque};
+use std::sync::RwLock;
+
+/// Ticks kept per symbol; the oldest are dropped first
+const MAX_TICKS_PER_SYMBOL: usize = 50_000;
+
+/// How long a footprint request keeps its symbol streamed, about a session
+const REQUEST_STREAM_HOURS: i64 = 8;
+
+#[derive(Debug, Default)]
+struct SymbolTicks {
+    /// Cumulative day volume of the last update, to derive trade sizes
+    last_day_volume: Option<i64>,
+    ticks: VecDeque<TradeTick>,
+}
+
+/// Per-symbol tick buffers shared by the feed and the footprint endpoint
+#[derive(Debug, Default)]
+pub struct TickStore {
+    symbols: RwLock<HashMap<String, SymbolTicks>>,
+    /// Last footprint request per symbol
+    requested: RwLock<HashMap<String, DateTime<Utc>>>,
+}
+
+impl TickStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a trade for `symbol`
+    pub fn record(&self, symbol: &str, tick: TradeTick) {
+        let mut symbols = self.symbols.write().expect("tick store lock poisoned");
+        let entry = symbols.entry(symbol.to_uppercase()).or_default();
+        Self::push(entry, tick);
+    }
+
+    fn push(entry: &mut SymbolTicks, tick: TradeTick) {
+        let new_session = entry
+            .ticks
+            .back()
+            .is_some_and(|last| session_date(last.time) != session_date(tick.time));
+        if new_session {
+            entry.ticks.clear();
+        }
+        if entry.ticks.len() >= MAX_TICKS_PER_SYMBOL {
+            entry.ticks.pop_front();
+        }
+        entry.ticks.push_back(tick);
+    }
+
+    /// Record a streaming price update as a trade
+    ///
+    /// The feed reports the cumulative day volume, so the trade size is its
+    /// increase since the previous update; updates without new volume are
+    /// quote changes and are skipped.
+    pub fn record_price_update(&self, update: &PriceUpdate) {
+        let (Some(price), Some(day_volume)) = (update.price, update.day_volume) else {
+            return;
+        };
+        let time = update.datetime().unwrap_or_else(Utc::now);
+        let symbol = canonical_symbol(&update.symbol);
+
+        let mut symbols = self.symbols.write().expect("tick store lock poisoned");
+        let entry = symbols.entry(symbol).or_default();
+        let previous = entry.last_day_volume.replace(day_volume);
+        let volume = match previous {
+            // Day volume restarting means a new session
+            Some(previous) if day_volume >= previous => day_volume - previous,
+            _ => return,
+        };
+        if volume == 0 {
+            return;
+        }
+        Self::push(
+            entry,
+            TradeTick {
+                time,
+                price,
+                volume,
+                bid: update.bid,
+                ask: update.ask,
+            },
+        );
+    }
+
+    /// Ticks of `symbol` in the current session, oldest first
+    pub fn ticks(&self, symbol: &str) -> Vec<TradeTick> {
+        let symbols = self.symbols.read().expect("tick store lock poisoned");
+        symbols
+            .get(&symbol.to_uppercase())
+            .map(|entry| entry.ticks.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Time of the latest tick of `symbol`
+    pub fn last_tick_at(&self, symbol: &str) -> Option<DateTime<Utc>> {
+        let symbols = self.symbols.read().expect("tick store lock poisoned");
+        symbols
+            .get(&symbol.to_uppercase())
+            .and_then(|entry| entry.ticks.back().map(|t| t.time))
+    }
+
+    /// Note a footprint request for `symbol` at `now`
+    pub fn request(&self, symbol: &str, now: DateTime<Utc>) {
+        let mut requested = self.requested.write().expect("tick store lock poisoned");
+        requested.insert(symbol.to_uppercase(), now);
+    }
+
+    /// Symbols whose footprint was requested recently, forgetting older ones
+    pub fn requested_symbols(&self, now: DateTime<Utc>) -> Vec<String> {
+        let cutoff = now - Duration::hours(REQUEST_STREAM_HOURS);
+        let mut requested = self.requested.write().expect("tick store lock poisoned");
+        requested.retain(|_, at| *at > cutoff);
+        requested.keys().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn update(timestamp: i64, price: rust_decimal::Decimal, day_volume: i64) -> PriceUpdate {
+        serde_json::from_value(serde_json::json!({
+            "event": "price",
+            "symbol": "BBCA:IDX",
+            "price": price,
+            "day_volume": day_volume,
+            "timestamp": timestamp,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_trade_sizes_from_day_volume() {
+        let store = TickStore::new();
+        // 2024-03-04 09:00 WIB
+        let open = 1_709_517_600;
+        store.record_price_update(&update(open, dec!(9275), 1_000));
+        store.record_price_update(&update(open + 5, dec!(9300), 1_600));
+        store.record_price_update(&update(open + 9, dec!(9300), 1_600));
+        store.record_price_update(&update(open + 12, dec!(9275), 2_000));
+
+        let ticks = store.ticks("bbca");
+        assert_eq!(ticks.len(), 2);
+        assert_eq!(ticks[0].volume, 600);
+        assert_eq!(ticks[1].volume, 400);
+        assert_eq!(
+            store.last_tick_at("BBCA").map(|t| t.timestamp()),
+            Some(open + 12)
+        );
+
+        // Next session starts a new buffer
+        let next = open + 24 * 3600;
+        store.record_price_update(&update(next, dec!(9325), 500));
+        store.record_price_update(&update(next + 3, dec!(9350), 800));
+        let ticks = store.ticks("BBCA");
+        assert_eq!(ticks.len(), 1);
+        assert_eq!(ticks[0].volume, 300);
+    }
+
+    #[test]
+    fn test_requested_symbols_expire() {
+        let store = TickStore::new();
+        let now = Utc::now();
+        store.request("bbca", now - Duration::hours(REQUEST_STREAM_HOURS + 1));
+        store.request("tlkm", now - Duration::hours(1));
+
+        assert_eq!(store.requested_symbols(now), vec!["TLKM".to_string()]);
+    }
+}
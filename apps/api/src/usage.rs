@@ -0,0 +1,266 @@
+//! Product usage analytics
+//!
+//! Handlers record which symbols are viewed, which screens are run and
+//! which alerts get clicked. Events are queued and written in batches by a
+//! background task, so recording never waits on the database; when the
+//! queue is full, events are dropped rather than slowing requests down.
+//!
+//! `USAGE_ANALYTICS` sets what is kept about the user:
+//! - `full`: the username
+//! - `hashed` (default): a salted hash, so distinct users can be counted
+//!   but not named; the salt is `USAGE_ANALYTICS_SALT`, else the JWT secret
+//! - `anonymous`: nothing
+//! - `off`: no events are recorded
+//!
+//! Events older than `USAGE_RETENTION_DAYS` (default 180) are purged daily.
+
+use chrono::Utc;
+use jejakcuan_core::ScreenFilter;
+use jejakcuan_db::repositories::{self, InsertUsageEvent};
+use ring::digest::{digest, SHA256};
+use sqlx::PgPool;
+use std::collections::BTreeSet;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Events queued before new ones are dropped
+const QUEUE_CAPACITY: usize = 10_000;
+
+/// Most events written in one insert
+const BATCH_SIZE: usize = 500;
+
+/// Longest an event waits in a partial batch
+const FLUSH_EVERY: Duration = Duration::from_secs(5);
+
+/// What is kept about the user behind an event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActorPrivacy {
+    Off,
+    Anonymous,
+    Hashed,
+    Full,
+}
+
+impl ActorPrivacy {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "off" | "false" | "0" => Some(Self::Off),
+            "anonymous" => Some(Self::Anonymous),
+            "hashed" => Some(Self::Hashed),
+            "full" => Some(Self::Full),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Anonymous => "anonymous",
+            Self::Hashed => "hashed",
+            Self::Full => "full",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UsageAnalyticsConfig {
+    pub privacy: ActorPrivacy,
+    /// Salt of the actor hash
+    pub salt: String,
+    pub retention_days: i64,
+}
+
+impl Default for UsageAnalyticsConfig {
+    fn default() -> Self {
+        Self {
+            privacy: ActorPrivacy::Hashed,
+            salt: String::new(),
+            retention_days: 180,
+        }
+    }
+}
+
+impl UsageAnalyticsConfig {
+    /// The actor stored for `username`
+    pub fn actor(&self, username: &str) -> Option<String> {
+        match self.privacy {
+            ActorPrivacy::Off | ActorPrivacy::Anonymous => None,
+            ActorPrivacy::Full => Some(username.to_string()),
+            ActorPrivacy::Hashed => {
+                let salted = format!("{}:{}", self.salt, username);
+                let hash = digest(&SHA256, salted.as_bytes());
+                Some(
+                    hash.as_ref()[..8]
+                        .iter()
+                        .map(|b| format!("{:02x}", b))
+                        .collect(),
+                )
+            }
+        }
+    }
+}
+
+/// Kinds of usage event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UsageKind {
+    SymbolViewed,
+    ScreenRun,
+    AlertClicked,
+}
+
+impl UsageKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UsageKind::SymbolViewed => "symbol_viewed",
+            UsageKind::ScreenRun => "screen_run",
+            UsageKind::AlertClicked => "alert_clicked",
+        }
+    }
+}
+
+/// Queues usage events for the background writer
+pub struct UsageRecorder {
+    config: UsageAnalyticsConfig,
+    tx: Option<mpsc::Sender<InsertUsageEvent>>,
+}
+
+impl UsageRecorder {
+    /// Recorder writing to `pool`; with recording off no writer is started
+    pub fn new(config: UsageAnalyticsConfig, pool: PgPool) -> Self {
+        if config.privacy == ActorPrivacy::Off {
+            return Self { config, tx: None };
+        }
+        let (tx, rx) = mpsc::channel(QUEUE_CAPACITY);
+        tokio::spawn(write_events(rx, pool));
+        Self {
+            config,
+            tx: Some(tx),
+        }
+    }
+
+    pub fn config(&self) -> &UsageAnalyticsConfig {
+        &self.config
+    }
+
+    /// Queue an event by `username`
+    pub fn record(&self, kind: UsageKind, subject: &str, symbol: Option<&str>, username: &str) {
+        let Some(tx) = &self.tx else {
+            return;
+        };
+        let event = InsertUsageEvent {
+            kind: kind.as_str().to_string(),
+            subject: subject.chars().take(200).collect(),
+            symbol: symbol.map(str::to_string),
+            actor: self.config.actor(username),
+            occurred_at: Utc::now(),
+        };
+        if tx.try_send(event).is_err() {
+            tracing::debug!("Usage event queue full; dropping {}", kind.as_str());
+        }
+    }
+}
+
+async fn write_events(mut rx: mpsc::Receiver<InsertUsageEvent>, pool: PgPool) {
+    let mut batch = Vec::with_capacity(BATCH_SIZE);
+    let mut ticker = tokio::time::interval(FLUSH_EVERY);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+    loop {
+        let closed = tokio::select! {
+            event = rx.recv() => match event {
+                Some(event) => {
+                    batch.push(event);
+                    if batch.len() < BATCH_SIZE {
+                        continue;
+                    }
+                    false
+                }
+                None => true,
+            },
+            _ = ticker.tick() => false,
+        };
+
+        if !batch.is_empty() {
+            if let Err(e) = repositories::usage_events::insert_usage_events(&pool, &batch).await {
+                tracing::warn!("Failed to write {} usage events: {}", batch.len(), e);
+            }
+            batch.clear();
+        }
+        if closed {
+            return;
+        }
+    }
+}
+
+/// Condition types a screen uses, e.g. `rsi_below,sector`
+///
+/// Thresholds and sector names are left out: the event says which
+/// conditions people screen on, not what they look for.
+pub fn screen_subject(filter: &ScreenFilter) -> String {
+    fn collect(value: &serde_json::Value, types: &mut BTreeSet<String>) {
+        match value {
+            serde_json::Value::Object(map) => {
+                if let Some(kind) = map.get("type").and_then(|t| t.as_str()) {
+                    if !matches!(kind, "all" | "any" | "not") {
+                        types.insert(kind.to_string());
+                    }
+                }
+                map.values().for_each(|v| collect(v, types));
+            }
+            serde_json::Value::Array(items) => items.iter().for_each(|v| collect(v, types)),
+            _ => {}
+        }
+    }
+
+    let mut types = BTreeSet::new();
+    if let Ok(value) = serde_json::to_value(filter) {
+        collect(&value, &mut types);
+    }
+    types.into_iter().collect::<Vec<_>>().join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_actor_privacy() {
+        let mut config = UsageAnalyticsConfig {
+            salt: "pepper".to_string(),
+            ..Default::default()
+        };
+        let hashed = config.actor("alice").unwrap();
+        assert_eq!(hashed.len(), 16);
+        assert_ne!(hashed, "alice");
+        assert_eq!(config.actor("alice"), Some(hashed.clone()));
+        assert_ne!(config.actor("bob"), Some(hashed.clone()));
+        config.salt = "salt".to_string();
+        assert_ne!(config.actor("alice"), Some(hashed));
+
+        config.privacy = ActorPrivacy::Anonymous;
+        assert_eq!(config.actor("alice"), None);
+        config.privacy = ActorPrivacy::Full;
+        assert_eq!(config.actor("alice").as_deref(), Some("alice"));
+
+        assert_eq!(ActorPrivacy::parse("Hashed"), Some(ActorPrivacy::Hashed));
+        assert_eq!(ActorPrivacy::parse("off"), Some(ActorPrivacy::Off));
+        assert_eq!(ActorPrivacy::parse("everything"), None);
+    }
+
+    #[test]
+    fn test_screen_subject_lists_condition_types_only() {
+        let filter = ScreenFilter::All {
+            filters: vec![
+                ScreenFilter::RsiBelow { value: dec!(30) },
+                ScreenFilter::Not {
+                    filter: Box::new(ScreenFilter::Sector {
+                        sectors: vec!["Banking".to_string()],
+                    }),
+                },
+                ScreenFilter::RsiBelow { value: dec!(25) },
+            ],
+        };
+        assert_eq!(screen_subject(&filter), "rsi_below,sector");
+    }
+}
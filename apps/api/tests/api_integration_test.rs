@@ -100,8 +100,38 @@ mod with_database {
             // For testing, allow "admin123" password
             password_hash: "$argon2id$v=19$m=19456,t=2,p=1$random_salt_here$hashed_password"
                 .to_string(),
+            users: Vec::new(),
             host: "127.0.0.1".to_string(),
             port: 0,
+            telegram_bot_token: None,
+            admin_telegram_chat_id: None,
+            admin_webhook_url: None,
+            admin_discord_webhook_url: None,
+            admin_slack_webhook_url: None,
+            whatsapp_phone_number_id: None,
+            whatsapp_access_token: None,
+            whatsapp_template_name: None,
+            admin_whatsapp_number: None,
+            risk_budget: Default::default(),
+            transaction_costs: Default::default(),
+            cache_snapshot_path: None,
+            analysis_warm_time_utc: None,
+            alert_scan_interval: None,
+            score_ticker_interval: None,
+            realtime_scoring: false,
+            sector_relative_technical: false,
+            soft_delete_retention_days: 30,
+            maintenance_time_utc: None,
+            notification_max_attempts: 5,
+            market_calendar: Default::default(),
+            secret_cipher: None,
+            demo_data_delay: None,
+            usage_analytics: Default::default(),
+            price_providers: vec![jejakcuan_data_sources::SymbolProvider::Yahoo],
+            twelvedata_api_key: None,
+            twelvedata_pool_keys: Vec::new(),
+            sectors_api_key: None,
+            backfill_daily_requests: None,
         }
     }
 
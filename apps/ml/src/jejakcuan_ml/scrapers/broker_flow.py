@@ -5,6 +5,9 @@ Sources:
 - StockBit broker summary
 - IDX broker data
 
+Scraped rows are staged in one batch per run and reach broker_summary only
+once an admin approves the batch (see /api/admin/staging).
+
 Broker codes reference:
 - XL: Deutsche Bank
 - AK: UBS
@@ -45,6 +48,9 @@ class BrokerTransaction:
 class BrokerFlowScraper(BaseScraper):
     """Scraper for broker flow (buy/sell) data."""
 
+    # Source recorded on broker_summary rows once their batch is approved
+    STAGING_SOURCE_ID = "scraper"
+
     INDOPREMIER_BASE = "https://www.indopremier.com"
     STOCKBIT_API = "https://api.stockbit.com"
     IDX_BASE = "https://www.idx.co.id"
@@ -73,12 +79,13 @@ class BrokerFlowScraper(BaseScraper):
         return "Broker Flow"
 
     async def scrape(self) -> int:
-        """Scrape broker flow data.
+        """Scrape broker flow data into a staging batch.
 
         Returns:
-            Number of records scraped
+            Number of records staged
         """
         count = 0
+        batch_id: int | None = None
 
         # Get symbols to scrape
         if self._symbols:
@@ -110,13 +117,19 @@ class BrokerFlowScraper(BaseScraper):
                         }
                         for t in transactions
                     ]
-                    inserted = self.db.insert_broker_summary_batch(batch)
-                    count += inserted
-                    logger.debug(f"Inserted {inserted} broker records for {symbol}")
+                    if batch_id is None:
+                        batch_id = self.db.create_staging_batch(
+                            self.STAGING_SOURCE_ID, "broker_summary"
+                        )
+                    staged = self.db.insert_staged_broker_summary_batch(batch_id, batch)
+                    count += staged
+                    logger.debug(f"Staged {staged} broker records for {symbol}")
 
             except Exception as e:
                 logger.warning(f"Failed to scrape broker flow for {symbol}: {e}")
 
+        if batch_id is not None:
+            logger.info(f"Staged {count} broker records in batch {batch_id} awaiting review")
         return count
 
     async def _fetch_broker_flow(
@@ -527,7 +540,7 @@ async def main() -> None:
     scraper = BrokerFlowScraper(symbols=symbols, days=days)
     try:
         count = await scraper.run()
-        logger.info(f"Completed: {count} broker flow records scraped")
+        logger.info(f"Completed: {count} broker flow records staged for review")
     except Exception as e:
         logger.error(f"Scraper failed: {e}")
         sys.exit(1)
@@ -286,6 +286,73 @@ class DatabaseClient:
             )
             return cur.rowcount
 
+    def create_staging_batch(self, source_id: str, dataset: str) -> int:
+        """Create a pending staging batch.
+
+        Args:
+            source_id: Source recorded on the rows once the batch is approved
+            dataset: Staged dataset ('broker_summary' or 'shareholdings')
+
+        Returns:
+            ID of the new batch
+        """
+        with self.cursor() as cur:
+            cur.execute(
+                """
+                INSERT INTO staging_batches (source_id, dataset)
+                VALUES (%s, %s)
+                RETURNING id
+                """,
+                (source_id, dataset),
+            )
+            return cur.fetchone()[0]
+
+    def insert_staged_broker_summary_batch(
+        self, batch_id: int, summaries: list[dict[str, Any]]
+    ) -> int:
+        """Add broker summary records to a staging batch.
+
+        The rows reach broker_summary only once an admin approves the batch.
+
+        Args:
+            batch_id: Staging batch ID
+            summaries: List of broker summary dictionaries
+
+        Returns:
+            Number of records staged
+        """
+        if not summaries:
+            return 0
+
+        with self.cursor() as cur:
+            psycopg2.extras.execute_values(
+                cur,
+                """
+                INSERT INTO staging_broker_summary (
+                    batch_id, time, symbol, broker_code, buy_volume, sell_volume,
+                    buy_value, sell_value
+                ) VALUES %s
+                """,
+                [
+                    (
+                        batch_id,
+                        s["time"],
+                        s["symbol"],
+                        s["broker_code"],
+                        s["buy_volume"],
+                        s["sell_volume"],
+                        s["buy_value"],
+                        s["sell_value"],
+                    )
+                    for s in summaries
+                ],
+            )
+            cur.execute(
+                "UPDATE staging_batches SET row_count = row_count + %s WHERE id = %s",
+                (len(summaries), batch_id),
+            )
+            return len(summaries)
+
     def upsert_financials(
         self,
         symbol: str,
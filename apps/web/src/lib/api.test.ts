@@ -142,7 +142,7 @@ describe('API Client', () => {
   describe('authentication', () => {
     it('stores token on successful login', async () => {
       mockFetch.mockResolvedValueOnce(createMockResponse({
-        body: { token: 'new-token', expires_at: 12345 },
+        body: { token: 'new-token', expires_at: 12345, role: 'admin' },
       }));
 
       await api.login('user', 'pass');
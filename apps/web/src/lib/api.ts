@@ -4,9 +4,12 @@ export function resolveApiBase(viteApiUrl: string | undefined | null): string {
 
 const API_BASE = resolveApiBase((import.meta as any).env?.VITE_API_URL);
 
+type UserRole = 'viewer' | 'operator' | 'admin';
+
 interface LoginResponse {
   token: string;
   expires_at: number;
+  role: UserRole;
 }
 
 interface Stock {
@@ -28,10 +31,291 @@ interface StockScore {
   ml_score: number;
 }
 
+type NewsSentimentLabel = 'positive' | 'neutral' | 'negative';
+
+interface StockNews {
+  id: number;
+  symbol: string;
+  title: string;
+  summary: string | null;
+  source: string;
+  url: string;
+  published_at: string;
+  sentiment: NewsSentimentLabel | null;
+  /** -1 to 1; null for headlines stored without a score */
+  sentiment_score: number | null;
+  keywords: string[] | null;
+  related_corporate_action_id: number | null;
+  created_at: string | null;
+}
+
+type ReportPeriod = 'q1' | 'q2' | 'q3' | 'annual';
+
+interface EarningsCalendarEntry {
+  id: number;
+  symbol: string;
+  fiscal_year: number;
+  period: ReportPeriod;
+  /** Publication date once published, otherwise the estimate */
+  expected_date: string;
+  deadline: string;
+  basis: 'published' | 'last_year' | 'deadline';
+  published_on: string | null;
+  alerted_at: string | null;
+  updated_at: string;
+}
+
+interface EarningsWeek {
+  /** ISO week, e.g. "2025-W43" */
+  week: string;
+  from: string;
+  to: string;
+  reports: EarningsCalendarEntry[];
+}
+
 interface RecomputeScoresResponse {
   computed: number;
+  /** Inputs unchanged since the latest score */
   skipped: number;
   errors: number;
+  prioritized: number;
+  sector_normalized: number;
+}
+
+type ScreenFilter =
+  | { type: 'composite_score_above' | 'composite_score_below'; value: number }
+  | { type: 'technical_score_above' | 'fundamental_score_above'; value: number }
+  | { type: 'rsi_below' | 'rsi_above' | 'rvol_above'; value: number }
+  | { type: 'pe_below' | 'pb_below' | 'roe_above'; value: number }
+  | { type: 'price_above' | 'price_below'; value: number }
+  | { type: 'pe_below_sector'; ratio?: number }
+  | { type: 'sector'; sectors: string[] }
+  | { type: 'wyckoff_phase'; phase: string }
+  | { type: 'custom_indicator_above' | 'custom_indicator_below'; name: string; value: number }
+  | { type: 'all' | 'any'; filters: ScreenFilter[] }
+  | { type: 'not'; filter: ScreenFilter };
+
+interface ScreenCandidate {
+  symbol: string;
+  sector: string | null;
+  composite_score: string | null;
+  technical_score: string | null;
+  fundamental_score: string | null;
+  close: string | null;
+  rsi: string | null;
+  rvol: string | null;
+  pe_ratio: string | null;
+  pb_ratio: string | null;
+  roe: string | null;
+  sector_median_pe: string | null;
+  wyckoff_phase: string | null;
+}
+
+interface ScreenResponse {
+  evaluated: number;
+  matched: number;
+  results: ScreenCandidate[];
+}
+
+interface SavedScreen {
+  id: number;
+  owner: string;
+  name: string;
+  filter: ScreenFilter;
+  alerts_enabled: boolean;
+  last_symbols: string[];
+  last_run_at: string | null;
+  created_at: string;
+  updated_at: string;
+}
+
+interface SavedScreenInput {
+  name: string;
+  filter: ScreenFilter;
+  alerts_enabled?: boolean;
+}
+
+interface ScreenDiff {
+  entered: string[];
+  left: string[];
+}
+
+interface ScreenRunResponse {
+  screen: SavedScreen;
+  diff: ScreenDiff;
+  results: ScreenCandidate[];
+}
+
+type BacktestStrategy =
+  | { kind: 'sma_crossover'; fast: number; slow: number }
+  | { kind: 'score_threshold'; entry: string; exit: string }
+  | { kind: 'rsi_reversion'; period: number; entry: number; exit: number };
+
+interface MonteCarloConfig {
+  /** 0 skips the simulation */
+  simulations: number;
+  seed: number;
+  ruin_loss_pct: number;
+}
+
+interface BacktestConfig {
+  initial_capital: string;
+  stop_loss_pct?: number | null;
+  max_hold_bars?: number | null;
+  monte_carlo?: MonteCarloConfig;
+}
+
+interface Distribution {
+  mean: number;
+  p5: number;
+  p25: number;
+  p50: number;
+  p75: number;
+  p95: number;
+}
+
+interface MonteCarloSummary {
+  simulations: number;
+  trades_per_path: number;
+  final_equity: Distribution;
+  total_return_pct: Distribution;
+  max_drawdown_pct: Distribution;
+  profit_probability_pct: number;
+  ruin_probability_pct: number;
+}
+
+interface BacktestMetrics {
+  initial_capital: string;
+  final_equity: string;
+  total_return_pct: number;
+  annualized_return_pct: number | null;
+  max_drawdown_pct: number;
+  sharpe_ratio: number | null;
+  trades: number;
+  win_rate_pct: number | null;
+  profit_factor: number | null;
+  avg_trade_return_pct: number | null;
+  exposure_pct: number;
+  buy_and_hold_return_pct: number;
+}
+
+interface BacktestTrade {
+  entry_date: string;
+  entry_price: string;
+  exit_date: string;
+  exit_price: string;
+  shares: number;
+  pnl: string;
+  return_pct: number;
+  bars_held: number;
+  exit_reason: 'signal' | 'stop_loss' | 'max_hold' | 'end_of_data';
+}
+
+interface EquityPoint {
+  date: string;
+  equity: string;
+  drawdown_pct: number;
+}
+
+interface BacktestRunSummary {
+  id: number;
+  name: string | null;
+  symbol: string;
+  strategy: BacktestStrategy;
+  config: BacktestConfig;
+  start_date: string;
+  end_date: string;
+  metrics: BacktestMetrics;
+  share_token: string | null;
+  created_at: string;
+}
+
+interface BacktestRun extends BacktestRunSummary {
+  owner: string;
+  costs: Record<string, number | string>;
+  trades: BacktestTrade[];
+  equity_curve: EquityPoint[];
+  monte_carlo: MonteCarloSummary | null;
+}
+
+type SharedBacktest = Omit<BacktestRun, 'id' | 'owner' | 'share_token'>;
+
+interface RunBacktestInput {
+  symbol: string;
+  strategy: BacktestStrategy;
+  from?: string;
+  to?: string;
+  config?: BacktestConfig;
+  name?: string;
+}
+
+interface ShareBacktestResponse {
+  token: string;
+  path: string;
+}
+
+type StrategyGrid =
+  | { kind: 'sma_crossover'; fast: number[]; slow: number[] }
+  | { kind: 'score_threshold'; entry: string[]; exit: string[] }
+  | { kind: 'rsi_reversion'; period: number[]; entry: number[]; exit: number[] };
+
+interface ParameterGrid {
+  strategy: StrategyGrid;
+  /** Empty or omitted keeps the base config's value */
+  stop_loss_pct?: number[];
+  max_hold_bars?: number[];
+}
+
+type OptimizationObjective = 'sharpe_ratio' | 'total_return' | 'profit_factor';
+
+interface RunOptimizationInput {
+  symbol: string;
+  grid: ParameterGrid;
+  from?: string;
+  to?: string;
+  config?: BacktestConfig;
+  objective?: OptimizationObjective;
+  /** Share of bars combinations are ranked on, 10 to 90 (default 70) */
+  in_sample_pct?: number;
+  name?: string;
+}
+
+interface BacktestOptimization {
+  id: number;
+  owner: string;
+  name: string | null;
+  symbol: string;
+  grid: ParameterGrid;
+  base_config: BacktestConfig;
+  costs: Record<string, number | string>;
+  objective: OptimizationObjective;
+  start_date: string;
+  end_date: string;
+  /** First out-of-sample session */
+  split_date: string | null;
+  status: 'pending' | 'running' | 'completed' | 'failed';
+  combinations_total: number;
+  combinations_done: number;
+  last_error: string | null;
+  created_at: string;
+  updated_at: string;
+  completed_at: string | null;
+}
+
+interface OptimizationResult {
+  optimization_id: number;
+  rank: number;
+  strategy: BacktestStrategy;
+  config: BacktestConfig;
+  in_sample: BacktestMetrics;
+  out_of_sample: BacktestMetrics | null;
+  in_sample_score: number | null;
+  out_of_sample_score: number | null;
+}
+
+interface OptimizationDetail {
+  optimization: BacktestOptimization;
+  results: OptimizationResult[];
 }
 
 interface StockPrice {
@@ -58,10 +342,14 @@ interface WatchlistItem {
   sort_order: number;
   notes: string | null;
   added_at: string;
+  deleted_at: string | null;
 }
 
 interface FundamentalData {
   symbol: string;
+  period_end: string;
+  report_date: string;
+  version: number;
   pe_ratio: number | null;
   pb_ratio: number | null;
   ps_ratio: number | null;
@@ -75,6 +363,8 @@ interface FundamentalData {
   dcf_margin_of_safety: number | null;
   sector_avg_pe: number | null;
   sector_avg_pb: number | null;
+  sector_avg_ev_ebitda: number | null;
+  ev_ebitda_percentile: number | null;
 }
 
 // Admin Types (Legacy)
@@ -98,11 +388,21 @@ interface DataSummary {
   newest_price_data: string | null;
 }
 
+interface TwelveDataKeyHealth {
+  key_id: string;
+  requests: number;
+  rate_limited: number;
+  errors: number;
+  available: boolean;
+  cooldown_remaining_secs: number | null;
+}
+
 interface DataStatusResponse {
   timestamp: string;
   overall_status: string;
   sources: DataSourceStatus[];
   summary: DataSummary;
+  twelvedata_keys: TwelveDataKeyHealth[];
 }
 
 interface RefreshResponse {
@@ -123,6 +423,7 @@ interface ConfigFieldStatus {
   env_var: string;
   required: boolean;
   is_set: boolean;
+  is_stored: boolean;
 }
 
 interface ConfigStatus {
@@ -185,6 +486,7 @@ interface Job {
   started_at: string;
   completed_at: string | null;
   duration_secs: number | null;
+  progress: { done: number; total: number } | null;
 }
 
 interface JobsListResponse {
@@ -192,6 +494,405 @@ interface JobsListResponse {
   count: number;
 }
 
+type AlertRulePriority = 'critical' | 'high' | 'medium' | 'low';
+
+interface AlertRule {
+  id: number;
+  owner: string;
+  name: string;
+  expression: string;
+  symbols: string[];
+  priority: AlertRulePriority;
+  enabled: boolean;
+  last_triggered_at: string | null;
+  created_at: string;
+  updated_at: string;
+  deleted_at: string | null;
+}
+
+interface AlertRuleInput {
+  name: string;
+  expression: string;
+  symbols?: string[];
+  priority?: AlertRulePriority;
+  enabled?: boolean;
+}
+
+interface AlertRuleEvaluation {
+  rule_id: number;
+  symbol: string;
+  matched: boolean;
+  values: Record<string, number>;
+  missing_metrics: string[];
+}
+
+interface AlertRuleSimulation {
+  expression: string;
+  from: string;
+  to: string;
+  symbols_evaluated: number;
+  evaluations: number;
+  fired: number;
+  fire_rate: number;
+  noisy: boolean;
+  by_symbol: { symbol: string; fired: number }[];
+  // Newest first, at most 500
+  firings: { symbol: string; date: string; values: Record<string, number> }[];
+  missing_metrics: string[];
+}
+
+interface ChangeRecord {
+  id: string;
+  timestamp: string;
+  action: string;
+  username: string | null;
+  resource_type: string;
+  resource_id: string | null;
+  before: Record<string, unknown> | null;
+  after: Record<string, unknown> | null;
+  changed_fields: string[];
+}
+
+type AlertPriority = 'Critical' | 'High' | 'Medium' | 'Low';
+
+type NotificationChannel =
+  | 'Email'
+  | 'Telegram'
+  | 'WebPush'
+  | 'Webhook'
+  | 'InApp'
+  | 'Discord'
+  | 'Slack'
+  | 'WhatsApp';
+
+interface AlertTypeFilter {
+  broker_alerts: boolean;
+  technical_alerts: boolean;
+  coordinated_buying: boolean;
+  foreign_flow: boolean;
+  wyckoff_events: boolean;
+  rsi_signals: boolean;
+  macd_crossovers: boolean;
+  volume_spikes: boolean;
+  price_breakouts: boolean;
+  earnings_upcoming: boolean;
+}
+
+interface QuietHours {
+  start: string;
+  end: string;
+  min_priority: AlertPriority;
+}
+
+interface AlertSubscription {
+  user_id: string;
+  symbols: string[];
+  alert_types: AlertTypeFilter;
+  min_priority: AlertPriority;
+  channels: NotificationChannel[];
+  quiet_hours: QuietHours | null;
+}
+
+interface AlertSubscriptionResponse {
+  symbol: string;
+  subscription: AlertSubscription | null;
+}
+
+interface AlertSubscriptionInput {
+  alert_types?: AlertTypeFilter;
+  min_priority?: AlertPriority;
+  channels: NotificationChannel[];
+  quiet_hours?: QuietHours | null;
+}
+
+type TradeSide = 'buy' | 'sell';
+
+interface PortfolioTransaction {
+  id: number;
+  user_id: string;
+  symbol: string;
+  side: TradeSide;
+  lots: number;
+  price: number;
+  fees: number;
+  executed_at: string;
+  note: string | null;
+  created_at: string;
+  updated_at: string;
+}
+
+interface PortfolioTransactionInput {
+  symbol: string;
+  side: TradeSide;
+  lots: number;
+  price: number;
+  fees?: number;
+  executed_at?: string;
+  note?: string;
+}
+
+interface PortfolioHolding {
+  user_id: string;
+  symbol: string;
+  shares: number;
+  average_cost: number;
+  cost_basis: number;
+  realized_pnl: number;
+  updated_at: string;
+}
+
+interface HoldingValuation {
+  symbol: string;
+  sector: string;
+  shares: number;
+  lots: number;
+  average_cost: string;
+  cost_basis: string;
+  price: string;
+  market_value: string;
+  unrealized_pnl: string;
+  unrealized_pnl_pct: string | null;
+  realized_pnl: string;
+  weight_pct: string;
+  composite_score: string | null;
+}
+
+interface SectorExposure {
+  sector: string;
+  market_value: string;
+  weight_pct: string;
+  symbols: string[];
+}
+
+interface PortfolioSummary {
+  holdings: HoldingValuation[];
+  cost_basis: string;
+  market_value: string;
+  unrealized_pnl: string;
+  unrealized_pnl_pct: string | null;
+  realized_pnl: string;
+  weighted_composite_score: string | null;
+  sector_exposure: SectorExposure[];
+}
+
+interface AlertHistoryEntry {
+  id: number;
+  alert_id: number | null;
+  triggered_at: string | null;
+  trigger_value: Record<string, unknown> | null;
+  notification_sent: boolean | null;
+  symbol: string | null;
+  category: 'broker' | 'technical' | 'event' | null;
+  kind: string | null;
+  priority: AlertRulePriority | null;
+  message: string | null;
+  acknowledged: boolean;
+  acknowledged_at: string | null;
+}
+
+interface AlertHistoryQuery {
+  symbol?: string;
+  since?: string;
+  priority?: AlertRulePriority;
+  acknowledged?: boolean;
+  limit?: number;
+}
+
+interface InAppNotification {
+  title: string;
+  body: string;
+  priority: 'Critical' | 'High' | 'Medium' | 'Low';
+  metadata: {
+    symbol: string | null;
+    alert_id: string | null;
+    action_url: string | null;
+    icon: string | null;
+  };
+  alert: Record<string, unknown> | null;
+  sent_at: string;
+}
+
+// Intraday composite score change from the score ticker
+interface ScoreUpdate {
+  symbol: string;
+  technical_score: number;
+  fundamental_score: number;
+  composite_score: number;
+  // Change from the last stored composite score
+  composite_change: number;
+  timestamp: number;
+}
+
+type MaintenanceTask = 'analyze' | 'refresh_aggregates' | 'stale_scores' | 'orphaned_jobs';
+
+type NotificationRetryStatus = 'pending' | 'dead';
+
+interface FailedNotification {
+  id: number;
+  channel: string;
+  recipient_id: string;
+  payload: Record<string, unknown>;
+  attempts: number;
+  last_error: string;
+  status: NotificationRetryStatus;
+  next_attempt_at: string;
+  created_at: string;
+  updated_at: string;
+}
+
+interface FailedNotificationsResponse {
+  status: NotificationRetryStatus;
+  notifications: FailedNotification[];
+  count: number;
+}
+
+interface RetryNotificationResponse {
+  id: number;
+  delivered: boolean;
+  status: NotificationRetryStatus | null;
+  last_error: string | null;
+}
+
+interface StoredSecretInfo {
+  name: string;
+  key_id: string;
+  needs_rotation: boolean;
+  overridden_by_env: boolean;
+  updated_at: string;
+}
+
+interface StoredSecretsResponse {
+  enabled: boolean;
+  current_key_id: string | null;
+  storable: string[];
+  secrets: StoredSecretInfo[];
+}
+
+interface RotateSecretsResponse {
+  current_key_id: string;
+  rotated: number;
+}
+
+interface BackfillJob {
+  id: number;
+  source: string;
+  intervals: string[];
+  start_date: string;
+  end_date: string;
+  status: 'pending' | 'running' | 'completed' | 'failed';
+  bars_written: number;
+  last_error: string | null;
+  created_at: string;
+  updated_at: string;
+  completed_at: string | null;
+  tasks_total: number;
+  tasks_done: number;
+  tasks_failed: number;
+}
+
+interface BackfillRequest {
+  job_id?: number;
+  intervals?: string[];
+  start_date?: string;
+  end_date?: string;
+  credits_per_minute?: number;
+}
+
+interface BackfillResponse {
+  backfill: BackfillJob;
+  job: Job;
+}
+
+interface BackfillJobsResponse {
+  jobs: BackfillJob[];
+  count: number;
+}
+
+interface PriceProviderHealth {
+  name: string;
+  priority: number;
+  requests: number;
+  failures: number;
+  consecutive_failures: number;
+  available: boolean;
+  cooldown_remaining_secs: number | null;
+  last_error: string | null;
+  last_success: string | null;
+}
+
+interface PriceProviderHealthResponse {
+  timestamp: string;
+  providers: PriceProviderHealth[];
+}
+
+type UsageEventKind = 'symbol_viewed' | 'screen_run' | 'alert_clicked';
+
+interface UsageSubjectCount {
+  kind: UsageEventKind;
+  subject: string;
+  events: number;
+  actors: number;
+}
+
+interface UsageDailyCount {
+  day: string;
+  kind: UsageEventKind;
+  events: number;
+}
+
+interface UsageAnalyticsResponse {
+  since: string;
+  privacy: 'off' | 'anonymous' | 'hashed' | 'full';
+  totals: Partial<Record<UsageEventKind, number>>;
+  distinct_actors: number;
+  top: Partial<Record<UsageEventKind, UsageSubjectCount[]>>;
+  daily: UsageDailyCount[];
+}
+
+interface AdminOverview {
+  generated_at: string;
+  requests: {
+    requests_last_hour: number;
+    requests_per_minute: number;
+    server_error_rate_pct: number;
+    client_error_rate_pct: number;
+    active_users: number;
+  };
+  analysis_cache: {
+    hits: number;
+    misses: number;
+    hit_rate_pct: number | null;
+  };
+  price_store: {
+    listening: boolean;
+    symbols: number;
+    bars: number;
+    hits: number;
+    misses: number;
+  };
+  tick_queue: {
+    depth: number;
+    capacity: number;
+    max_depth: number;
+    enqueued: number;
+    dropped: number;
+    flushed: number;
+    batches: number;
+  };
+  jobs: Record<JobStatus, number>;
+  data_sources: {
+    configured: number;
+    breached: number;
+    sources: {
+      source_id: string;
+      freshness_hours: number | null;
+      freshness_threshold_hours: number;
+      breached: boolean;
+    }[];
+  };
+  alerts_today: number;
+}
+
 interface TriggerResponse {
   source_id: string;
   status: string;
@@ -278,6 +979,393 @@ interface InstitutionalFlowAnalysis {
   signal_description: string;
 }
 
+interface FootprintLevel {
+  price: number;
+  buy_volume: number;
+  sell_volume: number;
+}
+
+interface FootprintBar {
+  start: string;
+  open: number;
+  high: number;
+  low: number;
+  close: number;
+  levels: FootprintLevel[];
+  buy_volume: number;
+  sell_volume: number;
+  delta: number;
+  poc: number;
+}
+
+interface FootprintResponse {
+  symbol: string;
+  bar_seconds: number;
+  last_tick_at: string | null;
+  bars: FootprintBar[];
+  delta: number[];
+  cumulative_delta: number[];
+}
+
+interface MomentumHorizon {
+  sessions: number;
+  weight: string;
+}
+
+interface MomentumRank {
+  rank: number;
+  symbol: string;
+  returns: string[];
+  score: string;
+  percentile: string;
+}
+
+interface MomentumRankingResponse {
+  horizons: MomentumHorizon[];
+  skip_sessions: number;
+  ranked: number;
+  computed_at: string;
+  ranks: MomentumRank[];
+}
+
+interface MomentumQuery {
+  horizons?: number[];
+  weights?: number[];
+  skipMonth?: boolean;
+  limit?: number;
+}
+
+type PairSignal = 'ratio_cheap' | 'ratio_rich' | 'neutral';
+
+interface RatioPoint {
+  date: string;
+  ratio: number;
+  zscore: number | null;
+}
+
+interface CointegrationResult {
+  hedge_ratio: number;
+  intercept: number;
+  adf_statistic: number;
+  critical_value_5pct: number;
+  is_cointegrated: boolean;
+  half_life: number | null;
+}
+
+interface PairAnalysisResponse {
+  long: string;
+  short: string;
+  window: number;
+  sessions: number;
+  latest_ratio: number | null;
+  latest_zscore: number | null;
+  signal: PairSignal | null;
+  cointegration: CointegrationResult | null;
+  ratios: RatioPoint[];
+}
+
+type CorporateEventKind = 'earnings' | 'dividend' | 'rights_issue';
+
+interface EventWindow {
+  pre_sessions: number;
+  post_sessions: number;
+  estimation_sessions: number;
+}
+
+interface EventStudySummary {
+  events: number;
+  offsets: number[];
+  average_abnormal_returns: number[];
+  cumulative_average: number[];
+  mean_car: number;
+  car_t_stat: number | null;
+  positive_car_pct: number;
+}
+
+interface SymbolEvent {
+  symbol: string;
+  event_date: string;
+  day0: string;
+  abnormal_returns: number[];
+  car: number;
+}
+
+interface EventStudyResponse {
+  event: CorporateEventKind;
+  symbol: string | null;
+  sector: string | null;
+  window: EventWindow;
+  model: 'market_model' | 'constant_mean';
+  events_found: number;
+  summary: EventStudySummary | null;
+  events: SymbolEvent[];
+}
+
+interface EventStudyQuery {
+  event: CorporateEventKind;
+  symbol?: string;
+  sector?: string;
+  years?: number;
+  pre?: number;
+  post?: number;
+}
+
+interface SymbolBeta {
+  symbol: string;
+  observations: number;
+  beta: number | null;
+  index_correlation: number | null;
+}
+
+interface CorrelationResponse {
+  days: number;
+  symbols: string[];
+  matrix: (number | null)[][];
+  betas: SymbolBeta[];
+}
+
+type RotationQuadrant = 'leading' | 'weakening' | 'lagging' | 'improving';
+
+interface RotationConfig {
+  short_sessions: number;
+  medium_sessions: number;
+  long_sessions: number;
+  trail_points: number;
+}
+
+interface RotationPoint {
+  date: string;
+  rs_ratio: number;
+  rs_momentum: number;
+}
+
+interface SectorRotation {
+  sector: string;
+  constituents: number;
+  relative_return_1w: number;
+  relative_return_4w: number;
+  relative_return_12w: number;
+  rs_ratio: number;
+  rs_momentum: number;
+  quadrant: RotationQuadrant;
+  trail: RotationPoint[];
+}
+
+interface SectorRotationResponse {
+  config: RotationConfig;
+  computed_at: string;
+  sectors: SectorRotation[];
+}
+
+interface OrderFlowDay {
+  symbol: string;
+  trade_date: string;
+  obi: number;
+  ofi: number;
+  ofi_zscore: number | null;
+  computed_at: string;
+  source: 'proxy' | 'depth';
+}
+
+interface OrderFlowHistoryResponse {
+  symbol: string;
+  zscore_period: number;
+  days: OrderFlowDay[];
+}
+
+interface BookLevel {
+  price: string;
+  volume: number;
+}
+
+interface DepthSnapshot {
+  time: string;
+  bids: BookLevel[];
+  asks: BookLevel[];
+}
+
+interface LiveOrderFlow {
+  time: string;
+  obi: string;
+  ofi: string;
+  cumulative_ofi: string;
+  snapshots: number;
+}
+
+interface OrderBookInput {
+  time?: string;
+  bids: { price: number | string; volume: number }[];
+  asks: { price: number | string; volume: number }[];
+}
+
+interface OrderBookResponse {
+  symbol: string;
+  levels: number;
+  snapshot: DepthSnapshot;
+  mid_price: string | null;
+  top_obi: string;
+  flow: LiveOrderFlow;
+}
+
+interface VolumeAtPrice {
+  low: string;
+  high: string;
+  volume: string;
+}
+
+interface VolumeProfile {
+  levels: VolumeAtPrice[];
+  total_volume: string;
+  point_of_control: string;
+  value_area_high: string;
+  value_area_low: string;
+}
+
+interface VolumeProfileResponse {
+  symbol: string;
+  days: number;
+  sessions: number;
+  last_close: string;
+  profile: VolumeProfile;
+}
+
+type WyckoffPhase = 'accumulation' | 'markup' | 'distribution' | 'markdown' | 'unknown';
+
+interface WyckoffPhaseSpan {
+  phase: WyckoffPhase;
+  start_index: number;
+  start_time: string;
+  end_index: number;
+  end_time: string;
+  peak_confidence: number;
+}
+
+interface WyckoffTransition {
+  from: WyckoffPhase;
+  to: WyckoffPhase;
+  index: number;
+  time: string;
+}
+
+type WyckoffProfile = 'blue_chip' | 'mid_cap' | 'small_cap';
+
+interface WyckoffConfig {
+  trend_lookback: number;
+  volume_lookback: number;
+  volume_spike_threshold: string;
+  sr_tolerance: string;
+  min_phase_bars: number;
+  narrow_spread_ratio: string;
+  wide_spread_ratio: string;
+  heavy_volume_ratio: string;
+  light_volume_ratio: string;
+}
+
+interface WyckoffRange {
+  bars: number;
+  height_pct: string;
+  support_tests: number;
+  volume_dry_up: string;
+  cause_quality: number;
+}
+
+interface WyckoffHistoryOptions {
+  profile?: WyckoffProfile;
+  volumeSpikeThreshold?: number;
+  srTolerance?: number;
+}
+
+interface WyckoffHistoryResponse {
+  symbol: string;
+  days: number;
+  sessions: number;
+  profile: WyckoffProfile;
+  config: WyckoffConfig;
+  current_phase: WyckoffPhase | null;
+  sessions_in_phase: number;
+  current_range: WyckoffRange | null;
+  phases: WyckoffPhaseSpan[];
+  transitions: WyckoffTransition[];
+  last_accumulation_to_markup: WyckoffTransition | null;
+  last_distribution_to_markdown: WyckoffTransition | null;
+}
+
+type IndicatorCategory = 'trend' | 'momentum' | 'volatility' | 'volume' | 'order_flow' | 'pattern';
+
+interface IndicatorParam {
+  name: string;
+  description: string;
+  default: number;
+  integer: boolean;
+}
+
+interface IndicatorOutput {
+  name: string;
+  description: string;
+}
+
+interface IndicatorInfo {
+  key: string;
+  name: string;
+  category: IndicatorCategory;
+  description: string;
+  inputs: string[];
+  params: IndicatorParam[];
+  outputs: IndicatorOutput[];
+  warmup: number;
+}
+
+type BulkAnalysisField =
+  | 'score'
+  | 'technical_score'
+  | 'fundamental_score'
+  | 'close'
+  | 'rsi'
+  | 'rvol'
+  | 'wyckoff_phase'
+  | 'broker_accum';
+
+interface BulkAnalysisRow {
+  symbol: string;
+  [field: string]: string | null;
+}
+
+interface BulkAnalysisResponse {
+  sector: string | null;
+  index: string | null;
+  fields: BulkAnalysisField[];
+  snapshot_at: string | null;
+  rows: BulkAnalysisRow[];
+}
+
+interface CustomIndicator {
+  id: number;
+  owner: string;
+  name: string;
+  expression: string;
+  description: string | null;
+  created_at: string;
+  updated_at: string;
+}
+
+interface CustomIndicatorInput {
+  name: string;
+  expression: string;
+  description?: string;
+}
+
+interface CustomIndicatorValue {
+  time: string;
+  value: string | null;
+}
+
+interface CustomIndicatorValues {
+  symbol: string;
+  expression: string;
+  latest: string | null;
+  values: CustomIndicatorValue[];
+}
+
 interface BrokerSummaryResponse {
   big_buyers: BrokerInfo[];
   big_sellers: BrokerInfo[];
@@ -307,6 +1395,7 @@ interface BollingerResponse {
 
 interface TechnicalResponse {
   last_price: number;
+  as_of: string;
   rsi: number;
   rsi_signal: string;
   macd: number;
@@ -349,6 +1438,29 @@ interface FullAnalysisResponse {
   technical: TechnicalResponse | null;
   valuation: ValuationResponse | null;
   conclusion: ConclusionResponse | null;
+  sections: Record<string, AnalysisSection>;
+  /** Only with `debugTimings` */
+  debug_timings?: AnalysisTimingReport;
+}
+
+interface StageTiming {
+  stage: string;
+  ms: number;
+  calls: number;
+}
+
+/** Stage breakdown of one computed analysis, slowest stage first */
+interface AnalysisTimingReport {
+  total_ms: number;
+  stages: StageTiming[];
+}
+
+type SectionStatus = 'ok' | 'insufficient_data' | 'source_error' | 'stale';
+
+interface AnalysisSection {
+  status: SectionStatus;
+  message: string | null;
+  elapsed_ms: number;
 }
 
 class ApiClient {
@@ -492,6 +1604,20 @@ class ApiClient {
     return this.fetch(`/api/stocks/${symbol}/score`);
   }
 
+  async getStockNews(symbol: string, limit?: number): Promise<StockNews[]> {
+    const query = limit ? `?limit=${limit}` : '';
+    return this.fetch(`/api/stocks/${symbol}/news${query}`);
+  }
+
+  async getStockEarnings(symbol: string): Promise<EarningsCalendarEntry[]> {
+    return this.fetch(`/api/stocks/${symbol}/earnings`);
+  }
+
+  async getEarningsCalendar(week?: string): Promise<EarningsWeek> {
+    const query = week ? `?week=${encodeURIComponent(week)}` : '';
+    return this.fetch(`/api/calendar/earnings${query}`);
+  }
+
   async getStockFreshness(symbol: string): Promise<StockFreshness> {
     return this.fetch(`/api/stocks/${symbol}/freshness`);
   }
@@ -509,8 +1635,141 @@ class ApiClient {
     return this.fetch(`/api/stocks/scores/top${params}`);
   }
 
-  async recomputeScores(): Promise<RecomputeScoresResponse> {
-    return this.fetch('/api/stocks/scores/recompute', { method: 'POST' });
+  async screenStocks(filter: ScreenFilter, limit?: number): Promise<ScreenResponse> {
+    return this.fetch('/api/stocks/screen', {
+      method: 'POST',
+      body: JSON.stringify({ filter, limit })
+    });
+  }
+
+  // Saved screens
+  async listSavedScreens(): Promise<SavedScreen[]> {
+    return this.fetch('/api/screens');
+  }
+
+  async getSavedScreen(id: number): Promise<SavedScreen> {
+    return this.fetch(`/api/screens/${id}`);
+  }
+
+  async createSavedScreen(input: SavedScreenInput): Promise<SavedScreen> {
+    return this.fetch('/api/screens', {
+      method: 'POST',
+      body: JSON.stringify(input)
+    });
+  }
+
+  async updateSavedScreen(id: number, input: SavedScreenInput): Promise<SavedScreen> {
+    return this.fetch(`/api/screens/${id}`, {
+      method: 'PUT',
+      body: JSON.stringify(input)
+    });
+  }
+
+  async deleteSavedScreen(id: number): Promise<{ success: boolean }> {
+    return this.fetch(`/api/screens/${id}`, { method: 'DELETE' });
+  }
+
+  async runSavedScreen(id: number): Promise<ScreenRunResponse> {
+    return this.fetch(`/api/screens/${id}/run`, { method: 'POST' });
+  }
+
+  // Backtests
+  async listBacktests(symbol?: string): Promise<BacktestRunSummary[]> {
+    const query = symbol ? `?symbol=${encodeURIComponent(symbol)}` : '';
+    return this.fetch(`/api/backtests${query}`);
+  }
+
+  async getBacktest(id: number): Promise<BacktestRun> {
+    return this.fetch(`/api/backtests/${id}`);
+  }
+
+  async runBacktest(input: RunBacktestInput): Promise<BacktestRun> {
+    return this.fetch('/api/backtests', {
+      method: 'POST',
+      body: JSON.stringify(input)
+    });
+  }
+
+  async deleteBacktest(id: number): Promise<{ success: boolean }> {
+    return this.fetch(`/api/backtests/${id}`, { method: 'DELETE' });
+  }
+
+  async shareBacktest(id: number): Promise<ShareBacktestResponse> {
+    return this.fetch(`/api/backtests/${id}/share`, { method: 'POST' });
+  }
+
+  async unshareBacktest(id: number): Promise<{ success: boolean }> {
+    return this.fetch(`/api/backtests/${id}/share`, { method: 'DELETE' });
+  }
+
+  async getSharedBacktest(token: string): Promise<SharedBacktest> {
+    return this.fetch(`/api/shared/backtests/${token}`);
+  }
+
+  async listOptimizations(): Promise<BacktestOptimization[]> {
+    return this.fetch('/api/backtests/optimizations');
+  }
+
+  async getOptimization(id: number, limit?: number): Promise<OptimizationDetail> {
+    const query = limit ? `?limit=${limit}` : '';
+    return this.fetch(`/api/backtests/optimizations/${id}${query}`);
+  }
+
+  async runOptimization(
+    input: RunOptimizationInput
+  ): Promise<{ optimization: BacktestOptimization; job: Job }> {
+    return this.fetch('/api/backtests/optimizations', {
+      method: 'POST',
+      body: JSON.stringify(input)
+    });
+  }
+
+  async deleteOptimization(id: number): Promise<{ success: boolean }> {
+    return this.fetch(`/api/backtests/optimizations/${id}`, { method: 'DELETE' });
+  }
+
+  // Custom indicators
+  async listCustomIndicators(): Promise<CustomIndicator[]> {
+    return this.fetch('/api/custom-indicators');
+  }
+
+  async createCustomIndicator(input: CustomIndicatorInput): Promise<CustomIndicator> {
+    return this.fetch('/api/custom-indicators', {
+      method: 'POST',
+      body: JSON.stringify(input)
+    });
+  }
+
+  async updateCustomIndicator(id: number, input: CustomIndicatorInput): Promise<CustomIndicator> {
+    return this.fetch(`/api/custom-indicators/${id}`, {
+      method: 'PUT',
+      body: JSON.stringify(input)
+    });
+  }
+
+  async deleteCustomIndicator(id: number): Promise<{ success: boolean }> {
+    return this.fetch(`/api/custom-indicators/${id}`, { method: 'DELETE' });
+  }
+
+  async previewCustomIndicator(
+    expression: string,
+    symbol: string,
+    days?: number
+  ): Promise<CustomIndicatorValues> {
+    return this.fetch('/api/custom-indicators/preview', {
+      method: 'POST',
+      body: JSON.stringify({ expression, symbol, days })
+    });
+  }
+
+  async getCustomIndicatorValues(id: number, symbol: string, days = 90): Promise<CustomIndicatorValues> {
+    return this.fetch(`/api/custom-indicators/${id}/values/${symbol}?days=${days}`);
+  }
+
+  /** Symbols with unchanged inputs are skipped unless `force` is set */
+  async recomputeScores(force = false): Promise<RecomputeScoresResponse> {
+    const query = force ? '?force=true' : '';
+    return this.fetch(`/api/stocks/scores/recompute${query}`, { method: 'POST' });
   }
 
   // Watchlist
@@ -529,6 +1788,175 @@ class ApiClient {
     await this.fetch(`/api/watchlist/${symbol}`, { method: 'DELETE' });
   }
 
+  async getDeletedWatchlist(): Promise<WatchlistItem[]> {
+    return this.fetch('/api/watchlist/deleted');
+  }
+
+  async restoreWatchlist(symbol?: string): Promise<WatchlistItem> {
+    return this.fetch('/api/watchlist/restore', {
+      method: 'POST',
+      body: JSON.stringify({ symbol })
+    });
+  }
+
+  async getWatchlistHistory(symbol?: string, limit?: number): Promise<ChangeRecord[]> {
+    const params = new URLSearchParams();
+    if (symbol) params.set('symbol', symbol);
+    if (limit) params.set('limit', limit.toString());
+    const query = params.toString();
+    return this.fetch(`/api/watchlist/history${query ? `?${query}` : ''}`);
+  }
+
+  // Alert preferences
+  async listAlertSubscriptions(): Promise<AlertSubscriptionResponse[]> {
+    return this.fetch('/api/watchlist/alerts');
+  }
+
+  async getAlertSubscription(symbol: string): Promise<AlertSubscriptionResponse> {
+    return this.fetch(`/api/watchlist/${symbol}/alerts`);
+  }
+
+  async updateAlertSubscription(
+    symbol: string,
+    input: AlertSubscriptionInput
+  ): Promise<AlertSubscriptionResponse> {
+    return this.fetch(`/api/watchlist/${symbol}/alerts`, {
+      method: 'PUT',
+      body: JSON.stringify(input),
+    });
+  }
+
+  async deleteAlertSubscription(symbol: string): Promise<void> {
+    await this.fetch(`/api/watchlist/${symbol}/alerts`, { method: 'DELETE' });
+  }
+
+  // Portfolio
+  async getPortfolio(): Promise<PortfolioSummary> {
+    return this.fetch('/api/portfolio');
+  }
+
+  async getPortfolioHoldings(): Promise<PortfolioHolding[]> {
+    return this.fetch('/api/portfolio/holdings');
+  }
+
+  async getPortfolioTransactions(symbol?: string): Promise<PortfolioTransaction[]> {
+    const query = symbol ? `?symbol=${encodeURIComponent(symbol)}` : '';
+    return this.fetch(`/api/portfolio/transactions${query}`);
+  }
+
+  async addPortfolioTransaction(input: PortfolioTransactionInput): Promise<PortfolioTransaction> {
+    return this.fetch('/api/portfolio/transactions', {
+      method: 'POST',
+      body: JSON.stringify(input),
+    });
+  }
+
+  async updatePortfolioTransaction(
+    id: number,
+    input: PortfolioTransactionInput
+  ): Promise<PortfolioTransaction> {
+    return this.fetch(`/api/portfolio/transactions/${id}`, {
+      method: 'PUT',
+      body: JSON.stringify(input),
+    });
+  }
+
+  async deletePortfolioTransaction(id: number): Promise<void> {
+    await this.fetch(`/api/portfolio/transactions/${id}`, { method: 'DELETE' });
+  }
+
+  // Alert history
+  async getAlerts(query: AlertHistoryQuery = {}): Promise<AlertHistoryEntry[]> {
+    const params = new URLSearchParams();
+    if (query.symbol) params.set('symbol', query.symbol);
+    if (query.since) params.set('since', query.since);
+    if (query.priority) params.set('priority', query.priority);
+    if (query.acknowledged !== undefined) params.set('acknowledged', String(query.acknowledged));
+    if (query.limit) params.set('limit', query.limit.toString());
+    const qs = params.toString();
+    return this.fetch(`/api/alerts${qs ? `?${qs}` : ''}`);
+  }
+
+  async acknowledgeAlert(id: number): Promise<AlertHistoryEntry> {
+    return this.fetch(`/api/alerts/${id}/ack`, { method: 'POST' });
+  }
+
+  // Records the click for usage analytics
+  async clickAlert(id: number): Promise<AlertHistoryEntry> {
+    return this.fetch(`/api/alerts/${id}/click`, { method: 'POST' });
+  }
+
+  // In-app notifications; the caller closes the returned socket
+  connectNotifications(onNotification: (notification: InAppNotification) => void): WebSocket {
+    const base = API_BASE || window.location.origin;
+    const url = new URL('/api/ws/notifications', base.replace(/^http/, 'ws'));
+    const token = this.getToken();
+    if (token) url.searchParams.set('token', token);
+
+    const socket = new WebSocket(url.toString());
+    socket.onmessage = (event) => {
+      onNotification(JSON.parse(event.data) as InAppNotification);
+    };
+    return socket;
+  }
+
+  // Intraday score changes; the caller closes the returned source
+  connectScoreStream(onUpdate: (update: ScoreUpdate) => void): EventSource {
+    const source = new EventSource(`${API_BASE}/api/stream/scores`, { withCredentials: true });
+    source.onmessage = (event) => {
+      const message = JSON.parse(event.data) as { type: string; data: ScoreUpdate };
+      if (message.type === 'ScoreUpdate') onUpdate(message.data);
+    };
+    return source;
+  }
+
+  // Alert rules
+  async getAlertRules(): Promise<AlertRule[]> {
+    return this.fetch('/api/alerts/rules');
+  }
+
+  async getAlertRuleMetrics(): Promise<{ metric: string; description: string }[]> {
+    return this.fetch('/api/alerts/rules/metrics');
+  }
+
+  async createAlertRule(rule: AlertRuleInput): Promise<AlertRule> {
+    return this.fetch('/api/alerts/rules', {
+      method: 'POST',
+      body: JSON.stringify(rule)
+    });
+  }
+
+  async updateAlertRule(id: number, rule: AlertRuleInput): Promise<AlertRule> {
+    return this.fetch(`/api/alerts/rules/${id}`, {
+      method: 'PUT',
+      body: JSON.stringify(rule)
+    });
+  }
+
+  async deleteAlertRule(id: number): Promise<void> {
+    await this.fetch(`/api/alerts/rules/${id}`, { method: 'DELETE' });
+  }
+
+  async restoreAlertRule(id: number): Promise<AlertRule> {
+    return this.fetch(`/api/alerts/rules/${id}/restore`, { method: 'POST' });
+  }
+
+  async getAlertRuleHistory(id?: number): Promise<ChangeRecord[]> {
+    return this.fetch(id === undefined ? '/api/alerts/rules/history' : `/api/alerts/rules/${id}/history`);
+  }
+
+  async evaluateAlertRule(id: number, symbol: string): Promise<AlertRuleEvaluation> {
+    return this.fetch(`/api/alerts/rules/${id}/evaluate/${symbol}`);
+  }
+
+  // Replays a draft rule over the last 90 days; no symbols means the watchlist
+  async simulateAlertRule(expression: string, symbols: string[] = []): Promise<AlertRuleSimulation> {
+    return this.fetch('/api/alerts/rules/simulate', {
+      method: 'POST',
+      body: JSON.stringify({ expression, symbols })
+    });
+  }
+
   // Fundamentals
   async getFundamentals(symbol: string): Promise<FundamentalData | null> {
     try {
@@ -543,10 +1971,18 @@ class ApiClient {
   }
 
   // Analysis
-  async getFullAnalysis(symbol: string, days?: number): Promise<FullAnalysisResponse | null> {
+  /** `debugTimings` bypasses the cache and returns the stage timings */
+  async getFullAnalysis(
+    symbol: string,
+    days?: number,
+    debugTimings = false
+  ): Promise<FullAnalysisResponse | null> {
     try {
-      const params = days ? `?days=${days}` : '';
-      return await this.fetchWithTimeout<FullAnalysisResponse>(`/api/analysis/${symbol}/analysis${params}`);
+      const params = new URLSearchParams();
+      if (days) params.set('days', String(days));
+      if (debugTimings) params.set('debug_timings', 'true');
+      const query = params.toString() ? `?${params}` : '';
+      return await this.fetchWithTimeout<FullAnalysisResponse>(`/api/analysis/${symbol}/analysis${query}`);
     } catch (error) {
       if (error instanceof Error && (error.message.includes('404') || error.message.includes('400'))) {
         return null;
@@ -555,10 +1991,18 @@ class ApiClient {
     }
   }
 
-  async getTechnicals(symbol: string, days?: number): Promise<TechnicalResponse | null> {
+  /** `adjusted` computes indicators on split/dividend-adjusted prices */
+  async getTechnicals(
+    symbol: string,
+    days?: number,
+    adjusted = false
+  ): Promise<TechnicalResponse | null> {
     try {
-      const params = days ? `?days=${days}` : '';
-      return await this.fetchWithTimeout<TechnicalResponse>(`/api/analysis/${symbol}/technicals${params}`);
+      const params = new URLSearchParams();
+      if (days) params.set('days', String(days));
+      if (adjusted) params.set('adjusted', 'true');
+      const query = params.toString() ? `?${params}` : '';
+      return await this.fetchWithTimeout<TechnicalResponse>(`/api/analysis/${symbol}/technicals${query}`);
     } catch (error) {
       if (error instanceof Error && (error.message.includes('404') || error.message.includes('400'))) {
         return null;
@@ -567,6 +2011,111 @@ class ApiClient {
     }
   }
 
+  async getFootprint(symbol: string, barSeconds = 60): Promise<FootprintResponse | null> {
+    try {
+      return await this.fetch<FootprintResponse>(
+        `/api/analysis/${symbol}/footprint?bar_seconds=${barSeconds}`
+      );
+    } catch (error) {
+      if (error instanceof Error && error.message.includes('404')) {
+        return null;
+      }
+      throw error;
+    }
+  }
+
+  async getMomentumRanking(query: MomentumQuery = {}): Promise<MomentumRankingResponse> {
+    const params = new URLSearchParams();
+    if (query.horizons) params.set('horizons', query.horizons.join(','));
+    if (query.weights) params.set('weights', query.weights.join(','));
+    if (query.skipMonth) params.set('skip_month', 'true');
+    if (query.limit) params.set('limit', query.limit.toString());
+    const qs = params.toString();
+    return this.fetch(`/api/analysis/momentum${qs ? `?${qs}` : ''}`);
+  }
+
+  async getPairAnalysis(
+    long: string,
+    short: string,
+    days = 365,
+    window = 60,
+  ): Promise<PairAnalysisResponse> {
+    const params = new URLSearchParams({
+      long,
+      short,
+      days: days.toString(),
+      window: window.toString(),
+    });
+    return this.fetch(`/api/analysis/pairs?${params}`);
+  }
+
+  async getCorrelation(symbols: string[], days = 90): Promise<CorrelationResponse> {
+    const params = new URLSearchParams({ symbols: symbols.join(','), days: days.toString() });
+    return this.fetch(`/api/analysis/correlation?${params}`);
+  }
+
+  async getEventStudy(query: EventStudyQuery): Promise<EventStudyResponse> {
+    const params = new URLSearchParams({ event: query.event });
+    if (query.symbol) params.set('symbol', query.symbol);
+    if (query.sector) params.set('sector', query.sector);
+    if (query.years) params.set('years', query.years.toString());
+    if (query.pre !== undefined) params.set('pre', query.pre.toString());
+    if (query.post !== undefined) params.set('post', query.post.toString());
+    return this.fetch(`/api/analysis/event-study?${params}`);
+  }
+
+  async getSectorRotation(): Promise<SectorRotationResponse> {
+    return this.fetch('/api/analysis/sectors/rotation');
+  }
+
+  async getOrderFlowHistory(symbol: string, days = 90): Promise<OrderFlowHistoryResponse> {
+    return this.fetch(`/api/analysis/${symbol}/order-flow?days=${days}`);
+  }
+
+  async getOrderBook(symbol: string): Promise<OrderBookResponse> {
+    return this.fetch(`/api/analysis/${symbol}/order-book`);
+  }
+
+  async postOrderBook(symbol: string, book: OrderBookInput): Promise<OrderBookResponse> {
+    return this.fetch(`/api/analysis/${symbol}/order-book`, {
+      method: 'POST',
+      body: JSON.stringify(book)
+    });
+  }
+
+  async getVolumeProfile(symbol: string, days = 60): Promise<VolumeProfileResponse> {
+    return this.fetch(`/api/analysis/${symbol}/volume-profile?days=${days}`);
+  }
+
+  async getWyckoffHistory(
+    symbol: string,
+    days = 365,
+    options: WyckoffHistoryOptions = {},
+  ): Promise<WyckoffHistoryResponse> {
+    const params = new URLSearchParams({ days: days.toString() });
+    if (options.profile) params.set('profile', options.profile);
+    if (options.volumeSpikeThreshold !== undefined) {
+      params.set('volume_spike_threshold', options.volumeSpikeThreshold.toString());
+    }
+    if (options.srTolerance !== undefined) {
+      params.set('sr_tolerance', options.srTolerance.toString());
+    }
+    return this.fetch(`/api/analysis/${symbol}/wyckoff/history?${params}`);
+  }
+
+  async getIndicators(): Promise<IndicatorInfo[]> {
+    return this.fetch('/api/analysis/indicators');
+  }
+
+  async getBulkAnalysis(
+    group: { sector: string } | { index: string },
+    fields?: BulkAnalysisField[]
+  ): Promise<BulkAnalysisResponse> {
+    const params = new URLSearchParams(group);
+    if (fields?.length) params.set('fields', fields.join(','));
+    return this.fetch(`/api/analysis/bulk?${params}`);
+  }
+
   async getBrokerFlow(symbol: string, days?: number): Promise<BrokerSummaryResponse | null> {
     try {
       const params = days ? `?days=${days}` : '';
@@ -579,6 +2128,14 @@ class ApiClient {
     }
   }
 
+  async getAdminOverview(): Promise<AdminOverview> {
+    return this.fetch('/api/admin/overview');
+  }
+
+  async getUsageAnalytics(days = 30): Promise<UsageAnalyticsResponse> {
+    return this.fetch(`/api/admin/analytics?days=${days}`);
+  }
+
   async getDataStatus(): Promise<DataStatusResponse> {
     return this.fetch('/api/admin/data-status');
   }
@@ -628,6 +2185,95 @@ class ApiClient {
       method: 'POST',
     });
   }
+
+  async runMaintenance(): Promise<JobsListResponse> {
+    return this.fetch('/api/admin/maintenance', {
+      method: 'POST',
+    });
+  }
+
+  async runMaintenanceTask(task: MaintenanceTask): Promise<Job> {
+    return this.fetch(`/api/admin/maintenance/${task}`, {
+      method: 'POST',
+    });
+  }
+
+  async startBackfill(request: BackfillRequest = {}): Promise<BackfillResponse> {
+    return this.fetch('/api/admin/data-sources/twelvedata/backfill', {
+      method: 'POST',
+      body: JSON.stringify(request),
+    });
+  }
+
+  async getBackfillJobs(): Promise<BackfillJobsResponse> {
+    return this.fetch('/api/admin/data-sources/twelvedata/backfill');
+  }
+
+  async getBackfillJob(id: number): Promise<BackfillJob> {
+    return this.fetch(`/api/admin/data-sources/twelvedata/backfill/${id}`);
+  }
+
+  async getPriceProviderHealth(): Promise<PriceProviderHealthResponse> {
+    return this.fetch('/api/admin/data-sources/price-providers');
+  }
+
+  async syncStockUniverse(): Promise<Job> {
+    return this.fetch('/api/admin/stocks/sync-universe', {
+      method: 'POST',
+    });
+  }
+
+  async ingestNews(): Promise<Job> {
+    return this.fetch('/api/admin/news/ingest', {
+      method: 'POST',
+    });
+  }
+
+  async ingestCorporateActions(): Promise<Job> {
+    return this.fetch('/api/admin/corporate-actions/ingest', {
+      method: 'POST',
+    });
+  }
+
+  async syncEarnings(): Promise<Job> {
+    return this.fetch('/api/admin/earnings/sync', {
+      method: 'POST',
+    });
+  }
+
+  async getFailedNotifications(
+    status: NotificationRetryStatus = 'dead',
+    limit = 100
+  ): Promise<FailedNotificationsResponse> {
+    return this.fetch(`/api/admin/notifications/failed?status=${status}&limit=${limit}`);
+  }
+
+  async retryFailedNotification(id: number): Promise<RetryNotificationResponse> {
+    return this.fetch(`/api/admin/notifications/failed/${id}/retry`, {
+      method: 'POST',
+    });
+  }
+
+  async getStoredSecrets(): Promise<StoredSecretsResponse> {
+    return this.fetch('/api/admin/secrets');
+  }
+
+  async putStoredSecret(name: string, value: string): Promise<StoredSecretInfo> {
+    return this.fetch(`/api/admin/secrets/${name}`, {
+      method: 'PUT',
+      body: JSON.stringify({ value }),
+    });
+  }
+
+  async deleteStoredSecret(name: string): Promise<void> {
+    await this.fetch(`/api/admin/secrets/${name}`, { method: 'DELETE' });
+  }
+
+  async rotateStoredSecrets(): Promise<RotateSecretsResponse> {
+    return this.fetch('/api/admin/secrets/rotate', {
+      method: 'POST',
+    });
+  }
 }
 
 export const api = new ApiClient();
@@ -636,12 +2282,73 @@ export type {
   StockScore, 
   StockPrice, 
   StockFreshness,
+  NewsSentimentLabel,
+  StockNews,
+  ReportPeriod,
+  EarningsCalendarEntry,
+  EarningsWeek,
   WatchlistItem, 
   LoginResponse, 
+  UserRole,
   FundamentalData,
   FullAnalysisResponse,
+  AnalysisTimingReport,
+  StageTiming,
   TechnicalResponse,
   BrokerSummaryResponse,
+  FootprintLevel,
+  FootprintBar,
+  FootprintResponse,
+  MomentumHorizon,
+  MomentumRank,
+  MomentumRankingResponse,
+  MomentumQuery,
+  PairSignal,
+  RatioPoint,
+  CointegrationResult,
+  PairAnalysisResponse,
+  CorporateEventKind,
+  EventWindow,
+  EventStudySummary,
+  SymbolEvent,
+  EventStudyResponse,
+  EventStudyQuery,
+  SymbolBeta,
+  CorrelationResponse,
+  RotationQuadrant,
+  RotationConfig,
+  RotationPoint,
+  SectorRotation,
+  SectorRotationResponse,
+  OrderFlowDay,
+  BookLevel,
+  DepthSnapshot,
+  LiveOrderFlow,
+  OrderBookInput,
+  OrderBookResponse,
+  OrderFlowHistoryResponse,
+  VolumeAtPrice,
+  VolumeProfile,
+  VolumeProfileResponse,
+  WyckoffPhase,
+  WyckoffPhaseSpan,
+  WyckoffTransition,
+  WyckoffHistoryResponse,
+  WyckoffProfile,
+  WyckoffConfig,
+  WyckoffRange,
+  WyckoffHistoryOptions,
+  IndicatorCategory,
+  IndicatorParam,
+  IndicatorOutput,
+  IndicatorInfo,
+  BulkAnalysisField,
+  BulkAnalysisRow,
+  BulkAnalysisResponse,
+  CustomIndicator,
+  CustomIndicatorInput,
+  CustomIndicatorValue,
+  CustomIndicatorValues,
   InstitutionalFlowAnalysis,
   AccumulatorInfo,
   ValuationResponse,
@@ -653,6 +2360,33 @@ export type {
   BollingerResponse,
   StrategyResponse,
   RecomputeScoresResponse,
+  ScreenFilter,
+  ScreenCandidate,
+  ScreenResponse,
+  SavedScreen,
+  SavedScreenInput,
+  ScreenDiff,
+  ScreenRunResponse,
+  BacktestStrategy,
+  MonteCarloConfig,
+  BacktestConfig,
+  Distribution,
+  MonteCarloSummary,
+  BacktestMetrics,
+  BacktestTrade,
+  EquityPoint,
+  BacktestRunSummary,
+  BacktestRun,
+  SharedBacktest,
+  RunBacktestInput,
+  ShareBacktestResponse,
+  StrategyGrid,
+  ParameterGrid,
+  OptimizationObjective,
+  RunOptimizationInput,
+  BacktestOptimization,
+  OptimizationResult,
+  OptimizationDetail,
   DataStatusResponse,
   DataSourceStatus,
   DataSummary,
@@ -673,6 +2407,49 @@ export type {
   Job,
   JobStatus,
   JobsListResponse,
+  MaintenanceTask,
+  NotificationRetryStatus,
+  FailedNotification,
+  FailedNotificationsResponse,
+  RetryNotificationResponse,
+  StoredSecretInfo,
+  StoredSecretsResponse,
+  RotateSecretsResponse,
+  BackfillJob,
+  BackfillRequest,
+  BackfillResponse,
+  BackfillJobsResponse,
+  PriceProviderHealth,
+  PriceProviderHealthResponse,
+  AdminOverview,
+  UsageEventKind,
+  UsageSubjectCount,
+  UsageDailyCount,
+  UsageAnalyticsResponse,
+  TradeSide,
+  PortfolioTransaction,
+  PortfolioTransactionInput,
+  PortfolioHolding,
+  HoldingValuation,
+  SectorExposure,
+  PortfolioSummary,
+  AlertHistoryEntry,
+  AlertHistoryQuery,
+  AlertPriority,
+  NotificationChannel,
+  AlertTypeFilter,
+  QuietHours,
+  AlertSubscription,
+  AlertSubscriptionResponse,
+  AlertSubscriptionInput,
+  InAppNotification,
+  ScoreUpdate,
+  AlertRule,
+  AlertRuleInput,
+  AlertRuleEvaluation,
+  AlertRuleSimulation,
+  AlertRulePriority,
+  ChangeRecord,
   RefreshStockResponse,
   RefreshSourceResponse,
   StockSourceType
@@ -63,6 +63,10 @@ export interface TechnicalAnalysis {
   };
   support: number[];
   resistance: number[];
+  atr: number | null;
+  atr_stop: number | null;
+  vwap: number | null;
+  price_vs_vwap_percent: number | null;
   summary: { sell: number; neutral: number; buy: number };
 }
 
@@ -90,6 +94,7 @@ export interface SignalAnalysis {
   thesis: string;
   target_price: number | null;
   stop_loss: number | null;
+  stop_loss_basis: 'support' | 'atr' | 'percent';
   upside_percent: number | null;
   downside_percent: number | null;
   risk_reward_ratio: number | null;
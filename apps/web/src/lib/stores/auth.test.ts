@@ -43,7 +43,7 @@ describe('Auth Store', () => {
 
   describe('login', () => {
     it('returns true and sets authenticated on successful login', async () => {
-      vi.mocked(api.login).mockResolvedValue({ token: 'test-token', expires_at: 12345 });
+      vi.mocked(api.login).mockResolvedValue({ token: 'test-token', expires_at: 12345, role: 'admin' });
       
       const result = await auth.login('user', 'pass');
       
@@ -71,7 +71,7 @@ describe('Auth Store', () => {
       
       vi.mocked(api.login).mockImplementation(async () => {
         loadingDuringRequest = get(auth).isLoading;
-        return { token: 'test-token', expires_at: 12345 };
+        return { token: 'test-token', expires_at: 12345, role: 'admin' };
       });
       
       await auth.login('user', 'pass');
@@ -0,0 +1,182 @@
+//! Change history of user-editable configuration
+//!
+//! Edits are logged as `DataModification` events whose details carry the
+//! before/after snapshots, and read back per resource.
+
+use crate::{AuditEvent, EventCategory, Severity};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+/// Fields that change on every write and say nothing about the edit
+const IGNORED_FIELDS: &[&str] = &["updated_at"];
+
+/// Build a change event with before/after snapshots
+///
+/// `before` is `None` for creations, `after` is `None` for deletions.
+pub fn change_event(
+    username: &str,
+    action: &str,
+    resource_type: &str,
+    resource_id: &str,
+    before: Option<Value>,
+    after: Option<Value>,
+) -> AuditEvent {
+    AuditEvent::new(
+        EventCategory::DataModification,
+        Severity::Info,
+        action,
+        resource_type,
+    )
+    .with_user(username, username)
+    .with_resource_id(resource_id)
+    .with_details(serde_json::json!({ "before": before, "after": after }))
+}
+
+/// One recorded change of a resource
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangeRecord {
+    pub id: Uuid,
+    pub timestamp: DateTime<Utc>,
+    pub action: String,
+    pub username: Option<String>,
+    pub resource_type: String,
+    pub resource_id: Option<String>,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+    /// Top-level fields that differ between the snapshots
+    pub changed_fields: Vec<String>,
+}
+
+#[derive(Debug, FromRow)]
+struct ChangeRow {
+    id: Uuid,
+    timestamp: DateTime<Utc>,
+    action: String,
+    username: Option<String>,
+    resource_type: String,
+    resource_id: Option<String>,
+    details: Value,
+}
+
+impl From<ChangeRow> for ChangeRecord {
+    fn from(row: ChangeRow) -> Self {
+        let snapshot = |key: &str| row.details.get(key).filter(|v| !v.is_null()).cloned();
+        let before = snapshot("before");
+        let after = snapshot("after");
+        Self {
+            changed_fields: changed_fields(before.as_ref(), after.as_ref()),
+            id: row.id,
+            timestamp: row.timestamp,
+            action: row.action,
+            username: row.username,
+            resource_type: row.resource_type,
+            resource_id: row.resource_id,
+            before,
+            after,
+        }
+    }
+}
+
+/// Top-level fields whose value differs between two object snapshots
+///
+/// A missing snapshot counts as empty, so creations and deletions list every
+/// field.
+pub fn changed_fields(before: Option<&Value>, after: Option<&Value>) -> Vec<String> {
+    let empty = serde_json::Map::new();
+    let before = before.and_then(Value::as_object).unwrap_or(&empty);
+    let after = after.and_then(Value::as_object).unwrap_or(&empty);
+
+    let mut fields: Vec<String> = before
+        .keys()
+        .chain(after.keys())
+        .filter(|key| !IGNORED_FIELDS.contains(&key.as_str()))
+        .filter(|key| before.get(*key) != after.get(*key))
+        .cloned()
+        .collect();
+    fields.sort();
+    fields.dedup();
+    fields
+}
+
+/// Filter for `get_change_history`
+#[derive(Debug, Clone)]
+pub struct ChangeHistoryQuery<'a> {
+    pub resource_type: &'a str,
+    /// All resources of the type when `None`
+    pub resource_id: Option<&'a str>,
+    /// Only changes made by this user
+    pub username: Option<&'a str>,
+    pub limit: i64,
+}
+
+/// Recorded changes matching `query`, newest first
+pub async fn get_change_history(
+    pool: &PgPool,
+    query: &ChangeHistoryQuery<'_>,
+) -> Result<Vec<ChangeRecord>, sqlx::Error> {
+    let rows = sqlx::query_as::<_, ChangeRow>(
+        r#"
+        SELECT
+            id,
+            timestamp,
+            action,
+            actor->>'username' AS username,
+            resource->>'resource_type' AS resource_type,
+            resource->>'resource_id' AS resource_id,
+            details
+        FROM audit_logs
+        WHERE category = $1
+          AND resource->>'resource_type' = $2
+          AND ($3::TEXT IS NULL OR resource->>'resource_id' = $3)
+          AND ($4::TEXT IS NULL OR actor->>'username' = $4)
+        ORDER BY timestamp DESC
+        LIMIT $5
+        "#,
+    )
+    .bind(serde_json::to_string(&EventCategory::DataModification).unwrap_or_default())
+    .bind(query.resource_type)
+    .bind(query.resource_id)
+    .bind(query.username)
+    .bind(query.limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().map(ChangeRecord::from).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_change_event() {
+        let event = change_event(
+            "admin",
+            "alert_rule_update",
+            "alert_rule",
+            "7",
+            Some(json!({ "name": "old" })),
+            Some(json!({ "name": "new" })),
+        );
+
+        assert!(matches!(event.category, EventCategory::DataModification));
+        assert_eq!(event.actor.username.as_deref(), Some("admin"));
+        assert_eq!(event.resource.resource_id.as_deref(), Some("7"));
+        assert_eq!(event.details["after"]["name"], "new");
+    }
+
+    #[test]
+    fn test_changed_fields() {
+        let before = json!({ "name": "a", "enabled": true, "updated_at": "t1" });
+        let after = json!({ "name": "b", "enabled": true, "updated_at": "t2" });
+        assert_eq!(changed_fields(Some(&before), Some(&after)), ["name"]);
+
+        // Creation lists every field except the ignored ones
+        assert_eq!(changed_fields(None, Some(&after)), ["enabled", "name"]);
+        assert!(changed_fields(None, None).is_empty());
+    }
+}
@@ -5,13 +5,16 @@
 //! - Data access
 //! - System events
 //! - Security events
+//! - Configuration change history
 //!
 //! Compliant with Indonesian PDP Law requirements
 
 mod events;
+mod history;
 mod logger;
 mod retention;
 
 pub use events::*;
+pub use history::*;
 pub use logger::*;
 pub use retention::*;
@@ -13,11 +13,16 @@ pub enum CacheError {
     Serialization(#[from] serde_json::Error),
     #[error("Key not found: {0}")]
     NotFound(String),
+    #[error("Snapshot file error: {0}")]
+    Io(#[from] std::io::Error),
 }
 
 pub type CacheResult<T> = Result<T, CacheError>;
 
 /// Redis cache client with connection pooling
+///
+/// Clones share the underlying connection.
+#[derive(Clone)]
 pub struct CacheClient {
     conn: ConnectionManager,
     default_ttl: Duration,
@@ -65,7 +70,8 @@ impl CacheClient {
     /// Set a value with default TTL
     pub async fn set<T: serde::Serialize>(&mut self, key: &str, value: &T) -> CacheResult<()> {
         let json = serde_json::to_string(value)?;
-        self.conn
+        let _: () = self
+            .conn
             .set_ex(key, json, self.default_ttl.as_secs())
             .await?;
         Ok(())
@@ -79,7 +85,23 @@ impl CacheClient {
         ttl: Duration,
     ) -> CacheResult<()> {
         let json = serde_json::to_string(value)?;
-        self.conn.set_ex(key, json, ttl.as_secs()).await?;
+        let _: () = self.conn.set_ex(key, json, ttl.as_secs()).await?;
+        Ok(())
+    }
+
+    /// Get a stored JSON document without deserializing it
+    pub async fn get_raw(&mut self, key: &str) -> CacheResult<Option<String>> {
+        Ok(self.conn.get(key).await?)
+    }
+
+    /// Store an already serialized JSON document with custom TTL
+    pub async fn set_raw_with_ttl(
+        &mut self,
+        key: &str,
+        json: &str,
+        ttl: Duration,
+    ) -> CacheResult<()> {
+        let _: () = self.conn.set_ex(key, json, ttl.as_secs()).await?;
         Ok(())
     }
 
@@ -89,6 +111,21 @@ impl CacheClient {
         Ok(deleted > 0)
     }
 
+    /// Delete every key matching `pattern`, returning how many were deleted
+    pub async fn delete_matching(&mut self, pattern: &str) -> CacheResult<usize> {
+        let mut keys = Vec::new();
+        let mut iter = self.conn.scan_match::<_, String>(pattern).await?;
+        while let Some(key) = iter.next_item().await {
+            keys.push(key);
+        }
+        drop(iter);
+        if keys.is_empty() {
+            return Ok(0);
+        }
+        let deleted: usize = self.conn.del(&keys).await?;
+        Ok(deleted)
+    }
+
     /// Check if key exists
     pub async fn exists(&mut self, key: &str) -> CacheResult<bool> {
         let exists: bool = self.conn.exists(key).await?;
@@ -110,14 +147,15 @@ impl CacheClient {
 
         let refs: Vec<(&str, &str)> = serialized.iter().map(|(k, v)| (*k, v.as_str())).collect();
 
-        redis::cmd("MSET")
+        let _: () = redis::cmd("MSET")
             .arg(&refs[..])
             .query_async(&mut self.conn)
             .await?;
 
         // Set TTL for each key
         for (key, _) in pairs {
-            self.conn
+            let _: () = self
+                .conn
                 .expire(*key, self.default_ttl.as_secs() as i64)
                 .await?;
         }
@@ -148,7 +186,7 @@ impl CacheClient {
 
     /// Add to a sorted set (for leaderboards, rankings)
     pub async fn zadd(&mut self, key: &str, member: &str, score: f64) -> CacheResult<()> {
-        self.conn.zadd(key, member, score).await?;
+        let _: () = self.conn.zadd(key, member, score).await?;
         Ok(())
     }
 
@@ -165,7 +203,7 @@ impl CacheClient {
 
     /// Publish message to channel
     pub async fn publish(&mut self, channel: &str, message: &str) -> CacheResult<()> {
-        self.conn.publish(channel, message).await?;
+        let _: () = self.conn.publish(channel, message).await?;
         Ok(())
     }
 
@@ -13,6 +13,9 @@ pub mod prefix {
     pub const USER_SESSION: &str = "session";
     pub const RATE_LIMIT: &str = "ratelimit";
     pub const LEADERBOARD: &str = "leaderboard";
+    pub const UNIVERSE: &str = "universe";
+    pub const SNAPSHOT: &str = "snapshot";
+    pub const ANALYSIS: &str = "analysis";
 }
 
 /// Generate cache keys for various entities
@@ -84,6 +87,54 @@ impl CacheKeys {
         format!("{}:{}", prefix::LEADERBOARD, category)
     }
 
+    /// Universe metadata key: universe:{name}, e.g. universe:sectors
+    pub fn universe(name: &str) -> String {
+        format!("{}:{}", prefix::UNIVERSE, name)
+    }
+
+    /// Latest cache snapshot key: snapshot:latest
+    pub fn snapshot() -> String {
+        format!("{}:latest", prefix::SNAPSHOT)
+    }
+
+    /// Full analysis response key: analysis:{symbol}:full:{days}
+    pub fn full_analysis(symbol: &str, days: i32) -> String {
+        format!(
+            "{}:{}:full:{}",
+            prefix::ANALYSIS,
+            symbol.to_uppercase(),
+            days
+        )
+    }
+
+    /// Pattern of every full analysis key of a symbol: analysis:{symbol}:full:*
+    pub fn full_analysis_pattern(symbol: &str) -> String {
+        format!("{}:{}:full:*", prefix::ANALYSIS, symbol.to_uppercase())
+    }
+
+    /// Momentum ranking key: analysis:momentum:{params}, where `params`
+    /// identifies the horizons, weights and skip
+    pub fn momentum_ranking(params: &str) -> String {
+        format!("{}:momentum:{}", prefix::ANALYSIS, params)
+    }
+
+    /// Price indicators of a score key: analysis:{symbol}:indicators:{digest},
+    /// where `digest` fingerprints every bar of the history, so a new or
+    /// patched bar never reads indicators of another history
+    pub fn price_indicators(symbol: &str, digest: &str) -> String {
+        format!(
+            "{}:{}:indicators:{}",
+            prefix::ANALYSIS,
+            symbol.to_uppercase(),
+            digest
+        )
+    }
+
+    /// Sector rotation key: analysis:sector_rotation
+    pub fn sector_rotation() -> String {
+        format!("{}:sector_rotation", prefix::ANALYSIS)
+    }
+
     /// Pattern for wildcard matching
     pub fn pattern(prefix: &str, symbol: Option<&str>) -> String {
         match symbol {
@@ -103,6 +154,39 @@ mod tests {
         assert_eq!(CacheKeys::stock_quote("BBRI"), "stock:quote:BBRI");
     }
 
+    #[test]
+    fn test_full_analysis_key() {
+        assert_eq!(
+            CacheKeys::full_analysis("bbca", 90),
+            "analysis:BBCA:full:90"
+        );
+        assert_eq!(
+            CacheKeys::full_analysis_pattern("bbca"),
+            "analysis:BBCA:full:*"
+        );
+    }
+
+    #[test]
+    fn test_price_indicators_key() {
+        assert_eq!(
+            CacheKeys::price_indicators("bbca", "200-9f2c5e0a1b3d4c6e"),
+            "analysis:BBCA:indicators:200-9f2c5e0a1b3d4c6e"
+        );
+    }
+
+    #[test]
+    fn test_momentum_ranking_key() {
+        assert_eq!(
+            CacheKeys::momentum_ranking("21x1,63x1:skip0"),
+            "analysis:momentum:21x1,63x1:skip0"
+        );
+    }
+
+    #[test]
+    fn test_sector_rotation_key() {
+        assert_eq!(CacheKeys::sector_rotation(), "analysis:sector_rotation");
+    }
+
     #[test]
     fn test_stock_price_key() {
         assert_eq!(
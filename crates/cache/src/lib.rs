@@ -5,11 +5,15 @@
 //! - Technical indicator calculations
 //! - Scoring results
 //! - Alert states
+//! - TTLs that lengthen outside IDX trading hours
+//! - Snapshots of hot keys, restored on startup after a deploy
 
 mod client;
 mod keys;
-mod stock_cache;
+mod snapshot;
+mod ttl;
 
 pub use client::*;
 pub use keys::*;
-pub use stock_cache::*;
+pub use snapshot::*;
+pub use ttl::*;
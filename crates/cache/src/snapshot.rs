@@ -0,0 +1,310 @@
+//! Snapshot and restore of hot cache state
+//!
+//! A deploy during market hours would otherwise start with cold analyses,
+//! momentum rankings and sector rotation, and every client would trigger the
+//! same recomputation at once. On shutdown the hot keys are dumped to disk
+//! (and to a Redis key); on startup every key that is missing is restored with
+//! the TTL it had left, less the time since the snapshot was taken.
+
+use crate::{prefix, CacheClient, CacheKeys, CacheResult};
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::debug;
+
+/// Bumped when the snapshot layout changes; other versions are ignored
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// Key patterns captured by default: the prefixes the API writes
+pub fn default_snapshot_patterns() -> Vec<String> {
+    [prefix::ANALYSIS]
+        .into_iter()
+        .map(|p| CacheKeys::pattern(p, None))
+        .collect()
+}
+
+/// Current time as Unix seconds
+pub fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+/// Value of one snapshotted key
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SnapshotValue {
+    /// Plain string (JSON-encoded values)
+    String { value: String },
+    /// Sorted set, e.g. a leaderboard
+    SortedSet { members: Vec<(String, f64)> },
+}
+
+/// One snapshotted key
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotEntry {
+    pub key: String,
+    #[serde(flatten)]
+    pub value: SnapshotValue,
+    /// Remaining TTL when the snapshot was taken; `None` if the key never expires
+    pub ttl_secs: Option<u64>,
+}
+
+impl SnapshotEntry {
+    /// TTL to restore with after `elapsed_secs`
+    ///
+    /// `None` means the key would have expired and is not restored;
+    /// `Some(None)` means the key has no expiry.
+    pub fn remaining_ttl(&self, elapsed_secs: i64) -> Option<Option<u64>> {
+        match self.ttl_secs {
+            None => Some(None),
+            Some(ttl) => {
+                let remaining = ttl as i64 - elapsed_secs.max(0);
+                (remaining > 0).then_some(Some(remaining as u64))
+            }
+        }
+    }
+}
+
+/// Hot cache state captured at shutdown
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CacheSnapshot {
+    pub version: u32,
+    /// Unix seconds
+    pub taken_at: i64,
+    pub entries: Vec<SnapshotEntry>,
+}
+
+impl CacheSnapshot {
+    pub fn new(entries: Vec<SnapshotEntry>) -> Self {
+        Self {
+            version: SNAPSHOT_VERSION,
+            taken_at: unix_now(),
+            entries,
+        }
+    }
+
+    /// Whether the snapshot is too old (or from another version) to restore
+    pub fn is_stale(&self, now: i64, max_age: Duration) -> bool {
+        self.version != SNAPSHOT_VERSION || now - self.taken_at > max_age.as_secs() as i64
+    }
+
+    /// Write the snapshot to `path`, replacing any previous one atomically
+    pub fn save(&self, path: &Path) -> CacheResult<()> {
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_vec(self)?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Read a snapshot from `path`; `None` if there is none
+    pub fn load(path: &Path) -> CacheResult<Option<Self>> {
+        match std::fs::read(path) {
+            Ok(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Outcome of a restore
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct RestoreReport {
+    pub restored: usize,
+    /// Keys already present (fresher than the snapshot) and left alone
+    pub skipped_existing: usize,
+    /// Keys whose TTL ran out since the snapshot
+    pub expired: usize,
+}
+
+impl CacheClient {
+    /// Capture every string and sorted-set key matching `patterns`
+    pub async fn snapshot(&mut self, patterns: &[String]) -> CacheResult<CacheSnapshot> {
+        let mut keys = Vec::new();
+        for pattern in patterns {
+            let mut iter = self.connection().scan_match::<_, String>(pattern).await?;
+            while let Some(key) = iter.next_item().await {
+                keys.push(key);
+            }
+        }
+        keys.sort();
+        keys.dedup();
+
+        let mut entries = Vec::with_capacity(keys.len());
+        for key in keys {
+            let conn = self.connection();
+            let key_type: String = redis::cmd("TYPE").arg(&key).query_async(conn).await?;
+            let value = match key_type.as_str() {
+                "string" => match conn.get::<_, Option<String>>(&key).await? {
+                    Some(value) => SnapshotValue::String { value },
+                    None => continue,
+                },
+                "zset" => SnapshotValue::SortedSet {
+                    members: conn.zrange_withscores(&key, 0, -1).await?,
+                },
+                other => {
+                    debug!("Skipping {} key {} in cache snapshot", other, key);
+                    continue;
+                }
+            };
+            // -1: no expiry, -2: expired since the scan
+            let ttl: i64 = conn.ttl(&key).await?;
+            if ttl == -2 {
+                continue;
+            }
+            entries.push(SnapshotEntry {
+                key,
+                value,
+                ttl_secs: (ttl >= 0).then_some(ttl as u64),
+            });
+        }
+
+        Ok(CacheSnapshot::new(entries))
+    }
+
+    /// Restore snapshotted keys that are missing, with their remaining TTL
+    pub async fn restore_snapshot(
+        &mut self,
+        snapshot: &CacheSnapshot,
+    ) -> CacheResult<RestoreReport> {
+        let elapsed = unix_now() - snapshot.taken_at;
+        let mut report = RestoreReport::default();
+
+        for entry in &snapshot.entries {
+            let Some(ttl) = entry.remaining_ttl(elapsed) else {
+                report.expired += 1;
+                continue;
+            };
+            let conn = self.connection();
+            if conn.exists::<_, bool>(&entry.key).await? {
+                report.skipped_existing += 1;
+                continue;
+            }
+
+            match &entry.value {
+                SnapshotValue::String { value } => {
+                    let _: () = conn.set(&entry.key, value).await?;
+                }
+                SnapshotValue::SortedSet { members } => {
+                    if members.is_empty() {
+                        continue;
+                    }
+                    let items: Vec<(f64, &str)> =
+                        members.iter().map(|(m, s)| (*s, m.as_str())).collect();
+                    let _: () = conn.zadd_multiple(&entry.key, &items).await?;
+                }
+            }
+            if let Some(ttl) = ttl {
+                let _: () = conn.expire(&entry.key, ttl as i64).await?;
+            }
+            report.restored += 1;
+        }
+
+        Ok(report)
+    }
+
+    /// Keep a copy of the snapshot in Redis, for hosts without persistent disk
+    pub async fn store_snapshot(&mut self, snapshot: &CacheSnapshot) -> CacheResult<()> {
+        let json = serde_json::to_string(snapshot)?;
+        let _: () = self.connection().set(CacheKeys::snapshot(), json).await?;
+        Ok(())
+    }
+
+    /// Snapshot previously stored in Redis
+    pub async fn load_stored_snapshot(&mut self) -> CacheResult<Option<CacheSnapshot>> {
+        self.get(&CacheKeys::snapshot()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot() -> CacheSnapshot {
+        CacheSnapshot::new(vec![
+            SnapshotEntry {
+                key: "stock:quote:BBCA".to_string(),
+                value: SnapshotValue::String {
+                    value: r#"{"price":9500}"#.to_string(),
+                },
+                ttl_secs: Some(30),
+            },
+            SnapshotEntry {
+                key: "leaderboard:composite".to_string(),
+                value: SnapshotValue::SortedSet {
+                    members: vec![("BBCA".to_string(), 82.5), ("TLKM".to_string(), 71.0)],
+                },
+                ttl_secs: None,
+            },
+        ])
+    }
+
+    #[test]
+    fn test_remaining_ttl() {
+        let snapshot = snapshot();
+        let quote = &snapshot.entries[0];
+        assert_eq!(quote.remaining_ttl(10), Some(Some(20)));
+        assert_eq!(quote.remaining_ttl(30), None);
+
+        let leaderboard = &snapshot.entries[1];
+        assert_eq!(leaderboard.remaining_ttl(86_400), Some(None));
+    }
+
+    #[test]
+    fn test_staleness() {
+        let snapshot = snapshot();
+        let max_age = Duration::from_secs(3600);
+        assert!(!snapshot.is_stale(snapshot.taken_at + 60, max_age));
+        assert!(snapshot.is_stale(snapshot.taken_at + 7200, max_age));
+
+        let other_version = CacheSnapshot {
+            version: SNAPSHOT_VERSION + 1,
+            ..snapshot
+        };
+        assert!(other_version.is_stale(other_version.taken_at, max_age));
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let path = std::env::temp_dir().join(format!(
+            "jejakcuan-cache-snapshot-{}.json",
+            std::process::id()
+        ));
+        let snapshot = snapshot();
+        snapshot.save(&path).unwrap();
+        assert_eq!(CacheSnapshot::load(&path).unwrap(), Some(snapshot));
+
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(CacheSnapshot::load(&path).unwrap(), None);
+    }
+
+    #[test]
+    fn test_default_patterns() {
+        assert_eq!(default_snapshot_patterns(), vec!["analysis:*".to_string()]);
+    }
+
+    // Requires a running Redis instance
+    #[tokio::test]
+    #[ignore]
+    async fn test_snapshot_restore_roundtrip() {
+        let mut cache = CacheClient::new("redis://localhost:6379").await.unwrap();
+        cache
+            .set_with_ttl("universe:snapshot_test", &"IDX", Duration::from_secs(60))
+            .await
+            .unwrap();
+
+        let patterns = vec!["universe:snapshot_test".to_string()];
+        let snapshot = cache.snapshot(&patterns).await.unwrap();
+        assert_eq!(snapshot.entries.len(), 1);
+
+        cache.delete("universe:snapshot_test").await.unwrap();
+        let report = cache.restore_snapshot(&snapshot).await.unwrap();
+        assert_eq!(report.restored, 1);
+        let value: Option<String> = cache.get("universe:snapshot_test").await.unwrap();
+        assert_eq!(value.as_deref(), Some("IDX"));
+
+        cache.delete("universe:snapshot_test").await.unwrap();
+    }
+}
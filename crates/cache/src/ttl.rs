@@ -0,0 +1,112 @@
+//! Market-hours-aware cache TTLs
+//!
+//! Quotes and scores only change while IDX is trading. During the session
+//! entries expire quickly; overnight, on weekends and on holidays they are
+//! kept much longer, but never past the next session open.
+
+use chrono::{DateTime, Utc};
+use jejakcuan_core::MarketCalendar;
+use std::time::Duration;
+
+/// TTL that depends on whether the market is open
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MarketAwareTtl {
+    pub market_hours: Duration,
+    /// Upper bound outside market hours
+    pub off_hours: Duration,
+}
+
+impl MarketAwareTtl {
+    pub const fn new(market_hours: Duration, off_hours: Duration) -> Self {
+        Self {
+            market_hours,
+            off_hours,
+        }
+    }
+
+    /// Same TTL at all times
+    pub const fn fixed(ttl: Duration) -> Self {
+        Self::new(ttl, ttl)
+    }
+
+    /// 30 seconds while trading, up to an hour otherwise
+    pub const fn quotes() -> Self {
+        Self::new(Duration::from_secs(30), Duration::from_secs(60 * 60))
+    }
+
+    /// 5 minutes while trading, up to 6 hours otherwise
+    pub const fn scores() -> Self {
+        Self::new(
+            Duration::from_secs(5 * 60),
+            Duration::from_secs(6 * 60 * 60),
+        )
+    }
+
+    /// TTL for an entry written at `now`
+    ///
+    /// Off-hours entries expire at the next open at the latest, and never
+    /// sooner than the market-hours TTL.
+    pub fn ttl_at(&self, calendar: &MarketCalendar, now: DateTime<Utc>) -> Duration {
+        if calendar.is_open(now) {
+            return self.market_hours;
+        }
+        let until_open = (calendar.next_open(now) - now)
+            .to_std()
+            .unwrap_or(self.market_hours);
+        until_open.min(self.off_hours).max(self.market_hours)
+    }
+
+    /// TTL for an entry written now
+    pub fn current(&self, calendar: &MarketCalendar) -> Duration {
+        self.ttl_at(calendar, Utc::now())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_ttl_follows_market_hours() {
+        let calendar = MarketCalendar::default();
+        let ttl = MarketAwareTtl::quotes();
+        // Wednesday 10:00 WIB
+        assert_eq!(
+            ttl.ttl_at(&calendar, at("2025-06-04T03:00:00Z")),
+            Duration::from_secs(30)
+        );
+        // Wednesday evening and Saturday: the full off-hours TTL
+        assert_eq!(
+            ttl.ttl_at(&calendar, at("2025-06-04T12:00:00Z")),
+            Duration::from_secs(3600)
+        );
+        assert_eq!(
+            ttl.ttl_at(&calendar, at("2025-06-07T03:00:00Z")),
+            Duration::from_secs(3600)
+        );
+        // 08:40 WIB: capped at the open, 20 minutes away
+        assert_eq!(
+            ttl.ttl_at(&calendar, at("2025-06-04T01:40:00Z")),
+            Duration::from_secs(20 * 60)
+        );
+        // 08:59:50 WIB: no shorter than the market-hours TTL
+        assert_eq!(
+            ttl.ttl_at(&calendar, at("2025-06-04T01:59:50Z")),
+            Duration::from_secs(30)
+        );
+    }
+
+    #[test]
+    fn test_fixed_ttl() {
+        let ttl = MarketAwareTtl::fixed(Duration::from_secs(90));
+        let calendar = MarketCalendar::default();
+        assert_eq!(
+            ttl.ttl_at(&calendar, at("2025-06-07T03:00:00Z")),
+            Duration::from_secs(90)
+        );
+    }
+}
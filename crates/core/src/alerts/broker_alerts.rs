@@ -28,6 +28,30 @@ impl AlertPriority {
             AlertPriority::Low => "low",
         }
     }
+
+    /// Whether this priority is `min` or more urgent
+    pub fn at_least(&self, min: AlertPriority) -> bool {
+        self.rank() >= min.rank()
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            AlertPriority::Critical => 3,
+            AlertPriority::High => 2,
+            AlertPriority::Medium => 1,
+            AlertPriority::Low => 0,
+        }
+    }
+
+    pub fn from_str_opt(s: &str) -> Option<Self> {
+        match s {
+            "critical" => Some(Self::Critical),
+            "high" => Some(Self::High),
+            "medium" => Some(Self::Medium),
+            "low" => Some(Self::Low),
+            _ => None,
+        }
+    }
 }
 
 /// Alert types for broker flow
@@ -59,6 +83,21 @@ pub enum BrokerAlertType {
     },
 }
 
+impl BrokerAlertType {
+    /// Key of the glossary entry explaining this alert
+    pub fn glossary_key(&self) -> &'static str {
+        match self {
+            BrokerAlertType::CoordinatedBuying { .. } => "coordinated_buying",
+            BrokerAlertType::ForeignInflow { .. } | BrokerAlertType::ForeignOutflow { .. } => {
+                "foreign_flow"
+            }
+            BrokerAlertType::InstitutionalAccumulation { .. } => "institutional_accumulation",
+            BrokerAlertType::InstitutionalDistribution { .. } => "institutional_distribution",
+            BrokerAlertType::HighConcentration { .. } => "broker_concentration",
+        }
+    }
+}
+
 /// Broker flow alert
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BrokerAlert {
@@ -70,6 +109,9 @@ pub struct BrokerAlert {
     pub created_at: DateTime<Utc>,
     pub triggered_value: Decimal,
     pub threshold_value: Decimal,
+    /// Glossary entry explaining the alert
+    #[serde(default)]
+    pub glossary_key: String,
 }
 
 impl BrokerAlert {
@@ -83,6 +125,7 @@ impl BrokerAlert {
     ) -> Self {
         let id = format!("broker_{}_{}", symbol, Utc::now().timestamp_millis());
         let message = generate_alert_message(&symbol, &alert_type);
+        let glossary_key = alert_type.glossary_key().to_string();
 
         Self {
             id,
@@ -93,6 +136,7 @@ impl BrokerAlert {
             created_at: Utc::now(),
             triggered_value,
             threshold_value,
+            glossary_key,
         }
     }
 }
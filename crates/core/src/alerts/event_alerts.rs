@@ -0,0 +1,171 @@
+//! Scheduled corporate event alerts
+//!
+//! Triggers alerts for:
+//! - Financial reports expected within the coming week
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::AlertPriority;
+use crate::earnings::ReportPeriod;
+
+/// Days ahead an expected report is announced
+pub const EARNINGS_ALERT_DAYS: i64 = 7;
+
+/// Event alert types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum EventAlertType {
+    /// A financial report is expected soon
+    EarningsUpcoming {
+        period: ReportPeriod,
+        fiscal_year: i32,
+        expected_date: NaiveDate,
+        /// Negative once the expected date has passed without a report
+        days_until: i64,
+    },
+}
+
+impl EventAlertType {
+    /// Key of the glossary entry explaining this alert
+    pub fn glossary_key(&self) -> &'static str {
+        match self {
+            EventAlertType::EarningsUpcoming { .. } => "earnings_upcoming",
+        }
+    }
+}
+
+/// Scheduled event alert
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventAlert {
+    pub id: String,
+    pub symbol: String,
+    pub alert_type: EventAlertType,
+    pub priority: AlertPriority,
+    pub message: String,
+    pub created_at: DateTime<Utc>,
+    /// Glossary entry explaining the alert
+    #[serde(default)]
+    pub glossary_key: String,
+}
+
+impl EventAlert {
+    pub fn new(symbol: String, alert_type: EventAlertType, priority: AlertPriority) -> Self {
+        let id = format!("event_{}_{}", symbol, Utc::now().timestamp_millis());
+        let message = generate_event_message(&symbol, &alert_type);
+        let glossary_key = alert_type.glossary_key().to_string();
+        Self {
+            id,
+            symbol,
+            alert_type,
+            priority,
+            message,
+            created_at: Utc::now(),
+            glossary_key,
+        }
+    }
+
+    /// Alert for a report expected on `expected_date`, if it is due within
+    /// `EARNINGS_ALERT_DAYS` of `today`
+    ///
+    /// Reports due within two days are high priority.
+    pub fn earnings_upcoming(
+        symbol: &str,
+        period: ReportPeriod,
+        fiscal_year: i32,
+        expected_date: NaiveDate,
+        today: NaiveDate,
+    ) -> Option<Self> {
+        let days_until = (expected_date - today).num_days();
+        if days_until > EARNINGS_ALERT_DAYS {
+            return None;
+        }
+        let priority = if days_until <= 2 {
+            AlertPriority::High
+        } else {
+            AlertPriority::Medium
+        };
+        Some(Self::new(
+            symbol.to_string(),
+            EventAlertType::EarningsUpcoming {
+                period,
+                fiscal_year,
+                expected_date,
+                days_until,
+            },
+            priority,
+        ))
+    }
+}
+
+fn generate_event_message(symbol: &str, alert_type: &EventAlertType) -> String {
+    match alert_type {
+        EventAlertType::EarningsUpcoming {
+            period,
+            fiscal_year,
+            expected_date,
+            days_until,
+        } => {
+            let report = format!("{} {} report", period.as_str().to_uppercase(), fiscal_year);
+            match days_until {
+                d if *d < 0 => format!(
+                    "{}: {} expected since {}, not yet published",
+                    symbol, report, expected_date
+                ),
+                0 => format!("{}: {} expected today", symbol, report),
+                1 => format!("{}: {} expected tomorrow", symbol, report),
+                d => format!(
+                    "{}: {} expected in {} days ({})",
+                    symbol, report, d, expected_date
+                ),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_earnings_upcoming() {
+        let today = date("2025-10-20");
+        let alert = EventAlert::earnings_upcoming(
+            "BBCA",
+            ReportPeriod::Q3,
+            2025,
+            date("2025-10-24"),
+            today,
+        )
+        .unwrap();
+        assert_eq!(alert.priority, AlertPriority::Medium);
+        assert_eq!(alert.glossary_key, "earnings_upcoming");
+        assert_eq!(
+            alert.message,
+            "BBCA: Q3 2025 report expected in 4 days (2025-10-24)"
+        );
+
+        let tomorrow = EventAlert::earnings_upcoming(
+            "BBCA",
+            ReportPeriod::Q3,
+            2025,
+            date("2025-10-21"),
+            today,
+        )
+        .unwrap();
+        assert_eq!(tomorrow.priority, AlertPriority::High);
+        assert!(tomorrow.message.ends_with("expected tomorrow"));
+
+        assert!(EventAlert::earnings_upcoming(
+            "BBCA",
+            ReportPeriod::Annual,
+            2025,
+            date("2026-03-31"),
+            today
+        )
+        .is_none());
+    }
+}
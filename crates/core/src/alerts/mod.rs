@@ -5,14 +5,23 @@
 //! - Technical indicator alerts (RSI, MACD, Wyckoff, breakouts)
 //! - Price alerts
 //! - Volume alerts
+//! - User-defined rules over computed indicators
+//! - Scheduled events such as upcoming financial reports
+//!
+//! Subscriptions route alerts to channels, optionally holding back less
+//! urgent alerts during quiet hours.
 
 mod broker_alerts;
+mod event_alerts;
+mod rules;
 mod technical_alerts;
 
 pub use broker_alerts::*;
+pub use event_alerts::*;
+pub use rules::*;
 pub use technical_alerts::*;
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, FixedOffset, NaiveTime, Utc};
 use serde::{Deserialize, Serialize};
 
 /// Unified alert type encompassing all alert categories
@@ -21,6 +30,7 @@ use serde::{Deserialize, Serialize};
 pub enum Alert {
     Broker(BrokerAlert),
     Technical(TechnicalAlert),
+    Event(EventAlert),
 }
 
 impl Alert {
@@ -28,6 +38,16 @@ impl Alert {
         match self {
             Alert::Broker(a) => &a.id,
             Alert::Technical(a) => &a.id,
+            Alert::Event(a) => &a.id,
+        }
+    }
+
+    /// Alert family, for storage and filtering
+    pub fn category(&self) -> &'static str {
+        match self {
+            Alert::Broker(_) => "broker",
+            Alert::Technical(_) => "technical",
+            Alert::Event(_) => "event",
         }
     }
 
@@ -35,6 +55,7 @@ impl Alert {
         match self {
             Alert::Broker(a) => &a.symbol,
             Alert::Technical(a) => &a.symbol,
+            Alert::Event(a) => &a.symbol,
         }
     }
 
@@ -42,6 +63,7 @@ impl Alert {
         match self {
             Alert::Broker(a) => a.priority,
             Alert::Technical(a) => a.priority,
+            Alert::Event(a) => a.priority,
         }
     }
 
@@ -49,6 +71,15 @@ impl Alert {
         match self {
             Alert::Broker(a) => &a.message,
             Alert::Technical(a) => &a.message,
+            Alert::Event(a) => &a.message,
+        }
+    }
+
+    pub fn glossary_key(&self) -> &str {
+        match self {
+            Alert::Broker(a) => &a.glossary_key,
+            Alert::Technical(a) => &a.glossary_key,
+            Alert::Event(a) => &a.glossary_key,
         }
     }
 
@@ -56,8 +87,42 @@ impl Alert {
         match self {
             Alert::Broker(a) => a.created_at,
             Alert::Technical(a) => a.created_at,
+            Alert::Event(a) => a.created_at,
         }
     }
+
+    /// Identity of the condition behind the alert, for suppressing repeats
+    ///
+    /// Alerts with the same key describe the same ongoing condition, whatever
+    /// the current values.
+    pub fn dedup_key(&self) -> String {
+        let detail = match self {
+            Alert::Technical(a) => match &a.alert_type {
+                TechnicalAlertType::UserLevelCrossed {
+                    level,
+                    crossed_above,
+                    ..
+                } => format!(":{}:{}", level.normalize(), crossed_above),
+                TechnicalAlertType::RuleMatched { rule_id, .. } => format!(":{}", rule_id),
+                TechnicalAlertType::BullishDivergence {
+                    indicator, hidden, ..
+                }
+                | TechnicalAlertType::BearishDivergence {
+                    indicator, hidden, ..
+                } => format!(":{}:{}", indicator, hidden),
+                _ => String::new(),
+            },
+            Alert::Event(a) => match &a.alert_type {
+                EventAlertType::EarningsUpcoming {
+                    period,
+                    fiscal_year,
+                    ..
+                } => format!(":{}:{}", fiscal_year, period.as_str()),
+            },
+            Alert::Broker(_) => String::new(),
+        };
+        format!("{}:{}{}", self.symbol(), self.glossary_key(), detail)
+    }
 }
 
 /// Alert subscription for user preferences
@@ -68,6 +133,63 @@ pub struct AlertSubscription {
     pub alert_types: AlertTypeFilter,
     pub min_priority: AlertPriority,
     pub channels: Vec<NotificationChannel>,
+    #[serde(default)]
+    pub quiet_hours: Option<QuietHours>,
+}
+
+/// WIB (UTC+7), the time zone quiet hours are given in
+const WIB_OFFSET_SECS: i32 = 7 * 3600;
+
+/// Daily window in which only urgent alerts are delivered
+///
+/// Times are WIB. A window whose end is before its start runs past midnight,
+/// e.g. 16:00-09:00 keeps everything but critical alerts to market hours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuietHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+    /// Alerts at least this urgent are still delivered
+    pub min_priority: AlertPriority,
+}
+
+impl QuietHours {
+    /// Whether `at` falls inside the window
+    pub fn contains(&self, at: DateTime<Utc>) -> bool {
+        let wib = FixedOffset::east_opt(WIB_OFFSET_SECS).expect("valid offset");
+        let time = at.with_timezone(&wib).time();
+        if self.start <= self.end {
+            time >= self.start && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+
+    /// Whether an alert of `priority` is held back at `at`
+    pub fn suppresses(&self, priority: AlertPriority, at: DateTime<Utc>) -> bool {
+        self.contains(at) && !priority.at_least(self.min_priority)
+    }
+}
+
+impl AlertSubscription {
+    /// Whether the alert should be routed to this subscriber
+    ///
+    /// An empty symbol list subscribes to every symbol.
+    pub fn accepts(&self, alert: &Alert) -> bool {
+        let symbol_ok = self.symbols.is_empty()
+            || self
+                .symbols
+                .iter()
+                .any(|s| s.eq_ignore_ascii_case(alert.symbol()));
+        symbol_ok && alert.priority().at_least(self.min_priority) && self.alert_types.allows(alert)
+    }
+
+    /// Whether the alert should be delivered at `at`, honoring quiet hours
+    pub fn delivers(&self, alert: &Alert, at: DateTime<Utc>) -> bool {
+        self.accepts(alert)
+            && !self
+                .quiet_hours
+                .is_some_and(|quiet| quiet.suppresses(alert.priority(), at))
+    }
 }
 
 /// Filter for alert types user wants to receive
@@ -82,6 +204,51 @@ pub struct AlertTypeFilter {
     pub macd_crossovers: bool,
     pub volume_spikes: bool,
     pub price_breakouts: bool,
+    /// Added after the other filters; stored filters without it keep it on
+    #[serde(default = "default_earnings_upcoming")]
+    pub earnings_upcoming: bool,
+}
+
+fn default_earnings_upcoming() -> bool {
+    true
+}
+
+impl AlertTypeFilter {
+    /// Whether the user wants this kind of alert
+    pub fn allows(&self, alert: &Alert) -> bool {
+        match alert {
+            Alert::Broker(a) => {
+                self.broker_alerts
+                    && match a.alert_type {
+                        BrokerAlertType::CoordinatedBuying { .. } => self.coordinated_buying,
+                        BrokerAlertType::ForeignInflow { .. }
+                        | BrokerAlertType::ForeignOutflow { .. } => self.foreign_flow,
+                        _ => true,
+                    }
+            }
+            Alert::Technical(a) => {
+                self.technical_alerts
+                    && match a.alert_type {
+                        TechnicalAlertType::RsiOverbought { .. }
+                        | TechnicalAlertType::RsiOversold { .. } => self.rsi_signals,
+                        TechnicalAlertType::MacdBullishCrossover { .. }
+                        | TechnicalAlertType::MacdBearishCrossover { .. } => self.macd_crossovers,
+                        TechnicalAlertType::WyckoffAccumulation { .. }
+                        | TechnicalAlertType::WyckoffDistribution { .. }
+                        | TechnicalAlertType::WyckoffSpring { .. }
+                        | TechnicalAlertType::WyckoffUpthrust { .. }
+                        | TechnicalAlertType::WyckoffEvent { .. } => self.wyckoff_events,
+                        TechnicalAlertType::VolumeSpike { .. } => self.volume_spikes,
+                        TechnicalAlertType::PriceBreakout { .. }
+                        | TechnicalAlertType::PriceBreakdown { .. } => self.price_breakouts,
+                        _ => true,
+                    }
+            }
+            Alert::Event(a) => match a.alert_type {
+                EventAlertType::EarningsUpcoming { .. } => self.earnings_upcoming,
+            },
+        }
+    }
 }
 
 impl Default for AlertTypeFilter {
@@ -96,6 +263,7 @@ impl Default for AlertTypeFilter {
             macd_crossovers: true,
             volume_spikes: true,
             price_breakouts: true,
+            earnings_upcoming: true,
         }
     }
 }
@@ -108,6 +276,9 @@ pub enum NotificationChannel {
     WebPush,
     Webhook,
     InApp,
+    Discord,
+    Slack,
+    WhatsApp,
 }
 
 #[cfg(test)]
@@ -129,7 +300,125 @@ mod tests {
 
         let alert = Alert::Broker(broker_alert);
         assert_eq!(alert.symbol(), "BBCA");
+        assert_eq!(alert.category(), "broker");
         assert_eq!(alert.priority(), AlertPriority::High);
         assert!(alert.message().contains("BBCA"));
+        assert_eq!(alert.dedup_key(), "BBCA:coordinated_buying");
+    }
+
+    #[test]
+    fn test_subscription_accepts() {
+        let rsi = Alert::Technical(TechnicalAlert::new(
+            "TLKM".to_string(),
+            TechnicalAlertType::RsiOversold {
+                rsi: rust_decimal_macros::dec!(25),
+            },
+            AlertPriority::Medium,
+        ));
+        let mut subscription = AlertSubscription {
+            user_id: "ops".to_string(),
+            symbols: vec![],
+            alert_types: AlertTypeFilter::default(),
+            min_priority: AlertPriority::Medium,
+            channels: vec![NotificationChannel::Telegram],
+            quiet_hours: None,
+        };
+        assert!(subscription.accepts(&rsi));
+
+        subscription.symbols = vec!["bbca".to_string()];
+        assert!(!subscription.accepts(&rsi));
+
+        subscription.symbols = vec!["tlkm".to_string()];
+        subscription.min_priority = AlertPriority::High;
+        assert!(!subscription.accepts(&rsi));
+
+        subscription.min_priority = AlertPriority::Low;
+        subscription.alert_types.rsi_signals = false;
+        assert!(!subscription.accepts(&rsi));
+
+        let spring = Alert::Technical(TechnicalAlert::new(
+            "TLKM".to_string(),
+            TechnicalAlertType::WyckoffEvent {
+                event: "spring".to_string(),
+                confidence: 75,
+            },
+            AlertPriority::Critical,
+        ));
+        assert!(subscription.accepts(&spring));
+        subscription.alert_types.wyckoff_events = false;
+        assert!(!subscription.accepts(&spring));
+    }
+
+    #[test]
+    fn test_event_alert_filter() {
+        let earnings = Alert::Event(
+            EventAlert::earnings_upcoming(
+                "BBRI",
+                crate::earnings::ReportPeriod::Q3,
+                2025,
+                "2025-10-24".parse().unwrap(),
+                "2025-10-20".parse().unwrap(),
+            )
+            .unwrap(),
+        );
+        assert_eq!(earnings.category(), "event");
+        assert_eq!(earnings.dedup_key(), "BBRI:earnings_upcoming:2025:q3");
+
+        // Filters stored before event alerts existed let them through
+        let stored = serde_json::json!({
+            "broker_alerts": true,
+            "technical_alerts": true,
+            "coordinated_buying": true,
+            "foreign_flow": true,
+            "wyckoff_events": true,
+            "rsi_signals": true,
+            "macd_crossovers": true,
+            "volume_spikes": true,
+            "price_breakouts": true
+        });
+        let mut filter: AlertTypeFilter = serde_json::from_value(stored).unwrap();
+        assert!(filter.allows(&earnings));
+        filter.earnings_upcoming = false;
+        assert!(!filter.allows(&earnings));
+    }
+
+    #[test]
+    fn test_quiet_hours() {
+        // Outside 09:00-16:00 WIB only critical alerts go out
+        let quiet = QuietHours {
+            start: NaiveTime::from_hms_opt(16, 0, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+            min_priority: AlertPriority::Critical,
+        };
+        let at = |s: &str| s.parse::<DateTime<Utc>>().unwrap();
+        // 10:00 and 15:59 WIB
+        assert!(!quiet.contains(at("2025-06-30T03:00:00Z")));
+        assert!(!quiet.contains(at("2025-06-30T08:59:00Z")));
+        // 16:00, 23:00 and 08:59 WIB
+        assert!(quiet.contains(at("2025-06-30T09:00:00Z")));
+        assert!(quiet.contains(at("2025-06-30T16:00:00Z")));
+        assert!(quiet.contains(at("2025-06-30T01:59:00Z")));
+
+        let evening = at("2025-06-30T13:00:00Z");
+        assert!(quiet.suppresses(AlertPriority::High, evening));
+        assert!(!quiet.suppresses(AlertPriority::Critical, evening));
+
+        let rsi = Alert::Technical(TechnicalAlert::new(
+            "TLKM".to_string(),
+            TechnicalAlertType::RsiOversold {
+                rsi: rust_decimal_macros::dec!(25),
+            },
+            AlertPriority::Medium,
+        ));
+        let subscription = AlertSubscription {
+            user_id: "ops".to_string(),
+            symbols: vec!["TLKM".to_string()],
+            alert_types: AlertTypeFilter::default(),
+            min_priority: AlertPriority::Low,
+            channels: vec![NotificationChannel::InApp],
+            quiet_hours: Some(quiet),
+        };
+        assert!(subscription.delivers(&rsi, at("2025-06-30T03:00:00Z")));
+        assert!(!subscription.delivers(&rsi, evening));
     }
 }
@@ -0,0 +1,629 @@
+//! User-defined alert rules
+//!
+//! A rule is a condition expression over named metrics, for example
+//! `rsi < 30 AND composite_score > 70 AND foreign_net_5_day > 0`.
+//! Comparisons can be combined with `AND` / `OR` (AND binds tighter) and
+//! grouped with parentheses. Metrics are resolved from a [`RuleContext`]
+//! filled with the latest computed indicators of one symbol. The rule
+//! owner's custom indicators are referenced as `custom_<name>`.
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::str::FromStr;
+
+use super::AlertPriority;
+
+/// Longest rule expression accepted, in characters
+pub const MAX_RULE_LEN: usize = 500;
+
+/// Deepest nesting of parentheses accepted
+pub const MAX_RULE_DEPTH: usize = 8;
+
+/// Metrics a rule may reference, with a short description
+pub const RULE_METRICS: &[(&str, &str)] = &[
+    ("close", "Last close price"),
+    ("change_pct", "Change from the previous close (%)"),
+    ("rvol", "Volume relative to the 20-day average"),
+    ("rsi", "14-period RSI"),
+    ("macd", "MACD line"),
+    ("macd_histogram", "MACD histogram"),
+    ("atr", "14-period ATR"),
+    ("price_vs_vwap_pct", "Last price relative to VWAP (%)"),
+    ("composite_score", "Composite score (0-100)"),
+    ("technical_score", "Technical score (0-100)"),
+    ("fundamental_score", "Fundamental score (0-100)"),
+    ("sentiment_score", "Sentiment score (0-100)"),
+    ("ml_score", "ML score (0-100)"),
+    (
+        "accumulation_score",
+        "Institutional accumulation score (0-100)",
+    ),
+    ("foreign_net_5_day", "5-day foreign net buy value"),
+    ("foreign_net_20_day", "20-day foreign net buy value"),
+    (
+        "institutional_net_5_day",
+        "5-day institutional net buy value",
+    ),
+    (
+        "institutional_net_20_day",
+        "20-day institutional net buy value",
+    ),
+];
+
+/// Prefix of metrics naming one of the rule owner's custom indicators
+pub const CUSTOM_METRIC_PREFIX: &str = "custom_";
+
+/// Custom indicator name of a `custom_<name>` metric
+pub fn custom_indicator_name(metric: &str) -> Option<&str> {
+    metric
+        .strip_prefix(CUSTOM_METRIC_PREFIX)
+        .filter(|name| !name.is_empty())
+}
+
+fn is_known_metric(name: &str) -> bool {
+    RULE_METRICS.iter().any(|(metric, _)| *metric == name) || custom_indicator_name(name).is_some()
+}
+
+/// Why a rule expression was rejected
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum RuleParseError {
+    #[error("rule expression is empty")]
+    Empty,
+    #[error("rule expression is longer than {MAX_RULE_LEN} characters")]
+    TooLong,
+    #[error("parentheses nest deeper than {MAX_RULE_DEPTH} levels")]
+    TooDeep,
+    #[error("unexpected character '{0}'")]
+    UnexpectedChar(char),
+    #[error("invalid number '{0}'")]
+    InvalidNumber(String),
+    #[error("unknown metric '{0}'")]
+    UnknownMetric(String),
+    #[error("expected {expected}, found {found}")]
+    Expected {
+        expected: &'static str,
+        found: String,
+    },
+}
+
+/// Comparison operator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompareOp {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+impl CompareOp {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CompareOp::Lt => "<",
+            CompareOp::Le => "<=",
+            CompareOp::Gt => ">",
+            CompareOp::Ge => ">=",
+            CompareOp::Eq => "==",
+            CompareOp::Ne => "!=",
+        }
+    }
+
+    fn apply(&self, left: Decimal, right: Decimal) -> bool {
+        match self {
+            CompareOp::Lt => left < right,
+            CompareOp::Le => left <= right,
+            CompareOp::Gt => left > right,
+            CompareOp::Ge => left >= right,
+            CompareOp::Eq => left == right,
+            CompareOp::Ne => left != right,
+        }
+    }
+}
+
+/// `metric op value`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Comparison {
+    pub metric: String,
+    pub op: CompareOp,
+    pub value: Decimal,
+}
+
+/// Parsed rule condition
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RuleExpr {
+    Compare(Comparison),
+    And { terms: Vec<RuleExpr> },
+    Or { terms: Vec<RuleExpr> },
+}
+
+impl RuleExpr {
+    /// Parse and validate an expression
+    pub fn parse(input: &str) -> Result<Self, RuleParseError> {
+        if input.chars().count() > MAX_RULE_LEN {
+            return Err(RuleParseError::TooLong);
+        }
+        let tokens = tokenize(input)?;
+        if tokens.is_empty() {
+            return Err(RuleParseError::Empty);
+        }
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            depth: 0,
+        };
+        let expr = parser.or_expr()?;
+        match parser.peek() {
+            None => Ok(expr),
+            Some(token) => Err(RuleParseError::Expected {
+                expected: "AND, OR or end of expression",
+                found: token.to_string(),
+            }),
+        }
+    }
+
+    /// Metrics referenced by the expression
+    pub fn metrics(&self) -> BTreeSet<&str> {
+        let mut metrics = BTreeSet::new();
+        self.collect_metrics(&mut metrics);
+        metrics
+    }
+
+    fn collect_metrics<'a>(&'a self, metrics: &mut BTreeSet<&'a str>) {
+        match self {
+            RuleExpr::Compare(c) => {
+                metrics.insert(c.metric.as_str());
+            }
+            RuleExpr::And { terms } | RuleExpr::Or { terms } => {
+                for term in terms {
+                    term.collect_metrics(metrics);
+                }
+            }
+        }
+    }
+
+    /// Evaluate against `context`; a comparison on a missing metric is false
+    pub fn matches(&self, context: &RuleContext) -> bool {
+        match self {
+            RuleExpr::Compare(c) => context
+                .get(&c.metric)
+                .is_some_and(|actual| c.op.apply(actual, c.value)),
+            RuleExpr::And { terms } => terms.iter().all(|t| t.matches(context)),
+            RuleExpr::Or { terms } => terms.iter().any(|t| t.matches(context)),
+        }
+    }
+}
+
+impl fmt::Display for RuleExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |f: &mut fmt::Formatter<'_>, terms: &[RuleExpr], keyword: &str| {
+            for (i, term) in terms.iter().enumerate() {
+                if i > 0 {
+                    write!(f, " {} ", keyword)?;
+                }
+                match term {
+                    RuleExpr::Or { .. } if keyword == "AND" => write!(f, "({})", term)?,
+                    _ => write!(f, "{}", term)?,
+                }
+            }
+            Ok(())
+        };
+        match self {
+            RuleExpr::Compare(c) => write!(f, "{} {} {}", c.metric, c.op.as_str(), c.value),
+            RuleExpr::And { terms } => join(f, terms, "AND"),
+            RuleExpr::Or { terms } => join(f, terms, "OR"),
+        }
+    }
+}
+
+/// Latest metric values of one symbol
+#[derive(Debug, Clone, Default)]
+pub struct RuleContext {
+    values: HashMap<String, Decimal>,
+}
+
+impl RuleContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(&mut self, metric: &str, value: Decimal) {
+        self.values.insert(metric.to_string(), value);
+    }
+
+    /// Set the metric if a value is available
+    pub fn set_opt(&mut self, metric: &str, value: Option<Decimal>) {
+        if let Some(value) = value {
+            self.set(metric, value);
+        }
+    }
+
+    pub fn get(&self, metric: &str) -> Option<Decimal> {
+        self.values.get(metric).copied()
+    }
+}
+
+/// A named, user-owned condition
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertRule {
+    pub name: String,
+    pub expression: String,
+    pub condition: RuleExpr,
+    /// Symbols the rule watches; empty means every watchlisted symbol
+    pub symbols: Vec<String>,
+    pub priority: AlertPriority,
+}
+
+/// Result of evaluating a rule for one symbol
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RuleEvaluation {
+    pub matched: bool,
+    /// Referenced metrics with no value for the symbol
+    pub missing_metrics: Vec<String>,
+}
+
+impl AlertRule {
+    pub fn new(
+        name: &str,
+        expression: &str,
+        symbols: Vec<String>,
+        priority: AlertPriority,
+    ) -> Result<Self, RuleParseError> {
+        Ok(Self {
+            name: name.to_string(),
+            expression: expression.to_string(),
+            condition: RuleExpr::parse(expression)?,
+            symbols: symbols.into_iter().map(|s| s.to_uppercase()).collect(),
+            priority,
+        })
+    }
+
+    pub fn applies_to(&self, symbol: &str) -> bool {
+        self.symbols.is_empty() || self.symbols.iter().any(|s| s.eq_ignore_ascii_case(symbol))
+    }
+
+    pub fn evaluate(&self, context: &RuleContext) -> RuleEvaluation {
+        RuleEvaluation {
+            matched: self.condition.matches(context),
+            missing_metrics: self
+                .condition
+                .metrics()
+                .into_iter()
+                .filter(|m| context.get(m).is_none())
+                .map(str::to_string)
+                .collect(),
+        }
+    }
+
+    /// Message sent when the rule matches
+    pub fn message(&self, symbol: &str) -> String {
+        format!(
+            "{}: rule '{}' matched ({})",
+            symbol, self.name, self.condition
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(Decimal),
+    Op(CompareOp),
+    And,
+    Or,
+    LParen,
+    RParen,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Ident(name) => write!(f, "'{}'", name),
+            Token::Number(value) => write!(f, "{}", value),
+            Token::Op(op) => write!(f, "'{}'", op.as_str()),
+            Token::And => write!(f, "AND"),
+            Token::Or => write!(f, "OR"),
+            Token::LParen => write!(f, "'('"),
+            Token::RParen => write!(f, "')'"),
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, RuleParseError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            '<' | '>' | '=' | '!' => {
+                let (op, len) = match (c, next) {
+                    ('<', Some('=')) => (CompareOp::Le, 2),
+                    ('<', _) => (CompareOp::Lt, 1),
+                    ('>', Some('=')) => (CompareOp::Ge, 2),
+                    ('>', _) => (CompareOp::Gt, 1),
+                    ('=', Some('=')) => (CompareOp::Eq, 2),
+                    ('=', _) => (CompareOp::Eq, 1),
+                    ('!', Some('=')) => (CompareOp::Ne, 2),
+                    _ => return Err(RuleParseError::UnexpectedChar(c)),
+                };
+                tokens.push(Token::Op(op));
+                i += len;
+            }
+            c if c.is_ascii_digit() || c == '-' || c == '.' => {
+                let start = i;
+                i += 1;
+                while i < chars.len() && (chars[i].is_ascii_digit() || "._".contains(chars[i])) {
+                    i += 1;
+                }
+                let text: String = chars[start..i].iter().collect();
+                let value = Decimal::from_str(&text.replace('_', ""))
+                    .map_err(|_| RuleParseError::InvalidNumber(text))?;
+                tokens.push(Token::Number(value));
+            }
+            c if c.is_ascii_alphabetic() => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                tokens.push(match word.to_ascii_uppercase().as_str() {
+                    "AND" => Token::And,
+                    "OR" => Token::Or,
+                    _ => Token::Ident(word.to_ascii_lowercase()),
+                });
+            }
+            _ => return Err(RuleParseError::UnexpectedChar(c)),
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    /// Parentheses open at the current position
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self, expected: &'static str) -> Result<Token, RuleParseError> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .ok_or(RuleParseError::Expected {
+                expected,
+                found: "end of expression".to_string(),
+            })?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn or_expr(&mut self) -> Result<RuleExpr, RuleParseError> {
+        let mut terms = vec![self.and_expr()?];
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            terms.push(self.and_expr()?);
+        }
+        Ok(if terms.len() == 1 {
+            terms.remove(0)
+        } else {
+            RuleExpr::Or { terms }
+        })
+    }
+
+    fn and_expr(&mut self) -> Result<RuleExpr, RuleParseError> {
+        let mut terms = vec![self.atom()?];
+        while self.peek() == Some(&Token::And) {
+            self.pos += 1;
+            terms.push(self.atom()?);
+        }
+        Ok(if terms.len() == 1 {
+            terms.remove(0)
+        } else {
+            RuleExpr::And { terms }
+        })
+    }
+
+    fn atom(&mut self) -> Result<RuleExpr, RuleParseError> {
+        match self.next("a metric or '('")? {
+            Token::LParen => {
+                self.depth += 1;
+                if self.depth > MAX_RULE_DEPTH {
+                    return Err(RuleParseError::TooDeep);
+                }
+                let expr = self.or_expr()?;
+                self.depth -= 1;
+                match self.next("')'")? {
+                    Token::RParen => Ok(expr),
+                    token => Err(RuleParseError::Expected {
+                        expected: "')'",
+                        found: token.to_string(),
+                    }),
+                }
+            }
+            Token::Ident(metric) => {
+                if !is_known_metric(&metric) {
+                    return Err(RuleParseError::UnknownMetric(metric));
+                }
+                let op = match self.next("a comparison operator")? {
+                    Token::Op(op) => op,
+                    token => {
+                        return Err(RuleParseError::Expected {
+                            expected: "a comparison operator",
+                            found: token.to_string(),
+                        })
+                    }
+                };
+                let value = match self.next("a number")? {
+                    Token::Number(value) => value,
+                    token => {
+                        return Err(RuleParseError::Expected {
+                            expected: "a number",
+                            found: token.to_string(),
+                        })
+                    }
+                };
+                Ok(RuleExpr::Compare(Comparison { metric, op, value }))
+            }
+            token => Err(RuleParseError::Expected {
+                expected: "a metric or '('",
+                found: token.to_string(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn context() -> RuleContext {
+        let mut context = RuleContext::new();
+        context.set("rsi", dec!(28));
+        context.set("composite_score", dec!(74));
+        context.set("foreign_net_5_day", dec!(-1_500_000));
+        context
+    }
+
+    #[test]
+    fn test_parse_and_evaluate() {
+        let rule = AlertRule::new(
+            "Oversold quality",
+            "rsi < 30 AND composite_score > 70 AND foreign_net_5_day > 0",
+            vec!["bbca".to_string()],
+            AlertPriority::High,
+        )
+        .unwrap();
+        assert!(rule.applies_to("BBCA"));
+        assert!(!rule.applies_to("TLKM"));
+
+        // Foreign selling fails the third condition
+        let evaluation = rule.evaluate(&context());
+        assert!(!evaluation.matched);
+        assert!(evaluation.missing_metrics.is_empty());
+
+        let mut buying = context();
+        buying.set("foreign_net_5_day", dec!(2_000_000));
+        assert!(rule.evaluate(&buying).matched);
+    }
+
+    #[test]
+    fn test_precedence_and_grouping() {
+        // AND binds tighter: true OR (false AND ...)
+        let expr = RuleExpr::parse("rsi <= 28 or composite_score > 90 and rsi > 50").unwrap();
+        assert!(expr.matches(&context()));
+
+        let grouped = RuleExpr::parse("(rsi <= 28 OR composite_score > 90) AND rsi > 50").unwrap();
+        assert!(!grouped.matches(&context()));
+        assert_eq!(
+            grouped.to_string(),
+            "(rsi <= 28 OR composite_score > 90) AND rsi > 50"
+        );
+        assert_eq!(
+            grouped.metrics().into_iter().collect::<Vec<_>>(),
+            vec!["composite_score", "rsi"]
+        );
+    }
+
+    #[test]
+    fn test_missing_metric_does_not_match() {
+        let rule = AlertRule::new("Volume", "rvol >= 2", vec![], AlertPriority::Medium).unwrap();
+        let evaluation = rule.evaluate(&context());
+        assert!(!evaluation.matched);
+        assert_eq!(evaluation.missing_metrics, vec!["rvol".to_string()]);
+        assert!(rule.applies_to("ANY"));
+    }
+
+    #[test]
+    fn test_custom_indicator_metrics() {
+        let rule = AlertRule::new(
+            "Trend spread",
+            "custom_ema_spread > 1.5 AND rsi < 70",
+            vec![],
+            AlertPriority::Low,
+        )
+        .unwrap();
+        let evaluation = rule.evaluate(&context());
+        assert_eq!(
+            evaluation.missing_metrics,
+            vec!["custom_ema_spread".to_string()]
+        );
+
+        let mut spread = context();
+        spread.set("custom_ema_spread", dec!(2.1));
+        assert!(rule.evaluate(&spread).matched);
+        assert_eq!(
+            custom_indicator_name("custom_ema_spread"),
+            Some("ema_spread")
+        );
+        assert_eq!(
+            RuleExpr::parse("custom_ > 0"),
+            Err(RuleParseError::UnknownMetric("custom_".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(RuleExpr::parse("  "), Err(RuleParseError::Empty));
+        assert_eq!(
+            RuleExpr::parse("rsii < 30"),
+            Err(RuleParseError::UnknownMetric("rsii".to_string()))
+        );
+        assert!(matches!(
+            RuleExpr::parse("rsi < 30 AND"),
+            Err(RuleParseError::Expected { .. })
+        ));
+        assert!(matches!(
+            RuleExpr::parse("(rsi < 30"),
+            Err(RuleParseError::Expected { .. })
+        ));
+        assert!(matches!(
+            RuleExpr::parse("rsi 30"),
+            Err(RuleParseError::Expected { .. })
+        ));
+        assert_eq!(
+            RuleExpr::parse("rsi < 30 # note"),
+            Err(RuleParseError::UnexpectedChar('#'))
+        );
+    }
+
+    #[test]
+    fn test_parse_limits() {
+        let nested = |depth: usize| format!("{}rsi < 30{}", "(".repeat(depth), ")".repeat(depth));
+        assert!(RuleExpr::parse(&nested(MAX_RULE_DEPTH)).is_ok());
+        assert_eq!(
+            RuleExpr::parse(&nested(MAX_RULE_DEPTH + 1)),
+            Err(RuleParseError::TooDeep)
+        );
+        // Rejected before tokenizing, however deep
+        assert_eq!(
+            RuleExpr::parse(&nested(100_000)),
+            Err(RuleParseError::TooLong)
+        );
+        let long = vec!["rsi < 30"; 60].join(" AND ");
+        assert_eq!(RuleExpr::parse(&long), Err(RuleParseError::TooLong));
+    }
+}
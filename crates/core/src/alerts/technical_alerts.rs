@@ -3,9 +3,12 @@
 //! Triggers alerts for:
 //! - RSI overbought/oversold
 //! - MACD crossovers
-//! - Wyckoff phase transitions
+//! - Wyckoff phase transitions and Spring/Upthrust/SOS/SOW events
 //! - Volume spikes
 //! - Price breakouts
+//! - Crosses of user-defined price levels
+//! - Intraday VWAP reclaims and losses on volume
+//! - Regular and hidden divergences between price and an oscillator
 
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
@@ -43,6 +46,12 @@ pub enum TechnicalAlertType {
     WyckoffUpthrust {
         price: Decimal,
     },
+    /// Wyckoff event detected on the latest bar
+    WyckoffEvent {
+        /// Snake-case event name, e.g. `spring` or `sign_of_strength`
+        event: String,
+        confidence: u8,
+    },
     VolumeSpike {
         rvol: Decimal,
     },
@@ -65,6 +74,78 @@ pub enum TechnicalAlertType {
     BollingerSqueeze {
         bandwidth: Decimal,
     },
+    UserLevelCrossed {
+        price: Decimal,
+        level: Decimal,
+        label: Option<String>,
+        crossed_above: bool,
+    },
+    /// Intraday close crossed above session VWAP on volume
+    VwapReclaimed {
+        price: Decimal,
+        vwap: Decimal,
+        rvol: Decimal,
+    },
+    /// Intraday close crossed below session VWAP on volume
+    VwapLost {
+        price: Decimal,
+        vwap: Decimal,
+        rvol: Decimal,
+    },
+    /// Price and an oscillator disagree at the latest swing low
+    BullishDivergence {
+        indicator: String,
+        /// Hidden (trend continuation) rather than regular (reversal)
+        hidden: bool,
+        confidence: u8,
+    },
+    /// Price and an oscillator disagree at the latest swing high
+    BearishDivergence {
+        indicator: String,
+        hidden: bool,
+        confidence: u8,
+    },
+    /// A user-defined rule matched
+    RuleMatched {
+        rule_id: i32,
+        name: String,
+        condition: String,
+    },
+}
+
+impl TechnicalAlertType {
+    /// Key of the glossary entry explaining this alert
+    pub fn glossary_key(&self) -> &'static str {
+        match self {
+            TechnicalAlertType::RsiOverbought { .. } => "rsi_overbought",
+            TechnicalAlertType::RsiOversold { .. } => "rsi_oversold",
+            TechnicalAlertType::MacdBullishCrossover { .. }
+            | TechnicalAlertType::MacdBearishCrossover { .. } => "macd_crossover",
+            TechnicalAlertType::WyckoffAccumulation { .. } => "wyckoff_accumulation",
+            TechnicalAlertType::WyckoffDistribution { .. } => "wyckoff_distribution",
+            TechnicalAlertType::WyckoffSpring { .. } => "wyckoff_spring",
+            TechnicalAlertType::WyckoffUpthrust { .. } => "wyckoff_upthrust",
+            TechnicalAlertType::WyckoffEvent { event, .. } => match event.as_str() {
+                "spring" => "wyckoff_spring",
+                "upthrust" => "wyckoff_upthrust",
+                "sign_of_strength" => "wyckoff_sign_of_strength",
+                "sign_of_weakness" => "wyckoff_sign_of_weakness",
+                _ => "wyckoff",
+            },
+            TechnicalAlertType::VolumeSpike { .. } => "volume_spike",
+            TechnicalAlertType::PriceBreakout { .. } => "price_breakout",
+            TechnicalAlertType::PriceBreakdown { .. } => "price_breakdown",
+            TechnicalAlertType::GoldenCross { .. } => "golden_cross",
+            TechnicalAlertType::DeathCross { .. } => "death_cross",
+            TechnicalAlertType::BollingerSqueeze { .. } => "bollinger_squeeze",
+            TechnicalAlertType::UserLevelCrossed { .. } => "user_price_level",
+            TechnicalAlertType::VwapReclaimed { .. } => "vwap_reclaim",
+            TechnicalAlertType::VwapLost { .. } => "vwap_loss",
+            TechnicalAlertType::BullishDivergence { .. } => "bullish_divergence",
+            TechnicalAlertType::BearishDivergence { .. } => "bearish_divergence",
+            TechnicalAlertType::RuleMatched { .. } => "alert_rule",
+        }
+    }
 }
 
 /// Technical alert
@@ -76,12 +157,16 @@ pub struct TechnicalAlert {
     pub priority: AlertPriority,
     pub message: String,
     pub created_at: DateTime<Utc>,
+    /// Glossary entry explaining the alert
+    #[serde(default)]
+    pub glossary_key: String,
 }
 
 impl TechnicalAlert {
     pub fn new(symbol: String, alert_type: TechnicalAlertType, priority: AlertPriority) -> Self {
         let id = format!("tech_{}_{}", symbol, Utc::now().timestamp_millis());
         let message = generate_tech_message(&symbol, &alert_type);
+        let glossary_key = alert_type.glossary_key().to_string();
         Self {
             id,
             symbol,
@@ -89,6 +174,7 @@ impl TechnicalAlert {
             priority,
             message,
             created_at: Utc::now(),
+            glossary_key,
         }
     }
 }
@@ -101,6 +187,17 @@ pub struct TechnicalAlertConfig {
     pub rvol_spike_threshold: Decimal,
     pub wyckoff_min_confidence: u8,
     pub bollinger_squeeze_threshold: Decimal,
+    /// Minimum intraday RVOL for a VWAP cross to alert
+    pub vwap_min_rvol: Decimal,
+    /// Minimum confidence for a divergence to alert
+    pub divergence_min_confidence: u8,
+    /// Minimum confidence for a Wyckoff event to alert
+    #[serde(default = "default_wyckoff_event_min_confidence")]
+    pub wyckoff_event_min_confidence: u8,
+}
+
+fn default_wyckoff_event_min_confidence() -> u8 {
+    60
 }
 
 impl Default for TechnicalAlertConfig {
@@ -111,15 +208,57 @@ impl Default for TechnicalAlertConfig {
             rvol_spike_threshold: dec!(2.5),
             wyckoff_min_confidence: 70,
             bollinger_squeeze_threshold: dec!(0.05),
+            vwap_min_rvol: dec!(1.5),
+            divergence_min_confidence: 60,
+            wyckoff_event_min_confidence: default_wyckoff_event_min_confidence(),
         }
     }
 }
 
+/// Horizontal price level drawn by the user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserPriceLevel {
+    pub id: Option<i32>,
+    pub price: Decimal,
+    pub label: Option<String>,
+}
+
+/// Divergence confirmed on the latest bars
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DivergenceSignal {
+    /// Oscillator price disagreed with, e.g. `rsi`
+    pub indicator: String,
+    pub bullish: bool,
+    pub hidden: bool,
+    pub confidence: u8,
+}
+
+/// Wyckoff event detected on the latest bar
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WyckoffEventSignal {
+    /// Snake-case event name, e.g. `spring`
+    pub event: String,
+    pub confidence: u8,
+}
+
+/// Wyckoff events that alert, with their priority
+///
+/// Springs and Upthrusts are false breaks that reverse the range and are the
+/// most urgent; SOS and SOW confirm a breakout already under way.
+const ALERTING_WYCKOFF_EVENTS: [(&str, AlertPriority); 4] = [
+    ("spring", AlertPriority::Critical),
+    ("upthrust", AlertPriority::Critical),
+    ("sign_of_strength", AlertPriority::High),
+    ("sign_of_weakness", AlertPriority::High),
+];
+
 /// Input for technical alert evaluation
 #[derive(Debug, Clone, Default)]
 pub struct TechnicalAlertInput {
     pub symbol: String,
     pub current_price: Decimal,
+    /// Previous close, needed to detect level crosses
+    pub prev_price: Option<Decimal>,
     pub rsi: Option<Decimal>,
     pub macd: Option<Decimal>,
     pub macd_signal: Option<Decimal>,
@@ -136,6 +275,17 @@ pub struct TechnicalAlertInput {
     pub wyckoff_confidence: Option<u8>,
     pub wyckoff_event: Option<String>,
     pub bollinger_bandwidth: Option<Decimal>,
+    pub user_levels: Vec<UserPriceLevel>,
+    /// Close of the current and previous intraday bar
+    pub intraday_close: Option<Decimal>,
+    pub prev_intraday_close: Option<Decimal>,
+    /// Session VWAP at the current and previous intraday bar
+    pub vwap: Option<Decimal>,
+    pub prev_vwap: Option<Decimal>,
+    /// Volume of the current intraday bar relative to the session so far
+    pub intraday_rvol: Option<Decimal>,
+    pub divergences: Vec<DivergenceSignal>,
+    pub wyckoff_events: Vec<WyckoffEventSignal>,
 }
 
 /// Technical alert engine
@@ -313,6 +463,26 @@ impl TechnicalAlertEngine {
             }
         }
 
+        for signal in &input.wyckoff_events {
+            if signal.confidence < self.config.wyckoff_event_min_confidence {
+                continue;
+            }
+            let Some(&(_, priority)) = ALERTING_WYCKOFF_EVENTS
+                .iter()
+                .find(|(event, _)| *event == signal.event)
+            else {
+                continue;
+            };
+            alerts.push(TechnicalAlert::new(
+                input.symbol.clone(),
+                TechnicalAlertType::WyckoffEvent {
+                    event: signal.event.clone(),
+                    confidence: signal.confidence,
+                },
+                priority,
+            ));
+        }
+
         // Bollinger squeeze
         if let Some(bandwidth) = input.bollinger_bandwidth {
             if bandwidth <= self.config.bollinger_squeeze_threshold {
@@ -324,6 +494,83 @@ impl TechnicalAlertEngine {
             }
         }
 
+        // User-defined levels
+        if let Some(prev_price) = input.prev_price {
+            for level in &input.user_levels {
+                let crossed_above = prev_price < level.price && input.current_price >= level.price;
+                let crossed_below = prev_price > level.price && input.current_price <= level.price;
+                if crossed_above || crossed_below {
+                    alerts.push(TechnicalAlert::new(
+                        input.symbol.clone(),
+                        TechnicalAlertType::UserLevelCrossed {
+                            price: input.current_price,
+                            level: level.price,
+                            label: level.label.clone(),
+                            crossed_above,
+                        },
+                        AlertPriority::High,
+                    ));
+                }
+            }
+        }
+
+        // VWAP reclaim/loss on volume
+        if let (Some(price), Some(prev_price), Some(vwap), Some(prev_vwap), Some(rvol)) = (
+            input.intraday_close,
+            input.prev_intraday_close,
+            input.vwap,
+            input.prev_vwap,
+            input.intraday_rvol,
+        ) {
+            if rvol >= self.config.vwap_min_rvol {
+                if prev_price <= prev_vwap && price > vwap {
+                    alerts.push(TechnicalAlert::new(
+                        input.symbol.clone(),
+                        TechnicalAlertType::VwapReclaimed { price, vwap, rvol },
+                        AlertPriority::High,
+                    ));
+                } else if prev_price >= prev_vwap && price < vwap {
+                    alerts.push(TechnicalAlert::new(
+                        input.symbol.clone(),
+                        TechnicalAlertType::VwapLost { price, vwap, rvol },
+                        AlertPriority::High,
+                    ));
+                }
+            }
+        }
+
+        // Divergences; regular ones call a reversal and rank higher
+        for divergence in &input.divergences {
+            if divergence.confidence < self.config.divergence_min_confidence {
+                continue;
+            }
+            let indicator = divergence.indicator.clone();
+            let (hidden, confidence) = (divergence.hidden, divergence.confidence);
+            let alert_type = if divergence.bullish {
+                TechnicalAlertType::BullishDivergence {
+                    indicator,
+                    hidden,
+                    confidence,
+                }
+            } else {
+                TechnicalAlertType::BearishDivergence {
+                    indicator,
+                    hidden,
+                    confidence,
+                }
+            };
+            let priority = if hidden {
+                AlertPriority::Medium
+            } else {
+                AlertPriority::High
+            };
+            alerts.push(TechnicalAlert::new(
+                input.symbol.clone(),
+                alert_type,
+                priority,
+            ));
+        }
+
         alerts
     }
 }
@@ -334,6 +581,15 @@ impl Default for TechnicalAlertEngine {
     }
 }
 
+fn indicator_label(indicator: &str) -> &str {
+    match indicator {
+        "rsi" => "RSI",
+        "macd_histogram" => "MACD histogram",
+        "obv" => "OBV",
+        other => other,
+    }
+}
+
 fn generate_tech_message(symbol: &str, alert_type: &TechnicalAlertType) -> String {
     match alert_type {
         TechnicalAlertType::RsiOverbought { rsi } => {
@@ -375,6 +631,19 @@ fn generate_tech_message(symbol: &str, alert_type: &TechnicalAlertType) -> Strin
                 symbol, price
             )
         }
+        TechnicalAlertType::WyckoffEvent { event, confidence } => {
+            let (label, reading) = match event.as_str() {
+                "spring" => ("spring", "false breakdown, bullish"),
+                "upthrust" => ("upthrust", "false breakout, bearish"),
+                "sign_of_strength" => ("sign of strength", "breakout on volume"),
+                "sign_of_weakness" => ("sign of weakness", "breakdown on volume"),
+                other => (other, "watch the range"),
+            };
+            format!(
+                "{}: Wyckoff {} ({}% confidence) - {}",
+                symbol, label, confidence, reading
+            )
+        }
         TechnicalAlertType::VolumeSpike { rvol } => {
             format!(
                 "{}: Volume spike at {:.1}x average - unusual activity",
@@ -405,6 +674,79 @@ fn generate_tech_message(symbol: &str, alert_type: &TechnicalAlertType) -> Strin
                 symbol, bandwidth
             )
         }
+        TechnicalAlertType::UserLevelCrossed {
+            price,
+            level,
+            label,
+            crossed_above,
+        } => {
+            let direction = if *crossed_above { "above" } else { "below" };
+            match label {
+                Some(label) => format!(
+                    "{}: Price crossed {} your level {} ({}) at {}",
+                    symbol, direction, level, label, price
+                ),
+                None => format!(
+                    "{}: Price crossed {} your level {} at {}",
+                    symbol, direction, level, price
+                ),
+            }
+        }
+        TechnicalAlertType::VwapReclaimed { price, vwap, rvol } => {
+            format!(
+                "{}: Reclaimed VWAP {:.0} at {} on {:.1}x volume - buyers in control",
+                symbol, vwap, price, rvol
+            )
+        }
+        TechnicalAlertType::VwapLost { price, vwap, rvol } => {
+            format!(
+                "{}: Lost VWAP {:.0} at {} on {:.1}x volume - sellers in control",
+                symbol, vwap, price, rvol
+            )
+        }
+        TechnicalAlertType::BullishDivergence {
+            indicator,
+            hidden,
+            confidence,
+        } => {
+            let (kind, reading) = if *hidden {
+                ("Hidden bullish", "uptrend likely to resume")
+            } else {
+                ("Bullish", "selling losing momentum")
+            };
+            format!(
+                "{}: {} divergence with {} ({}% confidence) - {}",
+                symbol,
+                kind,
+                indicator_label(indicator),
+                confidence,
+                reading
+            )
+        }
+        TechnicalAlertType::BearishDivergence {
+            indicator,
+            hidden,
+            confidence,
+        } => {
+            let (kind, reading) = if *hidden {
+                ("Hidden bearish", "downtrend likely to resume")
+            } else {
+                ("Bearish", "buying losing momentum")
+            };
+            format!(
+                "{}: {} divergence with {} ({}% confidence) - {}",
+                symbol,
+                kind,
+                indicator_label(indicator),
+                confidence,
+                reading
+            )
+        }
+        TechnicalAlertType::RuleMatched {
+            name, condition, ..
+        } => {
+            format!("{}: Your rule '{}' matched ({})", symbol, name, condition)
+        }
     }
 }
 
@@ -519,4 +861,157 @@ mod tests {
             .any(|a| matches!(a.alert_type, TechnicalAlertType::WyckoffSpring { .. })));
         assert!(alerts.iter().any(|a| a.priority == AlertPriority::Critical));
     }
+
+    #[test]
+    fn test_wyckoff_event_alerts() {
+        let engine = TechnicalAlertEngine::new();
+        let signal = |event: &str, confidence| WyckoffEventSignal {
+            event: event.to_string(),
+            confidence,
+        };
+        let input = TechnicalAlertInput {
+            symbol: "TLKM".into(),
+            wyckoff_events: vec![
+                signal("sign_of_strength", 80),
+                signal("upthrust", 70),
+                // Below the confidence floor
+                signal("spring", 50),
+                // Not an alerting event
+                signal("secondary_test", 90),
+            ],
+            ..Default::default()
+        };
+
+        let alerts = engine.evaluate(&input);
+        assert_eq!(alerts.len(), 2);
+
+        let sos = &alerts[0];
+        assert!(matches!(
+            &sos.alert_type,
+            TechnicalAlertType::WyckoffEvent { event, confidence: 80 } if event == "sign_of_strength"
+        ));
+        assert_eq!(sos.priority, AlertPriority::High);
+        assert_eq!(sos.glossary_key, "wyckoff_sign_of_strength");
+        assert!(sos.message.contains("sign of strength"));
+
+        assert_eq!(alerts[1].priority, AlertPriority::Critical);
+        assert_eq!(alerts[1].glossary_key, "wyckoff_upthrust");
+    }
+
+    #[test]
+    fn test_user_level_cross() {
+        let engine = TechnicalAlertEngine::new();
+        let input = TechnicalAlertInput {
+            symbol: "BBCA".into(),
+            current_price: dec!(9550),
+            prev_price: Some(dec!(9450)),
+            user_levels: vec![
+                UserPriceLevel {
+                    id: Some(1),
+                    price: dec!(9500),
+                    label: Some("Planned entry".into()),
+                },
+                UserPriceLevel {
+                    id: Some(2),
+                    price: dec!(10000),
+                    label: None,
+                },
+            ],
+            ..Default::default()
+        };
+
+        let alerts = engine.evaluate(&input);
+        let crosses: Vec<_> = alerts
+            .iter()
+            .filter(|a| matches!(a.alert_type, TechnicalAlertType::UserLevelCrossed { .. }))
+            .collect();
+        assert_eq!(crosses.len(), 1);
+        assert!(crosses[0]
+            .message
+            .contains("above your level 9500 (Planned entry)"));
+        assert_eq!(crosses[0].glossary_key, "user_price_level");
+    }
+
+    #[test]
+    fn test_vwap_reclaim_on_volume() {
+        let engine = TechnicalAlertEngine::new();
+        let mut input = TechnicalAlertInput {
+            symbol: "BBCA".into(),
+            current_price: dec!(9300),
+            intraday_close: Some(dec!(9300)),
+            prev_intraday_close: Some(dec!(9225)),
+            vwap: Some(dec!(9260)),
+            prev_vwap: Some(dec!(9250)),
+            intraday_rvol: Some(dec!(2.4)),
+            ..Default::default()
+        };
+        let alerts = engine.evaluate(&input);
+        assert_eq!(alerts.len(), 1);
+        assert!(matches!(
+            alerts[0].alert_type,
+            TechnicalAlertType::VwapReclaimed { .. }
+        ));
+        assert_eq!(alerts[0].glossary_key, "vwap_reclaim");
+
+        // A cross on thin volume does not alert
+        input.intraday_rvol = Some(dec!(1.1));
+        assert!(engine.evaluate(&input).is_empty());
+
+        input.intraday_rvol = Some(dec!(3));
+        input.intraday_close = Some(dec!(9200));
+        input.prev_intraday_close = Some(dec!(9275));
+        let alerts = engine.evaluate(&input);
+        assert!(matches!(
+            alerts[0].alert_type,
+            TechnicalAlertType::VwapLost { .. }
+        ));
+    }
+
+    #[test]
+    fn test_divergence_alerts() {
+        let engine = TechnicalAlertEngine::new();
+        let input = TechnicalAlertInput {
+            symbol: "TLKM".into(),
+            current_price: dec!(2800),
+            divergences: vec![
+                DivergenceSignal {
+                    indicator: "rsi".into(),
+                    bullish: true,
+                    hidden: false,
+                    confidence: 78,
+                },
+                DivergenceSignal {
+                    indicator: "obv".into(),
+                    bullish: false,
+                    hidden: true,
+                    confidence: 65,
+                },
+                // Below the confidence threshold
+                DivergenceSignal {
+                    indicator: "macd_histogram".into(),
+                    bullish: true,
+                    hidden: false,
+                    confidence: 45,
+                },
+            ],
+            ..Default::default()
+        };
+
+        let alerts = engine.evaluate(&input);
+        assert_eq!(alerts.len(), 2);
+        assert!(matches!(
+            alerts[0].alert_type,
+            TechnicalAlertType::BullishDivergence { hidden: false, .. }
+        ));
+        assert_eq!(alerts[0].priority, AlertPriority::High);
+        assert_eq!(alerts[0].glossary_key, "bullish_divergence");
+        assert!(alerts[0]
+            .message
+            .contains("Bullish divergence with RSI (78%"));
+        assert!(matches!(
+            alerts[1].alert_type,
+            TechnicalAlertType::BearishDivergence { hidden: true, .. }
+        ));
+        assert_eq!(alerts[1].priority, AlertPriority::Medium);
+    }
 }
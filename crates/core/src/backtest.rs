@@ -0,0 +1,881 @@
+//! Single-symbol strategy backtests
+//!
+//! Replays daily bars through a long-only strategy. A signal read at a
+//! session's close is filled at the next session's open, in whole lots,
+//! and charged the transaction-cost model at the ADTV of the bars before
+//! the fill, so a backtest never trades on a price it could not have seen.
+//! A position still open after the last bar is sold at its close.
+//!
+//! Grid searches over these parameters live in [`crate::optimization`];
+//! each report carries a [`crate::monte_carlo`] resampling of its trades.
+
+use crate::idx::{affordable_lots, LOT_SIZE};
+use crate::monte_carlo::{simulate_trades, MonteCarloConfig, MonteCarloSummary, MAX_SIMULATIONS};
+use crate::transaction_costs::{TradeSide, TransactionCostModel};
+use chrono::NaiveDate;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+/// IDX sessions in a year, for annualizing
+pub const TRADING_DAYS_PER_YEAR: f64 = 242.0;
+
+/// Bars averaged for the ADTV that prices spread and impact
+const ADTV_BARS: usize = 20;
+
+/// One daily bar of the backtested symbol
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BacktestBar {
+    pub date: NaiveDate,
+    pub open: Decimal,
+    pub close: Decimal,
+    pub volume: i64,
+    /// Composite score stored for the session, if any
+    #[serde(default)]
+    pub score: Option<Decimal>,
+}
+
+/// Entry and exit rules
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BacktestStrategy {
+    /// Long while the fast simple moving average of closes is above the slow one
+    SmaCrossover { fast: usize, slow: usize },
+    /// Long from a composite score at or above `entry` until it falls below `exit`
+    ///
+    /// Sessions without a stored score keep the previous decision.
+    ScoreThreshold { entry: Decimal, exit: Decimal },
+    /// Long from an RSI at or below `entry` (oversold) until it reaches `exit`
+    RsiReversion {
+        period: usize,
+        entry: f64,
+        exit: f64,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum BacktestError {
+    #[error("fast period must be positive and shorter than the slow period")]
+    InvalidPeriods,
+    #[error("exit score must be between 0 and the entry score, entry at most 100")]
+    InvalidThresholds,
+    #[error("RSI period must be positive and the entry below the exit, both within 0 to 100")]
+    InvalidRsi,
+    #[error("initial capital must be positive")]
+    InvalidCapital,
+    #[error("stop loss must be above 0% and below 100%")]
+    InvalidStopLoss,
+    #[error("maximum holding period must be at least one bar")]
+    InvalidHoldingPeriod,
+    #[error("Monte Carlo needs at most {MAX_SIMULATIONS} simulations and a ruin loss between 0% and 100%")]
+    InvalidMonteCarlo,
+    #[error("backtest needs at least {needed} bars, got {got}")]
+    NotEnoughBars { needed: usize, got: usize },
+}
+
+impl BacktestStrategy {
+    pub fn validate(&self) -> Result<(), BacktestError> {
+        match *self {
+            Self::SmaCrossover { fast, slow } => {
+                if fast == 0 || fast >= slow {
+                    return Err(BacktestError::InvalidPeriods);
+                }
+            }
+            Self::ScoreThreshold { entry, exit } => {
+                if exit < Decimal::ZERO || exit > entry || entry > dec!(100) {
+                    return Err(BacktestError::InvalidThresholds);
+                }
+            }
+            Self::RsiReversion {
+                period,
+                entry,
+                exit,
+            } => {
+                if period == 0 || !(0.0..100.0).contains(&entry) || !(entry < exit && exit <= 100.0)
+                {
+                    return Err(BacktestError::InvalidRsi);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Bars read before the first signal
+    pub fn warmup(&self) -> usize {
+        match *self {
+            Self::SmaCrossover { slow, .. } => slow,
+            Self::ScoreThreshold { .. } => 1,
+            Self::RsiReversion { period, .. } => period + 1,
+        }
+    }
+
+    /// Whether to hold the symbol after each bar's close
+    fn signals(&self, bars: &[BacktestBar]) -> Vec<bool> {
+        match *self {
+            Self::SmaCrossover { fast, slow } => {
+                let closes: Vec<f64> = bars
+                    .iter()
+                    .map(|b| b.close.to_f64().unwrap_or(0.0))
+                    .collect();
+                let fast_sma = sma(&closes, fast);
+                let slow_sma = sma(&closes, slow);
+                fast_sma
+                    .iter()
+                    .zip(&slow_sma)
+                    .map(|(f, s)| matches!((f, s), (Some(f), Some(s)) if f > s))
+                    .collect()
+            }
+            Self::ScoreThreshold { entry, exit } => {
+                let mut long = false;
+                bars.iter()
+                    .map(|bar| {
+                        if let Some(score) = bar.score {
+                            long = if long { score >= exit } else { score >= entry };
+                        }
+                        long
+                    })
+                    .collect()
+            }
+            Self::RsiReversion {
+                period,
+                entry,
+                exit,
+            } => {
+                let closes: Vec<f64> = bars
+                    .iter()
+                    .map(|b| b.close.to_f64().unwrap_or(0.0))
+                    .collect();
+                let mut long = false;
+                rsi(&closes, period)
+                    .into_iter()
+                    .map(|value| {
+                        if let Some(value) = value {
+                            long = if long { value < exit } else { value <= entry };
+                        }
+                        long
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Simple moving average ending at each index, once `period` values are in
+fn sma(values: &[f64], period: usize) -> Vec<Option<f64>> {
+    let mut sum = 0.0;
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, v)| {
+            sum += v;
+            if i >= period {
+                sum -= values[i - period];
+            }
+            (i + 1 >= period).then(|| sum / period as f64)
+        })
+        .collect()
+}
+
+/// Wilder's RSI at each index, once `period` changes are in
+fn rsi(values: &[f64], period: usize) -> Vec<Option<f64>> {
+    let mut out = vec![None; values.len()];
+    if values.len() <= period {
+        return out;
+    }
+    let change = |i: usize| values[i] - values[i - 1];
+    let (mut gain, mut loss) = (1..=period).fold((0.0, 0.0), |(g, l), i| {
+        let c = change(i);
+        (g + c.max(0.0), l + (-c).max(0.0))
+    });
+    gain /= period as f64;
+    loss /= period as f64;
+    let value = |gain: f64, loss: f64| {
+        if loss == 0.0 {
+            if gain == 0.0 {
+                50.0
+            } else {
+                100.0
+            }
+        } else {
+            100.0 - 100.0 / (1.0 + gain / loss)
+        }
+    };
+    out[period] = Some(value(gain, loss));
+    for (i, slot) in out.iter_mut().enumerate().skip(period + 1) {
+        let c = change(i);
+        gain = (gain * (period - 1) as f64 + c.max(0.0)) / period as f64;
+        loss = (loss * (period - 1) as f64 + (-c).max(0.0)) / period as f64;
+        *slot = Some(value(gain, loss));
+    }
+    out
+}
+
+/// Capital and risk settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BacktestConfig {
+    /// Starting cash, IDR
+    pub initial_capital: Decimal,
+    /// Sell at the next open once a close is this far below the entry (%);
+    /// the strategy must signal flat before it may buy again
+    #[serde(default)]
+    pub stop_loss_pct: Option<f64>,
+    /// Sell at the next open once a position has been held this many bars;
+    /// like a stop, the strategy must signal flat before buying again
+    #[serde(default)]
+    pub max_hold_bars: Option<usize>,
+    /// Resampling of the trades included in the report
+    #[serde(default)]
+    pub monte_carlo: MonteCarloConfig,
+}
+
+impl Default for BacktestConfig {
+    fn default() -> Self {
+        Self {
+            initial_capital: dec!(100_000_000),
+            stop_loss_pct: None,
+            max_hold_bars: None,
+            monte_carlo: MonteCarloConfig::default(),
+        }
+    }
+}
+
+impl BacktestConfig {
+    pub fn validate(&self) -> Result<(), BacktestError> {
+        if self.initial_capital <= Decimal::ZERO {
+            return Err(BacktestError::InvalidCapital);
+        }
+        if let Some(stop) = self.stop_loss_pct {
+            if !(stop > 0.0 && stop < 100.0) {
+                return Err(BacktestError::InvalidStopLoss);
+            }
+        }
+        if self.max_hold_bars == Some(0) {
+            return Err(BacktestError::InvalidHoldingPeriod);
+        }
+        if !self.monte_carlo.is_valid() {
+            return Err(BacktestError::InvalidMonteCarlo);
+        }
+        Ok(())
+    }
+}
+
+/// Why a position was closed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExitReason {
+    Signal,
+    StopLoss,
+    /// Held for the configured maximum
+    MaxHold,
+    /// Still open after the last bar
+    EndOfData,
+}
+
+/// A round trip
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BacktestTrade {
+    pub entry_date: NaiveDate,
+    pub entry_price: Decimal,
+    pub exit_date: NaiveDate,
+    pub exit_price: Decimal,
+    pub shares: i64,
+    /// Profit after fees, spread and impact on both sides
+    pub pnl: Decimal,
+    /// `pnl` in percent of the cash spent on entry
+    pub return_pct: f64,
+    pub bars_held: usize,
+    pub exit_reason: ExitReason,
+}
+
+/// Account value after a session's close
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EquityPoint {
+    pub date: NaiveDate,
+    pub equity: Decimal,
+    /// Below the running peak, percent
+    pub drawdown_pct: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BacktestMetrics {
+    pub initial_capital: Decimal,
+    pub final_equity: Decimal,
+    pub total_return_pct: f64,
+    /// Compounded yearly over the bars tested
+    pub annualized_return_pct: Option<f64>,
+    /// Deepest fall from a peak, percent
+    pub max_drawdown_pct: f64,
+    /// Annualized mean over deviation of daily equity returns
+    pub sharpe_ratio: Option<f64>,
+    pub trades: usize,
+    pub win_rate_pct: Option<f64>,
+    /// Gross profit over gross loss of closed trades
+    pub profit_factor: Option<f64>,
+    pub avg_trade_return_pct: Option<f64>,
+    /// Share of sessions ending with a position, percent
+    pub exposure_pct: f64,
+    /// Holding from the first close to the last, before costs
+    pub buy_and_hold_return_pct: f64,
+}
+
+/// Everything a backtest produced
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BacktestReport {
+    pub strategy: BacktestStrategy,
+    pub config: BacktestConfig,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub metrics: BacktestMetrics,
+    pub trades: Vec<BacktestTrade>,
+    pub equity_curve: Vec<EquityPoint>,
+    /// None without trades or with the simulation switched off
+    #[serde(default)]
+    pub monte_carlo: Option<MonteCarloSummary>,
+}
+
+struct OpenPosition {
+    entry_index: usize,
+    entry_price: Decimal,
+    shares: i64,
+    /// Notional plus entry costs
+    spent: Decimal,
+}
+
+enum Order {
+    Buy,
+    Sell(ExitReason),
+}
+
+/// Average traded value of the bars before `index`
+fn trailing_adtv(bars: &[BacktestBar], index: usize) -> Option<Decimal> {
+    let window = &bars[index.saturating_sub(ADTV_BARS)..index];
+    if window.is_empty() {
+        return None;
+    }
+    let total: Decimal = window
+        .iter()
+        .map(|b| b.close * Decimal::from(b.volume))
+        .sum();
+    Some(total / Decimal::from(window.len()))
+}
+
+/// Most whole lots `cash` buys at `price` with entry costs included
+fn lots_within(
+    cash: Decimal,
+    price: Decimal,
+    adtv: Option<Decimal>,
+    costs: &TransactionCostModel,
+) -> i64 {
+    let mut lots = affordable_lots(cash, price);
+    while lots > 0 {
+        let cost = costs.trade_cost(TradeSide::Buy, price, lots * LOT_SIZE, adtv);
+        if cost.notional + cost.total() <= cash {
+            break;
+        }
+        lots -= 1;
+    }
+    lots
+}
+
+fn close_position(
+    position: &OpenPosition,
+    bars: &[BacktestBar],
+    index: usize,
+    price: Decimal,
+    reason: ExitReason,
+    costs: &TransactionCostModel,
+) -> (Decimal, BacktestTrade) {
+    let sell = costs.trade_cost(
+        TradeSide::Sell,
+        price,
+        position.shares,
+        trailing_adtv(bars, index),
+    );
+    let received = sell.notional - sell.total();
+    let pnl = received - position.spent;
+    let return_pct = (pnl / position.spent * dec!(100)).to_f64().unwrap_or(0.0);
+    let trade = BacktestTrade {
+        entry_date: bars[position.entry_index].date,
+        entry_price: position.entry_price,
+        exit_date: bars[index].date,
+        exit_price: price,
+        shares: position.shares,
+        pnl: pnl.round_dp(2),
+        return_pct,
+        bars_held: index - position.entry_index,
+        exit_reason: reason,
+    };
+    (received, trade)
+}
+
+/// Run `strategy` over `bars`, oldest first
+pub fn run_backtest(
+    bars: &[BacktestBar],
+    strategy: &BacktestStrategy,
+    config: &BacktestConfig,
+    costs: &TransactionCostModel,
+) -> Result<BacktestReport, BacktestError> {
+    strategy.validate()?;
+    config.validate()?;
+    let needed = strategy.warmup() + 1;
+    if bars.len() < needed {
+        return Err(BacktestError::NotEnoughBars {
+            needed,
+            got: bars.len(),
+        });
+    }
+
+    let signals = strategy.signals(bars);
+    let stop_fraction = config
+        .stop_loss_pct
+        .and_then(|pct| Decimal::try_from(pct / 100.0).ok());
+    let mut cash = config.initial_capital;
+    let mut position: Option<OpenPosition> = None;
+    let mut pending: Option<Order> = None;
+    // After a forced exit the signal must turn flat before buying again
+    let mut armed = true;
+    let mut trades = Vec::new();
+    let mut equity_curve = Vec::with_capacity(bars.len());
+    let mut peak = cash;
+    let mut sessions_held = 0usize;
+
+    for (i, bar) in bars.iter().enumerate() {
+        match pending.take() {
+            Some(Order::Buy) => {
+                let adtv = trailing_adtv(bars, i);
+                let shares = lots_within(cash, bar.open, adtv, costs) * LOT_SIZE;
+                if shares > 0 {
+                    let cost = costs.trade_cost(TradeSide::Buy, bar.open, shares, adtv);
+                    let spent = cost.notional + cost.total();
+                    cash -= spent;
+                    position = Some(OpenPosition {
+                        entry_index: i,
+                        entry_price: bar.open,
+                        shares,
+                        spent,
+                    });
+                }
+            }
+            Some(Order::Sell(reason)) => {
+                if let Some(open) = position.take() {
+                    let (received, trade) = close_position(&open, bars, i, bar.open, reason, costs);
+                    cash += received;
+                    trades.push(trade);
+                }
+            }
+            None => {}
+        }
+
+        let held = position.as_ref().map_or(0, |p| p.shares);
+        if held > 0 {
+            sessions_held += 1;
+        }
+        let equity = cash + bar.close * Decimal::from(held);
+        peak = peak.max(equity);
+        equity_curve.push(EquityPoint {
+            date: bar.date,
+            equity: equity.round_dp(2),
+            drawdown_pct: drawdown_pct(equity, peak),
+        });
+
+        if !signals[i] {
+            armed = true;
+        }
+        if i + 1 == bars.len() {
+            break;
+        }
+        pending = match &position {
+            None if signals[i] && armed => Some(Order::Buy),
+            None => None,
+            Some(open) => {
+                let stopped = stop_fraction
+                    .is_some_and(|stop| bar.close <= open.entry_price * (Decimal::ONE - stop));
+                let expired = config
+                    .max_hold_bars
+                    .is_some_and(|max| i - open.entry_index + 1 >= max);
+                if stopped {
+                    armed = false;
+                    Some(Order::Sell(ExitReason::StopLoss))
+                } else if expired {
+                    armed = false;
+                    Some(Order::Sell(ExitReason::MaxHold))
+                } else if !signals[i] {
+                    Some(Order::Sell(ExitReason::Signal))
+                } else {
+                    None
+                }
+            }
+        };
+    }
+
+    let last = bars.len() - 1;
+    if let Some(open) = position.take() {
+        let (received, trade) = close_position(
+            &open,
+            bars,
+            last,
+            bars[last].close,
+            ExitReason::EndOfData,
+            costs,
+        );
+        cash += received;
+        trades.push(trade);
+        let point = equity_curve.last_mut().expect("one point per bar");
+        point.equity = cash.round_dp(2);
+        point.drawdown_pct = drawdown_pct(cash, peak);
+    }
+
+    let metrics = metrics(
+        config.initial_capital,
+        cash,
+        bars,
+        &trades,
+        &equity_curve,
+        sessions_held,
+    );
+    let monte_carlo = simulate_trades(config.initial_capital, &trades, &config.monte_carlo);
+    Ok(BacktestReport {
+        strategy: strategy.clone(),
+        config: config.clone(),
+        start: bars[0].date,
+        end: bars[last].date,
+        metrics,
+        trades,
+        equity_curve,
+        monte_carlo,
+    })
+}
+
+fn drawdown_pct(equity: Decimal, peak: Decimal) -> f64 {
+    if peak <= Decimal::ZERO {
+        return 0.0;
+    }
+    ((peak - equity) / peak * dec!(100))
+        .to_f64()
+        .unwrap_or(0.0)
+        .max(0.0)
+}
+
+fn pct_change(from: Decimal, to: Decimal) -> f64 {
+    if from.is_zero() {
+        return 0.0;
+    }
+    ((to - from) / from * dec!(100)).to_f64().unwrap_or(0.0)
+}
+
+fn metrics(
+    initial: Decimal,
+    final_equity: Decimal,
+    bars: &[BacktestBar],
+    trades: &[BacktestTrade],
+    curve: &[EquityPoint],
+    sessions_held: usize,
+) -> BacktestMetrics {
+    let growth = (final_equity / initial).to_f64().unwrap_or(1.0);
+    let years = (bars.len() - 1) as f64 / TRADING_DAYS_PER_YEAR;
+    let annualized_return_pct =
+        (years > 0.0 && growth > 0.0).then(|| (growth.powf(1.0 / years) - 1.0) * 100.0);
+
+    let daily: Vec<f64> = curve
+        .windows(2)
+        .filter_map(|w| {
+            let prev = w[0].equity.to_f64()?;
+            (prev > 0.0).then(|| w[1].equity.to_f64().unwrap_or(prev) / prev - 1.0)
+        })
+        .collect();
+    let sharpe_ratio = if daily.len() < 2 {
+        None
+    } else {
+        let n = daily.len() as f64;
+        let mean = daily.iter().sum::<f64>() / n;
+        let sd = (daily.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt();
+        (sd > 0.0).then(|| mean / sd * TRADING_DAYS_PER_YEAR.sqrt())
+    };
+
+    let wins = trades.iter().filter(|t| t.pnl > Decimal::ZERO).count();
+    let gross_profit: Decimal = trades
+        .iter()
+        .filter(|t| t.pnl > Decimal::ZERO)
+        .map(|t| t.pnl)
+        .sum();
+    let gross_loss: Decimal = trades
+        .iter()
+        .filter(|t| t.pnl < Decimal::ZERO)
+        .map(|t| -t.pnl)
+        .sum();
+    let (win_rate_pct, avg_trade_return_pct) = if trades.is_empty() {
+        (None, None)
+    } else {
+        let n = trades.len() as f64;
+        (
+            Some(wins as f64 / n * 100.0),
+            Some(trades.iter().map(|t| t.return_pct).sum::<f64>() / n),
+        )
+    };
+    let profit_factor = (gross_loss > Decimal::ZERO)
+        .then(|| (gross_profit / gross_loss).to_f64())
+        .flatten();
+
+    BacktestMetrics {
+        initial_capital: initial,
+        final_equity: final_equity.round_dp(2),
+        total_return_pct: pct_change(initial, final_equity),
+        annualized_return_pct,
+        max_drawdown_pct: curve.iter().map(|p| p.drawdown_pct).fold(0.0, f64::max),
+        sharpe_ratio,
+        trades: trades.len(),
+        win_rate_pct,
+        profit_factor,
+        avg_trade_return_pct,
+        exposure_pct: sessions_held as f64 / bars.len() as f64 * 100.0,
+        buy_and_hold_return_pct: pct_change(bars[0].close, bars[bars.len() - 1].close),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn free() -> TransactionCostModel {
+        TransactionCostModel {
+            broker_fee_bps: 0.0,
+            levy_bps: 0.0,
+            sell_tax_bps: 0.0,
+            min_spread_bps: 0.0,
+            max_spread_bps: 0.0,
+            impact_bps: 0.0,
+            default_slippage_bps: 0.0,
+            ..TransactionCostModel::default()
+        }
+    }
+
+    fn bars(closes: &[i64]) -> Vec<BacktestBar> {
+        let start = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, &close)| BacktestBar {
+                date: start + chrono::Duration::days(i as i64),
+                open: Decimal::from(close),
+                close: Decimal::from(close),
+                volume: 1_000_000,
+                score: None,
+            })
+            .collect()
+    }
+
+    fn config(capital: Decimal) -> BacktestConfig {
+        BacktestConfig {
+            initial_capital: capital,
+            stop_loss_pct: None,
+            max_hold_bars: None,
+            monte_carlo: MonteCarloConfig::default(),
+        }
+    }
+
+    #[test]
+    fn test_sma() {
+        let s = sma(&[1.0, 2.0, 3.0, 4.0], 2);
+        assert_eq!(s, vec![None, Some(1.5), Some(2.5), Some(3.5)]);
+    }
+
+    #[test]
+    fn test_crossover_fills_at_next_open() {
+        let mut bars = bars(&[100, 100, 100, 110, 120, 130, 120, 100, 90]);
+        // Opens differ from the closes the signal reads
+        for bar in bars.iter_mut() {
+            bar.open += dec!(1);
+        }
+        let strategy = BacktestStrategy::SmaCrossover { fast: 1, slow: 3 };
+        let report = run_backtest(&bars, &strategy, &config(dec!(1_010_000)), &free()).unwrap();
+
+        assert_eq!(report.trades.len(), 1);
+        let trade = &report.trades[0];
+        // Close 110 crosses above the 3-day mean on day 3; bought at day 4's open
+        assert_eq!(trade.entry_date, bars[4].date);
+        assert_eq!(trade.entry_price, dec!(121));
+        assert_eq!(trade.shares, 8300);
+        // Close 120 falls below the mean on day 6; sold at day 7's open
+        assert_eq!(trade.exit_date, bars[7].date);
+        assert_eq!(trade.exit_price, dec!(101));
+        assert_eq!(trade.exit_reason, ExitReason::Signal);
+        assert_eq!(trade.pnl, dec!(-166000));
+        assert_eq!(report.metrics.final_equity, dec!(844000));
+        assert_eq!(report.metrics.win_rate_pct, Some(0.0));
+        assert!(report.metrics.max_drawdown_pct > 0.0);
+    }
+
+    #[test]
+    fn test_report_includes_monte_carlo() {
+        let bars = bars(&[100, 100, 110, 120, 100, 105, 110, 100, 110, 120]);
+        let strategy = BacktestStrategy::SmaCrossover { fast: 1, slow: 2 };
+        let report = run_backtest(&bars, &strategy, &config(dec!(10_000_000)), &free()).unwrap();
+        let simulated = report.monte_carlo.unwrap();
+        assert_eq!(simulated.trades_per_path, report.trades.len());
+        assert_eq!(simulated.simulations, 1000);
+
+        let mut off = config(dec!(10_000_000));
+        off.monte_carlo.simulations = 0;
+        let report = run_backtest(&bars, &strategy, &off, &free()).unwrap();
+        assert!(report.monte_carlo.is_none());
+    }
+
+    #[test]
+    fn test_costs_lower_returns() {
+        let bars = bars(&[100, 100, 100, 110, 120, 130, 140, 150]);
+        let strategy = BacktestStrategy::SmaCrossover { fast: 1, slow: 2 };
+        let gross = run_backtest(&bars, &strategy, &config(dec!(10_000_000)), &free()).unwrap();
+        let net = run_backtest(
+            &bars,
+            &strategy,
+            &config(dec!(10_000_000)),
+            &TransactionCostModel::default(),
+        )
+        .unwrap();
+
+        assert_eq!(gross.trades[0].exit_reason, ExitReason::EndOfData);
+        assert!(net.metrics.final_equity < gross.metrics.final_equity);
+        assert!(net.trades[0].return_pct < gross.trades[0].return_pct);
+        // The closing sale is booked on the last point of the curve
+        assert_eq!(
+            net.equity_curve.last().unwrap().equity,
+            net.metrics.final_equity
+        );
+    }
+
+    #[test]
+    fn test_score_threshold_hysteresis() {
+        let mut bars = bars(&[100; 8]);
+        let scores = [60, 72, 68, 66, 64, 71, 50, 50];
+        for (bar, score) in bars.iter_mut().zip(scores) {
+            bar.score = Some(Decimal::from(score));
+        }
+        bars[3].score = None;
+        let strategy = BacktestStrategy::ScoreThreshold {
+            entry: dec!(70),
+            exit: dec!(65),
+        };
+        let report = run_backtest(&bars, &strategy, &config(dec!(1_000_000)), &free()).unwrap();
+
+        // In at 72, held through 68 and the missing score, out below 65
+        assert_eq!(report.trades.len(), 2);
+        assert_eq!(report.trades[0].entry_date, bars[2].date);
+        assert_eq!(report.trades[0].exit_date, bars[5].date);
+        assert_eq!(report.trades[1].entry_date, bars[6].date);
+        assert_eq!(report.trades[1].exit_date, bars[7].date);
+    }
+
+    #[test]
+    fn test_stop_loss_waits_for_flat_signal() {
+        let bars = bars(&[100, 100, 110, 120, 100, 105, 110, 100, 110, 120]);
+        let strategy = BacktestStrategy::SmaCrossover { fast: 1, slow: 2 };
+        let mut cfg = config(dec!(10_000_000));
+        cfg.stop_loss_pct = Some(10.0);
+        let report = run_backtest(&bars, &strategy, &cfg, &free()).unwrap();
+
+        let first = &report.trades[0];
+        assert_eq!(first.entry_price, dec!(120));
+        assert_eq!(first.exit_reason, ExitReason::StopLoss);
+        assert_eq!(first.exit_date, bars[5].date);
+        // 105 and 110 still signal long, but entries wait for the flat signal at 100
+        assert_eq!(report.trades.len(), 2);
+        assert_eq!(report.trades[1].entry_date, bars[9].date);
+    }
+
+    #[test]
+    fn test_rsi() {
+        let rising: Vec<f64> = (0..6).map(f64::from).collect();
+        let values = rsi(&rising, 3);
+        assert_eq!(values[..3], [None, None, None]);
+        assert_eq!(values[3], Some(100.0));
+
+        let mixed = rsi(&[10.0, 11.0, 10.0, 11.0, 10.0], 2);
+        assert_eq!(mixed[2], Some(50.0));
+        // The loss on the last bar outweighs the smoothed gain
+        assert!(mixed[4].unwrap() < 50.0);
+    }
+
+    #[test]
+    fn test_rsi_reversion() {
+        let bars = bars(&[100, 98, 96, 94, 95, 97, 99, 101, 103]);
+        let strategy = BacktestStrategy::RsiReversion {
+            period: 2,
+            entry: 10.0,
+            exit: 90.0,
+        };
+        let report = run_backtest(&bars, &strategy, &config(dec!(10_000_000)), &free()).unwrap();
+
+        // Oversold from the first reading, bought at the next open; sold once
+        // the rebound takes the RSI to 90
+        assert_eq!(report.trades.len(), 1);
+        assert_eq!(report.trades[0].entry_date, bars[3].date);
+        assert_eq!(report.trades[0].exit_reason, ExitReason::Signal);
+        assert!(report.trades[0].pnl > Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_max_hold() {
+        let bars = bars(&[100, 100, 110, 120, 130, 140, 130, 150, 160]);
+        let strategy = BacktestStrategy::SmaCrossover { fast: 1, slow: 2 };
+        let mut cfg = config(dec!(10_000_000));
+        cfg.max_hold_bars = Some(2);
+        let report = run_backtest(&bars, &strategy, &cfg, &free()).unwrap();
+
+        let first = &report.trades[0];
+        assert_eq!(first.entry_date, bars[3].date);
+        assert_eq!(first.bars_held, 2);
+        assert_eq!(first.exit_reason, ExitReason::MaxHold);
+        // Re-entered only after the signal turned flat at 130
+        assert_eq!(report.trades[1].entry_date, bars[8].date);
+    }
+
+    #[test]
+    fn test_validation() {
+        let bars = bars(&[100, 101, 102]);
+        let cfg = BacktestConfig::default();
+        let run = |strategy: BacktestStrategy| run_backtest(&bars, &strategy, &cfg, &free());
+
+        assert_eq!(
+            run(BacktestStrategy::SmaCrossover { fast: 5, slow: 5 }).unwrap_err(),
+            BacktestError::InvalidPeriods
+        );
+        assert_eq!(
+            run(BacktestStrategy::ScoreThreshold {
+                entry: dec!(60),
+                exit: dec!(70)
+            })
+            .unwrap_err(),
+            BacktestError::InvalidThresholds
+        );
+        assert_eq!(
+            run(BacktestStrategy::RsiReversion {
+                period: 14,
+                entry: 70.0,
+                exit: 30.0
+            })
+            .unwrap_err(),
+            BacktestError::InvalidRsi
+        );
+        assert_eq!(
+            run(BacktestStrategy::SmaCrossover { fast: 2, slow: 5 }).unwrap_err(),
+            BacktestError::NotEnoughBars { needed: 6, got: 3 }
+        );
+        let broke = BacktestConfig {
+            initial_capital: Decimal::ZERO,
+            stop_loss_pct: None,
+            max_hold_bars: None,
+            monte_carlo: MonteCarloConfig::default(),
+        };
+        assert_eq!(
+            run_backtest(
+                &bars,
+                &BacktestStrategy::SmaCrossover { fast: 1, slow: 2 },
+                &broke,
+                &free()
+            )
+            .unwrap_err(),
+            BacktestError::InvalidCapital
+        );
+    }
+}
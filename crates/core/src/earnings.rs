@@ -0,0 +1,237 @@
+//! Financial report calendar
+//!
+//! IDX issuers publish quarterly statements within a month of the quarter
+//! end and audited annual statements within three months of the year end
+//! (POJK 14/2022). A stock's next report is expected on the date it filed
+//! the same period last year, or on the regulatory deadline when there is
+//! no history.
+
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+/// Days after the deadline a late report is still expected
+pub const LATE_REPORT_GRACE_DAYS: i64 = 30;
+
+/// Reporting period of a financial statement
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportPeriod {
+    Q1,
+    /// Half-year statement
+    Q2,
+    Q3,
+    /// Audited full-year statement
+    Annual,
+}
+
+impl ReportPeriod {
+    pub const ALL: [ReportPeriod; 4] = [Self::Q1, Self::Q2, Self::Q3, Self::Annual];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Q1 => "q1",
+            Self::Q2 => "q2",
+            Self::Q3 => "q3",
+            Self::Annual => "annual",
+        }
+    }
+
+    pub fn from_str_opt(s: &str) -> Option<Self> {
+        match s {
+            "q1" => Some(Self::Q1),
+            "q2" => Some(Self::Q2),
+            "q3" => Some(Self::Q3),
+            "annual" => Some(Self::Annual),
+            _ => None,
+        }
+    }
+
+    /// Period from an IDX label: "TW1".."TW3", "Tahunan" or "Audit"
+    pub fn from_idx_label(label: &str) -> Option<Self> {
+        match label.trim().to_lowercase().as_str() {
+            "tw1" | "q1" => Some(Self::Q1),
+            "tw2" | "q2" => Some(Self::Q2),
+            "tw3" | "q3" => Some(Self::Q3),
+            "tahunan" | "audit" | "annual" | "fy" => Some(Self::Annual),
+            _ => None,
+        }
+    }
+
+    /// Last day of the period in `fiscal_year`
+    pub fn period_end(&self, fiscal_year: i32) -> NaiveDate {
+        let (month, day) = match self {
+            Self::Q1 => (3, 31),
+            Self::Q2 => (6, 30),
+            Self::Q3 => (9, 30),
+            Self::Annual => (12, 31),
+        };
+        NaiveDate::from_ymd_opt(fiscal_year, month, day).expect("valid period end")
+    }
+
+    /// Last day the statement may be published
+    ///
+    /// The unaudited deadline for half-years; reviewed half-year statements
+    /// may follow a month later.
+    pub fn filing_deadline(&self, fiscal_year: i32) -> NaiveDate {
+        match self {
+            Self::Q1 => NaiveDate::from_ymd_opt(fiscal_year, 4, 30),
+            Self::Q2 => NaiveDate::from_ymd_opt(fiscal_year, 7, 31),
+            Self::Q3 => NaiveDate::from_ymd_opt(fiscal_year, 10, 31),
+            Self::Annual => NaiveDate::from_ymd_opt(fiscal_year + 1, 3, 31),
+        }
+        .expect("valid deadline")
+    }
+}
+
+/// A published financial statement
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FiledReport {
+    pub period: ReportPeriod,
+    pub fiscal_year: i32,
+    pub filed_on: NaiveDate,
+}
+
+/// How an expected report date was estimated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EstimateBasis {
+    /// Filing date of the same period last year
+    LastYear,
+    /// Regulatory deadline, without history
+    Deadline,
+}
+
+impl EstimateBasis {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::LastYear => "last_year",
+            Self::Deadline => "deadline",
+        }
+    }
+}
+
+/// A financial statement not yet published
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExpectedReport {
+    pub period: ReportPeriod,
+    pub fiscal_year: i32,
+    pub expected_date: NaiveDate,
+    pub deadline: NaiveDate,
+    pub basis: EstimateBasis,
+}
+
+/// Same day one year later, Feb 29 falling back to Feb 28
+fn one_year_later(date: NaiveDate) -> NaiveDate {
+    date.with_year(date.year() + 1)
+        .or_else(|| NaiveDate::from_ymd_opt(date.year() + 1, 2, 28))
+        .expect("valid date")
+}
+
+/// Unpublished reports of one stock whose deadline falls between
+/// `LATE_REPORT_GRACE_DAYS` ago and `horizon_days` ahead of `today`,
+/// earliest deadline first
+pub fn expected_reports(
+    filed: &[FiledReport],
+    today: NaiveDate,
+    horizon_days: i64,
+) -> Vec<ExpectedReport> {
+    let is_filed = |period, year| {
+        filed
+            .iter()
+            .any(|f| f.period == period && f.fiscal_year == year)
+    };
+    let earliest = today - Duration::days(LATE_REPORT_GRACE_DAYS);
+    let latest = today + Duration::days(horizon_days);
+
+    let mut expected: Vec<ExpectedReport> = (today.year() - 1..=today.year() + 1)
+        .flat_map(|year| ReportPeriod::ALL.map(|period| (period, year)))
+        .filter(|(period, year)| {
+            let deadline = period.filing_deadline(*year);
+            deadline >= earliest && deadline <= latest && !is_filed(*period, *year)
+        })
+        .map(|(period, year)| {
+            let deadline = period.filing_deadline(year);
+            let last_year = filed
+                .iter()
+                .find(|f| f.period == period && f.fiscal_year == year - 1)
+                .map(|f| one_year_later(f.filed_on))
+                .filter(|d| *d > period.period_end(year) && *d <= deadline);
+            ExpectedReport {
+                period,
+                fiscal_year: year,
+                expected_date: last_year.unwrap_or(deadline),
+                deadline,
+                basis: if last_year.is_some() {
+                    EstimateBasis::LastYear
+                } else {
+                    EstimateBasis::Deadline
+                },
+            }
+        })
+        .collect();
+    expected.sort_by_key(|e| e.deadline);
+    expected
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_deadlines() {
+        assert_eq!(ReportPeriod::Q1.filing_deadline(2025), date("2025-04-30"));
+        assert_eq!(
+            ReportPeriod::Annual.filing_deadline(2024),
+            date("2025-03-31")
+        );
+        assert_eq!(ReportPeriod::Q2.period_end(2025), date("2025-06-30"));
+        assert_eq!(ReportPeriod::from_idx_label("TW3"), Some(ReportPeriod::Q3));
+        assert_eq!(
+            ReportPeriod::from_idx_label("Tahunan"),
+            Some(ReportPeriod::Annual)
+        );
+        assert_eq!(ReportPeriod::from_idx_label("RUPS"), None);
+    }
+
+    #[test]
+    fn test_expected_from_last_year() {
+        let filed = [
+            FiledReport {
+                period: ReportPeriod::Q3,
+                fiscal_year: 2024,
+                filed_on: date("2024-10-25"),
+            },
+            FiledReport {
+                period: ReportPeriod::Q2,
+                fiscal_year: 2025,
+                filed_on: date("2025-07-28"),
+            },
+        ];
+        let expected = expected_reports(&filed, date("2025-09-15"), 200);
+
+        // Q2 2025 is filed; Q3 follows last year's date, the annual the deadline
+        assert_eq!(expected.len(), 2);
+        assert_eq!(expected[0].period, ReportPeriod::Q3);
+        assert_eq!(expected[0].expected_date, date("2025-10-25"));
+        assert_eq!(expected[0].basis, EstimateBasis::LastYear);
+        assert_eq!(expected[1].period, ReportPeriod::Annual);
+        assert_eq!(expected[1].expected_date, date("2026-03-31"));
+        assert_eq!(expected[1].basis, EstimateBasis::Deadline);
+    }
+
+    #[test]
+    fn test_late_report_still_expected() {
+        // Q1 is two weeks overdue; it stays on the calendar within the grace
+        let expected = expected_reports(&[], date("2025-05-14"), 30);
+        assert_eq!(expected.len(), 1);
+        assert_eq!(expected[0].period, ReportPeriod::Q1);
+        assert_eq!(expected[0].fiscal_year, 2025);
+
+        let expected = expected_reports(&[], date("2025-06-14"), 30);
+        assert!(expected.is_empty());
+    }
+}
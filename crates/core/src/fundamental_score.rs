@@ -1,11 +1,20 @@
 //! Fundamental Score Engine
 //!
 //! Combines valuation metrics into a 0-100 fundamental score:
-//! - Valuation (P/E, P/B, EV/EBITDA vs sector) - 35%
+//! - Valuation (P/E, P/B, EV/EBITDA vs sector, FCF yield) - 35%
 //! - DCF Margin of Safety - 25%
-//! - Quality (ROE, ROA, Profit Margin) - 20%
+//! - Quality (ROE, ROA, Profit Margin, ROIC spread over WACC) - 20%
 //! - Financial Health (D/E, Current Ratio) - 20%
+//!
+//! Sector profiles (see `sector_profile`) override these weights and the set
+//! of metrics scored, adding revenue growth where it matters.
+//!
+//! Financials published longer ago than [`StalenessPolicy::FINANCIALS`]
+//! allows have every sub-score decayed toward neutral, noted in
+//! `stale_inputs`.
 
+use crate::scoring::{StaleInput, StalenessPolicy};
+use crate::sector_profile::{FundamentalMetric, SectorProfile};
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
@@ -45,6 +54,8 @@ pub struct FundamentalInput {
     pub ev_ebitda: Option<Decimal>,
     /// Sector average EV/EBITDA
     pub sector_ev_ebitda: Option<Decimal>,
+    /// Trailing free cash flow yield on market cap (%)
+    pub fcf_yield: Option<Decimal>,
     /// DCF margin of safety (%)
     pub dcf_margin: Option<Decimal>,
     /// ROE (%)
@@ -53,10 +64,19 @@ pub struct FundamentalInput {
     pub roa: Option<Decimal>,
     /// Profit margin (%)
     pub profit_margin: Option<Decimal>,
+    /// Return on invested capital (%)
+    pub roic: Option<Decimal>,
+    /// Weighted average cost of capital (%)
+    pub wacc: Option<Decimal>,
+    /// Year-over-year revenue growth (%)
+    pub revenue_growth: Option<Decimal>,
     /// Debt-to-Equity ratio
     pub debt_to_equity: Option<Decimal>,
     /// Current ratio
     pub current_ratio: Option<Decimal>,
+    /// Days since the financials were published
+    #[serde(default)]
+    pub financials_age_days: Option<i64>,
 }
 
 /// Fundamental score result with breakdown
@@ -76,6 +96,46 @@ pub struct FundamentalScoreBreakdown {
     pub signals: Vec<String>,
     /// Assessment summary
     pub assessment: FundamentalAssessment,
+    /// Whether returns beat the cost of capital, when ROIC and WACC are known
+    #[serde(default)]
+    pub economic_profile: Option<EconomicProfile>,
+    /// Sector profile used for weights and metric selection
+    #[serde(default)]
+    pub sector_profile: Option<String>,
+    /// Inputs past their freshness threshold, whose sub-scores were decayed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stale_inputs: Vec<StaleInput>,
+}
+
+/// Economic returns against the cost of capital
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EconomicProfile {
+    /// ROIC at least 5 points above WACC
+    Compounder,
+    /// ROIC above WACC
+    ValueCreator,
+    /// Strong ROE while ROIC is below WACC: returns come from leverage
+    LeveragedReturns,
+    /// ROIC below WACC
+    ValueDestroyer,
+}
+
+impl EconomicProfile {
+    /// Classify from ROIC, WACC and ROE (all %)
+    #[must_use]
+    pub fn classify(roic: Decimal, wacc: Decimal, roe: Option<Decimal>) -> Self {
+        let spread = roic - wacc;
+        if spread >= dec!(5) {
+            Self::Compounder
+        } else if spread > Decimal::ZERO {
+            Self::ValueCreator
+        } else if roe.is_some_and(|roe| roe >= dec!(15)) {
+            Self::LeveragedReturns
+        } else {
+            Self::ValueDestroyer
+        }
+    }
 }
 
 /// Assessment category
@@ -87,24 +147,93 @@ pub enum FundamentalAssessment {
     Insufficient,
 }
 
+/// Free cash flow yield band
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FcfYieldBand {
+    /// 10% and above
+    VeryHigh,
+    /// 7% to 10%
+    High,
+    /// 5% to 7%
+    Attractive,
+    /// 3% to 5%
+    Fair,
+    /// 0% to 3%
+    Low,
+    /// Burning cash
+    Negative,
+}
+
+impl FcfYieldBand {
+    /// Band for a yield in percent
+    #[must_use]
+    pub fn from_yield(fcf_yield: Decimal) -> Self {
+        if fcf_yield >= dec!(10) {
+            Self::VeryHigh
+        } else if fcf_yield >= dec!(7) {
+            Self::High
+        } else if fcf_yield >= dec!(5) {
+            Self::Attractive
+        } else if fcf_yield >= dec!(3) {
+            Self::Fair
+        } else if fcf_yield >= Decimal::ZERO {
+            Self::Low
+        } else {
+            Self::Negative
+        }
+    }
+
+    /// Valuation sub-score contribution (0-100)
+    #[must_use]
+    pub fn score(&self) -> Decimal {
+        match self {
+            Self::VeryHigh => dec!(95),
+            Self::High => dec!(85),
+            Self::Attractive => dec!(70),
+            Self::Fair => dec!(55),
+            Self::Low => dec!(40),
+            Self::Negative => dec!(15),
+        }
+    }
+}
+
 /// Fundamental Score Engine
 pub struct FundamentalScoreEngine {
     weights: FundamentalWeights,
+    metrics: Vec<FundamentalMetric>,
+    profile: Option<String>,
 }
 
 impl FundamentalScoreEngine {
     /// Create new engine with default weights
     #[must_use]
     pub fn new() -> Self {
-        Self {
-            weights: FundamentalWeights::default(),
-        }
+        Self::with_weights(FundamentalWeights::default())
     }
 
     /// Create engine with custom weights
     #[must_use]
     pub fn with_weights(weights: FundamentalWeights) -> Self {
-        Self { weights }
+        Self {
+            weights,
+            metrics: FundamentalMetric::ALL.to_vec(),
+            profile: None,
+        }
+    }
+
+    /// Create engine with a sector profile's weights and metric set
+    #[must_use]
+    pub fn with_profile(profile: &SectorProfile) -> Self {
+        Self {
+            weights: profile.weights.clone(),
+            metrics: profile.metrics.clone(),
+            profile: Some(profile.name.clone()),
+        }
+    }
+
+    fn scores(&self, metric: FundamentalMetric) -> bool {
+        self.metrics.contains(&metric)
     }
 
     /// Calculate fundamental score from input data
@@ -113,10 +242,33 @@ impl FundamentalScoreEngine {
         let mut signals = Vec::new();
 
         // Calculate sub-scores
-        let valuation_score = self.calculate_valuation_score(input, &mut signals);
-        let dcf_score = self.calculate_dcf_score(input, &mut signals);
-        let quality_score = self.calculate_quality_score(input, &mut signals);
-        let health_score = self.calculate_health_score(input, &mut signals);
+        let mut valuation_score = self.calculate_valuation_score(input, &mut signals);
+        let mut dcf_score = self.calculate_dcf_score(input, &mut signals);
+        let mut quality_score = self.calculate_quality_score(input, &mut signals);
+        let economic_profile = input
+            .roic
+            .zip(input.wacc)
+            .filter(|_| self.scores(FundamentalMetric::RoicSpread))
+            .map(|(roic, wacc)| EconomicProfile::classify(roic, wacc, input.roe));
+        let mut health_score = self.calculate_health_score(input, &mut signals);
+
+        // Every sub-score rests on the financials, so all of them decay
+        let mut stale_inputs = Vec::new();
+        if let Some(stale) = input
+            .financials_age_days
+            .and_then(|age| StalenessPolicy::FINANCIALS.check("financials", age))
+        {
+            for score in [
+                &mut valuation_score,
+                &mut dcf_score,
+                &mut quality_score,
+                &mut health_score,
+            ] {
+                *score = stale.apply(*score);
+            }
+            signals.push(stale.signal("Financials"));
+            stale_inputs.push(stale);
+        }
 
         // Weighted total
         let total_score = (valuation_score * self.weights.valuation
@@ -144,6 +296,9 @@ impl FundamentalScoreEngine {
             health_score: health_score.round_dp(2),
             signals,
             assessment,
+            economic_profile,
+            sector_profile: self.profile.clone(),
+            stale_inputs,
         }
     }
 
@@ -157,7 +312,11 @@ impl FundamentalScoreEngine {
         let mut count = 0;
 
         // P/E scoring (lower is better, relative to sector)
-        if let (Some(pe), Some(sector_pe)) = (input.pe_ratio, input.sector_pe) {
+        if let (Some(pe), Some(sector_pe), true) = (
+            input.pe_ratio,
+            input.sector_pe,
+            self.scores(FundamentalMetric::PeRatio),
+        ) {
             let pe_score = if pe <= Decimal::ZERO {
                 dec!(0) // Negative earnings = 0
             } else if sector_pe <= Decimal::ZERO {
@@ -186,7 +345,11 @@ impl FundamentalScoreEngine {
         }
 
         // P/B scoring
-        if let (Some(pb), Some(sector_pb)) = (input.pb_ratio, input.sector_pb) {
+        if let (Some(pb), Some(sector_pb), true) = (
+            input.pb_ratio,
+            input.sector_pb,
+            self.scores(FundamentalMetric::PbRatio),
+        ) {
             let pb_score = if pb <= Decimal::ZERO {
                 dec!(0)
             } else if sector_pb <= Decimal::ZERO {
@@ -211,7 +374,11 @@ impl FundamentalScoreEngine {
         }
 
         // EV/EBITDA scoring
-        if let (Some(ev), Some(sector_ev)) = (input.ev_ebitda, input.sector_ev_ebitda) {
+        if let (Some(ev), Some(sector_ev), true) = (
+            input.ev_ebitda,
+            input.sector_ev_ebitda,
+            self.scores(FundamentalMetric::EvEbitda),
+        ) {
             let ev_score = if ev <= Decimal::ZERO {
                 dec!(0)
             } else if sector_ev <= Decimal::ZERO {
@@ -235,6 +402,25 @@ impl FundamentalScoreEngine {
             count += 1;
         }
 
+        // FCF yield scoring (absolute bands, no sector comparison needed)
+        if let Some(fcf_yield) = input
+            .fcf_yield
+            .filter(|_| self.scores(FundamentalMetric::FcfYield))
+        {
+            let band = FcfYieldBand::from_yield(fcf_yield);
+            match band {
+                FcfYieldBand::VeryHigh | FcfYieldBand::High => {
+                    signals.push(format!("High FCF yield ({fcf_yield}%)"));
+                }
+                FcfYieldBand::Negative => {
+                    signals.push("Negative free cash flow".to_string());
+                }
+                _ => {}
+            }
+            total_score += band.score();
+            count += 1;
+        }
+
         if count > 0 {
             (total_score / Decimal::from(count)).round_dp(2)
         } else {
@@ -244,7 +430,10 @@ impl FundamentalScoreEngine {
 
     /// Calculate DCF-based score
     fn calculate_dcf_score(&self, input: &FundamentalInput, signals: &mut Vec<String>) -> Decimal {
-        match input.dcf_margin {
+        match input
+            .dcf_margin
+            .filter(|_| self.scores(FundamentalMetric::DcfMargin))
+        {
             Some(margin) => {
                 if margin >= dec!(30) {
                     signals.push(format!("Strong margin of safety ({margin}%)"));
@@ -267,7 +456,7 @@ impl FundamentalScoreEngine {
         }
     }
 
-    /// Calculate quality score (ROE, ROA, Profit Margin)
+    /// Calculate quality score (ROE, ROA, Profit Margin, ROIC spread, growth)
     fn calculate_quality_score(
         &self,
         input: &FundamentalInput,
@@ -277,7 +466,7 @@ impl FundamentalScoreEngine {
         let mut count = 0;
 
         // ROE scoring (higher is better)
-        if let Some(roe) = input.roe {
+        if let Some(roe) = input.roe.filter(|_| self.scores(FundamentalMetric::Roe)) {
             let roe_score = if roe >= dec!(25) {
                 signals.push(format!("Excellent ROE ({roe}%)"));
                 dec!(100)
@@ -296,7 +485,7 @@ impl FundamentalScoreEngine {
         }
 
         // ROA scoring
-        if let Some(roa) = input.roa {
+        if let Some(roa) = input.roa.filter(|_| self.scores(FundamentalMetric::Roa)) {
             let roa_score = if roa >= dec!(15) {
                 dec!(100)
             } else if roa >= dec!(10) {
@@ -313,7 +502,10 @@ impl FundamentalScoreEngine {
         }
 
         // Profit margin scoring
-        if let Some(pm) = input.profit_margin {
+        if let Some(pm) = input
+            .profit_margin
+            .filter(|_| self.scores(FundamentalMetric::ProfitMargin))
+        {
             let pm_score = if pm >= dec!(20) {
                 signals.push(format!("High profit margin ({pm}%)"));
                 dec!(100)
@@ -331,6 +523,63 @@ impl FundamentalScoreEngine {
             count += 1;
         }
 
+        // ROIC spread over WACC scoring (value creation, unaffected by leverage)
+        if let (Some(roic), Some(wacc), true) = (
+            input.roic,
+            input.wacc,
+            self.scores(FundamentalMetric::RoicSpread),
+        ) {
+            let spread = roic - wacc;
+            let spread_score = if spread >= dec!(10) {
+                dec!(100)
+            } else if spread >= dec!(5) {
+                dec!(85)
+            } else if spread >= dec!(0) {
+                dec!(65)
+            } else if spread >= dec!(-5) {
+                dec!(40)
+            } else {
+                dec!(20)
+            };
+
+            match EconomicProfile::classify(roic, wacc, input.roe) {
+                EconomicProfile::Compounder => signals.push(format!(
+                    "Compounder: ROIC ({roic}%) exceeds WACC ({wacc}%) by {spread} pts"
+                )),
+                EconomicProfile::LeveragedReturns => signals.push(format!(
+                    "ROE driven by leverage: ROIC ({roic}%) below WACC ({wacc}%)"
+                )),
+                EconomicProfile::ValueDestroyer => {
+                    signals.push(format!("ROIC ({roic}%) below cost of capital ({wacc}%)"))
+                }
+                EconomicProfile::ValueCreator => {}
+            }
+            total_score += spread_score;
+            count += 1;
+        }
+
+        // Revenue growth scoring
+        if let Some(growth) = input
+            .revenue_growth
+            .filter(|_| self.scores(FundamentalMetric::RevenueGrowth))
+        {
+            let growth_score = if growth >= dec!(25) {
+                signals.push(format!("Strong revenue growth ({growth}%)"));
+                dec!(100)
+            } else if growth >= dec!(15) {
+                dec!(85)
+            } else if growth >= dec!(5) {
+                dec!(65)
+            } else if growth >= dec!(0) {
+                dec!(45)
+            } else {
+                signals.push(format!("Revenue declining ({growth}%)"));
+                dec!(20)
+            };
+            total_score += growth_score;
+            count += 1;
+        }
+
         if count > 0 {
             (total_score / Decimal::from(count)).round_dp(2)
         } else {
@@ -338,7 +587,7 @@ impl FundamentalScoreEngine {
         }
     }
 
-    /// Calculate financial health score
+    /// Calculate financial health score (D/E, Current Ratio)
     fn calculate_health_score(
         &self,
         input: &FundamentalInput,
@@ -348,7 +597,10 @@ impl FundamentalScoreEngine {
         let mut count = 0;
 
         // Debt-to-Equity scoring (lower is generally better)
-        if let Some(de) = input.debt_to_equity {
+        if let Some(de) = input
+            .debt_to_equity
+            .filter(|_| self.scores(FundamentalMetric::DebtToEquity))
+        {
             let de_score = if de <= dec!(0.3) {
                 signals.push("Very low leverage".to_string());
                 dec!(100)
@@ -369,7 +621,10 @@ impl FundamentalScoreEngine {
         }
 
         // Current ratio scoring (higher is better, but too high may indicate inefficiency)
-        if let Some(cr) = input.current_ratio {
+        if let Some(cr) = input
+            .current_ratio
+            .filter(|_| self.scores(FundamentalMetric::CurrentRatio))
+        {
             let cr_score = if cr >= dec!(1.5) && cr <= dec!(3.0) {
                 dec!(90)
             } else if cr >= dec!(1.2) && cr < dec!(1.5) {
@@ -416,15 +671,49 @@ mod tests {
             sector_pb: Some(dec!(2)),
             ev_ebitda: Some(dec!(5)),
             sector_ev_ebitda: Some(dec!(10)),
+            fcf_yield: None,
             dcf_margin: Some(dec!(25)),
             roe: Some(dec!(20)),
             roa: Some(dec!(12)),
             profit_margin: Some(dec!(15)),
+            roic: None,
+            wacc: None,
+            revenue_growth: None,
             debt_to_equity: Some(dec!(0.4)),
             current_ratio: Some(dec!(1.8)),
+            financials_age_days: None,
         }
     }
 
+    #[test]
+    fn test_stale_financials_decay_toward_neutral() {
+        let engine = FundamentalScoreEngine::new();
+        let fresh = engine.calculate(&FundamentalInput {
+            financials_age_days: Some(90),
+            ..test_input()
+        });
+        assert!(fresh.stale_inputs.is_empty());
+
+        let stale = engine.calculate(&FundamentalInput {
+            financials_age_days: Some(300),
+            ..test_input()
+        });
+        assert_eq!(stale.stale_inputs.len(), 1);
+        assert!(stale.total_score < fresh.total_score);
+        assert!(stale.total_score > dec!(50));
+        assert!(stale
+            .signals
+            .iter()
+            .any(|s| s.starts_with("Financials 300 days old")));
+
+        let expired = engine.calculate(&FundamentalInput {
+            financials_age_days: Some(400),
+            ..test_input()
+        });
+        assert_eq!(expired.total_score, dec!(50));
+        assert_eq!(expired.valuation_score, dec!(50));
+    }
+
     #[test]
     fn test_default_weights_sum_to_one() {
         let weights = FundamentalWeights::default();
@@ -453,12 +742,17 @@ mod tests {
             sector_pb: Some(dec!(2)),
             ev_ebitda: Some(dec!(20)),
             sector_ev_ebitda: Some(dec!(10)),
+            fcf_yield: None,
             dcf_margin: Some(dec!(-20)),
             roe: Some(dec!(3)),
             roa: Some(dec!(1)),
             profit_margin: Some(dec!(2)),
+            roic: None,
+            wacc: None,
+            revenue_growth: None,
             debt_to_equity: Some(dec!(2.5)),
             current_ratio: Some(dec!(0.7)),
+            financials_age_days: None,
         };
         let result = engine.calculate(&input);
 
@@ -505,12 +799,17 @@ mod tests {
             sector_pb: Some(dec!(3)),
             ev_ebitda: Some(dec!(4)),
             sector_ev_ebitda: Some(dec!(15)),
+            fcf_yield: None,
             dcf_margin: Some(dec!(50)),
             roe: Some(dec!(30)),
             roa: Some(dec!(20)),
             profit_margin: Some(dec!(25)),
+            roic: None,
+            wacc: None,
+            revenue_growth: None,
             debt_to_equity: Some(dec!(0.1)),
             current_ratio: Some(dec!(2.0)),
+            financials_age_days: None,
         };
 
         let result = engine.calculate(&strong_input);
@@ -525,12 +824,17 @@ mod tests {
             sector_pb: Some(dec!(2)),
             ev_ebitda: Some(dec!(30)),
             sector_ev_ebitda: Some(dec!(10)),
+            fcf_yield: None,
             dcf_margin: Some(dec!(-50)),
             roe: Some(dec!(1)),
             roa: Some(dec!(0)),
             profit_margin: Some(dec!(-5)),
+            roic: None,
+            wacc: None,
+            revenue_growth: None,
             debt_to_equity: Some(dec!(5)),
             current_ratio: Some(dec!(0.5)),
+            financials_age_days: None,
         };
 
         let result = engine.calculate(&weak_input);
@@ -564,6 +868,22 @@ mod tests {
             .any(|s| s.contains("below book value")));
     }
 
+    #[test]
+    fn test_fcf_yield_bands() {
+        assert_eq!(FcfYieldBand::from_yield(dec!(12)), FcfYieldBand::VeryHigh);
+        assert_eq!(FcfYieldBand::from_yield(dec!(5)), FcfYieldBand::Attractive);
+        assert_eq!(FcfYieldBand::from_yield(dec!(-1)), FcfYieldBand::Negative);
+
+        let engine = FundamentalScoreEngine::new();
+        let input = FundamentalInput {
+            fcf_yield: Some(dec!(8)),
+            ..Default::default()
+        };
+        let result = engine.calculate(&input);
+        assert_eq!(result.valuation_score, dec!(85));
+        assert!(result.signals.iter().any(|s| s.contains("FCF yield")));
+    }
+
     #[test]
     fn test_dcf_signals() {
         let engine = FundamentalScoreEngine::new();
@@ -612,6 +932,38 @@ mod tests {
             .any(|s| s.contains("High profit margin")));
     }
 
+    #[test]
+    fn test_economic_profile_separates_leverage_from_value_creation() {
+        let engine = FundamentalScoreEngine::new();
+
+        let compounder = engine.calculate(&FundamentalInput {
+            roe: Some(dec!(22)),
+            roic: Some(dec!(20)),
+            wacc: Some(dec!(12)),
+            ..Default::default()
+        });
+        assert_eq!(
+            compounder.economic_profile,
+            Some(EconomicProfile::Compounder)
+        );
+        assert!(compounder.signals.iter().any(|s| s.contains("Compounder")));
+
+        let leveraged = engine.calculate(&FundamentalInput {
+            roe: Some(dec!(22)),
+            roic: Some(dec!(8)),
+            wacc: Some(dec!(12)),
+            ..Default::default()
+        });
+        assert_eq!(
+            leveraged.economic_profile,
+            Some(EconomicProfile::LeveragedReturns)
+        );
+        assert!(leveraged.quality_score < compounder.quality_score);
+
+        let unknown = engine.calculate(&FundamentalInput::default());
+        assert_eq!(unknown.economic_profile, None);
+    }
+
     #[test]
     fn test_health_signals() {
         let engine = FundamentalScoreEngine::new();
@@ -669,12 +1021,17 @@ mod tests {
             sector_pb: Some(dec!(2)),
             ev_ebitda: Some(dec!(10)),
             sector_ev_ebitda: Some(dec!(10)),
+            fcf_yield: None,
             dcf_margin: Some(dec!(5)),
             roe: Some(dec!(12)),
             roa: Some(dec!(7)),
             profit_margin: Some(dec!(8)),
+            roic: None,
+            wacc: None,
+            revenue_growth: None,
             debt_to_equity: Some(dec!(0.8)),
             current_ratio: Some(dec!(1.3)),
+            financials_age_days: None,
         };
         let result = engine.calculate(&moderate_input);
         assert_eq!(result.assessment, FundamentalAssessment::Moderate);
@@ -687,12 +1044,17 @@ mod tests {
             sector_pb: Some(dec!(2)),
             ev_ebitda: Some(dec!(15)),
             sector_ev_ebitda: Some(dec!(10)),
+            fcf_yield: None,
             dcf_margin: Some(dec!(-5)),
             roe: Some(dec!(6)),
             roa: Some(dec!(3)),
             profit_margin: Some(dec!(4)),
+            roic: None,
+            wacc: None,
+            revenue_growth: None,
             debt_to_equity: Some(dec!(1.8)),
             current_ratio: Some(dec!(0.9)),
+            financials_age_days: None,
         };
         let result = engine.calculate(&weak_input);
         assert_eq!(result.assessment, FundamentalAssessment::Weak);
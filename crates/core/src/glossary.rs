@@ -0,0 +1,774 @@
+//! Glossary of signals and indicators
+//!
+//! Structured signals carry a `glossary_key` that resolves to an entry here,
+//! so clients can show explanations without hard-coding the content.
+
+use serde::Serialize;
+
+/// Grouping of glossary entries
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GlossaryCategory {
+    Indicator,
+    PriceAction,
+    Wyckoff,
+    OrderFlow,
+    BrokerFlow,
+    Scoring,
+}
+
+impl GlossaryCategory {
+    pub fn from_str_opt(s: &str) -> Option<Self> {
+        match s {
+            "indicator" => Some(Self::Indicator),
+            "price_action" => Some(Self::PriceAction),
+            "wyckoff" => Some(Self::Wyckoff),
+            "order_flow" => Some(Self::OrderFlow),
+            "broker_flow" => Some(Self::BrokerFlow),
+            "scoring" => Some(Self::Scoring),
+            _ => None,
+        }
+    }
+}
+
+/// Explanation content for one signal or indicator
+#[derive(Debug, Clone, Serialize)]
+pub struct GlossaryEntry {
+    pub key: &'static str,
+    pub term: &'static str,
+    pub category: GlossaryCategory,
+    /// One-line tooltip text
+    pub summary: &'static str,
+    pub explanation: &'static str,
+    /// How to read the signal when it fires
+    pub interpretation: &'static str,
+    pub related: &'static [&'static str],
+}
+
+macro_rules! entry {
+    ($key:literal, $term:literal, $category:ident, $summary:literal, $explanation:literal, $interpretation:literal, [$($related:literal),*]) => {
+        GlossaryEntry {
+            key: $key,
+            term: $term,
+            category: GlossaryCategory::$category,
+            summary: $summary,
+            explanation: $explanation,
+            interpretation: $interpretation,
+            related: &[$($related),*],
+        }
+    };
+}
+
+static GLOSSARY: &[GlossaryEntry] = &[
+    // Indicators
+    entry!(
+        "rsi",
+        "Relative Strength Index (RSI)",
+        Indicator,
+        "Momentum oscillator from 0 to 100 comparing recent gains with losses.",
+        "RSI measures the speed of recent price changes over 14 periods. Readings near 100 mean gains have dominated; readings near 0 mean losses have dominated.",
+        "Above 70 is usually treated as overbought and below 30 as oversold. In strong trends RSI can stay extreme for a long time.",
+        ["rsi_overbought", "rsi_oversold", "rsi_divergence"]
+    ),
+    entry!(
+        "rsi_overbought",
+        "RSI Overbought",
+        Indicator,
+        "RSI rose above the overbought threshold.",
+        "Buying pressure has been unusually strong relative to selling pressure over the lookback window.",
+        "A warning that the move may be stretched, not a sell signal by itself. Look for weakening volume or a bearish divergence.",
+        ["rsi", "rsi_oversold"]
+    ),
+    entry!(
+        "rsi_oversold",
+        "RSI Oversold",
+        Indicator,
+        "RSI fell below the oversold threshold.",
+        "Selling pressure has been unusually strong relative to buying pressure over the lookback window.",
+        "Can precede a bounce, especially when price is at support or brokers are accumulating. Weak stocks can remain oversold.",
+        ["rsi", "rsi_overbought", "wyckoff_spring"]
+    ),
+    entry!(
+        "rsi_divergence",
+        "RSI Divergence",
+        Indicator,
+        "Price and RSI move in opposite directions.",
+        "A bullish divergence is a lower price low with a higher RSI low; a bearish divergence is a higher price high with a lower RSI high.",
+        "Suggests the current trend is losing momentum. Confirm with a break of structure before acting.",
+        ["rsi", "bullish_divergence", "bearish_divergence"]
+    ),
+    entry!(
+        "bullish_divergence",
+        "Bullish Divergence",
+        Indicator,
+        "At the latest swing low, price and an oscillator (RSI, MACD histogram or OBV) disagree.",
+        "Regular: price made a lower low while the oscillator made a higher low. Hidden: price made a higher low while the oscillator made a lower low. Swings are confirmed a few bars after they form.",
+        "A regular divergence warns that a decline is losing momentum; a hidden one suggests an uptrend pullback is ending. Confidence grows with how far price and oscillator moved apart.",
+        ["rsi_divergence", "bearish_divergence", "macd"]
+    ),
+    entry!(
+        "bearish_divergence",
+        "Bearish Divergence",
+        Indicator,
+        "At the latest swing high, price and an oscillator (RSI, MACD histogram or OBV) disagree.",
+        "Regular: price made a higher high while the oscillator made a lower high. Hidden: price made a lower high while the oscillator made a higher high. Swings are confirmed a few bars after they form.",
+        "A regular divergence warns that a rally is losing momentum; a hidden one suggests a downtrend bounce is ending.",
+        ["rsi_divergence", "bullish_divergence", "macd"]
+    ),
+    entry!(
+        "macd",
+        "MACD",
+        Indicator,
+        "Trend-momentum indicator built from the 12- and 26-period EMAs.",
+        "The MACD line is the difference between a fast and a slow EMA; the signal line is a 9-period EMA of MACD, and the histogram is their difference.",
+        "MACD above its signal line and rising indicates strengthening upside momentum.",
+        ["macd_crossover", "ema"]
+    ),
+    entry!(
+        "macd_crossover",
+        "MACD Crossover",
+        Indicator,
+        "MACD line crossed its signal line.",
+        "A bullish crossover happens when MACD crosses above the signal line; a bearish crossover when it crosses below.",
+        "Crossovers above the zero line carry more weight for bulls, and below zero for bears.",
+        ["macd"]
+    ),
+    entry!(
+        "ema",
+        "Exponential Moving Average (EMA)",
+        Indicator,
+        "Moving average that weights recent prices more heavily.",
+        "EMAs react faster than simple averages. The app uses short and long EMAs to describe trend direction.",
+        "Price above a rising EMA is a sign of an uptrend.",
+        ["golden_cross", "death_cross"]
+    ),
+    entry!(
+        "golden_cross",
+        "Golden Cross",
+        Indicator,
+        "Short-term EMA crossed above the long-term EMA.",
+        "Signals that recent prices have lifted above the longer-term average trend.",
+        "Often marks the start of a sustained uptrend but lags the actual bottom.",
+        ["death_cross", "ema"]
+    ),
+    entry!(
+        "death_cross",
+        "Death Cross",
+        Indicator,
+        "Short-term EMA crossed below the long-term EMA.",
+        "Signals that recent prices have fallen below the longer-term average trend.",
+        "Often confirms a downtrend that has already started.",
+        ["golden_cross", "ema"]
+    ),
+    entry!(
+        "bollinger_bands",
+        "Bollinger Bands",
+        Indicator,
+        "Volatility bands two standard deviations around a 20-period average.",
+        "The bands widen when volatility rises and narrow when it falls. Roughly 95% of closes fall inside the bands.",
+        "Touching the upper band in an uptrend is normal; closes outside the bands show an unusually strong move.",
+        ["bollinger_squeeze", "atr"]
+    ),
+    entry!(
+        "bollinger_squeeze",
+        "Bollinger Squeeze",
+        Indicator,
+        "Bollinger bandwidth contracted to an unusually low level.",
+        "Volatility is compressed, which tends to be followed by a volatility expansion.",
+        "The squeeze does not tell direction; wait for the breakout and check volume.",
+        ["bollinger_bands", "price_breakout"]
+    ),
+    entry!(
+        "atr",
+        "Average True Range (ATR)",
+        Indicator,
+        "Average daily trading range, including gaps.",
+        "ATR smooths the true range (the largest of high-low and the gaps from the previous close) over 14 periods using Wilder's method.",
+        "Used to size stops and positions: a stop two ATRs away is less likely to be hit by normal noise.",
+        ["bollinger_bands"]
+    ),
+    entry!(
+        "ichimoku_cloud",
+        "Ichimoku Cloud",
+        Indicator,
+        "Trend system whose cloud marks dynamic support and resistance.",
+        "The cloud is drawn between two leading spans computed from 26- and 52-period midpoints.",
+        "Price above the cloud is bullish, below is bearish, and inside is undecided.",
+        ["support_resistance"]
+    ),
+    entry!(
+        "fibonacci_retracement",
+        "Fibonacci Retracement",
+        Indicator,
+        "Pullback levels at 23.6%, 38.2%, 50%, 61.8% and 78.6% of a swing.",
+        "Levels are drawn between a swing low and swing high; traders watch them for pullbacks to stall.",
+        "Confluence with other support raises the significance of a level.",
+        ["support_resistance"]
+    ),
+    entry!(
+        "volume_spike",
+        "Volume Spike (RVOL)",
+        Indicator,
+        "Volume far above its recent average.",
+        "Relative volume (RVOL) divides today's volume by the average volume; 2x means twice the usual activity.",
+        "Spikes confirm breakouts and climaxes. A spike without price progress can indicate absorption.",
+        ["price_breakout", "wyckoff_selling_climax"]
+    ),
+    entry!(
+        "vwap",
+        "VWAP",
+        Indicator,
+        "Average price weighted by volume since the anchor, usually the session open.",
+        "Each bar's typical price (high + low + close) / 3 is weighted by its volume. Intraday VWAP restarts every session; the bands sit one and two volume-weighted standard deviations around it.",
+        "Institutions benchmark fills against VWAP, so price tends to gravitate back to it; a close beyond the outer band is stretched.",
+        ["vwap_reclaim", "vwap_loss", "volume_spike"]
+    ),
+    // Price action
+    entry!(
+        "support_resistance",
+        "Support and Resistance",
+        PriceAction,
+        "Price zones where buying or selling has repeatedly appeared.",
+        "Support is where declines have stalled; resistance is where rallies have stalled. Computed levels come from recent swing points.",
+        "A broken resistance often becomes support and vice versa.",
+        ["price_breakout", "price_breakdown"]
+    ),
+    entry!(
+        "price_breakout",
+        "Breakout",
+        PriceAction,
+        "Price closed above resistance.",
+        "Sellers at the resistance level have been absorbed and price moved into a new range.",
+        "More reliable with high relative volume; a quick return below the level is a failed breakout.",
+        ["support_resistance", "volume_spike", "wyckoff_upthrust"]
+    ),
+    entry!(
+        "price_breakdown",
+        "Breakdown",
+        PriceAction,
+        "Price closed below support.",
+        "Buyers at the support level gave way and price moved into a lower range.",
+        "A quick recovery above the level may be a Spring rather than a true breakdown.",
+        ["support_resistance", "wyckoff_spring"]
+    ),
+    entry!(
+        "vwap_reclaim",
+        "VWAP Reclaim",
+        PriceAction,
+        "Intraday price closed back above session VWAP on heavy volume.",
+        "Buyers absorbed the supply that held price under the session's average cost, and the volume shows real participation.",
+        "Day traders use the reclaim as an entry with a stop back under VWAP; a quick return below it is a failed reclaim.",
+        ["vwap", "vwap_loss"]
+    ),
+    entry!(
+        "vwap_loss",
+        "VWAP Loss",
+        PriceAction,
+        "Intraday price closed below session VWAP on heavy volume.",
+        "Sellers pushed price under the session's average cost, leaving most of today's buyers underwater.",
+        "Often a cue to trim intraday longs; VWAP tends to act as resistance until it is reclaimed.",
+        ["vwap", "vwap_reclaim"]
+    ),
+    entry!(
+        "user_price_level",
+        "Your Price Level",
+        PriceAction,
+        "A horizontal level you drew on the chart.",
+        "Custom levels mark zones you care about, such as planned entries, support you identified or profit targets.",
+        "An alert fires when the close crosses the level in either direction.",
+        ["support_resistance"]
+    ),
+    entry!(
+        "alert_rule",
+        "Your Alert Rule",
+        Scoring,
+        "A condition you wrote over indicators and scores.",
+        "Alert rules combine metrics such as RSI, scores and foreign flow with AND / OR, for example 'rsi < 30 AND composite_score > 70'.",
+        "An alert fires when every part of the condition holds on the latest data; it does not repeat while the condition stays true within the cooldown.",
+        []
+    ),
+    entry!(
+        "earnings_upcoming",
+        "Financial Report Due",
+        Scoring,
+        "The company is expected to publish a financial report soon.",
+        "Issuers publish quarterly statements within a month of the quarter end and audited annual statements within three months of the year end. The expected date is when the company filed the same report last year, or the deadline without history.",
+        "Prices can gap on results. Review position size and stops before the release; fundamental scores update once the report is in.",
+        ["composite_score"]
+    ),
+    // Wyckoff
+    entry!(
+        "wyckoff",
+        "Wyckoff Method",
+        Wyckoff,
+        "Framework reading supply and demand through price and volume cycles.",
+        "Markets move through accumulation, markup, distribution and markdown, driven by large operators building and unloading positions.",
+        "Identify the current phase, then look for the events that confirm the next one.",
+        ["wyckoff_accumulation", "wyckoff_distribution"]
+    ),
+    entry!(
+        "wyckoff_accumulation",
+        "Wyckoff Accumulation",
+        Wyckoff,
+        "Range-bound phase where large players absorb supply after a decline.",
+        "Price moves sideways with declining volatility while stronger hands buy from sellers.",
+        "Watch for a Spring or Sign of Strength to signal the transition to markup.",
+        ["wyckoff_spring", "wyckoff_sign_of_strength", "wyckoff_markup"]
+    ),
+    entry!(
+        "wyckoff_markup",
+        "Wyckoff Markup",
+        Wyckoff,
+        "Uptrend that follows accumulation.",
+        "Demand outweighs supply and price trends higher with pullbacks on lighter volume.",
+        "Pullbacks to former resistance are potential entries.",
+        ["wyckoff_accumulation"]
+    ),
+    entry!(
+        "wyckoff_distribution",
+        "Wyckoff Distribution",
+        Wyckoff,
+        "Range-bound phase at highs where large players sell into demand.",
+        "Price stalls after an advance while supply is released to late buyers.",
+        "Watch for an Upthrust or Sign of Weakness to signal the transition to markdown.",
+        ["wyckoff_upthrust", "wyckoff_sign_of_weakness", "wyckoff_markdown"]
+    ),
+    entry!(
+        "wyckoff_markdown",
+        "Wyckoff Markdown",
+        Wyckoff,
+        "Downtrend that follows distribution.",
+        "Supply outweighs demand and price trends lower with rallies on lighter volume.",
+        "Avoid buying rallies until a new accumulation range forms.",
+        ["wyckoff_distribution"]
+    ),
+    entry!(
+        "wyckoff_preliminary_support",
+        "Preliminary Support (PS)",
+        Wyckoff,
+        "First significant buying after a prolonged decline.",
+        "Volume increases and the spread narrows as buyers start to step in.",
+        "An early warning that the downtrend may be ending.",
+        ["wyckoff_selling_climax"]
+    ),
+    entry!(
+        "wyckoff_selling_climax",
+        "Selling Climax (SC)",
+        Wyckoff,
+        "Panic selling on very high volume that often marks the bottom.",
+        "Weak holders capitulate and large players absorb the supply.",
+        "The climax low frequently becomes the floor of the accumulation range.",
+        ["wyckoff_automatic_rally", "volume_spike"]
+    ),
+    entry!(
+        "wyckoff_automatic_rally",
+        "Automatic Rally (AR)",
+        Wyckoff,
+        "Bounce after a selling climax as selling pressure exhausts.",
+        "Short covering and bargain buying lift price quickly.",
+        "The rally high typically defines the top of the accumulation range.",
+        ["wyckoff_selling_climax", "wyckoff_secondary_test"]
+    ),
+    entry!(
+        "wyckoff_secondary_test",
+        "Secondary Test (ST)",
+        Wyckoff,
+        "Price revisits the selling climax area on lower volume.",
+        "Confirms that supply has diminished since the climax.",
+        "Lower volume on the test is constructive.",
+        ["wyckoff_selling_climax"]
+    ),
+    entry!(
+        "wyckoff_spring",
+        "Spring",
+        Wyckoff,
+        "False breakdown below support that quickly reverses.",
+        "Price dips below the trading range low, triggering stops, then closes back inside the range as large players absorb the supply.",
+        "One of the strongest bullish Wyckoff signals, especially on low volume during the dip.",
+        ["wyckoff_accumulation", "wyckoff_upthrust", "price_breakdown"]
+    ),
+    entry!(
+        "wyckoff_sign_of_strength",
+        "Sign of Strength (SOS)",
+        Wyckoff,
+        "Rally above range resistance on expanding volume.",
+        "Demand has overcome supply and price leaves the accumulation range.",
+        "Confirms accumulation; the next pullback is a Last Point of Support.",
+        ["wyckoff_last_point_of_support", "price_breakout"]
+    ),
+    entry!(
+        "wyckoff_last_point_of_support",
+        "Last Point of Support (LPS)",
+        Wyckoff,
+        "Final pullback on light volume before markup.",
+        "Price retraces toward former resistance without heavy selling.",
+        "Often the lowest-risk entry in the accumulation cycle.",
+        ["wyckoff_sign_of_strength", "wyckoff_markup"]
+    ),
+    entry!(
+        "wyckoff_preliminary_supply",
+        "Preliminary Supply (PSY)",
+        Wyckoff,
+        "First significant selling after a prolonged advance.",
+        "Large holders begin to sell and volume expands.",
+        "An early warning that the uptrend may be ending.",
+        ["wyckoff_buying_climax"]
+    ),
+    entry!(
+        "wyckoff_buying_climax",
+        "Buying Climax (BC)",
+        Wyckoff,
+        "Euphoric buying on very high volume that often marks the top.",
+        "Late buyers pile in while large players sell into the demand.",
+        "The climax high frequently becomes the ceiling of the distribution range.",
+        ["wyckoff_automatic_reaction", "volume_spike"]
+    ),
+    entry!(
+        "wyckoff_automatic_reaction",
+        "Automatic Reaction (AR)",
+        Wyckoff,
+        "Drop after a buying climax as demand exhausts.",
+        "Buying dries up and price falls quickly.",
+        "The reaction low typically defines the bottom of the distribution range.",
+        ["wyckoff_buying_climax"]
+    ),
+    entry!(
+        "wyckoff_sign_of_weakness",
+        "Sign of Weakness (SOW)",
+        Wyckoff,
+        "Decline below range support on expanding volume.",
+        "Supply has overcome demand and price leaves the distribution range.",
+        "Confirms distribution; the next rally is a Last Point of Supply.",
+        ["wyckoff_last_point_of_supply", "price_breakdown"]
+    ),
+    entry!(
+        "wyckoff_last_point_of_supply",
+        "Last Point of Supply (LPSY)",
+        Wyckoff,
+        "Final weak rally before markdown.",
+        "Price bounces toward former support on thin volume and fails.",
+        "Rallies that stall here are exits, not entries.",
+        ["wyckoff_sign_of_weakness", "wyckoff_markdown"]
+    ),
+    entry!(
+        "wyckoff_upthrust",
+        "Upthrust (UT)",
+        Wyckoff,
+        "False breakout above resistance that quickly reverses.",
+        "Price pokes above the trading range high, attracting breakout buyers, then closes back inside as supply hits.",
+        "A bearish signal, the mirror image of a Spring.",
+        ["wyckoff_distribution", "wyckoff_spring", "price_breakout"]
+    ),
+    entry!(
+        "vsa_no_demand",
+        "No Demand",
+        Wyckoff,
+        "Narrow up bar on volume lower than the previous two bars.",
+        "Volume spread analysis reads the thin volume as professional money not joining the rally.",
+        "Bearish, especially near resistance or in distribution; the next weak bar confirms it.",
+        ["vsa_no_supply", "wyckoff_distribution"]
+    ),
+    entry!(
+        "vsa_no_supply",
+        "No Supply",
+        Wyckoff,
+        "Narrow down bar on volume lower than the previous two bars.",
+        "Sellers are drying up: the low-volume test that price-only events miss.",
+        "Bullish near support or after a Spring; a following up bar on rising volume confirms it.",
+        ["vsa_no_demand", "wyckoff_secondary_test", "wyckoff_accumulation"]
+    ),
+    entry!(
+        "vsa_stopping_volume",
+        "Stopping Volume",
+        Wyckoff,
+        "Heavy-volume down bar that closes in the upper half of its range.",
+        "Large buyers absorb the selling, halting the decline for now.",
+        "An early sign of a bottom; often precedes a Selling Climax or Secondary Test.",
+        ["wyckoff_selling_climax", "vsa_effort_without_result"]
+    ),
+    entry!(
+        "vsa_effort_without_result",
+        "Effort Without Result",
+        Wyckoff,
+        "Heavy volume on an unusually narrow spread.",
+        "The effort (volume) did not produce a result (price movement), so the other side absorbed it.",
+        "Near the lows it suggests accumulation; near the highs, distribution.",
+        ["vsa_result_without_effort", "vsa_stopping_volume"]
+    ),
+    entry!(
+        "vsa_result_without_effort",
+        "Result Without Effort",
+        Wyckoff,
+        "Wide-spread bar on light volume.",
+        "Price moved a lot without participation, so the move lacks backing.",
+        "Treat the move with suspicion; it often retraces.",
+        ["vsa_effort_without_result", "volume_spike"]
+    ),
+    // Order flow
+    entry!(
+        "obi",
+        "Order Book Imbalance (OBI)",
+        OrderFlow,
+        "Balance between resting bid and ask volume, from -1 to +1.",
+        "OBI is (bid volume - ask volume) / (bid volume + ask volume) at the best levels of the order book.",
+        "Positive values mean more resting demand than supply. Large imbalances can be spoofed, so watch persistence.",
+        ["ofi", "vamp"]
+    ),
+    entry!(
+        "ofi",
+        "Order Flow Imbalance (OFI)",
+        OrderFlow,
+        "Net change in bid versus ask pressure between order book snapshots.",
+        "OFI adds bid size increases and ask size decreases at the best prices, capturing aggressive order flow rather than resting size.",
+        "Sustained positive OFI tends to lead short-term price increases.",
+        ["obi"]
+    ),
+    entry!(
+        "vamp",
+        "Volume-Adjusted Mid Price (VAMP)",
+        OrderFlow,
+        "Mid price weighted by the size on each side of the book.",
+        "Shifts toward the side with less liquidity, estimating where price is likely to trade next.",
+        "A VAMP above the simple mid price indicates buy-side pressure.",
+        ["obi"]
+    ),
+    entry!(
+        "adl",
+        "Accumulation/Distribution Line (ADL)",
+        OrderFlow,
+        "Running total of volume weighted by where price closes in its range.",
+        "Closes near the high add volume to the line; closes near the low subtract it.",
+        "A rising ADL while price is flat suggests quiet accumulation.",
+        ["institutional_accumulation"]
+    ),
+    // Broker flow
+    entry!(
+        "coordinated_buying",
+        "Coordinated Buying",
+        BrokerFlow,
+        "Several institutional brokers are net buyers at the same time.",
+        "Three or more institutional broker codes accumulating together suggests a shared view rather than one-off trades.",
+        "Stronger when the buying persists across multiple days.",
+        ["institutional_accumulation", "foreign_flow"]
+    ),
+    entry!(
+        "foreign_flow",
+        "Foreign Flow",
+        BrokerFlow,
+        "Net buying or selling by foreign brokers.",
+        "Foreign investors move large amounts of capital and often drive trends in IDX blue chips.",
+        "Persistent foreign inflow is supportive; outflow can weigh on price regardless of fundamentals.",
+        ["coordinated_buying"]
+    ),
+    entry!(
+        "institutional_accumulation",
+        "Institutional Accumulation",
+        BrokerFlow,
+        "Institutional brokers have been net buyers over consecutive days.",
+        "The accumulation score combines net flow, the number of accumulating institutions and persistence.",
+        "Rising scores during a sideways range fit the Wyckoff accumulation pattern.",
+        ["institutional_distribution", "wyckoff_accumulation"]
+    ),
+    entry!(
+        "institutional_distribution",
+        "Institutional Distribution",
+        BrokerFlow,
+        "Institutional brokers have been net sellers over consecutive days.",
+        "Large holders are reducing positions, often into strength.",
+        "Be cautious buying while distribution continues.",
+        ["institutional_accumulation", "wyckoff_distribution"]
+    ),
+    entry!(
+        "broker_concentration",
+        "Broker Concentration (HHI)",
+        BrokerFlow,
+        "How concentrated trading is among a few brokers.",
+        "Measured with the Herfindahl-Hirschman Index of broker market share; higher values mean fewer brokers dominate.",
+        "High concentration can indicate a single large player, or potential price manipulation in illiquid stocks.",
+        ["coordinated_buying"]
+    ),
+    // Scoring
+    entry!(
+        "composite_score",
+        "Composite Score",
+        Scoring,
+        "Overall 0-100 rating combining technical, fundamental, sentiment and ML scores.",
+        "Each component is scored separately and combined with configurable weights.",
+        "Higher is better; compare stocks within the same sector for the most meaningful ranking.",
+        ["trading_signal"]
+    ),
+    entry!(
+        "trading_signal",
+        "Trading Signal",
+        Scoring,
+        "Buy, hold or sell call derived from the composite score.",
+        "Scores of 75 and above map to strong buy, 60 to buy, 45 to hold, 30 to sell, and lower scores to strong sell.",
+        "Use with the stop-loss and target levels shown alongside it.",
+        ["composite_score"]
+    ),
+];
+
+/// All glossary entries
+pub fn glossary() -> &'static [GlossaryEntry] {
+    GLOSSARY
+}
+
+/// Look up a glossary entry by key
+pub fn glossary_entry(key: &str) -> Option<&'static GlossaryEntry> {
+    GLOSSARY.iter().find(|e| e.key == key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_keys_unique_and_related_resolve() {
+        let keys: HashSet<&str> = GLOSSARY.iter().map(|e| e.key).collect();
+        assert_eq!(keys.len(), GLOSSARY.len());
+
+        for entry in GLOSSARY {
+            for related in entry.related {
+                assert!(
+                    keys.contains(related),
+                    "{} references unknown key {}",
+                    entry.key,
+                    related
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_alert_keys_resolve() {
+        use crate::alerts::{BrokerAlertType, EventAlertType, TechnicalAlertType};
+        use rust_decimal::Decimal;
+
+        let zero = Decimal::ZERO;
+        let technical = [
+            TechnicalAlertType::RsiOverbought { rsi: zero },
+            TechnicalAlertType::RsiOversold { rsi: zero },
+            TechnicalAlertType::MacdBullishCrossover {
+                macd: zero,
+                signal: zero,
+            },
+            TechnicalAlertType::MacdBearishCrossover {
+                macd: zero,
+                signal: zero,
+            },
+            TechnicalAlertType::WyckoffAccumulation { confidence: 0 },
+            TechnicalAlertType::WyckoffDistribution { confidence: 0 },
+            TechnicalAlertType::WyckoffSpring { price: zero },
+            TechnicalAlertType::WyckoffUpthrust { price: zero },
+            TechnicalAlertType::WyckoffEvent {
+                event: "sign_of_strength".to_string(),
+                confidence: 0,
+            },
+            TechnicalAlertType::VolumeSpike { rvol: zero },
+            TechnicalAlertType::PriceBreakout {
+                price: zero,
+                resistance: zero,
+            },
+            TechnicalAlertType::PriceBreakdown {
+                price: zero,
+                support: zero,
+            },
+            TechnicalAlertType::GoldenCross {
+                ema_short: zero,
+                ema_long: zero,
+            },
+            TechnicalAlertType::DeathCross {
+                ema_short: zero,
+                ema_long: zero,
+            },
+            TechnicalAlertType::BollingerSqueeze { bandwidth: zero },
+            TechnicalAlertType::UserLevelCrossed {
+                price: zero,
+                level: zero,
+                label: None,
+                crossed_above: true,
+            },
+            TechnicalAlertType::VwapReclaimed {
+                price: zero,
+                vwap: zero,
+                rvol: zero,
+            },
+            TechnicalAlertType::VwapLost {
+                price: zero,
+                vwap: zero,
+                rvol: zero,
+            },
+            TechnicalAlertType::BullishDivergence {
+                indicator: String::new(),
+                hidden: false,
+                confidence: 0,
+            },
+            TechnicalAlertType::BearishDivergence {
+                indicator: String::new(),
+                hidden: true,
+                confidence: 0,
+            },
+            TechnicalAlertType::RuleMatched {
+                rule_id: 1,
+                name: String::new(),
+                condition: String::new(),
+            },
+        ];
+        let broker = [
+            BrokerAlertType::CoordinatedBuying {
+                broker_count: 3,
+                broker_codes: vec![],
+            },
+            BrokerAlertType::ForeignInflow {
+                net_value: zero,
+                threshold: zero,
+            },
+            BrokerAlertType::ForeignOutflow {
+                net_value: zero,
+                threshold: zero,
+            },
+            BrokerAlertType::InstitutionalAccumulation {
+                score: zero,
+                days_accumulated: 0,
+            },
+            BrokerAlertType::InstitutionalDistribution {
+                score: zero,
+                days_distributed: 0,
+            },
+            BrokerAlertType::HighConcentration {
+                hhi: zero,
+                top_broker: String::new(),
+            },
+        ];
+
+        let event = [EventAlertType::EarningsUpcoming {
+            period: crate::earnings::ReportPeriod::Q1,
+            fiscal_year: 2025,
+            expected_date: chrono::NaiveDate::MIN,
+            days_until: 0,
+        }];
+
+        let keys = technical
+            .iter()
+            .map(|t| t.glossary_key())
+            .chain(broker.iter().map(|b| b.glossary_key()))
+            .chain(event.iter().map(|e| e.glossary_key()));
+        for key in keys {
+            assert!(
+                glossary_entry(key).is_some(),
+                "missing glossary entry {}",
+                key
+            );
+        }
+    }
+
+    #[test]
+    fn test_lookup() {
+        assert_eq!(
+            glossary_entry("obi").unwrap().category,
+            GlossaryCategory::OrderFlow
+        );
+        assert!(glossary_entry("not_a_key").is_none());
+    }
+}
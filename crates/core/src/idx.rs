@@ -0,0 +1,143 @@
+//! IDX trading units
+//!
+//! Prices on the Indonesia Stock Exchange move in price-dependent ticks and
+//! shares trade in board lots of 100. Prices and quantities the system
+//! suggests should be ones an order can actually be placed at.
+
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+/// Shares per board lot
+pub const LOT_SIZE: i64 = 100;
+
+/// Tick ladder: (lower price bound, tick) from the highest band down
+const TICK_LADDER: [(Decimal, Decimal); 5] = [
+    (dec!(5000), dec!(25)),
+    (dec!(2000), dec!(10)),
+    (dec!(500), dec!(5)),
+    (dec!(200), dec!(2)),
+    (dec!(0), dec!(1)),
+];
+
+/// Direction to snap an off-tick price
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TickRounding {
+    Down,
+    Up,
+    Nearest,
+}
+
+/// Price increment allowed at `price`
+pub fn tick_size(price: Decimal) -> Decimal {
+    TICK_LADDER
+        .iter()
+        .find(|(lower, _)| price >= *lower)
+        .map(|(_, tick)| *tick)
+        .unwrap_or(Decimal::ONE)
+}
+
+/// Whether `price` sits on the tick ladder
+pub fn is_valid_tick(price: Decimal) -> bool {
+    price > Decimal::ZERO && (price % tick_size(price)).is_zero()
+}
+
+/// Snap `price` to a valid tick
+///
+/// The tick of the band `price` falls in is used; snapping up from just
+/// below a band boundary lands on the boundary, which is valid in both bands.
+pub fn round_to_tick(price: Decimal, rounding: TickRounding) -> Decimal {
+    if price <= Decimal::ZERO {
+        return Decimal::ZERO;
+    }
+    let tick = tick_size(price);
+    let ticks = price / tick;
+    let snapped = match rounding {
+        TickRounding::Down => ticks.floor(),
+        TickRounding::Up => ticks.ceil(),
+        TickRounding::Nearest => ticks.round(),
+    };
+    snapped * tick
+}
+
+/// Whole lots in `shares`, rounding down
+pub fn shares_to_lots(shares: i64) -> i64 {
+    shares.max(0) / LOT_SIZE
+}
+
+pub fn lots_to_shares(lots: i64) -> i64 {
+    lots * LOT_SIZE
+}
+
+/// Whole lots `amount` buys at `price`
+pub fn affordable_lots(amount: Decimal, price: Decimal) -> i64 {
+    if amount <= Decimal::ZERO || price <= Decimal::ZERO {
+        return 0;
+    }
+    let lot_value = price * Decimal::from(LOT_SIZE);
+    (amount / lot_value).floor().try_into().unwrap_or(0)
+}
+
+/// Whole lots that lose at most `risk_amount` if price falls from `entry`
+/// to `stop`
+pub fn lots_for_risk(risk_amount: Decimal, entry: Decimal, stop: Decimal) -> i64 {
+    let risk_per_share = entry - stop;
+    if risk_amount <= Decimal::ZERO || risk_per_share <= Decimal::ZERO {
+        return 0;
+    }
+    let risk_per_lot = risk_per_share * Decimal::from(LOT_SIZE);
+    (risk_amount / risk_per_lot).floor().try_into().unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tick_size_bands() {
+        assert_eq!(tick_size(dec!(50)), dec!(1));
+        assert_eq!(tick_size(dec!(199)), dec!(1));
+        assert_eq!(tick_size(dec!(200)), dec!(2));
+        assert_eq!(tick_size(dec!(498)), dec!(2));
+        assert_eq!(tick_size(dec!(500)), dec!(5));
+        assert_eq!(tick_size(dec!(1995)), dec!(5));
+        assert_eq!(tick_size(dec!(2000)), dec!(10));
+        assert_eq!(tick_size(dec!(4990)), dec!(10));
+        assert_eq!(tick_size(dec!(5000)), dec!(25));
+        assert_eq!(tick_size(dec!(9275)), dec!(25));
+    }
+
+    #[test]
+    fn test_round_to_tick() {
+        assert_eq!(round_to_tick(dec!(9283.4), TickRounding::Down), dec!(9275));
+        assert_eq!(round_to_tick(dec!(9283.4), TickRounding::Up), dec!(9300));
+        assert_eq!(
+            round_to_tick(dec!(9283.4), TickRounding::Nearest),
+            dec!(9275)
+        );
+        assert_eq!(round_to_tick(dec!(1998.2), TickRounding::Up), dec!(2000));
+        assert_eq!(round_to_tick(dec!(2004), TickRounding::Down), dec!(2000));
+        assert_eq!(round_to_tick(dec!(301), TickRounding::Down), dec!(300));
+        assert_eq!(round_to_tick(dec!(150.6), TickRounding::Nearest), dec!(151));
+        assert_eq!(round_to_tick(dec!(-5), TickRounding::Up), Decimal::ZERO);
+
+        assert!(is_valid_tick(dec!(9275)));
+        assert!(!is_valid_tick(dec!(9280)));
+        assert!(is_valid_tick(dec!(2000)));
+        assert!(!is_valid_tick(dec!(0)));
+    }
+
+    #[test]
+    fn test_lots() {
+        assert_eq!(shares_to_lots(250), 2);
+        assert_eq!(shares_to_lots(-100), 0);
+        assert_eq!(lots_to_shares(3), 300);
+
+        // 10,000,000 at 9,275 per share buys 10 lots (9,275,000)
+        assert_eq!(affordable_lots(dec!(10_000_000), dec!(9275)), 10);
+        assert_eq!(affordable_lots(dec!(500_000), dec!(9275)), 0);
+
+        // Risking 1,000,000 with a 250 stop distance allows 40 lots
+        assert_eq!(lots_for_risk(dec!(1_000_000), dec!(9275), dec!(9025)), 40);
+        assert_eq!(lots_for_risk(dec!(1_000_000), dec!(9000), dec!(9275)), 0);
+    }
+}
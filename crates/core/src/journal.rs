@@ -0,0 +1,434 @@
+//! Trade journal analytics
+//!
+//! Compares the user's realized trades with the signals that prompted them.
+//! The gap between what a signal would have returned and what was actually
+//! realized is attributed to behavioral leaks such as chasing entries,
+//! entering late, or holding losers longer than winners.
+//!
+//! Signal returns are paper trades, so they are charged the modeled
+//! transaction costs before being compared with realized returns.
+
+use crate::transaction_costs::TransactionCostModel;
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Entry slippage versus the signal price above which entries count as chased (%)
+const CHASE_THRESHOLD_PCT: f64 = 1.0;
+/// Delay between signal and entry above which entries count as late (hours)
+const LATE_ENTRY_HOURS: f64 = 24.0;
+/// Signal return minus realized return above which execution is leaking (%)
+const EXECUTION_GAP_THRESHOLD_PCT: f64 = 2.0;
+/// Loser/winner holding-time ratio above which losers are held too long
+const HOLDING_RATIO_THRESHOLD: f64 = 1.5;
+/// Win-rate shortfall of off-signal trades versus signal trades (percentage points)
+const OFF_SIGNAL_WIN_RATE_GAP: f64 = 10.0;
+/// Minimum sample size before a leak is reported
+const MIN_SAMPLE: usize = 3;
+
+/// Signal that prompted a trade
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignalReference {
+    pub source: String,
+    pub time: DateTime<Utc>,
+    pub price: Decimal,
+}
+
+/// A logged trade (long only, as on IDX for retail accounts)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalTrade {
+    pub symbol: String,
+    pub entry_time: DateTime<Utc>,
+    pub entry_price: Decimal,
+    pub quantity: i64,
+    pub exit_time: Option<DateTime<Utc>>,
+    pub exit_price: Option<Decimal>,
+    pub fees: Decimal,
+    pub signal: Option<SignalReference>,
+}
+
+impl JournalTrade {
+    pub fn is_closed(&self) -> bool {
+        self.exit_price.is_some()
+    }
+
+    /// Realized profit after fees
+    pub fn realized_pnl(&self) -> Option<Decimal> {
+        let exit = self.exit_price?;
+        Some((exit - self.entry_price) * Decimal::from(self.quantity) - self.fees)
+    }
+
+    /// Realized return after fees, in percent of the entry cost
+    pub fn realized_return_pct(&self) -> Option<f64> {
+        let cost = self.entry_price * Decimal::from(self.quantity);
+        if cost.is_zero() {
+            return None;
+        }
+        (self.realized_pnl()? / cost * Decimal::from(100)).to_f64()
+    }
+
+    /// Return had the trade been entered at the signal price and exited at the same exit
+    pub fn signal_return_pct(&self) -> Option<f64> {
+        let signal = self.signal.as_ref()?;
+        pct_change(signal.price, self.exit_price?)
+    }
+
+    /// Signal return after the modeled fees, spread and impact
+    pub fn signal_net_return_pct(
+        &self,
+        costs: &TransactionCostModel,
+        adtv: Option<Decimal>,
+    ) -> Option<f64> {
+        let signal = self.signal.as_ref()?;
+        costs.net_return_pct(signal.price, self.exit_price?, self.quantity, adtv)
+    }
+
+    /// How far above the signal price the entry was filled, in percent
+    pub fn entry_slippage_pct(&self) -> Option<f64> {
+        let signal = self.signal.as_ref()?;
+        pct_change(signal.price, self.entry_price)
+    }
+
+    /// Hours between the signal and the entry
+    pub fn entry_delay_hours(&self) -> Option<f64> {
+        let signal = self.signal.as_ref()?;
+        Some((self.entry_time - signal.time).num_minutes() as f64 / 60.0)
+    }
+
+    pub fn holding_days(&self) -> Option<f64> {
+        let exit = self.exit_time?;
+        Some((exit - self.entry_time).num_hours() as f64 / 24.0)
+    }
+}
+
+fn pct_change(from: Decimal, to: Decimal) -> Option<f64> {
+    if from.is_zero() {
+        return None;
+    }
+    ((to - from) / from * Decimal::from(100)).to_f64()
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, count) = values.fold((0.0, 0usize), |(s, c), v| (s + v, c + 1));
+    (count > 0).then(|| sum / count as f64)
+}
+
+fn win_rate<'a>(trades: impl Iterator<Item = &'a JournalTrade>) -> (Option<f64>, usize) {
+    let returns: Vec<f64> = trades.filter_map(|t| t.realized_return_pct()).collect();
+    let wins = returns.iter().filter(|r| **r > 0.0).count();
+    let rate = (!returns.is_empty()).then(|| wins as f64 / returns.len() as f64 * 100.0);
+    (rate, returns.len())
+}
+
+/// Kind of behavioral leak
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LeakKind {
+    /// Buying well above the signal price
+    ChasingEntries,
+    /// Acting on signals long after they fired
+    LateEntries,
+    /// Realizing much less than the signals would have
+    ExecutionGap,
+    /// Holding losing trades longer than winners
+    HoldingLosers,
+    /// Trades without a signal underperform signal-driven trades
+    OffSignalTrades,
+}
+
+/// A detected behavioral leak
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BehaviorLeak {
+    pub kind: LeakKind,
+    pub description: String,
+    /// Measured value the leak was detected from (unit depends on kind)
+    pub value: f64,
+}
+
+/// Realized performance compared with the system's signals
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct JournalAnalytics {
+    pub total_trades: usize,
+    pub open_trades: usize,
+    pub closed_trades: usize,
+    pub total_pnl: Decimal,
+    pub win_rate: Option<f64>,
+    pub avg_return_pct: Option<f64>,
+    pub signal_trades: usize,
+    pub signal_win_rate: Option<f64>,
+    pub off_signal_win_rate: Option<f64>,
+    /// Average return the linked signals would have produced, net of
+    /// modeled transaction costs
+    pub avg_signal_return_pct: Option<f64>,
+    /// Average realized return of signal-linked trades
+    pub avg_signal_trade_return_pct: Option<f64>,
+    pub avg_entry_slippage_pct: Option<f64>,
+    pub avg_entry_delay_hours: Option<f64>,
+    pub avg_winner_holding_days: Option<f64>,
+    pub avg_loser_holding_days: Option<f64>,
+    pub leaks: Vec<BehaviorLeak>,
+}
+
+/// Analyze journal trades against their signals
+///
+/// `adtv` holds the average daily traded value per symbol, for the slippage
+/// of the signal paper trades.
+pub fn analyze_journal(
+    trades: &[JournalTrade],
+    costs: &TransactionCostModel,
+    adtv: &BTreeMap<String, Decimal>,
+) -> JournalAnalytics {
+    let closed: Vec<&JournalTrade> = trades.iter().filter(|t| t.is_closed()).collect();
+    let linked: Vec<&JournalTrade> = trades.iter().filter(|t| t.signal.is_some()).collect();
+    let closed_linked: Vec<&JournalTrade> = closed
+        .iter()
+        .copied()
+        .filter(|t| t.signal.is_some())
+        .collect();
+
+    let (overall_win_rate, _) = win_rate(closed.iter().copied());
+    let (signal_win_rate, _) = win_rate(closed_linked.iter().copied());
+    let (off_signal_win_rate, off_signal_count) =
+        win_rate(closed.iter().copied().filter(|t| t.signal.is_none()));
+
+    let winners: Vec<&JournalTrade> = closed
+        .iter()
+        .copied()
+        .filter(|t| t.realized_pnl().is_some_and(|p| p > Decimal::ZERO))
+        .collect();
+    let losers: Vec<&JournalTrade> = closed
+        .iter()
+        .copied()
+        .filter(|t| t.realized_pnl().is_some_and(|p| p <= Decimal::ZERO))
+        .collect();
+
+    let mut analytics = JournalAnalytics {
+        total_trades: trades.len(),
+        open_trades: trades.len() - closed.len(),
+        closed_trades: closed.len(),
+        total_pnl: closed.iter().filter_map(|t| t.realized_pnl()).sum(),
+        win_rate: overall_win_rate,
+        avg_return_pct: mean(closed.iter().filter_map(|t| t.realized_return_pct())),
+        signal_trades: linked.len(),
+        signal_win_rate,
+        off_signal_win_rate,
+        avg_signal_return_pct: mean(
+            closed_linked
+                .iter()
+                .filter_map(|t| t.signal_net_return_pct(costs, adtv.get(&t.symbol).copied())),
+        ),
+        avg_signal_trade_return_pct: mean(
+            closed_linked.iter().filter_map(|t| t.realized_return_pct()),
+        ),
+        avg_entry_slippage_pct: mean(linked.iter().filter_map(|t| t.entry_slippage_pct())),
+        avg_entry_delay_hours: mean(linked.iter().filter_map(|t| t.entry_delay_hours())),
+        avg_winner_holding_days: mean(winners.iter().filter_map(|t| t.holding_days())),
+        avg_loser_holding_days: mean(losers.iter().filter_map(|t| t.holding_days())),
+        leaks: Vec::new(),
+    };
+
+    analytics.leaks = detect_leaks(
+        &analytics,
+        linked.len(),
+        closed_linked.len(),
+        off_signal_count,
+    );
+    analytics
+}
+
+fn detect_leaks(
+    analytics: &JournalAnalytics,
+    linked: usize,
+    closed_linked: usize,
+    off_signal: usize,
+) -> Vec<BehaviorLeak> {
+    let mut leaks = Vec::new();
+
+    if linked >= MIN_SAMPLE {
+        if let Some(slippage) = analytics
+            .avg_entry_slippage_pct
+            .filter(|s| *s > CHASE_THRESHOLD_PCT)
+        {
+            leaks.push(BehaviorLeak {
+                kind: LeakKind::ChasingEntries,
+                description: format!(
+                    "Entries are filled {:.1}% above the signal price on average",
+                    slippage
+                ),
+                value: slippage,
+            });
+        }
+
+        if let Some(delay) = analytics
+            .avg_entry_delay_hours
+            .filter(|d| *d > LATE_ENTRY_HOURS)
+        {
+            leaks.push(BehaviorLeak {
+                kind: LeakKind::LateEntries,
+                description: format!(
+                    "Signals are acted on {:.0} hours after they fire on average",
+                    delay
+                ),
+                value: delay,
+            });
+        }
+    }
+
+    if closed_linked >= MIN_SAMPLE {
+        if let (Some(signal), Some(realized)) = (
+            analytics.avg_signal_return_pct,
+            analytics.avg_signal_trade_return_pct,
+        ) {
+            let gap = signal - realized;
+            if gap > EXECUTION_GAP_THRESHOLD_PCT {
+                leaks.push(BehaviorLeak {
+                    kind: LeakKind::ExecutionGap,
+                    description: format!(
+                        "Signal-driven trades realized {:.1}% less than the signals returned",
+                        gap
+                    ),
+                    value: gap,
+                });
+            }
+        }
+    }
+
+    if let (Some(winner_days), Some(loser_days)) = (
+        analytics.avg_winner_holding_days,
+        analytics.avg_loser_holding_days,
+    ) {
+        if analytics.closed_trades >= MIN_SAMPLE
+            && winner_days > 0.0
+            && loser_days / winner_days > HOLDING_RATIO_THRESHOLD
+        {
+            let ratio = loser_days / winner_days;
+            leaks.push(BehaviorLeak {
+                kind: LeakKind::HoldingLosers,
+                description: format!(
+                    "Losing trades are held {:.1}x longer than winners ({:.1} vs {:.1} days)",
+                    ratio, loser_days, winner_days
+                ),
+                value: ratio,
+            });
+        }
+    }
+
+    if off_signal >= MIN_SAMPLE && closed_linked >= MIN_SAMPLE {
+        if let (Some(signal), Some(off)) =
+            (analytics.signal_win_rate, analytics.off_signal_win_rate)
+        {
+            let gap = signal - off;
+            if gap > OFF_SIGNAL_WIN_RATE_GAP {
+                leaks.push(BehaviorLeak {
+                    kind: LeakKind::OffSignalTrades,
+                    description: format!(
+                        "Trades without a signal win {:.0}% of the time versus {:.0}% with one",
+                        off, signal
+                    ),
+                    value: gap,
+                });
+            }
+        }
+    }
+
+    leaks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+    use rust_decimal_macros::dec;
+
+    fn trade(
+        entry: Decimal,
+        exit: Option<Decimal>,
+        held_days: i64,
+        signal: Option<(Decimal, i64)>,
+    ) -> JournalTrade {
+        let entry_time = Utc.with_ymd_and_hms(2024, 1, 10, 2, 0, 0).unwrap();
+        JournalTrade {
+            symbol: "BBCA".to_string(),
+            entry_time,
+            entry_price: entry,
+            quantity: 100,
+            exit_time: exit.map(|_| entry_time + Duration::days(held_days)),
+            exit_price: exit,
+            fees: Decimal::ZERO,
+            signal: signal.map(|(price, hours_before)| SignalReference {
+                source: "alert".to_string(),
+                time: entry_time - Duration::hours(hours_before),
+                price,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_trade_metrics() {
+        let t = trade(dec!(1020), Some(dec!(1100)), 5, Some((dec!(1000), 2)));
+
+        assert_eq!(t.realized_pnl(), Some(dec!(8000)));
+        assert!((t.signal_return_pct().unwrap() - 10.0).abs() < 1e-9);
+        let net = t
+            .signal_net_return_pct(&TransactionCostModel::default(), None)
+            .unwrap();
+        assert!(net < 10.0 && net > 9.0);
+        assert!((t.entry_slippage_pct().unwrap() - 2.0).abs() < 1e-9);
+        assert_eq!(t.entry_delay_hours(), Some(2.0));
+        assert_eq!(t.holding_days(), Some(5.0));
+    }
+
+    #[test]
+    fn test_detects_chasing_and_execution_gap() {
+        let trades: Vec<JournalTrade> = (0..3)
+            .map(|_| trade(dec!(1050), Some(dec!(1080)), 3, Some((dec!(1000), 1))))
+            .collect();
+
+        let analytics =
+            analyze_journal(&trades, &TransactionCostModel::default(), &BTreeMap::new());
+        let kinds: Vec<LeakKind> = analytics.leaks.iter().map(|l| l.kind).collect();
+
+        assert_eq!(analytics.signal_trades, 3);
+        assert!(kinds.contains(&LeakKind::ChasingEntries));
+        assert!(kinds.contains(&LeakKind::ExecutionGap));
+        assert!(!kinds.contains(&LeakKind::LateEntries));
+    }
+
+    #[test]
+    fn test_detects_holding_losers() {
+        let trades = vec![
+            trade(dec!(1000), Some(dec!(1100)), 2, None),
+            trade(dec!(1000), Some(dec!(1050)), 2, None),
+            trade(dec!(1000), Some(dec!(900)), 10, None),
+            trade(dec!(1000), None, 0, None),
+        ];
+
+        let analytics =
+            analyze_journal(&trades, &TransactionCostModel::default(), &BTreeMap::new());
+
+        assert_eq!(analytics.open_trades, 1);
+        assert_eq!(analytics.closed_trades, 3);
+        assert_eq!(analytics.total_pnl, dec!(5000));
+        assert!(analytics
+            .leaks
+            .iter()
+            .any(|l| l.kind == LeakKind::HoldingLosers));
+    }
+
+    #[test]
+    fn test_small_sample_reports_no_leaks() {
+        let trades = vec![trade(
+            dec!(1100),
+            Some(dec!(1000)),
+            1,
+            Some((dec!(1000), 72)),
+        )];
+        assert!(
+            analyze_journal(&trades, &TransactionCostModel::default(), &BTreeMap::new())
+                .leaks
+                .is_empty()
+        );
+    }
+}
@@ -2,17 +2,65 @@
 //!
 //! Provides:
 //! - Alert system for broker flow, technical, and price alerts
+//! - Single-symbol strategy backtests net of transaction costs
+//! - Grid-search parameter optimization with out-of-sample validation
+//! - Monte Carlo resampling of backtest trades
+//! - Financial report calendar from filing history and deadlines
 //! - Scoring engines for fundamental and technical analysis
+//! - Sector profiles selecting fundamental weights and metrics
+//! - Sector indices rebuilt from member closes and sector rotation
+//! - Trade journal analytics against system signals
+//! - Portfolio holdings, cost basis and exposure metrics
+//! - Portfolio risk-budget checks
+//! - Stock screener with composable filters
+//! - Transaction-cost model for simulated trades
+//! - IDX tick-size ladder and board lots
+//! - IDX market calendar of trading days and session hours
+//! - Market regime detection from index trend and macro data
+//! - Glossary content for signals and indicators
+//! - Lexicon sentiment of Indonesian financial news
 //! - Core domain models
 
 pub mod alerts;
+pub mod backtest;
+pub mod earnings;
 pub mod fundamental_score;
+pub mod glossary;
+pub mod idx;
+pub mod journal;
+pub mod market_calendar;
 pub mod models;
+pub mod monte_carlo;
+pub mod optimization;
+pub mod portfolio;
+pub mod regime;
+pub mod risk_budget;
 pub mod scoring;
+pub mod screener;
+pub mod sector_index;
+pub mod sector_profile;
+pub mod sentiment;
 pub mod technical_score;
+pub mod transaction_costs;
 
 pub use alerts::*;
+pub use backtest::*;
+pub use earnings::*;
 pub use fundamental_score::*;
+pub use glossary::*;
+pub use idx::*;
+pub use journal::*;
+pub use market_calendar::*;
 pub use models::*;
+pub use monte_carlo::*;
+pub use optimization::*;
+pub use portfolio::*;
+pub use regime::*;
+pub use risk_budget::*;
 pub use scoring::*;
+pub use screener::*;
+pub use sector_index::*;
+pub use sector_profile::*;
+pub use sentiment::*;
 pub use technical_score::*;
+pub use transaction_costs::*;
@@ -0,0 +1,118 @@
+//! IDX market calendar
+//!
+//! The regular market runs Monday to Friday from the 09:00 WIB open through
+//! the pre-closing auction and post-trading session, which ends at 16:15
+//! WIB. Exchange holidays are published by IDX each year, so they are
+//! configured rather than computed.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Utc, Weekday};
+use std::collections::BTreeSet;
+
+/// Session open in UTC (09:00 WIB)
+pub const SESSION_OPEN_UTC: (u32, u32) = (2, 0);
+
+/// End of post-trading in UTC (16:15 WIB)
+pub const SESSION_CLOSE_UTC: (u32, u32) = (9, 15);
+
+fn utc_time((hour, minute): (u32, u32)) -> NaiveTime {
+    NaiveTime::from_hms_opt(hour, minute, 0).expect("valid session time")
+}
+
+/// Trading days and hours of the exchange
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MarketCalendar {
+    holidays: BTreeSet<NaiveDate>,
+}
+
+impl MarketCalendar {
+    pub fn new(holidays: impl IntoIterator<Item = NaiveDate>) -> Self {
+        Self {
+            holidays: holidays.into_iter().collect(),
+        }
+    }
+
+    /// Parse a comma-separated list of `YYYY-MM-DD` holidays
+    pub fn parse_holidays(list: &str) -> Result<Self, chrono::ParseError> {
+        let holidays = list
+            .split(',')
+            .map(str::trim)
+            .filter(|d| !d.is_empty())
+            .map(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d"))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::new(holidays))
+    }
+
+    pub fn holidays(&self) -> impl Iterator<Item = &NaiveDate> {
+        self.holidays.iter()
+    }
+
+    /// Weekdays that are not exchange holidays
+    pub fn is_trading_day(&self, date: NaiveDate) -> bool {
+        !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) && !self.holidays.contains(&date)
+    }
+
+    /// Whether `now` falls within a trading day's session
+    pub fn is_open(&self, now: DateTime<Utc>) -> bool {
+        // The WIB session never crosses midnight UTC
+        self.is_trading_day(now.date_naive())
+            && now.time() >= utc_time(SESSION_OPEN_UTC)
+            && now.time() < utc_time(SESSION_CLOSE_UTC)
+    }
+
+    /// Next trading day at `at` (UTC), strictly after `now`
+    pub fn next_trading_day_at(&self, now: DateTime<Utc>, at: NaiveTime) -> DateTime<Utc> {
+        let mut date = now.date_naive();
+        if now.time() >= at {
+            date += Duration::days(1);
+        }
+        while !self.is_trading_day(date) {
+            date += Duration::days(1);
+        }
+        date.and_time(at).and_utc()
+    }
+
+    /// Next session open strictly after `now`
+    pub fn next_open(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        self.next_trading_day_at(now, utc_time(SESSION_OPEN_UTC))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_is_open() {
+        let calendar = MarketCalendar::parse_holidays("2025-06-06").unwrap();
+        // Wednesday 10:00 WIB and 17:00 WIB
+        assert!(calendar.is_open(at("2025-06-04T03:00:00Z")));
+        assert!(!calendar.is_open(at("2025-06-04T10:00:00Z")));
+        // Post-trading still counts, the open is inclusive
+        assert!(calendar.is_open(at("2025-06-04T09:10:00Z")));
+        assert!(calendar.is_open(at("2025-06-04T02:00:00Z")));
+        // Saturday and a Friday holiday
+        assert!(!calendar.is_open(at("2025-06-07T03:00:00Z")));
+        assert!(!calendar.is_open(at("2025-06-06T03:00:00Z")));
+    }
+
+    #[test]
+    fn test_next_open_skips_holidays() {
+        let calendar = MarketCalendar::parse_holidays("2025-06-09, 2025-06-10").unwrap();
+        assert_eq!(calendar.holidays().count(), 2);
+        // Wednesday evening opens Thursday
+        assert_eq!(
+            calendar.next_open(at("2025-06-04T10:00:00Z")),
+            at("2025-06-05T02:00:00Z")
+        );
+        // Friday evening skips the weekend and the Monday-Tuesday holidays
+        assert_eq!(
+            calendar.next_open(at("2025-06-06T10:00:00Z")),
+            at("2025-06-11T02:00:00Z")
+        );
+        assert!(MarketCalendar::parse_holidays("2025-13-01").is_err());
+    }
+}
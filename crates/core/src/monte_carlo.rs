@@ -0,0 +1,259 @@
+//! Monte Carlo simulation of backtest outcomes
+//!
+//! A backtest is one path through history. Resampling its trades with
+//! replacement, each compounded on the equity it was entered with, shows
+//! how final equity and drawdown could have spread had the same trades
+//! come in another order or mix, and how often the account would have
+//! been ruined. Draws are seeded, so a report reproduces exactly.
+
+use crate::backtest::BacktestTrade;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// Most paths one simulation may draw
+pub const MAX_SIMULATIONS: usize = 10_000;
+
+/// Simulation settings; fields left out keep their defaults
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MonteCarloConfig {
+    /// Resampled paths; 0 skips the simulation
+    pub simulations: usize,
+    pub seed: u64,
+    /// A path is ruined once equity falls this far below the initial
+    /// capital, percent
+    pub ruin_loss_pct: f64,
+}
+
+impl Default for MonteCarloConfig {
+    fn default() -> Self {
+        Self {
+            simulations: 1000,
+            seed: 42,
+            ruin_loss_pct: 50.0,
+        }
+    }
+}
+
+impl MonteCarloConfig {
+    pub fn is_valid(&self) -> bool {
+        self.simulations <= MAX_SIMULATIONS
+            && self.ruin_loss_pct > 0.0
+            && self.ruin_loss_pct < 100.0
+    }
+}
+
+/// Spread of one outcome over the simulated paths
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Distribution {
+    pub mean: f64,
+    pub p5: f64,
+    pub p25: f64,
+    pub p50: f64,
+    pub p75: f64,
+    pub p95: f64,
+}
+
+impl Distribution {
+    /// Nearest-rank percentiles; `values` must not be empty
+    fn from_values(mut values: Vec<f64>) -> Self {
+        values.sort_by(f64::total_cmp);
+        let at = |p: f64| {
+            let rank = (p / 100.0 * values.len() as f64).ceil() as usize;
+            values[rank.clamp(1, values.len()) - 1]
+        };
+        Self {
+            mean: values.iter().sum::<f64>() / values.len() as f64,
+            p5: at(5.0),
+            p25: at(25.0),
+            p50: at(50.0),
+            p75: at(75.0),
+            p95: at(95.0),
+        }
+    }
+}
+
+/// Outcomes of the resampled paths
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonteCarloSummary {
+    pub simulations: usize,
+    /// Trades drawn per path, as many as the backtest made
+    pub trades_per_path: usize,
+    /// IDR
+    pub final_equity: Distribution,
+    pub total_return_pct: Distribution,
+    /// Deepest fall from a peak, measured between trades, percent
+    pub max_drawdown_pct: Distribution,
+    /// Paths ending above the initial capital, percent
+    pub profit_probability_pct: f64,
+    /// Paths reaching the ruin loss, percent
+    pub ruin_probability_pct: f64,
+}
+
+/// SplitMix64: small, fast and identical on every platform
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn index(&mut self, len: usize) -> usize {
+        (self.next_u64() % len as u64) as usize
+    }
+}
+
+/// Each trade's profit relative to the equity it was entered with
+///
+/// Positions are opened with all available cash, so equity at an entry is
+/// the initial capital plus the profit of every earlier trade.
+fn equity_returns(initial_capital: Decimal, trades: &[BacktestTrade]) -> Vec<f64> {
+    let mut equity = initial_capital;
+    trades
+        .iter()
+        .map(|trade| {
+            let r = if equity > Decimal::ZERO {
+                (trade.pnl / equity).to_f64().unwrap_or(0.0)
+            } else {
+                0.0
+            };
+            equity += trade.pnl;
+            r
+        })
+        .collect()
+}
+
+/// Resample `trades` into `config.simulations` paths
+///
+/// None without trades or when the simulation is switched off.
+pub fn simulate_trades(
+    initial_capital: Decimal,
+    trades: &[BacktestTrade],
+    config: &MonteCarloConfig,
+) -> Option<MonteCarloSummary> {
+    if trades.is_empty() || config.simulations == 0 {
+        return None;
+    }
+    let returns = equity_returns(initial_capital, trades);
+    let initial = initial_capital.to_f64()?;
+    let ruin_level = initial * (1.0 - config.ruin_loss_pct / 100.0);
+    let mut rng = SplitMix64(config.seed);
+
+    let mut finals = Vec::with_capacity(config.simulations);
+    let mut drawdowns = Vec::with_capacity(config.simulations);
+    let mut ruined = 0usize;
+    for _ in 0..config.simulations {
+        let mut equity = initial;
+        let mut peak = initial;
+        let mut max_drawdown = 0.0f64;
+        let mut hit_ruin = false;
+        for _ in 0..returns.len() {
+            equity *= 1.0 + returns[rng.index(returns.len())];
+            peak = peak.max(equity);
+            if peak > 0.0 {
+                max_drawdown = max_drawdown.max((peak - equity) / peak * 100.0);
+            }
+            hit_ruin |= equity <= ruin_level;
+        }
+        if hit_ruin {
+            ruined += 1;
+        }
+        finals.push(equity);
+        drawdowns.push(max_drawdown);
+    }
+
+    let n = config.simulations as f64;
+    let profitable = finals.iter().filter(|&&e| e > initial).count();
+    let total_returns = finals.iter().map(|e| (e / initial - 1.0) * 100.0).collect();
+    Some(MonteCarloSummary {
+        simulations: config.simulations,
+        trades_per_path: returns.len(),
+        final_equity: Distribution::from_values(finals),
+        total_return_pct: Distribution::from_values(total_returns),
+        max_drawdown_pct: Distribution::from_values(drawdowns),
+        profit_probability_pct: profitable as f64 / n * 100.0,
+        ruin_probability_pct: ruined as f64 / n * 100.0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backtest::ExitReason;
+    use chrono::NaiveDate;
+    use rust_decimal_macros::dec;
+
+    fn trade(pnl: Decimal) -> BacktestTrade {
+        let date = NaiveDate::from_ymd_opt(2025, 1, 2).unwrap();
+        BacktestTrade {
+            entry_date: date,
+            entry_price: dec!(100),
+            exit_date: date,
+            exit_price: dec!(100),
+            shares: 100,
+            pnl,
+            return_pct: 0.0,
+            bars_held: 1,
+            exit_reason: ExitReason::Signal,
+        }
+    }
+
+    #[test]
+    fn test_equity_returns_compound() {
+        let trades = [trade(dec!(100)), trade(dec!(-110))];
+        let returns = equity_returns(dec!(1000), &trades);
+        assert_eq!(returns, vec![0.1, -0.1]);
+    }
+
+    #[test]
+    fn test_distribution_percentiles() {
+        let d = Distribution::from_values((1..=100).rev().map(f64::from).collect());
+        assert_eq!(d.p5, 5.0);
+        assert_eq!(d.p50, 50.0);
+        assert_eq!(d.p95, 95.0);
+        assert_eq!(d.mean, 50.5);
+    }
+
+    #[test]
+    fn test_simulation() {
+        let trades = [trade(dec!(100)), trade(dec!(-110)), trade(dec!(99))];
+        let config = MonteCarloConfig {
+            simulations: 500,
+            ..MonteCarloConfig::default()
+        };
+        let summary = simulate_trades(dec!(1000), &trades, &config).unwrap();
+        assert_eq!(summary.trades_per_path, 3);
+        // Two winners of 10% and one loser of 10%: most paths profit
+        assert!(summary.profit_probability_pct > 50.0);
+        assert!(summary.final_equity.p5 <= summary.final_equity.p50);
+        assert!(summary.final_equity.p50 <= summary.final_equity.p95);
+        // Three losses in a row are at most a 27% fall
+        assert_eq!(summary.ruin_probability_pct, 0.0);
+        assert!(summary.max_drawdown_pct.p95 < 28.0);
+        // Seeded: the same draws every time
+        assert_eq!(simulate_trades(dec!(1000), &trades, &config), Some(summary));
+
+        assert!(simulate_trades(dec!(1000), &[], &config).is_none());
+    }
+
+    #[test]
+    fn test_partial_config() {
+        let config: MonteCarloConfig = serde_json::from_str(r#"{"seed": 7}"#).unwrap();
+        assert_eq!(config.seed, 7);
+        assert_eq!(config.simulations, MonteCarloConfig::default().simulations);
+        assert_eq!(config.ruin_loss_pct, 50.0);
+    }
+
+    #[test]
+    fn test_ruin() {
+        let trades = [trade(dec!(-600)), trade(dec!(40))];
+        let summary = simulate_trades(dec!(1000), &trades, &MonteCarloConfig::default()).unwrap();
+        // Drawing the 60% loss at all ruins the path: 1 - (1/2)^2
+        assert!((summary.ruin_probability_pct - 75.0).abs() < 6.0);
+    }
+}
@@ -0,0 +1,372 @@
+//! Strategy parameter optimization
+//!
+//! Expands a grid of strategy and risk parameters into backtests. Each
+//! combination is tested on the earlier, in-sample part of the bars and
+//! validated on the later, out-of-sample part it was not ranked on; a
+//! combination that only shines in-sample is likely overfit.
+
+use crate::backtest::{
+    run_backtest, BacktestBar, BacktestConfig, BacktestError, BacktestMetrics, BacktestStrategy,
+};
+use crate::transaction_costs::TransactionCostModel;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// Most combinations one optimization may test
+pub const MAX_GRID_COMBINATIONS: usize = 500;
+
+/// Most values one grid parameter may take
+pub const MAX_GRID_VALUES: usize = 100;
+
+/// Default share of bars used in-sample, percent
+pub const DEFAULT_IN_SAMPLE_PCT: f64 = 70.0;
+
+/// Values tried for each strategy parameter
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StrategyGrid {
+    SmaCrossover {
+        fast: Vec<usize>,
+        slow: Vec<usize>,
+    },
+    ScoreThreshold {
+        entry: Vec<Decimal>,
+        exit: Vec<Decimal>,
+    },
+    RsiReversion {
+        period: Vec<usize>,
+        entry: Vec<f64>,
+        exit: Vec<f64>,
+    },
+}
+
+impl StrategyGrid {
+    /// Values given for each parameter
+    fn value_counts(&self) -> Vec<usize> {
+        match self {
+            Self::SmaCrossover { fast, slow } => vec![fast.len(), slow.len()],
+            Self::ScoreThreshold { entry, exit } => vec![entry.len(), exit.len()],
+            Self::RsiReversion {
+                period,
+                entry,
+                exit,
+            } => vec![period.len(), entry.len(), exit.len()],
+        }
+    }
+
+    /// Every strategy of the grid, invalid combinations included
+    fn strategies(&self) -> Vec<BacktestStrategy> {
+        let mut out = Vec::new();
+        match self {
+            Self::SmaCrossover { fast, slow } => {
+                for &fast in fast {
+                    for &slow in slow {
+                        out.push(BacktestStrategy::SmaCrossover { fast, slow });
+                    }
+                }
+            }
+            Self::ScoreThreshold { entry, exit } => {
+                for &entry in entry {
+                    for &exit in exit {
+                        out.push(BacktestStrategy::ScoreThreshold { entry, exit });
+                    }
+                }
+            }
+            Self::RsiReversion {
+                period,
+                entry,
+                exit,
+            } => {
+                for &period in period {
+                    for &entry in entry {
+                        for &exit in exit {
+                            out.push(BacktestStrategy::RsiReversion {
+                                period,
+                                entry,
+                                exit,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Parameters to search
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParameterGrid {
+    pub strategy: StrategyGrid,
+    /// Stop-loss levels tried (%); empty keeps the base config's
+    #[serde(default)]
+    pub stop_loss_pct: Vec<f64>,
+    /// Holding periods tried (bars); empty keeps the base config's
+    #[serde(default)]
+    pub max_hold_bars: Vec<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum OptimizationError {
+    #[error("parameter grid has no valid combination")]
+    EmptyGrid,
+    #[error("parameter grid has {0} combinations, at most {MAX_GRID_COMBINATIONS} allowed")]
+    GridTooLarge(usize),
+    #[error("a grid parameter may take at most {MAX_GRID_VALUES} values")]
+    TooManyValues,
+    #[error("in-sample share must be between 10% and 90%")]
+    InvalidSplit,
+    #[error(transparent)]
+    Backtest(#[from] BacktestError),
+}
+
+impl ParameterGrid {
+    /// Valid strategy and config combinations, on top of `base`
+    ///
+    /// Combinations the backtester would reject, such as a fast period not
+    /// below the slow one, are left out.
+    pub fn combinations(
+        &self,
+        base: &BacktestConfig,
+    ) -> Result<Vec<(BacktestStrategy, BacktestConfig)>, OptimizationError> {
+        base.validate()?;
+        // Sized before expanding, so an oversized grid is never built
+        let mut counts = self.strategy.value_counts();
+        counts.push(self.stop_loss_pct.len().max(1));
+        counts.push(self.max_hold_bars.len().max(1));
+        if counts.iter().any(|&n| n > MAX_GRID_VALUES) {
+            return Err(OptimizationError::TooManyValues);
+        }
+        let size = counts
+            .iter()
+            .try_fold(1usize, |size, &n| size.checked_mul(n))
+            .unwrap_or(usize::MAX);
+        if size > MAX_GRID_COMBINATIONS {
+            return Err(OptimizationError::GridTooLarge(size));
+        }
+
+        let stops: Vec<Option<f64>> = if self.stop_loss_pct.is_empty() {
+            vec![base.stop_loss_pct]
+        } else {
+            self.stop_loss_pct.iter().copied().map(Some).collect()
+        };
+        let holds: Vec<Option<usize>> = if self.max_hold_bars.is_empty() {
+            vec![base.max_hold_bars]
+        } else {
+            self.max_hold_bars.iter().copied().map(Some).collect()
+        };
+
+        let strategies = self.strategy.strategies();
+        let mut out = Vec::with_capacity(size);
+        for strategy in strategies.iter().filter(|s| s.validate().is_ok()) {
+            for &stop_loss_pct in &stops {
+                for &max_hold_bars in &holds {
+                    let config = BacktestConfig {
+                        stop_loss_pct,
+                        max_hold_bars,
+                        ..base.clone()
+                    };
+                    if config.validate().is_ok() {
+                        out.push((strategy.clone(), config));
+                    }
+                }
+            }
+        }
+        if out.is_empty() {
+            return Err(OptimizationError::EmptyGrid);
+        }
+        Ok(out)
+    }
+}
+
+/// Metric combinations are ranked by
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OptimizationObjective {
+    #[default]
+    SharpeRatio,
+    TotalReturn,
+    ProfitFactor,
+}
+
+impl OptimizationObjective {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::SharpeRatio => "sharpe_ratio",
+            Self::TotalReturn => "total_return",
+            Self::ProfitFactor => "profit_factor",
+        }
+    }
+
+    /// Objective value of a run; None when the metric is undefined
+    pub fn score(&self, metrics: &BacktestMetrics) -> Option<f64> {
+        match self {
+            Self::SharpeRatio => metrics.sharpe_ratio,
+            Self::TotalReturn => Some(metrics.total_return_pct),
+            Self::ProfitFactor => metrics.profit_factor,
+        }
+    }
+}
+
+/// Index of the first out-of-sample bar
+pub fn split_index(bars: usize, in_sample_pct: f64) -> Result<usize, OptimizationError> {
+    if !(10.0..=90.0).contains(&in_sample_pct) {
+        return Err(OptimizationError::InvalidSplit);
+    }
+    Ok((bars as f64 * in_sample_pct / 100.0).round() as usize)
+}
+
+/// One tested combination
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CombinationResult {
+    pub strategy: BacktestStrategy,
+    pub config: BacktestConfig,
+    pub in_sample: BacktestMetrics,
+    /// None when the out-of-sample bars are too few for the strategy
+    pub out_of_sample: Option<BacktestMetrics>,
+}
+
+/// Backtest one combination in and out of sample
+///
+/// The out-of-sample run starts with the strategy's warmup bars from
+/// before `split`, so a signal can already fill on the first bar at `split`.
+/// Only metrics are kept, so the runs skip the Monte Carlo simulation.
+pub fn evaluate_combination(
+    bars: &[BacktestBar],
+    split: usize,
+    strategy: &BacktestStrategy,
+    config: &BacktestConfig,
+    costs: &TransactionCostModel,
+) -> Result<CombinationResult, BacktestError> {
+    let mut run_config = config.clone();
+    run_config.monte_carlo.simulations = 0;
+    let split = split.min(bars.len());
+    let in_sample = run_backtest(&bars[..split], strategy, &run_config, costs)?;
+    let from = split.saturating_sub(strategy.warmup());
+    let out_of_sample = run_backtest(&bars[from..], strategy, &run_config, costs).ok();
+    Ok(CombinationResult {
+        strategy: strategy.clone(),
+        config: config.clone(),
+        in_sample: in_sample.metrics,
+        out_of_sample: out_of_sample.map(|r| r.metrics),
+    })
+}
+
+/// Order results best in-sample first; undefined scores go last
+pub fn rank_results(results: &mut [CombinationResult], objective: OptimizationObjective) {
+    results.sort_by(|a, b| {
+        let a = objective.score(&a.in_sample).unwrap_or(f64::NEG_INFINITY);
+        let b = objective.score(&b.in_sample).unwrap_or(f64::NEG_INFINITY);
+        b.total_cmp(&a)
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn bars(closes: &[i64]) -> Vec<BacktestBar> {
+        let start = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, &close)| BacktestBar {
+                date: start + chrono::Duration::days(i as i64),
+                open: Decimal::from(close),
+                close: Decimal::from(close),
+                volume: 1_000_000,
+                score: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_combinations_skip_invalid() {
+        let grid = ParameterGrid {
+            strategy: StrategyGrid::SmaCrossover {
+                fast: vec![5, 10, 20],
+                slow: vec![10, 20],
+            },
+            stop_loss_pct: vec![5.0, 10.0],
+            max_hold_bars: vec![],
+        };
+        let combos = grid.combinations(&BacktestConfig::default()).unwrap();
+        // 5/10, 5/20 and 10/20 are valid, each with two stops
+        assert_eq!(combos.len(), 6);
+        assert!(combos.iter().all(|(_, c)| c.max_hold_bars.is_none()));
+
+        let too_large = ParameterGrid {
+            strategy: StrategyGrid::SmaCrossover {
+                fast: (1..=30).collect(),
+                slow: (2..=31).collect(),
+            },
+            stop_loss_pct: vec![],
+            max_hold_bars: vec![],
+        };
+        assert_eq!(
+            too_large.combinations(&BacktestConfig::default()),
+            Err(OptimizationError::GridTooLarge(900))
+        );
+
+        let too_many_values = ParameterGrid {
+            strategy: StrategyGrid::RsiReversion {
+                period: vec![14],
+                entry: vec![30.0; 5_000],
+                exit: vec![70.0; 5_000],
+            },
+            stop_loss_pct: vec![],
+            max_hold_bars: vec![],
+        };
+        assert_eq!(
+            too_many_values.combinations(&BacktestConfig::default()),
+            Err(OptimizationError::TooManyValues)
+        );
+
+        let empty = ParameterGrid {
+            strategy: StrategyGrid::SmaCrossover {
+                fast: vec![20],
+                slow: vec![10],
+            },
+            stop_loss_pct: vec![],
+            max_hold_bars: vec![],
+        };
+        assert_eq!(
+            empty.combinations(&BacktestConfig::default()),
+            Err(OptimizationError::EmptyGrid)
+        );
+    }
+
+    #[test]
+    fn test_evaluate_and_rank() {
+        let mut closes: Vec<i64> = (0..30).map(|i| 100 + i * 2).collect();
+        closes.extend((0..15).map(|i| 160 - i * 3));
+        let bars = bars(&closes);
+        let split = split_index(bars.len(), 66.0).unwrap();
+        assert_eq!(split, 30);
+
+        let config = BacktestConfig::default();
+        let costs = TransactionCostModel::default();
+        let mut results: Vec<CombinationResult> = [(2, 5), (3, 10)]
+            .into_iter()
+            .map(|(fast, slow)| {
+                let strategy = BacktestStrategy::SmaCrossover { fast, slow };
+                evaluate_combination(&bars, split, &strategy, &config, &costs).unwrap()
+            })
+            .collect();
+        rank_results(&mut results, OptimizationObjective::TotalReturn);
+
+        // The faster pair enters the uptrend sooner
+        assert_eq!(
+            results[0].strategy,
+            BacktestStrategy::SmaCrossover { fast: 2, slow: 5 }
+        );
+        assert!(results[0].in_sample.total_return_pct > results[1].in_sample.total_return_pct);
+        // The out-of-sample run starts warmed up and only sees the decline
+        let oos = results[0].out_of_sample.as_ref().unwrap();
+        assert!(oos.total_return_pct <= 0.0);
+        assert!(oos.buy_and_hold_return_pct < 0.0);
+
+        assert_eq!(split_index(100, 95.0), Err(OptimizationError::InvalidSplit));
+    }
+}
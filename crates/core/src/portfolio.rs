@@ -0,0 +1,353 @@
+//! Portfolio holdings and metrics
+//!
+//! Holdings are replayed from the buy and sell transactions the user
+//! records. Cost basis follows the average-cost method IDX brokers report:
+//! buys add their cost including fees to the basis, sells release basis at
+//! the average cost and realize the difference net of their own fees.
+//! Valuation marks the holdings to the latest close and weights the
+//! composite scores and sectors by market value.
+
+use crate::idx::LOT_SIZE;
+use crate::risk_budget::UNKNOWN_SECTOR;
+use crate::transaction_costs::TradeSide;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A recorded buy or sell
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortfolioTransaction {
+    pub symbol: String,
+    pub side: TradeSide,
+    pub lots: i64,
+    pub price: Decimal,
+    pub fees: Decimal,
+    pub executed_at: DateTime<Utc>,
+}
+
+impl PortfolioTransaction {
+    pub fn shares(&self) -> i64 {
+        self.lots * LOT_SIZE
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PortfolioError {
+    #[error("{symbol}: selling {lots} lots on {date} with only {held} held")]
+    Oversold {
+        symbol: String,
+        lots: i64,
+        held: i64,
+        date: String,
+    },
+}
+
+/// Position in one symbol after replaying its transactions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Holding {
+    pub symbol: String,
+    pub shares: i64,
+    /// Average cost per share, fees included
+    pub average_cost: Decimal,
+    /// Cost of the shares still held
+    pub cost_basis: Decimal,
+    /// Profit from sells, net of fees
+    pub realized_pnl: Decimal,
+}
+
+impl Holding {
+    pub fn lots(&self) -> i64 {
+        self.shares / LOT_SIZE
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.shares > 0
+    }
+}
+
+/// Replay transactions into holdings, one per symbol traded
+///
+/// Transactions are applied in time order. Closed positions are kept for
+/// their realized P/L. Selling more than is held is an error.
+pub fn build_holdings(
+    transactions: &[PortfolioTransaction],
+) -> Result<Vec<Holding>, PortfolioError> {
+    let mut ordered: Vec<&PortfolioTransaction> = transactions.iter().collect();
+    ordered.sort_by_key(|t| t.executed_at);
+
+    let mut holdings: BTreeMap<String, Holding> = BTreeMap::new();
+    for tx in ordered {
+        let holding = holdings
+            .entry(tx.symbol.clone())
+            .or_insert_with(|| Holding {
+                symbol: tx.symbol.clone(),
+                shares: 0,
+                average_cost: Decimal::ZERO,
+                cost_basis: Decimal::ZERO,
+                realized_pnl: Decimal::ZERO,
+            });
+        let shares = tx.shares();
+
+        match tx.side {
+            TradeSide::Buy => {
+                holding.shares += shares;
+                holding.cost_basis += tx.price * Decimal::from(shares) + tx.fees;
+            }
+            TradeSide::Sell => {
+                if shares > holding.shares {
+                    return Err(PortfolioError::Oversold {
+                        symbol: tx.symbol.clone(),
+                        lots: tx.lots,
+                        held: holding.lots(),
+                        date: tx.executed_at.date_naive().to_string(),
+                    });
+                }
+                let released = holding.average_cost * Decimal::from(shares);
+                holding.realized_pnl += tx.price * Decimal::from(shares) - tx.fees - released;
+                holding.shares -= shares;
+                holding.cost_basis = if holding.shares == 0 {
+                    Decimal::ZERO
+                } else {
+                    holding.cost_basis - released
+                };
+            }
+        }
+        holding.average_cost = if holding.shares > 0 {
+            holding.cost_basis / Decimal::from(holding.shares)
+        } else {
+            Decimal::ZERO
+        };
+    }
+
+    Ok(holdings.into_values().collect())
+}
+
+/// Market data for valuing a holding
+#[derive(Debug, Clone, Default)]
+pub struct HoldingQuote {
+    pub price: Option<Decimal>,
+    pub sector: Option<String>,
+    pub composite_score: Option<Decimal>,
+}
+
+/// An open holding marked to market
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HoldingValuation {
+    pub symbol: String,
+    pub sector: String,
+    pub shares: i64,
+    pub lots: i64,
+    pub average_cost: Decimal,
+    pub cost_basis: Decimal,
+    /// Latest close; the average cost when there is no price yet
+    pub price: Decimal,
+    pub market_value: Decimal,
+    pub unrealized_pnl: Decimal,
+    pub unrealized_pnl_pct: Option<Decimal>,
+    pub realized_pnl: Decimal,
+    /// Share of the portfolio's market value (%)
+    pub weight_pct: Decimal,
+    pub composite_score: Option<Decimal>,
+}
+
+/// Market value held in one sector
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SectorExposure {
+    pub sector: String,
+    pub market_value: Decimal,
+    pub weight_pct: Decimal,
+    pub symbols: Vec<String>,
+}
+
+/// Portfolio-level metrics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortfolioSummary {
+    pub holdings: Vec<HoldingValuation>,
+    pub cost_basis: Decimal,
+    pub market_value: Decimal,
+    pub unrealized_pnl: Decimal,
+    pub unrealized_pnl_pct: Option<Decimal>,
+    /// Realized P/L of all positions, including closed ones
+    pub realized_pnl: Decimal,
+    /// Composite score weighted by market value, over holdings with a score
+    pub weighted_composite_score: Option<Decimal>,
+    /// Largest exposure first
+    pub sector_exposure: Vec<SectorExposure>,
+}
+
+fn pct(part: Decimal, whole: Decimal) -> Option<Decimal> {
+    (!whole.is_zero()).then(|| (part / whole * dec!(100)).round_dp(2))
+}
+
+/// Value holdings at their quotes and aggregate the portfolio metrics
+pub fn value_portfolio(
+    holdings: &[Holding],
+    quotes: &BTreeMap<String, HoldingQuote>,
+) -> PortfolioSummary {
+    let realized_pnl = holdings.iter().map(|h| h.realized_pnl).sum();
+    let empty = HoldingQuote::default();
+
+    let mut valuations: Vec<HoldingValuation> = holdings
+        .iter()
+        .filter(|h| h.is_open())
+        .map(|h| {
+            let quote = quotes.get(&h.symbol).unwrap_or(&empty);
+            let price = quote.price.unwrap_or(h.average_cost);
+            let market_value = price * Decimal::from(h.shares);
+            let unrealized_pnl = market_value - h.cost_basis;
+            HoldingValuation {
+                symbol: h.symbol.clone(),
+                sector: quote
+                    .sector
+                    .clone()
+                    .unwrap_or_else(|| UNKNOWN_SECTOR.to_string()),
+                shares: h.shares,
+                lots: h.lots(),
+                average_cost: h.average_cost.round_dp(4),
+                cost_basis: h.cost_basis.round_dp(2),
+                price,
+                market_value,
+                unrealized_pnl: unrealized_pnl.round_dp(2),
+                unrealized_pnl_pct: pct(unrealized_pnl, h.cost_basis),
+                realized_pnl: h.realized_pnl.round_dp(2),
+                weight_pct: Decimal::ZERO,
+                composite_score: quote.composite_score,
+            }
+        })
+        .collect();
+
+    let market_value: Decimal = valuations.iter().map(|v| v.market_value).sum();
+    let cost_basis: Decimal = valuations.iter().map(|v| v.cost_basis).sum();
+    let unrealized_pnl = market_value - cost_basis;
+
+    let mut sectors: BTreeMap<String, SectorExposure> = BTreeMap::new();
+    let (mut scored_value, mut score_sum) = (Decimal::ZERO, Decimal::ZERO);
+    for valuation in &mut valuations {
+        valuation.weight_pct = pct(valuation.market_value, market_value).unwrap_or_default();
+        if let Some(score) = valuation.composite_score {
+            scored_value += valuation.market_value;
+            score_sum += score * valuation.market_value;
+        }
+        let exposure = sectors
+            .entry(valuation.sector.clone())
+            .or_insert_with(|| SectorExposure {
+                sector: valuation.sector.clone(),
+                market_value: Decimal::ZERO,
+                weight_pct: Decimal::ZERO,
+                symbols: Vec::new(),
+            });
+        exposure.market_value += valuation.market_value;
+        exposure.symbols.push(valuation.symbol.clone());
+    }
+
+    let mut sector_exposure: Vec<SectorExposure> = sectors
+        .into_values()
+        .map(|mut exposure| {
+            exposure.weight_pct = pct(exposure.market_value, market_value).unwrap_or_default();
+            exposure
+        })
+        .collect();
+    sector_exposure.sort_by_key(|e| std::cmp::Reverse(e.market_value));
+    valuations.sort_by_key(|v| std::cmp::Reverse(v.market_value));
+
+    PortfolioSummary {
+        holdings: valuations,
+        cost_basis,
+        market_value,
+        unrealized_pnl,
+        unrealized_pnl_pct: pct(unrealized_pnl, cost_basis),
+        realized_pnl,
+        weighted_composite_score: (!scored_value.is_zero())
+            .then(|| (score_sum / scored_value).round_dp(2)),
+        sector_exposure,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tx(
+        symbol: &str,
+        side: TradeSide,
+        day: u32,
+        lots: i64,
+        price: Decimal,
+    ) -> PortfolioTransaction {
+        PortfolioTransaction {
+            symbol: symbol.to_string(),
+            side,
+            lots,
+            price,
+            fees: dec!(1000),
+            executed_at: format!("2025-03-{:02}T03:00:00Z", day).parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_average_cost_and_realized_pnl() {
+        let transactions = vec![
+            // Out of order on purpose: replay sorts by time
+            tx("BBCA", TradeSide::Sell, 10, 5, dec!(9500)),
+            tx("BBCA", TradeSide::Buy, 3, 10, dec!(9000)),
+            tx("BBCA", TradeSide::Buy, 5, 10, dec!(9200)),
+        ];
+        let holdings = build_holdings(&transactions).unwrap();
+        assert_eq!(holdings.len(), 1);
+        let bbca = &holdings[0];
+
+        // (1000 × 9000 + 1000 + 1000 × 9200 + 1000) / 2000 = 9101
+        assert_eq!(bbca.average_cost, dec!(9101));
+        assert_eq!(bbca.shares, 1500);
+        assert_eq!(bbca.cost_basis, dec!(13651500));
+        // 500 × (9500 − 9101) − 1000
+        assert_eq!(bbca.realized_pnl, dec!(198500));
+
+        let oversold = build_holdings(&[tx("TLKM", TradeSide::Sell, 3, 1, dec!(3000))]);
+        assert!(matches!(
+            oversold,
+            Err(PortfolioError::Oversold { held: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn test_value_portfolio() {
+        let holdings = build_holdings(&[
+            tx("BBCA", TradeSide::Buy, 3, 10, dec!(9000)),
+            tx("BMRI", TradeSide::Buy, 3, 20, dec!(6000)),
+            tx("TLKM", TradeSide::Buy, 3, 10, dec!(3000)),
+            tx("TLKM", TradeSide::Sell, 4, 10, dec!(3100)),
+        ])
+        .unwrap();
+
+        let quote = |price: Decimal, sector: &str, score: Option<Decimal>| HoldingQuote {
+            price: Some(price),
+            sector: Some(sector.to_string()),
+            composite_score: score,
+        };
+        let quotes = BTreeMap::from([
+            (
+                "BBCA".to_string(),
+                quote(dec!(10000), "Financials", Some(dec!(80))),
+            ),
+            (
+                "BMRI".to_string(),
+                quote(dec!(5000), "Financials", Some(dec!(60))),
+            ),
+        ]);
+        let summary = value_portfolio(&holdings, &quotes);
+
+        // TLKM is closed and only contributes realized P/L
+        assert_eq!(summary.holdings.len(), 2);
+        assert_eq!(summary.market_value, dec!(20000000));
+        assert_eq!(summary.realized_pnl, dec!(98000));
+        assert_eq!(summary.holdings[0].weight_pct, dec!(50));
+        // Equal market values: plain average of 80 and 60
+        assert_eq!(summary.weighted_composite_score, Some(dec!(70)));
+        assert_eq!(summary.sector_exposure.len(), 1);
+        assert_eq!(summary.sector_exposure[0].weight_pct, dec!(100));
+        assert_eq!(summary.sector_exposure[0].symbols, vec!["BBCA", "BMRI"]);
+    }
+}
@@ -0,0 +1,193 @@
+//! Market regime detection
+//!
+//! Classifies the backdrop for Indonesian equities from the composite index
+//! trend and the macro picture:
+//! - IHSG change over the lookback window
+//! - Bank Indonesia policy-rate trend (easing supports equities)
+//! - Rupiah trend against the dollar (a weakening rupiah drives foreign outflows)
+//! - Inflation against the BI target band
+
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+/// Upper end of Bank Indonesia's inflation target band (2.5% ± 1%)
+const INFLATION_TARGET_CEILING: Decimal = dec!(3.5);
+
+/// Minimum policy-rate move (percentage points) treated as a trend
+const POLICY_RATE_STEP: Decimal = dec!(0.25);
+
+/// Direction of the central bank policy rate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyRateTrend {
+    Easing,
+    Holding,
+    Tightening,
+}
+
+impl PolicyRateTrend {
+    /// Trend from the rate change over the lookback window (percentage points)
+    #[must_use]
+    pub fn from_change(change: Decimal) -> Self {
+        if change <= -POLICY_RATE_STEP {
+            Self::Easing
+        } else if change >= POLICY_RATE_STEP {
+            Self::Tightening
+        } else {
+            Self::Holding
+        }
+    }
+}
+
+/// Market regime
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MarketRegime {
+    RiskOn,
+    Neutral,
+    RiskOff,
+}
+
+/// Inputs to regime classification; missing inputs count as neutral
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RegimeInput {
+    /// IHSG change over the lookback window (%)
+    pub index_change_pct: Option<Decimal>,
+    pub policy_rate_trend: Option<PolicyRateTrend>,
+    /// USD/IDR change over the lookback window (%, positive = weaker rupiah)
+    pub usd_idr_change_pct: Option<Decimal>,
+    /// Latest year-over-year inflation (%)
+    pub inflation_yoy: Option<Decimal>,
+}
+
+/// Regime classification with the factors behind it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegimeAssessment {
+    pub regime: MarketRegime,
+    /// Sum of factor scores; positive favours risk-on
+    pub score: i32,
+    pub factors: Vec<String>,
+}
+
+/// Classify the market regime
+#[must_use]
+pub fn detect_regime(input: &RegimeInput) -> RegimeAssessment {
+    let mut score = 0;
+    let mut factors = Vec::new();
+
+    if let Some(change) = input.index_change_pct {
+        if change >= dec!(5) {
+            score += 2;
+            factors.push(format!("IHSG uptrend (+{change}%)"));
+        } else if change > Decimal::ZERO {
+            score += 1;
+        } else if change <= dec!(-5) {
+            score -= 2;
+            factors.push(format!("IHSG downtrend ({change}%)"));
+        } else if change < Decimal::ZERO {
+            score -= 1;
+        }
+    }
+
+    match input.policy_rate_trend {
+        Some(PolicyRateTrend::Easing) => {
+            score += 1;
+            factors.push("Bank Indonesia easing".to_string());
+        }
+        Some(PolicyRateTrend::Tightening) => {
+            score -= 1;
+            factors.push("Bank Indonesia tightening".to_string());
+        }
+        Some(PolicyRateTrend::Holding) | None => {}
+    }
+
+    if let Some(change) = input.usd_idr_change_pct {
+        if change >= dec!(3) {
+            score -= 1;
+            factors.push(format!("Rupiah weakening ({change}% vs USD)"));
+        } else if change <= dec!(-2) {
+            score += 1;
+            factors.push(format!("Rupiah strengthening ({change}% vs USD)"));
+        }
+    }
+
+    if let Some(inflation) = input.inflation_yoy {
+        if inflation > INFLATION_TARGET_CEILING {
+            score -= 1;
+            factors.push(format!("Inflation ({inflation}%) above target band"));
+        }
+    }
+
+    let regime = if score >= 2 {
+        MarketRegime::RiskOn
+    } else if score <= -2 {
+        MarketRegime::RiskOff
+    } else {
+        MarketRegime::Neutral
+    };
+
+    RegimeAssessment {
+        regime,
+        score,
+        factors,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy_rate_trend() {
+        assert_eq!(
+            PolicyRateTrend::from_change(dec!(-0.5)),
+            PolicyRateTrend::Easing
+        );
+        assert_eq!(
+            PolicyRateTrend::from_change(dec!(0)),
+            PolicyRateTrend::Holding
+        );
+        assert_eq!(
+            PolicyRateTrend::from_change(dec!(0.25)),
+            PolicyRateTrend::Tightening
+        );
+    }
+
+    #[test]
+    fn test_policy_rate_trend_tips_regime() {
+        let base = RegimeInput {
+            index_change_pct: Some(dec!(2)),
+            ..Default::default()
+        };
+        assert_eq!(detect_regime(&base).regime, MarketRegime::Neutral);
+
+        let easing = RegimeInput {
+            policy_rate_trend: Some(PolicyRateTrend::Easing),
+            ..base.clone()
+        };
+        let result = detect_regime(&easing);
+        assert_eq!(result.regime, MarketRegime::RiskOn);
+        assert!(result.factors.iter().any(|f| f.contains("easing")));
+    }
+
+    #[test]
+    fn test_risk_off() {
+        let input = RegimeInput {
+            index_change_pct: Some(dec!(-6)),
+            policy_rate_trend: Some(PolicyRateTrend::Tightening),
+            usd_idr_change_pct: Some(dec!(4)),
+            inflation_yoy: Some(dec!(4.2)),
+        };
+        let result = detect_regime(&input);
+        assert_eq!(result.regime, MarketRegime::RiskOff);
+        assert_eq!(result.score, -5);
+    }
+
+    #[test]
+    fn test_no_data_is_neutral() {
+        let result = detect_regime(&RegimeInput::default());
+        assert_eq!(result.regime, MarketRegime::Neutral);
+        assert!(result.factors.is_empty());
+    }
+}
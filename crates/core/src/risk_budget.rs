@@ -0,0 +1,325 @@
+//! Portfolio risk-budget checks
+//!
+//! Evaluates open positions against configured limits:
+//! - Maximum weight of a single position
+//! - Maximum weight of a single sector
+//! - Aggregate ATR-based risk versus the overall risk budget
+
+use crate::idx::{affordable_lots, lots_for_risk};
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Risk limits, all expressed as percent of portfolio equity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskBudgetConfig {
+    /// Total capital including cash; market value of positions is used when unset
+    pub capital: Option<Decimal>,
+    pub max_position_weight_pct: f64,
+    pub max_sector_weight_pct: f64,
+    /// Maximum aggregate loss if every position moves `atr_multiple` ATRs against it
+    pub max_portfolio_risk_pct: f64,
+    pub atr_multiple: f64,
+}
+
+impl Default for RiskBudgetConfig {
+    fn default() -> Self {
+        Self {
+            capital: None,
+            max_position_weight_pct: 20.0,
+            max_sector_weight_pct: 40.0,
+            max_portfolio_risk_pct: 6.0,
+            atr_multiple: 2.0,
+        }
+    }
+}
+
+/// An open position to evaluate
+#[derive(Debug, Clone)]
+pub struct PositionRiskInput {
+    pub symbol: String,
+    pub sector: Option<String>,
+    /// Number of shares
+    pub quantity: i64,
+    pub price: Decimal,
+    /// Latest ATR; positions without one contribute no volatility risk
+    pub atr: Option<Decimal>,
+}
+
+/// Per-position exposure
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PositionRisk {
+    pub symbol: String,
+    pub sector: String,
+    pub market_value: f64,
+    pub weight_pct: f64,
+    /// Loss at `atr_multiple` ATRs as percent of equity
+    pub risk_pct: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskBreachKind {
+    PositionWeight,
+    SectorConcentration,
+    PortfolioRisk,
+}
+
+/// A limit exceeded by the portfolio
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskBreach {
+    pub kind: RiskBreachKind,
+    /// Symbol, sector, or "portfolio"
+    pub subject: String,
+    pub value: f64,
+    pub limit: f64,
+    pub message: String,
+}
+
+/// Result of a risk-budget evaluation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskBudgetReport {
+    pub equity: f64,
+    pub positions: Vec<PositionRisk>,
+    pub sector_weights: BTreeMap<String, f64>,
+    pub total_risk_pct: f64,
+    pub risk_budget_used_pct: f64,
+    pub breaches: Vec<RiskBreach>,
+}
+
+impl RiskBudgetReport {
+    pub fn is_within_budget(&self) -> bool {
+        self.breaches.is_empty()
+    }
+}
+
+pub(crate) const UNKNOWN_SECTOR: &str = "Unknown";
+
+/// Evaluate positions against the risk budget
+pub fn evaluate_risk_budget(
+    positions: &[PositionRiskInput],
+    config: &RiskBudgetConfig,
+) -> RiskBudgetReport {
+    let market_values: Vec<f64> = positions
+        .iter()
+        .map(|p| {
+            (p.price * Decimal::from(p.quantity))
+                .to_f64()
+                .unwrap_or(0.0)
+        })
+        .collect();
+    let invested: f64 = market_values.iter().sum();
+    let equity = config
+        .capital
+        .and_then(|c| c.to_f64())
+        .filter(|c| *c > 0.0)
+        .unwrap_or(invested);
+
+    let pct_of_equity = |value: f64| {
+        if equity > 0.0 {
+            value / equity * 100.0
+        } else {
+            0.0
+        }
+    };
+
+    let mut sector_weights: BTreeMap<String, f64> = BTreeMap::new();
+    let mut breaches = Vec::new();
+    let mut total_risk_pct = 0.0;
+
+    let position_risks: Vec<PositionRisk> = positions
+        .iter()
+        .zip(&market_values)
+        .map(|(position, market_value)| {
+            let sector = position
+                .sector
+                .clone()
+                .unwrap_or_else(|| UNKNOWN_SECTOR.to_string());
+            let weight_pct = pct_of_equity(*market_value);
+            let risk_pct = position.atr.and_then(|atr| {
+                let loss = (atr * Decimal::from(position.quantity)).to_f64()? * config.atr_multiple;
+                Some(pct_of_equity(loss))
+            });
+
+            *sector_weights.entry(sector.clone()).or_default() += weight_pct;
+            total_risk_pct += risk_pct.unwrap_or(0.0);
+
+            if weight_pct > config.max_position_weight_pct {
+                breaches.push(RiskBreach {
+                    kind: RiskBreachKind::PositionWeight,
+                    subject: position.symbol.clone(),
+                    value: weight_pct,
+                    limit: config.max_position_weight_pct,
+                    message: format!(
+                        "{} is {:.1}% of the portfolio (limit {:.1}%)",
+                        position.symbol, weight_pct, config.max_position_weight_pct
+                    ),
+                });
+            }
+
+            PositionRisk {
+                symbol: position.symbol.clone(),
+                sector,
+                market_value: *market_value,
+                weight_pct,
+                risk_pct,
+            }
+        })
+        .collect();
+
+    for (sector, weight) in &sector_weights {
+        if *weight > config.max_sector_weight_pct {
+            breaches.push(RiskBreach {
+                kind: RiskBreachKind::SectorConcentration,
+                subject: sector.clone(),
+                value: *weight,
+                limit: config.max_sector_weight_pct,
+                message: format!(
+                    "{} sector is {:.1}% of the portfolio (limit {:.1}%)",
+                    sector, weight, config.max_sector_weight_pct
+                ),
+            });
+        }
+    }
+
+    if total_risk_pct > config.max_portfolio_risk_pct {
+        breaches.push(RiskBreach {
+            kind: RiskBreachKind::PortfolioRisk,
+            subject: "portfolio".to_string(),
+            value: total_risk_pct,
+            limit: config.max_portfolio_risk_pct,
+            message: format!(
+                "A {:.1} ATR move against all positions would cost {:.1}% of equity (budget {:.1}%)",
+                config.atr_multiple, total_risk_pct, config.max_portfolio_risk_pct
+            ),
+        });
+    }
+
+    let risk_budget_used_pct = if config.max_portfolio_risk_pct > 0.0 {
+        total_risk_pct / config.max_portfolio_risk_pct * 100.0
+    } else {
+        0.0
+    };
+
+    RiskBudgetReport {
+        equity,
+        positions: position_risks,
+        sector_weights,
+        total_risk_pct,
+        risk_budget_used_pct,
+        breaches,
+    }
+}
+
+/// Whole lots of a new position entered at `entry` with a stop at `stop`
+///
+/// Sized so the position stays within the position-weight limit and losing
+/// it at the stop costs no more than the whole risk budget. Needs the
+/// configured capital; `None` without it.
+pub fn suggested_position_lots(
+    config: &RiskBudgetConfig,
+    entry: Decimal,
+    stop: Decimal,
+) -> Option<i64> {
+    let capital = config.capital.filter(|c| *c > Decimal::ZERO)?;
+    let pct = |value: f64| Decimal::from_f64(value / 100.0).map(|p| capital * p);
+
+    let by_weight = affordable_lots(pct(config.max_position_weight_pct)?, entry);
+    let by_risk = lots_for_risk(pct(config.max_portfolio_risk_pct)?, entry, stop);
+    Some(by_weight.min(by_risk))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn position(symbol: &str, sector: &str, quantity: i64, price: Decimal) -> PositionRiskInput {
+        PositionRiskInput {
+            symbol: symbol.to_string(),
+            sector: Some(sector.to_string()),
+            quantity,
+            price,
+            atr: Some(price * dec!(0.02)),
+        }
+    }
+
+    #[test]
+    fn test_balanced_portfolio_within_budget() {
+        let positions = vec![
+            position("BBCA", "Financials", 1000, dec!(1000)),
+            position("TLKM", "Infrastructure", 1000, dec!(1000)),
+            position("UNVR", "Consumer", 1000, dec!(1000)),
+            position("ASII", "Industrials", 1000, dec!(1000)),
+            position("ADRO", "Energy", 1000, dec!(1000)),
+            position("ICBP", "Consumer Staples", 1000, dec!(1000)),
+        ];
+
+        let report = evaluate_risk_budget(&positions, &RiskBudgetConfig::default());
+        assert!(report.is_within_budget(), "{:?}", report.breaches);
+        assert!((report.total_risk_pct - 4.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_concentration_breaches() {
+        let positions = vec![
+            position("BBCA", "Financials", 3000, dec!(1000)),
+            position("BBRI", "Financials", 1000, dec!(1000)),
+            position("TLKM", "Infrastructure", 1000, dec!(1000)),
+        ];
+
+        let report = evaluate_risk_budget(&positions, &RiskBudgetConfig::default());
+        let kinds: Vec<RiskBreachKind> = report.breaches.iter().map(|b| b.kind).collect();
+
+        assert!(kinds.contains(&RiskBreachKind::PositionWeight));
+        assert!(kinds.contains(&RiskBreachKind::SectorConcentration));
+        assert!((report.sector_weights["Financials"] - 80.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_atr_risk_uses_configured_capital() {
+        let mut volatile = position("GOTO", "Technology", 10_000, dec!(100));
+        volatile.atr = Some(dec!(40));
+
+        let config = RiskBudgetConfig {
+            capital: Some(dec!(10_000_000)),
+            ..Default::default()
+        };
+        let report = evaluate_risk_budget(&[volatile], &config);
+
+        // 10,000 shares * 40 ATR * 2 = 800,000 = 8% of capital, while weight is only 10%
+        assert!((report.total_risk_pct - 8.0).abs() < 1e-9);
+        assert!(report
+            .breaches
+            .iter()
+            .any(|b| b.kind == RiskBreachKind::PortfolioRisk));
+        assert!(!report
+            .breaches
+            .iter()
+            .any(|b| b.kind == RiskBreachKind::PositionWeight));
+    }
+
+    #[test]
+    fn test_suggested_position_lots() {
+        let config = RiskBudgetConfig {
+            capital: Some(dec!(100_000_000)),
+            ..Default::default()
+        };
+        // Weight: 20,000,000 / 927,500 per lot = 21 lots
+        // Risk: 6,000,000 / 25,000 per lot = 240 lots
+        assert_eq!(
+            suggested_position_lots(&config, dec!(9275), dec!(9025)),
+            Some(21)
+        );
+        // A wide stop makes risk the binding limit: 6,000,000 / 400,000 = 15
+        assert_eq!(
+            suggested_position_lots(&config, dec!(9275), dec!(5275)),
+            Some(15)
+        );
+        assert_eq!(
+            suggested_position_lots(&RiskBudgetConfig::default(), dec!(9275), dec!(9025)),
+            None
+        );
+    }
+}
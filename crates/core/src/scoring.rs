@@ -1,4 +1,16 @@
 //! Scoring engine for combining technical, fundamental, sentiment, and ML scores
+//!
+//! Also holds the staleness decay shared by the score engines: once an input
+//! is older than its freshness threshold, the component it drives is pulled
+//! toward the neutral 50 in proportion to how stale it is, and the breakdown
+//! records it, so outdated broker flow or financials stop scoring confidently.
+
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+/// Score of a component with nothing to say
+pub const NEUTRAL_SCORE: Decimal = dec!(50);
 
 /// Weights for composite score calculation
 #[derive(Debug, Clone)]
@@ -34,6 +46,91 @@ pub fn calculate_composite_score(
         + ml * weights.ml
 }
 
+/// How long a score input stays fresh, and when it stops counting at all
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StalenessPolicy {
+    /// Age in days up to which the input is scored at full weight
+    pub fresh_days: i64,
+    /// Age in days at which its component has decayed to neutral
+    pub neutral_days: i64,
+}
+
+impl StalenessPolicy {
+    /// Broker summaries: a weekend old is still fresh
+    pub const BROKER_FLOW: Self = Self {
+        fresh_days: 3,
+        neutral_days: 8,
+    };
+
+    /// Financials, by publication date: a quarter plus the filing deadline
+    pub const FINANCIALS: Self = Self {
+        fresh_days: 150,
+        neutral_days: 365,
+    };
+
+    /// Share (0-1) of a component's distance from neutral kept at `age_days`
+    ///
+    /// 1 while fresh, falling linearly to 0 at `neutral_days`.
+    pub fn retained(&self, age_days: i64) -> Decimal {
+        if age_days <= self.fresh_days {
+            return Decimal::ONE;
+        }
+        if age_days >= self.neutral_days || self.neutral_days <= self.fresh_days {
+            return Decimal::ZERO;
+        }
+        (Decimal::from(self.neutral_days - age_days)
+            / Decimal::from(self.neutral_days - self.fresh_days))
+        .round_dp(4)
+    }
+
+    /// Days of decay at `age_days`, 0 while fresh and constant once neutral
+    ///
+    /// Changes exactly when [`Self::retained`] does, so it tells whether a
+    /// score computed earlier has decayed since.
+    pub fn decay_days(&self, age_days: i64) -> i64 {
+        age_days.clamp(self.fresh_days, self.neutral_days.max(self.fresh_days)) - self.fresh_days
+    }
+
+    /// Decay noted for `input` at `age_days`; `None` while it is fresh
+    pub fn check(&self, input: &str, age_days: i64) -> Option<StaleInput> {
+        (age_days > self.fresh_days).then(|| StaleInput {
+            input: input.to_string(),
+            age_days,
+            fresh_days: self.fresh_days,
+            retained: self.retained(age_days),
+        })
+    }
+}
+
+/// An input older than its freshness threshold, as noted in a breakdown
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StaleInput {
+    /// Input name, e.g. `broker_flow`
+    pub input: String,
+    pub age_days: i64,
+    pub fresh_days: i64,
+    /// Share of the component's distance from neutral that was kept
+    pub retained: Decimal,
+}
+
+impl StaleInput {
+    /// `score` pulled toward neutral by the decay
+    pub fn apply(&self, score: Decimal) -> Decimal {
+        NEUTRAL_SCORE + (score - NEUTRAL_SCORE) * self.retained
+    }
+
+    /// Breakdown signal describing the decay
+    pub fn signal(&self, label: &str) -> String {
+        format!(
+            "{} {} days old (fresh for {}), {}% of its signal kept",
+            label,
+            self.age_days,
+            self.fresh_days,
+            (self.retained * dec!(100)).round_dp(0)
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -52,4 +149,36 @@ mod tests {
         // 80*0.4 + 70*0.4 + 60*0.1 + 50*0.1 = 32 + 28 + 6 + 5 = 71
         assert!((score - 71.0).abs() < f64::EPSILON);
     }
+
+    #[test]
+    fn test_staleness_retained() {
+        let policy = StalenessPolicy {
+            fresh_days: 3,
+            neutral_days: 8,
+        };
+        assert_eq!(policy.retained(0), Decimal::ONE);
+        assert_eq!(policy.retained(3), Decimal::ONE);
+        assert_eq!(policy.retained(4), dec!(0.8));
+        assert_eq!(policy.retained(8), Decimal::ZERO);
+        assert_eq!(policy.retained(30), Decimal::ZERO);
+        assert_eq!(policy.decay_days(2), 0);
+        assert_eq!(policy.decay_days(5), 2);
+        assert_eq!(policy.decay_days(8), 5);
+        assert_eq!(policy.decay_days(30), 5);
+        assert!(policy.check("broker_flow", 3).is_none());
+    }
+
+    #[test]
+    fn test_stale_input_decays_toward_neutral() {
+        let stale = StalenessPolicy::BROKER_FLOW
+            .check("broker_flow", 4)
+            .unwrap();
+        assert_eq!(stale.apply(dec!(80)), dec!(74));
+        assert_eq!(stale.apply(dec!(20)), dec!(26));
+        assert_eq!(stale.apply(NEUTRAL_SCORE), NEUTRAL_SCORE);
+        assert_eq!(
+            stale.signal("Broker data"),
+            "Broker data 4 days old (fresh for 3), 80% of its signal kept"
+        );
+    }
 }
@@ -0,0 +1,433 @@
+//! Stock screener
+//!
+//! A screen is a tree of filters over each stock's latest scores,
+//! financials and indicator values, combined with `all`, `any` and `not`.
+//! A filter on a value the stock lacks (no financials, too little price
+//! history) does not match. The same evaluation backs the screen endpoint
+//! and screen-based alerts.
+
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Deepest nesting of `all`/`any`/`not` accepted
+pub const MAX_SCREEN_DEPTH: usize = 8;
+
+/// Latest values of one stock that screens run against
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScreenCandidate {
+    pub symbol: String,
+    pub sector: Option<String>,
+    pub composite_score: Option<Decimal>,
+    pub technical_score: Option<Decimal>,
+    pub fundamental_score: Option<Decimal>,
+    pub close: Option<Decimal>,
+    pub rsi: Option<Decimal>,
+    /// Latest volume against its 20-session average
+    pub rvol: Option<Decimal>,
+    pub pe_ratio: Option<Decimal>,
+    pub pb_ratio: Option<Decimal>,
+    pub roe: Option<Decimal>,
+    /// Median positive P/E of the stock's sector
+    pub sector_median_pe: Option<Decimal>,
+    /// Wyckoff phase in snake case, e.g. "accumulation"
+    pub wyckoff_phase: Option<String>,
+    /// Latest values of the screen owner's custom indicators, by name
+    #[serde(default)]
+    pub custom_indicators: BTreeMap<String, Decimal>,
+}
+
+/// One screen condition
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ScreenFilter {
+    CompositeScoreAbove {
+        value: Decimal,
+    },
+    CompositeScoreBelow {
+        value: Decimal,
+    },
+    TechnicalScoreAbove {
+        value: Decimal,
+    },
+    FundamentalScoreAbove {
+        value: Decimal,
+    },
+    RsiBelow {
+        value: Decimal,
+    },
+    RsiAbove {
+        value: Decimal,
+    },
+    RvolAbove {
+        value: Decimal,
+    },
+    PeBelow {
+        value: Decimal,
+    },
+    /// P/E below the sector median times `ratio` (default 1)
+    PeBelowSector {
+        #[serde(default)]
+        ratio: Option<Decimal>,
+    },
+    PbBelow {
+        value: Decimal,
+    },
+    RoeAbove {
+        value: Decimal,
+    },
+    PriceAbove {
+        value: Decimal,
+    },
+    PriceBelow {
+        value: Decimal,
+    },
+    /// Sector is one of `sectors` (case-insensitive)
+    Sector {
+        sectors: Vec<String>,
+    },
+    WyckoffPhase {
+        phase: String,
+    },
+    /// A custom indicator of the screen owner, by name
+    CustomIndicatorAbove {
+        name: String,
+        value: Decimal,
+    },
+    CustomIndicatorBelow {
+        name: String,
+        value: Decimal,
+    },
+    All {
+        filters: Vec<ScreenFilter>,
+    },
+    Any {
+        filters: Vec<ScreenFilter>,
+    },
+    Not {
+        filter: Box<ScreenFilter>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ScreenError {
+    #[error("{0} needs at least one filter")]
+    EmptyGroup(&'static str),
+    #[error("sector filter needs at least one sector")]
+    EmptySectors,
+    #[error("filters nest deeper than {MAX_SCREEN_DEPTH} levels")]
+    TooDeep,
+}
+
+fn above(value: Option<Decimal>, threshold: Decimal) -> bool {
+    value.is_some_and(|v| v > threshold)
+}
+
+fn below(value: Option<Decimal>, threshold: Decimal) -> bool {
+    value.is_some_and(|v| v < threshold)
+}
+
+impl ScreenFilter {
+    /// Check the filter tree is well formed
+    pub fn validate(&self) -> Result<(), ScreenError> {
+        self.validate_at(1)
+    }
+
+    fn validate_at(&self, depth: usize) -> Result<(), ScreenError> {
+        if depth > MAX_SCREEN_DEPTH {
+            return Err(ScreenError::TooDeep);
+        }
+        match self {
+            ScreenFilter::All { filters } if filters.is_empty() => {
+                Err(ScreenError::EmptyGroup("all"))
+            }
+            ScreenFilter::Any { filters } if filters.is_empty() => {
+                Err(ScreenError::EmptyGroup("any"))
+            }
+            ScreenFilter::All { filters } | ScreenFilter::Any { filters } => {
+                filters.iter().try_for_each(|f| f.validate_at(depth + 1))
+            }
+            ScreenFilter::Not { filter } => filter.validate_at(depth + 1),
+            ScreenFilter::Sector { sectors } if sectors.is_empty() => {
+                Err(ScreenError::EmptySectors)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether evaluating needs the close and indicators from price history
+    pub fn needs_price_history(&self) -> bool {
+        match self {
+            ScreenFilter::PriceAbove { .. }
+            | ScreenFilter::PriceBelow { .. }
+            | ScreenFilter::RsiBelow { .. }
+            | ScreenFilter::RsiAbove { .. }
+            | ScreenFilter::RvolAbove { .. }
+            | ScreenFilter::WyckoffPhase { .. }
+            | ScreenFilter::CustomIndicatorAbove { .. }
+            | ScreenFilter::CustomIndicatorBelow { .. } => true,
+            ScreenFilter::All { filters } | ScreenFilter::Any { filters } => {
+                filters.iter().any(ScreenFilter::needs_price_history)
+            }
+            ScreenFilter::Not { filter } => filter.needs_price_history(),
+            _ => false,
+        }
+    }
+
+    /// Names of the custom indicators the filter reads
+    pub fn custom_indicators(&self) -> BTreeSet<&str> {
+        let mut names = BTreeSet::new();
+        self.collect_custom_indicators(&mut names);
+        names
+    }
+
+    fn collect_custom_indicators<'a>(&'a self, names: &mut BTreeSet<&'a str>) {
+        match self {
+            ScreenFilter::CustomIndicatorAbove { name, .. }
+            | ScreenFilter::CustomIndicatorBelow { name, .. } => {
+                names.insert(name.as_str());
+            }
+            ScreenFilter::All { filters } | ScreenFilter::Any { filters } => {
+                for filter in filters {
+                    filter.collect_custom_indicators(names);
+                }
+            }
+            ScreenFilter::Not { filter } => filter.collect_custom_indicators(names),
+            _ => {}
+        }
+    }
+
+    pub fn matches(&self, c: &ScreenCandidate) -> bool {
+        match self {
+            ScreenFilter::CompositeScoreAbove { value } => above(c.composite_score, *value),
+            ScreenFilter::CompositeScoreBelow { value } => below(c.composite_score, *value),
+            ScreenFilter::TechnicalScoreAbove { value } => above(c.technical_score, *value),
+            ScreenFilter::FundamentalScoreAbove { value } => above(c.fundamental_score, *value),
+            ScreenFilter::RsiBelow { value } => below(c.rsi, *value),
+            ScreenFilter::RsiAbove { value } => above(c.rsi, *value),
+            ScreenFilter::RvolAbove { value } => above(c.rvol, *value),
+            ScreenFilter::PeBelow { value } => c
+                .pe_ratio
+                .is_some_and(|pe| pe > Decimal::ZERO && pe < *value),
+            ScreenFilter::PeBelowSector { ratio } => match (c.pe_ratio, c.sector_median_pe) {
+                (Some(pe), Some(median)) => {
+                    pe > Decimal::ZERO && pe < median * ratio.unwrap_or(Decimal::ONE)
+                }
+                _ => false,
+            },
+            ScreenFilter::PbBelow { value } => c
+                .pb_ratio
+                .is_some_and(|pb| pb > Decimal::ZERO && pb < *value),
+            ScreenFilter::RoeAbove { value } => above(c.roe, *value),
+            ScreenFilter::PriceAbove { value } => above(c.close, *value),
+            ScreenFilter::PriceBelow { value } => below(c.close, *value),
+            ScreenFilter::Sector { sectors } => c
+                .sector
+                .as_deref()
+                .is_some_and(|sector| sectors.iter().any(|s| s.eq_ignore_ascii_case(sector))),
+            ScreenFilter::WyckoffPhase { phase } => c
+                .wyckoff_phase
+                .as_deref()
+                .is_some_and(|p| p.eq_ignore_ascii_case(phase)),
+            ScreenFilter::CustomIndicatorAbove { name, value } => {
+                above(c.custom_indicators.get(name).copied(), *value)
+            }
+            ScreenFilter::CustomIndicatorBelow { name, value } => {
+                below(c.custom_indicators.get(name).copied(), *value)
+            }
+            ScreenFilter::All { filters } => filters.iter().all(|f| f.matches(c)),
+            ScreenFilter::Any { filters } => filters.iter().any(|f| f.matches(c)),
+            ScreenFilter::Not { filter } => !filter.matches(c),
+        }
+    }
+}
+
+/// Fill `sector_median_pe` from the positive P/E ratios of each sector
+pub fn attach_sector_median_pe(candidates: &mut [ScreenCandidate]) {
+    let mut by_sector: BTreeMap<String, Vec<Decimal>> = BTreeMap::new();
+    for c in candidates.iter() {
+        if let (Some(sector), Some(pe)) = (&c.sector, c.pe_ratio) {
+            if pe > Decimal::ZERO {
+                by_sector.entry(sector.clone()).or_default().push(pe);
+            }
+        }
+    }
+    let medians: BTreeMap<String, Decimal> = by_sector
+        .into_iter()
+        .map(|(sector, mut pes)| {
+            pes.sort();
+            let mid = pes.len() / 2;
+            let median = if pes.len() % 2 == 0 {
+                (pes[mid - 1] + pes[mid]) / dec!(2)
+            } else {
+                pes[mid]
+            };
+            (sector, median)
+        })
+        .collect();
+    for c in candidates.iter_mut() {
+        c.sector_median_pe = c.sector.as_ref().and_then(|s| medians.get(s).copied());
+    }
+}
+
+/// Candidates passing `filter`, best composite score first
+pub fn run_screen<'a>(
+    candidates: &'a [ScreenCandidate],
+    filter: &ScreenFilter,
+) -> Vec<&'a ScreenCandidate> {
+    let mut matched: Vec<&ScreenCandidate> =
+        candidates.iter().filter(|c| filter.matches(c)).collect();
+    matched.sort_by(|a, b| {
+        b.composite_score
+            .cmp(&a.composite_score)
+            .then_with(|| a.symbol.cmp(&b.symbol))
+    });
+    matched
+}
+
+/// Symbols that entered and left a screen's results between two runs
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScreenDiff {
+    pub entered: Vec<String>,
+    pub left: Vec<String>,
+}
+
+impl ScreenDiff {
+    pub fn is_empty(&self) -> bool {
+        self.entered.is_empty() && self.left.is_empty()
+    }
+}
+
+/// Compare the symbols of two runs, each sorted by symbol in the diff
+pub fn diff_screen_results(previous: &[String], current: &[String]) -> ScreenDiff {
+    let previous: BTreeSet<&String> = previous.iter().collect();
+    let current: BTreeSet<&String> = current.iter().collect();
+    ScreenDiff {
+        entered: current
+            .difference(&previous)
+            .map(|s| s.to_string())
+            .collect(),
+        left: previous
+            .difference(&current)
+            .map(|s| s.to_string())
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(
+        symbol: &str,
+        sector: &str,
+        score: Decimal,
+        pe: Option<Decimal>,
+    ) -> ScreenCandidate {
+        ScreenCandidate {
+            symbol: symbol.to_string(),
+            sector: Some(sector.to_string()),
+            composite_score: Some(score),
+            pe_ratio: pe,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_filter_dsl_parses_and_matches() {
+        let filter: ScreenFilter = serde_json::from_value(serde_json::json!({
+            "type": "all",
+            "filters": [
+                { "type": "composite_score_above", "value": "60" },
+                { "type": "sector", "sectors": ["financials"] },
+                { "type": "not", "filter": { "type": "rsi_above", "value": "70" } }
+            ]
+        }))
+        .unwrap();
+        assert!(filter.validate().is_ok());
+        assert!(filter.needs_price_history());
+
+        let mut bank = candidate("BBRI", "Financials", dec!(72), Some(dec!(12)));
+        assert!(filter.matches(&bank));
+        bank.rsi = Some(dec!(75));
+        assert!(!filter.matches(&bank));
+        // Missing RSI does not match rsi_above, so `not` passes
+        bank.rsi = None;
+        bank.composite_score = Some(dec!(55));
+        assert!(!filter.matches(&bank));
+
+        let empty = ScreenFilter::Any { filters: vec![] };
+        assert_eq!(empty.validate(), Err(ScreenError::EmptyGroup("any")));
+    }
+
+    #[test]
+    fn test_custom_indicator_filters() {
+        let filter: ScreenFilter = serde_json::from_value(serde_json::json!({
+            "type": "any",
+            "filters": [
+                { "type": "custom_indicator_above", "name": "ema_spread", "value": "1" },
+                { "type": "not", "filter": {
+                    "type": "custom_indicator_below", "name": "range_ratio", "value": "3"
+                } }
+            ]
+        }))
+        .unwrap();
+        assert!(filter.needs_price_history());
+        assert_eq!(
+            filter.custom_indicators().into_iter().collect::<Vec<_>>(),
+            vec!["ema_spread", "range_ratio"]
+        );
+
+        let mut stock = candidate("ASII", "Industrials", dec!(60), None);
+        stock
+            .custom_indicators
+            .insert("range_ratio".to_string(), dec!(2));
+        assert!(!filter.matches(&stock));
+        stock
+            .custom_indicators
+            .insert("ema_spread".to_string(), dec!(1.2));
+        assert!(filter.matches(&stock));
+    }
+
+    #[test]
+    fn test_pe_below_sector_and_ranking() {
+        let mut candidates = vec![
+            candidate("BBRI", "Financials", dec!(70), Some(dec!(10))),
+            candidate("BBCA", "Financials", dec!(80), Some(dec!(20))),
+            candidate("BMRI", "Financials", dec!(75), Some(dec!(12))),
+            candidate("ARTO", "Financials", dec!(60), Some(dec!(-5))),
+            candidate("TLKM", "Infrastructure", dec!(65), None),
+        ];
+        attach_sector_median_pe(&mut candidates);
+        assert_eq!(candidates[0].sector_median_pe, Some(dec!(12)));
+
+        let filter = ScreenFilter::PeBelowSector { ratio: None };
+        let symbols: Vec<&str> = run_screen(&candidates, &filter)
+            .iter()
+            .map(|c| c.symbol.as_str())
+            .collect();
+        assert_eq!(symbols, vec!["BBRI"]);
+
+        let loose = ScreenFilter::PeBelowSector {
+            ratio: Some(dec!(1.5)),
+        };
+        let symbols: Vec<&str> = run_screen(&candidates, &loose)
+            .iter()
+            .map(|c| c.symbol.as_str())
+            .collect();
+        assert_eq!(symbols, vec!["BMRI", "BBRI"]);
+    }
+
+    #[test]
+    fn test_diff_screen_results() {
+        let symbols = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let diff = diff_screen_results(
+            &symbols(&["BBRI", "TLKM", "ASII"]),
+            &symbols(&["TLKM", "BMRI", "ASII", "ADRO"]),
+        );
+        assert_eq!(diff.entered, symbols(&["ADRO", "BMRI"]));
+        assert_eq!(diff.left, symbols(&["BBRI"]));
+        assert!(diff_screen_results(&symbols(&["BBRI"]), &symbols(&["BBRI"])).is_empty());
+    }
+}
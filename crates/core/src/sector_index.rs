@@ -0,0 +1,307 @@
+//! Sector indices and rotation
+//!
+//! IDX publishes sector indices, but not with history in our data, so each
+//! sector's index is rebuilt from its members' daily closes: an equal-weight
+//! chain of the members' average daily return, starting at 100.
+//!
+//! Rotation follows a relative rotation graph (RRG). Relative strength is
+//! the sector index over the composite index (IHSG):
+//! - RS-Ratio: relative strength now against the long window ago, ×100
+//! - RS-Momentum: RS-Ratio now against the medium window ago, ×100
+//!
+//! Above 100 on both axes a sector is leading; it weakens as momentum turns,
+//! lags below 100 on both, and improves once momentum recovers.
+
+use chrono::NaiveDate;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Base value of a rebuilt sector index
+pub const SECTOR_INDEX_BASE: f64 = 100.0;
+
+/// A sector index rebuilt from member closes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SectorIndex {
+    pub sector: String,
+    /// Members with at least one daily return
+    pub constituents: usize,
+    /// Index value per session, oldest first
+    pub points: Vec<(NaiveDate, f64)>,
+}
+
+/// Equal-weight index of each sector
+///
+/// `members` holds each stock's sector and its closes, oldest first. A
+/// member contributes a return on every session after its first; sessions
+/// where no member traded are skipped.
+pub fn build_sector_indices(members: &[(&str, &[(NaiveDate, Decimal)])]) -> Vec<SectorIndex> {
+    // sector -> session -> (sum of returns, members)
+    let mut returns: BTreeMap<&str, BTreeMap<NaiveDate, (f64, usize)>> = BTreeMap::new();
+    let mut constituents: BTreeMap<&str, usize> = BTreeMap::new();
+
+    for (sector, closes) in members {
+        let closes: Vec<(NaiveDate, f64)> = closes
+            .iter()
+            .filter_map(|(d, c)| c.to_f64().filter(|c| *c > 0.0).map(|c| (*d, c)))
+            .collect();
+        if closes.len() < 2 {
+            continue;
+        }
+        *constituents.entry(sector).or_default() += 1;
+        let by_session = returns.entry(sector).or_default();
+        for w in closes.windows(2) {
+            let entry = by_session.entry(w[1].0).or_default();
+            entry.0 += w[1].1 / w[0].1 - 1.0;
+            entry.1 += 1;
+        }
+    }
+
+    returns
+        .into_iter()
+        .map(|(sector, by_session)| {
+            let mut value = SECTOR_INDEX_BASE;
+            let points = by_session
+                .into_iter()
+                .map(|(date, (sum, count))| {
+                    value *= 1.0 + sum / count as f64;
+                    (date, value)
+                })
+                .collect();
+            SectorIndex {
+                sector: sector.to_string(),
+                constituents: constituents[sector],
+                points,
+            }
+        })
+        .collect()
+}
+
+/// Sessions in the short, medium and long windows (1, 4 and 12 weeks)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RotationConfig {
+    pub short_sessions: usize,
+    /// RS-Momentum lookback
+    pub medium_sessions: usize,
+    /// RS-Ratio lookback
+    pub long_sessions: usize,
+    /// Weekly points in each sector's trail
+    pub trail_points: usize,
+}
+
+impl Default for RotationConfig {
+    fn default() -> Self {
+        Self {
+            short_sessions: 5,
+            medium_sessions: 20,
+            long_sessions: 60,
+            trail_points: 6,
+        }
+    }
+}
+
+impl RotationConfig {
+    /// Aligned sessions needed for a sector's latest point and trail
+    pub fn required_sessions(&self) -> usize {
+        self.long_sessions
+            + self.medium_sessions
+            + self.short_sessions * self.trail_points.saturating_sub(1)
+            + 1
+    }
+}
+
+/// RRG quadrant of a sector
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RotationQuadrant {
+    Leading,
+    Weakening,
+    Lagging,
+    Improving,
+}
+
+impl RotationQuadrant {
+    pub fn classify(rs_ratio: f64, rs_momentum: f64) -> Self {
+        match (rs_ratio >= 100.0, rs_momentum >= 100.0) {
+            (true, true) => Self::Leading,
+            (true, false) => Self::Weakening,
+            (false, false) => Self::Lagging,
+            (false, true) => Self::Improving,
+        }
+    }
+}
+
+/// One position on the rotation graph
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RotationPoint {
+    pub date: NaiveDate,
+    pub rs_ratio: f64,
+    pub rs_momentum: f64,
+}
+
+/// Rotation of one sector against the composite index
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SectorRotation {
+    pub sector: String,
+    pub constituents: usize,
+    /// Sector return minus composite return over 1, 4 and 12 weeks (%)
+    pub relative_return_1w: f64,
+    pub relative_return_4w: f64,
+    pub relative_return_12w: f64,
+    pub rs_ratio: f64,
+    pub rs_momentum: f64,
+    pub quadrant: RotationQuadrant,
+    /// Weekly positions, oldest first, ending at the latest
+    pub trail: Vec<RotationPoint>,
+}
+
+/// Place each sector on the rotation graph
+///
+/// Sectors without `config.required_sessions()` sessions in common with
+/// the benchmark are left out. Sorted by RS-Ratio, strongest first.
+pub fn sector_rotation(
+    indices: &[SectorIndex],
+    benchmark: &[(NaiveDate, Decimal)],
+    config: &RotationConfig,
+) -> Vec<SectorRotation> {
+    let benchmark: BTreeMap<NaiveDate, f64> = benchmark
+        .iter()
+        .filter_map(|(d, v)| v.to_f64().filter(|v| *v > 0.0).map(|v| (*d, v)))
+        .collect();
+
+    let mut rotations: Vec<SectorRotation> = indices
+        .iter()
+        .filter_map(|index| {
+            // Index and benchmark on common sessions
+            let aligned: Vec<(NaiveDate, f64, f64)> = index
+                .points
+                .iter()
+                .filter_map(|(d, v)| benchmark.get(d).map(|b| (*d, *v, *b)))
+                .collect();
+            if aligned.len() < config.required_sessions() {
+                return None;
+            }
+            let rs: Vec<f64> = aligned.iter().map(|(_, v, b)| v / b).collect();
+            let last = rs.len() - 1;
+
+            let ratio_at = |i: usize| 100.0 * rs[i] / rs[i - config.long_sessions];
+            let point_at = |i: usize| RotationPoint {
+                date: aligned[i].0,
+                rs_ratio: ratio_at(i),
+                rs_momentum: 100.0 * ratio_at(i) / ratio_at(i - config.medium_sessions),
+            };
+            let relative_return = |sessions: usize| {
+                let (_, v0, b0) = aligned[last - sessions];
+                let (_, v1, b1) = aligned[last];
+                ((v1 / v0) - (b1 / b0)) * 100.0
+            };
+
+            let trail: Vec<RotationPoint> = (0..config.trail_points.max(1))
+                .rev()
+                .map(|week| point_at(last - week * config.short_sessions))
+                .collect();
+            let latest = trail.last().cloned()?;
+            Some(SectorRotation {
+                sector: index.sector.clone(),
+                constituents: index.constituents,
+                relative_return_1w: relative_return(config.short_sessions),
+                relative_return_4w: relative_return(config.medium_sessions),
+                relative_return_12w: relative_return(config.long_sessions),
+                rs_ratio: latest.rs_ratio,
+                rs_momentum: latest.rs_momentum,
+                quadrant: RotationQuadrant::classify(latest.rs_ratio, latest.rs_momentum),
+                trail,
+            })
+        })
+        .collect();
+
+    rotations.sort_by(|a, b| b.rs_ratio.total_cmp(&a.rs_ratio));
+    rotations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::prelude::FromPrimitive;
+
+    fn day(i: usize) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, 1).unwrap() + chrono::Duration::days(i as i64)
+    }
+
+    fn series(values: impl Iterator<Item = f64>) -> Vec<(NaiveDate, Decimal)> {
+        values
+            .enumerate()
+            .map(|(i, v)| (day(i), Decimal::from_f64(v).unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn test_equal_weight_sector_index() {
+        let a = series([100.0, 110.0, 110.0].into_iter());
+        let b = series([50.0, 50.0, 45.0].into_iter());
+        let c = series([10.0].into_iter());
+        let indices = build_sector_indices(&[("Finance", &a), ("Finance", &b), ("Energy", &c)]);
+
+        // Energy has no returns yet
+        assert_eq!(indices.len(), 1);
+        let finance = &indices[0];
+        assert_eq!(finance.constituents, 2);
+        // +10% and 0% average +5%; then 0% and -10% average -5%
+        assert_eq!(finance.points[0].0, day(1));
+        assert!((finance.points[0].1 - 105.0).abs() < 1e-9);
+        assert!((finance.points[1].1 - 99.75).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_rotation_quadrants() {
+        let config = RotationConfig {
+            short_sessions: 2,
+            medium_sessions: 4,
+            long_sessions: 8,
+            trail_points: 3,
+        };
+        let n = config.required_sessions() + 5;
+        let benchmark = series((0..n).map(|_| 1000.0));
+        let index = |sector: &str, f: fn(usize) -> f64| SectorIndex {
+            sector: sector.to_string(),
+            constituents: 3,
+            points: (0..n).map(|i| (day(i), f(i))).collect(),
+        };
+        let indices = vec![
+            // Accelerating outperformance
+            index("Tech", |i| 100.0 * 1.001f64.powi((i * i) as i32)),
+            // Steady underperformance
+            index("Property", |i| 100.0 * 0.99f64.powi(i as i32)),
+            // Too short a history
+            SectorIndex {
+                sector: "New".to_string(),
+                constituents: 1,
+                points: vec![(day(0), 100.0)],
+            },
+        ];
+
+        let rotation = sector_rotation(&indices, &benchmark, &config);
+        assert_eq!(rotation.len(), 2);
+        assert_eq!(rotation[0].sector, "Tech");
+        assert_eq!(rotation[0].quadrant, RotationQuadrant::Leading);
+        assert!(rotation[0].relative_return_12w > rotation[0].relative_return_1w);
+        assert_eq!(rotation[0].trail.len(), 3);
+        assert_eq!(rotation[0].trail[2].date, day(n - 1));
+        // Constant relative decline: ratio below 100, momentum flat at 100
+        assert!(rotation[1].rs_ratio < 100.0);
+        assert!(rotation[1].relative_return_1w < 0.0);
+        assert_eq!(
+            RotationQuadrant::classify(95.0, 99.0),
+            RotationQuadrant::Lagging
+        );
+        assert_eq!(
+            RotationQuadrant::classify(102.0, 98.0),
+            RotationQuadrant::Weakening
+        );
+        assert_eq!(
+            RotationQuadrant::classify(97.0, 101.0),
+            RotationQuadrant::Improving
+        );
+    }
+}
@@ -0,0 +1,300 @@
+//! Sector-aware fundamental scoring profiles
+//!
+//! Each sector weighs the score components differently and only scores the
+//! metrics that mean something for its business model:
+//! - Banking: ROE, ROA and P/B; leverage and EV multiples are skipped
+//! - Mining and energy: EV/EBITDA, FCF yield and returns on capital
+//! - Technology: revenue growth and ROIC; book value is skipped
+//! - Everything else: the general profile with every metric
+
+use crate::fundamental_score::{FundamentalScoreEngine, FundamentalWeights};
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+/// Metric the fundamental engine can score
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FundamentalMetric {
+    PeRatio,
+    PbRatio,
+    EvEbitda,
+    FcfYield,
+    DcfMargin,
+    Roe,
+    Roa,
+    ProfitMargin,
+    RoicSpread,
+    RevenueGrowth,
+    DebtToEquity,
+    CurrentRatio,
+}
+
+impl FundamentalMetric {
+    pub const ALL: [FundamentalMetric; 12] = [
+        Self::PeRatio,
+        Self::PbRatio,
+        Self::EvEbitda,
+        Self::FcfYield,
+        Self::DcfMargin,
+        Self::Roe,
+        Self::Roa,
+        Self::ProfitMargin,
+        Self::RoicSpread,
+        Self::RevenueGrowth,
+        Self::DebtToEquity,
+        Self::CurrentRatio,
+    ];
+}
+
+/// Weights and scored metrics for a group of sectors
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SectorProfile {
+    /// Profile identifier, recorded in the score breakdown
+    pub name: String,
+    /// Sector names matched case-insensitively against `stocks.sector`
+    pub sectors: Vec<String>,
+    /// Subsector names that select this profile regardless of sector
+    pub subsectors: Vec<String>,
+    pub weights: FundamentalWeights,
+    pub metrics: Vec<FundamentalMetric>,
+}
+
+impl SectorProfile {
+    /// Profile applied when no sector profile matches
+    #[must_use]
+    pub fn general() -> Self {
+        Self {
+            name: "general".to_string(),
+            sectors: Vec::new(),
+            subsectors: Vec::new(),
+            weights: FundamentalWeights::default(),
+            metrics: FundamentalMetric::ALL.to_vec(),
+        }
+    }
+
+    /// Banks: leverage is the business, so D/E, current ratio, EV/EBITDA and
+    /// ROIC say little and the health score stays neutral
+    #[must_use]
+    pub fn banking() -> Self {
+        Self {
+            name: "banking".to_string(),
+            sectors: vec!["Banking".to_string()],
+            subsectors: vec!["Bank".to_string()],
+            weights: FundamentalWeights {
+                valuation: dec!(0.30),
+                dcf: dec!(0.10),
+                quality: dec!(0.35),
+                health: dec!(0.25),
+            },
+            metrics: vec![
+                FundamentalMetric::PeRatio,
+                FundamentalMetric::PbRatio,
+                FundamentalMetric::DcfMargin,
+                FundamentalMetric::Roe,
+                FundamentalMetric::Roa,
+            ],
+        }
+    }
+
+    /// Miners and energy producers: cyclical earnings, so EV/EBITDA and cash
+    /// generation matter more than P/E or book value
+    #[must_use]
+    pub fn resources() -> Self {
+        Self {
+            name: "resources".to_string(),
+            sectors: vec!["Mining".to_string(), "Energy".to_string()],
+            subsectors: vec!["Coal".to_string(), "Metal".to_string()],
+            weights: FundamentalWeights {
+                valuation: dec!(0.40),
+                dcf: dec!(0.15),
+                quality: dec!(0.25),
+                health: dec!(0.20),
+            },
+            metrics: vec![
+                FundamentalMetric::EvEbitda,
+                FundamentalMetric::FcfYield,
+                FundamentalMetric::DcfMargin,
+                FundamentalMetric::RoicSpread,
+                FundamentalMetric::DebtToEquity,
+                FundamentalMetric::CurrentRatio,
+            ],
+        }
+    }
+
+    /// Technology: asset-light growth businesses, judged on growth and
+    /// returns on capital rather than book value
+    #[must_use]
+    pub fn technology() -> Self {
+        Self {
+            name: "technology".to_string(),
+            sectors: vec!["Technology".to_string()],
+            subsectors: Vec::new(),
+            weights: FundamentalWeights {
+                valuation: dec!(0.25),
+                dcf: dec!(0.20),
+                quality: dec!(0.35),
+                health: dec!(0.20),
+            },
+            metrics: vec![
+                FundamentalMetric::PeRatio,
+                FundamentalMetric::EvEbitda,
+                FundamentalMetric::FcfYield,
+                FundamentalMetric::DcfMargin,
+                FundamentalMetric::ProfitMargin,
+                FundamentalMetric::RoicSpread,
+                FundamentalMetric::RevenueGrowth,
+                FundamentalMetric::DebtToEquity,
+                FundamentalMetric::CurrentRatio,
+            ],
+        }
+    }
+
+    /// Whether this profile scores the metric
+    #[must_use]
+    pub fn includes(&self, metric: FundamentalMetric) -> bool {
+        self.metrics.contains(&metric)
+    }
+
+    fn matches(&self, sector: Option<&str>, subsector: Option<&str>) -> bool {
+        let any_eq = |names: &[String], value: Option<&str>| {
+            value.is_some_and(|v| names.iter().any(|n| n.eq_ignore_ascii_case(v.trim())))
+        };
+        any_eq(&self.subsectors, subsector) || any_eq(&self.sectors, sector)
+    }
+}
+
+/// Registry selecting a scoring profile from a stock's sector
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SectorProfileRegistry {
+    profiles: Vec<SectorProfile>,
+    fallback: SectorProfile,
+}
+
+impl SectorProfileRegistry {
+    /// Registry with only the general profile
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            profiles: Vec::new(),
+            fallback: SectorProfile::general(),
+        }
+    }
+
+    /// Registry with the built-in banking, resources and technology profiles
+    #[must_use]
+    pub fn builtin() -> Self {
+        Self::new()
+            .with_profile(SectorProfile::banking())
+            .with_profile(SectorProfile::resources())
+            .with_profile(SectorProfile::technology())
+    }
+
+    /// Add a profile; later profiles take precedence over earlier ones
+    #[must_use]
+    pub fn with_profile(mut self, profile: SectorProfile) -> Self {
+        self.profiles.insert(0, profile);
+        self
+    }
+
+    pub fn profiles(&self) -> &[SectorProfile] {
+        &self.profiles
+    }
+
+    /// Profile for a sector/subsector, subsector matches first
+    #[must_use]
+    pub fn profile_for(&self, sector: Option<&str>, subsector: Option<&str>) -> &SectorProfile {
+        self.profiles
+            .iter()
+            .find(|p| p.matches(None, subsector))
+            .or_else(|| self.profiles.iter().find(|p| p.matches(sector, None)))
+            .unwrap_or(&self.fallback)
+    }
+
+    /// Scoring engine configured for a sector/subsector
+    #[must_use]
+    pub fn engine_for(
+        &self,
+        sector: Option<&str>,
+        subsector: Option<&str>,
+    ) -> FundamentalScoreEngine {
+        FundamentalScoreEngine::with_profile(self.profile_for(sector, subsector))
+    }
+}
+
+impl Default for SectorProfileRegistry {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fundamental_score::FundamentalInput;
+
+    #[test]
+    fn test_profile_weights_sum_to_one() {
+        for profile in SectorProfileRegistry::builtin()
+            .profiles()
+            .iter()
+            .chain([&SectorProfile::general()])
+        {
+            let w = &profile.weights;
+            assert_eq!(
+                w.valuation + w.dcf + w.quality + w.health,
+                dec!(1),
+                "{}",
+                profile.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_profile_selection() {
+        let registry = SectorProfileRegistry::builtin();
+        assert_eq!(
+            registry.profile_for(Some("Banking"), Some("Bank")).name,
+            "banking"
+        );
+        // Subsector wins over a broader sector
+        assert_eq!(
+            registry.profile_for(Some("Financial"), Some("bank")).name,
+            "banking"
+        );
+        assert_eq!(registry.profile_for(Some("mining"), None).name, "resources");
+        assert_eq!(
+            registry
+                .profile_for(Some("Technology"), Some("E-commerce"))
+                .name,
+            "technology"
+        );
+        assert_eq!(
+            registry
+                .profile_for(Some("Property"), Some("Real Estate"))
+                .name,
+            "general"
+        );
+        assert_eq!(registry.profile_for(None, None).name, "general");
+    }
+
+    #[test]
+    fn test_bank_ignores_leverage() {
+        let registry = SectorProfileRegistry::builtin();
+        let input = FundamentalInput {
+            roe: Some(dec!(20)),
+            debt_to_equity: Some(dec!(6)),
+            ..Default::default()
+        };
+
+        let general = registry
+            .engine_for(Some("Property"), None)
+            .calculate(&input);
+        let bank = registry
+            .engine_for(Some("Banking"), Some("Bank"))
+            .calculate(&input);
+
+        assert_eq!(bank.sector_profile.as_deref(), Some("banking"));
+        assert!(bank.health_score > general.health_score);
+        assert!(!bank.signals.iter().any(|s| s.contains("High leverage")));
+    }
+}
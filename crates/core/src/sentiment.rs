@@ -0,0 +1,350 @@
+//! News sentiment for Indonesian financial text
+//!
+//! A lexicon scorer for Bahasa Indonesia headlines and summaries: words and
+//! phrases such as "laba naik" or "gagal bayar" count for or against, and a
+//! negator ("tidak", "belum", ...) just before a term flips it. Article
+//! scores are combined per stock with a recency half-life into the 0-100
+//! sentiment component of the composite score.
+
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+/// Terms read as good news
+const POSITIVE_TERMS: &[&str] = &[
+    "naik",
+    "kenaikan",
+    "meningkat",
+    "peningkatan",
+    "menguat",
+    "penguatan",
+    "melonjak",
+    "lonjakan",
+    "melesat",
+    "meroket",
+    "tumbuh",
+    "bertumbuh",
+    "pertumbuhan",
+    "untung",
+    "keuntungan",
+    "rekor",
+    "positif",
+    "ekspansi",
+    "dividen",
+    "surplus",
+    "optimis",
+    "optimistis",
+    "membaik",
+    "pulih",
+    "pemulihan",
+    "bullish",
+    "akumulasi",
+    "borong",
+    "rebound",
+    "outperform",
+    "upgrade",
+    "kontrak baru",
+    "buyback",
+    "beli",
+];
+
+/// Terms read as bad news
+const NEGATIVE_TERMS: &[&str] = &[
+    "turun",
+    "penurunan",
+    "menurun",
+    "melemah",
+    "pelemahan",
+    "anjlok",
+    "merosot",
+    "ambruk",
+    "ambles",
+    "terjun",
+    "rugi",
+    "kerugian",
+    "merugi",
+    "negatif",
+    "gagal bayar",
+    "bangkrut",
+    "pailit",
+    "pkpu",
+    "default",
+    "koreksi",
+    "tertekan",
+    "defisit",
+    "suspensi",
+    "suspend",
+    "gugatan",
+    "digugat",
+    "pesimis",
+    "melambat",
+    "perlambatan",
+    "lesu",
+    "bearish",
+    "distribusi",
+    "downgrade",
+    "underperform",
+    "delisting",
+    "fraud",
+    "korupsi",
+    "jual",
+];
+
+/// Words that flip the sentiment of the term right after them
+const NEGATORS: &[&str] = &["tidak", "tak", "belum", "bukan", "tanpa", "gagal"];
+
+/// Score at or beyond which text is positive or negative
+const LABEL_THRESHOLD: Decimal = dec!(0.2);
+
+/// Polarity of a text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SentimentLabel {
+    Positive,
+    Neutral,
+    Negative,
+}
+
+impl SentimentLabel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Positive => "positive",
+            Self::Neutral => "neutral",
+            Self::Negative => "negative",
+        }
+    }
+
+    fn from_score(score: Decimal) -> Self {
+        if score >= LABEL_THRESHOLD {
+            Self::Positive
+        } else if score <= -LABEL_THRESHOLD {
+            Self::Negative
+        } else {
+            Self::Neutral
+        }
+    }
+}
+
+/// Lexicon sentiment of one text
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextSentiment {
+    /// -1 (all negative terms) to 1 (all positive); 0 with no terms
+    pub score: Decimal,
+    pub label: SentimentLabel,
+    pub positive_hits: u32,
+    pub negative_hits: u32,
+}
+
+/// Lowercase words of `text`, letters and digits only
+fn tokenize(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Polarity of the term starting at `tokens[i]` and its length in words
+///
+/// Two-word phrases win over their first word, so "gagal bayar" is one
+/// negative term rather than a negator.
+fn match_term(tokens: &[String], i: usize) -> Option<(i32, usize)> {
+    if let Some(next) = tokens.get(i + 1) {
+        let phrase = format!("{} {}", tokens[i], next);
+        if POSITIVE_TERMS.contains(&phrase.as_str()) {
+            return Some((1, 2));
+        }
+        if NEGATIVE_TERMS.contains(&phrase.as_str()) {
+            return Some((-1, 2));
+        }
+    }
+    let word = tokens[i].as_str();
+    if POSITIVE_TERMS.contains(&word) {
+        Some((1, 1))
+    } else if NEGATIVE_TERMS.contains(&word) {
+        Some((-1, 1))
+    } else {
+        None
+    }
+}
+
+/// Score Indonesian financial text by its sentiment terms
+#[must_use]
+pub fn score_text(text: &str) -> TextSentiment {
+    let tokens = tokenize(text);
+    let mut positive_hits = 0u32;
+    let mut negative_hits = 0u32;
+    let mut negate = false;
+    let mut i = 0;
+
+    while i < tokens.len() {
+        if let Some((polarity, len)) = match_term(&tokens, i) {
+            if (polarity > 0) != negate {
+                positive_hits += 1;
+            } else {
+                negative_hits += 1;
+            }
+            negate = false;
+            i += len;
+            continue;
+        }
+        negate = NEGATORS.contains(&tokens[i].as_str());
+        i += 1;
+    }
+
+    let total = positive_hits + negative_hits;
+    let score = if total == 0 {
+        Decimal::ZERO
+    } else {
+        ((Decimal::from(positive_hits) - Decimal::from(negative_hits)) / Decimal::from(total))
+            .round_dp(4)
+    };
+
+    TextSentiment {
+        score,
+        label: SentimentLabel::from_score(score),
+        positive_hits,
+        negative_hits,
+    }
+}
+
+/// Days after which an article counts half as much
+pub const NEWS_HALF_LIFE_DAYS: f64 = 3.0;
+
+/// Articles at which the news score reaches its full distance from neutral
+const FULL_COVERAGE_ARTICLES: f64 = 5.0;
+
+/// A scored article feeding a stock's sentiment
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScoredArticle {
+    /// Article sentiment, -1 to 1
+    pub score: Decimal,
+    /// Days since publication
+    pub age_days: f64,
+}
+
+/// Sentiment component of a stock's composite score
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NewsSentiment {
+    /// 0-100, 50 neutral
+    pub score: Decimal,
+    pub articles: usize,
+    pub positive: usize,
+    pub negative: usize,
+    /// Recency-weighted mean article score, -1 to 1
+    pub weighted_mean: Decimal,
+}
+
+/// Combine article scores into a 0-100 sentiment score
+///
+/// Recent articles weigh more, halving every `half_life_days`. A stock with
+/// one or two articles stays closer to neutral than one in the news all
+/// week. `None` without articles.
+#[must_use]
+pub fn aggregate_news_sentiment(
+    articles: &[ScoredArticle],
+    half_life_days: f64,
+) -> Option<NewsSentiment> {
+    if articles.is_empty() {
+        return None;
+    }
+
+    let half_life = half_life_days.max(f64::EPSILON);
+    let mut weighted = 0.0;
+    let mut total_weight = 0.0;
+    for article in articles {
+        let weight = 0.5f64.powf(article.age_days.max(0.0) / half_life);
+        weighted += article.score.to_f64().unwrap_or(0.0) * weight;
+        total_weight += weight;
+    }
+    let mean = if total_weight > 0.0 {
+        weighted / total_weight
+    } else {
+        0.0
+    };
+    let coverage = (articles.len() as f64 / FULL_COVERAGE_ARTICLES).min(1.0);
+    let score = (50.0 + 50.0 * mean * coverage).clamp(0.0, 100.0);
+
+    Some(NewsSentiment {
+        score: Decimal::from_f64(score).unwrap_or(dec!(50)).round_dp(2),
+        articles: articles.len(),
+        positive: articles
+            .iter()
+            .filter(|a| a.score >= LABEL_THRESHOLD)
+            .count(),
+        negative: articles
+            .iter()
+            .filter(|a| a.score <= -LABEL_THRESHOLD)
+            .count(),
+        weighted_mean: Decimal::from_f64(mean).unwrap_or_default().round_dp(4),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score_text() {
+        let good = score_text("Laba bersih BBCA naik 12%, dividen rekor");
+        assert_eq!(good.positive_hits, 3);
+        assert_eq!(good.negative_hits, 0);
+        assert_eq!(good.score, Decimal::ONE);
+        assert_eq!(good.label, SentimentLabel::Positive);
+
+        let bad = score_text("Saham WSKT anjlok usai gagal bayar obligasi");
+        assert_eq!(bad.negative_hits, 2);
+        assert_eq!(bad.label, SentimentLabel::Negative);
+
+        let mixed = score_text("Pendapatan naik, tetapi laba turun");
+        assert_eq!(mixed.positive_hits, 1);
+        assert_eq!(mixed.negative_hits, 1);
+        assert_eq!(mixed.label, SentimentLabel::Neutral);
+
+        let none = score_text("RUPS tahunan digelar pekan depan");
+        assert_eq!(none.score, Decimal::ZERO);
+        assert_eq!(none.label, SentimentLabel::Neutral);
+    }
+
+    #[test]
+    fn test_negation_flips_term() {
+        let s = score_text("Kinerja emiten tidak membaik");
+        assert_eq!(s.positive_hits, 0);
+        assert_eq!(s.negative_hits, 1);
+
+        let s = score_text("Harga saham belum turun");
+        assert_eq!(s.positive_hits, 1);
+
+        // Negation reaches only the next word
+        let s = score_text("tidak ada kabar, saham naik");
+        assert_eq!(s.positive_hits, 1);
+        assert_eq!(s.negative_hits, 0);
+    }
+
+    #[test]
+    fn test_aggregate_news_sentiment() {
+        assert!(aggregate_news_sentiment(&[], NEWS_HALF_LIFE_DAYS).is_none());
+
+        let article = |score, age_days| ScoredArticle { score, age_days };
+        let busy: Vec<_> = (0..5).map(|_| article(Decimal::ONE, 0.0)).collect();
+        let sentiment = aggregate_news_sentiment(&busy, NEWS_HALF_LIFE_DAYS).unwrap();
+        assert_eq!(sentiment.score, dec!(100));
+        assert_eq!(sentiment.positive, 5);
+
+        // One article moves the score a fifth of the way
+        let single =
+            aggregate_news_sentiment(&[article(dec!(-1), 0.0)], NEWS_HALF_LIFE_DAYS).unwrap();
+        assert_eq!(single.score, dec!(40));
+        assert_eq!(single.negative, 1);
+
+        // A fresh negative outweighs an older positive
+        let sentiment = aggregate_news_sentiment(
+            &[article(dec!(1), 6.0), article(dec!(-1), 0.0)],
+            NEWS_HALF_LIFE_DAYS,
+        )
+        .unwrap();
+        assert_eq!(sentiment.weighted_mean, dec!(-0.6));
+        assert!(sentiment.score < dec!(50));
+    }
+}
@@ -9,7 +9,17 @@
 //! - Fibonacci Support: 15%
 //! - Volume Analysis: 10%
 //! - RSI/MACD Signals: 10%
+//!
+//! Sub-scores can also be normalized within a sector before weighting: each
+//! is turned into a z-score against the sector's peers and then into a
+//! percentile, so a volume spike in a thin small cap and in a liquid bank
+//! are each judged against their own kind.
+//!
+//! Broker data older than [`StalenessPolicy::BROKER_FLOW`] allows has its
+//! sub-score decayed toward neutral, noted in `stale_inputs`.
 
+use crate::scoring::{StaleInput, StalenessPolicy};
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
@@ -25,8 +35,32 @@ pub struct TechnicalScoreBreakdown {
     pub volume_score: Decimal,
     pub momentum_score: Decimal,
     pub signals: Vec<String>,
+    /// Sector percentiles of the sub-scores, when `total_score` weighs those
+    /// instead of the absolute sub-scores above
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sector_relative: Option<SectorRelativeScores>,
+    /// Inputs past their freshness threshold, whose sub-scores were decayed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stale_inputs: Vec<StaleInput>,
+}
+
+/// Sub-scores as percentiles (0-100) among the sector's peers
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SectorRelativeScores {
+    pub sector: String,
+    /// Scored stocks in the sector, including this one
+    pub peers: usize,
+    pub order_flow_score: Decimal,
+    pub broker_score: Decimal,
+    pub ema_score: Decimal,
+    pub fibonacci_score: Decimal,
+    pub volume_score: Decimal,
+    pub momentum_score: Decimal,
 }
 
+/// Scored stocks a sector needs before its sub-scores are normalized
+pub const MIN_SECTOR_PEERS: usize = 5;
+
 /// Weights for technical score components
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TechnicalWeights {
@@ -64,17 +98,24 @@ pub struct TechnicalScoreInput {
     // Order flow (optional)
     pub obi: Option<Decimal>,
     pub ofi_trend: Option<Decimal>,
+    /// Latest OFI as a rolling z-score; used instead of `ofi_trend` when set
+    pub ofi_zscore: Option<Decimal>,
 
     // Broker data (optional)
     pub broker_score: Option<Decimal>,
     pub institutional_buying: bool,
     pub foreign_buying: bool,
+    /// Days since the latest broker summary
+    pub broker_age_days: Option<i64>,
 
     // Pre-calculated indicators (optional, will calculate if missing)
     pub ema20: Option<Decimal>,
     pub ema50: Option<Decimal>,
     pub rsi: Option<Decimal>,
     pub macd_histogram: Option<Decimal>,
+
+    // Recently completed candlestick patterns, as signal text
+    pub candlestick_patterns: Vec<String>,
 }
 
 impl Default for TechnicalScoreInput {
@@ -87,13 +128,16 @@ impl Default for TechnicalScoreInput {
             lows: vec![],
             obi: None,
             ofi_trend: None,
+            ofi_zscore: None,
             broker_score: None,
             institutional_buying: false,
             foreign_buying: false,
+            broker_age_days: None,
             ema20: None,
             ema50: None,
             rsi: None,
             macd_histogram: None,
+            candlestick_patterns: vec![],
         }
     }
 }
@@ -126,8 +170,17 @@ impl TechnicalScoreEngine {
         // 1. Order Flow Score (0-100)
         let order_flow_score = self.calculate_order_flow_score(input, &mut signals);
 
-        // 2. Broker Score (0-100)
-        let broker_score = self.calculate_broker_score(input, &mut signals);
+        // 2. Broker Score (0-100), decayed when the broker data is stale
+        let mut broker_score = self.calculate_broker_score(input, &mut signals);
+        let mut stale_inputs = Vec::new();
+        if let Some(stale) = input
+            .broker_age_days
+            .and_then(|age| StalenessPolicy::BROKER_FLOW.check("broker_flow", age))
+        {
+            broker_score = stale.apply(broker_score);
+            signals.push(stale.signal("Broker data"));
+            stale_inputs.push(stale);
+        }
 
         // 3. EMA Score (0-100)
         let ema_score = self.calculate_ema_score(input, &mut signals);
@@ -141,6 +194,9 @@ impl TechnicalScoreEngine {
         // 6. Momentum Score (RSI/MACD) (0-100)
         let momentum_score = self.calculate_momentum_score(input, &mut signals);
 
+        // Candlestick patterns are reported but not scored
+        signals.extend(input.candlestick_patterns.iter().cloned());
+
         // Calculate weighted total
         let total_score = (order_flow_score * self.weights.order_flow)
             + (broker_score * self.weights.broker)
@@ -158,6 +214,85 @@ impl TechnicalScoreEngine {
             volume_score: volume_score.round_dp(2),
             momentum_score: momentum_score.round_dp(2),
             signals,
+            sector_relative: None,
+            stale_inputs,
+        }
+    }
+
+    /// Weighted total of the six sub-scores
+    fn weighted_total(&self, scores: [Decimal; 6]) -> Decimal {
+        let w = &self.weights;
+        let weights = [
+            w.order_flow,
+            w.broker,
+            w.ema,
+            w.fibonacci,
+            w.volume,
+            w.momentum,
+        ];
+        scores
+            .iter()
+            .zip(weights)
+            .map(|(score, weight)| *score * weight)
+            .sum::<Decimal>()
+            .round_dp(2)
+    }
+
+    /// Rescore `breakdowns` on sub-scores normalized within their sector
+    ///
+    /// `sectors` is aligned with `breakdowns`. Each absolute sub-score is
+    /// compared with the sector's peers as a z-score, which is mapped to a
+    /// percentile through the normal distribution; a sub-score every peer
+    /// shares sits at 50. The absolute sub-scores are kept, so normalizing
+    /// again gives the same result. Stocks without a sector, or in a sector
+    /// with fewer than [`MIN_SECTOR_PEERS`], keep their absolute total.
+    pub fn normalize_by_sector(
+        &self,
+        breakdowns: &mut [TechnicalScoreBreakdown],
+        sectors: &[Option<String>],
+    ) {
+        let mut groups: std::collections::HashMap<String, Vec<usize>> =
+            std::collections::HashMap::new();
+        for (i, sector) in sectors.iter().enumerate().take(breakdowns.len()) {
+            if let Some(sector) = sector.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+                groups.entry(sector.to_string()).or_default().push(i);
+            }
+        }
+
+        for breakdown in breakdowns.iter_mut() {
+            breakdown.sector_relative = None;
+            breakdown.total_score = self.weighted_total(breakdown.components());
+        }
+
+        for (sector, members) in groups {
+            if members.len() < MIN_SECTOR_PEERS {
+                continue;
+            }
+            let columns: Vec<Vec<Decimal>> = (0..6)
+                .map(|c| {
+                    let values: Vec<f64> = members
+                        .iter()
+                        .map(|&i| breakdowns[i].components()[c].to_f64().unwrap_or(50.0))
+                        .collect();
+                    sector_percentiles(&values)
+                })
+                .collect();
+
+            for (row, &i) in members.iter().enumerate() {
+                let scores: [Decimal; 6] = std::array::from_fn(|c| columns[c][row]);
+                let breakdown = &mut breakdowns[i];
+                breakdown.total_score = self.weighted_total(scores);
+                breakdown.sector_relative = Some(SectorRelativeScores {
+                    sector: sector.clone(),
+                    peers: members.len(),
+                    order_flow_score: scores[0],
+                    broker_score: scores[1],
+                    ema_score: scores[2],
+                    fibonacci_score: scores[3],
+                    volume_score: scores[4],
+                    momentum_score: scores[5],
+                });
+            }
         }
     }
 
@@ -182,8 +317,17 @@ impl TechnicalScoreEngine {
             }
         }
 
-        // OFI trend contribution
-        if let Some(ofi) = input.ofi_trend {
+        // OFI contribution: two standard deviations is a full ±10
+        if let Some(z) = input.ofi_zscore {
+            let z_normalized = (z / dec!(2)).max(dec!(-1)).min(dec!(1));
+            score += z_normalized * dec!(10);
+
+            if z >= dec!(1.5) {
+                signals.push("Order flow well above its recent norm".to_string());
+            } else if z <= dec!(-1.5) {
+                signals.push("Order flow well below its recent norm".to_string());
+            }
+        } else if let Some(ofi) = input.ofi_trend {
             let ofi_normalized = ofi.max(dec!(-1)).min(dec!(1));
             score += ofi_normalized * dec!(10);
 
@@ -428,6 +572,56 @@ impl TechnicalScoreEngine {
     }
 }
 
+impl TechnicalScoreBreakdown {
+    /// Absolute sub-scores in weight order
+    fn components(&self) -> [Decimal; 6] {
+        [
+            self.order_flow_score,
+            self.broker_score,
+            self.ema_score,
+            self.fibonacci_score,
+            self.volume_score,
+            self.momentum_score,
+        ]
+    }
+}
+
+/// Percentile (0-100) of each value's z-score among `values`
+fn sector_percentiles(values: &[f64]) -> Vec<Decimal> {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let std_dev = (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n).sqrt();
+    values
+        .iter()
+        .map(|v| {
+            let percentile = if std_dev > f64::EPSILON {
+                normal_cdf((v - mean) / std_dev) * 100.0
+            } else {
+                50.0
+            };
+            Decimal::from_f64(percentile)
+                .unwrap_or(dec!(50))
+                .round_dp(2)
+        })
+        .collect()
+}
+
+/// Standard normal CDF (Abramowitz and Stegun 7.1.26, error below 1.5e-7)
+fn normal_cdf(z: f64) -> f64 {
+    let x = z.abs() / std::f64::consts::SQRT_2;
+    let t = 1.0 / (1.0 + 0.327_591_1 * x);
+    let poly = t
+        * (0.254_829_592
+            + t * (-0.284_496_736
+                + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    let erf = 1.0 - poly * (-x * x).exp();
+    if z >= 0.0 {
+        0.5 * (1.0 + erf)
+    } else {
+        0.5 * (1.0 - erf)
+    }
+}
+
 impl Default for TechnicalScoreEngine {
     fn default() -> Self {
         Self::new()
@@ -450,6 +644,43 @@ mod tests {
         assert_eq!(sum, dec!(1));
     }
 
+    #[test]
+    fn test_candlestick_patterns_in_signals() {
+        let engine = TechnicalScoreEngine::new();
+        let input = TechnicalScoreInput {
+            candlestick_patterns: vec!["Hammer candlestick (bullish)".to_string()],
+            ..Default::default()
+        };
+        let result = engine.calculate(&input);
+
+        assert!(result
+            .signals
+            .contains(&"Hammer candlestick (bullish)".to_string()));
+        assert_eq!(
+            result.total_score,
+            engine
+                .calculate(&TechnicalScoreInput::default())
+                .total_score
+        );
+    }
+
+    #[test]
+    fn test_ofi_zscore_replaces_trend() {
+        let engine = TechnicalScoreEngine::new();
+        let input = TechnicalScoreInput {
+            ofi_trend: Some(dec!(-1)),
+            ofi_zscore: Some(dec!(3)),
+            ..Default::default()
+        };
+        let result = engine.calculate(&input);
+
+        // z of 3 saturates at +10; the falling trend is ignored
+        assert_eq!(result.order_flow_score, dec!(60));
+        assert!(result
+            .signals
+            .contains(&"Order flow well above its recent norm".to_string()));
+    }
+
     #[test]
     fn test_neutral_score() {
         let engine = TechnicalScoreEngine::new();
@@ -609,4 +840,86 @@ mod tests {
             .iter()
             .any(|s| s.contains("Volume spike") || s.contains("average volume")));
     }
+
+    #[test]
+    fn test_stale_broker_data_decays_toward_neutral() {
+        let engine = TechnicalScoreEngine::new();
+        let fresh = TechnicalScoreInput {
+            broker_score: Some(dec!(80)),
+            broker_age_days: Some(1),
+            ..Default::default()
+        };
+        let result = engine.calculate(&fresh);
+        assert_eq!(result.broker_score, dec!(80));
+        assert!(result.stale_inputs.is_empty());
+
+        let stale = engine.calculate(&TechnicalScoreInput {
+            broker_age_days: Some(6),
+            ..fresh.clone()
+        });
+        // 6 days old keeps 2/5 of the distance from neutral
+        assert_eq!(stale.broker_score, dec!(62));
+        assert!(stale.total_score < result.total_score);
+        assert_eq!(stale.stale_inputs[0].input, "broker_flow");
+        assert!(stale
+            .signals
+            .iter()
+            .any(|s| s.starts_with("Broker data 6 days old")));
+
+        let expired = engine.calculate(&TechnicalScoreInput {
+            broker_age_days: Some(30),
+            ..fresh
+        });
+        assert_eq!(expired.broker_score, dec!(50));
+    }
+
+    #[test]
+    fn test_sector_relative_normalization() {
+        let engine = TechnicalScoreEngine::new();
+        let with_volume = |volume_score: Decimal| TechnicalScoreBreakdown {
+            volume_score,
+            ..engine.calculate(&TechnicalScoreInput::default())
+        };
+        // Banks rarely spike, small caps spike all the time
+        let mut breakdowns: Vec<TechnicalScoreBreakdown> = [40, 50, 50, 50, 60]
+            .iter()
+            .chain(&[70, 80, 80, 80, 90])
+            .chain(&[80])
+            .map(|v| with_volume(Decimal::from(*v)))
+            .collect();
+        let sectors: Vec<Option<String>> = std::iter::repeat_n(Some("Banking".to_string()), 5)
+            .chain(std::iter::repeat_n(Some("Small Caps".to_string()), 5))
+            .chain([None])
+            .collect();
+        engine.normalize_by_sector(&mut breakdowns, &sectors);
+
+        let bank_top = breakdowns[4].sector_relative.clone().unwrap();
+        let small_top = breakdowns[9].sector_relative.clone().unwrap();
+        assert_eq!((bank_top.sector.as_str(), bank_top.peers), ("Banking", 5));
+        // The same spike relative to peers scores the same in both sectors
+        assert_eq!(bank_top.volume_score, small_top.volume_score);
+        assert!(bank_top.volume_score > dec!(90));
+        assert_eq!(
+            breakdowns[1].sector_relative.as_ref().unwrap().volume_score,
+            dec!(50)
+        );
+        // Sub-scores every peer shares sit at the median
+        assert_eq!(bank_top.ema_score, dec!(50));
+        // Absolute sub-scores are kept
+        assert_eq!(breakdowns[9].volume_score, dec!(90));
+        assert!(breakdowns[4].total_score > breakdowns[6].total_score);
+
+        // Without a sector the absolute total stands
+        assert!(breakdowns[10].sector_relative.is_none());
+        assert_eq!(
+            breakdowns[10].total_score,
+            engine.weighted_total(breakdowns[10].components())
+        );
+
+        // Normalizing twice changes nothing
+        let once: Vec<Decimal> = breakdowns.iter().map(|b| b.total_score).collect();
+        engine.normalize_by_sector(&mut breakdowns, &sectors);
+        let twice: Vec<Decimal> = breakdowns.iter().map(|b| b.total_score).collect();
+        assert_eq!(once, twice);
+    }
 }
@@ -0,0 +1,231 @@
+//! Transaction-cost model for simulated trades
+//!
+//! Simulated returns are only meaningful net of what a real order pays:
+//! - Broker commission and exchange/clearing/depository levies on both sides
+//! - The final income tax on sale proceeds
+//! - Spread and market impact, which grow as liquidity (ADTV) shrinks and
+//!   as the order takes a larger share of it
+
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+const BPS: f64 = 10_000.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TradeSide {
+    Buy,
+    Sell,
+}
+
+/// Cost assumptions, in basis points of traded value
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionCostModel {
+    /// Broker commission, charged on both sides
+    pub broker_fee_bps: f64,
+    /// Exchange, clearing and depository levies, charged on both sides
+    pub levy_bps: f64,
+    /// Final income tax on sale proceeds
+    pub sell_tax_bps: f64,
+    /// Half spread paid in the most liquid names
+    pub min_spread_bps: f64,
+    /// Half spread cap for the least liquid names
+    pub max_spread_bps: f64,
+    /// Average daily traded value (IDR) from which the spread is at its minimum
+    pub liquid_adtv: Decimal,
+    /// Market impact of an order worth the full ADTV; scales with the square
+    /// root of the order's share of ADTV
+    pub impact_bps: f64,
+    /// Spread and impact assumed when the ADTV is unknown
+    pub default_slippage_bps: f64,
+}
+
+impl Default for TransactionCostModel {
+    fn default() -> Self {
+        Self {
+            broker_fee_bps: 10.0,
+            levy_bps: 4.5,
+            sell_tax_bps: 10.0,
+            min_spread_bps: 5.0,
+            max_spread_bps: 150.0,
+            liquid_adtv: dec!(100_000_000_000),
+            impact_bps: 100.0,
+            default_slippage_bps: 20.0,
+        }
+    }
+}
+
+/// Costs of one fill
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TradeCost {
+    /// Traded value at the quoted price
+    pub notional: Decimal,
+    /// Commission, levies and tax
+    pub fees: Decimal,
+    /// Spread and impact versus the quoted price
+    pub slippage: Decimal,
+}
+
+impl TradeCost {
+    pub fn total(&self) -> Decimal {
+        self.fees + self.slippage
+    }
+}
+
+impl TransactionCostModel {
+    /// Explicit fees of one side
+    pub fn fee_bps(&self, side: TradeSide) -> f64 {
+        let both_sides = self.broker_fee_bps + self.levy_bps;
+        match side {
+            TradeSide::Buy => both_sides,
+            TradeSide::Sell => both_sides + self.sell_tax_bps,
+        }
+    }
+
+    /// Spread plus impact for an order worth `order_value` in a stock
+    /// trading `adtv` a day
+    pub fn slippage_bps(&self, order_value: Decimal, adtv: Option<Decimal>) -> f64 {
+        let Some(adtv) = adtv.and_then(|a| a.to_f64()).filter(|a| *a > 0.0) else {
+            return self.default_slippage_bps;
+        };
+        let liquid = self.liquid_adtv.to_f64().unwrap_or(0.0);
+        let spread = (self.min_spread_bps * (liquid / adtv).sqrt()).clamp(
+            self.min_spread_bps,
+            self.max_spread_bps.max(self.min_spread_bps),
+        );
+        let participation = order_value.to_f64().unwrap_or(0.0).max(0.0) / adtv;
+        spread + self.impact_bps * participation.sqrt()
+    }
+
+    /// Costs of buying or selling `quantity` shares quoted at `price`
+    pub fn trade_cost(
+        &self,
+        side: TradeSide,
+        price: Decimal,
+        quantity: i64,
+        adtv: Option<Decimal>,
+    ) -> TradeCost {
+        let notional = price * Decimal::from(quantity.max(0));
+        let of_notional = |bps: f64| Decimal::from_f64(bps / BPS).unwrap_or_default() * notional;
+        TradeCost {
+            notional,
+            fees: of_notional(self.fee_bps(side)),
+            slippage: of_notional(self.slippage_bps(notional, adtv)),
+        }
+    }
+
+    /// Price actually paid or received after spread and impact
+    pub fn fill_price(
+        &self,
+        side: TradeSide,
+        price: Decimal,
+        quantity: i64,
+        adtv: Option<Decimal>,
+    ) -> Decimal {
+        let notional = price * Decimal::from(quantity.max(0));
+        let slip = Decimal::from_f64(self.slippage_bps(notional, adtv) / BPS).unwrap_or_default();
+        match side {
+            TradeSide::Buy => price * (Decimal::ONE + slip),
+            TradeSide::Sell => price * (Decimal::ONE - slip),
+        }
+    }
+
+    /// Return of buying at `entry` and selling at `exit`, net of all costs,
+    /// in percent of the cash spent
+    pub fn net_return_pct(
+        &self,
+        entry: Decimal,
+        exit: Decimal,
+        quantity: i64,
+        adtv: Option<Decimal>,
+    ) -> Option<f64> {
+        let buy = self.trade_cost(TradeSide::Buy, entry, quantity, adtv);
+        let sell = self.trade_cost(TradeSide::Sell, exit, quantity, adtv);
+        let spent = buy.notional + buy.total();
+        if spent.is_zero() {
+            return None;
+        }
+        let received = sell.notional - sell.total();
+        ((received - spent) / spent * Decimal::from(100)).to_f64()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fees_by_side() {
+        let model = TransactionCostModel::default();
+        assert!((model.fee_bps(TradeSide::Buy) - 14.5).abs() < 1e-9);
+        assert!((model.fee_bps(TradeSide::Sell) - 24.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_slippage_grows_as_liquidity_shrinks() {
+        let model = TransactionCostModel::default();
+        let order = dec!(100_000_000);
+
+        let liquid = model.slippage_bps(order, Some(dec!(500_000_000_000)));
+        let mid = model.slippage_bps(order, Some(dec!(5_000_000_000)));
+        let illiquid = model.slippage_bps(order, Some(dec!(200_000_000)));
+        assert!(liquid < mid && mid < illiquid);
+
+        // Minimum spread plus 100 bps × sqrt(0.0002)
+        assert!((liquid - (5.0 + 100.0 * 0.0002f64.sqrt())).abs() < 1e-9);
+        // Spread widens with sqrt(100bn / ADTV), order is half the ADTV
+        assert!((illiquid - (5.0 * 500f64.sqrt() + 100.0 * 0.5f64.sqrt())).abs() < 1e-9);
+        // Spread capped at 150 bps, order is the whole ADTV
+        let capped = model.slippage_bps(order, Some(dec!(100_000_000)));
+        assert!((capped - 250.0).abs() < 1e-9);
+
+        assert_eq!(model.slippage_bps(order, None), model.default_slippage_bps);
+        assert_eq!(
+            model.slippage_bps(order, Some(Decimal::ZERO)),
+            model.default_slippage_bps
+        );
+    }
+
+    #[test]
+    fn test_round_trip_costs() {
+        let model = TransactionCostModel {
+            default_slippage_bps: 0.0,
+            ..Default::default()
+        };
+
+        // Flat trade loses the fees of both sides
+        let flat = model
+            .net_return_pct(dec!(1000), dec!(1000), 100, None)
+            .unwrap();
+        assert!((flat + 0.39).abs() < 0.01);
+
+        // 10% gross is roughly 9.57% net
+        let net = model
+            .net_return_pct(dec!(1000), dec!(1100), 100, None)
+            .unwrap();
+        assert!(net < 10.0 && net > 9.5);
+
+        let cost = model.trade_cost(TradeSide::Sell, dec!(1000), 1000, None);
+        assert_eq!(cost.notional, dec!(1_000_000));
+        assert_eq!(cost.fees, dec!(2450));
+        assert_eq!(cost.slippage, Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_fill_price() {
+        let model = TransactionCostModel {
+            default_slippage_bps: 50.0,
+            ..Default::default()
+        };
+        assert_eq!(
+            model.fill_price(TradeSide::Buy, dec!(1000), 100, None),
+            dec!(1005)
+        );
+        assert_eq!(
+            model.fill_price(TradeSide::Sell, dec!(1000), 100, None),
+            dec!(995)
+        );
+    }
+}
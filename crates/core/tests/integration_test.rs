@@ -37,12 +37,17 @@ fn test_full_scoring_pipeline() {
         sector_pb: Some(dec!(2.5)),
         ev_ebitda: Some(dec!(8)),
         sector_ev_ebitda: Some(dec!(12)),
+        fcf_yield: None,
         dcf_margin: Some(dec!(20)),
         roe: Some(dec!(18)),
         roa: Some(dec!(10)),
         profit_margin: Some(dec!(12)),
+        roic: None,
+        wacc: None,
+        revenue_growth: None,
         debt_to_equity: Some(dec!(0.6)),
         current_ratio: Some(dec!(1.6)),
+        financials_age_days: None,
     };
     let fund_result = fund_engine.calculate(&fund_input);
 
@@ -95,12 +100,17 @@ fn test_bearish_scoring_pipeline() {
         sector_pb: Some(dec!(2)),
         ev_ebitda: Some(dec!(18)),
         sector_ev_ebitda: Some(dec!(10)),
+        fcf_yield: None,
         dcf_margin: Some(dec!(-15)),
         roe: Some(dec!(5)),
         roa: Some(dec!(2)),
         profit_margin: Some(dec!(3)),
+        roic: None,
+        wacc: None,
+        revenue_growth: None,
         debt_to_equity: Some(dec!(2.0)),
         current_ratio: Some(dec!(0.8)),
+        financials_age_days: None,
     };
     let fund_result = fund_engine.calculate(&fund_input);
 
@@ -348,6 +358,9 @@ fn test_fundamental_assessment_categories() {
         roe: Some(dec!(25)),
         roa: Some(dec!(15)),
         profit_margin: Some(dec!(20)),
+        roic: None,
+        wacc: None,
+        revenue_growth: None,
         debt_to_equity: Some(dec!(0.3)),
         current_ratio: Some(dec!(2.0)),
         ..Default::default()
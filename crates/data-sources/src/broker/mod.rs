@@ -3,15 +3,18 @@
 //! Provides:
 //! - Broker classification (foreign/local institutional, retail)
 //! - Data scraping from IDX
+//! - Pluggable parsers for IDX pages and broker terminal exports
 //! - Rolling accumulation detection (5-day, 20-day)
 //! - Coordinated buying analysis
 
 mod analysis;
 mod classification;
 mod models;
+mod parsers;
 mod scraper;
 
 pub use analysis::*;
 pub use classification::*;
 pub use models::*;
+pub use parsers::*;
 pub use scraper::*;
@@ -19,6 +19,31 @@ pub struct BrokerSummary {
     pub net_value: Decimal,
 }
 
+impl BrokerSummary {
+    /// Create a summary row, deriving net volume and value
+    pub fn new(
+        date: NaiveDate,
+        symbol: String,
+        broker_code: String,
+        buy_volume: i64,
+        buy_value: Decimal,
+        sell_volume: i64,
+        sell_value: Decimal,
+    ) -> Self {
+        Self {
+            date,
+            symbol,
+            broker_code,
+            buy_volume,
+            sell_volume,
+            buy_value,
+            sell_value,
+            net_volume: buy_volume - sell_volume,
+            net_value: buy_value - sell_value,
+        }
+    }
+}
+
 /// Aggregated broker activity for a stock
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BrokerActivity {
@@ -0,0 +1,589 @@
+//! Pluggable parsers for broker summary data formats
+//!
+//! Each supported format implements [`BrokerDataParser`] with its own
+//! format detection. The [`BrokerParserRegistry`] picks the first parser
+//! that recognizes the content, so scraped pages and user-exported files
+//! from broker terminals go through the same pipeline.
+
+use super::models::BrokerSummary;
+use crate::drift::{check_structure, StructureExpectation};
+use crate::error::DataSourceError;
+use crate::spreadsheet::{parse_date, CsvTable};
+use chrono::NaiveDate;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use scraper::{Html, Selector};
+use std::collections::BTreeMap;
+
+/// Parser name for IDX pipe-delimited broker text files
+pub const IDX_TEXT_PARSER: &str = "idx_text";
+/// Parser name for IDX broker summary HTML pages
+pub const BROKER_HTML_PARSER: &str = "idx_broker_html";
+/// Parser name for Stockbit broker summary exports
+pub const STOCKBIT_CSV_PARSER: &str = "stockbit_csv";
+/// Parser name for per-broker CSV exports (IPOT/Indopremier and similar)
+pub const BROKER_CSV_PARSER: &str = "broker_csv";
+
+/// Shares per lot on IDX
+const LOT_SIZE: i64 = 100;
+
+/// Page structure the IDX broker HTML parser depends on
+pub(crate) const BROKER_HTML_STRUCTURE: StructureExpectation = StructureExpectation {
+    selectors: &["table.broker-summary", "#broker-table", "table"],
+    columns: &[&["broker"], &["buy", "beli"], &["sell", "jual"]],
+};
+
+/// Symbol and date to apply when the content does not carry them
+#[derive(Debug, Clone, Default)]
+pub struct BrokerParseContext {
+    pub symbol: Option<String>,
+    pub date: Option<NaiveDate>,
+}
+
+impl BrokerParseContext {
+    pub fn new(symbol: &str, date: NaiveDate) -> Self {
+        Self {
+            symbol: Some(symbol.to_uppercase()),
+            date: Some(date),
+        }
+    }
+
+    fn require_symbol(&self) -> Result<String, DataSourceError> {
+        self.symbol
+            .clone()
+            .ok_or_else(|| DataSourceError::InvalidResponse("Symbol is required".into()))
+    }
+
+    fn require_date(&self) -> Result<NaiveDate, DataSourceError> {
+        self.date
+            .ok_or_else(|| DataSourceError::InvalidResponse("Date is required".into()))
+    }
+}
+
+/// A broker summary data format
+pub trait BrokerDataParser: Send + Sync {
+    /// Stable parser name
+    fn name(&self) -> &'static str;
+
+    /// Whether the content looks like this parser's format
+    fn detect(&self, content: &[u8]) -> bool;
+
+    /// Parse content into broker summary rows
+    fn parse(
+        &self,
+        content: &[u8],
+        ctx: &BrokerParseContext,
+    ) -> Result<Vec<BrokerSummary>, DataSourceError>;
+}
+
+/// Ordered collection of broker data parsers
+pub struct BrokerParserRegistry {
+    parsers: Vec<Box<dyn BrokerDataParser>>,
+}
+
+impl BrokerParserRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self {
+            parsers: Vec::new(),
+        }
+    }
+
+    /// Create a registry with all built-in parsers
+    ///
+    /// More specific formats are registered first so detection prefers them.
+    pub fn with_defaults() -> Self {
+        Self::new()
+            .register(IdxHtmlParser)
+            .register(IdxTextParser)
+            .register(StockbitCsvParser)
+            .register(BrokerCsvParser)
+    }
+
+    /// Add a parser; it is consulted after the ones already registered
+    pub fn register(mut self, parser: impl BrokerDataParser + 'static) -> Self {
+        self.parsers.push(Box::new(parser));
+        self
+    }
+
+    /// Names of registered parsers in detection order
+    pub fn names(&self) -> Vec<&'static str> {
+        self.parsers.iter().map(|p| p.name()).collect()
+    }
+
+    /// Look up a parser by name
+    pub fn get(&self, name: &str) -> Option<&dyn BrokerDataParser> {
+        self.parsers
+            .iter()
+            .find(|p| p.name() == name)
+            .map(|p| p.as_ref())
+    }
+
+    /// Find the first parser that recognizes the content
+    pub fn detect(&self, content: &[u8]) -> Option<&dyn BrokerDataParser> {
+        self.parsers
+            .iter()
+            .find(|p| p.detect(content))
+            .map(|p| p.as_ref())
+    }
+
+    /// Detect the format and parse, returning the parser name used
+    pub fn parse(
+        &self,
+        content: &[u8],
+        ctx: &BrokerParseContext,
+    ) -> Result<(&'static str, Vec<BrokerSummary>), DataSourceError> {
+        let parser = self.detect(content).ok_or_else(|| {
+            DataSourceError::UnsupportedFormat(format!(
+                "No broker parser recognized the content (tried: {})",
+                self.names().join(", ")
+            ))
+        })?;
+
+        Ok((parser.name(), parser.parse(content, ctx)?))
+    }
+}
+
+impl Default for BrokerParserRegistry {
+    fn default() -> Self {
+        Self::with_defaults()
+    }
+}
+
+impl std::fmt::Debug for BrokerParserRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BrokerParserRegistry")
+            .field("parsers", &self.names())
+            .finish()
+    }
+}
+
+// ============================================================================
+// IDX formats
+// ============================================================================
+
+/// IDX broker text file: `Date|Symbol|BrokerCode|BuyVol|BuyVal|SellVol|SellVal`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IdxTextParser;
+
+impl BrokerDataParser for IdxTextParser {
+    fn name(&self) -> &'static str {
+        IDX_TEXT_PARSER
+    }
+
+    fn detect(&self, content: &[u8]) -> bool {
+        String::from_utf8_lossy(content)
+            .lines()
+            .find(|l| !l.trim().is_empty())
+            .is_some_and(|line| line.split('|').count() >= 7)
+    }
+
+    fn parse(
+        &self,
+        content: &[u8],
+        ctx: &BrokerParseContext,
+    ) -> Result<Vec<BrokerSummary>, DataSourceError> {
+        let text = String::from_utf8_lossy(content);
+        let mut summaries = Vec::new();
+
+        for line in text.lines() {
+            let fields: Vec<&str> = line.split('|').collect();
+
+            if fields.len() >= 7 {
+                let Some(date) = ctx.date.or_else(|| parse_date(fields[0])) else {
+                    continue;
+                };
+                let symbol = match ctx.symbol {
+                    Some(ref symbol) => symbol.clone(),
+                    None => fields[1].trim().to_uppercase(),
+                };
+                let broker_code = fields[2].trim().to_string();
+                let buy_volume: i64 = fields[3].trim().parse().unwrap_or(0);
+                let buy_value: Decimal = fields[4].trim().parse().unwrap_or(Decimal::ZERO);
+                let sell_volume: i64 = fields[5].trim().parse().unwrap_or(0);
+                let sell_value: Decimal = fields[6].trim().parse().unwrap_or(Decimal::ZERO);
+
+                if !broker_code.is_empty() && (buy_volume > 0 || sell_volume > 0) {
+                    summaries.push(BrokerSummary::new(
+                        date,
+                        symbol,
+                        broker_code,
+                        buy_volume,
+                        buy_value,
+                        sell_volume,
+                        sell_value,
+                    ));
+                }
+            }
+        }
+
+        Ok(summaries)
+    }
+}
+
+/// IDX broker summary HTML table: `BrokerCode | BuyVol | BuyVal | SellVol | SellVal`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IdxHtmlParser;
+
+impl BrokerDataParser for IdxHtmlParser {
+    fn name(&self) -> &'static str {
+        BROKER_HTML_PARSER
+    }
+
+    fn detect(&self, content: &[u8]) -> bool {
+        let head: String =
+            String::from_utf8_lossy(&content[..content.len().min(4096)]).to_lowercase();
+        head.contains("<html") || head.contains("<table") || head.contains("<!doctype")
+    }
+
+    fn parse(
+        &self,
+        content: &[u8],
+        ctx: &BrokerParseContext,
+    ) -> Result<Vec<BrokerSummary>, DataSourceError> {
+        let html = String::from_utf8_lossy(content);
+        let symbol = ctx.require_symbol()?;
+        let date = ctx.require_date()?;
+
+        let check = check_structure(&html, &BROKER_HTML_STRUCTURE);
+        if !check.is_healthy() {
+            return Err(DataSourceError::ParserOutdated(format!(
+                "{}: {}",
+                BROKER_HTML_PARSER,
+                check.describe()
+            )));
+        }
+
+        let document = Html::parse_document(&html);
+
+        let table_selector = Selector::parse("table.broker-summary, #broker-table, table")
+            .map_err(|_| DataSourceError::InvalidResponse("Invalid selector".into()))?;
+        let row_selector = Selector::parse("tbody tr")
+            .map_err(|_| DataSourceError::InvalidResponse("Invalid row selector".into()))?;
+        let cell_selector = Selector::parse("td")
+            .map_err(|_| DataSourceError::InvalidResponse("Invalid cell selector".into()))?;
+
+        let mut summaries = Vec::new();
+
+        for table in document.select(&table_selector) {
+            let text = table.text().collect::<String>().to_lowercase();
+
+            // Look for broker-related tables
+            if text.contains("broker") || text.contains("buy") || text.contains("sell") {
+                for row in table.select(&row_selector) {
+                    let cells: Vec<_> = row.select(&cell_selector).collect();
+
+                    // Expected format: BrokerCode | BuyVol | BuyVal | SellVol | SellVal
+                    if cells.len() >= 5 {
+                        let broker_code = cells[0].text().collect::<String>().trim().to_string();
+
+                        if broker_code.len() == 2
+                            && broker_code.chars().all(|c| c.is_alphanumeric())
+                        {
+                            let buy_volume = parse_number(&cells[1].text().collect::<String>());
+                            let buy_value = parse_decimal(&cells[2].text().collect::<String>());
+                            let sell_volume = parse_number(&cells[3].text().collect::<String>());
+                            let sell_value = parse_decimal(&cells[4].text().collect::<String>());
+
+                            if buy_volume > 0 || sell_volume > 0 {
+                                summaries.push(BrokerSummary::new(
+                                    date,
+                                    symbol.clone(),
+                                    broker_code,
+                                    buy_volume,
+                                    buy_value,
+                                    sell_volume,
+                                    sell_value,
+                                ));
+                            }
+                        }
+                    }
+                }
+
+                if !summaries.is_empty() {
+                    break;
+                }
+            }
+        }
+
+        Ok(summaries)
+    }
+}
+
+// ============================================================================
+// Broker terminal exports
+// ============================================================================
+
+/// Stockbit broker summary export
+///
+/// Buyers and sellers are listed side by side and ranked independently:
+/// `BY | B.val | B.lot | B.avg | SL | S.val | S.lot | S.avg`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StockbitCsvParser;
+
+impl BrokerDataParser for StockbitCsvParser {
+    fn name(&self) -> &'static str {
+        STOCKBIT_CSV_PARSER
+    }
+
+    fn detect(&self, content: &[u8]) -> bool {
+        CsvTable::read(content).is_some_and(|table| {
+            table.column(&["blot"]).is_some() && table.column(&["slot"]).is_some()
+        })
+    }
+
+    fn parse(
+        &self,
+        content: &[u8],
+        ctx: &BrokerParseContext,
+    ) -> Result<Vec<BrokerSummary>, DataSourceError> {
+        let table = CsvTable::read(content)
+            .ok_or_else(|| DataSourceError::InvalidResponse("Empty CSV".into()))?;
+        let symbol = ctx.require_symbol()?;
+        let date = ctx.require_date()?;
+
+        let missing = |name: &str| DataSourceError::InvalidResponse(format!("Missing {}", name));
+        let buyer = table
+            .column(&["by", "buyer"])
+            .ok_or_else(|| missing("BY"))?;
+        let buy_value = table.column(&["bval"]).ok_or_else(|| missing("B.val"))?;
+        let buy_lot = table.column(&["blot"]).ok_or_else(|| missing("B.lot"))?;
+        let seller = table
+            .column(&["sl", "seller"])
+            .ok_or_else(|| missing("SL"))?;
+        let sell_value = table.column(&["sval"]).ok_or_else(|| missing("S.val"))?;
+        let sell_lot = table.column(&["slot"]).ok_or_else(|| missing("S.lot"))?;
+
+        // broker -> (buy_volume, buy_value, sell_volume, sell_value)
+        let mut totals: BTreeMap<String, (i64, Decimal, i64, Decimal)> = BTreeMap::new();
+
+        for row in &table.rows {
+            let code = table.cell(row, buyer).trim().to_uppercase();
+            if !code.is_empty() {
+                let entry = totals.entry(code).or_default();
+                entry.0 += table.amount(row, buy_lot).to_i64().unwrap_or(0) * LOT_SIZE;
+                entry.1 += table.amount(row, buy_value);
+            }
+
+            let code = table.cell(row, seller).trim().to_uppercase();
+            if !code.is_empty() {
+                let entry = totals.entry(code).or_default();
+                entry.2 += table.amount(row, sell_lot).to_i64().unwrap_or(0) * LOT_SIZE;
+                entry.3 += table.amount(row, sell_value);
+            }
+        }
+
+        Ok(totals
+            .into_iter()
+            .map(|(code, (bv, bval, sv, sval))| {
+                BrokerSummary::new(date, symbol.clone(), code, bv, bval, sv, sval)
+            })
+            .collect())
+    }
+}
+
+/// Generic one-row-per-broker CSV export
+///
+/// Recognizes English and Indonesian headers (e.g. `Broker, Buy Lot,
+/// Buy Val, Sell Lot, Sell Val` or `Kode Broker, Lot Beli, Nilai Beli, ...`).
+/// Optional `Date`/`Symbol` columns override the parse context per row.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BrokerCsvParser;
+
+const BROKER_COLUMNS: &[&str] = &["broker", "brokercode", "kodebroker", "code", "kode"];
+const BUY_VOLUME_COLUMNS: &[&str] = &["buyvol", "buyvolume", "bvol", "volumebeli", "buyshares"];
+const BUY_LOT_COLUMNS: &[&str] = &["buylot", "lotbeli", "belilot"];
+const BUY_VALUE_COLUMNS: &[&str] = &["buyval", "buyvalue", "nilaibeli", "belinilai"];
+const SELL_VOLUME_COLUMNS: &[&str] = &["sellvol", "sellvolume", "svol", "volumejual", "sellshares"];
+const SELL_LOT_COLUMNS: &[&str] = &["selllot", "lotjual", "juallot"];
+const SELL_VALUE_COLUMNS: &[&str] = &["sellval", "sellvalue", "nilaijual", "jualnilai"];
+const DATE_COLUMNS: &[&str] = &["date", "tanggal", "tgl"];
+const SYMBOL_COLUMNS: &[&str] = &["symbol", "stock", "kodesaham", "emiten", "ticker", "saham"];
+
+impl BrokerDataParser for BrokerCsvParser {
+    fn name(&self) -> &'static str {
+        BROKER_CSV_PARSER
+    }
+
+    fn detect(&self, content: &[u8]) -> bool {
+        CsvTable::read(content).is_some_and(|table| {
+            table.column(BROKER_COLUMNS).is_some()
+                && table.column(BUY_VALUE_COLUMNS).is_some()
+                && table.column(SELL_VALUE_COLUMNS).is_some()
+        })
+    }
+
+    fn parse(
+        &self,
+        content: &[u8],
+        ctx: &BrokerParseContext,
+    ) -> Result<Vec<BrokerSummary>, DataSourceError> {
+        let table = CsvTable::read(content)
+            .ok_or_else(|| DataSourceError::InvalidResponse("Empty CSV".into()))?;
+
+        let missing = |name: &str| DataSourceError::InvalidResponse(format!("Missing {}", name));
+        let broker = table
+            .column(BROKER_COLUMNS)
+            .ok_or_else(|| missing("broker column"))?;
+        let buy_value = table
+            .column(BUY_VALUE_COLUMNS)
+            .ok_or_else(|| missing("buy value column"))?;
+        let sell_value = table
+            .column(SELL_VALUE_COLUMNS)
+            .ok_or_else(|| missing("sell value column"))?;
+        let buy_volume = table.column(BUY_VOLUME_COLUMNS);
+        let buy_lot = table.column(BUY_LOT_COLUMNS);
+        let sell_volume = table.column(SELL_VOLUME_COLUMNS);
+        let sell_lot = table.column(SELL_LOT_COLUMNS);
+        let date_col = table.column(DATE_COLUMNS);
+        let symbol_col = table.column(SYMBOL_COLUMNS);
+
+        let shares = |row: &csv::StringRecord, volume: Option<usize>, lot: Option<usize>| match (
+            volume, lot,
+        ) {
+            (Some(col), _) => table.amount(row, col).to_i64().unwrap_or(0),
+            (None, Some(col)) => table.amount(row, col).to_i64().unwrap_or(0) * LOT_SIZE,
+            (None, None) => 0,
+        };
+
+        let mut summaries = Vec::new();
+        for (index, row) in table.rows.iter().enumerate() {
+            let broker_code = table.cell(row, broker).trim().to_uppercase();
+            if broker_code.is_empty() || broker_code.len() > 4 {
+                continue;
+            }
+
+            let date = date_col
+                .and_then(|col| parse_date(table.cell(row, col)))
+                .or(ctx.date)
+                .ok_or_else(|| {
+                    DataSourceError::InvalidResponse(format!("Row {}: missing date", index + 2))
+                })?;
+            let symbol = symbol_col
+                .map(|col| table.cell(row, col).trim().to_uppercase())
+                .filter(|s| !s.is_empty())
+                .or_else(|| ctx.symbol.clone())
+                .ok_or_else(|| {
+                    DataSourceError::InvalidResponse(format!("Row {}: missing symbol", index + 2))
+                })?;
+
+            summaries.push(BrokerSummary::new(
+                date,
+                symbol,
+                broker_code,
+                shares(row, buy_volume, buy_lot),
+                table.amount(row, buy_value),
+                shares(row, sell_volume, sell_lot),
+                table.amount(row, sell_value),
+            ));
+        }
+
+        Ok(summaries)
+    }
+}
+
+/// Parse number from text (handles thousand separators)
+fn parse_number(text: &str) -> i64 {
+    let cleaned: String = text
+        .chars()
+        .filter(|c| c.is_ascii_digit() || *c == '-')
+        .collect();
+
+    cleaned.parse().unwrap_or(0)
+}
+
+/// Parse decimal from text
+fn parse_decimal(text: &str) -> Decimal {
+    let cleaned: String = text
+        .chars()
+        .filter(|c| c.is_ascii_digit() || *c == '.' || *c == ',' || *c == '-')
+        .collect();
+
+    let normalized = cleaned.replace(',', ".");
+    normalized.parse().unwrap_or(Decimal::ZERO)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn ctx() -> BrokerParseContext {
+        BrokerParseContext::new("bbca", NaiveDate::from_ymd_opt(2024, 1, 2).unwrap())
+    }
+
+    #[test]
+    fn test_idx_text_parser() {
+        let content = b"20240102|BBCA|BK|1000|9500000|200|1900000\n20240102|BBCA|YP|0|0|0|0\n";
+        let registry = BrokerParserRegistry::with_defaults();
+
+        let (name, summaries) = registry.parse(content, &ctx()).unwrap();
+        assert_eq!(name, IDX_TEXT_PARSER);
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].symbol, "BBCA");
+        assert_eq!(summaries[0].net_volume, 800);
+    }
+
+    #[test]
+    fn test_idx_html_parser_detects_layout_change() {
+        let parser = IdxHtmlParser;
+
+        let html = b"<html><div class='summary'><span>BK</span></div></html>";
+        let result = parser.parse(html, &ctx());
+        assert!(matches!(result, Err(DataSourceError::ParserOutdated(_))));
+
+        let html = b"<table><thead><tr><th>Broker</th><th>Buy Vol</th><th>Buy Val</th>\
+                    <th>Sell Vol</th><th>Sell Val</th></tr></thead><tbody>\
+                    <tr><td>BK</td><td>1,000</td><td>5000</td><td>200</td><td>1000</td></tr>\
+                    </tbody></table>";
+        let summaries = parser.parse(html, &ctx()).unwrap();
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].net_volume, 800);
+    }
+
+    #[test]
+    fn test_stockbit_csv_parser() {
+        let content = b"BY,B.val,B.lot,B.avg,SL,S.val,S.lot,S.avg\n\
+                        BK,12.5B,13000,9615,YP,4.2B,4400,9545\n\
+                        YP,1.1B,1100,9600,BK,500M,520,9615\n";
+        let registry = BrokerParserRegistry::with_defaults();
+
+        let (name, summaries) = registry.parse(content, &ctx()).unwrap();
+        assert_eq!(name, STOCKBIT_CSV_PARSER);
+        assert_eq!(summaries.len(), 2);
+
+        let bk = summaries.iter().find(|s| s.broker_code == "BK").unwrap();
+        assert_eq!(bk.buy_volume, 1_300_000);
+        assert_eq!(bk.sell_volume, 52_000);
+        assert_eq!(bk.buy_value, dec!(12_500_000_000));
+        assert_eq!(bk.net_value, dec!(12_000_000_000));
+    }
+
+    #[test]
+    fn test_broker_csv_parser_indonesian_locale() {
+        let content = "Tanggal;Kode Saham;Kode Broker;Lot Beli;Nilai Beli;Lot Jual;Nilai Jual\n\
+                       02/01/2024;BBRI;CC;1.000;5.500.000,50;250;1.375.000\n"
+            .as_bytes();
+        let registry = BrokerParserRegistry::with_defaults();
+
+        let (name, summaries) = registry
+            .parse(content, &BrokerParseContext::default())
+            .unwrap();
+        assert_eq!(name, BROKER_CSV_PARSER);
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].symbol, "BBRI");
+        assert_eq!(
+            summaries[0].date,
+            NaiveDate::from_ymd_opt(2024, 1, 2).unwrap()
+        );
+        assert_eq!(summaries[0].buy_volume, 100_000);
+        assert_eq!(summaries[0].buy_value, dec!(5500000.50));
+    }
+
+    #[test]
+    fn test_unrecognized_format() {
+        let registry = BrokerParserRegistry::with_defaults();
+        let result = registry.parse(b"hello world", &ctx());
+        assert!(matches!(result, Err(DataSourceError::UnsupportedFormat(_))));
+    }
+}
@@ -11,23 +11,38 @@
 
 use super::classification::{get_broker_category, is_foreign_broker};
 use super::models::{BrokerAccumulationScore, BrokerActivity, BrokerCategory, BrokerSummary};
+use super::parsers::{
+    BrokerParseContext, BrokerParserRegistry, BROKER_HTML_PARSER, BROKER_HTML_STRUCTURE,
+    IDX_TEXT_PARSER,
+};
+use crate::drift::{check_structure, ParserHealthReport};
 use crate::error::DataSourceError;
+use crate::http::{self, FaultHook};
 use chrono::NaiveDate;
 use reqwest::Client;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
-use scraper::{Html, Selector};
+use std::sync::Arc;
 use std::time::Duration;
 use tracing::{debug, info, warn};
 
 const IDX_DATA_URL: &str = "https://idxdata3.co.id";
 const RATE_LIMIT_DELAY_MS: u64 = 500;
 
+fn broker_summary_html_url(symbol: &str) -> String {
+    format!(
+        "https://www.idx.co.id/id/data-pasar/ringkasan-perdagangan/ringkasan-broker/?kodeEmiten={}",
+        symbol.to_uppercase()
+    )
+}
+
 /// Broker summary scraper client
 #[derive(Debug, Clone)]
 pub struct BrokerScraper {
     client: Client,
     rate_limit_delay: Duration,
+    parsers: Arc<BrokerParserRegistry>,
+    faults: FaultHook,
 }
 
 impl BrokerScraper {
@@ -42,6 +57,8 @@ impl BrokerScraper {
         Self {
             client,
             rate_limit_delay: Duration::from_millis(RATE_LIMIT_DELAY_MS),
+            parsers: Arc::new(BrokerParserRegistry::with_defaults()),
+            faults: FaultHook::default(),
         }
     }
 
@@ -51,6 +68,38 @@ impl BrokerScraper {
         self
     }
 
+    /// Create scraper with a custom parser registry
+    pub fn with_parsers(mut self, parsers: BrokerParserRegistry) -> Self {
+        self.parsers = Arc::new(parsers);
+        self
+    }
+
+    /// Route requests through a fault injector
+    #[cfg(feature = "fault-injection")]
+    pub fn with_faults(mut self, faults: Arc<crate::fault::FaultInjector>) -> Self {
+        self.faults = Some(faults);
+        self
+    }
+
+    /// Parser registry used for fetched and uploaded broker data
+    pub fn parsers(&self) -> &BrokerParserRegistry {
+        &self.parsers
+    }
+
+    /// Parse broker data with a named parser
+    fn parse_with(
+        &self,
+        parser: &str,
+        content: &[u8],
+        symbol: &str,
+        date: NaiveDate,
+    ) -> Result<Vec<BrokerSummary>, DataSourceError> {
+        let parser = self.parsers.get(parser).ok_or_else(|| {
+            DataSourceError::UnsupportedFormat(format!("Parser {} is not registered", parser))
+        })?;
+        parser.parse(content, &BrokerParseContext::new(symbol, date))
+    }
+
     /// Apply rate limiting
     async fn rate_limit(&self) {
         tokio::time::sleep(self.rate_limit_delay).await;
@@ -103,60 +152,16 @@ impl BrokerScraper {
 
         self.rate_limit().await;
 
-        let response =
-            self.client.get(&url).send().await.map_err(|e| {
-                DataSourceError::ApiError(format!("Failed to fetch IDX data: {}", e))
-            })?;
+        let response = http::send(&self.faults, http::SOURCE_IDX_BROKER, self.client.get(&url))
+            .await
+            .map_err(|e| DataSourceError::ApiError(format!("Failed to fetch IDX data: {}", e)))?;
 
-        if !response.status().is_success() {
+        if !response.status.is_success() {
             return Ok(vec![]);
         }
 
-        let text = response.text().await.map_err(|e| {
-            DataSourceError::InvalidResponse(format!("Failed to read response: {}", e))
-        })?;
-
         // Parse IDX broker data format (pipe-delimited)
-        self.parse_idx_broker_text(&text, symbol, date)
-    }
-
-    /// Parse IDX broker summary text format
-    fn parse_idx_broker_text(
-        &self,
-        text: &str,
-        symbol: &str,
-        date: NaiveDate,
-    ) -> Result<Vec<BrokerSummary>, DataSourceError> {
-        let mut summaries = Vec::new();
-
-        for line in text.lines() {
-            let fields: Vec<&str> = line.split('|').collect();
-
-            // IDX format: Date|Symbol|BrokerCode|BuyVol|BuyVal|SellVol|SellVal
-            if fields.len() >= 7 {
-                let broker_code = fields[2].trim().to_string();
-                let buy_volume: i64 = fields[3].trim().parse().unwrap_or(0);
-                let buy_value: Decimal = fields[4].trim().parse().unwrap_or(Decimal::ZERO);
-                let sell_volume: i64 = fields[5].trim().parse().unwrap_or(0);
-                let sell_value: Decimal = fields[6].trim().parse().unwrap_or(Decimal::ZERO);
-
-                if !broker_code.is_empty() && (buy_volume > 0 || sell_volume > 0) {
-                    summaries.push(BrokerSummary {
-                        date,
-                        symbol: symbol.to_string(),
-                        broker_code,
-                        buy_volume,
-                        sell_volume,
-                        buy_value,
-                        sell_value,
-                        net_volume: buy_volume - sell_volume,
-                        net_value: buy_value - sell_value,
-                    });
-                }
-            }
-        }
-
-        Ok(summaries)
+        self.parse_with(IDX_TEXT_PARSER, response.body.as_bytes(), symbol, date)
     }
 
     /// Fetch broker summary from HTML page (alternative source)
@@ -166,90 +171,40 @@ impl BrokerScraper {
         date: NaiveDate,
     ) -> Result<Vec<BrokerSummary>, DataSourceError> {
         // Alternative URL pattern for HTML broker data
-        let url = format!(
-            "https://www.idx.co.id/id/data-pasar/ringkasan-perdagangan/ringkasan-broker/?kodeEmiten={}",
-            symbol.to_uppercase()
-        );
+        let url = broker_summary_html_url(symbol);
 
         self.rate_limit().await;
 
-        let response = self.client.get(&url).send().await.map_err(|e| {
-            DataSourceError::ApiError(format!("Failed to fetch broker HTML: {}", e))
-        })?;
+        let response = http::send(&self.faults, http::SOURCE_IDX_BROKER, self.client.get(&url))
+            .await
+            .map_err(|e| {
+                DataSourceError::ApiError(format!("Failed to fetch broker HTML: {}", e))
+            })?;
 
-        if !response.status().is_success() {
+        if !response.status.is_success() {
             return Ok(vec![]);
         }
 
-        let html = response.text().await.map_err(|e| {
-            DataSourceError::InvalidResponse(format!("Failed to read response: {}", e))
-        })?;
-
-        self.parse_broker_html(&html, symbol, date)
+        self.parse_with(BROKER_HTML_PARSER, response.body.as_bytes(), symbol, date)
     }
 
-    /// Parse broker summary from HTML table
-    fn parse_broker_html(
-        &self,
-        html: &str,
-        symbol: &str,
-        date: NaiveDate,
-    ) -> Result<Vec<BrokerSummary>, DataSourceError> {
-        let document = Html::parse_document(html);
-
-        let table_selector = Selector::parse("table.broker-summary, #broker-table, table")
-            .map_err(|_| DataSourceError::InvalidResponse("Invalid selector".into()))?;
-        let row_selector = Selector::parse("tbody tr")
-            .map_err(|_| DataSourceError::InvalidResponse("Invalid row selector".into()))?;
-        let cell_selector = Selector::parse("td")
-            .map_err(|_| DataSourceError::InvalidResponse("Invalid cell selector".into()))?;
-
-        let mut summaries = Vec::new();
-
-        for table in document.select(&table_selector) {
-            let text = table.text().collect::<String>().to_lowercase();
-
-            // Look for broker-related tables
-            if text.contains("broker") || text.contains("buy") || text.contains("sell") {
-                for row in table.select(&row_selector) {
-                    let cells: Vec<_> = row.select(&cell_selector).collect();
-
-                    // Expected format: BrokerCode | BuyVol | BuyVal | SellVol | SellVal
-                    if cells.len() >= 5 {
-                        let broker_code = cells[0].text().collect::<String>().trim().to_string();
-
-                        if broker_code.len() == 2
-                            && broker_code.chars().all(|c| c.is_alphanumeric())
-                        {
-                            let buy_volume = parse_number(&cells[1].text().collect::<String>());
-                            let buy_value = parse_decimal(&cells[2].text().collect::<String>());
-                            let sell_volume = parse_number(&cells[3].text().collect::<String>());
-                            let sell_value = parse_decimal(&cells[4].text().collect::<String>());
-
-                            if buy_volume > 0 || sell_volume > 0 {
-                                summaries.push(BrokerSummary {
-                                    date,
-                                    symbol: symbol.to_string(),
-                                    broker_code,
-                                    buy_volume,
-                                    sell_volume,
-                                    buy_value,
-                                    sell_value,
-                                    net_volume: buy_volume - sell_volume,
-                                    net_value: buy_value - sell_value,
-                                });
-                            }
-                        }
-                    }
-                }
-
-                if !summaries.is_empty() {
-                    break;
-                }
-            }
+    /// Validate the broker HTML parser against a reference stock page
+    pub async fn self_check(&self, reference_symbol: &str) -> ParserHealthReport {
+        let url = broker_summary_html_url(reference_symbol);
+
+        match http::send(&self.faults, http::SOURCE_IDX_BROKER, self.client.get(&url)).await {
+            Ok(response) if response.status.is_success() => ParserHealthReport::from_check(
+                BROKER_HTML_PARSER,
+                &url,
+                check_structure(&response.body, &BROKER_HTML_STRUCTURE),
+            ),
+            Ok(response) => ParserHealthReport::unreachable(
+                BROKER_HTML_PARSER,
+                &url,
+                format!("HTTP {}", response.status),
+            ),
+            Err(e) => ParserHealthReport::unreachable(BROKER_HTML_PARSER, &url, e.to_string()),
         }
-
-        Ok(summaries)
     }
 
     /// Get multiple days of broker data for analysis
@@ -444,27 +399,6 @@ impl Default for BrokerScraper {
     }
 }
 
-/// Parse number from text (handles thousand separators)
-fn parse_number(text: &str) -> i64 {
-    let cleaned: String = text
-        .chars()
-        .filter(|c| c.is_ascii_digit() || *c == '-')
-        .collect();
-
-    cleaned.parse().unwrap_or(0)
-}
-
-/// Parse decimal from text
-fn parse_decimal(text: &str) -> Decimal {
-    let cleaned: String = text
-        .chars()
-        .filter(|c| c.is_ascii_digit() || *c == '.' || *c == ',' || *c == '-')
-        .collect();
-
-    let normalized = cleaned.replace(',', ".");
-    normalized.parse().unwrap_or(Decimal::ZERO)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
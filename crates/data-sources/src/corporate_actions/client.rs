@@ -0,0 +1,138 @@
+//! Corporate action client over IDX and KSEI
+
+use super::models::{CorporateAction, CorporateActionKind};
+use super::parser;
+use crate::error::DataSourceError;
+use crate::http::{self, FaultHook};
+use crate::idx::IdxClient;
+use chrono::NaiveDate;
+use reqwest::{Client, StatusCode};
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+const KSEI_SCHEDULE_URL: &str = "https://www.ksei.co.id/publications/corporate-action-schedules";
+
+/// Corporate action client
+#[derive(Debug, Clone)]
+pub struct CorporateActionClient {
+    idx: IdxClient,
+    client: Client,
+    faults: FaultHook,
+}
+
+impl CorporateActionClient {
+    pub fn new() -> Self {
+        Self::with_idx(IdxClient::new())
+    }
+
+    /// Share an IDX client, and its request pacing, with other jobs
+    pub fn with_idx(idx: IdxClient) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .user_agent("Mozilla/5.0 (compatible; JejakCuan/1.0)")
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self {
+            idx,
+            client,
+            faults: FaultHook::default(),
+        }
+    }
+
+    /// Route requests through a fault injector
+    #[cfg(feature = "fault-injection")]
+    pub fn with_faults(mut self, faults: std::sync::Arc<crate::fault::FaultInjector>) -> Self {
+        self.idx = self.idx.with_faults(faults.clone());
+        self.faults = Some(faults);
+        self
+    }
+
+    /// Actions announced on IDX since `from`
+    pub async fn get_idx_actions(
+        &self,
+        from: NaiveDate,
+    ) -> Result<Vec<CorporateAction>, DataSourceError> {
+        let records = self.idx.get_corporate_actions(from).await?;
+        Ok(records.into_iter().map(parser::from_idx).collect())
+    }
+
+    /// Upcoming and recent distributions on the KSEI schedule
+    pub async fn get_ksei_schedule(&self) -> Result<Vec<CorporateAction>, DataSourceError> {
+        debug!("Fetching KSEI corporate action schedule");
+        let response = http::send(
+            &self.faults,
+            http::SOURCE_KSEI,
+            self.client.get(KSEI_SCHEDULE_URL),
+        )
+        .await?;
+
+        if response.status == StatusCode::TOO_MANY_REQUESTS {
+            return Err(DataSourceError::RateLimited);
+        }
+        if !response.status.is_success() {
+            return Err(DataSourceError::ApiError(format!(
+                "KSEI returned {}",
+                response.status
+            )));
+        }
+        parser::parse_ksei_schedule(&response.body)
+    }
+
+    /// Actions since `from` from both sources, one per stock, kind and ex-date
+    ///
+    /// IDX records come first and KSEI fills their missing ratios, amounts
+    /// and prices. Fails only when both sources do.
+    pub async fn get_corporate_actions(
+        &self,
+        from: NaiveDate,
+    ) -> Result<Vec<CorporateAction>, DataSourceError> {
+        let idx = self.get_idx_actions(from).await;
+        let ksei = self.get_ksei_schedule().await;
+
+        let (idx, ksei) = match (idx, ksei) {
+            (Err(e), Err(ksei_error)) => {
+                warn!("KSEI corporate action schedule failed: {}", ksei_error);
+                return Err(e);
+            }
+            (Ok(idx), Err(e)) => {
+                warn!("KSEI corporate action schedule failed: {}", e);
+                (idx, Vec::new())
+            }
+            (Err(e), Ok(ksei)) => {
+                warn!("IDX corporate actions failed: {}", e);
+                (Vec::new(), ksei)
+            }
+            (Ok(idx), Ok(ksei)) => (idx, ksei),
+        };
+        let ksei = ksei
+            .into_iter()
+            .filter(|a| a.ex_date.is_none_or(|d| d >= from));
+        Ok(merge_actions(idx.into_iter().chain(ksei)))
+    }
+}
+
+impl Default for CorporateActionClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Merge reports of the same action, keeping the first seen and its order
+fn merge_actions(actions: impl IntoIterator<Item = CorporateAction>) -> Vec<CorporateAction> {
+    let mut merged: Vec<CorporateAction> = Vec::new();
+    let mut index: HashMap<(String, CorporateActionKind, Option<NaiveDate>), usize> =
+        HashMap::new();
+    for action in actions {
+        let key = (action.symbol.clone(), action.kind, action.ex_date);
+        match index.get(&key) {
+            Some(&i) => merged[i].fill_from(&action),
+            None => {
+                index.insert(key, merged.len());
+                merged.push(action);
+            }
+        }
+    }
+    merged
+}
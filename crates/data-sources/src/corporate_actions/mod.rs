@@ -0,0 +1,14 @@
+//! Corporate action announcements
+//!
+//! Dividends, splits, bonus shares and rights issues from the IDX
+//! announcement list, cross-checked against the KSEI distribution
+//! schedule. IDX announces first; KSEI often carries the ratio or exercise
+//! price the IDX record leaves in free text.
+
+mod client;
+mod models;
+mod parser;
+
+pub use client::CorporateActionClient;
+pub use models::*;
+pub use parser::{parse_id_number, parse_ksei_schedule, parse_ratio};
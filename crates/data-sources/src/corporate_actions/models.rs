@@ -0,0 +1,97 @@
+//! Corporate action models
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// Kind of corporate action
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CorporateActionKind {
+    CashDividend,
+    StockDividend,
+    StockSplit,
+    ReverseSplit,
+    BonusShares,
+    RightsIssue,
+    Other,
+}
+
+impl CorporateActionKind {
+    /// Stored `action_type`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::CashDividend => "dividend",
+            Self::StockDividend => "stock_dividend",
+            Self::StockSplit => "stock_split",
+            Self::ReverseSplit => "reverse_split",
+            Self::BonusShares => "bonus_shares",
+            Self::RightsIssue => "rights_issue",
+            Self::Other => "other",
+        }
+    }
+
+    /// Kind from an IDX or KSEI label, English or Indonesian
+    pub fn from_label(label: &str) -> Self {
+        let label = label.to_lowercase();
+        let has = |terms: &[&str]| terms.iter().any(|t| label.contains(t));
+
+        // Reverse splits first: their labels contain "split" as well
+        if has(&["reverse", "penggabungan"]) {
+            Self::ReverseSplit
+        } else if has(&["split", "pemecahan"]) {
+            Self::StockSplit
+        } else if has(&["rights", "hmetd", "penawaran umum terbatas"]) {
+            Self::RightsIssue
+        } else if has(&["bonus"]) {
+            Self::BonusShares
+        } else if has(&["stock dividend", "dividen saham"]) {
+            Self::StockDividend
+        } else if has(&["dividend", "dividen"]) {
+            Self::CashDividend
+        } else {
+            Self::Other
+        }
+    }
+
+    /// Whether the action moves the share price on its ex-date
+    pub fn adjusts_price(&self) -> bool {
+        !matches!(self, Self::Other)
+    }
+}
+
+/// A corporate action of one listed stock
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CorporateAction {
+    pub symbol: String,
+    pub kind: CorporateActionKind,
+    pub announced_date: Option<NaiveDate>,
+    /// First session trading without the entitlement
+    pub ex_date: Option<NaiveDate>,
+    /// Payment or listing date
+    pub effective_date: Option<NaiveDate>,
+    pub description: String,
+    /// Cash per share for dividends
+    pub amount: Option<Decimal>,
+    /// New shares per old share as announced: the shares after a split, the
+    /// additional shares of bonus issues, stock dividends and rights
+    pub ratio: Option<Decimal>,
+    /// Exercise price of rights
+    pub price: Option<Decimal>,
+    /// "idx" or "ksei"
+    pub source: String,
+}
+
+impl CorporateAction {
+    /// Fill fields missing here from another report of the same action
+    pub fn fill_from(&mut self, other: &CorporateAction) {
+        self.announced_date = self.announced_date.or(other.announced_date);
+        self.effective_date = self.effective_date.or(other.effective_date);
+        self.amount = self.amount.or(other.amount);
+        self.ratio = self.ratio.or(other.ratio);
+        self.price = self.price.or(other.price);
+        if self.description.is_empty() {
+            self.description = other.description.clone();
+        }
+    }
+}
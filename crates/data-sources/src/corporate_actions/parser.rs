@@ -0,0 +1,247 @@
+//! IDX record conversion and the KSEI schedule parser
+
+use super::models::{CorporateAction, CorporateActionKind};
+use crate::error::DataSourceError;
+use crate::idx::IdxCorporateAction;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use scraper::{ElementRef, Html, Selector};
+use std::str::FromStr;
+
+pub(crate) const SOURCE_IDX_LABEL: &str = "idx";
+pub(crate) const SOURCE_KSEI_LABEL: &str = "ksei";
+
+/// Parse a number in Indonesian or plain notation, with or without "Rp"
+///
+/// A comma marks decimals ("1.250,50"); without one, a dot followed by
+/// exactly three digits separates thousands ("1.250" is 1250).
+pub fn parse_id_number(raw: &str) -> Option<Decimal> {
+    let cleaned: String = raw
+        .trim()
+        .trim_start_matches("Rp")
+        .trim_start_matches("IDR")
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    if cleaned.is_empty() || cleaned == "-" {
+        return None;
+    }
+
+    let normalized = if cleaned.contains(',') {
+        cleaned.replace('.', "").replace(',', ".")
+    } else if cleaned
+        .rsplit_once('.')
+        .is_some_and(|(_, tail)| tail.len() == 3)
+    {
+        cleaned.replace('.', "")
+    } else {
+        cleaned
+    };
+    Decimal::from_str(&normalized).ok()
+}
+
+/// New shares per old share from an "old : new" ratio
+///
+/// "1 : 5" is 5 (a split), "10 : 1" is 0.1 (one bonus share per ten held).
+pub fn parse_ratio(raw: &str) -> Option<Decimal> {
+    let (old, new) = raw.split_once(':')?;
+    let old = parse_id_number(old)?;
+    let new = parse_id_number(new)?;
+    if old <= Decimal::ZERO || new <= Decimal::ZERO {
+        return None;
+    }
+    Some((new / old).round_dp(8))
+}
+
+pub(crate) fn from_idx(record: IdxCorporateAction) -> CorporateAction {
+    CorporateAction {
+        symbol: record.code.trim().to_uppercase(),
+        kind: CorporateActionKind::from_label(&record.action_type),
+        announced_date: record.announced_date,
+        ex_date: record.ex_date,
+        effective_date: record.effective_date,
+        description: record.description.trim().to_string(),
+        amount: record.value.filter(|v| *v > Decimal::ZERO),
+        ratio: record.ratio.as_deref().and_then(parse_ratio),
+        price: record.price.filter(|p| *p > Decimal::ZERO),
+        source: SOURCE_IDX_LABEL.to_string(),
+    }
+}
+
+/// Date as KSEI prints it: "05-Mar-2025", "05 Mei 2025", "05/03/2025" or ISO
+fn parse_ksei_date(raw: &str) -> Option<NaiveDate> {
+    let raw = raw.trim();
+    for format in ["%Y-%m-%d", "%d/%m/%Y", "%d-%m-%Y"] {
+        if let Ok(date) = NaiveDate::parse_from_str(raw, format) {
+            return Some(date);
+        }
+    }
+
+    let parts: Vec<&str> = raw.split(['-', ' ']).filter(|p| !p.is_empty()).collect();
+    let [day, month, year] = parts.as_slice() else {
+        return None;
+    };
+    let month = match month.to_lowercase().get(..3)? {
+        "jan" => 1,
+        "feb" => 2,
+        "mar" => 3,
+        "apr" => 4,
+        "may" | "mei" => 5,
+        "jun" => 6,
+        "jul" => 7,
+        "aug" | "agu" | "agt" => 8,
+        "sep" => 9,
+        "oct" | "okt" => 10,
+        "nov" => 11,
+        "dec" | "des" => 12,
+        _ => return None,
+    };
+    NaiveDate::from_ymd_opt(year.parse().ok()?, month, day.parse().ok()?)
+}
+
+/// Column positions of the schedule table, found by header text
+#[derive(Debug, Default)]
+struct ScheduleColumns {
+    code: Option<usize>,
+    kind: Option<usize>,
+    ex_date: Option<usize>,
+    effective_date: Option<usize>,
+    ratio: Option<usize>,
+    amount: Option<usize>,
+    price: Option<usize>,
+}
+
+impl ScheduleColumns {
+    fn from_headers(headers: &[String]) -> Self {
+        let find = |terms: &[&str]| {
+            headers
+                .iter()
+                .position(|h| terms.iter().any(|t| h.contains(t)))
+        };
+        Self {
+            code: find(&["kode", "code"]),
+            kind: find(&["jenis", "type"]),
+            ex_date: find(&["tanggal ex", "ex date"]),
+            effective_date: find(&["pembayaran", "payment", "distribusi", "distribution"]),
+            ratio: find(&["rasio", "ratio"]),
+            amount: find(&["nilai", "amount"]),
+            price: find(&["harga", "price"]),
+        }
+    }
+
+    fn missing(&self) -> Vec<&'static str> {
+        [
+            ("code", self.code),
+            ("type", self.kind),
+            ("ex date", self.ex_date),
+        ]
+        .into_iter()
+        .filter(|(_, column)| column.is_none())
+        .map(|(name, _)| name)
+        .collect()
+    }
+}
+
+fn cell_text(cell: &ElementRef) -> String {
+    cell.text()
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Parse the KSEI corporate action schedule page
+///
+/// Columns are located by header text, so reordering them is harmless; a
+/// page without code, type and ex-date columns means the layout changed.
+pub fn parse_ksei_schedule(html: &str) -> Result<Vec<CorporateAction>, DataSourceError> {
+    let document = Html::parse_document(html);
+    let table_selector = Selector::parse("table").expect("valid selector");
+    let header_selector = Selector::parse("thead th, tr th").expect("valid selector");
+    let row_selector = Selector::parse("tbody tr").expect("valid selector");
+    let cell_selector = Selector::parse("td").expect("valid selector");
+
+    let mut last_missing = vec!["table"];
+    for table in document.select(&table_selector) {
+        let headers: Vec<String> = table
+            .select(&header_selector)
+            .map(|th| cell_text(&th).to_lowercase().replace('-', " "))
+            .collect();
+        let columns = ScheduleColumns::from_headers(&headers);
+        let missing = columns.missing();
+        if !missing.is_empty() {
+            last_missing = missing;
+            continue;
+        }
+        let (Some(code), Some(kind), Some(ex_date)) = (columns.code, columns.kind, columns.ex_date)
+        else {
+            continue;
+        };
+
+        let actions = table
+            .select(&row_selector)
+            .filter_map(|row| {
+                let cells: Vec<String> =
+                    row.select(&cell_selector).map(|c| cell_text(&c)).collect();
+                let column =
+                    |index: Option<usize>| index.and_then(|i| cells.get(i)).map(String::as_str);
+                let symbol = column(Some(code))?.trim().to_uppercase();
+                if symbol.is_empty() {
+                    return None;
+                }
+                let label = column(Some(kind))?;
+                Some(CorporateAction {
+                    symbol,
+                    kind: CorporateActionKind::from_label(label),
+                    announced_date: None,
+                    ex_date: column(Some(ex_date)).and_then(parse_ksei_date),
+                    effective_date: column(columns.effective_date).and_then(parse_ksei_date),
+                    description: label.to_string(),
+                    amount: column(columns.amount).and_then(parse_id_number),
+                    ratio: column(columns.ratio).and_then(parse_ratio),
+                    price: column(columns.price).and_then(parse_id_number),
+                    source: SOURCE_KSEI_LABEL.to_string(),
+                })
+            })
+            .collect();
+        return Ok(actions);
+    }
+
+    Err(DataSourceError::ParserOutdated(format!(
+        "KSEI corporate action schedule: no table with {}",
+        last_missing.join(", ")
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_parse_id_number() {
+        assert_eq!(parse_id_number("Rp 1.250,50"), Some(dec!(1250.50)));
+        assert_eq!(parse_id_number("1.250"), Some(dec!(1250)));
+        assert_eq!(parse_id_number("12.5"), Some(dec!(12.5)));
+        assert_eq!(parse_id_number("Rp 85"), Some(dec!(85)));
+        assert_eq!(parse_id_number("-"), None);
+    }
+
+    #[test]
+    fn test_parse_ratio() {
+        assert_eq!(parse_ratio("1 : 5"), Some(dec!(5)));
+        assert_eq!(parse_ratio("10:1"), Some(dec!(0.1)));
+        assert_eq!(parse_ratio("5 : 0"), None);
+        assert_eq!(parse_ratio("n/a"), None);
+    }
+
+    #[test]
+    fn test_parse_ksei_date() {
+        let expected = NaiveDate::from_ymd_opt(2025, 5, 6);
+        assert_eq!(parse_ksei_date("06-May-2025"), expected);
+        assert_eq!(parse_ksei_date("06 Mei 2025"), expected);
+        assert_eq!(parse_ksei_date("06/05/2025"), expected);
+        assert_eq!(parse_ksei_date("2025-05-06"), expected);
+        assert_eq!(parse_ksei_date("besok"), None);
+    }
+}
@@ -0,0 +1,176 @@
+//! HTML structure drift detection for scrapers
+//!
+//! Scrapers probe a known reference page and verify that the selectors and
+//! table columns they depend on are still present, so a site redesign shows
+//! up as an outdated parser rather than silently empty data.
+
+use chrono::{DateTime, Utc};
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+
+/// Health of a scraper's HTML parser
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParserStatus {
+    Healthy,
+    Outdated,
+    Unreachable,
+}
+
+/// Result of a parser self-check against a reference page
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParserHealthReport {
+    pub parser: String,
+    pub reference_url: String,
+    pub status: ParserStatus,
+    pub missing_selectors: Vec<String>,
+    pub missing_columns: Vec<String>,
+    pub message: Option<String>,
+    pub checked_at: DateTime<Utc>,
+}
+
+impl ParserHealthReport {
+    /// Build a report from a structure check
+    pub fn from_check(parser: &str, reference_url: &str, check: StructureCheck) -> Self {
+        let status = if check.is_healthy() {
+            ParserStatus::Healthy
+        } else {
+            ParserStatus::Outdated
+        };
+
+        Self {
+            parser: parser.to_string(),
+            reference_url: reference_url.to_string(),
+            status,
+            missing_selectors: check.missing_selectors,
+            missing_columns: check.missing_columns,
+            message: None,
+            checked_at: Utc::now(),
+        }
+    }
+
+    /// Build a report for a reference page that could not be fetched
+    pub fn unreachable(parser: &str, reference_url: &str, message: String) -> Self {
+        Self {
+            parser: parser.to_string(),
+            reference_url: reference_url.to_string(),
+            status: ParserStatus::Unreachable,
+            missing_selectors: vec![],
+            missing_columns: vec![],
+            message: Some(message),
+            checked_at: Utc::now(),
+        }
+    }
+}
+
+/// Structure a parser expects to find on a page
+#[derive(Debug, Clone, Copy)]
+pub struct StructureExpectation {
+    /// Selector alternatives; at least one must match
+    pub selectors: &'static [&'static str],
+    /// Column keywords (lowercase) that must appear in the matched element,
+    /// each given as a set of accepted alternatives (e.g. English/Indonesian)
+    pub columns: &'static [&'static [&'static str]],
+}
+
+/// Outcome of checking a page against a [`StructureExpectation`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StructureCheck {
+    pub missing_selectors: Vec<String>,
+    pub missing_columns: Vec<String>,
+}
+
+impl StructureCheck {
+    pub fn is_healthy(&self) -> bool {
+        self.missing_selectors.is_empty() && self.missing_columns.is_empty()
+    }
+
+    /// Human-readable summary of what is missing
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.missing_selectors.is_empty() {
+            parts.push(format!("selectors [{}]", self.missing_selectors.join(", ")));
+        }
+        if !self.missing_columns.is_empty() {
+            parts.push(format!("columns [{}]", self.missing_columns.join(", ")));
+        }
+        format!("missing {}", parts.join(" and "))
+    }
+}
+
+/// Check an HTML page against the expected structure
+///
+/// Columns are checked against every matched element; the element with the
+/// fewest missing columns is used for the report.
+pub fn check_structure(html: &str, expectation: &StructureExpectation) -> StructureCheck {
+    let document = Html::parse_document(html);
+
+    let mut texts = Vec::new();
+    for raw in expectation.selectors {
+        if let Ok(selector) = Selector::parse(raw) {
+            texts.extend(
+                document
+                    .select(&selector)
+                    .map(|el| el.text().collect::<String>().to_lowercase()),
+            );
+        }
+    }
+
+    if texts.is_empty() {
+        return StructureCheck {
+            missing_selectors: vec![expectation.selectors.join(" | ")],
+            missing_columns: vec![],
+        };
+    }
+
+    let missing_columns = texts
+        .iter()
+        .map(|text| {
+            expectation
+                .columns
+                .iter()
+                .filter(|alternatives| !alternatives.iter().any(|c| text.contains(c)))
+                .map(|alternatives| alternatives.join("|"))
+                .collect::<Vec<_>>()
+        })
+        .min_by_key(|missing| missing.len())
+        .unwrap_or_default();
+
+    StructureCheck {
+        missing_selectors: vec![],
+        missing_columns,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPECTATION: StructureExpectation = StructureExpectation {
+        selectors: &["table.broker-summary", "table"],
+        columns: &[&["broker"], &["buy", "beli"]],
+    };
+
+    #[test]
+    fn test_structure_healthy() {
+        let html = "<table><tr><th>Broker</th><th>Beli</th></tr></table>";
+        let check = check_structure(html, &EXPECTATION);
+        assert!(check.is_healthy());
+    }
+
+    #[test]
+    fn test_structure_missing_selector() {
+        let html = "<div>Broker Beli</div>";
+        let check = check_structure(html, &EXPECTATION);
+        assert!(!check.is_healthy());
+        assert_eq!(check.missing_selectors.len(), 1);
+    }
+
+    #[test]
+    fn test_structure_missing_column() {
+        let html = "<table><tr><th>Broker</th><th>Net</th></tr></table>";
+        let check = check_structure(html, &EXPECTATION);
+        assert_eq!(check.missing_columns, vec!["buy|beli".to_string()]);
+        assert!(check.describe().contains("buy|beli"));
+    }
+}
@@ -5,7 +5,7 @@ use thiserror::Error;
 #[derive(Debug, Error)]
 pub enum DataSourceError {
     #[error("HTTP request failed: {0}")]
-    HttpError(#[from] reqwest::Error),
+    HttpError(reqwest::Error),
 
     #[error("JSON parsing failed: {0}")]
     JsonError(#[from] serde_json::Error),
@@ -16,9 +16,29 @@ pub enum DataSourceError {
     #[error("Symbol not found: {0}")]
     SymbolNotFound(String),
 
+    #[error("Request timed out: {0}")]
+    Timeout(String),
+
     #[error("Rate limited")]
     RateLimited,
 
     #[error("API error: {0}")]
     ApiError(String),
+
+    #[error("Parser outdated: {0}")]
+    ParserOutdated(String),
+
+    #[error("Unsupported format: {0}")]
+    UnsupportedFormat(String),
+}
+
+impl From<reqwest::Error> for DataSourceError {
+    /// Client timeouts become [`DataSourceError::Timeout`]
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            DataSourceError::Timeout(e.to_string())
+        } else {
+            DataSourceError::HttpError(e)
+        }
+    }
 }
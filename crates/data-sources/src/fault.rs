@@ -0,0 +1,157 @@
+//! Fault injection for data-source clients
+//!
+//! Enabled by the `fault-injection` feature, for tests only. A
+//! [`FaultInjector`] attached to a client intercepts its requests to one
+//! source and plays queued faults in order: timeouts, HTTP errors such as
+//! 429, malformed markup and truncated (partial) bodies. A stubbed body can
+//! stand in for the network, so failover, rate-limit handling and parser
+//! health reporting can be exercised offline and deterministically.
+
+use crate::error::DataSourceError;
+use crate::http::{read, HttpResponse};
+use reqwest::header::HeaderMap;
+use reqwest::{RequestBuilder, StatusCode};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+/// A failure to simulate on one request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fault {
+    /// The request never completes
+    Timeout,
+    /// The upstream answers with this status and an empty body
+    Status(u16),
+    /// The body is replaced by markup the parsers do not recognise
+    MalformedBody,
+    /// Only the first `keep_pct` percent of the body arrives
+    PartialBody { keep_pct: u8 },
+}
+
+impl Fault {
+    pub const RATE_LIMITED: Fault = Fault::Status(429);
+    pub const SERVICE_UNAVAILABLE: Fault = Fault::Status(503);
+}
+
+/// Markup served for [`Fault::MalformedBody`]
+pub const MALFORMED_BODY: &str =
+    "<html><body><div class=\"maintenance\"><p>Sedang dalam pemeliharaan</div></body>";
+
+#[derive(Debug, Default)]
+struct SourceFaults {
+    queue: VecDeque<Fault>,
+    stub: Option<String>,
+    requests: usize,
+    injected: usize,
+}
+
+/// Queued faults and stubbed responses per source
+#[derive(Debug, Default)]
+pub struct FaultInjector {
+    sources: Mutex<HashMap<String, SourceFaults>>,
+}
+
+impl FaultInjector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue `fault` for the next `times` requests to `source`
+    pub fn inject(&self, source: &str, fault: Fault, times: usize) -> &Self {
+        let mut sources = self.sources.lock().expect("fault injector lock poisoned");
+        let entry = sources.entry(source.to_string()).or_default();
+        entry.queue.extend(std::iter::repeat_n(fault, times));
+        self
+    }
+
+    /// Serve `body` with 200 OK instead of calling `source`
+    pub fn stub(&self, source: &str, body: impl Into<String>) -> &Self {
+        let mut sources = self.sources.lock().expect("fault injector lock poisoned");
+        sources.entry(source.to_string()).or_default().stub = Some(body.into());
+        self
+    }
+
+    /// Drop every queued fault and stub
+    pub fn clear(&self) {
+        self.sources
+            .lock()
+            .expect("fault injector lock poisoned")
+            .clear();
+    }
+
+    /// Requests seen for `source`
+    pub fn requests(&self, source: &str) -> usize {
+        self.with_source(source, |s| s.requests)
+    }
+
+    /// Faults played for `source`
+    pub fn injected(&self, source: &str) -> usize {
+        self.with_source(source, |s| s.injected)
+    }
+
+    /// Faults still queued for `source`
+    pub fn pending(&self, source: &str) -> usize {
+        self.with_source(source, |s| s.queue.len())
+    }
+
+    fn with_source(&self, source: &str, f: impl Fn(&SourceFaults) -> usize) -> usize {
+        self.sources
+            .lock()
+            .expect("fault injector lock poisoned")
+            .get(source)
+            .map(f)
+            .unwrap_or(0)
+    }
+
+    pub(crate) async fn send(
+        &self,
+        source: &str,
+        request: RequestBuilder,
+    ) -> Result<HttpResponse, DataSourceError> {
+        let (fault, stub) = {
+            let mut sources = self.sources.lock().expect("fault injector lock poisoned");
+            let entry = sources.entry(source.to_string()).or_default();
+            entry.requests += 1;
+            let fault = entry.queue.pop_front();
+            if fault.is_some() {
+                entry.injected += 1;
+            }
+            (fault, entry.stub.clone())
+        };
+
+        match fault {
+            Some(Fault::Timeout) => {
+                return Err(DataSourceError::Timeout(format!("{} (injected)", source)))
+            }
+            Some(Fault::Status(code)) => {
+                return Ok(HttpResponse {
+                    status: StatusCode::from_u16(code).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+                    headers: HeaderMap::new(),
+                    body: String::new(),
+                })
+            }
+            _ => {}
+        }
+
+        let mut response = match stub {
+            Some(body) => HttpResponse {
+                status: StatusCode::OK,
+                headers: HeaderMap::new(),
+                body,
+            },
+            None => read(request).await?,
+        };
+        match fault {
+            Some(Fault::MalformedBody) => response.body = MALFORMED_BODY.to_string(),
+            Some(Fault::PartialBody { keep_pct }) => {
+                let keep = response.body.len() * keep_pct.min(100) as usize / 100;
+                let cut = (0..=keep)
+                    .rev()
+                    .find(|i| response.body.is_char_boundary(*i))
+                    .unwrap_or(0);
+                response.body.truncate(cut);
+            }
+            _ => {}
+        }
+        Ok(response)
+    }
+}
@@ -0,0 +1,70 @@
+//! HTTP plumbing shared by the data-source clients
+//!
+//! Clients send requests through [`send`], which reads the whole response.
+//! With the `fault-injection` feature a client can carry a
+//! [`FaultInjector`](crate::fault::FaultInjector) that stands in for the
+//! upstream: failing the request, replacing the status or corrupting the body.
+
+use crate::error::DataSourceError;
+use reqwest::header::HeaderMap;
+use reqwest::{RequestBuilder, StatusCode};
+
+/// Fault targets, one per upstream
+pub const SOURCE_TWELVEDATA: &str = "twelvedata";
+pub const SOURCE_SECTORS: &str = "sectors";
+pub const SOURCE_YAHOO: &str = "yahoo";
+pub const SOURCE_IDX: &str = "idx";
+pub const SOURCE_IDX_BROKER: &str = "idx_broker";
+pub const SOURCE_NEWS: &str = "news";
+pub const SOURCE_KSEI: &str = "ksei";
+
+/// A fully read response
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: String,
+}
+
+impl HttpResponse {
+    pub fn json<T: serde::de::DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        serde_json::from_str(&self.body)
+    }
+}
+
+/// Faults a client may be carrying
+#[cfg(feature = "fault-injection")]
+pub type FaultHook = Option<std::sync::Arc<crate::fault::FaultInjector>>;
+
+/// Faults a client may be carrying (none without `fault-injection`)
+#[cfg(not(feature = "fault-injection"))]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FaultHook;
+
+/// Send `request` to `source` and read the response
+pub async fn send(
+    faults: &FaultHook,
+    source: &str,
+    request: RequestBuilder,
+) -> Result<HttpResponse, DataSourceError> {
+    #[cfg(feature = "fault-injection")]
+    if let Some(faults) = faults {
+        return faults.send(source, request).await;
+    }
+    #[cfg(not(feature = "fault-injection"))]
+    let _ = (faults, source);
+
+    read(request).await
+}
+
+pub(crate) async fn read(request: RequestBuilder) -> Result<HttpResponse, DataSourceError> {
+    let response = request.send().await?;
+    let status = response.status();
+    let headers = response.headers().clone();
+    let body = response.text().await?;
+    Ok(HttpResponse {
+        status,
+        headers,
+        body,
+    })
+}
@@ -0,0 +1,314 @@
+//! IDX public API client implementation
+//!
+//! Requests are spaced at least `min_interval` apart across all clones of a
+//! client, and 429s and server errors are retried with exponential backoff.
+
+use super::models::*;
+use crate::error::DataSourceError;
+use crate::http::{self, FaultHook};
+use crate::provider::ListedSymbol;
+use chrono::NaiveDate;
+use jejakcuan_core::{MarketCalendar, ReportPeriod};
+use reqwest::{Client, StatusCode};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+use tracing::{debug, warn};
+
+const BASE_URL: &str = "https://www.idx.co.id/primary";
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+/// Gap between requests; the site starts refusing clients much faster than this
+pub const DEFAULT_MIN_INTERVAL: Duration = Duration::from_millis(1000);
+const MAX_RETRIES: u32 = 3;
+/// Rows per table request, enough for the whole exchange in one page
+const PAGE_LENGTH: i64 = 9999;
+
+/// Spaces requests at least `min_interval` apart
+#[derive(Debug)]
+struct RequestPacer {
+    min_interval: Duration,
+    next_slot: Mutex<Option<Instant>>,
+}
+
+impl RequestPacer {
+    fn new(min_interval: Duration) -> Self {
+        Self {
+            min_interval,
+            next_slot: Mutex::new(None),
+        }
+    }
+
+    /// Wait for this caller's turn
+    async fn wait(&self) {
+        let slot = {
+            let mut next = self.next_slot.lock().await;
+            let now = Instant::now();
+            let slot = next.map_or(now, |next| next.max(now));
+            *next = Some(slot + self.min_interval);
+            slot
+        };
+        tokio::time::sleep_until(slot).await;
+    }
+}
+
+/// IDX public API client
+#[derive(Debug, Clone)]
+pub struct IdxClient {
+    client: Client,
+    pacer: Arc<RequestPacer>,
+    faults: FaultHook,
+}
+
+impl IdxClient {
+    /// Create a client pacing requests `DEFAULT_MIN_INTERVAL` apart
+    pub fn new() -> Self {
+        let client = Client::builder()
+            .timeout(DEFAULT_TIMEOUT)
+            .user_agent("Mozilla/5.0 (compatible; JejakCuan/1.0)")
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self {
+            client,
+            pacer: Arc::new(RequestPacer::new(DEFAULT_MIN_INTERVAL)),
+            faults: FaultHook::default(),
+        }
+    }
+
+    /// Space requests at least `min_interval` apart
+    ///
+    /// Retry backoff starts from the same interval.
+    pub fn with_min_interval(mut self, min_interval: Duration) -> Self {
+        self.pacer = Arc::new(RequestPacer::new(min_interval));
+        self
+    }
+
+    /// Route requests through a fault injector
+    #[cfg(feature = "fault-injection")]
+    pub fn with_faults(mut self, faults: Arc<crate::fault::FaultInjector>) -> Self {
+        self.faults = Some(faults);
+        self
+    }
+
+    /// Execute a paced GET request with retry logic
+    async fn get_with_retry<T: serde::de::DeserializeOwned>(
+        &self,
+        path: &str,
+        params: &[(&str, String)],
+    ) -> Result<T, DataSourceError> {
+        let url = format!("{}/{}", BASE_URL, path);
+        let mut last_error = None;
+        let mut backoff = self.pacer.min_interval;
+
+        for attempt in 0..MAX_RETRIES {
+            if attempt > 0 {
+                debug!("Retry attempt {} for {} after {:?}", attempt, url, backoff);
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+            }
+            self.pacer.wait().await;
+
+            let request = self
+                .client
+                .get(&url)
+                .header("Accept", "application/json")
+                .header("Referer", "https://www.idx.co.id/")
+                .query(params);
+
+            match http::send(&self.faults, http::SOURCE_IDX, request).await {
+                Ok(response) => {
+                    let status = response.status;
+                    if status == StatusCode::TOO_MANY_REQUESTS {
+                        warn!("Rate limited by IDX, will retry");
+                        last_error = Some(DataSourceError::RateLimited);
+                        continue;
+                    }
+
+                    if status.is_server_error() {
+                        warn!("Server error from IDX: {}", status);
+                        last_error = Some(DataSourceError::ApiError(format!(
+                            "Server error: {}",
+                            status
+                        )));
+                        continue;
+                    }
+
+                    if !status.is_success() {
+                        return Err(DataSourceError::InvalidResponse(format!(
+                            "IDX error {} for {}",
+                            status, path
+                        )));
+                    }
+
+                    return response.json::<T>().map_err(|e| {
+                        DataSourceError::InvalidResponse(format!(
+                            "Failed to parse IDX {}: {}",
+                            path, e
+                        ))
+                    });
+                }
+                Err(e) => {
+                    warn!("Network error calling IDX: {}", e);
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| DataSourceError::ApiError("Max retries exceeded".into())))
+    }
+
+    /// All listed stocks
+    pub async fn get_listed_companies(&self) -> Result<Vec<ListedCompany>, DataSourceError> {
+        let params = [
+            ("emitenType", "s".to_string()),
+            ("start", "0".to_string()),
+            ("length", PAGE_LENGTH.to_string()),
+        ];
+        let page: IdxPage<ListedCompany> = self
+            .get_with_retry("ListedCompany/GetCompanyProfiles", &params)
+            .await?;
+        Ok(page.data)
+    }
+
+    /// The stock universe as canonical symbols
+    pub async fn get_listed_symbols(&self) -> Result<Vec<ListedSymbol>, DataSourceError> {
+        let companies = self.get_listed_companies().await?;
+        Ok(companies
+            .iter()
+            .map(ListedCompany::to_listed_symbol)
+            .collect())
+    }
+
+    /// Members of an index such as `LQ45`, `IDX30` or `ISSI`
+    pub async fn get_index_constituents(
+        &self,
+        index_code: &str,
+    ) -> Result<Vec<IndexConstituent>, DataSourceError> {
+        let params = [
+            ("indexCode", index_code.to_uppercase()),
+            ("start", "0".to_string()),
+            ("length", PAGE_LENGTH.to_string()),
+        ];
+        let page: IdxPage<IndexConstituent> = self
+            .get_with_retry("StockData/GetConstituent", &params)
+            .await?;
+        if page.data.is_empty() {
+            return Err(DataSourceError::SymbolNotFound(index_code.to_string()));
+        }
+        Ok(page.data)
+    }
+
+    /// Exchange holidays in a calendar year
+    pub async fn get_holidays(&self, year: i32) -> Result<Vec<IdxHoliday>, DataSourceError> {
+        let params = [("year", year.to_string())];
+        let response: HolidaysResponse = self.get_with_retry("Home/GetHoliday", &params).await?;
+        Ok(response
+            .results
+            .into_iter()
+            .filter(|h| chrono::Datelike::year(&h.date) == year)
+            .collect())
+    }
+
+    /// Trading calendar for a calendar year
+    pub async fn get_trading_calendar(&self, year: i32) -> Result<MarketCalendar, DataSourceError> {
+        Ok(trading_calendar(&self.get_holidays(year).await?))
+    }
+
+    /// Trading summary of every listed stock on `date`
+    ///
+    /// Empty on days the market was closed.
+    pub async fn get_stock_summary(
+        &self,
+        date: NaiveDate,
+    ) -> Result<Vec<StockSummary>, DataSourceError> {
+        let params = [
+            ("date", date.format("%Y%m%d").to_string()),
+            ("start", "0".to_string()),
+            ("length", PAGE_LENGTH.to_string()),
+        ];
+        let page: IdxPage<StockSummary> = self
+            .get_with_retry("TradingSummary/GetStockSummary", &params)
+            .await?;
+        Ok(page.data)
+    }
+
+    /// Financial statements published for `period` of `fiscal_year`, every
+    /// listed stock
+    pub async fn get_financial_reports(
+        &self,
+        fiscal_year: i32,
+        period: ReportPeriod,
+    ) -> Result<Vec<IdxFinancialReport>, DataSourceError> {
+        let periode = match period {
+            ReportPeriod::Q1 => "tw1",
+            ReportPeriod::Q2 => "tw2",
+            ReportPeriod::Q3 => "tw3",
+            ReportPeriod::Annual => "audit",
+        };
+        let params = [
+            ("indexFrom", "0".to_string()),
+            ("pageSize", PAGE_LENGTH.to_string()),
+            ("year", fiscal_year.to_string()),
+            ("reportType", "rdf".to_string()),
+            ("EmitenType", "s".to_string()),
+            ("periode", periode.to_string()),
+            ("kodeEmiten", String::new()),
+        ];
+        let response: FinancialReportsResponse = self
+            .get_with_retry("ListedCompany/GetFinancialReport", &params)
+            .await?;
+        Ok(response.results)
+    }
+
+    /// Corporate actions announced on or after `from`, every listed stock
+    pub async fn get_corporate_actions(
+        &self,
+        from: NaiveDate,
+    ) -> Result<Vec<IdxCorporateAction>, DataSourceError> {
+        let params = [
+            ("fromDate", from.format("%Y-%m-%d").to_string()),
+            ("page", "1".to_string()),
+            ("pageSize", PAGE_LENGTH.to_string()),
+        ];
+        let response: CorporateActionsResponse = self
+            .get_with_retry("ListedCompany/GetCorporateAction", &params)
+            .await?;
+        Ok(response.results)
+    }
+}
+
+impl Default for IdxClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_pacer_spaces_requests() {
+        let pacer = RequestPacer::new(Duration::from_millis(40));
+        let start = Instant::now();
+        for _ in 0..3 {
+            pacer.wait().await;
+        }
+        // First request goes at once, the next two wait one interval each
+        assert!(start.elapsed() >= Duration::from_millis(80));
+    }
+
+    #[tokio::test]
+    async fn test_pacer_does_not_bank_idle_time() {
+        let pacer = RequestPacer::new(Duration::from_millis(40));
+        pacer.wait().await;
+        tokio::time::sleep(Duration::from_millis(60)).await;
+
+        let resumed = Instant::now();
+        pacer.wait().await;
+        assert!(resumed.elapsed() < Duration::from_millis(20));
+        pacer.wait().await;
+        assert!(resumed.elapsed() >= Duration::from_millis(40));
+    }
+}
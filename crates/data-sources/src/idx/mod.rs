@@ -0,0 +1,18 @@
+//! IDX (Indonesia Stock Exchange) public JSON API client
+//!
+//! The www.idx.co.id site serves its data tables from unauthenticated JSON
+//! endpoints under `/primary`:
+//! - Listed companies, the stock universe
+//! - Index constituents (LQ45, IDX30, ISSI, ...)
+//! - Exchange holidays, for the trading calendar
+//! - Daily trading summary for every listed stock
+//! - Corporate action announcements
+//! - Financial statement publication dates
+//!
+//! The site throttles aggressive clients, so requests are paced.
+
+mod client;
+mod models;
+
+pub use client::IdxClient;
+pub use models::*;
@@ -0,0 +1,262 @@
+//! Data models for IDX API responses
+//!
+//! Field names follow the upstream JSON, which mixes Indonesian and English.
+//! Dates arrive as `YYYY-MM-DDT00:00:00` without an offset and numbers as
+//! JSON floats.
+
+use crate::ohlcv::PriceBar;
+use crate::provider::ListedSymbol;
+use chrono::{NaiveDate, NaiveDateTime};
+use jejakcuan_core::{FiledReport, MarketCalendar, ReportPeriod};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// Paged table response shared by the `/primary` endpoints
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdxPage<T> {
+    #[serde(rename = "recordsTotal", default)]
+    pub records_total: i64,
+    #[serde(default = "Vec::new")]
+    pub data: Vec<T>,
+}
+
+/// Listed company from the company profiles table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListedCompany {
+    #[serde(rename = "KodeEmiten")]
+    pub code: String,
+    #[serde(rename = "NamaEmiten")]
+    pub name: String,
+    #[serde(rename = "Sektor", default, deserialize_with = "non_empty")]
+    pub sector: Option<String>,
+    #[serde(rename = "SubSektor", default, deserialize_with = "non_empty")]
+    pub subsector: Option<String>,
+    #[serde(rename = "Industri", default, deserialize_with = "non_empty")]
+    pub industry: Option<String>,
+    /// Listing board: Utama, Pengembangan, Akselerasi, Ekonomi Baru
+    #[serde(rename = "PapanPencatatan", default, deserialize_with = "non_empty")]
+    pub board: Option<String>,
+    #[serde(
+        rename = "TanggalPencatatan",
+        default,
+        deserialize_with = "idx_date_opt"
+    )]
+    pub listing_date: Option<NaiveDate>,
+}
+
+impl ListedCompany {
+    pub fn to_listed_symbol(&self) -> ListedSymbol {
+        ListedSymbol {
+            symbol: self.code.trim().to_uppercase(),
+            name: Some(self.name.trim().to_string()),
+        }
+    }
+}
+
+/// Member of an index
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexConstituent {
+    #[serde(rename = "Code")]
+    pub code: String,
+    #[serde(rename = "Name")]
+    pub name: String,
+    #[serde(rename = "Shares", default)]
+    pub shares: Option<Decimal>,
+    /// Weight in the index, percent
+    #[serde(rename = "Weight", default)]
+    pub weight: Option<Decimal>,
+}
+
+/// Exchange holiday
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdxHoliday {
+    #[serde(rename = "Date", deserialize_with = "idx_date")]
+    pub date: NaiveDate,
+    #[serde(rename = "Description", default)]
+    pub description: String,
+}
+
+/// Holiday list response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HolidaysResponse {
+    #[serde(rename = "Results", default = "Vec::new")]
+    pub results: Vec<IdxHoliday>,
+}
+
+/// Trading calendar with the given holidays
+pub fn trading_calendar(holidays: &[IdxHoliday]) -> MarketCalendar {
+    MarketCalendar::new(holidays.iter().map(|h| h.date))
+}
+
+/// One stock's row in the daily trading summary
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StockSummary {
+    #[serde(rename = "Date", deserialize_with = "idx_date")]
+    pub date: NaiveDate,
+    #[serde(rename = "StockCode")]
+    pub code: String,
+    #[serde(rename = "StockName", default)]
+    pub name: String,
+    #[serde(rename = "Previous")]
+    pub previous: Decimal,
+    #[serde(rename = "OpenPrice")]
+    pub open: Decimal,
+    #[serde(rename = "High")]
+    pub high: Decimal,
+    #[serde(rename = "Low")]
+    pub low: Decimal,
+    #[serde(rename = "Close")]
+    pub close: Decimal,
+    #[serde(rename = "Change", default)]
+    pub change: Decimal,
+    /// Regular market volume, shares
+    #[serde(rename = "Volume")]
+    pub volume: Decimal,
+    /// Regular market value, IDR
+    #[serde(rename = "Value", default)]
+    pub value: Decimal,
+    #[serde(rename = "Frequency", default)]
+    pub frequency: Decimal,
+    #[serde(rename = "ForeignBuy", default)]
+    pub foreign_buy: Decimal,
+    #[serde(rename = "ForeignSell", default)]
+    pub foreign_sell: Decimal,
+    #[serde(rename = "ListedShares", default)]
+    pub listed_shares: Option<Decimal>,
+}
+
+impl StockSummary {
+    /// Whether the stock traded in the regular market
+    ///
+    /// Untraded stocks are reported with a zero open and the previous close
+    /// carried into high, low and close.
+    pub fn traded(&self) -> bool {
+        self.volume > Decimal::ZERO && self.open > Decimal::ZERO
+    }
+
+    /// Net foreign flow in shares (buy minus sell)
+    pub fn foreign_net(&self) -> Decimal {
+        self.foreign_buy - self.foreign_sell
+    }
+
+    /// Daily bar for a traded stock
+    pub fn to_price_bar(&self) -> Option<PriceBar> {
+        if !self.traded() {
+            return None;
+        }
+        Some(PriceBar {
+            date: self.date,
+            symbol: self.code.trim().to_uppercase(),
+            open: self.open,
+            high: self.high,
+            low: self.low,
+            close: self.close,
+            volume: self.volume.trunc().to_i64()?,
+        })
+    }
+}
+
+/// Corporate action announcement
+///
+/// Ratios and exercise prices are often only in the description.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdxCorporateAction {
+    #[serde(rename = "Code")]
+    pub code: String,
+    /// e.g. "Cash Dividend", "Stock Split", "Rights Issue"
+    #[serde(rename = "Type", default)]
+    pub action_type: String,
+    #[serde(rename = "AnnouncedDate", default, deserialize_with = "idx_date_opt")]
+    pub announced_date: Option<NaiveDate>,
+    #[serde(rename = "ExDate", default, deserialize_with = "idx_date_opt")]
+    pub ex_date: Option<NaiveDate>,
+    /// Payment or listing date
+    #[serde(rename = "EffectiveDate", default, deserialize_with = "idx_date_opt")]
+    pub effective_date: Option<NaiveDate>,
+    #[serde(rename = "Description", default)]
+    pub description: String,
+    /// Cash per share for dividends
+    #[serde(rename = "Value", default)]
+    pub value: Option<Decimal>,
+    /// Old to new shares, e.g. "1 : 5"
+    #[serde(rename = "Ratio", default, deserialize_with = "non_empty")]
+    pub ratio: Option<String>,
+    /// Exercise price of rights
+    #[serde(rename = "Price", default)]
+    pub price: Option<Decimal>,
+}
+
+/// Published financial statement
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdxFinancialReport {
+    #[serde(rename = "KodeEmiten")]
+    pub code: String,
+    #[serde(rename = "NamaEmiten", default, deserialize_with = "non_empty")]
+    pub name: Option<String>,
+    /// "TW1", "TW2", "TW3" or "Tahunan"
+    #[serde(rename = "Report_Period", default)]
+    pub report_period: String,
+    #[serde(rename = "Report_Year", default)]
+    pub report_year: String,
+    /// Upload time of the statement files
+    #[serde(rename = "File_Modified", default, deserialize_with = "idx_date_opt")]
+    pub published_on: Option<NaiveDate>,
+}
+
+impl IdxFinancialReport {
+    /// Stock symbol and filing, `None` for unreadable periods or dates
+    pub fn to_filed_report(&self) -> Option<(String, FiledReport)> {
+        Some((
+            self.code.trim().to_uppercase(),
+            FiledReport {
+                period: ReportPeriod::from_idx_label(&self.report_period)?,
+                fiscal_year: self.report_year.trim().parse().ok()?,
+                filed_on: self.published_on?,
+            },
+        ))
+    }
+}
+
+/// Financial report list response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FinancialReportsResponse {
+    #[serde(rename = "Results", default = "Vec::new")]
+    pub results: Vec<IdxFinancialReport>,
+}
+
+/// Corporate action list response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CorporateActionsResponse {
+    #[serde(rename = "Results", default = "Vec::new")]
+    pub results: Vec<IdxCorporateAction>,
+}
+
+fn parse_idx_date(raw: &str) -> Option<NaiveDate> {
+    let raw = raw.trim();
+    NaiveDateTime::parse_from_str(raw, "%Y-%m-%dT%H:%M:%S")
+        .map(|dt| dt.date())
+        .or_else(|_| NaiveDate::parse_from_str(raw, "%Y-%m-%d"))
+        .ok()
+}
+
+fn idx_date<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NaiveDate, D::Error> {
+    let raw = String::deserialize(deserializer)?;
+    parse_idx_date(&raw)
+        .ok_or_else(|| serde::de::Error::custom(format!("invalid IDX date: {}", raw)))
+}
+
+fn idx_date_opt<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<NaiveDate>, D::Error> {
+    let raw = Option::<String>::deserialize(deserializer)?;
+    match raw.as_deref().map(str::trim) {
+        None | Some("") => Ok(None),
+        Some(raw) => parse_idx_date(raw)
+            .map(Some)
+            .ok_or_else(|| serde::de::Error::custom(format!("invalid IDX date: {}", raw))),
+    }
+}
+
+fn non_empty<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    let raw = Option::<String>::deserialize(deserializer)?;
+    Ok(raw.map(|s| s.trim().to_string()).filter(|s| !s.is_empty()))
+}
@@ -1,38 +1,84 @@
 //! Data source adapters for JejakCuan
 //!
 //! This crate handles fetching data from external APIs:
-//! - IDX (Indonesia Stock Exchange) data via Yahoo Finance
+//! - IDX (Indonesia Stock Exchange) listed companies, index constituents,
+//!   holidays, daily trading summary and financial report publications from
+//!   its public JSON API
 //! - Yahoo Finance for stock quotes and historical data
 //! - Sectors.app for Indonesian market data and financials
 //! - Broker summary data for institutional flow analysis
-//! - News sources for sentiment analysis
+//! - Corporate actions (dividends, splits, rights) from IDX and KSEI
+//! - Macro series from Bank Indonesia (BI-Rate, inflation, reserves) and FX
+//! - Indonesian financial news feeds (RSS/JSON Feed) tagged by ticker
 //! - Shareholding data from KSEI/OJK for ownership tracking
+//! - HTML-structure drift detection for scrapers
+//! - API key usage tracking and daily quota forecasts
+//! - Intraday OHLCV bar aggregation from the TwelveData price stream
+//! - Symbol mapping between provider notations
+//! - Pluggable price providers with priority failover
+//! - CSV/XLSX handling for user-uploaded broker and price exports
+//! - Fault injection for client failure tests (`fault-injection` feature)
 
 pub mod broker;
+pub mod corporate_actions;
+pub mod drift;
 pub mod error;
+#[cfg(feature = "fault-injection")]
+pub mod fault;
+pub mod http;
+pub mod idx;
+pub mod macro_data;
+pub mod news;
+pub mod ohlcv;
+pub mod provider;
+pub mod quota;
 pub mod sectors;
 pub mod shareholding;
+pub mod spreadsheet;
+pub mod symbols;
 pub mod twelvedata;
 pub mod yahoo;
 
 pub use broker::{
     get_broker_category, is_foreign_broker, is_institutional_broker, BrokerAccumulationScore,
-    BrokerActivity, BrokerCategory, BrokerScraper, BrokerSummary,
+    BrokerActivity, BrokerCategory, BrokerDataParser, BrokerParseContext, BrokerParserRegistry,
+    BrokerScraper, BrokerSummary, BROKER_HTML_PARSER,
 };
+pub use corporate_actions::{CorporateAction, CorporateActionClient, CorporateActionKind};
+pub use drift::{ParserHealthReport, ParserStatus};
 pub use error::DataSourceError;
+#[cfg(feature = "fault-injection")]
+pub use fault::{Fault, FaultInjector};
+pub use idx::{
+    trading_calendar, IdxClient, IdxCorporateAction, IdxFinancialReport, IdxHoliday,
+    IndexConstituent, ListedCompany, StockSummary,
+};
+pub use macro_data::{MacroDataClient, MacroIndicator, MacroObservation};
+pub use news::{tag_symbols, FeedFormat, NewsArticle, NewsClient, NewsFeed, DEFAULT_NEWS_FEEDS};
+pub use ohlcv::{parse_ohlcv_csv, ParsedOhlcv, PriceBar};
+pub use provider::{
+    FailoverProvider, ListedSymbol, PriceDataProvider, PriceQuote, ProviderHealth,
+    FAILURES_BEFORE_COOLDOWN,
+};
+pub use quota::{ApiProvider, ApiUsageTracker, QuotaForecast, RateLimitHeaders};
 pub use sectors::{
-    CompaniesResponse, CompanyFinancials, CompanyQuery, DailyTransaction, Industry, KeyExecutive,
-    MajorShareholder, SectorsClient, SectorsCompany, SectorsPagination, StockMover, Subsector,
-    TopMovers,
+    AnalystRatings, CompaniesResponse, CompanyFinancials, CompanyQuery, DailyTransaction, Industry,
+    KeyExecutive, MajorShareholder, SectorsClient, SectorsCompany, SectorsPagination, StockMover,
+    Subsector, TargetPriceConsensus, TopMovers,
 };
 pub use shareholding::{
     ConcentrationMetrics, InsiderActivityScore, InstitutionalFlow, OwnershipChange, Shareholder,
     ShareholderType, ShareholdingScore, ShareholdingScraper, ShareholdingSnapshot,
-    ShareholdingSource,
+    ShareholdingSource, SHAREHOLDING_HTML_PARSER,
+};
+pub use symbols::{
+    canonical_symbol, default_provider_symbol, ResolvedSymbol, SymbolMapper, SymbolMapping,
+    SymbolProvider,
 };
 pub use twelvedata::{
-    ExchangeInfo, Interval, LatestPrice, MarketMover, MarketMoversResponse, PriceUpdate, Quote,
-    StockInfo, TimeSeriesMeta, TimeSeriesPoint, TimeSeriesResponse, TwelveDataClient,
+    BarAggregator, ExchangeInfo, Interval, IntradayBar, KeyHealth, KeyPool, LatestPrice,
+    MarketMover, MarketMoversResponse, PriceUpdate, Quote, StockInfo, TickQueue, TickQueueConfig,
+    TickQueueStats, TimeSeriesMeta, TimeSeriesPoint, TimeSeriesResponse, TwelveDataClient,
     TwelveDataWebSocket, WebSocketEvent,
 };
 pub use yahoo::YahooFinanceClient;
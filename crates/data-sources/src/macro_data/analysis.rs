@@ -0,0 +1,88 @@
+//! Macro series trends
+
+use super::models::MacroObservation;
+use chrono::{Duration, NaiveDate};
+use jejakcuan_core::PolicyRateTrend;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+/// Value in effect on `date`: the latest observation on or before it
+///
+/// Observations must be sorted oldest first.
+pub fn value_as_of(observations: &[MacroObservation], date: NaiveDate) -> Option<Decimal> {
+    observations
+        .iter()
+        .rev()
+        .find(|o| o.date <= date)
+        .map(|o| o.value)
+}
+
+/// Absolute change of the latest value over the last `days`
+pub fn change_over(observations: &[MacroObservation], days: i64) -> Option<Decimal> {
+    let latest = observations.last()?;
+    let earlier = value_as_of(observations, latest.date - Duration::days(days))?;
+    Some(latest.value - earlier)
+}
+
+/// Percentage change of the latest value over the last `days`
+pub fn change_pct_over(observations: &[MacroObservation], days: i64) -> Option<Decimal> {
+    let latest = observations.last()?;
+    let earlier = value_as_of(observations, latest.date - Duration::days(days))?;
+    if earlier.is_zero() {
+        return None;
+    }
+    Some(((latest.value - earlier) / earlier * dec!(100)).round_dp(2))
+}
+
+/// Direction of the policy rate over the last `days`
+pub fn policy_rate_trend(rates: &[MacroObservation], days: i64) -> Option<PolicyRateTrend> {
+    change_over(rates, days).map(PolicyRateTrend::from_change)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::macro_data::MacroIndicator;
+
+    fn rate(y: i32, m: u32, d: u32, value: Decimal) -> MacroObservation {
+        MacroObservation {
+            indicator: MacroIndicator::BiRate,
+            date: NaiveDate::from_ymd_opt(y, m, d).unwrap(),
+            value,
+        }
+    }
+
+    #[test]
+    fn test_policy_rate_trend() {
+        let rates = vec![
+            rate(2024, 6, 20, dec!(6.25)),
+            rate(2024, 9, 18, dec!(6.00)),
+            rate(2025, 1, 15, dec!(5.75)),
+        ];
+        // Six months back from January the June rate was in effect
+        assert_eq!(change_over(&rates, 180), Some(dec!(-0.50)));
+        assert_eq!(
+            policy_rate_trend(&rates, 180),
+            Some(PolicyRateTrend::Easing)
+        );
+        // Nothing published that far back
+        assert_eq!(policy_rate_trend(&rates, 365), None);
+    }
+
+    #[test]
+    fn test_change_pct_over() {
+        let fx = vec![
+            MacroObservation {
+                indicator: MacroIndicator::UsdIdr,
+                date: NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+                value: dec!(16000),
+            },
+            MacroObservation {
+                indicator: MacroIndicator::UsdIdr,
+                date: NaiveDate::from_ymd_opt(2025, 4, 1).unwrap(),
+                value: dec!(16480),
+            },
+        ];
+        assert_eq!(change_pct_over(&fx, 90), Some(dec!(3)));
+    }
+}
@@ -0,0 +1,268 @@
+//! Macro data fetching
+//!
+//! Bank Indonesia publishes the BI-Rate, inflation and reserves as HTML
+//! tables with Indonesian dates ("17 Juli 2025", "Juni 2025") and decimal
+//! commas. Market series (USD/IDR, IHSG) come from the Yahoo chart API.
+
+use super::models::{MacroIndicator, MacroObservation};
+use crate::error::DataSourceError;
+use crate::yahoo::YahooFinanceClient;
+use chrono::NaiveDate;
+use reqwest::Client;
+use rust_decimal::Decimal;
+use scraper::{Html, Selector};
+use std::str::FromStr;
+use std::time::Duration;
+use tracing::debug;
+
+const BI_RATE_URL: &str = "https://www.bi.go.id/id/statistik/indikator/bi-rate.aspx";
+const BI_INFLATION_URL: &str = "https://www.bi.go.id/id/statistik/indikator/data-inflasi.aspx";
+const BI_RESERVES_URL: &str = "https://www.bi.go.id/id/statistik/indikator/cadangan-devisa.aspx";
+
+const YAHOO_USD_IDR: &str = "IDR=X";
+const YAHOO_COMPOSITE_INDEX: &str = "^JKSE";
+
+/// Client for Bank Indonesia indicators and market macro series
+#[derive(Debug, Clone)]
+pub struct MacroDataClient {
+    client: Client,
+    yahoo: YahooFinanceClient,
+}
+
+impl MacroDataClient {
+    pub fn new() -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36")
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self {
+            client,
+            yahoo: YahooFinanceClient::new(),
+        }
+    }
+
+    /// Fetch the published history of one indicator, oldest first
+    ///
+    /// `range` applies to market series only (Yahoo range, e.g. "1y", "5y");
+    /// Bank Indonesia pages list their full recent history.
+    pub async fn fetch(
+        &self,
+        indicator: MacroIndicator,
+        range: &str,
+    ) -> Result<Vec<MacroObservation>, DataSourceError> {
+        match indicator {
+            MacroIndicator::BiRate => self.fetch_bank_indonesia(indicator, BI_RATE_URL).await,
+            MacroIndicator::Inflation => {
+                self.fetch_bank_indonesia(indicator, BI_INFLATION_URL).await
+            }
+            MacroIndicator::ForeignReserves => {
+                self.fetch_bank_indonesia(indicator, BI_RESERVES_URL).await
+            }
+            MacroIndicator::UsdIdr => self.fetch_yahoo(indicator, YAHOO_USD_IDR, range).await,
+            MacroIndicator::CompositeIndex => {
+                self.fetch_yahoo(indicator, YAHOO_COMPOSITE_INDEX, range)
+                    .await
+            }
+        }
+    }
+
+    async fn fetch_bank_indonesia(
+        &self,
+        indicator: MacroIndicator,
+        url: &str,
+    ) -> Result<Vec<MacroObservation>, DataSourceError> {
+        debug!("Fetching {} from {}", indicator.as_str(), url);
+        let response = self.client.get(url).send().await?;
+        if response.status() == 429 {
+            return Err(DataSourceError::RateLimited);
+        }
+        if !response.status().is_success() {
+            return Err(DataSourceError::ApiError(format!(
+                "Bank Indonesia returned {} for {}",
+                response.status(),
+                indicator.as_str()
+            )));
+        }
+
+        let html = response.text().await?;
+        parse_indicator_table(&html, indicator)
+    }
+
+    async fn fetch_yahoo(
+        &self,
+        indicator: MacroIndicator,
+        ticker: &str,
+        range: &str,
+    ) -> Result<Vec<MacroObservation>, DataSourceError> {
+        let bars = self.yahoo.get_chart(ticker, "1d", range).await?;
+        Ok(bars
+            .into_iter()
+            .map(|bar| MacroObservation {
+                indicator,
+                date: bar.timestamp.date_naive(),
+                value: bar.close,
+            })
+            .collect())
+    }
+}
+
+impl Default for MacroDataClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Parse a Bank Indonesia indicator table into observations, oldest first
+///
+/// Each row holds a period in its first cell and the value in its last
+/// numeric cell; header and malformed rows are skipped.
+pub fn parse_indicator_table(
+    html: &str,
+    indicator: MacroIndicator,
+) -> Result<Vec<MacroObservation>, DataSourceError> {
+    let document = Html::parse_document(html);
+    let row_selector = Selector::parse("table tr").expect("valid selector");
+    let cell_selector = Selector::parse("td").expect("valid selector");
+
+    let mut observations: Vec<MacroObservation> = document
+        .select(&row_selector)
+        .filter_map(|row| {
+            let cells: Vec<String> = row
+                .select(&cell_selector)
+                .map(|c| c.text().collect::<String>().trim().to_string())
+                .collect();
+            let date = parse_period(cells.first()?)?;
+            let value = cells.iter().skip(1).rev().find_map(|c| parse_number(c))?;
+            Some(MacroObservation {
+                indicator,
+                date,
+                value,
+            })
+        })
+        .collect();
+
+    if observations.is_empty() {
+        return Err(DataSourceError::ParserOutdated(format!(
+            "no {} rows found in Bank Indonesia table",
+            indicator.as_str()
+        )));
+    }
+
+    observations.sort_by_key(|o| o.date);
+    observations.dedup_by_key(|o| o.date);
+    Ok(observations)
+}
+
+/// Parse "17 Juli 2025", "Juni 2025" or their English equivalents
+///
+/// Month-only periods map to the first day of the month.
+pub fn parse_period(text: &str) -> Option<NaiveDate> {
+    let parts: Vec<&str> = text.split_whitespace().collect();
+    match parts.as_slice() {
+        [day, month, year] => {
+            NaiveDate::from_ymd_opt(year.parse().ok()?, month_number(month)?, day.parse().ok()?)
+        }
+        [month, year] => NaiveDate::from_ymd_opt(year.parse().ok()?, month_number(month)?, 1),
+        _ => None,
+    }
+}
+
+fn month_number(name: &str) -> Option<u32> {
+    let name = name.trim_end_matches(',').to_lowercase();
+    let month = match name.get(..3)? {
+        "jan" => 1,
+        "feb" => 2,
+        "mar" => 3,
+        "apr" => 4,
+        "mei" | "may" => 5,
+        "jun" => 6,
+        "jul" => 7,
+        "agu" | "aug" => 8,
+        "sep" => 9,
+        "okt" | "oct" => 10,
+        "nov" => 11,
+        "des" | "dec" => 12,
+        _ => return None,
+    };
+    Some(month)
+}
+
+/// Parse "5,75 %", "5.75%", "150,2" or "1.234,5"
+pub fn parse_number(text: &str) -> Option<Decimal> {
+    let cleaned: String = text
+        .chars()
+        .filter(|c| c.is_ascii_digit() || matches!(c, '.' | ',' | '-'))
+        .collect();
+    if cleaned.is_empty() {
+        return None;
+    }
+
+    let normalized = match (cleaned.rfind(','), cleaned.rfind('.')) {
+        // Both present: the later one is the decimal separator
+        (Some(comma), Some(dot)) if comma > dot => cleaned.replace('.', "").replace(',', "."),
+        (Some(_), Some(_)) => cleaned.replace(',', ""),
+        // A lone comma followed by three digits groups thousands
+        (Some(comma), None) if cleaned.len() - comma == 4 => cleaned.replace(',', ""),
+        (Some(_), None) => cleaned.replace(',', "."),
+        _ => cleaned,
+    };
+    Decimal::from_str(&normalized).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_parse_period() {
+        assert_eq!(
+            parse_period("17 Juli 2025"),
+            NaiveDate::from_ymd_opt(2025, 7, 17)
+        );
+        assert_eq!(
+            parse_period("Desember 2024"),
+            NaiveDate::from_ymd_opt(2024, 12, 1)
+        );
+        assert_eq!(
+            parse_period("August 2024"),
+            NaiveDate::from_ymd_opt(2024, 8, 1)
+        );
+        assert_eq!(parse_period("Tanggal"), None);
+    }
+
+    #[test]
+    fn test_parse_number() {
+        assert_eq!(parse_number("5,75 %"), Some(dec!(5.75)));
+        assert_eq!(parse_number("5.75%"), Some(dec!(5.75)));
+        assert_eq!(parse_number("1.234,5"), Some(dec!(1234.5)));
+        assert_eq!(parse_number("16,250"), Some(dec!(16250)));
+        assert_eq!(parse_number("-"), None);
+    }
+
+    #[test]
+    fn test_parse_indicator_table() {
+        let html = r#"
+            <table>
+                <tr><th>No</th><th>Tanggal</th><th>BI-Rate</th></tr>
+                <tr><td>18 September 2024</td><td>6,00 %</td></tr>
+                <tr><td>15 Januari 2025</td><td>5,75 %</td></tr>
+                <tr><td>Catatan</td><td>-</td></tr>
+            </table>
+        "#;
+        let observations = parse_indicator_table(html, MacroIndicator::BiRate).unwrap();
+        assert_eq!(observations.len(), 2);
+        assert_eq!(observations[0].value, dec!(6.00));
+        assert_eq!(
+            observations[1].date,
+            NaiveDate::from_ymd_opt(2025, 1, 15).unwrap()
+        );
+
+        assert!(matches!(
+            parse_indicator_table("<html></html>", MacroIndicator::Inflation),
+            Err(DataSourceError::ParserOutdated(_))
+        ));
+    }
+}
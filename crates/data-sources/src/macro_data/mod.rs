@@ -0,0 +1,15 @@
+//! Macro data for the Indonesian market
+//!
+//! This module handles fetching and summarizing macro series:
+//! - Bank Indonesia policy rate, CPI inflation and foreign reserves
+//! - USD/IDR exchange rate and the IHSG composite index via Yahoo Finance
+//!
+//! Trends derived here feed the market regime detector in `jejakcuan_core`.
+
+mod analysis;
+mod client;
+mod models;
+
+pub use analysis::*;
+pub use client::*;
+pub use models::*;
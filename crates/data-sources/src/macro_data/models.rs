@@ -0,0 +1,71 @@
+//! Macro indicator models
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// Macro series tracked for the Indonesian market
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MacroIndicator {
+    /// Bank Indonesia policy rate (%)
+    BiRate,
+    /// Year-over-year CPI inflation (%)
+    Inflation,
+    /// Rupiah per US dollar
+    UsdIdr,
+    /// Official foreign exchange reserves (USD billion)
+    ForeignReserves,
+    /// IHSG (Jakarta Composite Index) close
+    CompositeIndex,
+}
+
+impl MacroIndicator {
+    pub const ALL: [MacroIndicator; 5] = [
+        Self::BiRate,
+        Self::Inflation,
+        Self::UsdIdr,
+        Self::ForeignReserves,
+        Self::CompositeIndex,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::BiRate => "bi_rate",
+            Self::Inflation => "inflation",
+            Self::UsdIdr => "usd_idr",
+            Self::ForeignReserves => "foreign_reserves",
+            Self::CompositeIndex => "composite_index",
+        }
+    }
+
+    pub fn from_str_opt(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|i| i.as_str() == s)
+    }
+
+    /// Display unit
+    pub fn unit(&self) -> &'static str {
+        match self {
+            Self::BiRate | Self::Inflation => "%",
+            Self::UsdIdr => "IDR",
+            Self::ForeignReserves => "USD bn",
+            Self::CompositeIndex => "pts",
+        }
+    }
+
+    /// Where the series is fetched from
+    pub fn source(&self) -> &'static str {
+        match self {
+            Self::BiRate | Self::Inflation | Self::ForeignReserves => "bank_indonesia",
+            Self::UsdIdr | Self::CompositeIndex => "yahoo",
+        }
+    }
+}
+
+/// One dated value of a macro series
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MacroObservation {
+    pub indicator: MacroIndicator,
+    pub date: NaiveDate,
+    pub value: Decimal,
+}
@@ -0,0 +1,66 @@
+//! News feed HTTP client
+
+use super::models::{FeedFormat, NewsArticle, NewsFeed};
+use super::parser;
+use crate::error::DataSourceError;
+use crate::http::{self, FaultHook};
+use reqwest::{Client, StatusCode};
+use std::time::Duration;
+use tracing::debug;
+
+/// News feed client
+#[derive(Debug, Clone)]
+pub struct NewsClient {
+    client: Client,
+    faults: FaultHook,
+}
+
+impl NewsClient {
+    pub fn new() -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .user_agent("Mozilla/5.0 (compatible; JejakCuan/1.0)")
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self {
+            client,
+            faults: FaultHook::default(),
+        }
+    }
+
+    /// Route requests through a fault injector
+    #[cfg(feature = "fault-injection")]
+    pub fn with_faults(mut self, faults: std::sync::Arc<crate::fault::FaultInjector>) -> Self {
+        self.faults = Some(faults);
+        self
+    }
+
+    /// Fetch and parse the current items of a feed
+    pub async fn fetch_feed(&self, feed: &NewsFeed) -> Result<Vec<NewsArticle>, DataSourceError> {
+        debug!("Fetching news feed {}", feed.id);
+        let response =
+            http::send(&self.faults, http::SOURCE_NEWS, self.client.get(feed.url)).await?;
+
+        if response.status == StatusCode::TOO_MANY_REQUESTS {
+            return Err(DataSourceError::RateLimited);
+        }
+        if !response.status.is_success() {
+            return Err(DataSourceError::ApiError(format!(
+                "{} returned {}",
+                feed.id, response.status
+            )));
+        }
+
+        match feed.format {
+            FeedFormat::Rss => parser::parse_rss(&response.body, feed.id),
+            FeedFormat::JsonFeed => parser::parse_json_feed(&response.body, feed.id),
+        }
+    }
+}
+
+impl Default for NewsClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,13 @@
+//! Indonesian financial news feeds
+//!
+//! Headlines and summaries from RSS and JSON Feed sources such as Kontan,
+//! IQPlus and CNBC Indonesia. Articles are tagged with the tickers they
+//! mention so their sentiment can be attributed to stocks.
+
+mod client;
+mod models;
+mod parser;
+
+pub use client::NewsClient;
+pub use models::*;
+pub use parser::{parse_json_feed, parse_rss};
@@ -0,0 +1,84 @@
+//! News feed and article models
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+
+/// Wire format of a feed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedFormat {
+    /// RSS 2.0 `<channel><item>` documents
+    Rss,
+    /// JSON Feed 1.x (jsonfeed.org)
+    JsonFeed,
+}
+
+/// A news source to poll
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct NewsFeed {
+    /// Stored as the article source
+    pub id: &'static str,
+    pub name: &'static str,
+    pub url: &'static str,
+    pub format: FeedFormat,
+}
+
+/// Feeds polled by default
+pub const DEFAULT_NEWS_FEEDS: &[NewsFeed] = &[
+    NewsFeed {
+        id: "kontan",
+        name: "Kontan Investasi",
+        url: "https://investasi.kontan.co.id/rss",
+        format: FeedFormat::Rss,
+    },
+    NewsFeed {
+        id: "iqplus",
+        name: "IQPlus Stock News",
+        url: "https://www.iqplus.info/rss/stock_news.xml",
+        format: FeedFormat::Rss,
+    },
+    NewsFeed {
+        id: "cnbc_indonesia",
+        name: "CNBC Indonesia Market",
+        url: "https://www.cnbcindonesia.com/market/rss",
+        format: FeedFormat::Rss,
+    },
+];
+
+/// A headline from a feed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NewsArticle {
+    /// Feed id
+    pub source: String,
+    pub title: String,
+    /// Plain-text summary, markup stripped
+    pub summary: Option<String>,
+    pub url: String,
+    pub published_at: DateTime<Utc>,
+}
+
+impl NewsArticle {
+    /// Title and summary, the text that is scored and tagged
+    pub fn text(&self) -> String {
+        match &self.summary {
+            Some(summary) => format!("{}. {}", self.title, summary),
+            None => self.title.clone(),
+        }
+    }
+}
+
+/// Known tickers mentioned in `text`, sorted
+///
+/// Tickers are matched as whole upper-case words, as the Indonesian
+/// financial press writes them ("saham BBRI", "(ANTM)"), so ordinary words
+/// that happen to spell a ticker in lower case do not match.
+pub fn tag_symbols(text: &str, known: &HashSet<String>) -> Vec<String> {
+    text.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| word.len() == 4 && word.chars().all(|c| c.is_ascii_uppercase()))
+        .filter(|word| known.contains(*word))
+        .map(str::to_string)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
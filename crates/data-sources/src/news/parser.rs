@@ -0,0 +1,106 @@
+//! RSS and JSON Feed parsers
+
+use super::models::NewsArticle;
+use crate::error::DataSourceError;
+use chrono::{DateTime, Utc};
+use scraper::Html;
+use serde::Deserialize;
+
+/// Parse an RSS 2.0 document
+///
+/// Items without a title, link or readable date are skipped.
+pub fn parse_rss(body: &str, source: &str) -> Result<Vec<NewsArticle>, DataSourceError> {
+    let doc = roxmltree::Document::parse(body)
+        .map_err(|e| DataSourceError::InvalidResponse(format!("Invalid RSS: {}", e)))?;
+    if !doc.root_element().has_tag_name("rss") {
+        return Err(DataSourceError::InvalidResponse(format!(
+            "Expected <rss>, found <{}>",
+            doc.root_element().tag_name().name()
+        )));
+    }
+
+    let articles = doc
+        .descendants()
+        .filter(|n| n.has_tag_name("item"))
+        .filter_map(|item| {
+            let child = |name: &str| {
+                item.children()
+                    .find(|c| c.has_tag_name(name))
+                    .and_then(|c| c.text())
+                    .map(str::trim)
+                    .filter(|t| !t.is_empty())
+            };
+            Some(NewsArticle {
+                source: source.to_string(),
+                title: plain_text(child("title")?)?,
+                summary: child("description").and_then(plain_text),
+                url: child("link")?.to_string(),
+                published_at: parse_date(child("pubDate")?)?,
+            })
+        })
+        .collect();
+    Ok(articles)
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonFeed {
+    #[serde(default)]
+    items: Vec<JsonFeedItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonFeedItem {
+    url: Option<String>,
+    title: Option<String>,
+    summary: Option<String>,
+    content_text: Option<String>,
+    date_published: Option<String>,
+}
+
+/// Parse a JSON Feed document
+///
+/// Items without a title, URL or readable date are skipped.
+pub fn parse_json_feed(body: &str, source: &str) -> Result<Vec<NewsArticle>, DataSourceError> {
+    let feed: JsonFeed = serde_json::from_str(body)
+        .map_err(|e| DataSourceError::InvalidResponse(format!("Invalid JSON Feed: {}", e)))?;
+    let articles = feed
+        .items
+        .into_iter()
+        .filter_map(|item| {
+            Some(NewsArticle {
+                source: source.to_string(),
+                title: plain_text(item.title.as_deref()?)?,
+                summary: item
+                    .summary
+                    .or(item.content_text)
+                    .as_deref()
+                    .and_then(plain_text),
+                url: item.url?,
+                published_at: parse_date(item.date_published.as_deref()?)?,
+            })
+        })
+        .collect();
+    Ok(articles)
+}
+
+/// RFC 2822 as RSS specifies, or RFC 3339 as some feeds send instead
+fn parse_date(raw: &str) -> Option<DateTime<Utc>> {
+    let raw = raw.trim();
+    DateTime::parse_from_rfc2822(raw)
+        .or_else(|_| DateTime::parse_from_rfc3339(raw))
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+/// Text of a possibly HTML fragment, whitespace collapsed
+fn plain_text(raw: &str) -> Option<String> {
+    let fragment = Html::parse_fragment(raw);
+    let text = fragment
+        .root_element()
+        .text()
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    (!text.is_empty()).then_some(text)
+}
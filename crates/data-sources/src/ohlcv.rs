@@ -0,0 +1,204 @@
+//! OHLCV export parsing for user-uploaded price files
+//!
+//! Accepts daily price exports from charting and trading apps with English
+//! or Indonesian headers, e.g. `Date, Open, High, Low, Close, Volume` or
+//! `Tanggal;Pembukaan;Tertinggi;Terendah;Penutupan;Volume (Lot)`.
+
+use crate::error::DataSourceError;
+use crate::spreadsheet::{parse_date, CsvTable};
+use crate::symbols::canonical_symbol;
+use chrono::NaiveDate;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// Shares per lot on IDX
+const LOT_SIZE: i64 = 100;
+
+const DATE_COLUMNS: &[&str] = &["date", "tanggal", "tgl", "time", "datetime"];
+const SYMBOL_COLUMNS: &[&str] = &["symbol", "ticker", "kodesaham", "kode", "stock", "emiten"];
+const OPEN_COLUMNS: &[&str] = &["open", "pembukaan", "buka", "openprice"];
+const HIGH_COLUMNS: &[&str] = &["high", "tertinggi", "highprice"];
+const LOW_COLUMNS: &[&str] = &["low", "terendah", "lowprice"];
+const CLOSE_COLUMNS: &[&str] = &["close", "penutupan", "tutup", "closeprice", "last"];
+const VOLUME_COLUMNS: &[&str] = &["volume", "vol", "volumeshares", "volumesaham"];
+const VOLUME_LOT_COLUMNS: &[&str] = &["volumelot", "lot", "vollot"];
+
+/// Daily OHLCV bar from an uploaded file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PriceBar {
+    pub date: NaiveDate,
+    pub symbol: String,
+    pub open: Decimal,
+    pub high: Decimal,
+    pub low: Decimal,
+    pub close: Decimal,
+    pub volume: i64,
+}
+
+impl PriceBar {
+    /// Check that the bar is internally consistent
+    pub fn validate(&self) -> Result<(), String> {
+        if self.low <= Decimal::ZERO {
+            return Err("prices must be positive".to_string());
+        }
+        if self.high < self.low {
+            return Err(format!("high {} below low {}", self.high, self.low));
+        }
+        if self.open > self.high || self.open < self.low {
+            return Err(format!("open {} outside high/low range", self.open));
+        }
+        if self.close > self.high || self.close < self.low {
+            return Err(format!("close {} outside high/low range", self.close));
+        }
+        if self.volume < 0 {
+            return Err("negative volume".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Bars parsed from a file plus rows that could not be read
+#[derive(Debug, Clone, Default)]
+pub struct ParsedOhlcv {
+    pub bars: Vec<PriceBar>,
+    /// Unreadable rows as `Row N: reason`, numbered from the first data row
+    pub errors: Vec<String>,
+}
+
+/// Parse an OHLCV CSV export
+///
+/// `default_symbol` is used when the file has no symbol column.
+pub fn parse_ohlcv_csv(
+    content: &[u8],
+    default_symbol: Option<&str>,
+) -> Result<ParsedOhlcv, DataSourceError> {
+    let table = CsvTable::read(content)
+        .ok_or_else(|| DataSourceError::UnsupportedFormat("Not a CSV price export".into()))?;
+
+    let missing = |name: &str| DataSourceError::InvalidResponse(format!("Missing {} column", name));
+    let date_col = table.column(DATE_COLUMNS).ok_or_else(|| missing("date"))?;
+    let open_col = table.column(OPEN_COLUMNS).ok_or_else(|| missing("open"))?;
+    let high_col = table.column(HIGH_COLUMNS).ok_or_else(|| missing("high"))?;
+    let low_col = table.column(LOW_COLUMNS).ok_or_else(|| missing("low"))?;
+    let close_col = table
+        .column(CLOSE_COLUMNS)
+        .ok_or_else(|| missing("close"))?;
+    let volume_col = table.column(VOLUME_COLUMNS);
+    let lot_col = table.column(VOLUME_LOT_COLUMNS);
+    let symbol_col = table.column(SYMBOL_COLUMNS);
+
+    if symbol_col.is_none() && default_symbol.is_none() {
+        return Err(DataSourceError::InvalidResponse(
+            "File has no symbol column and no symbol was given".into(),
+        ));
+    }
+
+    let mut parsed = ParsedOhlcv::default();
+    for (index, row) in table.rows.iter().enumerate() {
+        let row_number = index + 1;
+
+        // Timestamps such as `2024-01-02 00:00:00` keep only the date part
+        let date_text = table.cell(row, date_col);
+        let Some(date) = parse_date(date_text.split_whitespace().next().unwrap_or("")) else {
+            parsed
+                .errors
+                .push(format!("Row {}: invalid date {:?}", row_number, date_text));
+            continue;
+        };
+
+        let symbol = symbol_col
+            .map(|col| table.cell(row, col).trim().to_uppercase())
+            .filter(|s| !s.is_empty())
+            .or_else(|| default_symbol.map(str::to_uppercase));
+        let Some(symbol) = symbol.map(|s| canonical_symbol(&s)) else {
+            parsed
+                .errors
+                .push(format!("Row {}: missing symbol", row_number));
+            continue;
+        };
+
+        let volume = match (volume_col, lot_col) {
+            (Some(col), _) => table.amount(row, col).to_i64().unwrap_or(0),
+            (None, Some(col)) => table.amount(row, col).to_i64().unwrap_or(0) * LOT_SIZE,
+            (None, None) => 0,
+        };
+
+        parsed.bars.push(PriceBar {
+            date,
+            symbol,
+            open: table.amount(row, open_col),
+            high: table.amount(row, high_col),
+            low: table.amount(row, low_col),
+            close: table.amount(row, close_col),
+            volume,
+        });
+    }
+
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_parse_english_export() {
+        let content = b"Date,Open,High,Low,Close,Adj Close,Volume\n\
+                        2024-01-02,9400,9500,9375,9475,9475,51234500\n\
+                        bad-date,1,1,1,1,1,1\n";
+
+        let parsed = parse_ohlcv_csv(content, Some("bbca.jk")).unwrap();
+        assert_eq!(parsed.bars.len(), 1);
+        assert_eq!(parsed.errors.len(), 1);
+        assert_eq!(parsed.bars[0].symbol, "BBCA");
+        assert_eq!(parsed.bars[0].close, dec!(9475));
+        assert_eq!(parsed.bars[0].volume, 51_234_500);
+    }
+
+    #[test]
+    fn test_parse_indonesian_export_with_lots() {
+        let content = "Tanggal;Kode Saham;Pembukaan;Tertinggi;Terendah;Penutupan;Volume (Lot)\n\
+                       02/01/2024;BBRI;5.700;5.775;5.650;5.725;1.250\n"
+            .as_bytes();
+
+        let parsed = parse_ohlcv_csv(content, None).unwrap();
+        assert_eq!(parsed.bars.len(), 1);
+        assert_eq!(parsed.bars[0].symbol, "BBRI");
+        assert_eq!(parsed.bars[0].high, dec!(5775));
+        assert_eq!(parsed.bars[0].volume, 125_000);
+    }
+
+    #[test]
+    fn test_missing_symbol_is_rejected() {
+        let content = b"Date,Open,High,Low,Close,Volume\n2024-01-02,1,1,1,1,1\n";
+        assert!(parse_ohlcv_csv(content, None).is_err());
+    }
+
+    #[test]
+    fn test_validate_bar() {
+        let bar = PriceBar {
+            date: NaiveDate::from_ymd_opt(2024, 1, 2).unwrap(),
+            symbol: "BBCA".to_string(),
+            open: dec!(9400),
+            high: dec!(9500),
+            low: dec!(9375),
+            close: dec!(9475),
+            volume: 1000,
+        };
+        assert!(bar.validate().is_ok());
+
+        let inverted = PriceBar {
+            high: dec!(9300),
+            ..bar.clone()
+        };
+        assert!(inverted.validate().is_err());
+
+        let zero = PriceBar {
+            low: Decimal::ZERO,
+            ..bar
+        };
+        assert!(zero.validate().is_err());
+    }
+}
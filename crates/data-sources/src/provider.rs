@@ -0,0 +1,863 @@
+//! Pluggable price data providers with failover
+//!
+//! Yahoo Finance, TwelveData and Sectors.app all serve quotes and daily
+//! history for IDX stocks. `PriceDataProvider` puts them behind one interface
+//! keyed by canonical symbols, and `FailoverProvider` tries them in priority
+//! order. A provider that keeps failing is skipped for a while so a dead
+//! upstream does not add a timeout to every request.
+
+use crate::error::DataSourceError;
+use crate::ohlcv::PriceBar;
+use crate::sectors::{CompanyQuery, SectorsClient};
+use crate::symbols::{canonical_symbol, default_provider_symbol, SymbolMapper, SymbolProvider};
+use crate::twelvedata::{Interval, TwelveDataClient};
+use crate::yahoo::YahooFinanceClient;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration as ChronoDuration, FixedOffset, NaiveDate, Utc};
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+/// Consecutive failures after which a provider is skipped
+pub const FAILURES_BEFORE_COOLDOWN: u32 = 3;
+/// How long a failing provider is skipped
+const FAILURE_COOLDOWN: Duration = Duration::from_secs(300);
+/// How long a rate-limited provider is skipped
+const RATE_LIMIT_COOLDOWN: Duration = Duration::from_secs(60);
+
+/// IDX trading hours are in WIB (UTC+7)
+const WIB_OFFSET_SECS: i32 = 7 * 3600;
+/// Max rows TwelveData returns per time series request
+const TWELVEDATA_MAX_OUTPUT: i32 = 5000;
+/// Page size for the Sectors.app company screener
+const SECTORS_PAGE_SIZE: i32 = 200;
+/// Lookback for a Sectors.app quote, which is built from daily bars
+const SECTORS_QUOTE_LOOKBACK_DAYS: i64 = 14;
+
+/// Latest price for a symbol
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PriceQuote {
+    /// Canonical IDX symbol
+    pub symbol: String,
+    pub price: Decimal,
+    pub open: Option<Decimal>,
+    pub high: Option<Decimal>,
+    pub low: Option<Decimal>,
+    pub previous_close: Option<Decimal>,
+    pub volume: Option<i64>,
+    /// Quote time when the provider reports one
+    pub time: Option<DateTime<Utc>>,
+    /// Provider that served the quote
+    pub source: &'static str,
+}
+
+/// Listed stock from a provider's symbol list
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ListedSymbol {
+    /// Canonical IDX symbol
+    pub symbol: String,
+    pub name: Option<String>,
+}
+
+/// Source of IDX quotes, daily history and the listed symbol universe
+///
+/// Symbols are canonical (`BBCA`); each provider maps them to its own notation.
+/// A symbol already in the provider's notation, as a stored override is, is
+/// passed through unchanged.
+#[async_trait]
+pub trait PriceDataProvider: Send + Sync {
+    /// Short provider name used in logs and health reports
+    fn name(&self) -> &'static str;
+
+    async fn get_quote(&self, symbol: &str) -> Result<PriceQuote, DataSourceError>;
+
+    /// Daily bars between `from` and `to` inclusive, oldest first
+    async fn get_history(
+        &self,
+        symbol: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<PriceBar>, DataSourceError>;
+
+    async fn get_symbols(&self) -> Result<Vec<ListedSymbol>, DataSourceError>;
+}
+
+fn wib() -> FixedOffset {
+    FixedOffset::east_opt(WIB_OFFSET_SECS).expect("valid WIB offset")
+}
+
+fn decimal(value: Option<f64>) -> Option<Decimal> {
+    value.and_then(Decimal::from_f64)
+}
+
+/// Smallest Yahoo chart range that reaches back to `from`
+fn yahoo_range(from: NaiveDate, today: NaiveDate) -> &'static str {
+    let days = (today - from).num_days();
+    match days {
+        d if d <= 28 => "1mo",
+        d if d <= 90 => "3mo",
+        d if d <= 180 => "6mo",
+        d if d <= 365 => "1y",
+        d if d <= 730 => "2y",
+        d if d <= 1825 => "5y",
+        _ => "max",
+    }
+}
+
+/// Sort oldest first and keep bars inside `[from, to]`
+fn clip_bars(mut bars: Vec<PriceBar>, from: NaiveDate, to: NaiveDate) -> Vec<PriceBar> {
+    bars.retain(|b| b.date >= from && b.date <= to);
+    bars.sort_by_key(|b| b.date);
+    bars.dedup_by_key(|b| b.date);
+    bars
+}
+
+#[async_trait]
+impl PriceDataProvider for YahooFinanceClient {
+    fn name(&self) -> &'static str {
+        SymbolProvider::Yahoo.as_str()
+    }
+
+    async fn get_quote(&self, symbol: &str) -> Result<PriceQuote, DataSourceError> {
+        let quote = YahooFinanceClient::get_quote(self, symbol).await?;
+        let price = decimal(quote.regular_market_price).ok_or_else(|| {
+            DataSourceError::InvalidResponse(format!("Yahoo quote for {} has no price", symbol))
+        })?;
+
+        Ok(PriceQuote {
+            symbol: canonical_symbol(symbol),
+            price,
+            open: decimal(quote.regular_market_open),
+            high: decimal(quote.regular_market_high),
+            low: decimal(quote.regular_market_low),
+            previous_close: decimal(quote.regular_market_previous_close),
+            volume: quote.regular_market_volume,
+            time: None,
+            source: self.name(),
+        })
+    }
+
+    async fn get_history(
+        &self,
+        symbol: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<PriceBar>, DataSourceError> {
+        let today = Utc::now().with_timezone(&wib()).date_naive();
+        let range = yahoo_range(from, today);
+        let symbol = canonical_symbol(symbol);
+
+        let bars = YahooFinanceClient::get_history(self, &symbol, "1d", range)
+            .await?
+            .into_iter()
+            .map(|p| PriceBar {
+                date: p.timestamp.with_timezone(&wib()).date_naive(),
+                symbol: symbol.clone(),
+                open: p.open,
+                high: p.high,
+                low: p.low,
+                close: p.close,
+                volume: p.volume,
+            })
+            .collect();
+
+        Ok(clip_bars(bars, from, to))
+    }
+
+    /// Yahoo has no listing endpoint; returns the built-in IDX list
+    async fn get_symbols(&self) -> Result<Vec<ListedSymbol>, DataSourceError> {
+        Ok(YahooFinanceClient::get_idx_stock_list()
+            .into_iter()
+            .map(|symbol| ListedSymbol {
+                symbol: symbol.to_string(),
+                name: None,
+            })
+            .collect())
+    }
+}
+
+#[async_trait]
+impl PriceDataProvider for TwelveDataClient {
+    fn name(&self) -> &'static str {
+        SymbolProvider::TwelveData.as_str()
+    }
+
+    async fn get_quote(&self, symbol: &str) -> Result<PriceQuote, DataSourceError> {
+        let provider_symbol = default_provider_symbol(symbol, SymbolProvider::TwelveData);
+        let quote = self.quote(&provider_symbol).await?;
+        let price = quote.close.ok_or_else(|| {
+            DataSourceError::InvalidResponse(format!(
+                "TwelveData quote for {} has no price",
+                symbol
+            ))
+        })?;
+
+        Ok(PriceQuote {
+            symbol: canonical_symbol(symbol),
+            price,
+            open: quote.open,
+            high: quote.high,
+            low: quote.low,
+            previous_close: quote.previous_close,
+            volume: quote.volume,
+            time: quote
+                .timestamp
+                .and_then(|ts| DateTime::from_timestamp(ts, 0)),
+            source: self.name(),
+        })
+    }
+
+    async fn get_history(
+        &self,
+        symbol: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<PriceBar>, DataSourceError> {
+        let provider_symbol = default_provider_symbol(symbol, SymbolProvider::TwelveData);
+        let symbol = canonical_symbol(symbol);
+        // end_date is exclusive on TwelveData
+        let response = self
+            .time_series(
+                &provider_symbol,
+                Interval::Day1,
+                Some(TWELVEDATA_MAX_OUTPUT),
+                Some(from),
+                Some(to + ChronoDuration::days(1)),
+            )
+            .await?;
+
+        let bars = response
+            .values
+            .into_iter()
+            .filter_map(|p| {
+                let date = NaiveDate::parse_from_str(&p.datetime, "%Y-%m-%d").ok()?;
+                Some(PriceBar {
+                    date,
+                    symbol: symbol.clone(),
+                    open: p.open,
+                    high: p.high,
+                    low: p.low,
+                    close: p.close,
+                    volume: p.volume.unwrap_or(0),
+                })
+            })
+            .collect();
+
+        Ok(clip_bars(bars, from, to))
+    }
+
+    async fn get_symbols(&self) -> Result<Vec<ListedSymbol>, DataSourceError> {
+        Ok(self
+            .stocks("IDX")
+            .await?
+            .into_iter()
+            .map(|s| ListedSymbol {
+                symbol: canonical_symbol(&s.symbol),
+                name: Some(s.name),
+            })
+            .collect())
+    }
+}
+
+#[async_trait]
+impl PriceDataProvider for SectorsClient {
+    fn name(&self) -> &'static str {
+        SymbolProvider::Sectors.as_str()
+    }
+
+    /// Sectors.app has no live quote; uses the latest daily bar
+    async fn get_quote(&self, symbol: &str) -> Result<PriceQuote, DataSourceError> {
+        let to = Utc::now().with_timezone(&wib()).date_naive();
+        let from = to - ChronoDuration::days(SECTORS_QUOTE_LOOKBACK_DAYS);
+        let bars = PriceDataProvider::get_history(self, symbol, from, to).await?;
+
+        let last = bars
+            .last()
+            .ok_or_else(|| DataSourceError::SymbolNotFound(symbol.to_string()))?;
+        let previous_close = bars.len().checked_sub(2).map(|i| bars[i].close);
+
+        Ok(PriceQuote {
+            symbol: last.symbol.clone(),
+            price: last.close,
+            open: Some(last.open),
+            high: Some(last.high),
+            low: Some(last.low),
+            previous_close,
+            volume: Some(last.volume),
+            time: None,
+            source: self.name(),
+        })
+    }
+
+    async fn get_history(
+        &self,
+        symbol: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<PriceBar>, DataSourceError> {
+        let symbol = canonical_symbol(symbol);
+        let provider_symbol = default_provider_symbol(&symbol, SymbolProvider::Sectors);
+        let rows = self
+            .get_daily_transaction(&provider_symbol, &from.to_string(), &to.to_string())
+            .await?;
+
+        // Days without trades come back without OHLC
+        let bars = rows
+            .into_iter()
+            .filter_map(|row| {
+                Some(PriceBar {
+                    date: row.date,
+                    symbol: symbol.clone(),
+                    open: row.open?,
+                    high: row.high?,
+                    low: row.low?,
+                    close: row.close?,
+                    volume: row.volume.unwrap_or(0),
+                })
+            })
+            .collect();
+
+        Ok(clip_bars(bars, from, to))
+    }
+
+    async fn get_symbols(&self) -> Result<Vec<ListedSymbol>, DataSourceError> {
+        let mut symbols = Vec::new();
+        let mut offset = 0;
+        loop {
+            let page = self
+                .search_companies(
+                    CompanyQuery::new()
+                        .order_by("symbol")
+                        .limit(SECTORS_PAGE_SIZE)
+                        .offset(offset),
+                )
+                .await?;
+
+            symbols.extend(page.results.into_iter().map(|c| ListedSymbol {
+                symbol: canonical_symbol(&c.symbol),
+                name: Some(c.company_name),
+            }));
+
+            match page.pagination.next_offset {
+                Some(next) if page.pagination.has_next && next > offset => offset = next,
+                _ => break,
+            }
+        }
+        Ok(symbols)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct ProviderState {
+    requests: u64,
+    failures: u64,
+    consecutive_failures: u32,
+    last_error: Option<String>,
+    last_success: Option<DateTime<Utc>>,
+    cooldown_until: Option<Instant>,
+}
+
+impl ProviderState {
+    fn cooling_down(&self, now: Instant) -> bool {
+        self.cooldown_until.is_some_and(|until| until > now)
+    }
+}
+
+/// Health of one provider in a failover chain
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderHealth {
+    pub name: &'static str,
+    /// Position in the chain, 0 is tried first
+    pub priority: usize,
+    pub requests: u64,
+    pub failures: u64,
+    pub consecutive_failures: u32,
+    pub available: bool,
+    /// Seconds until a failing provider is tried again
+    pub cooldown_remaining_secs: Option<u64>,
+    pub last_error: Option<String>,
+    pub last_success: Option<DateTime<Utc>>,
+}
+
+/// Tries providers in priority order until one answers
+///
+/// A provider is skipped after `FAILURES_BEFORE_COOLDOWN` consecutive errors
+/// or a rate limit, until its cooldown ends. When every provider is cooling
+/// down they are still tried in priority order rather than failing outright.
+/// `SymbolNotFound` falls through to the next provider without counting
+/// against the one that answered.
+///
+/// Stored symbol overrides are applied before each call, and results come
+/// back under the canonical symbol.
+pub struct FailoverProvider {
+    providers: Vec<Arc<dyn PriceDataProvider>>,
+    state: Mutex<Vec<ProviderState>>,
+    symbols: RwLock<SymbolMapper>,
+}
+
+impl FailoverProvider {
+    /// Create a chain; `providers` are in priority order
+    pub fn new(providers: Vec<Arc<dyn PriceDataProvider>>) -> Self {
+        let state = vec![ProviderState::default(); providers.len()];
+        Self {
+            providers,
+            state: Mutex::new(state),
+            symbols: RwLock::new(SymbolMapper::new()),
+        }
+    }
+
+    /// Replace the symbol overrides applied before calling a provider
+    pub fn set_symbol_mapper(&self, mapper: SymbolMapper) {
+        *self.symbols.write().expect("symbol mapper lock poisoned") = mapper;
+    }
+
+    /// Notation of provider `index` that a chain name maps to, if any
+    fn symbol_provider(&self, index: usize) -> Option<SymbolProvider> {
+        SymbolProvider::from_str_opt(self.providers[index].name())
+    }
+
+    /// Symbol to send provider `index`: its override, else the canonical symbol
+    fn provider_symbol(&self, index: usize, symbol: &str) -> String {
+        let canonical = canonical_symbol(symbol);
+        let Some(provider) = self.symbol_provider(index) else {
+            return canonical;
+        };
+        let mapper = self.symbols.read().expect("symbol mapper lock poisoned");
+        let mapped = mapper.to_provider(&canonical, provider);
+        if mapped == default_provider_symbol(&canonical, provider) {
+            canonical
+        } else {
+            mapped
+        }
+    }
+
+    /// Canonical symbol for one a provider listed under its own notation
+    fn listed_canonical(&self, index: usize, symbol: &str) -> String {
+        let Some(provider) = self.symbol_provider(index) else {
+            return symbol.to_string();
+        };
+        self.symbols
+            .read()
+            .expect("symbol mapper lock poisoned")
+            .to_canonical(&default_provider_symbol(symbol, provider), provider)
+    }
+
+    pub fn len(&self) -> usize {
+        self.providers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.providers.is_empty()
+    }
+
+    /// Provider names in priority order
+    pub fn names(&self) -> Vec<&'static str> {
+        self.providers.iter().map(|p| p.name()).collect()
+    }
+
+    /// Indices to try: healthy providers first, then cooling ones, each by priority
+    fn order_at(&self, now: Instant) -> Vec<usize> {
+        let state = self.state.lock().expect("provider state lock poisoned");
+        let (mut healthy, cooling): (Vec<usize>, Vec<usize>) =
+            (0..self.providers.len()).partition(|&i| !state[i].cooling_down(now));
+        healthy.extend(cooling);
+        healthy
+    }
+
+    /// Record the outcome of a call; returns the value on success
+    fn record_at<T>(
+        &self,
+        index: usize,
+        result: Result<T, DataSourceError>,
+        errors: &mut Vec<(&'static str, DataSourceError)>,
+        now: Instant,
+    ) -> Option<T> {
+        let mut state = self.state.lock().expect("provider state lock poisoned");
+        let s = &mut state[index];
+        s.requests += 1;
+
+        match result {
+            Ok(value) => {
+                s.consecutive_failures = 0;
+                s.cooldown_until = None;
+                s.last_success = Some(Utc::now());
+                Some(value)
+            }
+            Err(DataSourceError::SymbolNotFound(symbol)) => {
+                errors.push((
+                    self.providers[index].name(),
+                    DataSourceError::SymbolNotFound(symbol),
+                ));
+                None
+            }
+            Err(e) => {
+                s.failures += 1;
+                s.consecutive_failures += 1;
+                s.last_error = Some(e.to_string());
+                if matches!(e, DataSourceError::RateLimited) {
+                    s.cooldown_until = Some(now + RATE_LIMIT_COOLDOWN);
+                } else if s.consecutive_failures >= FAILURES_BEFORE_COOLDOWN {
+                    s.cooldown_until = Some(now + FAILURE_COOLDOWN);
+                }
+                tracing::warn!(
+                    "Price provider {} failed ({} in a row): {}",
+                    self.providers[index].name(),
+                    s.consecutive_failures,
+                    e
+                );
+                errors.push((self.providers[index].name(), e));
+                None
+            }
+        }
+    }
+
+    fn exhausted(symbol: &str, errors: Vec<(&'static str, DataSourceError)>) -> DataSourceError {
+        if errors.is_empty() {
+            return DataSourceError::ApiError("No price providers configured".to_string());
+        }
+        if errors
+            .iter()
+            .all(|(_, e)| matches!(e, DataSourceError::SymbolNotFound(_)))
+        {
+            return DataSourceError::SymbolNotFound(symbol.to_string());
+        }
+        let detail = errors
+            .iter()
+            .map(|(name, e)| format!("{}: {}", name, e))
+            .collect::<Vec<_>>()
+            .join("; ");
+        DataSourceError::ApiError(format!("All price providers failed: {}", detail))
+    }
+
+    /// Health of every provider in priority order
+    pub fn health(&self) -> Vec<ProviderHealth> {
+        let now = Instant::now();
+        let state = self.state.lock().expect("provider state lock poisoned");
+        self.providers
+            .iter()
+            .zip(state.iter())
+            .enumerate()
+            .map(|(priority, (provider, s))| {
+                let cooldown = s
+                    .cooldown_until
+                    .filter(|until| *until > now)
+                    .map(|until| (until - now).as_secs());
+                ProviderHealth {
+                    name: provider.name(),
+                    priority,
+                    requests: s.requests,
+                    failures: s.failures,
+                    consecutive_failures: s.consecutive_failures,
+                    available: cooldown.is_none(),
+                    cooldown_remaining_secs: cooldown,
+                    last_error: s.last_error.clone(),
+                    last_success: s.last_success,
+                }
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl PriceDataProvider for FailoverProvider {
+    fn name(&self) -> &'static str {
+        "failover"
+    }
+
+    async fn get_quote(&self, symbol: &str) -> Result<PriceQuote, DataSourceError> {
+        let canonical = canonical_symbol(symbol);
+        let mut errors = Vec::new();
+        for index in self.order_at(Instant::now()) {
+            let result = self.providers[index]
+                .get_quote(&self.provider_symbol(index, symbol))
+                .await
+                .map(|quote| PriceQuote {
+                    symbol: canonical.clone(),
+                    ..quote
+                });
+            if let Some(quote) = self.record_at(index, result, &mut errors, Instant::now()) {
+                return Ok(quote);
+            }
+        }
+        Err(Self::exhausted(symbol, errors))
+    }
+
+    async fn get_history(
+        &self,
+        symbol: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<PriceBar>, DataSourceError> {
+        let canonical = canonical_symbol(symbol);
+        let mut errors = Vec::new();
+        for index in self.order_at(Instant::now()) {
+            let result = self.providers[index]
+                .get_history(&self.provider_symbol(index, symbol), from, to)
+                .await
+                .map(|bars| {
+                    bars.into_iter()
+                        .map(|bar| PriceBar {
+                            symbol: canonical.clone(),
+                            ..bar
+                        })
+                        .collect::<Vec<_>>()
+                });
+            if let Some(bars) = self.record_at(index, result, &mut errors, Instant::now()) {
+                return Ok(bars);
+            }
+        }
+        Err(Self::exhausted(symbol, errors))
+    }
+
+    async fn get_symbols(&self) -> Result<Vec<ListedSymbol>, DataSourceError> {
+        let mut errors = Vec::new();
+        for index in self.order_at(Instant::now()) {
+            let result = self.providers[index].get_symbols().await.map(|symbols| {
+                symbols
+                    .into_iter()
+                    .map(|listed| ListedSymbol {
+                        symbol: self.listed_canonical(index, &listed.symbol),
+                        ..listed
+                    })
+                    .collect::<Vec<_>>()
+            });
+            if let Some(symbols) = self.record_at(index, result, &mut errors, Instant::now()) {
+                return Ok(symbols);
+            }
+        }
+        Err(Self::exhausted("*", errors))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::symbols::SymbolMapping;
+    use rust_decimal_macros::dec;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    enum Behavior {
+        Ok,
+        Fail,
+        RateLimited,
+        NotFound,
+    }
+
+    struct FakeProvider {
+        name: &'static str,
+        behavior: Behavior,
+        calls: AtomicU32,
+        last_symbol: Mutex<Option<String>>,
+    }
+
+    impl FakeProvider {
+        fn new(name: &'static str, behavior: Behavior) -> Arc<Self> {
+            Arc::new(Self {
+                name,
+                behavior,
+                calls: AtomicU32::new(0),
+                last_symbol: Mutex::new(None),
+            })
+        }
+
+        fn calls(&self) -> u32 {
+            self.calls.load(Ordering::Relaxed)
+        }
+
+        fn outcome<T>(&self, value: T) -> Result<T, DataSourceError> {
+            self.calls.fetch_add(1, Ordering::Relaxed);
+            match self.behavior {
+                Behavior::Ok => Ok(value),
+                Behavior::Fail => Err(DataSourceError::ApiError("down".into())),
+                Behavior::RateLimited => Err(DataSourceError::RateLimited),
+                Behavior::NotFound => Err(DataSourceError::SymbolNotFound("XXXX".into())),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl PriceDataProvider for FakeProvider {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        async fn get_quote(&self, symbol: &str) -> Result<PriceQuote, DataSourceError> {
+            *self.last_symbol.lock().unwrap() = Some(symbol.to_string());
+            self.outcome(PriceQuote {
+                symbol: symbol.to_string(),
+                price: dec!(9000),
+                open: None,
+                high: None,
+                low: None,
+                previous_close: None,
+                volume: None,
+                time: None,
+                source: self.name,
+            })
+        }
+
+        async fn get_history(
+            &self,
+            _symbol: &str,
+            _from: NaiveDate,
+            _to: NaiveDate,
+        ) -> Result<Vec<PriceBar>, DataSourceError> {
+            self.outcome(Vec::new())
+        }
+
+        async fn get_symbols(&self) -> Result<Vec<ListedSymbol>, DataSourceError> {
+            self.outcome(Vec::new())
+        }
+    }
+
+    fn chain(providers: &[&Arc<FakeProvider>]) -> FailoverProvider {
+        FailoverProvider::new(
+            providers
+                .iter()
+                .map(|p| Arc::clone(*p) as Arc<dyn PriceDataProvider>)
+                .collect(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_failover_uses_first_healthy_provider() {
+        let primary = FakeProvider::new("primary", Behavior::Fail);
+        let backup = FakeProvider::new("backup", Behavior::Ok);
+        let failover = chain(&[&primary, &backup]);
+
+        let quote = failover.get_quote("BBCA").await.unwrap();
+        assert_eq!(quote.source, "backup");
+        assert_eq!(primary.calls(), 1);
+
+        let health = failover.health();
+        assert_eq!(health[0].failures, 1);
+        assert!(health[0].available);
+        assert!(health[1].last_success.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_failing_provider_cools_down() {
+        let primary = FakeProvider::new("primary", Behavior::Fail);
+        let backup = FakeProvider::new("backup", Behavior::Ok);
+        let failover = chain(&[&primary, &backup]);
+
+        for _ in 0..FAILURES_BEFORE_COOLDOWN + 2 {
+            failover.get_quote("BBCA").await.unwrap();
+        }
+
+        // Skipped once the failure threshold is reached
+        assert_eq!(primary.calls(), FAILURES_BEFORE_COOLDOWN);
+        assert!(!failover.health()[0].available);
+
+        // Back in front once the cooldown has passed
+        let later = Instant::now() + FAILURE_COOLDOWN;
+        assert_eq!(failover.order_at(later), vec![0, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_cools_down_immediately() {
+        let primary = FakeProvider::new("primary", Behavior::RateLimited);
+        let backup = FakeProvider::new("backup", Behavior::Ok);
+        let failover = chain(&[&primary, &backup]);
+
+        failover
+            .get_history("BBCA", NaiveDate::MIN, NaiveDate::MAX)
+            .await
+            .unwrap();
+        failover
+            .get_history("BBCA", NaiveDate::MIN, NaiveDate::MAX)
+            .await
+            .unwrap();
+
+        assert_eq!(primary.calls(), 1);
+        assert_eq!(failover.order_at(Instant::now()), vec![1, 0]);
+    }
+
+    #[tokio::test]
+    async fn test_all_cooling_still_tried() {
+        let primary = FakeProvider::new("primary", Behavior::RateLimited);
+        let backup = FakeProvider::new("backup", Behavior::RateLimited);
+        let failover = chain(&[&primary, &backup]);
+
+        assert!(failover.get_symbols().await.is_err());
+        let err = failover.get_symbols().await.unwrap_err();
+        assert!(err.to_string().contains("primary"));
+        assert_eq!(primary.calls(), 2);
+        assert_eq!(backup.calls(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_symbol_not_found_is_not_a_failure() {
+        let primary = FakeProvider::new("primary", Behavior::NotFound);
+        let backup = FakeProvider::new("backup", Behavior::NotFound);
+        let failover = chain(&[&primary, &backup]);
+
+        for _ in 0..FAILURES_BEFORE_COOLDOWN {
+            let err = failover.get_quote("XXXX").await.unwrap_err();
+            assert!(matches!(err, DataSourceError::SymbolNotFound(s) if s == "XXXX"));
+        }
+
+        let health = failover.health();
+        assert!(health.iter().all(|h| h.available && h.failures == 0));
+        assert_eq!(health[0].requests, FAILURES_BEFORE_COOLDOWN as u64);
+    }
+
+    #[tokio::test]
+    async fn test_overrides_are_sent_and_results_stay_canonical() {
+        let yahoo = FakeProvider::new("yahoo", Behavior::Ok);
+        let twelvedata = FakeProvider::new("twelvedata", Behavior::Ok);
+        let failover = chain(&[&yahoo, &twelvedata]);
+        failover.set_symbol_mapper(SymbolMapper::with_mappings([SymbolMapping {
+            symbol: "BREN".to_string(),
+            provider: SymbolProvider::Yahoo,
+            provider_symbol: "BREN-R.JK".to_string(),
+        }]));
+
+        let quote = failover.get_quote("BREN").await.unwrap();
+        assert_eq!(quote.symbol, "BREN");
+        assert_eq!(
+            yahoo.last_symbol.lock().unwrap().as_deref(),
+            Some("BREN-R.JK")
+        );
+
+        failover.get_quote("BBCA").await.unwrap();
+        assert_eq!(yahoo.last_symbol.lock().unwrap().as_deref(), Some("BBCA"));
+        assert_eq!(failover.listed_canonical(0, "BREN-R"), "BREN");
+    }
+
+    #[test]
+    fn test_yahoo_range_covers_from() {
+        let today = NaiveDate::from_ymd_opt(2024, 6, 30).unwrap();
+        assert_eq!(yahoo_range(today - ChronoDuration::days(20), today), "1mo");
+        assert_eq!(yahoo_range(today - ChronoDuration::days(60), today), "3mo");
+        assert_eq!(yahoo_range(today - ChronoDuration::days(400), today), "2y");
+        assert_eq!(
+            yahoo_range(today - ChronoDuration::days(4000), today),
+            "max"
+        );
+    }
+
+    #[test]
+    fn test_clip_bars_sorts_and_filters() {
+        let bar = |day: u32| PriceBar {
+            date: NaiveDate::from_ymd_opt(2024, 1, day).unwrap(),
+            symbol: "BBCA".to_string(),
+            open: dec!(100),
+            high: dec!(110),
+            low: dec!(90),
+            close: dec!(105),
+            volume: 1000,
+        };
+        let from = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+        let to = NaiveDate::from_ymd_opt(2024, 1, 4).unwrap();
+
+        let clipped = clip_bars(vec![bar(5), bar(3), bar(1), bar(2), bar(3)], from, to);
+        let dates: Vec<u32> = clipped
+            .iter()
+            .map(|b| chrono::Datelike::day(&b.date))
+            .collect();
+        assert_eq!(dates, vec![2, 3]);
+    }
+}
@@ -0,0 +1,430 @@
+//! API key usage tracking and daily quota forecasting
+//!
+//! Clients record every request against the key that made it. Usage resets
+//! at 00:00 UTC, matching the TwelveData and Sectors.app billing day. The
+//! forecast extrapolates today's call rate to estimate when a key's daily
+//! quota runs out, flagging keys that would run dry during the IDX session.
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Utc};
+use reqwest::header::HeaderMap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// IDX session in UTC (09:00-16:00 WIB)
+const SESSION_OPEN_UTC: (u32, u32) = (2, 0);
+const SESSION_CLOSE_UTC: (u32, u32) = (9, 0);
+
+/// Minimum window used for the call rate, so the first calls of the day
+/// don't extrapolate into an absurd burn rate
+const MIN_RATE_WINDOW_MINUTES: i64 = 15;
+
+/// Remaining quota share below which a key is always flagged
+const LOW_QUOTA_PCT: f64 = 10.0;
+
+/// External APIs with metered keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiProvider {
+    TwelveData,
+    Sectors,
+}
+
+impl ApiProvider {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::TwelveData => "twelvedata",
+            Self::Sectors => "sectors",
+        }
+    }
+
+    /// Environment variable holding the provider's daily credit quota
+    pub fn quota_env_var(&self) -> &'static str {
+        match self {
+            Self::TwelveData => "TWELVEDATA_DAILY_QUOTA",
+            Self::Sectors => "SECTORS_DAILY_QUOTA",
+        }
+    }
+
+    /// Free-tier daily quota when none is configured
+    pub fn default_daily_quota(&self) -> Option<u64> {
+        match self {
+            Self::TwelveData => Some(800),
+            Self::Sectors => None,
+        }
+    }
+}
+
+/// Identify a key without exposing it: only the last 4 characters are kept
+pub fn key_fingerprint(api_key: &str) -> String {
+    let chars: Vec<char> = api_key.chars().collect();
+    let tail: String = chars[chars.len().saturating_sub(4)..].iter().collect();
+    format!("...{}", tail)
+}
+
+/// Rate-limit information reported by the provider on a response
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimitHeaders {
+    pub limit: Option<u64>,
+    pub remaining: Option<u64>,
+}
+
+impl RateLimitHeaders {
+    /// Read TwelveData `api-credits-*` or generic `x-ratelimit-*` headers
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let read = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.trim().parse::<u64>().ok())
+        };
+
+        let remaining = read("api-credits-left").or_else(|| read("x-ratelimit-remaining"));
+        let limit = read("x-ratelimit-limit").or_else(|| {
+            let used = read("api-credits-used")?;
+            Some(used + remaining?)
+        });
+
+        Self { limit, remaining }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct KeyUsage {
+    day: NaiveDate,
+    credits: u64,
+    requests: u64,
+    rate_limited: u64,
+    first_call_at: DateTime<Utc>,
+    last_call_at: DateTime<Utc>,
+    last_headers: RateLimitHeaders,
+    warned_on: Option<NaiveDate>,
+}
+
+impl KeyUsage {
+    fn new(at: DateTime<Utc>) -> Self {
+        Self {
+            day: at.date_naive(),
+            credits: 0,
+            requests: 0,
+            rate_limited: 0,
+            first_call_at: at,
+            last_call_at: at,
+            last_headers: RateLimitHeaders::default(),
+            warned_on: None,
+        }
+    }
+}
+
+/// Usage and quota forecast of one API key for the current UTC day
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuotaForecast {
+    pub provider: ApiProvider,
+    /// Masked key, see [`key_fingerprint`]
+    pub key_id: String,
+    pub requests_today: u64,
+    pub credits_used: u64,
+    pub rate_limited_today: u64,
+    pub daily_quota: Option<u64>,
+    pub credits_remaining: Option<u64>,
+    pub credits_per_hour: f64,
+    /// When the quota runs out at the current rate, if before the daily reset
+    pub projected_exhaustion: Option<DateTime<Utc>>,
+    /// Projected exhaustion falls inside today's remaining IDX session
+    pub exhausts_mid_session: bool,
+    /// Latest per-minute limits reported by the provider
+    pub last_rate_limit: RateLimitHeaders,
+    pub last_call_at: DateTime<Utc>,
+    pub warning: bool,
+}
+
+/// Thread-safe per-key usage counters shared by API clients
+#[derive(Debug, Default)]
+pub struct ApiUsageTracker {
+    usage: Mutex<HashMap<(ApiProvider, String), KeyUsage>>,
+    daily_quotas: HashMap<ApiProvider, u64>,
+}
+
+impl ApiUsageTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a tracker with daily quotas from the environment or free-tier defaults
+    pub fn from_env() -> Self {
+        let mut tracker = Self::new();
+        for provider in [ApiProvider::TwelveData, ApiProvider::Sectors] {
+            let quota = std::env::var(provider.quota_env_var())
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .or_else(|| provider.default_daily_quota());
+            if let Some(quota) = quota {
+                tracker = tracker.with_daily_quota(provider, quota);
+            }
+        }
+        tracker
+    }
+
+    pub fn with_daily_quota(mut self, provider: ApiProvider, credits: u64) -> Self {
+        self.daily_quotas.insert(provider, credits);
+        self
+    }
+
+    /// Record a response from the provider
+    pub fn record_response(
+        &self,
+        provider: ApiProvider,
+        api_key: &str,
+        credits: u64,
+        status: reqwest::StatusCode,
+        headers: &HeaderMap,
+    ) {
+        self.record_at(
+            provider,
+            api_key,
+            credits,
+            status == reqwest::StatusCode::TOO_MANY_REQUESTS,
+            RateLimitHeaders::from_headers(headers),
+            Utc::now(),
+        );
+    }
+
+    fn record_at(
+        &self,
+        provider: ApiProvider,
+        api_key: &str,
+        credits: u64,
+        rate_limited: bool,
+        headers: RateLimitHeaders,
+        at: DateTime<Utc>,
+    ) {
+        let mut usage = self.usage.lock().expect("usage lock poisoned");
+        let entry = usage
+            .entry((provider, key_fingerprint(api_key)))
+            .or_insert_with(|| KeyUsage::new(at));
+
+        if entry.day != at.date_naive() {
+            let warned_on = entry.warned_on;
+            *entry = KeyUsage::new(at);
+            entry.warned_on = warned_on;
+        }
+
+        entry.requests += 1;
+        entry.last_call_at = at;
+        if rate_limited {
+            entry.rate_limited += 1;
+        } else {
+            entry.credits += credits;
+        }
+        if headers != RateLimitHeaders::default() {
+            entry.last_headers = headers;
+        }
+    }
+
+    /// Forecasts for every key that made calls today
+    pub fn forecasts(&self) -> Vec<QuotaForecast> {
+        self.forecasts_at(Utc::now())
+    }
+
+    fn forecasts_at(&self, now: DateTime<Utc>) -> Vec<QuotaForecast> {
+        let usage = self.usage.lock().expect("usage lock poisoned");
+        let mut forecasts: Vec<QuotaForecast> = usage
+            .iter()
+            .filter(|(_, u)| u.day == now.date_naive())
+            .map(|((provider, key_id), u)| {
+                forecast(
+                    *provider,
+                    key_id,
+                    u,
+                    self.daily_quotas.get(provider).copied(),
+                    now,
+                )
+            })
+            .collect();
+        forecasts.sort_by(|a, b| {
+            (a.provider.as_str(), &a.key_id).cmp(&(b.provider.as_str(), &b.key_id))
+        });
+        forecasts
+    }
+
+    /// Forecasts for one provider
+    pub fn provider_forecasts(&self, provider: ApiProvider) -> Vec<QuotaForecast> {
+        self.forecasts()
+            .into_iter()
+            .filter(|f| f.provider == provider)
+            .collect()
+    }
+
+    /// Mark a key as warned today; returns false if it was already warned
+    pub fn mark_warned(&self, provider: ApiProvider, key_id: &str) -> bool {
+        let today = Utc::now().date_naive();
+        let mut usage = self.usage.lock().expect("usage lock poisoned");
+        match usage.get_mut(&(provider, key_id.to_string())) {
+            Some(entry) if entry.warned_on != Some(today) => {
+                entry.warned_on = Some(today);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+fn forecast(
+    provider: ApiProvider,
+    key_id: &str,
+    usage: &KeyUsage,
+    daily_quota: Option<u64>,
+    now: DateTime<Utc>,
+) -> QuotaForecast {
+    let window_minutes = (now - usage.first_call_at)
+        .num_minutes()
+        .max(MIN_RATE_WINDOW_MINUTES);
+    let credits_per_hour = usage.credits as f64 / window_minutes as f64 * 60.0;
+    let credits_remaining = daily_quota.map(|q| q.saturating_sub(usage.credits));
+
+    let next_reset = (now.date_naive() + Duration::days(1))
+        .and_time(NaiveTime::MIN)
+        .and_utc();
+    let projected_exhaustion = credits_remaining.and_then(|remaining| {
+        if remaining == 0 {
+            return Some(now);
+        }
+        if credits_per_hour <= 0.0 {
+            return None;
+        }
+        let minutes = (remaining as f64 / credits_per_hour * 60.0).ceil() as i64;
+        Some(now + Duration::minutes(minutes)).filter(|at| *at < next_reset)
+    });
+
+    let session_bound = |(h, m): (u32, u32)| {
+        now.date_naive()
+            .and_time(NaiveTime::from_hms_opt(h, m, 0).expect("valid session time"))
+            .and_utc()
+    };
+    let session_open = session_bound(SESSION_OPEN_UTC);
+    let session_close = session_bound(SESSION_CLOSE_UTC);
+    let exhausts_mid_session =
+        projected_exhaustion.is_some_and(|at| at >= session_open && at < session_close);
+
+    let low_quota = match (credits_remaining, daily_quota) {
+        (Some(remaining), Some(quota)) if quota > 0 => {
+            (remaining as f64 / quota as f64 * 100.0) < LOW_QUOTA_PCT
+        }
+        _ => false,
+    };
+
+    QuotaForecast {
+        provider,
+        key_id: key_id.to_string(),
+        requests_today: usage.requests,
+        credits_used: usage.credits,
+        rate_limited_today: usage.rate_limited,
+        daily_quota,
+        credits_remaining,
+        credits_per_hour,
+        projected_exhaustion,
+        exhausts_mid_session,
+        last_rate_limit: usage.last_headers.clone(),
+        last_call_at: usage.last_call_at,
+        warning: exhausts_mid_session || low_quota,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use reqwest::header::HeaderValue;
+
+    fn at(h: u32, m: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 6, 3, h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn test_key_fingerprint_masks_key() {
+        assert_eq!(key_fingerprint("abcdef123456"), "...3456");
+        assert_eq!(key_fingerprint("ab"), "...ab");
+    }
+
+    #[test]
+    fn test_parse_twelvedata_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("api-credits-used", HeaderValue::from_static("3"));
+        headers.insert("api-credits-left", HeaderValue::from_static("5"));
+
+        let parsed = RateLimitHeaders::from_headers(&headers);
+        assert_eq!(parsed.limit, Some(8));
+        assert_eq!(parsed.remaining, Some(5));
+    }
+
+    #[test]
+    fn test_forecast_flags_mid_session_exhaustion() {
+        let tracker = ApiUsageTracker::new().with_daily_quota(ApiProvider::TwelveData, 800);
+        // 600 credits in the first hour after the open
+        for i in 0..60 {
+            tracker.record_at(
+                ApiProvider::TwelveData,
+                "key-1234",
+                10,
+                false,
+                RateLimitHeaders::default(),
+                at(2, i),
+            );
+        }
+
+        let forecasts = tracker.forecasts_at(at(3, 0));
+        let f = &forecasts[0];
+
+        assert_eq!(f.key_id, "...1234");
+        assert_eq!(f.credits_used, 600);
+        assert_eq!(f.credits_remaining, Some(200));
+        // 600/hour leaves 20 minutes of quota
+        assert_eq!(f.projected_exhaustion, Some(at(3, 20)));
+        assert!(f.exhausts_mid_session);
+        assert!(f.warning);
+    }
+
+    #[test]
+    fn test_slow_usage_does_not_warn() {
+        let tracker = ApiUsageTracker::new().with_daily_quota(ApiProvider::Sectors, 1000);
+        tracker.record_at(
+            ApiProvider::Sectors,
+            "sectors-key",
+            1,
+            false,
+            RateLimitHeaders::default(),
+            at(2, 0),
+        );
+
+        let forecasts = tracker.forecasts_at(at(4, 0));
+        assert!(forecasts[0].projected_exhaustion.is_none());
+        assert!(!forecasts[0].warning);
+    }
+
+    #[test]
+    fn test_usage_resets_daily() {
+        let tracker = ApiUsageTracker::new();
+        let yesterday = at(8, 0) - Duration::days(1);
+        tracker.record_at(
+            ApiProvider::TwelveData,
+            "key",
+            50,
+            false,
+            RateLimitHeaders::default(),
+            yesterday,
+        );
+        assert!(tracker.forecasts_at(at(3, 0)).is_empty());
+
+        tracker.record_at(
+            ApiProvider::TwelveData,
+            "key",
+            1,
+            true,
+            RateLimitHeaders::default(),
+            at(3, 0),
+        );
+        let forecasts = tracker.forecasts_at(at(3, 0));
+        assert_eq!(forecasts[0].credits_used, 0);
+        assert_eq!(forecasts[0].rate_limited_today, 1);
+    }
+}
@@ -7,7 +7,10 @@
 
 use super::models::*;
 use crate::error::DataSourceError;
+use crate::http::{self, FaultHook};
+use crate::quota::{ApiProvider, ApiUsageTracker};
 use reqwest::{Client, StatusCode};
+use std::sync::Arc;
 use std::time::Duration;
 use tracing::{debug, warn};
 
@@ -22,6 +25,8 @@ const INITIAL_BACKOFF_MS: u64 = 1000;
 pub struct SectorsClient {
     client: Client,
     api_key: String,
+    usage: Option<Arc<ApiUsageTracker>>,
+    faults: FaultHook,
 }
 
 impl SectorsClient {
@@ -41,7 +46,12 @@ impl SectorsClient {
                 DataSourceError::ApiError(format!("Failed to create HTTP client: {}", e))
             })?;
 
-        Ok(Self { client, api_key })
+        Ok(Self {
+            client,
+            api_key,
+            usage: None,
+            faults: FaultHook::default(),
+        })
     }
 
     /// Create client from SECTORS_API_KEY environment variable
@@ -52,6 +62,19 @@ impl SectorsClient {
         Self::new(api_key)
     }
 
+    /// Record calls made by this client in a shared usage tracker
+    pub fn with_usage_tracker(mut self, tracker: Arc<ApiUsageTracker>) -> Self {
+        self.usage = Some(tracker);
+        self
+    }
+
+    /// Route requests through a fault injector
+    #[cfg(feature = "fault-injection")]
+    pub fn with_faults(mut self, faults: Arc<crate::fault::FaultInjector>) -> Self {
+        self.faults = Some(faults);
+        self
+    }
+
     /// Execute a GET request with retry logic
     async fn get_with_retry<T: serde::de::DeserializeOwned>(
         &self,
@@ -71,17 +94,24 @@ impl SectorsClient {
                 backoff_ms *= 2; // Exponential backoff
             }
 
-            let result = self
+            let request = self
                 .client
                 .get(url)
                 .header("Authorization", &self.api_key)
-                .query(params)
-                .send()
-                .await;
+                .query(params);
 
-            match result {
+            match http::send(&self.faults, http::SOURCE_SECTORS, request).await {
                 Ok(response) => {
-                    let status = response.status();
+                    let status = response.status;
+                    if let Some(ref usage) = self.usage {
+                        usage.record_response(
+                            ApiProvider::Sectors,
+                            &self.api_key,
+                            1,
+                            status,
+                            &response.headers,
+                        );
+                    }
 
                     if status == StatusCode::TOO_MANY_REQUESTS {
                         warn!("Rate limited by Sectors.app API, will retry");
@@ -99,20 +129,19 @@ impl SectorsClient {
                     }
 
                     if !status.is_success() {
-                        let error_text = response.text().await.unwrap_or_default();
                         return Err(DataSourceError::InvalidResponse(format!(
                             "API error {}: {}",
-                            status, error_text
+                            status, response.body
                         )));
                     }
 
-                    return response.json::<T>().await.map_err(|e| {
+                    return response.json::<T>().map_err(|e| {
                         DataSourceError::InvalidResponse(format!("Failed to parse response: {}", e))
                     });
                 }
                 Err(e) => {
                     warn!("Network error calling Sectors.app: {}", e);
-                    last_error = Some(DataSourceError::HttpError(e));
+                    last_error = Some(e);
                 }
             }
         }
@@ -253,6 +282,15 @@ impl SectorsClient {
             .unwrap_or_default())
     }
 
+    /// Get analyst target price consensus and rating distribution
+    pub async fn get_target_price(
+        &self,
+        symbol: &str,
+    ) -> Result<TargetPriceConsensus, DataSourceError> {
+        let url = format!("{}/company/target-price/{}/", BASE_URL_V1, symbol);
+        self.get_with_retry(&url, &[]).await
+    }
+
     /// Check if client is properly configured
     pub fn is_configured(&self) -> bool {
         !self.api_key.is_empty()
@@ -306,4 +344,22 @@ mod tests {
         let limit = params.iter().find(|(k, _)| *k == "limit").unwrap();
         assert_eq!(limit.1, "200"); // Should be capped at 200
     }
+
+    #[test]
+    fn test_target_price_deserialize() {
+        let json = r#"{
+            "symbol": "BBCA",
+            "target_price_mean": 11250,
+            "target_price_high": 12500,
+            "target_price_low": 9800,
+            "analyst_count": 24,
+            "ratings": {"strong_buy": 10, "buy": 9, "hold": 4, "sell": 1}
+        }"#;
+
+        let consensus: TargetPriceConsensus = serde_json::from_str(json).unwrap();
+        assert_eq!(consensus.analyst_count, Some(24));
+        assert_eq!(consensus.ratings.total(), 24);
+        assert_eq!(consensus.ratings.strong_sell, 0);
+        assert!(consensus.target_price_median.is_none());
+    }
 }
@@ -262,6 +262,47 @@ pub struct DailyTransaction {
     pub frequency: Option<i64>,
 }
 
+/// Analyst rating counts behind a target price consensus
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AnalystRatings {
+    #[serde(default)]
+    pub strong_buy: i32,
+    #[serde(default)]
+    pub buy: i32,
+    #[serde(default)]
+    pub hold: i32,
+    #[serde(default)]
+    pub sell: i32,
+    #[serde(default)]
+    pub strong_sell: i32,
+}
+
+impl AnalystRatings {
+    pub fn total(&self) -> i32 {
+        self.strong_buy + self.buy + self.hold + self.sell + self.strong_sell
+    }
+}
+
+/// Analyst target price consensus for a stock
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TargetPriceConsensus {
+    pub symbol: String,
+    #[serde(default)]
+    pub target_price_mean: Option<Decimal>,
+    #[serde(default)]
+    pub target_price_median: Option<Decimal>,
+    #[serde(default)]
+    pub target_price_high: Option<Decimal>,
+    #[serde(default)]
+    pub target_price_low: Option<Decimal>,
+    #[serde(default)]
+    pub analyst_count: Option<i32>,
+    #[serde(default)]
+    pub ratings: AnalystRatings,
+    #[serde(default)]
+    pub updated_on: Option<NaiveDate>,
+}
+
 /// Top movers response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TopMovers {
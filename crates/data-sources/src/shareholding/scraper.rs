@@ -15,6 +15,7 @@
 use super::models::{
     OwnershipChange, Shareholder, ShareholderType, ShareholdingSnapshot, ShareholdingSource,
 };
+use crate::drift::{check_structure, ParserHealthReport, StructureExpectation};
 use crate::error::DataSourceError;
 use chrono::NaiveDate;
 use reqwest::Client;
@@ -27,6 +28,28 @@ use tracing::{debug, info, warn};
 const IDX_BASE_URL: &str = "https://www.idx.co.id";
 const RATE_LIMIT_DELAY_MS: u64 = 500;
 
+/// Parser name reported by the IDX shareholding self-check
+pub const SHAREHOLDING_HTML_PARSER: &str = "idx_shareholding_html";
+
+/// Page structure the IDX shareholding parsers depend on
+const IDX_SHAREHOLDING_STRUCTURE: StructureExpectation = StructureExpectation {
+    selectors: &[
+        "#shareholder",
+        ".shareholder-section",
+        "[data-section='shareholder']",
+        "table",
+    ],
+    columns: &[&["pemegang saham", "shareholder", "kepemilikan"]],
+};
+
+fn idx_profile_url(symbol: &str) -> String {
+    format!(
+        "{}/id/perusahaan-tercatat/profil-perusahaan-tercatat/?kodeEmiten={}",
+        IDX_BASE_URL,
+        symbol.to_uppercase()
+    )
+}
+
 /// Shareholding data scraper client
 #[derive(Debug, Clone)]
 pub struct ShareholdingScraper {
@@ -219,11 +242,7 @@ impl ShareholdingScraper {
         debug!("Fetching IDX shareholding for {} on {}", symbol, date);
 
         // IDX company profile API
-        let url = format!(
-            "{}/id/perusahaan-tercatat/profil-perusahaan-tercatat/?kodeEmiten={}",
-            IDX_BASE_URL,
-            symbol.to_uppercase()
-        );
+        let url = idx_profile_url(symbol);
 
         self.rate_limit().await;
 
@@ -411,6 +430,40 @@ impl ShareholdingScraper {
         Ok(None)
     }
 
+    /// Validate the IDX shareholding parser against a reference company page
+    pub async fn self_check(&self, reference_symbol: &str) -> ParserHealthReport {
+        let url = idx_profile_url(reference_symbol);
+
+        let html = match self.client.get(&url).send().await {
+            Ok(response) if response.status().is_success() => response.text().await,
+            Ok(response) => {
+                return ParserHealthReport::unreachable(
+                    SHAREHOLDING_HTML_PARSER,
+                    &url,
+                    format!("HTTP {}", response.status()),
+                )
+            }
+            Err(e) => {
+                return ParserHealthReport::unreachable(
+                    SHAREHOLDING_HTML_PARSER,
+                    &url,
+                    e.to_string(),
+                )
+            }
+        };
+
+        match html {
+            Ok(html) => ParserHealthReport::from_check(
+                SHAREHOLDING_HTML_PARSER,
+                &url,
+                check_structure(&html, &IDX_SHAREHOLDING_STRUCTURE),
+            ),
+            Err(e) => {
+                ParserHealthReport::unreachable(SHAREHOLDING_HTML_PARSER, &url, e.to_string())
+            }
+        }
+    }
+
     /// Get shareholding snapshot from best available source
     ///
     /// Tries sources in order: KSEI > OJK > IDX
@@ -675,6 +728,15 @@ mod tests {
         assert!(changes.is_empty());
     }
 
+    #[test]
+    fn test_idx_shareholding_structure() {
+        let html = "<table><tr><th>Pemegang Saham</th><th>Jumlah</th></tr></table>";
+        assert!(check_structure(html, &IDX_SHAREHOLDING_STRUCTURE).is_healthy());
+
+        let html = "<table><tr><th>Direksi</th><th>Jabatan</th></tr></table>";
+        assert!(!check_structure(html, &IDX_SHAREHOLDING_STRUCTURE).is_healthy());
+    }
+
     #[tokio::test]
     async fn test_scraper_creation() {
         let scraper = ShareholdingScraper::new();
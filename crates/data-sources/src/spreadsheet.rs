@@ -0,0 +1,223 @@
+//! Spreadsheet handling for uploaded export files
+//!
+//! Broker terminals and trading apps export either CSV or Excel workbooks.
+//! Workbooks are flattened to CSV so every import parser only needs to
+//! understand one tabular format.
+
+use crate::error::DataSourceError;
+use calamine::{open_workbook_auto_from_rs, Data, Reader};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use std::borrow::Cow;
+use std::io::Cursor;
+
+/// ZIP container magic used by XLSX/XLSM/ODS files
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+/// OLE compound document magic used by legacy XLS files
+const OLE_MAGIC: &[u8] = &[0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1];
+
+/// Whether the content is an Excel/OpenDocument workbook
+pub fn is_spreadsheet(content: &[u8]) -> bool {
+    content.starts_with(ZIP_MAGIC) || content.starts_with(OLE_MAGIC)
+}
+
+/// Return CSV bytes for an upload, converting the first non-empty sheet of a workbook
+pub fn to_csv_bytes(content: &[u8]) -> Result<Cow<'_, [u8]>, DataSourceError> {
+    if !is_spreadsheet(content) {
+        return Ok(Cow::Borrowed(content));
+    }
+
+    let mut workbook = open_workbook_auto_from_rs(Cursor::new(content)).map_err(|e| {
+        DataSourceError::UnsupportedFormat(format!("Unreadable spreadsheet: {}", e))
+    })?;
+
+    for index in 0..workbook.sheet_names().len() {
+        let Some(Ok(range)) = workbook.worksheet_range_at(index) else {
+            continue;
+        };
+        if range.is_empty() {
+            continue;
+        }
+
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        for row in range.rows() {
+            let record: Vec<String> = row.iter().map(cell_to_string).collect();
+            if record.iter().all(|cell| cell.is_empty()) {
+                continue;
+            }
+            writer.write_record(&record).map_err(|e| {
+                DataSourceError::InvalidResponse(format!("Failed to convert sheet: {}", e))
+            })?;
+        }
+
+        let csv = writer.into_inner().map_err(|e| {
+            DataSourceError::InvalidResponse(format!("Failed to convert sheet: {}", e))
+        })?;
+        return Ok(Cow::Owned(csv));
+    }
+
+    Err(DataSourceError::InvalidResponse(
+        "Spreadsheet has no data".into(),
+    ))
+}
+
+/// Render a cell the way a CSV export of the same sheet would
+fn cell_to_string(cell: &Data) -> String {
+    match cell {
+        Data::Empty | Data::Error(_) => String::new(),
+        Data::String(s) | Data::DateTimeIso(s) | Data::DurationIso(s) => s.trim().to_string(),
+        Data::Int(i) => i.to_string(),
+        Data::Float(f) if f.fract() == 0.0 && f.abs() < i64::MAX as f64 => (*f as i64).to_string(),
+        Data::Float(f) => f.to_string(),
+        Data::Bool(b) => b.to_string(),
+        Data::DateTime(dt) => dt
+            .as_datetime()
+            .map(|dt| dt.date().format("%Y-%m-%d").to_string())
+            .unwrap_or_default(),
+    }
+}
+
+/// CSV content with normalized (lowercase alphanumeric) headers
+pub(crate) struct CsvTable {
+    headers: Vec<String>,
+    pub(crate) rows: Vec<csv::StringRecord>,
+    decimal_comma: bool,
+}
+
+impl CsvTable {
+    /// Read CSV, detecting `;` (Indonesian locale, decimal comma) or `,` delimiters
+    pub(crate) fn read(content: &[u8]) -> Option<Self> {
+        let text = String::from_utf8_lossy(content);
+        let header_line = text.lines().find(|l| !l.trim().is_empty())?;
+        if header_line.contains('|') || header_line.trim_start().starts_with('<') {
+            return None;
+        }
+
+        let delimiter = if header_line.matches(';').count() > header_line.matches(',').count() {
+            b';'
+        } else {
+            b','
+        };
+
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(delimiter)
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(text.trim_start_matches('\u{feff}').as_bytes());
+
+        let headers = reader
+            .headers()
+            .ok()?
+            .iter()
+            .map(normalize_header)
+            .collect::<Vec<_>>();
+        if headers.len() < 2 {
+            return None;
+        }
+
+        let rows = reader.records().filter_map(Result::ok).collect();
+
+        Some(Self {
+            headers,
+            rows,
+            decimal_comma: delimiter == b';',
+        })
+    }
+
+    pub(crate) fn column(&self, names: &[&str]) -> Option<usize> {
+        self.headers
+            .iter()
+            .position(|h| names.iter().any(|n| normalize_header(n) == *h))
+    }
+
+    pub(crate) fn cell<'a>(&self, row: &'a csv::StringRecord, col: usize) -> &'a str {
+        row.get(col).unwrap_or("")
+    }
+
+    pub(crate) fn amount(&self, row: &csv::StringRecord, col: usize) -> Decimal {
+        parse_amount(self.cell(row, col), self.decimal_comma).unwrap_or(Decimal::ZERO)
+    }
+}
+
+fn normalize_header(header: &str) -> String {
+    header
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .collect::<String>()
+        .to_lowercase()
+}
+
+/// Parse an exported amount such as `1,234,500`, `1.234.500,5` or `12.5B`
+pub(crate) fn parse_amount(text: &str, decimal_comma: bool) -> Option<Decimal> {
+    let trimmed = text.trim();
+    let (number, multiplier) = match trimmed.chars().last()?.to_ascii_uppercase() {
+        'K' => (&trimmed[..trimmed.len() - 1], Decimal::from(1_000)),
+        'M' => (&trimmed[..trimmed.len() - 1], Decimal::from(1_000_000)),
+        'B' => (&trimmed[..trimmed.len() - 1], Decimal::from(1_000_000_000)),
+        'T' => (
+            &trimmed[..trimmed.len() - 1],
+            Decimal::from(1_000_000_000_000i64),
+        ),
+        _ => (trimmed, Decimal::ONE),
+    };
+
+    let (thousands, decimal) = if decimal_comma {
+        ('.', ',')
+    } else {
+        (',', '.')
+    };
+    let cleaned: String = number
+        .chars()
+        .filter(|c| *c != thousands && *c != ' ')
+        .map(|c| if c == decimal { '.' } else { c })
+        .filter(|c| c.is_ascii_digit() || *c == '.' || *c == '-')
+        .collect();
+
+    cleaned.parse::<Decimal>().ok().map(|d| d * multiplier)
+}
+
+pub(crate) fn parse_date(text: &str) -> Option<NaiveDate> {
+    let text = text.trim();
+    ["%Y-%m-%d", "%d/%m/%Y", "%Y%m%d", "%d-%m-%Y"]
+        .iter()
+        .find_map(|fmt| NaiveDate::parse_from_str(text, fmt).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_csv_passes_through() {
+        let content = b"Broker,Buy Val,Sell Val\nBK,100,50\n";
+        assert!(!is_spreadsheet(content));
+        assert!(matches!(to_csv_bytes(content), Ok(Cow::Borrowed(_))));
+    }
+
+    #[test]
+    fn test_corrupt_workbook_is_rejected() {
+        let content = b"PK\x03\x04not really a zip";
+        assert!(is_spreadsheet(content));
+        assert!(matches!(
+            to_csv_bytes(content),
+            Err(DataSourceError::UnsupportedFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_cell_to_string() {
+        assert_eq!(cell_to_string(&Data::Float(12500.0)), "12500");
+        assert_eq!(cell_to_string(&Data::Float(9615.5)), "9615.5");
+        assert_eq!(cell_to_string(&Data::String(" BK ".into())), "BK");
+        assert_eq!(cell_to_string(&Data::Empty), "");
+    }
+
+    #[test]
+    fn test_parse_amount() {
+        assert_eq!(parse_amount("1,234,500", false), Some(dec!(1234500)));
+        assert_eq!(parse_amount("1.234.500,5", true), Some(dec!(1234500.5)));
+        assert_eq!(parse_amount("12.5B", false), Some(dec!(12_500_000_000)));
+        assert_eq!(parse_amount("-", false), None);
+    }
+}
@@ -0,0 +1,210 @@
+//! Symbol mapping between data providers
+//!
+//! The canonical symbol is the plain IDX ticker (`BBCA`). Providers use their
+//! own notation:
+//! - Yahoo Finance: `BBCA.JK`
+//! - TwelveData: `BBCA:IDX`
+//! - Sectors.app and IDX: `BBCA`
+//!
+//! Default rules cover almost every listing; persisted overrides handle the
+//! exceptions (renamed tickers, provider-specific aliases).
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+const YAHOO_SUFFIX: &str = ".JK";
+const TWELVEDATA_SUFFIX: &str = ":IDX";
+
+/// Provider with its own symbol notation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SymbolProvider {
+    Yahoo,
+    TwelveData,
+    Sectors,
+    Idx,
+}
+
+impl SymbolProvider {
+    pub const ALL: [SymbolProvider; 4] = [Self::Yahoo, Self::TwelveData, Self::Sectors, Self::Idx];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Yahoo => "yahoo",
+            Self::TwelveData => "twelvedata",
+            Self::Sectors => "sectors",
+            Self::Idx => "idx",
+        }
+    }
+
+    pub fn from_str_opt(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "yahoo" => Some(Self::Yahoo),
+            "twelvedata" | "twelve_data" => Some(Self::TwelveData),
+            "sectors" => Some(Self::Sectors),
+            "idx" => Some(Self::Idx),
+            _ => None,
+        }
+    }
+}
+
+/// A stored exception to the default notation rules
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SymbolMapping {
+    /// Canonical IDX ticker
+    pub symbol: String,
+    pub provider: SymbolProvider,
+    pub provider_symbol: String,
+}
+
+/// A symbol resolved into every provider's notation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResolvedSymbol {
+    pub symbol: String,
+    pub providers: BTreeMap<SymbolProvider, String>,
+}
+
+/// Canonical ticker for a symbol in any provider notation, using default rules only
+pub fn canonical_symbol(raw: &str) -> String {
+    let upper = raw.trim().to_uppercase();
+    upper
+        .strip_suffix(YAHOO_SUFFIX)
+        .or_else(|| upper.strip_suffix(TWELVEDATA_SUFFIX))
+        .unwrap_or(&upper)
+        .to_string()
+}
+
+/// Provider notation for a canonical ticker, using default rules only
+pub fn default_provider_symbol(symbol: &str, provider: SymbolProvider) -> String {
+    let symbol = canonical_symbol(symbol);
+    match provider {
+        SymbolProvider::Yahoo => format!("{}{}", symbol, YAHOO_SUFFIX),
+        SymbolProvider::TwelveData => format!("{}{}", symbol, TWELVEDATA_SUFFIX),
+        SymbolProvider::Sectors | SymbolProvider::Idx => symbol,
+    }
+}
+
+/// Translates symbols between providers, applying overrides before default rules
+#[derive(Debug, Clone, Default)]
+pub struct SymbolMapper {
+    /// (provider, canonical) -> provider symbol
+    forward: HashMap<(SymbolProvider, String), String>,
+    /// (provider, provider symbol) -> canonical
+    reverse: HashMap<(SymbolProvider, String), String>,
+}
+
+impl SymbolMapper {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_mappings(mappings: impl IntoIterator<Item = SymbolMapping>) -> Self {
+        let mut mapper = Self::new();
+        for mapping in mappings {
+            mapper.add(mapping);
+        }
+        mapper
+    }
+
+    pub fn add(&mut self, mapping: SymbolMapping) {
+        let symbol = mapping.symbol.trim().to_uppercase();
+        let provider_symbol = mapping.provider_symbol.trim().to_uppercase();
+        self.forward
+            .insert((mapping.provider, symbol.clone()), provider_symbol.clone());
+        self.reverse
+            .insert((mapping.provider, provider_symbol), symbol);
+    }
+
+    /// Symbol to send to a provider for a canonical ticker
+    pub fn to_provider(&self, symbol: &str, provider: SymbolProvider) -> String {
+        let symbol = canonical_symbol(symbol);
+        self.forward
+            .get(&(provider, symbol.clone()))
+            .cloned()
+            .unwrap_or_else(|| default_provider_symbol(&symbol, provider))
+    }
+
+    /// Canonical ticker for a symbol received from a known provider
+    pub fn to_canonical(&self, provider_symbol: &str, provider: SymbolProvider) -> String {
+        let upper = provider_symbol.trim().to_uppercase();
+        self.reverse
+            .get(&(provider, upper.clone()))
+            .cloned()
+            .unwrap_or_else(|| canonical_symbol(&upper))
+    }
+
+    /// Canonical ticker for a symbol of unknown origin, e.g. from an uploaded file
+    pub fn canonicalize(&self, raw: &str) -> String {
+        let upper = raw.trim().to_uppercase();
+        SymbolProvider::ALL
+            .iter()
+            .find_map(|provider| self.reverse.get(&(*provider, upper.clone())))
+            .cloned()
+            .unwrap_or_else(|| canonical_symbol(&upper))
+    }
+
+    /// Canonical ticker plus its notation for every provider
+    pub fn resolve(&self, raw: &str) -> ResolvedSymbol {
+        let symbol = self.canonicalize(raw);
+        let providers = SymbolProvider::ALL
+            .iter()
+            .map(|provider| (*provider, self.to_provider(&symbol, *provider)))
+            .collect();
+        ResolvedSymbol { symbol, providers }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_rules() {
+        assert_eq!(canonical_symbol("bbca.jk"), "BBCA");
+        assert_eq!(canonical_symbol("BBCA:IDX"), "BBCA");
+        assert_eq!(canonical_symbol(" BBCA "), "BBCA");
+        assert_eq!(
+            default_provider_symbol("BBCA", SymbolProvider::Yahoo),
+            "BBCA.JK"
+        );
+        assert_eq!(
+            default_provider_symbol("BBCA.JK", SymbolProvider::TwelveData),
+            "BBCA:IDX"
+        );
+        assert_eq!(
+            default_provider_symbol("BBCA", SymbolProvider::Sectors),
+            "BBCA"
+        );
+    }
+
+    #[test]
+    fn test_overrides_take_precedence() {
+        let mapper = SymbolMapper::with_mappings([SymbolMapping {
+            symbol: "BREN".to_string(),
+            provider: SymbolProvider::Yahoo,
+            provider_symbol: "BREN-R.JK".to_string(),
+        }]);
+
+        assert_eq!(
+            mapper.to_provider("BREN", SymbolProvider::Yahoo),
+            "BREN-R.JK"
+        );
+        assert_eq!(
+            mapper.to_provider("BREN", SymbolProvider::TwelveData),
+            "BREN:IDX"
+        );
+        assert_eq!(
+            mapper.to_canonical("bren-r.jk", SymbolProvider::Yahoo),
+            "BREN"
+        );
+        assert_eq!(mapper.canonicalize("BREN-R.JK"), "BREN");
+    }
+
+    #[test]
+    fn test_resolve_lists_every_provider() {
+        let resolved = SymbolMapper::new().resolve("TLKM:IDX");
+        assert_eq!(resolved.symbol, "TLKM");
+        assert_eq!(resolved.providers.len(), SymbolProvider::ALL.len());
+        assert_eq!(resolved.providers[&SymbolProvider::Yahoo], "TLKM.JK");
+    }
+}
@@ -0,0 +1,267 @@
+//! Intraday OHLCV bars from streaming price updates
+//!
+//! The WebSocket feed only reports prices and the cumulative day volume. The
+//! aggregator buckets updates into bars aligned to the interval (1m bars
+//! start on the minute, 5m bars on multiples of five minutes, and so on) and
+//! hands back each bar once its interval is over: either when an update for
+//! a later bucket arrives or when `flush_until` passes its end, for symbols
+//! that go quiet.
+//!
+//! Volume is the increase of the day volume since the previous update of the
+//! symbol. The first update of a symbol has no known trade size and adds none;
+//! a day volume lower than the previous one is a new session. Updates older
+//! than the symbol's latest are dropped; their volume still shows up in the
+//! next update's day volume.
+
+use super::models::{Interval, PriceUpdate};
+use crate::symbols::canonical_symbol;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Intervals built when none are given
+pub const DEFAULT_BAR_INTERVALS: [Interval; 3] = [Interval::Min1, Interval::Min5, Interval::Min15];
+
+/// OHLCV bar of one symbol over one interval
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct IntradayBar {
+    /// Canonical ticker, without the exchange suffix
+    pub symbol: String,
+    pub interval: Interval,
+    /// Start of the interval; the bar covers `[start, start + interval)`
+    pub start: DateTime<Utc>,
+    pub open: Decimal,
+    pub high: Decimal,
+    pub low: Decimal,
+    pub close: Decimal,
+    pub volume: i64,
+    /// Price updates in the bar
+    pub ticks: u32,
+}
+
+impl IntradayBar {
+    pub fn end(&self) -> DateTime<Utc> {
+        self.start + self.interval.duration().unwrap_or_default()
+    }
+
+    fn update(&mut self, price: Decimal, volume: i64) {
+        self.high = self.high.max(price);
+        self.low = self.low.min(price);
+        self.close = price;
+        self.volume += volume;
+        self.ticks += 1;
+    }
+}
+
+#[derive(Debug)]
+struct SymbolBars {
+    /// Open bar per interval, in the aggregator's interval order
+    open: Vec<Option<IntradayBar>>,
+    last_update: DateTime<Utc>,
+    last_day_volume: Option<i64>,
+}
+
+/// Builds intraday bars for every symbol on the feed
+#[derive(Debug)]
+pub struct BarAggregator {
+    intervals: Vec<Interval>,
+    symbols: HashMap<String, SymbolBars>,
+    /// Updates dropped for arriving out of order
+    late: u64,
+}
+
+impl BarAggregator {
+    /// Aggregator for `intervals`; daily and longer intervals are ignored
+    pub fn new(intervals: &[Interval]) -> Self {
+        let mut kept: Vec<Interval> = Vec::new();
+        for interval in intervals {
+            if interval.duration().is_some() && !kept.contains(interval) {
+                kept.push(*interval);
+            }
+        }
+        Self {
+            intervals: kept,
+            symbols: HashMap::new(),
+            late: 0,
+        }
+    }
+
+    pub fn intervals(&self) -> &[Interval] {
+        &self.intervals
+    }
+
+    /// Updates dropped for being older than the symbol's latest
+    pub fn late_updates(&self) -> u64 {
+        self.late
+    }
+
+    /// Add an update; returns the bars it completed
+    ///
+    /// Updates without a price (quote-only messages) are skipped. Updates
+    /// without a timestamp are taken as current.
+    pub fn push(&mut self, update: &PriceUpdate) -> Vec<IntradayBar> {
+        let Some(price) = update.price else {
+            return Vec::new();
+        };
+        let time = update.datetime().unwrap_or_else(Utc::now);
+        let symbol = canonical_symbol(&update.symbol);
+
+        let entry = self
+            .symbols
+            .entry(symbol.clone())
+            .or_insert_with(|| SymbolBars {
+                open: vec![None; self.intervals.len()],
+                last_update: time,
+                last_day_volume: None,
+            });
+        if time < entry.last_update {
+            self.late += 1;
+            return Vec::new();
+        }
+        entry.last_update = time;
+
+        let volume = match update.day_volume {
+            Some(day_volume) => match entry.last_day_volume.replace(day_volume) {
+                Some(previous) if day_volume >= previous => day_volume - previous,
+                Some(_) => day_volume,
+                None => 0,
+            },
+            None => 0,
+        };
+
+        let mut completed = Vec::new();
+        for (interval, slot) in self.intervals.iter().zip(entry.open.iter_mut()) {
+            let start = bucket_start(time, *interval);
+            match slot {
+                Some(bar) if bar.start == start => bar.update(price, volume),
+                _ => {
+                    let next = IntradayBar {
+                        symbol: symbol.clone(),
+                        interval: *interval,
+                        start,
+                        open: price,
+                        high: price,
+                        low: price,
+                        close: price,
+                        volume,
+                        ticks: 1,
+                    };
+                    completed.extend(slot.replace(next));
+                }
+            }
+        }
+        completed
+    }
+
+    /// Take the open bars whose interval ended at or before `now`
+    pub fn flush_until(&mut self, now: DateTime<Utc>) -> Vec<IntradayBar> {
+        let mut completed = Vec::new();
+        for entry in self.symbols.values_mut() {
+            for slot in entry.open.iter_mut() {
+                if slot.as_ref().is_some_and(|bar| bar.end() <= now) {
+                    completed.extend(slot.take());
+                }
+            }
+        }
+        completed
+    }
+
+    /// Take every open bar, complete or not, e.g. on shutdown
+    pub fn flush_all(&mut self) -> Vec<IntradayBar> {
+        self.symbols
+            .values_mut()
+            .flat_map(|entry| entry.open.iter_mut().filter_map(Option::take))
+            .collect()
+    }
+}
+
+impl Default for BarAggregator {
+    fn default() -> Self {
+        Self::new(&DEFAULT_BAR_INTERVALS)
+    }
+}
+
+/// Start of the `interval` bucket holding `time`
+///
+/// Buckets are aligned to the Unix epoch; WIB is a whole-hour offset, so
+/// minute and hour bars line up with local clock time.
+fn bucket_start(time: DateTime<Utc>, interval: Interval) -> DateTime<Utc> {
+    let seconds = interval
+        .duration()
+        .map(|d| d.num_seconds())
+        .unwrap_or(1)
+        .max(1);
+    let start = time.timestamp().div_euclid(seconds) * seconds;
+    DateTime::from_timestamp(start, 0).unwrap_or(time)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    // 2024-03-04 02:00:00 UTC, 09:00 WIB
+    const OPEN: i64 = 1_709_517_600;
+
+    fn update(offset: i64, price: Decimal, day_volume: i64) -> PriceUpdate {
+        PriceUpdate {
+            event: "price".to_string(),
+            symbol: "BBCA.JK".to_string(),
+            currency: None,
+            exchange: None,
+            mic_code: None,
+            instrument_type: None,
+            price: Some(price),
+            bid: None,
+            ask: None,
+            day_volume: Some(day_volume),
+            timestamp: Some(OPEN + offset),
+        }
+    }
+
+    #[test]
+    fn test_bars_complete_on_interval_close() {
+        let mut aggregator = BarAggregator::new(&[Interval::Min1, Interval::Min5, Interval::Day1]);
+        assert_eq!(aggregator.intervals(), &[Interval::Min1, Interval::Min5]);
+
+        assert!(aggregator.push(&update(5, dec!(9275), 1_000)).is_empty());
+        assert!(aggregator.push(&update(20, dec!(9300), 1_600)).is_empty());
+        assert!(aggregator.push(&update(50, dec!(9250), 2_000)).is_empty());
+
+        // Next minute closes the first 1m bar only
+        let completed = aggregator.push(&update(65, dec!(9325), 2_500));
+        assert_eq!(completed.len(), 1);
+        let bar = &completed[0];
+        assert_eq!(bar.symbol, "BBCA");
+        assert_eq!(bar.interval, Interval::Min1);
+        assert_eq!(bar.start.timestamp(), OPEN);
+        assert_eq!(
+            (bar.open, bar.high, bar.low, bar.close),
+            (dec!(9275), dec!(9300), dec!(9250), dec!(9250))
+        );
+        // The first update of the symbol adds no volume
+        assert_eq!((bar.volume, bar.ticks), (1_000, 3));
+
+        // An out-of-order update is dropped; its volume lands in the next one
+        assert!(aggregator.push(&update(30, dec!(9200), 2_600)).is_empty());
+        assert_eq!(aggregator.late_updates(), 1);
+        assert!(aggregator.push(&update(70, dec!(9325), 2_800)).is_empty());
+
+        // A quiet symbol's bars close on the timer
+        let minute_end = DateTime::from_timestamp(OPEN + 120, 0).unwrap();
+        let flushed = aggregator.flush_until(minute_end);
+        assert_eq!(flushed.len(), 1);
+        assert_eq!((flushed[0].close, flushed[0].volume), (dec!(9325), 800));
+
+        let five = aggregator.flush_all();
+        assert_eq!(five.len(), 1);
+        assert_eq!(five[0].interval, Interval::Min5);
+        assert_eq!(
+            (five[0].open, five[0].high, five[0].low, five[0].close),
+            (dec!(9275), dec!(9325), dec!(9250), dec!(9325))
+        );
+        assert_eq!((five[0].volume, five[0].ticks), (1_800, 5));
+        assert!(aggregator.flush_all().is_empty());
+    }
+}
@@ -1,9 +1,13 @@
 //! TwelveData REST API client implementation
 
+use super::key_pool::{KeyHealth, KeyPool};
 use super::models::*;
 use crate::error::DataSourceError;
+use crate::http::{self, FaultHook};
+use crate::quota::{key_fingerprint, ApiProvider, ApiUsageTracker};
 use chrono::NaiveDate;
 use reqwest::Client;
+use std::sync::Arc;
 use std::time::Duration;
 use tracing::{debug, warn};
 
@@ -12,16 +16,27 @@ const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
 const MAX_RETRIES: u32 = 3;
 
 /// TwelveData REST API client
+///
+/// Requests rotate over a [`KeyPool`], so several free-tier keys can be
+/// combined; a key that gets rate limited is rested while the others serve.
 #[derive(Debug, Clone)]
 pub struct TwelveDataClient {
     client: Client,
-    api_key: String,
+    keys: Arc<KeyPool>,
+    usage: Option<Arc<ApiUsageTracker>>,
+    faults: FaultHook,
 }
 
 impl TwelveDataClient {
     /// Create a new TwelveData client
     pub fn new(api_key: String) -> Result<Self, DataSourceError> {
-        if api_key.is_empty() {
+        Self::with_keys(vec![api_key])
+    }
+
+    /// Create a client rotating over several API keys
+    pub fn with_keys(api_keys: Vec<String>) -> Result<Self, DataSourceError> {
+        let keys = KeyPool::new(api_keys);
+        if keys.is_empty() {
             return Err(DataSourceError::InvalidResponse(
                 "TwelveData API key is required".to_string(),
             ));
@@ -35,59 +50,116 @@ impl TwelveDataClient {
                 DataSourceError::ApiError(format!("Failed to create HTTP client: {}", e))
             })?;
 
-        Ok(Self { client, api_key })
+        Ok(Self {
+            client,
+            keys: Arc::new(keys),
+            usage: None,
+            faults: FaultHook::default(),
+        })
     }
 
-    /// Create client from TWELVEDATA_API_KEY environment variable
+    /// Create client from TWELVEDATA_API_KEY and the comma-separated
+    /// TWELVEDATA_API_KEYS environment variables
     pub fn from_env() -> Result<Self, DataSourceError> {
-        let api_key = std::env::var("TWELVEDATA_API_KEY").map_err(|_| {
-            DataSourceError::InvalidResponse(
+        let mut keys: Vec<String> = std::env::var("TWELVEDATA_API_KEY").into_iter().collect();
+        if let Ok(pool) = std::env::var("TWELVEDATA_API_KEYS") {
+            keys.extend(pool.split(',').map(str::to_string));
+        }
+
+        if keys.iter().all(|k| k.trim().is_empty()) {
+            return Err(DataSourceError::InvalidResponse(
                 "TWELVEDATA_API_KEY environment variable not set".into(),
-            )
-        })?;
-        Self::new(api_key)
+            ));
+        }
+        Self::with_keys(keys)
+    }
+
+    /// Record calls made by this client in a shared usage tracker
+    pub fn with_usage_tracker(mut self, tracker: Arc<ApiUsageTracker>) -> Self {
+        self.usage = Some(tracker);
+        self
+    }
+
+    /// Route requests through a fault injector
+    #[cfg(feature = "fault-injection")]
+    pub fn with_faults(mut self, faults: Arc<crate::fault::FaultInjector>) -> Self {
+        self.faults = Some(faults);
+        self
     }
 
     /// Get API key for WebSocket connection
     pub fn api_key(&self) -> &str {
-        &self.api_key
+        self.keys.primary()
+    }
+
+    /// Request counts and rate-limit state of each pooled key
+    pub fn key_health(&self) -> Vec<KeyHealth> {
+        self.keys.health()
     }
 
     /// Execute GET request with retry logic
+    ///
+    /// A 429 moves on to the next available key without backing off; the
+    /// backoff only applies once every key is rate limited.
     async fn get_with_retry<T: serde::de::DeserializeOwned>(
         &self,
         endpoint: &str,
         params: &[(&str, &str)],
     ) -> Result<T, DataSourceError> {
         let url = format!("{}/{}", BASE_URL, endpoint);
+        // Batch endpoints charge one credit per symbol
+        let credits = params
+            .iter()
+            .find(|(key, _)| *key == "symbol")
+            .map(|(_, symbols)| symbols.split(',').count() as u64)
+            .unwrap_or(1);
         let mut last_error = None;
         let mut backoff_ms = 1000u64;
+        let mut rotated = false;
+        let max_attempts = MAX_RETRIES + self.keys.len() as u32 - 1;
 
-        for attempt in 0..MAX_RETRIES {
-            if attempt > 0 {
+        for attempt in 0..max_attempts {
+            if attempt > 0 && !rotated {
                 debug!("Retry attempt {} for {}", attempt, endpoint);
                 tokio::time::sleep(Duration::from_millis(backoff_ms)).await;
                 backoff_ms *= 2;
             }
+            rotated = false;
 
+            let (key_index, api_key) = self.keys.next_key();
             let mut request = self.client.get(&url);
             for (key, value) in params {
                 request = request.query(&[(key, value)]);
             }
-            request = request.query(&[("apikey", self.api_key.as_str())]);
+            request = request.query(&[("apikey", api_key)]);
 
-            match request.send().await {
+            match http::send(&self.faults, http::SOURCE_TWELVEDATA, request).await {
                 Ok(response) => {
-                    let status = response.status();
+                    let status = response.status;
+                    if let Some(ref usage) = self.usage {
+                        usage.record_response(
+                            ApiProvider::TwelveData,
+                            api_key,
+                            credits,
+                            status,
+                            &response.headers,
+                        );
+                    }
 
                     if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
-                        warn!("Rate limited by TwelveData API");
+                        warn!(
+                            "Rate limited by TwelveData API on key {}",
+                            key_fingerprint(api_key)
+                        );
+                        self.keys.record_rate_limited(key_index);
+                        rotated = self.keys.has_alternative(key_index);
                         last_error = Some(DataSourceError::RateLimited);
                         continue;
                     }
 
                     if status.is_server_error() {
                         warn!("Server error from TwelveData: {}", status);
+                        self.keys.record_error(key_index);
                         last_error = Some(DataSourceError::ApiError(format!(
                             "Server error: {}",
                             status
@@ -96,20 +168,22 @@ impl TwelveDataClient {
                     }
 
                     if !status.is_success() {
-                        let error_text = response.text().await.unwrap_or_default();
+                        self.keys.record_error(key_index);
                         return Err(DataSourceError::InvalidResponse(format!(
                             "API error {}: {}",
-                            status, error_text
+                            status, response.body
                         )));
                     }
 
-                    return response.json::<T>().await.map_err(|e| {
+                    self.keys.record_success(key_index);
+                    return response.json::<T>().map_err(|e| {
                         DataSourceError::InvalidResponse(format!("Failed to parse response: {}", e))
                     });
                 }
                 Err(e) => {
                     warn!("Network error: {}", e);
-                    last_error = Some(DataSourceError::HttpError(e));
+                    self.keys.record_error(key_index);
+                    last_error = Some(e);
                 }
             }
         }
@@ -217,6 +291,16 @@ mod tests {
         assert!(client.is_err());
     }
 
+    #[test]
+    fn test_key_pool_client() {
+        let client =
+            TwelveDataClient::with_keys(vec!["first".to_string(), "second".to_string()]).unwrap();
+        assert_eq!(client.api_key(), "first");
+        assert_eq!(client.key_health().len(), 2);
+
+        assert!(TwelveDataClient::with_keys(vec![" ".to_string()]).is_err());
+    }
+
     #[test]
     fn test_interval_display() {
         assert_eq!(Interval::Min1.as_str(), "1min");
@@ -224,4 +308,11 @@ mod tests {
         assert_eq!(Interval::Day1.as_str(), "1day");
         assert_eq!(Interval::Month1.as_str(), "1month");
     }
+
+    #[test]
+    fn test_interval_parse() {
+        assert_eq!(Interval::from_str_opt("1h"), Some(Interval::Hour1));
+        assert_eq!(Interval::from_str_opt("15min"), Some(Interval::Min15));
+        assert_eq!(Interval::from_str_opt("15m"), None);
+    }
 }
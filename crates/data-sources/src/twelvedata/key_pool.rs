@@ -0,0 +1,218 @@
+//! Rotating pool of TwelveData API keys
+//!
+//! Free-tier keys are limited per minute and per day, so several keys can be
+//! pooled to cover the whole IDX universe. Requests go round-robin over the
+//! keys; a key answering 429 cools down for a minute and is skipped until then.
+
+use crate::quota::key_fingerprint;
+use serde::Serialize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How long a rate-limited key is skipped (TwelveData limits are per minute)
+const RATE_LIMIT_COOLDOWN: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Default)]
+struct KeyState {
+    requests: u64,
+    rate_limited: u64,
+    errors: u64,
+    cooldown_until: Option<Instant>,
+}
+
+/// Health of one pooled key
+#[derive(Debug, Clone, Serialize)]
+pub struct KeyHealth {
+    /// Masked key
+    pub key_id: String,
+    pub requests: u64,
+    pub rate_limited: u64,
+    pub errors: u64,
+    pub available: bool,
+    /// Seconds until a rate-limited key is used again
+    pub cooldown_remaining_secs: Option<u64>,
+}
+
+/// Round-robin key pool with per-key cooldown on rate limits
+#[derive(Debug)]
+pub struct KeyPool {
+    keys: Vec<String>,
+    next: AtomicUsize,
+    state: Mutex<Vec<KeyState>>,
+}
+
+impl KeyPool {
+    /// Create a pool, dropping blank and duplicate keys
+    pub fn new(keys: Vec<String>) -> Self {
+        let mut unique: Vec<String> = Vec::new();
+        for key in keys {
+            let key = key.trim().to_string();
+            if !key.is_empty() && !unique.contains(&key) {
+                unique.push(key);
+            }
+        }
+
+        let state = vec![KeyState::default(); unique.len()];
+        Self {
+            keys: unique,
+            next: AtomicUsize::new(0),
+            state: Mutex::new(state),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// First configured key, used for the WebSocket connection
+    pub fn primary(&self) -> &str {
+        &self.keys[0]
+    }
+
+    /// Pick the next key that is not cooling down
+    ///
+    /// When every key is cooling down, the one that recovers first is returned.
+    pub fn next_key(&self) -> (usize, &str) {
+        self.next_key_at(Instant::now())
+    }
+
+    fn next_key_at(&self, now: Instant) -> (usize, &str) {
+        let state = self.state.lock().expect("key pool lock poisoned");
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+
+        let index = (0..self.keys.len())
+            .map(|offset| (start + offset) % self.keys.len())
+            .find(|&i| state[i].cooldown_until.is_none_or(|until| until <= now))
+            .unwrap_or_else(|| {
+                (0..self.keys.len())
+                    .min_by_key(|&i| state[i].cooldown_until)
+                    .unwrap_or(0)
+            });
+
+        (index, &self.keys[index])
+    }
+
+    /// Whether any key other than `index` can be used right now
+    pub fn has_alternative(&self, index: usize) -> bool {
+        let now = Instant::now();
+        let state = self.state.lock().expect("key pool lock poisoned");
+        state
+            .iter()
+            .enumerate()
+            .any(|(i, s)| i != index && s.cooldown_until.is_none_or(|until| until <= now))
+    }
+
+    pub fn record_success(&self, index: usize) {
+        let mut state = self.state.lock().expect("key pool lock poisoned");
+        state[index].requests += 1;
+        state[index].cooldown_until = None;
+    }
+
+    pub fn record_rate_limited(&self, index: usize) {
+        self.record_rate_limited_at(index, Instant::now());
+    }
+
+    fn record_rate_limited_at(&self, index: usize, now: Instant) {
+        let mut state = self.state.lock().expect("key pool lock poisoned");
+        state[index].requests += 1;
+        state[index].rate_limited += 1;
+        state[index].cooldown_until = Some(now + RATE_LIMIT_COOLDOWN);
+    }
+
+    pub fn record_error(&self, index: usize) {
+        let mut state = self.state.lock().expect("key pool lock poisoned");
+        state[index].requests += 1;
+        state[index].errors += 1;
+    }
+
+    /// Health of every key in the pool
+    pub fn health(&self) -> Vec<KeyHealth> {
+        let now = Instant::now();
+        let state = self.state.lock().expect("key pool lock poisoned");
+        self.keys
+            .iter()
+            .zip(state.iter())
+            .map(|(key, s)| {
+                let cooldown = s
+                    .cooldown_until
+                    .filter(|until| *until > now)
+                    .map(|until| (until - now).as_secs());
+                KeyHealth {
+                    key_id: key_fingerprint(key),
+                    requests: s.requests,
+                    rate_limited: s.rate_limited,
+                    errors: s.errors,
+                    available: cooldown.is_none(),
+                    cooldown_remaining_secs: cooldown,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool() -> KeyPool {
+        KeyPool::new(vec![
+            "key-a".to_string(),
+            "key-b".to_string(),
+            " ".to_string(),
+            "key-a".to_string(),
+            "key-c".to_string(),
+        ])
+    }
+
+    #[test]
+    fn test_pool_dedupes_keys() {
+        let pool = pool();
+        assert_eq!(pool.len(), 3);
+        assert_eq!(pool.primary(), "key-a");
+    }
+
+    #[test]
+    fn test_round_robin() {
+        let pool = pool();
+        let now = Instant::now();
+        let picked: Vec<&str> = (0..4).map(|_| pool.next_key_at(now).1).collect();
+        assert_eq!(picked, vec!["key-a", "key-b", "key-c", "key-a"]);
+    }
+
+    #[test]
+    fn test_rate_limited_key_is_skipped() {
+        let pool = pool();
+        let now = Instant::now();
+        pool.record_rate_limited_at(1, now);
+
+        let picked: Vec<&str> = (0..3).map(|_| pool.next_key_at(now).1).collect();
+        assert!(!picked.contains(&"key-b"));
+        assert!(pool.has_alternative(1));
+
+        // After the cooldown the key is back in rotation
+        let later = now + RATE_LIMIT_COOLDOWN;
+        let picked: Vec<&str> = (0..3).map(|_| pool.next_key_at(later).1).collect();
+        assert!(picked.contains(&"key-b"));
+    }
+
+    #[test]
+    fn test_all_keys_cooling_down_picks_earliest_recovery() {
+        let pool = pool();
+        let now = Instant::now();
+        pool.record_rate_limited_at(0, now + Duration::from_secs(5));
+        pool.record_rate_limited_at(1, now);
+        pool.record_rate_limited_at(2, now + Duration::from_secs(10));
+
+        assert_eq!(pool.next_key_at(now).0, 1);
+        assert!(!pool.has_alternative(1));
+
+        let health = pool.health();
+        assert_eq!(health[1].rate_limited, 1);
+        assert!(!health[1].available);
+    }
+}
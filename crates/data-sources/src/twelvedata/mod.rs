@@ -4,16 +4,25 @@
 //! - Real-time price streaming via WebSocket (170ms latency target)
 //! - Historical time series data
 //! - Market quotes and movers
+//! - Round-robin rotation over multiple API keys
 //!
 //! # WebSocket Features
 //! - Auto-reconnection with exponential backoff
 //! - Subscription management for multiple symbols
-//! - Backpressure handling
+//! - Backpressure handling: ticks can go to a bounded, batch-drained queue
+//!   that drops the oldest ticks instead of stalling the socket
+//! - Intraday OHLCV bars (1m/5m/15m) aggregated from the price updates
 
+mod bar_aggregator;
 mod client;
+mod key_pool;
 mod models;
+mod tick_queue;
 mod websocket;
 
+pub use bar_aggregator::{BarAggregator, IntradayBar, DEFAULT_BAR_INTERVALS};
 pub use client::TwelveDataClient;
+pub use key_pool::{KeyHealth, KeyPool};
 pub use models::*;
+pub use tick_queue::{TickQueue, TickQueueConfig, TickQueueStats};
 pub use websocket::{TwelveDataWebSocket, WebSocketEvent};
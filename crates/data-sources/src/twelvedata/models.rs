@@ -1,6 +1,6 @@
 //! Data models for TwelveData API responses
 
-use chrono::{DateTime, NaiveDate, Utc};
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveDateTime, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
@@ -48,6 +48,25 @@ pub struct TimeSeriesPoint {
     pub volume: Option<i64>,
 }
 
+impl TimeSeriesPoint {
+    /// Bar start in UTC; `datetime` is in the exchange's local time
+    ///
+    /// Daily and longer bars carry a date only and start at local midnight.
+    pub fn time(&self, exchange_offset: FixedOffset) -> Option<DateTime<Utc>> {
+        let local = NaiveDateTime::parse_from_str(&self.datetime, "%Y-%m-%d %H:%M:%S")
+            .ok()
+            .or_else(|| {
+                NaiveDate::parse_from_str(&self.datetime, "%Y-%m-%d")
+                    .ok()
+                    .map(|d| d.and_time(chrono::NaiveTime::MIN))
+            })?;
+        local
+            .and_local_timezone(exchange_offset)
+            .single()
+            .map(|t| t.with_timezone(&Utc))
+    }
+}
+
 /// Time series metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TimeSeriesMeta {
@@ -173,7 +192,7 @@ pub struct ExchangeInfo {
 }
 
 /// Time interval for data requests
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Interval {
     Min1,
     Min5,
@@ -204,6 +223,45 @@ impl Interval {
             Interval::Month1 => "1month",
         }
     }
+
+    pub fn from_str_opt(s: &str) -> Option<Self> {
+        [
+            Interval::Min1,
+            Interval::Min5,
+            Interval::Min15,
+            Interval::Min30,
+            Interval::Min45,
+            Interval::Hour1,
+            Interval::Hour2,
+            Interval::Hour4,
+            Interval::Day1,
+            Interval::Week1,
+            Interval::Month1,
+        ]
+        .into_iter()
+        .find(|interval| interval.as_str() == s)
+    }
+
+    /// Length of intraday intervals; `None` for daily and longer
+    pub fn duration(&self) -> Option<Duration> {
+        match self {
+            Interval::Min1 => Some(Duration::minutes(1)),
+            Interval::Min5 => Some(Duration::minutes(5)),
+            Interval::Min15 => Some(Duration::minutes(15)),
+            Interval::Min30 => Some(Duration::minutes(30)),
+            Interval::Min45 => Some(Duration::minutes(45)),
+            Interval::Hour1 => Some(Duration::hours(1)),
+            Interval::Hour2 => Some(Duration::hours(2)),
+            Interval::Hour4 => Some(Duration::hours(4)),
+            Interval::Day1 | Interval::Week1 | Interval::Month1 => None,
+        }
+    }
+}
+
+impl Serialize for Interval {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
 }
 
 impl std::fmt::Display for Interval {
@@ -0,0 +1,275 @@
+//! Bounded ingestion queue for WebSocket ticks
+//!
+//! In volatile sessions the feed can outpace database writes. Ticks go into a
+//! bounded queue that a single writer task drains in batches; when the queue
+//! is full the oldest tick is dropped, since a newer price for the symbol
+//! supersedes it. Connection and subscription events stay on the event
+//! channel and are never dropped.
+
+use super::models::PriceUpdate;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::time::Instant;
+use tracing::warn;
+
+/// Tick queue sizing
+#[derive(Debug, Clone)]
+pub struct TickQueueConfig {
+    /// Ticks held before the oldest are dropped
+    pub capacity: usize,
+    /// Ticks handed to the writer per batch
+    pub batch_size: usize,
+    /// Longest a partial batch waits before it is flushed
+    pub flush_interval: Duration,
+}
+
+impl Default for TickQueueConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 10_000,
+            batch_size: 500,
+            flush_interval: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Queue depth and throughput counters
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TickQueueStats {
+    pub depth: usize,
+    pub capacity: usize,
+    /// Highest depth seen since the queue was created
+    pub max_depth: usize,
+    pub enqueued: u64,
+    /// Ticks dropped because the queue was full
+    pub dropped: u64,
+    pub flushed: u64,
+    pub batches: u64,
+}
+
+#[derive(Debug, Default)]
+struct QueueState {
+    ticks: VecDeque<PriceUpdate>,
+    max_depth: usize,
+    enqueued: u64,
+    dropped: u64,
+    flushed: u64,
+    batches: u64,
+}
+
+/// Bounded drop-oldest queue of price ticks, drained in batches
+#[derive(Debug)]
+pub struct TickQueue {
+    config: TickQueueConfig,
+    state: Mutex<QueueState>,
+    notify: Notify,
+    closed: AtomicBool,
+}
+
+impl TickQueue {
+    pub fn new(config: TickQueueConfig) -> Self {
+        let config = TickQueueConfig {
+            capacity: config.capacity.max(1),
+            batch_size: config.batch_size.max(1),
+            flush_interval: config.flush_interval,
+        };
+        Self {
+            state: Mutex::new(QueueState {
+                ticks: VecDeque::with_capacity(config.capacity),
+                ..Default::default()
+            }),
+            config,
+            notify: Notify::new(),
+            closed: AtomicBool::new(false),
+        }
+    }
+
+    /// Enqueue a tick without waiting; returns false if an older tick was dropped
+    pub fn push(&self, tick: PriceUpdate) -> bool {
+        let mut state = self.state.lock().expect("tick queue lock poisoned");
+        let dropped = state.ticks.len() >= self.config.capacity;
+        if dropped {
+            state.ticks.pop_front();
+            state.dropped += 1;
+            if state.dropped.is_power_of_two() {
+                warn!(
+                    "Tick queue full ({} ticks), {} dropped so far",
+                    self.config.capacity, state.dropped
+                );
+            }
+        }
+        state.ticks.push_back(tick);
+        state.enqueued += 1;
+        state.max_depth = state.max_depth.max(state.ticks.len());
+        let batch_ready = state.ticks.len() >= self.config.batch_size;
+        drop(state);
+
+        if batch_ready {
+            self.notify.notify_one();
+        }
+        !dropped
+    }
+
+    /// Take up to one batch without waiting
+    pub fn try_next_batch(&self) -> Vec<PriceUpdate> {
+        let mut state = self.state.lock().expect("tick queue lock poisoned");
+        let take = state.ticks.len().min(self.config.batch_size);
+        let batch: Vec<PriceUpdate> = state.ticks.drain(..take).collect();
+        if !batch.is_empty() {
+            state.flushed += batch.len() as u64;
+            state.batches += 1;
+        }
+        batch
+    }
+
+    /// Wait for the next batch
+    ///
+    /// Returns a full batch as soon as one is queued, or whatever is queued
+    /// once the flush interval passes. Returns `None` after `close` once the
+    /// queue is empty.
+    pub async fn next_batch(&self) -> Option<Vec<PriceUpdate>> {
+        let mut deadline = Instant::now() + self.config.flush_interval;
+        loop {
+            let notified = self.notify.notified();
+            let depth = self.len();
+            if depth >= self.config.batch_size {
+                return Some(self.try_next_batch());
+            }
+            if self.is_closed() {
+                return (depth > 0).then(|| self.try_next_batch());
+            }
+
+            tokio::select! {
+                _ = notified => {}
+                _ = tokio::time::sleep_until(deadline) => {
+                    let batch = self.try_next_batch();
+                    if !batch.is_empty() {
+                        return Some(batch);
+                    }
+                    deadline = Instant::now() + self.config.flush_interval;
+                }
+            }
+        }
+    }
+
+    /// Stop accepting waits; the writer drains what is left and then stops
+    pub fn close(&self) {
+        self.closed.store(true, Ordering::Release);
+        self.notify.notify_one();
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Acquire)
+    }
+
+    pub fn len(&self) -> usize {
+        self.state
+            .lock()
+            .expect("tick queue lock poisoned")
+            .ticks
+            .len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn stats(&self) -> TickQueueStats {
+        let state = self.state.lock().expect("tick queue lock poisoned");
+        TickQueueStats {
+            depth: state.ticks.len(),
+            capacity: self.config.capacity,
+            max_depth: state.max_depth,
+            enqueued: state.enqueued,
+            dropped: state.dropped,
+            flushed: state.flushed,
+            batches: state.batches,
+        }
+    }
+}
+
+impl Default for TickQueue {
+    fn default() -> Self {
+        Self::new(TickQueueConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+
+    fn tick(symbol: &str, price: i64) -> PriceUpdate {
+        PriceUpdate {
+            event: "price".to_string(),
+            symbol: symbol.to_string(),
+            currency: None,
+            exchange: None,
+            mic_code: None,
+            instrument_type: None,
+            price: Some(Decimal::from(price)),
+            bid: None,
+            ask: None,
+            day_volume: None,
+            timestamp: None,
+        }
+    }
+
+    fn queue(capacity: usize, batch_size: usize) -> TickQueue {
+        TickQueue::new(TickQueueConfig {
+            capacity,
+            batch_size,
+            flush_interval: Duration::from_millis(20),
+        })
+    }
+
+    #[test]
+    fn test_drops_oldest_when_full() {
+        let queue = queue(3, 10);
+        for price in 1..=3 {
+            assert!(queue.push(tick("BBCA", price)));
+        }
+        assert!(!queue.push(tick("BBCA", 4)));
+
+        let batch = queue.try_next_batch();
+        let prices: Vec<_> = batch.iter().filter_map(|t| t.price).collect();
+        assert_eq!(
+            prices,
+            vec![Decimal::from(2), Decimal::from(3), Decimal::from(4)]
+        );
+
+        let stats = queue.stats();
+        assert_eq!(stats.enqueued, 4);
+        assert_eq!(stats.dropped, 1);
+        assert_eq!(stats.flushed, 3);
+        assert_eq!(stats.max_depth, 3);
+        assert_eq!(stats.depth, 0);
+    }
+
+    #[tokio::test]
+    async fn test_batches_by_size_then_interval() {
+        let queue = queue(100, 2);
+        for price in 1..=3 {
+            queue.push(tick("TLKM", price));
+        }
+
+        // A full batch is returned immediately, the remainder after the interval
+        assert_eq!(queue.next_batch().await.unwrap().len(), 2);
+        assert_eq!(queue.next_batch().await.unwrap().len(), 1);
+        assert_eq!(queue.stats().batches, 2);
+    }
+
+    #[tokio::test]
+    async fn test_close_drains_then_stops() {
+        let queue = queue(100, 10);
+        queue.push(tick("ASII", 1));
+        queue.close();
+
+        assert_eq!(queue.next_batch().await.unwrap().len(), 1);
+        assert!(queue.next_batch().await.is_none());
+    }
+}
@@ -3,9 +3,11 @@
 //! Features:
 //! - Auto-reconnection with exponential backoff
 //! - Subscription management
-//! - Backpressure handling
+//! - Backpressure handling: with a tick queue attached, price ticks never
+//!   block the socket reader; the oldest are dropped when the writer lags
 
 use super::models::{PriceUpdate, SubscribeAction, WebSocketMessage};
+use super::tick_queue::TickQueue;
 use crate::error::DataSourceError;
 use futures_util::{SinkExt, StreamExt};
 use std::collections::HashSet;
@@ -40,6 +42,7 @@ pub struct TwelveDataWebSocket {
     event_rx: Arc<Mutex<mpsc::Receiver<WebSocketEvent>>>,
     running: Arc<RwLock<bool>>,
     command_tx: Option<mpsc::Sender<WebSocketCommand>>,
+    tick_queue: Option<Arc<TickQueue>>,
 }
 
 #[derive(Debug)]
@@ -61,9 +64,23 @@ impl TwelveDataWebSocket {
             event_rx: Arc::new(Mutex::new(event_rx)),
             running: Arc::new(RwLock::new(false)),
             command_tx: None,
+            tick_queue: None,
         }
     }
 
+    /// Route price ticks to `queue` instead of the event channel
+    ///
+    /// Other events still arrive through `recv`.
+    pub fn with_tick_queue(mut self, queue: Arc<TickQueue>) -> Self {
+        self.tick_queue = Some(queue);
+        self
+    }
+
+    /// Attached tick queue, for draining and queue metrics
+    pub fn tick_queue(&self) -> Option<&Arc<TickQueue>> {
+        self.tick_queue.as_ref()
+    }
+
     /// Create from environment variable
     pub fn from_env() -> Result<Self, DataSourceError> {
         let api_key = std::env::var("TWELVEDATA_API_KEY")
@@ -87,9 +104,18 @@ impl TwelveDataWebSocket {
         let event_tx = self.event_tx.clone();
         let subscriptions = self.subscriptions.clone();
         let running = self.running.clone();
+        let tick_queue = self.tick_queue.clone();
 
         tokio::spawn(async move {
-            Self::connection_loop(api_key, event_tx, subscriptions, running, command_rx).await;
+            Self::connection_loop(
+                api_key,
+                event_tx,
+                subscriptions,
+                running,
+                command_rx,
+                tick_queue,
+            )
+            .await;
         });
 
         Ok(())
@@ -102,6 +128,7 @@ impl TwelveDataWebSocket {
         subscriptions: Arc<RwLock<HashSet<String>>>,
         running: Arc<RwLock<bool>>,
         mut command_rx: mpsc::Receiver<WebSocketCommand>,
+        tick_queue: Option<Arc<TickQueue>>,
     ) {
         let mut reconnect_delay = RECONNECT_DELAY_MS;
 
@@ -149,7 +176,7 @@ impl TwelveDataWebSocket {
                                         if let Ok(ws_msg) = serde_json::from_str::<WebSocketMessage>(&text) {
                                             match ws_msg {
                                                 WebSocketMessage::Price(price) => {
-                                                    let _ = event_tx.send(WebSocketEvent::Price(price)).await;
+                                                    Self::dispatch_price(&event_tx, tick_queue.as_deref(), price).await;
                                                 }
                                                 WebSocketMessage::SubscribeStatus { success, .. } => {
                                                     let symbols: Vec<String> = success.iter().map(|s| s.symbol.clone()).collect();
@@ -246,6 +273,22 @@ impl TwelveDataWebSocket {
         }
     }
 
+    /// Hand a tick to the queue if attached, else to the event channel
+    async fn dispatch_price(
+        event_tx: &mpsc::Sender<WebSocketEvent>,
+        tick_queue: Option<&TickQueue>,
+        price: PriceUpdate,
+    ) {
+        match tick_queue {
+            Some(queue) => {
+                queue.push(price);
+            }
+            None => {
+                let _ = event_tx.send(WebSocketEvent::Price(price)).await;
+            }
+        }
+    }
+
     /// Subscribe to symbols
     pub async fn subscribe(&self, symbols: Vec<String>) -> Result<(), DataSourceError> {
         if let Some(tx) = &self.command_tx {
@@ -306,6 +349,27 @@ mod tests {
         assert!(!ws.api_key.is_empty());
     }
 
+    #[test]
+    fn test_ticks_go_to_attached_queue() {
+        let queue = Arc::new(TickQueue::default());
+        let ws = TwelveDataWebSocket::new("test_key".to_string()).with_tick_queue(queue.clone());
+        assert!(ws.tick_queue().is_some());
+
+        let price: PriceUpdate = serde_json::from_str(
+            r#"{"event":"price","symbol":"BBCA","price":9250,"timestamp":1718000000}"#,
+        )
+        .unwrap();
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(TwelveDataWebSocket::dispatch_price(
+                &ws.event_tx,
+                ws.tick_queue().map(|q| q.as_ref()),
+                price,
+            ));
+        assert_eq!(queue.stats().enqueued, 1);
+    }
+
     #[test]
     fn test_subscribe_action() {
         let action = SubscribeAction::subscribe(vec!["AAPL".to_string(), "MSFT".to_string()]);
@@ -3,6 +3,9 @@
 use super::models::*;
 use super::parser;
 use crate::error::DataSourceError;
+use crate::http::{self, FaultHook};
+use crate::symbols::{canonical_symbol, default_provider_symbol, SymbolProvider};
+use futures_util::stream::{self, StreamExt};
 use reqwest::Client;
 use std::time::Duration;
 use tracing::{debug, warn};
@@ -10,10 +13,17 @@ use tracing::{debug, warn};
 const YAHOO_QUOTE_API: &str = "https://query1.finance.yahoo.com/v7/finance/quote";
 const YAHOO_CHART_API: &str = "https://query1.finance.yahoo.com/v8/finance/chart";
 
+/// Symbols per quote request; Yahoo accepts longer lists but throttles them harder
+pub const QUOTE_BATCH_SIZE: usize = 50;
+/// Requests in flight during bulk fetches
+pub const DEFAULT_MAX_CONCURRENCY: usize = 8;
+
 /// Yahoo Finance API client
 #[derive(Debug, Clone)]
 pub struct YahooFinanceClient {
     client: Client,
+    max_concurrency: usize,
+    faults: FaultHook,
 }
 
 impl YahooFinanceClient {
@@ -25,21 +35,34 @@ impl YahooFinanceClient {
             .build()
             .expect("Failed to create HTTP client");
 
-        Self { client }
+        Self {
+            client,
+            max_concurrency: DEFAULT_MAX_CONCURRENCY,
+            faults: FaultHook::default(),
+        }
+    }
+
+    /// Set how many requests bulk fetches keep in flight
+    pub fn with_max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.max_concurrency = max_concurrency.max(1);
+        self
+    }
+
+    /// Route requests through a fault injector
+    #[cfg(feature = "fault-injection")]
+    pub fn with_faults(mut self, faults: std::sync::Arc<crate::fault::FaultInjector>) -> Self {
+        self.faults = Some(faults);
+        self
     }
 
     /// Convert IDX symbol to Yahoo Finance format (add .JK suffix)
     pub fn to_yahoo_symbol(symbol: &str) -> String {
-        if symbol.ends_with(".JK") {
-            symbol.to_string()
-        } else {
-            format!("{}.JK", symbol)
-        }
+        default_provider_symbol(symbol, SymbolProvider::Yahoo)
     }
 
     /// Convert Yahoo Finance symbol back to IDX format (remove .JK suffix)
     pub fn from_yahoo_symbol(symbol: &str) -> String {
-        symbol.trim_end_matches(".JK").to_string()
+        canonical_symbol(symbol)
     }
 
     /// Get quote for a single stock
@@ -48,14 +71,14 @@ impl YahooFinanceClient {
         debug!("Fetching quote for {}", yahoo_symbol);
 
         let url = format!("{}?symbols={}", YAHOO_QUOTE_API, yahoo_symbol);
-        let response = self.client.get(&url).send().await?;
+        let response = http::send(&self.faults, http::SOURCE_YAHOO, self.client.get(&url)).await?;
 
-        if response.status() == 429 {
+        if response.status == 429 {
             warn!("Rate limited by Yahoo Finance");
             return Err(DataSourceError::RateLimited);
         }
 
-        let data: QuoteResponse = response.json().await?;
+        let data: QuoteResponse = response.json()?;
 
         if let Some(error) = data.quote_response.error {
             return Err(DataSourceError::ApiError(error.to_string()));
@@ -77,14 +100,14 @@ impl YahooFinanceClient {
         debug!("Fetching quotes for {} symbols", yahoo_symbols.len());
 
         let url = format!("{}?symbols={}", YAHOO_QUOTE_API, yahoo_symbols.join(","));
-        let response = self.client.get(&url).send().await?;
+        let response = http::send(&self.faults, http::SOURCE_YAHOO, self.client.get(&url)).await?;
 
-        if response.status() == 429 {
+        if response.status == 429 {
             warn!("Rate limited by Yahoo Finance");
             return Err(DataSourceError::RateLimited);
         }
 
-        let data: QuoteResponse = response.json().await?;
+        let data: QuoteResponse = response.json()?;
 
         if let Some(error) = data.quote_response.error {
             return Err(DataSourceError::ApiError(error.to_string()));
@@ -97,6 +120,83 @@ impl YahooFinanceClient {
             .collect()
     }
 
+    /// Get quotes for any number of symbols
+    ///
+    /// Symbols are split into batches of [`QUOTE_BATCH_SIZE`], fetched with
+    /// bounded parallelism. A failed batch is reported without dropping the rest.
+    pub async fn get_quotes_bulk(&self, symbols: &[&str]) -> BulkFetchResult<YahooQuote> {
+        let batches: Vec<Vec<&str>> = symbols
+            .chunks(QUOTE_BATCH_SIZE)
+            .map(|chunk| chunk.to_vec())
+            .collect();
+        debug!(
+            "Fetching {} quotes in {} batches",
+            symbols.len(),
+            batches.len()
+        );
+
+        let outcomes: Vec<_> = stream::iter(batches)
+            .map(|batch| async move {
+                let result = self.get_quotes(&batch).await;
+                (batch, result)
+            })
+            .buffer_unordered(self.max_concurrency)
+            .collect()
+            .await;
+
+        let mut bulk = BulkFetchResult {
+            results: Vec::with_capacity(symbols.len()),
+            failures: Vec::new(),
+        };
+        for (batch, result) in outcomes {
+            match result {
+                Ok(quotes) => bulk.results.extend(quotes),
+                Err(e) => {
+                    warn!("Quote batch of {} symbols failed: {}", batch.len(), e);
+                    bulk.failures.push(BulkFetchFailure {
+                        symbols: batch.iter().map(|s| s.to_string()).collect(),
+                        error: e.to_string(),
+                    });
+                }
+            }
+        }
+        bulk
+    }
+
+    /// Get historical OHLCV data for many symbols concurrently
+    ///
+    /// Returns `(symbol, bars)` pairs in completion order.
+    pub async fn get_history_bulk(
+        &self,
+        symbols: &[&str],
+        interval: &str,
+        range: &str,
+    ) -> BulkFetchResult<(String, Vec<YahooOHLCV>)> {
+        let outcomes: Vec<_> = stream::iter(symbols.iter().copied())
+            .map(|symbol| async move {
+                let result = self.get_history(symbol, interval, range).await;
+                (symbol, result)
+            })
+            .buffer_unordered(self.max_concurrency)
+            .collect()
+            .await;
+
+        let mut bulk = BulkFetchResult {
+            results: Vec::with_capacity(symbols.len()),
+            failures: Vec::new(),
+        };
+        for (symbol, result) in outcomes {
+            match result {
+                Ok(bars) => bulk.results.push((symbol.to_string(), bars)),
+                Err(e) => bulk.failures.push(BulkFetchFailure {
+                    symbols: vec![symbol.to_string()],
+                    error: e.to_string(),
+                }),
+            }
+        }
+        bulk
+    }
+
     /// Get historical OHLCV data
     ///
     /// # Arguments
@@ -109,7 +209,25 @@ impl YahooFinanceClient {
         interval: &str,
         range: &str,
     ) -> Result<Vec<YahooOHLCV>, DataSourceError> {
-        let yahoo_symbol = Self::to_yahoo_symbol(symbol);
+        self.get_chart(&Self::to_yahoo_symbol(symbol), interval, range)
+            .await
+            .map_err(|e| match e {
+                DataSourceError::SymbolNotFound(_) => {
+                    DataSourceError::SymbolNotFound(symbol.to_string())
+                }
+                e => e,
+            })
+    }
+
+    /// Get historical OHLCV data for a ticker already in Yahoo notation
+    ///
+    /// Used for instruments outside IDX equities, e.g. `^JKSE` or `IDR=X`.
+    pub async fn get_chart(
+        &self,
+        yahoo_symbol: &str,
+        interval: &str,
+        range: &str,
+    ) -> Result<Vec<YahooOHLCV>, DataSourceError> {
         debug!(
             "Fetching history for {} (interval={}, range={})",
             yahoo_symbol, interval, range
@@ -119,18 +237,18 @@ impl YahooFinanceClient {
             "{}/{}?interval={}&range={}",
             YAHOO_CHART_API, yahoo_symbol, interval, range
         );
-        let response = self.client.get(&url).send().await?;
+        let response = http::send(&self.faults, http::SOURCE_YAHOO, self.client.get(&url)).await?;
 
-        if response.status() == 429 {
+        if response.status == 429 {
             warn!("Rate limited by Yahoo Finance");
             return Err(DataSourceError::RateLimited);
         }
 
-        if response.status() == 404 {
-            return Err(DataSourceError::SymbolNotFound(symbol.to_string()));
+        if response.status == 404 {
+            return Err(DataSourceError::SymbolNotFound(yahoo_symbol.to_string()));
         }
 
-        let data: ChartResponse = response.json().await?;
+        let data: ChartResponse = response.json()?;
 
         if let Some(error) = data.chart.error {
             return Err(DataSourceError::ApiError(error.to_string()));
@@ -185,6 +303,12 @@ mod tests {
         assert_eq!(YahooFinanceClient::from_yahoo_symbol("BBCA"), "BBCA");
     }
 
+    #[test]
+    fn test_concurrency_is_at_least_one() {
+        let client = YahooFinanceClient::new().with_max_concurrency(0);
+        assert_eq!(client.max_concurrency, 1);
+    }
+
     #[test]
     fn test_stock_list_not_empty() {
         let list = YahooFinanceClient::get_idx_stock_list();
@@ -4,5 +4,5 @@ mod client;
 mod models;
 mod parser;
 
-pub use client::YahooFinanceClient;
+pub use client::{YahooFinanceClient, DEFAULT_MAX_CONCURRENCY, QUOTE_BATCH_SIZE};
 pub use models::*;
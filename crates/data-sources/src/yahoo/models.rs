@@ -53,6 +53,29 @@ pub struct YahooStockInfo {
     pub profit_margin: Option<f64>,
 }
 
+/// Symbols that could not be fetched in a bulk request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkFetchFailure {
+    pub symbols: Vec<String>,
+    pub error: String,
+}
+
+/// Outcome of a bulk fetch: one failed batch doesn't sink the others
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkFetchResult<T> {
+    pub results: Vec<T>,
+    pub failures: Vec<BulkFetchFailure>,
+}
+
+impl<T> BulkFetchResult<T> {
+    pub fn failed_symbols(&self) -> Vec<&str> {
+        self.failures
+            .iter()
+            .flat_map(|f| f.symbols.iter().map(String::as_str))
+            .collect()
+    }
+}
+
 // Internal response structures for parsing
 #[derive(Debug, Deserialize)]
 pub(crate) struct QuoteResponse {
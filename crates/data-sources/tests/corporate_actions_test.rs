@@ -0,0 +1,120 @@
+//! Corporate action tests against recorded IDX and KSEI responses
+//! Run with: cargo test -p jejakcuan-data-sources --test corporate_actions_test
+
+use chrono::NaiveDate;
+use jejakcuan_data_sources::corporate_actions::parse_ksei_schedule;
+use jejakcuan_data_sources::http::{SOURCE_IDX, SOURCE_KSEI};
+use jejakcuan_data_sources::{
+    CorporateActionClient, CorporateActionKind, DataSourceError, Fault, FaultInjector, IdxClient,
+};
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+use std::time::Duration;
+
+const IDX_ACTIONS: &str = include_str!("fixtures/idx/corporate_actions.json");
+const KSEI_SCHEDULE: &str = include_str!("fixtures/ksei/corporate_action_schedule.html");
+
+fn client(faults: &Arc<FaultInjector>) -> CorporateActionClient {
+    let idx = IdxClient::new().with_min_interval(Duration::from_millis(5));
+    CorporateActionClient::with_idx(idx).with_faults(faults.clone())
+}
+
+fn date(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+#[tokio::test]
+async fn test_idx_actions() {
+    let faults = Arc::new(FaultInjector::new());
+    faults.stub(SOURCE_IDX, IDX_ACTIONS);
+    let actions = client(&faults)
+        .get_idx_actions(date("2021-01-01"))
+        .await
+        .unwrap();
+
+    assert_eq!(actions.len(), 4);
+    let split = &actions[0];
+    assert_eq!(split.kind, CorporateActionKind::StockSplit);
+    assert_eq!(split.ex_date, Some(date("2021-10-13")));
+    assert_eq!(split.ratio, Some(dec!(5)));
+
+    let dividend = &actions[1];
+    assert_eq!(dividend.symbol, "BBRI");
+    assert_eq!(dividend.kind, CorporateActionKind::CashDividend);
+    assert_eq!(dividend.amount, Some(dec!(208.40)));
+    assert_eq!(dividend.ratio, None);
+
+    // The rights issue leaves ratio and price to the description
+    assert_eq!(actions[2].kind, CorporateActionKind::RightsIssue);
+    assert_eq!(actions[2].price, None);
+    assert_eq!(actions[3].kind, CorporateActionKind::Other);
+    assert!(!actions[3].kind.adjusts_price());
+}
+
+#[test]
+fn test_ksei_schedule() {
+    let actions = parse_ksei_schedule(KSEI_SCHEDULE).unwrap();
+
+    // The row without a code is skipped
+    assert_eq!(actions.len(), 3);
+    assert_eq!(actions[0].amount, Some(dec!(208.40)));
+    assert_eq!(actions[0].effective_date, Some(date("2025-04-29")));
+
+    let rights = &actions[1];
+    assert_eq!(rights.kind, CorporateActionKind::RightsIssue);
+    assert_eq!(rights.ex_date, Some(date("2025-05-20")));
+    assert_eq!(rights.ratio, Some(dec!(0.25)));
+    assert_eq!(rights.price, Some(dec!(1050)));
+
+    assert_eq!(actions[2].kind, CorporateActionKind::BonusShares);
+    assert_eq!(actions[2].ratio, Some(dec!(0.1)));
+}
+
+#[test]
+fn test_ksei_layout_change() {
+    let html = KSEI_SCHEDULE.replace("Tanggal Ex", "Tanggal Efektif");
+    let result = parse_ksei_schedule(&html);
+    assert!(matches!(result, Err(DataSourceError::ParserOutdated(_))));
+}
+
+#[tokio::test]
+async fn test_merged_actions() {
+    let faults = Arc::new(FaultInjector::new());
+    faults.stub(SOURCE_IDX, IDX_ACTIONS);
+    faults.stub(SOURCE_KSEI, KSEI_SCHEDULE);
+    let actions = client(&faults)
+        .get_corporate_actions(date("2025-01-01"))
+        .await
+        .unwrap();
+
+    // IDX's four plus the bonus issue only KSEI lists
+    assert_eq!(actions.len(), 5);
+    let rights = actions.iter().find(|a| a.symbol == "BUKA").unwrap();
+    assert_eq!(rights.source, "idx");
+    assert_eq!(rights.ratio, Some(dec!(0.25)));
+    assert_eq!(rights.price, Some(dec!(1050)));
+    assert_eq!(rights.effective_date, Some(date("2025-06-05")));
+    assert_eq!(actions[4].symbol, "ANTM");
+    assert_eq!(actions[4].source, "ksei");
+}
+
+#[tokio::test]
+async fn test_one_source_down() {
+    let faults = Arc::new(FaultInjector::new());
+    faults.stub(SOURCE_IDX, IDX_ACTIONS);
+    faults.inject(SOURCE_KSEI, Fault::Status(503), 1);
+    let actions = client(&faults)
+        .get_corporate_actions(date("2025-01-01"))
+        .await
+        .unwrap();
+    assert_eq!(actions.len(), 4);
+    assert_eq!(faults.requests(SOURCE_KSEI), 1);
+
+    faults.clear();
+    faults.inject(SOURCE_IDX, Fault::Status(503), 3);
+    faults.inject(SOURCE_KSEI, Fault::Status(503), 1);
+    let result = client(&faults)
+        .get_corporate_actions(date("2025-01-01"))
+        .await;
+    assert!(result.is_err());
+}
@@ -0,0 +1,242 @@
+//! Fault-injection tests for the data-source clients
+//! Run with: cargo test -p jejakcuan-data-sources --test fault_injection
+
+use chrono::NaiveDate;
+use jejakcuan_data_sources::http::{self, SOURCE_IDX_BROKER, SOURCE_TWELVEDATA, SOURCE_YAHOO};
+use jejakcuan_data_sources::{
+    BrokerScraper, DataSourceError, FailoverProvider, Fault, FaultInjector, ParserStatus,
+    PriceDataProvider, TwelveDataClient, YahooFinanceClient, FAILURES_BEFORE_COOLDOWN,
+};
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+use std::time::Duration;
+
+const BROKER_HTML: &str = r#"<html><body><table class="broker-summary">
+<tr><th>Broker</th><th>Buy Volume</th><th>Buy Value</th><th>Sell Volume</th><th>Sell Value</th></tr>
+<tr><td>YP</td><td>1000</td><td>9500000</td><td>500</td><td>4750000</td></tr>
+</table></body></html>"#;
+
+const TWELVEDATA_QUOTE: &str = r#"{"symbol":"BBCA","close":"9500"}"#;
+
+fn twelvedata(faults: &Arc<FaultInjector>) -> TwelveDataClient {
+    TwelveDataClient::with_keys(vec!["key-one".into(), "key-two".into()])
+        .unwrap()
+        .with_faults(faults.clone())
+}
+
+/// Yahoo first, TwelveData as the backup, both behind `faults`
+fn failover(faults: &Arc<FaultInjector>) -> FailoverProvider {
+    faults.stub(SOURCE_TWELVEDATA, TWELVEDATA_QUOTE);
+    FailoverProvider::new(vec![
+        Arc::new(YahooFinanceClient::new().with_faults(faults.clone())),
+        Arc::new(twelvedata(faults)),
+    ])
+}
+
+#[tokio::test]
+async fn test_rate_limit_rotates_to_next_key() {
+    let faults = Arc::new(FaultInjector::new());
+    faults
+        .stub(SOURCE_TWELVEDATA, r#"{"price":"9500"}"#)
+        .inject(SOURCE_TWELVEDATA, Fault::RATE_LIMITED, 1);
+    let client = twelvedata(&faults);
+
+    let price = client.price("BBCA").await.expect("second key succeeds");
+    assert_eq!(price.price, dec!(9500));
+    assert_eq!(faults.requests(SOURCE_TWELVEDATA), 2);
+
+    let health = client.key_health();
+    assert_eq!(health.iter().map(|k| k.rate_limited).sum::<u64>(), 1);
+    assert_eq!(health.iter().filter(|k| !k.available).count(), 1);
+}
+
+#[tokio::test]
+async fn test_timeout_is_retried() {
+    let faults = Arc::new(FaultInjector::new());
+    faults
+        .stub(SOURCE_TWELVEDATA, r#"{"price":"9500"}"#)
+        .inject(SOURCE_TWELVEDATA, Fault::Timeout, 1);
+    let client = twelvedata(&faults);
+
+    assert!(client.price("BBCA").await.is_ok());
+    assert_eq!(faults.injected(SOURCE_TWELVEDATA), 1);
+    assert_eq!(faults.pending(SOURCE_TWELVEDATA), 0);
+    assert_eq!(client.key_health().iter().map(|k| k.errors).sum::<u64>(), 1);
+}
+
+#[tokio::test]
+async fn test_partial_body_is_invalid_response() {
+    let faults = Arc::new(FaultInjector::new());
+    faults
+        .stub(SOURCE_TWELVEDATA, r#"{"price":"9500"}"#)
+        .inject(SOURCE_TWELVEDATA, Fault::PartialBody { keep_pct: 50 }, 1);
+
+    let result = twelvedata(&faults).price("BBCA").await;
+    assert!(matches!(result, Err(DataSourceError::InvalidResponse(_))));
+    // Bad data is not retried
+    assert_eq!(faults.requests(SOURCE_TWELVEDATA), 1);
+}
+
+#[tokio::test]
+async fn test_yahoo_rate_limit_surfaces() {
+    let faults = Arc::new(FaultInjector::new());
+    faults.inject(SOURCE_YAHOO, Fault::RATE_LIMITED, 1);
+
+    let client = YahooFinanceClient::new().with_faults(faults.clone());
+    let result = client.get_quote("BBCA").await;
+    assert!(matches!(result, Err(DataSourceError::RateLimited)));
+}
+
+#[tokio::test]
+async fn test_broker_self_check_reports_parser_status() {
+    let faults = Arc::new(FaultInjector::new());
+    faults.stub(SOURCE_IDX_BROKER, BROKER_HTML);
+    let scraper = BrokerScraper::new()
+        .with_rate_limit(0)
+        .with_faults(faults.clone());
+
+    assert_eq!(
+        scraper.self_check("BBCA").await.status,
+        ParserStatus::Healthy
+    );
+
+    faults.inject(SOURCE_IDX_BROKER, Fault::MalformedBody, 1);
+    let report = scraper.self_check("BBCA").await;
+    assert_eq!(report.status, ParserStatus::Outdated);
+    assert!(!report.missing_selectors.is_empty());
+
+    faults.inject(SOURCE_IDX_BROKER, Fault::SERVICE_UNAVAILABLE, 1);
+    assert_eq!(
+        scraper.self_check("BBCA").await.status,
+        ParserStatus::Unreachable
+    );
+
+    faults.inject(SOURCE_IDX_BROKER, Fault::Timeout, 1);
+    assert_eq!(
+        scraper.self_check("BBCA").await.status,
+        ParserStatus::Unreachable
+    );
+}
+
+#[tokio::test]
+async fn test_malformed_broker_html_is_parser_outdated() {
+    let faults = Arc::new(FaultInjector::new());
+    faults
+        .stub(SOURCE_IDX_BROKER, BROKER_HTML)
+        .inject(SOURCE_IDX_BROKER, Fault::MalformedBody, 1);
+    let scraper = BrokerScraper::new()
+        .with_rate_limit(0)
+        .with_faults(faults.clone());
+    let date = NaiveDate::from_ymd_opt(2024, 6, 3).unwrap();
+
+    let result = scraper.get_broker_summary_html("BBCA", date).await;
+    assert!(matches!(result, Err(DataSourceError::ParserOutdated(_))));
+
+    let summaries = scraper
+        .get_broker_summary_html("BBCA", date)
+        .await
+        .expect("stubbed page parses");
+    assert_eq!(summaries.len(), 1);
+    assert_eq!(summaries[0].broker_code, "YP");
+}
+
+#[tokio::test]
+async fn test_client_timeout_maps_to_timeout() {
+    // Accepts connections but never answers
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = tokio::spawn(async move {
+        let mut held = Vec::new();
+        while let Ok((stream, _)) = listener.accept().await {
+            held.push(stream);
+        }
+    });
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_millis(100))
+        .build()
+        .unwrap();
+    let result = http::send(&None, SOURCE_YAHOO, client.get(format!("http://{}", addr))).await;
+    assert!(matches!(result, Err(DataSourceError::Timeout(_))));
+    server.abort();
+}
+
+#[tokio::test]
+async fn test_failover_on_injected_timeout() {
+    let faults = Arc::new(FaultInjector::new());
+    faults.inject(SOURCE_YAHOO, Fault::Timeout, 1);
+    let provider = failover(&faults);
+
+    let quote = provider.get_quote("BBCA").await.expect("backup answers");
+    assert_eq!(quote.source, "twelvedata");
+    assert_eq!(quote.price, dec!(9500));
+
+    let health = provider.health();
+    assert_eq!(health[0].failures, 1);
+    assert!(health[0]
+        .last_error
+        .as_deref()
+        .is_some_and(|e| e.contains("timed out")));
+    // One timeout does not take the primary out of rotation
+    assert!(health[0].available);
+    assert!(health[1].last_success.is_some());
+}
+
+#[tokio::test]
+async fn test_injected_rate_limit_opens_circuit_at_once() {
+    let faults = Arc::new(FaultInjector::new());
+    faults.inject(SOURCE_YAHOO, Fault::RATE_LIMITED, 1);
+    let provider = failover(&faults);
+
+    provider.get_quote("BBCA").await.expect("backup answers");
+    assert!(!provider.health()[0].available);
+
+    // Skipped while cooling down
+    let quote = provider.get_quote("BBCA").await.expect("backup answers");
+    assert_eq!(quote.source, "twelvedata");
+    assert_eq!(faults.requests(SOURCE_YAHOO), 1);
+    assert_eq!(faults.requests(SOURCE_TWELVEDATA), 2);
+}
+
+#[tokio::test]
+async fn test_repeated_outages_open_circuit() {
+    let faults = Arc::new(FaultInjector::new());
+    faults.inject(
+        SOURCE_YAHOO,
+        Fault::SERVICE_UNAVAILABLE,
+        FAILURES_BEFORE_COOLDOWN as usize + 2,
+    );
+    let provider = failover(&faults);
+
+    for _ in 0..FAILURES_BEFORE_COOLDOWN + 2 {
+        let quote = provider.get_quote("BBCA").await.expect("backup answers");
+        assert_eq!(quote.source, "twelvedata");
+    }
+
+    // The primary stops being called once the failure threshold is reached
+    assert_eq!(
+        faults.requests(SOURCE_YAHOO),
+        FAILURES_BEFORE_COOLDOWN as usize
+    );
+    assert_eq!(faults.pending(SOURCE_YAHOO), 2);
+
+    let health = provider.health();
+    assert!(!health[0].available);
+    assert!(health[0].cooldown_remaining_secs.is_some());
+    assert_eq!(health[0].consecutive_failures, FAILURES_BEFORE_COOLDOWN);
+}
+
+#[tokio::test]
+async fn test_every_provider_failing_is_reported() {
+    let faults = Arc::new(FaultInjector::new());
+    let provider = failover(&faults);
+    faults
+        .inject(SOURCE_YAHOO, Fault::SERVICE_UNAVAILABLE, 1)
+        .inject(SOURCE_TWELVEDATA, Fault::PartialBody { keep_pct: 30 }, 1);
+
+    let result = provider.get_quote("BBCA").await;
+    let Err(DataSourceError::ApiError(detail)) = result else {
+        panic!("expected every provider to fail, got {:?}", result);
+    };
+    assert!(detail.contains("yahoo") && detail.contains("twelvedata"));
+}
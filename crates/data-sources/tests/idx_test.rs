@@ -0,0 +1,163 @@
+//! IDX client tests against recorded responses
+//! Run with: cargo test -p jejakcuan-data-sources --test idx_test
+
+use chrono::NaiveDate;
+use jejakcuan_core::ReportPeriod;
+use jejakcuan_data_sources::http::SOURCE_IDX;
+use jejakcuan_data_sources::{DataSourceError, Fault, FaultInjector, IdxClient};
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+use std::time::Duration;
+
+const LISTED_COMPANIES: &str = include_str!("fixtures/idx/listed_companies.json");
+const INDEX_CONSTITUENTS: &str = include_str!("fixtures/idx/index_constituents.json");
+const HOLIDAYS: &str = include_str!("fixtures/idx/holidays.json");
+const STOCK_SUMMARY: &str = include_str!("fixtures/idx/stock_summary.json");
+const FINANCIAL_REPORTS: &str = include_str!("fixtures/idx/financial_reports.json");
+
+fn client(faults: &Arc<FaultInjector>) -> IdxClient {
+    IdxClient::new()
+        .with_min_interval(Duration::from_millis(5))
+        .with_faults(faults.clone())
+}
+
+fn stubbed(body: &str) -> (Arc<FaultInjector>, IdxClient) {
+    let faults = Arc::new(FaultInjector::new());
+    faults.stub(SOURCE_IDX, body);
+    let client = client(&faults);
+    (faults, client)
+}
+
+fn date(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+#[tokio::test]
+async fn test_listed_companies() {
+    let (_, client) = stubbed(LISTED_COMPANIES);
+    let companies = client.get_listed_companies().await.unwrap();
+
+    assert_eq!(companies.len(), 3);
+    let bbca = &companies[0];
+    assert_eq!(bbca.code, "BBCA");
+    assert_eq!(bbca.sector.as_deref(), Some("Keuangan"));
+    assert_eq!(bbca.board.as_deref(), Some("Utama"));
+    assert_eq!(bbca.listing_date, Some(date("2000-05-31")));
+
+    // Blank strings are absent rather than empty
+    let zyrx = &companies[2];
+    assert_eq!(zyrx.sector, None);
+    assert_eq!(zyrx.listing_date, None);
+
+    let symbols = client.get_listed_symbols().await.unwrap();
+    assert_eq!(symbols[2].symbol, "ZYRX");
+    assert_eq!(
+        symbols[2].name.as_deref(),
+        Some("PT Zyrexindo Mandiri Buana Tbk.")
+    );
+}
+
+#[tokio::test]
+async fn test_index_constituents() {
+    let (_, client) = stubbed(INDEX_CONSTITUENTS);
+    let members = client.get_index_constituents("lq45").await.unwrap();
+
+    assert_eq!(members.len(), 2);
+    assert_eq!(members[0].code, "BBCA");
+    assert_eq!(members[0].weight, Some(dec!(18.42)));
+    assert_eq!(members[1].shares, Some(dec!(151559001604)));
+}
+
+#[tokio::test]
+async fn test_unknown_index_is_not_found() {
+    let (_, client) = stubbed(r#"{"draw":0,"recordsTotal":0,"recordsFiltered":0,"data":[]}"#);
+    let err = client.get_index_constituents("NOPE").await.unwrap_err();
+    assert!(matches!(err, DataSourceError::SymbolNotFound(_)));
+}
+
+#[tokio::test]
+async fn test_trading_calendar() {
+    let (_, client) = stubbed(HOLIDAYS);
+
+    // The previous year's cuti bersama is dropped
+    let holidays = client.get_holidays(2025).await.unwrap();
+    assert_eq!(holidays.len(), 3);
+    assert_eq!(holidays[0].description, "Tahun Baru 2025 Masehi");
+
+    let calendar = client.get_trading_calendar(2025).await.unwrap();
+    assert!(!calendar.is_trading_day(date("2025-03-31")));
+    assert!(!calendar.is_trading_day(date("2025-04-01")));
+    assert!(calendar.is_trading_day(date("2025-04-02")));
+    // Weekends stay closed
+    assert!(!calendar.is_trading_day(date("2025-04-05")));
+}
+
+#[tokio::test]
+async fn test_stock_summary() {
+    let (_, client) = stubbed(STOCK_SUMMARY);
+    let summary = client.get_stock_summary(date("2025-06-30")).await.unwrap();
+
+    assert_eq!(summary.len(), 2);
+    let bbca = &summary[0];
+    assert_eq!(bbca.date, date("2025-06-30"));
+    assert_eq!(bbca.close, dec!(8725));
+    assert_eq!(bbca.foreign_net(), dec!(12185300));
+
+    let bar = bbca.to_price_bar().expect("BBCA traded");
+    assert_eq!(bar.symbol, "BBCA");
+    assert_eq!(bar.open, dec!(8650));
+    assert_eq!(bar.volume, 85_412_300);
+    assert!(bar.validate().is_ok());
+
+    // No trades: zero open and volume, previous close carried over
+    let zyrx = &summary[1];
+    assert!(!zyrx.traded());
+    assert!(zyrx.to_price_bar().is_none());
+}
+
+#[tokio::test]
+async fn test_financial_reports() {
+    let (_, client) = stubbed(FINANCIAL_REPORTS);
+    let reports = client
+        .get_financial_reports(2025, ReportPeriod::Q1)
+        .await
+        .unwrap();
+    assert_eq!(reports.len(), 3);
+
+    let (symbol, filed) = reports[1].to_filed_report().unwrap();
+    assert_eq!(symbol, "TLKM");
+    assert_eq!(filed.period, ReportPeriod::Q1);
+    assert_eq!(filed.fiscal_year, 2025);
+    assert_eq!(filed.filed_on, date("2025-04-29"));
+
+    // Listed without files yet: no publication date
+    assert!(reports[2].to_filed_report().is_none());
+}
+
+#[tokio::test]
+async fn test_rate_limit_is_retried() {
+    let (faults, client) = stubbed(INDEX_CONSTITUENTS);
+    faults.inject(SOURCE_IDX, Fault::RATE_LIMITED, 2);
+
+    assert!(client.get_index_constituents("LQ45").await.is_ok());
+    assert_eq!(faults.requests(SOURCE_IDX), 3);
+}
+
+#[tokio::test]
+async fn test_persistent_rate_limit_gives_up() {
+    let (faults, client) = stubbed(INDEX_CONSTITUENTS);
+    faults.inject(SOURCE_IDX, Fault::RATE_LIMITED, 5);
+
+    let err = client.get_index_constituents("LQ45").await.unwrap_err();
+    assert!(matches!(err, DataSourceError::RateLimited));
+    assert_eq!(faults.requests(SOURCE_IDX), 3);
+}
+
+#[tokio::test]
+async fn test_maintenance_page_is_invalid_response() {
+    let (faults, client) = stubbed(LISTED_COMPANIES);
+    faults.inject(SOURCE_IDX, Fault::MalformedBody, 1);
+
+    let err = client.get_listed_companies().await.unwrap_err();
+    assert!(matches!(err, DataSourceError::InvalidResponse(_)));
+}
@@ -0,0 +1,106 @@
+//! News feed tests against recorded responses
+//! Run with: cargo test -p jejakcuan-data-sources --test news_test
+
+use chrono::{TimeZone, Utc};
+use jejakcuan_data_sources::http::SOURCE_NEWS;
+use jejakcuan_data_sources::{
+    tag_symbols, DataSourceError, Fault, FaultInjector, FeedFormat, NewsClient, NewsFeed,
+};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+const KONTAN_RSS: &str = include_str!("fixtures/news/kontan.xml");
+const IQPLUS_JSON: &str = include_str!("fixtures/news/jsonfeed.json");
+
+const KONTAN: NewsFeed = NewsFeed {
+    id: "kontan",
+    name: "Kontan",
+    url: "https://investasi.kontan.co.id/rss",
+    format: FeedFormat::Rss,
+};
+
+const IQPLUS: NewsFeed = NewsFeed {
+    id: "iqplus",
+    name: "IQPlus",
+    url: "https://www.iqplus.info/feed.json",
+    format: FeedFormat::JsonFeed,
+};
+
+fn stubbed(body: &str) -> (Arc<FaultInjector>, NewsClient) {
+    let faults = Arc::new(FaultInjector::new());
+    faults.stub(SOURCE_NEWS, body);
+    let client = NewsClient::new().with_faults(faults.clone());
+    (faults, client)
+}
+
+#[tokio::test]
+async fn test_rss_feed() {
+    let (_, client) = stubbed(KONTAN_RSS);
+    let articles = client.fetch_feed(&KONTAN).await.unwrap();
+
+    // The item with an unreadable date is skipped
+    assert_eq!(articles.len(), 2);
+    let bbri = &articles[0];
+    assert_eq!(bbri.source, "kontan");
+    assert_eq!(bbri.title, "Laba Bersih BBRI Naik 12% pada Semester I-2025");
+    assert_eq!(
+        bbri.summary.as_deref(),
+        Some("PT Bank Rakyat Indonesia Tbk (BBRI) membukukan kenaikan laba bersih & dividen interim.")
+    );
+    assert_eq!(
+        bbri.published_at,
+        Utc.with_ymd_and_hms(2025, 7, 30, 11, 5, 0).unwrap()
+    );
+    assert!(bbri.text().starts_with("Laba Bersih BBRI Naik 12%"));
+}
+
+#[tokio::test]
+async fn test_json_feed() {
+    let (_, client) = stubbed(IQPLUS_JSON);
+    let articles = client.fetch_feed(&IQPLUS).await.unwrap();
+
+    assert_eq!(articles.len(), 1);
+    assert_eq!(articles[0].title, "ANTM Borong Nikel, Penjualan Tumbuh");
+    assert_eq!(
+        articles[0].summary.as_deref(),
+        Some("Aneka Tambang (ANTM) mencatat pertumbuhan penjualan.")
+    );
+    assert_eq!(
+        articles[0].published_at,
+        Utc.with_ymd_and_hms(2025, 7, 30, 2, 15, 0).unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_maintenance_page_is_invalid_response() {
+    let (faults, client) = stubbed(KONTAN_RSS);
+    faults.inject(SOURCE_NEWS, Fault::MalformedBody, 1);
+
+    let err = client.fetch_feed(&KONTAN).await.unwrap_err();
+    assert!(matches!(err, DataSourceError::InvalidResponse(_)));
+}
+
+#[tokio::test]
+async fn test_rate_limited_feed() {
+    let (faults, client) = stubbed(KONTAN_RSS);
+    faults.inject(SOURCE_NEWS, Fault::RATE_LIMITED, 1);
+
+    let err = client.fetch_feed(&KONTAN).await.unwrap_err();
+    assert!(matches!(err, DataSourceError::RateLimited));
+}
+
+#[test]
+fn test_tag_symbols() {
+    let known: HashSet<String> = ["BBRI", "ANTM", "BUMI", "WSKT"]
+        .into_iter()
+        .map(String::from)
+        .collect();
+
+    assert_eq!(
+        tag_symbols("Saham BBRI dan ANTM menguat, BBRI memimpin", &known),
+        vec!["ANTM", "BBRI"]
+    );
+    // Lower-case words and unknown tickers are ignored
+    assert!(tag_symbols("Harga di bumi naik, XXXX stagnan", &known).is_empty());
+    assert_eq!(tag_symbols("(WSKT.JK) tertekan", &known), vec!["WSKT"]);
+}
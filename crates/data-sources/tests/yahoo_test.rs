@@ -49,3 +49,21 @@ async fn test_get_multiple_quotes() {
         println!("{}: {:?}", quote.symbol, quote.regular_market_price);
     }
 }
+
+#[tokio::test]
+#[ignore]
+async fn test_bulk_quotes_and_history() {
+    let client = YahooFinanceClient::new().with_max_concurrency(4);
+    let symbols = YahooFinanceClient::get_idx_stock_list();
+
+    let quotes = client.get_quotes_bulk(&symbols).await;
+    println!(
+        "Got {} quotes, failed: {:?}",
+        quotes.results.len(),
+        quotes.failed_symbols()
+    );
+    assert!(!quotes.results.is_empty());
+
+    let history = client.get_history_bulk(&symbols[..10], "1d", "1mo").await;
+    assert_eq!(history.results.len() + history.failures.len(), 10);
+}
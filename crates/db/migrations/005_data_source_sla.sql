@@ -0,0 +1,13 @@
+-- Per-data-source refresh tracking for freshness SLA monitoring
+
+CREATE TABLE IF NOT EXISTS data_source_sla (
+    source_id TEXT PRIMARY KEY,
+    last_success_at TIMESTAMPTZ,
+    last_failure_at TIMESTAMPTZ,
+    last_error TEXT,
+    consecutive_failures INTEGER NOT NULL DEFAULT 0,
+    total_runs INTEGER NOT NULL DEFAULT 0,
+    avg_duration_secs DOUBLE PRECISION,
+    last_breach_notified_at TIMESTAMPTZ,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,39 @@
+-- Staging area for scraped data awaiting manual approval
+
+CREATE TABLE IF NOT EXISTS staging_batches (
+    id SERIAL PRIMARY KEY,
+    source_id TEXT NOT NULL,
+    dataset TEXT NOT NULL, -- 'broker_summary', 'shareholdings'
+    status TEXT NOT NULL DEFAULT 'pending', -- 'pending', 'approved', 'rejected'
+    row_count INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    reviewed_at TIMESTAMPTZ,
+    review_note TEXT
+);
+
+CREATE TABLE IF NOT EXISTS staging_broker_summary (
+    batch_id INTEGER NOT NULL REFERENCES staging_batches(id) ON DELETE CASCADE,
+    time TIMESTAMPTZ NOT NULL,
+    symbol VARCHAR(10) NOT NULL,
+    broker_code VARCHAR(4) NOT NULL,
+    buy_volume BIGINT DEFAULT 0,
+    sell_volume BIGINT DEFAULT 0,
+    buy_value NUMERIC(20, 2) DEFAULT 0,
+    sell_value NUMERIC(20, 2) DEFAULT 0
+);
+
+CREATE TABLE IF NOT EXISTS staging_shareholdings (
+    batch_id INTEGER NOT NULL REFERENCES staging_batches(id) ON DELETE CASCADE,
+    symbol VARCHAR(10) NOT NULL,
+    reported_date DATE NOT NULL,
+    shareholder_name VARCHAR(255) NOT NULL,
+    shareholder_type VARCHAR(50),
+    shares_held BIGINT NOT NULL,
+    percentage NUMERIC(10, 4) NOT NULL,
+    change_shares BIGINT DEFAULT 0,
+    change_percentage NUMERIC(10, 4) DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS idx_staging_batches_status ON staging_batches(status, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_staging_broker_summary_batch ON staging_broker_summary(batch_id);
+CREATE INDEX IF NOT EXISTS idx_staging_shareholdings_batch ON staging_shareholdings(batch_id);
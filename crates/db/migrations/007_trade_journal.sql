@@ -0,0 +1,26 @@
+-- Trade journal: actual trades logged by the user, linked to the signal that prompted them
+
+CREATE TABLE IF NOT EXISTS trade_journal (
+    id SERIAL PRIMARY KEY,
+    symbol VARCHAR(10) NOT NULL,
+    entry_time TIMESTAMPTZ NOT NULL,
+    entry_price NUMERIC(18, 4) NOT NULL,
+    quantity BIGINT NOT NULL, -- shares, not lots
+    exit_time TIMESTAMPTZ,
+    exit_price NUMERIC(18, 4),
+    fees NUMERIC(20, 2) NOT NULL DEFAULT 0,
+    rationale TEXT,
+    exit_note TEXT,
+    signal_source TEXT, -- 'alert', 'score', 'screener', 'manual'
+    alert_history_id INT REFERENCES alert_history(id) ON DELETE SET NULL,
+    signal_time TIMESTAMPTZ,
+    signal_price NUMERIC(18, 4),
+    signal_score NUMERIC(5, 2),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT fk_trade_journal_symbol FOREIGN KEY (symbol) REFERENCES stocks(symbol),
+    CONSTRAINT chk_trade_journal_quantity CHECK (quantity > 0)
+);
+
+CREATE INDEX IF NOT EXISTS idx_trade_journal_symbol ON trade_journal(symbol, entry_time DESC);
+CREATE INDEX IF NOT EXISTS idx_trade_journal_open ON trade_journal(entry_time DESC) WHERE exit_time IS NULL;
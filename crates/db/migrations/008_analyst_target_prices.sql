@@ -0,0 +1,19 @@
+-- Analyst target price consensus snapshots (Sectors.app), one per symbol per day
+
+CREATE TABLE IF NOT EXISTS analyst_target_prices (
+    symbol VARCHAR(10) NOT NULL REFERENCES stocks(symbol),
+    as_of DATE NOT NULL,
+    source VARCHAR(50) NOT NULL DEFAULT 'sectors_app',
+    target_mean NUMERIC(18, 4),
+    target_median NUMERIC(18, 4),
+    target_high NUMERIC(18, 4),
+    target_low NUMERIC(18, 4),
+    analyst_count INT,
+    strong_buy INT NOT NULL DEFAULT 0,
+    buy INT NOT NULL DEFAULT 0,
+    hold INT NOT NULL DEFAULT 0,
+    sell INT NOT NULL DEFAULT 0,
+    strong_sell INT NOT NULL DEFAULT 0,
+    fetched_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (symbol, as_of)
+);
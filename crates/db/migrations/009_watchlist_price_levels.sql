@@ -0,0 +1,18 @@
+-- User-drawn horizontal price levels per symbol (support zones, planned entries)
+
+CREATE TABLE IF NOT EXISTS watchlist_levels (
+    id SERIAL PRIMARY KEY,
+    symbol VARCHAR(10) NOT NULL,
+    price NUMERIC(18, 4) NOT NULL,
+    kind VARCHAR(20) NOT NULL DEFAULT 'note', -- 'support', 'resistance', 'entry', 'target', 'stop', 'note'
+    label TEXT,
+    note TEXT,
+    alert_enabled BOOLEAN NOT NULL DEFAULT true,
+    last_triggered_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT fk_watchlist_levels_symbol FOREIGN KEY (symbol) REFERENCES stocks(symbol),
+    CONSTRAINT chk_watchlist_levels_price CHECK (price > 0)
+);
+
+CREATE INDEX IF NOT EXISTS idx_watchlist_levels_symbol ON watchlist_levels(symbol, price);
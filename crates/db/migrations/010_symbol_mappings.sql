@@ -0,0 +1,11 @@
+-- Provider-specific symbol overrides; symbols without a row follow the
+-- default notation rules (BBCA.JK for Yahoo, BBCA:IDX for TwelveData)
+
+CREATE TABLE IF NOT EXISTS symbol_mappings (
+    symbol VARCHAR(10) NOT NULL REFERENCES stocks(symbol),
+    provider VARCHAR(20) NOT NULL,
+    provider_symbol VARCHAR(30) NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (symbol, provider),
+    UNIQUE (provider, provider_symbol)
+);
@@ -0,0 +1,122 @@
+-- Point-in-time financials
+--
+-- `financials` keeps the current figures per period. Every insert and every
+-- change of figures is also recorded in `financials_versions` together with
+-- the date the figures became public, so backtests and as-of analysis only
+-- see what was published at the time.
+
+-- IDX filing deadlines: annual reports within 90 days, interim within 30
+CREATE OR REPLACE FUNCTION financials_filing_deadline(period_end DATE) RETURNS DATE AS $$
+    SELECT period_end + CASE WHEN EXTRACT(MONTH FROM period_end) = 12 THEN 90 ELSE 30 END;
+$$ LANGUAGE SQL IMMUTABLE;
+
+ALTER TABLE financials ADD COLUMN IF NOT EXISTS report_date DATE;
+
+-- Figures were public by the filing deadline, or earlier if ingested before it
+UPDATE financials
+SET report_date = LEAST(created_at::date, financials_filing_deadline(period_end))
+WHERE report_date IS NULL;
+
+ALTER TABLE financials ALTER COLUMN report_date SET NOT NULL;
+
+CREATE TABLE IF NOT EXISTS financials_versions (
+    id SERIAL PRIMARY KEY,
+    symbol VARCHAR(10) NOT NULL REFERENCES stocks(symbol),
+    period_end DATE NOT NULL,
+    version INT NOT NULL,
+    report_date DATE NOT NULL,
+    revenue NUMERIC(20, 2),
+    net_income NUMERIC(20, 2),
+    total_assets NUMERIC(20, 2),
+    total_equity NUMERIC(20, 2),
+    total_debt NUMERIC(20, 2),
+    ebitda NUMERIC(20, 2),
+    free_cash_flow NUMERIC(20, 2),
+    eps NUMERIC(18, 4),
+    book_value_per_share NUMERIC(18, 4),
+    pe_ratio NUMERIC(10, 2),
+    pb_ratio NUMERIC(10, 2),
+    ev_ebitda NUMERIC(10, 2),
+    roe NUMERIC(10, 4),
+    roa NUMERIC(10, 4),
+    recorded_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT uq_financials_versions UNIQUE (symbol, period_end, version)
+);
+
+CREATE INDEX IF NOT EXISTS idx_financials_versions_pit
+    ON financials_versions(symbol, report_date, period_end DESC);
+
+INSERT INTO financials_versions (
+    symbol, period_end, version, report_date, revenue, net_income, total_assets,
+    total_equity, total_debt, ebitda, free_cash_flow, eps, book_value_per_share,
+    pe_ratio, pb_ratio, ev_ebitda, roe, roa, recorded_at
+)
+SELECT
+    symbol, period_end, 1, report_date, revenue, net_income, total_assets,
+    total_equity, total_debt, ebitda, free_cash_flow, eps, book_value_per_share,
+    pe_ratio, pb_ratio, ev_ebitda, roe, roa, COALESCE(created_at, NOW())
+FROM financials
+ON CONFLICT (symbol, period_end, version) DO NOTHING;
+
+-- Writers that don't know the publication date get the conservative default;
+-- restated figures are public no earlier than the day they are recorded
+CREATE OR REPLACE FUNCTION set_financials_report_date() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        NEW.report_date := COALESCE(
+            NEW.report_date,
+            LEAST(CURRENT_DATE, financials_filing_deadline(NEW.period_end))
+        );
+    ELSIF (NEW.revenue, NEW.net_income, NEW.total_assets, NEW.total_equity, NEW.total_debt,
+           NEW.ebitda, NEW.free_cash_flow, NEW.eps, NEW.book_value_per_share, NEW.pe_ratio,
+           NEW.pb_ratio, NEW.ev_ebitda, NEW.roe, NEW.roa)
+          IS DISTINCT FROM
+          (OLD.revenue, OLD.net_income, OLD.total_assets, OLD.total_equity, OLD.total_debt,
+           OLD.ebitda, OLD.free_cash_flow, OLD.eps, OLD.book_value_per_share, OLD.pe_ratio,
+           OLD.pb_ratio, OLD.ev_ebitda, OLD.roe, OLD.roa)
+          AND NEW.report_date IS NOT DISTINCT FROM OLD.report_date THEN
+        NEW.report_date := GREATEST(OLD.report_date, CURRENT_DATE);
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION record_financials_version() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'UPDATE'
+       AND (NEW.revenue, NEW.net_income, NEW.total_assets, NEW.total_equity, NEW.total_debt,
+            NEW.ebitda, NEW.free_cash_flow, NEW.eps, NEW.book_value_per_share, NEW.pe_ratio,
+            NEW.pb_ratio, NEW.ev_ebitda, NEW.roe, NEW.roa, NEW.report_date)
+           IS NOT DISTINCT FROM
+           (OLD.revenue, OLD.net_income, OLD.total_assets, OLD.total_equity, OLD.total_debt,
+            OLD.ebitda, OLD.free_cash_flow, OLD.eps, OLD.book_value_per_share, OLD.pe_ratio,
+            OLD.pb_ratio, OLD.ev_ebitda, OLD.roe, OLD.roa, OLD.report_date) THEN
+        RETURN NULL;
+    END IF;
+
+    INSERT INTO financials_versions (
+        symbol, period_end, version, report_date, revenue, net_income, total_assets,
+        total_equity, total_debt, ebitda, free_cash_flow, eps, book_value_per_share,
+        pe_ratio, pb_ratio, ev_ebitda, roe, roa
+    )
+    SELECT
+        NEW.symbol, NEW.period_end, COALESCE(MAX(version), 0) + 1, NEW.report_date,
+        NEW.revenue, NEW.net_income, NEW.total_assets, NEW.total_equity, NEW.total_debt,
+        NEW.ebitda, NEW.free_cash_flow, NEW.eps, NEW.book_value_per_share, NEW.pe_ratio,
+        NEW.pb_ratio, NEW.ev_ebitda, NEW.roe, NEW.roa
+    FROM financials_versions
+    WHERE symbol = NEW.symbol AND period_end = NEW.period_end;
+
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_financials_report_date ON financials;
+CREATE TRIGGER trg_financials_report_date
+    BEFORE INSERT OR UPDATE ON financials
+    FOR EACH ROW EXECUTE FUNCTION set_financials_report_date();
+
+DROP TRIGGER IF EXISTS trg_financials_version ON financials;
+CREATE TRIGGER trg_financials_version
+    AFTER INSERT OR UPDATE ON financials
+    FOR EACH ROW EXECUTE FUNCTION record_financials_version();
@@ -0,0 +1,17 @@
+-- Quarterly fundamental score history
+--
+-- One score per stock per calendar quarter end, computed only from the
+-- financials that had been published by that date (financials_versions).
+
+CREATE TABLE IF NOT EXISTS fundamental_score_history (
+    symbol VARCHAR(10) NOT NULL REFERENCES stocks(symbol),
+    as_of DATE NOT NULL,
+    -- Financial period and version the score was computed from
+    period_end DATE NOT NULL,
+    version INT NOT NULL,
+    report_date DATE NOT NULL,
+    fundamental_score NUMERIC(5, 2) NOT NULL,
+    fundamental_breakdown JSONB,
+    computed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (symbol, as_of)
+);
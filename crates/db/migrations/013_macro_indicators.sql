@@ -0,0 +1,11 @@
+-- Macro series for the market dashboard and regime detection: BI-Rate,
+-- inflation, USD/IDR, foreign reserves and the IHSG composite index
+
+CREATE TABLE IF NOT EXISTS macro_indicators (
+    indicator VARCHAR(30) NOT NULL,
+    observed_on DATE NOT NULL,
+    value DECIMAL(18, 4) NOT NULL,
+    source VARCHAR(30) NOT NULL,
+    fetched_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (indicator, observed_on)
+);
@@ -0,0 +1,5 @@
+-- Record which source supplied each broker summary row so coverage reports
+-- can show where the data behind accumulation scores came from. Existing rows
+-- and the Python scraper (which does not set the column) default to 'scraper'.
+
+ALTER TABLE broker_summary ADD COLUMN IF NOT EXISTS source VARCHAR(50) NOT NULL DEFAULT 'scraper';
@@ -0,0 +1,18 @@
+-- User-defined alert rules: condition expressions over computed indicators,
+-- e.g. 'rsi < 30 AND composite_score > 70 AND foreign_net_5_day > 0'
+
+CREATE TABLE IF NOT EXISTS alert_rules (
+    id SERIAL PRIMARY KEY,
+    owner VARCHAR(100) NOT NULL, -- username of the creator
+    name VARCHAR(100) NOT NULL,
+    expression TEXT NOT NULL,
+    symbols TEXT[] NOT NULL DEFAULT '{}', -- empty: every watchlisted symbol
+    priority VARCHAR(10) NOT NULL DEFAULT 'medium', -- 'critical', 'high', 'medium', 'low'
+    enabled BOOLEAN NOT NULL DEFAULT true,
+    last_triggered_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_alert_rules_owner ON alert_rules(owner, id);
+CREATE INDEX IF NOT EXISTS idx_alert_rules_enabled ON alert_rules(enabled) WHERE enabled = true;
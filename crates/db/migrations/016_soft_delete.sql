@@ -0,0 +1,8 @@
+-- Soft-delete for watchlist entries and alert rules, so accidental removals
+-- can be restored. Rows are purged by the retention job once the window passes.
+
+ALTER TABLE watchlist ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+ALTER TABLE alert_rules ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_watchlist_deleted ON watchlist(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_alert_rules_deleted ON alert_rules(deleted_at) WHERE deleted_at IS NOT NULL;
@@ -0,0 +1,14 @@
+-- Persist every fired alert so it can be reviewed and acknowledged later.
+-- trigger_value holds the full alert payload.
+
+ALTER TABLE alert_history ADD COLUMN IF NOT EXISTS symbol VARCHAR(10);
+ALTER TABLE alert_history ADD COLUMN IF NOT EXISTS category VARCHAR(20); -- 'broker', 'technical'
+ALTER TABLE alert_history ADD COLUMN IF NOT EXISTS kind VARCHAR(50); -- glossary key, e.g. 'rsi_oversold'
+ALTER TABLE alert_history ADD COLUMN IF NOT EXISTS priority VARCHAR(10); -- 'critical', 'high', 'medium', 'low'
+ALTER TABLE alert_history ADD COLUMN IF NOT EXISTS message TEXT;
+ALTER TABLE alert_history ADD COLUMN IF NOT EXISTS acknowledged BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE alert_history ADD COLUMN IF NOT EXISTS acknowledged_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_alert_history_triggered ON alert_history(triggered_at DESC);
+CREATE INDEX IF NOT EXISTS idx_alert_history_symbol ON alert_history(symbol, triggered_at DESC);
+CREATE INDEX IF NOT EXISTS idx_alert_history_unacknowledged ON alert_history(triggered_at DESC) WHERE acknowledged = false;
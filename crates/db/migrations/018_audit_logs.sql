@@ -0,0 +1,20 @@
+-- Audit trail written by jejakcuan-audit (mirrors AUDIT_TABLE_MIGRATION), also
+-- the source of the configuration change history
+
+CREATE TABLE IF NOT EXISTS audit_logs (
+    id UUID PRIMARY KEY,
+    timestamp TIMESTAMPTZ NOT NULL,
+    category VARCHAR(50) NOT NULL,
+    severity VARCHAR(20) NOT NULL,
+    outcome VARCHAR(20) NOT NULL,
+    actor JSONB NOT NULL,
+    action VARCHAR(100) NOT NULL,
+    resource JSONB NOT NULL,
+    details JSONB NOT NULL DEFAULT '{}',
+    client JSONB NOT NULL DEFAULT '{}'
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_timestamp ON audit_logs (timestamp);
+CREATE INDEX IF NOT EXISTS idx_audit_category ON audit_logs (category);
+CREATE INDEX IF NOT EXISTS idx_audit_actor_user ON audit_logs ((actor->>'user_id'));
+CREATE INDEX IF NOT EXISTS idx_audit_resource ON audit_logs ((resource->>'resource_type'), (resource->>'resource_id'), timestamp DESC);
@@ -0,0 +1,19 @@
+-- Notifications whose delivery failed, retried with exponential backoff.
+-- Rows are deleted once delivered; after the last attempt (or on a
+-- permanent error) they stay as dead letters until retried by hand.
+
+CREATE TABLE IF NOT EXISTS notification_retries (
+    id SERIAL PRIMARY KEY,
+    channel VARCHAR(20) NOT NULL,
+    recipient_id TEXT NOT NULL,
+    payload JSONB NOT NULL, -- the full notification
+    attempts INTEGER NOT NULL DEFAULT 1,
+    last_error TEXT NOT NULL,
+    status VARCHAR(10) NOT NULL DEFAULT 'pending', -- 'pending', 'dead'
+    next_attempt_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_notification_retries_due ON notification_retries(next_attempt_at) WHERE status = 'pending';
+CREATE INDEX IF NOT EXISTS idx_notification_retries_dead ON notification_retries(updated_at DESC) WHERE status = 'dead';
@@ -0,0 +1,20 @@
+-- Per-user, per-symbol alert notification preferences. A symbol with a
+-- subscription is routed by it instead of the default operator channels.
+
+CREATE TABLE IF NOT EXISTS alert_subscriptions (
+    id SERIAL PRIMARY KEY,
+    user_id VARCHAR(100) NOT NULL,
+    symbol VARCHAR(10) NOT NULL,
+    alert_types JSONB NOT NULL DEFAULT '{}', -- AlertTypeFilter
+    min_priority VARCHAR(10) NOT NULL DEFAULT 'medium', -- 'critical', 'high', 'medium', 'low'
+    channels JSONB NOT NULL DEFAULT '[]', -- e.g. ["Telegram", "InApp"]
+    -- Quiet hours in WIB; only alerts at least quiet_min_priority go out
+    quiet_start TIME,
+    quiet_end TIME,
+    quiet_min_priority VARCHAR(10),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, symbol)
+);
+
+CREATE INDEX IF NOT EXISTS idx_alert_subscriptions_user ON alert_subscriptions(user_id);
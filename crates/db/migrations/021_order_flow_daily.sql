@@ -0,0 +1,13 @@
+-- Daily order flow proxies per symbol, derived from OHLCV bars when scores
+-- are computed. OBI is the close location in the day's range (-1 to +1),
+-- OFI the signed share imbalance and ofi_zscore its rolling z-score.
+
+CREATE TABLE IF NOT EXISTS order_flow_daily (
+    symbol VARCHAR(10) NOT NULL REFERENCES stocks(symbol),
+    trade_date DATE NOT NULL,
+    obi DECIMAL(8, 4) NOT NULL,
+    ofi DECIMAL(20, 2) NOT NULL,
+    ofi_zscore DECIMAL(10, 4), -- NULL during the z-score warm-up
+    computed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (symbol, trade_date)
+);
@@ -0,0 +1,32 @@
+-- Portfolio: the user's buy/sell transactions and the holdings replayed
+-- from them. Holdings are rewritten from the transactions after every change.
+
+CREATE TABLE IF NOT EXISTS portfolio_transactions (
+    id SERIAL PRIMARY KEY,
+    user_id VARCHAR(100) NOT NULL,
+    symbol VARCHAR(10) NOT NULL REFERENCES stocks(symbol),
+    side VARCHAR(4) NOT NULL, -- 'buy', 'sell'
+    lots BIGINT NOT NULL, -- 1 lot = 100 shares
+    price NUMERIC(18, 4) NOT NULL,
+    fees NUMERIC(20, 2) NOT NULL DEFAULT 0,
+    executed_at TIMESTAMPTZ NOT NULL,
+    note TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_portfolio_transactions_side CHECK (side IN ('buy', 'sell')),
+    CONSTRAINT chk_portfolio_transactions_lots CHECK (lots > 0),
+    CONSTRAINT chk_portfolio_transactions_price CHECK (price > 0)
+);
+
+CREATE INDEX IF NOT EXISTS idx_portfolio_transactions_user ON portfolio_transactions(user_id, executed_at);
+
+CREATE TABLE IF NOT EXISTS portfolio_holdings (
+    user_id VARCHAR(100) NOT NULL,
+    symbol VARCHAR(10) NOT NULL REFERENCES stocks(symbol),
+    shares BIGINT NOT NULL,
+    average_cost NUMERIC(18, 4) NOT NULL,
+    cost_basis NUMERIC(20, 2) NOT NULL,
+    realized_pnl NUMERIC(20, 2) NOT NULL DEFAULT 0,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, symbol)
+);
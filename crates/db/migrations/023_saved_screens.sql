@@ -0,0 +1,18 @@
+-- Named screener filters, re-run daily after the data refresh; a change in
+-- the result set is alerted
+
+CREATE TABLE IF NOT EXISTS saved_screens (
+    id SERIAL PRIMARY KEY,
+    owner VARCHAR(100) NOT NULL, -- username of the creator
+    name VARCHAR(100) NOT NULL,
+    filter JSONB NOT NULL, -- screener filter tree
+    alerts_enabled BOOLEAN NOT NULL DEFAULT true,
+    last_symbols TEXT[] NOT NULL DEFAULT '{}', -- result set of the last run
+    last_run_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT uq_saved_screens_owner_name UNIQUE (owner, name)
+);
+
+CREATE INDEX IF NOT EXISTS idx_saved_screens_alerts ON saved_screens(alerts_enabled)
+    WHERE alerts_enabled = true;
@@ -0,0 +1,13 @@
+-- Data-source credentials and notifier tokens set through the admin API,
+-- encrypted at rest with AES-256-GCM; never stored in plaintext
+
+CREATE TABLE IF NOT EXISTS stored_secrets (
+    name VARCHAR(100) PRIMARY KEY, -- environment variable the secret stands in for
+    key_id VARCHAR(32) NOT NULL, -- master key that sealed it
+    nonce BYTEA NOT NULL,
+    ciphertext BYTEA NOT NULL, -- includes the GCM tag
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_stored_secrets_key_id ON stored_secrets(key_id);
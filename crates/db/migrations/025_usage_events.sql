@@ -0,0 +1,15 @@
+-- Product usage events: symbols viewed, screens run, alerts clicked. The
+-- actor is the username, a salted hash of it or NULL, depending on the
+-- anonymization setting at the time the event was recorded.
+
+CREATE TABLE IF NOT EXISTS usage_events (
+    id BIGSERIAL PRIMARY KEY,
+    kind VARCHAR(30) NOT NULL, -- symbol_viewed, screen_run, alert_clicked
+    subject VARCHAR(200) NOT NULL, -- symbol, screen filter types or alert kind
+    symbol VARCHAR(10),
+    actor VARCHAR(100),
+    occurred_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_usage_events_kind_time ON usage_events(kind, occurred_at DESC);
+CREATE INDEX IF NOT EXISTS idx_usage_events_time ON usage_events(occurred_at);
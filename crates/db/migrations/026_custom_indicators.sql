@@ -0,0 +1,13 @@
+-- User-defined indicators: arithmetic expressions over built-in indicators,
+-- usable in screens and alert rules by name
+
+CREATE TABLE IF NOT EXISTS custom_indicators (
+    id SERIAL PRIMARY KEY,
+    owner VARCHAR(100) NOT NULL, -- username of the creator
+    name VARCHAR(50) NOT NULL, -- identifier, e.g. ema_spread
+    expression VARCHAR(200) NOT NULL, -- canonical form, e.g. (ema20 - ema50) / atr14
+    description TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT uq_custom_indicators_owner_name UNIQUE (owner, name)
+);
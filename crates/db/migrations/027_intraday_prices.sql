@@ -0,0 +1,20 @@
+-- Intraday OHLCV bars aggregated from the streaming price feed. A bar is
+-- written once its interval closes; a restart mid-interval may write it
+-- again, so rows are keyed by symbol, interval and start time.
+
+CREATE TABLE IF NOT EXISTS intraday_prices (
+    time TIMESTAMPTZ NOT NULL, -- start of the interval
+    symbol VARCHAR(10) NOT NULL,
+    interval VARCHAR(10) NOT NULL, -- 1min, 5min, 15min
+    open NUMERIC(18, 4) NOT NULL,
+    high NUMERIC(18, 4) NOT NULL,
+    low NUMERIC(18, 4) NOT NULL,
+    close NUMERIC(18, 4) NOT NULL,
+    volume BIGINT NOT NULL,
+    ticks INTEGER NOT NULL,
+    CONSTRAINT fk_intraday_prices_symbol FOREIGN KEY (symbol) REFERENCES stocks(symbol)
+);
+
+SELECT create_hypertable('intraday_prices', 'time', if_not_exists => TRUE);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_intraday_prices_symbol_interval_time
+    ON intraday_prices(symbol, interval, time DESC);
@@ -0,0 +1,11 @@
+-- Latest price-derived indicators per symbol, recomputed for the whole
+-- universe after the close so bulk reads do not recompute them per symbol.
+
+CREATE TABLE IF NOT EXISTS indicator_snapshots (
+    symbol VARCHAR(10) PRIMARY KEY REFERENCES stocks(symbol),
+    close NUMERIC(18, 4),
+    rsi NUMERIC(8, 4),
+    rvol NUMERIC(12, 4),
+    wyckoff_phase VARCHAR(30),
+    computed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,22 @@
+-- Order book depth snapshots posted by depth feeds. Ladders are stored best
+-- level first as JSON arrays of {price, volume}, with the OBI over the top
+-- levels and the OFI since the symbol's previous snapshot.
+
+CREATE TABLE IF NOT EXISTS order_book_snapshots (
+    time TIMESTAMPTZ NOT NULL,
+    symbol VARCHAR(10) NOT NULL,
+    bids JSONB NOT NULL,
+    asks JSONB NOT NULL,
+    obi DECIMAL(8, 4) NOT NULL,
+    ofi DECIMAL(20, 2) NOT NULL,
+    CONSTRAINT fk_order_book_snapshots_symbol FOREIGN KEY (symbol) REFERENCES stocks(symbol)
+);
+
+SELECT create_hypertable('order_book_snapshots', 'time', if_not_exists => TRUE);
+CREATE INDEX IF NOT EXISTS idx_order_book_snapshots_symbol_time
+    ON order_book_snapshots(symbol, time DESC);
+
+-- Where a day's OBI/OFI came from: 'proxy' from the OHLCV bar, 'depth' from
+-- order book snapshots. Depth days are not overwritten by proxies.
+ALTER TABLE order_flow_daily
+    ADD COLUMN IF NOT EXISTS source VARCHAR(10) NOT NULL DEFAULT 'proxy';
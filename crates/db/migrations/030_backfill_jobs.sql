@@ -0,0 +1,34 @@
+-- Resumable historical backfills. A job covers a date range for a set of
+-- symbols and intervals; each (symbol, interval) pair tracks its own page
+-- cursor, so an interrupted job resumes where it stopped.
+
+CREATE TABLE IF NOT EXISTS backfill_jobs (
+    id BIGSERIAL PRIMARY KEY,
+    source VARCHAR(20) NOT NULL, -- twelvedata
+    intervals TEXT[] NOT NULL,
+    start_date DATE NOT NULL,
+    end_date DATE NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending', -- pending, running, completed, failed
+    bars_written BIGINT NOT NULL DEFAULT 0,
+    last_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ
+);
+
+CREATE TABLE IF NOT EXISTS backfill_progress (
+    job_id BIGINT NOT NULL REFERENCES backfill_jobs(id) ON DELETE CASCADE,
+    symbol VARCHAR(10) NOT NULL,
+    interval VARCHAR(10) NOT NULL,
+    -- End date of the next page; pages walk back from the job's end date
+    cursor_date DATE,
+    pages INTEGER NOT NULL DEFAULT 0,
+    bars_written INTEGER NOT NULL DEFAULT 0,
+    done BOOLEAN NOT NULL DEFAULT FALSE,
+    error TEXT,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (job_id, symbol, interval)
+);
+
+CREATE INDEX IF NOT EXISTS idx_backfill_progress_pending
+    ON backfill_progress(job_id) WHERE NOT done;
@@ -0,0 +1,12 @@
+-- Lexicon sentiment of each stored headline (-1 to 1), and one row per
+-- article and stock so re-polled feeds do not double count.
+
+ALTER TABLE stock_news ADD COLUMN IF NOT EXISTS sentiment_score NUMERIC(5, 4);
+
+DELETE FROM stock_news a
+USING stock_news b
+WHERE a.symbol = b.symbol AND a.url = b.url AND a.id > b.id;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_stock_news_symbol_url ON stock_news(symbol, url);
+CREATE INDEX IF NOT EXISTS idx_stock_news_symbol_published
+    ON stock_news(symbol, published_at DESC);
@@ -0,0 +1,22 @@
+-- Saved backtest runs. Each run keeps its full report so parameter
+-- variations can be compared later; a share token opens one run read-only
+-- to anyone holding the link.
+
+CREATE TABLE IF NOT EXISTS backtest_runs (
+    id SERIAL PRIMARY KEY,
+    owner VARCHAR(100) NOT NULL, -- username of the creator
+    name VARCHAR(100),
+    symbol VARCHAR(10) NOT NULL,
+    strategy JSONB NOT NULL, -- entry/exit rules and their parameters
+    config JSONB NOT NULL, -- capital and stop loss
+    costs JSONB NOT NULL, -- transaction-cost model the run was charged
+    start_date DATE NOT NULL,
+    end_date DATE NOT NULL,
+    metrics JSONB NOT NULL,
+    trades JSONB NOT NULL,
+    equity_curve JSONB NOT NULL,
+    share_token VARCHAR(64) UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_backtest_runs_owner ON backtest_runs(owner, created_at DESC);
@@ -0,0 +1,18 @@
+-- Terms needed to adjust price history: split/bonus/rights ratio (new
+-- shares per old share) and rights exercise price, plus where each row
+-- came from. One row per stock, action type and ex-date so repeated
+-- ingestion updates rather than duplicates.
+
+ALTER TABLE corporate_actions ADD COLUMN IF NOT EXISTS ratio NUMERIC(20, 8);
+ALTER TABLE corporate_actions ADD COLUMN IF NOT EXISTS price NUMERIC(20, 4);
+ALTER TABLE corporate_actions ADD COLUMN IF NOT EXISTS source TEXT;
+
+DELETE FROM corporate_actions a
+USING corporate_actions b
+WHERE a.symbol = b.symbol
+  AND a.action_type = b.action_type
+  AND a.ex_date = b.ex_date
+  AND a.id > b.id;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_corporate_actions_symbol_type_ex
+    ON corporate_actions(symbol, action_type, ex_date);
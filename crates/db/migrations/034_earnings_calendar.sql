@@ -0,0 +1,21 @@
+-- Financial report calendar: one row per stock and reporting period, either
+-- published (with its IDX upload date) or expected (estimated from last
+-- year's filing or the regulatory deadline). alerted_at records the
+-- upcoming-report alert so it is sent once.
+
+CREATE TABLE IF NOT EXISTS earnings_calendar (
+    id SERIAL PRIMARY KEY,
+    symbol TEXT NOT NULL REFERENCES stocks(symbol) ON DELETE CASCADE,
+    fiscal_year INTEGER NOT NULL,
+    period TEXT NOT NULL,
+    expected_date DATE NOT NULL,
+    deadline DATE NOT NULL,
+    basis TEXT NOT NULL,
+    published_on DATE,
+    alerted_at TIMESTAMPTZ,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (symbol, fiscal_year, period)
+);
+
+CREATE INDEX IF NOT EXISTS idx_earnings_calendar_expected
+    ON earnings_calendar(expected_date);
@@ -0,0 +1,40 @@
+-- Strategy parameter grid searches. A job backtests every combination of
+-- its grid on the in-sample bars before split_date and validates it on the
+-- bars from split_date on; results are stored ranked by the in-sample
+-- objective.
+
+CREATE TABLE IF NOT EXISTS backtest_optimizations (
+    id SERIAL PRIMARY KEY,
+    owner VARCHAR(100) NOT NULL, -- username of the creator
+    name VARCHAR(100),
+    symbol VARCHAR(10) NOT NULL,
+    grid JSONB NOT NULL, -- parameter values searched
+    base_config JSONB NOT NULL, -- capital and risk settings not searched
+    costs JSONB NOT NULL,
+    objective VARCHAR(20) NOT NULL, -- sharpe_ratio, total_return, profit_factor
+    start_date DATE NOT NULL,
+    end_date DATE NOT NULL,
+    split_date DATE, -- first out-of-sample session, set once bars are loaded
+    status VARCHAR(20) NOT NULL DEFAULT 'pending', -- pending, running, completed, failed
+    combinations_total INTEGER NOT NULL DEFAULT 0,
+    combinations_done INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_backtest_optimizations_owner
+    ON backtest_optimizations(owner, created_at DESC);
+
+CREATE TABLE IF NOT EXISTS backtest_optimization_results (
+    optimization_id INTEGER NOT NULL REFERENCES backtest_optimizations(id) ON DELETE CASCADE,
+    rank INTEGER NOT NULL, -- 1 is the best in-sample objective
+    strategy JSONB NOT NULL,
+    config JSONB NOT NULL,
+    in_sample JSONB NOT NULL, -- metrics
+    out_of_sample JSONB, -- metrics; NULL when too few bars remain
+    in_sample_score DOUBLE PRECISION,
+    out_of_sample_score DOUBLE PRECISION,
+    PRIMARY KEY (optimization_id, rank)
+);
@@ -0,0 +1,5 @@
+-- Fingerprint of the inputs a score snapshot was computed from: the latest
+-- price, broker summary, financials and news timestamps of its symbol.
+-- A recompute skips symbols whose fingerprint has not changed since.
+
+ALTER TABLE stock_scores ADD COLUMN IF NOT EXISTS input_hash VARCHAR(16);
@@ -0,0 +1,5 @@
+-- Monte Carlo resampling of a saved run's trades: distributions of final
+-- equity and drawdown and the probability of ruin. NULL for runs without
+-- trades and runs saved before the simulation existed.
+
+ALTER TABLE backtest_runs ADD COLUMN IF NOT EXISTS monte_carlo JSONB;
@@ -0,0 +1,21 @@
+-- Announce every change to a symbol's daily prices on the
+-- `stock_prices_changed` channel, with the symbol as payload. The API keeps
+-- hot symbols' prices in memory and drops a symbol when it is announced, so
+-- writes from any process (the scrapers included) are seen at once.
+-- Postgres delivers a payload once per transaction however many rows changed.
+
+CREATE OR REPLACE FUNCTION notify_stock_prices_changed() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        PERFORM pg_notify('stock_prices_changed', OLD.symbol);
+    ELSE
+        PERFORM pg_notify('stock_prices_changed', NEW.symbol);
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_stock_prices_notify ON stock_prices;
+CREATE TRIGGER trg_stock_prices_notify
+    AFTER INSERT OR UPDATE OR DELETE ON stock_prices
+    FOR EACH ROW EXECUTE FUNCTION notify_stock_prices_changed();
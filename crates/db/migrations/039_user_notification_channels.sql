@@ -0,0 +1,12 @@
+-- Where each user receives alerts on the external channels. Alerts that
+-- belong to a user (their rules, screens and symbol preferences) go to these
+-- addresses; the in-app feed needs none.
+
+CREATE TABLE IF NOT EXISTS user_notification_channels (
+    user_id VARCHAR(100) NOT NULL,
+    channel VARCHAR(20) NOT NULL, -- e.g. 'Telegram', 'Webhook'
+    recipient TEXT NOT NULL, -- chat id, webhook URL or phone number
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, channel)
+);
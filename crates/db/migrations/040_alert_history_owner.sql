@@ -0,0 +1,6 @@
+-- Whose alert a fired alert is: the owner of the rule that fired it. Alerts
+-- on the shared watchlist have no owner and are visible to every user.
+
+ALTER TABLE alert_history ADD COLUMN IF NOT EXISTS owner VARCHAR(100);
+
+CREATE INDEX IF NOT EXISTS idx_alert_history_owner ON alert_history(owner, triggered_at DESC);
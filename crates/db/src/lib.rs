@@ -3,7 +3,9 @@
 pub mod models;
 pub mod pool;
 pub mod repositories;
+pub mod secrets;
 
 pub use models::*;
 pub use pool::*;
 pub use repositories::*;
+pub use secrets::*;
@@ -1,6 +1,6 @@
 //! Database models (row types)
 
-use chrono::{DateTime, NaiveDate, Utc};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
 use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
@@ -99,9 +99,36 @@ pub struct FinancialsRow {
     pub ev_ebitda: Option<Decimal>,
     pub roe: Option<Decimal>,
     pub roa: Option<Decimal>,
+    /// Date the figures became public
+    pub report_date: NaiveDate,
     pub created_at: DateTime<Utc>,
 }
 
+/// Financials as published on `report_date`; restatements add a new version
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct FinancialsVersionRow {
+    pub id: i32,
+    pub symbol: String,
+    pub period_end: NaiveDate,
+    pub version: i32,
+    pub report_date: NaiveDate,
+    pub revenue: Option<Decimal>,
+    pub net_income: Option<Decimal>,
+    pub total_assets: Option<Decimal>,
+    pub total_equity: Option<Decimal>,
+    pub total_debt: Option<Decimal>,
+    pub ebitda: Option<Decimal>,
+    pub free_cash_flow: Option<Decimal>,
+    pub eps: Option<Decimal>,
+    pub book_value_per_share: Option<Decimal>,
+    pub pe_ratio: Option<Decimal>,
+    pub pb_ratio: Option<Decimal>,
+    pub ev_ebitda: Option<Decimal>,
+    pub roe: Option<Decimal>,
+    pub roa: Option<Decimal>,
+    pub recorded_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
 pub struct StockScoreRow {
     pub time: DateTime<Utc>,
@@ -120,6 +147,90 @@ pub struct StockScoreRow {
     pub fundamental_breakdown: Option<serde_json::Value>,
     pub sentiment_breakdown: Option<serde_json::Value>,
     pub ml_breakdown: Option<serde_json::Value>,
+    /// Fingerprint of the inputs the snapshot was computed from
+    #[serde(default)]
+    pub input_hash: Option<String>,
+}
+
+/// Latest timestamps of the data a symbol's score is computed from
+#[derive(Debug, Clone, PartialEq, FromRow, Serialize, Deserialize)]
+pub struct ScoreInputsRow {
+    pub symbol: String,
+    pub price_time: Option<DateTime<Utc>>,
+    pub broker_time: Option<DateTime<Utc>>,
+    /// Latest financials version or statement update
+    pub financials_time: Option<DateTime<Utc>>,
+    pub news_time: Option<DateTime<Utc>>,
+    /// Publication date of the financials scored, for their staleness
+    pub financials_report_date: Option<NaiveDate>,
+    /// Checksum of the headlines in the sentiment window and their scores
+    pub news_digest: Option<String>,
+    /// Oldest broker summary in the scoring window
+    pub broker_window_start: Option<DateTime<Utc>>,
+    /// Sector and subsector, which pick the fundamental scoring engine
+    pub sector: Option<String>,
+    pub subsector: Option<String>,
+}
+
+/// Income statement figures used for derived valuation metrics
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct IncomeStatementRow {
+    pub symbol: String,
+    pub fiscal_year: i32,
+    /// None for annual reports
+    pub fiscal_quarter: Option<i32>,
+    pub period_end: NaiveDate,
+    pub revenue: Option<i64>,
+    pub operating_income: Option<i64>,
+    pub depreciation_amortization: Option<i64>,
+    pub interest_expense: Option<i64>,
+    pub earnings_before_tax: Option<i64>,
+    pub tax_expense: Option<i64>,
+    pub net_income: Option<i64>,
+    pub shares_outstanding: Option<i64>,
+}
+
+/// Balance sheet figures used for derived valuation metrics
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct BalanceSheetRow {
+    pub symbol: String,
+    pub fiscal_year: i32,
+    pub fiscal_quarter: Option<i32>,
+    pub period_end: NaiveDate,
+    pub cash_and_equivalents: Option<i64>,
+    pub short_term_investments: Option<i64>,
+    pub total_assets: Option<i64>,
+    pub total_debt: Option<i64>,
+    pub total_equity: Option<i64>,
+}
+
+/// Cash flow figures used for derived valuation metrics
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct CashFlowRow {
+    pub symbol: String,
+    pub fiscal_year: i32,
+    pub fiscal_quarter: Option<i32>,
+    pub period_end: NaiveDate,
+    pub net_income: Option<i64>,
+    pub depreciation_amortization: Option<i64>,
+    pub change_in_working_capital: Option<i64>,
+    pub operating_cash_flow: Option<i64>,
+    pub capital_expenditure: Option<i64>,
+    pub free_cash_flow: Option<i64>,
+}
+
+/// Fundamental score as it stood at a past quarter end
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct FundamentalScoreHistoryRow {
+    pub symbol: String,
+    pub as_of: NaiveDate,
+    pub period_end: NaiveDate,
+    pub version: i32,
+    pub report_date: NaiveDate,
+    #[serde(serialize_with = "serialize_decimal_as_f64")]
+    pub fundamental_score: Decimal,
+    pub fundamental_breakdown: Option<serde_json::Value>,
+    pub computed_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
@@ -129,6 +240,95 @@ pub struct WatchlistRow {
     pub sort_order: i32,
     pub notes: Option<String>,
     pub added_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct WatchlistLevelRow {
+    pub id: i32,
+    pub symbol: String,
+    #[serde(serialize_with = "serialize_decimal_as_f64")]
+    pub price: Decimal,
+    pub kind: String,
+    pub label: Option<String>,
+    pub note: Option<String>,
+    pub alert_enabled: bool,
+    pub last_triggered_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct AlertRuleRow {
+    pub id: i32,
+    pub owner: String,
+    pub name: String,
+    pub expression: String,
+    pub symbols: Vec<String>,
+    pub priority: String,
+    pub enabled: bool,
+    pub last_triggered_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+/// A fired alert, kept for review
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct AlertHistoryRow {
+    pub id: i32,
+    pub alert_id: Option<i32>,
+    pub triggered_at: Option<DateTime<Utc>>,
+    pub trigger_value: Option<serde_json::Value>,
+    pub notification_sent: Option<bool>,
+    pub symbol: Option<String>,
+    pub category: Option<String>,
+    pub kind: Option<String>,
+    pub priority: Option<String>,
+    pub message: Option<String>,
+    pub acknowledged: bool,
+    pub acknowledged_at: Option<DateTime<Utc>>,
+    /// Owner of the rule that fired it; `None` for shared alerts
+    pub owner: Option<String>,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct AlertSubscriptionRow {
+    pub id: i32,
+    pub user_id: String,
+    pub symbol: String,
+    pub alert_types: serde_json::Value,
+    pub min_priority: String,
+    pub channels: serde_json::Value,
+    pub quiet_start: Option<NaiveTime>,
+    pub quiet_end: Option<NaiveTime>,
+    pub quiet_min_priority: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Where a user receives alerts on one external channel
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct UserNotificationChannelRow {
+    pub user_id: String,
+    pub channel: String,
+    pub recipient: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct NotificationRetryRow {
+    pub id: i32,
+    pub channel: String,
+    pub recipient_id: String,
+    pub payload: serde_json::Value,
+    pub attempts: i32,
+    pub last_error: String,
+    pub status: String,
+    pub next_attempt_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
@@ -139,3 +339,428 @@ pub struct SettingsRow {
     pub preferences: serde_json::Value,
     pub updated_at: DateTime<Utc>,
 }
+
+/// Planner and vacuum statistics of a table, from pg_stat_user_tables
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct TableHealthRow {
+    pub schema_name: String,
+    pub table_name: String,
+    pub live_tuples: i64,
+    pub dead_tuples: i64,
+    pub modified_since_analyze: i64,
+    pub last_vacuum: Option<DateTime<Utc>>,
+    pub last_analyze: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DataSourceSlaRow {
+    pub source_id: String,
+    pub last_success_at: Option<DateTime<Utc>>,
+    pub last_failure_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub consecutive_failures: i32,
+    pub total_runs: i32,
+    pub avg_duration_secs: Option<f64>,
+    pub last_breach_notified_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct StagingBatchRow {
+    pub id: i32,
+    pub source_id: String,
+    pub dataset: String,
+    pub status: String,
+    pub row_count: i32,
+    pub created_at: DateTime<Utc>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub review_note: Option<String>,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct TradeJournalRow {
+    pub id: i32,
+    pub symbol: String,
+    pub entry_time: DateTime<Utc>,
+    #[serde(serialize_with = "serialize_decimal_as_f64")]
+    pub entry_price: Decimal,
+    pub quantity: i64,
+    pub exit_time: Option<DateTime<Utc>>,
+    #[serde(serialize_with = "serialize_option_decimal_as_f64")]
+    pub exit_price: Option<Decimal>,
+    #[serde(serialize_with = "serialize_decimal_as_f64")]
+    pub fees: Decimal,
+    pub rationale: Option<String>,
+    pub exit_note: Option<String>,
+    pub signal_source: Option<String>,
+    pub alert_history_id: Option<i32>,
+    pub signal_time: Option<DateTime<Utc>>,
+    #[serde(serialize_with = "serialize_option_decimal_as_f64")]
+    pub signal_price: Option<Decimal>,
+    #[serde(serialize_with = "serialize_option_decimal_as_f64")]
+    pub signal_score: Option<Decimal>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct MacroIndicatorRow {
+    pub indicator: String,
+    pub observed_on: NaiveDate,
+    #[serde(serialize_with = "serialize_decimal_as_f64")]
+    pub value: Decimal,
+    pub source: String,
+    pub fetched_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct OrderFlowDailyRow {
+    pub symbol: String,
+    pub trade_date: NaiveDate,
+    #[serde(serialize_with = "serialize_decimal_as_f64")]
+    pub obi: Decimal,
+    #[serde(serialize_with = "serialize_decimal_as_f64")]
+    pub ofi: Decimal,
+    #[serde(serialize_with = "serialize_option_decimal_as_f64")]
+    pub ofi_zscore: Option<Decimal>,
+    pub computed_at: DateTime<Utc>,
+    /// `proxy` (from the daily bar) or `depth` (from order book snapshots)
+    pub source: String,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct PortfolioTransactionRow {
+    pub id: i32,
+    pub user_id: String,
+    pub symbol: String,
+    pub side: String,
+    pub lots: i64,
+    #[serde(serialize_with = "serialize_decimal_as_f64")]
+    pub price: Decimal,
+    #[serde(serialize_with = "serialize_decimal_as_f64")]
+    pub fees: Decimal,
+    pub executed_at: DateTime<Utc>,
+    pub note: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct PortfolioHoldingRow {
+    pub user_id: String,
+    pub symbol: String,
+    pub shares: i64,
+    #[serde(serialize_with = "serialize_decimal_as_f64")]
+    pub average_cost: Decimal,
+    #[serde(serialize_with = "serialize_decimal_as_f64")]
+    pub cost_basis: Decimal,
+    #[serde(serialize_with = "serialize_decimal_as_f64")]
+    pub realized_pnl: Decimal,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct SymbolMappingRow {
+    pub symbol: String,
+    pub provider: String,
+    pub provider_symbol: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct AnalystTargetPriceRow {
+    pub symbol: String,
+    pub as_of: NaiveDate,
+    pub source: String,
+    #[serde(serialize_with = "serialize_option_decimal_as_f64")]
+    pub target_mean: Option<Decimal>,
+    #[serde(serialize_with = "serialize_option_decimal_as_f64")]
+    pub target_median: Option<Decimal>,
+    #[serde(serialize_with = "serialize_option_decimal_as_f64")]
+    pub target_high: Option<Decimal>,
+    #[serde(serialize_with = "serialize_option_decimal_as_f64")]
+    pub target_low: Option<Decimal>,
+    pub analyst_count: Option<i32>,
+    pub strong_buy: i32,
+    pub buy: i32,
+    pub hold: i32,
+    pub sell: i32,
+    pub strong_sell: i32,
+    pub fetched_at: DateTime<Utc>,
+}
+
+/// Corporate action such as a dividend or rights issue
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct CorporateActionRow {
+    pub id: i32,
+    pub symbol: String,
+    /// e.g. 'dividend', 'rights_issue', 'stock_split'
+    pub action_type: String,
+    pub announced_date: NaiveDate,
+    pub effective_date: Option<NaiveDate>,
+    pub ex_date: Option<NaiveDate>,
+    pub description: String,
+    #[serde(serialize_with = "serialize_option_decimal_as_f64")]
+    pub value: Option<Decimal>,
+    pub status: Option<String>,
+    pub source_url: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    /// New shares per old share of splits, bonus issues and rights
+    #[serde(serialize_with = "serialize_option_decimal_as_f64")]
+    pub ratio: Option<Decimal>,
+    /// Rights exercise price
+    #[serde(serialize_with = "serialize_option_decimal_as_f64")]
+    pub price: Option<Decimal>,
+    /// 'idx', 'ksei', or NULL for rows from the Python scraper
+    pub source: Option<String>,
+}
+
+/// Named screener filter and the result set of its last run
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct SavedScreenRow {
+    pub id: i32,
+    pub owner: String,
+    pub name: String,
+    pub filter: serde_json::Value,
+    pub alerts_enabled: bool,
+    pub last_symbols: Vec<String>,
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Encrypted credential; the sealed bytes are never serialized
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct StoredSecretRow {
+    pub name: String,
+    pub key_id: String,
+    #[serde(skip)]
+    pub nonce: Vec<u8>,
+    #[serde(skip)]
+    pub ciphertext: Vec<u8>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Events and distinct actors for one subject of a usage event kind
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct UsageSubjectCountRow {
+    pub kind: String,
+    pub subject: String,
+    pub events: i64,
+    /// Distinct known actors; anonymous events are not counted
+    pub actors: i64,
+}
+
+/// Usage events of one kind on one day
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct UsageDailyCountRow {
+    pub day: NaiveDate,
+    pub kind: String,
+    pub events: i64,
+}
+
+/// Named indicator expression of one user
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct CustomIndicatorRow {
+    pub id: i32,
+    pub owner: String,
+    pub name: String,
+    pub expression: String,
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Intraday OHLCV bar built from the streaming feed
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct IntradayPriceRow {
+    /// Start of the interval
+    pub time: DateTime<Utc>,
+    pub symbol: String,
+    pub interval: String,
+    #[serde(serialize_with = "serialize_decimal_as_f64")]
+    pub open: Decimal,
+    #[serde(serialize_with = "serialize_decimal_as_f64")]
+    pub high: Decimal,
+    #[serde(serialize_with = "serialize_decimal_as_f64")]
+    pub low: Decimal,
+    #[serde(serialize_with = "serialize_decimal_as_f64")]
+    pub close: Decimal,
+    pub volume: i64,
+    pub ticks: i32,
+}
+
+/// Latest materialized price indicators of a symbol
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct IndicatorSnapshotRow {
+    pub symbol: String,
+    pub close: Option<Decimal>,
+    pub rsi: Option<Decimal>,
+    pub rvol: Option<Decimal>,
+    /// Snake case, e.g. "accumulation"
+    pub wyckoff_phase: Option<String>,
+    pub computed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct OrderBookSnapshotRow {
+    pub time: DateTime<Utc>,
+    pub symbol: String,
+    /// `[{price, volume}]`, best bid first
+    pub bids: serde_json::Value,
+    /// `[{price, volume}]`, best ask first
+    pub asks: serde_json::Value,
+    #[serde(serialize_with = "serialize_decimal_as_f64")]
+    pub obi: Decimal,
+    #[serde(serialize_with = "serialize_decimal_as_f64")]
+    pub ofi: Decimal,
+}
+
+/// Historical backfill job with its progress counts
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct BackfillJobRow {
+    pub id: i64,
+    pub source: String,
+    pub intervals: Vec<String>,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub status: String,
+    pub bars_written: i64,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    /// (symbol, interval) pairs in the job
+    pub tasks_total: i64,
+    pub tasks_done: i64,
+    /// Pairs whose last page failed
+    pub tasks_failed: i64,
+}
+
+/// Page cursor of one symbol and interval in a backfill job
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct BackfillProgressRow {
+    pub job_id: i64,
+    pub symbol: String,
+    pub interval: String,
+    pub cursor_date: Option<NaiveDate>,
+    pub pages: i32,
+    pub bars_written: i32,
+    pub done: bool,
+    pub error: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Headline tagged to a stock
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct StockNewsRow {
+    pub id: i32,
+    pub symbol: String,
+    pub title: String,
+    pub summary: Option<String>,
+    /// Feed id, e.g. 'kontan'
+    pub source: String,
+    pub url: String,
+    pub published_at: DateTime<Utc>,
+    /// 'positive', 'neutral' or 'negative'
+    pub sentiment: Option<String>,
+    /// -1 to 1
+    #[serde(serialize_with = "serialize_option_decimal_as_f64")]
+    pub sentiment_score: Option<Decimal>,
+    pub keywords: Option<Vec<String>>,
+    pub related_corporate_action_id: Option<i32>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Published or expected financial report of a stock
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct EarningsCalendarRow {
+    pub id: i32,
+    pub symbol: String,
+    pub fiscal_year: i32,
+    /// 'q1', 'q2', 'q3' or 'annual'
+    pub period: String,
+    /// Publication date once published
+    pub expected_date: NaiveDate,
+    pub deadline: NaiveDate,
+    /// 'published', 'last_year' or 'deadline'
+    pub basis: String,
+    pub published_on: Option<NaiveDate>,
+    pub alerted_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Saved backtest with its full report
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct BacktestRunRow {
+    pub id: i32,
+    pub owner: String,
+    pub name: Option<String>,
+    pub symbol: String,
+    pub strategy: serde_json::Value,
+    pub config: serde_json::Value,
+    pub costs: serde_json::Value,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub metrics: serde_json::Value,
+    pub trades: serde_json::Value,
+    pub equity_curve: serde_json::Value,
+    /// Distributions of resampled outcomes
+    pub monte_carlo: Option<serde_json::Value>,
+    /// Set while the run is shared read-only
+    pub share_token: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Saved backtest without its trades and equity curve, for listings
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct BacktestRunSummaryRow {
+    pub id: i32,
+    pub name: Option<String>,
+    pub symbol: String,
+    pub strategy: serde_json::Value,
+    pub config: serde_json::Value,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub metrics: serde_json::Value,
+    pub share_token: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Parameter grid search with its progress
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct BacktestOptimizationRow {
+    pub id: i32,
+    pub owner: String,
+    pub name: Option<String>,
+    pub symbol: String,
+    pub grid: serde_json::Value,
+    pub base_config: serde_json::Value,
+    pub costs: serde_json::Value,
+    pub objective: String,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    /// First out-of-sample session
+    pub split_date: Option<NaiveDate>,
+    pub status: String,
+    pub combinations_total: i32,
+    pub combinations_done: i32,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// One ranked combination of a grid search
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct BacktestOptimizationResultRow {
+    pub optimization_id: i32,
+    pub rank: i32,
+    pub strategy: serde_json::Value,
+    pub config: serde_json::Value,
+    pub in_sample: serde_json::Value,
+    pub out_of_sample: Option<serde_json::Value>,
+    pub in_sample_score: Option<f64>,
+    pub out_of_sample_score: Option<f64>,
+}
@@ -1,13 +1,63 @@
 //! Repository implementations for database access
 
+pub mod alerts;
+pub mod backfill_jobs;
+pub mod backtests;
 pub mod broker_summary;
+pub mod corporate_actions;
+pub mod custom_indicators;
+pub mod data_source_sla;
+pub mod earnings;
+pub mod indicator_snapshots;
+pub mod intraday_prices;
+pub mod macro_indicators;
+pub mod maintenance;
+pub mod news;
+pub mod notification_channels;
+pub mod notification_retries;
+pub mod order_flow;
+pub mod portfolio;
 pub mod prices;
+pub mod saved_screens;
 pub mod scores;
+pub mod staging;
+pub mod statements;
 pub mod stocks;
+pub mod stored_secrets;
+pub mod subscriptions;
+pub mod symbol_mappings;
+pub mod target_prices;
+pub mod trade_journal;
+pub mod usage_events;
 pub mod watchlist;
 
+pub use alerts::*;
+pub use backfill_jobs::*;
+pub use backtests::*;
 pub use broker_summary::*;
+pub use corporate_actions::*;
+pub use custom_indicators::*;
+pub use data_source_sla::*;
+pub use earnings::*;
+pub use indicator_snapshots::*;
+pub use intraday_prices::*;
+pub use macro_indicators::*;
+pub use maintenance::*;
+pub use news::*;
+pub use notification_channels::*;
+pub use notification_retries::*;
+pub use order_flow::*;
+pub use portfolio::*;
 pub use prices::*;
+pub use saved_screens::*;
 pub use scores::*;
+pub use staging::*;
+pub use statements::*;
 pub use stocks::*;
+pub use stored_secrets::*;
+pub use subscriptions::*;
+pub use symbol_mappings::*;
+pub use target_prices::*;
+pub use trade_journal::*;
+pub use usage_events::*;
 pub use watchlist::*;
@@ -0,0 +1,287 @@
+//! Alert repository
+
+use crate::models::{AlertHistoryRow, AlertRuleRow};
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+/// User alert rule for insertion or update
+pub struct InsertAlertRule<'a> {
+    pub owner: &'a str,
+    pub name: &'a str,
+    pub expression: &'a str,
+    pub symbols: &'a [String],
+    pub priority: &'a str,
+    pub enabled: bool,
+}
+
+/// Fired alert for insertion into the history
+pub struct InsertAlertHistory<'a> {
+    pub symbol: &'a str,
+    pub category: &'a str,
+    pub kind: &'a str,
+    pub priority: &'a str,
+    pub message: &'a str,
+    pub payload: &'a serde_json::Value,
+    pub triggered_at: DateTime<Utc>,
+    /// Owner of the rule that fired it; `None` for shared alerts
+    pub owner: Option<&'a str>,
+}
+
+/// Filter for the alert history; unset fields match everything
+#[derive(Debug, Default)]
+pub struct AlertHistoryFilter<'a> {
+    pub symbol: Option<&'a str>,
+    pub since: Option<DateTime<Utc>>,
+    /// Accepted priorities
+    pub priorities: Option<&'a [String]>,
+    pub acknowledged: Option<bool>,
+    /// Only shared alerts and those owned by this user
+    pub visible_to: Option<&'a str>,
+    pub limit: i64,
+}
+
+/// Alerts fired since `since`
+pub async fn count_alerts_triggered_since(
+    pool: &PgPool,
+    since: DateTime<Utc>,
+) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM alert_history WHERE triggered_at >= $1")
+        .bind(since)
+        .fetch_one(pool)
+        .await
+}
+
+/// Rules created by `owner`, oldest first
+pub async fn get_alert_rules(pool: &PgPool, owner: &str) -> Result<Vec<AlertRuleRow>, sqlx::Error> {
+    sqlx::query_as::<_, AlertRuleRow>(
+        "SELECT * FROM alert_rules WHERE owner = $1 AND deleted_at IS NULL ORDER BY id",
+    )
+    .bind(owner)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn get_alert_rule(
+    pool: &PgPool,
+    owner: &str,
+    id: i32,
+) -> Result<Option<AlertRuleRow>, sqlx::Error> {
+    sqlx::query_as::<_, AlertRuleRow>(
+        "SELECT * FROM alert_rules WHERE id = $1 AND owner = $2 AND deleted_at IS NULL",
+    )
+    .bind(id)
+    .bind(owner)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Enabled rules of every user
+pub async fn get_enabled_alert_rules(pool: &PgPool) -> Result<Vec<AlertRuleRow>, sqlx::Error> {
+    sqlx::query_as::<_, AlertRuleRow>(
+        "SELECT * FROM alert_rules WHERE enabled AND deleted_at IS NULL ORDER BY id",
+    )
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn insert_alert_rule(
+    pool: &PgPool,
+    rule: &InsertAlertRule<'_>,
+) -> Result<AlertRuleRow, sqlx::Error> {
+    sqlx::query_as::<_, AlertRuleRow>(
+        r#"
+        INSERT INTO alert_rules (owner, name, expression, symbols, priority, enabled)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING *
+        "#,
+    )
+    .bind(rule.owner)
+    .bind(rule.name)
+    .bind(rule.expression)
+    .bind(rule.symbols)
+    .bind(rule.priority)
+    .bind(rule.enabled)
+    .fetch_one(pool)
+    .await
+}
+
+/// Replace a rule's fields; returns None if it does not belong to the owner
+pub async fn update_alert_rule(
+    pool: &PgPool,
+    id: i32,
+    rule: &InsertAlertRule<'_>,
+) -> Result<Option<AlertRuleRow>, sqlx::Error> {
+    sqlx::query_as::<_, AlertRuleRow>(
+        r#"
+        UPDATE alert_rules
+        SET name = $3, expression = $4, symbols = $5, priority = $6, enabled = $7,
+            updated_at = NOW()
+        WHERE id = $1 AND owner = $2 AND deleted_at IS NULL
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .bind(rule.owner)
+    .bind(rule.name)
+    .bind(rule.expression)
+    .bind(rule.symbols)
+    .bind(rule.priority)
+    .bind(rule.enabled)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Record that a rule fired
+pub async fn mark_alert_rule_triggered(
+    pool: &PgPool,
+    id: i32,
+    triggered_at: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE alert_rules SET last_triggered_at = $2 WHERE id = $1")
+        .bind(id)
+        .bind(triggered_at)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Soft-delete a rule; it stays restorable until purged
+pub async fn delete_alert_rule(pool: &PgPool, owner: &str, id: i32) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        "UPDATE alert_rules SET deleted_at = NOW() WHERE id = $1 AND owner = $2 AND deleted_at IS NULL",
+    )
+    .bind(id)
+    .bind(owner)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Undo a soft-delete made at or after `since`
+pub async fn restore_alert_rule(
+    pool: &PgPool,
+    owner: &str,
+    id: i32,
+    since: DateTime<Utc>,
+) -> Result<Option<AlertRuleRow>, sqlx::Error> {
+    sqlx::query_as::<_, AlertRuleRow>(
+        r#"
+        UPDATE alert_rules SET deleted_at = NULL, updated_at = NOW()
+        WHERE id = $1 AND owner = $2 AND deleted_at >= $3
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .bind(owner)
+    .bind(since)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Permanently remove rules soft-deleted before `before`
+pub async fn purge_deleted_alert_rules(
+    pool: &PgPool,
+    before: DateTime<Utc>,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM alert_rules WHERE deleted_at < $1")
+        .bind(before)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected())
+}
+
+pub async fn insert_alert_history(
+    pool: &PgPool,
+    alert: &InsertAlertHistory<'_>,
+) -> Result<AlertHistoryRow, sqlx::Error> {
+    sqlx::query_as::<_, AlertHistoryRow>(
+        r#"
+        INSERT INTO alert_history
+            (symbol, category, kind, priority, message, trigger_value, triggered_at, owner)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        RETURNING *
+        "#,
+    )
+    .bind(alert.symbol)
+    .bind(alert.category)
+    .bind(alert.kind)
+    .bind(alert.priority)
+    .bind(alert.message)
+    .bind(alert.payload)
+    .bind(alert.triggered_at)
+    .bind(alert.owner)
+    .fetch_one(pool)
+    .await
+}
+
+/// Fired alerts matching `filter`, newest first
+pub async fn get_alert_history(
+    pool: &PgPool,
+    filter: &AlertHistoryFilter<'_>,
+) -> Result<Vec<AlertHistoryRow>, sqlx::Error> {
+    sqlx::query_as::<_, AlertHistoryRow>(
+        r#"
+        SELECT * FROM alert_history
+        WHERE ($1::VARCHAR IS NULL OR symbol = $1)
+          AND ($2::TIMESTAMPTZ IS NULL OR triggered_at >= $2)
+          AND ($3::TEXT[] IS NULL OR priority = ANY($3))
+          AND ($4::BOOLEAN IS NULL OR acknowledged = $4)
+          AND ($5::VARCHAR IS NULL OR owner IS NULL OR owner = $5)
+        ORDER BY triggered_at DESC NULLS LAST, id DESC
+        LIMIT $6
+        "#,
+    )
+    .bind(filter.symbol)
+    .bind(filter.since)
+    .bind(filter.priorities)
+    .bind(filter.acknowledged)
+    .bind(filter.visible_to)
+    .bind(filter.limit)
+    .fetch_all(pool)
+    .await
+}
+
+/// One fired alert, if shared or owned by `user`
+pub async fn get_alert_history_entry(
+    pool: &PgPool,
+    user: &str,
+    id: i32,
+) -> Result<Option<AlertHistoryRow>, sqlx::Error> {
+    sqlx::query_as::<_, AlertHistoryRow>(
+        "SELECT * FROM alert_history WHERE id = $1 AND (owner IS NULL OR owner = $2)",
+    )
+    .bind(id)
+    .bind(user)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Record that notifications went out for a fired alert
+pub async fn mark_alert_history_notified(pool: &PgPool, id: i32) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE alert_history SET notification_sent = true WHERE id = $1")
+        .bind(id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Acknowledge a fired alert shared with or owned by `user`; acknowledging
+/// twice keeps the first time
+pub async fn acknowledge_alert(
+    pool: &PgPool,
+    user: &str,
+    id: i32,
+) -> Result<Option<AlertHistoryRow>, sqlx::Error> {
+    sqlx::query_as::<_, AlertHistoryRow>(
+        r#"
+        UPDATE alert_history
+        SET acknowledged = true, acknowledged_at = COALESCE(acknowledged_at, NOW())
+        WHERE id = $1 AND (owner IS NULL OR owner = $2)
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .bind(user)
+    .fetch_optional(pool)
+    .await
+}
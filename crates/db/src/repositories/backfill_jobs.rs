@@ -0,0 +1,188 @@
+//! Historical backfill job repository
+
+use crate::models::{BackfillJobRow, BackfillProgressRow};
+use chrono::NaiveDate;
+use sqlx::PgPool;
+
+/// Job columns with progress counts over its (symbol, interval) pairs
+const JOB_SELECT: &str = r#"
+    SELECT j.*,
+        COUNT(p.symbol) AS tasks_total,
+        COUNT(p.symbol) FILTER (WHERE p.done) AS tasks_done,
+        COUNT(p.symbol) FILTER (WHERE p.error IS NOT NULL) AS tasks_failed
+    FROM backfill_jobs j
+    LEFT JOIN backfill_progress p ON p.job_id = j.id
+"#;
+
+/// Create a job with a pending entry for every symbol and interval
+pub async fn create_backfill_job(
+    pool: &PgPool,
+    source: &str,
+    intervals: &[String],
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    symbols: &[String],
+) -> Result<BackfillJobRow, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let (job_id,): (i64,) = sqlx::query_as(
+        r#"
+        INSERT INTO backfill_jobs (source, intervals, start_date, end_date)
+        VALUES ($1, $2, $3, $4)
+        RETURNING id
+        "#,
+    )
+    .bind(source)
+    .bind(intervals)
+    .bind(start_date)
+    .bind(end_date)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO backfill_progress (job_id, symbol, interval)
+        SELECT $1, s.symbol, i.interval
+        FROM UNNEST($2::VARCHAR[]) AS s(symbol)
+        CROSS JOIN UNNEST($3::VARCHAR[]) AS i(interval)
+        "#,
+    )
+    .bind(job_id)
+    .bind(symbols)
+    .bind(intervals)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    get_backfill_job(pool, job_id)
+        .await?
+        .ok_or(sqlx::Error::RowNotFound)
+}
+
+/// Get a job with its progress counts
+pub async fn get_backfill_job(
+    pool: &PgPool,
+    job_id: i64,
+) -> Result<Option<BackfillJobRow>, sqlx::Error> {
+    sqlx::query_as::<_, BackfillJobRow>(&format!("{JOB_SELECT} WHERE j.id = $1 GROUP BY j.id"))
+        .bind(job_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Latest jobs of `source`, newest first
+pub async fn list_backfill_jobs(
+    pool: &PgPool,
+    source: &str,
+    limit: i64,
+) -> Result<Vec<BackfillJobRow>, sqlx::Error> {
+    sqlx::query_as::<_, BackfillJobRow>(&format!(
+        "{JOB_SELECT} WHERE j.source = $1 GROUP BY j.id ORDER BY j.created_at DESC LIMIT $2"
+    ))
+    .bind(source)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
+
+/// Unfinished (symbol, interval) pairs of a job, by symbol
+pub async fn get_pending_backfill_progress(
+    pool: &PgPool,
+    job_id: i64,
+) -> Result<Vec<BackfillProgressRow>, sqlx::Error> {
+    sqlx::query_as::<_, BackfillProgressRow>(
+        r#"
+        SELECT * FROM backfill_progress
+        WHERE job_id = $1 AND NOT done
+        ORDER BY symbol, interval
+        "#,
+    )
+    .bind(job_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Record a fetched page: move the cursor and add its bars to the job
+pub async fn record_backfill_page(
+    pool: &PgPool,
+    progress: &BackfillProgressRow,
+    cursor_date: Option<NaiveDate>,
+    bars_written: i32,
+    done: bool,
+) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    sqlx::query(
+        r#"
+        UPDATE backfill_progress
+        SET cursor_date = $4, pages = pages + 1, bars_written = bars_written + $5,
+            done = $6, error = NULL, updated_at = NOW()
+        WHERE job_id = $1 AND symbol = $2 AND interval = $3
+        "#,
+    )
+    .bind(progress.job_id)
+    .bind(&progress.symbol)
+    .bind(&progress.interval)
+    .bind(cursor_date)
+    .bind(bars_written)
+    .bind(done)
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query(
+        r#"
+        UPDATE backfill_jobs
+        SET bars_written = bars_written + $2, updated_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(progress.job_id)
+    .bind(i64::from(bars_written))
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await
+}
+
+/// Record a failed page; the cursor stays for the next run
+pub async fn record_backfill_error(
+    pool: &PgPool,
+    progress: &BackfillProgressRow,
+    error: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE backfill_progress
+        SET error = $4, updated_at = NOW()
+        WHERE job_id = $1 AND symbol = $2 AND interval = $3
+        "#,
+    )
+    .bind(progress.job_id)
+    .bind(&progress.symbol)
+    .bind(&progress.interval)
+    .bind(error)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Set a job's status; `completed` and `failed` also stamp the completion
+pub async fn set_backfill_job_status(
+    pool: &PgPool,
+    job_id: i64,
+    status: &str,
+    last_error: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE backfill_jobs
+        SET status = $2,
+            last_error = COALESCE($3, last_error),
+            updated_at = NOW(),
+            completed_at = CASE WHEN $2 IN ('completed', 'failed') THEN NOW() END
+        WHERE id = $1
+        "#,
+    )
+    .bind(job_id)
+    .bind(status)
+    .bind(last_error)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
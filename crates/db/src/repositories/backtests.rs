@@ -0,0 +1,362 @@
+//! Saved backtest and parameter optimization repository
+
+use crate::models::{
+    BacktestOptimizationResultRow, BacktestOptimizationRow, BacktestRunRow, BacktestRunSummaryRow,
+};
+use chrono::NaiveDate;
+use sqlx::PgPool;
+
+/// Backtest report to save
+pub struct InsertBacktestRun<'a> {
+    pub owner: &'a str,
+    pub name: Option<&'a str>,
+    pub symbol: &'a str,
+    pub strategy: &'a serde_json::Value,
+    pub config: &'a serde_json::Value,
+    pub costs: &'a serde_json::Value,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub metrics: &'a serde_json::Value,
+    pub trades: &'a serde_json::Value,
+    pub equity_curve: &'a serde_json::Value,
+    pub monte_carlo: Option<&'a serde_json::Value>,
+}
+
+pub async fn insert_backtest_run(
+    pool: &PgPool,
+    run: &InsertBacktestRun<'_>,
+) -> Result<BacktestRunRow, sqlx::Error> {
+    sqlx::query_as::<_, BacktestRunRow>(
+        r#"
+        INSERT INTO backtest_runs
+            (owner, name, symbol, strategy, config, costs, start_date, end_date,
+             metrics, trades, equity_curve, monte_carlo)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+        RETURNING *
+        "#,
+    )
+    .bind(run.owner)
+    .bind(run.name)
+    .bind(run.symbol)
+    .bind(run.strategy)
+    .bind(run.config)
+    .bind(run.costs)
+    .bind(run.start_date)
+    .bind(run.end_date)
+    .bind(run.metrics)
+    .bind(run.trades)
+    .bind(run.equity_curve)
+    .bind(run.monte_carlo)
+    .fetch_one(pool)
+    .await
+}
+
+/// Runs saved by `owner`, newest first, optionally for one symbol
+pub async fn get_backtest_runs(
+    pool: &PgPool,
+    owner: &str,
+    symbol: Option<&str>,
+) -> Result<Vec<BacktestRunSummaryRow>, sqlx::Error> {
+    sqlx::query_as::<_, BacktestRunSummaryRow>(
+        r#"
+        SELECT id, name, symbol, strategy, config, start_date, end_date, metrics,
+               share_token, created_at
+        FROM backtest_runs
+        WHERE owner = $1 AND ($2::text IS NULL OR symbol = $2)
+        ORDER BY created_at DESC, id DESC
+        "#,
+    )
+    .bind(owner)
+    .bind(symbol)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn get_backtest_run(
+    pool: &PgPool,
+    owner: &str,
+    id: i32,
+) -> Result<Option<BacktestRunRow>, sqlx::Error> {
+    sqlx::query_as::<_, BacktestRunRow>("SELECT * FROM backtest_runs WHERE id = $1 AND owner = $2")
+        .bind(id)
+        .bind(owner)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Run shared under `token`
+pub async fn get_shared_backtest_run(
+    pool: &PgPool,
+    token: &str,
+) -> Result<Option<BacktestRunRow>, sqlx::Error> {
+    sqlx::query_as::<_, BacktestRunRow>("SELECT * FROM backtest_runs WHERE share_token = $1")
+        .bind(token)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Share a run under `token`, keeping the token of a run already shared
+///
+/// Returns the run's token; None if the run does not belong to the owner.
+pub async fn share_backtest_run(
+    pool: &PgPool,
+    owner: &str,
+    id: i32,
+    token: &str,
+) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar::<_, String>(
+        r#"
+        UPDATE backtest_runs
+        SET share_token = COALESCE(share_token, $3)
+        WHERE id = $1 AND owner = $2
+        RETURNING share_token
+        "#,
+    )
+    .bind(id)
+    .bind(owner)
+    .bind(token)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Revoke a run's share link; false if the run does not belong to the owner
+pub async fn unshare_backtest_run(
+    pool: &PgPool,
+    owner: &str,
+    id: i32,
+) -> Result<bool, sqlx::Error> {
+    let result =
+        sqlx::query("UPDATE backtest_runs SET share_token = NULL WHERE id = $1 AND owner = $2")
+            .bind(id)
+            .bind(owner)
+            .execute(pool)
+            .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn delete_backtest_run(pool: &PgPool, owner: &str, id: i32) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM backtest_runs WHERE id = $1 AND owner = $2")
+        .bind(id)
+        .bind(owner)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Grid search to create, pending until its job starts
+pub struct InsertBacktestOptimization<'a> {
+    pub owner: &'a str,
+    pub name: Option<&'a str>,
+    pub symbol: &'a str,
+    pub grid: &'a serde_json::Value,
+    pub base_config: &'a serde_json::Value,
+    pub costs: &'a serde_json::Value,
+    pub objective: &'a str,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+}
+
+/// Ranked combination to store
+pub struct InsertOptimizationResult<'a> {
+    pub rank: i32,
+    pub strategy: &'a serde_json::Value,
+    pub config: &'a serde_json::Value,
+    pub in_sample: &'a serde_json::Value,
+    pub out_of_sample: Option<&'a serde_json::Value>,
+    pub in_sample_score: Option<f64>,
+    pub out_of_sample_score: Option<f64>,
+}
+
+pub async fn create_backtest_optimization(
+    pool: &PgPool,
+    optimization: &InsertBacktestOptimization<'_>,
+) -> Result<BacktestOptimizationRow, sqlx::Error> {
+    sqlx::query_as::<_, BacktestOptimizationRow>(
+        r#"
+        INSERT INTO backtest_optimizations
+            (owner, name, symbol, grid, base_config, costs, objective, start_date, end_date)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        RETURNING *
+        "#,
+    )
+    .bind(optimization.owner)
+    .bind(optimization.name)
+    .bind(optimization.symbol)
+    .bind(optimization.grid)
+    .bind(optimization.base_config)
+    .bind(optimization.costs)
+    .bind(optimization.objective)
+    .bind(optimization.start_date)
+    .bind(optimization.end_date)
+    .fetch_one(pool)
+    .await
+}
+
+/// Grid searches created by `owner`, newest first
+pub async fn get_backtest_optimizations(
+    pool: &PgPool,
+    owner: &str,
+) -> Result<Vec<BacktestOptimizationRow>, sqlx::Error> {
+    sqlx::query_as::<_, BacktestOptimizationRow>(
+        r#"
+        SELECT * FROM backtest_optimizations
+        WHERE owner = $1
+        ORDER BY created_at DESC, id DESC
+        "#,
+    )
+    .bind(owner)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn get_backtest_optimization(
+    pool: &PgPool,
+    owner: &str,
+    id: i32,
+) -> Result<Option<BacktestOptimizationRow>, sqlx::Error> {
+    sqlx::query_as::<_, BacktestOptimizationRow>(
+        "SELECT * FROM backtest_optimizations WHERE id = $1 AND owner = $2",
+    )
+    .bind(id)
+    .bind(owner)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Mark a grid search running over `combinations` from `split_date` on
+pub async fn start_backtest_optimization(
+    pool: &PgPool,
+    id: i32,
+    split_date: NaiveDate,
+    combinations: i32,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE backtest_optimizations
+        SET status = 'running', split_date = $2, combinations_total = $3,
+            combinations_done = 0, updated_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(id)
+    .bind(split_date)
+    .bind(combinations)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+pub async fn record_optimization_progress(
+    pool: &PgPool,
+    id: i32,
+    done: i32,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE backtest_optimizations
+        SET combinations_done = $2, updated_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(id)
+    .bind(done)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Store the ranked results and mark the grid search completed
+pub async fn complete_backtest_optimization(
+    pool: &PgPool,
+    id: i32,
+    results: &[InsertOptimizationResult<'_>],
+) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    sqlx::query("DELETE FROM backtest_optimization_results WHERE optimization_id = $1")
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+    for result in results {
+        sqlx::query(
+            r#"
+            INSERT INTO backtest_optimization_results
+                (optimization_id, rank, strategy, config, in_sample, out_of_sample,
+                 in_sample_score, out_of_sample_score)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+        )
+        .bind(id)
+        .bind(result.rank)
+        .bind(result.strategy)
+        .bind(result.config)
+        .bind(result.in_sample)
+        .bind(result.out_of_sample)
+        .bind(result.in_sample_score)
+        .bind(result.out_of_sample_score)
+        .execute(&mut *tx)
+        .await?;
+    }
+    sqlx::query(
+        r#"
+        UPDATE backtest_optimizations
+        SET status = 'completed', updated_at = NOW(), completed_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(id)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await
+}
+
+pub async fn fail_backtest_optimization(
+    pool: &PgPool,
+    id: i32,
+    error: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE backtest_optimizations
+        SET status = 'failed', last_error = $2, updated_at = NOW(), completed_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(id)
+    .bind(error)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Best `limit` results of a grid search, by rank
+pub async fn get_optimization_results(
+    pool: &PgPool,
+    id: i32,
+    limit: i64,
+) -> Result<Vec<BacktestOptimizationResultRow>, sqlx::Error> {
+    sqlx::query_as::<_, BacktestOptimizationResultRow>(
+        r#"
+        SELECT * FROM backtest_optimization_results
+        WHERE optimization_id = $1
+        ORDER BY rank
+        LIMIT $2
+        "#,
+    )
+    .bind(id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn delete_backtest_optimization(
+    pool: &PgPool,
+    owner: &str,
+    id: i32,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM backtest_optimizations WHERE id = $1 AND owner = $2")
+        .bind(id)
+        .bind(owner)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
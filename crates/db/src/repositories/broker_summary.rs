@@ -1,9 +1,29 @@
 //! Broker summary repository
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use rust_decimal::Decimal;
 use sqlx::{FromRow, PgPool};
 
+/// Broker summary data for insertion
+pub struct InsertBrokerSummary<'a> {
+    pub time: DateTime<Utc>,
+    pub symbol: &'a str,
+    pub broker_code: &'a str,
+    pub buy_volume: i64,
+    pub sell_volume: i64,
+    pub buy_value: Decimal,
+    pub sell_value: Decimal,
+    /// Where the row came from, e.g. `scraper` or `import:<parser>`
+    pub source: &'a str,
+}
+
+/// Row counts from a broker summary upsert
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BrokerSummaryUpsert {
+    pub inserted: u64,
+    pub replaced: u64,
+}
+
 #[derive(Debug, Clone, FromRow)]
 pub struct BrokerFlowAggregateRow {
     pub broker_code: String,
@@ -55,6 +75,48 @@ pub async fn get_broker_flow_aggregates(
     .await
 }
 
+/// Broker flow aggregates of one symbol within a batch
+#[derive(Debug, Clone, FromRow)]
+pub struct SymbolBrokerFlowAggregateRow {
+    pub symbol: String,
+    #[sqlx(flatten)]
+    pub aggregate: BrokerFlowAggregateRow,
+}
+
+/// [`get_broker_flow_aggregates`] for several symbols in one query
+pub async fn get_broker_flow_aggregates_for_symbols(
+    pool: &PgPool,
+    symbols: &[String],
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Vec<SymbolBrokerFlowAggregateRow>, sqlx::Error> {
+    sqlx::query_as::<_, SymbolBrokerFlowAggregateRow>(
+        r#"
+        SELECT
+            bs.symbol,
+            bs.broker_code,
+            b.name AS broker_name,
+            COALESCE(b.category, 'unknown') AS category,
+            SUM(bs.buy_volume)::bigint AS buy_volume,
+            SUM(bs.sell_volume)::bigint AS sell_volume,
+            SUM(bs.buy_value) AS buy_value,
+            SUM(bs.sell_value) AS sell_value,
+            SUM(bs.net_volume)::bigint AS net_volume,
+            SUM(bs.net_value) AS net_value
+        FROM broker_summary bs
+        LEFT JOIN brokers b ON b.code = bs.broker_code
+        WHERE bs.symbol = ANY($1) AND bs.time >= $2 AND bs.time <= $3
+        GROUP BY bs.symbol, bs.broker_code, b.name, b.category
+        ORDER BY bs.symbol, net_value DESC
+        "#,
+    )
+    .bind(symbols)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await
+}
+
 pub async fn get_price_range(
     pool: &PgPool,
     symbol: &str,
@@ -132,3 +194,137 @@ pub async fn get_daily_broker_summaries(
     .fetch_all(pool)
     .await
 }
+
+/// Insert broker summaries, replacing existing rows for the same symbol, broker and day
+pub async fn upsert_broker_summaries(
+    pool: &PgPool,
+    rows: &[InsertBrokerSummary<'_>],
+) -> Result<BrokerSummaryUpsert, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let mut counts = BrokerSummaryUpsert::default();
+
+    for row in rows {
+        let deleted = sqlx::query(
+            r#"
+            DELETE FROM broker_summary
+            WHERE symbol = $1 AND broker_code = $2 AND time::date = $3::date
+            "#,
+        )
+        .bind(row.symbol)
+        .bind(row.broker_code)
+        .bind(row.time)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        sqlx::query(
+            r#"
+            INSERT INTO broker_summary (
+                time, symbol, broker_code, buy_volume, sell_volume, buy_value, sell_value,
+                source
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+        )
+        .bind(row.time)
+        .bind(row.symbol)
+        .bind(row.broker_code)
+        .bind(row.buy_volume)
+        .bind(row.sell_volume)
+        .bind(row.buy_value)
+        .bind(row.sell_value)
+        .bind(row.source)
+        .execute(&mut *tx)
+        .await?;
+
+        if deleted > 0 {
+            counts.replaced += 1;
+        } else {
+            counts.inserted += 1;
+        }
+    }
+
+    tx.commit().await?;
+    Ok(counts)
+}
+
+/// Broker data present for one trading day
+#[derive(Debug, Clone, FromRow)]
+pub struct BrokerCoverageDayRow {
+    pub day: NaiveDate,
+    pub sources: Vec<String>,
+    pub broker_count: i64,
+}
+
+/// Days with broker data for a symbol, and the sources that supplied them
+pub async fn get_broker_coverage_days(
+    pool: &PgPool,
+    symbol: &str,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Vec<BrokerCoverageDayRow>, sqlx::Error> {
+    sqlx::query_as::<_, BrokerCoverageDayRow>(
+        r#"
+        SELECT
+            time::date AS day,
+            ARRAY_AGG(DISTINCT source ORDER BY source) AS sources,
+            COUNT(DISTINCT broker_code) AS broker_count
+        FROM broker_summary
+        WHERE symbol = $1 AND time >= $2 AND time <= $3
+        GROUP BY time::date
+        ORDER BY day
+        "#,
+    )
+    .bind(symbol)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await
+}
+
+/// Broker data coverage of one symbol over a window
+#[derive(Debug, Clone, FromRow)]
+pub struct BrokerCoverageSummaryRow {
+    pub symbol: String,
+    /// Days with price data
+    pub trading_days: i64,
+    /// Trading days that also have broker data
+    pub covered_days: i64,
+    pub last_day: Option<NaiveDate>,
+}
+
+/// Broker data coverage for every active stock, measured against its price days
+pub async fn get_broker_coverage_summary(
+    pool: &PgPool,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Vec<BrokerCoverageSummaryRow>, sqlx::Error> {
+    sqlx::query_as::<_, BrokerCoverageSummaryRow>(
+        r#"
+        WITH price_days AS (
+            SELECT DISTINCT symbol, time::date AS day
+            FROM stock_prices
+            WHERE time >= $1 AND time <= $2
+        ),
+        broker_days AS (
+            SELECT DISTINCT symbol, time::date AS day
+            FROM broker_summary
+            WHERE time >= $1 AND time <= $2
+        )
+        SELECT
+            s.symbol,
+            (SELECT COUNT(*) FROM price_days p WHERE p.symbol = s.symbol) AS trading_days,
+            (SELECT COUNT(*) FROM price_days p
+             JOIN broker_days b ON b.symbol = p.symbol AND b.day = p.day
+             WHERE p.symbol = s.symbol) AS covered_days,
+            (SELECT MAX(day) FROM broker_days b WHERE b.symbol = s.symbol) AS last_day
+        FROM stocks s
+        WHERE s.is_active
+        ORDER BY s.symbol
+        "#,
+    )
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await
+}
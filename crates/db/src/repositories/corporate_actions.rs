@@ -0,0 +1,104 @@
+//! Corporate action repository
+
+use crate::models::CorporateActionRow;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+
+/// Action to store, keyed by stock, type and ex-date
+#[derive(Debug, Clone)]
+pub struct InsertCorporateAction<'a> {
+    pub symbol: &'a str,
+    pub action_type: &'a str,
+    pub announced_date: NaiveDate,
+    pub effective_date: Option<NaiveDate>,
+    pub ex_date: NaiveDate,
+    pub description: &'a str,
+    pub value: Option<Decimal>,
+    pub ratio: Option<Decimal>,
+    pub price: Option<Decimal>,
+    pub source: &'a str,
+}
+
+/// Store actions, filling terms missing from rows already stored
+///
+/// Known terms are never overwritten. Returns the number of rows written.
+pub async fn upsert_corporate_actions(
+    pool: &PgPool,
+    actions: &[InsertCorporateAction<'_>],
+) -> Result<u64, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let mut written = 0;
+
+    for action in actions {
+        written += sqlx::query(
+            r#"
+            INSERT INTO corporate_actions
+                (symbol, action_type, announced_date, effective_date, ex_date,
+                 description, value, ratio, price, source)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            ON CONFLICT (symbol, action_type, ex_date) DO UPDATE SET
+                effective_date = COALESCE(corporate_actions.effective_date, EXCLUDED.effective_date),
+                value = COALESCE(corporate_actions.value, EXCLUDED.value),
+                ratio = COALESCE(corporate_actions.ratio, EXCLUDED.ratio),
+                price = COALESCE(corporate_actions.price, EXCLUDED.price),
+                source = COALESCE(corporate_actions.source, EXCLUDED.source)
+            "#,
+        )
+        .bind(action.symbol)
+        .bind(action.action_type)
+        .bind(action.announced_date)
+        .bind(action.effective_date)
+        .bind(action.ex_date)
+        .bind(action.description)
+        .bind(action.value)
+        .bind(action.ratio)
+        .bind(action.price)
+        .bind(action.source)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+    }
+
+    tx.commit().await?;
+    Ok(written)
+}
+
+/// Actions of `action_type` announced on or after `from` for `symbols`,
+/// oldest first
+pub async fn get_corporate_actions(
+    pool: &PgPool,
+    symbols: &[String],
+    action_type: &str,
+    from: NaiveDate,
+) -> Result<Vec<CorporateActionRow>, sqlx::Error> {
+    sqlx::query_as::<_, CorporateActionRow>(
+        r#"
+        SELECT * FROM corporate_actions
+        WHERE symbol = ANY($1) AND action_type = $2 AND announced_date >= $3
+        ORDER BY announced_date, symbol
+        "#,
+    )
+    .bind(symbols)
+    .bind(action_type)
+    .bind(from)
+    .fetch_all(pool)
+    .await
+}
+
+/// Every action of `symbol` with an ex-date, oldest ex-date first
+pub async fn get_actions_with_ex_date(
+    pool: &PgPool,
+    symbol: &str,
+) -> Result<Vec<CorporateActionRow>, sqlx::Error> {
+    sqlx::query_as::<_, CorporateActionRow>(
+        r#"
+        SELECT * FROM corporate_actions
+        WHERE symbol = $1 AND ex_date IS NOT NULL
+        ORDER BY ex_date
+        "#,
+    )
+    .bind(symbol)
+    .fetch_all(pool)
+    .await
+}
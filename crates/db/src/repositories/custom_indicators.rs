@@ -0,0 +1,107 @@
+//! Custom indicator repository
+
+use crate::models::CustomIndicatorRow;
+use sqlx::PgPool;
+
+/// Custom indicator for insertion or update
+pub struct InsertCustomIndicator<'a> {
+    pub owner: &'a str,
+    pub name: &'a str,
+    pub expression: &'a str,
+    pub description: Option<&'a str>,
+}
+
+/// Indicators defined by `owner`, by name
+pub async fn get_custom_indicators(
+    pool: &PgPool,
+    owner: &str,
+) -> Result<Vec<CustomIndicatorRow>, sqlx::Error> {
+    sqlx::query_as::<_, CustomIndicatorRow>(
+        "SELECT * FROM custom_indicators WHERE owner = $1 ORDER BY name",
+    )
+    .bind(owner)
+    .fetch_all(pool)
+    .await
+}
+
+/// Indicators of several owners, by owner and name
+pub async fn get_custom_indicators_of(
+    pool: &PgPool,
+    owners: &[String],
+) -> Result<Vec<CustomIndicatorRow>, sqlx::Error> {
+    sqlx::query_as::<_, CustomIndicatorRow>(
+        "SELECT * FROM custom_indicators WHERE owner = ANY($1) ORDER BY owner, name",
+    )
+    .bind(owners)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn get_custom_indicator(
+    pool: &PgPool,
+    owner: &str,
+    id: i32,
+) -> Result<Option<CustomIndicatorRow>, sqlx::Error> {
+    sqlx::query_as::<_, CustomIndicatorRow>(
+        "SELECT * FROM custom_indicators WHERE id = $1 AND owner = $2",
+    )
+    .bind(id)
+    .bind(owner)
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn insert_custom_indicator(
+    pool: &PgPool,
+    indicator: &InsertCustomIndicator<'_>,
+) -> Result<CustomIndicatorRow, sqlx::Error> {
+    sqlx::query_as::<_, CustomIndicatorRow>(
+        r#"
+        INSERT INTO custom_indicators (owner, name, expression, description)
+        VALUES ($1, $2, $3, $4)
+        RETURNING *
+        "#,
+    )
+    .bind(indicator.owner)
+    .bind(indicator.name)
+    .bind(indicator.expression)
+    .bind(indicator.description)
+    .fetch_one(pool)
+    .await
+}
+
+/// Replace an indicator's fields; None if it does not belong to the owner
+pub async fn update_custom_indicator(
+    pool: &PgPool,
+    id: i32,
+    indicator: &InsertCustomIndicator<'_>,
+) -> Result<Option<CustomIndicatorRow>, sqlx::Error> {
+    sqlx::query_as::<_, CustomIndicatorRow>(
+        r#"
+        UPDATE custom_indicators
+        SET name = $3, expression = $4, description = $5, updated_at = NOW()
+        WHERE id = $1 AND owner = $2
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .bind(indicator.owner)
+    .bind(indicator.name)
+    .bind(indicator.expression)
+    .bind(indicator.description)
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn delete_custom_indicator(
+    pool: &PgPool,
+    owner: &str,
+    id: i32,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM custom_indicators WHERE id = $1 AND owner = $2")
+        .bind(id)
+        .bind(owner)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
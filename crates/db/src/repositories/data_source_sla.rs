@@ -0,0 +1,92 @@
+//! Data source SLA repository
+
+use crate::models::DataSourceSlaRow;
+use sqlx::PgPool;
+
+/// Get SLA tracking rows for all data sources that have run at least once
+pub async fn get_data_source_sla(pool: &PgPool) -> Result<Vec<DataSourceSlaRow>, sqlx::Error> {
+    sqlx::query_as::<_, DataSourceSlaRow>("SELECT * FROM data_source_sla ORDER BY source_id")
+        .fetch_all(pool)
+        .await
+}
+
+/// Get SLA tracking row for a single data source
+pub async fn get_data_source_sla_by_id(
+    pool: &PgPool,
+    source_id: &str,
+) -> Result<Option<DataSourceSlaRow>, sqlx::Error> {
+    sqlx::query_as::<_, DataSourceSlaRow>("SELECT * FROM data_source_sla WHERE source_id = $1")
+        .bind(source_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Record the outcome of a data source refresh run
+///
+/// Successful runs reset the consecutive-failure counter; the average
+/// duration is a running mean over all recorded runs.
+pub async fn record_data_source_run(
+    pool: &PgPool,
+    source_id: &str,
+    success: bool,
+    duration_secs: f64,
+    error: Option<&str>,
+) -> Result<DataSourceSlaRow, sqlx::Error> {
+    sqlx::query_as::<_, DataSourceSlaRow>(
+        r#"
+        INSERT INTO data_source_sla (
+            source_id,
+            last_success_at,
+            last_failure_at,
+            last_error,
+            consecutive_failures,
+            total_runs,
+            avg_duration_secs
+        )
+        VALUES (
+            $1,
+            CASE WHEN $2 THEN NOW() END,
+            CASE WHEN $2 THEN NULL ELSE NOW() END,
+            $4,
+            CASE WHEN $2 THEN 0 ELSE 1 END,
+            1,
+            $3
+        )
+        ON CONFLICT (source_id) DO UPDATE SET
+            last_success_at = CASE WHEN $2 THEN NOW() ELSE data_source_sla.last_success_at END,
+            last_failure_at = CASE WHEN $2 THEN data_source_sla.last_failure_at ELSE NOW() END,
+            last_error = CASE WHEN $2 THEN data_source_sla.last_error ELSE $4 END,
+            consecutive_failures =
+                CASE WHEN $2 THEN 0 ELSE data_source_sla.consecutive_failures + 1 END,
+            total_runs = data_source_sla.total_runs + 1,
+            avg_duration_secs = (
+                COALESCE(data_source_sla.avg_duration_secs, 0) * data_source_sla.total_runs + $3
+            ) / (data_source_sla.total_runs + 1),
+            updated_at = NOW()
+        RETURNING *
+        "#,
+    )
+    .bind(source_id)
+    .bind(success)
+    .bind(duration_secs)
+    .bind(error)
+    .fetch_one(pool)
+    .await
+}
+
+/// Mark that admins were notified about an SLA breach for a data source
+pub async fn mark_sla_breach_notified(pool: &PgPool, source_id: &str) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO data_source_sla (source_id, last_breach_notified_at)
+        VALUES ($1, NOW())
+        ON CONFLICT (source_id) DO UPDATE SET
+            last_breach_notified_at = NOW(),
+            updated_at = NOW()
+        "#,
+    )
+    .bind(source_id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,148 @@
+//! Financial report calendar repository
+
+use crate::models::EarningsCalendarRow;
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::PgPool;
+
+/// Published or expected report to store
+#[derive(Debug, Clone)]
+pub struct InsertEarnings<'a> {
+    pub symbol: &'a str,
+    pub fiscal_year: i32,
+    pub period: &'a str,
+    pub expected_date: NaiveDate,
+    pub deadline: NaiveDate,
+    pub basis: &'a str,
+    pub published_on: Option<NaiveDate>,
+}
+
+/// Store reports, keyed by stock, fiscal year and period
+///
+/// A new estimate never replaces a published report. Returns the number of
+/// rows written.
+pub async fn upsert_earnings(
+    pool: &PgPool,
+    reports: &[InsertEarnings<'_>],
+) -> Result<u64, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let mut written = 0;
+
+    for report in reports {
+        written += sqlx::query(
+            r#"
+            INSERT INTO earnings_calendar
+                (symbol, fiscal_year, period, expected_date, deadline, basis, published_on)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (symbol, fiscal_year, period) DO UPDATE SET
+                expected_date = EXCLUDED.expected_date,
+                deadline = EXCLUDED.deadline,
+                basis = EXCLUDED.basis,
+                published_on = EXCLUDED.published_on,
+                updated_at = NOW()
+            WHERE earnings_calendar.published_on IS NULL
+               OR EXCLUDED.published_on IS NOT NULL
+            "#,
+        )
+        .bind(report.symbol)
+        .bind(report.fiscal_year)
+        .bind(report.period)
+        .bind(report.expected_date)
+        .bind(report.deadline)
+        .bind(report.basis)
+        .bind(report.published_on)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+    }
+
+    tx.commit().await?;
+    Ok(written)
+}
+
+/// Reports of `symbol` expected or published on or after `since`, oldest first
+pub async fn get_stock_earnings(
+    pool: &PgPool,
+    symbol: &str,
+    since: NaiveDate,
+) -> Result<Vec<EarningsCalendarRow>, sqlx::Error> {
+    sqlx::query_as::<_, EarningsCalendarRow>(
+        r#"
+        SELECT * FROM earnings_calendar
+        WHERE symbol = $1 AND expected_date >= $2
+        ORDER BY expected_date, fiscal_year, period
+        "#,
+    )
+    .bind(symbol)
+    .bind(since)
+    .fetch_all(pool)
+    .await
+}
+
+/// Reports of every stock expected or published from `from` to `to`
+/// inclusive, by date then symbol
+pub async fn get_earnings_between(
+    pool: &PgPool,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<EarningsCalendarRow>, sqlx::Error> {
+    sqlx::query_as::<_, EarningsCalendarRow>(
+        r#"
+        SELECT * FROM earnings_calendar
+        WHERE expected_date BETWEEN $1 AND $2
+        ORDER BY expected_date, symbol
+        "#,
+    )
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await
+}
+
+/// Unpublished reports of `symbol` expected by `until` whose deadline has
+/// not passed on `today`, and that have not been alerted
+pub async fn get_unalerted_earnings(
+    pool: &PgPool,
+    symbol: &str,
+    today: NaiveDate,
+    until: NaiveDate,
+) -> Result<Vec<EarningsCalendarRow>, sqlx::Error> {
+    sqlx::query_as::<_, EarningsCalendarRow>(
+        r#"
+        SELECT * FROM earnings_calendar
+        WHERE symbol = $1
+          AND published_on IS NULL
+          AND alerted_at IS NULL
+          AND expected_date <= $3
+          AND deadline >= $2
+        ORDER BY expected_date
+        "#,
+    )
+    .bind(symbol)
+    .bind(today)
+    .bind(until)
+    .fetch_all(pool)
+    .await
+}
+
+/// Record that the upcoming-report alert was sent
+pub async fn mark_earnings_alerted(
+    pool: &PgPool,
+    symbol: &str,
+    fiscal_year: i32,
+    period: &str,
+    at: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE earnings_calendar SET alerted_at = $4
+        WHERE symbol = $1 AND fiscal_year = $2 AND period = $3
+        "#,
+    )
+    .bind(symbol)
+    .bind(fiscal_year)
+    .bind(period)
+    .bind(at)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
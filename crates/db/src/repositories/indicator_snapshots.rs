@@ -0,0 +1,72 @@
+//! Materialized indicator snapshot repository
+
+use crate::models::IndicatorSnapshotRow;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+
+/// Indicator snapshot for insertion
+#[derive(Debug, Clone)]
+pub struct InsertIndicatorSnapshot {
+    pub symbol: String,
+    pub close: Option<Decimal>,
+    pub rsi: Option<Decimal>,
+    pub rvol: Option<Decimal>,
+    pub wyckoff_phase: Option<String>,
+}
+
+/// Replace the snapshots of the given symbols in one statement
+pub async fn upsert_indicator_snapshots(
+    pool: &PgPool,
+    snapshots: &[InsertIndicatorSnapshot],
+    computed_at: DateTime<Utc>,
+) -> Result<u64, sqlx::Error> {
+    if snapshots.is_empty() {
+        return Ok(0);
+    }
+    let symbols: Vec<&str> = snapshots.iter().map(|s| s.symbol.as_str()).collect();
+    let closes: Vec<Option<Decimal>> = snapshots.iter().map(|s| s.close).collect();
+    let rsis: Vec<Option<Decimal>> = snapshots.iter().map(|s| s.rsi).collect();
+    let rvols: Vec<Option<Decimal>> = snapshots.iter().map(|s| s.rvol).collect();
+    let phases: Vec<Option<&str>> = snapshots
+        .iter()
+        .map(|s| s.wyckoff_phase.as_deref())
+        .collect();
+
+    let result = sqlx::query(
+        r#"
+        INSERT INTO indicator_snapshots (symbol, close, rsi, rvol, wyckoff_phase, computed_at)
+        SELECT symbol, close, rsi, rvol, wyckoff_phase, $6
+        FROM UNNEST($1::VARCHAR[], $2::NUMERIC[], $3::NUMERIC[], $4::NUMERIC[], $5::VARCHAR[])
+            AS s(symbol, close, rsi, rvol, wyckoff_phase)
+        ON CONFLICT (symbol) DO UPDATE SET
+            close = EXCLUDED.close,
+            rsi = EXCLUDED.rsi,
+            rvol = EXCLUDED.rvol,
+            wyckoff_phase = EXCLUDED.wyckoff_phase,
+            computed_at = EXCLUDED.computed_at
+        "#,
+    )
+    .bind(&symbols)
+    .bind(&closes)
+    .bind(&rsis)
+    .bind(&rvols)
+    .bind(&phases)
+    .bind(computed_at)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// Snapshots of `symbols`; symbols never computed are missing
+pub async fn get_indicator_snapshots(
+    pool: &PgPool,
+    symbols: &[String],
+) -> Result<Vec<IndicatorSnapshotRow>, sqlx::Error> {
+    sqlx::query_as::<_, IndicatorSnapshotRow>(
+        "SELECT * FROM indicator_snapshots WHERE symbol = ANY($1) ORDER BY symbol",
+    )
+    .bind(symbols)
+    .fetch_all(pool)
+    .await
+}
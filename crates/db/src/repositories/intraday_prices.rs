@@ -0,0 +1,96 @@
+//! Intraday price bar repository
+
+use crate::models::IntradayPriceRow;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+
+/// Intraday bar for insertion
+#[derive(Debug, Clone)]
+pub struct InsertIntradayBar {
+    pub time: DateTime<Utc>,
+    pub symbol: String,
+    pub interval: String,
+    pub open: Decimal,
+    pub high: Decimal,
+    pub low: Decimal,
+    pub close: Decimal,
+    pub volume: i64,
+    pub ticks: i32,
+}
+
+/// Insert a batch of bars in one statement, merging into bars already written
+///
+/// A bar written again for the same bucket, e.g. the rest of a bucket that
+/// was flushed at shutdown, covers later ticks: the stored open is kept, the
+/// range widened, the close replaced and volume and ticks added. Bars of
+/// symbols missing from `stocks` are skipped.
+pub async fn upsert_intraday_bars(
+    pool: &PgPool,
+    bars: &[InsertIntradayBar],
+) -> Result<u64, sqlx::Error> {
+    if bars.is_empty() {
+        return Ok(0);
+    }
+    let times: Vec<DateTime<Utc>> = bars.iter().map(|b| b.time).collect();
+    let symbols: Vec<&str> = bars.iter().map(|b| b.symbol.as_str()).collect();
+    let intervals: Vec<&str> = bars.iter().map(|b| b.interval.as_str()).collect();
+    let opens: Vec<Decimal> = bars.iter().map(|b| b.open).collect();
+    let highs: Vec<Decimal> = bars.iter().map(|b| b.high).collect();
+    let lows: Vec<Decimal> = bars.iter().map(|b| b.low).collect();
+    let closes: Vec<Decimal> = bars.iter().map(|b| b.close).collect();
+    let volumes: Vec<i64> = bars.iter().map(|b| b.volume).collect();
+    let ticks: Vec<i32> = bars.iter().map(|b| b.ticks).collect();
+
+    let result = sqlx::query(
+        r#"
+        INSERT INTO intraday_prices (time, symbol, interval, open, high, low, close, volume, ticks)
+        SELECT b.* FROM UNNEST(
+            $1::TIMESTAMPTZ[], $2::VARCHAR[], $3::VARCHAR[], $4::NUMERIC[], $5::NUMERIC[],
+            $6::NUMERIC[], $7::NUMERIC[], $8::BIGINT[], $9::INTEGER[]
+        ) AS b(time, symbol, interval, open, high, low, close, volume, ticks)
+        WHERE b.symbol IN (SELECT symbol FROM stocks)
+        ON CONFLICT (symbol, interval, time) DO UPDATE SET
+            high = GREATEST(intraday_prices.high, EXCLUDED.high),
+            low = LEAST(intraday_prices.low, EXCLUDED.low),
+            close = EXCLUDED.close,
+            volume = intraday_prices.volume + EXCLUDED.volume,
+            ticks = intraday_prices.ticks + EXCLUDED.ticks
+        "#,
+    )
+    .bind(&times)
+    .bind(&symbols)
+    .bind(&intervals)
+    .bind(&opens)
+    .bind(&highs)
+    .bind(&lows)
+    .bind(&closes)
+    .bind(&volumes)
+    .bind(&ticks)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// Bars of `symbol` at `interval` starting in `[from, to]`, oldest first
+pub async fn get_intraday_bars(
+    pool: &PgPool,
+    symbol: &str,
+    interval: &str,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Vec<IntradayPriceRow>, sqlx::Error> {
+    sqlx::query_as::<_, IntradayPriceRow>(
+        r#"
+        SELECT * FROM intraday_prices
+        WHERE symbol = $1 AND interval = $2 AND time >= $3 AND time <= $4
+        ORDER BY time
+        "#,
+    )
+    .bind(symbol)
+    .bind(interval)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await
+}
@@ -0,0 +1,69 @@
+//! Macro indicator repository
+
+use crate::models::MacroIndicatorRow;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+
+/// Store observations for one indicator, replacing values already stored for the same day
+///
+/// Returns the number of rows written.
+pub async fn upsert_macro_observations(
+    pool: &PgPool,
+    indicator: &str,
+    source: &str,
+    observations: &[(NaiveDate, Decimal)],
+) -> Result<u64, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let mut written = 0;
+
+    for (observed_on, value) in observations {
+        written += sqlx::query(
+            r#"
+            INSERT INTO macro_indicators (indicator, observed_on, value, source)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (indicator, observed_on) DO UPDATE SET
+                value = EXCLUDED.value,
+                source = EXCLUDED.source,
+                fetched_at = NOW()
+            "#,
+        )
+        .bind(indicator)
+        .bind(observed_on)
+        .bind(value)
+        .bind(source)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+    }
+
+    tx.commit().await?;
+    Ok(written)
+}
+
+/// Get an indicator's observations since `from`, oldest first
+///
+/// Includes the last observation before `from` so the value in effect at
+/// the start of the window is known for sparse series like the BI-Rate.
+pub async fn get_macro_series(
+    pool: &PgPool,
+    indicator: &str,
+    from: NaiveDate,
+) -> Result<Vec<MacroIndicatorRow>, sqlx::Error> {
+    sqlx::query_as::<_, MacroIndicatorRow>(
+        r#"
+        SELECT * FROM macro_indicators
+        WHERE indicator = $1
+          AND observed_on >= COALESCE(
+              (SELECT MAX(observed_on) FROM macro_indicators
+               WHERE indicator = $1 AND observed_on <= $2),
+              $2
+          )
+        ORDER BY observed_on
+        "#,
+    )
+    .bind(indicator)
+    .bind(from)
+    .fetch_all(pool)
+    .await
+}
@@ -0,0 +1,86 @@
+//! Database maintenance repository
+
+use crate::models::TableHealthRow;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+/// Tuple and vacuum/analyze statistics of every user table
+pub async fn get_table_health(pool: &PgPool) -> Result<Vec<TableHealthRow>, sqlx::Error> {
+    sqlx::query_as::<_, TableHealthRow>(
+        r#"
+        SELECT
+            schemaname AS schema_name,
+            relname AS table_name,
+            n_live_tup AS live_tuples,
+            n_dead_tup AS dead_tuples,
+            n_mod_since_analyze AS modified_since_analyze,
+            GREATEST(last_vacuum, last_autovacuum) AS last_vacuum,
+            GREATEST(last_analyze, last_autoanalyze) AS last_analyze
+        FROM pg_stat_user_tables
+        ORDER BY n_dead_tup DESC
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Refresh planner statistics of one table
+pub async fn analyze_table(pool: &PgPool, schema: &str, table: &str) -> Result<(), sqlx::Error> {
+    let sql = format!(
+        "ANALYZE {}.{}",
+        quote_identifier(schema),
+        quote_identifier(table)
+    );
+    sqlx::query(&sql).execute(pool).await?;
+    Ok(())
+}
+
+/// Qualified names of the TimescaleDB continuous aggregates
+pub async fn get_continuous_aggregates(pool: &PgPool) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar::<_, String>(
+        r#"
+        SELECT format('%I.%I', view_schema, view_name)
+        FROM timescaledb_information.continuous_aggregates
+        ORDER BY view_schema, view_name
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Materialize a continuous aggregate over `[start, end)`
+pub async fn refresh_continuous_aggregate(
+    pool: &PgPool,
+    view: &str,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("CALL refresh_continuous_aggregate($1::regclass, $2, $3)")
+        .bind(view)
+        .bind(start)
+        .bind(end)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Delete scores of inactive stocks and scores older than `before`
+///
+/// Inactive stocks would otherwise keep their last score in the rankings.
+pub async fn delete_stale_scores(pool: &PgPool, before: DateTime<Utc>) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        DELETE FROM stock_scores
+        WHERE time < $1
+           OR symbol IN (SELECT symbol FROM stocks WHERE is_active = false)
+        "#,
+    )
+    .bind(before)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
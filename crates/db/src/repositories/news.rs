@@ -0,0 +1,95 @@
+//! Stock news repository
+
+use crate::models::StockNewsRow;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+
+/// Headline to store against one stock
+#[derive(Debug, Clone)]
+pub struct InsertNews<'a> {
+    pub symbol: &'a str,
+    pub title: &'a str,
+    pub summary: Option<&'a str>,
+    pub source: &'a str,
+    pub url: &'a str,
+    pub published_at: DateTime<Utc>,
+    pub sentiment: &'a str,
+    pub sentiment_score: Decimal,
+}
+
+/// Store headlines, skipping articles already stored for the stock
+///
+/// Rows stored without a score (by the Python scraper) get this one.
+/// Returns the number of rows written.
+pub async fn insert_news(pool: &PgPool, news: &[InsertNews<'_>]) -> Result<u64, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let mut written = 0;
+
+    for item in news {
+        written += sqlx::query(
+            r#"
+            INSERT INTO stock_news
+                (symbol, title, summary, source, url, published_at, sentiment, sentiment_score)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT (symbol, url) DO UPDATE SET
+                sentiment = EXCLUDED.sentiment,
+                sentiment_score = EXCLUDED.sentiment_score
+            WHERE stock_news.sentiment_score IS NULL
+            "#,
+        )
+        .bind(item.symbol)
+        .bind(item.title)
+        .bind(item.summary)
+        .bind(item.source)
+        .bind(item.url)
+        .bind(item.published_at)
+        .bind(item.sentiment)
+        .bind(item.sentiment_score)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+    }
+
+    tx.commit().await?;
+    Ok(written)
+}
+
+/// Scored headlines for a stock published at or after `since`, newest first
+pub async fn get_scored_news_since(
+    pool: &PgPool,
+    symbol: &str,
+    since: DateTime<Utc>,
+) -> Result<Vec<StockNewsRow>, sqlx::Error> {
+    sqlx::query_as::<_, StockNewsRow>(
+        r#"
+        SELECT * FROM stock_news
+        WHERE symbol = $1 AND published_at >= $2 AND sentiment_score IS NOT NULL
+        ORDER BY published_at DESC
+        "#,
+    )
+    .bind(symbol)
+    .bind(since)
+    .fetch_all(pool)
+    .await
+}
+
+/// Latest headlines for a stock, newest first
+pub async fn get_recent_news(
+    pool: &PgPool,
+    symbol: &str,
+    limit: i64,
+) -> Result<Vec<StockNewsRow>, sqlx::Error> {
+    sqlx::query_as::<_, StockNewsRow>(
+        r#"
+        SELECT * FROM stock_news
+        WHERE symbol = $1
+        ORDER BY published_at DESC
+        LIMIT $2
+        "#,
+    )
+    .bind(symbol)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
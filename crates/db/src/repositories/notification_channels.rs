@@ -0,0 +1,66 @@
+//! Per-user notification channel repository
+
+use crate::models::UserNotificationChannelRow;
+use sqlx::PgPool;
+
+/// Channel addresses of `user_id`
+pub async fn get_user_notification_channels(
+    pool: &PgPool,
+    user_id: &str,
+) -> Result<Vec<UserNotificationChannelRow>, sqlx::Error> {
+    sqlx::query_as::<_, UserNotificationChannelRow>(
+        "SELECT * FROM user_notification_channels WHERE user_id = $1 ORDER BY channel",
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Channel addresses of every user
+pub async fn get_all_user_notification_channels(
+    pool: &PgPool,
+) -> Result<Vec<UserNotificationChannelRow>, sqlx::Error> {
+    sqlx::query_as::<_, UserNotificationChannelRow>(
+        "SELECT * FROM user_notification_channels ORDER BY user_id, channel",
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Set where a user receives a channel, replacing the previous address
+pub async fn upsert_user_notification_channel(
+    pool: &PgPool,
+    user_id: &str,
+    channel: &str,
+    recipient: &str,
+) -> Result<UserNotificationChannelRow, sqlx::Error> {
+    sqlx::query_as::<_, UserNotificationChannelRow>(
+        r#"
+        INSERT INTO user_notification_channels (user_id, channel, recipient)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (user_id, channel) DO UPDATE SET
+            recipient = EXCLUDED.recipient,
+            updated_at = NOW()
+        RETURNING *
+        "#,
+    )
+    .bind(user_id)
+    .bind(channel)
+    .bind(recipient)
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn delete_user_notification_channel(
+    pool: &PgPool,
+    user_id: &str,
+    channel: &str,
+) -> Result<bool, sqlx::Error> {
+    let result =
+        sqlx::query("DELETE FROM user_notification_channels WHERE user_id = $1 AND channel = $2")
+            .bind(user_id)
+            .bind(channel)
+            .execute(pool)
+            .await?;
+    Ok(result.rows_affected() > 0)
+}
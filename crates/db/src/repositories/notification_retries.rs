@@ -0,0 +1,151 @@
+//! Notification retry queue repository
+
+use crate::models::NotificationRetryRow;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+/// Failed delivery for insertion into the retry queue
+pub struct InsertNotificationRetry<'a> {
+    pub channel: &'a str,
+    pub recipient_id: &'a str,
+    pub payload: &'a serde_json::Value,
+    pub last_error: &'a str,
+    /// 'pending' to retry at `next_attempt_at`, 'dead' to give up
+    pub status: &'a str,
+    pub next_attempt_at: DateTime<Utc>,
+}
+
+pub async fn insert_notification_retry(
+    pool: &PgPool,
+    retry: &InsertNotificationRetry<'_>,
+) -> Result<NotificationRetryRow, sqlx::Error> {
+    sqlx::query_as::<_, NotificationRetryRow>(
+        r#"
+        INSERT INTO notification_retries
+            (channel, recipient_id, payload, last_error, status, next_attempt_at)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING *
+        "#,
+    )
+    .bind(retry.channel)
+    .bind(retry.recipient_id)
+    .bind(retry.payload)
+    .bind(retry.last_error)
+    .bind(retry.status)
+    .bind(retry.next_attempt_at)
+    .fetch_one(pool)
+    .await
+}
+
+/// Claim the `limit` pending retries longest due at `now`, by id
+///
+/// Claimed rows are moved to `lease_until`, so another worker polling before
+/// they are attempted passes them over; rows a concurrent claim has locked
+/// are skipped rather than waited on.
+pub async fn claim_due_notification_retries(
+    pool: &PgPool,
+    now: DateTime<Utc>,
+    lease_until: DateTime<Utc>,
+    limit: i64,
+) -> Result<Vec<NotificationRetryRow>, sqlx::Error> {
+    let mut rows = sqlx::query_as::<_, NotificationRetryRow>(
+        r#"
+        WITH due AS (
+            SELECT id FROM notification_retries
+            WHERE status = 'pending' AND next_attempt_at <= $1
+            ORDER BY next_attempt_at, id
+            LIMIT $3
+            FOR UPDATE SKIP LOCKED
+        )
+        UPDATE notification_retries r
+        SET next_attempt_at = $2
+        FROM due
+        WHERE r.id = due.id
+        RETURNING r.*
+        "#,
+    )
+    .bind(now)
+    .bind(lease_until)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+    rows.sort_by_key(|row| row.id);
+    Ok(rows)
+}
+
+/// Retries with `status`, most recently updated first
+pub async fn get_notification_retries(
+    pool: &PgPool,
+    status: &str,
+    limit: i64,
+) -> Result<Vec<NotificationRetryRow>, sqlx::Error> {
+    sqlx::query_as::<_, NotificationRetryRow>(
+        r#"
+        SELECT * FROM notification_retries
+        WHERE status = $1
+        ORDER BY updated_at DESC, id DESC
+        LIMIT $2
+        "#,
+    )
+    .bind(status)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn get_notification_retry(
+    pool: &PgPool,
+    id: i32,
+) -> Result<Option<NotificationRetryRow>, sqlx::Error> {
+    sqlx::query_as::<_, NotificationRetryRow>("SELECT * FROM notification_retries WHERE id = $1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Record another failed attempt; `status` 'dead' moves it to the dead letters
+pub async fn record_notification_retry_failure(
+    pool: &PgPool,
+    id: i32,
+    error: &str,
+    status: &str,
+    next_attempt_at: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE notification_retries
+        SET attempts = attempts + 1, last_error = $2, status = $3,
+            next_attempt_at = $4, updated_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(id)
+    .bind(error)
+    .bind(status)
+    .bind(next_attempt_at)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Remove dead letters last updated before `before`
+pub async fn purge_dead_notification_retries(
+    pool: &PgPool,
+    before: DateTime<Utc>,
+) -> Result<u64, sqlx::Error> {
+    let result =
+        sqlx::query("DELETE FROM notification_retries WHERE status = 'dead' AND updated_at < $1")
+            .bind(before)
+            .execute(pool)
+            .await?;
+    Ok(result.rows_affected())
+}
+
+/// Remove a delivered notification from the queue
+pub async fn delete_notification_retry(pool: &PgPool, id: i32) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM notification_retries WHERE id = $1")
+        .bind(id)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
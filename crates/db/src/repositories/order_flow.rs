@@ -0,0 +1,161 @@
+//! Daily order flow and order book snapshot repository
+
+use crate::models::{OrderBookSnapshotRow, OrderFlowDailyRow};
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+
+/// Order flow proxies of one trading day
+#[derive(Debug, Clone)]
+pub struct OrderFlowObservation {
+    pub trade_date: NaiveDate,
+    pub obi: Decimal,
+    pub ofi: Decimal,
+    pub ofi_zscore: Option<Decimal>,
+    /// `proxy` or `depth`
+    pub source: &'static str,
+}
+
+/// Order flow read from the daily bar
+pub const SOURCE_PROXY: &str = "proxy";
+/// Order flow read from order book snapshots
+pub const SOURCE_DEPTH: &str = "depth";
+
+/// Store a symbol's daily order flow, replacing days already stored
+///
+/// A proxy never replaces a depth-sourced day. Returns the number of rows
+/// written.
+pub async fn upsert_order_flow(
+    pool: &PgPool,
+    symbol: &str,
+    observations: &[OrderFlowObservation],
+) -> Result<u64, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let mut written = 0;
+
+    for observation in observations {
+        written += sqlx::query(
+            r#"
+            INSERT INTO order_flow_daily (symbol, trade_date, obi, ofi, ofi_zscore, source)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (symbol, trade_date) DO UPDATE SET
+                obi = EXCLUDED.obi,
+                ofi = EXCLUDED.ofi,
+                ofi_zscore = EXCLUDED.ofi_zscore,
+                source = EXCLUDED.source,
+                computed_at = NOW()
+            WHERE order_flow_daily.source = 'proxy' OR EXCLUDED.source = 'depth'
+            "#,
+        )
+        .bind(symbol)
+        .bind(observation.trade_date)
+        .bind(observation.obi)
+        .bind(observation.ofi)
+        .bind(observation.ofi_zscore)
+        .bind(observation.source)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+    }
+
+    tx.commit().await?;
+    Ok(written)
+}
+
+/// Get a symbol's daily order flow since `from`, oldest first
+pub async fn get_order_flow_history(
+    pool: &PgPool,
+    symbol: &str,
+    from: NaiveDate,
+) -> Result<Vec<OrderFlowDailyRow>, sqlx::Error> {
+    sqlx::query_as::<_, OrderFlowDailyRow>(
+        r#"
+        SELECT * FROM order_flow_daily
+        WHERE symbol = $1 AND trade_date >= $2
+        ORDER BY trade_date
+        "#,
+    )
+    .bind(symbol)
+    .bind(from)
+    .fetch_all(pool)
+    .await
+}
+
+/// Add a depth snapshot's OFI to a symbol's session, storing its OBI
+///
+/// `ofi` is the change since the previous snapshot; it is summed into a
+/// depth-sourced day, so the session total survives a restart, and
+/// replaces a proxy day. The z-score is kept and refreshed with the next
+/// score computation.
+pub async fn upsert_depth_order_flow(
+    pool: &PgPool,
+    symbol: &str,
+    trade_date: NaiveDate,
+    obi: Decimal,
+    ofi: Decimal,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO order_flow_daily (symbol, trade_date, obi, ofi, source)
+        VALUES ($1, $2, $3, $4, 'depth')
+        ON CONFLICT (symbol, trade_date) DO UPDATE SET
+            obi = EXCLUDED.obi,
+            ofi = CASE
+                WHEN order_flow_daily.source = 'depth' THEN order_flow_daily.ofi + EXCLUDED.ofi
+                ELSE EXCLUDED.ofi
+            END,
+            source = 'depth',
+            computed_at = NOW()
+        "#,
+    )
+    .bind(symbol)
+    .bind(trade_date)
+    .bind(obi)
+    .bind(ofi)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Store an order book snapshot
+pub async fn insert_order_book_snapshot(
+    pool: &PgPool,
+    snapshot: &OrderBookSnapshotRow,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO order_book_snapshots (time, symbol, bids, asks, obi, ofi)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        "#,
+    )
+    .bind(snapshot.time)
+    .bind(&snapshot.symbol)
+    .bind(&snapshot.bids)
+    .bind(&snapshot.asks)
+    .bind(snapshot.obi)
+    .bind(snapshot.ofi)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Order book snapshots of `symbol` in `[from, to]`, oldest first
+pub async fn get_order_book_snapshots(
+    pool: &PgPool,
+    symbol: &str,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Vec<OrderBookSnapshotRow>, sqlx::Error> {
+    sqlx::query_as::<_, OrderBookSnapshotRow>(
+        r#"
+        SELECT * FROM order_book_snapshots
+        WHERE symbol = $1 AND time >= $2 AND time <= $3
+        ORDER BY time
+        "#,
+    )
+    .bind(symbol)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await
+}
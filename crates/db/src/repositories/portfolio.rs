@@ -0,0 +1,189 @@
+//! Portfolio repository
+
+use crate::models::{PortfolioHoldingRow, PortfolioTransactionRow};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sqlx::{PgConnection, PgExecutor, PgPool};
+
+/// Portfolio transaction for insertion or update
+pub struct InsertPortfolioTransaction<'a> {
+    pub user_id: &'a str,
+    pub symbol: &'a str,
+    /// 'buy' or 'sell'
+    pub side: &'a str,
+    pub lots: i64,
+    pub price: Decimal,
+    pub fees: Decimal,
+    pub executed_at: DateTime<Utc>,
+    pub note: Option<&'a str>,
+}
+
+/// Holding replayed from the transactions
+pub struct InsertPortfolioHolding<'a> {
+    pub symbol: &'a str,
+    pub shares: i64,
+    pub average_cost: Decimal,
+    pub cost_basis: Decimal,
+    pub realized_pnl: Decimal,
+}
+
+/// Transactions of `user_id`, oldest first
+pub async fn get_portfolio_transactions<'e>(
+    executor: impl PgExecutor<'e>,
+    user_id: &str,
+    symbol: Option<&str>,
+) -> Result<Vec<PortfolioTransactionRow>, sqlx::Error> {
+    sqlx::query_as::<_, PortfolioTransactionRow>(
+        r#"
+        SELECT * FROM portfolio_transactions
+        WHERE user_id = $1 AND ($2::text IS NULL OR symbol = $2)
+        ORDER BY executed_at, id
+        "#,
+    )
+    .bind(user_id)
+    .bind(symbol)
+    .fetch_all(executor)
+    .await
+}
+
+pub async fn get_portfolio_transaction(
+    pool: &PgPool,
+    user_id: &str,
+    id: i32,
+) -> Result<Option<PortfolioTransactionRow>, sqlx::Error> {
+    sqlx::query_as::<_, PortfolioTransactionRow>(
+        "SELECT * FROM portfolio_transactions WHERE user_id = $1 AND id = $2",
+    )
+    .bind(user_id)
+    .bind(id)
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn insert_portfolio_transaction<'e>(
+    executor: impl PgExecutor<'e>,
+    transaction: &InsertPortfolioTransaction<'_>,
+) -> Result<PortfolioTransactionRow, sqlx::Error> {
+    sqlx::query_as::<_, PortfolioTransactionRow>(
+        r#"
+        INSERT INTO portfolio_transactions
+            (user_id, symbol, side, lots, price, fees, executed_at, note)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        RETURNING *
+        "#,
+    )
+    .bind(transaction.user_id)
+    .bind(transaction.symbol)
+    .bind(transaction.side)
+    .bind(transaction.lots)
+    .bind(transaction.price)
+    .bind(transaction.fees)
+    .bind(transaction.executed_at)
+    .bind(transaction.note)
+    .fetch_one(executor)
+    .await
+}
+
+/// Replace a transaction's fields; `None` when it does not exist
+pub async fn update_portfolio_transaction<'e>(
+    executor: impl PgExecutor<'e>,
+    id: i32,
+    transaction: &InsertPortfolioTransaction<'_>,
+) -> Result<Option<PortfolioTransactionRow>, sqlx::Error> {
+    sqlx::query_as::<_, PortfolioTransactionRow>(
+        r#"
+        UPDATE portfolio_transactions SET
+            symbol = $3,
+            side = $4,
+            lots = $5,
+            price = $6,
+            fees = $7,
+            executed_at = $8,
+            note = $9,
+            updated_at = NOW()
+        WHERE id = $1 AND user_id = $2
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .bind(transaction.user_id)
+    .bind(transaction.symbol)
+    .bind(transaction.side)
+    .bind(transaction.lots)
+    .bind(transaction.price)
+    .bind(transaction.fees)
+    .bind(transaction.executed_at)
+    .bind(transaction.note)
+    .fetch_optional(executor)
+    .await
+}
+
+pub async fn delete_portfolio_transaction<'e>(
+    executor: impl PgExecutor<'e>,
+    user_id: &str,
+    id: i32,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM portfolio_transactions WHERE user_id = $1 AND id = $2")
+        .bind(user_id)
+        .bind(id)
+        .execute(executor)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Holdings of `user_id`, by symbol
+pub async fn get_portfolio_holdings(
+    pool: &PgPool,
+    user_id: &str,
+) -> Result<Vec<PortfolioHoldingRow>, sqlx::Error> {
+    sqlx::query_as::<_, PortfolioHoldingRow>(
+        "SELECT * FROM portfolio_holdings WHERE user_id = $1 ORDER BY symbol",
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Replace all holdings of `user_id`
+///
+/// Runs on the caller's connection, so the holdings can be written in the
+/// same transaction as the change they replay.
+pub async fn replace_portfolio_holdings(
+    conn: &mut PgConnection,
+    user_id: &str,
+    holdings: &[InsertPortfolioHolding<'_>],
+) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM portfolio_holdings WHERE user_id = $1")
+        .bind(user_id)
+        .execute(&mut *conn)
+        .await?;
+
+    for holding in holdings {
+        sqlx::query(
+            r#"
+            INSERT INTO portfolio_holdings
+                (user_id, symbol, shares, average_cost, cost_basis, realized_pnl)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+        .bind(user_id)
+        .bind(holding.symbol)
+        .bind(holding.shares)
+        .bind(holding.average_cost)
+        .bind(holding.cost_basis)
+        .bind(holding.realized_pnl)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Serialize portfolio changes of `user_id` until the transaction ends
+pub async fn lock_portfolio(conn: &mut PgConnection, user_id: &str) -> Result<(), sqlx::Error> {
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtext('portfolio:' || $1))")
+        .bind(user_id)
+        .execute(conn)
+        .await?;
+    Ok(())
+}
@@ -1,9 +1,10 @@
 //! Price data repository
 
 use crate::models::StockPriceRow;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use rust_decimal::Decimal;
 use sqlx::PgPool;
+use std::collections::BTreeSet;
 
 /// Price data for insertion
 pub struct InsertPrice<'a> {
@@ -16,6 +17,31 @@ pub struct InsertPrice<'a> {
     pub volume: i64,
 }
 
+/// Row counts from a price upsert
+#[derive(Debug, Clone, Default)]
+pub struct PriceUpsert {
+    pub inserted: u64,
+    pub replaced: u64,
+    /// Rows that already existed for the day and were left untouched
+    pub duplicates: u64,
+    /// Symbols with at least one inserted or replaced row
+    pub written: BTreeSet<String>,
+}
+
+/// Time of the newest price bar or broker summary across all symbols
+pub async fn get_latest_data_time(pool: &PgPool) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+    sqlx::query_scalar::<_, Option<DateTime<Utc>>>(
+        r#"
+        SELECT GREATEST(
+            (SELECT MAX(time) FROM stock_prices),
+            (SELECT MAX(time) FROM broker_summary)
+        )
+        "#,
+    )
+    .fetch_one(pool)
+    .await
+}
+
 /// Get latest price for a stock
 pub async fn get_latest_price(
     pool: &PgPool,
@@ -29,6 +55,21 @@ pub async fn get_latest_price(
     .await
 }
 
+/// Get the last price at or before `at`
+pub async fn get_price_as_of(
+    pool: &PgPool,
+    symbol: &str,
+    at: DateTime<Utc>,
+) -> Result<Option<StockPriceRow>, sqlx::Error> {
+    sqlx::query_as::<_, StockPriceRow>(
+        "SELECT * FROM stock_prices WHERE symbol = $1 AND time <= $2 ORDER BY time DESC LIMIT 1",
+    )
+    .bind(symbol)
+    .bind(at)
+    .fetch_optional(pool)
+    .await
+}
+
 /// Get price history for a stock
 pub async fn get_price_history(
     pool: &PgPool,
@@ -46,6 +87,197 @@ pub async fn get_price_history(
     .await
 }
 
+/// A daily bar read as floats, for in-memory columns
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct FloatPriceRow {
+    pub time: DateTime<Utc>,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: i64,
+}
+
+/// [`get_price_history`] with prices cast to `float8` by Postgres
+pub async fn get_float_price_history(
+    pool: &PgPool,
+    symbol: &str,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Vec<FloatPriceRow>, sqlx::Error> {
+    sqlx::query_as::<_, FloatPriceRow>(
+        r#"
+        SELECT time, open::float8 AS open, high::float8 AS high, low::float8 AS low,
+            close::float8 AS close, volume
+        FROM stock_prices
+        WHERE symbol = $1 AND time >= $2 AND time <= $3
+        ORDER BY time
+        "#,
+    )
+    .bind(symbol)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await
+}
+
+/// Price histories of several stocks in one query, ordered by symbol then time
+pub async fn get_price_histories(
+    pool: &PgPool,
+    symbols: &[String],
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Vec<StockPriceRow>, sqlx::Error> {
+    sqlx::query_as::<_, StockPriceRow>(
+        r#"
+        SELECT * FROM stock_prices
+        WHERE symbol = ANY($1) AND time >= $2 AND time <= $3
+        ORDER BY symbol, time
+        "#,
+    )
+    .bind(symbols)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await
+}
+
+/// Dates with price data for a stock, oldest first
+pub async fn get_price_days(
+    pool: &PgPool,
+    symbol: &str,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Vec<NaiveDate>, sqlx::Error> {
+    sqlx::query_scalar::<_, NaiveDate>(
+        r#"
+        SELECT DISTINCT time::date AS day
+        FROM stock_prices
+        WHERE symbol = $1 AND time >= $2 AND time <= $3
+        ORDER BY day
+        "#,
+    )
+    .bind(symbol)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await
+}
+
+/// Symbols ordered by average daily traded value (close × volume) since
+/// `from`, most liquid first
+pub async fn get_symbols_by_traded_value(
+    pool: &PgPool,
+    from: DateTime<Utc>,
+) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar::<_, String>(
+        r#"
+        SELECT symbol
+        FROM stock_prices
+        WHERE time >= $1
+        GROUP BY symbol
+        ORDER BY AVG(close * volume) DESC
+        "#,
+    )
+    .bind(from)
+    .fetch_all(pool)
+    .await
+}
+
+/// Symbols whose average daily traded value since `from` is at least
+/// `min_value`, most liquid first
+pub async fn get_liquid_symbols(
+    pool: &PgPool,
+    from: DateTime<Utc>,
+    min_value: Decimal,
+) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar::<_, String>(
+        r#"
+        SELECT symbol
+        FROM stock_prices
+        WHERE time >= $1
+        GROUP BY symbol
+        HAVING AVG(close * volume) >= $2
+        ORDER BY AVG(close * volume) DESC
+        "#,
+    )
+    .bind(from)
+    .bind(min_value)
+    .fetch_all(pool)
+    .await
+}
+
+/// Average daily traded value (close × volume) of `symbols` since `from`
+pub async fn get_average_traded_values(
+    pool: &PgPool,
+    symbols: &[String],
+    from: DateTime<Utc>,
+) -> Result<Vec<(String, Decimal)>, sqlx::Error> {
+    sqlx::query_as::<_, (String, Decimal)>(
+        r#"
+        SELECT symbol, AVG(close * volume)
+        FROM stock_prices
+        WHERE symbol = ANY($1) AND time >= $2
+        GROUP BY symbol
+        "#,
+    )
+    .bind(symbols)
+    .bind(from)
+    .fetch_all(pool)
+    .await
+}
+
+/// Closes of every symbol since `from`, by symbol then oldest first
+pub async fn get_universe_closes(
+    pool: &PgPool,
+    from: DateTime<Utc>,
+) -> Result<Vec<(String, Decimal)>, sqlx::Error> {
+    sqlx::query_as::<_, (String, Decimal)>(
+        r#"
+        SELECT symbol, close
+        FROM stock_prices
+        WHERE time >= $1
+        ORDER BY symbol, time
+        "#,
+    )
+    .bind(from)
+    .fetch_all(pool)
+    .await
+}
+
+/// Daily bars of every symbol since `from`, by symbol then oldest first
+pub async fn get_universe_prices(
+    pool: &PgPool,
+    from: DateTime<Utc>,
+) -> Result<Vec<StockPriceRow>, sqlx::Error> {
+    sqlx::query_as::<_, StockPriceRow>(
+        "SELECT * FROM stock_prices WHERE time >= $1 ORDER BY symbol, time",
+    )
+    .bind(from)
+    .fetch_all(pool)
+    .await
+}
+
+/// Closes of every active stock with a sector since `from`, by sector,
+/// symbol, then oldest first
+pub async fn get_sector_closes(
+    pool: &PgPool,
+    from: DateTime<Utc>,
+) -> Result<Vec<(String, String, DateTime<Utc>, Decimal)>, sqlx::Error> {
+    sqlx::query_as::<_, (String, String, DateTime<Utc>, Decimal)>(
+        r#"
+        SELECT s.sector, p.symbol, p.time, p.close
+        FROM stock_prices p
+        JOIN stocks s ON s.symbol = p.symbol
+        WHERE p.time >= $1 AND s.is_active AND s.sector IS NOT NULL
+        ORDER BY s.sector, p.symbol, p.time
+        "#,
+    )
+    .bind(from)
+    .fetch_all(pool)
+    .await
+}
+
 /// Insert price data
 pub async fn insert_price(pool: &PgPool, price: &InsertPrice<'_>) -> Result<(), sqlx::Error> {
     sqlx::query(
@@ -66,3 +298,66 @@ pub async fn insert_price(pool: &PgPool, price: &InsertPrice<'_>) -> Result<(),
     .await?;
     Ok(())
 }
+
+/// Insert daily prices, detecting rows that already exist for the same symbol and day
+///
+/// Existing days are replaced when `overwrite` is set and counted as
+/// duplicates otherwise.
+pub async fn upsert_prices(
+    pool: &PgPool,
+    prices: &[InsertPrice<'_>],
+    overwrite: bool,
+) -> Result<PriceUpsert, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let mut counts = PriceUpsert::default();
+
+    for price in prices {
+        let exists = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM stock_prices WHERE symbol = $1 AND time::date = $2::date)",
+        )
+        .bind(price.symbol)
+        .bind(price.time)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if exists {
+            if !overwrite {
+                counts.duplicates += 1;
+                continue;
+            }
+            sqlx::query("DELETE FROM stock_prices WHERE symbol = $1 AND time::date = $2::date")
+                .bind(price.symbol)
+                .bind(price.time)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO stock_prices (time, symbol, open, high, low, close, volume)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(price.time)
+        .bind(price.symbol)
+        .bind(price.open)
+        .bind(price.high)
+        .bind(price.low)
+        .bind(price.close)
+        .bind(price.volume)
+        .execute(&mut *tx)
+        .await?;
+
+        if exists {
+            counts.replaced += 1;
+        } else {
+            counts.inserted += 1;
+        }
+        if !counts.written.contains(price.symbol) {
+            counts.written.insert(price.symbol.to_string());
+        }
+    }
+
+    tx.commit().await?;
+    Ok(counts)
+}
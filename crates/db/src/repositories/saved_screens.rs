@@ -0,0 +1,118 @@
+//! Saved screen repository
+
+use crate::models::SavedScreenRow;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+/// Saved screen for insertion or update
+pub struct InsertSavedScreen<'a> {
+    pub owner: &'a str,
+    pub name: &'a str,
+    pub filter: &'a serde_json::Value,
+    pub alerts_enabled: bool,
+}
+
+/// Screens saved by `owner`, oldest first
+pub async fn get_saved_screens(
+    pool: &PgPool,
+    owner: &str,
+) -> Result<Vec<SavedScreenRow>, sqlx::Error> {
+    sqlx::query_as::<_, SavedScreenRow>("SELECT * FROM saved_screens WHERE owner = $1 ORDER BY id")
+        .bind(owner)
+        .fetch_all(pool)
+        .await
+}
+
+pub async fn get_saved_screen(
+    pool: &PgPool,
+    owner: &str,
+    id: i32,
+) -> Result<Option<SavedScreenRow>, sqlx::Error> {
+    sqlx::query_as::<_, SavedScreenRow>("SELECT * FROM saved_screens WHERE id = $1 AND owner = $2")
+        .bind(id)
+        .bind(owner)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Screens of every user with alerts enabled
+pub async fn get_alerting_saved_screens(pool: &PgPool) -> Result<Vec<SavedScreenRow>, sqlx::Error> {
+    sqlx::query_as::<_, SavedScreenRow>(
+        "SELECT * FROM saved_screens WHERE alerts_enabled ORDER BY id",
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Insert a screen with the result set it has now
+pub async fn insert_saved_screen(
+    pool: &PgPool,
+    screen: &InsertSavedScreen<'_>,
+    symbols: &[String],
+) -> Result<SavedScreenRow, sqlx::Error> {
+    sqlx::query_as::<_, SavedScreenRow>(
+        r#"
+        INSERT INTO saved_screens (owner, name, filter, alerts_enabled, last_symbols, last_run_at)
+        VALUES ($1, $2, $3, $4, $5, NOW())
+        RETURNING *
+        "#,
+    )
+    .bind(screen.owner)
+    .bind(screen.name)
+    .bind(screen.filter)
+    .bind(screen.alerts_enabled)
+    .bind(symbols)
+    .fetch_one(pool)
+    .await
+}
+
+/// Replace a screen's fields and result set; None if it does not belong to the owner
+pub async fn update_saved_screen(
+    pool: &PgPool,
+    id: i32,
+    screen: &InsertSavedScreen<'_>,
+    symbols: &[String],
+) -> Result<Option<SavedScreenRow>, sqlx::Error> {
+    sqlx::query_as::<_, SavedScreenRow>(
+        r#"
+        UPDATE saved_screens
+        SET name = $3, filter = $4, alerts_enabled = $5, last_symbols = $6,
+            last_run_at = NOW(), updated_at = NOW()
+        WHERE id = $1 AND owner = $2
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .bind(screen.owner)
+    .bind(screen.name)
+    .bind(screen.filter)
+    .bind(screen.alerts_enabled)
+    .bind(symbols)
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn delete_saved_screen(pool: &PgPool, owner: &str, id: i32) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM saved_screens WHERE id = $1 AND owner = $2")
+        .bind(id)
+        .bind(owner)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Store the result set of a scheduled run
+pub async fn record_saved_screen_run(
+    pool: &PgPool,
+    id: i32,
+    symbols: &[String],
+    run_at: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE saved_screens SET last_symbols = $2, last_run_at = $3 WHERE id = $1")
+        .bind(id)
+        .bind(symbols)
+        .bind(run_at)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
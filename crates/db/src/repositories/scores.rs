@@ -1,7 +1,7 @@
 //! Score repository
 
-use crate::models::StockScoreRow;
-use chrono::{DateTime, Utc};
+use crate::models::{FundamentalScoreHistoryRow, ScoreInputsRow, StockScoreRow};
+use chrono::{DateTime, NaiveDate, Utc};
 use rust_decimal::Decimal;
 use sqlx::PgPool;
 
@@ -19,6 +19,19 @@ pub struct InsertStockScore {
     pub fundamental_breakdown: Option<serde_json::Value>,
     pub sentiment_breakdown: Option<serde_json::Value>,
     pub ml_breakdown: Option<serde_json::Value>,
+    pub input_hash: Option<String>,
+}
+
+/// Historical fundamental score for insertion
+#[derive(Debug, Clone)]
+pub struct InsertFundamentalScoreHistory<'a> {
+    pub symbol: &'a str,
+    pub as_of: NaiveDate,
+    pub period_end: NaiveDate,
+    pub version: i32,
+    pub report_date: NaiveDate,
+    pub fundamental_score: Decimal,
+    pub fundamental_breakdown: Option<serde_json::Value>,
 }
 
 /// Get latest scores for all stocks
@@ -56,6 +69,79 @@ pub async fn get_stock_score(
     .await
 }
 
+/// Scoring windows the inputs of [`get_score_inputs`] are read over
+#[derive(Debug, Clone, Copy)]
+pub struct ScoreInputWindows {
+    /// Financials are those published by this date
+    pub as_of: NaiveDate,
+    /// Headlines since this time feed the sentiment
+    pub news_since: DateTime<Utc>,
+    /// Broker summaries since this time feed the flow
+    pub broker_since: DateTime<Utc>,
+}
+
+/// Latest input timestamps of each of `symbols`, with the report date of
+/// the financials published by `as_of`, a checksum of the scored headlines
+/// and the oldest broker summary within their windows, and the sector
+pub async fn get_score_inputs(
+    pool: &PgPool,
+    symbols: &[String],
+    windows: ScoreInputWindows,
+) -> Result<Vec<ScoreInputsRow>, sqlx::Error> {
+    sqlx::query_as::<_, ScoreInputsRow>(
+        r#"
+        SELECT s.symbol,
+            (SELECT MAX(time) FROM stock_prices p WHERE p.symbol = s.symbol) AS price_time,
+            (SELECT MAX(time) FROM broker_summary b WHERE b.symbol = s.symbol) AS broker_time,
+            GREATEST(
+                (SELECT MAX(recorded_at) FROM financials_versions f WHERE f.symbol = s.symbol),
+                (SELECT MAX(updated_at) FROM income_statements i WHERE i.symbol = s.symbol),
+                (SELECT MAX(updated_at) FROM balance_sheets bs WHERE bs.symbol = s.symbol),
+                (SELECT MAX(updated_at) FROM cash_flow_statements c WHERE c.symbol = s.symbol)
+            ) AS financials_time,
+            (SELECT MAX(published_at) FROM stock_news n WHERE n.symbol = s.symbol) AS news_time,
+            (SELECT f.report_date FROM financials_versions f
+             WHERE f.symbol = s.symbol AND f.report_date <= $2
+             ORDER BY f.period_end DESC, f.version DESC LIMIT 1) AS financials_report_date,
+            (SELECT md5(string_agg(n.url || ':' || COALESCE(n.sentiment_score::TEXT, ''),
+                                   ',' ORDER BY n.url))
+             FROM stock_news n
+             WHERE n.symbol = s.symbol AND n.published_at >= $3) AS news_digest,
+            (SELECT MIN(time) FROM broker_summary b
+             WHERE b.symbol = s.symbol AND b.time >= $4) AS broker_window_start,
+            s.sector,
+            s.subsector
+        FROM stocks s
+        WHERE s.symbol = ANY($1)
+        "#,
+    )
+    .bind(symbols)
+    .bind(windows.as_of)
+    .bind(windows.news_since)
+    .bind(windows.broker_since)
+    .fetch_all(pool)
+    .await
+}
+
+/// Input fingerprint of each symbol's latest score; None for snapshots
+/// stored without one
+pub async fn get_latest_input_hashes(
+    pool: &PgPool,
+    symbols: &[String],
+) -> Result<Vec<(String, Option<String>)>, sqlx::Error> {
+    sqlx::query_as::<_, (String, Option<String>)>(
+        r#"
+        SELECT DISTINCT ON (symbol) symbol, input_hash
+        FROM stock_scores
+        WHERE symbol = ANY($1)
+        ORDER BY symbol, time DESC
+        "#,
+    )
+    .bind(symbols)
+    .fetch_all(pool)
+    .await
+}
+
 /// Insert a computed score snapshot
 pub async fn insert_stock_score(
     pool: &PgPool,
@@ -74,9 +160,10 @@ pub async fn insert_stock_score(
             technical_breakdown,
             fundamental_breakdown,
             sentiment_breakdown,
-            ml_breakdown
+            ml_breakdown,
+            input_hash
         )
-        VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11)
+        VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11,$12)
         RETURNING *
         "#,
     )
@@ -91,6 +178,108 @@ pub async fn insert_stock_score(
     .bind(score.fundamental_breakdown.clone())
     .bind(score.sentiment_breakdown.clone())
     .bind(score.ml_breakdown.clone())
+    .bind(score.input_hash.as_deref())
+    .fetch_one(pool)
+    .await
+}
+
+/// Replace the technical and composite score of a stored snapshot
+pub async fn update_technical_score(
+    pool: &PgPool,
+    symbol: &str,
+    time: DateTime<Utc>,
+    technical_score: Decimal,
+    composite_score: Decimal,
+    technical_breakdown: Option<serde_json::Value>,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE stock_scores
+        SET technical_score = $3, composite_score = $4, technical_breakdown = $5
+        WHERE symbol = $1 AND time = $2
+        "#,
+    )
+    .bind(symbol)
+    .bind(time)
+    .bind(technical_score)
+    .bind(composite_score)
+    .bind(technical_breakdown)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// Get score snapshots for a stock within a time range, oldest first
+pub async fn get_score_history(
+    pool: &PgPool,
+    symbol: &str,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Vec<StockScoreRow>, sqlx::Error> {
+    sqlx::query_as::<_, StockScoreRow>(
+        r#"
+        SELECT * FROM stock_scores
+        WHERE symbol = $1 AND time >= $2 AND time <= $3
+        ORDER BY time ASC
+        "#,
+    )
+    .bind(symbol)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await
+}
+
+/// Get quarterly fundamental scores for a stock within a date range, oldest first
+pub async fn get_fundamental_score_history(
+    pool: &PgPool,
+    symbol: &str,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<FundamentalScoreHistoryRow>, sqlx::Error> {
+    sqlx::query_as::<_, FundamentalScoreHistoryRow>(
+        r#"
+        SELECT * FROM fundamental_score_history
+        WHERE symbol = $1 AND as_of >= $2 AND as_of <= $3
+        ORDER BY as_of ASC
+        "#,
+    )
+    .bind(symbol)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await
+}
+
+/// Insert or replace the fundamental score for a stock at a quarter end
+pub async fn upsert_fundamental_score_history(
+    pool: &PgPool,
+    score: &InsertFundamentalScoreHistory<'_>,
+) -> Result<FundamentalScoreHistoryRow, sqlx::Error> {
+    sqlx::query_as::<_, FundamentalScoreHistoryRow>(
+        r#"
+        INSERT INTO fundamental_score_history (
+            symbol, as_of, period_end, version, report_date,
+            fundamental_score, fundamental_breakdown
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ON CONFLICT (symbol, as_of) DO UPDATE SET
+            period_end = EXCLUDED.period_end,
+            version = EXCLUDED.version,
+            report_date = EXCLUDED.report_date,
+            fundamental_score = EXCLUDED.fundamental_score,
+            fundamental_breakdown = EXCLUDED.fundamental_breakdown,
+            computed_at = NOW()
+        RETURNING *
+        "#,
+    )
+    .bind(score.symbol)
+    .bind(score.as_of)
+    .bind(score.period_end)
+    .bind(score.version)
+    .bind(score.report_date)
+    .bind(score.fundamental_score)
+    .bind(score.fundamental_breakdown.clone())
     .fetch_one(pool)
     .await
 }
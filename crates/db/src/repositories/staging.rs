@@ -0,0 +1,368 @@
+//! Scraper staging repository
+//!
+//! Scraped data is written to staging tables first, reviewed via a diff
+//! against the live tables, and promoted once an admin approves it. The
+//! Python broker scraper stages broker summaries itself; shareholdings are
+//! staged by the API's ingest job.
+
+use crate::models::StagingBatchRow;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sqlx::{FromRow, PgPool};
+
+/// Live dataset a staging batch targets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StagingDataset {
+    BrokerSummary,
+    Shareholdings,
+}
+
+impl StagingDataset {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::BrokerSummary => "broker_summary",
+            Self::Shareholdings => "shareholdings",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "broker_summary" => Some(Self::BrokerSummary),
+            "shareholdings" => Some(Self::Shareholdings),
+            _ => None,
+        }
+    }
+}
+
+/// Shareholding row for staging
+#[derive(Debug, Clone)]
+pub struct StagedShareholding {
+    pub symbol: String,
+    pub reported_date: NaiveDate,
+    pub shareholder_name: String,
+    pub shareholder_type: Option<String>,
+    pub shares_held: i64,
+    pub percentage: Decimal,
+    pub change_shares: i64,
+    pub change_percentage: Decimal,
+}
+
+/// Staged-vs-live comparison for one symbol and period
+///
+/// Totals are net value for broker summaries and held percentage for
+/// shareholdings.
+#[derive(Debug, Clone, FromRow)]
+pub struct StagingDiffRow {
+    pub symbol: String,
+    pub period: NaiveDate,
+    pub staged_rows: i64,
+    pub live_rows: i64,
+    pub added: i64,
+    pub removed: i64,
+    pub changed: i64,
+    pub staged_total: Decimal,
+    pub live_total: Decimal,
+}
+
+/// Create a new pending staging batch
+pub async fn create_staging_batch(
+    pool: &PgPool,
+    source_id: &str,
+    dataset: StagingDataset,
+) -> Result<StagingBatchRow, sqlx::Error> {
+    sqlx::query_as::<_, StagingBatchRow>(
+        "INSERT INTO staging_batches (source_id, dataset) VALUES ($1, $2) RETURNING *",
+    )
+    .bind(source_id)
+    .bind(dataset.as_str())
+    .fetch_one(pool)
+    .await
+}
+
+/// Add shareholding rows to a staging batch
+pub async fn insert_staged_shareholdings(
+    pool: &PgPool,
+    batch_id: i32,
+    rows: &[StagedShareholding],
+) -> Result<u64, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    for row in rows {
+        sqlx::query(
+            r#"
+            INSERT INTO staging_shareholdings (
+                batch_id, symbol, reported_date, shareholder_name, shareholder_type,
+                shares_held, percentage, change_shares, change_percentage
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            "#,
+        )
+        .bind(batch_id)
+        .bind(&row.symbol)
+        .bind(row.reported_date)
+        .bind(&row.shareholder_name)
+        .bind(&row.shareholder_type)
+        .bind(row.shares_held)
+        .bind(row.percentage)
+        .bind(row.change_shares)
+        .bind(row.change_percentage)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    sqlx::query("UPDATE staging_batches SET row_count = row_count + $2 WHERE id = $1")
+        .bind(batch_id)
+        .bind(rows.len() as i32)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(rows.len() as u64)
+}
+
+/// List staging batches, optionally filtered by status
+pub async fn get_staging_batches(
+    pool: &PgPool,
+    status: Option<&str>,
+    limit: i64,
+) -> Result<Vec<StagingBatchRow>, sqlx::Error> {
+    sqlx::query_as::<_, StagingBatchRow>(
+        r#"
+        SELECT * FROM staging_batches
+        WHERE $1::text IS NULL OR status = $1
+        ORDER BY created_at DESC
+        LIMIT $2
+        "#,
+    )
+    .bind(status)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
+
+/// Get a staging batch by ID
+pub async fn get_staging_batch(
+    pool: &PgPool,
+    batch_id: i32,
+) -> Result<Option<StagingBatchRow>, sqlx::Error> {
+    sqlx::query_as::<_, StagingBatchRow>("SELECT * FROM staging_batches WHERE id = $1")
+        .bind(batch_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Compare a staging batch against the live table
+pub async fn get_staging_diff(
+    pool: &PgPool,
+    batch_id: i32,
+    dataset: StagingDataset,
+) -> Result<Vec<StagingDiffRow>, sqlx::Error> {
+    let query = match dataset {
+        StagingDataset::BrokerSummary => {
+            r#"
+            WITH staged AS (
+                SELECT symbol, time::date AS day, broker_code,
+                       SUM(buy_value) AS buy_value, SUM(sell_value) AS sell_value
+                FROM staging_broker_summary
+                WHERE batch_id = $1
+                GROUP BY symbol, time::date, broker_code
+            ),
+            live AS (
+                SELECT bs.symbol, bs.time::date AS day, bs.broker_code,
+                       SUM(bs.buy_value) AS buy_value, SUM(bs.sell_value) AS sell_value
+                FROM broker_summary bs
+                JOIN (SELECT DISTINCT symbol, day FROM staged) k
+                    ON k.symbol = bs.symbol AND k.day = bs.time::date
+                GROUP BY bs.symbol, bs.time::date, bs.broker_code
+            )
+            SELECT
+                COALESCE(s.symbol, l.symbol)::text AS symbol,
+                COALESCE(s.day, l.day) AS period,
+                COUNT(s.broker_code) AS staged_rows,
+                COUNT(l.broker_code) AS live_rows,
+                COUNT(*) FILTER (WHERE l.broker_code IS NULL) AS added,
+                COUNT(*) FILTER (WHERE s.broker_code IS NULL) AS removed,
+                COUNT(*) FILTER (
+                    WHERE s.broker_code IS NOT NULL AND l.broker_code IS NOT NULL
+                      AND (s.buy_value <> l.buy_value OR s.sell_value <> l.sell_value)
+                ) AS changed,
+                COALESCE(SUM(s.buy_value - s.sell_value), 0) AS staged_total,
+                COALESCE(SUM(l.buy_value - l.sell_value), 0) AS live_total
+            FROM staged s
+            FULL OUTER JOIN live l
+                ON s.symbol = l.symbol AND s.day = l.day AND s.broker_code = l.broker_code
+            GROUP BY 1, 2
+            ORDER BY 1, 2
+            "#
+        }
+        StagingDataset::Shareholdings => {
+            r#"
+            WITH staged AS (
+                SELECT symbol, reported_date AS day, shareholder_name, shares_held, percentage
+                FROM staging_shareholdings
+                WHERE batch_id = $1
+            ),
+            live AS (
+                SELECT sh.symbol, sh.reported_date AS day, sh.shareholder_name,
+                       sh.shares_held, sh.percentage
+                FROM shareholdings sh
+                JOIN (SELECT DISTINCT symbol, day FROM staged) k
+                    ON k.symbol = sh.symbol AND k.day = sh.reported_date
+            )
+            SELECT
+                COALESCE(s.symbol, l.symbol)::text AS symbol,
+                COALESCE(s.day, l.day) AS period,
+                COUNT(s.shareholder_name) AS staged_rows,
+                COUNT(l.shareholder_name) AS live_rows,
+                COUNT(*) FILTER (WHERE l.shareholder_name IS NULL) AS added,
+                COUNT(*) FILTER (WHERE s.shareholder_name IS NULL) AS removed,
+                COUNT(*) FILTER (
+                    WHERE s.shareholder_name IS NOT NULL AND l.shareholder_name IS NOT NULL
+                      AND s.shares_held <> l.shares_held
+                ) AS changed,
+                COALESCE(SUM(s.percentage), 0) AS staged_total,
+                COALESCE(SUM(l.percentage), 0) AS live_total
+            FROM staged s
+            FULL OUTER JOIN live l
+                ON s.symbol = l.symbol AND s.day = l.day
+               AND s.shareholder_name = l.shareholder_name
+            GROUP BY 1, 2
+            ORDER BY 1, 2
+            "#
+        }
+    };
+
+    sqlx::query_as::<_, StagingDiffRow>(query)
+        .bind(batch_id)
+        .fetch_all(pool)
+        .await
+}
+
+/// Promote a pending batch into the live table
+///
+/// Live rows for every (symbol, period) present in the batch are replaced
+/// by the staged rows. Returns `None` if the batch is missing or no longer
+/// pending.
+pub async fn approve_staging_batch(
+    pool: &PgPool,
+    batch_id: i32,
+    note: Option<&str>,
+) -> Result<Option<StagingBatchRow>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let batch = sqlx::query_as::<_, StagingBatchRow>(
+        "SELECT * FROM staging_batches WHERE id = $1 AND status = 'pending' FOR UPDATE",
+    )
+    .bind(batch_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some(batch) = batch else {
+        return Ok(None);
+    };
+
+    match StagingDataset::parse(&batch.dataset) {
+        Some(StagingDataset::BrokerSummary) => {
+            sqlx::query(
+                r#"
+                DELETE FROM broker_summary bs
+                USING (
+                    SELECT DISTINCT symbol, time::date AS day
+                    FROM staging_broker_summary
+                    WHERE batch_id = $1
+                ) s
+                WHERE bs.symbol = s.symbol AND bs.time::date = s.day
+                "#,
+            )
+            .bind(batch_id)
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query(
+                r#"
+                INSERT INTO broker_summary (
+                    time, symbol, broker_code, buy_volume, sell_volume, buy_value, sell_value,
+                    source
+                )
+                SELECT time, symbol, broker_code, buy_volume, sell_volume, buy_value, sell_value,
+                    $2
+                FROM staging_broker_summary
+                WHERE batch_id = $1
+                "#,
+            )
+            .bind(batch_id)
+            .bind(&batch.source_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+        Some(StagingDataset::Shareholdings) => {
+            sqlx::query(
+                r#"
+                DELETE FROM shareholdings sh
+                USING (
+                    SELECT DISTINCT symbol, reported_date
+                    FROM staging_shareholdings
+                    WHERE batch_id = $1
+                ) s
+                WHERE sh.symbol = s.symbol AND sh.reported_date = s.reported_date
+                "#,
+            )
+            .bind(batch_id)
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query(
+                r#"
+                INSERT INTO shareholdings (
+                    symbol, reported_date, shareholder_name, shareholder_type,
+                    shares_held, percentage, change_shares, change_percentage
+                )
+                SELECT symbol, reported_date, shareholder_name, shareholder_type,
+                       shares_held, percentage, change_shares, change_percentage
+                FROM staging_shareholdings
+                WHERE batch_id = $1
+                "#,
+            )
+            .bind(batch_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+        None => return Ok(None),
+    }
+
+    let approved = sqlx::query_as::<_, StagingBatchRow>(
+        r#"
+        UPDATE staging_batches
+        SET status = 'approved', reviewed_at = NOW(), review_note = $2
+        WHERE id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(batch_id)
+    .bind(note)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(Some(approved))
+}
+
+/// Reject a pending batch, leaving the live tables untouched
+pub async fn reject_staging_batch(
+    pool: &PgPool,
+    batch_id: i32,
+    note: Option<&str>,
+) -> Result<Option<StagingBatchRow>, sqlx::Error> {
+    sqlx::query_as::<_, StagingBatchRow>(
+        r#"
+        UPDATE staging_batches
+        SET status = 'rejected', reviewed_at = NOW(), review_note = $2
+        WHERE id = $1 AND status = 'pending'
+        RETURNING *
+        "#,
+    )
+    .bind(batch_id)
+    .bind(note)
+    .fetch_optional(pool)
+    .await
+}
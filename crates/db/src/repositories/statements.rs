@@ -0,0 +1,83 @@
+//! Financial statement repository
+//!
+//! Point-in-time reads treat a statement as published on the earlier of its
+//! ingestion date and the filing deadline for its period.
+
+use crate::models::{BalanceSheetRow, CashFlowRow, IncomeStatementRow};
+use chrono::NaiveDate;
+use sqlx::PgPool;
+
+/// Get annual and quarterly income statements published by `as_of`, newest first
+pub async fn get_income_statements_as_of(
+    pool: &PgPool,
+    symbol: &str,
+    as_of: NaiveDate,
+    limit: i64,
+) -> Result<Vec<IncomeStatementRow>, sqlx::Error> {
+    sqlx::query_as::<_, IncomeStatementRow>(
+        r#"
+        SELECT symbol, fiscal_year, fiscal_quarter, period_end, revenue, operating_income,
+               depreciation_amortization, interest_expense, earnings_before_tax, tax_expense,
+               net_income, shares_outstanding
+        FROM income_statements
+        WHERE symbol = $1
+          AND LEAST(created_at::date, financials_filing_deadline(period_end)) <= $2
+        ORDER BY period_end DESC, fiscal_quarter DESC NULLS FIRST
+        LIMIT $3
+        "#,
+    )
+    .bind(symbol)
+    .bind(as_of)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
+
+/// Get the latest balance sheet published by `as_of`
+pub async fn get_balance_sheet_as_of(
+    pool: &PgPool,
+    symbol: &str,
+    as_of: NaiveDate,
+) -> Result<Option<BalanceSheetRow>, sqlx::Error> {
+    sqlx::query_as::<_, BalanceSheetRow>(
+        r#"
+        SELECT symbol, fiscal_year, fiscal_quarter, period_end, cash_and_equivalents,
+               short_term_investments, total_assets, total_debt, total_equity
+        FROM balance_sheets
+        WHERE symbol = $1
+          AND LEAST(created_at::date, financials_filing_deadline(period_end)) <= $2
+        ORDER BY period_end DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(symbol)
+    .bind(as_of)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Get annual and quarterly cash flow statements published by `as_of`, newest first
+pub async fn get_cash_flows_as_of(
+    pool: &PgPool,
+    symbol: &str,
+    as_of: NaiveDate,
+    limit: i64,
+) -> Result<Vec<CashFlowRow>, sqlx::Error> {
+    sqlx::query_as::<_, CashFlowRow>(
+        r#"
+        SELECT symbol, fiscal_year, fiscal_quarter, period_end, net_income,
+               depreciation_amortization, change_in_working_capital, operating_cash_flow,
+               capital_expenditure, free_cash_flow
+        FROM cash_flow_statements
+        WHERE symbol = $1
+          AND LEAST(created_at::date, financials_filing_deadline(period_end)) <= $2
+        ORDER BY period_end DESC, fiscal_quarter DESC NULLS FIRST
+        LIMIT $3
+        "#,
+    )
+    .bind(symbol)
+    .bind(as_of)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
@@ -1,7 +1,7 @@
 //! Stock repository
 
-use crate::models::{FinancialsRow, StockRow};
-use chrono::{DateTime, Utc};
+use crate::models::{FinancialsRow, FinancialsVersionRow, StockRow};
+use chrono::{DateTime, NaiveDate, Utc};
 use sqlx::PgPool;
 
 /// Get all active stocks
@@ -22,7 +22,20 @@ pub async fn get_stock_by_symbol(
         .await
 }
 
+/// Stocks among `symbols`, unknown symbols left out
+pub async fn get_stocks_by_symbols(
+    pool: &PgPool,
+    symbols: &[String],
+) -> Result<Vec<StockRow>, sqlx::Error> {
+    sqlx::query_as::<_, StockRow>("SELECT * FROM stocks WHERE symbol = ANY($1)")
+        .bind(symbols)
+        .fetch_all(pool)
+        .await
+}
+
 /// Insert or update stock
+///
+/// A missing or blank sector or subsector keeps the stored one.
 pub async fn upsert_stock(
     pool: &PgPool,
     symbol: &str,
@@ -36,8 +49,8 @@ pub async fn upsert_stock(
         VALUES ($1, $2, $3, $4)
         ON CONFLICT (symbol) DO UPDATE SET
             name = EXCLUDED.name,
-            sector = EXCLUDED.sector,
-            subsector = EXCLUDED.subsector,
+            sector = COALESCE(NULLIF(EXCLUDED.sector, ''), stocks.sector),
+            subsector = COALESCE(NULLIF(EXCLUDED.subsector, ''), stocks.subsector),
             updated_at = NOW()
         RETURNING *
         "#,
@@ -63,6 +76,92 @@ pub async fn get_financials(
     .await
 }
 
+/// Latest financials of every stock
+pub async fn get_all_latest_financials(pool: &PgPool) -> Result<Vec<FinancialsRow>, sqlx::Error> {
+    sqlx::query_as::<_, FinancialsRow>(
+        "SELECT DISTINCT ON (symbol) * FROM financials ORDER BY symbol, period_end DESC",
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Get the latest financials that had been published by `as_of`
+///
+/// Reads the version history, so later restatements of a period are ignored
+/// until their own report date.
+pub async fn get_financials_as_of(
+    pool: &PgPool,
+    symbol: &str,
+    as_of: NaiveDate,
+) -> Result<Option<FinancialsVersionRow>, sqlx::Error> {
+    sqlx::query_as::<_, FinancialsVersionRow>(
+        r#"
+        SELECT * FROM financials_versions
+        WHERE symbol = $1 AND report_date <= $2
+        ORDER BY period_end DESC, version DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(symbol)
+    .bind(as_of)
+    .fetch_optional(pool)
+    .await
+}
+
+/// [`get_financials_as_of`] for several stocks in one query
+///
+/// Stocks without financials published by `as_of` are left out.
+pub async fn get_financials_as_of_for_symbols(
+    pool: &PgPool,
+    symbols: &[String],
+    as_of: NaiveDate,
+) -> Result<Vec<FinancialsVersionRow>, sqlx::Error> {
+    sqlx::query_as::<_, FinancialsVersionRow>(
+        r#"
+        SELECT DISTINCT ON (symbol) * FROM financials_versions
+        WHERE symbol = ANY($1) AND report_date <= $2
+        ORDER BY symbol, period_end DESC, version DESC
+        "#,
+    )
+    .bind(symbols)
+    .bind(as_of)
+    .fetch_all(pool)
+    .await
+}
+
+/// Get every published version of a stock's financials, newest period first
+pub async fn get_financials_versions(
+    pool: &PgPool,
+    symbol: &str,
+) -> Result<Vec<FinancialsVersionRow>, sqlx::Error> {
+    sqlx::query_as::<_, FinancialsVersionRow>(
+        "SELECT * FROM financials_versions WHERE symbol = $1 ORDER BY period_end DESC, version DESC",
+    )
+    .bind(symbol)
+    .fetch_all(pool)
+    .await
+}
+
+/// Dates financials were first published for `symbols` since `from`, as
+/// (symbol, report date) oldest first; restatements are left out
+pub async fn get_earnings_report_dates(
+    pool: &PgPool,
+    symbols: &[String],
+    from: NaiveDate,
+) -> Result<Vec<(String, NaiveDate)>, sqlx::Error> {
+    sqlx::query_as::<_, (String, NaiveDate)>(
+        r#"
+        SELECT symbol, report_date FROM financials_versions
+        WHERE symbol = ANY($1) AND version = 1 AND report_date >= $2
+        ORDER BY report_date, symbol
+        "#,
+    )
+    .bind(symbols)
+    .bind(from)
+    .fetch_all(pool)
+    .await
+}
+
 pub async fn get_latest_financials_created_at(
     pool: &PgPool,
     symbol: &str,
@@ -0,0 +1,109 @@
+//! Encrypted secret repository
+
+use crate::models::StoredSecretRow;
+use crate::secrets::{SealedSecret, SecretCipher, SecretError};
+use sqlx::PgPool;
+
+impl StoredSecretRow {
+    pub fn sealed(&self) -> SealedSecret {
+        SealedSecret {
+            key_id: self.key_id.clone(),
+            nonce: self.nonce.clone(),
+            ciphertext: self.ciphertext.clone(),
+        }
+    }
+}
+
+/// Every stored secret, sealed, by name
+pub async fn get_stored_secrets(pool: &PgPool) -> Result<Vec<StoredSecretRow>, sqlx::Error> {
+    sqlx::query_as::<_, StoredSecretRow>("SELECT * FROM stored_secrets ORDER BY name")
+        .fetch_all(pool)
+        .await
+}
+
+/// Decrypted value of the secret called `name`
+pub async fn get_secret(
+    pool: &PgPool,
+    cipher: &SecretCipher,
+    name: &str,
+) -> Result<Option<String>, SecretError> {
+    let row = sqlx::query_as::<_, StoredSecretRow>("SELECT * FROM stored_secrets WHERE name = $1")
+        .bind(name)
+        .fetch_optional(pool)
+        .await?;
+    row.map(|row| cipher.open(&row.name, &row.sealed()))
+        .transpose()
+}
+
+/// Encrypt and store `value` as `name`, replacing any previous value
+pub async fn put_secret(
+    pool: &PgPool,
+    cipher: &SecretCipher,
+    name: &str,
+    value: &str,
+) -> Result<StoredSecretRow, SecretError> {
+    let sealed = cipher.seal(name, value)?;
+    let row = sqlx::query_as::<_, StoredSecretRow>(
+        r#"
+        INSERT INTO stored_secrets (name, key_id, nonce, ciphertext)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (name) DO UPDATE SET
+            key_id = EXCLUDED.key_id,
+            nonce = EXCLUDED.nonce,
+            ciphertext = EXCLUDED.ciphertext,
+            updated_at = NOW()
+        RETURNING *
+        "#,
+    )
+    .bind(name)
+    .bind(&sealed.key_id)
+    .bind(&sealed.nonce)
+    .bind(&sealed.ciphertext)
+    .fetch_one(pool)
+    .await?;
+    Ok(row)
+}
+
+/// Delete the secret called `name`; false if there was none
+pub async fn delete_secret(pool: &PgPool, name: &str) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM stored_secrets WHERE name = $1")
+        .bind(name)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Re-encrypt secrets sealed with a retired key under the current key
+///
+/// Runs in one transaction, so a secret that no longer opens leaves every
+/// row as it was. Returns the number of secrets re-encrypted.
+pub async fn rotate_secrets(pool: &PgPool, cipher: &SecretCipher) -> Result<u64, SecretError> {
+    let mut tx = pool.begin().await?;
+    let rows = sqlx::query_as::<_, StoredSecretRow>(
+        "SELECT * FROM stored_secrets WHERE key_id <> $1 FOR UPDATE",
+    )
+    .bind(cipher.current_key_id())
+    .fetch_all(&mut *tx)
+    .await?;
+
+    for row in &rows {
+        let value = cipher.open(&row.name, &row.sealed())?;
+        let sealed = cipher.seal(&row.name, &value)?;
+        sqlx::query(
+            r#"
+            UPDATE stored_secrets
+            SET key_id = $2, nonce = $3, ciphertext = $4, updated_at = NOW()
+            WHERE name = $1
+            "#,
+        )
+        .bind(&row.name)
+        .bind(&sealed.key_id)
+        .bind(&sealed.nonce)
+        .bind(&sealed.ciphertext)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(rows.len() as u64)
+}
@@ -0,0 +1,102 @@
+//! Alert subscription repository
+
+use crate::models::AlertSubscriptionRow;
+use chrono::NaiveTime;
+use sqlx::PgPool;
+
+/// Alert subscription for insertion or update
+pub struct UpsertAlertSubscription<'a> {
+    pub user_id: &'a str,
+    pub symbol: &'a str,
+    pub alert_types: &'a serde_json::Value,
+    pub min_priority: &'a str,
+    pub channels: &'a serde_json::Value,
+    pub quiet_start: Option<NaiveTime>,
+    pub quiet_end: Option<NaiveTime>,
+    pub quiet_min_priority: Option<&'a str>,
+}
+
+/// Subscriptions of `user_id`, by symbol
+pub async fn get_alert_subscriptions(
+    pool: &PgPool,
+    user_id: &str,
+) -> Result<Vec<AlertSubscriptionRow>, sqlx::Error> {
+    sqlx::query_as::<_, AlertSubscriptionRow>(
+        "SELECT * FROM alert_subscriptions WHERE user_id = $1 ORDER BY symbol",
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Subscriptions of every user
+pub async fn get_all_alert_subscriptions(
+    pool: &PgPool,
+) -> Result<Vec<AlertSubscriptionRow>, sqlx::Error> {
+    sqlx::query_as::<_, AlertSubscriptionRow>(
+        "SELECT * FROM alert_subscriptions ORDER BY symbol, user_id",
+    )
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn get_alert_subscription(
+    pool: &PgPool,
+    user_id: &str,
+    symbol: &str,
+) -> Result<Option<AlertSubscriptionRow>, sqlx::Error> {
+    sqlx::query_as::<_, AlertSubscriptionRow>(
+        "SELECT * FROM alert_subscriptions WHERE user_id = $1 AND symbol = $2",
+    )
+    .bind(user_id)
+    .bind(symbol)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Create or replace the subscription of a user to a symbol
+pub async fn upsert_alert_subscription(
+    pool: &PgPool,
+    subscription: &UpsertAlertSubscription<'_>,
+) -> Result<AlertSubscriptionRow, sqlx::Error> {
+    sqlx::query_as::<_, AlertSubscriptionRow>(
+        r#"
+        INSERT INTO alert_subscriptions
+            (user_id, symbol, alert_types, min_priority, channels,
+             quiet_start, quiet_end, quiet_min_priority)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        ON CONFLICT (user_id, symbol) DO UPDATE SET
+            alert_types = EXCLUDED.alert_types,
+            min_priority = EXCLUDED.min_priority,
+            channels = EXCLUDED.channels,
+            quiet_start = EXCLUDED.quiet_start,
+            quiet_end = EXCLUDED.quiet_end,
+            quiet_min_priority = EXCLUDED.quiet_min_priority,
+            updated_at = NOW()
+        RETURNING *
+        "#,
+    )
+    .bind(subscription.user_id)
+    .bind(subscription.symbol)
+    .bind(subscription.alert_types)
+    .bind(subscription.min_priority)
+    .bind(subscription.channels)
+    .bind(subscription.quiet_start)
+    .bind(subscription.quiet_end)
+    .bind(subscription.quiet_min_priority)
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn delete_alert_subscription(
+    pool: &PgPool,
+    user_id: &str,
+    symbol: &str,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM alert_subscriptions WHERE user_id = $1 AND symbol = $2")
+        .bind(user_id)
+        .bind(symbol)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
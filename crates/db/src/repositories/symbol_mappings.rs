@@ -0,0 +1,50 @@
+//! Provider symbol mapping repository
+
+use crate::models::SymbolMappingRow;
+use sqlx::PgPool;
+
+/// Get all provider symbol overrides
+pub async fn get_symbol_mappings(pool: &PgPool) -> Result<Vec<SymbolMappingRow>, sqlx::Error> {
+    sqlx::query_as::<_, SymbolMappingRow>("SELECT * FROM symbol_mappings ORDER BY symbol, provider")
+        .fetch_all(pool)
+        .await
+}
+
+/// Create or replace the override for a symbol on one provider
+pub async fn upsert_symbol_mapping(
+    pool: &PgPool,
+    symbol: &str,
+    provider: &str,
+    provider_symbol: &str,
+) -> Result<SymbolMappingRow, sqlx::Error> {
+    sqlx::query_as::<_, SymbolMappingRow>(
+        r#"
+        INSERT INTO symbol_mappings (symbol, provider, provider_symbol)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (symbol, provider) DO UPDATE SET
+            provider_symbol = EXCLUDED.provider_symbol,
+            updated_at = NOW()
+        RETURNING *
+        "#,
+    )
+    .bind(symbol)
+    .bind(provider)
+    .bind(provider_symbol)
+    .fetch_one(pool)
+    .await
+}
+
+/// Delete an override, returning whether it existed
+pub async fn delete_symbol_mapping(
+    pool: &PgPool,
+    symbol: &str,
+    provider: &str,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM symbol_mappings WHERE symbol = $1 AND provider = $2")
+        .bind(symbol)
+        .bind(provider)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
@@ -0,0 +1,81 @@
+//! Analyst target price repository
+
+use crate::models::AnalystTargetPriceRow;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+
+/// Target price consensus snapshot for insertion
+pub struct InsertAnalystTargetPrice<'a> {
+    pub symbol: &'a str,
+    pub as_of: NaiveDate,
+    pub source: &'a str,
+    pub target_mean: Option<Decimal>,
+    pub target_median: Option<Decimal>,
+    pub target_high: Option<Decimal>,
+    pub target_low: Option<Decimal>,
+    pub analyst_count: Option<i32>,
+    pub strong_buy: i32,
+    pub buy: i32,
+    pub hold: i32,
+    pub sell: i32,
+    pub strong_sell: i32,
+}
+
+/// Store a consensus snapshot, replacing any snapshot for the same day
+pub async fn upsert_analyst_target_price(
+    pool: &PgPool,
+    target: &InsertAnalystTargetPrice<'_>,
+) -> Result<AnalystTargetPriceRow, sqlx::Error> {
+    sqlx::query_as::<_, AnalystTargetPriceRow>(
+        r#"
+        INSERT INTO analyst_target_prices (
+            symbol, as_of, source, target_mean, target_median, target_high, target_low,
+            analyst_count, strong_buy, buy, hold, sell, strong_sell
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+        ON CONFLICT (symbol, as_of) DO UPDATE SET
+            source = EXCLUDED.source,
+            target_mean = EXCLUDED.target_mean,
+            target_median = EXCLUDED.target_median,
+            target_high = EXCLUDED.target_high,
+            target_low = EXCLUDED.target_low,
+            analyst_count = EXCLUDED.analyst_count,
+            strong_buy = EXCLUDED.strong_buy,
+            buy = EXCLUDED.buy,
+            hold = EXCLUDED.hold,
+            sell = EXCLUDED.sell,
+            strong_sell = EXCLUDED.strong_sell,
+            fetched_at = NOW()
+        RETURNING *
+        "#,
+    )
+    .bind(target.symbol)
+    .bind(target.as_of)
+    .bind(target.source)
+    .bind(target.target_mean)
+    .bind(target.target_median)
+    .bind(target.target_high)
+    .bind(target.target_low)
+    .bind(target.analyst_count)
+    .bind(target.strong_buy)
+    .bind(target.buy)
+    .bind(target.hold)
+    .bind(target.sell)
+    .bind(target.strong_sell)
+    .fetch_one(pool)
+    .await
+}
+
+/// Get the most recent consensus snapshot for a stock
+pub async fn get_latest_target_price(
+    pool: &PgPool,
+    symbol: &str,
+) -> Result<Option<AnalystTargetPriceRow>, sqlx::Error> {
+    sqlx::query_as::<_, AnalystTargetPriceRow>(
+        "SELECT * FROM analyst_target_prices WHERE symbol = $1 ORDER BY as_of DESC LIMIT 1",
+    )
+    .bind(symbol)
+    .fetch_optional(pool)
+    .await
+}
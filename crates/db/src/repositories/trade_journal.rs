@@ -0,0 +1,147 @@
+//! Trade journal repository
+
+use crate::models::TradeJournalRow;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sqlx::{FromRow, PgPool};
+
+/// Trade journal entry for insertion
+pub struct InsertTradeJournalEntry<'a> {
+    pub symbol: &'a str,
+    pub entry_time: DateTime<Utc>,
+    pub entry_price: Decimal,
+    pub quantity: i64,
+    pub fees: Decimal,
+    pub rationale: Option<&'a str>,
+    pub signal_source: Option<&'a str>,
+    pub alert_history_id: Option<i32>,
+    pub signal_time: Option<DateTime<Utc>>,
+    pub signal_price: Option<Decimal>,
+    pub signal_score: Option<Decimal>,
+}
+
+/// When and at what price an alert fired
+#[derive(Debug, Clone, FromRow)]
+pub struct AlertSignalRow {
+    pub triggered_at: Option<DateTime<Utc>>,
+    pub price: Option<Decimal>,
+}
+
+/// List journal entries, newest first
+pub async fn get_trade_journal(
+    pool: &PgPool,
+    symbol: Option<&str>,
+    open_only: bool,
+) -> Result<Vec<TradeJournalRow>, sqlx::Error> {
+    sqlx::query_as::<_, TradeJournalRow>(
+        r#"
+        SELECT * FROM trade_journal
+        WHERE ($1::text IS NULL OR symbol = $1)
+          AND (NOT $2 OR exit_time IS NULL)
+        ORDER BY entry_time DESC
+        "#,
+    )
+    .bind(symbol)
+    .bind(open_only)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn get_trade_journal_entry(
+    pool: &PgPool,
+    id: i32,
+) -> Result<Option<TradeJournalRow>, sqlx::Error> {
+    sqlx::query_as::<_, TradeJournalRow>("SELECT * FROM trade_journal WHERE id = $1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+pub async fn insert_trade_journal_entry(
+    pool: &PgPool,
+    entry: &InsertTradeJournalEntry<'_>,
+) -> Result<TradeJournalRow, sqlx::Error> {
+    sqlx::query_as::<_, TradeJournalRow>(
+        r#"
+        INSERT INTO trade_journal (
+            symbol, entry_time, entry_price, quantity, fees, rationale,
+            signal_source, alert_history_id, signal_time, signal_price, signal_score
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        RETURNING *
+        "#,
+    )
+    .bind(entry.symbol)
+    .bind(entry.entry_time)
+    .bind(entry.entry_price)
+    .bind(entry.quantity)
+    .bind(entry.fees)
+    .bind(entry.rationale)
+    .bind(entry.signal_source)
+    .bind(entry.alert_history_id)
+    .bind(entry.signal_time)
+    .bind(entry.signal_price)
+    .bind(entry.signal_score)
+    .fetch_one(pool)
+    .await
+}
+
+/// Record the exit of an open trade; exit fees are added to entry fees
+///
+/// Returns `None` if the entry is missing or already closed.
+pub async fn close_trade_journal_entry(
+    pool: &PgPool,
+    id: i32,
+    exit_time: DateTime<Utc>,
+    exit_price: Decimal,
+    exit_fees: Decimal,
+    exit_note: Option<&str>,
+) -> Result<Option<TradeJournalRow>, sqlx::Error> {
+    sqlx::query_as::<_, TradeJournalRow>(
+        r#"
+        UPDATE trade_journal
+        SET exit_time = $2,
+            exit_price = $3,
+            fees = fees + $4,
+            exit_note = $5,
+            updated_at = NOW()
+        WHERE id = $1 AND exit_time IS NULL
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .bind(exit_time)
+    .bind(exit_price)
+    .bind(exit_fees)
+    .bind(exit_note)
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn delete_trade_journal_entry(pool: &PgPool, id: i32) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM trade_journal WHERE id = $1")
+        .bind(id)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Look up the trigger time and price of a fired alert
+pub async fn get_alert_signal(
+    pool: &PgPool,
+    alert_history_id: i32,
+) -> Result<Option<AlertSignalRow>, sqlx::Error> {
+    sqlx::query_as::<_, AlertSignalRow>(
+        r#"
+        SELECT
+            triggered_at,
+            CASE WHEN trigger_value->>'price' ~ '^[0-9.]+$'
+                 THEN (trigger_value->>'price')::numeric END AS price
+        FROM alert_history
+        WHERE id = $1
+        "#,
+    )
+    .bind(alert_history_id)
+    .fetch_optional(pool)
+    .await
+}
@@ -0,0 +1,109 @@
+//! Product usage event repository
+
+use crate::models::{UsageDailyCountRow, UsageSubjectCountRow};
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+/// Usage event for insertion
+#[derive(Debug, Clone)]
+pub struct InsertUsageEvent {
+    pub kind: String,
+    pub subject: String,
+    pub symbol: Option<String>,
+    /// Username, a hash of it, or None when anonymized
+    pub actor: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// Insert a batch of events in one statement
+pub async fn insert_usage_events(
+    pool: &PgPool,
+    events: &[InsertUsageEvent],
+) -> Result<u64, sqlx::Error> {
+    if events.is_empty() {
+        return Ok(0);
+    }
+    let kinds: Vec<&str> = events.iter().map(|e| e.kind.as_str()).collect();
+    let subjects: Vec<&str> = events.iter().map(|e| e.subject.as_str()).collect();
+    let symbols: Vec<Option<&str>> = events.iter().map(|e| e.symbol.as_deref()).collect();
+    let actors: Vec<Option<&str>> = events.iter().map(|e| e.actor.as_deref()).collect();
+    let times: Vec<DateTime<Utc>> = events.iter().map(|e| e.occurred_at).collect();
+
+    let result = sqlx::query(
+        r#"
+        INSERT INTO usage_events (kind, subject, symbol, actor, occurred_at)
+        SELECT * FROM UNNEST($1::VARCHAR[], $2::VARCHAR[], $3::VARCHAR[], $4::VARCHAR[], $5::TIMESTAMPTZ[])
+        "#,
+    )
+    .bind(&kinds)
+    .bind(&subjects)
+    .bind(&symbols)
+    .bind(&actors)
+    .bind(&times)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// Busiest subjects per kind since `since`, at most `limit` per kind
+pub async fn get_top_usage_subjects(
+    pool: &PgPool,
+    since: DateTime<Utc>,
+    limit: i64,
+) -> Result<Vec<UsageSubjectCountRow>, sqlx::Error> {
+    sqlx::query_as::<_, UsageSubjectCountRow>(
+        r#"
+        SELECT kind, subject, events, actors FROM (
+            SELECT kind, subject, COUNT(*) AS events, COUNT(DISTINCT actor) AS actors,
+                   ROW_NUMBER() OVER (PARTITION BY kind ORDER BY COUNT(*) DESC, subject) AS rank
+            FROM usage_events
+            WHERE occurred_at >= $1
+            GROUP BY kind, subject
+        ) ranked
+        WHERE rank <= $2
+        ORDER BY kind, events DESC, subject
+        "#,
+    )
+    .bind(since)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
+
+/// Events per kind per day since `since`, oldest first
+pub async fn get_daily_usage_counts(
+    pool: &PgPool,
+    since: DateTime<Utc>,
+) -> Result<Vec<UsageDailyCountRow>, sqlx::Error> {
+    sqlx::query_as::<_, UsageDailyCountRow>(
+        r#"
+        SELECT occurred_at::date AS day, kind, COUNT(*) AS events
+        FROM usage_events
+        WHERE occurred_at >= $1
+        GROUP BY day, kind
+        ORDER BY day, kind
+        "#,
+    )
+    .bind(since)
+    .fetch_all(pool)
+    .await
+}
+
+/// Distinct known actors since `since`
+pub async fn count_usage_actors(pool: &PgPool, since: DateTime<Utc>) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(DISTINCT actor) FROM usage_events WHERE occurred_at >= $1",
+    )
+    .bind(since)
+    .fetch_one(pool)
+    .await
+}
+
+/// Delete events recorded before `before`
+pub async fn purge_usage_events(pool: &PgPool, before: DateTime<Utc>) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM usage_events WHERE occurred_at < $1")
+        .bind(before)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected())
+}
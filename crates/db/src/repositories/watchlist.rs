@@ -1,22 +1,37 @@
 //! Watchlist repository
 
-use crate::models::WatchlistRow;
+use crate::models::{WatchlistLevelRow, WatchlistRow};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
 use sqlx::PgPool;
 
-/// Get all watchlist items
+/// User price level for insertion or update
+pub struct InsertWatchlistLevel<'a> {
+    pub symbol: &'a str,
+    pub price: Decimal,
+    pub kind: &'a str,
+    pub label: Option<&'a str>,
+    pub note: Option<&'a str>,
+    pub alert_enabled: bool,
+}
+
+/// Get all watchlist items, excluding soft-deleted ones
 pub async fn get_watchlist(pool: &PgPool) -> Result<Vec<WatchlistRow>, sqlx::Error> {
-    sqlx::query_as::<_, WatchlistRow>("SELECT * FROM watchlist ORDER BY sort_order")
-        .fetch_all(pool)
-        .await
+    sqlx::query_as::<_, WatchlistRow>(
+        "SELECT * FROM watchlist WHERE deleted_at IS NULL ORDER BY sort_order",
+    )
+    .fetch_all(pool)
+    .await
 }
 
-/// Add stock to watchlist
+/// Add stock to watchlist, reviving it if it was soft-deleted
 pub async fn add_to_watchlist(pool: &PgPool, symbol: &str) -> Result<WatchlistRow, sqlx::Error> {
     sqlx::query_as::<_, WatchlistRow>(
         r#"
         INSERT INTO watchlist (symbol, sort_order)
         VALUES ($1, (SELECT COALESCE(MAX(sort_order), 0) + 1 FROM watchlist))
-        ON CONFLICT (symbol) DO NOTHING
+        ON CONFLICT (symbol) DO UPDATE SET deleted_at = NULL, added_at = NOW()
+        WHERE watchlist.deleted_at IS NOT NULL
         RETURNING *
         "#,
     )
@@ -25,11 +40,183 @@ pub async fn add_to_watchlist(pool: &PgPool, symbol: &str) -> Result<WatchlistRo
     .await
 }
 
-/// Remove stock from watchlist
-pub async fn remove_from_watchlist(pool: &PgPool, symbol: &str) -> Result<(), sqlx::Error> {
-    sqlx::query("DELETE FROM watchlist WHERE symbol = $1")
-        .bind(symbol)
+/// Soft-delete stock from watchlist; it stays restorable until purged
+///
+/// Returns the removed item, or None if it was not on the watchlist.
+pub async fn remove_from_watchlist(
+    pool: &PgPool,
+    symbol: &str,
+) -> Result<Option<WatchlistRow>, sqlx::Error> {
+    sqlx::query_as::<_, WatchlistRow>(
+        r#"
+        UPDATE watchlist SET deleted_at = NOW()
+        WHERE symbol = $1 AND deleted_at IS NULL
+        RETURNING *
+        "#,
+    )
+    .bind(symbol)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Watchlist items deleted at or after `since`, most recent first
+pub async fn get_deleted_watchlist(
+    pool: &PgPool,
+    since: DateTime<Utc>,
+) -> Result<Vec<WatchlistRow>, sqlx::Error> {
+    sqlx::query_as::<_, WatchlistRow>(
+        "SELECT * FROM watchlist WHERE deleted_at >= $1 ORDER BY deleted_at DESC",
+    )
+    .bind(since)
+    .fetch_all(pool)
+    .await
+}
+
+/// Undo a soft-delete made at or after `since`
+///
+/// Without a symbol, the most recently deleted item is restored.
+pub async fn restore_watchlist_item(
+    pool: &PgPool,
+    symbol: Option<&str>,
+    since: DateTime<Utc>,
+) -> Result<Option<WatchlistRow>, sqlx::Error> {
+    sqlx::query_as::<_, WatchlistRow>(
+        r#"
+        UPDATE watchlist SET deleted_at = NULL
+        WHERE id = (
+            SELECT id FROM watchlist
+            WHERE deleted_at >= $2 AND ($1::VARCHAR IS NULL OR symbol = $1)
+            ORDER BY deleted_at DESC
+            LIMIT 1
+        )
+        RETURNING *
+        "#,
+    )
+    .bind(symbol)
+    .bind(since)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Permanently remove items soft-deleted before `before`
+pub async fn purge_deleted_watchlist(
+    pool: &PgPool,
+    before: DateTime<Utc>,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM watchlist WHERE deleted_at < $1")
+        .bind(before)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected())
+}
+
+/// Get user price levels for a stock, ordered by price
+pub async fn get_watchlist_levels(
+    pool: &PgPool,
+    symbol: &str,
+) -> Result<Vec<WatchlistLevelRow>, sqlx::Error> {
+    sqlx::query_as::<_, WatchlistLevelRow>(
+        "SELECT * FROM watchlist_levels WHERE symbol = $1 ORDER BY price",
+    )
+    .bind(symbol)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn get_watchlist_level(
+    pool: &PgPool,
+    symbol: &str,
+    id: i32,
+) -> Result<Option<WatchlistLevelRow>, sqlx::Error> {
+    sqlx::query_as::<_, WatchlistLevelRow>(
+        "SELECT * FROM watchlist_levels WHERE id = $1 AND symbol = $2",
+    )
+    .bind(id)
+    .bind(symbol)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Get all levels with alerts enabled, across symbols
+pub async fn get_alerting_watchlist_levels(
+    pool: &PgPool,
+) -> Result<Vec<WatchlistLevelRow>, sqlx::Error> {
+    sqlx::query_as::<_, WatchlistLevelRow>(
+        "SELECT * FROM watchlist_levels WHERE alert_enabled ORDER BY symbol, price",
+    )
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn insert_watchlist_level(
+    pool: &PgPool,
+    level: &InsertWatchlistLevel<'_>,
+) -> Result<WatchlistLevelRow, sqlx::Error> {
+    sqlx::query_as::<_, WatchlistLevelRow>(
+        r#"
+        INSERT INTO watchlist_levels (symbol, price, kind, label, note, alert_enabled)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING *
+        "#,
+    )
+    .bind(level.symbol)
+    .bind(level.price)
+    .bind(level.kind)
+    .bind(level.label)
+    .bind(level.note)
+    .bind(level.alert_enabled)
+    .fetch_one(pool)
+    .await
+}
+
+/// Replace a level's fields; returns None if it does not belong to the symbol
+pub async fn update_watchlist_level(
+    pool: &PgPool,
+    id: i32,
+    level: &InsertWatchlistLevel<'_>,
+) -> Result<Option<WatchlistLevelRow>, sqlx::Error> {
+    sqlx::query_as::<_, WatchlistLevelRow>(
+        r#"
+        UPDATE watchlist_levels
+        SET price = $3, kind = $4, label = $5, note = $6, alert_enabled = $7, updated_at = NOW()
+        WHERE id = $1 AND symbol = $2
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .bind(level.symbol)
+    .bind(level.price)
+    .bind(level.kind)
+    .bind(level.label)
+    .bind(level.note)
+    .bind(level.alert_enabled)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Record that a level alert fired
+pub async fn mark_watchlist_level_triggered(
+    pool: &PgPool,
+    id: i32,
+    triggered_at: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE watchlist_levels SET last_triggered_at = $2 WHERE id = $1")
+        .bind(id)
+        .bind(triggered_at)
         .execute(pool)
         .await?;
     Ok(())
 }
+
+pub async fn delete_watchlist_level(
+    pool: &PgPool,
+    symbol: &str,
+    id: i32,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM watchlist_levels WHERE id = $1 AND symbol = $2")
+        .bind(id)
+        .bind(symbol)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
@@ -0,0 +1,199 @@
+//! Encryption at rest for stored credentials
+//!
+//! Secrets are sealed with AES-256-GCM under a master key supplied by the
+//! environment. Every ciphertext records the id of the key that sealed it,
+//! so retired keys stay readable while rows are re-encrypted under the
+//! current one. The secret's name is bound as associated data: ciphertext
+//! copied onto another row fails to open.
+//!
+//! - `SECRETS_MASTER_KEY`: base64 of 32 random bytes, the current key
+//! - `SECRETS_MASTER_KEY_FILE`: file holding that value instead, for keys
+//!   delivered by a KMS or secret manager
+//! - `SECRETS_PREVIOUS_KEYS`: comma-separated retired keys, accepted for
+//!   decryption until rotation has re-encrypted every row
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::digest::{digest, SHA256};
+use ring::rand::{SecureRandom, SystemRandom};
+use std::fmt;
+use std::sync::Arc;
+use thiserror::Error;
+
+/// Bytes in a master key
+pub const MASTER_KEY_LEN: usize = 32;
+
+#[derive(Debug, Error)]
+pub enum SecretError {
+    #[error("Invalid master key: {0}")]
+    InvalidKey(String),
+
+    #[error("Secret was sealed with unknown key {0}")]
+    UnknownKey(String),
+
+    #[error("Secret could not be decrypted")]
+    Decrypt,
+
+    #[error("Secret could not be encrypted")]
+    Encrypt,
+
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// A secret as stored: ciphertext (with tag), its nonce and the sealing key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SealedSecret {
+    pub key_id: String,
+    pub nonce: Vec<u8>,
+    pub ciphertext: Vec<u8>,
+}
+
+struct MasterKey {
+    id: String,
+    key: LessSafeKey,
+}
+
+impl MasterKey {
+    fn new(bytes: &[u8]) -> Result<Self, SecretError> {
+        if bytes.len() != MASTER_KEY_LEN {
+            return Err(SecretError::InvalidKey(format!(
+                "expected {} bytes, got {}",
+                MASTER_KEY_LEN,
+                bytes.len()
+            )));
+        }
+        let key = UnboundKey::new(&AES_256_GCM, bytes)
+            .map_err(|_| SecretError::InvalidKey("rejected by AES-256-GCM".into()))?;
+        Ok(Self {
+            id: key_id(bytes),
+            key: LessSafeKey::new(key),
+        })
+    }
+}
+
+/// Key id: the first 8 bytes of the key's SHA-256, hex-encoded
+fn key_id(bytes: &[u8]) -> String {
+    digest(&SHA256, bytes).as_ref()[..8]
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn decode_key(encoded: &str) -> Result<MasterKey, SecretError> {
+    let bytes = STANDARD
+        .decode(encoded.trim())
+        .map_err(|e| SecretError::InvalidKey(e.to_string()))?;
+    MasterKey::new(&bytes)
+}
+
+/// Seals with the current master key, opens with it or a retired one
+#[derive(Clone)]
+pub struct SecretCipher {
+    /// Current key first, then retired keys
+    keys: Arc<Vec<MasterKey>>,
+    rng: SystemRandom,
+}
+
+impl fmt::Debug for SecretCipher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SecretCipher")
+            .field("current_key_id", &self.current_key_id())
+            .field(
+                "previous_key_ids",
+                &self.keys[1..].iter().map(|k| &k.id).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl SecretCipher {
+    /// Cipher from raw key bytes; `previous` keys only decrypt
+    pub fn new(current: &[u8], previous: &[&[u8]]) -> Result<Self, SecretError> {
+        let keys = std::iter::once(current)
+            .chain(previous.iter().copied())
+            .map(MasterKey::new)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            keys: Arc::new(keys),
+            rng: SystemRandom::new(),
+        })
+    }
+
+    /// Cipher from a base64 current key and comma-separated base64 retired keys
+    pub fn parse(current: &str, previous: &str) -> Result<Self, SecretError> {
+        let keys = std::iter::once(current)
+            .chain(previous.split(',').filter(|k| !k.trim().is_empty()))
+            .map(decode_key)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            keys: Arc::new(keys),
+            rng: SystemRandom::new(),
+        })
+    }
+
+    /// Cipher from the environment; `None` when no master key is configured
+    pub fn from_env() -> Result<Option<Self>, SecretError> {
+        let current = match std::env::var("SECRETS_MASTER_KEY") {
+            Ok(key) if !key.trim().is_empty() => key,
+            _ => match std::env::var("SECRETS_MASTER_KEY_FILE") {
+                Ok(path) if !path.trim().is_empty() => std::fs::read_to_string(path.trim())
+                    .map_err(|e| SecretError::InvalidKey(format!("{}: {}", path.trim(), e)))?,
+                _ => return Ok(None),
+            },
+        };
+        let previous = std::env::var("SECRETS_PREVIOUS_KEYS").unwrap_or_default();
+        Self::parse(&current, &previous).map(Some)
+    }
+
+    /// Id of the key new secrets are sealed with
+    pub fn current_key_id(&self) -> &str {
+        &self.keys[0].id
+    }
+
+    /// Whether `sealed` should be re-encrypted under the current key
+    pub fn needs_rotation(&self, sealed: &SealedSecret) -> bool {
+        sealed.key_id != self.current_key_id()
+    }
+
+    /// Encrypt `plaintext` for the secret called `name`
+    pub fn seal(&self, name: &str, plaintext: &str) -> Result<SealedSecret, SecretError> {
+        let current = &self.keys[0];
+        let mut nonce = [0u8; NONCE_LEN];
+        self.rng
+            .fill(&mut nonce)
+            .map_err(|_| SecretError::Encrypt)?;
+        let mut ciphertext = plaintext.as_bytes().to_vec();
+        current
+            .key
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(name.as_bytes()),
+                &mut ciphertext,
+            )
+            .map_err(|_| SecretError::Encrypt)?;
+        Ok(SealedSecret {
+            key_id: current.id.clone(),
+            nonce: nonce.to_vec(),
+            ciphertext,
+        })
+    }
+
+    /// Decrypt the secret called `name`
+    pub fn open(&self, name: &str, sealed: &SealedSecret) -> Result<String, SecretError> {
+        let key = self
+            .keys
+            .iter()
+            .find(|k| k.id == sealed.key_id)
+            .ok_or_else(|| SecretError::UnknownKey(sealed.key_id.clone()))?;
+        let nonce =
+            Nonce::try_assume_unique_for_key(&sealed.nonce).map_err(|_| SecretError::Decrypt)?;
+        let mut buffer = sealed.ciphertext.clone();
+        let plaintext = key
+            .key
+            .open_in_place(nonce, Aad::from(name.as_bytes()), &mut buffer)
+            .map_err(|_| SecretError::Decrypt)?;
+        String::from_utf8(plaintext.to_vec()).map_err(|_| SecretError::Decrypt)
+    }
+}
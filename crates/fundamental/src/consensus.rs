@@ -0,0 +1,182 @@
+//! Street consensus versus model valuation
+
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+/// Analyst rating counts
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct RatingDistribution {
+    pub strong_buy: i32,
+    pub buy: i32,
+    pub hold: i32,
+    pub sell: i32,
+    pub strong_sell: i32,
+}
+
+impl RatingDistribution {
+    pub fn total(&self) -> i32 {
+        self.strong_buy + self.buy + self.hold + self.sell + self.strong_sell
+    }
+
+    /// Share of buy and strong-buy ratings (0-100)
+    pub fn buy_pct(&self) -> Option<Decimal> {
+        self.pct(self.strong_buy + self.buy)
+    }
+
+    /// Share of sell and strong-sell ratings (0-100)
+    pub fn sell_pct(&self) -> Option<Decimal> {
+        self.pct(self.sell + self.strong_sell)
+    }
+
+    /// Average rating on a 1 (strong sell) to 5 (strong buy) scale
+    pub fn average_score(&self) -> Option<Decimal> {
+        let total = self.total();
+        if total <= 0 {
+            return None;
+        }
+        let weighted =
+            5 * self.strong_buy + 4 * self.buy + 3 * self.hold + 2 * self.sell + self.strong_sell;
+        Some(Decimal::from(weighted) / Decimal::from(total))
+    }
+
+    fn pct(&self, count: i32) -> Option<Decimal> {
+        let total = self.total();
+        (total > 0).then(|| Decimal::from(count) * dec!(100) / Decimal::from(total))
+    }
+}
+
+/// Overall street rating derived from the distribution
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConsensusRating {
+    StrongBuy,
+    Buy,
+    Hold,
+    Sell,
+    StrongSell,
+}
+
+impl ConsensusRating {
+    pub fn from_average(score: Decimal) -> Self {
+        if score >= dec!(4.5) {
+            Self::StrongBuy
+        } else if score >= dec!(3.5) {
+            Self::Buy
+        } else if score > dec!(2.5) {
+            Self::Hold
+        } else if score > dec!(1.5) {
+            Self::Sell
+        } else {
+            Self::StrongSell
+        }
+    }
+}
+
+/// How the street target compares with the model fair value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConsensusStance {
+    StreetMoreBullish,
+    Aligned,
+    ModelMoreBullish,
+}
+
+/// Street target within this percent of model fair value counts as aligned
+const ALIGNMENT_BAND_PCT: Decimal = dec!(10);
+
+/// Street consensus compared with the model fair value
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsensusComparison {
+    pub consensus_target: Decimal,
+    pub model_fair_value: Decimal,
+    pub current_price: Decimal,
+    /// Upside from current price to consensus target
+    pub implied_upside_pct: Decimal,
+    /// Upside from current price to model fair value
+    pub model_upside_pct: Decimal,
+    /// Consensus target relative to model fair value
+    pub consensus_vs_model_pct: Decimal,
+    pub stance: ConsensusStance,
+    pub rating: Option<ConsensusRating>,
+}
+
+/// Compare a street target price with the model fair value
+///
+/// Returns `None` when any price is missing or non-positive.
+pub fn compare_with_consensus(
+    consensus_target: Decimal,
+    model_fair_value: Decimal,
+    current_price: Decimal,
+    ratings: &RatingDistribution,
+) -> Option<ConsensusComparison> {
+    if consensus_target <= Decimal::ZERO
+        || model_fair_value <= Decimal::ZERO
+        || current_price <= Decimal::ZERO
+    {
+        return None;
+    }
+
+    let pct_change = |from: Decimal, to: Decimal| ((to - from) / from * dec!(100)).round_dp(2);
+    let consensus_vs_model_pct = pct_change(model_fair_value, consensus_target);
+
+    let stance = if consensus_vs_model_pct > ALIGNMENT_BAND_PCT {
+        ConsensusStance::StreetMoreBullish
+    } else if consensus_vs_model_pct < -ALIGNMENT_BAND_PCT {
+        ConsensusStance::ModelMoreBullish
+    } else {
+        ConsensusStance::Aligned
+    };
+
+    Some(ConsensusComparison {
+        consensus_target,
+        model_fair_value,
+        current_price,
+        implied_upside_pct: pct_change(current_price, consensus_target),
+        model_upside_pct: pct_change(current_price, model_fair_value),
+        consensus_vs_model_pct,
+        stance,
+        rating: ratings.average_score().map(ConsensusRating::from_average),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rating_distribution() {
+        let ratings = RatingDistribution {
+            strong_buy: 6,
+            buy: 2,
+            hold: 2,
+            sell: 0,
+            strong_sell: 0,
+        };
+
+        assert_eq!(ratings.total(), 10);
+        assert_eq!(ratings.buy_pct(), Some(dec!(80)));
+        assert_eq!(ratings.average_score(), Some(dec!(4.4)));
+        assert_eq!(
+            ConsensusRating::from_average(ratings.average_score().unwrap()),
+            ConsensusRating::Buy
+        );
+        assert!(RatingDistribution::default().average_score().is_none());
+    }
+
+    #[test]
+    fn test_compare_with_consensus() {
+        let ratings = RatingDistribution::default();
+
+        let cmp = compare_with_consensus(dec!(12000), dec!(10000), dec!(9600), &ratings).unwrap();
+        assert_eq!(cmp.implied_upside_pct, dec!(25));
+        assert_eq!(cmp.consensus_vs_model_pct, dec!(20));
+        assert_eq!(cmp.stance, ConsensusStance::StreetMoreBullish);
+        assert!(cmp.rating.is_none());
+
+        let cmp = compare_with_consensus(dec!(10500), dec!(10000), dec!(9600), &ratings).unwrap();
+        assert_eq!(cmp.stance, ConsensusStance::Aligned);
+
+        assert!(compare_with_consensus(Decimal::ZERO, dec!(10000), dec!(9600), &ratings).is_none());
+    }
+}
@@ -5,15 +5,21 @@
 //! - EV/EBITDA valuation
 //! - Price-to-Book ratio
 //! - ROE/ROA metrics
+//! - ROIC and its spread over WACC
 //! - Sector peer comparison
 //! - DCF (Discounted Cash Flow) valuation
+//! - Street consensus versus model fair value
 
+pub mod consensus;
 pub mod dcf;
 pub mod error;
 pub mod metrics;
 pub mod peers;
+pub mod roic;
 
+pub use consensus::*;
 pub use dcf::*;
 pub use error::*;
 pub use metrics::*;
 pub use peers::*;
+pub use roic::*;
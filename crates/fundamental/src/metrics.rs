@@ -1,5 +1,6 @@
 //! Fundamental valuation metrics
 
+use chrono::NaiveDate;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
@@ -93,6 +94,253 @@ pub fn calculate_ev_ebitda(enterprise_value: Decimal, ebitda: Decimal) -> Option
     Some((enterprise_value / ebitda).round_dp(2))
 }
 
+/// How quarterly statements report their figures
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuarterlyBasis {
+    /// Each quarter covers only its own three months
+    Discrete,
+    /// Each quarter is cumulative from the start of the fiscal year (IDX filings)
+    YearToDate,
+}
+
+/// A statement covering a fiscal year or one of its quarters
+pub trait FiscalPeriod {
+    fn fiscal_year(&self) -> i32;
+    /// None for a full-year report, 1-4 for a quarter
+    fn fiscal_quarter(&self) -> Option<u32>;
+}
+
+/// Income statement figures for one reporting period
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IncomePeriod {
+    pub fiscal_year: i32,
+    /// None for a full-year report, 1-4 for a quarter
+    pub fiscal_quarter: Option<u32>,
+    pub period_end: NaiveDate,
+    pub revenue: Option<Decimal>,
+    pub operating_income: Option<Decimal>,
+    pub depreciation_amortization: Option<Decimal>,
+    pub net_income: Option<Decimal>,
+    pub interest_expense: Option<Decimal>,
+    pub earnings_before_tax: Option<Decimal>,
+    pub tax_expense: Option<Decimal>,
+    /// Reported EBITDA, when the source provides it
+    pub ebitda: Option<Decimal>,
+}
+
+impl IncomePeriod {
+    /// Reported EBITDA, or operating income plus depreciation and amortization
+    pub fn ebitda(&self) -> Option<Decimal> {
+        self.ebitda
+            .or_else(|| Some(self.operating_income? + self.depreciation_amortization?.abs()))
+    }
+}
+
+impl FiscalPeriod for IncomePeriod {
+    fn fiscal_year(&self) -> i32 {
+        self.fiscal_year
+    }
+
+    fn fiscal_quarter(&self) -> Option<u32> {
+        self.fiscal_quarter
+    }
+}
+
+/// Cash flow statement figures for one reporting period
+///
+/// Signs follow the cash flow statement: capital expenditure and working
+/// capital build-up are negative.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CashFlowPeriod {
+    pub fiscal_year: i32,
+    /// None for a full-year report, 1-4 for a quarter
+    pub fiscal_quarter: Option<u32>,
+    pub period_end: NaiveDate,
+    pub net_income: Option<Decimal>,
+    pub depreciation_amortization: Option<Decimal>,
+    pub change_in_working_capital: Option<Decimal>,
+    pub operating_cash_flow: Option<Decimal>,
+    pub capital_expenditure: Option<Decimal>,
+    /// Reported free cash flow, when the source provides it
+    pub free_cash_flow: Option<Decimal>,
+}
+
+impl CashFlowPeriod {
+    /// Reported free cash flow, or operating cash flow less capital expenditure
+    pub fn free_cash_flow(&self) -> Option<Decimal> {
+        self.free_cash_flow.or_else(|| {
+            Some(calculate_free_cash_flow(
+                self.operating_cash_flow?,
+                self.capital_expenditure.unwrap_or(Decimal::ZERO),
+            ))
+        })
+    }
+
+    /// Owner earnings for the period
+    pub fn owner_earnings(&self) -> Option<Decimal> {
+        Some(calculate_owner_earnings(
+            self.net_income?,
+            self.depreciation_amortization?,
+            self.capital_expenditure.unwrap_or(Decimal::ZERO),
+            self.change_in_working_capital.unwrap_or(Decimal::ZERO),
+        ))
+    }
+}
+
+impl FiscalPeriod for CashFlowPeriod {
+    fn fiscal_year(&self) -> i32 {
+        self.fiscal_year
+    }
+
+    fn fiscal_quarter(&self) -> Option<u32> {
+        self.fiscal_quarter
+    }
+}
+
+/// Calculate Free Cash Flow
+/// FCF = Operating Cash Flow - Capital Expenditure
+///
+/// Capital expenditure may be given with either sign.
+pub fn calculate_free_cash_flow(
+    operating_cash_flow: Decimal,
+    capital_expenditure: Decimal,
+) -> Decimal {
+    operating_cash_flow - capital_expenditure.abs()
+}
+
+/// Calculate Owner Earnings (Buffett)
+/// Owner Earnings = Net Income + D&A - CapEx + Change in Working Capital
+///
+/// All capital expenditure is treated as maintenance since IDX filings do not
+/// split out growth capex, which makes the figure conservative. The working
+/// capital change uses the cash flow statement sign (negative when working
+/// capital grows).
+pub fn calculate_owner_earnings(
+    net_income: Decimal,
+    depreciation_amortization: Decimal,
+    capital_expenditure: Decimal,
+    change_in_working_capital: Decimal,
+) -> Decimal {
+    net_income + depreciation_amortization.abs() - capital_expenditure.abs()
+        + change_in_working_capital
+}
+
+/// Calculate FCF yield (%)
+/// FCF Yield = Free Cash Flow / Market Cap
+pub fn calculate_fcf_yield(free_cash_flow: Decimal, market_cap: Decimal) -> Option<Decimal> {
+    if market_cap <= Decimal::ZERO {
+        return None;
+    }
+    Some(((free_cash_flow / market_cap) * dec!(100)).round_dp(2))
+}
+
+/// Trailing twelve months of a figure from annual and quarterly statements
+///
+/// Uses the most recent quarters when they are newer than the last annual
+/// report, and falls back to the last annual figure when the quarters needed
+/// are missing.
+pub fn trailing_twelve_months<P, F>(
+    periods: &[P],
+    basis: QuarterlyBasis,
+    value: F,
+) -> Option<Decimal>
+where
+    P: FiscalPeriod,
+    F: Fn(&P) -> Option<Decimal>,
+{
+    let annual = |year: i32| {
+        periods
+            .iter()
+            .find(|p| p.fiscal_year() == year && p.fiscal_quarter().is_none())
+            .and_then(&value)
+    };
+    let quarter = |year: i32, q: u32| {
+        periods
+            .iter()
+            .find(|p| p.fiscal_year() == year && p.fiscal_quarter() == Some(q))
+            .and_then(&value)
+    };
+
+    let latest_annual = periods
+        .iter()
+        .filter(|p| p.fiscal_quarter().is_none())
+        .map(|p| p.fiscal_year())
+        .max();
+    let latest_quarter = periods
+        .iter()
+        .filter_map(|p| p.fiscal_quarter().map(|q| (p.fiscal_year(), q)))
+        .max();
+    let annual_fallback = latest_annual.and_then(annual);
+
+    let Some((year, q)) = latest_quarter else {
+        return annual_fallback;
+    };
+    if latest_annual.is_some_and(|annual_year| annual_year >= year) {
+        return annual_fallback;
+    }
+
+    let from_quarters = match basis {
+        QuarterlyBasis::Discrete => std::iter::successors(Some((year, q)), |&(y, qq)| {
+            Some(if qq == 1 { (y - 1, 4) } else { (y, qq - 1) })
+        })
+        .take(4)
+        .map(|(y, qq)| quarter(y, qq))
+        .sum::<Option<Decimal>>(),
+        QuarterlyBasis::YearToDate if q == 4 => quarter(year, 4),
+        QuarterlyBasis::YearToDate => {
+            let prior_full_year = annual(year - 1).or_else(|| quarter(year - 1, 4));
+            match (quarter(year, q), prior_full_year, quarter(year - 1, q)) {
+                (Some(ytd), Some(prior), Some(prior_ytd)) => Some(ytd + prior - prior_ytd),
+                _ => None,
+            }
+        }
+    };
+
+    from_quarters.or(annual_fallback)
+}
+
+/// Trailing twelve months EBITDA
+pub fn ttm_ebitda(periods: &[IncomePeriod], basis: QuarterlyBasis) -> Option<Decimal> {
+    trailing_twelve_months(periods, basis, IncomePeriod::ebitda)
+}
+
+/// Trailing twelve months free cash flow
+pub fn ttm_free_cash_flow(periods: &[CashFlowPeriod], basis: QuarterlyBasis) -> Option<Decimal> {
+    trailing_twelve_months(periods, basis, CashFlowPeriod::free_cash_flow)
+}
+
+/// Trailing twelve months owner earnings
+pub fn ttm_owner_earnings(periods: &[CashFlowPeriod], basis: QuarterlyBasis) -> Option<Decimal> {
+    trailing_twelve_months(periods, basis, CashFlowPeriod::owner_earnings)
+}
+
+/// FCF yield (%) from market cap and TTM free cash flow
+pub fn fcf_yield_from_statements(
+    market_cap: Decimal,
+    periods: &[CashFlowPeriod],
+    basis: QuarterlyBasis,
+) -> Option<Decimal> {
+    calculate_fcf_yield(ttm_free_cash_flow(periods, basis)?, market_cap)
+}
+
+/// EV/EBITDA from market cap, the latest balance sheet, and TTM EBITDA
+///
+/// For tickers whose data source does not provide the ratio.
+pub fn ev_ebitda_from_statements(
+    market_cap: Decimal,
+    total_debt: Decimal,
+    cash: Decimal,
+    periods: &[IncomePeriod],
+    basis: QuarterlyBasis,
+) -> Option<Decimal> {
+    let ebitda = ttm_ebitda(periods, basis)?;
+    calculate_ev_ebitda(
+        calculate_enterprise_value(market_cap, total_debt, cash),
+        ebitda,
+    )
+}
+
 /// Calculate EV/Revenue ratio
 pub fn calculate_ev_revenue(enterprise_value: Decimal, revenue: Decimal) -> Option<Decimal> {
     if revenue <= Decimal::ZERO {
@@ -128,6 +376,31 @@ pub fn calculate_profit_margin(net_income: Decimal, revenue: Decimal) -> Option<
     Some(((net_income / revenue) * dec!(100)).round_dp(2))
 }
 
+/// Calculate revenue growth (%)
+/// Growth = (Current - Previous) / Previous
+pub fn calculate_revenue_growth(current: Decimal, previous: Decimal) -> Option<Decimal> {
+    if previous <= Decimal::ZERO {
+        return None;
+    }
+    Some((((current - previous) / previous) * dec!(100)).round_dp(2))
+}
+
+/// Year-over-year revenue growth between the two latest annual reports
+pub fn annual_revenue_growth(periods: &[IncomePeriod]) -> Option<Decimal> {
+    let revenue = |year: i32| {
+        periods
+            .iter()
+            .find(|p| p.fiscal_year == year && p.fiscal_quarter.is_none())
+            .and_then(|p| p.revenue)
+    };
+    let latest = periods
+        .iter()
+        .filter(|p| p.fiscal_quarter.is_none() && p.revenue.is_some())
+        .map(|p| p.fiscal_year)
+        .max()?;
+    calculate_revenue_growth(revenue(latest)?, revenue(latest - 1)?)
+}
+
 /// Calculate Debt-to-Equity ratio
 pub fn calculate_debt_to_equity(total_debt: Decimal, total_equity: Decimal) -> Option<Decimal> {
     if total_equity <= Decimal::ZERO {
@@ -310,6 +583,135 @@ mod tests {
         assert_eq!(calculate_ev_ebitda(dec!(1000), dec!(0)), None);
     }
 
+    fn period(year: i32, quarter: Option<u32>, ebitda: Decimal) -> IncomePeriod {
+        IncomePeriod {
+            fiscal_year: year,
+            fiscal_quarter: quarter,
+            period_end: NaiveDate::from_ymd_opt(year, quarter.unwrap_or(4) * 3, 28).unwrap(),
+            operating_income: Some(ebitda - dec!(10)),
+            depreciation_amortization: Some(dec!(-10)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_ttm_discrete_quarters() {
+        let periods = vec![
+            period(2023, None, dec!(400)),
+            period(2023, Some(2), dec!(100)),
+            period(2023, Some(3), dec!(110)),
+            period(2023, Some(4), dec!(120)),
+            period(2024, Some(1), dec!(130)),
+        ];
+        assert_eq!(
+            ttm_ebitda(&periods, QuarterlyBasis::Discrete),
+            Some(dec!(460))
+        );
+
+        // A gap in the quarters falls back to the annual report
+        let gapped: Vec<_> = periods
+            .into_iter()
+            .filter(|p| p.fiscal_quarter != Some(3))
+            .collect();
+        assert_eq!(
+            ttm_ebitda(&gapped, QuarterlyBasis::Discrete),
+            Some(dec!(400))
+        );
+    }
+
+    #[test]
+    fn test_ttm_year_to_date_quarters() {
+        let periods = vec![
+            period(2023, None, dec!(400)),
+            period(2023, Some(2), dec!(180)),
+            period(2024, Some(2), dec!(220)),
+        ];
+        // 220 YTD + 400 last year - 180 same period last year
+        assert_eq!(
+            ttm_ebitda(&periods, QuarterlyBasis::YearToDate),
+            Some(dec!(440))
+        );
+    }
+
+    #[test]
+    fn test_ev_ebitda_from_statements() {
+        let periods = vec![period(2023, None, dec!(100))];
+        assert_eq!(
+            ev_ebitda_from_statements(
+                dec!(1000),
+                dec!(200),
+                dec!(50),
+                &periods,
+                QuarterlyBasis::Discrete
+            ),
+            Some(dec!(11.5))
+        );
+        assert_eq!(
+            ev_ebitda_from_statements(
+                dec!(1000),
+                dec!(200),
+                dec!(50),
+                &[],
+                QuarterlyBasis::Discrete
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_free_cash_flow_and_yield() {
+        assert_eq!(calculate_free_cash_flow(dec!(500), dec!(-200)), dec!(300));
+        assert_eq!(calculate_free_cash_flow(dec!(500), dec!(200)), dec!(300));
+        assert_eq!(calculate_fcf_yield(dec!(300), dec!(5000)), Some(dec!(6)));
+        assert_eq!(calculate_fcf_yield(dec!(300), dec!(0)), None);
+    }
+
+    #[test]
+    fn test_ttm_owner_earnings() {
+        let quarter = |year, q, net_income| CashFlowPeriod {
+            fiscal_year: year,
+            fiscal_quarter: Some(q),
+            period_end: NaiveDate::from_ymd_opt(year, q * 3, 28).unwrap(),
+            net_income: Some(net_income),
+            depreciation_amortization: Some(dec!(20)),
+            change_in_working_capital: Some(dec!(-5)),
+            operating_cash_flow: Some(net_income + dec!(15)),
+            capital_expenditure: Some(dec!(-30)),
+            free_cash_flow: None,
+        };
+        let periods = vec![
+            quarter(2023, 3, dec!(100)),
+            quarter(2023, 4, dec!(100)),
+            quarter(2024, 1, dec!(100)),
+            quarter(2024, 2, dec!(100)),
+        ];
+
+        // Each quarter: 100 + 20 - 30 - 5 = 85
+        assert_eq!(
+            ttm_owner_earnings(&periods, QuarterlyBasis::Discrete),
+            Some(dec!(340))
+        );
+        // Each quarter: 115 - 30 = 85
+        assert_eq!(
+            fcf_yield_from_statements(dec!(3400), &periods, QuarterlyBasis::Discrete),
+            Some(dec!(10))
+        );
+    }
+
+    #[test]
+    fn test_annual_revenue_growth() {
+        let annual = |year: i32, revenue: Decimal| IncomePeriod {
+            fiscal_year: year,
+            fiscal_quarter: None,
+            period_end: NaiveDate::from_ymd_opt(year, 12, 31).unwrap(),
+            revenue: Some(revenue),
+            ..Default::default()
+        };
+        let periods = vec![annual(2024, dec!(1200)), annual(2023, dec!(1000))];
+        assert_eq!(annual_revenue_growth(&periods), Some(dec!(20)));
+        assert_eq!(annual_revenue_growth(&periods[..1]), None);
+    }
+
     #[test]
     fn test_roe() {
         assert_eq!(calculate_roe(dec!(100), dec!(500)), Some(dec!(20)));
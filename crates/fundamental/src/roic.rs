@@ -0,0 +1,198 @@
+//! Return on invested capital
+//!
+//! ROIC = NOPAT / Invested Capital, compared against WACC. A positive spread
+//! means the business earns more on its capital than that capital costs;
+//! unlike ROE it cannot be inflated by leverage.
+
+use crate::dcf::{calculate_cost_of_equity, calculate_wacc, IndonesianMarketDefaults};
+use crate::metrics::{trailing_twelve_months, IncomePeriod, QuarterlyBasis};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+/// Credit spread over the risk-free rate when interest expense is unknown
+const DEFAULT_CREDIT_SPREAD: Decimal = dec!(2.0);
+
+/// ROIC against the cost of capital
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EconomicReturns {
+    pub nopat: Decimal,
+    pub invested_capital: Decimal,
+    /// ROIC (%)
+    pub roic: Decimal,
+    /// WACC (%)
+    pub wacc: Decimal,
+    /// ROIC minus WACC (percentage points)
+    pub spread: Decimal,
+}
+
+/// Effective tax rate (%), or None when it is not meaningful
+pub fn effective_tax_rate(tax_expense: Decimal, earnings_before_tax: Decimal) -> Option<Decimal> {
+    if earnings_before_tax <= Decimal::ZERO {
+        return None;
+    }
+    let rate = (tax_expense / earnings_before_tax) * dec!(100);
+    if rate < Decimal::ZERO || rate > dec!(50) {
+        return None; // One-offs or loss carry-forwards
+    }
+    Some(rate.round_dp(2))
+}
+
+/// Calculate NOPAT
+/// NOPAT = Operating Income * (1 - Tax Rate)
+pub fn calculate_nopat(operating_income: Decimal, tax_rate: Decimal) -> Decimal {
+    operating_income * (dec!(1) - tax_rate / dec!(100))
+}
+
+/// Calculate Invested Capital
+/// Invested Capital = Total Equity + Total Debt - Cash
+pub fn calculate_invested_capital(
+    total_equity: Decimal,
+    total_debt: Decimal,
+    cash: Decimal,
+) -> Decimal {
+    total_equity + total_debt - cash
+}
+
+/// Calculate ROIC (%)
+/// ROIC = NOPAT / Invested Capital
+pub fn calculate_roic(nopat: Decimal, invested_capital: Decimal) -> Option<Decimal> {
+    if invested_capital <= Decimal::ZERO {
+        return None;
+    }
+    Some(((nopat / invested_capital) * dec!(100)).round_dp(2))
+}
+
+/// WACC (%) from market-value weights and Indonesian market defaults
+///
+/// Cost of debt is interest expense over debt when available, otherwise the
+/// risk-free rate plus a default credit spread.
+pub fn estimate_wacc(
+    market_cap: Decimal,
+    total_debt: Decimal,
+    interest_expense: Option<Decimal>,
+    beta: Option<Decimal>,
+) -> Option<Decimal> {
+    let capital = market_cap + total_debt;
+    if market_cap <= Decimal::ZERO || capital <= Decimal::ZERO {
+        return None;
+    }
+
+    let cost_of_equity = calculate_cost_of_equity(
+        IndonesianMarketDefaults::RISK_FREE_RATE,
+        beta.unwrap_or(IndonesianMarketDefaults::DEFAULT_BETA),
+        IndonesianMarketDefaults::MARKET_RISK_PREMIUM,
+    );
+    let fallback_cost_of_debt = IndonesianMarketDefaults::RISK_FREE_RATE + DEFAULT_CREDIT_SPREAD;
+    let cost_of_debt = match interest_expense {
+        Some(interest) if total_debt > Decimal::ZERO => {
+            let rate = interest.abs() / total_debt * dec!(100);
+            if rate > Decimal::ZERO && rate < dec!(30) {
+                rate
+            } else {
+                fallback_cost_of_debt
+            }
+        }
+        _ => fallback_cost_of_debt,
+    };
+
+    Some(calculate_wacc(
+        cost_of_equity,
+        cost_of_debt,
+        IndonesianMarketDefaults::TAX_RATE,
+        total_debt / capital,
+    ))
+}
+
+/// Trailing twelve months NOPAT, taxed at the TTM effective rate
+///
+/// Falls back to the statutory rate when the effective rate is not meaningful.
+pub fn ttm_nopat(periods: &[IncomePeriod], basis: QuarterlyBasis) -> Option<Decimal> {
+    let operating_income = trailing_twelve_months(periods, basis, |p| p.operating_income)?;
+    let tax_rate = trailing_twelve_months(periods, basis, |p| p.tax_expense)
+        .zip(trailing_twelve_months(periods, basis, |p| {
+            p.earnings_before_tax
+        }))
+        .and_then(|(tax, ebt)| effective_tax_rate(tax, ebt))
+        .unwrap_or(IndonesianMarketDefaults::TAX_RATE);
+    Some(calculate_nopat(operating_income, tax_rate))
+}
+
+/// ROIC and its spread over WACC from statements and market cap
+pub fn economic_returns(
+    periods: &[IncomePeriod],
+    basis: QuarterlyBasis,
+    total_equity: Decimal,
+    total_debt: Decimal,
+    cash: Decimal,
+    market_cap: Decimal,
+) -> Option<EconomicReturns> {
+    let nopat = ttm_nopat(periods, basis)?;
+    let invested_capital = calculate_invested_capital(total_equity, total_debt, cash);
+    let roic = calculate_roic(nopat, invested_capital)?;
+    let interest_expense = trailing_twelve_months(periods, basis, |p| p.interest_expense);
+    let wacc = estimate_wacc(market_cap, total_debt, interest_expense, None)?;
+
+    Some(EconomicReturns {
+        nopat: nopat.round_dp(2),
+        invested_capital,
+        roic,
+        wacc,
+        spread: roic - wacc,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_roic_components() {
+        assert_eq!(effective_tax_rate(dec!(22), dec!(100)), Some(dec!(22)));
+        assert_eq!(effective_tax_rate(dec!(10), dec!(-100)), None);
+        assert_eq!(calculate_nopat(dec!(100), dec!(25)), dec!(75));
+        assert_eq!(
+            calculate_invested_capital(dec!(800), dec!(300), dec!(100)),
+            dec!(1000)
+        );
+        assert_eq!(calculate_roic(dec!(150), dec!(1000)), Some(dec!(15)));
+        assert_eq!(calculate_roic(dec!(150), dec!(0)), None);
+    }
+
+    #[test]
+    fn test_wacc_without_debt_is_cost_of_equity() {
+        // 6.5% risk-free + 1.0 beta * 7% premium
+        assert_eq!(
+            estimate_wacc(dec!(1000), dec!(0), None, None),
+            Some(dec!(13.5))
+        );
+    }
+
+    #[test]
+    fn test_economic_returns() {
+        let periods = vec![IncomePeriod {
+            fiscal_year: 2024,
+            fiscal_quarter: None,
+            period_end: NaiveDate::from_ymd_opt(2024, 12, 31).unwrap(),
+            operating_income: Some(dec!(300)),
+            earnings_before_tax: Some(dec!(280)),
+            tax_expense: Some(dec!(56)),
+            ..Default::default()
+        }];
+
+        let returns = economic_returns(
+            &periods,
+            QuarterlyBasis::Discrete,
+            dec!(1000),
+            dec!(0),
+            dec!(0),
+            dec!(3000),
+        )
+        .unwrap();
+
+        // NOPAT 300 * (1 - 20%) = 240 on 1000 invested
+        assert_eq!(returns.roic, dec!(24));
+        assert_eq!(returns.spread, dec!(10.5));
+    }
+}
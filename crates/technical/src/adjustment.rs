@@ -0,0 +1,219 @@
+//! Split and dividend adjustment of price history
+//!
+//! Corporate actions make raw history jump on the ex-date: a 1:5 split cuts
+//! the price to a fifth overnight and a cash dividend takes its amount off
+//! the open. Adjusting scales every bar before an ex-date by that action's
+//! factor, so returns and indicators read across it while the latest bars
+//! keep their traded prices.
+
+use crate::timeframe::DatedBar;
+use crate::wyckoff::OhlcvBar;
+use chrono::NaiveDate;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// Decimal places kept in adjusted prices
+const ADJUSTED_PRICE_DP: u32 = 4;
+
+/// What a corporate action does to the share price
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AdjustmentKind {
+    /// Each share becomes `ratio` shares: 5 for a 1:5 split, 0.2 for a 5:1
+    /// reverse split, 1.1 for a 10:1 bonus issue or stock dividend
+    Split { ratio: Decimal },
+    /// Cash paid per share
+    CashDividend { amount: Decimal },
+    /// `ratio` new shares offered per share held, at `price`
+    RightsIssue { ratio: Decimal, price: Decimal },
+}
+
+/// A price-moving corporate action
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PriceAdjustment {
+    /// First session trading without the entitlement
+    pub ex_date: NaiveDate,
+    #[serde(flatten)]
+    pub kind: AdjustmentKind,
+}
+
+impl PriceAdjustment {
+    /// Multiplier for prices before the ex-date, given the last close before it
+    pub fn price_factor(&self, prior_close: Decimal) -> Decimal {
+        match self.kind {
+            AdjustmentKind::Split { ratio } if ratio > Decimal::ZERO => Decimal::ONE / ratio,
+            AdjustmentKind::CashDividend { amount }
+                if amount > Decimal::ZERO && amount < prior_close =>
+            {
+                (prior_close - amount) / prior_close
+            }
+            // Rights priced at or above the market are worth nothing
+            AdjustmentKind::RightsIssue { ratio, price }
+                if ratio > Decimal::ZERO && price < prior_close =>
+            {
+                let theoretical = (prior_close + ratio * price) / (Decimal::ONE + ratio);
+                theoretical / prior_close
+            }
+            _ => Decimal::ONE,
+        }
+    }
+
+    /// Multiplier for volumes before the ex-date
+    pub fn volume_factor(&self) -> Decimal {
+        match self.kind {
+            AdjustmentKind::Split { ratio } if ratio > Decimal::ZERO => ratio,
+            _ => Decimal::ONE,
+        }
+    }
+}
+
+/// Adjust daily bars (oldest first) for `adjustments`
+///
+/// Actions need bars on both sides of their ex-date; one before the first
+/// bar or after the last leaves the series as it is.
+pub fn adjust_bars(bars: &[DatedBar], adjustments: &[PriceAdjustment]) -> Vec<DatedBar> {
+    // Factors taking effect below each bar index
+    let mut price_at = vec![Decimal::ONE; bars.len()];
+    let mut volume_at = vec![Decimal::ONE; bars.len()];
+    for adjustment in adjustments {
+        let index = bars.partition_point(|b| b.date < adjustment.ex_date);
+        if index == 0 || index == bars.len() {
+            continue;
+        }
+        price_at[index] *= adjustment.price_factor(bars[index - 1].bar.close);
+        volume_at[index] *= adjustment.volume_factor();
+    }
+
+    let mut price_factor = Decimal::ONE;
+    let mut volume_factor = Decimal::ONE;
+    let mut adjusted: Vec<DatedBar> = bars
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(i, daily)| {
+            if i + 1 < bars.len() {
+                price_factor *= price_at[i + 1];
+                volume_factor *= volume_at[i + 1];
+            }
+            let price = |p: Decimal| (p * price_factor).round_dp(ADJUSTED_PRICE_DP);
+            DatedBar {
+                date: daily.date,
+                bar: OhlcvBar {
+                    open: price(daily.bar.open),
+                    high: price(daily.bar.high),
+                    low: price(daily.bar.low),
+                    close: price(daily.bar.close),
+                    volume: (Decimal::from(daily.bar.volume) * volume_factor)
+                        .round()
+                        .to_i64()
+                        .unwrap_or(daily.bar.volume),
+                },
+            }
+        })
+        .collect();
+    adjusted.reverse();
+    adjusted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn daily(date: &str, close: Decimal) -> DatedBar {
+        DatedBar {
+            date: date.parse().unwrap(),
+            bar: OhlcvBar {
+                open: close,
+                high: close,
+                low: close,
+                close,
+                volume: 1000,
+            },
+        }
+    }
+
+    fn action(ex_date: &str, kind: AdjustmentKind) -> PriceAdjustment {
+        PriceAdjustment {
+            ex_date: ex_date.parse().unwrap(),
+            kind,
+        }
+    }
+
+    #[test]
+    fn test_split_scales_prices_and_volume() {
+        let bars = vec![
+            daily("2025-03-03", dec!(9000)),
+            daily("2025-03-04", dec!(9200)),
+            daily("2025-03-05", dec!(1850)),
+        ];
+        let split = action("2025-03-05", AdjustmentKind::Split { ratio: dec!(5) });
+        let adjusted = adjust_bars(&bars, &[split]);
+
+        assert_eq!(adjusted[0].bar.close, dec!(1800));
+        assert_eq!(adjusted[1].bar.close, dec!(1840));
+        assert_eq!(adjusted[0].bar.volume, 5000);
+        // The ex-date and later keep their traded prices
+        assert_eq!(adjusted[2].bar.close, dec!(1850));
+        assert_eq!(adjusted[2].bar.volume, 1000);
+    }
+
+    #[test]
+    fn test_dividend_and_rights_factors() {
+        let dividend = action(
+            "2025-04-10",
+            AdjustmentKind::CashDividend { amount: dec!(200) },
+        );
+        assert_eq!(dividend.price_factor(dec!(5000)), dec!(0.96));
+        assert_eq!(dividend.volume_factor(), Decimal::ONE);
+        // A dividend the price cannot cover is bad data, not an adjustment
+        assert_eq!(dividend.price_factor(dec!(150)), Decimal::ONE);
+
+        // 1 new share per 4 held at 600 against a 1000 close: TERP 920
+        let rights = action(
+            "2025-04-10",
+            AdjustmentKind::RightsIssue {
+                ratio: dec!(0.25),
+                price: dec!(600),
+            },
+        );
+        assert_eq!(rights.price_factor(dec!(1000)), dec!(0.92));
+        let out_of_money = action(
+            "2025-04-10",
+            AdjustmentKind::RightsIssue {
+                ratio: dec!(0.25),
+                price: dec!(1200),
+            },
+        );
+        assert_eq!(out_of_money.price_factor(dec!(1000)), Decimal::ONE);
+    }
+
+    #[test]
+    fn test_factors_compound_backwards() {
+        let bars = vec![
+            daily("2025-05-02", dec!(1000)),
+            daily("2025-05-05", dec!(1000)),
+            daily("2025-05-06", dec!(500)),
+            daily("2025-05-07", dec!(450)),
+        ];
+        let adjustments = [
+            action("2025-05-06", AdjustmentKind::Split { ratio: dec!(2) }),
+            action(
+                "2025-05-07",
+                AdjustmentKind::CashDividend { amount: dec!(50) },
+            ),
+            // Outside the bars: ignored
+            action("2025-05-02", AdjustmentKind::Split { ratio: dec!(10) }),
+            action("2025-06-01", AdjustmentKind::Split { ratio: dec!(10) }),
+        ];
+        let adjusted = adjust_bars(&bars, &adjustments);
+
+        // Dividend factor 0.9 on 500; split halves the older bars as well
+        assert_eq!(adjusted[2].bar.close, dec!(450));
+        assert_eq!(adjusted[1].bar.close, dec!(450));
+        assert_eq!(adjusted[0].bar.close, dec!(450));
+        assert_eq!(adjusted[0].bar.volume, 2000);
+        assert_eq!(adjusted[3].bar.close, dec!(450));
+    }
+}
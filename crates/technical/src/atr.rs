@@ -0,0 +1,218 @@
+//! Average True Range (ATR) calculations
+//!
+//! Also provides volatility stops built on ATR:
+//! - Chandelier exit: highest high (lowest low for shorts) less a multiple of ATR
+//! - Fixed ATR stop: a multiple of ATR below (above) a reference price
+
+use crate::error::TechnicalError;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+/// Standard chandelier exit lookback (about one trading month)
+pub const CHANDELIER_PERIOD: usize = 22;
+/// Standard chandelier exit ATR multiple
+pub const CHANDELIER_MULTIPLIER: Decimal = dec!(3);
+
+/// Calculate True Range for each bar after the first
+///
+/// TR = max(high - low, |high - previous close|, |low - previous close|)
+pub fn calculate_true_range(
+    highs: &[Decimal],
+    lows: &[Decimal],
+    closes: &[Decimal],
+) -> Result<Vec<Decimal>, TechnicalError> {
+    if highs.len() != lows.len() || highs.len() != closes.len() {
+        return Err(TechnicalError::InvalidParameter(
+            "highs, lows and closes must have the same length".to_string(),
+        ));
+    }
+    if closes.len() < 2 {
+        return Err(TechnicalError::InsufficientData {
+            required: 2,
+            actual: closes.len(),
+        });
+    }
+
+    Ok((1..closes.len())
+        .map(|i| {
+            let prev_close = closes[i - 1];
+            (highs[i] - lows[i])
+                .max((highs[i] - prev_close).abs())
+                .max((lows[i] - prev_close).abs())
+        })
+        .collect())
+}
+
+/// Calculate ATR using Wilder's smoothing
+///
+/// Output is aligned with the input bars; the first `period` values are zero.
+pub fn calculate_atr(
+    highs: &[Decimal],
+    lows: &[Decimal],
+    closes: &[Decimal],
+    period: usize,
+) -> Result<Vec<Decimal>, TechnicalError> {
+    if period == 0 {
+        return Err(TechnicalError::InvalidPeriod(
+            "ATR period must be positive".to_string(),
+        ));
+    }
+    if closes.len() < period + 1 {
+        return Err(TechnicalError::InsufficientData {
+            required: period + 1,
+            actual: closes.len(),
+        });
+    }
+
+    let true_ranges = calculate_true_range(highs, lows, closes)?;
+    let period_dec = Decimal::from(period as i64);
+
+    let mut atr_values = vec![Decimal::ZERO; period];
+    let mut atr = true_ranges[..period].iter().sum::<Decimal>() / period_dec;
+    atr_values.push(atr);
+
+    for tr in true_ranges.iter().skip(period) {
+        atr = (atr * Decimal::from(period as i64 - 1) + *tr) / period_dec;
+        atr_values.push(atr);
+    }
+
+    Ok(atr_values)
+}
+
+/// Calculate ATR 14 (standard period)
+pub fn calculate_atr14(
+    highs: &[Decimal],
+    lows: &[Decimal],
+    closes: &[Decimal],
+) -> Result<Vec<Decimal>, TechnicalError> {
+    calculate_atr(highs, lows, closes, 14)
+}
+
+/// Chandelier exit stops, aligned with the input bars
+///
+/// The first `period` values are zero, matching `calculate_atr`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChandelierExit {
+    /// Trailing stop for long positions
+    pub long_stop: Vec<Decimal>,
+    /// Trailing stop for short positions
+    pub short_stop: Vec<Decimal>,
+}
+
+/// Calculate the chandelier exit
+///
+/// Long stop = highest high over `period` - `multiplier` × ATR;
+/// short stop = lowest low over `period` + `multiplier` × ATR.
+pub fn calculate_chandelier_exit(
+    highs: &[Decimal],
+    lows: &[Decimal],
+    closes: &[Decimal],
+    period: usize,
+    multiplier: Decimal,
+) -> Result<ChandelierExit, TechnicalError> {
+    if multiplier <= Decimal::ZERO {
+        return Err(TechnicalError::InvalidParameter(
+            "ATR multiplier must be positive".to_string(),
+        ));
+    }
+    let atr = calculate_atr(highs, lows, closes, period)?;
+
+    let mut long_stop = vec![Decimal::ZERO; period];
+    let mut short_stop = vec![Decimal::ZERO; period];
+    for (i, atr) in atr.iter().enumerate().skip(period) {
+        let window = i + 1 - period..=i;
+        let highest = highs[window.clone()]
+            .iter()
+            .max()
+            .copied()
+            .unwrap_or_default();
+        let lowest = lows[window].iter().min().copied().unwrap_or_default();
+        long_stop.push(highest - multiplier * atr);
+        short_stop.push(lowest + multiplier * atr);
+    }
+
+    Ok(ChandelierExit {
+        long_stop,
+        short_stop,
+    })
+}
+
+/// Calculate the standard (22, 3) chandelier exit
+pub fn calculate_chandelier_exit22(
+    highs: &[Decimal],
+    lows: &[Decimal],
+    closes: &[Decimal],
+) -> Result<ChandelierExit, TechnicalError> {
+    calculate_chandelier_exit(
+        highs,
+        lows,
+        closes,
+        CHANDELIER_PERIOD,
+        CHANDELIER_MULTIPLIER,
+    )
+}
+
+/// Stop for a long position `multiplier` ATRs below `price`, floored at zero
+pub fn atr_stop_loss(price: Decimal, atr: Decimal, multiplier: Decimal) -> Decimal {
+    (price - atr * multiplier).max(Decimal::ZERO)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_true_range_uses_gaps() {
+        let highs = vec![dec!(105), dec!(112)];
+        let lows = vec![dec!(95), dec!(108)];
+        let closes = vec![dec!(100), dec!(110)];
+
+        // Gap up: high - previous close (12) exceeds high - low (4)
+        let tr = calculate_true_range(&highs, &lows, &closes).unwrap();
+        assert_eq!(tr, vec![dec!(12)]);
+    }
+
+    #[test]
+    fn test_atr_constant_range() {
+        let closes: Vec<Decimal> = (0..20).map(|_| dec!(100)).collect();
+        let highs: Vec<Decimal> = closes.iter().map(|c| c + dec!(5)).collect();
+        let lows: Vec<Decimal> = closes.iter().map(|c| c - dec!(5)).collect();
+
+        let atr = calculate_atr14(&highs, &lows, &closes).unwrap();
+        assert_eq!(atr.len(), closes.len());
+        assert_eq!(atr[13], Decimal::ZERO);
+        assert_eq!(*atr.last().unwrap(), dec!(10));
+    }
+
+    #[test]
+    fn test_chandelier_exit() {
+        let closes: Vec<Decimal> = (0..30).map(|i| Decimal::from(100 + i)).collect();
+        let highs: Vec<Decimal> = closes.iter().map(|c| c + dec!(2)).collect();
+        let lows: Vec<Decimal> = closes.iter().map(|c| c - dec!(2)).collect();
+
+        let exit = calculate_chandelier_exit22(&highs, &lows, &closes).unwrap();
+        assert_eq!(exit.long_stop.len(), closes.len());
+        assert_eq!(exit.long_stop[21], Decimal::ZERO);
+
+        // Every true range is the high - low span of 4, so ATR = 4
+        let last = closes.len() - 1;
+        assert_eq!(exit.long_stop[last], dec!(131) - dec!(12));
+        assert_eq!(exit.short_stop[last], dec!(106) + dec!(12));
+        assert!(exit.long_stop[last] < closes[last]);
+
+        assert!(calculate_chandelier_exit(&highs, &lows, &closes, 22, dec!(0)).is_err());
+    }
+
+    #[test]
+    fn test_atr_stop_loss() {
+        assert_eq!(atr_stop_loss(dec!(1000), dec!(25), dec!(2)), dec!(950));
+        assert_eq!(atr_stop_loss(dec!(50), dec!(30), dec!(2)), Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_atr_insufficient_data() {
+        let prices = vec![dec!(100); 10];
+        assert!(calculate_atr14(&prices, &prices, &prices).is_err());
+    }
+}
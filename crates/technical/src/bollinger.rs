@@ -1,16 +1,17 @@
 //! Bollinger Bands calculations
 
 use crate::error::TechnicalError;
+use crate::series::IndicatorSeries;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
 
-/// Bollinger Bands result
+/// Bollinger Bands result; each band warms up for `period - 1` bars
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BollingerBands {
-    pub upper: Vec<Decimal>,
-    pub middle: Vec<Decimal>,
-    pub lower: Vec<Decimal>,
+    pub upper: IndicatorSeries,
+    pub middle: IndicatorSeries,
+    pub lower: IndicatorSeries,
 }
 
 /// Calculate Bollinger Bands (default: 20 period, 2 std dev)
@@ -57,14 +58,14 @@ pub fn calculate_bollinger_bands_custom(
     }
 
     Ok(BollingerBands {
-        upper,
-        middle,
-        lower,
+        upper: IndicatorSeries::new(upper, period - 1),
+        middle: IndicatorSeries::new(middle, period - 1),
+        lower: IndicatorSeries::new(lower, period - 1),
     })
 }
 
 /// Approximate square root for Decimal using Newton's method
-fn sqrt_decimal(n: Decimal) -> Decimal {
+pub(crate) fn sqrt_decimal(n: Decimal) -> Decimal {
     if n <= Decimal::ZERO {
         return Decimal::ZERO;
     }
@@ -95,12 +96,9 @@ pub fn percent_b(price: Decimal, upper: Decimal, lower: Decimal) -> Decimal {
 
 /// Interpret Bollinger Band position
 pub fn bollinger_signal(price: Decimal, bands: &BollingerBands) -> &'static str {
-    if bands.upper.is_empty() {
+    let (Some(upper), Some(lower)) = (bands.upper.last_valid(), bands.lower.last_valid()) else {
         return "neutral";
-    }
-
-    let upper = *bands.upper.last().unwrap();
-    let lower = *bands.lower.last().unwrap();
+    };
 
     if price >= upper {
         "overbought"
@@ -126,9 +124,10 @@ mod tests {
         assert_eq!(bb.lower.len(), prices.len());
 
         // Upper should be > middle > lower
+        assert_eq!(bb.middle.warmup(), 19);
         for i in 19..prices.len() {
-            assert!(bb.upper[i] >= bb.middle[i]);
-            assert!(bb.middle[i] >= bb.lower[i]);
+            assert!(bb.upper.get(i) >= bb.middle.get(i));
+            assert!(bb.middle.get(i) >= bb.lower.get(i));
         }
     }
 
@@ -175,9 +174,9 @@ mod tests {
     #[test]
     fn test_bollinger_signal_overbought() {
         let bands = BollingerBands {
-            upper: vec![dec!(110)],
-            middle: vec![dec!(100)],
-            lower: vec![dec!(90)],
+            upper: IndicatorSeries::new(vec![dec!(110)], 0),
+            middle: IndicatorSeries::new(vec![dec!(100)], 0),
+            lower: IndicatorSeries::new(vec![dec!(90)], 0),
         };
         assert_eq!(bollinger_signal(dec!(115), &bands), "overbought");
         assert_eq!(bollinger_signal(dec!(110), &bands), "overbought");
@@ -186,9 +185,9 @@ mod tests {
     #[test]
     fn test_bollinger_signal_oversold() {
         let bands = BollingerBands {
-            upper: vec![dec!(110)],
-            middle: vec![dec!(100)],
-            lower: vec![dec!(90)],
+            upper: IndicatorSeries::new(vec![dec!(110)], 0),
+            middle: IndicatorSeries::new(vec![dec!(100)], 0),
+            lower: IndicatorSeries::new(vec![dec!(90)], 0),
         };
         assert_eq!(bollinger_signal(dec!(85), &bands), "oversold");
         assert_eq!(bollinger_signal(dec!(90), &bands), "oversold");
@@ -197,9 +196,9 @@ mod tests {
     #[test]
     fn test_bollinger_signal_neutral() {
         let bands = BollingerBands {
-            upper: vec![dec!(110)],
-            middle: vec![dec!(100)],
-            lower: vec![dec!(90)],
+            upper: IndicatorSeries::new(vec![dec!(110)], 0),
+            middle: IndicatorSeries::new(vec![dec!(100)], 0),
+            lower: IndicatorSeries::new(vec![dec!(90)], 0),
         };
         assert_eq!(bollinger_signal(dec!(100), &bands), "neutral");
     }
@@ -207,9 +206,9 @@ mod tests {
     #[test]
     fn test_bollinger_signal_empty() {
         let bands = BollingerBands {
-            upper: vec![],
-            middle: vec![],
-            lower: vec![],
+            upper: IndicatorSeries::new(vec![], 0),
+            middle: IndicatorSeries::new(vec![], 0),
+            lower: IndicatorSeries::new(vec![], 0),
         };
         assert_eq!(bollinger_signal(dec!(100), &bands), "neutral");
     }
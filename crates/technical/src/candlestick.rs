@@ -0,0 +1,358 @@
+//! Candlestick Pattern Recognition
+//!
+//! Detects common reversal and continuation patterns from OHLCV bars:
+//! - Engulfing (bullish/bearish): body fully engulfs the previous body
+//! - Hammer: long lower shadow after a decline
+//! - Doji: open and close almost equal, indecision
+//! - Morning/Evening Star: three-bar reversal around a small-bodied bar
+//! - Three White Soldiers: three strong advancing bars
+
+use crate::wyckoff::OhlcvBar;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+/// Bars a hammer looks back to confirm the preceding decline
+const TREND_LOOKBACK: usize = 3;
+
+/// Direction a pattern points to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PatternBias {
+    Bullish,
+    Bearish,
+    Neutral,
+}
+
+impl PatternBias {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PatternBias::Bullish => "bullish",
+            PatternBias::Bearish => "bearish",
+            PatternBias::Neutral => "neutral",
+        }
+    }
+}
+
+/// Recognized candlestick patterns
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CandlestickPattern {
+    /// Bullish body engulfs the previous bearish body
+    BullishEngulfing,
+    /// Bearish body engulfs the previous bullish body
+    BearishEngulfing,
+    /// Small body near the high with a long lower shadow, after a decline
+    Hammer,
+    /// Open and close almost equal
+    Doji,
+    /// Large bearish bar, small-bodied bar, bullish bar closing into the first body
+    MorningStar,
+    /// Large bullish bar, small-bodied bar, bearish bar closing into the first body
+    EveningStar,
+    /// Three consecutive bullish bars closing higher, each near its high
+    ThreeWhiteSoldiers,
+}
+
+impl CandlestickPattern {
+    /// Human-readable pattern name
+    pub fn name(&self) -> &'static str {
+        match self {
+            CandlestickPattern::BullishEngulfing => "Bullish engulfing",
+            CandlestickPattern::BearishEngulfing => "Bearish engulfing",
+            CandlestickPattern::Hammer => "Hammer",
+            CandlestickPattern::Doji => "Doji",
+            CandlestickPattern::MorningStar => "Morning star",
+            CandlestickPattern::EveningStar => "Evening star",
+            CandlestickPattern::ThreeWhiteSoldiers => "Three white soldiers",
+        }
+    }
+
+    pub fn bias(&self) -> PatternBias {
+        match self {
+            CandlestickPattern::BullishEngulfing
+            | CandlestickPattern::Hammer
+            | CandlestickPattern::MorningStar
+            | CandlestickPattern::ThreeWhiteSoldiers => PatternBias::Bullish,
+            CandlestickPattern::BearishEngulfing | CandlestickPattern::EveningStar => {
+                PatternBias::Bearish
+            }
+            CandlestickPattern::Doji => PatternBias::Neutral,
+        }
+    }
+}
+
+/// A detected pattern, completed at bar `index`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CandlestickDetection {
+    pub pattern: CandlestickPattern,
+    /// Index of the last bar of the pattern
+    pub index: usize,
+    pub bias: PatternBias,
+}
+
+impl CandlestickDetection {
+    fn new(pattern: CandlestickPattern, index: usize) -> Self {
+        Self {
+            pattern,
+            index,
+            bias: pattern.bias(),
+        }
+    }
+
+    /// Signal text for score breakdowns
+    pub fn signal(&self) -> String {
+        format!(
+            "{} candlestick ({})",
+            self.pattern.name(),
+            self.bias.as_str()
+        )
+    }
+}
+
+/// Detect all candlestick patterns, in bar order
+pub fn detect_candlestick_patterns(bars: &[OhlcvBar]) -> Vec<CandlestickDetection> {
+    let mut detections = Vec::new();
+
+    for i in 0..bars.len() {
+        if is_doji(&bars[i]) {
+            detections.push(CandlestickDetection::new(CandlestickPattern::Doji, i));
+        }
+        if is_hammer(bars, i) {
+            detections.push(CandlestickDetection::new(CandlestickPattern::Hammer, i));
+        }
+        if i >= 1 {
+            let (prev, bar) = (&bars[i - 1], &bars[i]);
+            if is_bearish(prev) && is_bullish(bar) && engulfs(bar, prev) {
+                detections.push(CandlestickDetection::new(
+                    CandlestickPattern::BullishEngulfing,
+                    i,
+                ));
+            }
+            if is_bullish(prev) && is_bearish(bar) && engulfs(bar, prev) {
+                detections.push(CandlestickDetection::new(
+                    CandlestickPattern::BearishEngulfing,
+                    i,
+                ));
+            }
+        }
+        if i >= 2 {
+            let window = &bars[i - 2..=i];
+            if is_morning_star(window) {
+                detections.push(CandlestickDetection::new(
+                    CandlestickPattern::MorningStar,
+                    i,
+                ));
+            }
+            if is_evening_star(window) {
+                detections.push(CandlestickDetection::new(
+                    CandlestickPattern::EveningStar,
+                    i,
+                ));
+            }
+            if is_three_white_soldiers(window) {
+                detections.push(CandlestickDetection::new(
+                    CandlestickPattern::ThreeWhiteSoldiers,
+                    i,
+                ));
+            }
+        }
+    }
+
+    detections
+}
+
+/// Patterns completed within the last `lookback` bars
+pub fn recent_candlestick_patterns(
+    bars: &[OhlcvBar],
+    lookback: usize,
+) -> Vec<CandlestickDetection> {
+    let start = bars.len().saturating_sub(lookback);
+    detect_candlestick_patterns(bars)
+        .into_iter()
+        .filter(|d| d.index >= start)
+        .collect()
+}
+
+fn body(bar: &OhlcvBar) -> Decimal {
+    (bar.close - bar.open).abs()
+}
+
+fn range(bar: &OhlcvBar) -> Decimal {
+    bar.high - bar.low
+}
+
+fn upper_shadow(bar: &OhlcvBar) -> Decimal {
+    bar.high - bar.open.max(bar.close)
+}
+
+fn lower_shadow(bar: &OhlcvBar) -> Decimal {
+    bar.open.min(bar.close) - bar.low
+}
+
+fn is_bullish(bar: &OhlcvBar) -> bool {
+    bar.close > bar.open
+}
+
+fn is_bearish(bar: &OhlcvBar) -> bool {
+    bar.close < bar.open
+}
+
+/// Body covers at least half of the range
+fn is_long_body(bar: &OhlcvBar) -> bool {
+    range(bar) > Decimal::ZERO && body(bar) >= range(bar) * dec!(0.5)
+}
+
+fn engulfs(bar: &OhlcvBar, prev: &OhlcvBar) -> bool {
+    bar.open.min(bar.close) <= prev.open.min(prev.close)
+        && bar.open.max(bar.close) >= prev.open.max(prev.close)
+        && body(bar) > body(prev)
+}
+
+fn is_doji(bar: &OhlcvBar) -> bool {
+    range(bar) > Decimal::ZERO && body(bar) <= range(bar) * dec!(0.1)
+}
+
+fn is_hammer(bars: &[OhlcvBar], i: usize) -> bool {
+    if i < TREND_LOOKBACK {
+        return false;
+    }
+    let bar = &bars[i];
+    let body = body(bar);
+    let declining = bars[i - 1].close < bars[i - TREND_LOOKBACK].close;
+
+    declining && !is_doji(bar) && lower_shadow(bar) >= body * dec!(2) && upper_shadow(bar) <= body
+}
+
+fn is_morning_star(window: &[OhlcvBar]) -> bool {
+    let (first, star, last) = (&window[0], &window[1], &window[2]);
+    let midpoint = (first.open + first.close) / dec!(2);
+
+    is_bearish(first)
+        && is_long_body(first)
+        && body(star) <= body(first) * dec!(0.3)
+        && star.open.max(star.close) <= first.close
+        && is_bullish(last)
+        && last.close > midpoint
+}
+
+fn is_evening_star(window: &[OhlcvBar]) -> bool {
+    let (first, star, last) = (&window[0], &window[1], &window[2]);
+    let midpoint = (first.open + first.close) / dec!(2);
+
+    is_bullish(first)
+        && is_long_body(first)
+        && body(star) <= body(first) * dec!(0.3)
+        && star.open.min(star.close) >= first.close
+        && is_bearish(last)
+        && last.close < midpoint
+}
+
+fn is_three_white_soldiers(window: &[OhlcvBar]) -> bool {
+    window.iter().all(|bar| {
+        is_bullish(bar) && is_long_body(bar) && upper_shadow(bar) <= range(bar) * dec!(0.25)
+    }) && window.windows(2).all(|pair| {
+        let (prev, bar) = (&pair[0], &pair[1]);
+        bar.close > prev.close && bar.open >= prev.open && bar.open <= prev.close
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar(open: Decimal, high: Decimal, low: Decimal, close: Decimal) -> OhlcvBar {
+        OhlcvBar {
+            open,
+            high,
+            low,
+            close,
+            volume: 1_000_000,
+        }
+    }
+
+    fn patterns(bars: &[OhlcvBar]) -> Vec<(CandlestickPattern, usize)> {
+        detect_candlestick_patterns(bars)
+            .into_iter()
+            .map(|d| (d.pattern, d.index))
+            .collect()
+    }
+
+    #[test]
+    fn test_engulfing() {
+        let bullish = [
+            bar(dec!(105), dec!(106), dec!(99), dec!(100)),
+            bar(dec!(99), dec!(108), dec!(98), dec!(107)),
+        ];
+        assert!(patterns(&bullish).contains(&(CandlestickPattern::BullishEngulfing, 1)));
+
+        let bearish = [
+            bar(dec!(100), dec!(106), dec!(99), dec!(105)),
+            bar(dec!(106), dec!(107), dec!(97), dec!(98)),
+        ];
+        let detections = detect_candlestick_patterns(&bearish);
+        let engulfing = detections
+            .iter()
+            .find(|d| d.pattern == CandlestickPattern::BearishEngulfing)
+            .unwrap();
+        assert_eq!(engulfing.index, 1);
+        assert_eq!(engulfing.bias, PatternBias::Bearish);
+    }
+
+    #[test]
+    fn test_hammer_and_doji() {
+        let bars = [
+            bar(dec!(110), dec!(111), dec!(107), dec!(108)),
+            bar(dec!(108), dec!(109), dec!(104), dec!(105)),
+            bar(dec!(105), dec!(106), dec!(101), dec!(102)),
+            // Small body at the top, long lower shadow
+            bar(dec!(101), dec!(102.5), dec!(95), dec!(102)),
+            // Open == close
+            bar(dec!(102), dec!(104), dec!(100), dec!(102)),
+        ];
+        let found = patterns(&bars);
+        assert!(found.contains(&(CandlestickPattern::Hammer, 3)));
+        assert!(found.contains(&(CandlestickPattern::Doji, 4)));
+        assert!(!found.contains(&(CandlestickPattern::Hammer, 4)));
+    }
+
+    #[test]
+    fn test_stars() {
+        let morning = [
+            bar(dec!(110), dec!(111), dec!(99), dec!(100)),
+            bar(dec!(99), dec!(100), dec!(97), dec!(98.5)),
+            bar(dec!(99), dec!(108), dec!(98), dec!(107)),
+        ];
+        assert!(patterns(&morning).contains(&(CandlestickPattern::MorningStar, 2)));
+
+        let evening = [
+            bar(dec!(100), dec!(111), dec!(99), dec!(110)),
+            bar(dec!(111), dec!(113), dec!(110), dec!(111.5)),
+            bar(dec!(111), dec!(112), dec!(102), dec!(103)),
+        ];
+        assert!(patterns(&evening).contains(&(CandlestickPattern::EveningStar, 2)));
+        assert!(!patterns(&evening).contains(&(CandlestickPattern::MorningStar, 2)));
+    }
+
+    #[test]
+    fn test_three_white_soldiers_and_recent() {
+        let bars = [
+            bar(dec!(100), dec!(104.5), dec!(99.5), dec!(104)),
+            bar(dec!(103), dec!(108.5), dec!(102.5), dec!(108)),
+            bar(dec!(107), dec!(112.5), dec!(106.5), dec!(112)),
+        ];
+        let detections = detect_candlestick_patterns(&bars);
+        let soldiers = detections
+            .iter()
+            .find(|d| d.pattern == CandlestickPattern::ThreeWhiteSoldiers)
+            .unwrap();
+        assert_eq!(soldiers.index, 2);
+        assert_eq!(
+            soldiers.signal(),
+            "Three white soldiers candlestick (bullish)"
+        );
+
+        assert!(recent_candlestick_patterns(&bars, 0).is_empty());
+        assert!(!recent_candlestick_patterns(&bars, 1).is_empty());
+    }
+}
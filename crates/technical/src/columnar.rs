@@ -0,0 +1,387 @@
+//! Indicators over `f64` price columns
+//!
+//! The daily indicators of the technical analysis, computed on prices held
+//! as aligned float columns (the API's in-memory price store) rather than
+//! `Decimal` slices. Only the latest values are returned. Results match the
+//! `Decimal` versions in the sibling modules up to float rounding, including
+//! their warm-up requirements and edge cases.
+//!
+//! Not glob re-exported: use as `columnar::rsi` and so on.
+
+use crate::atr::CHANDELIER_PERIOD;
+use crate::error::TechnicalError;
+
+fn require(len: usize, required: usize) -> Result<(), TechnicalError> {
+    if len < required {
+        return Err(TechnicalError::InsufficientData {
+            required,
+            actual: len,
+        });
+    }
+    Ok(())
+}
+
+/// EMA of `values` from its first full period on, seeded with the SMA
+///
+/// Holds `values.len() - period + 1` values, the first for bar `period - 1`.
+pub fn ema(values: &[f64], period: usize) -> Result<Vec<f64>, TechnicalError> {
+    if period == 0 {
+        return Err(TechnicalError::InvalidPeriod(
+            "Period must be > 0".to_string(),
+        ));
+    }
+    require(values.len(), period)?;
+
+    let k = 2.0 / (period as f64 + 1.0);
+    let mut ema = values[..period].iter().sum::<f64>() / period as f64;
+    let mut out = Vec::with_capacity(values.len() - period + 1);
+    out.push(ema);
+    for value in &values[period..] {
+        ema = value * k + ema * (1.0 - k);
+        out.push(ema);
+    }
+    Ok(out)
+}
+
+/// Latest RSI with Wilder's smoothing, as [`crate::calculate_rsi`]
+pub fn rsi(closes: &[f64], period: usize) -> Result<f64, TechnicalError> {
+    require(closes.len(), period + 1)?;
+    if period == 0 {
+        return Err(TechnicalError::InvalidPeriod(
+            "Period must be > 0".to_string(),
+        ));
+    }
+
+    let n = period as f64;
+    let mut avg_gain = 0.0;
+    let mut avg_loss = 0.0;
+    for w in closes[..=period].windows(2) {
+        let change = w[1] - w[0];
+        if change > 0.0 {
+            avg_gain += change;
+        } else {
+            avg_loss -= change;
+        }
+    }
+    avg_gain /= n;
+    avg_loss /= n;
+    for w in closes[period..].windows(2) {
+        let change = w[1] - w[0];
+        avg_gain = (avg_gain * (n - 1.0) + change.max(0.0)) / n;
+        avg_loss = (avg_loss * (n - 1.0) + (-change).max(0.0)) / n;
+    }
+
+    // No losses scores RS as 100, like the Decimal version
+    let rs = if avg_loss == 0.0 {
+        100.0
+    } else {
+        avg_gain / avg_loss
+    };
+    Ok(100.0 - 100.0 / (1.0 + rs))
+}
+
+/// Zone of an RSI value, as [`crate::rsi_signal`]
+pub fn rsi_signal(rsi: f64) -> &'static str {
+    if rsi >= 70.0 {
+        "overbought"
+    } else if rsi <= 30.0 {
+        "oversold"
+    } else {
+        "neutral"
+    }
+}
+
+/// Latest MACD values
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Macd {
+    pub line: f64,
+    pub signal: f64,
+    pub histogram: f64,
+    /// Histogram of the bar before, if it is out of warm-up
+    pub previous_histogram: Option<f64>,
+}
+
+impl Macd {
+    /// Trend and crossover reading, as [`crate::macd_signal`]
+    pub fn signal_label(&self) -> &'static str {
+        let last = self.histogram;
+        match self.previous_histogram {
+            Some(prev) if last > 0.0 && prev <= 0.0 => "bullish_crossover",
+            Some(prev) if last < 0.0 && prev >= 0.0 => "bearish_crossover",
+            _ if last > 0.0 => "bullish",
+            _ => "bearish",
+        }
+    }
+}
+
+/// Latest MACD, as [`crate::calculate_macd_custom`]
+pub fn macd(
+    closes: &[f64],
+    fast_period: usize,
+    slow_period: usize,
+    signal_period: usize,
+) -> Result<Macd, TechnicalError> {
+    require(closes.len(), slow_period + signal_period)?;
+    let fast = ema(closes, fast_period)?;
+    let slow = ema(closes, slow_period)?;
+
+    // Both EMAs end on the last bar; the slow one starts later
+    let skip = fast.len() - slow.len();
+    let line: Vec<f64> = fast[skip..].iter().zip(&slow).map(|(f, s)| f - s).collect();
+    let signal = ema(&line, signal_period)?;
+    let histogram = |i: usize| line[line.len() - signal.len() + i] - signal[i];
+
+    let last = signal.len() - 1;
+    Ok(Macd {
+        line: line[line.len() - 1],
+        signal: signal[last],
+        histogram: histogram(last),
+        previous_histogram: last.checked_sub(1).map(histogram),
+    })
+}
+
+/// Latest MACD (12, 26, 9)
+pub fn macd_standard(closes: &[f64]) -> Result<Macd, TechnicalError> {
+    macd(closes, 12, 26, 9)
+}
+
+/// Latest Bollinger Bands
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BollingerBands {
+    pub upper: f64,
+    pub middle: f64,
+    pub lower: f64,
+}
+
+/// Latest Bollinger Bands over the population standard deviation, as
+/// [`crate::calculate_bollinger_bands_custom`]
+pub fn bollinger_bands(
+    closes: &[f64],
+    period: usize,
+    num_std_dev: f64,
+) -> Result<BollingerBands, TechnicalError> {
+    if period == 0 {
+        return Err(TechnicalError::InvalidPeriod(
+            "Period must be > 0".to_string(),
+        ));
+    }
+    require(closes.len(), period)?;
+    let window = &closes[closes.len() - period..];
+    let sma = window.iter().sum::<f64>() / period as f64;
+    let variance = window.iter().map(|p| (p - sma) * (p - sma)).sum::<f64>() / period as f64;
+    let band = variance.sqrt() * num_std_dev;
+    Ok(BollingerBands {
+        upper: sma + band,
+        middle: sma,
+        lower: sma - band,
+    })
+}
+
+/// Latest ATR with Wilder's smoothing, as [`crate::calculate_atr`]
+pub fn atr(
+    highs: &[f64],
+    lows: &[f64],
+    closes: &[f64],
+    period: usize,
+) -> Result<f64, TechnicalError> {
+    if highs.len() != lows.len() || highs.len() != closes.len() {
+        return Err(TechnicalError::InvalidParameter(
+            "highs, lows and closes must have the same length".to_string(),
+        ));
+    }
+    if period == 0 {
+        return Err(TechnicalError::InvalidPeriod(
+            "ATR period must be positive".to_string(),
+        ));
+    }
+    require(closes.len(), period + 1)?;
+
+    let true_range = |i: usize| {
+        let prev_close = closes[i - 1];
+        (highs[i] - lows[i])
+            .max((highs[i] - prev_close).abs())
+            .max((lows[i] - prev_close).abs())
+    };
+    let n = period as f64;
+    let mut atr = (1..=period).map(true_range).sum::<f64>() / n;
+    for i in period + 1..closes.len() {
+        atr = (atr * (n - 1.0) + true_range(i)) / n;
+    }
+    Ok(atr)
+}
+
+/// Latest chandelier exit for longs: the highest high over `period` less
+/// `multiplier` ATRs, as [`crate::calculate_chandelier_exit`]
+pub fn chandelier_long_stop(
+    highs: &[f64],
+    lows: &[f64],
+    closes: &[f64],
+    period: usize,
+    multiplier: f64,
+) -> Result<f64, TechnicalError> {
+    if multiplier <= 0.0 {
+        return Err(TechnicalError::InvalidParameter(
+            "ATR multiplier must be positive".to_string(),
+        ));
+    }
+    let atr = atr(highs, lows, closes, period)?;
+    let highest = highs[highs.len() - period..]
+        .iter()
+        .copied()
+        .fold(f64::MIN, f64::max);
+    Ok(highest - multiplier * atr)
+}
+
+/// Latest chandelier exit for longs over the standard 22 bars
+pub fn chandelier_long_stop22(
+    highs: &[f64],
+    lows: &[f64],
+    closes: &[f64],
+    multiplier: f64,
+) -> Result<f64, TechnicalError> {
+    chandelier_long_stop(highs, lows, closes, CHANDELIER_PERIOD, multiplier)
+}
+
+/// VWAP over every bar, on the typical price, as [`crate::calculate_vwap`]
+pub fn vwap(
+    highs: &[f64],
+    lows: &[f64],
+    closes: &[f64],
+    volumes: &[f64],
+) -> Result<f64, TechnicalError> {
+    if highs.len() != lows.len() || highs.len() != closes.len() || highs.len() != volumes.len() {
+        return Err(TechnicalError::InvalidParameter(
+            "highs, lows, closes and volumes must have the same length".to_string(),
+        ));
+    }
+    require(closes.len(), 1)?;
+
+    let mut cumulative_pv = 0.0;
+    let mut cumulative_volume = 0.0;
+    for i in 0..closes.len() {
+        let volume = volumes[i].max(0.0);
+        cumulative_pv += (highs[i] + lows[i] + closes[i]) / 3.0 * volume;
+        cumulative_volume += volume;
+    }
+    let last = closes.len() - 1;
+    Ok(if cumulative_volume > 0.0 {
+        cumulative_pv / cumulative_volume
+    } else {
+        (highs[last] + lows[last] + closes[last]) / 3.0
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        calculate_atr14, calculate_bollinger_bands, calculate_chandelier_exit22, calculate_macd,
+        calculate_rsi14, calculate_vwap, macd_signal, rsi_signal as decimal_rsi_signal, OhlcvBar,
+        CHANDELIER_MULTIPLIER,
+    };
+    use rust_decimal::prelude::ToPrimitive;
+    use rust_decimal::Decimal;
+
+    /// A choppy uptrend in whole rupiah, as IDX prices are
+    fn columns() -> (Vec<f64>, Vec<f64>, Vec<f64>, Vec<f64>) {
+        let closes: Vec<f64> = (0..120)
+            .map(|i| 1000.0 + (i % 9) as f64 * 25.0 - (i % 4) as f64 * 15.0 + i as f64 * 2.0)
+            .collect();
+        let highs = closes.iter().map(|c| c + 20.0).collect();
+        let lows = closes.iter().map(|c| c - 15.0).collect();
+        let volumes = (0..120)
+            .map(|i| 1_000_000.0 + (i % 7) as f64 * 50_000.0)
+            .collect();
+        (highs, lows, closes, volumes)
+    }
+
+    fn decimals(values: &[f64]) -> Vec<Decimal> {
+        values.iter().map(|v| Decimal::from(*v as i64)).collect()
+    }
+
+    fn close_to(a: f64, b: Decimal) -> bool {
+        (a - b.to_f64().unwrap()).abs() < 1e-6
+    }
+
+    #[test]
+    fn test_matches_decimal_indicators() {
+        let (highs, lows, closes, volumes) = columns();
+        let (h, l, c) = (decimals(&highs), decimals(&lows), decimals(&closes));
+
+        let expected_rsi = calculate_rsi14(&c).unwrap().last_valid().unwrap();
+        let rsi = rsi(&closes, 14).unwrap();
+        assert!(close_to(rsi, expected_rsi));
+        assert_eq!(rsi_signal(rsi), decimal_rsi_signal(expected_rsi));
+
+        let expected = calculate_macd(&c).unwrap();
+        let macd = macd_standard(&closes).unwrap();
+        assert!(close_to(
+            macd.line,
+            expected.macd_line.last_valid().unwrap()
+        ));
+        assert!(close_to(
+            macd.signal,
+            expected.signal_line.last_valid().unwrap()
+        ));
+        assert!(close_to(
+            macd.histogram,
+            expected.histogram.last_valid().unwrap()
+        ));
+        assert_eq!(macd.signal_label(), macd_signal(&expected));
+
+        let expected = calculate_bollinger_bands(&c).unwrap();
+        let bands = bollinger_bands(&closes, 20, 2.0).unwrap();
+        assert!(close_to(
+            bands.middle,
+            expected.middle.last_valid().unwrap()
+        ));
+        // The Decimal square root is a Newton approximation
+        assert!(
+            (bands.upper - expected.upper.last_valid().unwrap().to_f64().unwrap()).abs() < 1e-3
+        );
+
+        let expected_atr = *calculate_atr14(&h, &l, &c).unwrap().last().unwrap();
+        assert!(close_to(
+            atr(&highs, &lows, &closes, 14).unwrap(),
+            expected_atr
+        ));
+
+        let multiplier = CHANDELIER_MULTIPLIER.to_f64().unwrap();
+        let expected = calculate_chandelier_exit22(&h, &l, &c).unwrap();
+        assert!(close_to(
+            chandelier_long_stop22(&highs, &lows, &closes, multiplier).unwrap(),
+            *expected.long_stop.last().unwrap()
+        ));
+
+        let bars: Vec<OhlcvBar> = (0..closes.len())
+            .map(|i| OhlcvBar {
+                open: c[i],
+                high: h[i],
+                low: l[i],
+                close: c[i],
+                volume: volumes[i] as i64,
+            })
+            .collect();
+        let expected_vwap = *calculate_vwap(&bars).unwrap().last().unwrap();
+        assert!(close_to(
+            vwap(&highs, &lows, &closes, &volumes).unwrap(),
+            expected_vwap
+        ));
+    }
+
+    #[test]
+    fn test_edge_cases() {
+        assert!(rsi(&[100.0; 10], 14).is_err());
+        // A flat series has no losses
+        assert_eq!(rsi(&[100.0; 20], 14).unwrap(), 100.0 - 100.0 / 101.0);
+        assert!(macd_standard(&[100.0; 34]).is_err());
+        // 35 bars give the signal line two values
+        assert!(macd_standard(&[100.0; 35])
+            .unwrap()
+            .previous_histogram
+            .is_some());
+        assert!(atr(&[1.0], &[1.0, 2.0], &[1.0], 14).is_err());
+        // No volume at all falls back to the last typical price
+        assert_eq!(vwap(&[12.0], &[6.0], &[9.0], &[0.0]).unwrap(), 9.0);
+    }
+}
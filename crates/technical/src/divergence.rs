@@ -0,0 +1,411 @@
+//! Divergences between price swings and an oscillator
+//!
+//! Swing lows and highs are found on bar lows and highs; each swing is
+//! compared with the previous swing of the same side, using the oscillator
+//! value on the swing bars:
+//! - Regular bullish: lower price low, higher oscillator low (downtrend tiring)
+//! - Hidden bullish: higher price low, lower oscillator low (uptrend pullback)
+//! - Regular bearish: higher price high, lower oscillator high (uptrend tiring)
+//! - Hidden bearish: lower price high, higher oscillator high (downtrend rally)
+//!
+//! A swing needs `pivot_strength` bars on both sides, so the latest one is
+//! confirmed that many bars after it formed.
+
+use crate::error::TechnicalError;
+use crate::macd::calculate_macd;
+use crate::rsi::calculate_rsi14;
+use crate::series::IndicatorSeries;
+use crate::volume::calculate_obv;
+use crate::wyckoff::OhlcvBar;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// Oscillators divergences are checked against
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DivergenceOscillator {
+    Rsi,
+    MacdHistogram,
+    Obv,
+}
+
+impl DivergenceOscillator {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DivergenceOscillator::Rsi => "rsi",
+            DivergenceOscillator::MacdHistogram => "macd_histogram",
+            DivergenceOscillator::Obv => "obv",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DivergenceKind {
+    RegularBullish,
+    HiddenBullish,
+    RegularBearish,
+    HiddenBearish,
+}
+
+impl DivergenceKind {
+    pub fn is_bullish(&self) -> bool {
+        matches!(
+            self,
+            DivergenceKind::RegularBullish | DivergenceKind::HiddenBullish
+        )
+    }
+
+    pub fn is_hidden(&self) -> bool {
+        matches!(
+            self,
+            DivergenceKind::HiddenBullish | DivergenceKind::HiddenBearish
+        )
+    }
+}
+
+/// One divergence between two swings of the same side
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Divergence {
+    pub kind: DivergenceKind,
+    pub oscillator: DivergenceOscillator,
+    /// Bar indices of the earlier and later swing
+    pub first_pivot: usize,
+    pub second_pivot: usize,
+    /// Swing low or high of price at each pivot
+    pub first_price: Decimal,
+    pub second_price: Decimal,
+    /// Oscillator value at each pivot
+    pub first_value: Decimal,
+    pub second_value: Decimal,
+    /// 0-100, from how far price and oscillator moved apart
+    pub confidence: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DivergenceConfig {
+    /// Bars on each side a swing must exceed
+    pub pivot_strength: usize,
+    /// Fewest and most bars between the two swings
+    pub min_gap: usize,
+    pub max_gap: usize,
+}
+
+impl Default for DivergenceConfig {
+    fn default() -> Self {
+        Self {
+            pivot_strength: 3,
+            min_gap: 5,
+            max_gap: 60,
+        }
+    }
+}
+
+/// Price move, as a fraction, that earns the full price share of confidence
+const FULL_PRICE_MOVE: f64 = 0.05;
+/// Oscillator move, relative to its larger swing value, for the full share
+const FULL_OSCILLATOR_MOVE: f64 = 0.20;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Low,
+    High,
+}
+
+/// Indices of confirmed swing lows or highs
+///
+/// A swing is strictly beyond the `strength` bars before it and at least as
+/// extreme as the `strength` bars after it, so a flat top counts once.
+fn pivots(bars: &[OhlcvBar], side: Side, strength: usize) -> Vec<usize> {
+    let value = |i: usize| match side {
+        Side::Low => bars[i].low,
+        Side::High => bars[i].high,
+    };
+    let beyond = |a: Decimal, b: Decimal| match side {
+        Side::Low => a < b,
+        Side::High => a > b,
+    };
+    if strength == 0 || bars.len() < 2 * strength + 1 {
+        return Vec::new();
+    }
+    (strength..bars.len() - strength)
+        .filter(|&i| {
+            let v = value(i);
+            (i - strength..i).all(|j| beyond(v, value(j)))
+                && (i + 1..=i + strength).all(|j| !beyond(value(j), v))
+        })
+        .collect()
+}
+
+fn relative_move(from: Decimal, to: Decimal) -> f64 {
+    let scale = from.abs().max(to.abs());
+    if scale.is_zero() {
+        return 0.0;
+    }
+    ((to - from).abs() / scale).to_f64().unwrap_or(0.0)
+}
+
+/// Base 40, plus up to 30 each for the price and oscillator moves
+fn confidence(price_move: f64, oscillator_move: f64) -> u8 {
+    let price = (price_move / FULL_PRICE_MOVE).min(1.0);
+    let oscillator = (oscillator_move / FULL_OSCILLATOR_MOVE).min(1.0);
+    (40.0 + 30.0 * price + 30.0 * oscillator).round() as u8
+}
+
+/// Divergences between `bars` and `oscillator`, ordered by the later swing
+///
+/// `oscillator` must be aligned with `bars`; swings inside its warm-up are
+/// skipped.
+pub fn detect_divergences(
+    bars: &[OhlcvBar],
+    oscillator: &IndicatorSeries,
+    kind: DivergenceOscillator,
+    config: &DivergenceConfig,
+) -> Result<Vec<Divergence>, TechnicalError> {
+    if oscillator.len() != bars.len() {
+        return Err(TechnicalError::InvalidParameter(format!(
+            "Oscillator has {} values for {} bars",
+            oscillator.len(),
+            bars.len()
+        )));
+    }
+    if config.pivot_strength == 0 || config.min_gap > config.max_gap {
+        return Err(TechnicalError::InvalidParameter(
+            "Divergence needs a pivot strength of at least 1 and min_gap <= max_gap".to_string(),
+        ));
+    }
+
+    let mut divergences = Vec::new();
+    for side in [Side::Low, Side::High] {
+        let swings: Vec<(usize, Decimal, Decimal)> = pivots(bars, side, config.pivot_strength)
+            .into_iter()
+            .filter_map(|i| {
+                let price = match side {
+                    Side::Low => bars[i].low,
+                    Side::High => bars[i].high,
+                };
+                oscillator.get(i).map(|value| (i, price, value))
+            })
+            .collect();
+
+        for pair in swings.windows(2) {
+            let (first, first_price, first_value) = pair[0];
+            let (second, second_price, second_value) = pair[1];
+            let gap = second - first;
+            if gap < config.min_gap || gap > config.max_gap {
+                continue;
+            }
+
+            let divergence_kind = match side {
+                Side::Low if second_price < first_price && second_value > first_value => {
+                    DivergenceKind::RegularBullish
+                }
+                Side::Low if second_price > first_price && second_value < first_value => {
+                    DivergenceKind::HiddenBullish
+                }
+                Side::High if second_price > first_price && second_value < first_value => {
+                    DivergenceKind::RegularBearish
+                }
+                Side::High if second_price < first_price && second_value > first_value => {
+                    DivergenceKind::HiddenBearish
+                }
+                _ => continue,
+            };
+
+            divergences.push(Divergence {
+                kind: divergence_kind,
+                oscillator: kind,
+                first_pivot: first,
+                second_pivot: second,
+                first_price,
+                second_price,
+                first_value,
+                second_value,
+                confidence: confidence(
+                    relative_move(first_price, second_price),
+                    relative_move(first_value, second_value),
+                ),
+            });
+        }
+    }
+
+    divergences.sort_by_key(|d| (d.second_pivot, d.first_pivot));
+    Ok(divergences)
+}
+
+/// Divergences against RSI(14), the MACD histogram and OBV
+///
+/// An oscillator without enough bars for its warm-up is skipped.
+pub fn detect_all_divergences(
+    bars: &[OhlcvBar],
+    config: &DivergenceConfig,
+) -> Result<Vec<Divergence>, TechnicalError> {
+    let closes: Vec<Decimal> = bars.iter().map(|b| b.close).collect();
+    let volumes: Vec<i64> = bars.iter().map(|b| b.volume).collect();
+
+    let mut oscillators = Vec::new();
+    if let Ok(rsi) = calculate_rsi14(&closes) {
+        oscillators.push((DivergenceOscillator::Rsi, rsi));
+    }
+    if let Ok(macd) = calculate_macd(&closes) {
+        oscillators.push((DivergenceOscillator::MacdHistogram, macd.histogram));
+    }
+    if let Ok(obv) = calculate_obv(&closes, &volumes) {
+        let values = obv.into_iter().map(Decimal::from).collect();
+        oscillators.push((DivergenceOscillator::Obv, IndicatorSeries::new(values, 0)));
+    }
+
+    let mut divergences = Vec::new();
+    for (kind, series) in &oscillators {
+        divergences.extend(detect_divergences(bars, series, *kind, config)?);
+    }
+    divergences.sort_by_key(|d| (d.second_pivot, d.first_pivot));
+    Ok(divergences)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    /// Bars with a one-point range around `closes`
+    fn bars(closes: &[i64]) -> Vec<OhlcvBar> {
+        closes
+            .iter()
+            .map(|c| OhlcvBar {
+                open: Decimal::from(*c),
+                high: Decimal::from(*c + 1),
+                low: Decimal::from(*c - 1),
+                close: Decimal::from(*c),
+                volume: 1000,
+            })
+            .collect()
+    }
+
+    fn series(values: &[i64]) -> IndicatorSeries {
+        IndicatorSeries::new(values.iter().map(|v| Decimal::from(*v)).collect(), 0)
+    }
+
+    #[test]
+    fn test_regular_and_hidden_divergences() {
+        // Swing lows at 3 (99) and 10 (94); swing highs at 6 (111) and 13 (116)
+        let prices = bars(&[
+            105, 103, 101, 100, 104, 108, 110, 107, 101, 97, 95, 102, 109, 115, 111, 108, 106,
+        ]);
+        // Oscillator: higher low at the second low, lower high at the second high
+        let osc = series(&[
+            50, 45, 40, 30, 45, 60, 80, 65, 50, 42, 38, 55, 68, 72, 60, 55, 50,
+        ]);
+        let found = detect_divergences(
+            &prices,
+            &osc,
+            DivergenceOscillator::Rsi,
+            &DivergenceConfig::default(),
+        )
+        .unwrap();
+
+        assert_eq!(found.len(), 2);
+        let bullish = &found[0];
+        assert_eq!(bullish.kind, DivergenceKind::RegularBullish);
+        assert_eq!((bullish.first_pivot, bullish.second_pivot), (3, 10));
+        assert_eq!(
+            (bullish.first_price, bullish.second_price),
+            (dec!(99), dec!(94))
+        );
+        assert_eq!(
+            (bullish.first_value, bullish.second_value),
+            (dec!(30), dec!(38))
+        );
+        assert!(bullish.confidence > 40 && bullish.confidence <= 100);
+
+        let bearish = &found[1];
+        assert_eq!(bearish.kind, DivergenceKind::RegularBearish);
+        assert_eq!((bearish.first_pivot, bearish.second_pivot), (6, 13));
+
+        // The same swings with the oscillator confirming price: nothing
+        let osc = series(&[
+            50, 45, 40, 30, 45, 60, 80, 65, 50, 42, 20, 55, 68, 90, 60, 55, 50,
+        ]);
+        let found = detect_divergences(
+            &prices,
+            &osc,
+            DivergenceOscillator::Rsi,
+            &DivergenceConfig::default(),
+        )
+        .unwrap();
+        assert!(found.is_empty());
+
+        let higher_low = bars(&[
+            105, 103, 101, 100, 104, 108, 110, 107, 105, 103, 102, 106, 109, 112, 111, 108, 106,
+        ]);
+        let osc = series(&[
+            50, 45, 40, 35, 45, 60, 70, 65, 50, 42, 30, 55, 68, 72, 60, 55, 50,
+        ]);
+        let found = detect_divergences(
+            &higher_low,
+            &osc,
+            DivergenceOscillator::MacdHistogram,
+            &DivergenceConfig::default(),
+        )
+        .unwrap();
+        // Higher low in price, lower low in the oscillator; the highs agree
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].kind, DivergenceKind::HiddenBullish);
+        assert!(found[0].kind.is_bullish() && found[0].kind.is_hidden());
+        assert_eq!((found[0].first_pivot, found[0].second_pivot), (3, 10));
+    }
+
+    #[test]
+    fn test_divergence_input_checks_and_warmup() {
+        let prices = bars(&[
+            105, 103, 101, 100, 104, 108, 110, 107, 101, 97, 95, 102, 109, 115, 111, 108, 106,
+        ]);
+        assert!(detect_divergences(
+            &prices,
+            &series(&[1, 2, 3]),
+            DivergenceOscillator::Obv,
+            &DivergenceConfig::default()
+        )
+        .is_err());
+
+        // Swings inside the warm-up are ignored
+        let warm = IndicatorSeries::new(
+            vec![
+                dec!(50),
+                dec!(45),
+                dec!(40),
+                dec!(30),
+                dec!(45),
+                dec!(60),
+                dec!(80),
+                dec!(65),
+                dec!(50),
+                dec!(42),
+                dec!(38),
+                dec!(55),
+                dec!(68),
+                dec!(72),
+                dec!(60),
+                dec!(55),
+                dec!(50),
+            ],
+            8,
+        );
+        let found = detect_divergences(
+            &prices,
+            &warm,
+            DivergenceOscillator::Rsi,
+            &DivergenceConfig::default(),
+        )
+        .unwrap();
+        assert!(found.is_empty());
+
+        // Too few bars for any oscillator: nothing, not an error
+        assert!(
+            detect_all_divergences(&prices[..5], &DivergenceConfig::default())
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
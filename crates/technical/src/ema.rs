@@ -1,6 +1,7 @@
 //! Exponential Moving Average (EMA) calculations
 
 use crate::error::TechnicalError;
+use crate::series::IndicatorSeries;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 
@@ -8,7 +9,10 @@ use rust_decimal_macros::dec;
 ///
 /// EMA = Price(t) × k + EMA(y) × (1 − k)
 /// where k = 2 / (N + 1), N = period
-pub fn calculate_ema(prices: &[Decimal], period: usize) -> Result<Vec<Decimal>, TechnicalError> {
+///
+/// The first EMA is the SMA of the first `period` prices, so the warm-up is
+/// `period - 1` bars.
+pub fn calculate_ema(prices: &[Decimal], period: usize) -> Result<IndicatorSeries, TechnicalError> {
     if prices.len() < period {
         return Err(TechnicalError::InsufficientData {
             required: period,
@@ -25,41 +29,33 @@ pub fn calculate_ema(prices: &[Decimal], period: usize) -> Result<Vec<Decimal>,
     let k = Decimal::from(2) / Decimal::from(period as i64 + 1);
     let one_minus_k = dec!(1) - k;
 
-    let mut ema_values = Vec::with_capacity(prices.len());
+    let mut ema_values = vec![Decimal::ZERO; period - 1];
 
     // First EMA is SMA of first `period` values
-    let initial_sma: Decimal =
-        prices[..period].iter().sum::<Decimal>() / Decimal::from(period as i64);
-
-    // Fill with None for periods before we have enough data
-    for _ in 0..period - 1 {
-        ema_values.push(Decimal::ZERO);
-    }
-
-    ema_values.push(initial_sma);
+    let mut ema = prices[..period].iter().sum::<Decimal>() / Decimal::from(period as i64);
+    ema_values.push(ema);
 
     // Calculate EMA for remaining prices
     for price in prices.iter().skip(period) {
-        let prev_ema = *ema_values.last().unwrap();
-        let new_ema = (*price * k) + (prev_ema * one_minus_k);
-        ema_values.push(new_ema);
+        ema = (*price * k) + (ema * one_minus_k);
+        ema_values.push(ema);
     }
 
-    Ok(ema_values)
+    Ok(IndicatorSeries::new(ema_values, period - 1))
 }
 
 /// Calculate EMA 20 (commonly used)
-pub fn calculate_ema20(prices: &[Decimal]) -> Result<Vec<Decimal>, TechnicalError> {
+pub fn calculate_ema20(prices: &[Decimal]) -> Result<IndicatorSeries, TechnicalError> {
     calculate_ema(prices, 20)
 }
 
 /// Calculate EMA 50
-pub fn calculate_ema50(prices: &[Decimal]) -> Result<Vec<Decimal>, TechnicalError> {
+pub fn calculate_ema50(prices: &[Decimal]) -> Result<IndicatorSeries, TechnicalError> {
     calculate_ema(prices, 50)
 }
 
 /// Calculate EMA 200
-pub fn calculate_ema200(prices: &[Decimal]) -> Result<Vec<Decimal>, TechnicalError> {
+pub fn calculate_ema200(prices: &[Decimal]) -> Result<IndicatorSeries, TechnicalError> {
     calculate_ema(prices, 200)
 }
 
@@ -107,13 +103,14 @@ mod tests {
         let ema = calculate_ema(&prices, 5).unwrap();
         assert_eq!(ema.len(), prices.len());
 
-        // First 4 values should be zero (not enough data)
-        assert_eq!(ema[0], Decimal::ZERO);
-        assert_eq!(ema[3], Decimal::ZERO);
+        // First 4 values are warm-up (not enough data)
+        assert_eq!(ema.warmup(), 4);
+        assert_eq!(ema.get(0), None);
+        assert_eq!(ema.get(3), None);
 
         // 5th value should be SMA of first 5
         let expected_sma = (dec!(100) + dec!(102) + dec!(104) + dec!(103) + dec!(105)) / dec!(5);
-        assert_eq!(ema[4], expected_sma);
+        assert_eq!(ema.get(4), Some(expected_sma));
     }
 
     #[test]
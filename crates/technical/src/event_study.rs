@@ -0,0 +1,295 @@
+//! Event study
+//!
+//! Measures how a stock behaves around an event (earnings release,
+//! dividend or rights-issue announcement) by its abnormal returns: the daily
+//! return minus what the stock would have been expected to return anyway.
+//! The expectation comes from an estimation window ending before the event:
+//! - Market model: OLS of the stock's returns on the benchmark's (IHSG)
+//! - Constant mean: the stock's average return, when no benchmark is given
+//!
+//! Day 0 is the first session on or after the event date. Averaging the
+//! abnormal returns across events gives the typical reaction; returns are
+//! in percent.
+
+use crate::error::TechnicalError;
+use crate::pairs::{align_closes, ols};
+use crate::stats::mean;
+use chrono::NaiveDate;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// Sessions before, after and estimating an event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventWindow {
+    pub pre_sessions: usize,
+    pub post_sessions: usize,
+    /// Sessions fitting the expected return, ending before the window
+    pub estimation_sessions: usize,
+}
+
+impl Default for EventWindow {
+    fn default() -> Self {
+        Self {
+            pre_sessions: 5,
+            post_sessions: 10,
+            estimation_sessions: 120,
+        }
+    }
+}
+
+impl EventWindow {
+    pub fn validate(&self) -> Result<(), TechnicalError> {
+        if self.estimation_sessions < 20 {
+            return Err(TechnicalError::InvalidParameter(
+                "estimation window needs at least 20 sessions".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Day offsets of the window, `-pre..=post`
+    pub fn offsets(&self) -> Vec<i64> {
+        (-(self.pre_sessions as i64)..=self.post_sessions as i64).collect()
+    }
+}
+
+/// How expected returns were modelled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExpectedReturnModel {
+    MarketModel,
+    ConstantMean,
+}
+
+/// Abnormal returns around one event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventAbnormalReturns {
+    pub event_date: NaiveDate,
+    /// Session taken as day 0
+    pub day0: NaiveDate,
+    /// One per window offset (%)
+    pub abnormal_returns: Vec<f64>,
+    /// Cumulative abnormal return over the window (%)
+    pub car: f64,
+}
+
+/// Daily returns (%) of aligned stock and benchmark closes, dated by the later close
+fn daily_returns(aligned: &[(NaiveDate, f64, f64)]) -> Vec<(NaiveDate, f64, f64)> {
+    aligned
+        .windows(2)
+        .map(|w| {
+            (
+                w[1].0,
+                (w[1].1 / w[0].1 - 1.0) * 100.0,
+                (w[1].2 / w[0].2 - 1.0) * 100.0,
+            )
+        })
+        .collect()
+}
+
+/// Abnormal returns of `stock` around each event date
+///
+/// With an empty `benchmark` the constant-mean model is used. Events
+/// without a full estimation and event window of history are skipped.
+pub fn event_abnormal_returns(
+    stock: &[(NaiveDate, Decimal)],
+    benchmark: &[(NaiveDate, Decimal)],
+    event_dates: &[NaiveDate],
+    window: &EventWindow,
+) -> (ExpectedReturnModel, Vec<EventAbnormalReturns>) {
+    let (model, aligned) = if benchmark.is_empty() {
+        let aligned = stock
+            .iter()
+            .filter_map(|(d, c)| c.to_f64().filter(|c| *c > 0.0).map(|c| (*d, c, 1.0)))
+            .collect();
+        (ExpectedReturnModel::ConstantMean, aligned)
+    } else {
+        (
+            ExpectedReturnModel::MarketModel,
+            align_closes(stock, benchmark),
+        )
+    };
+    let returns = daily_returns(&aligned);
+
+    let events = event_dates
+        .iter()
+        .filter_map(|event_date| {
+            let day0 = returns.iter().position(|(d, _, _)| d >= event_date)?;
+            let start = day0.checked_sub(window.pre_sessions)?;
+            let end = day0 + window.post_sessions;
+            let estimation_start = start.checked_sub(window.estimation_sessions)?;
+            if end >= returns.len() {
+                return None;
+            }
+
+            let estimation = &returns[estimation_start..start];
+            let stock_est: Vec<f64> = estimation.iter().map(|r| r.1).collect();
+            // Expected return = alpha + beta × market return
+            let (alpha, beta) = match model {
+                ExpectedReturnModel::MarketModel => {
+                    let market_est: Vec<f64> = estimation.iter().map(|r| r.2).collect();
+                    ols(&market_est, &stock_est)?
+                }
+                ExpectedReturnModel::ConstantMean => (mean(&stock_est), 0.0),
+            };
+
+            let abnormal_returns: Vec<f64> = returns[start..=end]
+                .iter()
+                .map(|(_, stock, market)| stock - (alpha + beta * market))
+                .collect();
+            Some(EventAbnormalReturns {
+                event_date: *event_date,
+                day0: returns[day0].0,
+                car: abnormal_returns.iter().sum(),
+                abnormal_returns,
+            })
+        })
+        .collect();
+    (model, events)
+}
+
+/// Abnormal returns averaged across events
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventStudySummary {
+    pub events: usize,
+    pub offsets: Vec<i64>,
+    /// Average abnormal return per offset (%)
+    pub average_abnormal_returns: Vec<f64>,
+    /// Running sum of the averages (%)
+    pub cumulative_average: Vec<f64>,
+    /// Mean cumulative abnormal return over the window (%)
+    pub mean_car: f64,
+    /// Cross-sectional t-statistic of the mean CAR; `None` below two events
+    pub car_t_stat: Option<f64>,
+    /// Share of events with a positive CAR (%)
+    pub positive_car_pct: f64,
+}
+
+/// Average the abnormal returns of `events` over `window`
+pub fn summarize_events(
+    events: &[EventAbnormalReturns],
+    window: &EventWindow,
+) -> Option<EventStudySummary> {
+    if events.is_empty() {
+        return None;
+    }
+    let offsets = window.offsets();
+    let average_abnormal_returns: Vec<f64> = (0..offsets.len())
+        .map(|i| {
+            mean(
+                &events
+                    .iter()
+                    .map(|e| e.abnormal_returns[i])
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .collect();
+    let cumulative_average = average_abnormal_returns
+        .iter()
+        .scan(0.0, |sum, ar| {
+            *sum += ar;
+            Some(*sum)
+        })
+        .collect();
+
+    let cars: Vec<f64> = events.iter().map(|e| e.car).collect();
+    let mean_car = mean(&cars);
+    let car_t_stat = (cars.len() >= 2)
+        .then(|| {
+            let var =
+                cars.iter().map(|c| (c - mean_car).powi(2)).sum::<f64>() / (cars.len() - 1) as f64;
+            let se = (var / cars.len() as f64).sqrt();
+            (se > 0.0).then(|| mean_car / se)
+        })
+        .flatten();
+    let positive = cars.iter().filter(|c| **c > 0.0).count();
+
+    Some(EventStudySummary {
+        events: events.len(),
+        offsets,
+        average_abnormal_returns,
+        cumulative_average,
+        mean_car,
+        car_t_stat,
+        positive_car_pct: positive as f64 / cars.len() as f64 * 100.0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::prelude::FromPrimitive;
+
+    fn day(i: usize) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, 1).unwrap() + chrono::Duration::days(i as i64)
+    }
+
+    fn series(values: &[f64]) -> Vec<(NaiveDate, Decimal)> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (day(i), Decimal::from_f64(*v).unwrap()))
+            .collect()
+    }
+
+    fn small_window() -> EventWindow {
+        EventWindow {
+            pre_sessions: 1,
+            post_sessions: 2,
+            estimation_sessions: 20,
+        }
+    }
+
+    #[test]
+    fn test_market_model_strips_market_move() {
+        // Stock moves 2× the market; on day 30 it also jumps 5% on its own
+        let mut market = vec![1000.0];
+        let mut stock = vec![100.0];
+        for i in 1..40 {
+            let m = if i % 2 == 0 { 0.01 } else { -0.005 };
+            market.push(market[i - 1] * (1.0 + m));
+            let own = if i == 30 { 0.05 } else { 0.0 };
+            stock.push(stock[i - 1] * (1.0 + 2.0 * m + own));
+        }
+        let (model, events) = event_abnormal_returns(
+            &series(&stock),
+            &series(&market),
+            &[day(30)],
+            &small_window(),
+        );
+        assert_eq!(model, ExpectedReturnModel::MarketModel);
+        let event = &events[0];
+        assert_eq!(event.day0, day(30));
+        assert!((event.abnormal_returns[1] - 5.0).abs() < 0.1);
+        assert!(event.abnormal_returns[0].abs() < 1e-6);
+        assert!((event.car - 5.0).abs() < 0.1);
+
+        // Too close to the start for an estimation window
+        let (_, skipped) =
+            event_abnormal_returns(&series(&stock), &[], &[day(10)], &small_window());
+        assert!(skipped.is_empty());
+    }
+
+    #[test]
+    fn test_summarize_events() {
+        let window = small_window();
+        let event = |car_parts: [f64; 4]| EventAbnormalReturns {
+            event_date: day(0),
+            day0: day(0),
+            abnormal_returns: car_parts.to_vec(),
+            car: car_parts.iter().sum(),
+        };
+        let summary = summarize_events(
+            &[event([0.0, 2.0, 1.0, 0.0]), event([0.0, 4.0, -1.0, 0.0])],
+            &window,
+        )
+        .unwrap();
+        assert_eq!(summary.offsets, vec![-1, 0, 1, 2]);
+        assert_eq!(summary.average_abnormal_returns, vec![0.0, 3.0, 0.0, 0.0]);
+        assert_eq!(summary.cumulative_average[3], 3.0);
+        assert_eq!(summary.mean_car, 3.0);
+        assert_eq!(summary.positive_car_pct, 100.0);
+        assert!(summarize_events(&[], &window).is_none());
+    }
+}
@@ -0,0 +1,619 @@
+//! Custom indicator expressions
+//!
+//! A derived indicator is arithmetic (`+ - * /`, parentheses, unary minus)
+//! over built-in ones, for example `(ema20 - ema50) / atr14`. Variables are:
+//! - bar fields: `open`, `high`, `low`, `close`, `volume`
+//! - period indicators, written as key and period: `ema20`, `rsi14`,
+//!   `atr14`, `rvol20`
+//! - indicators with their default parameters: `macd`, `macd_signal`,
+//!   `macd_histogram`, `bb_upper`, `bb_middle`, `bb_lower`, `obv`, `vpt`,
+//!   `vwap`
+//!
+//! An expression evaluates to one value per bar, `None` while any variable
+//! is warming up and where a division by zero or overflow occurs.
+
+use crate::{
+    calculate_atr, calculate_bollinger_bands, calculate_ema, calculate_macd, calculate_obv,
+    calculate_rsi, calculate_rvol, calculate_vpt, calculate_vwap, IndicatorSeries, OhlcvBar,
+};
+use rust_decimal::Decimal;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::str::FromStr;
+
+/// Longest expression accepted, in characters
+pub const MAX_EXPRESSION_LEN: usize = 200;
+
+/// Largest period of a period indicator variable
+pub const MAX_EXPRESSION_PERIOD: usize = 500;
+
+/// Why an expression was rejected
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ExpressionError {
+    #[error("expression is empty")]
+    Empty,
+    #[error("expression is longer than {MAX_EXPRESSION_LEN} characters")]
+    TooLong,
+    #[error("unexpected character '{0}'")]
+    UnexpectedChar(char),
+    #[error("invalid number '{0}'")]
+    InvalidNumber(String),
+    #[error("unknown variable '{0}'")]
+    UnknownVariable(String),
+    #[error("period of '{0}' must be 1-{MAX_EXPRESSION_PERIOD}")]
+    InvalidPeriod(String),
+    #[error("expected {expected}, found {found}")]
+    Expected {
+        expected: &'static str,
+        found: String,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArithOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+impl ArithOp {
+    fn symbol(&self) -> char {
+        match self {
+            ArithOp::Add => '+',
+            ArithOp::Sub => '-',
+            ArithOp::Mul => '*',
+            ArithOp::Div => '/',
+        }
+    }
+
+    fn precedence(&self) -> u8 {
+        match self {
+            ArithOp::Add | ArithOp::Sub => 1,
+            ArithOp::Mul | ArithOp::Div => 2,
+        }
+    }
+
+    fn apply(&self, left: Decimal, right: Decimal) -> Option<Decimal> {
+        match self {
+            ArithOp::Add => left.checked_add(right),
+            ArithOp::Sub => left.checked_sub(right),
+            ArithOp::Mul => left.checked_mul(right),
+            ArithOp::Div => left.checked_div(right),
+        }
+    }
+}
+
+/// Series a variable reads
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Source {
+    Open,
+    High,
+    Low,
+    Close,
+    Volume,
+    Ema(usize),
+    Rsi(usize),
+    Atr(usize),
+    Rvol(usize),
+    Macd,
+    MacdSignal,
+    MacdHistogram,
+    BollingerUpper,
+    BollingerMiddle,
+    BollingerLower,
+    Obv,
+    Vpt,
+    Vwap,
+}
+
+/// Variables read as is, by name
+const FIXED_VARIABLES: &[(&str, Source)] = &[
+    ("open", Source::Open),
+    ("high", Source::High),
+    ("low", Source::Low),
+    ("close", Source::Close),
+    ("volume", Source::Volume),
+    ("macd", Source::Macd),
+    ("macd_signal", Source::MacdSignal),
+    ("macd_histogram", Source::MacdHistogram),
+    ("bb_upper", Source::BollingerUpper),
+    ("bb_middle", Source::BollingerMiddle),
+    ("bb_lower", Source::BollingerLower),
+    ("obv", Source::Obv),
+    ("vpt", Source::Vpt),
+    ("vwap", Source::Vwap),
+];
+
+/// Source of a period variable, given its period
+type PeriodSource = fn(usize) -> Source;
+
+/// Variables taking a period suffix, by key
+const PERIOD_VARIABLES: &[(&str, PeriodSource)] = &[
+    ("ema", Source::Ema),
+    ("rsi", Source::Rsi),
+    ("atr", Source::Atr),
+    ("rvol", Source::Rvol),
+];
+
+impl Source {
+    fn resolve(name: &str) -> Result<Self, ExpressionError> {
+        if let Some((_, source)) = FIXED_VARIABLES.iter().find(|(fixed, _)| *fixed == name) {
+            return Ok(*source);
+        }
+
+        let key = name.trim_end_matches(|c: char| c.is_ascii_digit());
+        let (_, source) = PERIOD_VARIABLES
+            .iter()
+            .find(|(period_key, _)| *period_key == key)
+            .ok_or_else(|| ExpressionError::UnknownVariable(name.to_string()))?;
+        let period = name[key.len()..]
+            .parse::<usize>()
+            .ok()
+            .filter(|p| (1..=MAX_EXPRESSION_PERIOD).contains(p))
+            .ok_or_else(|| ExpressionError::InvalidPeriod(name.to_string()))?;
+        Ok(source(period))
+    }
+
+    /// Values aligned with `bars`; all `None` without enough bars
+    fn series(&self, bars: &[OhlcvBar]) -> Vec<Option<Decimal>> {
+        let closes: Vec<Decimal> = bars.iter().map(|b| b.close).collect();
+        let volumes: Vec<i64> = bars.iter().map(|b| b.volume).collect();
+        let from_series = |series: Option<IndicatorSeries>| match series {
+            Some(series) => (0..bars.len()).map(|i| series.get(i)).collect(),
+            None => vec![None; bars.len()],
+        };
+        // Leading `warmup` values of plain vectors are padding
+        let from_values = |values: Option<Vec<Decimal>>, warmup: usize| match values {
+            Some(values) => values
+                .into_iter()
+                .enumerate()
+                .map(|(i, v)| (i >= warmup).then_some(v))
+                .collect(),
+            None => vec![None; bars.len()],
+        };
+
+        match *self {
+            Source::Open => bars.iter().map(|b| Some(b.open)).collect(),
+            Source::High => bars.iter().map(|b| Some(b.high)).collect(),
+            Source::Low => bars.iter().map(|b| Some(b.low)).collect(),
+            Source::Close => closes.into_iter().map(Some).collect(),
+            Source::Volume => volumes
+                .into_iter()
+                .map(|v| Some(Decimal::from(v)))
+                .collect(),
+            Source::Ema(period) => from_series(calculate_ema(&closes, period).ok()),
+            Source::Rsi(period) => from_series(calculate_rsi(&closes, period).ok()),
+            Source::Atr(period) => {
+                let highs: Vec<Decimal> = bars.iter().map(|b| b.high).collect();
+                let lows: Vec<Decimal> = bars.iter().map(|b| b.low).collect();
+                from_values(calculate_atr(&highs, &lows, &closes, period).ok(), period)
+            }
+            Source::Rvol(period) => from_values(calculate_rvol(&volumes, period).ok(), period),
+            Source::Macd | Source::MacdSignal | Source::MacdHistogram => {
+                from_series(calculate_macd(&closes).ok().map(|macd| match self {
+                    Source::Macd => macd.macd_line,
+                    Source::MacdSignal => macd.signal_line,
+                    _ => macd.histogram,
+                }))
+            }
+            Source::BollingerUpper | Source::BollingerMiddle | Source::BollingerLower => {
+                from_series(
+                    calculate_bollinger_bands(&closes)
+                        .ok()
+                        .map(|bands| match self {
+                            Source::BollingerUpper => bands.upper,
+                            Source::BollingerMiddle => bands.middle,
+                            _ => bands.lower,
+                        }),
+                )
+            }
+            Source::Obv => from_values(
+                calculate_obv(&closes, &volumes)
+                    .ok()
+                    .map(|obv| obv.into_iter().map(Decimal::from).collect()),
+                0,
+            ),
+            Source::Vpt => from_values(calculate_vpt(&closes, &volumes).ok(), 0),
+            Source::Vwap => from_values(calculate_vwap(bars).ok(), 0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Node {
+    Number(Decimal),
+    Variable(String),
+    Neg(Box<Node>),
+    Binary {
+        op: ArithOp,
+        left: Box<Node>,
+        right: Box<Node>,
+    },
+}
+
+impl Node {
+    fn collect_variables<'a>(&'a self, variables: &mut BTreeSet<&'a str>) {
+        match self {
+            Node::Number(_) => {}
+            Node::Variable(name) => {
+                variables.insert(name);
+            }
+            Node::Neg(inner) => inner.collect_variables(variables),
+            Node::Binary { left, right, .. } => {
+                left.collect_variables(variables);
+                right.collect_variables(variables);
+            }
+        }
+    }
+
+    fn value_at(
+        &self,
+        index: usize,
+        series: &HashMap<&str, Vec<Option<Decimal>>>,
+    ) -> Option<Decimal> {
+        match self {
+            Node::Number(value) => Some(*value),
+            Node::Variable(name) => series.get(name.as_str())?.get(index).copied().flatten(),
+            Node::Neg(inner) => inner.value_at(index, series).map(|v| -v),
+            Node::Binary { op, left, right } => op.apply(
+                left.value_at(index, series)?,
+                right.value_at(index, series)?,
+            ),
+        }
+    }
+
+    /// Write the node, parenthesized when binding looser than `min_precedence`
+    fn write(&self, f: &mut fmt::Formatter<'_>, min_precedence: u8) -> fmt::Result {
+        match self {
+            Node::Number(value) => write!(f, "{}", value),
+            Node::Variable(name) => write!(f, "{}", name),
+            Node::Neg(inner) => {
+                write!(f, "-")?;
+                inner.write(f, 3)
+            }
+            Node::Binary { op, left, right } => {
+                let precedence = op.precedence();
+                if precedence < min_precedence {
+                    write!(f, "(")?;
+                }
+                left.write(f, precedence)?;
+                write!(f, " {} ", op.symbol())?;
+                // The right operand of `-` and `/` keeps its grouping
+                let right_min = match op {
+                    ArithOp::Sub | ArithOp::Div => precedence + 1,
+                    ArithOp::Add | ArithOp::Mul => precedence,
+                };
+                right.write(f, right_min)?;
+                if precedence < min_precedence {
+                    write!(f, ")")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Parsed custom indicator expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndicatorExpr {
+    root: Node,
+}
+
+impl IndicatorExpr {
+    /// Parse and validate an expression
+    pub fn parse(input: &str) -> Result<Self, ExpressionError> {
+        if input.chars().count() > MAX_EXPRESSION_LEN {
+            return Err(ExpressionError::TooLong);
+        }
+        let tokens = tokenize(input)?;
+        if tokens.is_empty() {
+            return Err(ExpressionError::Empty);
+        }
+        let mut parser = Parser { tokens, pos: 0 };
+        let root = parser.sum()?;
+        match parser.peek() {
+            None => Ok(Self { root }),
+            Some(token) => Err(ExpressionError::Expected {
+                expected: "an operator or end of expression",
+                found: token.to_string(),
+            }),
+        }
+    }
+
+    /// Variables referenced by the expression
+    pub fn variables(&self) -> BTreeSet<&str> {
+        let mut variables = BTreeSet::new();
+        self.root.collect_variables(&mut variables);
+        variables
+    }
+
+    /// Value at every bar of `bars` (oldest first)
+    pub fn evaluate(&self, bars: &[OhlcvBar]) -> Vec<Option<Decimal>> {
+        let series: HashMap<&str, Vec<Option<Decimal>>> = self
+            .variables()
+            .into_iter()
+            .filter_map(|name| Some((name, Source::resolve(name).ok()?.series(bars))))
+            .collect();
+        (0..bars.len())
+            .map(|i| self.root.value_at(i, &series))
+            .collect()
+    }
+
+    /// Value at the last bar of `bars`
+    pub fn latest(&self, bars: &[OhlcvBar]) -> Option<Decimal> {
+        self.evaluate(bars).last().copied().flatten()
+    }
+}
+
+impl FromStr for IndicatorExpr {
+    type Err = ExpressionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// Canonical form: lowercase variables, single spaces, minimal parentheses
+impl fmt::Display for IndicatorExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.root.write(f, 0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(Decimal),
+    Op(ArithOp),
+    LParen,
+    RParen,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Ident(name) => write!(f, "'{}'", name),
+            Token::Number(value) => write!(f, "{}", value),
+            Token::Op(op) => write!(f, "'{}'", op.symbol()),
+            Token::LParen => write!(f, "'('"),
+            Token::RParen => write!(f, "')'"),
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, ExpressionError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let op = match c {
+            '+' => Some(ArithOp::Add),
+            '-' => Some(ArithOp::Sub),
+            '*' => Some(ArithOp::Mul),
+            '/' => Some(ArithOp::Div),
+            _ => None,
+        };
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            _ if op.is_some() => {
+                tokens.extend(op.map(Token::Op));
+                i += 1;
+            }
+            c if c.is_ascii_digit() || c == '.' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_digit() || "._".contains(chars[i])) {
+                    i += 1;
+                }
+                let text: String = chars[start..i].iter().collect();
+                let value = Decimal::from_str(&text.replace('_', ""))
+                    .map_err(|_| ExpressionError::InvalidNumber(text))?;
+                tokens.push(Token::Number(value));
+            }
+            c if c.is_ascii_alphabetic() => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                tokens.push(Token::Ident(word.to_ascii_lowercase()));
+            }
+            _ => return Err(ExpressionError::UnexpectedChar(c)),
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self, expected: &'static str) -> Result<Token, ExpressionError> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .ok_or(ExpressionError::Expected {
+                expected,
+                found: "end of expression".to_string(),
+            })?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    /// Left-associative chain of `operand` joined by `ops`
+    fn chain(
+        &mut self,
+        ops: [ArithOp; 2],
+        operand: fn(&mut Self) -> Result<Node, ExpressionError>,
+    ) -> Result<Node, ExpressionError> {
+        let mut node = operand(self)?;
+        while let Some(Token::Op(op)) = self.peek() {
+            let op = *op;
+            if !ops.contains(&op) {
+                break;
+            }
+            self.pos += 1;
+            node = Node::Binary {
+                op,
+                left: Box::new(node),
+                right: Box::new(operand(self)?),
+            };
+        }
+        Ok(node)
+    }
+
+    fn sum(&mut self) -> Result<Node, ExpressionError> {
+        self.chain([ArithOp::Add, ArithOp::Sub], Self::product)
+    }
+
+    fn product(&mut self) -> Result<Node, ExpressionError> {
+        self.chain([ArithOp::Mul, ArithOp::Div], Self::unary)
+    }
+
+    fn unary(&mut self) -> Result<Node, ExpressionError> {
+        if self.peek() == Some(&Token::Op(ArithOp::Sub)) {
+            self.pos += 1;
+            return Ok(Node::Neg(Box::new(self.unary()?)));
+        }
+        self.atom()
+    }
+
+    fn atom(&mut self) -> Result<Node, ExpressionError> {
+        match self.next("a number, variable or '('")? {
+            Token::Number(value) => Ok(Node::Number(value)),
+            Token::Ident(name) => {
+                Source::resolve(&name)?;
+                Ok(Node::Variable(name))
+            }
+            Token::LParen => {
+                let node = self.sum()?;
+                match self.next("')'")? {
+                    Token::RParen => Ok(node),
+                    token => Err(ExpressionError::Expected {
+                        expected: "')'",
+                        found: token.to_string(),
+                    }),
+                }
+            }
+            token => Err(ExpressionError::Expected {
+                expected: "a number, variable or '('",
+                found: token.to_string(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn bars(count: usize) -> Vec<OhlcvBar> {
+        (0..count)
+            .map(|i| {
+                let close = dec!(1000) + Decimal::from(i as i64 * 5);
+                OhlcvBar {
+                    open: close - dec!(5),
+                    high: close + dec!(10),
+                    low: close - dec!(10),
+                    close,
+                    volume: 10_000,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_and_canonical_form() {
+        let expr = IndicatorExpr::parse("(EMA20 - ema50)/atr14").unwrap();
+        assert_eq!(expr.to_string(), "(ema20 - ema50) / atr14");
+        assert_eq!(
+            expr.variables().into_iter().collect::<Vec<_>>(),
+            vec!["atr14", "ema20", "ema50"]
+        );
+        assert_eq!(
+            IndicatorExpr::parse("close - (high - low) * -2 / (1 + 1)")
+                .unwrap()
+                .to_string(),
+            "close - (high - low) * -2 / (1 + 1)"
+        );
+
+        assert_eq!(
+            IndicatorExpr::parse("ema20 + sma20"),
+            Err(ExpressionError::UnknownVariable("sma20".to_string()))
+        );
+        assert_eq!(
+            IndicatorExpr::parse("rsi0"),
+            Err(ExpressionError::InvalidPeriod("rsi0".to_string()))
+        );
+        assert_eq!(
+            IndicatorExpr::parse("ema"),
+            Err(ExpressionError::InvalidPeriod("ema".to_string()))
+        );
+        assert!(matches!(
+            IndicatorExpr::parse("(close"),
+            Err(ExpressionError::Expected { .. })
+        ));
+        assert!(matches!(
+            IndicatorExpr::parse("close close"),
+            Err(ExpressionError::Expected { .. })
+        ));
+        assert_eq!(IndicatorExpr::parse(" "), Err(ExpressionError::Empty));
+        assert_eq!(
+            IndicatorExpr::parse("close > 1"),
+            Err(ExpressionError::UnexpectedChar('>'))
+        );
+    }
+
+    #[test]
+    fn test_evaluate_aligned_with_bars() {
+        let bars = bars(60);
+        let expr = IndicatorExpr::parse("(ema20 - ema50) / atr14").unwrap();
+        let values = expr.evaluate(&bars);
+        assert_eq!(values.len(), 60);
+        // EMA50 warms up last
+        assert!(values[..49].iter().all(Option::is_none));
+        assert!(values[49..].iter().all(Option::is_some));
+
+        // In an uptrend the fast EMA is above the slow one
+        let latest = expr.latest(&bars).unwrap();
+        assert!(latest > Decimal::ZERO);
+        let ema20 = calculate_ema(&bars.iter().map(|b| b.close).collect::<Vec<_>>(), 20)
+            .unwrap()
+            .last_valid()
+            .unwrap();
+        assert_eq!(
+            IndicatorExpr::parse("ema20 * 2 - ema20")
+                .unwrap()
+                .latest(&bars),
+            Some(ema20)
+        );
+
+        // Division by zero has no value
+        let flat = IndicatorExpr::parse("close / (high - high)").unwrap();
+        assert!(flat.evaluate(&bars).iter().all(Option::is_none));
+        // Too few bars for the indicator
+        assert_eq!(
+            IndicatorExpr::parse("rsi14").unwrap().latest(&bars[..10]),
+            None
+        );
+    }
+}
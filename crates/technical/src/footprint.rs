@@ -0,0 +1,288 @@
+//! Order-flow footprint
+//!
+//! A footprint bar splits a bar's traded volume by price level into volume
+//! that lifted the offer (aggressive buys) and volume that hit the bid
+//! (aggressive sells). Each trade's aggressor is read from the quote when one
+//! is available (at or above the ask is a buy, at or below the bid a sell)
+//! and otherwise from the tick rule: an uptick is a buy, a downtick a sell
+//! and an unchanged price keeps the previous side. Trades that cannot be
+//! classified, such as the first trade without a quote, are split evenly.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Executed trade from a streaming feed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeTick {
+    pub time: DateTime<Utc>,
+    pub price: Decimal,
+    pub volume: i64,
+    /// Best bid and ask when the trade printed
+    pub bid: Option<Decimal>,
+    pub ask: Option<Decimal>,
+}
+
+/// Side that initiated a trade
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Aggressor {
+    Buy,
+    Sell,
+}
+
+/// Volume traded at one price within a footprint bar
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FootprintLevel {
+    pub price: Decimal,
+    pub buy_volume: i64,
+    pub sell_volume: i64,
+}
+
+impl FootprintLevel {
+    pub fn delta(&self) -> i64 {
+        self.buy_volume - self.sell_volume
+    }
+
+    pub fn volume(&self) -> i64 {
+        self.buy_volume + self.sell_volume
+    }
+}
+
+/// Bar with its volume split by price level and aggressor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FootprintBar {
+    pub start: DateTime<Utc>,
+    pub open: Decimal,
+    pub high: Decimal,
+    pub low: Decimal,
+    pub close: Decimal,
+    /// Price levels, lowest first
+    pub levels: Vec<FootprintLevel>,
+    pub buy_volume: i64,
+    pub sell_volume: i64,
+    /// Buy minus sell volume
+    pub delta: i64,
+    /// Price level with the most volume (point of control)
+    pub poc: Decimal,
+}
+
+#[derive(Debug, Clone)]
+struct OpenBar {
+    start: DateTime<Utc>,
+    open: Decimal,
+    high: Decimal,
+    low: Decimal,
+    close: Decimal,
+    /// (buy, sell) volume per price
+    levels: BTreeMap<Decimal, (i64, i64)>,
+}
+
+impl OpenBar {
+    fn to_bar(&self) -> FootprintBar {
+        let levels: Vec<FootprintLevel> = self
+            .levels
+            .iter()
+            .map(|(price, (buy, sell))| FootprintLevel {
+                price: *price,
+                buy_volume: *buy,
+                sell_volume: *sell,
+            })
+            .collect();
+        let buy_volume = levels.iter().map(|l| l.buy_volume).sum();
+        let sell_volume = levels.iter().map(|l| l.sell_volume).sum();
+        // Ties go to the lower price
+        let poc = levels
+            .iter()
+            .rev()
+            .max_by_key(|l| l.volume())
+            .map(|l| l.price)
+            .unwrap_or(self.close);
+        FootprintBar {
+            start: self.start,
+            open: self.open,
+            high: self.high,
+            low: self.low,
+            close: self.close,
+            levels,
+            buy_volume,
+            sell_volume,
+            delta: buy_volume - sell_volume,
+            poc,
+        }
+    }
+}
+
+/// Builds footprint bars of a fixed length from ticks in time order
+#[derive(Debug, Clone)]
+pub struct FootprintBuilder {
+    bar_seconds: i64,
+    closed: Vec<FootprintBar>,
+    current: Option<OpenBar>,
+    last_price: Option<Decimal>,
+    last_side: Option<Aggressor>,
+}
+
+impl FootprintBuilder {
+    /// Bars of `bar_seconds`, aligned to the epoch (and so to the minute)
+    pub fn new(bar_seconds: i64) -> Self {
+        Self {
+            bar_seconds: bar_seconds.max(1),
+            closed: Vec::new(),
+            current: None,
+            last_price: None,
+            last_side: None,
+        }
+    }
+
+    fn bar_start(&self, time: DateTime<Utc>) -> DateTime<Utc> {
+        let secs = time.timestamp();
+        let start = secs - secs.rem_euclid(self.bar_seconds);
+        DateTime::from_timestamp(start, 0).unwrap_or(time)
+    }
+
+    /// Aggressor of a trade by the quote rule, falling back to the tick rule
+    pub fn classify(&self, tick: &TradeTick) -> Option<Aggressor> {
+        match (tick.bid, tick.ask) {
+            (_, Some(ask)) if tick.price >= ask => return Some(Aggressor::Buy),
+            (Some(bid), _) if tick.price <= bid => return Some(Aggressor::Sell),
+            _ => {}
+        }
+        match self.last_price {
+            Some(last) if tick.price > last => Some(Aggressor::Buy),
+            Some(last) if tick.price < last => Some(Aggressor::Sell),
+            _ => self.last_side,
+        }
+    }
+
+    /// Add a trade; ticks older than the open bar are ignored
+    pub fn push(&mut self, tick: &TradeTick) {
+        if tick.volume <= 0 {
+            return;
+        }
+        let start = self.bar_start(tick.time);
+        if self.current.as_ref().is_some_and(|bar| start < bar.start) {
+            return;
+        }
+
+        let side = self.classify(tick);
+        self.last_price = Some(tick.price);
+        if side.is_some() {
+            self.last_side = side;
+        }
+
+        if self.current.as_ref().is_some_and(|bar| bar.start != start) {
+            if let Some(bar) = self.current.take() {
+                self.closed.push(bar.to_bar());
+            }
+        }
+        let bar = self.current.get_or_insert_with(|| OpenBar {
+            start,
+            open: tick.price,
+            high: tick.price,
+            low: tick.price,
+            close: tick.price,
+            levels: BTreeMap::new(),
+        });
+        bar.high = bar.high.max(tick.price);
+        bar.low = bar.low.min(tick.price);
+        bar.close = tick.price;
+
+        let level = bar.levels.entry(tick.price).or_insert((0, 0));
+        match side {
+            Some(Aggressor::Buy) => level.0 += tick.volume,
+            Some(Aggressor::Sell) => level.1 += tick.volume,
+            None => {
+                let half = tick.volume / 2;
+                level.0 += tick.volume - half;
+                level.1 += half;
+            }
+        }
+    }
+
+    /// Closed bars followed by the bar in progress
+    pub fn bars(&self) -> Vec<FootprintBar> {
+        let mut bars = self.closed.clone();
+        if let Some(ref bar) = self.current {
+            bars.push(bar.to_bar());
+        }
+        bars
+    }
+}
+
+/// Footprint bars of `bar_seconds` from ticks in time order
+pub fn build_footprint(ticks: &[TradeTick], bar_seconds: i64) -> Vec<FootprintBar> {
+    let mut builder = FootprintBuilder::new(bar_seconds);
+    for tick in ticks {
+        builder.push(tick);
+    }
+    builder.bars()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn tick(secs: i64, price: Decimal, volume: i64) -> TradeTick {
+        TradeTick {
+            time: DateTime::from_timestamp(1_709_521_200 + secs, 0).unwrap(),
+            price,
+            volume,
+            bid: None,
+            ask: None,
+        }
+    }
+
+    #[test]
+    fn test_tick_rule_and_levels() {
+        let ticks = vec![
+            // First trade has no reference: split evenly
+            tick(0, dec!(9275), 1000),
+            tick(5, dec!(9300), 500),  // uptick: buy
+            tick(10, dec!(9300), 300), // unchanged: still buy
+            tick(20, dec!(9275), 800), // downtick: sell
+        ];
+        let bars = build_footprint(&ticks, 60);
+        assert_eq!(bars.len(), 1);
+        let bar = &bars[0];
+
+        assert_eq!(bar.levels.len(), 2);
+        assert_eq!(bar.levels[0].price, dec!(9275));
+        assert_eq!(bar.levels[0].buy_volume, 500);
+        assert_eq!(bar.levels[0].sell_volume, 1300);
+        assert_eq!(bar.levels[1].buy_volume, 800);
+        assert_eq!(bar.buy_volume, 1300);
+        assert_eq!(bar.sell_volume, 1300);
+        assert_eq!(bar.delta, 0);
+        assert_eq!(bar.poc, dec!(9275));
+        assert_eq!(
+            (bar.open, bar.high, bar.low, bar.close),
+            (dec!(9275), dec!(9300), dec!(9275), dec!(9275))
+        );
+    }
+
+    #[test]
+    fn test_quote_rule_and_bar_rollover() {
+        let mut at_ask = tick(0, dec!(1000), 200);
+        at_ask.bid = Some(dec!(995));
+        at_ask.ask = Some(dec!(1000));
+        // A downtick that trades at the ask is still a buy
+        let mut builder = FootprintBuilder::new(60);
+        builder.push(&tick(0, dec!(1005), 100));
+        assert_eq!(builder.classify(&at_ask), Some(Aggressor::Buy));
+
+        builder.push(&at_ask);
+        builder.push(&tick(61, dec!(995), 400));
+        // Late tick for the closed bar is ignored
+        builder.push(&tick(30, dec!(990), 1000));
+
+        let bars = builder.bars();
+        assert_eq!(bars.len(), 2);
+        assert_eq!(bars[0].delta, 250 - 50);
+        assert_eq!(bars[1].sell_volume, 400);
+        assert_eq!(bars[1].delta, -400);
+        assert_eq!(bars[1].start.timestamp() % 60, 0);
+    }
+}
@@ -6,29 +6,83 @@
 //! - RSI (Relative Strength Index)
 //! - MACD (Moving Average Convergence Divergence)
 //! - Bollinger Bands
+//! - ATR (Average True Range)
 //! - OBV (On-Balance Volume)
 //! - VPT (Volume Price Trend)
 //! - RVOL (Relative Volume)
+//! - VWAP (Volume-Weighted Average Price), including anchored VWAP
+//! - Session VWAP deviation bands and VWAP reclaim/loss detection on intraday bars
+//! - Volume profile with point of control and 70% value area
 //! - OBI (Order Book Imbalance)
 //! - OFI (Order Flow Imbalance)
-//! - Wyckoff Phase Detection
+//! - Order-flow footprint bars with delta and cumulative delta
+//! - Wyckoff Phase Detection and phase history
+//! - Candlestick Pattern Recognition
+//! - Regular and hidden divergences between price and RSI, MACD histogram or OBV
+//! - Streaming (incremental) indicator state for live price feeds
+//! - Resampling daily bars to weekly and monthly timeframes
+//! - Split, dividend and rights-issue adjustment of price history
+//! - Renko bricks (fixed or ATR size) and Point & Figure columns
+//! - Multi-horizon momentum ranking with an optional skip month
+//! - Pair analysis: price ratio z-score and Engle-Granger cointegration
+//! - Event studies: abnormal returns around corporate events
+//! - Return statistics: log returns, correlation and beta
+//! - A registry describing every indicator's parameters, outputs and warm-up
+//! - Custom indicator expressions: arithmetic over built-in indicators
+//!
+//! EMA, RSI, MACD and Bollinger Bands return `IndicatorSeries`, which stays
+//! aligned with the input bars and marks the warm-up values. `columnar` has
+//! the latest values of the daily analysis indicators over `f64` columns.
 
+pub mod adjustment;
+pub mod atr;
 pub mod bollinger;
+pub mod candlestick;
+pub mod columnar;
+pub mod divergence;
 pub mod ema;
 pub mod error;
+pub mod event_study;
+pub mod expression;
 pub mod fibonacci;
+pub mod footprint;
 pub mod macd;
+pub mod momentum;
 pub mod orderflow;
+pub mod pairs;
+pub mod registry;
+pub mod renko;
 pub mod rsi;
+pub mod series;
+pub mod session_vwap;
+pub mod stats;
+pub mod streaming;
+pub mod timeframe;
 pub mod volume;
 pub mod wyckoff;
 
+pub use adjustment::*;
+pub use atr::*;
 pub use bollinger::*;
+pub use candlestick::*;
+pub use divergence::*;
 pub use ema::*;
 pub use error::*;
+pub use event_study::*;
+pub use expression::*;
 pub use fibonacci::*;
+pub use footprint::*;
 pub use macd::*;
+pub use momentum::*;
 pub use orderflow::*;
+pub use pairs::*;
+pub use registry::*;
+pub use renko::*;
 pub use rsi::*;
+pub use series::*;
+pub use session_vwap::*;
+pub use stats::*;
+pub use streaming::*;
+pub use timeframe::*;
 pub use volume::*;
 pub use wyckoff::*;
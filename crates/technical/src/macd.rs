@@ -1,15 +1,18 @@
 //! MACD (Moving Average Convergence Divergence) calculations
 
-use crate::{calculate_ema, error::TechnicalError};
+use crate::{calculate_ema, error::TechnicalError, series::IndicatorSeries};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
 /// MACD result containing line, signal, and histogram
+///
+/// The MACD line warms up with the slow EMA; the signal line and histogram
+/// need a further `signal_period - 1` bars.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MacdResult {
-    pub macd_line: Vec<Decimal>,
-    pub signal_line: Vec<Decimal>,
-    pub histogram: Vec<Decimal>,
+    pub macd_line: IndicatorSeries,
+    pub signal_line: IndicatorSeries,
+    pub histogram: IndicatorSeries,
 }
 
 /// Calculate MACD with default parameters (12, 26, 9)
@@ -35,21 +38,13 @@ pub fn calculate_macd_custom(
     let ema_slow = calculate_ema(prices, slow_period)?;
 
     // MACD line = EMA(fast) - EMA(slow)
-    let macd_line: Vec<Decimal> = ema_fast
-        .iter()
-        .zip(ema_slow.iter())
-        .map(|(f, s)| *f - *s)
-        .collect();
+    let macd_line = ema_fast.zip_with(&ema_slow, |f, s| f - s);
 
-    // Signal line = EMA of MACD line
-    let signal_line = calculate_ema(&macd_line, signal_period)?;
+    // Signal line = EMA of the MACD line, from its first valid value
+    let signal_line = calculate_ema(macd_line.valid(), signal_period)?.offset(macd_line.warmup());
 
     // Histogram = MACD line - Signal line
-    let histogram: Vec<Decimal> = macd_line
-        .iter()
-        .zip(signal_line.iter())
-        .map(|(m, s)| *m - *s)
-        .collect();
+    let histogram = macd_line.zip_with(&signal_line, |m, s| m - s);
 
     Ok(MacdResult {
         macd_line,
@@ -60,12 +55,11 @@ pub fn calculate_macd_custom(
 
 /// Interpret MACD signal
 pub fn macd_signal(macd: &MacdResult) -> &'static str {
-    if macd.histogram.is_empty() {
+    let histogram = macd.histogram.valid();
+    let Some(last_hist) = histogram.last() else {
         return "neutral";
-    }
-
-    let last_hist = macd.histogram.last().unwrap();
-    let prev_hist = macd.histogram.get(macd.histogram.len().saturating_sub(2));
+    };
+    let prev_hist = histogram.len().checked_sub(2).map(|i| &histogram[i]);
 
     match prev_hist {
         Some(prev) => {
@@ -103,6 +97,10 @@ mod tests {
         assert_eq!(macd.macd_line.len(), prices.len());
         assert_eq!(macd.signal_line.len(), prices.len());
         assert_eq!(macd.histogram.len(), prices.len());
+
+        assert_eq!(macd.macd_line.warmup(), 25);
+        assert_eq!(macd.signal_line.warmup(), 33);
+        assert_eq!(macd.histogram.warmup(), 33);
     }
 
     #[test]
@@ -115,9 +113,9 @@ mod tests {
     #[test]
     fn test_macd_signal_bullish() {
         let macd = MacdResult {
-            macd_line: vec![dec!(1)],
-            signal_line: vec![dec!(0)],
-            histogram: vec![dec!(1)],
+            macd_line: IndicatorSeries::new(vec![dec!(1)], 0),
+            signal_line: IndicatorSeries::new(vec![dec!(0)], 0),
+            histogram: IndicatorSeries::new(vec![dec!(1)], 0),
         };
         assert_eq!(macd_signal(&macd), "bullish");
     }
@@ -125,9 +123,9 @@ mod tests {
     #[test]
     fn test_macd_signal_bearish() {
         let macd = MacdResult {
-            macd_line: vec![dec!(-1)],
-            signal_line: vec![dec!(0)],
-            histogram: vec![dec!(-1)],
+            macd_line: IndicatorSeries::new(vec![dec!(-1)], 0),
+            signal_line: IndicatorSeries::new(vec![dec!(0)], 0),
+            histogram: IndicatorSeries::new(vec![dec!(-1)], 0),
         };
         assert_eq!(macd_signal(&macd), "bearish");
     }
@@ -135,9 +133,9 @@ mod tests {
     #[test]
     fn test_macd_signal_bullish_crossover() {
         let macd = MacdResult {
-            macd_line: vec![dec!(-1), dec!(1)],
-            signal_line: vec![dec!(0), dec!(0)],
-            histogram: vec![dec!(-1), dec!(1)],
+            macd_line: IndicatorSeries::new(vec![dec!(-1), dec!(1)], 0),
+            signal_line: IndicatorSeries::new(vec![dec!(0), dec!(0)], 0),
+            histogram: IndicatorSeries::new(vec![dec!(-1), dec!(1)], 0),
         };
         assert_eq!(macd_signal(&macd), "bullish_crossover");
     }
@@ -145,9 +143,9 @@ mod tests {
     #[test]
     fn test_macd_signal_bearish_crossover() {
         let macd = MacdResult {
-            macd_line: vec![dec!(1), dec!(-1)],
-            signal_line: vec![dec!(0), dec!(0)],
-            histogram: vec![dec!(1), dec!(-1)],
+            macd_line: IndicatorSeries::new(vec![dec!(1), dec!(-1)], 0),
+            signal_line: IndicatorSeries::new(vec![dec!(0), dec!(0)], 0),
+            histogram: IndicatorSeries::new(vec![dec!(1), dec!(-1)], 0),
         };
         assert_eq!(macd_signal(&macd), "bearish_crossover");
     }
@@ -155,9 +153,9 @@ mod tests {
     #[test]
     fn test_macd_signal_empty() {
         let macd = MacdResult {
-            macd_line: vec![],
-            signal_line: vec![],
-            histogram: vec![],
+            macd_line: IndicatorSeries::new(vec![], 0),
+            signal_line: IndicatorSeries::new(vec![], 0),
+            histogram: IndicatorSeries::new(vec![], 0),
         };
         assert_eq!(macd_signal(&macd), "neutral");
     }
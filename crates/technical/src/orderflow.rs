@@ -3,11 +3,26 @@
 //! Order flow indicators measure buying and selling pressure:
 //! - OBI (Order Book Imbalance): Measures bid/ask volume imbalance
 //! - OFI (Order Flow Imbalance): Measures changes in bid/ask volumes
+//! - Delta and cumulative delta of aggressive buy/sell volume per footprint bar
+//! - Daily OBI/OFI proxies from OHLCV bars and a rolling z-score of OFI
+//! - Depth OBI and multi-level OFI from full bid/ask ladders
+//!
+//! Without order book history, the daily proxies read pressure from where
+//! the close sits in the day's range: the OBI proxy is the money flow
+//! multiplier (-1 to +1) and the OFI proxy is that imbalance in shares.
+//! Where depth snapshots are available, OFI is summed over the top levels of
+//! successive snapshots instead.
 
 use crate::error::TechnicalError;
+use crate::footprint::FootprintBar;
+use crate::series::IndicatorSeries;
+use crate::wyckoff::OhlcvBar;
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
 
 /// Order book snapshot at a point in time
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -227,6 +242,164 @@ pub fn calculate_vamp(
     (bid_price * ask_vol + ask_price * bid_vol) / Decimal::from(total_volume)
 }
 
+/// Book levels read by the depth OBI and OFI by default
+pub const DEFAULT_DEPTH_LEVELS: usize = 5;
+
+/// Resting volume at one price
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BookLevel {
+    pub price: Decimal,
+    pub volume: i64,
+}
+
+/// Bid and ask ladders at a point in time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DepthSnapshot {
+    pub time: DateTime<Utc>,
+    /// Best (highest) bid first
+    pub bids: Vec<BookLevel>,
+    /// Best (lowest) ask first
+    pub asks: Vec<BookLevel>,
+}
+
+impl DepthSnapshot {
+    /// Snapshot with the ladders sorted best first and empty levels dropped
+    pub fn new(time: DateTime<Utc>, mut bids: Vec<BookLevel>, mut asks: Vec<BookLevel>) -> Self {
+        bids.retain(|l| l.volume > 0);
+        asks.retain(|l| l.volume > 0);
+        bids.sort_by_key(|l| Reverse(l.price));
+        asks.sort_by_key(|l| l.price);
+        Self { time, bids, asks }
+    }
+
+    /// Whether the best bid is at or above the best ask
+    pub fn is_crossed(&self) -> bool {
+        match (self.bids.first(), self.asks.first()) {
+            (Some(bid), Some(ask)) => bid.price >= ask.price,
+            _ => false,
+        }
+    }
+
+    pub fn mid_price(&self) -> Option<Decimal> {
+        Some((self.bids.first()?.price + self.asks.first()?.price) / dec!(2))
+    }
+
+    /// Level `index` of both sides as a top-of-book snapshot
+    pub fn level(&self, index: usize) -> Option<OrderBookSnapshot> {
+        let (bid, ask) = (self.bids.get(index)?, self.asks.get(index)?);
+        Some(OrderBookSnapshot {
+            timestamp: self.time.timestamp(),
+            bid_price: bid.price,
+            bid_volume: bid.volume,
+            ask_price: ask.price,
+            ask_volume: ask.volume,
+        })
+    }
+}
+
+/// OBI over the top `levels` of each side of the book
+pub fn calculate_depth_obi(snapshot: &DepthSnapshot, levels: usize) -> ObiResult {
+    let bid_volume = snapshot.bids.iter().take(levels).map(|l| l.volume).sum();
+    let ask_volume = snapshot.asks.iter().take(levels).map(|l| l.volume).sum();
+    calculate_obi(bid_volume, ask_volume)
+}
+
+/// Multi-level OFI between two depth snapshots
+///
+/// The OFI of each of the top `levels` is taken as if that level were the
+/// top of the book, and the levels are summed. Levels missing from either
+/// snapshot are skipped.
+pub fn calculate_depth_ofi(
+    prev: &DepthSnapshot,
+    current: &DepthSnapshot,
+    levels: usize,
+) -> OfiResult {
+    let ofi: Decimal = (0..levels)
+        .filter_map(|i| Some(calculate_ofi(&prev.level(i)?, &current.level(i)?).ofi))
+        .sum();
+    let interpretation = if ofi > Decimal::ZERO {
+        "buying_pressure"
+    } else if ofi < Decimal::ZERO {
+        "selling_pressure"
+    } else {
+        "neutral"
+    };
+    OfiResult {
+        ofi,
+        cumulative_ofi: ofi,
+        interpretation: interpretation.to_string(),
+    }
+}
+
+/// Live OBI and cumulative OFI of one symbol
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LiveOrderFlow {
+    pub time: DateTime<Utc>,
+    pub obi: Decimal,
+    /// OFI since the previous snapshot
+    pub ofi: Decimal,
+    /// OFI summed since the tracker started
+    pub cumulative_ofi: Decimal,
+    pub snapshots: usize,
+}
+
+/// Incremental depth OBI/OFI over successive snapshots of one symbol
+#[derive(Debug, Clone)]
+pub struct DepthFlowTracker {
+    levels: usize,
+    previous: Option<DepthSnapshot>,
+    cumulative_ofi: Decimal,
+    snapshots: usize,
+}
+
+impl DepthFlowTracker {
+    pub fn new(levels: usize) -> Self {
+        Self {
+            levels: levels.max(1),
+            previous: None,
+            cumulative_ofi: Decimal::ZERO,
+            snapshots: 0,
+        }
+    }
+
+    /// Add the next snapshot
+    ///
+    /// Snapshots older than the previous one and crossed books are ignored
+    /// and return `None`.
+    pub fn update(&mut self, snapshot: DepthSnapshot) -> Option<LiveOrderFlow> {
+        if snapshot.is_crossed()
+            || self
+                .previous
+                .as_ref()
+                .is_some_and(|prev| snapshot.time < prev.time)
+        {
+            return None;
+        }
+        let ofi = self
+            .previous
+            .as_ref()
+            .map(|prev| calculate_depth_ofi(prev, &snapshot, self.levels).ofi)
+            .unwrap_or_default();
+        self.cumulative_ofi += ofi;
+        self.snapshots += 1;
+        let flow = LiveOrderFlow {
+            time: snapshot.time,
+            obi: calculate_depth_obi(&snapshot, self.levels).obi,
+            ofi,
+            cumulative_ofi: self.cumulative_ofi,
+            snapshots: self.snapshots,
+        };
+        self.previous = Some(snapshot);
+        Some(flow)
+    }
+}
+
+impl Default for DepthFlowTracker {
+    fn default() -> Self {
+        Self::new(DEFAULT_DEPTH_LEVELS)
+    }
+}
+
 /// Calculate buy/sell volume split based on price position
 /// Buy volume = Volume × (Close - Low) / (High - Low)
 /// Sell volume = Volume × (High - Close) / (High - Low)
@@ -296,6 +469,95 @@ pub fn calculate_adl(
     Ok(adl)
 }
 
+/// Per-bar delta (aggressive buy minus sell volume) and its running total
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DeltaSeries {
+    pub delta: Vec<i64>,
+    pub cumulative_delta: Vec<i64>,
+}
+
+/// Delta series of footprint bars, aligned with the bars
+pub fn calculate_delta_series(bars: &[FootprintBar]) -> DeltaSeries {
+    let delta: Vec<i64> = bars.iter().map(|b| b.delta).collect();
+    let cumulative_delta = delta
+        .iter()
+        .scan(0i64, |total, d| {
+            *total += d;
+            Some(*total)
+        })
+        .collect();
+    DeltaSeries {
+        delta,
+        cumulative_delta,
+    }
+}
+
+/// Sessions in the default OFI z-score window
+pub const OFI_ZSCORE_PERIOD: usize = 20;
+
+/// Daily order flow proxies of one bar
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DailyOrderFlow {
+    /// Close location in the day's range, -1 to +1
+    pub obi: Decimal,
+    /// Signed share imbalance: `obi × volume`
+    pub ofi: Decimal,
+}
+
+/// Daily OBI/OFI proxies, aligned with the bars
+pub fn daily_order_flow(bars: &[OhlcvBar]) -> Vec<DailyOrderFlow> {
+    bars.iter()
+        .map(|bar| {
+            let obi = money_flow_multiplier(bar.high, bar.low, bar.close);
+            DailyOrderFlow {
+                obi,
+                ofi: obi * Decimal::from(bar.volume.max(0)),
+            }
+        })
+        .collect()
+}
+
+/// Rolling z-score of OFI values over `period` sessions
+///
+/// Each value is compared with the mean and standard deviation of the window
+/// ending at it, so a day is judged against the stock's own recent flow
+/// rather than in absolute shares. A flat window scores zero.
+pub fn calculate_ofi_zscore(
+    ofi: &[Decimal],
+    period: usize,
+) -> Result<IndicatorSeries, TechnicalError> {
+    if period < 2 || ofi.len() < period {
+        return Err(TechnicalError::InsufficientData {
+            required: period.max(2),
+            actual: ofi.len(),
+        });
+    }
+
+    let mut values = vec![Decimal::ZERO; period - 1];
+    for i in (period - 1)..ofi.len() {
+        let window = &ofi[i + 1 - period..=i];
+        let mean = window.iter().sum::<Decimal>() / Decimal::from(period as i64);
+        let variance = window
+            .iter()
+            .map(|v| (*v - mean) * (*v - mean))
+            .sum::<Decimal>()
+            / Decimal::from(period as i64);
+        // Share-sized variances are beyond the Decimal Newton iteration
+        let std_dev = variance
+            .to_f64()
+            .map(f64::sqrt)
+            .and_then(Decimal::from_f64)
+            .unwrap_or(Decimal::ZERO);
+        values.push(if std_dev.is_zero() {
+            Decimal::ZERO
+        } else {
+            ((ofi[i] - mean) / std_dev).round_dp(4)
+        });
+    }
+
+    Ok(IndicatorSeries::new(values, period - 1))
+}
+
 /// Generate order flow score for technical analysis
 /// Combines OBI, OFI trend, and volume analysis
 pub fn order_flow_score(
@@ -328,6 +590,88 @@ pub fn order_flow_score(
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_daily_order_flow_and_zscore() {
+        let bar = |close: Decimal, volume: i64| OhlcvBar {
+            open: dec!(100),
+            high: dec!(110),
+            low: dec!(90),
+            close,
+            volume,
+        };
+        let flow = daily_order_flow(&[
+            bar(dec!(110), 1000),
+            bar(dec!(90), 500),
+            bar(dec!(100), 800),
+        ]);
+        assert_eq!(flow[0].obi, dec!(1));
+        assert_eq!(flow[0].ofi, dec!(1000));
+        assert_eq!(flow[1].ofi, dec!(-500));
+        assert_eq!(flow[2].ofi, Decimal::ZERO);
+
+        // Mean 100 and deviation √20000 ≈ 141.42, so 300 is 1.41σ above
+        let ofi = vec![dec!(100), dec!(-100), dec!(100), dec!(300)];
+        let z = calculate_ofi_zscore(&ofi, 4).unwrap();
+        assert_eq!(z.warmup(), 3);
+        assert_eq!(z.last_valid(), Some(dec!(1.4142)));
+
+        // A flat window has no deviation to scale by
+        let z = calculate_ofi_zscore(&[dec!(5); 5], 3).unwrap();
+        assert_eq!(z.last_valid(), Some(Decimal::ZERO));
+        assert!(calculate_ofi_zscore(&ofi, 10).is_err());
+    }
+
+    #[test]
+    fn test_depth_obi_and_ofi() {
+        let level = |price: Decimal, volume: i64| BookLevel { price, volume };
+        let at = |secs: i64| DateTime::from_timestamp(1_709_517_600 + secs, 0).unwrap();
+        let first = DepthSnapshot::new(
+            at(0),
+            vec![
+                level(dec!(98), 500),
+                level(dec!(99), 1000),
+                level(dec!(97), 0),
+            ],
+            vec![level(dec!(101), 800), level(dec!(100), 400)],
+        );
+        // Sorted best first, empty levels dropped
+        assert_eq!(
+            first.bids,
+            vec![level(dec!(99), 1000), level(dec!(98), 500)]
+        );
+        assert_eq!(first.asks[0].price, dec!(100));
+        assert_eq!(first.mid_price(), Some(dec!(99.5)));
+
+        // 1500 bid against 1200 ask
+        assert_eq!(calculate_depth_obi(&first, 5).obi, dec!(300) / dec!(2700));
+        assert_eq!(calculate_depth_obi(&first, 1).obi, dec!(600) / dec!(1400));
+
+        // Bids build on both levels, the best ask is half taken
+        let second = DepthSnapshot::new(
+            at(5),
+            vec![level(dec!(99), 1300), level(dec!(98), 700)],
+            vec![level(dec!(100), 200), level(dec!(101), 800)],
+        );
+        // Level 0: +300 bid, -200 ask; level 1: +200 bid, 0 ask
+        assert_eq!(calculate_depth_ofi(&first, &second, 5).ofi, dec!(700));
+
+        let mut tracker = DepthFlowTracker::default();
+        assert_eq!(tracker.update(first.clone()).unwrap().ofi, Decimal::ZERO);
+        let live = tracker.update(second).unwrap();
+        assert_eq!(
+            (live.ofi, live.cumulative_ofi, live.snapshots),
+            (dec!(700), dec!(700), 2)
+        );
+        // Late and crossed snapshots are ignored
+        assert!(tracker.update(first).is_none());
+        let crossed = DepthSnapshot::new(
+            at(10),
+            vec![level(dec!(101), 100)],
+            vec![level(dec!(100), 100)],
+        );
+        assert!(tracker.update(crossed).is_none());
+    }
+
     #[test]
     fn test_obi_calculation() {
         // Equal volume
@@ -591,4 +935,31 @@ mod tests {
         let score = order_flow_score(dec!(-1), dec!(-1), false);
         assert!(score >= dec!(0));
     }
+
+    #[test]
+    fn test_delta_series() {
+        use crate::footprint::{build_footprint, TradeTick};
+        use chrono::DateTime;
+
+        let tick = |secs: i64, price: Decimal, volume: i64| TradeTick {
+            time: DateTime::from_timestamp(1_709_521_200 + secs, 0).unwrap(),
+            price,
+            volume,
+            bid: Some(dec!(995)),
+            ask: Some(dec!(1000)),
+        };
+        let bars = build_footprint(
+            &[
+                tick(0, dec!(1000), 300),
+                tick(10, dec!(995), 100),
+                tick(60, dec!(995), 500),
+                tick(120, dec!(1000), 50),
+            ],
+            60,
+        );
+        let series = calculate_delta_series(&bars);
+        assert_eq!(series.delta, vec![200, -500, 50]);
+        assert_eq!(series.cumulative_delta, vec![200, -300, -250]);
+        assert_eq!(calculate_delta_series(&[]), DeltaSeries::default());
+    }
 }
@@ -0,0 +1,258 @@
+//! Pair (relative-value) analysis
+//!
+//! A pair trade is long one stock and short a similar one, betting on their
+//! price ratio rather than on direction. The ratio's z-score against its
+//! rolling mean shows how stretched it is; the bet only makes sense when the
+//! two prices are cointegrated, so the spread actually reverts. That is
+//! checked with the Engle-Granger test: regress log prices on each other
+//! and run an ADF test (no constant, no lags) on the residual spread.
+
+use crate::error::TechnicalError;
+use crate::stats::mean;
+use chrono::NaiveDate;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Default rolling window of the ratio z-score, in sessions
+pub const PAIR_ZSCORE_WINDOW: usize = 60;
+
+/// |z| at which the ratio counts as stretched
+pub const PAIR_ENTRY_ZSCORE: f64 = 2.0;
+
+/// Engle-Granger 5% critical value for two series (MacKinnon)
+const EG_CRITICAL_5PCT: f64 = -3.34;
+
+/// Observations needed for a meaningful cointegration test
+const MIN_COINTEGRATION_OBSERVATIONS: usize = 30;
+
+/// Ratio of the two legs on one session
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RatioPoint {
+    pub date: NaiveDate,
+    /// Long close divided by short close
+    pub ratio: f64,
+    /// `None` during the window warm-up
+    pub zscore: Option<f64>,
+}
+
+/// Engle-Granger cointegration test of the log prices
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CointegrationResult {
+    /// Slope of log(long) on log(short)
+    pub hedge_ratio: f64,
+    pub intercept: f64,
+    /// ADF t-statistic of the residual spread
+    pub adf_statistic: f64,
+    pub critical_value_5pct: f64,
+    pub is_cointegrated: bool,
+    /// Sessions for the spread to close half its gap; `None` if it does not revert
+    pub half_life: Option<f64>,
+}
+
+/// Where the ratio stands against its rolling mean
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PairSignal {
+    /// Long leg cheap against the short leg: favors the pair as given
+    RatioCheap,
+    /// Long leg rich against the short leg: favors the reverse pair
+    RatioRich,
+    Neutral,
+}
+
+impl PairSignal {
+    pub fn from_zscore(zscore: f64) -> Self {
+        if zscore <= -PAIR_ENTRY_ZSCORE {
+            PairSignal::RatioCheap
+        } else if zscore >= PAIR_ENTRY_ZSCORE {
+            PairSignal::RatioRich
+        } else {
+            PairSignal::Neutral
+        }
+    }
+}
+
+/// Closes of the two legs on the sessions both traded, oldest first
+pub fn align_closes(
+    long: &[(NaiveDate, Decimal)],
+    short: &[(NaiveDate, Decimal)],
+) -> Vec<(NaiveDate, f64, f64)> {
+    let short_by_date: BTreeMap<NaiveDate, Decimal> = short.iter().copied().collect();
+    let mut aligned: Vec<(NaiveDate, f64, f64)> = long
+        .iter()
+        .filter_map(|(date, long_close)| {
+            let short_close = short_by_date.get(date)?;
+            Some((*date, long_close.to_f64()?, short_close.to_f64()?))
+        })
+        .filter(|(_, l, s)| *l > 0.0 && *s > 0.0)
+        .collect();
+    aligned.sort_by_key(|(date, _, _)| *date);
+    aligned.dedup_by_key(|(date, _, _)| *date);
+    aligned
+}
+
+/// Price ratio with its rolling z-score over `window` sessions
+pub fn ratio_series(aligned: &[(NaiveDate, f64, f64)], window: usize) -> Vec<RatioPoint> {
+    let ratios: Vec<f64> = aligned.iter().map(|(_, l, s)| l / s).collect();
+    aligned
+        .iter()
+        .enumerate()
+        .map(|(i, (date, _, _))| {
+            let zscore = (window >= 2 && i + 1 >= window).then(|| {
+                let slice = &ratios[i + 1 - window..=i];
+                let m = mean(slice);
+                let sd =
+                    (slice.iter().map(|r| (r - m).powi(2)).sum::<f64>() / window as f64).sqrt();
+                if sd > 0.0 {
+                    (ratios[i] - m) / sd
+                } else {
+                    0.0
+                }
+            });
+            RatioPoint {
+                date: *date,
+                ratio: ratios[i],
+                zscore,
+            }
+        })
+        .collect()
+}
+
+/// Ordinary least squares of `y` on `x`: (intercept, slope)
+pub(crate) fn ols(x: &[f64], y: &[f64]) -> Option<(f64, f64)> {
+    let (mx, my) = (mean(x), mean(y));
+    let sxx: f64 = x.iter().map(|v| (v - mx).powi(2)).sum();
+    if sxx == 0.0 {
+        return None;
+    }
+    let sxy: f64 = x.iter().zip(y).map(|(a, b)| (a - mx) * (b - my)).sum();
+    let slope = sxy / sxx;
+    Some((my - slope * mx, slope))
+}
+
+/// Engle-Granger test on the aligned closes
+pub fn engle_granger(
+    aligned: &[(NaiveDate, f64, f64)],
+) -> Result<CointegrationResult, TechnicalError> {
+    if aligned.len() < MIN_COINTEGRATION_OBSERVATIONS {
+        return Err(TechnicalError::InsufficientData {
+            required: MIN_COINTEGRATION_OBSERVATIONS,
+            actual: aligned.len(),
+        });
+    }
+    let log_long: Vec<f64> = aligned.iter().map(|(_, l, _)| l.ln()).collect();
+    let log_short: Vec<f64> = aligned.iter().map(|(_, _, s)| s.ln()).collect();
+    let (intercept, hedge_ratio) = ols(&log_short, &log_long).ok_or_else(|| {
+        TechnicalError::CalculationError("short leg price never changes".to_string())
+    })?;
+    let spread: Vec<f64> = log_long
+        .iter()
+        .zip(&log_short)
+        .map(|(l, s)| l - intercept - hedge_ratio * s)
+        .collect();
+
+    // Δe_t = γ·e_{t-1} + ε
+    let lagged = &spread[..spread.len() - 1];
+    let diffs: Vec<f64> = spread.windows(2).map(|w| w[1] - w[0]).collect();
+    let sum_sq: f64 = lagged.iter().map(|e| e * e).sum();
+    if sum_sq == 0.0 {
+        return Err(TechnicalError::CalculationError(
+            "spread is constant".to_string(),
+        ));
+    }
+    let gamma = lagged.iter().zip(&diffs).map(|(e, d)| e * d).sum::<f64>() / sum_sq;
+    let residual_var = lagged
+        .iter()
+        .zip(&diffs)
+        .map(|(e, d)| (d - gamma * e).powi(2))
+        .sum::<f64>()
+        / (diffs.len() - 1) as f64;
+    let adf_statistic = gamma / (residual_var / sum_sq).sqrt();
+
+    let half_life = (gamma < 0.0 && gamma > -1.0).then(|| -(2f64.ln()) / (1.0 + gamma).ln());
+
+    Ok(CointegrationResult {
+        hedge_ratio,
+        intercept,
+        adf_statistic,
+        critical_value_5pct: EG_CRITICAL_5PCT,
+        is_cointegrated: adf_statistic < EG_CRITICAL_5PCT,
+        half_life,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::prelude::FromPrimitive;
+
+    fn day(i: usize) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, 1).unwrap() + chrono::Duration::days(i as i64)
+    }
+
+    fn closes(values: impl Iterator<Item = f64>) -> Vec<(NaiveDate, Decimal)> {
+        values
+            .enumerate()
+            .map(|(i, v)| (day(i), Decimal::from_f64(v).unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn test_align_and_ratio_zscore() {
+        let long = closes([100.0, 102.0, 104.0, 120.0].into_iter());
+        let mut short = closes([50.0, 51.0, 52.0, 50.0].into_iter());
+        // Short leg missing a session
+        short.remove(1);
+        let aligned = align_closes(&long, &short);
+        assert_eq!(aligned.len(), 3);
+
+        let points = ratio_series(&aligned, 3);
+        assert_eq!(points[0].ratio, 2.0);
+        assert!(points[1].zscore.is_none());
+        // Ratios 2, 2, 2.4: the last is √2 deviations above the mean
+        let z = points[2].zscore.unwrap();
+        assert!((z - 2f64.sqrt()).abs() < 1e-9);
+        assert_eq!(PairSignal::from_zscore(z), PairSignal::Neutral);
+        assert_eq!(PairSignal::from_zscore(-2.5), PairSignal::RatioCheap);
+    }
+
+    #[test]
+    fn test_engle_granger() {
+        // Short leg wanders; long leg tracks 1.5× it plus an AR(1) spread
+        // e_t = 0.5·e_{t-1} + shock that keeps pulling back to zero
+        let short: Vec<f64> = (0..120)
+            .map(|i| 1000.0 * (1.0 + 0.002 * i as f64 + 0.05 * (i as f64 / 7.0).sin()))
+            .collect();
+        let mut spread = 0.0;
+        let long: Vec<f64> = short
+            .iter()
+            .enumerate()
+            .map(|(i, s)| {
+                let shock = ((i as f64 * 12.9898).sin() * 43758.5453).fract() * 0.02;
+                spread = 0.5 * spread + shock;
+                1.5 * s * spread.exp()
+            })
+            .collect();
+        let aligned = align_closes(
+            &closes(long.into_iter()),
+            &closes(short.clone().into_iter()),
+        );
+        let result = engle_granger(&aligned).unwrap();
+        assert!((result.hedge_ratio - 1.0).abs() < 0.05);
+        assert!(result.is_cointegrated);
+        assert!(result.half_life.unwrap() < 5.0);
+
+        // A trending gap between the legs does not revert
+        let drifting: Vec<f64> = short
+            .iter()
+            .enumerate()
+            .map(|(i, s)| s * (1.0 + 0.004 * i as f64) * (1.0 + 0.03 * (i as f64 / 3.0).cos()))
+            .collect();
+        let aligned = align_closes(&closes(drifting.into_iter()), &closes(short.into_iter()));
+        assert!(!engle_granger(&aligned).unwrap().is_cointegrated);
+
+        assert!(engle_granger(&aligned[..10]).is_err());
+    }
+}
@@ -0,0 +1,485 @@
+//! Indicator metadata registry
+//!
+//! Describes every indicator this crate computes: its parameters with their
+//! defaults, the fields it outputs and how many leading bars are warm-up with
+//! the default parameters. Screeners and rule editors build their forms from
+//! [`INDICATORS`] instead of hard-coding what the crate supports.
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IndicatorCategory {
+    Trend,
+    Momentum,
+    Volatility,
+    Volume,
+    OrderFlow,
+    Pattern,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct IndicatorParam {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub default: f64,
+    /// Whether only whole numbers are accepted (periods, bar counts)
+    pub integer: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct IndicatorOutput {
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct IndicatorInfo {
+    /// Stable identifier, used by API clients
+    pub key: &'static str,
+    pub name: &'static str,
+    pub category: IndicatorCategory,
+    pub description: &'static str,
+    /// Bar fields the indicator reads
+    pub inputs: &'static [&'static str],
+    pub params: &'static [IndicatorParam],
+    pub outputs: &'static [IndicatorOutput],
+    /// Leading bars without a meaningful value, with the default parameters
+    pub warmup: usize,
+}
+
+const fn period(description: &'static str, default: f64) -> IndicatorParam {
+    IndicatorParam {
+        name: "period",
+        description,
+        default,
+        integer: true,
+    }
+}
+
+const fn output(name: &'static str, description: &'static str) -> IndicatorOutput {
+    IndicatorOutput { name, description }
+}
+
+/// Every indicator of the crate
+pub const INDICATORS: &[IndicatorInfo] = &[
+    IndicatorInfo {
+        key: "ema",
+        name: "Exponential Moving Average",
+        category: IndicatorCategory::Trend,
+        description: "EMA seeded with the simple average of the first period",
+        inputs: &["close"],
+        params: &[period("Bars averaged", 20.0)],
+        outputs: &[output("ema", "Moving average value")],
+        warmup: 19,
+    },
+    IndicatorInfo {
+        key: "fibonacci",
+        name: "Fibonacci Retracement",
+        category: IndicatorCategory::Trend,
+        description: "Retracement levels between the swing high and low",
+        inputs: &["high", "low"],
+        params: &[],
+        outputs: &[
+            output("level_0", "0% level (swing high)"),
+            output("level_236", "23.6% level"),
+            output("level_382", "38.2% level"),
+            output("level_500", "50% level"),
+            output("level_618", "61.8% level"),
+            output("level_786", "78.6% level"),
+            output("level_1000", "100% level (swing low)"),
+        ],
+        warmup: 0,
+    },
+    IndicatorInfo {
+        key: "rsi",
+        name: "Relative Strength Index",
+        category: IndicatorCategory::Momentum,
+        description: "Wilder RSI, 0 to 100",
+        inputs: &["close"],
+        params: &[period("Bars of gains and losses", 14.0)],
+        outputs: &[output("rsi", "RSI value")],
+        warmup: 14,
+    },
+    IndicatorInfo {
+        key: "macd",
+        name: "MACD",
+        category: IndicatorCategory::Momentum,
+        description: "Difference of a fast and slow EMA with its signal EMA",
+        inputs: &["close"],
+        params: &[
+            IndicatorParam {
+                name: "fast_period",
+                description: "Bars of the fast EMA",
+                default: 12.0,
+                integer: true,
+            },
+            IndicatorParam {
+                name: "slow_period",
+                description: "Bars of the slow EMA",
+                default: 26.0,
+                integer: true,
+            },
+            IndicatorParam {
+                name: "signal_period",
+                description: "Bars of the signal EMA",
+                default: 9.0,
+                integer: true,
+            },
+        ],
+        outputs: &[
+            output("macd_line", "Fast EMA minus slow EMA"),
+            output("signal_line", "EMA of the MACD line"),
+            output("histogram", "MACD line minus signal line"),
+        ],
+        warmup: 33,
+    },
+    IndicatorInfo {
+        key: "momentum",
+        name: "Multi-horizon Momentum",
+        category: IndicatorCategory::Momentum,
+        description: "Weighted blend of 21, 63 and 126-session returns",
+        inputs: &["close"],
+        params: &[IndicatorParam {
+            name: "skip_sessions",
+            description: "Sessions left out at the recent end of every horizon",
+            default: 0.0,
+            integer: true,
+        }],
+        outputs: &[output("score", "Blended return across the horizons")],
+        warmup: 126,
+    },
+    IndicatorInfo {
+        key: "bollinger",
+        name: "Bollinger Bands",
+        category: IndicatorCategory::Volatility,
+        description: "Simple average with bands a number of standard deviations away",
+        inputs: &["close"],
+        params: &[
+            period("Bars averaged", 20.0),
+            IndicatorParam {
+                name: "std_dev",
+                description: "Standard deviations from the middle band",
+                default: 2.0,
+                integer: false,
+            },
+        ],
+        outputs: &[
+            output("upper", "Upper band"),
+            output("middle", "Simple moving average"),
+            output("lower", "Lower band"),
+        ],
+        warmup: 19,
+    },
+    IndicatorInfo {
+        key: "atr",
+        name: "Average True Range",
+        category: IndicatorCategory::Volatility,
+        description: "True range smoothed with Wilder's method",
+        inputs: &["high", "low", "close"],
+        params: &[period("Bars smoothed", 14.0)],
+        outputs: &[output("atr", "Average true range in price units")],
+        warmup: 14,
+    },
+    IndicatorInfo {
+        key: "chandelier_exit",
+        name: "Chandelier Exit",
+        category: IndicatorCategory::Volatility,
+        description: "Trailing stops a multiple of ATR from the period's extremes",
+        inputs: &["high", "low", "close"],
+        params: &[
+            period("Bars of the extremes and ATR", 22.0),
+            IndicatorParam {
+                name: "multiplier",
+                description: "ATRs between the extreme and the stop",
+                default: 3.0,
+                integer: false,
+            },
+        ],
+        outputs: &[
+            output("long_stop", "Stop for long positions"),
+            output("short_stop", "Stop for short positions"),
+        ],
+        warmup: 22,
+    },
+    IndicatorInfo {
+        key: "obv",
+        name: "On-Balance Volume",
+        category: IndicatorCategory::Volume,
+        description: "Running volume, added on up closes and subtracted on down closes",
+        inputs: &["close", "volume"],
+        params: &[],
+        outputs: &[output("obv", "Cumulative signed volume")],
+        warmup: 0,
+    },
+    IndicatorInfo {
+        key: "vpt",
+        name: "Volume Price Trend",
+        category: IndicatorCategory::Volume,
+        description: "Running volume weighted by the close-to-close change",
+        inputs: &["close", "volume"],
+        params: &[],
+        outputs: &[output("vpt", "Cumulative weighted volume")],
+        warmup: 0,
+    },
+    IndicatorInfo {
+        key: "rvol",
+        name: "Relative Volume",
+        category: IndicatorCategory::Volume,
+        description: "Volume over the average of the previous bars",
+        inputs: &["volume"],
+        params: &[period("Previous bars averaged", 20.0)],
+        outputs: &[output("rvol", "Ratio to the average volume")],
+        warmup: 20,
+    },
+    IndicatorInfo {
+        key: "vwap",
+        name: "Volume-Weighted Average Price",
+        category: IndicatorCategory::Volume,
+        description: "Cumulative VWAP of the typical price from the first (anchor) bar",
+        inputs: &["high", "low", "close", "volume"],
+        params: &[],
+        outputs: &[output("vwap", "Volume-weighted average price")],
+        warmup: 0,
+    },
+    IndicatorInfo {
+        key: "session_vwap_bands",
+        name: "Session VWAP Bands",
+        category: IndicatorCategory::Volume,
+        description: "Intraday VWAP with one and two standard deviation bands",
+        inputs: &["high", "low", "close", "volume"],
+        params: &[],
+        outputs: &[
+            output("vwap", "Session VWAP"),
+            output("std_dev", "Volume-weighted standard deviation"),
+            output("upper_1", "VWAP plus one standard deviation"),
+            output("lower_1", "VWAP minus one standard deviation"),
+            output("upper_2", "VWAP plus two standard deviations"),
+            output("lower_2", "VWAP minus two standard deviations"),
+        ],
+        warmup: 0,
+    },
+    IndicatorInfo {
+        key: "volume_profile",
+        name: "Volume Profile",
+        category: IndicatorCategory::Volume,
+        description: "Volume binned by price with point of control and 70% value area",
+        inputs: &["high", "low", "close", "volume"],
+        params: &[IndicatorParam {
+            name: "bins",
+            description: "Equal price buckets",
+            default: 24.0,
+            integer: true,
+        }],
+        outputs: &[
+            output(
+                "point_of_control",
+                "Midpoint of the bucket with the most volume",
+            ),
+            output("value_area_high", "Top of the value area"),
+            output("value_area_low", "Bottom of the value area"),
+            output("levels", "Volume per price bucket"),
+        ],
+        warmup: 0,
+    },
+    IndicatorInfo {
+        key: "adl",
+        name: "Accumulation/Distribution Line",
+        category: IndicatorCategory::OrderFlow,
+        description: "Running volume weighted by the close location in the range",
+        inputs: &["high", "low", "close", "volume"],
+        params: &[],
+        outputs: &[output("adl", "Cumulative money flow volume")],
+        warmup: 0,
+    },
+    IndicatorInfo {
+        key: "ofi_zscore",
+        name: "Order Flow Imbalance Z-Score",
+        category: IndicatorCategory::OrderFlow,
+        description: "Z-score of the daily signed share imbalance",
+        inputs: &["high", "low", "close", "volume"],
+        params: &[period("Days in the z-score window", 20.0)],
+        outputs: &[output("zscore", "Standard deviations from the window mean")],
+        warmup: 19,
+    },
+    IndicatorInfo {
+        key: "footprint",
+        name: "Footprint Bars",
+        category: IndicatorCategory::OrderFlow,
+        description: "Intraday bars of trades with buy/sell delta and cumulative delta",
+        inputs: &["ticks"],
+        params: &[IndicatorParam {
+            name: "bar_seconds",
+            description: "Seconds per bar",
+            default: 60.0,
+            integer: true,
+        }],
+        outputs: &[
+            output("delta", "Buy minus sell volume of the bar"),
+            output("cumulative_delta", "Running delta of the session"),
+        ],
+        warmup: 0,
+    },
+    IndicatorInfo {
+        key: "wyckoff",
+        name: "Wyckoff Phase",
+        category: IndicatorCategory::Pattern,
+        description: "Accumulation, markup, distribution or markdown phase with events",
+        inputs: &["open", "high", "low", "close", "volume"],
+        params: &[
+            IndicatorParam {
+                name: "trend_lookback",
+                description: "Bars of the prior trend",
+                default: 20.0,
+                integer: true,
+            },
+            IndicatorParam {
+                name: "volume_lookback",
+                description: "Bars of the average volume",
+                default: 20.0,
+                integer: true,
+            },
+            IndicatorParam {
+                name: "min_phase_bars",
+                description: "Fewest bars in a trading range",
+                default: 10.0,
+                integer: true,
+            },
+        ],
+        outputs: &[
+            output("phase", "Detected phase"),
+            output("confidence", "Confidence of the phase, 0 to 95"),
+            output("events", "Wyckoff and VSA events"),
+        ],
+        warmup: 29,
+    },
+    IndicatorInfo {
+        key: "candlestick",
+        name: "Candlestick Patterns",
+        category: IndicatorCategory::Pattern,
+        description: "Single, two and three-bar candlestick patterns",
+        inputs: &["open", "high", "low", "close"],
+        params: &[],
+        outputs: &[output("pattern", "Pattern ending at the bar")],
+        warmup: 0,
+    },
+    IndicatorInfo {
+        key: "divergence",
+        name: "Divergences",
+        category: IndicatorCategory::Pattern,
+        description: "Regular and hidden divergences between price and an oscillator",
+        inputs: &["high", "low", "close", "volume"],
+        params: &[
+            IndicatorParam {
+                name: "pivot_strength",
+                description: "Bars on each side a swing must exceed",
+                default: 3.0,
+                integer: true,
+            },
+            IndicatorParam {
+                name: "min_gap",
+                description: "Fewest bars between the two swings",
+                default: 5.0,
+                integer: true,
+            },
+            IndicatorParam {
+                name: "max_gap",
+                description: "Most bars between the two swings",
+                default: 60.0,
+                integer: true,
+            },
+        ],
+        outputs: &[
+            output("kind", "Regular or hidden, bullish or bearish"),
+            output("oscillator", "RSI, MACD histogram or OBV"),
+            output("confidence", "Confidence, 40 to 100"),
+        ],
+        warmup: 14,
+    },
+];
+
+/// Metadata of the indicator with `key`
+pub fn indicator_info(key: &str) -> Option<&'static IndicatorInfo> {
+    INDICATORS.iter().find(|i| i.key == key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+    use std::collections::HashSet;
+
+    fn default_param(key: &str, name: &str) -> f64 {
+        indicator_info(key)
+            .and_then(|i| i.params.iter().find(|p| p.name == name))
+            .map(|p| p.default)
+            .unwrap()
+    }
+
+    fn zeros<T: Default + PartialEq>(values: &[T]) -> usize {
+        values.iter().take_while(|v| **v == T::default()).count()
+    }
+
+    #[test]
+    fn test_registry_matches_indicators() {
+        let keys: HashSet<_> = INDICATORS.iter().map(|i| i.key).collect();
+        assert_eq!(keys.len(), INDICATORS.len());
+        assert!(indicator_info("unknown").is_none());
+
+        let closes: Vec<Decimal> = (0..80)
+            .map(|i| dec!(1000) + Decimal::from(i % 7 * 10 + i))
+            .collect();
+        let highs: Vec<Decimal> = closes.iter().map(|c| c + dec!(15)).collect();
+        let lows: Vec<Decimal> = closes.iter().map(|c| c - dec!(15)).collect();
+        let volumes: Vec<i64> = (0..80).map(|i| 1_000 + i % 5 * 100).collect();
+        let warmup = |key| indicator_info(key).unwrap().warmup;
+
+        assert_eq!(calculate_ema(&closes, 20).unwrap().warmup(), warmup("ema"));
+        assert_eq!(calculate_rsi14(&closes).unwrap().warmup(), warmup("rsi"));
+        let macd = calculate_macd(&closes).unwrap();
+        assert_eq!(macd.histogram.warmup(), warmup("macd"));
+        assert_eq!(
+            calculate_bollinger_bands(&closes).unwrap().upper.warmup(),
+            warmup("bollinger")
+        );
+        let atr = calculate_atr14(&highs, &lows, &closes).unwrap();
+        assert_eq!(zeros(&atr), warmup("atr"));
+        let chandelier = calculate_chandelier_exit22(&highs, &lows, &closes).unwrap();
+        assert_eq!(zeros(&chandelier.long_stop), warmup("chandelier_exit"));
+        let rvol = calculate_rvol(&volumes, 20).unwrap();
+        assert_eq!(zeros(&rvol), warmup("rvol"));
+        let ofi: Vec<Decimal> = (0..40).map(|i| Decimal::from(i % 3 * 100 - 100)).collect();
+        let zscore = calculate_ofi_zscore(&ofi, OFI_ZSCORE_PERIOD).unwrap();
+        assert_eq!(zscore.warmup(), warmup("ofi_zscore"));
+
+        let wyckoff = WyckoffConfig::default();
+        assert_eq!(
+            wyckoff.trend_lookback.max(wyckoff.volume_lookback) + wyckoff.min_phase_bars - 1,
+            warmup("wyckoff")
+        );
+        assert_eq!(
+            MomentumConfig::default()
+                .horizons
+                .iter()
+                .map(|h| h.sessions)
+                .max(),
+            Some(warmup("momentum"))
+        );
+
+        let divergence = DivergenceConfig::default();
+        assert_eq!(
+            default_param("divergence", "pivot_strength"),
+            divergence.pivot_strength as f64
+        );
+        assert_eq!(
+            default_param("divergence", "max_gap"),
+            divergence.max_gap as f64
+        );
+        assert_eq!(
+            default_param("chandelier_exit", "period"),
+            CHANDELIER_PERIOD as f64
+        );
+    }
+}
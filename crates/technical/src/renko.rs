@@ -0,0 +1,372 @@
+//! Renko and Point & Figure bar construction
+//!
+//! Both drop time and keep only price moves of at least a fixed size, which
+//! filters the tick-sized noise of thinly traded IDX small caps. Renko bricks
+//! convert back to `OhlcvBar`s so Wyckoff and trend detection run on them
+//! unchanged.
+//!
+//! Both are built from closes. A brick or column spans a price range; a move
+//! in the same direction needs one more size beyond its end, a reversal needs
+//! the reversal amount beyond its start.
+
+use crate::atr::calculate_atr;
+use crate::error::TechnicalError;
+use crate::wyckoff::OhlcvBar;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// Default Point & Figure reversal, in boxes
+pub const DEFAULT_PNF_REVERSAL: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BrickDirection {
+    Up,
+    Down,
+}
+
+/// How the Renko brick size is chosen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BrickSize {
+    /// Fixed price amount
+    Fixed(Decimal),
+    /// Latest ATR of the bars over this period
+    Atr(usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RenkoBrick {
+    pub direction: BrickDirection,
+    pub open: Decimal,
+    pub close: Decimal,
+    /// Share of the volume traded since the previous brick
+    pub volume: i64,
+    /// Source bar whose close completed the brick
+    pub index: usize,
+}
+
+impl RenkoBrick {
+    pub fn to_bar(&self) -> OhlcvBar {
+        OhlcvBar {
+            open: self.open,
+            high: self.open.max(self.close),
+            low: self.open.min(self.close),
+            close: self.close,
+            volume: self.volume,
+        }
+    }
+}
+
+/// Point & Figure column: X (up) or O (down)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PnfColumn {
+    pub direction: BrickDirection,
+    pub high: Decimal,
+    pub low: Decimal,
+    /// Boxes between `low` and `high`
+    pub boxes: usize,
+    /// First and last source bar extending the column
+    pub start_index: usize,
+    pub end_index: usize,
+}
+
+impl PnfColumn {
+    /// `'X'` for up columns, `'O'` for down columns
+    pub fn symbol(&self) -> char {
+        match self.direction {
+            BrickDirection::Up => 'X',
+            BrickDirection::Down => 'O',
+        }
+    }
+}
+
+/// Brick size in price for `bars`
+pub fn resolve_brick_size(bars: &[OhlcvBar], size: BrickSize) -> Result<Decimal, TechnicalError> {
+    let size = match size {
+        BrickSize::Fixed(size) => size,
+        BrickSize::Atr(period) => {
+            let highs: Vec<Decimal> = bars.iter().map(|b| b.high).collect();
+            let lows: Vec<Decimal> = bars.iter().map(|b| b.low).collect();
+            let closes: Vec<Decimal> = bars.iter().map(|b| b.close).collect();
+            calculate_atr(&highs, &lows, &closes, period)?
+                .last()
+                .copied()
+                .unwrap_or_default()
+        }
+    };
+    if size <= Decimal::ZERO {
+        return Err(TechnicalError::InvalidParameter(
+            "Brick size must be positive".to_string(),
+        ));
+    }
+    Ok(size)
+}
+
+/// Renko bricks from `bars` (oldest first)
+///
+/// The first close is the starting reference. A bar completing several
+/// bricks splits the volume since the previous brick evenly between them.
+pub fn build_renko(bars: &[OhlcvBar], size: BrickSize) -> Result<Vec<RenkoBrick>, TechnicalError> {
+    let Some(first) = bars.first() else {
+        return Err(TechnicalError::InsufficientData {
+            required: 1,
+            actual: 0,
+        });
+    };
+    let size = resolve_brick_size(bars, size)?;
+
+    let mut bricks: Vec<RenkoBrick> = Vec::new();
+    let (mut high, mut low) = (first.close, first.close);
+    let mut pending_volume = 0;
+    for (index, bar) in bars.iter().enumerate() {
+        pending_volume += bar.volume;
+        let formed = bricks.len();
+        loop {
+            let (direction, open, close) = if bar.close >= high + size {
+                (BrickDirection::Up, high, high + size)
+            } else if bar.close <= low - size {
+                (BrickDirection::Down, low, low - size)
+            } else {
+                break;
+            };
+            high = open.max(close);
+            low = open.min(close);
+            bricks.push(RenkoBrick {
+                direction,
+                open,
+                close,
+                volume: 0,
+                index,
+            });
+        }
+
+        let new = &mut bricks[formed..];
+        if let Some(count) = i64::try_from(new.len()).ok().filter(|n| *n > 0) {
+            for brick in new.iter_mut() {
+                brick.volume = pending_volume / count;
+            }
+            if let Some(last) = new.last_mut() {
+                last.volume += pending_volume % count;
+            }
+            pending_volume = 0;
+        }
+    }
+
+    Ok(bricks)
+}
+
+/// Renko bricks of `bars` as `OhlcvBar`s, for indicators taking bars
+pub fn renko_bars(bars: &[OhlcvBar], size: BrickSize) -> Result<Vec<OhlcvBar>, TechnicalError> {
+    Ok(build_renko(bars, size)?
+        .iter()
+        .map(RenkoBrick::to_bar)
+        .collect())
+}
+
+/// Point & Figure columns from `bars` (oldest first)
+///
+/// Prices are snapped to multiples of `box_size`; a new column starts once
+/// the close moves `reversal` boxes against the current one.
+pub fn point_and_figure(
+    bars: &[OhlcvBar],
+    box_size: Decimal,
+    reversal: usize,
+) -> Result<Vec<PnfColumn>, TechnicalError> {
+    if box_size <= Decimal::ZERO {
+        return Err(TechnicalError::InvalidParameter(
+            "Box size must be positive".to_string(),
+        ));
+    }
+    if reversal == 0 {
+        return Err(TechnicalError::InvalidParameter(
+            "Reversal must be at least one box".to_string(),
+        ));
+    }
+    let Some(first) = bars.first() else {
+        return Err(TechnicalError::InsufficientData {
+            required: 1,
+            actual: 0,
+        });
+    };
+
+    // Whole boxes from `from` to `to`, towards zero
+    let boxes_between = |from: Decimal, to: Decimal| -> i64 {
+        ((to - from) / box_size)
+            .trunc()
+            .try_into()
+            .unwrap_or_default()
+    };
+    let reversal = reversal as i64;
+    let base = (first.close / box_size).floor() * box_size;
+
+    let mut columns: Vec<PnfColumn> = Vec::new();
+    for (index, bar) in bars.iter().enumerate() {
+        let Some(column) = columns.last_mut() else {
+            let moved = boxes_between(base, bar.close);
+            if moved != 0 {
+                let end = base + box_size * Decimal::from(moved);
+                columns.push(PnfColumn {
+                    direction: if moved > 0 {
+                        BrickDirection::Up
+                    } else {
+                        BrickDirection::Down
+                    },
+                    high: base.max(end),
+                    low: base.min(end),
+                    boxes: moved.unsigned_abs() as usize,
+                    start_index: index,
+                    end_index: index,
+                });
+            }
+            continue;
+        };
+
+        let (extension, against) = match column.direction {
+            BrickDirection::Up => (
+                boxes_between(column.high, bar.close),
+                -boxes_between(column.high, bar.close),
+            ),
+            BrickDirection::Down => (
+                -boxes_between(column.low, bar.close),
+                boxes_between(column.low, bar.close),
+            ),
+        };
+        if extension > 0 {
+            let step = box_size * Decimal::from(extension);
+            match column.direction {
+                BrickDirection::Up => column.high += step,
+                BrickDirection::Down => column.low -= step,
+            }
+            column.boxes += extension as usize;
+            column.end_index = index;
+        } else if against >= reversal {
+            let step = box_size * Decimal::from(against);
+            let reversed = match column.direction {
+                BrickDirection::Up => PnfColumn {
+                    direction: BrickDirection::Down,
+                    high: column.high,
+                    low: column.high - step,
+                    boxes: against as usize,
+                    start_index: index,
+                    end_index: index,
+                },
+                BrickDirection::Down => PnfColumn {
+                    direction: BrickDirection::Up,
+                    high: column.low + step,
+                    low: column.low,
+                    boxes: against as usize,
+                    start_index: index,
+                    end_index: index,
+                },
+            };
+            columns.push(reversed);
+        }
+    }
+
+    Ok(columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn bar(close: Decimal) -> OhlcvBar {
+        OhlcvBar {
+            open: close,
+            high: close + dec!(5),
+            low: close - dec!(5),
+            close,
+            volume: 100,
+        }
+    }
+
+    fn bars(closes: &[Decimal]) -> Vec<OhlcvBar> {
+        closes.iter().map(|c| bar(*c)).collect()
+    }
+
+    #[test]
+    fn test_renko_bricks() {
+        let closes = [
+            dec!(100),
+            dec!(104),
+            dec!(111),
+            // Gap of three bricks
+            dec!(131),
+            // Pullback inside the reversal distance
+            dec!(115),
+            // Two bricks below the last brick's open
+            dec!(99),
+        ];
+        let bricks = build_renko(&bars(&closes), BrickSize::Fixed(dec!(10))).unwrap();
+        let spans: Vec<_> = bricks
+            .iter()
+            .map(|b| (b.direction, b.open, b.close, b.index))
+            .collect();
+        assert_eq!(
+            spans,
+            vec![
+                (BrickDirection::Up, dec!(100), dec!(110), 2),
+                (BrickDirection::Up, dec!(110), dec!(120), 3),
+                (BrickDirection::Up, dec!(120), dec!(130), 3),
+                (BrickDirection::Down, dec!(120), dec!(110), 5),
+                (BrickDirection::Down, dec!(110), dec!(100), 5),
+            ]
+        );
+        // Volume since the previous brick is split between the bricks
+        assert_eq!(bricks[0].volume, 300);
+        assert_eq!(bricks[1].volume, 50);
+        assert_eq!(bricks.iter().map(|b| b.volume).sum::<i64>(), 600);
+
+        let brick_bar = bricks[3].to_bar();
+        assert_eq!(
+            (brick_bar.high, brick_bar.low, brick_bar.close),
+            (dec!(120), dec!(110), dec!(110))
+        );
+
+        // Flat closes with a constant 10-point range
+        let flat = bars(&[dec!(100); 5]);
+        assert_eq!(
+            resolve_brick_size(&flat, BrickSize::Atr(3)).unwrap(),
+            dec!(10)
+        );
+        assert!(build_renko(&flat, BrickSize::Atr(3)).unwrap().is_empty());
+        assert!(build_renko(&bars(&closes), BrickSize::Fixed(Decimal::ZERO)).is_err());
+        assert!(build_renko(&[], BrickSize::Fixed(dec!(10))).is_err());
+    }
+
+    #[test]
+    fn test_point_and_figure_columns() {
+        let closes = [
+            dec!(100),
+            dec!(103),
+            dec!(109),
+            // Two boxes back: not a reversal
+            dec!(106),
+            dec!(113),
+            // Three boxes back: O column
+            dec!(104),
+            dec!(99),
+            // Two boxes up, then three: X column
+            dec!(104),
+            dec!(107),
+        ];
+        let columns = point_and_figure(&bars(&closes), dec!(2), DEFAULT_PNF_REVERSAL).unwrap();
+        let spans: Vec<_> = columns
+            .iter()
+            .map(|c| (c.symbol(), c.high, c.low, c.boxes))
+            .collect();
+        assert_eq!(
+            spans,
+            vec![
+                ('X', dec!(112), dec!(100), 6),
+                ('O', dec!(112), dec!(100), 6),
+                ('X', dec!(106), dec!(100), 3),
+            ]
+        );
+        assert_eq!((columns[0].start_index, columns[0].end_index), (1, 4));
+        assert_eq!((columns[1].start_index, columns[1].end_index), (5, 6));
+        assert!(point_and_figure(&bars(&closes), dec!(2), 0).is_err());
+    }
+}
@@ -1,6 +1,7 @@
 //! Relative Strength Index (RSI) calculations
 
 use crate::error::TechnicalError;
+use crate::series::IndicatorSeries;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 
@@ -8,7 +9,9 @@ use rust_decimal_macros::dec;
 ///
 /// RSI = 100 - (100 / (1 + RS))
 /// RS = Average Gain / Average Loss
-pub fn calculate_rsi(prices: &[Decimal], period: usize) -> Result<Vec<Decimal>, TechnicalError> {
+///
+/// The first RSI needs `period` price changes, so the warm-up is `period` bars.
+pub fn calculate_rsi(prices: &[Decimal], period: usize) -> Result<IndicatorSeries, TechnicalError> {
     if prices.len() < period + 1 {
         return Err(TechnicalError::InsufficientData {
             required: period + 1,
@@ -70,11 +73,11 @@ pub fn calculate_rsi(prices: &[Decimal], period: usize) -> Result<Vec<Decimal>,
         rsi_values.push(dec!(100) - (dec!(100) / (dec!(1) + rs)));
     }
 
-    Ok(rsi_values)
+    Ok(IndicatorSeries::new(rsi_values, period))
 }
 
 /// Calculate RSI 14 (standard period)
-pub fn calculate_rsi14(prices: &[Decimal]) -> Result<Vec<Decimal>, TechnicalError> {
+pub fn calculate_rsi14(prices: &[Decimal]) -> Result<IndicatorSeries, TechnicalError> {
     calculate_rsi(prices, 14)
 }
 
@@ -100,7 +103,8 @@ mod tests {
 
         let rsi = calculate_rsi(&prices, 14).unwrap();
 
-        for value in rsi.iter().skip(14) {
+        assert_eq!(rsi.warmup(), 14);
+        for value in rsi.valid() {
             assert!(*value >= Decimal::ZERO);
             assert!(*value <= dec!(100));
         }
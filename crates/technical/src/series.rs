@@ -0,0 +1,205 @@
+//! Indicator output series
+//!
+//! Every indicator needs some bars before its first meaningful value. An
+//! `IndicatorSeries` keeps one value per input bar and records how many of
+//! the leading values are warm-up, so padding is never mistaken for data:
+//! - `get` and `last_valid` return `None` inside the warm-up
+//! - `valid` is the slice after the warm-up
+//! - `aligned_with` pairs values with the bars they were computed from
+//! - `export` applies an explicit warm-up policy for charts and APIs
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// How warm-up values are represented when a series is exported
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WarmupPolicy {
+    /// Warm-up slots are `None` (JSON `null`), keeping alignment with bars
+    Null,
+    /// Warm-up slots are dropped; the output starts at the first valid value
+    Omit,
+    /// Warm-up slots carry a fixed value
+    Fill(Decimal),
+}
+
+/// Indicator values aligned one-to-one with the input bars
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndicatorSeries {
+    /// Warm-up slots hold zero
+    values: Vec<Decimal>,
+    warmup: usize,
+}
+
+impl IndicatorSeries {
+    /// Series whose first `warmup` values are not yet meaningful
+    pub fn new(values: Vec<Decimal>, warmup: usize) -> Self {
+        let warmup = warmup.min(values.len());
+        let mut values = values;
+        values[..warmup].fill(Decimal::ZERO);
+        Self { values, warmup }
+    }
+
+    /// Series computed on the input after its first `leading` bars, realigned
+    /// with the full input
+    pub fn offset(self, leading: usize) -> Self {
+        let mut values = vec![Decimal::ZERO; leading];
+        values.extend(self.values);
+        Self {
+            values,
+            warmup: leading + self.warmup,
+        }
+    }
+
+    /// Combine two series computed from the same bars, value by value
+    ///
+    /// The result is in warm-up wherever either input is.
+    pub fn zip_with(
+        &self,
+        other: &IndicatorSeries,
+        f: impl Fn(Decimal, Decimal) -> Decimal,
+    ) -> IndicatorSeries {
+        let values = self
+            .values
+            .iter()
+            .zip(&other.values)
+            .map(|(a, b)| f(*a, *b))
+            .collect();
+        IndicatorSeries::new(values, self.warmup.max(other.warmup))
+    }
+
+    /// Number of values, including warm-up
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Number of leading warm-up values
+    pub fn warmup(&self) -> usize {
+        self.warmup
+    }
+
+    /// Value at bar `index`; `None` during warm-up or past the end
+    pub fn get(&self, index: usize) -> Option<Decimal> {
+        if index < self.warmup {
+            return None;
+        }
+        self.values.get(index).copied()
+    }
+
+    /// Latest value, if the series has warmed up
+    pub fn last_valid(&self) -> Option<Decimal> {
+        self.valid().last().copied()
+    }
+
+    /// Values after the warm-up
+    pub fn valid(&self) -> &[Decimal] {
+        &self.values[self.warmup..]
+    }
+
+    /// All values with warm-up slots as zero
+    pub fn padded(&self) -> &[Decimal] {
+        &self.values
+    }
+
+    /// Pair each bar with its value
+    ///
+    /// Series and bars are aligned on their last element, so bars before the
+    /// start of the series (or in its warm-up) get `None`.
+    pub fn aligned_with<'a, T>(
+        &'a self,
+        bars: &'a [T],
+    ) -> impl Iterator<Item = (&'a T, Option<Decimal>)> + 'a {
+        let skipped = self.values.len().saturating_sub(bars.len());
+        let missing = bars.len().saturating_sub(self.values.len());
+        bars.iter().enumerate().map(move |(i, bar)| {
+            let value = i.checked_sub(missing).and_then(|j| self.get(j + skipped));
+            (bar, value)
+        })
+    }
+
+    /// Values with warm-up represented according to `policy`
+    pub fn export(&self, policy: WarmupPolicy) -> Vec<Option<Decimal>> {
+        let valid = self.valid().iter().map(|v| Some(*v));
+        let warmup = match policy {
+            WarmupPolicy::Null => Some(None),
+            WarmupPolicy::Omit => None,
+            WarmupPolicy::Fill(value) => Some(Some(value)),
+        };
+        match warmup {
+            Some(slot) => std::iter::repeat_n(slot, self.warmup)
+                .chain(valid)
+                .collect(),
+            None => valid.collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn series() -> IndicatorSeries {
+        IndicatorSeries::new(vec![dec!(9), dec!(9), dec!(1), dec!(2), dec!(3)], 2)
+    }
+
+    #[test]
+    fn test_warmup_is_hidden() {
+        let series = series();
+        assert_eq!(series.len(), 5);
+        assert_eq!(series.get(1), None);
+        assert_eq!(series.get(2), Some(dec!(1)));
+        assert_eq!(series.get(5), None);
+        assert_eq!(series.valid(), &[dec!(1), dec!(2), dec!(3)]);
+        assert_eq!(series.padded()[0], Decimal::ZERO);
+        assert_eq!(series.last_valid(), Some(dec!(3)));
+
+        let cold = IndicatorSeries::new(vec![dec!(1), dec!(2)], 5);
+        assert_eq!(cold.warmup(), 2);
+        assert_eq!(cold.last_valid(), None);
+    }
+
+    #[test]
+    fn test_export_policies() {
+        let series = series();
+        assert_eq!(
+            series.export(WarmupPolicy::Null),
+            vec![None, None, Some(dec!(1)), Some(dec!(2)), Some(dec!(3))]
+        );
+        assert_eq!(series.export(WarmupPolicy::Omit).len(), 3);
+        assert_eq!(
+            series.export(WarmupPolicy::Fill(dec!(50)))[0],
+            Some(dec!(50))
+        );
+    }
+
+    #[test]
+    fn test_aligned_with() {
+        let series = series();
+        let bars = ["a", "b", "c", "d", "e", "f"];
+        let aligned: Vec<_> = series.aligned_with(&bars).collect();
+        assert_eq!(aligned[0], (&"a", None));
+        assert_eq!(aligned[3], (&"d", Some(dec!(1))));
+        assert_eq!(aligned[5], (&"f", Some(dec!(3))));
+
+        // Fewer bars than values: align on the latest
+        let aligned: Vec<_> = series.aligned_with(&bars[..2]).collect();
+        assert_eq!(aligned, vec![(&"a", Some(dec!(2))), (&"b", Some(dec!(3)))]);
+    }
+
+    #[test]
+    fn test_offset_and_zip() {
+        let suffix = IndicatorSeries::new(vec![dec!(1), dec!(2), dec!(3)], 1).offset(2);
+        assert_eq!(suffix.len(), 5);
+        assert_eq!(suffix.warmup(), 3);
+        assert_eq!(suffix.get(3), Some(dec!(2)));
+
+        let diff = series().zip_with(&suffix, |a, b| a - b);
+        assert_eq!(diff.warmup(), 3);
+        assert_eq!(diff.valid(), &[dec!(0), dec!(0)]);
+    }
+}
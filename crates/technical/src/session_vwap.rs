@@ -0,0 +1,265 @@
+//! Session-anchored VWAP with deviation bands
+//!
+//! Intraday VWAP restarts at every IDX trading session. The bands sit one and
+//! two volume-weighted standard deviations of the typical price around it, so
+//! a move to the outer band is stretched relative to what the session has
+//! actually traded at. Day traders watch the VWAP itself as the line between
+//! buyers and sellers being in control; a cross of it on heavy volume is the
+//! signal `session_vwap_cross` reports.
+
+use crate::bollinger::sqrt_decimal;
+use crate::error::TechnicalError;
+use crate::wyckoff::OhlcvBar;
+use chrono::{DateTime, FixedOffset, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+/// WIB (UTC+7), the exchange's time zone
+const WIB_OFFSET_SECS: i32 = 7 * 3600;
+
+/// Intraday bar with the time it opened
+#[derive(Debug, Clone)]
+pub struct IntradayBar {
+    pub time: DateTime<Utc>,
+    pub bar: OhlcvBar,
+}
+
+/// Trading session (WIB calendar date) a bar belongs to
+pub fn session_date(time: DateTime<Utc>) -> NaiveDate {
+    let wib = FixedOffset::east_opt(WIB_OFFSET_SECS).expect("valid offset");
+    time.with_timezone(&wib).date_naive()
+}
+
+/// Session VWAP and its deviation bands at one bar
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct VwapBands {
+    pub vwap: Decimal,
+    /// Volume-weighted standard deviation of the typical price this session
+    pub std_dev: Decimal,
+    pub upper_1: Decimal,
+    pub lower_1: Decimal,
+    pub upper_2: Decimal,
+    pub lower_2: Decimal,
+}
+
+impl VwapBands {
+    fn new(vwap: Decimal, std_dev: Decimal) -> Self {
+        Self {
+            vwap,
+            std_dev,
+            upper_1: vwap + std_dev,
+            lower_1: vwap - std_dev,
+            upper_2: vwap + std_dev * dec!(2),
+            lower_2: vwap - std_dev * dec!(2),
+        }
+    }
+}
+
+/// Calculate session VWAP bands for intraday bars (oldest first)
+///
+/// Output is aligned with the input bars. Until the first bar with volume in
+/// a session, the VWAP is that bar's typical price and the bands are flat.
+pub fn calculate_session_vwap_bands(
+    bars: &[IntradayBar],
+) -> Result<Vec<VwapBands>, TechnicalError> {
+    if bars.is_empty() {
+        return Err(TechnicalError::InsufficientData {
+            required: 1,
+            actual: 0,
+        });
+    }
+
+    let mut bands = Vec::with_capacity(bars.len());
+    let mut session = None;
+    let mut cumulative_pv = Decimal::ZERO;
+    let mut cumulative_p2v = Decimal::ZERO;
+    let mut cumulative_volume = Decimal::ZERO;
+
+    for intraday in bars {
+        let date = session_date(intraday.time);
+        if session != Some(date) {
+            session = Some(date);
+            cumulative_pv = Decimal::ZERO;
+            cumulative_p2v = Decimal::ZERO;
+            cumulative_volume = Decimal::ZERO;
+        }
+
+        let bar = &intraday.bar;
+        let typical = (bar.high + bar.low + bar.close) / dec!(3);
+        let volume = Decimal::from(bar.volume.max(0));
+        cumulative_pv += typical * volume;
+        cumulative_p2v += typical * typical * volume;
+        cumulative_volume += volume;
+
+        if cumulative_volume > Decimal::ZERO {
+            let vwap = cumulative_pv / cumulative_volume;
+            let variance = (cumulative_p2v / cumulative_volume - vwap * vwap).max(Decimal::ZERO);
+            bands.push(VwapBands::new(vwap, sqrt_decimal(variance)));
+        } else {
+            bands.push(VwapBands::new(typical, Decimal::ZERO));
+        }
+    }
+
+    Ok(bands)
+}
+
+/// Direction of a VWAP cross
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VwapCross {
+    /// Close moved from below to above VWAP
+    Reclaim,
+    /// Close moved from above to below VWAP
+    Loss,
+}
+
+/// The latest bar's close crossing its session VWAP
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct VwapCrossEvent {
+    pub cross: VwapCross,
+    pub price: Decimal,
+    pub vwap: Decimal,
+    /// Volume of the crossing bar relative to the session's earlier bars
+    pub rvol: Decimal,
+}
+
+/// Volume of the last bar relative to the average of the earlier bars in its
+/// session; `None` on a session's first bar or without earlier volume
+pub fn session_rvol(bars: &[IntradayBar]) -> Option<Decimal> {
+    let (last, earlier) = bars.split_last()?;
+    let date = session_date(last.time);
+    let session: Vec<i64> = earlier
+        .iter()
+        .rev()
+        .take_while(|b| session_date(b.time) == date)
+        .map(|b| b.bar.volume.max(0))
+        .collect();
+    if session.is_empty() {
+        return None;
+    }
+    let average = Decimal::from(session.iter().sum::<i64>()) / Decimal::from(session.len() as i64);
+    if average.is_zero() {
+        return None;
+    }
+    Some(Decimal::from(last.bar.volume.max(0)) / average)
+}
+
+/// Detect the last bar reclaiming or losing session VWAP on volume
+///
+/// Both bars must belong to the same session; the crossing bar's volume must
+/// be at least `min_rvol` times the session's earlier average.
+pub fn session_vwap_cross(bars: &[IntradayBar], min_rvol: Decimal) -> Option<VwapCrossEvent> {
+    if bars.len() < 2 {
+        return None;
+    }
+    let (prev, last) = (&bars[bars.len() - 2], &bars[bars.len() - 1]);
+    if session_date(prev.time) != session_date(last.time) {
+        return None;
+    }
+
+    let bands = calculate_session_vwap_bands(bars).ok()?;
+    let (prev_vwap, vwap) = (bands[bands.len() - 2].vwap, bands[bands.len() - 1].vwap);
+    let (prev_close, close) = (prev.bar.close, last.bar.close);
+
+    let cross = if prev_close <= prev_vwap && close > vwap {
+        VwapCross::Reclaim
+    } else if prev_close >= prev_vwap && close < vwap {
+        VwapCross::Loss
+    } else {
+        return None;
+    };
+
+    let rvol = session_rvol(bars)?;
+    (rvol >= min_rvol).then_some(VwapCrossEvent {
+        cross,
+        price: close,
+        vwap,
+        rvol,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    /// Bar at `hour:minute` WIB on 2024-03-`day`
+    fn bar(day: u32, hour: u32, minute: u32, price: i64, volume: i64) -> IntradayBar {
+        let wib = FixedOffset::east_opt(WIB_OFFSET_SECS).unwrap();
+        let price = Decimal::from(price);
+        IntradayBar {
+            time: wib
+                .with_ymd_and_hms(2024, 3, day, hour, minute, 0)
+                .unwrap()
+                .with_timezone(&Utc),
+            bar: OhlcvBar {
+                open: price,
+                high: price,
+                low: price,
+                close: price,
+                volume,
+            },
+        }
+    }
+
+    #[test]
+    fn test_bands_reset_each_session() {
+        let bars = vec![
+            bar(4, 9, 0, 100, 100),
+            bar(4, 9, 5, 110, 100),
+            bar(4, 15, 55, 120, 200),
+            // Next session starts over
+            bar(5, 9, 0, 200, 50),
+        ];
+        let bands = calculate_session_vwap_bands(&bars).unwrap();
+        assert_eq!(bands.len(), 4);
+
+        assert_eq!(bands[0].vwap, dec!(100));
+        assert_eq!(bands[0].std_dev, Decimal::ZERO);
+        // Equal volume at 100 and 110: VWAP 105, deviation 5
+        assert_eq!(bands[1].vwap, dec!(105));
+        assert!((bands[1].std_dev - dec!(5)).abs() < dec!(0.0001));
+        assert!((bands[1].upper_2 - dec!(115)).abs() < dec!(0.001));
+        assert!((bands[1].lower_1 - dec!(100)).abs() < dec!(0.001));
+        // 100×100 + 110×100 + 120×200 over 400
+        assert_eq!(bands[2].vwap, dec!(112.5));
+
+        assert_eq!(bands[3].vwap, dec!(200));
+        assert_eq!(bands[3].std_dev, Decimal::ZERO);
+
+        assert!(calculate_session_vwap_bands(&[]).is_err());
+    }
+
+    #[test]
+    fn test_vwap_reclaim_and_loss() {
+        let mut bars = vec![
+            bar(4, 9, 0, 110, 1000),
+            bar(4, 9, 5, 100, 1000),
+            bar(4, 9, 10, 102, 1000),
+        ];
+        // VWAP ≈ 104; a close of 106 on 3x volume reclaims it
+        bars.push(bar(4, 9, 15, 106, 3000));
+        let event = session_vwap_cross(&bars, dec!(2)).unwrap();
+        assert_eq!(event.cross, VwapCross::Reclaim);
+        assert_eq!(event.price, dec!(106));
+        assert_eq!(event.rvol, dec!(3));
+
+        // Same cross on ordinary volume is ignored
+        bars.pop();
+        bars.push(bar(4, 9, 15, 106, 1000));
+        assert!(session_vwap_cross(&bars, dec!(2)).is_none());
+
+        // Falling back under VWAP on volume loses it
+        bars.push(bar(4, 9, 20, 100, 4000));
+        let event = session_vwap_cross(&bars, dec!(2)).unwrap();
+        assert_eq!(event.cross, VwapCross::Loss);
+    }
+
+    #[test]
+    fn test_no_cross_across_sessions() {
+        let bars = vec![bar(4, 15, 55, 100, 1000), bar(5, 9, 0, 120, 5000)];
+        assert!(session_vwap_cross(&bars, dec!(1)).is_none());
+        assert_eq!(session_rvol(&bars), None);
+    }
+}
@@ -0,0 +1,176 @@
+//! Return statistics
+//!
+//! Log returns, Pearson correlation and beta against a benchmark. Closes
+//! arrive as `Decimal`; a close that is zero, negative or does not fit an
+//! `f64` breaks the return chain instead of producing NaN or infinity, and
+//! statistics over too few or constant returns are `None` rather than a
+//! division by zero.
+
+use chrono::NaiveDate;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use std::cmp::Ordering;
+
+/// Common returns needed before a correlation or beta is reported
+pub const MIN_RETURN_OBSERVATIONS: usize = 10;
+
+pub fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+/// Log returns between consecutive closes, dated by the later close
+pub fn log_returns(closes: &[(NaiveDate, Decimal)]) -> Vec<(NaiveDate, f64)> {
+    closes
+        .windows(2)
+        .filter_map(|w| {
+            let prev = w[0].1.to_f64().filter(|c| *c > 0.0)?;
+            let close = w[1].1.to_f64().filter(|c| *c > 0.0)?;
+            Some((w[1].0, (close / prev).ln()))
+        })
+        .collect()
+}
+
+/// Returns of two series on the dates both have, oldest first
+pub fn align_returns(a: &[(NaiveDate, f64)], b: &[(NaiveDate, f64)]) -> (Vec<f64>, Vec<f64>) {
+    let (mut x, mut y) = (Vec::new(), Vec::new());
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].0.cmp(&b[j].0) {
+            Ordering::Less => i += 1,
+            Ordering::Greater => j += 1,
+            Ordering::Equal => {
+                x.push(a[i].1);
+                y.push(b[j].1);
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    (x, y)
+}
+
+/// Sample covariance; `None` below two pairs or for unequal lengths
+pub fn covariance(x: &[f64], y: &[f64]) -> Option<f64> {
+    if x.len() != y.len() || x.len() < 2 {
+        return None;
+    }
+    let (mx, my) = (mean(x), mean(y));
+    let sum: f64 = x.iter().zip(y).map(|(a, b)| (a - mx) * (b - my)).sum();
+    Some(sum / (x.len() - 1) as f64)
+}
+
+/// Sample variance; `None` below two values
+pub fn variance(x: &[f64]) -> Option<f64> {
+    covariance(x, x)
+}
+
+/// Pearson correlation; `None` when either series is constant
+pub fn pearson_correlation(x: &[f64], y: &[f64]) -> Option<f64> {
+    let cov = covariance(x, y)?;
+    let sd = (variance(x)? * variance(y)?).sqrt();
+    if sd <= f64::EPSILON {
+        return None;
+    }
+    // Rounding can push a perfect correlation just past ±1
+    Some((cov / sd).clamp(-1.0, 1.0))
+}
+
+/// Beta of `asset` against `market`: cov(asset, market) / var(market)
+pub fn beta(asset: &[f64], market: &[f64]) -> Option<f64> {
+    let market_var = variance(market)?;
+    if market_var <= f64::EPSILON {
+        return None;
+    }
+    Some(covariance(asset, market)? / market_var)
+}
+
+/// Pairwise correlation of dated return series
+///
+/// Each pair is compared on the dates both traded; pairs with fewer than
+/// `MIN_RETURN_OBSERVATIONS` common returns are `None`. The diagonal is 1.
+pub fn correlation_matrix(series: &[Vec<(NaiveDate, f64)>]) -> Vec<Vec<Option<f64>>> {
+    let n = series.len();
+    let mut matrix = vec![vec![None; n]; n];
+    for i in 0..n {
+        matrix[i][i] = Some(1.0);
+        for j in i + 1..n {
+            let (x, y) = align_returns(&series[i], &series[j]);
+            let corr = (x.len() >= MIN_RETURN_OBSERVATIONS)
+                .then(|| pearson_correlation(&x, &y))
+                .flatten();
+            matrix[i][j] = corr;
+            matrix[j][i] = corr;
+        }
+    }
+    matrix
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn day(i: usize) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, 1).unwrap() + chrono::Duration::days(i as i64)
+    }
+
+    fn dated(values: &[f64]) -> Vec<(NaiveDate, f64)> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (day(i), *v))
+            .collect()
+    }
+
+    #[test]
+    fn test_log_returns_skip_invalid_closes() {
+        let closes = vec![
+            (day(0), dec!(100)),
+            (day(1), dec!(110)),
+            (day(2), dec!(0)),
+            (day(3), dec!(121)),
+            (day(4), dec!(121)),
+        ];
+        let returns = log_returns(&closes);
+        // The zero close drops both returns that touch it
+        assert_eq!(returns.len(), 2);
+        assert_eq!(returns[0].0, day(1));
+        assert!((returns[0].1 - (1.1f64).ln()).abs() < 1e-12);
+        assert_eq!(returns[1], (day(4), 0.0));
+    }
+
+    #[test]
+    fn test_correlation_and_beta() {
+        let market = [0.01, -0.02, 0.015, 0.0, -0.005, 0.02, -0.01, 0.005];
+        let levered: Vec<f64> = market.iter().map(|r| 1.5 * r + 0.001).collect();
+        let inverse: Vec<f64> = market.iter().map(|r| -r).collect();
+
+        assert!((pearson_correlation(&levered, &market).unwrap() - 1.0).abs() < 1e-12);
+        assert!((pearson_correlation(&inverse, &market).unwrap() + 1.0).abs() < 1e-12);
+        assert!((beta(&levered, &market).unwrap() - 1.5).abs() < 1e-12);
+
+        // Constant or too-short series have no answer
+        assert_eq!(pearson_correlation(&[0.01; 8], &market), None);
+        assert_eq!(beta(&market, &[0.0; 8]), None);
+        assert_eq!(variance(&[0.01]), None);
+        assert_eq!(covariance(&market, &market[..4]), None);
+    }
+
+    #[test]
+    fn test_correlation_matrix_aligns_dates() {
+        let a: Vec<f64> = (0..20)
+            .map(|i| ((i * 7) % 5) as f64 / 100.0 - 0.02)
+            .collect();
+        let b: Vec<f64> = a.iter().map(|r| 2.0 * r).collect();
+        let mut shifted = dated(&b);
+        // b missed a session; alignment drops it instead of misaligning
+        shifted.remove(3);
+        let short = dated(&a[..5]);
+
+        let matrix = correlation_matrix(&[dated(&a), shifted, short]);
+        assert_eq!(matrix[0][0], Some(1.0));
+        assert!((matrix[0][1].unwrap() - 1.0).abs() < 1e-12);
+        assert_eq!(matrix[1][0], matrix[0][1]);
+        assert_eq!(matrix[0][2], None);
+    }
+}
@@ -0,0 +1,376 @@
+//! Incremental indicator state for streaming prices
+//!
+//! The batch functions (`calculate_ema`, `calculate_rsi`, `calculate_macd`)
+//! recompute the whole series. These states keep the running values instead,
+//! so a price feed can maintain indicators per symbol in O(1) per update:
+//! - `update` commits a closed bar
+//! - `peek` previews the value for an in-progress bar without committing it
+//!
+//! States reproduce the batch values exactly, including the warm-up length.
+
+use crate::error::TechnicalError;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+fn validate_period(period: usize) -> Result<(), TechnicalError> {
+    if period == 0 {
+        return Err(TechnicalError::InvalidPeriod(
+            "Period must be > 0".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Running EMA, seeded with the SMA of the first `period` prices
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmaState {
+    period: usize,
+    k: Decimal,
+    seed_sum: Decimal,
+    count: usize,
+    value: Option<Decimal>,
+}
+
+impl EmaState {
+    pub fn new(period: usize) -> Result<Self, TechnicalError> {
+        validate_period(period)?;
+        Ok(Self {
+            period,
+            k: Decimal::from(2) / Decimal::from(period as i64 + 1),
+            seed_sum: Decimal::ZERO,
+            count: 0,
+            value: None,
+        })
+    }
+
+    /// State after replaying a price history
+    pub fn from_history(period: usize, prices: &[Decimal]) -> Result<Self, TechnicalError> {
+        let mut state = Self::new(period)?;
+        for price in prices {
+            state.update(*price);
+        }
+        Ok(state)
+    }
+
+    /// Add a closing price; returns the EMA once warmed up
+    pub fn update(&mut self, price: Decimal) -> Option<Decimal> {
+        self.count += 1;
+        self.value = match self.value {
+            Some(prev) => Some(price * self.k + prev * (dec!(1) - self.k)),
+            None => {
+                self.seed_sum += price;
+                (self.count == self.period)
+                    .then(|| self.seed_sum / Decimal::from(self.period as i64))
+            }
+        };
+        self.value
+    }
+
+    /// EMA if `price` were the next close, without committing it
+    pub fn peek(&self, price: Decimal) -> Option<Decimal> {
+        self.clone().update(price)
+    }
+
+    pub fn value(&self) -> Option<Decimal> {
+        self.value
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.value.is_some()
+    }
+}
+
+/// Running RSI with Wilder smoothing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RsiState {
+    period: usize,
+    prev_price: Option<Decimal>,
+    changes: usize,
+    avg_gain: Decimal,
+    avg_loss: Decimal,
+    value: Option<Decimal>,
+}
+
+impl RsiState {
+    pub fn new(period: usize) -> Result<Self, TechnicalError> {
+        validate_period(period)?;
+        Ok(Self {
+            period,
+            prev_price: None,
+            changes: 0,
+            avg_gain: Decimal::ZERO,
+            avg_loss: Decimal::ZERO,
+            value: None,
+        })
+    }
+
+    /// State after replaying a price history
+    pub fn from_history(period: usize, prices: &[Decimal]) -> Result<Self, TechnicalError> {
+        let mut state = Self::new(period)?;
+        for price in prices {
+            state.update(*price);
+        }
+        Ok(state)
+    }
+
+    /// Add a closing price; returns the RSI once `period` changes are seen
+    pub fn update(&mut self, price: Decimal) -> Option<Decimal> {
+        let prev = self.prev_price.replace(price)?;
+
+        let change = price - prev;
+        let gain = change.max(Decimal::ZERO);
+        let loss = (-change).max(Decimal::ZERO);
+        let period = Decimal::from(self.period as i64);
+        self.changes += 1;
+
+        if self.changes < self.period {
+            // Sums until the first `period` changes are in
+            self.avg_gain += gain;
+            self.avg_loss += loss;
+            return None;
+        } else if self.changes == self.period {
+            self.avg_gain = (self.avg_gain + gain) / period;
+            self.avg_loss = (self.avg_loss + loss) / period;
+        } else {
+            self.avg_gain = (self.avg_gain * (period - dec!(1)) + gain) / period;
+            self.avg_loss = (self.avg_loss * (period - dec!(1)) + loss) / period;
+        }
+
+        let rs = if self.avg_loss == Decimal::ZERO {
+            dec!(100)
+        } else {
+            self.avg_gain / self.avg_loss
+        };
+        self.value = Some(dec!(100) - (dec!(100) / (dec!(1) + rs)));
+        self.value
+    }
+
+    /// RSI if `price` were the next close, without committing it
+    pub fn peek(&self, price: Decimal) -> Option<Decimal> {
+        self.clone().update(price)
+    }
+
+    pub fn value(&self) -> Option<Decimal> {
+        self.value
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.value.is_some()
+    }
+}
+
+/// Latest MACD values
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MacdValue {
+    pub macd: Decimal,
+    pub signal: Decimal,
+    pub histogram: Decimal,
+}
+
+/// Running MACD
+///
+/// The signal EMA starts once the slow EMA is warmed up, so the first value
+/// arrives after `slow_period + signal_period - 1` prices.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MacdState {
+    fast: EmaState,
+    slow: EmaState,
+    signal: EmaState,
+    value: Option<MacdValue>,
+}
+
+impl MacdState {
+    /// MACD with the standard (12, 26, 9) parameters
+    pub fn standard() -> Self {
+        Self::new(12, 26, 9).expect("standard MACD periods are valid")
+    }
+
+    pub fn new(
+        fast_period: usize,
+        slow_period: usize,
+        signal_period: usize,
+    ) -> Result<Self, TechnicalError> {
+        if fast_period >= slow_period {
+            return Err(TechnicalError::InvalidParameter(
+                "Fast period must be shorter than slow period".to_string(),
+            ));
+        }
+        Ok(Self {
+            fast: EmaState::new(fast_period)?,
+            slow: EmaState::new(slow_period)?,
+            signal: EmaState::new(signal_period)?,
+            value: None,
+        })
+    }
+
+    /// Add a closing price; returns MACD, signal and histogram once warmed up
+    pub fn update(&mut self, price: Decimal) -> Option<MacdValue> {
+        let fast = self.fast.update(price);
+        let slow = self.slow.update(price);
+        let macd = fast? - slow?;
+        let signal = self.signal.update(macd)?;
+        self.value = Some(MacdValue {
+            macd,
+            signal,
+            histogram: macd - signal,
+        });
+        self.value
+    }
+
+    /// MACD if `price` were the next close, without committing it
+    pub fn peek(&self, price: Decimal) -> Option<MacdValue> {
+        self.clone().update(price)
+    }
+
+    pub fn value(&self) -> Option<MacdValue> {
+        self.value
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.value.is_some()
+    }
+}
+
+/// Latest values of the standard indicator set
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndicatorSnapshot {
+    pub price: Decimal,
+    pub ema20: Option<Decimal>,
+    pub ema50: Option<Decimal>,
+    pub rsi14: Option<Decimal>,
+    pub macd: Option<MacdValue>,
+}
+
+/// Per-symbol state for EMA 20/50, RSI 14 and MACD (12, 26, 9)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndicatorState {
+    ema20: EmaState,
+    ema50: EmaState,
+    rsi14: RsiState,
+    macd: MacdState,
+}
+
+impl IndicatorState {
+    pub fn new() -> Self {
+        Self {
+            ema20: EmaState::new(20).expect("valid period"),
+            ema50: EmaState::new(50).expect("valid period"),
+            rsi14: RsiState::new(14).expect("valid period"),
+            macd: MacdState::standard(),
+        }
+    }
+
+    /// State after replaying a price history, e.g. daily closes from the database
+    pub fn from_history(prices: &[Decimal]) -> Self {
+        let mut state = Self::new();
+        for price in prices {
+            state.update(*price);
+        }
+        state
+    }
+
+    /// Commit a closing price
+    pub fn update(&mut self, price: Decimal) -> IndicatorSnapshot {
+        IndicatorSnapshot {
+            price,
+            ema20: self.ema20.update(price),
+            ema50: self.ema50.update(price),
+            rsi14: self.rsi14.update(price),
+            macd: self.macd.update(price),
+        }
+    }
+
+    /// Indicators for a live tick on the current bar, without committing it
+    pub fn peek(&self, price: Decimal) -> IndicatorSnapshot {
+        IndicatorSnapshot {
+            price,
+            ema20: self.ema20.peek(price),
+            ema50: self.ema50.peek(price),
+            rsi14: self.rsi14.peek(price),
+            macd: self.macd.peek(price),
+        }
+    }
+}
+
+impl Default for IndicatorState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{calculate_ema, calculate_macd, calculate_rsi};
+
+    fn prices() -> Vec<Decimal> {
+        (0..80)
+            .map(|i| Decimal::from(1000 + (i * 37) % 23 * 5 + i * 2))
+            .collect()
+    }
+
+    #[test]
+    fn test_ema_state_matches_batch() {
+        let prices = prices();
+        let batch = calculate_ema(&prices, 20).unwrap();
+        let mut state = EmaState::new(20).unwrap();
+
+        for (i, price) in prices.iter().enumerate() {
+            let value = state.update(*price);
+            if i < 19 {
+                assert_eq!(value, None);
+            } else {
+                assert_eq!(value, batch.get(i));
+            }
+        }
+    }
+
+    #[test]
+    fn test_rsi_state_matches_batch() {
+        let prices = prices();
+        let batch = calculate_rsi(&prices, 14).unwrap();
+        let state = RsiState::from_history(14, &prices).unwrap();
+        assert_eq!(state.value(), batch.last_valid());
+
+        let mut state = RsiState::new(14).unwrap();
+        for (i, price) in prices.iter().enumerate() {
+            let value = state.update(*price);
+            assert_eq!(value, batch.get(i));
+        }
+    }
+
+    #[test]
+    fn test_macd_state_matches_batch() {
+        let prices = prices();
+        let batch = calculate_macd(&prices).unwrap();
+        let mut state = MacdState::standard();
+        for (i, price) in prices.iter().enumerate() {
+            let value = state.update(*price);
+            assert_eq!(value.map(|v| v.histogram), batch.histogram.get(i));
+        }
+        let value = state.value().unwrap();
+        assert_eq!(Some(value.macd), batch.macd_line.last_valid());
+        assert_eq!(Some(value.signal), batch.signal_line.last_valid());
+    }
+
+    #[test]
+    fn test_peek_does_not_commit() {
+        let prices = prices();
+        let state = IndicatorState::from_history(&prices);
+        let before = state.peek(dec!(1200));
+        let again = state.peek(dec!(1200));
+        assert_eq!(before, again);
+
+        let mut committed = state.clone();
+        assert_eq!(committed.update(dec!(1200)), before);
+        assert!(before.ema50.is_some() && before.rsi14.is_some() && before.macd.is_some());
+    }
+
+    #[test]
+    fn test_invalid_periods() {
+        assert!(EmaState::new(0).is_err());
+        assert!(RsiState::new(0).is_err());
+        assert!(MacdState::new(26, 12, 9).is_err());
+    }
+}
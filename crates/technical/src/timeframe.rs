@@ -0,0 +1,136 @@
+//! Timeframe resampling
+//!
+//! Builds weekly and monthly bars from daily bars so the same indicators can
+//! be read on a higher timeframe. Weeks start on Monday; a resampled bar takes
+//! the first open, highest high, lowest low, last close and summed volume of
+//! the daily bars in its period.
+
+use crate::wyckoff::OhlcvBar;
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+/// Bar timeframe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Timeframe {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+impl Timeframe {
+    pub const ALL: [Timeframe; 3] = [Timeframe::Daily, Timeframe::Weekly, Timeframe::Monthly];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Timeframe::Daily => "daily",
+            Timeframe::Weekly => "weekly",
+            Timeframe::Monthly => "monthly",
+        }
+    }
+
+    /// First day of the period containing `date`
+    pub fn period_start(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Timeframe::Daily => date,
+            Timeframe::Weekly => {
+                date - Duration::days(date.weekday().num_days_from_monday() as i64)
+            }
+            Timeframe::Monthly => date.with_day(1).unwrap_or(date),
+        }
+    }
+}
+
+/// OHLCV bar with the date its period starts on
+#[derive(Debug, Clone)]
+pub struct DatedBar {
+    pub date: NaiveDate,
+    pub bar: OhlcvBar,
+}
+
+/// Resample daily bars (oldest first) to `timeframe`
+///
+/// The latest period may be incomplete; its bar covers the days so far.
+pub fn resample_ohlcv(bars: &[DatedBar], timeframe: Timeframe) -> Vec<DatedBar> {
+    let mut resampled: Vec<DatedBar> = Vec::new();
+
+    for daily in bars {
+        let start = timeframe.period_start(daily.date);
+        match resampled.last_mut() {
+            Some(current) if current.date == start => {
+                current.bar.high = current.bar.high.max(daily.bar.high);
+                current.bar.low = current.bar.low.min(daily.bar.low);
+                current.bar.close = daily.bar.close;
+                current.bar.volume += daily.bar.volume;
+            }
+            _ => resampled.push(DatedBar {
+                date: start,
+                bar: daily.bar.clone(),
+            }),
+        }
+    }
+
+    resampled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+
+    fn daily(date: &str, open: Decimal, high: Decimal, low: Decimal, close: Decimal) -> DatedBar {
+        DatedBar {
+            date: date.parse().unwrap(),
+            bar: OhlcvBar {
+                open,
+                high,
+                low,
+                close,
+                volume: 100,
+            },
+        }
+    }
+
+    fn bars() -> Vec<DatedBar> {
+        vec![
+            // Thursday, Friday
+            daily("2025-01-30", dec!(100), dec!(105), dec!(98), dec!(104)),
+            daily("2025-01-31", dec!(104), dec!(108), dec!(103), dec!(107)),
+            // Monday to Wednesday of the next week, in February
+            daily("2025-02-03", dec!(107), dec!(110), dec!(101), dec!(102)),
+            daily("2025-02-04", dec!(102), dec!(103), dec!(95), dec!(96)),
+            daily("2025-02-05", dec!(96), dec!(99), dec!(94), dec!(98)),
+        ]
+    }
+
+    #[test]
+    fn test_resample_weekly() {
+        let weekly = resample_ohlcv(&bars(), Timeframe::Weekly);
+        assert_eq!(weekly.len(), 2);
+
+        assert_eq!(weekly[0].date, "2025-01-27".parse::<NaiveDate>().unwrap());
+        assert_eq!(weekly[0].bar.open, dec!(100));
+        assert_eq!(weekly[0].bar.close, dec!(107));
+
+        let week = &weekly[1].bar;
+        assert_eq!(weekly[1].date, "2025-02-03".parse::<NaiveDate>().unwrap());
+        assert_eq!(
+            (week.open, week.high, week.low, week.close),
+            (dec!(107), dec!(110), dec!(94), dec!(98))
+        );
+        assert_eq!(week.volume, 300);
+    }
+
+    #[test]
+    fn test_resample_monthly_and_daily() {
+        let monthly = resample_ohlcv(&bars(), Timeframe::Monthly);
+        assert_eq!(monthly.len(), 2);
+        assert_eq!(monthly[0].bar.high, dec!(108));
+        assert_eq!(monthly[1].date, "2025-02-01".parse::<NaiveDate>().unwrap());
+        assert_eq!(monthly[1].bar.open, dec!(107));
+
+        assert_eq!(resample_ohlcv(&bars(), Timeframe::Daily).len(), 5);
+        assert!(resample_ohlcv(&[], Timeframe::Weekly).is_empty());
+    }
+}
@@ -1,8 +1,10 @@
-//! Volume-based indicators (OBV, VPT)
+//! Volume-based indicators (OBV, VPT, RVOL, VWAP) and volume profile
 
 use crate::error::TechnicalError;
+use crate::wyckoff::OhlcvBar;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
 
 /// Calculate On-Balance Volume (OBV)
 /// OBV adds volume on up days, subtracts on down days
@@ -120,6 +122,202 @@ pub fn obv_divergence(prices: &[Decimal], obv: &[i64], lookback: usize) -> Optio
     }
 }
 
+/// Calculate Volume-Weighted Average Price from the first bar
+///
+/// Uses the typical price (high + low + close) / 3 of each bar.
+pub fn calculate_vwap(bars: &[OhlcvBar]) -> Result<Vec<Decimal>, TechnicalError> {
+    calculate_anchored_vwap(bars, 0)
+}
+
+/// Calculate VWAP anchored at `anchor_index` (e.g. a swing low or earnings day)
+///
+/// Output is aligned with the input bars; values before the anchor are zero.
+/// Until the first bar with volume, the VWAP is that bar's typical price.
+pub fn calculate_anchored_vwap(
+    bars: &[OhlcvBar],
+    anchor_index: usize,
+) -> Result<Vec<Decimal>, TechnicalError> {
+    if bars.is_empty() {
+        return Err(TechnicalError::InsufficientData {
+            required: 1,
+            actual: 0,
+        });
+    }
+    if anchor_index >= bars.len() {
+        return Err(TechnicalError::InvalidParameter(format!(
+            "Anchor index {} is beyond the last bar ({})",
+            anchor_index,
+            bars.len() - 1
+        )));
+    }
+
+    let mut vwap = vec![Decimal::ZERO; anchor_index];
+    let mut cumulative_pv = Decimal::ZERO;
+    let mut cumulative_volume = Decimal::ZERO;
+
+    for bar in &bars[anchor_index..] {
+        let typical = (bar.high + bar.low + bar.close) / dec!(3);
+        let volume = Decimal::from(bar.volume.max(0));
+        cumulative_pv += typical * volume;
+        cumulative_volume += volume;
+
+        if cumulative_volume > Decimal::ZERO {
+            vwap.push(cumulative_pv / cumulative_volume);
+        } else {
+            vwap.push(typical);
+        }
+    }
+
+    Ok(vwap)
+}
+
+/// Price buckets in a volume profile unless asked otherwise
+pub const DEFAULT_VOLUME_PROFILE_BINS: usize = 24;
+
+/// Share of the traded volume inside the value area
+pub const VALUE_AREA_SHARE: Decimal = dec!(0.70);
+
+/// Volume traded within one price bucket
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VolumeAtPrice {
+    pub low: Decimal,
+    pub high: Decimal,
+    pub volume: Decimal,
+}
+
+/// Where volume concentrated by price level
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VolumeProfile {
+    /// Buckets from the lowest price up
+    pub levels: Vec<VolumeAtPrice>,
+    pub total_volume: Decimal,
+    /// Midpoint of the bucket with the most volume (POC)
+    pub point_of_control: Decimal,
+    /// Top and bottom of the buckets holding 70% of the volume around the POC
+    pub value_area_high: Decimal,
+    pub value_area_low: Decimal,
+}
+
+/// Bin the volume of `bars` into `bins` equal price buckets
+///
+/// Each bar's volume is spread over its high-low range in proportion to how
+/// much of the range each bucket covers; a bar without range puts all of it
+/// in the bucket holding its close. The value area grows from the point of
+/// control one bucket at a time, towards the side with more volume (upwards
+/// on a tie), until it holds `VALUE_AREA_SHARE` of the volume.
+pub fn volume_profile(bars: &[OhlcvBar], bins: usize) -> Result<VolumeProfile, TechnicalError> {
+    if bars.is_empty() {
+        return Err(TechnicalError::InsufficientData {
+            required: 1,
+            actual: 0,
+        });
+    }
+    if bins == 0 {
+        return Err(TechnicalError::InvalidParameter(
+            "Volume profile needs at least one bin".to_string(),
+        ));
+    }
+
+    let low = bars
+        .iter()
+        .map(|b| b.low.min(b.close))
+        .min()
+        .unwrap_or_default();
+    let high = bars
+        .iter()
+        .map(|b| b.high.max(b.close))
+        .max()
+        .unwrap_or_default();
+    // A range-less window is a single level
+    let bins = if high > low { bins } else { 1 };
+    let width = (high - low) / Decimal::from(bins);
+    let bucket_of = |price: Decimal| -> usize {
+        if width.is_zero() {
+            return 0;
+        }
+        ((price - low) / width)
+            .floor()
+            .to_string()
+            .parse::<usize>()
+            .unwrap_or(0)
+            .min(bins - 1)
+    };
+
+    let mut levels: Vec<VolumeAtPrice> = (0..bins)
+        .map(|i| VolumeAtPrice {
+            low: low + width * Decimal::from(i),
+            high: if i + 1 == bins {
+                high
+            } else {
+                low + width * Decimal::from(i + 1)
+            },
+            volume: Decimal::ZERO,
+        })
+        .collect();
+
+    for bar in bars {
+        let volume = Decimal::from(bar.volume.max(0));
+        let range = bar.high - bar.low;
+        if range <= Decimal::ZERO {
+            levels[bucket_of(bar.close)].volume += volume;
+            continue;
+        }
+        for level in &mut levels[bucket_of(bar.low)..=bucket_of(bar.high)] {
+            let overlap = level.high.min(bar.high) - level.low.max(bar.low);
+            if overlap > Decimal::ZERO {
+                level.volume += volume * overlap / range;
+            }
+        }
+    }
+
+    let total_volume: Decimal = levels.iter().map(|l| l.volume).sum();
+    if total_volume.is_zero() {
+        return Err(TechnicalError::CalculationError(
+            "No volume traded in the window".to_string(),
+        ));
+    }
+
+    // Most volume; the lowest such bucket on a tie
+    let poc = (1..levels.len()).fold(0, |best, i| {
+        if levels[i].volume > levels[best].volume {
+            i
+        } else {
+            best
+        }
+    });
+
+    let target = total_volume * VALUE_AREA_SHARE;
+    let (mut lo, mut hi) = (poc, poc);
+    let mut inside = levels[poc].volume;
+    while inside < target && (lo > 0 || hi + 1 < levels.len()) {
+        let below = (lo > 0).then(|| levels[lo - 1].volume);
+        let above = (hi + 1 < levels.len()).then(|| levels[hi + 1].volume);
+        match (below, above) {
+            (Some(b), Some(a)) if b > a => {
+                lo -= 1;
+                inside += b;
+            }
+            (_, Some(a)) => {
+                hi += 1;
+                inside += a;
+            }
+            (Some(b), None) => {
+                lo -= 1;
+                inside += b;
+            }
+            (None, None) => break,
+        }
+    }
+
+    Ok(VolumeProfile {
+        point_of_control: (levels[poc].low + levels[poc].high) / dec!(2),
+        value_area_high: levels[hi].high,
+        value_area_low: levels[lo].low,
+        total_volume,
+        levels,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -216,6 +414,48 @@ mod tests {
         assert!(!is_volume_spike(dec!(2), dec!(2)));
     }
 
+    fn bar(high: i64, low: i64, close: i64, volume: i64) -> OhlcvBar {
+        OhlcvBar {
+            open: Decimal::from(close),
+            high: Decimal::from(high),
+            low: Decimal::from(low),
+            close: Decimal::from(close),
+            volume,
+        }
+    }
+
+    #[test]
+    fn test_vwap() {
+        // Typical prices 100, 110, 120
+        let bars = vec![
+            bar(105, 95, 100, 100),
+            bar(115, 105, 110, 300),
+            bar(125, 115, 120, 0),
+        ];
+        let vwap = calculate_vwap(&bars).unwrap();
+        assert_eq!(vwap[0], dec!(100));
+        assert_eq!(vwap[1], dec!(107.5));
+        // No volume on the last bar leaves VWAP unchanged
+        assert_eq!(vwap[2], dec!(107.5));
+    }
+
+    #[test]
+    fn test_anchored_vwap() {
+        let bars = vec![
+            bar(105, 95, 100, 1000),
+            bar(115, 105, 110, 100),
+            bar(125, 115, 120, 100),
+        ];
+        let vwap = calculate_anchored_vwap(&bars, 1).unwrap();
+        assert_eq!(vwap.len(), 3);
+        assert_eq!(vwap[0], Decimal::ZERO);
+        assert_eq!(vwap[1], dec!(110));
+        assert_eq!(vwap[2], dec!(115));
+
+        assert!(calculate_anchored_vwap(&bars, 3).is_err());
+        assert!(calculate_vwap(&[]).is_err());
+    }
+
     #[test]
     fn test_obv_divergence_bullish() {
         // Price falling but OBV rising = bullish divergence
@@ -254,4 +494,67 @@ mod tests {
         let result = obv_divergence(&prices, &obv, 2);
         assert!(result.is_none());
     }
+
+    fn range_bar(low: Decimal, high: Decimal, close: Decimal, volume: i64) -> OhlcvBar {
+        OhlcvBar {
+            open: close,
+            high,
+            low,
+            close,
+            volume,
+        }
+    }
+
+    #[test]
+    fn test_volume_profile_poc_and_value_area() {
+        let bars = vec![
+            // Heavy trade between 100 and 102
+            range_bar(dec!(100), dec!(102), dec!(101), 6000),
+            range_bar(dec!(100), dec!(102), dec!(100), 4000),
+            // Thin excursions to either side
+            range_bar(dec!(96), dec!(100), dec!(99), 800),
+            range_bar(dec!(102), dec!(110), dec!(108), 1600),
+        ];
+        let profile = volume_profile(&bars, 7).unwrap();
+
+        assert_eq!(profile.levels.len(), 7);
+        assert_eq!(profile.levels[0].low, dec!(96));
+        assert_eq!(profile.levels[6].high, dec!(110));
+        assert_eq!(profile.total_volume, dec!(12400));
+        // Buckets of 2: the 100-102 bucket holds 10,000 of the volume
+        assert_eq!(profile.point_of_control, dec!(101));
+        assert_eq!(profile.levels[2].volume, dec!(10000));
+        // 10,000 of 12,400 is already above 70%
+        assert_eq!(profile.value_area_low, dec!(100));
+        assert_eq!(profile.value_area_high, dec!(102));
+    }
+
+    #[test]
+    fn test_volume_profile_value_area_expands_towards_volume() {
+        let bars: Vec<OhlcvBar> = [(10, 100), (11, 300), (12, 500), (13, 200), (14, 150)]
+            .iter()
+            .map(|(price, volume)| {
+                range_bar(
+                    Decimal::from(*price),
+                    Decimal::from(*price),
+                    Decimal::from(*price),
+                    *volume,
+                )
+            })
+            .collect();
+        let profile = volume_profile(&bars, 5).unwrap();
+
+        // Range 10-14 in buckets of 0.8; each close lands in its own bucket
+        assert_eq!(profile.levels[2].volume, dec!(500));
+        assert_eq!(profile.point_of_control, dec!(12));
+        // 500, then 300 below (beats 200 above), then 200 above: 1000 of 1250
+        assert_eq!(profile.value_area_low, dec!(10.8));
+        assert_eq!(profile.value_area_high, dec!(13.2));
+
+        let flat = volume_profile(&[range_bar(dec!(50), dec!(50), dec!(50), 10)], 24).unwrap();
+        assert_eq!(flat.levels.len(), 1);
+        assert_eq!(flat.point_of_control, dec!(50));
+        assert!(volume_profile(&[], 24).is_err());
+        assert!(volume_profile(&bars, 0).is_err());
+    }
 }
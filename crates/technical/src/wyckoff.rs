@@ -5,8 +5,28 @@
 //! - Markup: Uptrend phase
 //! - Distribution: Smart money selling before markdown
 //! - Markdown: Downtrend phase
+//!
+//! Bars are also classified volume spread analysis (VSA) style, comparing
+//! each bar's spread and volume with the bars before it: no demand, no
+//! supply, stopping volume and effort-vs-result anomalies. These catch the
+//! low-volume tests that price-based events miss and adjust the phase
+//! confidence.
+//!
+//! When support and resistance bound a trading range, [`WyckoffRange`]
+//! measures the cause being built: how long the range has run, how tall it
+//! is, how often support was tested and whether volume dried up. Its cause
+//! quality moves the confidence of accumulation and distribution readings.
+//!
+//! Thresholds are tuned per liquidity tier with [`WyckoffProfile`]: thin
+//! small caps spike on volume and overshoot levels far more often than blue
+//! chips, so the same settings would flag events on noise.
+//!
+//! [`WyckoffTracker`] runs the detection bar by bar and keeps the history of
+//! phases, so transitions such as Accumulation to Markup can be dated.
 
 use crate::error::TechnicalError;
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
@@ -27,6 +47,19 @@ pub enum WyckoffPhase {
     Unknown,
 }
 
+impl WyckoffPhase {
+    /// Key of the glossary entry explaining this phase
+    pub fn glossary_key(&self) -> &'static str {
+        match self {
+            WyckoffPhase::Accumulation => "wyckoff_accumulation",
+            WyckoffPhase::Markup => "wyckoff_markup",
+            WyckoffPhase::Distribution => "wyckoff_distribution",
+            WyckoffPhase::Markdown => "wyckoff_markdown",
+            WyckoffPhase::Unknown => "wyckoff",
+        }
+    }
+}
+
 /// Wyckoff events that signal phase transitions
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -57,6 +90,66 @@ pub enum WyckoffEvent {
     Spring,
     /// Upthrust - False breakout above resistance, bearish
     Upthrust,
+    /// No Demand - Narrow up bar on volume below the previous two bars, bearish
+    NoDemand,
+    /// No Supply - Narrow down bar on volume below the previous two bars, bullish
+    NoSupply,
+    /// Stopping Volume - Heavy-volume down bar closing off its low, bullish
+    StoppingVolume,
+    /// Effort Without Result - Heavy volume on a narrow spread, absorption
+    EffortWithoutResult,
+    /// Result Without Effort - Wide spread on light volume, an unsupported move
+    ResultWithoutEffort,
+}
+
+impl WyckoffEvent {
+    /// Snake-case name, as serialized
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WyckoffEvent::PreliminarySupport => "preliminary_support",
+            WyckoffEvent::SellingClimax => "selling_climax",
+            WyckoffEvent::AutomaticRally => "automatic_rally",
+            WyckoffEvent::SecondaryTest => "secondary_test",
+            WyckoffEvent::SignOfStrength => "sign_of_strength",
+            WyckoffEvent::LastPointOfSupport => "last_point_of_support",
+            WyckoffEvent::PreliminarySupply => "preliminary_supply",
+            WyckoffEvent::BuyingClimax => "buying_climax",
+            WyckoffEvent::AutomaticReaction => "automatic_reaction",
+            WyckoffEvent::SignOfWeakness => "sign_of_weakness",
+            WyckoffEvent::LastPointOfSupply => "last_point_of_supply",
+            WyckoffEvent::Spring => "spring",
+            WyckoffEvent::Upthrust => "upthrust",
+            WyckoffEvent::NoDemand => "no_demand",
+            WyckoffEvent::NoSupply => "no_supply",
+            WyckoffEvent::StoppingVolume => "stopping_volume",
+            WyckoffEvent::EffortWithoutResult => "effort_without_result",
+            WyckoffEvent::ResultWithoutEffort => "result_without_effort",
+        }
+    }
+
+    /// Key of the glossary entry explaining this event
+    pub fn glossary_key(&self) -> &'static str {
+        match self {
+            WyckoffEvent::PreliminarySupport => "wyckoff_preliminary_support",
+            WyckoffEvent::SellingClimax => "wyckoff_selling_climax",
+            WyckoffEvent::AutomaticRally => "wyckoff_automatic_rally",
+            WyckoffEvent::SecondaryTest => "wyckoff_secondary_test",
+            WyckoffEvent::SignOfStrength => "wyckoff_sign_of_strength",
+            WyckoffEvent::LastPointOfSupport => "wyckoff_last_point_of_support",
+            WyckoffEvent::PreliminarySupply => "wyckoff_preliminary_supply",
+            WyckoffEvent::BuyingClimax => "wyckoff_buying_climax",
+            WyckoffEvent::AutomaticReaction => "wyckoff_automatic_reaction",
+            WyckoffEvent::SignOfWeakness => "wyckoff_sign_of_weakness",
+            WyckoffEvent::LastPointOfSupply => "wyckoff_last_point_of_supply",
+            WyckoffEvent::Spring => "wyckoff_spring",
+            WyckoffEvent::Upthrust => "wyckoff_upthrust",
+            WyckoffEvent::NoDemand => "vsa_no_demand",
+            WyckoffEvent::NoSupply => "vsa_no_supply",
+            WyckoffEvent::StoppingVolume => "vsa_stopping_volume",
+            WyckoffEvent::EffortWithoutResult => "vsa_effort_without_result",
+            WyckoffEvent::ResultWithoutEffort => "vsa_result_without_effort",
+        }
+    }
 }
 
 /// Wyckoff analysis result
@@ -72,10 +165,29 @@ pub struct WyckoffAnalysis {
     pub support: Option<Decimal>,
     /// Resistance level
     pub resistance: Option<Decimal>,
+    /// Trading range between support and resistance, when price is in one
+    #[serde(default)]
+    pub range: Option<WyckoffRange>,
     /// Phase description
     pub description: String,
 }
 
+/// Statistics of the trading range price is currently in
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WyckoffRange {
+    /// Consecutive bars, up to the latest, closing inside the range
+    pub bars: usize,
+    /// Range height as a percentage of support
+    pub height_pct: Decimal,
+    /// Separate visits of the lows to support
+    pub support_tests: usize,
+    /// Average volume in the second half of the range over the first half;
+    /// below 1 volume is drying up
+    pub volume_dry_up: Decimal,
+    /// Composite 0-100 score of the cause built in the range
+    pub cause_quality: u8,
+}
+
 /// A detected Wyckoff event with context
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WyckoffEventDetection {
@@ -102,7 +214,7 @@ pub struct OhlcvBar {
 }
 
 /// Configuration for Wyckoff detection
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct WyckoffConfig {
     /// Lookback period for trend detection
     pub trend_lookback: usize,
@@ -114,6 +226,14 @@ pub struct WyckoffConfig {
     pub sr_tolerance: Decimal,
     /// Minimum bars for phase detection
     pub min_phase_bars: usize,
+    /// VSA: spread below this multiple of the average spread is narrow
+    pub narrow_spread_ratio: Decimal,
+    /// VSA: spread above this multiple of the average spread is wide
+    pub wide_spread_ratio: Decimal,
+    /// VSA: volume at or above this multiple of the average volume is heavy
+    pub heavy_volume_ratio: Decimal,
+    /// VSA: volume below this multiple of the average volume is light
+    pub light_volume_ratio: Decimal,
 }
 
 impl Default for WyckoffConfig {
@@ -124,10 +244,122 @@ impl Default for WyckoffConfig {
             volume_spike_threshold: dec!(2.0),
             sr_tolerance: dec!(0.02),
             min_phase_bars: 10,
+            narrow_spread_ratio: dec!(0.7),
+            wide_spread_ratio: dec!(1.5),
+            heavy_volume_ratio: dec!(1.5),
+            light_volume_ratio: dec!(0.7),
         }
     }
 }
 
+/// Sessions averaged for the traded value that picks a profile
+pub const PROFILE_ADTV_SESSIONS: usize = 20;
+
+/// Average daily traded value (IDR) at or above which a stock is a blue chip
+const BLUE_CHIP_MIN_ADTV: Decimal = dec!(50_000_000_000);
+/// Average daily traded value (IDR) at or above which a stock is a mid cap
+const MID_CAP_MIN_ADTV: Decimal = dec!(5_000_000_000);
+/// Market cap (IDR) of a blue chip, used when traded value is unknown
+const BLUE_CHIP_MIN_MARKET_CAP: i64 = 100_000_000_000_000;
+/// Market cap (IDR) of a mid cap, used when traded value is unknown
+const MID_CAP_MIN_MARKET_CAP: i64 = 10_000_000_000_000;
+
+/// Named Wyckoff thresholds for a liquidity tier
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WyckoffProfile {
+    /// Deep books: smaller volume surges and tighter levels are meaningful
+    BlueChip,
+    /// The default thresholds
+    MidCap,
+    /// Thin books: only large surges count and levels are looser
+    SmallCap,
+}
+
+impl WyckoffProfile {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WyckoffProfile::BlueChip => "blue_chip",
+            WyckoffProfile::MidCap => "mid_cap",
+            WyckoffProfile::SmallCap => "small_cap",
+        }
+    }
+
+    pub fn from_str_opt(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().replace('-', "_").as_str() {
+            "blue_chip" => Some(WyckoffProfile::BlueChip),
+            "mid_cap" => Some(WyckoffProfile::MidCap),
+            "small_cap" => Some(WyckoffProfile::SmallCap),
+            _ => None,
+        }
+    }
+
+    /// Tier from average daily traded value, falling back to market cap
+    ///
+    /// Traded value decides when known, since it is what the volume
+    /// thresholds react to. Without either measure the mid-cap defaults apply.
+    pub fn classify(adtv: Option<Decimal>, market_cap: Option<i64>) -> Self {
+        if let Some(adtv) = adtv {
+            return if adtv >= BLUE_CHIP_MIN_ADTV {
+                WyckoffProfile::BlueChip
+            } else if adtv >= MID_CAP_MIN_ADTV {
+                WyckoffProfile::MidCap
+            } else {
+                WyckoffProfile::SmallCap
+            };
+        }
+        match market_cap {
+            Some(cap) if cap >= BLUE_CHIP_MIN_MARKET_CAP => WyckoffProfile::BlueChip,
+            Some(cap) if cap >= MID_CAP_MIN_MARKET_CAP => WyckoffProfile::MidCap,
+            Some(_) => WyckoffProfile::SmallCap,
+            None => WyckoffProfile::MidCap,
+        }
+    }
+
+    /// Tier from the traded value of the latest bars, falling back to market cap
+    pub fn for_bars(bars: &[OhlcvBar], market_cap: Option<i64>) -> Self {
+        Self::classify(
+            average_traded_value(bars, PROFILE_ADTV_SESSIONS),
+            market_cap,
+        )
+    }
+
+    pub fn config(&self) -> WyckoffConfig {
+        let defaults = WyckoffConfig::default();
+        match self {
+            WyckoffProfile::BlueChip => WyckoffConfig {
+                volume_spike_threshold: dec!(1.8),
+                sr_tolerance: dec!(0.015),
+                heavy_volume_ratio: dec!(1.4),
+                ..defaults
+            },
+            WyckoffProfile::MidCap => defaults,
+            WyckoffProfile::SmallCap => WyckoffConfig {
+                volume_spike_threshold: dec!(3.0),
+                sr_tolerance: dec!(0.04),
+                narrow_spread_ratio: dec!(0.6),
+                wide_spread_ratio: dec!(1.8),
+                heavy_volume_ratio: dec!(2.0),
+                light_volume_ratio: dec!(0.5),
+                ..defaults
+            },
+        }
+    }
+}
+
+/// Average close x volume over the last `sessions` bars
+pub fn average_traded_value(bars: &[OhlcvBar], sessions: usize) -> Option<Decimal> {
+    let recent = &bars[bars.len().saturating_sub(sessions)..];
+    if recent.is_empty() {
+        return None;
+    }
+    let total: Decimal = recent
+        .iter()
+        .map(|b| b.close * Decimal::from(b.volume))
+        .sum();
+    Some(total / Decimal::from(recent.len() as i64))
+}
+
 /// Detect Wyckoff phase from OHLCV data
 pub fn detect_wyckoff_phase(
     bars: &[OhlcvBar],
@@ -152,14 +384,22 @@ pub fn detect_wyckoff_phase(
     // Detect support and resistance
     let (support, resistance) = detect_support_resistance(&highs, &lows, config);
 
-    // Detect events
-    let events = detect_wyckoff_events(bars, config, support, resistance);
+    // Detect events, price-based and VSA
+    let mut events = detect_wyckoff_events(bars, config, support, resistance);
+    events.extend(detect_vsa_events(bars, config));
+    events.sort_by_key(|e| e.index);
 
     // Determine phase based on trend, volatility, and events
     let (phase, confidence) = determine_phase(
         &closes, &volumes, trend, &events, support, resistance, config,
     );
 
+    let range = match (support, resistance) {
+        (Some(sup), Some(res)) => measure_range(bars, sup, res, config),
+        _ => None,
+    };
+    let confidence = adjust_for_cause(phase, confidence, range.as_ref());
+
     let description = generate_phase_description(phase, &events);
 
     Ok(WyckoffAnalysis {
@@ -168,10 +408,225 @@ pub fn detect_wyckoff_phase(
         events,
         support,
         resistance,
+        range,
         description,
     })
 }
 
+/// Bars each tracker step analyses, ending at the new bar
+pub const DEFAULT_TRACKER_WINDOW: usize = 60;
+
+/// Consecutive readings of a new phase needed before the tracker switches
+pub const TRACKER_CONFIRM_BARS: usize = 3;
+
+/// A stretch of bars spent in one phase
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WyckoffPhaseSpan {
+    pub phase: WyckoffPhase,
+    /// Index of the first bar in the phase
+    pub start_index: usize,
+    pub start_time: DateTime<Utc>,
+    /// Index of the last bar in the phase so far
+    pub end_index: usize,
+    pub end_time: DateTime<Utc>,
+    /// Highest confidence read during the span
+    pub peak_confidence: u8,
+}
+
+impl WyckoffPhaseSpan {
+    /// Bars spent in the phase
+    pub fn bars(&self) -> usize {
+        self.end_index - self.start_index + 1
+    }
+}
+
+/// A change of phase between two spans
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WyckoffTransition {
+    pub from: WyckoffPhase,
+    pub to: WyckoffPhase,
+    /// Index of the first bar of the new phase
+    pub index: usize,
+    pub time: DateTime<Utc>,
+}
+
+/// Tracks the Wyckoff phase bar by bar and records its history
+///
+/// Each bar runs [`detect_wyckoff_phase`] on the trailing window. A new
+/// phase is taken once it is read [`TRACKER_CONFIRM_BARS`] times in a row,
+/// and its span starts at the first of those readings; Unknown readings
+/// keep the current phase, so a transitional stretch does not split it.
+#[derive(Debug, Clone)]
+pub struct WyckoffTracker {
+    config: WyckoffConfig,
+    window: usize,
+    bars: Vec<OhlcvBar>,
+    /// Bars pushed so far, including those dropped from the window
+    count: usize,
+    spans: Vec<WyckoffPhaseSpan>,
+    pending: Option<PendingPhase>,
+}
+
+/// A reading that differs from the current phase, awaiting confirmation
+#[derive(Debug, Clone, Copy)]
+struct PendingPhase {
+    phase: WyckoffPhase,
+    start_index: usize,
+    start_time: DateTime<Utc>,
+    /// End of the current span if the phase is confirmed
+    previous_end_time: Option<DateTime<Utc>>,
+    readings: usize,
+    peak_confidence: u8,
+}
+
+impl WyckoffTracker {
+    pub fn new(config: WyckoffConfig) -> Self {
+        Self::with_window(config, DEFAULT_TRACKER_WINDOW)
+    }
+
+    /// Tracker analysing `window` bars per step, at least what detection needs
+    pub fn with_window(config: WyckoffConfig, window: usize) -> Self {
+        let min_required =
+            config.trend_lookback.max(config.volume_lookback) + config.min_phase_bars;
+        Self {
+            config,
+            window: window.max(min_required),
+            bars: Vec::new(),
+            count: 0,
+            spans: Vec::new(),
+            pending: None,
+        }
+    }
+
+    /// Add the next bar; returns the transition it confirmed, if any
+    pub fn push(&mut self, time: DateTime<Utc>, bar: OhlcvBar) -> Option<WyckoffTransition> {
+        let index = self.count;
+        self.count += 1;
+        self.bars.push(bar);
+        if self.bars.len() > self.window {
+            self.bars.remove(0);
+        }
+
+        let reading = detect_wyckoff_phase(&self.bars, &self.config).ok();
+        let (phase, confidence) = match reading {
+            Some(analysis) if analysis.phase != WyckoffPhase::Unknown => {
+                (analysis.phase, analysis.confidence)
+            }
+            _ => {
+                self.extend_current(index, time, None);
+                return None;
+            }
+        };
+
+        if self.current_phase() == Some(phase) {
+            self.pending = None;
+            self.extend_current(index, time, Some(confidence));
+            return None;
+        }
+
+        let pending = match self.pending {
+            Some(pending) if pending.phase == phase => PendingPhase {
+                readings: pending.readings + 1,
+                peak_confidence: pending.peak_confidence.max(confidence),
+                ..pending
+            },
+            _ => PendingPhase {
+                phase,
+                start_index: index,
+                start_time: time,
+                previous_end_time: self.spans.last().map(|s| s.end_time),
+                readings: 1,
+                peak_confidence: confidence,
+            },
+        };
+        if pending.readings < TRACKER_CONFIRM_BARS {
+            self.pending = Some(pending);
+            self.extend_current(index, time, None);
+            return None;
+        }
+
+        self.pending = None;
+        let from = self.current_phase();
+        if let Some(current) = self.spans.last_mut() {
+            // The old span ends where the new phase was first read
+            current.end_index = pending.start_index - 1;
+            current.end_time = pending.previous_end_time.unwrap_or(current.end_time);
+        }
+        self.spans.push(WyckoffPhaseSpan {
+            phase,
+            start_index: pending.start_index,
+            start_time: pending.start_time,
+            end_index: index,
+            end_time: time,
+            peak_confidence: pending.peak_confidence,
+        });
+
+        from.map(|from| WyckoffTransition {
+            from,
+            to: phase,
+            index: pending.start_index,
+            time: pending.start_time,
+        })
+    }
+
+    fn extend_current(&mut self, index: usize, time: DateTime<Utc>, confidence: Option<u8>) {
+        if let Some(current) = self.spans.last_mut() {
+            current.end_index = index;
+            current.end_time = time;
+            if let Some(confidence) = confidence {
+                current.peak_confidence = current.peak_confidence.max(confidence);
+            }
+        }
+    }
+
+    /// Phase of the latest span, `None` before the first reading
+    pub fn current_phase(&self) -> Option<WyckoffPhase> {
+        self.spans.last().map(|s| s.phase)
+    }
+
+    /// Phases in order, the last one ongoing
+    pub fn history(&self) -> &[WyckoffPhaseSpan] {
+        &self.spans
+    }
+
+    /// Phase changes in order
+    pub fn transitions(&self) -> Vec<WyckoffTransition> {
+        self.spans
+            .windows(2)
+            .map(|pair| WyckoffTransition {
+                from: pair[0].phase,
+                to: pair[1].phase,
+                index: pair[1].start_index,
+                time: pair[1].start_time,
+            })
+            .collect()
+    }
+
+    /// Latest transition from `from` to `to`
+    pub fn last_transition(
+        &self,
+        from: WyckoffPhase,
+        to: WyckoffPhase,
+    ) -> Option<WyckoffTransition> {
+        self.transitions()
+            .into_iter()
+            .rev()
+            .find(|t| t.from == from && t.to == to)
+    }
+
+    /// Bars spent in the current phase so far
+    pub fn bars_in_phase(&self) -> usize {
+        self.spans.last().map_or(0, WyckoffPhaseSpan::bars)
+    }
+
+    /// Time spent in the current phase so far
+    pub fn time_in_phase(&self) -> chrono::Duration {
+        self.spans
+            .last()
+            .map_or_else(chrono::Duration::zero, |s| s.end_time - s.start_time)
+    }
+}
+
 /// Calculate price trend (-1.0 to 1.0)
 fn calculate_trend(closes: &[Decimal], lookback: usize) -> Decimal {
     if closes.len() < lookback + 1 {
@@ -387,6 +842,97 @@ fn detect_wyckoff_events(
     events
 }
 
+/// Classify bars by volume spread analysis
+///
+/// Spread and volume are compared with the averages of the
+/// `volume_lookback` bars before each bar, and the bar's direction with the
+/// previous close.
+fn detect_vsa_events(bars: &[OhlcvBar], config: &WyckoffConfig) -> Vec<WyckoffEventDetection> {
+    let mut events = Vec::new();
+
+    for i in config.volume_lookback.max(2)..bars.len() {
+        let bar = &bars[i];
+        let prev_bars = &bars[i - config.volume_lookback..i];
+        let lookback = Decimal::from(prev_bars.len() as i64);
+        let avg_volume = Decimal::from(prev_bars.iter().map(|b| b.volume).sum::<i64>()) / lookback;
+        let avg_spread = prev_bars.iter().map(|b| b.high - b.low).sum::<Decimal>() / lookback;
+        if avg_volume <= Decimal::ZERO || avg_spread <= Decimal::ZERO {
+            continue;
+        }
+
+        let volume_ratio = Decimal::from(bar.volume) / avg_volume;
+        let spread_ratio = (bar.high - bar.low) / avg_spread;
+        let prev_close = bars[i - 1].close;
+        let up = bar.close > prev_close;
+        let down = bar.close < prev_close;
+        let narrow = spread_ratio < config.narrow_spread_ratio;
+        let heavy = volume_ratio >= config.heavy_volume_ratio;
+        // Lighter than both previous bars, the classic VSA test
+        let below_previous_two = bar.volume < bars[i - 1].volume && bar.volume < bars[i - 2].volume;
+
+        let mut push = |event, confidence| {
+            events.push(WyckoffEventDetection {
+                event,
+                index: i,
+                price: bar.close,
+                volume: bar.volume,
+                confidence,
+            })
+        };
+
+        if narrow && below_previous_two && up {
+            push(
+                WyckoffEvent::NoDemand,
+                vsa_confidence(Decimal::ONE / volume_ratio.max(dec!(0.01)), spread_ratio),
+            );
+        } else if narrow && below_previous_two && down {
+            push(
+                WyckoffEvent::NoSupply,
+                vsa_confidence(Decimal::ONE / volume_ratio.max(dec!(0.01)), spread_ratio),
+            );
+        }
+
+        // Buying absorbs the selling: the bar closes in its upper half
+        let range = bar.high - bar.low;
+        let closes_off_low = range > Decimal::ZERO && (bar.close - bar.low) / range >= dec!(0.5);
+        if heavy && down && closes_off_low {
+            push(
+                WyckoffEvent::StoppingVolume,
+                vsa_confidence(volume_ratio, Decimal::ONE),
+            );
+        } else if heavy && narrow {
+            push(
+                WyckoffEvent::EffortWithoutResult,
+                vsa_confidence(volume_ratio, spread_ratio),
+            );
+        }
+
+        if spread_ratio > config.wide_spread_ratio && volume_ratio < config.light_volume_ratio {
+            push(
+                WyckoffEvent::ResultWithoutEffort,
+                vsa_confidence(spread_ratio, Decimal::ONE / volume_ratio.max(dec!(0.01))),
+            );
+        }
+    }
+
+    events
+}
+
+/// Confidence of a VSA event from how far its volume (`strength`) and
+/// spread depart from normal; `spread_ratio` below one counts as narrowness
+fn vsa_confidence(strength: Decimal, spread_ratio: Decimal) -> u8 {
+    let strength_bonus = if strength >= dec!(3) {
+        25
+    } else if strength >= dec!(2) {
+        15
+    } else {
+        5
+    };
+    let spread_bonus = if spread_ratio < dec!(0.5) { 10 } else { 0 };
+
+    (45 + strength_bonus + spread_bonus).min(100)
+}
+
 fn calculate_avg_volume(bars: &[OhlcvBar], lookback: usize) -> i64 {
     let start = bars.len().saturating_sub(lookback);
     let sum: i64 = bars[start..].iter().map(|b| b.volume).sum();
@@ -531,9 +1077,185 @@ fn determine_phase(
         (WyckoffPhase::Unknown, 30)
     };
 
+    let confidence = adjust_for_vsa(phase, confidence, &recent_events, support, resistance);
+
     (phase, confidence)
 }
 
+/// Most the recent VSA events move the phase confidence either way
+const MAX_VSA_ADJUSTMENT: i32 = 15;
+
+/// Raise or lower the phase confidence by the recent VSA events
+///
+/// No supply and stopping volume confirm the bullish phases, no demand the
+/// bearish ones. Absorption (effort without result) counts for the side of
+/// the range it happens on: near the lows it is demand absorbing supply.
+/// A move without effort weakens the trending phases.
+fn adjust_for_vsa(
+    phase: WyckoffPhase,
+    confidence: u8,
+    recent_events: &[&WyckoffEventDetection],
+    support: Option<Decimal>,
+    resistance: Option<Decimal>,
+) -> u8 {
+    let mid = match (support, resistance) {
+        (Some(sup), Some(res)) => Some((sup + res) / dec!(2)),
+        _ => None,
+    };
+
+    let mut bias = 0i32;
+    let mut unsupported_moves = 0i32;
+    for e in recent_events {
+        match e.event {
+            WyckoffEvent::NoSupply | WyckoffEvent::StoppingVolume => bias += 1,
+            WyckoffEvent::NoDemand => bias -= 1,
+            WyckoffEvent::EffortWithoutResult => match mid {
+                Some(mid) if e.price < mid => bias += 1,
+                Some(_) => bias -= 1,
+                None => {}
+            },
+            WyckoffEvent::ResultWithoutEffort => unsupported_moves += 1,
+            _ => {}
+        }
+    }
+
+    let adjustment = match phase {
+        WyckoffPhase::Accumulation => bias * 5,
+        WyckoffPhase::Distribution => -bias * 5,
+        WyckoffPhase::Markup => bias * 5 - unsupported_moves * 5,
+        WyckoffPhase::Markdown => -bias * 5 - unsupported_moves * 5,
+        WyckoffPhase::Unknown => 0,
+    }
+    .clamp(-MAX_VSA_ADJUSTMENT, MAX_VSA_ADJUSTMENT);
+
+    (i32::from(confidence) + adjustment).clamp(0, 95) as u8
+}
+
+/// Range length, in multiples of `min_phase_bars`, that earns full duration credit
+const FULL_CAUSE_PHASES: usize = 3;
+/// Support tests that earn full test credit
+const FULL_CAUSE_TESTS: usize = 3;
+/// Volume dry-up ratio at or below which dry-up credit is full
+const FULL_DRY_UP_RATIO: Decimal = dec!(0.5);
+/// Range heights (percent) that make a tradeable cause
+const MIN_CAUSE_HEIGHT_PCT: Decimal = dec!(5);
+const MAX_CAUSE_HEIGHT_PCT: Decimal = dec!(30);
+
+/// Measure the trading range between `support` and `resistance`
+///
+/// The range runs back from the latest bar for as long as closes stay
+/// within the levels, give or take `sr_tolerance`; intrabar probes beyond
+/// them (springs, upthrusts) do not end it. `None` when the levels are
+/// inverted or the latest close is already outside them.
+fn measure_range(
+    bars: &[OhlcvBar],
+    support: Decimal,
+    resistance: Decimal,
+    config: &WyckoffConfig,
+) -> Option<WyckoffRange> {
+    if support <= Decimal::ZERO || resistance <= support {
+        return None;
+    }
+    let floor = support * (Decimal::ONE - config.sr_tolerance);
+    let ceiling = resistance * (Decimal::ONE + config.sr_tolerance);
+    let inside = bars
+        .iter()
+        .rev()
+        .take_while(|b| b.close >= floor && b.close <= ceiling)
+        .count();
+    if inside < 2 {
+        return None;
+    }
+    let range_bars = &bars[bars.len() - inside..];
+
+    let touch = support * (Decimal::ONE + config.sr_tolerance);
+    let mut support_tests = 0;
+    let mut at_support = false;
+    for bar in range_bars {
+        let touching = bar.low <= touch;
+        if touching && !at_support {
+            support_tests += 1;
+        }
+        at_support = touching;
+    }
+
+    let half = inside / 2;
+    let first = range_bars[..half].iter().map(|b| b.volume).sum::<i64>() / half as i64;
+    let second = range_bars[half..].iter().map(|b| b.volume).sum::<i64>() / (inside - half) as i64;
+    let volume_dry_up = if first > 0 {
+        (Decimal::from(second) / Decimal::from(first)).round_dp(2)
+    } else {
+        Decimal::ONE
+    };
+
+    let height_pct = ((resistance - support) / support * dec!(100)).round_dp(2);
+    let mut range = WyckoffRange {
+        bars: inside,
+        height_pct,
+        support_tests,
+        volume_dry_up,
+        cause_quality: 0,
+    };
+    range.cause_quality = cause_quality(&range, config);
+    Some(range)
+}
+
+/// Score the cause a range has built, 0-100
+///
+/// Duration carries 35 points, support tests 25, volume dry-up 25 and a
+/// tradeable height 15. A long, well-tested range on falling volume is the
+/// cause that precedes a sustained move.
+fn cause_quality(range: &WyckoffRange, config: &WyckoffConfig) -> u8 {
+    let full_bars = (config.min_phase_bars * FULL_CAUSE_PHASES).max(1);
+    let duration =
+        Decimal::from(range.bars.min(full_bars) as i64) / Decimal::from(full_bars as i64);
+    let tests = Decimal::from(range.support_tests.min(FULL_CAUSE_TESTS) as i64)
+        / Decimal::from(FULL_CAUSE_TESTS as i64);
+    let dry_up = if range.volume_dry_up <= FULL_DRY_UP_RATIO {
+        Decimal::ONE
+    } else if range.volume_dry_up >= Decimal::ONE {
+        Decimal::ZERO
+    } else {
+        (Decimal::ONE - range.volume_dry_up) / (Decimal::ONE - FULL_DRY_UP_RATIO)
+    };
+    let height = if range.height_pct < MIN_CAUSE_HEIGHT_PCT {
+        range.height_pct / MIN_CAUSE_HEIGHT_PCT
+    } else if range.height_pct > MAX_CAUSE_HEIGHT_PCT {
+        dec!(0.3)
+    } else {
+        Decimal::ONE
+    };
+
+    let score = duration * dec!(35) + tests * dec!(25) + dry_up * dec!(25) + height * dec!(15);
+    score
+        .round()
+        .clamp(Decimal::ZERO, dec!(100))
+        .to_u8()
+        .unwrap_or(0)
+}
+
+/// Most the cause quality moves the phase confidence either way
+const MAX_CAUSE_ADJUSTMENT: i32 = 10;
+
+/// Raise or lower range-phase confidence by the quality of the cause
+///
+/// Only accumulation and distribution are read from a range; a score of 50
+/// leaves the confidence as it is.
+fn adjust_for_cause(phase: WyckoffPhase, confidence: u8, range: Option<&WyckoffRange>) -> u8 {
+    let Some(range) = range else {
+        return confidence;
+    };
+    if !matches!(
+        phase,
+        WyckoffPhase::Accumulation | WyckoffPhase::Distribution
+    ) {
+        return confidence;
+    }
+    let adjustment = ((i32::from(range.cause_quality) - 50) / 5)
+        .clamp(-MAX_CAUSE_ADJUSTMENT, MAX_CAUSE_ADJUSTMENT);
+    (i32::from(confidence) + adjustment).clamp(0, 95) as u8
+}
+
 fn calculate_volatility(closes: &[Decimal], lookback: usize) -> Decimal {
     let start = closes.len().saturating_sub(lookback);
     let recent = &closes[start..];
@@ -830,6 +1552,274 @@ mod tests {
         );
     }
 
+    /// Five bars falling into a 100-110 range, then `range_len` bars cycling in it
+    fn range_bars(range_len: usize, volume: impl Fn(usize) -> i64) -> Vec<OhlcvBar> {
+        let cycle = [101, 103, 105, 107, 109, 108, 106, 104, 102, 100];
+        let mut bars: Vec<_> = (0..5)
+            .map(|i| {
+                let close = Decimal::from(130 - i * 3);
+                OhlcvBar {
+                    open: close + dec!(1),
+                    high: close + dec!(2),
+                    low: close - dec!(1),
+                    close,
+                    volume: 3000,
+                }
+            })
+            .collect();
+        bars.extend((0..range_len).map(|i| {
+            let close = Decimal::from(cycle[i % cycle.len()]);
+            OhlcvBar {
+                open: close,
+                high: close + dec!(1),
+                low: close - dec!(1),
+                close,
+                volume: volume(i),
+            }
+        }));
+        bars
+    }
+
+    #[test]
+    fn test_measure_range() {
+        let config = WyckoffConfig::default();
+        let bars = range_bars(30, |i| if i < 15 { 2000 } else { 800 });
+
+        let range = measure_range(&bars, dec!(100), dec!(110), &config).unwrap();
+        // The falling bars before the range close above it
+        assert_eq!(range.bars, 30);
+        assert_eq!(range.height_pct, dec!(10));
+        // Lows within 2% of support: bars 0-1, 8-11, 18-21 and 28-29
+        assert_eq!(range.support_tests, 4);
+        assert_eq!(range.volume_dry_up, dec!(0.4));
+        assert_eq!(range.cause_quality, 100);
+
+        // Short range on rising volume builds little cause
+        let bars = range_bars(6, |i| 1000 + i as i64 * 200);
+        let range = measure_range(&bars, dec!(100), dec!(110), &config).unwrap();
+        assert_eq!(range.bars, 6);
+        assert_eq!(range.support_tests, 1);
+        assert!(range.volume_dry_up > Decimal::ONE);
+        assert_eq!(range.cause_quality, 30);
+
+        // Inverted levels or a close outside the range
+        assert!(measure_range(&bars, dec!(110), dec!(100), &config).is_none());
+        assert!(measure_range(&bars, dec!(120), dec!(130), &config).is_none());
+    }
+
+    #[test]
+    fn test_cause_adjusts_range_confidence() {
+        let range = |cause_quality| WyckoffRange {
+            bars: 30,
+            height_pct: dec!(10),
+            support_tests: 3,
+            volume_dry_up: dec!(0.5),
+            cause_quality,
+        };
+
+        let strong = range(100);
+        let weak = range(0);
+        assert_eq!(
+            adjust_for_cause(WyckoffPhase::Accumulation, 70, Some(&strong)),
+            80
+        );
+        assert_eq!(
+            adjust_for_cause(WyckoffPhase::Distribution, 70, Some(&weak)),
+            60
+        );
+        assert_eq!(
+            adjust_for_cause(WyckoffPhase::Accumulation, 70, Some(&range(50))),
+            70
+        );
+        assert_eq!(
+            adjust_for_cause(WyckoffPhase::Markup, 70, Some(&strong)),
+            70
+        );
+        assert_eq!(adjust_for_cause(WyckoffPhase::Accumulation, 70, None), 70);
+    }
+
+    #[test]
+    fn test_vsa_events() {
+        let config = WyckoffConfig::default();
+        // Steady bars: 10-point spread, 1M volume, alternating closes
+        let mut bars: Vec<OhlcvBar> = (0..25)
+            .map(|i| {
+                let close = if i % 2 == 0 { dec!(105) } else { dec!(104) };
+                OhlcvBar {
+                    open: dec!(104),
+                    high: dec!(110),
+                    low: dec!(100),
+                    close,
+                    volume: 1_000_000,
+                }
+            })
+            .collect();
+        let steady = bars.len();
+        // No demand: narrow up bar on the lightest volume
+        bars.push(OhlcvBar {
+            open: dec!(104),
+            high: dec!(107),
+            low: dec!(103),
+            close: dec!(106),
+            volume: 400_000,
+        });
+        // Stopping volume: heavy down bar closing near its high
+        bars.push(OhlcvBar {
+            open: dec!(104),
+            high: dec!(106),
+            low: dec!(94),
+            close: dec!(105),
+            volume: 3_500_000,
+        });
+        // Effort without result: heavy volume, hardly any range
+        bars.push(OhlcvBar {
+            open: dec!(105),
+            high: dec!(107),
+            low: dec!(104),
+            close: dec!(106),
+            volume: 2_500_000,
+        });
+        // Result without effort: wide bar on light volume
+        bars.push(OhlcvBar {
+            open: dec!(106),
+            high: dec!(125),
+            low: dec!(105),
+            close: dec!(124),
+            volume: 500_000,
+        });
+        // No supply: narrow down bar lighter than the two before
+        bars.push(OhlcvBar {
+            open: dec!(124),
+            high: dec!(125),
+            low: dec!(121),
+            close: dec!(122),
+            volume: 300_000,
+        });
+
+        let events = detect_vsa_events(&bars, &config);
+        let found: Vec<(usize, WyckoffEvent)> = events.iter().map(|e| (e.index, e.event)).collect();
+        assert_eq!(
+            found,
+            vec![
+                (steady, WyckoffEvent::NoDemand),
+                (steady + 1, WyckoffEvent::StoppingVolume),
+                (steady + 2, WyckoffEvent::EffortWithoutResult),
+                (steady + 3, WyckoffEvent::ResultWithoutEffort),
+                (steady + 4, WyckoffEvent::NoSupply),
+            ]
+        );
+        // 3.5x volume is a strong stopping bar
+        assert_eq!(events[1].confidence, 70);
+    }
+
+    #[test]
+    fn test_vsa_adjusts_phase_confidence() {
+        let detection = |event, price| WyckoffEventDetection {
+            event,
+            index: 0,
+            price,
+            volume: 0,
+            confidence: 60,
+        };
+        let no_supply = detection(WyckoffEvent::NoSupply, dec!(101));
+        let stopping = detection(WyckoffEvent::StoppingVolume, dec!(100));
+        let low_absorption = detection(WyckoffEvent::EffortWithoutResult, dec!(102));
+        let high_absorption = detection(WyckoffEvent::EffortWithoutResult, dec!(109));
+        let unsupported = detection(WyckoffEvent::ResultWithoutEffort, dec!(110));
+        let (sup, res) = (Some(dec!(100)), Some(dec!(110)));
+
+        let bullish = [&no_supply, &stopping, &low_absorption];
+        assert_eq!(
+            adjust_for_vsa(WyckoffPhase::Accumulation, 55, &bullish, sup, res),
+            70
+        );
+        assert_eq!(
+            adjust_for_vsa(WyckoffPhase::Distribution, 55, &bullish, sup, res),
+            40
+        );
+        // Absorption at the highs counts against accumulation
+        assert_eq!(
+            adjust_for_vsa(
+                WyckoffPhase::Accumulation,
+                55,
+                &[&high_absorption],
+                sup,
+                res
+            ),
+            50
+        );
+        // Unsupported moves weaken trends but not ranges
+        assert_eq!(
+            adjust_for_vsa(WyckoffPhase::Markup, 80, &[&unsupported], sup, res),
+            75
+        );
+        assert_eq!(
+            adjust_for_vsa(WyckoffPhase::Accumulation, 55, &[&unsupported], sup, res),
+            55
+        );
+        assert_eq!(adjust_for_vsa(WyckoffPhase::Markup, 80, &[], sup, res), 80);
+    }
+
+    #[test]
+    fn test_tracker_dates_accumulation_to_markup() {
+        let start = DateTime::parse_from_rfc3339("2024-01-01T09:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        // Sixty sessions in a tight range on fading volume, then a rally
+        let mut prices: Vec<_> = (0..60)
+            .map(|i| {
+                let base = dec!(100) + Decimal::from(i % 5) * dec!(0.5);
+                (base, base + dec!(1), base - dec!(0.5), base)
+            })
+            .collect();
+        prices.extend((1..=40).map(|i| {
+            let base = dec!(102) * (dec!(1.01) + Decimal::from(i) * dec!(0.01));
+            (base, base + dec!(1.5), base - dec!(0.5), base + dec!(1))
+        }));
+        let mut volumes: Vec<i64> = (0..60).map(|i| 2000 - i * 20).collect();
+        volumes.extend((0..40).map(|i| 2000 + i * 50));
+        let bars = create_test_bars(&prices, &volumes);
+
+        let mut tracker = WyckoffTracker::new(WyckoffConfig::default());
+        let mut confirmed = Vec::new();
+        for (i, bar) in bars.into_iter().enumerate() {
+            confirmed.extend(tracker.push(start + chrono::Duration::days(i as i64), bar));
+        }
+
+        // A one-off Distribution reading in the first window is not confirmed
+        let phases: Vec<_> = tracker
+            .history()
+            .iter()
+            .map(|s| (s.phase, s.start_index, s.end_index))
+            .collect();
+        assert_eq!(
+            phases,
+            [
+                (WyckoffPhase::Accumulation, 30, 59),
+                (WyckoffPhase::Markup, 60, 99),
+            ]
+        );
+        let markup = WyckoffTransition {
+            from: WyckoffPhase::Accumulation,
+            to: WyckoffPhase::Markup,
+            index: 60,
+            time: start + chrono::Duration::days(60),
+        };
+        assert_eq!(confirmed, [markup]);
+        assert_eq!(tracker.transitions(), [markup]);
+        assert_eq!(
+            tracker.last_transition(WyckoffPhase::Accumulation, WyckoffPhase::Markup),
+            Some(markup)
+        );
+        assert_eq!(tracker.current_phase(), Some(WyckoffPhase::Markup));
+        assert_eq!(tracker.bars_in_phase(), 40);
+        assert_eq!(tracker.time_in_phase(), chrono::Duration::days(39));
+        assert_eq!(
+            tracker.history()[0].end_time,
+            start + chrono::Duration::days(59)
+        );
+    }
+
     #[test]
     fn test_wyckoff_event_serialization() {
         let event = WyckoffEvent::Spring;
@@ -849,6 +1839,7 @@ mod tests {
             events: vec![],
             support: Some(dec!(100)),
             resistance: Some(dec!(110)),
+            range: None,
             description: "Test".to_string(),
         };
 
@@ -856,4 +1847,119 @@ mod tests {
         assert!(json.contains("markup"));
         assert!(json.contains("100"));
     }
+
+    #[test]
+    fn test_profile_classification() {
+        let bn = |v: i64| Some(Decimal::from(v) * dec!(1_000_000_000));
+        assert_eq!(
+            WyckoffProfile::classify(bn(120), Some(1)),
+            WyckoffProfile::BlueChip
+        );
+        assert_eq!(
+            WyckoffProfile::classify(bn(8), None),
+            WyckoffProfile::MidCap
+        );
+        // Traded value wins over a large market cap
+        assert_eq!(
+            WyckoffProfile::classify(bn(1), Some(BLUE_CHIP_MIN_MARKET_CAP)),
+            WyckoffProfile::SmallCap
+        );
+        assert_eq!(
+            WyckoffProfile::classify(None, Some(BLUE_CHIP_MIN_MARKET_CAP)),
+            WyckoffProfile::BlueChip
+        );
+        assert_eq!(WyckoffProfile::classify(None, None), WyckoffProfile::MidCap);
+
+        // 1,000 x 1,000,000 shares = 1bn IDR a day
+        let bars: Vec<OhlcvBar> = (0..30)
+            .map(|_| OhlcvBar {
+                open: dec!(1000),
+                high: dec!(1010),
+                low: dec!(990),
+                close: dec!(1000),
+                volume: 1_000_000,
+            })
+            .collect();
+        assert_eq!(average_traded_value(&bars, 20), bn(1));
+        assert_eq!(
+            WyckoffProfile::for_bars(&bars, None),
+            WyckoffProfile::SmallCap
+        );
+        assert_eq!(average_traded_value(&[], 20), None);
+    }
+
+    #[test]
+    fn test_profile_configs() {
+        let blue = WyckoffProfile::BlueChip.config();
+        let mid = WyckoffProfile::MidCap.config();
+        let small = WyckoffProfile::SmallCap.config();
+
+        assert_eq!(mid, WyckoffConfig::default());
+        assert!(blue.volume_spike_threshold < mid.volume_spike_threshold);
+        assert!(small.volume_spike_threshold > mid.volume_spike_threshold);
+        assert!(blue.sr_tolerance < mid.sr_tolerance && mid.sr_tolerance < small.sr_tolerance);
+
+        for profile in [
+            WyckoffProfile::BlueChip,
+            WyckoffProfile::MidCap,
+            WyckoffProfile::SmallCap,
+        ] {
+            assert_eq!(
+                WyckoffProfile::from_str_opt(profile.as_str()),
+                Some(profile)
+            );
+        }
+        assert_eq!(
+            WyckoffProfile::from_str_opt("Small-Cap"),
+            Some(WyckoffProfile::SmallCap)
+        );
+        assert_eq!(WyckoffProfile::from_str_opt("penny"), None);
+    }
+
+    #[test]
+    fn test_glossary_keys_resolve() {
+        let phases = [
+            WyckoffPhase::Accumulation,
+            WyckoffPhase::Markup,
+            WyckoffPhase::Distribution,
+            WyckoffPhase::Markdown,
+            WyckoffPhase::Unknown,
+        ];
+        let events = [
+            WyckoffEvent::PreliminarySupport,
+            WyckoffEvent::SellingClimax,
+            WyckoffEvent::AutomaticRally,
+            WyckoffEvent::SecondaryTest,
+            WyckoffEvent::SignOfStrength,
+            WyckoffEvent::LastPointOfSupport,
+            WyckoffEvent::PreliminarySupply,
+            WyckoffEvent::BuyingClimax,
+            WyckoffEvent::AutomaticReaction,
+            WyckoffEvent::SignOfWeakness,
+            WyckoffEvent::LastPointOfSupply,
+            WyckoffEvent::Spring,
+            WyckoffEvent::Upthrust,
+            WyckoffEvent::NoDemand,
+            WyckoffEvent::NoSupply,
+            WyckoffEvent::StoppingVolume,
+            WyckoffEvent::EffortWithoutResult,
+            WyckoffEvent::ResultWithoutEffort,
+        ];
+
+        for event in &events {
+            assert_eq!(serde_json::to_value(event).unwrap(), event.as_str());
+        }
+
+        let keys = phases
+            .iter()
+            .map(|p| p.glossary_key())
+            .chain(events.iter().map(|e| e.glossary_key()));
+        for key in keys {
+            assert!(
+                jejakcuan_core::glossary_entry(key).is_some(),
+                "missing glossary entry {}",
+                key
+            );
+        }
+    }
 }